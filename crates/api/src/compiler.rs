@@ -0,0 +1,433 @@
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_compiler::Compiler as CoreCompiler;
+use farmfe_core::{
+  context::update_schedule::{
+    UpdateSchedule as CoreUpdateSchedule, UpdateSchedulePolicy as CoreUpdateSchedulePolicy,
+  },
+  module::{module_graph::ImportChainStep, ModuleId},
+  plugin::{HmrFullReload, HmrFullReloadReason, UpdateType},
+};
+
+use crate::{
+  config::Config,
+  error::{Error, Result},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateKind {
+  Added,
+  Updated,
+  Removed,
+}
+
+impl From<UpdateKind> for UpdateType {
+  fn from(kind: UpdateKind) -> Self {
+    match kind {
+      UpdateKind::Added => UpdateType::Added,
+      UpdateKind::Updated => UpdateType::Updated,
+      UpdateKind::Removed => UpdateType::Removed,
+    }
+  }
+}
+
+/// When the resources of an update are regenerated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateSchedule {
+  /// regenerate the resources before the next update or when [Compiler::flush_deferred_updates] is called. The resources
+  /// are flushed before [Compiler::resource] and [Compiler::resources] return, so they are never served stale
+  Deferred,
+  /// regenerate the resources in a background thread, [Compiler::update] returns without waiting for them
+  Async,
+  /// regenerate the resources before [Compiler::update] returns
+  Sync,
+}
+
+impl From<UpdateSchedule> for CoreUpdateSchedule {
+  fn from(schedule: UpdateSchedule) -> Self {
+    match schedule {
+      UpdateSchedule::Deferred => CoreUpdateSchedule::Deferred,
+      UpdateSchedule::Async => CoreUpdateSchedule::Async,
+      UpdateSchedule::Sync => CoreUpdateSchedule::Sync,
+    }
+  }
+}
+
+impl From<CoreUpdateSchedule> for UpdateSchedule {
+  fn from(schedule: CoreUpdateSchedule) -> Self {
+    match schedule {
+      CoreUpdateSchedule::Deferred => UpdateSchedule::Deferred,
+      CoreUpdateSchedule::Async => UpdateSchedule::Async,
+      CoreUpdateSchedule::Sync => UpdateSchedule::Sync,
+    }
+  }
+}
+
+/// The schedule of each kind of update trigger, the most urgent schedule of the triggers of an update is used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateSchedulePolicy {
+  /// the changed modules of the updates
+  pub module_change: UpdateSchedule,
+  /// the changed modules of background updates, e.g. lazy compilation
+  pub background: UpdateSchedule,
+  /// the update creates new module groups, e.g. a new dynamic import
+  pub new_module_group: UpdateSchedule,
+  /// the injected resources of html entries may change, [UpdateOutput::updated_html_resources] is only reported when it's [UpdateSchedule::Sync]
+  pub html_entry: UpdateSchedule,
+  /// the update affects workers
+  pub worker: UpdateSchedule,
+}
+
+impl Default for UpdateSchedulePolicy {
+  fn default() -> Self {
+    CoreUpdateSchedulePolicy::default().into()
+  }
+}
+
+impl From<UpdateSchedulePolicy> for CoreUpdateSchedulePolicy {
+  fn from(policy: UpdateSchedulePolicy) -> Self {
+    Self {
+      module_change: policy.module_change.into(),
+      background: policy.background.into(),
+      new_module_group: policy.new_module_group.into(),
+      html_entry: policy.html_entry.into(),
+      worker: policy.worker.into(),
+    }
+  }
+}
+
+impl From<CoreUpdateSchedulePolicy> for UpdateSchedulePolicy {
+  fn from(policy: CoreUpdateSchedulePolicy) -> Self {
+    Self {
+      module_change: policy.module_change.into(),
+      background: policy.background.into(),
+      new_module_group: policy.new_module_group.into(),
+      html_entry: policy.html_entry.into(),
+      worker: policy.worker.into(),
+    }
+  }
+}
+
+/// The result of [Compiler::update]
+#[derive(Debug, Clone, Default)]
+pub struct UpdateOutput {
+  pub added: Vec<String>,
+  pub changed: Vec<String>,
+  pub removed: Vec<String>,
+  /// module map of the added and changed modules, evaluated by the hmr runtime
+  pub immutable_modules: String,
+  pub mutable_modules: String,
+  /// updated module id -> the paths from the updated module to its hmr boundaries, empty if the page should be reloaded
+  pub boundaries: HashMap<String, Vec<Vec<String>>>,
+  /// why the page should be reloaded instead of applying the update
+  pub full_reload: Option<FullReload>,
+  /// names of the resources removed with the removed modules
+  pub removed_resources: Vec<String>,
+  /// dynamically imported module id -> (name, `script` or `link`) of the resources that load it, [None] if the dynamic
+  /// imports are not changed by the update
+  pub dynamic_resources_map: Option<HashMap<String, Vec<(String, String)>>>,
+  /// names of the html resources whose injected resources changed, the page should be reloaded to apply them
+  pub updated_html_resources: Vec<String>,
+}
+
+/// The propagation path of an update that is not accepted by any hmr boundary, so the page is reloaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FullReload {
+  /// the updated module
+  pub module: String,
+  /// the importers from the updated module to the module that stops the propagation
+  pub path: Vec<String>,
+  pub reason: FullReloadReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FullReloadReason {
+  /// the update reaches an entry that doesn't accept it
+  Entry,
+  /// the last importer is not a script module, which can't accept the update
+  NonScriptImporter,
+}
+
+impl FullReloadReason {
+  /// `entry` or `nonScriptImporter`, the reason sent to the hmr clients
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      FullReloadReason::Entry => "entry",
+      FullReloadReason::NonScriptImporter => "nonScriptImporter",
+    }
+  }
+}
+
+impl From<HmrFullReload> for FullReload {
+  fn from(full_reload: HmrFullReload) -> Self {
+    Self {
+      module: full_reload.module,
+      path: full_reload.path,
+      reason: match full_reload.reason {
+        HmrFullReloadReason::Entry => FullReloadReason::Entry,
+        HmrFullReloadReason::NonScriptImporter => FullReloadReason::NonScriptImporter,
+      },
+    }
+  }
+}
+
+/// A resource generated by the compiler, e.g. a js chunk, a css file or an asset
+#[derive(Debug, Clone)]
+pub struct OutputResource {
+  pub name: String,
+  pub bytes: Vec<u8>,
+  /// true if the resource is consumed by other resources and is not written on its own, e.g. the inlined runtime
+  pub emitted: bool,
+}
+
+/// The result of [Compiler::import_cost]
+#[derive(Debug, Clone, Default)]
+pub struct ImportCost {
+  /// the imported module
+  pub module_id: String,
+  /// the imported module and its static dependencies that are not imported by the importer otherwise
+  pub modules: Vec<String>,
+  /// size of the transformed code of the modules before minification
+  pub size: usize,
+  pub gzip_size: usize,
+  /// static dependencies of the imported module that are bundled by the other imports of the importer anyway
+  pub shared_modules: Vec<String>,
+}
+
+pub struct Compiler {
+  compiler: Arc<CoreCompiler>,
+}
+
+impl Compiler {
+  /// Create a compiler with the internal plugins of farm
+  pub fn new(config: Config) -> Result<Self> {
+    Ok(Self {
+      compiler: Arc::new(CoreCompiler::new(config.0, vec![])?),
+    })
+  }
+
+  /// Wrap a compiler owned by [crate::MultiProjectCompiler]
+  pub(crate) fn from_core(compiler: Arc<CoreCompiler>) -> Self {
+    Self { compiler }
+  }
+
+  /// Build the module graph and generate the resources
+  pub fn compile(&self) -> Result<()> {
+    self.compiler.compile().map_err(Error::from)
+  }
+
+  /// Recompile the changed files, `paths` are absolute paths. [Compiler::compile] should be called before this method
+  pub fn update(&self, paths: Vec<(String, UpdateKind)>) -> Result<UpdateOutput> {
+    let paths = paths
+      .into_iter()
+      .map(|(path, kind)| (path, kind.into()))
+      .collect();
+    let result = self.compiler.update(paths, || {}, true, true)?;
+    let mode = self.compiler.context().config.mode.clone();
+
+    Ok(UpdateOutput {
+      added: to_strings(result.added_module_ids),
+      changed: to_strings(result.updated_module_ids),
+      removed: to_strings(result.removed_module_ids),
+      immutable_modules: result.immutable_resources,
+      mutable_modules: result.mutable_resources,
+      boundaries: result.boundaries,
+      full_reload: result.hmr_full_reload.map(FullReload::from),
+      removed_resources: result.removed_resources,
+      dynamic_resources_map: result.dynamic_resources_map.map(|dynamic_resources_map| {
+        dynamic_resources_map
+          .into_iter()
+          .map(|(id, resources)| {
+            (
+              id.id(mode.clone()),
+              resources
+                .into_iter()
+                .map(|(name, ty)| (name, ty.to_html_tag()))
+                .collect(),
+            )
+          })
+          .collect()
+      }),
+      updated_html_resources: result.updated_html_resources,
+    })
+  }
+
+  /// The policy that decides when the resources of the updates are regenerated
+  pub fn update_schedule_policy(&self) -> UpdateSchedulePolicy {
+    self.compiler.update_schedule_policy().into()
+  }
+
+  pub fn set_update_schedule_policy(&self, policy: UpdateSchedulePolicy) {
+    self.compiler.set_update_schedule_policy(policy.into());
+  }
+
+  /// Regenerate the resources of the [UpdateSchedule::Deferred] updates now, returns the number of the regenerated updates
+  pub fn flush_deferred_updates(&self) -> usize {
+    self.compiler.flush_deferred_updates()
+  }
+
+  /// Warnings of the last compilation
+  pub fn warnings(&self) -> Vec<String> {
+    self.compiler.context().log_store.lock().warnings().clone()
+  }
+
+  /// All the generated resources, sorted by name. Fails if a large asset can't be read from its source path
+  pub fn resources(&self) -> Result<Vec<OutputResource>> {
+    self.flush_deferred_updates();
+
+    let mut resources = self
+      .compiler
+      .context()
+      .resources_map
+      .iter()
+      .map(|resource| {
+        // large assets are read from the source path
+        let bytes = resource.read_bytes().map_err(|e| {
+          Error::new(format!(
+            "Failed to read the resource {}: {e}",
+            resource.name
+          ))
+        })?;
+
+        Ok(OutputResource {
+          name: resource.name.clone(),
+          bytes: bytes.into_owned(),
+          emitted: resource.emitted,
+        })
+      })
+      .collect::<Result<Vec<_>>>()?;
+    resources.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(resources)
+  }
+
+  pub fn resource(&self, name: &str) -> Option<Vec<u8>> {
+    self.flush_deferred_updates();
+
+    self
+      .compiler
+      .context()
+      .resources_map
+      .get(name)
+      .and_then(|resource| resource.read_bytes().ok().map(|bytes| bytes.into_owned()))
+  }
+
+  /// Ids of all the modules in the module graph, an id is the path relative to the root with the query
+  pub fn modules(&self) -> Vec<String> {
+    let module_graph = self.compiler.context().module_graph.read();
+    let mut modules = module_graph
+      .modules()
+      .into_iter()
+      .map(|m| m.id.to_string())
+      .collect::<Vec<_>>();
+    modules.sort();
+
+    modules
+  }
+
+  /// Entry module id -> entry name
+  pub fn entries(&self) -> HashMap<String, String> {
+    let module_graph = self.compiler.context().module_graph.read();
+
+    module_graph
+      .entries
+      .iter()
+      .map(|(id, name)| (id.to_string(), name.clone()))
+      .collect()
+  }
+
+  /// Modules imported by the module, `module_id` is a module id or an absolute path
+  pub fn dependencies(&self, module_id: &str) -> Result<Vec<String>> {
+    let id = self.get_module_id(module_id)?;
+    let module_graph = self.compiler.context().module_graph.read();
+
+    Ok(to_strings(module_graph.dependencies_ids(&id)))
+  }
+
+  /// Modules that import the module, `module_id` is a module id or an absolute path
+  pub fn dependents(&self, module_id: &str) -> Result<Vec<String>> {
+    let id = self.get_module_id(module_id)?;
+    let module_graph = self.compiler.context().module_graph.read();
+
+    Ok(to_strings(module_graph.dependents_ids(&id)))
+  }
+
+  /// The shortest import chains from the entries to the module, each chain starts with an entry and ends with the module
+  pub fn import_chains(&self, module_id: &str) -> Result<Vec<Vec<String>>> {
+    let id = self.get_module_id(module_id)?;
+
+    Ok(
+      self
+        .compiler
+        .import_chains(&id)
+        .into_iter()
+        .map(|chain| chain_to_module_ids(&chain))
+        .collect(),
+    )
+  }
+
+  /// The modules bundled because of importing `source` from `importer` and their size, e.g. for import cost editor extensions.
+  /// `importer` is a module id or an absolute path
+  pub fn import_cost(&self, source: &str, importer: &str) -> Result<ImportCost> {
+    let cost = self.compiler.import_cost(source, importer)?;
+
+    Ok(ImportCost {
+      module_id: cost.module_id.to_string(),
+      modules: to_strings(cost.modules),
+      size: cost.size,
+      gzip_size: cost.gzip_size,
+      shared_modules: to_strings(cost.shared_modules),
+    })
+  }
+
+  pub(crate) fn public_path(&self) -> String {
+    self.compiler.context().config.output.public_path.clone()
+  }
+
+  fn get_module_id(&self, module_id: &str) -> Result<ModuleId> {
+    let context = self.compiler.context();
+    let id = context.str_to_module_id(module_id);
+
+    if context.module_graph.read().has_module(&id) {
+      Ok(id)
+    } else {
+      Err(Error::new(format!(
+        "Module {module_id} is not found in the module graph"
+      )))
+    }
+  }
+}
+
+fn to_strings(ids: Vec<ModuleId>) -> Vec<String> {
+  ids.into_iter().map(|id| id.to_string()).collect()
+}
+
+fn chain_to_module_ids(chain: &[ImportChainStep]) -> Vec<String> {
+  chain
+    .first()
+    .map(|step| step.from.to_string())
+    .into_iter()
+    .chain(chain.iter().map(|step| step.to.to_string()))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn update_schedule_policy_of_core() {
+    let policy = UpdateSchedulePolicy {
+      module_change: UpdateSchedule::Deferred,
+      ..Default::default()
+    };
+    let core_policy = CoreUpdateSchedulePolicy::from(policy.clone());
+
+    assert_eq!(core_policy.module_change, CoreUpdateSchedule::Deferred);
+    assert_eq!(
+      core_policy.new_module_group,
+      CoreUpdateSchedulePolicy::default().new_module_group
+    );
+    assert_eq!(UpdateSchedulePolicy::from(core_policy), policy);
+  }
+}
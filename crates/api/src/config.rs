@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use farmfe_core::{
+  config::{
+    bool_or_obj::BoolOrObj, config_regex::ConfigRegex, persistent_cache::PersistentCacheConfig,
+    Config as CoreConfig, Mode as CoreMode, SourcemapConfig, TargetEnv as CoreTargetEnv,
+  },
+  serde_json,
+};
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+  #[default]
+  Development,
+  Production,
+}
+
+impl From<Mode> for CoreMode {
+  fn from(mode: Mode) -> Self {
+    match mode {
+      Mode::Development => CoreMode::Development,
+      Mode::Production => CoreMode::Production,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TargetEnv {
+  #[default]
+  Browser,
+  Node,
+  /// a library that runs in browser
+  Library,
+}
+
+impl From<TargetEnv> for CoreTargetEnv {
+  fn from(target_env: TargetEnv) -> Self {
+    match target_env {
+      TargetEnv::Browser => CoreTargetEnv::Browser,
+      TargetEnv::Node => CoreTargetEnv::Node,
+      TargetEnv::Library => CoreTargetEnv::Library,
+    }
+  }
+}
+
+/// The resolved config of the compiler, created by [ConfigBuilder]
+#[derive(Debug, Clone)]
+pub struct Config(pub(crate) CoreConfig);
+
+/// Builder of [Config]. Options that are not set keep the defaults of the farm cli, except that progress reporting is
+/// disabled and no entry is configured.
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+  config: CoreConfig,
+}
+
+impl ConfigBuilder {
+  /// `root` is the absolute path of the project, relative paths of the other options are resolved from it
+  pub fn new(root: impl Into<String>) -> Self {
+    let root = root.into();
+
+    Self {
+      config: CoreConfig {
+        input: HashMap::new(),
+        persistent_cache: Box::new(PersistentCacheConfig::get_default_config(&root)),
+        root,
+        progress: false,
+        ..Default::default()
+      },
+    }
+  }
+
+  /// Create the builder from the normalized config in json, the same as the config passed to the compiler by the farm cli
+  pub fn from_json(json: &str) -> Result<Self> {
+    let config = serde_json::from_str(json)
+      .map_err(|e| Error::new(format!("Failed to parse the config: {e}")))?;
+
+    Ok(Self { config })
+  }
+
+  /// Add an entry, e.g. `input("index", "./index.html")`
+  pub fn input(mut self, name: impl Into<String>, path: impl Into<String>) -> Self {
+    self.config.input.insert(name.into(), path.into());
+    self
+  }
+
+  pub fn mode(mut self, mode: Mode) -> Self {
+    self.config.mode = mode.into();
+    self
+  }
+
+  pub fn target_env(mut self, target_env: TargetEnv) -> Self {
+    self.config.output.target_env = target_env.into();
+    self
+  }
+
+  /// Directory the resources are written to, `dist` by default
+  pub fn output_path(mut self, path: impl Into<String>) -> Self {
+    self.config.output.path = path.into();
+    self
+  }
+
+  pub fn public_path(mut self, public_path: impl Into<String>) -> Self {
+    self.config.output.public_path = public_path.into();
+    self
+  }
+
+  /// Absolute path of the runtime entry, e.g. `node_modules/@farmfe/runtime/src/index.js`
+  pub fn runtime_path(mut self, path: impl Into<String>) -> Self {
+    self.config.runtime.path = path.into();
+    self
+  }
+
+  /// Absolute path of a runtime plugin, e.g. `node_modules/@farmfe/runtime-plugin-hmr/src/index.ts`
+  pub fn runtime_plugin(mut self, path: impl Into<String>) -> Self {
+    self.config.runtime.plugins.push(path.into());
+    self
+  }
+
+  /// Absolute path of `@swc/helpers`
+  pub fn swc_helpers_path(mut self, path: impl Into<String>) -> Self {
+    self.config.runtime.swc_helpers_path = path.into();
+    self
+  }
+
+  /// Replace `key` with the `value` expression in the code, e.g. `define("process.env.NODE_ENV", "\"production\"")`
+  pub fn define(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+    self
+      .config
+      .define
+      .insert(key.into(), serde_json::Value::String(value.into()));
+    self
+  }
+
+  /// Modules whose source matches the regex are not bundled
+  pub fn external(mut self, pattern: &str) -> Self {
+    self.config.external.push(ConfigRegex::new(pattern));
+    self
+  }
+
+  pub fn minify(mut self, enabled: bool) -> Self {
+    self.config.minify = Box::new(BoolOrObj::Bool(enabled));
+    self
+  }
+
+  pub fn tree_shaking(mut self, enabled: bool) -> Self {
+    self.config.tree_shaking = Box::new(BoolOrObj::Bool(enabled));
+    self
+  }
+
+  pub fn sourcemap(mut self, enabled: bool) -> Self {
+    self.config.sourcemap = Box::new(SourcemapConfig::Bool(enabled));
+    self
+  }
+
+  pub fn lazy_compilation(mut self, enabled: bool) -> Self {
+    self.config.lazy_compilation = enabled;
+    self
+  }
+
+  /// The cache is stored under `node_modules/.farm/cache` of the root
+  pub fn persistent_cache(mut self, enabled: bool) -> Self {
+    self.config.persistent_cache = Box::new(if enabled {
+      PersistentCacheConfig::get_default_config(&self.config.root)
+    } else {
+      PersistentCacheConfig::Bool(false)
+    });
+    self
+  }
+
+  pub fn build(self) -> Result<Config> {
+    if self.config.input.is_empty() {
+      return Err(Error::new("At least one input is required"));
+    }
+
+    Ok(Config(self.config))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn build_config() {
+    let config = ConfigBuilder::new("/project")
+      .input("index", "./index.html")
+      .mode(Mode::Production)
+      .target_env(TargetEnv::Node)
+      .define("DEBUG", "false")
+      .persistent_cache(false)
+      .build()
+      .unwrap()
+      .0;
+
+    assert_eq!(config.root, "/project");
+    assert_eq!(
+      config.input,
+      HashMap::from([("index".to_string(), "./index.html".to_string())])
+    );
+    assert!(matches!(config.mode, CoreMode::Production));
+    assert!(config.output.target_env.is_node());
+    assert!(!config.persistent_cache.enabled());
+    assert!(!config.progress);
+  }
+
+  #[test]
+  fn build_config_without_input() {
+    let err = ConfigBuilder::new("/project").build().unwrap_err();
+
+    assert_eq!(err.message(), "At least one input is required");
+  }
+}
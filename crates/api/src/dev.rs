@@ -0,0 +1,477 @@
+//! Serve a development compilation from an existing http server instead of the dev server of the farm cli.
+//! [DevHandler] maps the request paths to the resources of the compiler and builds the hmr messages of the updates,
+//! the host server sends the messages to the hmr clients over its own websocket. With the `tower` feature,
+//! [DevLayer] mounts the handler into a tower based server like axum and passes the other requests to the inner service.
+//! [MultiProjectDevHandler] serves the projects of a [MultiProjectCompiler] from one server by their mounts.
+
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_core::serde_json::{json, Map, Value};
+
+use crate::{
+  compiler::{Compiler, UpdateKind, UpdateOutput},
+  error::{Error, Result},
+  multi_project::MultiProjectCompiler,
+};
+
+/// A resource served by [DevHandler::handle]
+#[derive(Debug, Clone)]
+pub struct DevResponse {
+  /// name of the served resource, e.g. `index.html`
+  pub name: String,
+  pub content_type: &'static str,
+  pub body: Vec<u8>,
+}
+
+#[derive(Clone)]
+pub struct DevHandler {
+  compiler: Arc<Compiler>,
+  public_path: String,
+  spa: bool,
+}
+
+impl DevHandler {
+  /// [Compiler::compile] should be called before the requests are handled
+  pub fn new(compiler: Arc<Compiler>) -> Self {
+    let public_path = compiler.public_path();
+
+    Self {
+      compiler,
+      public_path,
+      spa: true,
+    }
+  }
+
+  /// Serve `index.html` for the html requests that match no resource, default to true
+  pub fn spa(mut self, spa: bool) -> Self {
+    self.spa = spa;
+    self
+  }
+
+  pub fn compiler(&self) -> &Arc<Compiler> {
+    &self.compiler
+  }
+
+  /// The resource of the url path, e.g. `/assets/index.js?t=1`. [None] if the path is not a resource of the compiler
+  /// and the request should be handled by the host server. `accept_html` is true if the request accepts `text/html`.
+  /// The deferred updates are regenerated before the resource is read, see [Compiler::flush_deferred_updates]
+  pub fn handle(&self, path: &str, accept_html: bool) -> Option<DevResponse> {
+    let name = resource_name(path, &self.public_path);
+
+    if let Some(body) = self.compiler.resource(name) {
+      return Some(DevResponse {
+        name: name.to_string(),
+        content_type: content_type(name),
+        body,
+      });
+    }
+
+    if !self.spa || !accept_html {
+      return None;
+    }
+
+    // the closest html entry of the path, e.g. `about.html` for `/about/team`
+    let mut segments = name.split('/').collect::<Vec<_>>();
+
+    while !segments.is_empty() {
+      let html = format!("{}.html", segments.join("/"));
+
+      if let Some(body) = self.compiler.resource(&html) {
+        return Some(DevResponse {
+          name: html,
+          content_type: content_type("index.html"),
+          body,
+        });
+      }
+
+      segments.pop();
+    }
+
+    self
+      .compiler
+      .resource("index.html")
+      .map(|body| DevResponse {
+        name: "index.html".to_string(),
+        content_type: content_type("index.html"),
+        body,
+      })
+  }
+
+  /// Recompile the changed files and return the messages that should be sent to the hmr clients, see [hmr_messages]
+  pub fn update(&self, paths: Vec<(String, UpdateKind)>) -> Result<Vec<String>> {
+    let output = self.compiler.update(paths)?;
+
+    Ok(hmr_messages(&output))
+  }
+}
+
+/// Dispatch the requests to the [DevHandler] of the project mounted on the url path, e.g. `/app1/index.js` is served
+/// by the resource `index.js` of the project mounted on `/app1/`
+#[derive(Clone)]
+pub struct MultiProjectDevHandler {
+  compiler: Arc<MultiProjectCompiler>,
+  handlers: HashMap<String, DevHandler>,
+}
+
+impl MultiProjectDevHandler {
+  /// [MultiProjectCompiler::compile] should be called before the requests are handled
+  pub fn new(compiler: Arc<MultiProjectCompiler>) -> Self {
+    let handlers = compiler
+      .projects()
+      .into_iter()
+      .map(|name| {
+        let mut handler = DevHandler::new(compiler.project(&name).unwrap().clone());
+        // the mount is stripped by the routing, the paths passed to the handler are relative to it
+        handler.public_path = "/".to_string();
+        (name, handler)
+      })
+      .collect();
+
+    Self { compiler, handlers }
+  }
+
+  /// See [DevHandler::spa], default to true
+  pub fn spa(mut self, spa: bool) -> Self {
+    for handler in self.handlers.values_mut() {
+      handler.spa = spa;
+    }
+
+    self
+  }
+
+  pub fn compiler(&self) -> &Arc<MultiProjectCompiler> {
+    &self.compiler
+  }
+
+  /// See [DevHandler::handle], [None] if no project is mounted on the path
+  pub fn handle(&self, path: &str, accept_html: bool) -> Option<DevResponse> {
+    let mounted = self.compiler.resolve_mount(path)?;
+
+    self.handlers[mounted.name].handle(&mounted.path, accept_html)
+  }
+
+  /// Recompile the changed files of the project, see [DevHandler::update]
+  pub fn update(&self, project: &str, paths: Vec<(String, UpdateKind)>) -> Result<Vec<String>> {
+    self
+      .handlers
+      .get(project)
+      .ok_or_else(|| Error::new(format!("Project {project} is not found")))?
+      .update(paths)
+  }
+}
+
+/// The `farm-update` message of the update, followed by a `farm-prune-resources` message if resources are removed and a `full-reload`
+/// message if the page should be reloaded, like the messages of the hmr engine of the farm cli
+pub fn hmr_messages(output: &UpdateOutput) -> Vec<String> {
+  let (dynamic_resources, dynamic_module_resources_map) = dynamic_resources(output);
+  let mut messages = vec![json!({
+    "type": "farm-update",
+    "result": {
+      "added": output.added,
+      "changed": output.changed,
+      "removed": output.removed,
+      "immutableModules": output.immutable_modules.trim(),
+      "mutableModules": output.mutable_modules.trim(),
+      "boundaries": output.boundaries,
+      "fullReload": output.full_reload.as_ref().map(|full_reload| json!({
+        "module": full_reload.module,
+        "path": full_reload.path,
+        "reason": full_reload.reason.as_str(),
+      })),
+      "dynamicResources": dynamic_resources,
+      "dynamicModuleResourcesMap": dynamic_module_resources_map,
+    }
+  })
+  .to_string()];
+
+  if !output.removed_resources.is_empty() {
+    messages.push(
+      json!({
+        "type": "farm-prune-resources",
+        "resources": output.removed_resources,
+      })
+      .to_string(),
+    );
+  }
+
+  if output.full_reload.is_some() || !output.updated_html_resources.is_empty() {
+    messages.push(json!({ "type": "full-reload" }).to_string());
+  }
+
+  messages
+}
+
+/// The resources of the dynamic imports and the indexes of the resources of each module, the arguments of
+/// `setDynamicModuleResourcesMap` of the runtime. Both are null if the dynamic imports are not changed
+fn dynamic_resources(output: &UpdateOutput) -> (Value, Value) {
+  let Some(dynamic_resources_map) = &output.dynamic_resources_map else {
+    return (Value::Null, Value::Null);
+  };
+
+  let mut resources = vec![];
+  let mut resource_indexes = HashMap::new();
+  let mut module_resources = Map::new();
+
+  for (id, module_resources_of_id) in dynamic_resources_map {
+    let indexes = module_resources_of_id
+      .iter()
+      .map(|(name, tag)| {
+        *resource_indexes
+          .entry((name.as_str(), tag.as_str()))
+          .or_insert_with(|| {
+            resources.push(json!({
+              "path": name,
+              "type": if tag == "script" { 0 } else { 1 },
+            }));
+            resources.len() - 1
+          })
+      })
+      .collect::<Vec<_>>();
+    module_resources.insert(id.clone(), json!(indexes));
+  }
+
+  (Value::Array(resources), Value::Object(module_resources))
+}
+
+/// The resource name of the url path without the public path, `index.html` for the root
+fn resource_name<'a>(path: &'a str, public_path: &str) -> &'a str {
+  let path = path.split(['?', '#']).next().unwrap_or_default();
+  let public_path = public_path.trim_end_matches('/');
+  // `/app` is the prefix of `/app/index.js` but not of `/application.js`
+  let name = path
+    .strip_prefix(public_path)
+    .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+    .unwrap_or(path)
+    .trim_start_matches('/');
+
+  if name.is_empty() {
+    "index.html"
+  } else {
+    name
+  }
+}
+
+fn content_type(name: &str) -> &'static str {
+  let ext = name
+    .rsplit_once('.')
+    .map(|(_, ext)| ext)
+    .unwrap_or_default();
+
+  match ext {
+    "html" | "htm" => "text/html; charset=utf-8",
+    "js" | "mjs" | "cjs" => "text/javascript; charset=utf-8",
+    "css" => "text/css; charset=utf-8",
+    "json" | "map" => "application/json",
+    "svg" => "image/svg+xml",
+    "png" => "image/png",
+    "jpg" | "jpeg" => "image/jpeg",
+    "gif" => "image/gif",
+    "webp" => "image/webp",
+    "ico" => "image/x-icon",
+    "wasm" => "application/wasm",
+    "woff" => "font/woff",
+    "woff2" => "font/woff2",
+    "ttf" => "font/ttf",
+    "txt" => "text/plain; charset=utf-8",
+    _ => "application/octet-stream",
+  }
+}
+
+#[cfg(feature = "tower")]
+mod tower {
+  use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+  };
+
+  use http::{header, HeaderValue, Method, Request, Response};
+  use tower_service::Service;
+
+  use super::DevHandler;
+
+  /// Mount [DevHandler] in front of `inner`, e.g. `Router::new().route(..).layer(DevLayer::new(handler))` of axum
+  #[derive(Clone)]
+  pub struct DevLayer {
+    handler: DevHandler,
+  }
+
+  impl DevLayer {
+    pub fn new(handler: DevHandler) -> Self {
+      Self { handler }
+    }
+  }
+
+  impl<S> tower_layer::Layer<S> for DevLayer {
+    type Service = DevService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+      DevService {
+        handler: self.handler.clone(),
+        inner,
+      }
+    }
+  }
+
+  /// Responds the `GET` and `HEAD` requests of the resources, the other requests are passed to `inner`
+  #[derive(Clone)]
+  pub struct DevService<S> {
+    handler: DevHandler,
+    inner: S,
+  }
+
+  impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for DevService<S>
+  where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+    ResBody: From<Vec<u8>> + Send + 'static,
+  {
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+      self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+      let accept_html = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"));
+      let resource = if matches!(*req.method(), Method::GET | Method::HEAD) {
+        self.handler.handle(req.uri().path(), accept_html)
+      } else {
+        None
+      };
+
+      let Some(resource) = resource else {
+        return Box::pin(self.inner.call(req));
+      };
+
+      let body = if req.method() == Method::HEAD {
+        vec![]
+      } else {
+        resource.body
+      };
+      let mut response = Response::new(ResBody::from(body));
+      response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(resource.content_type),
+      );
+      response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+
+      Box::pin(async move { Ok(response) })
+    }
+  }
+}
+
+#[cfg(feature = "tower")]
+pub use self::tower::{DevLayer, DevService};
+
+#[cfg(test)]
+mod tests {
+  use farmfe_core::serde_json::{self, Value};
+
+  use super::*;
+  use crate::{config::ConfigBuilder, multi_project::Project};
+
+  #[test]
+  fn hmr_messages_of_update() {
+    let output = UpdateOutput {
+      changed: vec!["src/index.ts".to_string()],
+      mutable_modules: "{}\n".to_string(),
+      removed_resources: vec!["src_about.js".to_string()],
+      ..Default::default()
+    };
+    let messages = hmr_messages(&output)
+      .into_iter()
+      .map(|message| serde_json::from_str::<Value>(&message).unwrap())
+      .collect::<Vec<_>>();
+
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0]["type"], "farm-update");
+    assert_eq!(messages[0]["result"]["changed"][0], "src/index.ts");
+    assert_eq!(messages[0]["result"]["mutableModules"], "{}");
+    assert_eq!(messages[0]["result"]["dynamicResources"], Value::Null);
+    assert_eq!(messages[1]["type"], "farm-prune-resources");
+    assert_eq!(messages[1]["resources"][0], "src_about.js");
+  }
+
+  #[test]
+  fn hmr_messages_of_dynamic_imports_and_reloads() {
+    let output = UpdateOutput {
+      added: vec!["src/about.ts".to_string()],
+      dynamic_resources_map: Some(HashMap::from([(
+        "src/about.ts".to_string(),
+        vec![
+          ("src_about.js".to_string(), "script".to_string()),
+          ("src_about.css".to_string(), "link".to_string()),
+        ],
+      )])),
+      updated_html_resources: vec!["index.html".to_string()],
+      ..Default::default()
+    };
+    let messages = hmr_messages(&output)
+      .into_iter()
+      .map(|message| serde_json::from_str::<Value>(&message).unwrap())
+      .collect::<Vec<_>>();
+
+    assert_eq!(messages.len(), 2);
+    assert_eq!(
+      messages[0]["result"]["dynamicResources"],
+      json!([
+        { "path": "src_about.js", "type": 0 },
+        { "path": "src_about.css", "type": 1 },
+      ])
+    );
+    assert_eq!(
+      messages[0]["result"]["dynamicModuleResourcesMap"],
+      json!({ "src/about.ts": [0, 1] })
+    );
+    assert_eq!(messages[1]["type"], "full-reload");
+  }
+
+  #[test]
+  fn resource_name_of_path() {
+    assert_eq!(resource_name("/", "/"), "index.html");
+    assert_eq!(resource_name("/index.js?t=1", "/"), "index.js");
+    assert_eq!(resource_name("/app/index.js", "/app/"), "index.js");
+    assert_eq!(resource_name("/app", "/app/"), "index.html");
+    // the public path only matches whole segments
+    assert_eq!(resource_name("/application.js", "/app"), "application.js");
+  }
+
+  #[test]
+  fn route_requests_by_mount() {
+    let project = |name: &str, mount: &str| Project {
+      name: name.to_string(),
+      mount: mount.to_string(),
+      config: ConfigBuilder::new(env!("CARGO_MANIFEST_DIR"))
+        .input("index", "./index.html")
+        .persistent_cache(false)
+        .build()
+        .unwrap(),
+    };
+    let compiler =
+      MultiProjectCompiler::new(vec![project("app1", "/app1/"), project("app2", "/app2/")])
+        .unwrap();
+    let handler = MultiProjectDevHandler::new(Arc::new(compiler)).spa(false);
+
+    let mounted = handler
+      .compiler()
+      .resolve_mount("/app2/index.js?t=1")
+      .unwrap();
+    assert_eq!(mounted.name, "app2");
+    assert_eq!(mounted.path, "/index.js?t=1");
+    assert_eq!(handler.handlers["app2"].public_path, "/");
+    // nothing is compiled, the mounted paths fall through to the host server as well
+    assert!(handler.handle("/app2/index.js", true).is_none());
+    assert!(handler.handle("/app10/index.js", true).is_none());
+    assert!(handler.update("app3", vec![]).is_err());
+  }
+}
@@ -0,0 +1,37 @@
+use std::fmt::{Display, Formatter};
+
+use farmfe_core::error::CompilationError;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors of the compiler, the message is the same as the one printed by the farm cli
+#[derive(Debug)]
+pub struct Error {
+  message: String,
+}
+
+impl Error {
+  pub(crate) fn new(message: impl Into<String>) -> Self {
+    Self {
+      message: message.into(),
+    }
+  }
+
+  pub fn message(&self) -> &str {
+    &self.message
+  }
+}
+
+impl Display for Error {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.message)
+  }
+}
+
+impl std::error::Error for Error {}
+
+impl From<CompilationError> for Error {
+  fn from(e: CompilationError) -> Self {
+    Self::new(e.to_string())
+  }
+}
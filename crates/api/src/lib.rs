@@ -0,0 +1,38 @@
+//! Stable Rust API of the farm compiler, for tools that embed the compiler, e.g. SSR servers, test runners and monitoring agents.
+//!
+//! The internal crates like `farmfe_core` and `farmfe_compiler` change frequently. This crate wraps them behind a small surface
+//! that follows semver, breaking changes of the items exported here are only made in major versions. Types of the internal
+//! crates never appear in the signatures, ids of modules and names of resources are plain strings.
+//!
+//! ```no_run
+//! use farmfe_api::{Compiler, ConfigBuilder, Mode, UpdateKind};
+//!
+//! let config = ConfigBuilder::new("/path/to/project")
+//!   .input("index", "./index.html")
+//!   .mode(Mode::Development)
+//!   .runtime_path("/path/to/@farmfe/runtime/src/index.js")
+//!   .build()?;
+//! let compiler = Compiler::new(config)?;
+//!
+//! compiler.compile()?;
+//! let update = compiler.update(vec![("/path/to/project/src/index.ts".to_string(), UpdateKind::Updated)])?;
+//! # Ok::<(), farmfe_api::Error>(())
+//! ```
+#![deny(clippy::all)]
+
+mod compiler;
+mod config;
+mod dev;
+mod error;
+mod multi_project;
+
+pub use compiler::{
+  Compiler, FullReload, FullReloadReason, ImportCost, OutputResource, UpdateKind, UpdateOutput,
+  UpdateSchedule, UpdateSchedulePolicy,
+};
+pub use config::{Config, ConfigBuilder, Mode, TargetEnv};
+pub use dev::{hmr_messages, DevHandler, DevResponse, MultiProjectDevHandler};
+#[cfg(feature = "tower")]
+pub use dev::{DevLayer, DevService};
+pub use error::{Error, Result};
+pub use multi_project::{MountedProject, MultiProjectCompiler, Project};
@@ -0,0 +1,84 @@
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_compiler::multi_project::{
+  MultiProjectCompiler as CoreMultiProjectCompiler, ProjectOptions,
+};
+
+use crate::{compiler::Compiler, config::Config, error::Result};
+
+/// A project of [MultiProjectCompiler]
+#[derive(Debug, Clone)]
+pub struct Project {
+  /// unique name of the project, used as the suffix of the persistent cache namespace
+  pub name: String,
+  /// url prefix the project is served on, for example `/app1/`
+  pub mount: String,
+  pub config: Config,
+}
+
+/// The project serving a url, see [MultiProjectCompiler::resolve_mount]
+pub struct MountedProject<'a> {
+  pub name: &'a str,
+  pub compiler: &'a Arc<Compiler>,
+  /// the url path relative to the mount, starting with `/`
+  pub path: String,
+}
+
+/// Multiple projects in one process, every project has its own compilation and persistent cache
+/// while all of them share the same thread pool
+pub struct MultiProjectCompiler {
+  compiler: CoreMultiProjectCompiler,
+  projects: HashMap<String, Arc<Compiler>>,
+}
+
+impl MultiProjectCompiler {
+  pub fn new(projects: Vec<Project>) -> Result<Self> {
+    let compiler = CoreMultiProjectCompiler::new(
+      projects
+        .into_iter()
+        .map(|project| ProjectOptions {
+          name: project.name,
+          mount: project.mount,
+          config: project.config.0,
+          plugins: vec![],
+        })
+        .collect(),
+    )?;
+    let projects = compiler
+      .projects()
+      .into_iter()
+      .map(|name| {
+        let project = Compiler::from_core(compiler.get(name).unwrap().clone());
+        (name.clone(), Arc::new(project))
+      })
+      .collect();
+
+    Ok(Self { compiler, projects })
+  }
+
+  /// Compile all projects concurrently, the first error is returned
+  pub fn compile(&self) -> Result<()> {
+    self.compiler.compile_all().map_err(Into::into)
+  }
+
+  pub fn project(&self, name: &str) -> Option<&Arc<Compiler>> {
+    self.projects.get(name)
+  }
+
+  /// Names of the projects, the project with the most specific mount comes first
+  pub fn projects(&self) -> Vec<String> {
+    self.compiler.projects().into_iter().cloned().collect()
+  }
+
+  /// Find the project serving `url`, e.g. the project mounted on `/app1/` for `/app1/index.js`
+  pub fn resolve_mount(&self, url: &str) -> Option<MountedProject<'_>> {
+    let mounted = self.compiler.resolve_mount(url)?;
+    let (name, compiler) = self.projects.get_key_value(mounted.name)?;
+
+    Some(MountedProject {
+      name,
+      compiler,
+      path: mounted.path,
+    })
+  }
+}
@@ -0,0 +1,263 @@
+//! Checkpoint of the build stage, see [farmfe_core::config::checkpoint::CheckpointConfig].
+//! The module graph is persisted after the build stage and restored by the next compilation of the same input,
+//! so a crashed or interrupted build resumes at the generate stage.
+use std::{
+  collections::{BTreeMap, HashMap},
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  cache::cache_store::FARM_CACHE_VERSION,
+  cache_item,
+  context::CompilationContext,
+  module::{
+    module_graph::{ModuleGraph, ModuleGraphEdge},
+    Module, ModuleId, ModuleMetaData,
+  },
+  rayon::prelude::*,
+  relative_path::RelativePath,
+  resource::Resource,
+  rkyv::Deserialize,
+  serde_json::{self, Value},
+  serialize, try_deserialize,
+};
+
+#[cache_item]
+struct BuildCheckpoint {
+  modules: Vec<Module>,
+  /// edges in the order of the dependencies of each module
+  edges: Vec<CheckpointEdge>,
+  entries: HashMap<ModuleId, String>,
+  /// module -> its watch dependencies, e.g. the scss files imported by `index.scss`
+  watch_dependencies: HashMap<ModuleId, Vec<ModuleId>>,
+  /// resolved path -> content hash of the files the module graph is built from
+  file_hashes: HashMap<String, String>,
+  /// resources emitted by plugins during the build stage, e.g. static assets
+  resources: Vec<Resource>,
+}
+
+#[cache_item]
+struct CheckpointEdge {
+  from: ModuleId,
+  to: ModuleId,
+  edge: ModuleGraphEdge,
+}
+
+/// Path of the checkpoint of the input, [None] if checkpoints are disabled.
+/// The checkpoint is keyed by the config and the plugins, so a checkpoint of other options is never restored
+fn checkpoint_path(
+  input: &HashMap<String, String>,
+  context: &Arc<CompilationContext>,
+) -> Option<PathBuf> {
+  let checkpoint_config = context.config.checkpoint.as_ref()?;
+  let input = input.iter().collect::<BTreeMap<_, _>>();
+  let plugins = context
+    .plugin_driver
+    .plugins
+    .iter()
+    .map(|p| p.name())
+    .collect::<Vec<_>>();
+  let config = serde_json::to_value(&*context.config)
+    .map(sort_object_keys)
+    .unwrap_or_default();
+  let key =
+    serde_json::to_string(&(config, input, plugins, FARM_CACHE_VERSION)).unwrap_or_default();
+  let hash = context.config.hash.hash(key.as_bytes(), 32);
+
+  Some(
+    RelativePath::new(&checkpoint_config.dir)
+      .to_logical_path(&context.config.root)
+      .join(format!("{hash}.checkpoint")),
+  )
+}
+
+/// The config contains hash maps whose iteration order differs between processes
+fn sort_object_keys(value: Value) -> Value {
+  match value {
+    Value::Object(map) => {
+      let mut entries = map.into_iter().collect::<Vec<_>>();
+      entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+      Value::Object(
+        entries
+          .into_iter()
+          .map(|(key, value)| (key, sort_object_keys(value)))
+          .collect(),
+      )
+    }
+    Value::Array(values) => Value::Array(values.into_iter().map(sort_object_keys).collect()),
+    value => value,
+  }
+}
+
+/// Content hashes of the files on disk, virtual modules are skipped
+fn hash_files(
+  module_ids: Vec<&ModuleId>,
+  context: &Arc<CompilationContext>,
+) -> HashMap<String, String> {
+  module_ids
+    .into_par_iter()
+    .filter_map(|module_id| {
+      let resolved_path = module_id.resolved_path(&context.config.root);
+      let bytes = std::fs::read(&resolved_path).ok()?;
+
+      Some((resolved_path, context.config.hash.hash(&bytes, 32)))
+    })
+    .collect()
+}
+
+/// Persist the module graph and the resources emitted by the build stage, it's written to a temporary file first
+/// so a build killed while writing never leaves a truncated checkpoint behind
+pub fn write_checkpoint(input: &HashMap<String, String>, context: &Arc<CompilationContext>) {
+  let Some(path) = checkpoint_path(input, context) else {
+    return;
+  };
+
+  let checkpoint = {
+    let module_graph = context.module_graph.read();
+    let watch_graph = context.watch_graph.read();
+    let modules = module_graph.modules();
+
+    let edges = modules
+      .iter()
+      .flat_map(|module| {
+        module_graph
+          .dependencies(&module.id)
+          .into_iter()
+          .map(|(dep, edge)| CheckpointEdge {
+            from: module.id.clone(),
+            to: dep,
+            edge: edge.clone(),
+          })
+      })
+      .collect();
+    let watch_dependencies = modules
+      .iter()
+      .filter(|module| watch_graph.has_module(&module.id))
+      .map(|module| {
+        let deps = watch_graph.dependencies(&module.id);
+        (module.id.clone(), deps.into_iter().cloned().collect())
+      })
+      .collect();
+
+    let mut files = modules.iter().map(|m| &m.id).collect::<Vec<_>>();
+    files.extend(watch_graph.modules());
+
+    BuildCheckpoint {
+      file_hashes: hash_files(files, context),
+      modules: modules.into_iter().cloned().collect(),
+      edges,
+      entries: module_graph.entries.clone(),
+      watch_dependencies,
+      resources: context
+        .resources_map
+        .iter()
+        .map(|resource| resource.value().clone())
+        .collect(),
+    }
+  };
+
+  let bytes = serialize!(&checkpoint);
+  let tmp_path = path.with_extension("tmp");
+  let result = Path::new(&path)
+    .parent()
+    .map_or(Ok(()), std::fs::create_dir_all)
+    .and_then(|_| std::fs::write(&tmp_path, bytes))
+    .and_then(|_| std::fs::rename(&tmp_path, &path));
+
+  if let Err(err) = result {
+    context.logger.warn(
+      module_path!(),
+      format!("failed to write the build checkpoint {path:?}: {err}"),
+    );
+  }
+}
+
+/// Restore the module graph from the checkpoint of the input, returns false if there is no valid checkpoint
+/// or any of the files it's built from changed, then the module graph should be built as usual
+pub fn restore_checkpoint(
+  input: &HashMap<String, String>,
+  context: &Arc<CompilationContext>,
+) -> bool {
+  let Some(path) = checkpoint_path(input, context) else {
+    return false;
+  };
+  let Ok(bytes) = std::fs::read(&path) else {
+    return false;
+  };
+
+  // the checkpoint may be written by another version of farm or truncated by the crash
+  let Some(checkpoint) = try_deserialize!(&bytes, BuildCheckpoint) else {
+    discard_checkpoint(&path, "it's invalid", context);
+    return false;
+  };
+  let changed = checkpoint
+    .file_hashes
+    .par_iter()
+    .any(|(resolved_path, hash)| {
+      std::fs::read(resolved_path)
+        .map_or(true, |bytes| context.config.hash.hash(&bytes, 32) != *hash)
+    });
+
+  if changed {
+    let _ = std::fs::remove_file(&path);
+    return false;
+  }
+
+  let mut module_graph = context.module_graph.write();
+
+  for mut module in checkpoint.modules {
+    // the same as the modules restored from the persistent cache, marks and the generate stage results are re-resolved
+    if let ModuleMetaData::Script(script) = module.meta.as_mut() {
+      script.top_level_mark = 0;
+      script.unresolved_mark = 0;
+    }
+    module.module_groups.clear();
+    module.resource_pot = None;
+    module.used_exports.clear();
+
+    module_graph.add_module(module);
+  }
+
+  for CheckpointEdge { from, to, edge } in checkpoint.edges {
+    if let Err(err) = module_graph.add_edge(&from, &to, edge) {
+      // the module graph is empty before it's restored
+      *module_graph = ModuleGraph::new();
+      drop(module_graph);
+      discard_checkpoint(&path, &err.to_string(), context);
+      return false;
+    }
+  }
+
+  module_graph.entries = checkpoint.entries;
+  module_graph.update_execution_order_for_modules_by(context.config.execution_order_tie_break);
+  drop(module_graph);
+
+  for (module_id, deps) in checkpoint.watch_dependencies {
+    let _ = context.add_watch_files(module_id, deps);
+  }
+
+  for resource in checkpoint.resources {
+    context
+      .resources_map
+      .insert(resource.name.clone(), resource);
+  }
+
+  true
+}
+
+fn discard_checkpoint(path: &Path, reason: &str, context: &Arc<CompilationContext>) {
+  context.logger.warn(
+    module_path!(),
+    format!("the build checkpoint {path:?} is discarded, {reason}"),
+  );
+  let _ = std::fs::remove_file(path);
+}
+
+/// Remove the checkpoint of the input once the build finished
+pub fn remove_checkpoint(input: &HashMap<String, String>, context: &Arc<CompilationContext>) {
+  if let Some(path) = checkpoint_path(input, context) {
+    let _ = std::fs::remove_file(path);
+  }
+}
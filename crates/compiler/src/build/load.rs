@@ -3,8 +3,16 @@ use std::sync::Arc;
 use farmfe_core::{
   context::CompilationContext,
   error::{CompilationError, Result},
-  plugin::{PluginHookContext, PluginLoadHookParam, PluginLoadHookResult},
+  module::{
+    sub_module::{parent_query, sub_module_of_query},
+    ModuleId,
+  },
+  plugin::{
+    PluginHookContext, PluginLoadHookParam, PluginLoadHookResult, PluginSplitModuleHookParam,
+  },
 };
+use farmfe_toolkit::source_editor::SourceEditor;
+use farmfe_utils::stringify_query;
 
 pub fn load(
   load_param: &PluginLoadHookParam,
@@ -14,6 +22,10 @@ pub fn load(
   #[cfg(feature = "profile")]
   farmfe_core::puffin::profile_function!();
 
+  if let Some((kind, index)) = sub_module_of_query(&load_param.query) {
+    return load_sub_module(load_param, &kind, index, context, hook_context);
+  }
+
   let loaded = match context
     .plugin_driver
     .load(load_param, context, hook_context)
@@ -35,5 +47,104 @@ pub fn load(
     }
   };
 
-  Ok(loaded)
+  let split = context
+    .plugin_driver
+    .split_module(
+      &PluginSplitModuleHookParam {
+        module_id: &load_param.module_id.as_str().into(),
+        resolved_path: load_param.resolved_path,
+        content: &loaded.content,
+        module_type: &loaded.module_type,
+      },
+      context,
+    )
+    .map_err(|e| CompilationError::LoadError {
+      resolved_path: load_param.module_id.to_string(),
+      source: Some(Box::new(e)),
+    })?;
+
+  Ok(match split {
+    Some(split) => PluginLoadHookResult {
+      content: split.content,
+      module_type: split.module_type,
+      source_map: None,
+    },
+    None => loaded,
+  })
+}
+
+/// Load the block of the single file component, the parent module is loaded and split again so the sub module
+/// does not depend on the state of the parent module, e.g. when the parent module is cached
+fn load_sub_module(
+  load_param: &PluginLoadHookParam,
+  kind: &str,
+  index: usize,
+  context: &Arc<CompilationContext>,
+  hook_context: &PluginHookContext,
+) -> Result<PluginLoadHookResult> {
+  let query = parent_query(&load_param.query);
+  let parent_id = ModuleId::new(
+    load_param.resolved_path,
+    &stringify_query(&query),
+    &context.config.root,
+  );
+  let parent_param = PluginLoadHookParam {
+    module_id: parent_id.to_string(),
+    resolved_path: load_param.resolved_path,
+    query,
+    meta: load_param.meta.clone(),
+  };
+  let load_error = |source: Option<CompilationError>| CompilationError::LoadError {
+    resolved_path: load_param.module_id.to_string(),
+    source: source.map(|e| e.into()),
+  };
+
+  let parent = context
+    .plugin_driver
+    .load(&parent_param, context, hook_context)
+    .map_err(|e| load_error(Some(e)))?
+    .ok_or_else(|| load_error(None))?;
+  let split = context
+    .plugin_driver
+    .split_module(
+      &PluginSplitModuleHookParam {
+        module_id: &parent_id,
+        resolved_path: load_param.resolved_path,
+        content: &parent.content,
+        module_type: &parent.module_type,
+      },
+      context,
+    )
+    .map_err(|e| load_error(Some(e)))?
+    .ok_or_else(|| load_error(None))?;
+  let sub_module = split
+    .sub_modules
+    .into_iter()
+    .find(|sub_module| sub_module.kind == kind && sub_module.index == index)
+    .ok_or_else(|| {
+      load_error(Some(CompilationError::GenericError(format!(
+        "{} does not have the block {kind}.{index}",
+        parent_id.to_string()
+      ))))
+    })?;
+
+  // map the block back to its position in the parent file
+  let mut editor = SourceEditor::new(parent.content.as_str(), load_param.resolved_path);
+  if sub_module.start > 0 {
+    editor.remove(0, sub_module.start)?;
+  }
+  if sub_module.end < parent.content.len() {
+    editor.remove(sub_module.end, parent.content.len())?;
+  }
+  let (content, source_map) = editor.generate();
+  let mut buf = vec![];
+  source_map
+    .to_writer(&mut buf)
+    .expect("failed to write sourcemap");
+
+  Ok(PluginLoadHookResult {
+    content,
+    module_type: sub_module.module_type,
+    source_map: Some(String::from_utf8(buf).unwrap()),
+  })
 }
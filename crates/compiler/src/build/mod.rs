@@ -1,5 +1,5 @@
 use std::{
-  collections::HashMap,
+  collections::{HashMap, HashSet},
   path::PathBuf,
   sync::{
     mpsc::{channel, Receiver, Sender},
@@ -21,6 +21,7 @@ use farmfe_core::{
     plugin_driver::PluginDriverTransformHookResult, PluginAnalyzeDepsHookResultEntry,
     PluginHookContext, PluginLoadHookParam, PluginParseHookParam, PluginProcessModuleHookParam,
     PluginResolveHookParam, PluginResolveHookResult, PluginTransformHookParam, ResolveKind,
+    ResolveTrace,
   },
   rayon::ThreadPool,
   serde_json::json,
@@ -32,8 +33,15 @@ use farmfe_utils::stringify_query;
 
 use crate::{
   build::{
-    analyze_deps::analyze_deps, finalize_module::finalize_module, load::load, parse::parse,
-    resolve::resolve, transform::transform,
+    analyze_deps::analyze_deps,
+    checkpoint::restore_checkpoint,
+    finalize_module::finalize_module,
+    load::load,
+    normalize_module_system::normalize_module_system,
+    parse::parse,
+    polyfill_entries::{inject_polyfill_entries, should_inject_polyfill_entries},
+    resolve::resolve,
+    transform::transform,
   },
   Compiler,
 };
@@ -55,13 +63,23 @@ macro_rules! call_and_catch_error {
 }
 
 pub(crate) mod analyze_deps;
+pub(crate) mod checkpoint;
 pub(crate) mod finalize_module;
 pub(crate) mod load;
 pub(crate) mod module_cache;
+pub(crate) mod normalize_module_system;
 pub(crate) mod parse;
+pub(crate) mod polyfill_entries;
 pub(crate) mod resolve;
 pub(crate) mod transform;
 
+/// A dependency of a built module, the cached module id and resolve trace are set when it's restored from the cache
+pub(crate) type ModuleDependency = (
+  PluginAnalyzeDepsHookResultEntry,
+  Option<ModuleId>,
+  Option<ResolveTrace>,
+);
+
 #[derive(Debug)]
 pub(crate) struct ResolveModuleIdResult {
   pub module_id: ModuleId,
@@ -80,21 +98,25 @@ pub(crate) struct BuildModuleGraphThreadedParams {
   pub err_sender: Sender<CompilationError>,
   pub order: usize,
   pub cached_dependency: Option<ModuleId>,
+  /// how the cached dependency was resolved, it's not resolved again
+  pub cached_resolve_trace: Option<ResolveTrace>,
 }
 
 pub(crate) struct HandleDependenciesParams {
   pub module: Module,
   pub resolve_param: PluginResolveHookParam,
   pub order: usize,
-  pub deps: Vec<(PluginAnalyzeDepsHookResultEntry, Option<ModuleId>)>,
+  /// how the module is resolved from the importer
+  pub resolve_trace: Option<ResolveTrace>,
+  pub deps: Vec<ModuleDependency>,
   pub thread_pool: Arc<ThreadPool>,
   pub err_sender: Sender<CompilationError>,
   pub context: Arc<CompilationContext>,
 }
 
 enum ResolveModuleResult {
-  /// The module is already built
-  Built(ModuleId),
+  /// The module is already built, with how it's resolved if it's not a cached dependency
+  Built(ModuleId, Option<ResolveTrace>),
   Cached(ModuleId),
   /// A full new normal module resolved successfully
   Success(Box<ResolvedModuleInfo>),
@@ -115,12 +137,12 @@ impl Compiler {
     }
   }
 
-  pub(crate) fn build(&self) -> Result<()> {
+  pub(crate) fn build(&self, input: &HashMap<String, String>) -> Result<()> {
     self.context.plugin_driver.build_start(&self.context)?;
 
     let (err_sender, err_receiver) = Self::create_thread_channel();
 
-    for (order, (name, source)) in self.context.config.input.iter().enumerate() {
+    for (order, (name, source)) in input.iter().enumerate() {
       let params = BuildModuleGraphThreadedParams {
         thread_pool: self.thread_pool.clone(),
         resolve_param: PluginResolveHookParam {
@@ -132,6 +154,7 @@ impl Compiler {
         err_sender: err_sender.clone(),
         order,
         cached_dependency: None,
+        cached_resolve_trace: None,
       };
       Self::build_module_graph_threaded(params);
     }
@@ -144,7 +167,7 @@ impl Compiler {
       errors.push(err);
     }
 
-    self.handle_global_log(&mut errors);
+    Self::handle_global_log(&self.context, &mut errors);
     if !errors.is_empty() {
       // set stats if stats is enabled
       self.context.record_manager.set_build_end_time();
@@ -178,7 +201,8 @@ impl Compiler {
 
     // Topo sort the module graph
     let mut module_graph = self.context.module_graph.write();
-    module_graph.update_execution_order_for_modules();
+    module_graph
+      .update_execution_order_for_modules_by(self.context.config.execution_order_tie_break);
     drop(module_graph);
 
     // set stats if stats is enabled
@@ -190,19 +214,59 @@ impl Compiler {
     }
   }
 
-  pub(crate) fn handle_global_log(&self, errors: &mut Vec<CompilationError>) {
-    for err in self.context.log_store.lock().errors() {
+  /// Restore the module graph from the checkpoint written by an interrupted build of the same input instead of building it,
+  /// returns false if the module graph is not empty or there is no valid checkpoint
+  pub(crate) fn resume_build(&self, input: &HashMap<String, String>) -> Result<bool> {
+    if !self.context.module_graph.read().modules().is_empty()
+      || !restore_checkpoint(input, &self.context)
+    {
+      return Ok(false);
+    }
+
+    self.context.plugin_driver.build_start(&self.context)?;
+    self.set_module_graph_stats();
+    self.context.plugin_driver.build_end(&self.context)?;
+
+    Ok(true)
+  }
+
+  /// Remove the modules that are not reachable from the entries, which are built by the previous compilation
+  pub(crate) fn remove_unreachable_modules(&self) {
+    let mut module_graph = self.context.module_graph.write();
+    let mut reachable = HashSet::new();
+
+    for entry in module_graph.entries.keys() {
+      module_graph.dfs(entry, &mut |id| {
+        reachable.insert(id.clone());
+      });
+    }
+
+    let unreachable = module_graph
+      .modules()
+      .into_iter()
+      .filter(|module| !reachable.contains(&module.id))
+      .map(|module| module.id.clone())
+      .collect::<Vec<_>>();
+
+    for module_id in unreachable {
+      module_graph.remove_module(&module_id);
+    }
+  }
+
+  pub(crate) fn handle_global_log(
+    context: &Arc<CompilationContext>,
+    errors: &mut Vec<CompilationError>,
+  ) {
+    for err in context.log_store.lock().errors() {
       errors.push(CompilationError::GenericError(err.to_string()));
     }
 
-    if !self.context.log_store.lock().warnings().is_empty() {
-      for warning in self.context.log_store.lock().warnings() {
-        println!("[warn] {warning}");
-      }
+    for warning in context.log_store.lock().warnings() {
+      context.logger.warn(module_path!(), warning.as_str());
     }
 
     // clear log store
-    self.context.log_store.lock().clear();
+    context.log_store.lock().clear();
   }
 
   pub(crate) fn resolve_module_id(
@@ -260,9 +324,10 @@ impl Compiler {
   /// Resolving, loading, transforming and parsing a module, return the module and its dependencies if success
   pub(crate) fn build_module(
     resolve_result: PluginResolveHookResult,
+    resolve_kind: &ResolveKind,
     module: &mut Module,
     context: &Arc<CompilationContext>,
-  ) -> Result<Vec<(PluginAnalyzeDepsHookResultEntry, Option<ModuleId>)>> {
+  ) -> Result<Vec<ModuleDependency>> {
     // skip timestamp and content hash for modules
     module.last_update_timestamp = if module.immutable {
       0
@@ -274,6 +339,7 @@ impl Compiler {
       try_get_module_cache_by_timestamp(&module.id, module.last_update_timestamp, context)?
     {
       *module = cached_module.module;
+      context.progress.module_cached();
       return Ok(CachedModule::dep_sources(cached_module.dependencies));
     }
 
@@ -291,6 +357,7 @@ impl Compiler {
     };
 
     let load_result = call_and_catch_error!(load, &load_param, context, &hook_context);
+    context.progress.module_loaded();
     let mut source_map_chain = vec![];
 
     if let Some(source_map) = load_result.source_map {
@@ -311,12 +378,13 @@ impl Compiler {
     };
 
     let transform_result = call_and_catch_error!(transform, transform_param, context);
+    context.progress.module_transformed();
     // ================ Transform End ===============
     module.content = Arc::new(transform_result.content.clone());
     module.content_hash = if module.immutable {
       "immutable_module".to_string()
     } else {
-      get_content_hash_of_module(&transform_result.content)
+      get_content_hash_of_module(&transform_result.content, context)
     };
 
     // skip building if the module is already built and the cache is enabled
@@ -332,6 +400,7 @@ impl Compiler {
 
     let deps = Self::build_module_after_transform(
       resolve_result,
+      resolve_kind,
       load_module_type,
       transform_result,
       module,
@@ -339,11 +408,12 @@ impl Compiler {
       &hook_context,
     )?;
 
-    Ok(deps.into_iter().map(|dep| (dep, None)).collect())
+    Ok(deps.into_iter().map(|dep| (dep, None, None)).collect())
   }
 
   fn build_module_after_transform(
     resolve_result: PluginResolveHookResult,
+    resolve_kind: &ResolveKind,
     load_module_type: ModuleType,
     transform_result: PluginDriverTransformHookResult,
     module: &mut Module,
@@ -360,6 +430,10 @@ impl Compiler {
     };
 
     let mut module_meta = call_and_catch_error!(parse, &parse_param, context, hook_context);
+
+    if should_inject_polyfill_entries(resolve_kind, &module.id, context) {
+      inject_polyfill_entries(&mut module_meta, context);
+    }
     // ================ Parse End ===============
 
     // ================ Process Module Start ===============
@@ -379,6 +453,22 @@ impl Compiler {
     }
 
     // ================ Process Module End ===============
+
+    // ================ Normalize Module System Start ===============
+    if let Err(e) = normalize_module_system(
+      &parse_param.module_id,
+      &parse_param.module_type,
+      &parse_param.content,
+      &mut module_meta,
+      context,
+    ) {
+      return Err(CompilationError::ProcessModuleError {
+        resolved_path: resolve_result.resolved_path,
+        source: Some(Box::new(e)),
+      });
+    }
+    // ================ Normalize Module System End ===============
+
     module.size = parse_param.content.as_bytes().len();
     module.module_type = parse_param.module_type;
     module.side_effects = resolve_result.side_effects;
@@ -389,6 +479,7 @@ impl Compiler {
     let resolved_path = module.id.resolved_path(&context.config.root);
     let package_info =
       load_package_json(PathBuf::from(resolved_path), Default::default()).unwrap_or_default();
+    module.package_license = package_info.license().unwrap_or_default();
     module.package_name = package_info.name.unwrap_or("default".to_string());
     module.package_version = package_info.version.unwrap_or("0.0.0".to_string());
 
@@ -412,6 +503,7 @@ impl Compiler {
       err_sender,
       order,
       cached_dependency,
+      cached_resolve_trace,
     } = params;
 
     let c_thread_pool = thread_pool.clone();
@@ -431,13 +523,25 @@ impl Compiler {
       };
 
       match resolve_module_result {
-        ResolveModuleResult::Built(module_id) => {
+        ResolveModuleResult::Built(module_id, resolve_trace) => {
           farm_profile_scope!(format!("module {:?} already exists", module_id));
+          // the module graph is reused by Compiler::compile_subset, an existing module can be a new entry
+          if let ResolveKind::Entry(name) = &resolve_param.kind {
+            context
+              .module_graph
+              .write()
+              .entries
+              .entry(module_id.clone())
+              .or_insert_with(|| name.clone());
+          }
           // add edge to the graph
-          Self::add_edge(&resolve_param, module_id, order, &context);
+          let resolve_trace = resolve_trace.or(cached_resolve_trace);
+          Self::add_edge(&resolve_param, module_id, order, resolve_trace, &context);
         }
         ResolveModuleResult::Cached(module_id) => {
           farm_profile_scope!(format!("cache module {:?}", module_id));
+          context.progress.module_resolved();
+          context.progress.module_cached();
           let mut cached_module = context.cache_manager.module_cache.get_cache(&module_id);
 
           if let Err(e) = handle_cached_modules(&mut cached_module, &context) {
@@ -449,6 +553,7 @@ impl Compiler {
             module: cached_module.module,
             resolve_param,
             order,
+            resolve_trace: cached_resolve_trace,
             deps: CachedModule::dep_sources(cached_module.dependencies),
             thread_pool: c_thread_pool,
             err_sender,
@@ -462,18 +567,33 @@ impl Compiler {
           resolve_module_id_result,
         }) => {
           farm_profile_scope!(format!("new module {:?}", module.id));
+          context.progress.module_resolved();
+          let resolve_trace = resolve_module_id_result
+            .resolve_result
+            .resolve_trace
+            .clone();
+
           if resolve_module_id_result.resolve_result.external {
             // insert external module to the graph
             let module_id = module.id.clone();
             Self::add_module(module, &resolve_param.kind, &context);
-            Self::add_edge(&resolve_param, module_id, order, &context);
+            Self::add_edge(&resolve_param, module_id, order, resolve_trace, &context);
             return;
           }
 
-          match Self::build_module(
-            resolve_module_id_result.resolve_result,
-            &mut module,
-            &context,
+          // a panicking plugin fails the module instead of aborting the process
+          let module_id = module.id.clone();
+          match context.catch_panic(
+            || format!("building module `{}`", module_id.to_string()),
+            || vec![module_id.clone()],
+            || {
+              Self::build_module(
+                resolve_module_id_result.resolve_result,
+                &resolve_param.kind,
+                &mut module,
+                &context,
+              )
+            },
           ) {
             Err(e) => {
               err_sender.send(e).unwrap();
@@ -483,6 +603,7 @@ impl Compiler {
                 module,
                 resolve_param,
                 order,
+                resolve_trace,
                 deps,
                 thread_pool: c_thread_pool,
                 err_sender,
@@ -501,6 +622,7 @@ impl Compiler {
       module,
       resolve_param,
       order,
+      resolve_trace,
       deps,
       thread_pool,
       err_sender,
@@ -512,10 +634,16 @@ impl Compiler {
     // add module to the graph
     Self::add_module(module, &resolve_param.kind, &context);
     // add edge to the graph
-    Self::add_edge(&resolve_param, module_id.clone(), order, &context);
+    Self::add_edge(
+      &resolve_param,
+      module_id.clone(),
+      order,
+      resolve_trace,
+      &context,
+    );
 
     // resolving dependencies recursively in the thread pool
-    for (order, (dep, cached_dependency)) in deps.into_iter().enumerate() {
+    for (order, (dep, cached_dependency, cached_resolve_trace)) in deps.into_iter().enumerate() {
       let params = BuildModuleGraphThreadedParams {
         thread_pool: thread_pool.clone(),
         resolve_param: PluginResolveHookParam {
@@ -527,6 +655,11 @@ impl Compiler {
         err_sender: err_sender.clone(),
         order,
         cached_dependency: if immutable { cached_dependency } else { None },
+        cached_resolve_trace: if immutable {
+          cached_resolve_trace
+        } else {
+          None
+        },
       };
       Self::build_module_graph_threaded(params);
     }
@@ -536,6 +669,7 @@ impl Compiler {
     resolve_param: &PluginResolveHookParam,
     module_id: ModuleId,
     order: usize,
+    resolve_trace: Option<ResolveTrace>,
     context: &CompilationContext,
   ) {
     let mut module_graph = context.module_graph.write();
@@ -548,6 +682,7 @@ impl Compiler {
           source: resolve_param.source.clone(),
           kind: resolve_param.kind.clone(),
           order,
+          resolve_trace,
         },
       ).expect("failed to add edge to the module graph, the endpoint modules of the edge should be in the graph")
     }
@@ -605,10 +740,7 @@ impl Compiler {
       !module_id_str.ends_with(DYNAMIC_VIRTUAL_SUFFIX)
         && context
           .config
-          .partial_bundling
-          .immutable_modules
-          .iter()
-          .any(|im| im.is_match(&module_id_str)),
+          .is_immutable_module(&resolve_module_id_result.module_id),
     )
   }
 
@@ -640,7 +772,8 @@ fn resolve_module(
   let res = if module_graph.has_module(&module_id) {
     farm_profile_scope!(format!("module {:?} already exists", module_id));
     // the module has already been handled and it should not be handled twice
-    ResolveModuleResult::Built(module_id)
+    let resolve_trace = resolve_module_id_result.and_then(|r| r.resolve_result.resolve_trace);
+    ResolveModuleResult::Built(module_id, resolve_trace)
   } else {
     if let Some(cached_dependency) = cached_dependency {
       farm_profile_scope!(format!("cache module {:?} ", module_id));
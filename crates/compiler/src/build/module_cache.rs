@@ -12,6 +12,13 @@ use farmfe_core::{
   farm_profile_function,
   module::ModuleId,
   rayon::prelude::*,
+  swc_ecma_ast::{
+    Decl, EsVersion, ExportSpecifier, Id, ModuleDecl, ModuleExportName, ModuleItem, TsModuleName,
+  },
+};
+use farmfe_toolkit::{
+  script::{module_type_from_id, parse_module, syntax_from_module_type},
+  swc_ecma_utils::find_pat_ids,
 };
 
 pub fn get_timestamp_of_module(module_id: &ModuleId, root: &str) -> u128 {
@@ -46,7 +53,7 @@ pub fn get_timestamp_of_module(module_id: &ModuleId, root: &str) -> u128 {
     .as_nanos()
 }
 
-pub fn get_content_hash_of_module(content: &str) -> String {
+pub fn get_content_hash_of_module(content: &str, context: &Arc<CompilationContext>) -> String {
   farm_profile_function!("get_content_hash_of_module".to_string());
 
   let content = if content.is_empty() {
@@ -55,7 +62,7 @@ pub fn get_content_hash_of_module(content: &str) -> String {
     content.to_string()
   };
 
-  let module_content_hash = farmfe_toolkit::hash::sha256(content.as_bytes(), 32);
+  let module_content_hash = context.config.hash.hash(content.as_bytes(), 32);
   module_content_hash
 }
 
@@ -230,7 +237,8 @@ pub fn set_module_graph_cache(module_ids: Vec<ModuleId>, context: &Arc<Compilati
             CachedWatchDependency {
               dependency: id.clone(),
               timestamp: get_timestamp_of_module(id, &context.config.root),
-              hash: get_content_hash_of_module(&content),
+              hash: get_content_hash_of_module(&content, context),
+              export_signature: get_export_signature_of_module(id, &content, context),
             }
           })
           .collect(),
@@ -312,22 +320,29 @@ fn is_watch_dependencies_timestamp_changed(
     return false;
   }
 
-  let cached_dep_timestamp_map = cached_module
+  let cached_dep_map = cached_module
     .watch_dependencies
     .iter()
-    .map(|dep| (dep.dependency.clone(), dep.timestamp))
+    .map(|dep| (dep.dependency.clone(), dep))
     .collect::<HashMap<_, _>>();
 
   for dep in &relation_dependencies {
     let resolved_path = PathBuf::from(dep.resolved_path(&context.config.root));
-    let cached_timestamp = cached_dep_timestamp_map.get(dep);
+    let Some(cached_dep) = cached_dep_map.get(dep) else {
+      return true;
+    };
 
-    if !resolved_path.exists()
-      || cached_timestamp.is_none()
-      || get_timestamp_of_module(dep, &context.config.root) != *cached_timestamp.unwrap()
-    {
+    if !resolved_path.exists() {
       return true;
     }
+
+    if get_timestamp_of_module(dep, &context.config.root) != cached_dep.timestamp {
+      let content = std::fs::read_to_string(resolved_path).unwrap_or_default();
+
+      if !is_export_signature_unchanged(cached_dep, &content, context) {
+        return true;
+      }
+    }
   }
 
   false
@@ -348,27 +363,178 @@ fn is_watch_dependencies_content_hash_changed(
     return false;
   }
 
-  let cached_dep_hash_map = cached_module
+  let cached_dep_map = cached_module
     .watch_dependencies
     .iter()
-    .map(|dep| (dep.dependency.clone(), dep.hash.clone()))
+    .map(|dep| (dep.dependency.clone(), dep))
     .collect::<HashMap<_, _>>();
 
   for dep in relation_dependencies {
     let resolved_path = PathBuf::from(dep.resolved_path(&context.config.root));
-    let cached_hash = cached_dep_hash_map.get(dep);
+    let Some(cached_dep) = cached_dep_map.get(dep) else {
+      return true;
+    };
 
-    if !resolved_path.exists() || cached_hash.is_none() {
+    if !resolved_path.exists() {
       return true;
     }
 
     let content = std::fs::read_to_string(resolved_path).unwrap();
-    let hash = get_content_hash_of_module(&content);
+    let hash = get_content_hash_of_module(&content, context);
 
-    if hash != *cached_hash.unwrap() {
+    if hash != cached_dep.hash && !is_export_signature_unchanged(cached_dep, &content, context) {
       return true;
     }
   }
 
   false
 }
+
+/// Whether the changed content of a script watch dependency keeps the exports of the cached one. The dependent modules only see
+/// the exports of a script dependency, e.g. the types used by a type aware transform, so they are still valid in this case
+fn is_export_signature_unchanged(
+  cached_dep: &CachedWatchDependency,
+  content: &str,
+  context: &Arc<CompilationContext>,
+) -> bool {
+  let Some(cached_signature) = &cached_dep.export_signature else {
+    return false;
+  };
+
+  get_export_signature_of_module(&cached_dep.dependency, content, context)
+    .is_some_and(|signature| signature == *cached_signature)
+}
+
+/// Hash of the sorted export names of a script module, including the type exports and the sources of the re-exports.
+/// [None] if the module is not a script module, it can't be parsed or [PersistentModuleCacheKeyStrategy::export_signature] is disabled
+///
+/// [PersistentModuleCacheKeyStrategy::export_signature]: farmfe_core::config::persistent_cache::PersistentModuleCacheKeyStrategy::export_signature
+pub fn get_export_signature_of_module(
+  module_id: &ModuleId,
+  content: &str,
+  context: &Arc<CompilationContext>,
+) -> Option<String> {
+  farm_profile_function!(format!("get_export_signature_of_module: {:?}", module_id));
+
+  if !context.config.persistent_cache.export_signature_enabled() {
+    return None;
+  }
+
+  let resolved_path = module_id.resolved_path(&context.config.root);
+  let module_type = module_type_from_id(&resolved_path)?;
+  let syntax = syntax_from_module_type(&module_type, context.config.script.parser.clone())?;
+  let ast = parse_module(&resolved_path, content, syntax, EsVersion::EsNext)
+    .ok()?
+    .ast;
+
+  let export_name = |name: &ModuleExportName| match name {
+    ModuleExportName::Ident(ident) => ident.sym.to_string(),
+    ModuleExportName::Str(name) => name.value.to_string(),
+  };
+  let mut exports = vec![];
+
+  for item in &ast.body {
+    let ModuleItem::ModuleDecl(module_decl) = item else {
+      continue;
+    };
+
+    match module_decl {
+      ModuleDecl::ExportDecl(export_decl) => match &export_decl.decl {
+        Decl::Class(class) => exports.push(class.ident.sym.to_string()),
+        Decl::Fn(func) => exports.push(func.ident.sym.to_string()),
+        Decl::Var(var) => {
+          for decl in &var.decls {
+            let ids = find_pat_ids::<_, Id>(&decl.name);
+            exports.extend(ids.into_iter().map(|(sym, _)| sym.to_string()));
+          }
+        }
+        Decl::TsInterface(interface) => exports.push(format!("type {}", interface.id.sym)),
+        Decl::TsTypeAlias(alias) => exports.push(format!("type {}", alias.id.sym)),
+        Decl::TsEnum(ts_enum) => exports.push(format!("enum {}", ts_enum.id.sym)),
+        Decl::TsModule(ts_module) => match &ts_module.id {
+          TsModuleName::Ident(ident) => exports.push(format!("namespace {}", ident.sym)),
+          TsModuleName::Str(name) => exports.push(format!("namespace {}", name.value)),
+        },
+        _ => {}
+      },
+      ModuleDecl::ExportNamed(named) => {
+        let prefix = match &named.src {
+          Some(src) => format!("{} from ", src.value),
+          None => String::new(),
+        };
+        let type_only = if named.type_only { "type " } else { "" };
+
+        for specifier in &named.specifiers {
+          let name = match specifier {
+            ExportSpecifier::Named(named) => {
+              export_name(named.exported.as_ref().unwrap_or(&named.orig))
+            }
+            ExportSpecifier::Default(default) => default.exported.sym.to_string(),
+            ExportSpecifier::Namespace(namespace) => export_name(&namespace.name),
+          };
+          exports.push(format!("{prefix}{type_only}{name}"));
+        }
+      }
+      ModuleDecl::ExportDefaultDecl(_) | ModuleDecl::ExportDefaultExpr(_) => {
+        exports.push("default".to_string())
+      }
+      ModuleDecl::ExportAll(export_all) => exports.push(format!("{} from *", export_all.src.value)),
+      ModuleDecl::TsExportAssignment(_) => exports.push("export =".to_string()),
+      _ => {}
+    }
+  }
+
+  exports.sort();
+
+  Some(get_content_hash_of_module(&exports.join("\n"), context))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use farmfe_core::{
+    config::{persistent_cache::PersistentCacheConfig, Config},
+    context::CompilationContext,
+  };
+
+  use super::get_export_signature_of_module;
+
+  #[test]
+  fn export_signature_of_module() {
+    let context = Arc::new(
+      CompilationContext::new(
+        Config {
+          persistent_cache: Box::new(PersistentCacheConfig::Bool(true)),
+          ..Default::default()
+        },
+        vec![],
+      )
+      .unwrap(),
+    );
+    let signature =
+      |id: &str, content: &str| get_export_signature_of_module(&id.into(), content, &context);
+
+    let original = signature(
+      "types.ts",
+      "export interface User { name: string }\nexport const version = 1;\nexport * from './role';",
+    );
+    assert!(original.is_some());
+    // internal edits keep the exports
+    assert_eq!(
+      signature(
+        "types.ts",
+        "export interface User { name: string; age: number }\nexport const version = 2;\nexport * from './role';",
+      ),
+      original
+    );
+    assert_ne!(
+      signature(
+        "types.ts",
+        "export interface User { name: string }\nexport const version = 1;\nexport { version as v };",
+      ),
+      original
+    );
+    assert_eq!(signature("index.scss", "$color: red;"), None);
+  }
+}
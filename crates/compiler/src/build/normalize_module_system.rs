@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::Result,
+  module::{ModuleId, ModuleMetaData, ModuleSystem, ModuleType},
+  plugin::{PluginDetectModuleSystemHookParam, PluginNormalizeModuleSystemHookParam},
+};
+
+/// Detect the custom module system of the module and let plugins normalize it, e.g. AMD to esm.
+/// The detected module system is kept if no plugin normalizes the module
+pub fn normalize_module_system(
+  module_id: &ModuleId,
+  module_type: &ModuleType,
+  content: &Arc<String>,
+  meta: &mut ModuleMetaData,
+  context: &Arc<CompilationContext>,
+) -> Result<()> {
+  #[cfg(feature = "profile")]
+  farmfe_core::puffin::profile_function!();
+
+  let Some(module_system) = context.plugin_driver.detect_module_system(
+    &PluginDetectModuleSystemHookParam {
+      module_id,
+      module_type,
+      content,
+      meta,
+    },
+    context,
+  )?
+  else {
+    return Ok(());
+  };
+
+  let normalized = context.plugin_driver.normalize_module_system(
+    &mut PluginNormalizeModuleSystemHookParam {
+      module_id,
+      module_type,
+      module_system: &module_system,
+      meta,
+    },
+    context,
+  )?;
+
+  if let ModuleMetaData::Script(script) = meta {
+    if normalized.is_some() {
+      script.original_module_system = Some(module_system);
+    } else {
+      script.module_system = ModuleSystem::Custom(module_system);
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  context::CompilationContext,
+  module::{ModuleId, ModuleMetaData},
+  plugin::ResolveKind,
+  relative_path::RelativePath,
+  swc_common::DUMMY_SP,
+  swc_ecma_ast::{ImportDecl, ImportPhase, ModuleDecl, ModuleItem, Str},
+};
+use farmfe_toolkit::script::constant::RUNTIME_SUFFIX;
+
+/// Whether the module is an entry that should import `polyfill_entries`: script entries, scripts of html entries,
+/// and the same modules when they are rebuilt by hmr updates
+pub fn should_inject_polyfill_entries(
+  kind: &ResolveKind,
+  module_id: &ModuleId,
+  context: &Arc<CompilationContext>,
+) -> bool {
+  if context.config.polyfill_entries.is_empty() || module_id.to_string().ends_with(RUNTIME_SUFFIX) {
+    return false;
+  }
+
+  match kind {
+    ResolveKind::Entry(_) | ResolveKind::ScriptSrc => true,
+    ResolveKind::HmrUpdate => {
+      let module_graph = context.module_graph.read();
+
+      module_graph.entries.contains_key(module_id)
+        || module_graph
+          .dependents(module_id)
+          .into_iter()
+          .any(|(_, edge)| edge.iter().any(|item| item.kind == ResolveKind::ScriptSrc))
+    }
+    _ => false,
+  }
+}
+
+/// Import `polyfill_entries` at the top of the entry, so the polyfills are executed before any code of the entry.
+/// The imports are added to the ast instead of the source code, so the source map of the entry is not affected
+pub fn inject_polyfill_entries(meta: &mut ModuleMetaData, context: &Arc<CompilationContext>) {
+  let ModuleMetaData::Script(script) = meta else {
+    return;
+  };
+
+  let imports = context.config.polyfill_entries.iter().map(|source| {
+    ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+      span: DUMMY_SP,
+      specifiers: vec![],
+      src: Box::new(Str {
+        span: DUMMY_SP,
+        value: polyfill_source(source, &context.config.root).into(),
+        raw: None,
+      }),
+      type_only: false,
+      with: None,
+      phase: ImportPhase::Evaluation,
+    }))
+  });
+
+  script.ast.body.splice(0..0, imports);
+}
+
+/// Relative sources are relative to the root, every entry imports the same polyfill wherever the entry is
+fn polyfill_source(source: &str, root: &str) -> String {
+  if source.starts_with("./") || source.starts_with("../") {
+    RelativePath::new(source)
+      .to_logical_path(root)
+      .to_string_lossy()
+      .to_string()
+  } else {
+    source.to_string()
+  }
+}
@@ -1,8 +1,10 @@
 use std::sync::Arc;
 
 use farmfe_core::{
+  config::module_types::CustomModuleTypeTreatAs,
   context::CompilationContext,
   error::{CompilationError, Result},
+  module::ModuleType,
   plugin::{plugin_driver::PluginDriverTransformHookResult, PluginTransformHookParam},
 };
 
@@ -13,7 +15,8 @@ pub fn transform(
   #[cfg(feature = "profile")]
   farmfe_core::puffin::profile_function!();
   let module_id = transform_param.module_id.to_string();
-  let transformed = context
+  let load_module_type = transform_param.module_type.clone();
+  let mut transformed = context
     .plugin_driver
     .transform(transform_param, context)
     .map_err(|e| CompilationError::TransformError {
@@ -21,5 +24,16 @@ pub fn transform(
       msg: e.to_string(),
     })?;
 
+  // the extensions declared as `js-via-plugin` are transformed to js by the plugins
+  if let ModuleType::Custom(ext) = transformed
+    .module_type
+    .as_ref()
+    .unwrap_or(&load_module_type)
+  {
+    if context.config.custom_module_type(ext) == Some(CustomModuleTypeTreatAs::JsViaPlugin) {
+      transformed.module_type = Some(ModuleType::Js);
+    }
+  }
+
   Ok(transformed)
 }
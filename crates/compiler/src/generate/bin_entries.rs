@@ -0,0 +1,194 @@
+//! Shebangs and extension-less executables of the entries of node targets, see `output.shebang` and `output.bin`
+use std::{
+  collections::{HashMap, HashSet},
+  path::Path,
+  sync::Arc,
+};
+
+use farmfe_core::{
+  context::CompilationContext,
+  module::ModuleMetaData,
+  resource::{ResourceOrigin, ResourceType},
+  serde_json::{self, Value},
+};
+use farmfe_toolkit::common::set_source_map_file;
+
+use super::finalize_resources::sync_resource_pots;
+
+/// Prepend the shebang to the js resources of the entries, and remove the extensions of their names if `output.bin` is enabled.
+/// The resources are finalized, so the shebang is the first line of the written files
+pub fn emit_bin_entries(context: &Arc<CompilationContext>) {
+  let output = &context.config.output;
+
+  if !output.target_env.is_node() {
+    return;
+  }
+
+  // resource pot id -> shebang of its entry module
+  let entries = {
+    let module_graph = context.module_graph.read();
+    let resource_pot_map = context.resource_pot_map.read();
+
+    resource_pot_map
+      .resource_pots()
+      .into_iter()
+      .filter_map(|resource_pot| {
+        let entry = resource_pot.entry_module.as_ref()?;
+        let module_shebang = match module_graph.module(entry)?.meta.as_ref() {
+          ModuleMetaData::Script(script) => script.ast.shebang.as_ref().map(|s| s.to_string()),
+          _ => None,
+        };
+
+        Some((resource_pot.id.clone(), module_shebang))
+      })
+      .collect::<HashMap<_, _>>()
+  };
+
+  let mut renames = HashMap::new();
+  // resource pot id -> name of its renamed entry
+  let mut renamed_entries = HashMap::new();
+  let mut prefixed = HashSet::new();
+  let resources_map = &context.resources_map;
+
+  for mut entry in resources_map.iter_mut() {
+    let resource = entry.value_mut();
+    let ResourceOrigin::ResourcePot(resource_pot_id) = &resource.origin else {
+      continue;
+    };
+    let Some(module_shebang) = entries.get(resource_pot_id) else {
+      continue;
+    };
+
+    if !matches!(resource.resource_type, ResourceType::Js) || resource.emitted {
+      continue;
+    }
+
+    if !resource.is_executable() {
+      if let Some(shebang) = output
+        .shebang
+        .resolve(module_shebang.as_deref(), output.bin)
+      {
+        resource.bytes = [format!("#!{shebang}\n").as_bytes(), &resource.bytes].concat();
+        prefixed.insert(resource_pot_id.clone());
+      }
+    }
+
+    let name = Path::new(&resource.name)
+      .with_extension("")
+      .to_string_lossy()
+      .replace('\\', "/");
+
+    if output.bin && name != resource.name {
+      renamed_entries.insert(resource_pot_id.clone(), resource.name.clone());
+      renames.insert(resource.name.clone(), name);
+    }
+  }
+
+  // the shebang line is prepended to the generated code
+  for mut resource in resources_map.iter_mut() {
+    if matches!(&resource.resource_type, ResourceType::SourceMap(id) if prefixed.contains(id)) {
+      shift_source_map(&mut resource.bytes);
+    }
+  }
+
+  renames.retain(|name, new_name| {
+    if resources_map.contains_key(new_name.as_str()) {
+      context.logger.warn(
+        module_path!(),
+        format!("{name} is not emitted as {new_name}, which is used by another resource"),
+      );
+      return false;
+    }
+
+    true
+  });
+
+  // the source maps of the entries are renamed with them, `x.js.map` -> `x.map`
+  let map_renames = resources_map
+    .iter()
+    .filter_map(|resource| {
+      let ResourceType::SourceMap(resource_pot_id) = &resource.resource_type else {
+        return None;
+      };
+      let entry = renamed_entries.get(resource_pot_id)?;
+      let new_entry = renames.get(entry)?;
+
+      Some((
+        resource.name.clone(),
+        format!("{new_entry}.map"),
+        entry.clone(),
+        new_entry.clone(),
+      ))
+    })
+    .collect::<Vec<_>>();
+
+  for (name, new_name, entry, new_entry) in map_renames {
+    if resources_map.contains_key(&new_name) {
+      continue;
+    }
+
+    if let Some(mut resource) = resources_map.get_mut(&entry) {
+      replace_source_map_url(
+        &mut resource.bytes,
+        &file_name(&name),
+        &file_name(&new_name),
+      );
+    }
+
+    if let Some(mut map) = resources_map.get_mut(&name) {
+      set_source_map_file(&mut map, &new_entry);
+    }
+
+    renames.insert(name, new_name);
+  }
+
+  for (name, new_name) in &renames {
+    let (_, mut resource) = resources_map.remove(name).unwrap();
+    resource.name = new_name.clone();
+    resources_map.insert(new_name.clone(), resource);
+  }
+
+  sync_resource_pots(context, &renames, &[]);
+}
+
+/// Map the generated code one line down
+fn shift_source_map(bytes: &mut Vec<u8>) {
+  let Ok(mut map) = serde_json::from_slice::<Value>(bytes) else {
+    return;
+  };
+  let Some(Value::String(mappings)) = map.get_mut("mappings") else {
+    return;
+  };
+
+  mappings.insert(0, ';');
+
+  if let Ok(shifted) = serde_json::to_vec(&map) {
+    *bytes = shifted;
+  }
+}
+
+fn file_name(name: &str) -> String {
+  Path::new(name)
+    .file_name()
+    .map(|name| name.to_string_lossy().to_string())
+    .unwrap_or_else(|| name.to_string())
+}
+
+/// Point the trailing `sourceMappingURL` comment of the js to the renamed source map, inline source maps are kept
+fn replace_source_map_url(bytes: &mut Vec<u8>, map_url: &str, new_map_url: &str) {
+  let comment = format!("\n//# sourceMappingURL={map_url}");
+  let Some(start) = bytes
+    .windows(comment.len())
+    .rposition(|window| window == comment.as_bytes())
+  else {
+    return;
+  };
+
+  if bytes[start + comment.len()..]
+    .iter()
+    .all(u8::is_ascii_whitespace)
+  {
+    bytes.truncate(start);
+    bytes.extend(format!("\n//# sourceMappingURL={new_map_url}").into_bytes());
+  }
+}
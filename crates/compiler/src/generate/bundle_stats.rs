@@ -0,0 +1,360 @@
+//! Bundle analysis report of the generated resources: the size and gzip size of every resource,
+//! the rendered size of the modules in it, and the module graph edges that caused each module to be included.
+//! The byte ranges of the modules in the emitted resources are resolved by the source maps, so coverage tools can attribute production bytes to modules.
+//! The exported names of the es modules are optionally included, they are recorded before tree shaking removes the unused ones.
+use std::{collections::HashMap, io::Write, sync::Arc};
+
+use farmfe_core::{
+  context::CompilationContext,
+  module::{ModuleId, ModuleSystem},
+  rayon::prelude::{IntoParallelIterator, ParallelIterator},
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde_json,
+  stats::{
+    BundleEdgeStats, BundleModuleStats, BundleResourceStats, BundleStats, ModuleExportsStats,
+  },
+};
+use farmfe_plugin_tree_shake::module_statements::script_module_exports;
+use farmfe_toolkit::{common::SourcemapSources, sourcemap::SourceMap};
+use flate2::{write::GzEncoder, Compression};
+
+pub fn emit_bundle_stats(context: &Arc<CompilationContext>) {
+  let Some(config) = context.config.bundle_stats.as_ref() else {
+    return;
+  };
+
+  let mut edges = vec![];
+  let module_graph = context.module_graph.read();
+
+  for module in module_graph.modules() {
+    for (dependency, edge) in module_graph.dependencies(&module.id) {
+      let package = module_graph
+        .module(&dependency)
+        .and_then(|module| module.node_modules_package());
+
+      for item in edge.iter() {
+        edges.push(BundleEdgeStats {
+          importer: module.id.clone(),
+          dependency: dependency.clone(),
+          source: item.source.clone(),
+          kind: item.kind.clone(),
+          resolve_trace: item.resolve_trace.clone(),
+          package: package.clone(),
+        });
+      }
+    }
+  }
+
+  drop(module_graph);
+  edges.sort_by(|a, b| (&a.importer, &a.dependency).cmp(&(&b.importer, &b.dependency)));
+
+  let mut resources = {
+    let resources_map = &context.resources_map;
+    let source_maps = resources_map
+      .iter()
+      .filter(|r| matches!(r.resource_type, ResourceType::SourceMap(_)))
+      .filter_map(|r| match &r.origin {
+        ResourceOrigin::ResourcePot(id) => Some((id.clone(), r)),
+        ResourceOrigin::Module(_) | ResourceOrigin::Modules(_) => None,
+      })
+      .collect::<HashMap<_, _>>();
+    let sources = SourcemapSources::new(&context.config);
+    let resources = resources_map
+      .iter()
+      .filter(|r| !r.emitted && !matches!(r.resource_type, ResourceType::SourceMap(_)))
+      .collect::<Vec<_>>();
+
+    resources
+      .into_par_iter()
+      .map(|resource| {
+        let source_map = match &resource.origin {
+          ResourceOrigin::ResourcePot(id) => source_maps.get(id).map(|r| r.value()),
+          ResourceOrigin::Module(_) | ResourceOrigin::Modules(_) => None,
+        };
+
+        resource_stats(
+          resource.value(),
+          source_map,
+          &sources,
+          context,
+          config.gzip_size,
+        )
+      })
+      .collect::<Vec<_>>()
+  };
+  resources.sort_by(|a, b| a.name.cmp(&b.name));
+
+  let unused_exports = context
+    .record_manager
+    .unused_exports
+    .read()
+    .clone()
+    .unwrap_or_default();
+  let exports = context
+    .record_manager
+    .module_exports
+    .read()
+    .clone()
+    .unwrap_or_default();
+  let bundle_stats = BundleStats {
+    resources,
+    edges,
+    unused_exports,
+    exports,
+  };
+
+  context.resources_map.insert(
+    config.filename.clone(),
+    Resource {
+      name: config.filename.clone(),
+      bytes: serde_json::to_vec_pretty(&bundle_stats).unwrap(),
+      resource_type: ResourceType::Custom("json".to_string()),
+      origin: ResourceOrigin::ResourcePot(config.filename.clone()),
+      ..Default::default()
+    },
+  );
+
+  context.record_manager.set_bundle_stats(bundle_stats);
+}
+
+/// Record the exports of the es modules for [emit_bundle_stats], it must run before tree shaking
+pub fn record_module_exports(context: &Arc<CompilationContext>) {
+  if !context
+    .config
+    .bundle_stats
+    .as_ref()
+    .is_some_and(|config| config.exports)
+  {
+    return;
+  }
+
+  let module_graph = context.module_graph.read();
+  let mut module_exports = module_graph
+    .modules()
+    .into_par_iter()
+    .filter(|module| !module.external && module.module_type.is_script())
+    .filter_map(|module| {
+      let script = module.meta.as_script();
+
+      // the exports of commonjs modules can not be analyzed statically
+      if !matches!(script.module_system, ModuleSystem::EsModule) {
+        return None;
+      }
+
+      Some(ModuleExportsStats {
+        id: module.id.clone(),
+        exports: script_module_exports(context, script),
+      })
+    })
+    .collect::<Vec<_>>();
+  module_exports.sort_by(|a, b| a.id.cmp(&b.id));
+
+  context.record_manager.set_module_exports(module_exports);
+}
+
+fn resource_stats(
+  resource: &Resource,
+  source_map: Option<&Resource>,
+  sources: &SourcemapSources,
+  context: &Arc<CompilationContext>,
+  gzip_size: bool,
+) -> BundleResourceStats {
+  let mut modules = resource
+    .info
+    .as_ref()
+    .map(|info| {
+      // the sources of the source map are the remapped resolved paths of the modules
+      let module_sources = info
+        .modules
+        .keys()
+        .map(|id| {
+          (
+            sources.remap(&id.resolved_path_with_query(&context.config.root)),
+            id.clone(),
+          )
+        })
+        .collect::<HashMap<_, _>>();
+      let mut ranges = source_map
+        .and_then(|map| SourceMap::from_slice(&map.bytes).ok())
+        .map(|map| {
+          module_ranges(
+            &String::from_utf8_lossy(&resource.bytes),
+            &map,
+            &module_sources,
+          )
+        })
+        .unwrap_or_default();
+
+      info
+        .modules
+        .values()
+        .map(|m| BundleModuleStats {
+          id: m.id.clone(),
+          rendered_size: m.rendered_length,
+          original_size: m.original_length,
+          ranges: ranges.remove(&m.id).unwrap_or_default(),
+        })
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+  modules.sort_by(|a, b| b.rendered_size.cmp(&a.rendered_size).then(a.id.cmp(&b.id)));
+
+  BundleResourceStats {
+    name: resource.name.clone(),
+    resource_type: resource.resource_type.to_ext(),
+    size: match &resource.source_path {
+      // large assets are not loaded into memory
+      Some(source_path) => std::fs::metadata(source_path)
+        .map(|m| m.len() as usize)
+        .unwrap_or_default(),
+      None => resource.bytes.len(),
+    },
+    gzip_size: if gzip_size && resource.source_path.is_none() {
+      gzip_len(&resource.bytes)
+    } else {
+      0
+    },
+    origin: resource.origin.clone(),
+    modules,
+  }
+}
+
+/// Attribute the bytes of the code to the modules by the segments of the source map,
+/// a segment spans from its generated position to the next segment or the end of the line
+fn module_ranges(
+  code: &str,
+  map: &SourceMap,
+  module_sources: &HashMap<String, ModuleId>,
+) -> HashMap<ModuleId, Vec<[usize; 2]>> {
+  let mut segments = map
+    .tokens()
+    .map(|token| {
+      (
+        token.get_dst_line() as usize,
+        token.get_dst_col() as usize,
+        token.get_source().and_then(|src| module_sources.get(src)),
+      )
+    })
+    .collect::<Vec<_>>();
+  segments.sort_by_key(|(line, col, _)| (*line, *col));
+
+  let mut cursor = Utf16Cursor::new(code);
+  let mut ranges: HashMap<ModuleId, Vec<[usize; 2]>> = HashMap::new();
+
+  for (i, (line, col, module_id)) in segments.iter().enumerate() {
+    let Some(module_id) = module_id else {
+      continue;
+    };
+    let Some(start) = cursor.offset(*line, *col) else {
+      continue;
+    };
+    let end = match segments.get(i + 1) {
+      Some((next_line, next_col, _)) if next_line == line => cursor.offset(*line, *next_col),
+      _ => Some(cursor.line_end(*line)),
+    };
+    let Some(end) = end.filter(|end| *end > start) else {
+      continue;
+    };
+
+    let module_ranges = ranges.entry((*module_id).clone()).or_default();
+
+    match module_ranges.last_mut() {
+      Some(last) if last[1] == start => last[1] = end,
+      _ => module_ranges.push([start, end]),
+    }
+  }
+
+  ranges
+}
+
+/// Convert the utf16 columns of the source map to byte offsets, the positions must be converted in order
+struct Utf16Cursor<'a> {
+  code: &'a str,
+  line_starts: Vec<usize>,
+  line: usize,
+  offset: usize,
+  col: usize,
+}
+
+impl<'a> Utf16Cursor<'a> {
+  fn new(code: &'a str) -> Self {
+    Self {
+      code,
+      line_starts: std::iter::once(0)
+        .chain(code.match_indices('\n').map(|(i, _)| i + 1))
+        .collect(),
+      line: 0,
+      offset: 0,
+      col: 0,
+    }
+  }
+
+  fn line_end(&self, line: usize) -> usize {
+    self
+      .line_starts
+      .get(line + 1)
+      .map(|start| start - 1)
+      .unwrap_or(self.code.len())
+  }
+
+  fn offset(&mut self, line: usize, col: usize) -> Option<usize> {
+    let start = *self.line_starts.get(line)?;
+
+    if line != self.line || col < self.col {
+      self.line = line;
+      self.offset = start;
+      self.col = 0;
+    }
+
+    let end = self.line_end(line);
+
+    while self.col < col {
+      let c = self.code[self.offset..end].chars().next()?;
+      self.offset += c.len_utf8();
+      self.col += c.len_utf16();
+    }
+
+    Some(self.offset)
+  }
+}
+
+pub(crate) fn gzip_len(bytes: &[u8]) -> usize {
+  let mut encoder = GzEncoder::new(vec![], Compression::default());
+  encoder.write_all(bytes).unwrap();
+  encoder.finish().map(|r| r.len()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use farmfe_core::module::ModuleId;
+  use farmfe_toolkit::sourcemap::SourceMap;
+
+  use super::{gzip_len, module_ranges};
+
+  #[test]
+  fn test_module_ranges() {
+    let code = "a();b();\nc();";
+    let map = SourceMap::from_slice(
+      br#"{"version":3,"sources":["/a.js","/b.js"],"names":[],"mappings":"AAAA,ICAA;ADAA"}"#,
+    )
+    .unwrap();
+    let module_sources = HashMap::from([
+      ("/a.js".to_string(), "a.js".into()),
+      ("/b.js".to_string(), "b.js".into()),
+    ]);
+    let ranges = module_ranges(code, &map, &module_sources);
+
+    assert_eq!(ranges[&ModuleId::from("a.js")], vec![[0, 4], [9, 13]]);
+    assert_eq!(ranges[&ModuleId::from("b.js")], vec![[4, 8]]);
+  }
+
+  #[test]
+  fn test_gzip_len() {
+    let bytes = "console.log('hello');".repeat(100);
+    let len = gzip_len(bytes.as_bytes());
+
+    assert!(len > 0 && len < bytes.len());
+  }
+}
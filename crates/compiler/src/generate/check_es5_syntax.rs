@@ -0,0 +1,21 @@
+//! Validate the emitted js resources when `output.es5` is enabled, so that syntax that is not downgraded fails the build
+//! instead of breaking old webviews at runtime.
+use std::sync::Arc;
+
+use farmfe_core::{context::CompilationContext, error::Result, resource::ResourceType};
+use farmfe_toolkit::script::es5_syntax::check_es5_syntax;
+
+pub fn check_es5_resources(context: &Arc<CompilationContext>) -> Result<()> {
+  let mut resources = context
+    .resources_map
+    .iter()
+    .filter(|r| matches!(r.resource_type, ResourceType::Js) && !r.emitted)
+    .collect::<Vec<_>>();
+  resources.sort_by(|a, b| a.name.cmp(&b.name));
+
+  for resource in resources {
+    check_es5_syntax(&resource.name, &String::from_utf8_lossy(&resource.bytes))?;
+  }
+
+  Ok(())
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use farmfe_core::{context::CompilationContext, error::Result, resource::Resource};
+
+/// Emit the finalized resources to the sink of the context, see [farmfe_core::resource::emit_sink::EmitSink].
+/// With `only_changed`, e.g. after an update, the resources whose content is the same as the last emitted one are skipped
+pub fn emit_resources(context: &Arc<CompilationContext>, only_changed: bool) -> Result<()> {
+  let sink = context.emit_sink.read().clone();
+
+  for resource in context.resources_map.iter().filter(|r| r.should_write()) {
+    if !sink.is_noop() {
+      let fingerprint = fingerprint(resource.value(), context);
+      let unchanged = context
+        .emitted_resources
+        .get(resource.key())
+        .is_some_and(|emitted| *emitted == fingerprint);
+
+      if only_changed && unchanged {
+        continue;
+      }
+
+      sink.emit(resource.value(), context)?;
+      context
+        .emitted_resources
+        .insert(resource.key().clone(), fingerprint);
+    }
+
+    context.progress.resource_written();
+  }
+
+  sink.finish(context)
+}
+
+/// Resources backed by a source path are copied from it, the path identifies their content
+fn fingerprint(resource: &Resource, context: &CompilationContext) -> String {
+  match &resource.source_path {
+    Some(source_path) => format!("path:{source_path}"),
+    None => context.config.hash.hash(&resource.bytes, 32),
+  }
+}
@@ -0,0 +1,142 @@
+//! The manifest of the script entries for host pages without a html entry, see `entryManifest`.
+//! The resources are finalized, so the names in the manifest are the names of the written files
+use std::{collections::BTreeMap, sync::Arc};
+
+use farmfe_core::{
+  config::ModuleFormat,
+  context::CompilationContext,
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde::Serialize,
+  serde_json,
+};
+
+#[derive(Debug, Default, Serialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+struct EntryManifestItem {
+  /// the js resource of the entry, which imports the other js resources itself
+  file: String,
+  /// js resources imported by the entry, they can be preloaded by the host page
+  imports: Vec<String>,
+  css: Vec<String>,
+}
+
+pub fn emit_entry_manifest(context: &Arc<CompilationContext>) {
+  let Some(config) = context.config.entry_manifest.as_ref() else {
+    return;
+  };
+
+  let public_path = context.config.output.static_public_path();
+  let mut manifest = BTreeMap::new();
+
+  {
+    let module_graph = context.module_graph.read();
+    let module_group_graph = context.module_group_graph.read();
+    let resource_pot_map = context.resource_pot_map.read();
+    let resources_map = &context.resources_map;
+
+    for (entry, name) in &module_graph.entries {
+      let is_script = module_graph
+        .module(entry)
+        .is_some_and(|module| module.module_type.is_script());
+      let Some(module_group) = module_group_graph.module_group(entry) else {
+        continue;
+      };
+
+      if !is_script {
+        continue;
+      }
+
+      let mut item = EntryManifestItem::default();
+
+      for resource_pot_id in module_group.sorted_resource_pots(&module_graph, &resource_pot_map) {
+        let Some(resource_pot) = resource_pot_map.resource_pot(&resource_pot_id) else {
+          continue;
+        };
+
+        for resource_name in resource_pot.resources() {
+          let Some(resource) = resources_map.get(resource_name) else {
+            continue;
+          };
+
+          if resource.emitted {
+            continue;
+          }
+
+          let url = format!("{public_path}{}", resource.name);
+
+          match resource.resource_type {
+            ResourceType::Js if resource_pot.entry_module.as_ref() == Some(entry) => {
+              item.file = url;
+            }
+            ResourceType::Js => item.imports.push(url),
+            ResourceType::Css => item.css.push(url),
+            _ => {}
+          }
+        }
+      }
+
+      manifest.insert(name.clone(), item);
+    }
+  }
+
+  let mut resources = vec![(
+    config.filename.clone(),
+    serde_json::to_vec_pretty(&manifest).unwrap(),
+    ResourceType::Asset("json".to_string()),
+  )];
+
+  if let Some(loader) = &config.loader {
+    for (name, item) in &manifest {
+      resources.push((
+        loader.replace("[entryName]", name),
+        loader_snippet(item, &context.config.output.format).into_bytes(),
+        ResourceType::Asset("html".to_string()),
+      ));
+    }
+  }
+
+  for (name, bytes, resource_type) in resources {
+    context.resources_map.insert(
+      name.clone(),
+      Resource {
+        name: name.clone(),
+        bytes,
+        emitted: false,
+        resource_type,
+        origin: ResourceOrigin::ResourcePot(name),
+        source_path: None,
+        scope: Default::default(),
+        info: None,
+      },
+    );
+  }
+}
+
+/// The tags the host page includes to load the entry
+fn loader_snippet(item: &EntryManifestItem, format: &ModuleFormat) -> String {
+  let mut tags = item
+    .css
+    .iter()
+    .map(|css| format!(r#"<link rel="stylesheet" href="{css}">"#))
+    .collect::<Vec<_>>();
+
+  match format {
+    ModuleFormat::EsModule => {
+      tags.extend(
+        item
+          .imports
+          .iter()
+          .map(|import| format!(r#"<link rel="modulepreload" href="{import}">"#)),
+      );
+      tags.push(format!(
+        r#"<script type="module" src="{}"></script>"#,
+        item.file
+      ));
+    }
+    ModuleFormat::CommonJs => {
+      tags.push(format!(r#"<script src="{}"></script>"#, item.file));
+    }
+  }
+
+  format!("{}\n", tags.join("\n"))
+}
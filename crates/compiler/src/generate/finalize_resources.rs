@@ -1,47 +1,129 @@
-use std::sync::Arc;
+use std::{
+  collections::{HashMap, HashSet},
+  sync::Arc,
+};
 
 use farmfe_core::{
-  config::Mode, context::CompilationContext, plugin::PluginFinalizeResourcesHookParams,
+  config::Mode,
+  context::CompilationContext,
+  plugin::PluginFinalizeResourcesHookParams,
+  resource::{
+    content_hash::{normalize_finalized_resources, replace_hash_placeholders},
+    Resource, ResourceOrigin,
+  },
 };
 
 pub fn finalize_resources(context: &Arc<CompilationContext>) -> farmfe_core::error::Result<()> {
+  let mut resources_map = context.take_resources();
+  let result = finalize_resources_map(&mut resources_map, context);
+  // the resources are put back even if a hook failed, so they are still served
+  context.put_resources(resources_map);
+  let (renames, added) = result?;
+
+  sync_resource_pots(context, &renames, &added);
+
+  Ok(())
+}
+
+/// Call the `finalize_resources` hooks and replace the hash placeholders, returns the renamed and the added resources
+fn finalize_resources_map(
+  resources_map: &mut HashMap<String, Resource>,
+  context: &Arc<CompilationContext>,
+) -> farmfe_core::error::Result<(HashMap<String, String>, Vec<String>)> {
+  let existing = resources_map.keys().cloned().collect::<HashSet<_>>();
+
+  let mut param = PluginFinalizeResourcesHookParams {
+    resources_map,
+    config: &context.config,
+  };
+
+  context
+    .plugin_driver
+    .finalize_resources(&mut param, context)?;
+
+  // plugins may add, remove or rename resources
+  normalize_finalized_resources(resources_map, &context.config.hash);
+  let added = resources_map
+    .keys()
+    .filter(|name| !existing.contains(*name))
+    .cloned()
+    .collect::<Vec<_>>();
+
+  // resources of other modes are never present in the output, e.g. dev server only resources in production
+  resources_map.retain(|_, resource| resource.scope.is_available(&context.config.mode));
+
+  // if cache enabled, clear unused resources
+  if context.config.persistent_cache.enabled()
+    && matches!(context.config.mode, Mode::Production)
+    && !context.config.lazy_compilation
   {
-    let mut resources_map = context.resources_map.lock();
-
-    let mut param = PluginFinalizeResourcesHookParams {
-      resources_map: &mut resources_map,
-      config: &context.config,
-    };
-
-    context
-      .plugin_driver
-      .finalize_resources(&mut param, context)?;
-
-    // if cache enabled, clear unused resources
-    if context.config.persistent_cache.enabled()
-      && matches!(context.config.mode, Mode::Production)
-      && !context.config.lazy_compilation
-    {
-      let mut resources_to_remove = vec![];
-      let module_graph = context.module_graph.read();
-
-      for resource in resources_map.values() {
-        match &resource.origin {
-          farmfe_core::resource::ResourceOrigin::ResourcePot(_) => { /* do nothing for resource pot */
-          }
-          farmfe_core::resource::ResourceOrigin::Module(m) => {
-            if !module_graph.has_module(m) {
-              resources_to_remove.push(resource.name.clone());
-            }
-          }
+    let mut resources_to_remove = vec![];
+    let module_graph = context.module_graph.read();
+
+    for resource in resources_map.values() {
+      // resources of resource pots are always kept, they have no modules in their origin
+      if resource
+        .origin
+        .modules()
+        .iter()
+        .any(|m| !module_graph.has_module(m))
+      {
+        resources_to_remove.push(resource.name.clone());
+      }
+    }
+
+    resources_to_remove.into_iter().for_each(|r| {
+      resources_map.remove(&r);
+    });
+  }
+
+  // all resources are finalized, the hashes of the resources referenced by other resources are known now
+  let renames = replace_hash_placeholders(resources_map, &context.config.hash);
+  let added = added
+    .into_iter()
+    .map(|name| renames.get(&name).cloned().unwrap_or(name))
+    .collect::<Vec<_>>();
+
+  Ok((renames, added))
+}
+
+/// Keep the resources of resource pots in sync with `resources_map` after the hashes are replaced and plugins changed the resources
+pub(crate) fn sync_resource_pots(
+  context: &Arc<CompilationContext>,
+  renames: &HashMap<String, String>,
+  added: &[String],
+) {
+  let mut resource_pot_map = context.resource_pot_map.write();
+  let resources_map = &context.resources_map;
+
+  for resource_pot in resource_pot_map.resource_pots_mut() {
+    let stale = resource_pot
+      .resources()
+      .into_iter()
+      .filter_map(|name| {
+        let new_name = renames.get(name).unwrap_or(name);
+
+        if new_name != name || !resources_map.contains_key(new_name) {
+          Some((name.clone(), new_name.clone()))
+        } else {
+          None
         }
+      })
+      .collect::<Vec<_>>();
+
+    for (old_name, new_name) in stale {
+      resource_pot.remove_resource(&old_name);
+
+      if resources_map.contains_key(&new_name) {
+        resource_pot.add_resource(new_name);
       }
+    }
 
-      resources_to_remove.into_iter().for_each(|r| {
-        resources_map.remove(&r);
-      });
+    // resources added or renamed by plugins
+    for resource in added.iter().filter_map(|name| resources_map.get(name)) {
+      if matches!(&resource.origin, ResourceOrigin::ResourcePot(id) if id == &resource_pot.id) {
+        resource_pot.add_resource(resource.name.clone());
+      }
     }
   }
-
-  Ok(())
 }
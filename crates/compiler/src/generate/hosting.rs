@@ -0,0 +1,249 @@
+//! Config of the hosting providers generated from the neutral `hosting` config: `_redirects` and `_headers` of Netlify,
+//! `vercel.json` of Vercel and a `nginx.conf` snippet. The files are emitted as resources alongside the build.
+use std::{fmt::Write, sync::Arc};
+
+use farmfe_core::{
+  config::{
+    hosting::{HostingConfig, HostingProvider},
+    Mode,
+  },
+  context::CompilationContext,
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde_json::{self, json, Value},
+};
+
+const NETLIFY_REDIRECTS: &str = "_redirects";
+const NETLIFY_HEADERS: &str = "_headers";
+const VERCEL_CONFIG: &str = "vercel.json";
+const NGINX_CONFIG: &str = "nginx.conf";
+/// the rest of the path matched by the trailing `*` of a path
+const SPLAT: &str = ":splat";
+
+pub fn emit_hosting_config(context: &Arc<CompilationContext>) {
+  let Some(config) = context.config.hosting.as_ref() else {
+    return;
+  };
+
+  if !matches!(context.config.mode, Mode::Production) {
+    return;
+  }
+
+  let mut files = vec![];
+
+  for provider in &config.providers {
+    match provider {
+      HostingProvider::Netlify => {
+        files.push((NETLIFY_REDIRECTS, "txt", netlify_redirects(config)));
+        files.push((NETLIFY_HEADERS, "txt", netlify_headers(config)));
+      }
+      HostingProvider::Vercel => {
+        files.push((VERCEL_CONFIG, "json", vercel_config(config)));
+      }
+      HostingProvider::Nginx => {
+        files.push((NGINX_CONFIG, "conf", nginx_config(config)));
+      }
+    }
+  }
+
+  for (name, ext, content) in files {
+    if content.is_empty() {
+      continue;
+    }
+
+    context.resources_map.insert(
+      name.to_string(),
+      Resource {
+        name: name.to_string(),
+        bytes: content.into_bytes(),
+        resource_type: ResourceType::Custom(ext.to_string()),
+        origin: ResourceOrigin::ResourcePot(name.to_string()),
+        ..Default::default()
+      },
+    );
+  }
+}
+
+/// `/docs/*` -> (`/docs/`, true)
+fn split_wildcard(path: &str) -> (&str, bool) {
+  match path.strip_suffix('*') {
+    Some(prefix) => (prefix, true),
+    None => (path, false),
+  }
+}
+
+fn netlify_redirects(config: &HostingConfig) -> String {
+  let mut lines = vec![];
+
+  for redirect in &config.redirects {
+    lines.push(format!(
+      "{} {} {}",
+      redirect.from, redirect.to, redirect.status
+    ));
+  }
+
+  for rewrite in &config.rewrites {
+    lines.push(format!("{} {} 200", rewrite.from, rewrite.to));
+  }
+
+  if let Some(fallback) = &config.spa_fallback {
+    lines.push(format!("/* {fallback} 200"));
+  }
+
+  lines.into_iter().fold(String::new(), |mut out, line| {
+    let _ = writeln!(out, "{line}");
+    out
+  })
+}
+
+fn netlify_headers(config: &HostingConfig) -> String {
+  config
+    .immutable
+    .iter()
+    .fold(String::new(), |mut out, path| {
+      let _ = write!(
+        out,
+        "{path}\n  Cache-Control: {}\n",
+        config.immutable_cache_control
+      );
+      out
+    })
+}
+
+/// `/docs/*` -> `/docs/:splat*`, `/guide/:splat` -> `/guide/:splat*`
+fn vercel_path(path: &str) -> String {
+  match split_wildcard(path) {
+    (prefix, true) => format!("{prefix}{SPLAT}*"),
+    (path, false) => path.replace(SPLAT, &format!("{SPLAT}*")),
+  }
+}
+
+fn vercel_config(config: &HostingConfig) -> String {
+  let redirects = config
+    .redirects
+    .iter()
+    .map(|redirect| {
+      json!({
+        "source": vercel_path(&redirect.from),
+        "destination": vercel_path(&redirect.to),
+        "statusCode": redirect.status,
+      })
+    })
+    .collect::<Vec<_>>();
+  let mut rewrites = config
+    .rewrites
+    .iter()
+    .map(|rewrite| {
+      json!({
+        "source": vercel_path(&rewrite.from),
+        "destination": vercel_path(&rewrite.to),
+      })
+    })
+    .collect::<Vec<_>>();
+
+  // vercel serves the existing files before the rewrites
+  if let Some(fallback) = &config.spa_fallback {
+    rewrites.push(json!({
+      "source": vercel_path("/*"),
+      "destination": fallback,
+    }));
+  }
+
+  let headers = config
+    .immutable
+    .iter()
+    .map(|path| {
+      json!({
+        "source": vercel_path(path),
+        "headers": [{ "key": "Cache-Control", "value": config.immutable_cache_control }],
+      })
+    })
+    .collect::<Vec<_>>();
+
+  let mut vercel_config = serde_json::Map::new();
+
+  for (key, value) in [
+    ("redirects", redirects),
+    ("rewrites", rewrites),
+    ("headers", headers),
+  ] {
+    if !value.is_empty() {
+      vercel_config.insert(key.to_string(), Value::Array(value));
+    }
+  }
+
+  if vercel_config.is_empty() {
+    return String::new();
+  }
+
+  serde_json::to_string_pretty(&vercel_config).unwrap()
+}
+
+/// `/docs/*` -> `^/docs/(.*)$`
+fn nginx_regex(path: &str) -> String {
+  let (prefix, wildcard) = split_wildcard(path);
+  let mut regex = String::from("^");
+
+  for c in prefix.chars() {
+    if ".+?()[]{}|^$\\".contains(c) {
+      regex.push('\\');
+    }
+    regex.push(c);
+  }
+
+  if wildcard {
+    regex.push_str("(.*)");
+  }
+
+  regex.push('$');
+  regex
+}
+
+fn nginx_config(config: &HostingConfig) -> String {
+  let mut blocks = vec!["# generated by farm, include it in the server block".to_string()];
+
+  if config.precompressed {
+    blocks
+      .push("gzip_static on;\n# requires the ngx_brotli module\n# brotli_static on;".to_string());
+  }
+
+  for redirect in &config.redirects {
+    let target = redirect.to.replace(SPLAT, "$1");
+    let location = match split_wildcard(&redirect.from) {
+      (_, true) => format!("~ {}", nginx_regex(&redirect.from)),
+      (path, false) => format!("= {path}"),
+    };
+
+    blocks.push(format!(
+      "location {location} {{\n  return {} {target};\n}}",
+      redirect.status
+    ));
+  }
+
+  for rewrite in &config.rewrites {
+    blocks.push(format!(
+      "rewrite {} {} last;",
+      nginx_regex(&rewrite.from),
+      rewrite.to.replace(SPLAT, "$1")
+    ));
+  }
+
+  for path in &config.immutable {
+    let location = match split_wildcard(path) {
+      (prefix, true) => format!("^~ {prefix}"),
+      (path, false) => format!("= {path}"),
+    };
+
+    blocks.push(format!(
+      "location {location} {{\n  add_header Cache-Control \"{}\";\n  try_files $uri =404;\n}}",
+      config.immutable_cache_control
+    ));
+  }
+
+  if let Some(fallback) = &config.spa_fallback {
+    blocks.push(format!(
+      "location / {{\n  try_files $uri $uri/ {fallback};\n}}"
+    ));
+  }
+
+  format!("{}\n", blocks.join("\n\n"))
+}
@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  context::CompilationContext,
+  enhanced_magic_string::{magic_string::MagicString, types::SourceMapOptions},
+  error::{CompilationError, Result},
+  plugin::PluginInjectResourcePotCodeHookResult,
+  resource::resource_pot::{ResourcePot, ResourcePotInfo},
+};
+
+/// Code injected into the resource pot by `output.injections` and the `inject_resource_pot_code` hook
+pub fn resolve_resource_pot_injection(
+  resource_pot_info: &ResourcePotInfo,
+  context: &Arc<CompilationContext>,
+) -> Result<PluginInjectResourcePotCodeHookResult> {
+  let mut injection = PluginInjectResourcePotCodeHookResult::default();
+
+  for config in &context.config.output.injections {
+    if config.is_match(
+      &resource_pot_info.resource_pot_type,
+      &resource_pot_info.name,
+    ) {
+      injection.merge(&PluginInjectResourcePotCodeHookResult {
+        banner: config.banner.clone().unwrap_or_default(),
+        intro: config.intro.clone().unwrap_or_default(),
+        footer: config.footer.clone().unwrap_or_default(),
+      });
+    }
+  }
+
+  let plugin_injection = context
+    .plugin_driver
+    .inject_resource_pot_code(resource_pot_info, context)?;
+  injection.merge(&plugin_injection);
+
+  Ok(injection)
+}
+
+/// Prepend and append code to the rendered content of the resource pot.
+/// A source map of the injection is pushed to the map chain, so the final source map still points to the original modules
+pub fn inject_resource_pot_code(
+  resource_pot: &mut ResourcePot,
+  prepend: &str,
+  append: &str,
+) -> Result<()> {
+  if prepend.is_empty() && append.is_empty() {
+    return Ok(());
+  }
+
+  let content = resource_pot.meta.rendered_content.clone();
+  let mut magic_string = MagicString::new(&content, None);
+
+  if !prepend.is_empty() {
+    magic_string.prepend(prepend);
+  }
+
+  if !append.is_empty() {
+    if !content.is_empty() && !content.ends_with('\n') {
+      magic_string.append("\n");
+    }
+
+    magic_string.append(append);
+  }
+
+  resource_pot.meta.rendered_content = Arc::new(magic_string.to_string());
+
+  if !resource_pot.meta.rendered_map_chain.is_empty() {
+    let map = magic_string
+      .generate_map(SourceMapOptions {
+        include_content: Some(true),
+        ..Default::default()
+      })
+      .map_err(|_| CompilationError::GenerateSourceMapError {
+        id: resource_pot.id.to_string(),
+      })?;
+    let mut buf = vec![];
+    map
+      .to_writer(&mut buf)
+      .map_err(|_| CompilationError::GenerateSourceMapError {
+        id: resource_pot.id.to_string(),
+      })?;
+
+    resource_pot
+      .meta
+      .rendered_map_chain
+      .push(Arc::new(String::from_utf8(buf).unwrap()));
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use farmfe_core::resource::resource_pot::{ResourcePot, ResourcePotType};
+
+  use super::inject_resource_pot_code;
+
+  #[test]
+  fn inject_code() {
+    let mut resource_pot = ResourcePot::new("index".to_string(), ResourcePotType::Js);
+    resource_pot.meta.rendered_content = Arc::new("console.log(1);".to_string());
+
+    inject_resource_pot_code(&mut resource_pot, "/* banner */\n", "/* footer */\n").unwrap();
+
+    assert_eq!(
+      resource_pot.meta.rendered_content.as_str(),
+      "/* banner */\nconsole.log(1);\n/* footer */\n"
+    );
+    assert!(resource_pot.meta.rendered_map_chain.is_empty());
+  }
+}
@@ -0,0 +1,94 @@
+//! Extract the license comments of the modules in a js resource pot to a sibling `<resource>.LICENSE.txt`
+//! when `output.extractLicenseComments` is enabled. The comments are collected when the resource pot is rendered
+//! and kept in the resource pot meta, so the resource can be emitted for cached resource pots too.
+
+use std::{collections::HashSet, sync::Arc};
+
+use farmfe_core::{
+  config::comments::is_license_comment,
+  context::CompilationContext,
+  resource::{
+    resource_pot::{ResourcePot, ResourcePotType},
+    Resource, ResourceOrigin, ResourceType, LICENSE_RESOURCE_TYPE,
+  },
+  swc_common::comments::{Comment, CommentKind},
+};
+
+const LICENSE_COMMENTS_KEY: &str = "license_comments";
+const LICENSE_RESOURCE_SUFFIX: &str = ".LICENSE.txt";
+
+/// Collect the license comments of the modules in execution order, duplicated comments are only kept once
+pub fn collect_license_comments(resource_pot: &mut ResourcePot, context: &Arc<CompilationContext>) {
+  if !context.config.output.extract_license_comments
+    || !matches!(resource_pot.resource_pot_type, ResourcePotType::Js)
+  {
+    return;
+  }
+
+  let module_graph = context.module_graph.read();
+  let mut modules = resource_pot
+    .modules()
+    .into_iter()
+    .filter_map(|id| module_graph.module(id))
+    .filter(|m| m.module_type.is_script())
+    .collect::<Vec<_>>();
+  modules.sort_by_key(|m| m.execution_order);
+
+  let mut visited = HashSet::new();
+  let mut license_comments = vec![];
+
+  for module in modules {
+    let comments = &module.meta.as_script().comments;
+    let mut items = comments
+      .leading
+      .iter()
+      .chain(comments.trailing.iter())
+      .collect::<Vec<_>>();
+    items.sort_by_key(|item| item.byte_pos);
+
+    for comment in items.into_iter().flat_map(|item| &item.comment) {
+      if !is_license_comment(comment) {
+        continue;
+      }
+
+      let text = comment_to_string(comment);
+
+      if visited.insert(text.clone()) {
+        license_comments.push(text);
+      }
+    }
+  }
+
+  if !license_comments.is_empty() {
+    resource_pot.meta.custom_data.insert(
+      LICENSE_COMMENTS_KEY.to_string(),
+      license_comments.join("\n\n"),
+    );
+  }
+}
+
+/// The `<resource>.LICENSE.txt` of the resource generated by the resource pot, [None] if there is no license comment
+pub fn create_license_comments_resource(
+  resource_pot: &ResourcePot,
+  resource_name: &str,
+) -> Option<Resource> {
+  let license_comments = resource_pot.meta.custom_data.get(LICENSE_COMMENTS_KEY)?;
+
+  Some(Resource {
+    name: format!("{resource_name}{LICENSE_RESOURCE_SUFFIX}"),
+    bytes: format!("{license_comments}\n").into_bytes(),
+    emitted: false,
+    resource_type: ResourceType::Custom(LICENSE_RESOURCE_TYPE.to_string()),
+    origin: ResourceOrigin::ResourcePot(resource_pot.id.clone()),
+    source_path: None,
+    scope: Default::default(),
+    info: None,
+  })
+}
+
+fn comment_to_string(comment: &Comment) -> String {
+  match comment.kind {
+    CommentKind::Block => format!("/*{}*/", comment.text),
+    CommentKind::Line => format!("//{}", comment.text),
+  }
+}
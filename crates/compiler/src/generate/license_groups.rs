@@ -0,0 +1,253 @@
+//! Route immutable modules into separate resource pots by the license family of their packages,
+//! so permissive and copyleft code never end up in the same output file. A license report is emitted for each license group.
+use std::{collections::BTreeSet, sync::Arc};
+
+use farmfe_core::{
+  context::CompilationContext,
+  module::Module,
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde_json,
+};
+
+pub const LICENSE_GROUP_PREFIX: &str = "vendor-";
+const LICENSE_REPORT_SUFFIX: &str = ".licenses.json";
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum LicenseFamily {
+  Permissive,
+  Unknown,
+  Copyleft,
+}
+
+impl LicenseFamily {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      LicenseFamily::Permissive => "permissive",
+      LicenseFamily::Unknown => "unknown",
+      LicenseFamily::Copyleft => "copyleft",
+    }
+  }
+}
+
+const PERMISSIVE_LICENSES: [&str; 14] = [
+  "MIT",
+  "MIT-0",
+  "ISC",
+  "BSD-2-Clause",
+  "BSD-3-Clause",
+  "0BSD",
+  "Apache-2.0",
+  "Unlicense",
+  "CC0-1.0",
+  "CC-BY-3.0",
+  "CC-BY-4.0",
+  "Zlib",
+  "BlueOak-1.0.0",
+  "Python-2.0",
+];
+
+const COPYLEFT_LICENSE_PREFIXES: [&str; 9] = [
+  "GPL", "LGPL", "AGPL", "MPL", "EPL", "CDDL", "EUPL", "OSL", "CC-BY-SA",
+];
+
+fn classify_single_license(license: &str) -> LicenseFamily {
+  let license = license.trim().trim_end_matches('+');
+  let license = license.strip_suffix("-or-later").unwrap_or(license);
+  let license = license.strip_suffix("-only").unwrap_or(license);
+
+  if PERMISSIVE_LICENSES
+    .iter()
+    .any(|l| l.eq_ignore_ascii_case(license))
+  {
+    LicenseFamily::Permissive
+  } else if COPYLEFT_LICENSE_PREFIXES
+    .iter()
+    .any(|p| license.to_ascii_uppercase().starts_with(p))
+  {
+    LicenseFamily::Copyleft
+  } else {
+    LicenseFamily::Unknown
+  }
+}
+
+/// Classify a spdx license expression. For `OR` the most permissive choice is used, for `AND` the most restrictive one is used.
+/// `AND` binds tighter than `OR` and parentheses are honored, expressions that can not be parsed are [LicenseFamily::Unknown]
+pub fn classify_license(expression: &str) -> LicenseFamily {
+  let spaced = expression.replace('(', " ( ").replace(')', " ) ");
+  let tokens = spaced.split_whitespace().collect::<Vec<_>>();
+  let mut parser = LicenseExpressionParser { tokens, pos: 0 };
+
+  match parser.parse_or() {
+    Some(family) if parser.pos == parser.tokens.len() => family,
+    _ => LicenseFamily::Unknown,
+  }
+}
+
+struct LicenseExpressionParser<'a> {
+  tokens: Vec<&'a str>,
+  pos: usize,
+}
+
+impl<'a> LicenseExpressionParser<'a> {
+  fn eat(&mut self, token: &str) -> bool {
+    let matched = self
+      .tokens
+      .get(self.pos)
+      .is_some_and(|t| t.eq_ignore_ascii_case(token));
+
+    if matched {
+      self.pos += 1;
+    }
+
+    matched
+  }
+
+  fn parse_or(&mut self) -> Option<LicenseFamily> {
+    let mut family = self.parse_and()?;
+
+    while self.eat("OR") {
+      family = family.min(self.parse_and()?);
+    }
+
+    Some(family)
+  }
+
+  fn parse_and(&mut self) -> Option<LicenseFamily> {
+    let mut family = self.parse_primary()?;
+
+    while self.eat("AND") {
+      family = family.max(self.parse_primary()?);
+    }
+
+    Some(family)
+  }
+
+  fn parse_primary(&mut self) -> Option<LicenseFamily> {
+    if self.eat("(") {
+      let family = self.parse_or()?;
+      return self.eat(")").then_some(family);
+    }
+
+    let license = *self.tokens.get(self.pos)?;
+
+    if [")", "AND", "OR", "WITH"]
+      .iter()
+      .any(|t| t.eq_ignore_ascii_case(license))
+    {
+      return None;
+    }
+
+    self.pos += 1;
+
+    // the exception does not change the license family, e.g. `GPL-2.0 WITH Classpath-exception-2.0`
+    if self.eat("WITH") {
+      self.tokens.get(self.pos)?;
+      self.pos += 1;
+    }
+
+    Some(classify_single_license(license))
+  }
+}
+
+/// Only modules of third party packages are grouped by license
+pub fn get_license_group_name(module: &Module) -> Option<String> {
+  if !module.immutable {
+    return None;
+  }
+
+  Some(format!(
+    "{LICENSE_GROUP_PREFIX}{}",
+    classify_license(&module.package_license).as_str()
+  ))
+}
+
+/// Emit `vendor-{family}.licenses.json` for every license group, which lists the packages and their licenses
+pub fn emit_license_reports(context: &Arc<CompilationContext>) {
+  if !context.config.partial_bundling.license_groups {
+    return;
+  }
+
+  let module_graph = context.module_graph.read();
+  let resource_pot_map = context.resource_pot_map.read();
+  let resources_map = &context.resources_map;
+
+  for resource_pot in resource_pot_map.resource_pots() {
+    if !resource_pot.name.starts_with(LICENSE_GROUP_PREFIX) {
+      continue;
+    }
+
+    let packages = resource_pot
+      .modules()
+      .into_iter()
+      .filter_map(|id| module_graph.module(id))
+      .map(|m| {
+        (
+          m.package_name.clone(),
+          m.package_version.clone(),
+          m.package_license.clone(),
+        )
+      })
+      .collect::<BTreeSet<_>>();
+
+    let name = format!("{}{LICENSE_REPORT_SUFFIX}", resource_pot.name);
+    // js and css resource pots of the same group share one report
+    let mut report = resources_map
+      .get(&name)
+      .and_then(|r| serde_json::from_slice::<Vec<serde_json::Value>>(&r.bytes).ok())
+      .unwrap_or_default();
+
+    for (name, version, license) in packages {
+      let item = serde_json::json!({ "name": name, "version": version, "license": license });
+
+      if !report.contains(&item) {
+        report.push(item);
+      }
+    }
+
+    resources_map.insert(
+      name.clone(),
+      Resource {
+        name,
+        bytes: serde_json::to_vec_pretty(&report).unwrap(),
+        emitted: false,
+        resource_type: ResourceType::Custom("json".to_string()),
+        origin: ResourceOrigin::ResourcePot(resource_pot.id.clone()),
+        source_path: None,
+        scope: Default::default(),
+        info: None,
+      },
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{classify_license, LicenseFamily};
+
+  #[test]
+  fn test_classify_license() {
+    assert_eq!(classify_license("MIT"), LicenseFamily::Permissive);
+    assert_eq!(classify_license("GPL-3.0-or-later"), LicenseFamily::Copyleft);
+    assert_eq!(classify_license("(MIT OR GPL-2.0)"), LicenseFamily::Permissive);
+    assert_eq!(classify_license("MIT AND LGPL-2.1"), LicenseFamily::Copyleft);
+    assert_eq!(classify_license(""), LicenseFamily::Unknown);
+    assert_eq!(classify_license("SEE LICENSE IN LICENSE"), LicenseFamily::Unknown);
+    assert_eq!(
+      classify_license("(MIT OR Apache-2.0) AND GPL-3.0"),
+      LicenseFamily::Copyleft
+    );
+    assert_eq!(
+      classify_license("MIT OR Apache-2.0 AND GPL-3.0"),
+      LicenseFamily::Permissive
+    );
+    assert_eq!(
+      classify_license("GPL-2.0 AND (MIT OR (ISC AND Zlib))"),
+      LicenseFamily::Copyleft
+    );
+    assert_eq!(
+      classify_license("GPL-2.0-only WITH Classpath-exception-2.0 OR MIT"),
+      LicenseFamily::Permissive
+    );
+    assert_eq!(classify_license("(MIT OR GPL-2.0"), LicenseFamily::Unknown);
+  }
+}
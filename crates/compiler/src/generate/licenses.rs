@@ -0,0 +1,158 @@
+//! Collect the licenses of the third party packages bundled into the resource pots. The license of a package is read from its package.json
+//! and the license text from the `LICENSE`, `LICENCE` or `COPYING` file next to it. A json report and a third party notices file are emitted,
+//! and the build fails if a package has a license that is not allowed by `licenses.deny` or `licenses.allow`.
+use std::{
+  collections::BTreeMap,
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde_json,
+  stats::PackageLicenseStats,
+};
+use farmfe_toolkit::resolve::load_package_json;
+use farmfe_utils::relative;
+
+const LICENSE_FILE_PREFIXES: [&str; 3] = ["LICENSE", "LICENCE", "COPYING"];
+
+pub fn emit_licenses(context: &Arc<CompilationContext>) -> Result<()> {
+  let Some(config) = context.config.licenses.as_ref() else {
+    return Ok(());
+  };
+
+  let module_graph = context.module_graph.read();
+  let resource_pot_map = context.resource_pot_map.read();
+  // (name, version) -> (package, license text)
+  let mut packages = BTreeMap::new();
+
+  for resource_pot in resource_pot_map.resource_pots() {
+    for module_id in resource_pot.modules() {
+      let Some(module) = module_graph.module(module_id) else {
+        continue;
+      };
+
+      let key = (module.package_name.clone(), module.package_version.clone());
+
+      if !module.immutable || module.external || packages.contains_key(&key) {
+        continue;
+      }
+
+      let resolved_path = module.id.resolved_path(&context.config.root);
+      let license_file = load_package_json(PathBuf::from(resolved_path), Default::default())
+        .ok()
+        .and_then(|info| find_license_file(Path::new(info.dir())));
+      let license_text = license_file
+        .as_ref()
+        .and_then(|file| std::fs::read_to_string(file).ok());
+
+      packages.insert(
+        key,
+        (
+          PackageLicenseStats {
+            name: module.package_name.clone(),
+            version: module.package_version.clone(),
+            license: module.package_license.clone(),
+            license_file: license_file
+              .map(|file| relative(&context.config.root, &file.to_string_lossy())),
+          },
+          license_text,
+        ),
+      );
+    }
+  }
+
+  let disallowed = packages
+    .values()
+    .filter(|(package, _)| !config.is_allowed(&package.license))
+    .map(|(package, _)| {
+      let license = if package.license.is_empty() {
+        "unknown license"
+      } else {
+        &package.license
+      };
+      format!("  {}@{}: {license}", package.name, package.version)
+    })
+    .collect::<Vec<_>>();
+
+  if !disallowed.is_empty() {
+    return Err(CompilationError::GenericError(format!(
+      "Licenses of the bundled packages are not allowed:\n{}",
+      disallowed.join("\n")
+    )));
+  }
+
+  let report = packages
+    .values()
+    .map(|(package, _)| package.clone())
+    .collect::<Vec<_>>();
+  let resources_map = &context.resources_map;
+
+  resources_map.insert(
+    config.filename.clone(),
+    Resource {
+      name: config.filename.clone(),
+      bytes: serde_json::to_vec_pretty(&report).unwrap(),
+      resource_type: ResourceType::Custom("json".to_string()),
+      origin: ResourceOrigin::ResourcePot(config.filename.clone()),
+      ..Default::default()
+    },
+  );
+
+  if !config.notices.is_empty() {
+    let notices = packages
+      .values()
+      .map(|(package, license_text)| {
+        let mut notice = format!(
+          "{}@{}\nLicense: {}\n",
+          package.name, package.version, package.license
+        );
+
+        if let Some(license_text) = license_text {
+          notice.push('\n');
+          notice.push_str(license_text.trim_end());
+          notice.push('\n');
+        }
+
+        notice
+      })
+      .collect::<Vec<_>>();
+
+    resources_map.insert(
+      config.notices.clone(),
+      Resource {
+        name: config.notices.clone(),
+        bytes: notices.join("\n---\n\n").into_bytes(),
+        resource_type: ResourceType::Custom("txt".to_string()),
+        origin: ResourceOrigin::ResourcePot(config.notices.clone()),
+        ..Default::default()
+      },
+    );
+  }
+
+  Ok(())
+}
+
+/// The first file of the package directory named like `LICENSE`, `LICENSE.md` or `COPYING.txt`, case insensitive
+fn find_license_file(dir: &Path) -> Option<PathBuf> {
+  let mut files = std::fs::read_dir(dir)
+    .ok()?
+    .filter_map(|entry| entry.ok())
+    .map(|entry| entry.path())
+    .filter(|path| {
+      path.is_file()
+        && path.file_name().is_some_and(|name| {
+          let name = name.to_string_lossy().to_ascii_uppercase();
+          LICENSE_FILE_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+        })
+    })
+    .collect::<Vec<_>>();
+  files.sort();
+
+  files.into_iter().next()
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  cache::cache_store::CacheStoreKey,
+  cache_item,
+  config::minify::{MinifyMode, MinifyOptions},
+  context::CompilationContext,
+  deserialize,
+  error::Result,
+  resource::resource_pot::{ResourcePot, ResourcePotType},
+  rkyv::Deserialize,
+  serde_json, serialize,
+};
+
+#[cache_item]
+struct CachedMinifiedResourcePot {
+  content: String,
+  map: Option<String>,
+}
+
+/// Options of the resource pot if it should be minified as a whole. Resource pots that defer minify are minified regardless of `minify.mode`,
+/// `minify.js` and `minify.css` toggle the resource pot types, `minify.exclude` also matches the name of the resource pot, e.g. `^vendor`
+fn resource_pot_minify_options(
+  resource_pot: &ResourcePot,
+  context: &Arc<CompilationContext>,
+) -> Option<MinifyOptions> {
+  if resource_pot.is_passthrough() {
+    return None;
+  }
+
+  let options = Option::<MinifyOptions>::from(&*context.config.minify)?;
+
+  if !resource_pot.is_defer_minify_as_resource_pot()
+    && !matches!(options.mode, MinifyMode::ResourcePot)
+  {
+    return None;
+  }
+
+  let enabled = match resource_pot.resource_pot_type {
+    ResourcePotType::Js | ResourcePotType::Runtime => options.js,
+    ResourcePotType::Css => options.css,
+    _ => true,
+  };
+
+  if !enabled
+    || options
+      .exclude
+      .iter()
+      .any(|exclude| exclude.is_match(&resource_pot.name))
+  {
+    return None;
+  }
+
+  Some(options)
+}
+
+/// Minify the rendered resource pot by the `minify_resource_pot` hook. Resource pots are minified in parallel by their callers.
+/// Immutable resource pots reuse the minified result of the persistent cache when the rendered content is not changed
+pub fn minify_resource_pot(
+  resource_pot: &mut ResourcePot,
+  context: &Arc<CompilationContext>,
+) -> Result<()> {
+  let Some(options) = resource_pot_minify_options(resource_pot, context) else {
+    return Ok(());
+  };
+
+  let store_key =
+    (resource_pot.immutable && context.config.persistent_cache.enabled()).then(|| CacheStoreKey {
+      name: format!("{}-minify_resource_pot", resource_pot.id),
+      key: context.config.hash.hash(
+        [
+          resource_pot.meta.rendered_content.as_bytes(),
+          serde_json::to_string(&options).unwrap().as_bytes(),
+          &[context.config.sourcemap.enabled(resource_pot.immutable) as u8],
+        ]
+        .concat()
+        .as_slice(),
+        32,
+      ),
+    });
+
+  if let Some(store_key) = &store_key {
+    let cache = &context.cache_manager.custom;
+
+    if cache.has_cache(&store_key.name) && !cache.is_cache_changed(store_key) {
+      if let Some(bytes) = cache.read_cache(&store_key.name) {
+        let cached = deserialize!(&bytes, CachedMinifiedResourcePot);
+        resource_pot.meta.rendered_content = Arc::new(cached.content);
+
+        if let Some(map) = cached.map {
+          resource_pot.meta.rendered_map_chain.push(Arc::new(map));
+        }
+
+        return Ok(());
+      }
+    }
+  }
+
+  let map_chain_len = resource_pot.meta.rendered_map_chain.len();
+
+  context
+    .plugin_driver
+    .minify_resource_pot(resource_pot, context)?;
+
+  if let Some(store_key) = store_key {
+    let cached = CachedMinifiedResourcePot {
+      content: resource_pot.meta.rendered_content.to_string(),
+      // the minifier appends at most one source map to the chain
+      map: resource_pot
+        .meta
+        .rendered_map_chain
+        .get(map_chain_len)
+        .map(|map| map.to_string()),
+    };
+
+    // the cache is only an optimization, the minified resource pot is still emitted when it can not be written
+    if let Err(e) = context
+      .cache_manager
+      .custom
+      .write_single_cache(store_key, serialize!(&cached))
+    {
+      context.log_store.lock().add_warning(format!(
+        "Failed to cache the minified resource pot {}: {e}",
+        resource_pot.id
+      ));
+    }
+  }
+
+  Ok(())
+}
@@ -1,23 +1,48 @@
 use std::collections::HashMap;
 
-use farmfe_core::{error::Result, plugin::PluginHookContext};
+use farmfe_core::{
+  config::Mode, context::progress::BuildPhase, error::Result, plugin::PluginHookContext,
+};
 
 use crate::{
   generate::{
-    finalize_resources::finalize_resources, partial_bundling::partial_bundling,
+    bin_entries::emit_bin_entries,
+    bundle_stats::{emit_bundle_stats, record_module_exports},
+    check_es5_syntax::check_es5_resources,
+    emit_resources::emit_resources,
+    entry_manifest::emit_entry_manifest,
+    finalize_resources::finalize_resources,
+    hosting::emit_hosting_config,
+    license_groups::emit_license_reports,
+    licenses::emit_licenses,
+    partial_bundling::partial_bundling,
     render_resource_pots::render_resource_pots_and_generate_resources,
+    unused_exports::emit_unused_exports,
   },
   Compiler,
 };
 
+pub(crate) mod bin_entries;
+pub(crate) mod bundle_stats;
+pub(crate) mod check_es5_syntax;
+pub(crate) mod emit_resources;
+pub(crate) mod entry_manifest;
 pub(crate) mod finalize_resources;
+pub(crate) mod hosting;
+pub(crate) mod inject_resource_pot_code;
+pub(crate) mod license_comments;
+pub(crate) mod license_groups;
+pub(crate) mod licenses;
+pub(crate) mod minify_resource_pot;
 pub(crate) mod partial_bundling;
 pub(crate) mod render_resource_pots;
 pub(crate) mod resource_cache;
+pub(crate) mod unused_exports;
 
 impl Compiler {
   /// the generate stage
   pub(crate) fn generate(&self) -> Result<()> {
+    self.context.progress.set_phase(BuildPhase::Generate);
     self.context.plugin_driver.generate_start(&self.context)?;
 
     let hook_context = PluginHookContext {
@@ -25,6 +50,10 @@ impl Compiler {
       meta: HashMap::new(),
     };
 
+    // before tree shaking, which removes the unused exports
+    emit_unused_exports(&self.context);
+    record_module_exports(&self.context);
+
     self.optimize_module_graph()?;
 
     partial_bundling(&self.context, &hook_context)?;
@@ -33,8 +62,29 @@ impl Compiler {
 
     self.render_and_generate_resources(&hook_context)?;
 
+    emit_license_reports(&self.context);
+
+    emit_licenses(&self.context)?;
+
     finalize_resources(&self.context)?;
 
+    emit_bin_entries(&self.context);
+
+    emit_hosting_config(&self.context);
+
+    emit_entry_manifest(&self.context);
+
+    // the hmr runtime of development is never downgraded
+    if self.context.config.output.es5 && matches!(self.context.config.mode, Mode::Production) {
+      check_es5_resources(&self.context)?;
+    }
+
+    // after finalize_resources, so the sizes are the same as the written files
+    emit_bundle_stats(&self.context);
+
+    self.context.progress.set_phase(BuildPhase::Write);
+    emit_resources(&self.context, false)?;
+
     self.context.plugin_driver.generate_end(&self.context)
   }
 
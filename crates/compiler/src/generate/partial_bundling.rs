@@ -2,7 +2,7 @@ use std::collections::HashSet;
 use std::sync::Arc;
 
 use farmfe_core::{
-  config::partial_bundling::PartialBundlingEnforceResourceConfig,
+  config::minify::MinifyMode,
   context::CompilationContext,
   error::CompilationError,
   module::{module_graph::ModuleGraph, module_group::ModuleGroupGraph, Module, ModuleId},
@@ -13,6 +13,10 @@ use farmfe_core::{
   },
 };
 
+use farmfe_toolkit::common::MinifyBuilder;
+
+use super::license_groups::get_license_group_name;
+
 pub fn partial_bundling(
   context: &Arc<CompilationContext>,
   hook_context: &PluginHookContext,
@@ -54,11 +58,12 @@ pub fn generate_resource_pot_map(
   context: &Arc<CompilationContext>,
   hook_context: &PluginHookContext,
 ) -> farmfe_core::error::Result<ResourcePotMap> {
-  let (enforce_resource_pots, modules) = generate_enforce_resource_pots(context);
+  let (enforce_resource_pots, modules) = generate_enforce_resource_pots(context)?;
 
   let mut resources_pots = call_partial_bundling_hook(&modules, context, hook_context)?;
   // extends enforce resource pots
   resources_pots.extend(enforce_resource_pots);
+  split_passthrough_resource_pots(&mut resources_pots, context);
   fill_necessary_fields_for_resource_pot(resources_pots.iter_mut().collect(), context);
 
   let mut resource_pot_map = ResourcePotMap::new();
@@ -70,21 +75,94 @@ pub fn generate_resource_pot_map(
   Ok(resource_pot_map)
 }
 
+/// The name of the resource pot that the module is enforced to, matched by `enforceResources` first,
+/// then pinned by the `partition_resource_pots` hook, then the license group if `licenseGroups` is enabled
 pub fn get_enforce_resource_name_for_module(
-  module_id: &ModuleId,
-  enforce_resources: &Vec<PartialBundlingEnforceResourceConfig>,
-) -> Option<String> {
-  for enforce_resource_config in enforce_resources {
+  module: &Module,
+  context: &Arc<CompilationContext>,
+) -> farmfe_core::error::Result<Option<String>> {
+  let config = &context.config.partial_bundling;
+
+  for enforce_resource_config in &config.enforce_resources {
     if enforce_resource_config
       .test
       .iter()
-      .any(|test| test.is_match(&module_id.to_string()))
+      .any(|test| test.is_match(&module.id.to_string()))
     {
-      return Some(enforce_resource_config.name.clone());
+      return Ok(Some(enforce_resource_config.name.clone()));
+    }
+  }
+
+  if let Some(name) = context
+    .plugin_driver
+    .partition_resource_pots(module, context)?
+  {
+    return Ok(Some(name));
+  }
+
+  if config.license_groups {
+    return Ok(get_license_group_name(module));
+  }
+
+  Ok(None)
+}
+
+/// When resource pots are minified as a whole, move the modules excluded by `minify.include` and `minify.exclude`
+/// (e.g. already minified vendor files) to a passthrough resource pot named `<name>_passthrough`, which is not minified
+fn split_passthrough_resource_pots(
+  resource_pots: &mut Vec<ResourcePot>,
+  context: &Arc<CompilationContext>,
+) {
+  let minify_builder =
+    MinifyBuilder::create_builder(&context.config.minify, Some(MinifyMode::ResourcePot));
+
+  if !minify_builder.is_match_mode() {
+    return;
+  }
+
+  let mut passthrough_resource_pots = vec![];
+
+  for resource_pot in resource_pots.iter_mut() {
+    if !matches!(
+      resource_pot.resource_pot_type,
+      ResourcePotType::Js | ResourcePotType::Css
+    ) {
+      continue;
+    }
+
+    let excluded = resource_pot
+      .modules()
+      .into_iter()
+      .filter(|id| !minify_builder.is_enabled(&id.resolved_path(&context.config.root)))
+      .cloned()
+      .collect::<Vec<_>>();
+
+    if excluded.is_empty() {
+      continue;
+    }
+
+    // all the modules are excluded, the resource pot itself is passed through
+    if excluded.len() == resource_pot.modules().len() {
+      resource_pot.set_passthrough();
+      continue;
+    }
+
+    let mut passthrough = ResourcePot::new(
+      format!("{}_passthrough", resource_pot.name),
+      resource_pot.resource_pot_type.clone(),
+    );
+    passthrough.immutable = resource_pot.immutable;
+    passthrough.set_passthrough();
+
+    for module_id in excluded {
+      resource_pot.remove_module(&module_id);
+      passthrough.add_module(module_id);
     }
+
+    passthrough_resource_pots.push(passthrough);
   }
 
-  None
+  resource_pots.extend(passthrough_resource_pots);
 }
 
 pub fn call_partial_bundling_hook(
@@ -162,7 +240,7 @@ pub fn get_resource_pot_id_for_enforce_resources_by_removed_module(
 
 fn generate_enforce_resource_pots(
   context: &Arc<CompilationContext>,
-) -> (Vec<ResourcePot>, Vec<ModuleId>) {
+) -> farmfe_core::error::Result<(Vec<ResourcePot>, Vec<ModuleId>)> {
   let mut modules = HashSet::new();
   let mut enforce_resource_pot_map = ResourcePotMap::new();
   let module_graph = context.module_graph.read();
@@ -171,15 +249,13 @@ fn generate_enforce_resource_pots(
   // generate enforce resource pots first
   for g in module_group_graph.module_groups() {
     for module_id in g.modules() {
+      let module = module_graph.module(module_id).unwrap();
       // ignore external module
-      if module_graph.module(module_id).unwrap().external {
+      if module.external {
         continue;
       }
 
-      if let Some(name) = get_enforce_resource_name_for_module(
-        module_id,
-        &context.config.partial_bundling.enforce_resources,
-      ) {
+      if let Some(name) = get_enforce_resource_name_for_module(module, context)? {
         let (resource_pot_type, resource_pot_name, resource_pot_id) =
           get_resource_pot_id_for_enforce_resources(name.clone(), module_id, &module_graph);
 
@@ -203,7 +279,7 @@ fn generate_enforce_resource_pots(
   // sort modules to make it stable
   modules.sort();
 
-  (enforce_resource_pot_map.take_resource_pots(), modules)
+  Ok((enforce_resource_pot_map.take_resource_pots(), modules))
 }
 
 #[cfg(test)]
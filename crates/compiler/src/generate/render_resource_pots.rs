@@ -3,22 +3,27 @@ use std::sync::Arc;
 use farmfe_core::{
   context::CompilationContext,
   error::{CompilationError, Result},
-  parking_lot::Mutex,
   plugin::{
     PluginGenerateResourcesHookResult, PluginHookContext, PluginRenderResourcePotHookParam,
   },
   rayon::prelude::{IntoParallelIterator, ParallelIterator},
   resource::{
+    content_hash::create_hash_placeholder,
     resource_pot::{ResourcePot, ResourcePotInfo},
-    ResourceType,
+    Resource, ResourceType,
   },
 };
 use farmfe_toolkit::{
-  common::append_source_map_comment,
-  fs::{transform_output_entry_filename, transform_output_filename},
+  common::{append_source_map_comment, set_source_map_file},
+  fs::{transform_output_entry_filename_with_hash, transform_output_filename_with_hash},
 };
 
-use crate::generate::resource_cache::{set_resource_cache, try_get_resource_cache};
+use crate::generate::{
+  inject_resource_pot_code::{inject_resource_pot_code, resolve_resource_pot_injection},
+  license_comments::{collect_license_comments, create_license_comments_resource},
+  minify_resource_pot::minify_resource_pot,
+  resource_cache::{set_resource_cache, try_get_resource_cache},
+};
 
 pub fn render_resource_pots_and_generate_resources(
   resource_pots: Vec<&mut ResourcePot>,
@@ -28,15 +33,19 @@ pub fn render_resource_pots_and_generate_resources(
   #[cfg(feature = "profile")]
   farmfe_core::puffin::profile_function!();
 
-  let resources = Mutex::new(vec![]);
   let entries = context.module_graph.read().entries.clone();
+  context.progress.set_resource_pots(resource_pots.len());
 
-  let mut resource_pots_need_render = vec![];
-
-  for resource_pot in resource_pots {
-    let cached_resource_pot = try_get_resource_cache(resource_pot, context)?;
+  // restoring cached resource pots deserializes the cached resources, which is done in parallel too
+  let resource_pots_need_render = resource_pots
+    .into_par_iter()
+    .map(|resource_pot| {
+      let cached_resource_pot = match try_get_resource_cache(resource_pot, context) {
+        Ok(Some(cached_resource_pot)) => cached_resource_pot,
+        Ok(None) => return Ok(Some(resource_pot)),
+        Err(e) => return Err(e),
+      };
 
-    if let Some(cached_resource_pot) = cached_resource_pot {
       let rendered_resource_pot_info = ResourcePotInfo::new(resource_pot);
 
       let mut cached_resource = cached_resource_pot.resources;
@@ -46,18 +55,31 @@ pub fn render_resource_pots_and_generate_resources(
       resource_pot.add_resource(cached_resource.resource.name.clone());
 
       cached_resource.resource.info = Some(rendered_resource_pot_info);
+      let mut pot_resources = vec![];
 
-      resources.lock().push(cached_resource.resource);
+      if let Some(license) =
+        create_license_comments_resource(resource_pot, &cached_resource.resource.name)
+      {
+        resource_pot.add_resource(license.name.clone());
+        pot_resources.push(license);
+      }
+
+      pot_resources.push(cached_resource.resource);
 
       if let Some(map) = cached_resource.source_map {
         resource_pot.add_resource(map.name.clone());
 
-        resources.lock().push(map);
+        pot_resources.push(map);
       }
-    } else {
-      resource_pots_need_render.push(resource_pot);
-    }
-  }
+
+      insert_resources(pot_resources, context);
+      context.progress.resource_pot_rendered();
+      Ok(None)
+    })
+    .collect::<Result<Vec<_>>>()?
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
 
   context
     .plugin_driver
@@ -84,30 +106,36 @@ pub fn render_resource_pots_and_generate_resources(
         &mut resource_pot_info,
       )?;
 
+      let mut pot_resources = vec![];
       let r = &mut res.resource;
       let resource_pot_info: ResourcePotInfo = resource_pot_info.unwrap();
 
       // ignore runtime resource
       if !matches!(r.resource_type, ResourceType::Runtime) {
         let content_with_extra_content_hash = &[
+          resource_pot.id.as_bytes(),
           &r.bytes,
           augment_resource_hash.unwrap_or_default().as_bytes(),
         ]
         .concat();
+        // the final hash depends on the resources referenced by this resource, it's replaced after finalize_resources
+        let content_hash =
+          || create_hash_placeholder(&context.config.hash, content_with_extra_content_hash);
+
         if let Some(name) = resource_pot.entry_module.as_ref() {
           let entry_name = entries.get(name).unwrap();
-          r.name = transform_output_entry_filename(
+          r.name = transform_output_entry_filename_with_hash(
             context.config.output.entry_filename.clone(),
             resource_pot.id.to_string().as_str(),
             entry_name,
-            content_with_extra_content_hash,
+            content_hash,
             &r.resource_type.to_ext(),
           );
         } else {
-          r.name = transform_output_filename(
+          r.name = transform_output_filename_with_hash(
             context.config.output.filename.clone(),
             &r.name,
-            content_with_extra_content_hash,
+            content_hash,
             &r.resource_type.to_ext(),
           );
         }
@@ -131,6 +159,11 @@ pub fn render_resource_pots_and_generate_resources(
           res.resource.name,
           source_map.resource_type.to_ext()
         );
+
+        if context.config.sourcemap_base.is_some() {
+          set_source_map_file(&mut source_map, &res.resource.name);
+        }
+
         append_source_map_comment(&mut res.resource, &source_map, &context.config.sourcemap);
 
         if context.config.persistent_cache.enabled() {
@@ -139,7 +172,7 @@ pub fn render_resource_pots_and_generate_resources(
 
         resource_pot.add_resource(source_map.name.clone());
 
-        resources.lock().push(source_map);
+        pot_resources.push(source_map);
       }
 
       if context.config.persistent_cache.enabled() {
@@ -147,25 +180,32 @@ pub fn render_resource_pots_and_generate_resources(
         set_resource_cache(resource_pot, &cached_result, context);
       }
 
+      if let Some(license) = create_license_comments_resource(resource_pot, &res.resource.name) {
+        resource_pot.add_resource(license.name.clone());
+        pot_resources.push(license);
+      }
+
       resource_pot.add_resource(res.resource.name.clone());
 
       res.resource.info = Some(resource_pot_info);
 
-      resources.lock().push(res.resource);
+      pot_resources.push(res.resource);
+      insert_resources(pot_resources, context);
+      context.progress.resource_pot_rendered();
       Ok::<(), CompilationError>(())
     })?;
 
-  let mut resources_map: farmfe_core::parking_lot::lock_api::MutexGuard<
-    '_,
-    farmfe_core::parking_lot::RawMutex,
-    std::collections::HashMap<String, farmfe_core::resource::Resource>,
-  > = context.resources_map.lock();
+  Ok(())
+}
 
-  for resource in resources.lock().drain(..) {
-    resources_map.insert(resource.name.clone(), resource);
+/// Insert the resources of a resource pot as soon as it's rendered, resources_map is sharded so the resource pots rendered
+/// in parallel don't wait for each other
+fn insert_resources(resources: Vec<Resource>, context: &Arc<CompilationContext>) {
+  for resource in resources {
+    context
+      .resources_map
+      .insert(resource.name.clone(), resource);
   }
-
-  Ok(())
 }
 
 pub fn render_resource_pot_generate_resources(
@@ -174,8 +214,55 @@ pub fn render_resource_pot_generate_resources(
   hook_context: &PluginHookContext,
   skip_render: bool,
   chunk_resource_info: &mut Option<ResourcePotInfo>,
+) -> Result<(PluginGenerateResourcesHookResult, Option<String>)> {
+  // a panicking plugin fails the resource pot instead of aborting the process
+  let resource_pot_id = resource_pot.id.clone();
+  let modules = resource_pot
+    .modules()
+    .into_iter()
+    .cloned()
+    .collect::<Vec<_>>();
+
+  let result = context.catch_panic(
+    || format!("rendering resource pot `{resource_pot_id}`"),
+    || modules,
+    || {
+      render_and_generate_resources(
+        resource_pot,
+        context,
+        hook_context,
+        skip_render,
+        chunk_resource_info,
+      )
+    },
+  );
+
+  // the panic may leave the meta half rendered, reset it so it's never reused, e.g. by the next hmr update
+  if matches!(result, Err(CompilationError::PanicError { .. })) {
+    let passthrough = resource_pot.is_passthrough();
+    resource_pot.meta = Default::default();
+
+    if passthrough {
+      resource_pot.set_passthrough();
+    }
+  }
+
+  result
+}
+
+fn render_and_generate_resources(
+  resource_pot: &mut ResourcePot,
+  context: &Arc<CompilationContext>,
+  hook_context: &PluginHookContext,
+  skip_render: bool,
+  chunk_resource_info: &mut Option<ResourcePotInfo>,
 ) -> Result<(PluginGenerateResourcesHookResult, Option<String>)> {
   let mut augment_resource_hash = None;
+  let mut injection = None;
+  let renderer = context
+    .plugin_driver
+    .resource_pot_renderer(&resource_pot.resource_pot_type)
+    .cloned();
 
   if !skip_render {
     #[cfg(feature = "profile")]
@@ -185,15 +272,27 @@ pub fn render_resource_pot_generate_resources(
     ));
     #[cfg(feature = "profile")]
     farmfe_core::puffin::profile_scope!(id);
-    let meta = context
-      .plugin_driver
-      .render_resource_pot_modules(resource_pot, context, hook_context)?
-      .ok_or(CompilationError::PluginHookResultCheckError {
-        hook_name: format!("render_resource_pot_modules({:?})", resource_pot.id),
-      })?;
+    let meta = if let Some(renderer) = &renderer {
+      renderer.render_resource_pot_modules(resource_pot, context, hook_context)?
+    } else {
+      context
+        .plugin_driver
+        .render_resource_pot_modules(resource_pot, context, hook_context)?
+        .ok_or(CompilationError::PluginHookResultCheckError {
+          hook_name: format!("render_resource_pot_modules({:?})", resource_pot.id),
+        })?
+    };
 
+    // the passthrough flag is set when partial bundling and must survive the rendered meta
+    let passthrough = resource_pot.is_passthrough();
     resource_pot.meta = meta;
 
+    if passthrough {
+      resource_pot.set_passthrough();
+    }
+
+    collect_license_comments(resource_pot, context);
+
     let mut param = PluginRenderResourcePotHookParam {
       content: resource_pot.meta.rendered_content.clone(),
       source_map_chain: resource_pot.meta.rendered_map_chain.clone(),
@@ -211,6 +310,11 @@ pub fn render_resource_pot_generate_resources(
       .plugin_driver
       .augment_resource_hash(&param.resource_pot_info, context)?;
 
+    let resource_pot_injection = resolve_resource_pot_injection(&param.resource_pot_info, context)?;
+    // the intro is injected before optimizing, so it's minified together with the resource pot
+    inject_resource_pot_code(resource_pot, &resource_pot_injection.intro, "")?;
+    injection = Some(resource_pot_injection);
+
     *chunk_resource_info = Some(param.resource_pot_info);
   }
 
@@ -226,6 +330,12 @@ pub fn render_resource_pot_generate_resources(
     context
       .plugin_driver
       .optimize_resource_pot(resource_pot, context)?;
+    minify_resource_pot(resource_pot, context)?;
+  }
+
+  // inject banner and footer before the source map is generated by generate_resources, so the mappings stay correct
+  if let Some(injection) = injection {
+    inject_resource_pot_code(resource_pot, &injection.banner, &injection.footer)?;
   }
 
   {
@@ -237,7 +347,9 @@ pub fn render_resource_pot_generate_resources(
     #[cfg(feature = "profile")]
     farmfe_core::puffin::profile_scope!(id);
 
-    Ok((
+    let resources = if let Some(renderer) = &renderer {
+      renderer.generate_resources(resource_pot, context, hook_context)?
+    } else {
       context
         .plugin_driver
         .generate_resources(resource_pot, context, hook_context)?
@@ -245,8 +357,9 @@ pub fn render_resource_pot_generate_resources(
           name: resource_pot.id.to_string(),
           ty: resource_pot.resource_pot_type.clone(),
           source: None,
-        })?,
-      augment_resource_hash,
-    ))
+        })?
+    };
+
+    Ok((resources, augment_resource_hash))
   }
 }
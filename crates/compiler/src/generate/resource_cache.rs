@@ -33,7 +33,7 @@ pub fn get_resource_cache_key(
     }
   }
 
-  farmfe_toolkit::hash::sha256(code.as_bytes(), 32)
+  context.config.hash.hash(code.as_bytes(), 32)
 }
 
 pub fn try_get_resource_cache(
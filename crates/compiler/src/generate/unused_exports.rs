@@ -0,0 +1,298 @@
+//! Report the exports of each es module that are never imported in the module graph, so library maintainers can prune dead api surface.
+//! Exports that are not imported by other modules but exposed by an entry are reported separately as the public api.
+//! It runs before tree shaking, which removes the unused exports from the ast.
+use std::{
+  collections::{HashMap, HashSet, VecDeque},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  context::CompilationContext,
+  module::{module_graph::ModuleGraph, ModuleId, ModuleSystem},
+  plugin::ResolveKind,
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde_json,
+  stats::UnusedExportsStats,
+  swc_ecma_ast::{
+    Decl, ExportSpecifier, ImportSpecifier, ModuleDecl, ModuleExportName, ModuleItem,
+  },
+};
+use farmfe_toolkit::{
+  script::defined_idents_collector::DefinedIdentsCollector, swc_ecma_visit::VisitWith,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum UsedExport {
+  Name(String),
+  /// the whole module is used, e.g. `import * as a from './a'` or `import('./a')`
+  All,
+}
+
+#[derive(Default)]
+struct ModuleExports {
+  /// names exported by the module, including the re-exported names
+  names: HashSet<String>,
+  /// exported name -> (source module, imported name), the imported name is [None] for `export * as ns from './a'`
+  reexports: HashMap<String, (ModuleId, Option<String>)>,
+  /// modules of `export * from './a'`
+  stars: Vec<ModuleId>,
+}
+
+pub fn emit_unused_exports(context: &Arc<CompilationContext>) {
+  let Some(config) = context.config.unused_exports.as_ref() else {
+    return;
+  };
+
+  let module_graph = context.module_graph.read();
+  let (exports_map, imports) = analyze_module_graph(&module_graph);
+
+  let imported = mark_used_exports(&exports_map, imports.clone());
+  let exposed = mark_used_exports(
+    &exports_map,
+    imports
+      .into_iter()
+      .chain(
+        module_graph
+          .entries
+          .keys()
+          .map(|id| (id.clone(), UsedExport::All)),
+      )
+      .collect(),
+  );
+  drop(module_graph);
+
+  let mut unused_exports = exports_map
+    .iter()
+    .filter_map(|(id, exports)| {
+      let empty = HashSet::new();
+      let imported = imported.get(id).unwrap_or(&empty);
+      let exposed = exposed.get(id).unwrap_or(&empty);
+
+      let mut unused = exports
+        .names
+        .iter()
+        .filter(|name| !exposed.contains(*name))
+        .cloned()
+        .collect::<Vec<_>>();
+      let mut public = exports
+        .names
+        .iter()
+        .filter(|name| exposed.contains(*name) && !imported.contains(*name))
+        .cloned()
+        .collect::<Vec<_>>();
+
+      if unused.is_empty() && public.is_empty() {
+        return None;
+      }
+
+      unused.sort();
+      public.sort();
+
+      Some(UnusedExportsStats {
+        id: id.clone(),
+        unused,
+        public,
+      })
+    })
+    .collect::<Vec<_>>();
+  unused_exports.sort_by(|a, b| a.id.cmp(&b.id));
+
+  context.resources_map.insert(
+    config.filename.clone(),
+    Resource {
+      name: config.filename.clone(),
+      bytes: serde_json::to_vec_pretty(&unused_exports).unwrap(),
+      resource_type: ResourceType::Custom("json".to_string()),
+      origin: ResourceOrigin::ResourcePot(config.filename.clone()),
+      ..Default::default()
+    },
+  );
+  context.record_manager.set_unused_exports(unused_exports);
+}
+
+/// Collect the exports of the es modules and the exports requested by the importers
+fn analyze_module_graph(
+  module_graph: &ModuleGraph,
+) -> (
+  HashMap<ModuleId, ModuleExports>,
+  Vec<(ModuleId, UsedExport)>,
+) {
+  let mut exports_map = HashMap::new();
+  let mut imports = vec![];
+
+  for module in module_graph.modules() {
+    // dynamic imports and requires can access any export of the module
+    for (dep, edge) in module_graph.dependencies(&module.id) {
+      if edge
+        .iter()
+        .any(|item| matches!(item.kind, ResolveKind::DynamicImport | ResolveKind::Require))
+      {
+        imports.push((dep, UsedExport::All));
+      }
+    }
+
+    if module.external || !module.module_type.is_script() {
+      continue;
+    }
+
+    let meta = module.meta.as_script();
+    let resolve = |source: &str| module_graph.get_dep_by_source_optional(&module.id, source, None);
+    let mut exports = ModuleExports::default();
+
+    for item in &meta.ast.body {
+      let ModuleItem::ModuleDecl(decl) = item else {
+        continue;
+      };
+
+      match decl {
+        ModuleDecl::Import(import) if !import.type_only => {
+          let Some(dep) = resolve(&import.src.value) else {
+            continue;
+          };
+
+          for specifier in &import.specifiers {
+            let used = match specifier {
+              ImportSpecifier::Named(named) => UsedExport::Name(
+                named
+                  .imported
+                  .as_ref()
+                  .map(module_export_name_to_string)
+                  .unwrap_or_else(|| named.local.sym.to_string()),
+              ),
+              ImportSpecifier::Default(_) => UsedExport::Name("default".to_string()),
+              ImportSpecifier::Namespace(_) => UsedExport::All,
+            };
+            imports.push((dep.clone(), used));
+          }
+        }
+        ModuleDecl::ExportDecl(export) => match &export.decl {
+          Decl::Class(class) => {
+            exports.names.insert(class.ident.sym.to_string());
+          }
+          Decl::Fn(func) => {
+            exports.names.insert(func.ident.sym.to_string());
+          }
+          Decl::Var(var) => {
+            for decl in &var.decls {
+              let mut collector = DefinedIdentsCollector::new();
+              decl.name.visit_with(&mut collector);
+              exports.names.extend(
+                collector
+                  .defined_idents
+                  .into_iter()
+                  .map(|id| id.0.to_string()),
+              );
+            }
+          }
+          _ => {}
+        },
+        ModuleDecl::ExportNamed(export) if !export.type_only => {
+          let dep = export.src.as_ref().and_then(|src| resolve(&src.value));
+
+          for specifier in &export.specifiers {
+            let (exported, imported) = match specifier {
+              ExportSpecifier::Named(named) => (
+                named.exported.as_ref().unwrap_or(&named.orig).clone(),
+                Some(module_export_name_to_string(&named.orig)),
+              ),
+              ExportSpecifier::Namespace(namespace) => (namespace.name.clone(), None),
+              ExportSpecifier::Default(default) => (
+                ModuleExportName::Ident(default.exported.clone()),
+                Some("default".to_string()),
+              ),
+            };
+            let exported = module_export_name_to_string(&exported);
+
+            if let Some(dep) = &dep {
+              exports
+                .reexports
+                .insert(exported.clone(), (dep.clone(), imported));
+            }
+
+            exports.names.insert(exported);
+          }
+        }
+        ModuleDecl::ExportDefaultDecl(_) | ModuleDecl::ExportDefaultExpr(_) => {
+          exports.names.insert("default".to_string());
+        }
+        ModuleDecl::ExportAll(export) if !export.type_only => {
+          if let Some(dep) = resolve(&export.src.value) {
+            exports.stars.push(dep);
+          }
+        }
+        _ => {}
+      }
+    }
+
+    // the exports of commonjs modules can not be analyzed statically
+    if matches!(meta.module_system, ModuleSystem::EsModule) {
+      exports_map.insert(module.id.clone(), exports);
+    }
+  }
+
+  (exports_map, imports)
+}
+
+/// module id -> the exports that are used, following the re-exports to the modules that declare them
+fn mark_used_exports(
+  exports_map: &HashMap<ModuleId, ModuleExports>,
+  requests: Vec<(ModuleId, UsedExport)>,
+) -> HashMap<ModuleId, HashSet<String>> {
+  let mut used: HashMap<ModuleId, HashSet<String>> = HashMap::new();
+  let mut visited = HashSet::new();
+  let mut queue = VecDeque::from(requests);
+
+  while let Some((id, request)) = queue.pop_front() {
+    let Some(exports) = exports_map.get(&id) else {
+      continue;
+    };
+
+    if !visited.insert((id.clone(), request.clone())) {
+      continue;
+    }
+
+    match request {
+      UsedExport::Name(name) => {
+        if let Some((dep, imported)) = exports.reexports.get(&name) {
+          queue.push_back((
+            dep.clone(),
+            imported.clone().map_or(UsedExport::All, UsedExport::Name),
+          ));
+        } else if !exports.names.contains(&name) && name != "default" {
+          // `export *` does not re-export the default export
+          for star in &exports.stars {
+            queue.push_back((star.clone(), UsedExport::Name(name.clone())));
+          }
+        }
+
+        used.entry(id).or_default().insert(name);
+      }
+      UsedExport::All => {
+        for (dep, imported) in exports.reexports.values() {
+          queue.push_back((
+            dep.clone(),
+            imported.clone().map_or(UsedExport::All, UsedExport::Name),
+          ));
+        }
+
+        for star in &exports.stars {
+          queue.push_back((star.clone(), UsedExport::All));
+        }
+
+        used
+          .entry(id)
+          .or_default()
+          .extend(exports.names.iter().cloned());
+      }
+    }
+  }
+
+  used
+}
+
+fn module_export_name_to_string(name: &ModuleExportName) -> String {
+  match name {
+    ModuleExportName::Ident(ident) => ident.sym.to_string(),
+    ModuleExportName::Str(str) => str.value.to_string(),
+  }
+}
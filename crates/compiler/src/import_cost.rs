@@ -0,0 +1,139 @@
+//! Estimated cost of an import for editor extensions, e.g. the size shown next to `import { debounce } from 'lodash-es'`.
+//! The cost is measured on the transformed code of the module graph, so it's consistent with what the compiler bundles.
+use std::collections::HashSet;
+
+use farmfe_core::{
+  error::{CompilationError, Result},
+  module::{module_graph::ModuleGraph, ModuleId},
+  plugin::{PluginResolveHookParam, ResolveKind},
+  serde::{Deserialize, Serialize},
+};
+
+use crate::{generate::bundle_stats::gzip_len, Compiler};
+
+/// The modules bundled because of an import and their size, see [Compiler::import_cost]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+pub struct ImportCost {
+  /// the imported module
+  pub module_id: ModuleId,
+  /// the imported module and its static dependencies that are not imported by the importer otherwise, empty if the module is external
+  pub modules: Vec<ModuleId>,
+  /// size of the transformed code of [ImportCost::modules] before minification
+  pub size: usize,
+  pub gzip_size: usize,
+  /// static dependencies of the imported module that are bundled by the other imports of the importer anyway
+  pub shared_modules: Vec<ModuleId>,
+}
+
+impl Compiler {
+  /// The cost of importing `source` from `importer`, `importer` is a module id or an absolute path.
+  /// The imported module must be built, [Compiler::compile] or [Compiler::update] should be called before this method
+  pub fn import_cost(&self, source: &str, importer: &str) -> Result<ImportCost> {
+    let importer_id = self.context.str_to_module_id(importer);
+    let module_graph = self.context.module_graph.read();
+
+    if !module_graph.has_module(&importer_id) {
+      return Err(CompilationError::GenericError(format!(
+        "Module {importer} is not found in the module graph"
+      )));
+    }
+
+    let imported = module_graph
+      .dependencies(&importer_id)
+      .into_iter()
+      .find(|(_, edge)| edge.iter().any(|item| item.source == source))
+      .map(|(id, _)| id);
+    let module_id = match imported {
+      Some(module_id) => module_id,
+      // the import is not saved yet, resolve it like the importer imports it
+      None => {
+        Self::resolve_module_id(
+          &PluginResolveHookParam {
+            source: source.to_string(),
+            importer: Some(importer_id.clone()),
+            kind: ResolveKind::Import,
+          },
+          &self.context,
+        )?
+        .module_id
+      }
+    };
+
+    let Some(module) = module_graph.module(&module_id) else {
+      return Err(CompilationError::GenericError(format!(
+        "{source} imported by {importer} is not built yet"
+      )));
+    };
+
+    if module.external {
+      return Ok(ImportCost {
+        module_id,
+        modules: vec![],
+        size: 0,
+        gzip_size: 0,
+        shared_modules: vec![],
+      });
+    }
+
+    let other_imports = module_graph
+      .dependencies(&importer_id)
+      .into_iter()
+      .filter(|(id, edge)| *id != module_id && !edge.is_dynamic())
+      .map(|(id, _)| id)
+      .collect::<Vec<_>>();
+    // the importer is bundled anyway, e.g. when the imported module imports it back
+    let mut bundled = static_dependencies(&module_graph, other_imports);
+    bundled.insert(importer_id.clone());
+
+    let (mut shared_modules, mut modules): (Vec<_>, Vec<_>) =
+      static_dependencies(&module_graph, vec![module_id.clone()])
+        .into_iter()
+        .filter(|id| !module_graph.module(id).unwrap().external)
+        .partition(|id| bundled.contains(id));
+    modules.sort();
+    shared_modules.sort();
+
+    let mut code = String::new();
+
+    for id in &modules {
+      code.push_str(&module_graph.module(id).unwrap().content);
+      code.push('\n');
+    }
+
+    Ok(ImportCost {
+      module_id,
+      size: modules
+        .iter()
+        .map(|id| module_graph.module(id).unwrap().content.len())
+        .sum(),
+      gzip_size: if modules.is_empty() {
+        0
+      } else {
+        gzip_len(code.as_bytes())
+      },
+      modules,
+      shared_modules,
+    })
+  }
+}
+
+/// The modules and their static dependencies, dynamic imports are loaded as separate resources and not counted
+fn static_dependencies(module_graph: &ModuleGraph, modules: Vec<ModuleId>) -> HashSet<ModuleId> {
+  let mut visited = HashSet::new();
+  let mut stack = modules;
+
+  while let Some(id) = stack.pop() {
+    if !visited.insert(id.clone()) {
+      continue;
+    }
+
+    for (dependency, edge) in module_graph.dependencies(&id) {
+      if !edge.is_dynamic() && !visited.contains(&dependency) {
+        stack.push(dependency);
+      }
+    }
+  }
+
+  visited
+}
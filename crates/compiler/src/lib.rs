@@ -5,23 +5,34 @@
 #![allow(clippy::assigning_clones)]
 #![feature(box_patterns)]
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Instant};
 
 use farmfe_core::{
   config::{Config, Mode},
-  context::CompilationContext,
+  context::{metrics::MetricsGauges, progress::BuildPhase, CompilationContext},
   error::Result,
   farm_profile_function,
+  module::{
+    module_graph::{ImportChainStep, ModuleGraph, ModuleGraphFilter},
+    module_statement::{ModuleExport, ModuleStatement},
+    ModuleId,
+  },
   plugin::Plugin,
   rayon::{ThreadPool, ThreadPoolBuilder},
 };
 
+use build::checkpoint::{remove_checkpoint, write_checkpoint};
+
 pub use farmfe_plugin_css::FARM_CSS_MODULES_SUFFIX;
 pub use farmfe_plugin_lazy_compilation::DYNAMIC_VIRTUAL_SUFFIX;
 pub use farmfe_plugin_runtime::RUNTIME_SUFFIX;
+pub use farmfe_plugin_tree_shake::module_statements::{module_exports, module_statements};
 
 pub mod build;
 pub mod generate;
+pub mod import_cost;
+pub mod multi_project;
+pub mod testing;
 pub mod trace_module_graph;
 pub mod update;
 
@@ -32,7 +43,16 @@ pub struct Compiler {
 
 impl Compiler {
   /// The params are [farmfe_core::config::Config] and dynamic load rust plugins and js plugins [farmfe_core::plugin::Plugin]
-  pub fn new(config: Config, mut plugin_adapters: Vec<Arc<dyn Plugin>>) -> Result<Self> {
+  pub fn new(config: Config, plugin_adapters: Vec<Arc<dyn Plugin>>) -> Result<Self> {
+    Self::new_with_thread_pool(config, plugin_adapters, create_thread_pool())
+  }
+
+  /// Same as [Compiler::new], but the compiler runs on the given thread pool. Useful when multiple compilers live in one process.
+  pub fn new_with_thread_pool(
+    config: Config,
+    mut plugin_adapters: Vec<Arc<dyn Plugin>>,
+    thread_pool: Arc<ThreadPool>,
+  ) -> Result<Self> {
     let mut plugins = vec![
       Arc::new(farmfe_plugin_runtime::FarmPluginRuntime::new(&config)) as _,
       Arc::new(farmfe_plugin_bundle::FarmPluginBundle::new()) as _,
@@ -55,6 +75,12 @@ impl Compiler {
       plugins.push(Arc::new(farmfe_plugin_progress::FarmPluginProgress::new(&config)) as _);
     }
 
+    // registered before lazy compilation, so the remote modules and the exposed modules are resolved
+    // by the federation plugin instead of being compiled lazily
+    if config.federation.is_some() {
+      plugins.push(Arc::new(farmfe_plugin_federation::FarmPluginFederation::new(&config)) as _);
+    }
+
     if config.lazy_compilation {
       plugins.push(
         Arc::new(farmfe_plugin_lazy_compilation::FarmPluginLazyCompilation::new(&config)) as _,
@@ -70,6 +96,42 @@ impl Compiler {
       plugins.push(Arc::new(farmfe_plugin_html::FarmPluginMinifyHtml::new(&config)) as _);
     }
 
+    if config.routes.is_some() {
+      plugins.push(Arc::new(farmfe_plugin_routes::FarmPluginRoutes::new(&config)) as _);
+    }
+
+    if config.module_boundaries.is_some() {
+      plugins.push(Arc::new(
+        farmfe_plugin_module_boundaries::FarmPluginModuleBoundaries::new(&config),
+      ) as _);
+    }
+
+    if config.circular_dependency.is_some() {
+      plugins.push(Arc::new(
+        farmfe_plugin_circular_dependency::FarmPluginCircularDependency::new(&config),
+      ) as _);
+    }
+
+    if config.dependency_policy.is_some() {
+      plugins.push(Arc::new(
+        farmfe_plugin_dependency_policy::FarmPluginDependencyPolicy::new(&config),
+      ) as _);
+    }
+
+    if !config.patches.is_empty() {
+      plugins.push(Arc::new(farmfe_plugin_patch::FarmPluginPatch::new(&config)) as _);
+    }
+
+    if config.vendor_reference.is_some() {
+      plugins.push(
+        Arc::new(farmfe_plugin_vendor_reference::FarmPluginVendorReference::new(&config)) as _,
+      );
+    }
+
+    if !config.entry_define.is_empty() {
+      plugins.push(Arc::new(farmfe_plugin_define::FarmPluginEntryDefine::new(&config)) as _);
+    }
+
     if config.preset_env.enabled() {
       plugins.push(Arc::new(farmfe_plugin_polyfill::FarmPluginPolyfill::new(&config)) as _);
     }
@@ -79,32 +141,34 @@ impl Compiler {
 
     plugins.append(&mut plugin_adapters);
 
-    Self::new_without_internal_plugins(config, plugins)
+    Self::new_without_internal_plugins_with_thread_pool(config, plugins, thread_pool)
   }
 
   pub fn new_without_internal_plugins(
+    config: Config,
+    plugins: Vec<Arc<dyn Plugin>>,
+  ) -> Result<Self> {
+    Self::new_without_internal_plugins_with_thread_pool(config, plugins, create_thread_pool())
+  }
+
+  pub fn new_without_internal_plugins_with_thread_pool(
     config: Config,
     mut plugins: Vec<Arc<dyn Plugin>>,
+    thread_pool: Arc<ThreadPool>,
   ) -> Result<Self> {
     // sort plugins by priority to make larger priority plugin run first
     plugins.sort_by_key(|b| std::cmp::Reverse(b.priority()));
 
-    let mut context = CompilationContext::new(config, plugins)?;
-    context.plugin_driver.config(&mut context.config)?;
+    let context = CompilationContext::new(config, plugins)?;
 
     Ok(Self {
       context: Arc::new(context),
-      thread_pool: Arc::new(
-        ThreadPoolBuilder::new()
-          .num_threads(num_cpus::get())
-          .build()
-          .unwrap(),
-      ),
+      thread_pool,
     })
   }
 
   pub fn trace_dependencies(&self) -> Result<Vec<String>> {
-    self.build()?;
+    self.build(&self.context.config.input)?;
 
     let module_graph = self.context.module_graph.read();
     let mut dependencies = vec![];
@@ -126,7 +190,62 @@ impl Compiler {
 
   /// Compile the project using the configuration
   pub fn compile(&self) -> Result<()> {
+    self.compile_input(&self.context.config.input, false)
+  }
+
+  /// Compile only the subgraph reachable from `input` (entry name -> source) instead of `config.input`,
+  /// e.g. to preview a single component of a huge project. The modules built by the previous compilation are reused
+  /// and the modules that are not reachable are removed from the module graph, so only the resource pots of the subgraph are generated.
+  /// The module graph is rebuilt from scratch when tree shaking is enabled, because tree shaking modifies the asts of the modules.
+  /// Unlike [Compiler::compile_partial], which adds entries to an existing compilation, the rest of the project is dropped
+  pub fn compile_subset(&self, input: &HashMap<String, String>) -> Result<()> {
+    {
+      let mut module_graph = self.context.module_graph.write();
+
+      if self.context.config.tree_shaking.enabled() {
+        *module_graph = ModuleGraph::new();
+      } else {
+        module_graph.entries.clear();
+
+        // the module groups and resource pots of the previous compilation are generated again
+        for module in module_graph.modules_mut() {
+          module.module_groups.clear();
+          module.resource_pot = None;
+        }
+      }
+    }
+    self.context.resources_map.clear();
+
+    // the runtime entry injected by the runtime plugin is always compiled
+    let mut input = input.clone();
+    for (name, source) in &self.context.config.input {
+      if source.ends_with(RUNTIME_SUFFIX) {
+        input.insert(name.clone(), source.clone());
+      }
+    }
+
+    self.compile_input(&input, true)
+  }
+
+  fn compile_input(&self, input: &HashMap<String, String>, prune_unreachable: bool) -> Result<()> {
+    let start = Instant::now();
+    let result = self.run_compilation(input, prune_unreachable);
+    self.context.metrics.build_finished(
+      start.elapsed(),
+      result.is_ok(),
+      &self.context.progress.progress(),
+    );
+
+    result
+  }
+
+  fn run_compilation(
+    &self,
+    input: &HashMap<String, String>,
+    prune_unreachable: bool,
+  ) -> Result<()> {
     self.context.record_manager.set_start_time();
+    self.context.progress.reset();
     if self.context.config.persistent_cache.enabled() {
       self
         .context
@@ -138,7 +257,14 @@ impl Compiler {
     {
       #[cfg(feature = "profile")]
       farmfe_core::puffin::profile_scope!("Build Stage");
-      self.build()?;
+      if !self.resume_build(input)? {
+        self.build(input)?;
+        write_checkpoint(input, &self.context);
+      }
+
+      if prune_unreachable {
+        self.remove_unreachable_modules();
+      }
     }
     self.context.record_manager.set_build_end_time();
     {
@@ -146,11 +272,14 @@ impl Compiler {
       farmfe_core::puffin::profile_scope!("Generate Stage");
       self.generate()?;
     }
+    self.context.module_graph_snapshots.invalidate();
 
     self
       .context
       .plugin_driver
       .finish(&self.context.record_manager, &self.context)?;
+    self.context.progress.set_phase(BuildPhase::Finished);
+    remove_checkpoint(input, &self.context);
 
     if self.context.config.persistent_cache.enabled() {
       self
@@ -158,7 +287,10 @@ impl Compiler {
         .plugin_driver
         .write_plugin_cache(&self.context)
         .unwrap_or_else(|err| {
-          eprintln!("write plugin cache error: {err:?}");
+          self
+            .context
+            .logger
+            .error(module_path!(), format!("write plugin cache error: {err:?}"));
         });
 
       if matches!(self.context.config.mode, Mode::Development) {
@@ -179,6 +311,72 @@ impl Compiler {
   pub fn context(&self) -> &Arc<CompilationContext> {
     &self.context
   }
+
+  /// Counters and histograms of the builds and hmr updates and the current size of the compilation,
+  /// in the Prometheus text format. Served by the dev server and the daemon for monitoring
+  pub fn metrics(&self) -> String {
+    let (modules, edges, module_bytes) = {
+      let module_graph = self.context.module_graph.read();
+      let modules = module_graph.modules();
+      let module_bytes = modules.iter().map(|m| m.content.len()).sum::<usize>();
+
+      (modules.len(), module_graph.edge_count(), module_bytes)
+    };
+    let (resources, resource_bytes) = {
+      let resources_map = &self.context.resources_map;
+
+      (
+        resources_map.len(),
+        resources_map.iter().map(|r| r.bytes.len()).sum::<usize>(),
+      )
+    };
+
+    self.context.metrics.render(&MetricsGauges {
+      modules,
+      edges,
+      resources,
+      memory_bytes: module_bytes + resource_bytes,
+    })
+  }
+
+  /// Explain why the module is included: all the shortest import chains from the entries to the module.
+  /// [Compiler::compile] should be called before this method.
+  pub fn import_chains(&self, module_id: &ModuleId) -> Vec<Vec<ImportChainStep>> {
+    self
+      .context
+      .module_graph_snapshot()
+      .import_chains(module_id)
+  }
+
+  /// Graphviz DOT diagram of the modules selected by the filter, [Compiler::compile] should be called before this method
+  pub fn module_graph_to_dot(&self, filter: &ModuleGraphFilter) -> Result<String> {
+    self.context.module_graph_snapshot().to_dot(filter)
+  }
+
+  /// Mermaid flowchart of the modules selected by the filter, [Compiler::compile] should be called before this method
+  pub fn module_graph_to_mermaid(&self, filter: &ModuleGraphFilter) -> Result<String> {
+    self.context.module_graph_snapshot().to_mermaid(filter)
+  }
+
+  /// Imports, exports, dependencies and side effects of the top level statements of the script module,
+  /// [None] if the module is not a script module
+  pub fn module_statements(&self, module_id: &ModuleId) -> Option<Vec<ModuleStatement>> {
+    module_statements(&self.context, module_id)
+  }
+
+  /// Exported names and re-exports of the script module, [None] if the module is not a script module
+  pub fn module_exports(&self, module_id: &ModuleId) -> Option<Vec<ModuleExport>> {
+    module_exports(&self.context, module_id)
+  }
+}
+
+pub fn create_thread_pool() -> Arc<ThreadPool> {
+  Arc::new(
+    ThreadPoolBuilder::new()
+      .num_threads(num_cpus::get())
+      .build()
+      .unwrap(),
+  )
 }
 
 fn write_cache(context: Arc<CompilationContext>) {
@@ -0,0 +1,190 @@
+//! Host multiple projects in one process. Every project owns an isolated [farmfe_core::context::CompilationContext]
+//! and persistent cache namespace, while all of them share the same thread pool.
+//! The dev handler of `farmfe_api` and the daemon use [MultiProjectCompiler::resolve_mount] to dispatch requests like `/app1/index.js` to the project mounted on `/app1/`.
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_core::{
+  config::{persistent_cache::PersistentCacheConfig, Config},
+  error::{CompilationError, Result},
+  plugin::Plugin,
+  rayon::ThreadPool,
+};
+
+use crate::{create_thread_pool, Compiler};
+
+pub struct ProjectOptions {
+  /// unique name of the project, used as the suffix of the persistent cache namespace
+  pub name: String,
+  /// url prefix the project is served on, for example `/app1/`
+  pub mount: String,
+  pub config: Config,
+  pub plugins: Vec<Arc<dyn Plugin>>,
+}
+
+/// The project serving a url, see [MultiProjectCompiler::resolve_mount]
+pub struct MountedProject<'a> {
+  pub name: &'a str,
+  pub compiler: &'a Arc<Compiler>,
+  /// the url path relative to the mount, starting with `/`
+  pub path: String,
+}
+
+pub struct MultiProjectCompiler {
+  /// (mount, name), sorted by the length of mount descending so the most specific mount matches first
+  mounts: Vec<(String, String)>,
+  compilers: HashMap<String, Arc<Compiler>>,
+  pub thread_pool: Arc<ThreadPool>,
+}
+
+impl MultiProjectCompiler {
+  pub fn new(projects: Vec<ProjectOptions>) -> Result<Self> {
+    let thread_pool = create_thread_pool();
+    let mut mounts = vec![];
+    let mut compilers = HashMap::new();
+
+    for ProjectOptions {
+      name,
+      mount,
+      mut config,
+      plugins,
+    } in projects
+    {
+      if compilers.contains_key(&name) {
+        return Err(CompilationError::GenericError(format!(
+          "Duplicate project name {name}"
+        )));
+      }
+
+      let mount = normalize_mount(&mount);
+
+      if mounts.iter().any(|(m, _)| m == &mount) {
+        return Err(CompilationError::GenericError(format!(
+          "Mount {mount} of project {name} is already used by another project"
+        )));
+      }
+
+      isolate_project_config(&name, &mount, &mut config);
+
+      let compiler = Compiler::new_with_thread_pool(config, plugins, thread_pool.clone())?;
+      mounts.push((mount, name.clone()));
+      compilers.insert(name, Arc::new(compiler));
+    }
+
+    mounts.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+
+    Ok(Self {
+      mounts,
+      compilers,
+      thread_pool,
+    })
+  }
+
+  pub fn get(&self, name: &str) -> Option<&Arc<Compiler>> {
+    self.compilers.get(name)
+  }
+
+  pub fn projects(&self) -> Vec<&String> {
+    self.mounts.iter().map(|(_, name)| name).collect()
+  }
+
+  /// Compile all projects. Projects are compiled concurrently, the first error is returned.
+  pub fn compile_all(&self) -> Result<()> {
+    std::thread::scope(|s| {
+      let handles = self
+        .compilers
+        .values()
+        .map(|compiler| s.spawn(move || compiler.compile()))
+        .collect::<Vec<_>>();
+
+      handles
+        .into_iter()
+        .try_for_each(|h| h.join().expect("project compilation panicked"))
+    })
+  }
+
+  /// Find the project serving `url`, the query and hash of the url are kept in [MountedProject::path].
+  pub fn resolve_mount(&self, url: &str) -> Option<MountedProject<'_>> {
+    self.mounts.iter().find_map(|(mount, name)| {
+      let rest = strip_mount(url, mount)?;
+
+      Some(MountedProject {
+        name,
+        compiler: &self.compilers[name],
+        path: format!("/{rest}"),
+      })
+    })
+  }
+}
+
+/// `/app1`, `/app1?t=1` and `/app1/index.js` are served by `/app1/`, `/app10/index.js` is not
+fn strip_mount<'a>(url: &'a str, mount: &str) -> Option<&'a str> {
+  if let Some(rest) = url.strip_prefix(mount) {
+    return Some(rest);
+  }
+
+  url
+    .strip_prefix(mount.trim_end_matches('/'))
+    .filter(|rest| rest.is_empty() || rest.starts_with(['?', '#']))
+}
+
+fn normalize_mount(mount: &str) -> String {
+  let trimmed = mount.trim_matches('/');
+
+  if trimmed.is_empty() {
+    "/".to_string()
+  } else {
+    format!("/{trimmed}/")
+  }
+}
+
+/// Make sure projects never share caches or output urls
+fn isolate_project_config(name: &str, mount: &str, config: &mut Config) {
+  if config.persistent_cache.enabled() {
+    let mut cache_config = config.persistent_cache.as_obj(&config.root);
+    cache_config.namespace = format!("{}-{}", cache_config.namespace, name);
+    config.persistent_cache = Box::new(PersistentCacheConfig::Obj(cache_config));
+  }
+
+  if config.output.public_path == "/" {
+    config.output.public_path = mount.to_string();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use farmfe_core::config::{persistent_cache::PersistentCacheConfig, Config};
+
+  use super::{isolate_project_config, normalize_mount, strip_mount};
+
+  #[test]
+  fn test_strip_mount() {
+    assert_eq!(strip_mount("/app1/index.js", "/app1/"), Some("index.js"));
+    assert_eq!(strip_mount("/app1", "/app1/"), Some(""));
+    assert_eq!(strip_mount("/app1?t=1", "/app1/"), Some("?t=1"));
+    assert_eq!(strip_mount("/app10/index.js", "/app1/"), None);
+    assert_eq!(strip_mount("/index.js", "/"), Some("index.js"));
+  }
+
+  #[test]
+  fn test_normalize_mount() {
+    assert_eq!(normalize_mount("app1"), "/app1/");
+    assert_eq!(normalize_mount("/app1"), "/app1/");
+    assert_eq!(normalize_mount("/nested/app2/"), "/nested/app2/");
+    assert_eq!(normalize_mount(""), "/");
+  }
+
+  #[test]
+  fn test_isolate_project_config() {
+    let mut config = Config {
+      persistent_cache: Box::new(PersistentCacheConfig::Bool(true)),
+      ..Default::default()
+    };
+    isolate_project_config("app1", "/app1/", &mut config);
+
+    assert_eq!(
+      config.persistent_cache.as_raw_object().namespace,
+      "farm-cache-app1"
+    );
+    assert_eq!(config.output.public_path, "/app1/");
+  }
+}
@@ -0,0 +1,320 @@
+//! Test support for plugin authors, compile a small project with the plugins under test and assert the results by snapshots.
+//!
+//! ```ignore
+//! let result = TestProject::new()
+//!   .file("index.ts", "import { a } from './a'; console.log(a);")
+//!   .file("a.ts", "export const a = 1;")
+//!   .input("index", "./index.ts")
+//!   .plugin(Arc::new(MyPlugin::new()))
+//!   .compile()
+//!   .unwrap();
+//!
+//! assert!(result.resource("index.js").unwrap().contains("console.log"));
+//! result.assert_resources_snapshot("tests/snapshots/index.snap");
+//! ```
+
+use std::{
+  collections::{BTreeMap, HashMap},
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  },
+};
+
+use farmfe_core::{
+  config::{
+    bool_or_obj::BoolOrObj, persistent_cache::PersistentCacheConfig, preset_env::PresetEnvConfig,
+    Config, Mode, SourcemapConfig,
+  },
+  error::{CompilationError, Result},
+  plugin::Plugin,
+};
+use farmfe_testing_helpers::is_update_snapshot_from_env;
+
+use crate::Compiler;
+
+#[cfg(feature = "conformance")]
+pub mod conformance;
+
+/// path of the runtime entry written to the test project, relative to its root
+const RUNTIME_ENTRY: &str = "__farm_runtime/index.js";
+
+static PROJECT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A project whose files are kept in memory until it's compiled, then they are written to a temporary directory that is
+/// removed when the [TestCompileResult] is dropped.
+/// The config defaults to a production build without minify, source maps, persistent cache, lazy compilation and hashes
+/// in the resource names, so the results are stable
+pub struct TestProject {
+  files: BTreeMap<String, Vec<u8>>,
+  config: Config,
+  plugins: Vec<Arc<dyn Plugin>>,
+}
+
+impl Default for TestProject {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl TestProject {
+  pub fn new() -> Self {
+    let mut config = Config {
+      input: HashMap::new(),
+      mode: Mode::Production,
+      sourcemap: Box::new(SourcemapConfig::Bool(false)),
+      lazy_compilation: false,
+      progress: false,
+      minify: Box::new(BoolOrObj::Bool(false)),
+      preset_env: Box::new(PresetEnvConfig::Bool(false)),
+      persistent_cache: Box::new(PersistentCacheConfig::Bool(false)),
+      ..Default::default()
+    };
+    config.output.filename = "[resourceName].[ext]".to_string();
+    config.output.entry_filename = "[entryName].[ext]".to_string();
+
+    Self {
+      // a runtime is required to render script modules, the test project only needs an empty one
+      files: BTreeMap::from([(RUNTIME_ENTRY.to_string(), b"export {};\n".to_vec())]),
+      config,
+      plugins: vec![],
+    }
+  }
+
+  /// Add a file, the path is relative to the root of the project, e.g. `src/index.ts` or `node_modules/dep/package.json`
+  pub fn file(mut self, path: impl Into<String>, content: impl Into<Vec<u8>>) -> Self {
+    self.files.insert(path.into(), content.into());
+    self
+  }
+
+  /// Add the files of the directory recursively, e.g. a fixture project
+  pub fn dir(mut self, dir: impl AsRef<Path>) -> Result<Self> {
+    let dir = dir.as_ref();
+    let mut stack = vec![dir.to_path_buf()];
+    let read_error = |path: &Path, e: std::io::Error| {
+      CompilationError::GenericError(format!("Failed to read test file {path:?}: {e}"))
+    };
+
+    while let Some(current) = stack.pop() {
+      for entry in std::fs::read_dir(&current).map_err(|e| read_error(&current, e))? {
+        let path = entry.map_err(|e| read_error(&current, e))?.path();
+
+        if path.is_dir() {
+          stack.push(path);
+        } else {
+          let content = std::fs::read(&path).map_err(|e| read_error(&path, e))?;
+          let relative = path.strip_prefix(dir).unwrap().to_string_lossy();
+          self.files.insert(relative.replace('\\', "/"), content);
+        }
+      }
+    }
+
+    Ok(self)
+  }
+
+  /// Add an entry, e.g. `.input("index", "./src/index.ts")`
+  pub fn input(mut self, name: impl Into<String>, path: impl Into<String>) -> Self {
+    self.config.input.insert(name.into(), path.into());
+    self
+  }
+
+  /// Modify the config, `root` and `runtime.path` are overridden when compiling
+  pub fn config(mut self, f: impl FnOnce(&mut Config)) -> Self {
+    f(&mut self.config);
+    self
+  }
+
+  /// Add a plugin, the internal plugins are always registered
+  pub fn plugin(mut self, plugin: Arc<dyn Plugin>) -> Self {
+    self.plugins.push(plugin);
+    self
+  }
+
+  /// Write the files and compile the project
+  pub fn compile(self) -> Result<TestCompileResult> {
+    let root = std::env::temp_dir().join(format!(
+      "farm-test-project-{}-{}",
+      std::process::id(),
+      PROJECT_ID.fetch_add(1, Ordering::SeqCst)
+    ));
+
+    match self.write_and_compile(&root) {
+      Ok(compiler) => Ok(TestCompileResult {
+        compiler: Some(compiler),
+        root,
+      }),
+      Err(e) => {
+        let _ = std::fs::remove_dir_all(&root);
+        Err(e)
+      }
+    }
+  }
+
+  fn write_and_compile(self, root: &Path) -> Result<Compiler> {
+    for (path, content) in &self.files {
+      let path = root.join(path);
+      std::fs::create_dir_all(path.parent().unwrap())
+        .and_then(|_| std::fs::write(&path, content))
+        .map_err(|e| {
+          CompilationError::GenericError(format!("Failed to write test file {path:?}: {e}"))
+        })?;
+    }
+
+    let mut config = self.config;
+    config.root = root.to_string_lossy().to_string();
+    config.runtime.path = root.join(RUNTIME_ENTRY).to_string_lossy().to_string();
+
+    let compiler = Compiler::new(config, self.plugins)?;
+    compiler.compile()?;
+
+    Ok(compiler)
+  }
+}
+
+/// Result of [TestProject::compile], the temporary directory of the project is removed when it's dropped
+pub struct TestCompileResult {
+  compiler: Option<Compiler>,
+  root: PathBuf,
+}
+
+impl TestCompileResult {
+  pub fn compiler(&self) -> &Compiler {
+    self.compiler.as_ref().unwrap()
+  }
+
+  pub fn root(&self) -> &Path {
+    &self.root
+  }
+
+  /// resource name -> content, the runtime and the emitted resources are excluded
+  pub fn resources(&self) -> BTreeMap<String, String> {
+    self
+      .compiler()
+      .context()
+      .resources_map
+      .iter()
+      .filter(|resource| !resource.emitted && !resource.key().starts_with("__farm_runtime"))
+      .map(|resource| {
+        (
+          resource.key().clone(),
+          String::from_utf8_lossy(&resource.read_bytes().unwrap()).to_string(),
+        )
+      })
+      .collect()
+  }
+
+  pub fn resource(&self, name: &str) -> Option<String> {
+    self
+      .compiler()
+      .context()
+      .resources_map
+      .get(name)
+      .map(|resource| String::from_utf8_lossy(&resource.read_bytes().unwrap()).to_string())
+  }
+
+  /// ids of the modules in the module graph, sorted
+  pub fn module_ids(&self) -> Vec<String> {
+    let module_graph = self.compiler().context().module_graph.read();
+    let mut module_ids = module_graph
+      .modules()
+      .into_iter()
+      .map(|module| module.id.to_string())
+      .filter(|id| !id.starts_with("__farm_runtime"))
+      .collect::<Vec<_>>();
+    module_ids.sort();
+
+    module_ids
+  }
+
+  /// ids of the dependencies of the module with their import sources and kinds, e.g. `./a -> a.ts (Import)`
+  pub fn dependencies(&self, module_id: &str) -> Vec<String> {
+    let module_graph = self.compiler().context().module_graph.read();
+    let mut dependencies = module_graph
+      .dependencies(&module_id.into())
+      .into_iter()
+      .flat_map(|(dep, edge)| {
+        edge
+          .items()
+          .iter()
+          .map(|item| format!("{} -> {} ({:?})", item.source, dep.to_string(), item.kind))
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+    dependencies.sort();
+
+    dependencies
+  }
+
+  /// The resources joined by their names, sorted by name, e.g. `//index.js:\n<content>`
+  pub fn resources_snapshot(&self) -> String {
+    self
+      .resources()
+      .into_iter()
+      .map(|(name, content)| format!("//{name}:\n{content}"))
+      .collect::<Vec<_>>()
+      .join("\n\n")
+  }
+
+  /// The module graph, one module per line followed by its dependencies
+  pub fn module_graph_snapshot(&self) -> String {
+    self
+      .module_ids()
+      .into_iter()
+      .map(|module_id| {
+        let dependencies = self.dependencies(&module_id);
+
+        if dependencies.is_empty() {
+          module_id
+        } else {
+          format!("{module_id}\n  {}", dependencies.join("\n  "))
+        }
+      })
+      .collect::<Vec<_>>()
+      .join("\n")
+  }
+
+  pub fn assert_resources_snapshot(&self, snapshot: impl AsRef<Path>) {
+    assert_snapshot(snapshot, &self.resources_snapshot());
+  }
+
+  pub fn assert_module_graph_snapshot(&self, snapshot: impl AsRef<Path>) {
+    assert_snapshot(snapshot, &self.module_graph_snapshot());
+  }
+}
+
+impl Drop for TestCompileResult {
+  fn drop(&mut self) {
+    // the compiler may still read the files when it's dropped
+    drop(self.compiler.take());
+    let _ = std::fs::remove_dir_all(&self.root);
+  }
+}
+
+/// Compare the content with the snapshot file line by line, the whitespaces around the lines are ignored.
+/// A relative path is resolved from `CARGO_MANIFEST_DIR`, the directory of the crate under test.
+/// The snapshot is written when it does not exist or `FARM_UPDATE_SNAPSHOTS` is set
+pub fn assert_snapshot(snapshot: impl AsRef<Path>, content: &str) {
+  let snapshot = match std::env::var("CARGO_MANIFEST_DIR") {
+    Ok(dir) if snapshot.as_ref().is_relative() => PathBuf::from(dir).join(snapshot),
+    _ => snapshot.as_ref().to_path_buf(),
+  };
+
+  if is_update_snapshot_from_env() || !snapshot.exists() {
+    if let Some(parent) = snapshot.parent() {
+      std::fs::create_dir_all(parent).unwrap();
+    }
+
+    std::fs::write(&snapshot, content).unwrap();
+    return;
+  }
+
+  let expected = std::fs::read_to_string(&snapshot).unwrap();
+  let expected_lines = expected.trim().lines().map(str::trim).collect::<Vec<_>>();
+  let content_lines = content.trim().lines().map(str::trim).collect::<Vec<_>>();
+
+  assert_eq!(
+    expected_lines, content_lines,
+    "snapshot {snapshot:?} does not match, set FARM_UPDATE_SNAPSHOTS to update it"
+  );
+}
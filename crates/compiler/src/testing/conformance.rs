@@ -0,0 +1,301 @@
+//! Golden-file conformance suite, compiles every fixture project of a directory across the output format and target env
+//! matrix and compares the emitted resources with the expected ones.
+//!
+//! A fixture project is a sub directory of the suite directory. The entry is `./index.ts` unless a `conformance.json` overrides it,
+//! the json is merged into the config of the project and may narrow the matrix of the project:
+//! ```json
+//! { "input": { "main": "./main.ts" }, "formats": ["esm"], "targets": ["browser"], "config": { "define": { "DEBUG": false } } }
+//! ```
+//! The expected resources are stored in `__expected__/<target>-<format>.snap` of the fixture project, they are written when they
+//! do not exist or `FARM_UPDATE_SNAPSHOTS` is set.
+
+use std::{
+  collections::HashMap,
+  fmt::Display,
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  config::{Config, ModuleFormat, TargetEnv},
+  error::{CompilationError, Result},
+  plugin::Plugin,
+  serde::Deserialize,
+  serde_json::{self, Value},
+};
+use farmfe_testing_helpers::is_update_snapshot_from_env;
+
+use super::TestProject;
+
+const EXPECTED_DIR: &str = "__expected__";
+const CONFORMANCE_CONFIG: &str = "conformance.json";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(crate = "farmfe_core::serde", default)]
+struct ConformanceCaseConfig {
+  input: Option<HashMap<String, String>>,
+  formats: Option<Vec<ModuleFormat>>,
+  targets: Option<Vec<TargetEnv>>,
+  config: Option<Value>,
+}
+
+/// Compiles the fixture projects of a directory across the output format and target env matrix, see the module docs
+pub struct ConformanceSuite {
+  dir: PathBuf,
+  formats: Vec<ModuleFormat>,
+  targets: Vec<TargetEnv>,
+  plugins: Box<dyn Fn() -> Vec<Arc<dyn Plugin>>>,
+}
+
+impl ConformanceSuite {
+  /// The matrix defaults to esm and cjs for browser and node
+  pub fn new(dir: impl Into<PathBuf>) -> Self {
+    Self {
+      dir: dir.into(),
+      formats: vec![ModuleFormat::EsModule, ModuleFormat::CommonJs],
+      targets: vec![TargetEnv::Browser, TargetEnv::Node],
+      plugins: Box::new(Vec::new),
+    }
+  }
+
+  pub fn formats(mut self, formats: Vec<ModuleFormat>) -> Self {
+    self.formats = formats;
+    self
+  }
+
+  pub fn targets(mut self, targets: Vec<TargetEnv>) -> Self {
+    self.targets = targets;
+    self
+  }
+
+  /// Plugins registered for every compilation, created for each of them so their states are not shared
+  pub fn plugins(mut self, plugins: impl Fn() -> Vec<Arc<dyn Plugin>> + 'static) -> Self {
+    self.plugins = Box::new(plugins);
+    self
+  }
+
+  /// Compile all the fixture projects, sorted by name
+  pub fn run(&self) -> Result<ConformanceReport> {
+    let mut cases = std::fs::read_dir(&self.dir)
+      .map_err(|e| {
+        CompilationError::GenericError(format!(
+          "Failed to read conformance suite {:?}: {e}",
+          self.dir
+        ))
+      })?
+      .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+      .filter(|path| path.is_dir())
+      .collect::<Vec<_>>();
+    cases.sort();
+
+    let mut results = vec![];
+
+    for case in cases {
+      results.extend(self.run_case(&case)?);
+    }
+
+    Ok(ConformanceReport { results })
+  }
+
+  fn run_case(&self, case: &Path) -> Result<Vec<ConformanceResult>> {
+    let name = case.file_name().unwrap().to_string_lossy().to_string();
+    let case_config = read_case_config(case)?;
+    let formats = case_config.formats.as_ref().unwrap_or(&self.formats);
+    let targets = case_config.targets.as_ref().unwrap_or(&self.targets);
+    let mut results = vec![];
+
+    for target in targets {
+      for format in formats {
+        let variant = format!("{}-{}", enum_name(target), enum_name(format));
+        let outcome = match self.compile(case, &case_config, target, format) {
+          Ok(actual) => compare_expected(
+            &case.join(EXPECTED_DIR).join(format!("{variant}.snap")),
+            &actual,
+          ),
+          Err(e) => ConformanceOutcome::Error(e.to_string()),
+        };
+
+        results.push(ConformanceResult {
+          case: name.clone(),
+          variant,
+          outcome,
+        });
+      }
+    }
+
+    Ok(results)
+  }
+
+  fn compile(
+    &self,
+    case: &Path,
+    case_config: &ConformanceCaseConfig,
+    target: &TargetEnv,
+    format: &ModuleFormat,
+  ) -> Result<String> {
+    let mut project = TestProject::new().dir(case)?;
+    project
+      .files
+      .retain(|path, _| !path.starts_with(EXPECTED_DIR) && path != CONFORMANCE_CONFIG);
+
+    if let Some(config) = &case_config.config {
+      project.config = merge_config(&project.config, config)?;
+    }
+
+    project.config.input = case_config
+      .input
+      .clone()
+      .unwrap_or_else(|| HashMap::from([("index".to_string(), "./index.ts".to_string())]));
+    project.config.output.target_env = target.clone();
+    project.config.output.format = *format;
+
+    for plugin in (self.plugins)() {
+      project = project.plugin(plugin);
+    }
+
+    Ok(project.compile()?.resources_snapshot())
+  }
+}
+
+fn read_case_config(case: &Path) -> Result<ConformanceCaseConfig> {
+  let path = case.join(CONFORMANCE_CONFIG);
+
+  if !path.exists() {
+    return Ok(ConformanceCaseConfig::default());
+  }
+
+  std::fs::read_to_string(&path)
+    .map_err(|e| e.to_string())
+    .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+    .map_err(|e| {
+      CompilationError::GenericError(format!("Failed to read conformance config {path:?}: {e}"))
+    })
+}
+
+fn merge_config(config: &Config, overrides: &Value) -> Result<Config> {
+  fn merge(base: &mut Value, overrides: &Value) {
+    match (base, overrides) {
+      (Value::Object(base), Value::Object(overrides)) => {
+        for (key, value) in overrides {
+          merge(base.entry(key.clone()).or_insert(Value::Null), value);
+        }
+      }
+      (base, overrides) => *base = overrides.clone(),
+    }
+  }
+
+  let mut value = serde_json::to_value(config).unwrap();
+  merge(&mut value, overrides);
+
+  serde_json::from_value(value)
+    .map_err(|e| CompilationError::GenericError(format!("Invalid conformance config: {e}")))
+}
+
+/// serialized name of the unit variant, e.g. `browser` or `esm`
+fn enum_name(value: &impl farmfe_core::serde::Serialize) -> String {
+  match serde_json::to_value(value) {
+    Ok(Value::String(name)) => name,
+    _ => "unknown".to_string(),
+  }
+}
+
+fn compare_expected(expected: &Path, actual: &str) -> ConformanceOutcome {
+  if is_update_snapshot_from_env() || !expected.exists() {
+    return match std::fs::create_dir_all(expected.parent().unwrap())
+      .and_then(|_| std::fs::write(expected, actual))
+    {
+      Ok(_) => ConformanceOutcome::Updated,
+      Err(e) => ConformanceOutcome::Error(format!("Failed to write {expected:?}: {e}")),
+    };
+  }
+
+  let expected_content = match std::fs::read_to_string(expected) {
+    Ok(content) => content,
+    Err(e) => return ConformanceOutcome::Error(format!("Failed to read {expected:?}: {e}")),
+  };
+  let expected_lines = expected_content.trim().lines().map(str::trim);
+  let actual_lines = actual.trim().lines().map(str::trim);
+
+  for (line, (expected, actual)) in expected_lines.clone().zip(actual_lines.clone()).enumerate() {
+    if expected != actual {
+      return ConformanceOutcome::Failed(format!("line {}:\n- {expected}\n+ {actual}", line + 1));
+    }
+  }
+
+  if expected_lines.count() != actual_lines.count() {
+    return ConformanceOutcome::Failed("the number of lines is different".to_string());
+  }
+
+  ConformanceOutcome::Passed
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConformanceOutcome {
+  Passed,
+  /// the expected resources did not exist or `FARM_UPDATE_SNAPSHOTS` is set, they are written
+  Updated,
+  /// the first different line
+  Failed(String),
+  /// the compilation failed
+  Error(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ConformanceResult {
+  /// name of the fixture project
+  pub case: String,
+  /// `<target>-<format>`, e.g. `browser-esm`
+  pub variant: String,
+  pub outcome: ConformanceOutcome,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConformanceReport {
+  pub results: Vec<ConformanceResult>,
+}
+
+impl ConformanceReport {
+  pub fn failures(&self) -> Vec<&ConformanceResult> {
+    self
+      .results
+      .iter()
+      .filter(|r| {
+        matches!(
+          r.outcome,
+          ConformanceOutcome::Failed(_) | ConformanceOutcome::Error(_)
+        )
+      })
+      .collect()
+  }
+
+  /// Panic with all the failures
+  pub fn assert_passed(&self) {
+    let failures = self.failures();
+
+    if !failures.is_empty() {
+      panic!(
+        "{} of {} conformance cases failed, set FARM_UPDATE_SNAPSHOTS to update the expected resources\n\n{}",
+        failures.len(),
+        self.results.len(),
+        failures
+          .iter()
+          .map(|r| r.to_string())
+          .collect::<Vec<_>>()
+          .join("\n\n")
+      );
+    }
+  }
+}
+
+impl Display for ConformanceResult {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match &self.outcome {
+      ConformanceOutcome::Passed => write!(f, "{} [{}]: passed", self.case, self.variant),
+      ConformanceOutcome::Updated => write!(f, "{} [{}]: updated", self.case, self.variant),
+      ConformanceOutcome::Failed(diff) => {
+        write!(f, "{} [{}]: failed\n{diff}", self.case, self.variant)
+      }
+      ConformanceOutcome::Error(e) => write!(f, "{} [{}]: error\n{e}", self.case, self.variant),
+    }
+  }
+}
@@ -44,7 +44,7 @@ impl TracedModuleGraph {
 
 impl Compiler {
   pub fn trace_module_graph(&self) -> farmfe_core::error::Result<TracedModuleGraph> {
-    self.build()?;
+    self.build(&self.context.config.input)?;
 
     let mut graph = TracedModuleGraph::new(self.context.config.root.clone());
     let module_graph = self.context.module_graph.read();
@@ -0,0 +1,226 @@
+//! Compile a subset of entries on demand and merge the result into the existing compilation,
+//! the built modules, module groups and resource pots are patched the same way as an update.
+
+use std::{
+  collections::{HashMap, HashSet},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  context::UpdatePriority,
+  error::{CompilationError, Result},
+  module::{module_graph::ModuleGraph, ModuleId},
+  plugin::{PluginModuleGraphUpdatedHookParams, PluginResolveHookParam, ResolveKind},
+  serde_json::json,
+};
+use farmfe_plugin_partial_bundling::module_group_graph_from_entries;
+
+use crate::{
+  build::{module_cache::set_module_graph_cache, BuildModuleGraphThreadedParams},
+  generate::finalize_resources::finalize_resources,
+  Compiler,
+};
+
+use super::{
+  diff_and_patch_module_graph::DiffResult,
+  regenerate_resources::regenerate_resources_for_affected_module_groups,
+  update_context::UpdateContext, BuildUpdateModuleGraphThreadedParams,
+};
+
+impl Compiler {
+  /// Compile only the module groups reachable from `entries` and merge them into the existing module graph,
+  /// module group graph and resource pot map. Modules that are already in the module graph are reused instead of rebuilt,
+  /// the existing module groups sharing them are regenerated.
+  /// Entries whose name is already compiled are skipped. Return the ids of the newly added modules.
+  ///
+  /// [Compiler::compile] should be called before this method.
+  pub fn compile_partial(&self, entries: HashMap<String, String>) -> Result<Vec<ModuleId>> {
+    let entries = {
+      let module_graph = self.context.module_graph.read();
+      let compiled_entries = module_graph.entries.values().collect::<HashSet<_>>();
+
+      entries
+        .into_iter()
+        .filter(|(name, _)| !compiled_entries.contains(name))
+        .collect::<Vec<_>>()
+    };
+
+    if entries.is_empty() {
+      return Ok(vec![]);
+    }
+
+    let _update_lock = self.context.update_lock.acquire();
+    self.context.record_manager.set_start_time();
+    let (err_sender, err_receiver) = Self::create_thread_channel();
+    let update_context = Arc::new(UpdateContext::new(UpdatePriority::Interactive));
+    let mut entry_ids = HashMap::new();
+
+    for (name, source) in entries {
+      let resolve_param = PluginResolveHookParam {
+        source,
+        importer: None,
+        kind: ResolveKind::Entry(name.clone()),
+      };
+      let entry_id = Self::resolve_module_id(&resolve_param, &self.context)?.module_id;
+      entry_ids.insert(entry_id, name);
+
+      let params = BuildUpdateModuleGraphThreadedParams {
+        build_module_graph_threaded_params: BuildModuleGraphThreadedParams {
+          resolve_param,
+          context: self.context.clone(),
+          err_sender: err_sender.clone(),
+          thread_pool: self.thread_pool.clone(),
+          order: 0,
+          cached_dependency: None,
+          cached_resolve_trace: None,
+        },
+        order: None,
+        update_context: update_context.clone(),
+      };
+
+      Self::update_module_graph_threaded(params);
+    }
+
+    drop(err_sender);
+
+    let mut errors = vec![];
+
+    while let Ok(err) = err_receiver.recv() {
+      errors.push(err);
+    }
+
+    Self::handle_global_log(&self.context, &mut errors);
+
+    if !errors.is_empty() {
+      self.context.record_manager.set_build_end_time();
+      self.context.record_manager.set_end_time();
+
+      let errors_json = json!(errors.iter().map(|e| e.to_string()).collect::<Vec<_>>());
+      return Err(CompilationError::GenericError(errors_json.to_string()));
+    }
+
+    self.context.record_manager.set_build_end_time();
+
+    let added_modules = {
+      let mut module_graph = self.context.module_graph.write();
+      let mut update_module_graph = update_context.module_graph.write();
+      let added_modules = merge_module_graph(&mut module_graph, &mut update_module_graph);
+
+      for (entry_id, name) in &entry_ids {
+        module_graph.entries.insert(entry_id.clone(), name.clone());
+      }
+
+      module_graph
+        .update_execution_order_for_modules_by(self.context.config.execution_order_tie_break);
+      added_modules
+    };
+
+    if self.context.config.persistent_cache.enabled() {
+      set_module_graph_cache(added_modules.clone(), &self.context);
+    }
+
+    // the module groups that did not exist before and the module groups sharing modules with them are regenerated
+    let affected_module_groups = {
+      let mut module_graph = self.context.module_graph.write();
+      let mut module_group_graph = self.context.module_group_graph.write();
+      let entry_ids = entry_ids.into_keys().collect::<Vec<_>>();
+      let partial_module_group_graph =
+        module_group_graph_from_entries(&entry_ids, &mut module_graph);
+      let mut affected_module_groups = HashSet::new();
+
+      for module_group in partial_module_group_graph.module_groups() {
+        if !module_group_graph.has(&module_group.id) {
+          module_group_graph.add_module_group(module_group.clone());
+          affected_module_groups.insert(module_group.id.clone());
+        }
+      }
+
+      for module_group_id in &affected_module_groups {
+        for dep in partial_module_group_graph.dependencies_ids(module_group_id) {
+          if !module_group_graph.has_edge(module_group_id, &dep) {
+            module_group_graph.add_edge(module_group_id, &dep);
+          }
+        }
+      }
+
+      // modules shared with the existing module groups may be moved to other resource pots,
+      // so the existing module groups of the shared modules are regenerated too
+      let shared_module_groups = affected_module_groups
+        .iter()
+        .flat_map(|id| module_group_graph.module_group(id).unwrap().modules())
+        .flat_map(|module_id| {
+          module_graph
+            .module(module_id)
+            .unwrap()
+            .module_groups
+            .clone()
+        })
+        .collect::<HashSet<_>>();
+      affected_module_groups.extend(shared_module_groups);
+
+      affected_module_groups
+    };
+
+    self.context.plugin_driver.module_graph_updated(
+      &PluginModuleGraphUpdatedHookParams {
+        added_modules_ids: added_modules.clone(),
+        removed_modules_ids: vec![],
+        updated_modules_ids: vec![],
+      },
+      &self.context,
+    )?;
+
+    let diff_result = DiffResult {
+      added_modules: added_modules.iter().cloned().collect(),
+      ..Default::default()
+    };
+
+    regenerate_resources_for_affected_module_groups(
+      affected_module_groups,
+      diff_result,
+      &vec![],
+      &HashMap::new(),
+      &self.context,
+    )?;
+    finalize_resources(&self.context)?;
+
+    self.context.record_manager.set_end_time();
+
+    Ok(added_modules)
+  }
+}
+
+/// Move the modules that only exist in `partial_module_graph` to `module_graph`, together with their dependency edges.
+/// Return the ids of the moved modules.
+fn merge_module_graph(
+  module_graph: &mut ModuleGraph,
+  partial_module_graph: &mut ModuleGraph,
+) -> Vec<ModuleId> {
+  let added_modules = partial_module_graph
+    .modules()
+    .into_iter()
+    .filter(|m| !module_graph.has_module(&m.id))
+    .map(|m| m.id.clone())
+    .collect::<Vec<_>>();
+
+  let added_edges = added_modules
+    .iter()
+    .flat_map(|from| {
+      partial_module_graph
+        .dependencies(from)
+        .into_iter()
+        .map(|(to, edge)| (from.clone(), to, edge.clone()))
+        .collect::<Vec<_>>()
+    })
+    .collect::<Vec<_>>();
+
+  for module_id in &added_modules {
+    module_graph.add_module(partial_module_graph.take_module(module_id));
+  }
+
+  for (from, to, edge) in added_edges {
+    module_graph.add_edge(&from, &to, edge).unwrap();
+  }
+
+  added_modules
+}
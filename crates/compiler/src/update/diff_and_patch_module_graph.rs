@@ -34,6 +34,12 @@ pub struct DiffResult {
   pub removed_modules: HashSet<ModuleId>,
 }
 
+impl DiffResult {
+  pub fn is_empty(&self) -> bool {
+    self.deps_changes.is_empty() && self.added_modules.is_empty() && self.removed_modules.is_empty()
+  }
+}
+
 #[cfg(test)]
 impl DiffResult {
   pub fn readable_print(&self) {
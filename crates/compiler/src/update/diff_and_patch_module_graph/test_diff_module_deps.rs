@@ -259,6 +259,7 @@ fn test_diff_module_deps_complex_1() {
             order: 1,
             kind: ResolveKind::DynamicImport,
             source: "./D".to_string(),
+            resolve_trace: None,
           }])
         )],
       }
@@ -40,6 +40,7 @@ fn test_diff_module_graph_complex_1() {
             order: 1,
             kind: ResolveKind::DynamicImport,
             source: "./D".to_string(),
+            resolve_trace: None,
           }])
         )],
       }
@@ -126,6 +127,7 @@ fn test_diff_module_graph_complex_2() {
               kind: ResolveKind::DynamicImport,
               source: "./D".to_string(),
               order: 1,
+              resolve_trace: None,
             }])
           )],
         }
@@ -140,6 +142,7 @@ fn test_diff_module_graph_complex_2() {
               kind: ResolveKind::DynamicImport,
               source: "./F".to_string(),
               order: 0,
+              resolve_trace: None,
             }])
           )],
         }
@@ -154,6 +157,7 @@ fn test_diff_module_graph_complex_2() {
               kind: ResolveKind::Import,
               source: "./A".to_string(),
               order: 0,
+              resolve_trace: None,
             }])
           )],
         }
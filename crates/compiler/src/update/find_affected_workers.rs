@@ -0,0 +1,22 @@
+use std::{collections::HashSet, sync::Arc};
+
+use farmfe_core::{
+  context::CompilationContext,
+  module::{module_group::ModuleGroupId, ModuleId},
+};
+
+/// Web workers are bundled as standalone resources that are not hot updated, the affected workers should be reloaded instead
+pub fn find_affected_workers(
+  affected_module_groups: &HashSet<ModuleGroupId>,
+  context: &Arc<CompilationContext>,
+) -> Vec<ModuleId> {
+  let module_graph = context.module_graph.read();
+  let mut workers = affected_module_groups
+    .iter()
+    .filter(|group_id| module_graph.is_worker(group_id))
+    .cloned()
+    .collect::<Vec<_>>();
+  workers.sort();
+
+  workers
+}
@@ -6,61 +6,113 @@ use std::{
 use farmfe_core::{
   context::CompilationContext,
   module::{module_graph::ModuleGraph, ModuleId},
+  plugin::{HmrFullReload, HmrFullReloadReason},
 };
 
+#[derive(Debug, Default)]
+pub struct HmrBoundaries {
+  /// updated module id -> the paths from the updated module to its closest boundaries
+  pub boundaries: HashMap<String, Vec<Vec<String>>>,
+  /// boundary module id -> its dependencies on the paths that are accepted by `import.meta.hot.accept(deps, cb)`,
+  /// the runtime calls the accept callbacks of the boundary with the new exports of these dependencies
+  pub accepted_deps: HashMap<String, Vec<String>>,
+  /// the first update that is not accepted, the other fields are empty in this case
+  pub full_reload: Option<HmrFullReload>,
+}
+
 pub fn find_hmr_boundaries(
   update_module_ids: &Vec<ModuleId>,
   context: &Arc<CompilationContext>,
-) -> HashMap<String, Vec<Vec<String>>> {
-  let mut boundaries = HashMap::new();
+) -> HmrBoundaries {
+  let mut hmr_boundaries = HmrBoundaries::default();
   let module_graph = context.module_graph.read();
+  let mode = context.config.mode.clone();
 
   for id in update_module_ids {
-    let mut stack = vec![id.clone()];
+    // the sub modules of a single file component are updated with the component, they share its boundaries
+    let parent = id
+      .sub_module_parent()
+      .filter(|parent| update_module_ids.contains(parent) && module_graph.has_module(parent));
+    let (start, mut stack) = match &parent {
+      Some(parent) => (parent, vec![id.clone(), parent.clone()]),
+      None => (id, vec![id.clone()]),
+    };
     let mut visited = HashSet::new();
     let mut res = vec![];
-    let all_path_accepted =
-      find_hmr_accepted_recursively(id, &module_graph, &mut stack, &mut visited, &mut res);
     // if any of the path is not accepted, reload the whole page
-    if !all_path_accepted {
-      return HashMap::new();
+    if let Err((path, reason)) =
+      find_hmr_accepted_recursively(start, &module_graph, &mut stack, &mut visited, &mut res)
+    {
+      return HmrBoundaries {
+        full_reload: Some(HmrFullReload {
+          module: id.id(mode.clone()),
+          path: path.into_iter().map(|id| id.id(mode.clone())).collect(),
+          reason,
+        }),
+        ..Default::default()
+      };
+    }
+
+    for path in &res {
+      if let Some((boundary, dep)) = accepted_dep_of_path(path, &module_graph) {
+        let deps = hmr_boundaries
+          .accepted_deps
+          .entry(boundary.id(mode.clone()))
+          .or_default();
+        let dep = dep.id(mode.clone());
+
+        if !deps.contains(&dep) {
+          deps.push(dep);
+        }
+      }
     }
 
-    boundaries.insert(
-      id.id(context.config.mode.clone()),
+    hmr_boundaries.boundaries.insert(
+      id.id(mode.clone()),
       res
         .into_iter()
-        .map(|v| {
-          v.into_iter()
-            .map(|id| id.id(context.config.mode.clone()))
-            .collect()
-        })
+        .map(|v| v.into_iter().map(|id| id.id(mode.clone())).collect())
         .collect(),
     );
   }
 
-  boundaries
+  hmr_boundaries
+}
+
+/// The boundary and the dependency it accepts if the path ends with a module that accepts its dependency on the path
+fn accepted_dep_of_path<'a>(
+  path: &'a [ModuleId],
+  module_graph: &ModuleGraph,
+) -> Option<(&'a ModuleId, &'a ModuleId)> {
+  let [.., dep, boundary] = path else {
+    return None;
+  };
+  let module = module_graph.module(boundary)?;
+
+  (module.module_type.is_script() && module.meta.as_script().hmr_accepted_deps.contains(dep))
+    .then_some((boundary, dep))
 }
 
+/// The path that is not accepted and why if the update is not accepted by all the paths
 fn find_hmr_accepted_recursively(
   id: &ModuleId,
   module_graph: &ModuleGraph,
   stack: &mut Vec<ModuleId>,
   visited: &mut HashSet<ModuleId>,
   res: &mut Vec<Vec<ModuleId>>,
-) -> bool {
+) -> Result<(), (Vec<ModuleId>, HmrFullReloadReason)> {
   let module = module_graph.module(id).unwrap();
 
   // There is a path from the module to the root that does not have HMR accepted
   if module_graph.entries.contains_key(id) {
-    return false;
+    return Err((stack.clone(), HmrFullReloadReason::Entry));
   }
 
   // self accepted, non script modules are not self-acceptable for now
   if module.module_type.is_script() && module.meta.as_script().hmr_self_accepted {
     res.push(stack.clone());
 
-    return true;
+    return Ok(());
   }
 
   // check if any of the importers accepts the module
@@ -74,7 +126,9 @@ fn find_hmr_accepted_recursively(
       let parent_module = module_graph.module(&parent).unwrap();
 
       if !parent_module.module_type.is_script() {
-        return false;
+        let mut path = stack.clone();
+        path.push(parent.clone());
+        return Err((path, HmrFullReloadReason::NonScriptImporter));
       }
       // if the importer accepts the module, push
       if parent_module
@@ -91,17 +145,12 @@ fn find_hmr_accepted_recursively(
       }
 
       stack.push(parent.clone());
-      let all_path_accepted =
-        find_hmr_accepted_recursively(&parent, module_graph, stack, visited, res);
+      find_hmr_accepted_recursively(&parent, module_graph, stack, visited, res)?;
       stack.pop();
-
-      if !all_path_accepted {
-        return false;
-      }
     }
   }
 
-  true
+  Ok(())
 }
 
 #[cfg(test)]
@@ -114,15 +163,18 @@ mod tests {
   use farmfe_core::{
     config::{Config, Mode},
     context::CompilationContext,
-    module::{module_graph::ModuleGraph, ModuleMetaData, ModuleType, ScriptModuleMetaData},
-    parking_lot::RwLock,
+    module::{
+      module_graph::{ModuleGraph, ModuleGraphEdgeDataItem},
+      Module, ModuleId, ModuleMetaData, ModuleType, ScriptModuleMetaData,
+    },
+    plugin::ResolveKind,
   };
   use farmfe_testing_helpers::construct_test_module_graph;
 
   use super::find_hmr_boundaries;
 
   fn create_context(module_graph: ModuleGraph) -> Arc<CompilationContext> {
-    let mut context = CompilationContext::new(
+    let context = CompilationContext::new(
       Config {
         mode: Mode::Development,
         ..Default::default()
@@ -131,7 +183,7 @@ mod tests {
     )
     .unwrap();
 
-    context.module_graph = Box::new(RwLock::new(module_graph));
+    *context.module_graph.write() = module_graph;
     Arc::new(context)
   }
   #[test]
@@ -146,9 +198,14 @@ mod tests {
     }));
 
     let context = create_context(module_graph);
-    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context);
-
-    assert_eq!(boundaries, HashMap::new());
+    let hmr_boundaries = find_hmr_boundaries(&vec!["F".into()], &context);
+
+    assert_eq!(hmr_boundaries.boundaries, HashMap::new());
+    // the path that is not accepted is reported
+    let full_reload = hmr_boundaries.full_reload.unwrap();
+    assert_eq!(full_reload.module, "F");
+    assert_eq!(full_reload.path[0], "F");
+    assert!(full_reload.path.len() > 1);
   }
 
   #[test]
@@ -169,7 +226,7 @@ mod tests {
     }));
 
     let context = create_context(module_graph);
-    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context);
+    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context).boundaries;
     // Be careful, the order of the paths may not be guaranteed. check the order if the test fails.
     assert_eq!(
       boundaries,
@@ -194,11 +251,65 @@ mod tests {
     }));
 
     let context = create_context(module_graph);
-    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context);
+    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context).boundaries;
     // Be careful, the order of the paths may not be guaranteed. check the order if the test fails.
     assert_eq!(boundaries, HashMap::new());
   }
 
+  #[test]
+  fn find_hmr_boundaries_sub_module() {
+    let mut module_graph = construct_test_module_graph();
+    let sub_module_id: ModuleId = "F?farm_sub_module=template.0".into();
+    let mut sub_module = Module::new(sub_module_id.clone());
+    sub_module.module_type = ModuleType::Js;
+    sub_module.meta = Box::new(ModuleMetaData::Script(ScriptModuleMetaData {
+      hmr_self_accepted: true,
+      ..Default::default()
+    }));
+    module_graph.add_module(sub_module);
+    module_graph
+      .add_edge_item(
+        &"F".into(),
+        &sub_module_id,
+        ModuleGraphEdgeDataItem {
+          source: "./F?farm_sub_module=template.0".to_string(),
+          kind: ResolveKind::Import,
+          order: 0,
+          resolve_trace: None,
+        },
+      )
+      .unwrap();
+
+    for id in ["C", "D"] {
+      let module = module_graph.module_mut(&id.into()).unwrap();
+      module.module_type = ModuleType::Js;
+      module.meta = Box::new(ModuleMetaData::Script(ScriptModuleMetaData {
+        hmr_self_accepted: true,
+        ..Default::default()
+      }));
+    }
+
+    let context = create_context(module_graph);
+    let boundaries = find_hmr_boundaries(&vec![sub_module_id.clone()], &context).boundaries;
+    // the self accepted sub module is the boundary when it is updated alone
+    assert_eq!(
+      boundaries[&sub_module_id.to_string()],
+      vec![vec![sub_module_id.to_string()]]
+    );
+
+    let boundaries =
+      find_hmr_boundaries(&vec!["F".into(), sub_module_id.clone()], &context).boundaries;
+    let mut paths = boundaries[&sub_module_id.to_string()].clone();
+    paths.sort();
+    assert_eq!(
+      paths,
+      vec![
+        vec![sub_module_id.to_string(), "F".to_string(), "C".to_string()],
+        vec![sub_module_id.to_string(), "F".to_string(), "D".to_string()],
+      ]
+    );
+  }
+
   #[test]
   fn find_hmr_boundaries_deps_1() {
     let mut module_graph = construct_test_module_graph();
@@ -219,7 +330,7 @@ mod tests {
     }));
 
     let context = create_context(module_graph);
-    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context);
+    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context).boundaries;
     // Be careful, the order of the paths may not be guaranteed. check the order if the test fails.
     assert_eq!(
       boundaries,
@@ -252,7 +363,7 @@ mod tests {
     }));
 
     let context = create_context(module_graph);
-    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context);
+    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context).boundaries;
     // Be careful, the order of the paths may not be guaranteed. check the order if the test fails.
     assert_eq!(boundaries, HashMap::new());
   }
@@ -284,13 +395,70 @@ mod tests {
     }));
 
     let context = create_context(module_graph);
-    let boundaries = find_hmr_boundaries(&vec!["G".into()], &context);
+    let hmr_boundaries = find_hmr_boundaries(&vec!["G".into()], &context);
     // Be careful, the order of the paths may not be guaranteed. check the order if the test fails.
     assert_eq!(
-      boundaries,
+      hmr_boundaries.boundaries,
       vec![("G".into(), vec![vec!["G".into(), "E".into(), "B".into()]])]
         .into_iter()
         .collect::<HashMap<_, _>>()
     );
+    assert_eq!(
+      hmr_boundaries.accepted_deps,
+      HashMap::from([("B".into(), vec!["E".into()])])
+    );
+  }
+
+  #[test]
+  fn find_hmr_boundaries_deps_multiple() {
+    let mut module_graph = construct_test_module_graph();
+
+    let module_a = module_graph.module_mut(&"A".into()).unwrap();
+    module_a.module_type = ModuleType::Js;
+    module_a.meta = Box::new(ModuleMetaData::Script(ScriptModuleMetaData {
+      hmr_accepted_deps: HashSet::from(["D".into()]),
+      ..Default::default()
+    }));
+    // import.meta.hot.accept(['./D', './E'], cb)
+    let module_b = module_graph.module_mut(&"B".into()).unwrap();
+    module_b.module_type = ModuleType::Js;
+    module_b.meta = Box::new(ModuleMetaData::Script(ScriptModuleMetaData {
+      hmr_accepted_deps: HashSet::from(["D".into(), "E".into()]),
+      ..Default::default()
+    }));
+
+    for id in ["D", "E"] {
+      let module = module_graph.module_mut(&id.into()).unwrap();
+      module.module_type = ModuleType::Js;
+      module.meta = Box::new(ModuleMetaData::Script(Default::default()));
+    }
+
+    let context = create_context(module_graph);
+    let mut hmr_boundaries = find_hmr_boundaries(&vec!["D".into(), "E".into()], &context);
+
+    for paths in hmr_boundaries.boundaries.values_mut() {
+      paths.sort();
+    }
+    for deps in hmr_boundaries.accepted_deps.values_mut() {
+      deps.sort();
+    }
+
+    assert_eq!(
+      hmr_boundaries.boundaries,
+      HashMap::from([
+        (
+          "D".into(),
+          vec![vec!["D".into(), "A".into()], vec!["D".into(), "B".into()]]
+        ),
+        ("E".into(), vec![vec!["E".into(), "B".into()]]),
+      ])
+    );
+    assert_eq!(
+      hmr_boundaries.accepted_deps,
+      HashMap::from([
+        ("A".into(), vec!["D".into()]),
+        ("B".into(), vec!["D".into(), "E".into()]),
+      ])
+    );
   }
 }
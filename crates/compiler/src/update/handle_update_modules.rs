@@ -3,7 +3,7 @@ use std::{collections::HashMap, sync::Arc};
 use farmfe_core::{
   context::CompilationContext,
   module::ModuleId,
-  plugin::{PluginUpdateModulesHookParams, UpdateResult, UpdateType},
+  plugin::{PluginUpdateModulesHookParams, PluginWatchChangeHookParams, UpdateResult, UpdateType},
   serde_json,
   stats::CompilationPluginHookStats,
 };
@@ -14,6 +14,8 @@ pub fn handle_update_modules(
   context: &Arc<CompilationContext>,
   update_result: &mut UpdateResult,
 ) -> farmfe_core::error::Result<Vec<(String, UpdateType)>> {
+  let paths = handle_watch_change(paths, context)?;
+  let paths = merge_invalidated_modules(paths, context);
   let (before_paths, start_time) = if context.config.record {
     (
       paths.clone(),
@@ -191,7 +193,73 @@ struct UpdateModulesStatsResult {
   pub update_result: UpdateResult,
 }
 
-fn resolve_watch_graph_paths(
+/// Call the watch_change hook for each changed path, plugins may remap the change to other modules
+fn handle_watch_change(
+  paths: Vec<(String, UpdateType)>,
+  context: &Arc<CompilationContext>,
+) -> farmfe_core::error::Result<Vec<(String, UpdateType)>> {
+  let mut result: Vec<(String, UpdateType)> = vec![];
+
+  for (path, update_type) in paths {
+    let mut params = PluginWatchChangeHookParams {
+      id: path.clone(),
+      event: (&update_type).into(),
+      paths: vec![(path, update_type)],
+    };
+
+    context.plugin_driver.watch_change(&mut params, context)?;
+
+    for (path, update_type) in params.paths {
+      if !result.iter().any(|(p, _)| *p == path) {
+        result.push((path, update_type));
+      }
+    }
+  }
+
+  Ok(result)
+}
+
+/// Modules invalidated by `context.invalidate_module` are recompiled like the files are changed
+fn merge_invalidated_modules(
+  mut paths: Vec<(String, UpdateType)>,
+  context: &Arc<CompilationContext>,
+) -> Vec<(String, UpdateType)> {
+  let invalidated_modules = context.take_invalidated_modules();
+
+  if invalidated_modules.is_empty() {
+    return paths;
+  }
+
+  let module_graph = context.module_graph.read();
+
+  for (module_id, recompile_dependents) in invalidated_modules {
+    if !module_graph.has_module(&module_id) {
+      continue;
+    }
+
+    let mut module_ids = vec![module_id.clone()];
+
+    if recompile_dependents {
+      module_ids.extend(module_graph.dependents_ids(&module_id));
+    }
+
+    for module_id in module_ids {
+      context
+        .cache_manager
+        .module_cache
+        .invalidate_cache(&module_id);
+      let path = module_id.resolved_path_with_query(&context.config.root);
+
+      if !paths.iter().any(|(p, _)| *p == path) {
+        paths.push((path, UpdateType::Updated));
+      }
+    }
+  }
+
+  paths
+}
+
+pub(super) fn resolve_watch_graph_paths(
   paths: Vec<(String, UpdateType)>,
   context: &Arc<CompilationContext>,
 ) -> Vec<(String, UpdateType)> {
@@ -0,0 +1,110 @@
+use std::{
+  collections::{HashMap, HashSet},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  context::CompilationContext,
+  module::{module_group::ModuleGroupId, ModuleId, ModuleType},
+  regex::Regex,
+  resource::ResourceType,
+};
+use farmfe_toolkit::lazy_static::lazy_static;
+
+lazy_static! {
+  /// The tags that load the resources of the module groups, the inline scripts like `setDynamicModuleResourcesMap` are excluded
+  static ref INJECTED_RESOURCE_TAG: Regex =
+    Regex::new(r"<script\b[^>]*\bsrc=[^>]*>|<link\b[^>]*>").unwrap();
+}
+
+/// Collect the modules of the groups of the html entries
+pub fn html_entry_modules(
+  context: &Arc<CompilationContext>,
+) -> HashMap<ModuleGroupId, HashSet<ModuleId>> {
+  let module_graph = context.module_graph.read();
+  let module_group_graph = context.module_group_graph.read();
+
+  module_graph
+    .entries
+    .keys()
+    .filter(|entry| {
+      module_graph
+        .module(entry)
+        .is_some_and(|m| matches!(m.module_type, ModuleType::Html))
+    })
+    .filter_map(|entry| module_group_graph.module_group(entry))
+    .map(|group| (group.id.clone(), group.modules().clone()))
+    .collect()
+}
+
+/// Html entries inject the resources of their module groups, they may only change when modules are added to or removed from the groups.
+/// Other changes of the groups, like a new dynamic import, only update the inline dynamic resources map
+pub fn is_html_entry_affected(
+  previous_html_entry_modules: &HashMap<ModuleGroupId, HashSet<ModuleId>>,
+  affected_module_groups: &HashSet<ModuleGroupId>,
+  context: &Arc<CompilationContext>,
+) -> bool {
+  let module_group_graph = context.module_group_graph.read();
+
+  previous_html_entry_modules
+    .iter()
+    .filter(|(entry, _)| affected_module_groups.contains(*entry))
+    .any(|(entry, previous_modules)| {
+      module_group_graph
+        .module_group(entry)
+        .map_or(true, |group| group.modules() != previous_modules)
+    })
+}
+
+/// Collect the injected `<script src>` and `<link>` tags of each html resource
+pub fn html_resources(context: &Arc<CompilationContext>) -> HashMap<String, Vec<String>> {
+  context
+    .resources_map
+    .iter()
+    .filter(|r| matches!(r.resource_type, ResourceType::Html))
+    .map(|r| (r.name.clone(), injected_resource_tags(&r.bytes)))
+    .collect()
+}
+
+fn injected_resource_tags(bytes: &[u8]) -> Vec<String> {
+  let html = String::from_utf8_lossy(bytes);
+
+  INJECTED_RESOURCE_TAG
+    .find_iter(&html)
+    .map(|m| m.as_str().to_string())
+    .collect()
+}
+
+/// Return the names of the html resources that are added or whose injected tags changed since `previous_html_resources`.
+/// Changes of the inline scripts (e.g. the dynamic resources map) are updated by hmr and do not need a reload
+pub fn updated_html_resources(
+  previous_html_resources: HashMap<String, Vec<String>>,
+  context: &Arc<CompilationContext>,
+) -> Vec<String> {
+  let mut updated = html_resources(context)
+    .into_iter()
+    .filter(|(name, tags)| previous_html_resources.get(name) != Some(tags))
+    .map(|(name, _)| name)
+    .collect::<Vec<_>>();
+  updated.sort();
+
+  updated
+}
+
+#[cfg(test)]
+mod tests {
+  use super::injected_resource_tags;
+
+  #[test]
+  fn injected_resource_tags_ignore_inline_scripts() {
+    let html = r#"<html><head><link rel="stylesheet" href="/index.css"></head><body><script>window.__farm.setDynamicModuleResourcesMap([],{});</script><script src="/index.js"></script></body></html>"#;
+
+    assert_eq!(
+      injected_resource_tags(html.as_bytes()),
+      vec![
+        r#"<link rel="stylesheet" href="/index.css">"#.to_string(),
+        r#"<script src="/index.js">"#.to_string(),
+      ]
+    );
+  }
+}
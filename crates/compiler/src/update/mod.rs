@@ -1,20 +1,29 @@
 use std::{
   collections::{HashMap, HashSet},
   sync::Arc,
+  time::Instant,
 };
 
 use farmfe_core::{
   cache::module_cache::CachedModule,
-  context::CompilationContext,
+  context::{
+    update_schedule::{UpdateLockGuard, UpdateSchedule, UpdateSchedulePolicy},
+    CompilationContext, UpdatePriority,
+  },
   error::CompilationError,
-  module::{module_graph::ModuleGraphEdgeDataItem, module_group::ModuleGroupId, Module, ModuleId},
-  plugin::{PluginResolveHookParam, ResolveKind, UpdateResult, UpdateType},
-  resource::ResourceType,
+  module::{
+    module_graph::{ModuleGraph, ModuleGraphEdgeDataItem},
+    module_group::{ModuleGroupGraph, ModuleGroupId},
+    Module, ModuleId,
+  },
+  plugin::{PluginResolveHookParam, ResolveKind, ResolveTrace, UpdateResult, UpdateType},
+  resource::{resource_pot_map::ResourcePotMap, ResourceType},
   serde::Serialize,
   serde_json::{self, json},
   stats::CompilationPluginHookStats,
 };
 
+use farmfe_plugin_runtime::{AsyncModules, ASYNC_MODULES};
 use farmfe_toolkit::get_dynamic_resources_map::get_dynamic_resources_map;
 
 use crate::{
@@ -22,36 +31,51 @@ use crate::{
     module_cache::handle_cached_modules, BuildModuleGraphThreadedParams, HandleDependenciesParams,
     ResolvedModuleInfo,
   },
-  generate::finalize_resources::finalize_resources,
+  generate::{emit_resources::emit_resources, finalize_resources::finalize_resources},
   Compiler,
 };
 use farmfe_core::error::Result;
 
 use self::{
-  diff_and_patch_module_graph::{diff_module_graph, patch_module_graph, DiffResult},
+  diff_and_patch_module_graph::{diff_module_graph, patch_module_graph},
+  find_affected_workers::find_affected_workers,
   handle_update_modules::handle_update_modules,
+  html_resources::{
+    html_entry_modules, html_resources, is_html_entry_affected, updated_html_resources,
+  },
   module_cache::set_updated_modules_cache,
   patch_module_group_graph::patch_module_group_graph,
+  prune_removed_resources::{invalidate_derived_resources, prune_removed_resources},
+  rebuild_scope::full_rebuild_reason,
   regenerate_resources::{
     regenerate_resources_for_affected_module_groups, render_and_generate_update_resource,
   },
   update_context::UpdateContext,
 };
 
+mod compile_partial;
 mod diff_and_patch_module_graph;
+mod find_affected_workers;
 mod find_hmr_boundaries;
 mod handle_update_modules;
+mod html_resources;
 mod module_cache;
 mod patch_module_group_graph;
+mod plan_update;
+mod prune_removed_resources;
+mod rebuild_scope;
 mod regenerate_resources;
 mod update_context;
 
+pub use diff_and_patch_module_graph::{DiffResult, ModuleDepsDiffResult};
+pub use plan_update::UpdatePlan;
+
 enum ResolveModuleResult {
   Cached(ModuleId),
   /// This module is already in previous module graph before the update, and we met it again when resolving dependencies
-  ExistingBeforeUpdate(ModuleId),
+  ExistingBeforeUpdate(ModuleId, Option<ResolveTrace>),
   /// This module is added during the update, and we met it again when resolving dependencies
-  ExistingWhenUpdate(ModuleId),
+  ExistingWhenUpdate(ModuleId, Option<ResolveTrace>),
   /// This module is a full new resolved module, and we need to do the full building process
   Success(Box<ResolvedModuleInfo>),
 }
@@ -69,6 +93,9 @@ struct HandleUpdateDependenciesParams {
   update_context: Arc<UpdateContext>,
 }
 
+/// dynamically imported module -> (resource name, resource type) of the resources loaded for it
+type DynamicResourcesMap = HashMap<ModuleId, Vec<(String, ResourceType)>>;
+
 impl Compiler {
   fn set_update_module_graph_stats(&self, update_context: &Arc<UpdateContext>) {
     if self.context.config.record {
@@ -146,15 +173,61 @@ impl Compiler {
   where
     F: FnOnce() + Send + Sync + 'static,
   {
+    self.update_with_priority(
+      paths,
+      callback,
+      sync,
+      generate_update_resource,
+      UpdatePriority::Interactive,
+    )
+  }
+
+  /// Same as [Compiler::update], background updates yield to interactive updates at module boundaries
+  pub fn update_with_priority<F>(
+    &self,
+    paths: Vec<(String, UpdateType)>,
+    callback: F,
+    sync: bool,
+    generate_update_resource: bool,
+    priority: UpdatePriority,
+  ) -> Result<UpdateResult>
+  where
+    F: FnOnce() + Send + Sync + 'static,
+  {
+    let start = Instant::now();
+    let result = self.run_update(paths, callback, sync, generate_update_resource, priority);
+    self
+      .context
+      .metrics
+      .update_finished(start.elapsed(), result.is_ok());
+
+    result
+  }
+
+  fn run_update<F>(
+    &self,
+    paths: Vec<(String, UpdateType)>,
+    callback: F,
+    sync: bool,
+    generate_update_resource: bool,
+    priority: UpdatePriority,
+  ) -> Result<UpdateResult>
+  where
+    F: FnOnce() + Send + Sync + 'static,
+  {
+    let _interactive_update_guard =
+      (priority == UpdatePriority::Interactive).then(|| self.context.begin_interactive_update());
+    // a background update builds its modules before it takes the lock, so interactive updates preempt it until then
+    let update_lock = (priority == UpdatePriority::Interactive).then(|| self.lock_update());
+
     self.context.record_manager.add_hmr_compilation_stats();
     self.context.record_manager.set_start_time();
 
     // mark the compilation as update
     self.context.set_update();
-    let (err_sender, err_receiver) = Self::create_thread_channel();
-    let update_context = Arc::new(UpdateContext::new());
+    let update_context = Arc::new(UpdateContext::new(priority));
 
-    let mut old_watch_extra_resources: HashSet<ModuleId> = self
+    let old_watch_extra_resources: HashSet<ModuleId> = self
       .context
       .watch_graph
       .read()
@@ -167,53 +240,13 @@ impl Compiler {
     self.context.clear_log_store();
     let paths = handle_update_modules(paths, &self.context, &mut update_result)?;
 
-    for (path, update_type) in paths.clone() {
-      match update_type {
-        UpdateType::Added => {
-          return Err(farmfe_core::error::CompilationError::GenericError(
-            "Added is not supported yet".to_string(),
-          ));
-        }
-
-        UpdateType::Updated => {
-          let resolve_param = PluginResolveHookParam {
-            kind: ResolveKind::HmrUpdate,
-            source: path,
-            importer: None,
-          };
-
-          let params = BuildUpdateModuleGraphThreadedParams {
-            build_module_graph_threaded_params: BuildModuleGraphThreadedParams {
-              resolve_param,
-              context: self.context.clone(),
-              err_sender: err_sender.clone(),
-              thread_pool: self.thread_pool.clone(),
-              order: 0,
-              cached_dependency: None,
-            },
-            order: None,
-            update_context: update_context.clone(),
-          };
-
-          Self::update_module_graph_threaded(params);
-        }
-        UpdateType::Removed => {
-          return Err(farmfe_core::error::CompilationError::GenericError(
-            "Removed is not supported yet".to_string(),
-          ));
-        }
-      }
-    }
-
-    drop(err_sender);
-
-    let mut errors = vec![];
-
-    while let Ok(err) = err_receiver.recv() {
-      errors.push(err);
+    if let Some(reason) = full_rebuild_reason(&paths, &self.context) {
+      let _update_lock = update_lock.unwrap_or_else(|| self.lock_update());
+      return self.full_rebuild(reason, old_watch_extra_resources, update_result, callback);
     }
 
-    self.handle_global_log(&mut errors);
+    let errors = self.build_update_module_graph(&self.context, &paths, &update_context)?;
+    let mut update_lock = Some(update_lock.unwrap_or_else(|| self.lock_update()));
 
     if !errors.is_empty() {
       self.context.record_manager.set_build_end_time();
@@ -242,9 +275,11 @@ impl Compiler {
         .map(|m| m.id.clone())
         .collect::<HashSet<_>>()
     };
+    let previous_html_entry_modules = html_entry_modules(&self.context);
 
     let (affected_module_groups, updated_module_ids, diff_result, removed_modules) =
       self.diff_and_patch_context(paths, &update_context);
+    self.context.module_graph_snapshots.invalidate();
     // record graph patch result
     self.set_module_group_graph_stats();
 
@@ -253,6 +288,10 @@ impl Compiler {
       set_updated_modules_cache(&updated_module_ids, &diff_result, &self.context);
     }
 
+    // stale resources of the removed modules should not be served any more
+    update_result.removed_resources = prune_removed_resources(&removed_modules, &self.context);
+    invalidate_derived_resources(&updated_module_ids, &self.context);
+
     // call module graph updated hook
     self.context.plugin_driver.module_graph_updated(
       &farmfe_core::plugin::PluginModuleGraphUpdatedHookParams {
@@ -263,38 +302,75 @@ impl Compiler {
       &self.context,
     )?;
 
+    // modules whose asyncness is changed by the update, e.g. an importer of a module that adds top level await,
+    // are rendered and executed again like they are updated
+    let mut updated_module_ids = updated_module_ids;
+
+    if let Some(async_modules) = self.context.custom.get(ASYNC_MODULES) {
+      let async_modules = async_modules.downcast_ref::<AsyncModules>().unwrap();
+      let mut async_changed_modules = async_modules
+        .changed
+        .iter()
+        .filter(|id| !updated_module_ids.contains(*id) && !diff_result.added_modules.contains(*id))
+        .cloned()
+        .collect::<Vec<_>>();
+      async_changed_modules.sort();
+      updated_module_ids.extend(async_changed_modules);
+    }
+
+    let html_entry_affected = is_html_entry_affected(
+      &previous_html_entry_modules,
+      &affected_module_groups,
+      &self.context,
+    );
+    // workers are bundled as standalone resources, they must be regenerated before they are reloaded
+    update_result.updated_workers = find_affected_workers(&affected_module_groups, &self.context);
+    let has_new_module_group = affected_module_groups
+      .iter()
+      .any(|ag| !previous_module_groups.contains(ag));
+
+    let schedule = if sync {
+      UpdateSchedule::Sync
+    } else {
+      let policy = self.context.update_scheduler.policy();
+      let module_change = match priority {
+        UpdatePriority::Interactive => policy.module_change,
+        UpdatePriority::Background => policy.background,
+      };
+
+      [
+        (has_new_module_group, policy.new_module_group),
+        (html_entry_affected, policy.html_entry),
+        (!update_result.updated_workers.is_empty(), policy.worker),
+      ]
+      .into_iter()
+      .filter(|(triggered, _)| *triggered)
+      .map(|(_, schedule)| schedule)
+      .fold(module_change, std::cmp::max)
+    };
+
+    // the updated html can only be reported when the resources are regenerated synchronously
+    let previous_html_resources = (html_entry_affected && schedule == UpdateSchedule::Sync)
+      .then(|| html_resources(&self.context));
+
     let dynamic_resources_map = self.regenerate_resources(
       affected_module_groups,
-      previous_module_groups,
       &updated_module_ids,
       diff_result.clone(),
       removed_modules,
       callback,
-      sync,
-    );
-
-    // after update_module, diff old_resource and new_resource
-    {
-      let watch_graph = self.context.watch_graph.read();
-      let module_ids: HashSet<&ModuleId> = watch_graph.modules().into_iter().collect();
-
-      let watch_diff_result = &mut update_result.extra_watch_result;
-
-      for id in module_ids {
-        if !old_watch_extra_resources.remove(id) {
-          watch_diff_result
-            .add
-            .push(id.resolved_path(&self.context.config.root));
-        };
-      }
+      schedule,
+      &mut update_lock,
+    )?;
 
-      watch_diff_result.remove.extend(
-        old_watch_extra_resources
-          .into_iter()
-          .map(|r| r.resolved_path(&self.context.config.root)),
-      );
+    if let Some(previous_html_resources) = previous_html_resources {
+      update_result.updated_html_resources =
+        updated_html_resources(previous_html_resources, &self.context);
     }
 
+    // after update_module, diff old_resource and new_resource
+    self.diff_watch_files(old_watch_extra_resources, &mut update_result);
+
     // If the module type is not script, we should skip render and generate update resource.
     // and just return `window.location.reload()`
     let should_reload_page = updated_module_ids.iter().any(|id| {
@@ -303,16 +379,21 @@ impl Compiler {
       !module.module_type.is_script()
     });
 
-    let (immutable_resources, mutable_resources) = if should_reload_page {
-      ("window.location.reload()".to_string(), "{}".to_string())
+    let (immutable_resources, mutable_resources, css_updates) = if should_reload_page {
+      (
+        "window.location.reload()".to_string(),
+        "{}".to_string(),
+        HashMap::new(),
+      )
     } else if generate_update_resource {
       render_and_generate_update_resource(&updated_module_ids, &diff_result, &self.context)?
     } else {
-      ("{}".to_string(), "{}".to_string())
+      ("{}".to_string(), "{}".to_string(), HashMap::new())
     };
 
     // find the boundaries.
-    let boundaries = find_hmr_boundaries::find_hmr_boundaries(&updated_module_ids, &self.context);
+    let hmr_boundaries =
+      find_hmr_boundaries::find_hmr_boundaries(&updated_module_ids, &self.context);
 
     update_result
       .added_module_ids
@@ -323,11 +404,154 @@ impl Compiler {
       .extend(diff_result.removed_modules);
     update_result.immutable_resources = immutable_resources;
     update_result.mutable_resources = mutable_resources;
-    update_result.boundaries = boundaries;
+    update_result.css_updates = css_updates;
+    update_result.boundaries = hmr_boundaries.boundaries;
+    update_result.accepted_deps = hmr_boundaries.accepted_deps;
+    update_result.hmr_full_reload = hmr_boundaries.full_reload;
     update_result.dynamic_resources_map = dynamic_resources_map;
     Ok(update_result)
   }
 
+  /// Resolve, load and transform the updated modules and their new dependencies into the partial module graph of the update,
+  /// the errors of the modules are returned
+  fn build_update_module_graph(
+    &self,
+    context: &Arc<CompilationContext>,
+    paths: &[(String, UpdateType)],
+    update_context: &Arc<UpdateContext>,
+  ) -> Result<Vec<CompilationError>> {
+    let (err_sender, err_receiver) = Self::create_thread_channel();
+
+    for (path, update_type) in paths.iter().cloned() {
+      match update_type {
+        UpdateType::Added => {
+          return Err(farmfe_core::error::CompilationError::GenericError(
+            "Added is not supported yet".to_string(),
+          ));
+        }
+
+        UpdateType::Updated => {
+          let resolve_param = PluginResolveHookParam {
+            kind: ResolveKind::HmrUpdate,
+            source: path,
+            importer: None,
+          };
+
+          let params = BuildUpdateModuleGraphThreadedParams {
+            build_module_graph_threaded_params: BuildModuleGraphThreadedParams {
+              resolve_param,
+              context: context.clone(),
+              err_sender: err_sender.clone(),
+              thread_pool: self.thread_pool.clone(),
+              order: 0,
+              cached_dependency: None,
+              cached_resolve_trace: None,
+            },
+            order: None,
+            update_context: update_context.clone(),
+          };
+
+          Self::update_module_graph_threaded(params);
+        }
+        UpdateType::Removed => {
+          return Err(farmfe_core::error::CompilationError::GenericError(
+            "Removed is not supported yet".to_string(),
+          ));
+        }
+      }
+    }
+
+    drop(err_sender);
+
+    let mut errors = vec![];
+
+    while let Ok(err) = err_receiver.recv() {
+      errors.push(err);
+    }
+
+    Self::handle_global_log(context, &mut errors);
+
+    Ok(errors)
+  }
+
+  /// Compile the whole project again instead of patching the module graph, see [full_rebuild_reason].
+  /// The unchanged modules are restored from the persistent cache, and the page is reloaded to apply the new resources
+  fn full_rebuild<F>(
+    &self,
+    reason: String,
+    old_watch_extra_resources: HashSet<ModuleId>,
+    mut update_result: UpdateResult,
+    callback: F,
+  ) -> Result<UpdateResult>
+  where
+    F: FnOnce() + Send + Sync + 'static,
+  {
+    self.context.logger.info(
+      module_path!(),
+      format!("escalated the update to a full rebuild: {reason}"),
+    );
+
+    let previous_resources = self
+      .context
+      .resources_map
+      .iter()
+      .map(|r| r.key().clone())
+      .collect::<HashSet<_>>();
+
+    *self.context.module_graph.write() = ModuleGraph::new();
+    *self.context.module_group_graph.write() = ModuleGroupGraph::new();
+    *self.context.resource_pot_map.write() = ResourcePotMap::new();
+    self.context.resources_map.clear();
+    self.context.module_graph_snapshots.invalidate();
+
+    self.compile()?;
+    self.diff_watch_files(old_watch_extra_resources, &mut update_result);
+
+    {
+      let mut removed_resources = previous_resources
+        .into_iter()
+        .filter(|name| !self.context.resources_map.contains_key(name))
+        .collect::<Vec<_>>();
+      removed_resources.sort();
+      update_result.removed_resources = removed_resources;
+    }
+
+    callback();
+    self.context.plugin_driver.update_finished(&self.context)?;
+
+    update_result.immutable_resources = "window.location.reload()".to_string();
+    update_result.mutable_resources = "{}".to_string();
+    update_result.full_rebuild_reason = Some(reason);
+
+    Ok(update_result)
+  }
+
+  /// Watched files added and removed by the update
+  fn diff_watch_files(
+    &self,
+    mut old_watch_extra_resources: HashSet<ModuleId>,
+    update_result: &mut UpdateResult,
+  ) {
+    let watch_graph = self.context.watch_graph.read();
+    let module_ids: HashSet<&ModuleId> = watch_graph.modules().into_iter().collect();
+
+    let watch_diff_result = &mut update_result.extra_watch_result;
+
+    for id in module_ids {
+      if !old_watch_extra_resources.remove(id) {
+        watch_diff_result
+          .add
+          .push(id.resolved_path(&self.context.config.root));
+      };
+    }
+
+    watch_diff_result.remove.extend(
+      old_watch_extra_resources
+        .into_iter()
+        .map(|r| r.resolved_path(&self.context.config.root)),
+    );
+  }
+
   /// Resolving, loading, transforming and parsing a module in a separate thread.
   /// This method is similar to the build_module_graph_threaded method in the build/mod.rs file,
   /// the difference is that this method is used for updating the module graph, only handles the updated and added module, and ignores the existing unchanged module,
@@ -342,6 +566,7 @@ impl Compiler {
           thread_pool,
           order: _,
           cached_dependency,
+          cached_resolve_trace,
         },
       order,
       update_context,
@@ -349,6 +574,10 @@ impl Compiler {
     let c_thread_pool = thread_pool.clone();
 
     thread_pool.spawn(move || {
+      if update_context.priority == UpdatePriority::Background {
+        context.yield_to_interactive_updates();
+      }
+
       let resolve_module_result = match resolve_module(
         &resolve_param,
         cached_dependency,
@@ -376,6 +605,7 @@ impl Compiler {
             module: cached_module.module,
             resolve_param,
             order: order.unwrap_or(0),
+            resolve_trace: cached_resolve_trace,
             deps: CachedModule::dep_sources(cached_module.dependencies),
             thread_pool: c_thread_pool,
             err_sender,
@@ -390,7 +620,7 @@ impl Compiler {
 
           Self::handle_update_dependencies(params);
         }
-        ResolveModuleResult::ExistingBeforeUpdate(module_id) => {
+        ResolveModuleResult::ExistingBeforeUpdate(module_id, resolve_trace) => {
           // if the module does not exist, insert a placeholder module to the update module graph
           {
             let mut update_module_graph = update_context.module_graph.write();
@@ -400,10 +630,22 @@ impl Compiler {
               update_module_graph.add_module(module);
             }
           }
-          Self::add_edge_to_update_module_graph(&update_context, &resolve_param, &module_id, order);
+          Self::add_edge_to_update_module_graph(
+            &update_context,
+            &resolve_param,
+            &module_id,
+            order,
+            resolve_trace.or(cached_resolve_trace),
+          );
         }
-        ResolveModuleResult::ExistingWhenUpdate(module_id) => {
-          Self::add_edge_to_update_module_graph(&update_context, &resolve_param, &module_id, order);
+        ResolveModuleResult::ExistingWhenUpdate(module_id, resolve_trace) => {
+          Self::add_edge_to_update_module_graph(
+            &update_context,
+            &resolve_param,
+            &module_id,
+            order,
+            resolve_trace.or(cached_resolve_trace),
+          );
         }
         ResolveModuleResult::Success(box ResolvedModuleInfo {
           mut module,
@@ -412,6 +654,10 @@ impl Compiler {
           let mut graph_watch = context.watch_graph.write();
           graph_watch.delete_module(&module.id);
           drop(graph_watch);
+          let resolve_trace = resolve_module_id_result
+            .resolve_result
+            .resolve_trace
+            .clone();
 
           if resolve_module_id_result.resolve_result.external {
             // insert external module to the graph
@@ -425,14 +671,23 @@ impl Compiler {
               &resolve_param,
               &module_id,
               order,
+              resolve_trace,
             );
             return;
           }
 
-          match Self::build_module(
-            resolve_module_id_result.resolve_result,
-            &mut module,
-            &context,
+          let module_id = module.id.clone();
+          match context.catch_panic(
+            || format!("building module `{}`", module_id.to_string()),
+            || vec![module_id.clone()],
+            || {
+              Self::build_module(
+                resolve_module_id_result.resolve_result,
+                &resolve_param.kind,
+                &mut module,
+                &context,
+              )
+            },
           ) {
             Ok(deps) => {
               let params = HandleUpdateDependenciesParams {
@@ -444,6 +699,7 @@ impl Compiler {
                   module,
                   deps,
                   order: order.unwrap_or(0),
+                  resolve_trace,
                 },
                 order,
                 update_context,
@@ -469,6 +725,7 @@ impl Compiler {
           thread_pool,
           module,
           deps,
+          resolve_trace,
           ..
         },
       order,
@@ -478,9 +735,15 @@ impl Compiler {
     let module_id = module.id.clone();
     let immutable = module.immutable;
     Self::add_module_to_update_module_graph(&update_context, &resolve_param.kind, module);
-    Self::add_edge_to_update_module_graph(&update_context, &resolve_param, &module_id, order);
+    Self::add_edge_to_update_module_graph(
+      &update_context,
+      &resolve_param,
+      &module_id,
+      order,
+      resolve_trace,
+    );
 
-    for (order, (dep, cached_dependency)) in deps.into_iter().enumerate() {
+    for (order, (dep, cached_dependency, cached_resolve_trace)) in deps.into_iter().enumerate() {
       let params = BuildUpdateModuleGraphThreadedParams {
         build_module_graph_threaded_params: BuildModuleGraphThreadedParams {
           thread_pool: thread_pool.clone(),
@@ -493,6 +756,11 @@ impl Compiler {
           err_sender: err_sender.clone(),
           order,
           cached_dependency: if immutable { cached_dependency } else { None },
+          cached_resolve_trace: if immutable {
+            cached_resolve_trace
+          } else {
+            None
+          },
         },
         order: Some(order),
         update_context: update_context.clone(),
@@ -526,6 +794,7 @@ impl Compiler {
     resolve_param: &PluginResolveHookParam,
     module_id: &ModuleId,
     order: Option<usize>,
+    resolve_trace: Option<ResolveTrace>,
   ) {
     let mut update_module_graph = update_context.module_graph.write();
 
@@ -540,6 +809,7 @@ impl Compiler {
             kind: resolve_param.kind.clone(),
             source: resolve_param.source.clone(),
             order,
+            resolve_trace,
           },
         )
         .expect("Both the importer and the module should be in the update module graph");
@@ -563,7 +833,8 @@ impl Compiler {
       .collect();
     let mut module_graph = self.context.module_graph.write();
     let mut update_module_graph = update_context.module_graph.write();
-    update_module_graph.update_execution_order_for_modules();
+    update_module_graph
+      .update_execution_order_for_modules_by(self.context.config.execution_order_tie_break);
 
     let diff_result = diff_module_graph(start_points.clone(), &module_graph, &update_module_graph);
 
@@ -600,16 +871,45 @@ impl Compiler {
     )
   }
 
+  /// The policy that decides when the resources of the updates are regenerated, see [UpdateSchedulePolicy]
+  pub fn update_schedule_policy(&self) -> UpdateSchedulePolicy {
+    self.context.update_scheduler.policy()
+  }
+
+  pub fn set_update_schedule_policy(&self, policy: UpdateSchedulePolicy) {
+    self.context.update_scheduler.set_policy(policy);
+  }
+
+  /// Regenerate the resources of the [UpdateSchedule::Deferred] updates now, returns the number of the regenerated updates
+  pub fn flush_deferred_updates(&self) -> usize {
+    // called before the resources are served, skip the lock when there is nothing to flush
+    if !self.context.update_scheduler.has_deferred() {
+      return 0;
+    }
+
+    let _update_lock = self.context.update_lock.acquire();
+    self.context.update_scheduler.flush()
+  }
+
+  /// Wait for the running update, see [farmfe_core::context::update_schedule::UpdateLock]
+  fn lock_update(&self) -> UpdateLockGuard {
+    let update_lock = self.context.update_lock.acquire();
+    // the deferred regenerations of the previous updates go first, so the resources are regenerated in order
+    self.context.update_scheduler.flush();
+
+    update_lock
+  }
+
   fn regenerate_resources<F>(
     &self,
     affected_module_groups: HashSet<ModuleGroupId>,
-    previous_module_groups: HashSet<ModuleGroupId>,
     updated_module_ids: &Vec<ModuleId>,
     diff_result: DiffResult,
     removed_modules: HashMap<ModuleId, Module>,
     callback: F,
-    sync: bool,
-  ) -> Option<HashMap<ModuleId, Vec<(String, ResourceType)>>>
+    schedule: UpdateSchedule,
+    update_lock: &mut Option<UpdateLockGuard>,
+  ) -> Result<Option<DynamicResourcesMap>>
   where
     F: FnOnce() + Send + Sync + 'static,
   {
@@ -617,25 +917,19 @@ impl Compiler {
     let cloned_updated_module_ids = updated_module_ids.clone();
     let cloned_context = self.context.clone();
 
-    // if there are new module groups, we should run the tasks synchronously
-    if sync
-      || affected_module_groups
-        .iter()
-        .any(|ag| !previous_module_groups.contains(ag))
-    {
+    if schedule == UpdateSchedule::Sync {
       regenerate_resources_for_affected_module_groups(
         affected_module_groups,
         diff_result,
         &cloned_updated_module_ids,
         &removed_modules,
         &cloned_context,
-      )
-      .unwrap();
+      )?;
 
-      finalize_resources(&cloned_context).unwrap();
+      finalize_resources(&cloned_context)?;
+      emit_resources(&cloned_context, true)?;
       let module_group_graph = self.context.module_group_graph.read();
       let resource_pot_map = self.context.resource_pot_map.read();
-      let resources_map = self.context.resources_map.lock();
       let module_graph = self.context.module_graph.read();
 
       let mut dynamic_resources = HashMap::new();
@@ -645,21 +939,26 @@ impl Compiler {
           &module_group_graph,
           entry_id,
           &resource_pot_map,
-          &resources_map,
+          |name| {
+            self
+              .context
+              .resources_map
+              .get(name)
+              .map(|r| r.resource_type.clone())
+          },
           &module_graph,
         ));
       }
 
       dynamic_resources_map = Some(dynamic_resources);
       callback();
-      self
-        .context
-        .plugin_driver
-        .update_finished(&self.context)
-        .unwrap();
+      self.context.plugin_driver.update_finished(&self.context)?;
       self.context.record_manager.set_end_time();
+
+      #[cfg(feature = "lock_debug")]
+      farmfe_core::context::lock_tracker::print_lock_stats();
     } else {
-      std::thread::spawn(move || {
+      let regenerate = move || {
         if let Err(e) = regenerate_resources_for_affected_module_groups(
           affected_module_groups,
           diff_result,
@@ -667,21 +966,49 @@ impl Compiler {
           &removed_modules,
           &cloned_context,
         ) {
-          println!("Failed to regenerate resources: {e}");
-          println!("modules to regenerate: {cloned_updated_module_ids:?}");
+          cloned_context.logger.error(
+            module_path!(),
+            format!(
+              "Failed to regenerate resources: {e}, modules to regenerate: {cloned_updated_module_ids:?}"
+            ),
+          );
+        }
+
+        // the update is already returned, the errors can only be reported
+        if let Err(e) =
+          finalize_resources(&cloned_context).and_then(|_| emit_resources(&cloned_context, true))
+        {
+          cloned_context.logger.error(
+            module_path!(),
+            format!("Failed to emit the regenerated resources: {e}"),
+          );
         }
 
-        finalize_resources(&cloned_context).unwrap();
         callback();
         cloned_context
           .plugin_driver
           .update_finished(&cloned_context)
           .unwrap();
         cloned_context.record_manager.set_end_time();
-      });
+
+        #[cfg(feature = "lock_debug")]
+        farmfe_core::context::lock_tracker::print_lock_stats();
+      };
+
+      if schedule == UpdateSchedule::Deferred {
+        // deferred regenerations run under the lock of the update that flushes them
+        self.context.update_scheduler.defer(Box::new(regenerate));
+      } else {
+        // the lock is released when the resources are regenerated
+        let update_lock = update_lock.take();
+        std::thread::spawn(move || {
+          regenerate();
+          drop(update_lock);
+        });
+      }
     }
 
-    dynamic_resources_map
+    Ok(dynamic_resources_map)
   }
 }
 
@@ -700,6 +1027,9 @@ fn resolve_module(
     resolve_module_id_result = Some(Compiler::resolve_module_id(resolve_param, context)?);
     resolve_module_id_result.as_ref().unwrap().module_id.clone()
   };
+  let resolve_trace = resolve_module_id_result
+    .as_ref()
+    .and_then(|r| r.resolve_result.resolve_trace.clone());
 
   // if this is the root module, we should always rebuild it
   if is_root {
@@ -709,12 +1039,7 @@ fn resolve_module(
       module: Compiler::create_module(
         resolve_module_id_result.module_id.clone(),
         resolve_module_id_result.resolve_result.external,
-        context
-          .config
-          .partial_bundling
-          .immutable_modules
-          .iter()
-          .any(|im| im.is_match(&module_id.to_string())),
+        context.config.is_immutable_module(&module_id),
       ),
       resolve_module_id_result,
     })));
@@ -722,13 +1047,19 @@ fn resolve_module(
 
   let module_graph = context.module_graph.write();
   if module_graph.has_module(&module_id) {
-    return Ok(ResolveModuleResult::ExistingBeforeUpdate(module_id));
+    return Ok(ResolveModuleResult::ExistingBeforeUpdate(
+      module_id,
+      resolve_trace,
+    ));
   }
   drop(module_graph);
 
   let mut update_module_graph = update_context.module_graph.write();
   if update_module_graph.has_module(&module_id) {
-    return Ok(ResolveModuleResult::ExistingWhenUpdate(module_id));
+    return Ok(ResolveModuleResult::ExistingWhenUpdate(
+      module_id,
+      resolve_trace,
+    ));
   }
 
   if let Some(cached_dependency) = cached_dependency {
@@ -0,0 +1,129 @@
+//! Dry run of the update, the changed modules are compiled with an isolated context into the partial module graph of the update
+//! and diffed against a copy of the module graph, so editor integrations can preview the blast radius of a change and plugin authors
+//! can test their invalidation logic without touching the module graph, the watch graph, the caches and the resources of the compilation.
+
+use std::{collections::HashSet, sync::Arc};
+
+use farmfe_core::{
+  context::UpdatePriority,
+  error::{CompilationError, Result},
+  module::{module_group::ModuleGroupId, ModuleId},
+  plugin::UpdateType,
+  resource::resource_pot::ResourcePotId,
+  serde::Serialize,
+};
+
+use crate::Compiler;
+
+use super::{
+  diff_and_patch_module_graph::{diff_module_graph, patch_module_graph, DiffResult},
+  handle_update_modules::resolve_watch_graph_paths,
+  patch_module_group_graph::patch_module_group_graph,
+  rebuild_scope::full_rebuild_reason,
+  update_context::UpdateContext,
+};
+
+/// The changes [Compiler::update] would apply for the same paths
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase", crate = "farmfe_core::serde")]
+pub struct UpdatePlan {
+  pub updated_module_ids: Vec<ModuleId>,
+  pub diff_result: DiffResult,
+  pub affected_module_groups: Vec<ModuleGroupId>,
+  /// the resource pots that would be regenerated, including the resource pots of the removed modules
+  pub affected_resource_pots: Vec<ResourcePotId>,
+  /// why the update would be escalated to a full compilation, the diff is empty in this case, see `adaptiveRebuild`
+  pub full_rebuild_reason: Option<String>,
+}
+
+impl Compiler {
+  /// Plan the update of `paths` without applying it. The watch_change and update_modules hooks are not called
+  /// and the invalidated modules are kept, as they are only consumed by the real update
+  pub fn plan_update(&self, paths: Vec<(String, UpdateType)>) -> Result<UpdatePlan> {
+    let paths = resolve_watch_graph_paths(paths, &self.context);
+
+    if let Some(reason) = full_rebuild_reason(&paths, &self.context) {
+      return Ok(UpdatePlan {
+        full_rebuild_reason: Some(reason),
+        ..Default::default()
+      });
+    }
+
+    // the resolve, load and transform hooks add watch files, emit assets and fill the caches of the context they are called with,
+    // the existing modules are only checked by id, so the copy of the module graph is enough
+    let context = Arc::new(self.context.isolated());
+    *context.module_graph.write() = self.context.module_graph.read().snapshot();
+
+    let update_context = Arc::new(UpdateContext::new(UpdatePriority::Interactive));
+    let errors = self.build_update_module_graph(&context, &paths, &update_context)?;
+
+    if !errors.is_empty() {
+      let error_messages = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+      return Err(CompilationError::GenericError(
+        farmfe_core::serde_json::json!(error_messages).to_string(),
+      ));
+    }
+
+    let start_points: Vec<ModuleId> = paths
+      .into_iter()
+      .map(|path| ModuleId::from_resolved_path_with_query(&path.0, &self.context.config.root))
+      .collect();
+
+    let (mut module_graph, mut module_group_graph) = {
+      let module_graph = self.context.module_graph.read();
+      let module_group_graph = self.context.module_group_graph.read();
+      (module_graph.snapshot(), module_group_graph.clone())
+    };
+    // the updated modules are replaced by the modules of the update when patching, which are not in resource pots yet
+    let updated_resource_pots = start_points
+      .iter()
+      .filter_map(|id| module_graph.module(id)?.resource_pot.clone())
+      .collect::<Vec<_>>();
+    let mut update_module_graph = update_context.module_graph.write();
+    update_module_graph
+      .update_execution_order_for_modules_by(self.context.config.execution_order_tie_break);
+
+    let diff_result = diff_module_graph(start_points.clone(), &module_graph, &update_module_graph);
+    let removed_modules = patch_module_graph(
+      start_points.clone(),
+      &diff_result,
+      &mut module_graph,
+      &mut update_module_graph,
+    );
+    let affected_module_groups = patch_module_group_graph(
+      start_points.clone(),
+      &diff_result,
+      &removed_modules,
+      &mut module_graph,
+      &mut module_group_graph,
+    );
+
+    // the resource pots of the copy are the resource pots before the update, the new module groups have no resource pots yet
+    let mut affected_resource_pots = HashSet::new();
+
+    for module_group_id in &affected_module_groups {
+      if let Some(module_group) = module_group_graph.module_group(module_group_id) {
+        affected_resource_pots.extend(module_group.resource_pots().iter().cloned());
+      }
+    }
+
+    affected_resource_pots.extend(updated_resource_pots);
+
+    for module in removed_modules.values() {
+      affected_resource_pots.extend(module.resource_pot.clone());
+    }
+
+    let mut affected_module_groups = affected_module_groups.into_iter().collect::<Vec<_>>();
+    affected_module_groups.sort();
+    let mut affected_resource_pots = affected_resource_pots.into_iter().collect::<Vec<_>>();
+    affected_resource_pots.sort();
+
+    Ok(UpdatePlan {
+      updated_module_ids: start_points,
+      diff_result,
+      affected_module_groups,
+      affected_resource_pots,
+      full_rebuild_reason: None,
+    })
+  }
+}
@@ -0,0 +1,214 @@
+use std::{
+  collections::{HashMap, HashSet},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  context::CompilationContext,
+  module::{Module, ModuleId},
+  resource::ResourceOrigin,
+};
+
+/// Remove the resources of the removed modules, and the resource pots that only contain removed modules.
+/// Returns the names of the removed resources, so the runtime can prune them.
+pub fn prune_removed_resources(
+  removed_modules: &HashMap<ModuleId, Module>,
+  context: &Arc<CompilationContext>,
+) -> Vec<String> {
+  if removed_modules.is_empty() {
+    return vec![];
+  }
+
+  let mut resource_pot_map = context.resource_pot_map.write();
+  let mut module_group_graph = context.module_group_graph.write();
+  let resources_map = &context.resources_map;
+  let mut removed_resources = HashSet::new();
+
+  let empty_resource_pots = resource_pot_map
+    .resource_pots()
+    .into_iter()
+    .filter(|rp| {
+      let modules = rp.modules();
+      !modules.is_empty() && modules.iter().all(|m| removed_modules.contains_key(*m))
+    })
+    .map(|rp| rp.id.clone())
+    .collect::<Vec<_>>();
+
+  for resource_pot_id in empty_resource_pots {
+    let resource_pot = resource_pot_map
+      .remove_resource_pot(&resource_pot_id)
+      .unwrap();
+
+    for module_group in module_group_graph.module_groups_mut() {
+      module_group.remove_resource_pot(&resource_pot_id);
+    }
+
+    for resource in resource_pot.resources() {
+      resources_map.remove(resource);
+      removed_resources.insert(resource.clone());
+    }
+  }
+
+  // resources emitted by the removed modules, e.g. assets, or derived from them, e.g. a sprite sheet
+  resources_map.retain(|name, resource| {
+    let removed = resource
+      .origin
+      .modules()
+      .iter()
+      .any(|m| removed_modules.contains_key(m));
+
+    if removed {
+      removed_resources.insert(name.clone());
+    }
+
+    !removed
+  });
+
+  let mut removed_resources = removed_resources.into_iter().collect::<Vec<_>>();
+  removed_resources.sort();
+
+  removed_resources
+}
+
+/// Remove the resources derived from several modules when any of them is updated, they are generated again by the plugins
+/// that derive them when the resources are regenerated. The removed resources are not reported to the clients, as they are
+/// usually regenerated under the same names.
+pub fn invalidate_derived_resources(
+  updated_module_ids: &[ModuleId],
+  context: &Arc<CompilationContext>,
+) {
+  if updated_module_ids.is_empty() {
+    return;
+  }
+
+  let updated_module_ids = updated_module_ids.iter().collect::<HashSet<_>>();
+
+  context
+    .resources_map
+    .retain(|_, resource| match &resource.origin {
+      ResourceOrigin::Modules(module_ids) => {
+        !module_ids.iter().any(|m| updated_module_ids.contains(m))
+      }
+      _ => true,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::HashMap, sync::Arc};
+
+  use farmfe_core::{
+    context::CompilationContext,
+    module::{Module, ModuleId},
+    resource::{
+      resource_pot::{ResourcePot, ResourcePotType},
+      Resource, ResourceOrigin,
+    },
+  };
+
+  use super::{invalidate_derived_resources, prune_removed_resources};
+
+  #[test]
+  fn prune_resources_of_removed_modules() {
+    let context = Arc::new(CompilationContext::default());
+    let removed: ModuleId = "removed.ts".into();
+    let kept: ModuleId = "kept.ts".into();
+
+    {
+      let mut resource_pot_map = context.resource_pot_map.write();
+      let resources_map = &context.resources_map;
+
+      for (name, modules) in [("removed", vec![&removed]), ("kept", vec![&removed, &kept])] {
+        let mut resource_pot = ResourcePot::new(name.to_string(), ResourcePotType::Js);
+        modules
+          .into_iter()
+          .for_each(|m| resource_pot.add_module(m.clone()));
+        resource_pot.add_resource(format!("{name}.js"));
+        resources_map.insert(
+          format!("{name}.js"),
+          Resource {
+            name: format!("{name}.js"),
+            origin: ResourceOrigin::ResourcePot(resource_pot.id.clone()),
+            ..Default::default()
+          },
+        );
+        resource_pot_map.add_resource_pot(resource_pot);
+      }
+
+      resources_map.insert(
+        "logo.png".to_string(),
+        Resource {
+          name: "logo.png".to_string(),
+          origin: ResourceOrigin::Module(removed.clone()),
+          ..Default::default()
+        },
+      );
+      resources_map.insert(
+        "sprite.png".to_string(),
+        Resource {
+          name: "sprite.png".to_string(),
+          origin: ResourceOrigin::Modules(vec![kept.clone(), removed.clone()]),
+          ..Default::default()
+        },
+      );
+    }
+
+    let removed_modules = HashMap::from([(removed.clone(), Module::new(removed))]);
+
+    assert_eq!(
+      prune_removed_resources(&removed_modules, &context),
+      vec![
+        "logo.png".to_string(),
+        "removed.js".to_string(),
+        "sprite.png".to_string()
+      ]
+    );
+    assert_eq!(context.resource_pot_map.read().resource_pots().len(), 1);
+    assert_eq!(
+      context
+        .resources_map
+        .iter()
+        .map(|r| r.key().clone())
+        .collect::<Vec<_>>(),
+      vec!["kept.js"]
+    );
+  }
+
+  #[test]
+  fn invalidate_resources_derived_from_updated_modules() {
+    let context = Arc::new(CompilationContext::default());
+    let updated: ModuleId = "icons/a.svg".into();
+
+    {
+      let resources_map = &context.resources_map;
+
+      for (name, origin) in [
+        (
+          "sprite.svg",
+          ResourceOrigin::Modules(vec!["icons/b.svg".into(), updated.clone()]),
+        ),
+        ("a.svg", ResourceOrigin::Module(updated.clone())),
+      ] {
+        resources_map.insert(
+          name.to_string(),
+          Resource {
+            name: name.to_string(),
+            origin,
+            ..Default::default()
+          },
+        );
+      }
+    }
+
+    invalidate_derived_resources(&[updated], &context);
+
+    assert_eq!(
+      context
+        .resources_map
+        .iter()
+        .map(|r| r.key().clone())
+        .collect::<Vec<_>>(),
+      vec!["a.svg"]
+    );
+  }
+}
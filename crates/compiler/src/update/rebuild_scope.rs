@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use farmfe_core::{context::CompilationContext, plugin::UpdateType};
+
+/// Why the update should be escalated to a full compilation, [None] if it should be updated incrementally.
+/// Updates changing more modules than `adaptiveRebuild.threshold` of the module graph are escalated, e.g. after switching branches
+pub fn full_rebuild_reason(
+  paths: &[(String, UpdateType)],
+  context: &Arc<CompilationContext>,
+) -> Option<String> {
+  let config = context.config.adaptive_rebuild.as_ref()?;
+  let total = context.module_graph.read().modules().len();
+  let changed = paths.len();
+
+  if total == 0 || changed < config.min_changed_modules {
+    return None;
+  }
+
+  let ratio = changed as f64 / total as f64;
+
+  (ratio > config.threshold).then(|| {
+    format!(
+      "{changed} of {total} modules changed ({:.0}%), more than the threshold {:.0}%",
+      ratio * 100.0,
+      config.threshold * 100.0
+    )
+  })
+}
@@ -38,7 +38,7 @@ pub fn generate_and_diff_resource_pots(
     updated_module_ids,
     removed_modules,
     context,
-  );
+  )?;
 
   // for enforce resource pots, only rerender it when it's modules are changed, added or removed.
   let mut resources_pots =
@@ -102,58 +102,57 @@ fn handle_enforce_resource_pots(
   updated_module_ids: &Vec<ModuleId>,
   removed_modules: &HashMap<ModuleId, Module>,
   context: &Arc<CompilationContext>,
-) -> (Vec<ResourcePotId>, Vec<ModuleId>) {
+) -> farmfe_core::error::Result<(Vec<ResourcePotId>, Vec<ModuleId>)> {
   let module_graph = context.module_graph.read();
   let mut resource_pot_map = context.resource_pot_map.write();
   let mut un_enforced_modules = HashSet::new();
   let mut affected_resource_pot_ids = HashSet::new();
 
-  let is_module_external = |module_id: &ModuleId| {
-    let module = if let Some(module) = removed_modules.get(module_id) {
+  let get_module = |module_id: &ModuleId| {
+    if let Some(module) = removed_modules.get(module_id) {
       module
     } else {
       module_graph.module(module_id).unwrap()
-    };
-
-    module.external
+    }
   };
+  let is_module_external = |module_id: &ModuleId| get_module(module_id).external;
+
+  let mut handle_changed_modules =
+    |module_ids: &HashSet<ModuleId>, ty: ChangedModuleType| -> farmfe_core::error::Result<()> {
+      for module_id in module_ids {
+        // ignore external module
+        if is_module_external(module_id) {
+          continue;
+        }
 
-  let mut handle_changed_modules = |module_ids: &HashSet<ModuleId>, ty: ChangedModuleType| {
-    for module_id in module_ids {
-      // ignore external module
-      if is_module_external(module_id) {
-        continue;
-      }
-
-      if let Some(name) = get_enforce_resource_name_for_module(
-        module_id,
-        &context.config.partial_bundling.enforce_resources,
-      ) {
-        let (resource_pot_type, resource_pot_name, resource_pot_id) =
-          if ty != ChangedModuleType::Removed {
-            get_resource_pot_id_for_enforce_resources(name, module_id, &module_graph)
-          } else {
-            let module = removed_modules.get(module_id).unwrap_or_else(|| {
-              panic!("can not find module {module_id:?}");
-            });
-            get_resource_pot_id_for_enforce_resources_by_removed_module(name, module)
-          };
-        affected_resource_pot_ids.insert(resource_pot_id.clone());
-
-        if let Some(resource_pot) = resource_pot_map.resource_pot_mut(&resource_pot_id) {
-          if ty == ChangedModuleType::Added {
+        if let Some(name) = get_enforce_resource_name_for_module(get_module(module_id), context)? {
+          let (resource_pot_type, resource_pot_name, resource_pot_id) =
+            if ty != ChangedModuleType::Removed {
+              get_resource_pot_id_for_enforce_resources(name, module_id, &module_graph)
+            } else {
+              let module = removed_modules.get(module_id).unwrap_or_else(|| {
+                panic!("can not find module {module_id:?}");
+              });
+              get_resource_pot_id_for_enforce_resources_by_removed_module(name, module)
+            };
+          affected_resource_pot_ids.insert(resource_pot_id.clone());
+
+          if let Some(resource_pot) = resource_pot_map.resource_pot_mut(&resource_pot_id) {
+            if ty == ChangedModuleType::Added {
+              resource_pot.add_module(module_id.clone());
+            } else if ty == ChangedModuleType::Removed {
+              resource_pot.remove_module(module_id);
+            }
+          } else if ty != ChangedModuleType::Removed {
+            let mut resource_pot = ResourcePot::new(resource_pot_name, resource_pot_type);
             resource_pot.add_module(module_id.clone());
-          } else if ty == ChangedModuleType::Removed {
-            resource_pot.remove_module(module_id);
+            resource_pot_map.add_resource_pot(resource_pot);
           }
-        } else if ty != ChangedModuleType::Removed {
-          let mut resource_pot = ResourcePot::new(resource_pot_name, resource_pot_type);
-          resource_pot.add_module(module_id.clone());
-          resource_pot_map.add_resource_pot(resource_pot);
         }
       }
-    }
-  };
+
+      Ok(())
+    };
 
   handle_changed_modules(
     &updated_module_ids
@@ -161,9 +160,9 @@ fn handle_enforce_resource_pots(
       .into_iter()
       .collect::<HashSet<_>>(),
     ChangedModuleType::Updated,
-  );
-  handle_changed_modules(&diff_result.added_modules, ChangedModuleType::Added);
-  handle_changed_modules(&diff_result.removed_modules, ChangedModuleType::Removed);
+  )?;
+  handle_changed_modules(&diff_result.added_modules, ChangedModuleType::Added)?;
+  handle_changed_modules(&diff_result.removed_modules, ChangedModuleType::Removed)?;
 
   // Filter out the modules that are not in any enforce resource pot
   for module_id in affected_modules {
@@ -171,10 +170,7 @@ fn handle_enforce_resource_pots(
       continue;
     }
 
-    if let Some(name) = get_enforce_resource_name_for_module(
-      module_id,
-      &context.config.partial_bundling.enforce_resources,
-    ) {
+    if let Some(name) = get_enforce_resource_name_for_module(get_module(module_id), context)? {
       let (_, _, resource_pot_id) =
         get_resource_pot_id_for_enforce_resources(name, module_id, &module_graph);
 
@@ -186,24 +182,32 @@ fn handle_enforce_resource_pots(
     }
   }
 
-  // remove the resource pot if it's modules are empty
-  for id in &affected_resource_pot_ids {
-    let resource_pot = resource_pot_map.resource_pot_mut(id).unwrap_or_else(|| {
-      panic!("resource pot not found: {id:?}");
-    });
+  let mut removed_resources = vec![];
+  // remove the resource pot if it's modules are empty, the resource pot may be pruned already when all its modules are removed
+  affected_resource_pot_ids.retain(|id| {
+    let Some(resource_pot) = resource_pot_map.resource_pot(id) else {
+      return false;
+    };
 
-    if resource_pot.modules().is_empty() {
-      resource_pot_map.remove_resource_pot(id);
+    if !resource_pot.modules().is_empty() {
+      return true;
     }
-  }
+
+    let resource_pot = resource_pot_map.remove_resource_pot(id).unwrap();
+    removed_resources.extend(resource_pot.resources().into_iter().cloned());
+
+    false
+  });
+
+  remove_resources(&removed_resources, context);
 
   let mut modules = un_enforced_modules.into_iter().collect::<Vec<_>>();
   modules.sort();
 
-  (
+  Ok((
     affected_resource_pot_ids.into_iter().collect::<Vec<_>>(),
     modules,
-  )
+  ))
 }
 
 fn diff_and_patch_resource_pot_map(
@@ -221,6 +225,7 @@ fn diff_and_patch_resource_pot_map(
   let mut module_group_graph = context.module_group_graph.write();
 
   let mut new_resource_pot_ids = HashSet::new();
+  let mut removed_resources = vec![];
 
   for mut resource_pot in resources_pots {
     let mut module_groups = HashSet::new();
@@ -253,11 +258,7 @@ fn diff_and_patch_resource_pot_map(
               });
 
             // also remove the related resource
-            let mut resource_maps = context.resources_map.lock();
-
-            for resource in resource_pot.resources() {
-              resource_maps.remove(resource);
-            }
+            removed_resources.extend(resource_pot.resources().into_iter().cloned());
           }
         }
       }
@@ -281,9 +282,18 @@ fn diff_and_patch_resource_pot_map(
     }
   }
 
+  remove_resources(&removed_resources, context);
+
   new_resource_pot_ids
 }
 
+/// Remove the resources of the removed resource pots
+pub(super) fn remove_resources(resources: &[String], context: &Arc<CompilationContext>) {
+  for resource in resources {
+    context.resources_map.remove(resource);
+  }
+}
+
 #[cfg(test)]
 mod test_handle_enforce_resource_pots;
 
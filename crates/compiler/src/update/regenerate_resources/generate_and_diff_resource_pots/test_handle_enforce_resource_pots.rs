@@ -126,7 +126,8 @@ fn test_handle_enforce_resource_pots() {
     &updated_modules,
     &removed_modules,
     &context,
-  );
+  )
+  .unwrap();
 
   assert_eq!(
     enforce_resource_pots,
@@ -235,8 +236,97 @@ fn test_handle_enforce_resource_pots_one_module_changed() {
     &updated_modules,
     &removed_modules,
     &context,
-  );
+  )
+  .unwrap();
 
   assert!(enforce_resource_pots.is_empty());
   assert_eq!(un_enforce_resource_pots, vec!["I".into()]);
 }
+
+struct PinModulesPlugin;
+
+impl Plugin for PinModulesPlugin {
+  fn name(&self) -> &str {
+    "PinModulesPlugin"
+  }
+
+  fn partition_resource_pots(
+    &self,
+    module: &Module,
+    _context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<Option<String>> {
+    Ok(
+      ["F", "H"]
+        .contains(&module.id.to_string().as_str())
+        .then(|| "pinned".to_string()),
+    )
+  }
+}
+
+/// modules pinned by the partition_resource_pots hook should stay in their resource pot when updating
+#[test]
+fn test_handle_enforce_resource_pots_partition_hook() {
+  let mut module_graph = construct_test_module_graph_complex();
+  let mut update_module_graph = construct_test_module_graph_complex();
+  let mut module_group_graph = module_group_graph_from_entries(
+    &module_graph.entries.clone().into_keys().collect(),
+    &mut module_graph,
+  );
+  let updated_modules = vec!["F".into(), "E".into()];
+  let diff_result = diff_module_graph(updated_modules.clone(), &module_graph, &update_module_graph);
+  let removed_modules = patch_module_graph(
+    updated_modules.clone(),
+    &diff_result,
+    &mut module_graph,
+    &mut update_module_graph,
+  );
+  let affected_modules = module_group_graph
+    .module_groups()
+    .into_iter()
+    .flat_map(|group| group.modules().clone())
+    .collect::<HashSet<_>>()
+    .into_iter()
+    .collect::<Vec<_>>();
+  patch_module_group_graph(
+    updated_modules.clone(),
+    &diff_result,
+    &removed_modules,
+    &mut module_graph,
+    &mut module_group_graph,
+  );
+
+  let config = Config::default();
+  let plugins: Vec<Arc<dyn Plugin + 'static>> = vec![
+    Arc::new(farmfe_plugin_partial_bundling::FarmPluginPartialBundling::new(&config)),
+    Arc::new(PinModulesPlugin),
+  ];
+  let context = Arc::new(CompilationContext::new(config, plugins).unwrap());
+  *context.module_graph.write() = module_graph;
+  *context.module_group_graph.write() = module_group_graph;
+
+  let resource_pot_map =
+    generate_resource_pot_map(&context, &PluginHookContext::default()).unwrap();
+  context.resource_pot_map.write().replace(resource_pot_map);
+
+  let (enforce_resource_pots, un_enforce_resource_pots) = handle_enforce_resource_pots(
+    &affected_modules,
+    &diff_result,
+    &updated_modules,
+    &removed_modules,
+    &context,
+  )
+  .unwrap();
+
+  assert_eq!(
+    enforce_resource_pots,
+    vec!["pinned_custom(\"__farm_unknown\")".to_string()]
+  );
+  assert!(!un_enforce_resource_pots.contains(&"F".into()));
+  assert!(!un_enforce_resource_pots.contains(&"H".into()));
+
+  let resource_pot_map = context.resource_pot_map.read();
+  let pinned = resource_pot_map
+    .resource_pot(&"pinned_custom(\"__farm_unknown\")".to_string())
+    .unwrap();
+  assert_eq!(pinned.modules(), vec![&"F".into(), &"H".into()]);
+}
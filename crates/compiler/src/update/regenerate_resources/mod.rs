@@ -6,15 +6,20 @@ use farmfe_core::{
   enhanced_magic_string::types::SourceMapOptions,
   error::CompilationError,
   module::{module_group::ModuleGroupId, Module, ModuleId},
-  resource::resource_pot::{ResourcePot, ResourcePotMetaData, ResourcePotType},
+  rayon,
+  resource::{
+    resource_pot::{ResourcePot, ResourcePotId, ResourcePotMetaData, ResourcePotType},
+    resource_pot_map::ResourcePotMap,
+  },
 };
 
+use farmfe_plugin_css::transform_css_to_script::{CssHmrStyles, CSS_HMR_STYLES};
 use farmfe_plugin_runtime::render_resource_pot::{
+  rendered_module_cache::{RenderedModuleCache, RENDERED_MODULE_CACHE},
   resource_pot_to_runtime_object, RenderedJsResourcePot,
 };
-use farmfe_plugin_runtime::ASYNC_MODULES;
-use farmfe_toolkit::hash::base64_encode;
-use farmfe_utils::relative;
+use farmfe_plugin_runtime::{AsyncModules, ASYNC_MODULES};
+use farmfe_toolkit::{common::SourcemapSources, hash::base64_encode};
 
 use crate::{
   generate::render_resource_pots::{
@@ -27,13 +32,13 @@ use super::diff_and_patch_module_graph::DiffResult;
 
 mod generate_and_diff_resource_pots;
 
-use generate_and_diff_resource_pots::generate_and_diff_resource_pots;
+use generate_and_diff_resource_pots::{generate_and_diff_resource_pots, remove_resources};
 
 pub fn render_and_generate_update_resource(
   updated_module_ids: &Vec<ModuleId>,
   diff_result: &DiffResult,
   context: &Arc<CompilationContext>,
-) -> farmfe_core::error::Result<(String, String)> {
+) -> farmfe_core::error::Result<(String, String, HashMap<ModuleId, String>)> {
   let mut immutable_update_resource_pot = ResourcePot::new(
     String::from("__IMMUTABLE_UPDATE_RESOURCE_POT__"),
     ResourcePotType::Js,
@@ -46,6 +51,20 @@ pub fn render_and_generate_update_resource(
   );
   mutable_update_resource_pot.immutable = false;
 
+  // the cache lives across updates, so modules that are not changed since the last update are not rendered again
+  let rendered_module_cache = context
+    .custom
+    .entry(RENDERED_MODULE_CACHE.to_string())
+    .or_insert_with(|| Box::<RenderedModuleCache>::default())
+    .downgrade();
+  let rendered_module_cache = rendered_module_cache
+    .downcast_ref::<RenderedModuleCache>()
+    .unwrap();
+
+  for removed in &diff_result.removed_modules {
+    rendered_module_cache.remove(removed);
+  }
+
   let module_graph = context.module_graph.read();
 
   for added in &diff_result.added_modules {
@@ -79,24 +98,33 @@ pub fn render_and_generate_update_resource(
   let gen_resource_pot_code =
     |resource_pot: &mut ResourcePot| -> farmfe_core::error::Result<String> {
       let async_modules = context.custom.get(ASYNC_MODULES).unwrap();
-      let async_modules = async_modules.downcast_ref::<HashSet<ModuleId>>().unwrap();
+      let async_modules = &async_modules
+        .downcast_ref::<AsyncModules>()
+        .unwrap()
+        .modules;
       if !resource_pot.modules().is_empty() {
         let RenderedJsResourcePot {
           mut bundle,
           rendered_modules,
           ..
-        } = resource_pot_to_runtime_object(resource_pot, &module_graph, async_modules, context)?;
+        } = resource_pot_to_runtime_object(
+          resource_pot,
+          &module_graph,
+          async_modules,
+          Some(rendered_module_cache),
+          context,
+        )?;
         bundle.prepend("(");
         bundle.append(")", None);
 
         let mut rendered_map_chain = vec![];
 
         if context.config.sourcemap.enabled(resource_pot.immutable) {
-          let root = context.config.root.clone();
+          let sources = SourcemapSources::new(&context.config);
           let map = bundle
             .generate_map(SourceMapOptions {
               include_content: Some(true),
-              remap_source: Some(Box::new(move |src| format!("/{}", relative(&root, src)))),
+              remap_source: Some(Box::new(move |src| sources.remap(src))),
               ..Default::default()
             })
             .map_err(|_| CompilationError::GenerateSourceMapError {
@@ -142,10 +170,39 @@ pub fn render_and_generate_update_resource(
       Ok("{}".to_string())
     };
 
-  let immutable_update_resource = gen_resource_pot_code(&mut immutable_update_resource_pot)?;
-  let mutable_update_resource = gen_resource_pot_code(&mut mutable_update_resource_pot)?;
+  // the update resource pots are independent, render them in parallel
+  let (immutable_update_resource, mutable_update_resource) = rayon::join(
+    || gen_resource_pot_code(&mut immutable_update_resource_pot),
+    || gen_resource_pot_code(&mut mutable_update_resource_pot),
+  );
+  let immutable_update_resource = immutable_update_resource?;
+  let mutable_update_resource = mutable_update_resource?;
+  let css_updates = css_updates(updated_module_ids, diff_result, context);
+
+  Ok((
+    immutable_update_resource,
+    mutable_update_resource,
+    css_updates,
+  ))
+}
 
-  Ok((immutable_update_resource, mutable_update_resource))
+/// Css text of the updated css modules, the runtime replaces their styles in place and does not re-execute them.
+/// Modules whose dependencies changed are excluded, their `@import`s have to be loaded by executing the module
+fn css_updates(
+  updated_module_ids: &[ModuleId],
+  diff_result: &DiffResult,
+  context: &Arc<CompilationContext>,
+) -> HashMap<ModuleId, String> {
+  let Some(styles) = context.custom.get(CSS_HMR_STYLES) else {
+    return HashMap::new();
+  };
+  let styles = styles.downcast_ref::<CssHmrStyles>().unwrap();
+
+  updated_module_ids
+    .iter()
+    .filter(|id| !diff_result.deps_changes.iter().any(|(m, _)| m == *id))
+    .filter_map(|id| styles.get(id).map(|css| (id.clone(), css)))
+    .collect()
 }
 
 pub fn regenerate_resources_for_affected_module_groups(
@@ -158,7 +215,7 @@ pub fn regenerate_resources_for_affected_module_groups(
   // if there are deps changes, update execution order
   {
     let mut module_graph = context.module_graph.write();
-    module_graph.update_execution_order_for_modules();
+    module_graph.update_execution_order_for_modules_by(context.config.execution_order_tie_break);
   }
 
   // skip diff resource pots if diff_result is empty
@@ -181,6 +238,7 @@ pub fn regenerate_resources_for_affected_module_groups(
   let mut resource_pot_map = context.resource_pot_map.write();
   // always rerender the updated module's resource pot
   let module_graph = context.module_graph.read();
+  let mut removed_resources = vec![];
 
   for updated_module_id in updated_module_ids {
     let module = module_graph.module(updated_module_id).unwrap();
@@ -191,23 +249,21 @@ pub fn regenerate_resources_for_affected_module_groups(
     }
 
     // also remove the related resources, the resources will be regenerated later
-    let mut resource_maps = context.resources_map.lock();
     let resource_pot = resource_pot_map.resource_pot_mut(resource_pot_id).unwrap();
-
-    for resource in resource_pot.resources() {
-      resource_maps.remove(resource);
-    }
-
+    removed_resources.extend(resource_pot.resources().into_iter().cloned());
     resource_pot.clear_resources();
   }
 
-  let mut resource_pots = resource_pot_map
-    .resource_pots_mut()
-    .into_iter()
-    .filter(|rp| affected_resource_pots_ids.contains(&rp.id))
-    .collect::<Vec<&mut ResourcePot>>();
+  remove_resources(&removed_resources, context);
+
+  // render a snapshot of the affected resource pots, so the module graph and resource pot map
+  // are not locked while rendering and the dev server can still query them
+  let mut snapshot = snapshot_resource_pots(&resource_pot_map, &affected_resource_pots_ids);
 
   drop(module_graph);
+  drop(resource_pot_map);
+
+  let mut resource_pots = snapshot.iter_mut().collect::<Vec<&mut ResourcePot>>();
 
   // call process_resource_pot_map hook
   context
@@ -216,12 +272,20 @@ pub fn regenerate_resources_for_affected_module_groups(
 
   render_resource_pots_and_generate_resources(resource_pots, context, &Default::default())?;
 
+  let mut resource_pot_map = context.resource_pot_map.write();
+  let stale_resources = write_back_rendered_resource_pots(&mut resource_pot_map, snapshot);
+  drop(resource_pot_map);
+
+  remove_resources(&stale_resources, context);
+
   if context.config.persistent_cache.enabled() {
     context
       .plugin_driver
       .write_plugin_cache(context)
       .unwrap_or_else(|err| {
-        eprintln!("write plugin cache error: {err:?}");
+        context
+          .logger
+          .error(module_path!(), format!("write plugin cache error: {err:?}"));
       });
 
     write_cache(context.clone());
@@ -230,6 +294,41 @@ pub fn regenerate_resources_for_affected_module_groups(
   Ok(())
 }
 
+fn snapshot_resource_pots(
+  resource_pot_map: &ResourcePotMap,
+  resource_pot_ids: &[ResourcePotId],
+) -> Vec<ResourcePot> {
+  resource_pot_ids
+    .iter()
+    .filter_map(|id| resource_pot_map.resource_pot(id).cloned())
+    .collect()
+}
+
+/// Write the rendered meta and resources of the snapshot back to the resource pot map. The other fields of the resource pots
+/// may be changed while rendering, e.g. modules moved by another update, so they are kept as is.
+/// Returns the resources of the resource pots that are removed while rendering, they are stale
+fn write_back_rendered_resource_pots(
+  resource_pot_map: &mut ResourcePotMap,
+  rendered: Vec<ResourcePot>,
+) -> Vec<String> {
+  let mut stale_resources = vec![];
+
+  for mut rendered in rendered {
+    match resource_pot_map.resource_pot_mut(&rendered.id) {
+      Some(resource_pot) => {
+        resource_pot.meta = rendered.take_meta();
+
+        for resource in rendered.resources() {
+          resource_pot.add_resource(resource.clone());
+        }
+      }
+      None => stale_resources.extend(rendered.resources().into_iter().cloned()),
+    }
+  }
+
+  stale_resources
+}
+
 fn clear_resource_pot_of_modules_in_module_groups(
   module_group_id: &HashSet<ModuleGroupId>,
   context: &Arc<CompilationContext>,
@@ -245,3 +344,6 @@ fn clear_resource_pot_of_modules_in_module_groups(
     }
   }
 }
+
+#[cfg(test)]
+mod test_write_back_rendered_resource_pots;
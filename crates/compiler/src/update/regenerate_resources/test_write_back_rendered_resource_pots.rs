@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use farmfe_core::resource::{
+  resource_pot::{ResourcePot, ResourcePotType},
+  resource_pot_map::ResourcePotMap,
+};
+
+use super::{snapshot_resource_pots, write_back_rendered_resource_pots};
+
+#[test]
+fn test_write_back_rendered_resource_pots() {
+  let mut resource_pot_map = ResourcePotMap::new();
+  let mut index = ResourcePot::new("index".to_string(), ResourcePotType::Js);
+  index.add_module("a".into());
+  resource_pot_map.add_resource_pot(index);
+  resource_pot_map.add_resource_pot(ResourcePot::new("removed".to_string(), ResourcePotType::Js));
+
+  let ids = resource_pot_map
+    .resource_pots()
+    .into_iter()
+    .map(|resource_pot| resource_pot.id.clone())
+    .collect::<Vec<_>>();
+  let mut snapshot = snapshot_resource_pots(&resource_pot_map, &ids);
+
+  for resource_pot in &mut snapshot {
+    resource_pot.meta.rendered_content = Arc::new(format!("rendered {}", resource_pot.name));
+    resource_pot.add_resource(format!("{}.js", resource_pot.name));
+  }
+
+  // the resource pots are changed by others while the snapshot is rendered
+  let index_id = ResourcePot::gen_id("index", ResourcePotType::Js);
+  let index = resource_pot_map.resource_pot_mut(&index_id).unwrap();
+  index.add_module("b".into());
+  index.add_resource("index.css".to_string());
+  resource_pot_map.remove_resource_pot(&ResourcePot::gen_id("removed", ResourcePotType::Js));
+
+  let stale_resources = write_back_rendered_resource_pots(&mut resource_pot_map, snapshot);
+
+  let index = resource_pot_map.resource_pot(&index_id).unwrap();
+  let mut modules = index.modules();
+  modules.sort();
+  assert_eq!(modules, vec![&"a".into(), &"b".into()]);
+  let mut resources = index.resources();
+  resources.sort();
+  assert_eq!(resources, vec!["index.css", "index.js"]);
+  assert_eq!(*index.meta.rendered_content, "rendered index");
+
+  // the removed resource pot is not added back
+  assert_eq!(resource_pot_map.resource_pots().len(), 1);
+  assert_eq!(stale_resources, vec!["removed.js".to_string()]);
+}
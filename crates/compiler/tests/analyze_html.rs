@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use farmfe_compiler::testing::TestProject;
+use farmfe_core::{
+  context::{
+    diagnostics::{Diagnostic, DiagnosticSeverity},
+    CompilationContext,
+  },
+  error::Result,
+  plugin::{Plugin, PluginAnalyzeHtmlHookParam},
+  swc_html_ast::Child,
+};
+
+/// Reports the html entries whose `<html>` has no `lang` attribute
+struct HtmlLangPlugin {
+  severity: DiagnosticSeverity,
+}
+
+impl Plugin for HtmlLangPlugin {
+  fn name(&self) -> &str {
+    "HtmlLangPlugin"
+  }
+
+  fn analyze_html(
+    &self,
+    param: &mut PluginAnalyzeHtmlHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    let has_lang = param.ast.children.iter().any(|child| match child {
+      Child::Element(element) => {
+        element.tag_name == "html" && element.attributes.iter().any(|attr| attr.name == "lang")
+      }
+      _ => false,
+    });
+
+    if !has_lang {
+      param.diagnostics.push(Diagnostic {
+        code: "html-missing-lang".to_string(),
+        severity: self.severity,
+        message: format!("{} misses the lang attribute", param.resource_name),
+        modules: vec![param.module_id.clone()],
+        plugin: Some(self.name().to_string()),
+      });
+    }
+
+    Ok(Some(()))
+  }
+}
+
+fn project(html: &str, severity: DiagnosticSeverity) -> TestProject {
+  TestProject::new()
+    .file("index.html", html)
+    .file(
+      "about.html",
+      "<!DOCTYPE html><html lang=\"en\"><head></head><body></body></html>",
+    )
+    .file("index.ts", "console.log('index');\n")
+    .input("index", "./index.html")
+    .input("about", "./about.html")
+    .plugin(Arc::new(HtmlLangPlugin { severity }))
+}
+
+#[test]
+fn analyze_html_reports_diagnostics() {
+  let result = project(
+    "<!DOCTYPE html><html><head></head><body><script src=\"./index.ts\"></script></body></html>",
+    DiagnosticSeverity::Warning,
+  )
+  .compile()
+  .unwrap();
+
+  let diagnostics = result.compiler().context().diagnostics.lock();
+  let diagnostics = diagnostics.diagnostics();
+  assert_eq!(diagnostics.len(), 1);
+  assert_eq!(diagnostics[0].code, "html-missing-lang");
+  assert_eq!(
+    diagnostics[0].message,
+    "index.html misses the lang attribute"
+  );
+  assert_eq!(diagnostics[0].modules, vec!["index.html".into()]);
+}
+
+#[test]
+fn analyze_html_errors_fail_the_build() {
+  let result = project(
+    "<!DOCTYPE html><html><head></head><body></body></html>",
+    DiagnosticSeverity::Error,
+  )
+  .compile();
+
+  let Err(error) = result else {
+    panic!("the build should fail");
+  };
+  assert!(
+    error
+      .to_string()
+      .contains("index.html misses the lang attribute"),
+    "{error}"
+  );
+}
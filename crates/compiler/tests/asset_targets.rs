@@ -0,0 +1,93 @@
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_core::{
+  config::asset::{asset_target_key, asset_target_placeholder, ASSET_TARGETS_MANIFEST},
+  context::CompilationContext,
+  error::Result,
+  plugin::{Plugin, PluginTransformAssetHookParam},
+  resource::ResourceType,
+  serde_json,
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+/// Uppercases the assets of the webview target
+struct WebviewPlugin;
+
+impl Plugin for WebviewPlugin {
+  fn name(&self) -> &str {
+    "webview"
+  }
+
+  fn transform_asset(
+    &self,
+    param: &mut PluginTransformAssetHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if param.target.as_deref() != Some("webview") {
+      return Ok(None);
+    }
+
+    param.content = param.content.to_ascii_uppercase();
+
+    Ok(Some(()))
+  }
+}
+
+#[test]
+fn asset_targets() {
+  fixture!(
+    "tests/fixtures/asset_targets/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let compiler = create_compiler_with_args(cwd, crate_path, |mut config, mut plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        config.assets.targets = vec!["web".to_string(), "webview".to_string()];
+        plugins.push(Arc::new(WebviewPlugin));
+        (config, plugins)
+      });
+      compiler.compile().unwrap();
+
+      let module_id = compiler
+        .context()
+        .module_graph
+        .read()
+        .modules()
+        .into_iter()
+        .find(|m| m.id.relative_path() == "hello.txt")
+        .map(|m| m.id.clone())
+        .unwrap();
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let manifest = serde_json::from_slice::<HashMap<String, HashMap<String, String>>>(
+        &resources_map.get(ASSET_TARGETS_MANIFEST).unwrap().bytes,
+      )
+      .unwrap();
+      let assets = &manifest[&asset_target_key(&module_id)];
+
+      assert!(assets["web"].starts_with("web/"));
+      assert_eq!(resources_map.get(&assets["web"]).unwrap().bytes, b"hello\n");
+      assert!(assets["webview"].starts_with("webview/"));
+      assert_eq!(
+        resources_map.get(&assets["webview"]).unwrap().bytes,
+        b"HELLO\n"
+      );
+
+      // the script and the css reference the asset by the placeholder that is replaced when the variants are written
+      let placeholder = asset_target_placeholder(&module_id);
+      let code_of = |is_type: fn(&ResourceType) -> bool| {
+        resources_map
+          .values()
+          .filter(|r| is_type(&r.resource_type))
+          .map(|r| String::from_utf8_lossy(&r.bytes).to_string())
+          .collect::<Vec<_>>()
+          .join("\n")
+      };
+
+      assert!(code_of(|t| matches!(t, ResourceType::Js)).contains(&placeholder));
+      assert!(code_of(|t| matches!(t, ResourceType::Css)).contains(&placeholder));
+    }
+  );
+}
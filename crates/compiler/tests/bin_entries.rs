@@ -0,0 +1,116 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use farmfe_core::{
+  config::{bool_or_obj::BoolOrObj, ShebangConfig, ShebangMode, SourcemapConfig, TargetEnv},
+  serde_json::{self, Value},
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+/// name -> content of the emitted js resources
+fn compile(
+  cwd: PathBuf,
+  crate_path: PathBuf,
+  input: &str,
+  shebang: ShebangConfig,
+  bin: bool,
+) -> HashMap<String, String> {
+  let compiler = create_compiler_with_args(cwd, crate_path, |mut config, plugins| {
+    config.input = HashMap::from([("index".to_string(), input.to_string())]);
+    config.output.target_env = TargetEnv::Node;
+    config.output.shebang = shebang;
+    config.output.bin = bin;
+    config.minify = Box::new(BoolOrObj::Bool(false));
+    (config, plugins)
+  });
+  compiler.compile().unwrap();
+
+  compiler
+    .context()
+    .resources_map
+    .iter()
+    .filter(|r| !r.name.ends_with(".map"))
+    .map(|r| {
+      (
+        r.name.clone(),
+        String::from_utf8_lossy(&r.bytes).to_string(),
+      )
+    })
+    .collect()
+}
+
+#[test]
+fn bin_entries() {
+  fixture!("tests/fixtures/bin_entries/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap().to_path_buf();
+
+    // the shebang of the entry module is preserved by default
+    let resources = compile(
+      cwd.clone(),
+      crate_path.clone(),
+      "./index.ts",
+      ShebangConfig::default(),
+      false,
+    );
+    assert!(resources["index.js"].starts_with("#!/usr/bin/env -S node --enable-source-maps\n"));
+
+    let resources = compile(
+      cwd.clone(),
+      crate_path.clone(),
+      "./index.ts",
+      ShebangConfig::Mode(ShebangMode::Remove),
+      false,
+    );
+    assert!(!resources["index.js"].starts_with("#!"));
+
+    // inserted into the entries without a shebang
+    let resources = compile(
+      cwd.clone(),
+      crate_path.clone(),
+      "./greet.ts",
+      ShebangConfig::Custom("#!/usr/bin/env bun".to_string()),
+      false,
+    );
+    assert!(resources["index.js"].starts_with("#!/usr/bin/env bun\n"));
+
+    // extension-less executables
+    let resources = compile(
+      cwd.clone(),
+      crate_path.clone(),
+      "./greet.ts",
+      ShebangConfig::default(),
+      true,
+    );
+    assert!(!resources.contains_key("index.js"));
+    assert!(resources["index"].starts_with("#!/usr/bin/env node\n"));
+  });
+}
+
+#[test]
+fn bin_entries_with_source_maps() {
+  fixture!("tests/fixtures/bin_entries/greet.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap().to_path_buf();
+    let compiler = create_compiler_with_args(cwd, crate_path, |mut config, plugins| {
+      config.input = HashMap::from([("index".to_string(), "./greet.ts".to_string())]);
+      config.output.target_env = TargetEnv::Node;
+      config.output.bin = true;
+      config.sourcemap = Box::new(SourcemapConfig::Bool(true));
+      (config, plugins)
+    });
+    compiler.compile().unwrap();
+
+    let resources_map = compiler.context().resources_map.clone().into_read_only();
+    assert!(!resources_map.contains_key("index.js.map"));
+
+    // the source map is renamed with the entry
+    let code = String::from_utf8_lossy(&resources_map.get("index").unwrap().bytes).to_string();
+    assert!(code.trim_end().ends_with("//# sourceMappingURL=index.map"));
+
+    let map =
+      serde_json::from_slice::<Value>(&resources_map.get("index.map").unwrap().bytes).unwrap();
+    assert_eq!(map["file"], "index");
+  });
+}
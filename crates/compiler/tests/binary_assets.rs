@@ -0,0 +1,94 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use farmfe_compiler::Compiler;
+use farmfe_core::config::{config_regex::ConfigRegex, ModuleFormat, TargetEnv};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+fn create_compiler(cwd: PathBuf, crate_path: PathBuf, input: &str, hash: bool) -> Compiler {
+  create_compiler_with_args(cwd, crate_path, |mut config, plugins| {
+    config.input = HashMap::from([("index".to_string(), input.to_string())]);
+    config.output.target_env = TargetEnv::Node;
+    config.output.format = ModuleFormat::EsModule;
+    config.external = vec![ConfigRegex::new("^node:")];
+    config.assets.binary.hash = hash;
+    (config, plugins)
+  })
+}
+
+fn resource_names(compiler: &Compiler) -> Vec<String> {
+  let mut names = compiler
+    .context()
+    .resources_map
+    .iter()
+    .map(|r| r.name.clone())
+    .collect::<Vec<_>>();
+  names.sort();
+
+  names
+}
+
+#[test]
+fn binary_assets_naming() {
+  fixture!(
+    "tests/fixtures/binary_assets/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+
+      let compiler = create_compiler(cwd.clone(), crate_path.clone(), "./index.ts", true);
+      compiler.compile().unwrap();
+      let names = resource_names(&compiler);
+      let addon = names
+        .iter()
+        .find(|name| name.starts_with("addon-") && name.ends_with(".node"))
+        .unwrap();
+      assert!(names
+        .iter()
+        .any(|name| name.starts_with("model-") && name.ends_with(".onnx")));
+
+      let index = compiler.context().resources_map.get("index.js").unwrap();
+      let code = String::from_utf8_lossy(&index.bytes);
+      assert!(code.contains(&format!("createRequire(import.meta.url)(\"./{addon}\")")));
+
+      // the original filenames are kept without the hash
+      let compiler = create_compiler(cwd, crate_path, "./index.ts", false);
+      compiler.compile().unwrap();
+      let names = resource_names(&compiler);
+      assert!(names.contains(&"addon.node".to_string()));
+      assert!(names.contains(&"model.onnx".to_string()));
+
+      let index = compiler.context().resources_map.get("index.js").unwrap();
+      let code = String::from_utf8_lossy(&index.bytes);
+      assert!(code.contains("createRequire(import.meta.url)(\"./addon.node\")"));
+    }
+  );
+}
+
+#[test]
+fn binary_assets_duplicate_names() {
+  fixture!(
+    "tests/fixtures/binary_assets/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+
+      let compiler = create_compiler(cwd.clone(), crate_path.clone(), "./duplicate.ts", false);
+      let err = compiler.compile().unwrap_err().to_string();
+      assert!(
+        err.contains("Binary files a/addon.node, b/addon.node are all emitted as addon.node"),
+        "{err}"
+      );
+
+      // the content hash tells them apart
+      let compiler = create_compiler(cwd, crate_path, "./duplicate.ts", true);
+      compiler.compile().unwrap();
+      let addons = resource_names(&compiler)
+        .into_iter()
+        .filter(|name| name.ends_with(".node"))
+        .count();
+      assert_eq!(addons, 2);
+    }
+  );
+}
@@ -1,9 +1,18 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use farmfe_core::config::{bool_or_obj::BoolOrObj, config_regex::ConfigRegex, Mode, TargetEnv};
+use farmfe_compiler::{testing::TestProject, Compiler};
+use farmfe_core::{
+  config::{
+    bool_or_obj::BoolOrObj, config_regex::ConfigRegex,
+    partial_bundling::PartialBundlingEnforceResourceConfig, Config, Mode, ModuleFormat, TargetEnv,
+  },
+  resource::ResourceType,
+};
+use farmfe_testing_helpers::fixture;
 mod common;
 use crate::common::{
-  assert_compiler_result_with_config, create_compiler_with_args, AssertCompilerResultConfig,
+  assert_compiler_result_with_config, create_compiler_with_args, create_config,
+  AssertCompilerResultConfig,
 };
 
 #[allow(dead_code)]
@@ -69,3 +78,125 @@ fn test(file: String, crate_path: String) {
 }
 
 farmfe_testing::testing! {"tests/fixtures/bundle/library/**/index.ts", test}
+
+#[test]
+fn native_esm_html_entry() {
+  fixture!(
+    "tests/fixtures/bundle/native_esm/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.html".to_string())]);
+          config.output.native_esm = true;
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let html =
+        String::from_utf8_lossy(&resources_map.get("index.html").unwrap().bytes).to_string();
+
+      assert!(html.contains(r#"type="module""#), "{html}");
+      assert!(!html.contains("bootstrap()"), "{html}");
+      assert!(resources_map
+        .values()
+        .all(|r| !matches!(r.resource_type, ResourceType::Runtime)));
+    }
+  );
+}
+
+#[test]
+fn native_esm_with_runtime_features() {
+  fixture!(
+    "tests/fixtures/bundle/native_esm/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let mut config = create_config(cwd, crate_path);
+      config.input = HashMap::from([("index".to_string(), "./index.html".to_string())]);
+      config.output.native_esm = true;
+      config.mode = Mode::Development;
+      config.lazy_compilation = true;
+
+      let Err(err) = Compiler::new(config, vec![]) else {
+        panic!("native esm builds should not be created with the runtime features");
+      };
+
+      assert!(
+        err
+          .to_string()
+          .contains("development mode and lazyCompilation"),
+        "{err}"
+      );
+    }
+  );
+}
+
+#[test]
+fn native_esm_requires_esm_format() {
+  let mut config = Config::default();
+  config.output.native_esm = true;
+  config.output.format = ModuleFormat::CommonJs;
+
+  let Err(err) = Compiler::new(config, vec![]) else {
+    panic!("native esm builds should not be created with the commonjs format");
+  };
+
+  assert!(
+    err
+      .to_string()
+      .contains("output.nativeEsm requires output.format to be esm"),
+    "{err}"
+  );
+}
+
+#[test]
+fn native_esm_resources_import_each_other() {
+  let result = TestProject::new()
+    .file(
+      "index.ts",
+      "import { shared } from './shared';\nimport('./lazy').then((m) => console.log(m.lazy));\nconsole.log(shared);\n",
+    )
+    .file("shared.ts", "export const shared = 'shared';\n")
+    .file(
+      "lazy.ts",
+      "import { shared } from './shared';\nexport const lazy = shared + 'lazy';\n",
+    )
+    .input("index", "./index.ts")
+    .config(|config| {
+      config.output.native_esm = true;
+      config.partial_bundling.enforce_resources = vec![PartialBundlingEnforceResourceConfig {
+        test: vec![ConfigRegex::new("^shared\\.ts")],
+        name: "shared".to_string(),
+      }];
+    })
+    .compile()
+    .unwrap();
+  let resources = result.resources();
+  let lazy = resources
+    .keys()
+    .find(|name| name.starts_with("lazy"))
+    .unwrap();
+
+  // the resources are loaded by the browser without the runtime
+  for (name, content) in &resources {
+    assert!(
+      !content.contains("__farm_module_system__"),
+      "{name}:\n{content}"
+    );
+    assert!(
+      !content.contains("__FARM_BUNDLE_REFERENCE_SLOT__"),
+      "{name}:\n{content}"
+    );
+  }
+
+  let index = &resources["index.js"];
+  assert!(index.contains(r#"from "./shared.js""#), "{index}");
+  assert!(index.contains(&format!(r#"import("./{lazy}")"#)), "{index}");
+  assert!(
+    resources[lazy].contains(r#"from "./shared.js""#),
+    "{}",
+    resources[lazy]
+  );
+  assert!(resources["shared.js"].contains("export { shared }"));
+}
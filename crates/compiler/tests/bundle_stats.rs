@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use farmfe_compiler::testing::TestProject;
+use farmfe_core::{
+  config::{
+    bundle_stats::BundleStatsConfig, persistent_cache::PersistentCacheConfig,
+    unused_exports::UnusedExportsConfig,
+  },
+  module::ModuleId,
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[test]
+fn bundle_stats() {
+  fixture!(
+    "tests/fixtures/bundle_stats/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          config.bundle_stats = Some(Box::default());
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let filename = BundleStatsConfig::default().filename;
+      assert!(compiler.context().resources_map.contains_key(&filename));
+
+      let bundle_stats = compiler.context().record_manager.bundle_stats.read();
+      let bundle_stats = bundle_stats.as_ref().unwrap();
+      let index = bundle_stats
+        .resources
+        .iter()
+        .find(|r| r.name == "index.js")
+        .unwrap();
+      let entry: ModuleId = "index.ts".into();
+      let dep: ModuleId = "dep.ts".into();
+
+      assert!(index.size > 0 && index.gzip_size > 0);
+      assert!(index.modules.iter().any(|m| m.id == dep));
+      assert!(bundle_stats
+        .edges
+        .iter()
+        .any(|e| e.importer == entry && e.dependency == dep));
+    }
+  );
+}
+
+#[test]
+fn unused_exports() {
+  fixture!(
+    "tests/fixtures/unused_exports/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          config.unused_exports = Some(Box::default());
+          config.bundle_stats = Some(Box::default());
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let filename = UnusedExportsConfig::default().filename;
+      assert!(compiler.context().resources_map.contains_key(&filename));
+
+      let bundle_stats = compiler.context().record_manager.bundle_stats.read();
+      let unused_exports = bundle_stats
+        .as_ref()
+        .unwrap()
+        .unused_exports
+        .iter()
+        .map(|s| (s.id.to_string(), (s.unused.clone(), s.public.clone())))
+        .collect::<HashMap<_, _>>();
+      let strings = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+      assert_eq!(
+        unused_exports,
+        HashMap::from([
+          (
+            "a.ts".to_string(),
+            (strings(&["default", "unusedA"]), strings(&[]))
+          ),
+          ("b.ts".to_string(), (strings(&["unusedB"]), strings(&["b"]))),
+          ("c.ts".to_string(), (strings(&[]), strings(&["c"]))),
+          ("index.ts".to_string(), (strings(&[]), strings(&["b"]))),
+        ])
+      );
+    }
+  );
+}
+
+#[test]
+fn unused_exports_with_persistent_cache() {
+  let result = TestProject::new()
+    .dir("tests/fixtures/unused_exports")
+    .unwrap()
+    .input("index", "./index.ts")
+    .config(|config| {
+      config.persistent_cache = Box::new(PersistentCacheConfig::Bool(true));
+      config.unused_exports = Some(Box::default());
+    })
+    .compile()
+    .unwrap();
+
+  // the report is not generated by a module, it's never pruned as a resource of a removed module
+  let filename = UnusedExportsConfig::default().filename;
+  assert!(result.resource(&filename).unwrap().contains("unusedA"));
+}
+
+#[test]
+fn module_exports() {
+  fixture!(
+    "tests/fixtures/unused_exports/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          config.bundle_stats = Some(Box::new(BundleStatsConfig {
+            exports: true,
+            ..Default::default()
+          }));
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let bundle_stats = compiler.context().record_manager.bundle_stats.read();
+      let exports = bundle_stats
+        .as_ref()
+        .unwrap()
+        .exports
+        .iter()
+        .map(|s| {
+          (
+            s.id.to_string(),
+            s.exports.iter().map(|e| e.name.clone()).collect::<Vec<_>>(),
+          )
+        })
+        .collect::<HashMap<_, _>>();
+
+      // recorded before tree shaking removes the unused exports
+      assert_eq!(exports["a.ts"], vec!["a", "unusedA", "default"]);
+      assert_eq!(exports["b.ts"], vec!["b", "unusedB"]);
+      assert_eq!(exports["index.ts"], vec!["b", "*"]);
+
+      let index_exports = compiler.module_exports(&"index.ts".into()).unwrap();
+      assert_eq!(index_exports[1].source, Some("./c".to_string()));
+    }
+  );
+}
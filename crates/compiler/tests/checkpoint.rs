@@ -0,0 +1,136 @@
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  },
+};
+
+use farmfe_compiler::Compiler;
+use farmfe_core::{
+  config::checkpoint::CheckpointConfig,
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  plugin::{Plugin, PluginHookContext, PluginLoadHookParam, PluginLoadHookResult},
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+/// Counts the loaded modules, and fails the generate stage to simulate a crashed build
+struct CrashPlugin {
+  crash: bool,
+  loaded: AtomicUsize,
+}
+
+impl Plugin for CrashPlugin {
+  fn name(&self) -> &str {
+    "crash"
+  }
+
+  fn load(
+    &self,
+    _param: &PluginLoadHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginLoadHookResult>> {
+    self.loaded.fetch_add(1, Ordering::SeqCst);
+    Ok(None)
+  }
+
+  fn generate_start(&self, _context: &Arc<CompilationContext>) -> Result<Option<()>> {
+    if self.crash {
+      return Err(CompilationError::GenericError("crashed".to_string()));
+    }
+
+    Ok(None)
+  }
+}
+
+fn create_compiler(cwd: PathBuf, crate_path: PathBuf, crash: bool) -> (Compiler, Arc<CrashPlugin>) {
+  let plugin = Arc::new(CrashPlugin {
+    crash,
+    loaded: AtomicUsize::new(0),
+  });
+  let compiler = create_compiler_with_args(cwd, crate_path, |mut config, mut plugins| {
+    config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+    config.checkpoint = Some(Box::default());
+    plugins.push(plugin.clone());
+    (config, plugins)
+  });
+
+  (compiler, plugin)
+}
+
+fn output(compiler: &Compiler) -> String {
+  compiler
+    .context()
+    .resources_map
+    .iter()
+    .filter(|r| r.name.ends_with(".js"))
+    .map(|r| String::from_utf8_lossy(&r.bytes).to_string())
+    .collect()
+}
+
+fn checkpoints(cwd: &Path) -> usize {
+  std::fs::read_dir(cwd.join(CheckpointConfig::default().dir))
+    .map(|entries| entries.count())
+    .unwrap_or(0)
+}
+
+// both cases share the checkpoint of the fixture, so they run sequentially
+#[test]
+fn resume_from_checkpoint() {
+  fixture!("tests/fixtures/checkpoint/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap().to_path_buf();
+
+    let (compiler, plugin) = create_compiler(cwd.clone(), crate_path.clone(), true);
+    assert!(compiler.compile().is_err());
+    assert!(plugin.loaded.load(Ordering::SeqCst) > 0);
+    assert_eq!(checkpoints(&cwd), 1);
+
+    // the module graph is restored from the checkpoint instead of being built again
+    let (compiler, plugin) = create_compiler(cwd.clone(), crate_path.clone(), false);
+    compiler.compile().unwrap();
+    assert_eq!(plugin.loaded.load(Ordering::SeqCst), 0);
+    assert!(output(&compiler).contains("hello checkpoint"));
+    assert_eq!(checkpoints(&cwd), 0);
+
+    // the checkpoint is discarded when the files changed after it's written
+    let (compiler, _) = create_compiler(cwd.clone(), crate_path.clone(), true);
+    assert!(compiler.compile().is_err());
+
+    let message_file = cwd.join("message.ts");
+    let original_message = std::fs::read_to_string(&message_file).unwrap();
+    std::fs::write(&message_file, "export const message = 'changed';\n").unwrap();
+
+    let (compiler, plugin) = create_compiler(cwd.clone(), crate_path.clone(), false);
+    let result = compiler.compile();
+    std::fs::write(&message_file, original_message).unwrap();
+
+    result.unwrap();
+    assert!(plugin.loaded.load(Ordering::SeqCst) > 0);
+    assert!(output(&compiler).contains("changed"));
+    assert_eq!(checkpoints(&cwd), 0);
+
+    // a corrupt checkpoint is discarded and the module graph is built again
+    let (compiler, _) = create_compiler(cwd.clone(), crate_path.clone(), true);
+    assert!(compiler.compile().is_err());
+
+    for entry in std::fs::read_dir(cwd.join(CheckpointConfig::default().dir)).unwrap() {
+      let path = entry.unwrap().path();
+      let mut bytes = std::fs::read(&path).unwrap();
+      bytes.truncate(bytes.len() / 2);
+      std::fs::write(&path, bytes).unwrap();
+    }
+
+    let (compiler, plugin) = create_compiler(cwd.clone(), crate_path.clone(), false);
+    compiler.compile().unwrap();
+    assert!(plugin.loaded.load(Ordering::SeqCst) > 0);
+    assert!(output(&compiler).contains("hello checkpoint"));
+    assert_eq!(checkpoints(&cwd), 0);
+  });
+}
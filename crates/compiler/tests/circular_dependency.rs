@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use farmfe_core::{
+  config::{
+    circular_dependency::{CircularDependencyConfig, CircularDependencySeverity},
+    config_regex::ConfigRegex,
+    Config,
+  },
+  context::diagnostics::DiagnosticSeverity,
+  module::ModuleId,
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+fn with_circular_dependency(
+  mut config: Config,
+  circular_dependency: CircularDependencyConfig,
+) -> Config {
+  config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+  config.circular_dependency = Some(Box::new(circular_dependency));
+  config
+}
+
+#[test]
+fn circular_dependency_warn() {
+  fixture!(
+    "tests/fixtures/circular_dependency/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler = create_compiler_with_args(cwd.to_path_buf(), crate_path, |config, plugins| {
+        (
+          with_circular_dependency(config, CircularDependencyConfig::default()),
+          plugins,
+        )
+      });
+      compiler.compile().unwrap();
+
+      let diagnostics = compiler.context().diagnostics.lock();
+      let diagnostics = diagnostics.diagnostics();
+
+      // the cycle through the dynamic import of lazy.ts is ignored
+      assert_eq!(diagnostics.len(), 1);
+      assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+      assert_eq!(
+        diagnostics[0].message,
+        "Circular dependency: a.ts -> b.ts -> a.ts"
+      );
+      assert_eq!(
+        diagnostics[0].modules,
+        vec![ModuleId::from("a.ts"), ModuleId::from("b.ts")]
+      );
+    }
+  );
+}
+
+#[test]
+fn circular_dependency_error() {
+  fixture!(
+    "tests/fixtures/circular_dependency/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler = create_compiler_with_args(cwd.to_path_buf(), crate_path, |config, plugins| {
+        let circular_dependency = CircularDependencyConfig {
+          severity: CircularDependencySeverity::Error,
+          ignore_dynamic_imports: false,
+          ..Default::default()
+        };
+        (
+          with_circular_dependency(config, circular_dependency),
+          plugins,
+        )
+      });
+      let err = compiler.compile().unwrap_err().to_string();
+
+      // a.ts, b.ts and lazy.ts are one strongly connected component, the shortest cycle is reported
+      assert!(err.contains("a.ts -> b.ts -> a.ts"));
+      assert!(compiler.context().diagnostics.lock().has_errors());
+    }
+  );
+}
+
+#[test]
+fn circular_dependency_filters() {
+  fixture!(
+    "tests/fixtures/circular_dependency/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler = create_compiler_with_args(cwd.to_path_buf(), crate_path, |config, plugins| {
+        let circular_dependency = CircularDependencyConfig {
+          severity: CircularDependencySeverity::Error,
+          exclude: vec![ConfigRegex::new("b\\.ts$")],
+          ..Default::default()
+        };
+        (
+          with_circular_dependency(config, circular_dependency),
+          plugins,
+        )
+      });
+      compiler.compile().unwrap();
+
+      assert!(compiler
+        .context()
+        .diagnostics
+        .lock()
+        .diagnostics()
+        .is_empty());
+    }
+  );
+}
@@ -259,7 +259,7 @@ pub fn create_compiler_with_plugins(
 }
 
 pub fn get_compiler_result(compiler: &Compiler, config: &AssertCompilerResultConfig) -> String {
-  let resources_map = compiler.context().resources_map.lock();
+  let resources_map = compiler.context().resources_map.clone().into_read_only();
   let mut result = vec![];
 
   for (name, resource) in resources_map.iter() {
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use farmfe_core::module::ModuleId;
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_plugins;
+
+mod common;
+
+#[test]
+fn compile_partial_entries() {
+  fixture!(
+    "tests/fixtures/compile_partial/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler = create_compiler_with_plugins(
+        HashMap::from([("index".to_string(), "./index.ts".to_string())]),
+        cwd.to_path_buf(),
+        crate_path,
+        false,
+        vec![],
+      );
+      compiler.compile().unwrap();
+
+      let added_modules = compiler
+        .compile_partial(HashMap::from([(
+          "other".to_string(),
+          "./other.ts".to_string(),
+        )]))
+        .unwrap();
+      // shared.ts is already compiled by the index entry, only other.ts is built
+      assert_eq!(added_modules, vec![ModuleId::from("other.ts")]);
+
+      {
+        let module_graph = compiler.context().module_graph.read();
+        assert_eq!(
+          module_graph.entries.get(&"other.ts".into()).unwrap(),
+          "other"
+        );
+        assert!(module_graph.has_edge(&"other.ts".into(), &"shared.ts".into()));
+
+        let module_group_graph = compiler.context().module_group_graph.read();
+        assert!(module_group_graph.has(&"other.ts".into()));
+
+        let resource_pot_id = module_graph
+          .module(&"other.ts".into())
+          .unwrap()
+          .resource_pot
+          .clone()
+          .unwrap();
+        let resource_pot_map = compiler.context().resource_pot_map.read();
+        let resource_pot = resource_pot_map.resource_pot(&resource_pot_id).unwrap();
+        let resources_map = compiler.context().resources_map.clone().into_read_only();
+        assert!(resource_pot
+          .resources()
+          .iter()
+          .all(|r| resources_map.contains_key(*r)));
+        assert!(!resource_pot.resources().is_empty());
+      }
+
+      // compiled entries are skipped
+      let added_modules = compiler
+        .compile_partial(HashMap::from([(
+          "other".to_string(),
+          "./other.ts".to_string(),
+        )]))
+        .unwrap();
+      assert!(added_modules.is_empty());
+    }
+  );
+}
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use farmfe_core::{config::bool_or_obj::BoolOrObj, module::ModuleId};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[test]
+fn compile_subset() {
+  fixture!(
+    "tests/fixtures/unused_exports/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          config.tree_shaking = Box::new(BoolOrObj::Bool(false));
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+      assert!(compiler
+        .context()
+        .module_graph
+        .read()
+        .has_module(&"a.ts".into()));
+
+      compiler
+        .compile_subset(&HashMap::from([("b".to_string(), "./b.ts".to_string())]))
+        .unwrap();
+
+      let module_graph = compiler.context().module_graph.read();
+
+      assert!(module_graph.has_module(&"b.ts".into()));
+      assert!(!module_graph.has_module(&"a.ts".into()));
+      assert!(!module_graph.has_module(&"index.ts".into()));
+      assert_eq!(module_graph.entries[&ModuleId::from("b.ts")], "b");
+      assert!(!module_graph
+        .entries
+        .contains_key(&ModuleId::from("index.ts")));
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      assert!(resources_map.contains_key("b.js"));
+      assert!(!resources_map.contains_key("index.js"));
+    }
+  );
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use farmfe_compiler::Compiler;
+use farmfe_core::{
+  config::{persistent_cache::PersistentCacheConfig, Config},
+  error::Result,
+  parking_lot::Mutex,
+  plugin::Plugin,
+  serde_json::Value,
+};
+
+struct PresetPlugin;
+
+impl Plugin for PresetPlugin {
+  fn name(&self) -> &str {
+    "PresetPlugin"
+  }
+
+  fn priority(&self) -> i32 {
+    200
+  }
+
+  fn config(&self, config: &mut Config) -> Result<Option<()>> {
+    config
+      .define
+      .insert("__FRAMEWORK__".to_string(), Value::String("preset".into()));
+    config.persistent_cache = Box::new(PersistentCacheConfig::Bool(true));
+
+    Ok(Some(()))
+  }
+}
+
+#[derive(Default)]
+struct ObserverPlugin {
+  hooks: Mutex<Vec<String>>,
+}
+
+impl Plugin for ObserverPlugin {
+  fn name(&self) -> &str {
+    "ObserverPlugin"
+  }
+
+  fn config(&self, config: &mut Config) -> Result<Option<()>> {
+    // the plugin with a larger priority modified the config first
+    assert!(config.define.contains_key("__FRAMEWORK__"));
+    self.hooks.lock().push("config".to_string());
+
+    Ok(Some(()))
+  }
+
+  fn config_resolved(&self, config: &Config) -> Result<Option<()>> {
+    // the persistent cache enabled by the preset is normalized
+    assert!(matches!(
+      *config.persistent_cache,
+      PersistentCacheConfig::Obj(_)
+    ));
+    self.hooks.lock().push("config_resolved".to_string());
+
+    Ok(Some(()))
+  }
+}
+
+#[test]
+fn config_hooks() {
+  let observer = Arc::new(ObserverPlugin::default());
+  let compiler = Compiler::new_without_internal_plugins(
+    Config::default(),
+    vec![observer.clone() as Arc<dyn Plugin>, Arc::new(PresetPlugin)],
+  )
+  .unwrap();
+
+  assert_eq!(*observer.hooks.lock(), vec!["config", "config_resolved"]);
+
+  let config = &compiler.context().config;
+  assert_eq!(
+    config.define.get("__FRAMEWORK__"),
+    Some(&Value::String("preset".into()))
+  );
+  assert!(config.persistent_cache.enabled());
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "conformance")]
+
+use farmfe_compiler::testing::conformance::{ConformanceOutcome, ConformanceSuite};
+use farmfe_core::config::{ModuleFormat, TargetEnv};
+
+fn write(path: std::path::PathBuf, content: &str) {
+  std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+  std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn conformance_suite() {
+  let suite_dir =
+    std::env::temp_dir().join(format!("farm-conformance-suite-{}", std::process::id()));
+  write(
+    suite_dir.join("basic/index.ts"),
+    "import { a } from './a';\nconsole.log(a);\n",
+  );
+  write(suite_dir.join("basic/a.ts"), "export const a = 'a';\n");
+  write(
+    suite_dir.join("custom-entry/main.ts"),
+    "console.log(DEBUG);\n",
+  );
+  write(
+    suite_dir.join("custom-entry/conformance.json"),
+    r#"{ "input": { "main": "./main.ts" }, "formats": ["esm"], "config": { "define": { "DEBUG": "false" } } }"#,
+  );
+
+  let suite = ConformanceSuite::new(&suite_dir)
+    .formats(vec![ModuleFormat::EsModule, ModuleFormat::CommonJs])
+    .targets(vec![TargetEnv::Browser, TargetEnv::Node]);
+
+  // the expected resources are written by the first run
+  let report = suite.run().unwrap();
+  let variants = report
+    .results
+    .iter()
+    .map(|r| format!("{} {}", r.case, r.variant))
+    .collect::<Vec<_>>();
+  assert_eq!(
+    variants,
+    vec![
+      "basic browser-esm",
+      "basic browser-cjs",
+      "basic node-esm",
+      "basic node-cjs",
+      "custom-entry browser-esm",
+      "custom-entry node-esm",
+    ]
+  );
+  assert!(report
+    .results
+    .iter()
+    .all(|r| r.outcome == ConformanceOutcome::Updated));
+  assert!(suite_dir
+    .join("basic/__expected__/browser-esm.snap")
+    .exists());
+
+  let report = suite.run().unwrap();
+  assert!(report
+    .results
+    .iter()
+    .all(|r| r.outcome == ConformanceOutcome::Passed));
+  report.assert_passed();
+
+  write(
+    suite_dir.join("custom-entry/__expected__/node-esm.snap"),
+    "//main.js:\nconsole.log(true);\n",
+  );
+  let report = suite.run().unwrap();
+  let failures = report.failures();
+  assert_eq!(failures.len(), 1);
+  assert_eq!(failures[0].case, "custom-entry");
+  assert_eq!(failures[0].variant, "node-esm");
+  assert!(matches!(failures[0].outcome, ConformanceOutcome::Failed(_)));
+
+  std::fs::remove_dir_all(&suite_dir).unwrap();
+}
@@ -4,7 +4,7 @@ use farmfe_core::config::{CssConfig, CssModulesConfig, CssPrefixerConfig};
 use farmfe_testing_helpers::fixture;
 mod common;
 
-use crate::common::{assert_compiler_result, create_css_compiler};
+use crate::common::{assert_compiler_result, create_compiler_with_args, create_css_compiler};
 
 #[test]
 fn css_modules() {
@@ -22,6 +22,7 @@ fn css_modules() {
         modules: Some(CssModulesConfig {
           indent_name: "farm-[name]".into(),
           paths: vec![".+".to_string()],
+          ..Default::default()
         }),
         ..Default::default()
       },
@@ -85,3 +86,30 @@ fn css_url_replacer() {
     }
   );
 }
+
+#[test]
+fn css_styles_anchor() {
+  fixture!(
+    "tests/fixtures/css/styles_anchor/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.html".to_string())]);
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let html =
+        String::from_utf8_lossy(&resources_map.get("index.html").unwrap().bytes).to_string();
+      let injected_link = html.find("rel=\"stylesheet\"").unwrap();
+      let anchor = html.find("farm-styles").unwrap();
+      let cdn_link = html.find("https://cdn.example.com/theme.css").unwrap();
+
+      // the extracted styles are injected before the anchor, the stylesheets after it take precedence
+      assert!(injected_link < anchor, "{html}");
+      assert!(anchor < cdn_link, "{html}");
+    }
+  );
+}
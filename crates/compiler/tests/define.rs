@@ -0,0 +1,350 @@
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_core::{
+  config::{bool_or_obj::BoolOrObj, build_meta::BuildMetaConfig, Config},
+  context::CompilationContext,
+  module::{Module, ModuleMetaData, ScriptModuleMetaData},
+  plugin::Plugin,
+  serde_json::{json, Value},
+};
+use farmfe_plugin_define::FarmPluginDefine;
+use farmfe_testing_helpers::fixture;
+
+mod common;
+
+use common::create_compiler_with_args;
+
+fn defines() -> HashMap<String, Value> {
+  HashMap::from([
+    ("process.env.NODE_ENV".to_string(), json!("\"production\"")),
+    (
+      "$__farm_regex:((global(This)?\\.)?process|import\\.meta)\\.env\\.FARM_API_URL".to_string(),
+      json!("\"https://api.example.com\""),
+    ),
+    ("__DEV__".to_string(), json!(false)),
+  ])
+}
+
+#[test]
+fn define_replace_globals() {
+  fixture!("tests/fixtures/define/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler =
+      create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        config.define = defines();
+        (config, plugins)
+      });
+    compiler.compile().unwrap();
+
+    let resources_map = compiler.context().resources_map.clone().into_read_only();
+    let index = String::from_utf8_lossy(&resources_map.get("index.js").unwrap().bytes);
+
+    assert!(index.contains(r#"const env = "production";"#));
+    assert!(index.contains(r#"const apiUrl = "https://api.example.com";"#));
+    assert!(index.contains(r#"const apiUrlFromGlobal = "https://api.example.com";"#));
+    // only the whole expression is replaced
+    assert!(index.contains("const notDefined = process.env.FARM_API_URL_V2;"));
+    // strings and shadowed identifiers are kept
+    assert!(index.contains("'process.env.NODE_ENV'"));
+    // the shadowed parameter may be renamed by the hygiene pass
+    assert!(index.contains(".env.NODE_ENV;"));
+    assert!(index.contains("__DEV__: false"));
+
+    let module_graph = compiler.context().module_graph.read();
+    let module = module_graph.module(&"index.ts".into()).unwrap();
+    let mut used_defines = module
+      .meta
+      .as_script()
+      .used_defines
+      .keys()
+      .cloned()
+      .collect::<Vec<_>>();
+    used_defines.sort();
+
+    assert_eq!(
+      used_defines,
+      vec![
+        "$__farm_regex:((global(This)?\\.)?process|import\\.meta)\\.env\\.FARM_API_URL".to_string(),
+        "__DEV__".to_string(),
+        "process.env.NODE_ENV".to_string(),
+      ]
+    );
+  });
+}
+
+#[test]
+fn define_typeof_object_members_and_fold_branches() {
+  fixture!("tests/fixtures/define/fold/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler =
+      create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        config.define = defines();
+        config
+          .define
+          .insert("typeof window".to_string(), json!("\"undefined\""));
+        config.define.insert(
+          "__BUILD_INFO__".to_string(),
+          json!({ "sha": "abc", "time": 1 }),
+        );
+        // only the branches folded by the define plugin are checked, tree shaking would remove the dead branches too
+        config.tree_shaking = Box::new(BoolOrObj::Bool(false));
+        (config, plugins)
+      });
+    compiler.compile().unwrap();
+
+    let resources_map = compiler.context().resources_map.clone().into_read_only();
+    let index = String::from_utf8_lossy(&resources_map.get("index.js").unwrap().bytes);
+
+    assert!(index.contains("const mode = 'production';"));
+    assert!(index.contains(r#"const length = "production".length;"#));
+    assert!(index.contains(r#"const sha = "abc";"#));
+    assert!(index.contains("const time = 1;"));
+    assert!(index.contains(r#""sha": "abc""#));
+    // the branch declaring `var` is kept
+    assert!(index.contains("var devOnly = true;"));
+
+    // the dynamic import of the removed branch is not analyzed
+    let module_graph = compiler.context().module_graph.read();
+    assert!(!module_graph.has_module(&"browser.ts".into()));
+
+    let mut used_defines = module_graph
+      .module(&"index.ts".into())
+      .unwrap()
+      .meta
+      .as_script()
+      .used_defines
+      .keys()
+      .cloned()
+      .collect::<Vec<_>>();
+    used_defines.sort();
+
+    assert_eq!(
+      used_defines,
+      vec![
+        "__BUILD_INFO__".to_string(),
+        "__DEV__".to_string(),
+        "process.env.NODE_ENV".to_string(),
+        "typeof window".to_string(),
+      ]
+    );
+  });
+}
+
+#[test]
+fn define_entry_overrides() {
+  fixture!(
+    "tests/fixtures/define/entry_define/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([
+            ("index".to_string(), "./index.ts".to_string()),
+            ("admin".to_string(), "./admin.ts".to_string()),
+          ]);
+          config.define = HashMap::from([
+            ("ADMIN_BUILD".to_string(), json!(false)),
+            ("API_URL".to_string(), json!("\"/api\"")),
+          ]);
+          config.entry_define = HashMap::from([(
+            "admin".to_string(),
+            HashMap::from([("ADMIN_BUILD".to_string(), json!(true))]),
+          )]);
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let index = String::from_utf8_lossy(&resources_map.get("index.js").unwrap().bytes);
+      let admin = String::from_utf8_lossy(&resources_map.get("admin.js").unwrap().bytes);
+
+      assert!(index.contains("mode = 'public';"));
+      assert!(admin.contains("mode = 'admin';"));
+      // the defines without overrides are kept
+      assert!(index.contains(r#"apiUrl = "/api";"#));
+      assert!(admin.contains(r#"apiUrl = "/api";"#));
+
+      // the shared module is compiled once for each entry variant
+      let module_graph = compiler.context().module_graph.read();
+      let shared = module_graph.module(&"shared.ts".into()).unwrap();
+      let admin_shared = module_graph
+        .module(&"shared.ts?farm_entry_define=admin".into())
+        .unwrap();
+
+      assert_eq!(
+        shared.meta.as_script().used_defines.get("ADMIN_BUILD"),
+        Some(&"false".to_string())
+      );
+      assert_eq!(
+        admin_shared
+          .meta
+          .as_script()
+          .used_defines
+          .get("ADMIN_BUILD"),
+        Some(&"true".to_string())
+      );
+      // the entry is tagged with its name
+      assert!(module_graph.has_module(&"admin.ts?farm_entry_define=admin".into()));
+      assert!(!module_graph.has_module(&"index.ts?farm_entry_define=admin".into()));
+    }
+  );
+}
+
+#[test]
+fn define_invalidate_cached_modules() {
+  let create_plugin = |define: HashMap<String, Value>| {
+    let plugin = FarmPluginDefine::new(&Config::default());
+    let mut config = Config {
+      define,
+      ..Default::default()
+    };
+    plugin.config(&mut config).unwrap();
+    plugin
+  };
+  let create_module = |used_defines: HashMap<String, String>| {
+    let mut module = Module::new("index.ts".into());
+    module.meta = Box::new(ModuleMetaData::Script(ScriptModuleMetaData {
+      used_defines,
+      ..Default::default()
+    }));
+    module
+  };
+  let context = Arc::new(CompilationContext::new(Config::default(), vec![]).unwrap());
+
+  let previous = create_plugin(defines());
+  let cache = previous.write_plugin_cache(&context).unwrap().unwrap();
+
+  let mut changed_defines = defines();
+  changed_defines.insert("__DEV__".to_string(), json!(true));
+  let plugin = create_plugin(changed_defines);
+  plugin.plugin_cache_loaded(&cache, &context).unwrap();
+
+  let uses_node_env = create_module(HashMap::from([(
+    "process.env.NODE_ENV".to_string(),
+    "\"production\"".to_string(),
+  )]));
+  let uses_dev = create_module(HashMap::from([(
+    "__DEV__".to_string(),
+    "false".to_string(),
+  )]));
+
+  assert_eq!(
+    plugin
+      .handle_persistent_cached_module(&uses_node_env, &context)
+      .unwrap(),
+    None
+  );
+  assert_eq!(
+    plugin
+      .handle_persistent_cached_module(&uses_dev, &context)
+      .unwrap(),
+    Some(true)
+  );
+
+  // any module may use a new define
+  let mut added_defines = defines();
+  added_defines.insert("__VERSION__".to_string(), json!("\"1.0.0\""));
+  let plugin = create_plugin(added_defines);
+  plugin.plugin_cache_loaded(&cache, &context).unwrap();
+
+  assert_eq!(
+    plugin
+      .handle_persistent_cached_module(&uses_node_env, &context)
+      .unwrap(),
+    Some(true)
+  );
+}
+
+#[test]
+fn define_build_time_keeps_cached_modules() {
+  let create_plugin = || {
+    let plugin = FarmPluginDefine::new(&Config::default());
+    let mut config = Config {
+      define: HashMap::from([(
+        "import.meta.env".to_string(),
+        json!(r#"{"FARM_APP":"app"}"#),
+      )]),
+      build_meta: Some(Box::new(BuildMetaConfig {
+        package: false,
+        git_commit: false,
+        build_time: true,
+        mode: false,
+      })),
+      ..Default::default()
+    };
+    plugin.config(&mut config).unwrap();
+    plugin
+  };
+  let context = Arc::new(CompilationContext::new(Config::default(), vec![]).unwrap());
+
+  let cache = create_plugin()
+    .write_plugin_cache(&context)
+    .unwrap()
+    .unwrap();
+  std::thread::sleep(std::time::Duration::from_millis(2));
+  let plugin = create_plugin();
+  plugin.plugin_cache_loaded(&cache, &context).unwrap();
+
+  // the build time of the previous build is replaced in the cached modules
+  for (key, code) in [
+    ("import.meta.env.BUILD_TIME", "1"),
+    ("import.meta.env", r#"{"BUILD_TIME":1,"FARM_APP":"app"}"#),
+  ] {
+    let mut module = Module::new("index.ts".into());
+    module.meta = Box::new(ModuleMetaData::Script(ScriptModuleMetaData {
+      used_defines: HashMap::from([(key.to_string(), code.to_string())]),
+      ..Default::default()
+    }));
+
+    assert_eq!(
+      plugin
+        .handle_persistent_cached_module(&module, &context)
+        .unwrap(),
+      None,
+      "{key}"
+    );
+  }
+}
+
+#[test]
+fn define_build_meta() {
+  fixture!(
+    "tests/fixtures/define/build_meta/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          config.define = HashMap::from([
+            (
+              "import.meta.env".to_string(),
+              json!(r#"{"FARM_APP":"app"}"#),
+            ),
+            ("import.meta.env.MODE".to_string(), json!("\"staging\"")),
+          ]);
+          config.build_meta = Some(Box::new(BuildMetaConfig {
+            git_commit: false,
+            ..Default::default()
+          }));
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let index = String::from_utf8_lossy(&resources_map.get("index.js").unwrap().bytes);
+
+      assert!(index.contains(r#"const version = "1.2.3";"#));
+      assert!(index.contains(r#"const name = "build-meta-app";"#));
+      assert!(!index.contains("const time = import.meta.env.BUILD_TIME;"));
+      assert!(index.contains(r#""BUILD_TIME": "#));
+      // disabled metadata is not defined
+      assert!(!index.contains("GIT_COMMIT\":"));
+      // the configured defines are not overridden
+      assert!(index.contains(r#"const mode = "staging";"#));
+      assert!(index.contains(r#""PACKAGE_VERSION": "1.2.3""#));
+      assert!(index.contains(r#""FARM_APP": "app""#));
+    }
+  );
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use farmfe_core::{
+  config::{
+    config_regex::ConfigRegex,
+    dependency_policy::{DependencyPolicyConfig, DependencyRule, DependencyRuleSeverity},
+    Config,
+  },
+  context::diagnostics::DiagnosticSeverity,
+  module::ModuleId,
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+fn with_dependency_policy(mut config: Config, rules: Vec<DependencyRule>) -> Config {
+  config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+  config.dependency_policy = Some(Box::new(DependencyPolicyConfig { rules }));
+  config
+}
+
+#[test]
+fn dependency_policy_deny_warn() {
+  fixture!(
+    "tests/fixtures/dependency_policy/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler = create_compiler_with_args(cwd.to_path_buf(), crate_path, |config, plugins| {
+        let rule = DependencyRule {
+          from: vec![ConfigRegex::new("^src/ui/")],
+          deny: vec![ConfigRegex::new("^src/server/")],
+          severity: DependencyRuleSeverity::Warn,
+          message: Some("call the api instead".to_string()),
+          ..Default::default()
+        };
+        (with_dependency_policy(config, vec![rule]), plugins)
+      });
+      compiler.compile().unwrap();
+
+      let diagnostics = compiler.context().diagnostics.lock();
+      let diagnostics = diagnostics.diagnostics();
+
+      assert_eq!(diagnostics.len(), 1);
+      assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+      assert_eq!(
+        diagnostics[0].message,
+        "Import `../server/db` of `src/ui/button.ts` is not allowed by the dependency policy, call the api instead:\n  index.ts -> src/ui/app.ts -> src/ui/button.ts -> src/server/db.ts"
+      );
+      assert_eq!(
+        diagnostics[0].modules,
+        vec![
+          ModuleId::from("index.ts"),
+          ModuleId::from("src/ui/app.ts"),
+          ModuleId::from("src/ui/button.ts"),
+          ModuleId::from("src/server/db.ts"),
+        ]
+      );
+    }
+  );
+}
+
+#[test]
+fn dependency_policy_allow_error() {
+  fixture!(
+    "tests/fixtures/dependency_policy/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler = create_compiler_with_args(cwd.to_path_buf(), crate_path, |config, plugins| {
+        // src/ui may only import src/ui
+        let rule = DependencyRule {
+          from: vec![ConfigRegex::new("^src/ui/")],
+          allow: vec![ConfigRegex::new("^src/ui/")],
+          ..Default::default()
+        };
+        (with_dependency_policy(config, vec![rule]), plugins)
+      });
+      let err = compiler.compile().unwrap_err().to_string();
+
+      assert!(err.contains("Import `../server/db` of `src/ui/button.ts`"));
+      assert!(compiler.context().diagnostics.lock().has_errors());
+    }
+  );
+}
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use farmfe_testing_helpers::fixture;
+mod common;
+
+use crate::common::create_compiler_with_args;
+
+#[test]
+fn dynamic_import_hints() {
+  fixture!(
+    "tests/fixtures/dynamic_import_hints/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.html".to_string())]);
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let html =
+        String::from_utf8_lossy(&resources_map.get("index.html").unwrap().bytes).to_string();
+      let links = html
+        .split("<link")
+        .skip(1)
+        .map(|link| link.split('>').next().unwrap())
+        .collect::<Vec<_>>();
+      let has_link = |rel: &str, name: &str| {
+        links
+          .iter()
+          .any(|link| link.contains(&format!("rel=\"{rel}\"")) && link.contains(name))
+      };
+
+      assert!(has_link("preload", "editor"), "{html}");
+      assert!(has_link("prefetch", "help"), "{html}");
+      // the dynamic import without hints is loaded on demand only
+      assert!(
+        !links.iter().any(|link| link.contains("user-settings")),
+        "{html}"
+      );
+      assert!(resources_map
+        .keys()
+        .any(|name| name.starts_with("user-settings") && name.ends_with(".js")));
+    }
+  );
+}
@@ -0,0 +1,113 @@
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_core::{
+  config::Mode,
+  context::CompilationContext,
+  error::Result,
+  parking_lot::Mutex,
+  plugin::UpdateType,
+  resource::{emit_sink::EmitSink, Resource},
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[derive(Default)]
+struct CollectEmitSink {
+  emitted: Mutex<Vec<String>>,
+  finished: Mutex<bool>,
+}
+
+impl EmitSink for CollectEmitSink {
+  fn name(&self) -> &str {
+    "CollectEmitSink"
+  }
+
+  fn emit(&self, resource: &Resource, _context: &CompilationContext) -> Result<()> {
+    self.emitted.lock().push(resource.name.clone());
+    Ok(())
+  }
+
+  fn finish(&self, _context: &CompilationContext) -> Result<()> {
+    *self.finished.lock() = true;
+    Ok(())
+  }
+}
+
+#[test]
+fn custom_emit_sink() {
+  fixture!(
+    "tests/fixtures/bundle_stats/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          (config, plugins)
+        });
+      let sink = Arc::new(CollectEmitSink::default());
+      compiler.context().set_emit_sink(sink.clone());
+      compiler.compile().unwrap();
+
+      let mut emitted = sink.emitted.lock().clone();
+      emitted.sort();
+      let mut expected = compiler
+        .context()
+        .resources_map
+        .iter()
+        .filter(|r| r.should_write())
+        .map(|r| r.name.clone())
+        .collect::<Vec<_>>();
+      expected.sort();
+
+      assert!(emitted.contains(&"index.js".to_string()));
+      assert_eq!(emitted, expected);
+      assert!(*sink.finished.lock());
+    }
+  );
+}
+
+#[test]
+fn update_emits_changed_resources() {
+  fixture!(
+    "tests/fixtures/update/html-entry/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let compiler = create_compiler_with_args(cwd.clone(), crate_path, |mut config, plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.html".to_string())]);
+        config.mode = Mode::Development;
+        config.output.filename = "[resourceName].[ext]".to_string();
+        (config, plugins)
+      });
+      let sink = Arc::new(CollectEmitSink::default());
+      compiler.context().set_emit_sink(sink.clone());
+
+      compiler.compile().unwrap();
+      let emitted = std::mem::take(&mut *sink.emitted.lock());
+      assert!(emitted.contains(&"index.html".to_string()), "{emitted:?}");
+
+      let update_file = cwd.join("index.ts").to_string_lossy().to_string();
+      let original_index = std::fs::read_to_string(&update_file).unwrap();
+      std::fs::write(
+        &update_file,
+        format!("{original_index}console.log('updated');\n"),
+      )
+      .unwrap();
+      let result = compiler.update(
+        vec![(update_file.clone(), UpdateType::Updated)],
+        || {},
+        true,
+        true,
+      );
+      std::fs::write(&update_file, original_index).unwrap();
+      result.unwrap();
+
+      // only the script of the updated module changes, the html injects the same resources
+      let emitted = sink.emitted.lock().clone();
+      assert_eq!(emitted.len(), 1, "{emitted:?}");
+      assert!(emitted[0].ends_with(".js"), "{emitted:?}");
+    }
+  );
+}
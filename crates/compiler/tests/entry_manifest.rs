@@ -0,0 +1,72 @@
+use farmfe_compiler::testing::TestProject;
+use farmfe_core::{
+  config::{entry_manifest::EntryManifestConfig, persistent_cache::PersistentCacheConfig},
+  serde_json::{self, Value},
+};
+
+#[test]
+fn entry_manifest_of_script_entries() {
+  let result = TestProject::new()
+    .file(
+      "src/widget.ts",
+      "import './widget.css';\nimport('./lazy');\nconsole.log('widget');\n",
+    )
+    .file("src/widget.css", ".widget { color: red; }\n")
+    .file("src/lazy.ts", "export const lazy = 'lazy';\n")
+    .input("widget", "./src/widget.ts")
+    .config(|config| {
+      config.output.public_path = "/static/".to_string();
+      config.entry_manifest = Some(Box::new(EntryManifestConfig {
+        loader: Some("[entryName].tags.html".to_string()),
+        ..Default::default()
+      }));
+    })
+    .compile()
+    .unwrap();
+
+  let manifest =
+    serde_json::from_str::<Value>(&result.resource("entry-manifest.json").unwrap()).unwrap();
+  let widget = &manifest["widget"];
+  assert_eq!(widget["file"], "/static/widget.js");
+
+  let css = widget["css"].as_array().unwrap();
+  assert_eq!(css.len(), 1);
+  let css = css[0].as_str().unwrap();
+  assert!(css.starts_with("/static/") && css.ends_with(".css"));
+  // the css of the entry is extracted
+  let css_resource = result.resource(css.trim_start_matches("/static/")).unwrap();
+  assert!(css_resource.contains("color: red"));
+  // the css is not loaded again by the runtime
+  assert!(result
+    .resource("widget.js")
+    .unwrap()
+    .contains(&format!("'{}'", css.trim_start_matches("/static/"))));
+
+  let tags = result.resource("widget.tags.html").unwrap();
+  assert_eq!(
+    tags,
+    format!(
+      "<link rel=\"stylesheet\" href=\"{css}\">\n<script type=\"module\" src=\"/static/widget.js\"></script>\n"
+    )
+  );
+}
+
+#[test]
+fn entry_manifest_with_persistent_cache() {
+  let result = TestProject::new()
+    .file("src/widget.ts", "console.log('widget');\n")
+    .input("widget", "./src/widget.ts")
+    .config(|config| {
+      config.persistent_cache = Box::new(PersistentCacheConfig::Bool(true));
+      config.entry_manifest = Some(Box::new(EntryManifestConfig {
+        loader: Some("[entryName].tags.html".to_string()),
+        ..Default::default()
+      }));
+    })
+    .compile()
+    .unwrap();
+
+  // the manifest and the loader snippets are not generated by modules, they are never pruned as resources of removed modules
+  assert!(result.resource("entry-manifest.json").is_some());
+  assert!(result.resource("widget.tags.html").is_some());
+}
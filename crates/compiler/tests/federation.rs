@@ -0,0 +1,109 @@
+use std::collections::{BTreeMap, HashMap};
+
+use farmfe_core::{
+  config::{
+    federation::{
+      FederationConfig, FederationManifest, FederationSharedConfig,
+      FEDERATION_MANIFEST_RESOURCE_TYPE,
+    },
+    Mode,
+  },
+  module::ModuleId,
+  resource::ResourceType,
+  serde_json,
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+fn federation_config() -> FederationConfig {
+  FederationConfig {
+    name: "app".to_string(),
+    exposes: BTreeMap::from([("./Button".to_string(), "./Button.ts".to_string())]),
+    remotes: BTreeMap::from([(
+      "app2".to_string(),
+      "http://localhost:3001/federation-manifest.json".to_string(),
+    )]),
+    shared: BTreeMap::from([(
+      "shared-dep".to_string(),
+      FederationSharedConfig {
+        singleton: true,
+        ..Default::default()
+      },
+    )]),
+    ..Default::default()
+  }
+}
+
+#[test]
+fn federation_expose_and_consume() {
+  fixture!("tests/fixtures/federation/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler =
+      create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        config.federation = Some(Box::new(federation_config()));
+        (config, plugins)
+      });
+    compiler.compile().unwrap();
+
+    let resources_map = compiler.context().resources_map.clone().into_read_only();
+    let manifest_resource = &resources_map.get("federation-manifest.json").unwrap();
+    assert!(matches!(
+      &manifest_resource.resource_type,
+      ResourceType::Custom(ty) if ty == FEDERATION_MANIFEST_RESOURCE_TYPE
+    ));
+
+    let manifest: FederationManifest = serde_json::from_slice(&manifest_resource.bytes).unwrap();
+    assert_eq!(manifest.name, "app");
+
+    let button = &manifest.exposes["./Button"];
+    assert_eq!(
+      button.module_id,
+      ModuleId::from("Button.ts").id(Mode::Production)
+    );
+    assert!(button.resources.iter().any(|r| r.path == "app_Button.js"));
+    assert!(resources_map.contains_key("app_Button.js"));
+    assert!(resources_map.contains_key("app.js"));
+    // the consumers load the container entry to set up the module system of this build
+    assert_eq!(
+      manifest.namespace,
+      compiler.context().config.runtime.namespace
+    );
+    assert!(manifest.entry.iter().any(|r| r.path == "app.js"));
+
+    let shared = &manifest.shared["shared-dep"];
+    assert_eq!(shared.version, "1.2.3");
+    assert_eq!(shared.required_version, "^1.2.3");
+    assert!(shared.singleton);
+
+    let index = String::from_utf8(resources_map.get("index.js").unwrap().bytes.clone()).unwrap();
+    assert!(index.contains("federationShared="));
+
+    // the dynamic import of the remote module loads the manifest of the remote build at runtime
+    assert!(resources_map.values().any(|r| {
+      let code = String::from_utf8_lossy(&r.bytes);
+      code.contains("http://localhost:3001/federation-manifest.json") && code.contains("./Header")
+    }));
+  });
+}
+
+#[test]
+fn federation_static_import_of_remote() {
+  fixture!("tests/fixtures/federation/static.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler =
+      create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+        config.input = HashMap::from([("static".to_string(), "./static.ts".to_string())]);
+        config.federation = Some(Box::new(federation_config()));
+        (config, plugins)
+      });
+
+    let err = compiler.compile().unwrap_err();
+    assert!(err
+      .to_string()
+      .contains("Remote module `app2/Header` should be imported dynamically"));
+  });
+}
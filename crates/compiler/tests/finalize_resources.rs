@@ -0,0 +1,75 @@
+use std::sync::{
+  atomic::{AtomicBool, Ordering},
+  Arc,
+};
+
+use farmfe_compiler::testing::TestProject;
+use farmfe_core::{
+  context::CompilationContext,
+  error::Result,
+  plugin::{Plugin, PluginFinalizeResourcesHookParams},
+  resource::{Resource, ResourceType},
+};
+
+#[derive(Default)]
+struct FinalizePlugin {
+  taken_while_finalizing: AtomicBool,
+}
+
+impl Plugin for FinalizePlugin {
+  fn name(&self) -> &str {
+    "FinalizePlugin"
+  }
+
+  fn finalize_resources(
+    &self,
+    param: &mut PluginFinalizeResourcesHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    // the resources are moved to the hooks instead of being cloned
+    let taken = param
+      .resources_map
+      .keys()
+      .all(|name| !context.resources_map.contains_key(name));
+    self.taken_while_finalizing.store(taken, Ordering::SeqCst);
+
+    // a resource emitted by another thread meanwhile
+    context.resources_map.insert(
+      "emitted.txt".to_string(),
+      Resource {
+        name: "emitted.txt".to_string(),
+        bytes: b"emitted".to_vec(),
+        resource_type: ResourceType::Asset("txt".to_string()),
+        ..Default::default()
+      },
+    );
+
+    param.resources_map.insert(
+      "finalized.txt".to_string(),
+      Resource {
+        name: "finalized.txt".to_string(),
+        bytes: b"finalized".to_vec(),
+        resource_type: ResourceType::Asset("txt".to_string()),
+        ..Default::default()
+      },
+    );
+
+    Ok(Some(()))
+  }
+}
+
+#[test]
+fn take_resources_while_finalizing() {
+  let plugin = Arc::new(FinalizePlugin::default());
+  let result = TestProject::new()
+    .file("index.ts", "console.log('index');\n")
+    .input("index", "./index.ts")
+    .plugin(plugin.clone())
+    .compile()
+    .unwrap();
+
+  assert!(plugin.taken_while_finalizing.load(Ordering::SeqCst));
+  assert!(result.resource("index.js").unwrap().contains("index"));
+  assert_eq!(result.resource("finalized.txt").unwrap(), "finalized");
+  assert_eq!(result.resource("emitted.txt").unwrap(), "emitted");
+}
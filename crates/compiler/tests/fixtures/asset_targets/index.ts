@@ -0,0 +1,4 @@
+import hello from './hello.txt';
+import './index.css';
+
+console.log(hello);
@@ -0,0 +1,4 @@
+#!/usr/bin/env -S node --enable-source-maps
+import { greet } from "./greet";
+
+console.log(greet(process.argv[2]));
@@ -0,0 +1,4 @@
+import a from "./a/addon.node";
+import b from "./b/addon.node";
+
+console.log(a, b);
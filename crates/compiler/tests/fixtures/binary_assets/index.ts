@@ -0,0 +1,4 @@
+import addon from "./a/addon.node";
+import model from "./model.onnx";
+
+console.log(addon, model);
@@ -0,0 +1,5 @@
+export function render(root: HTMLElement | null) {
+  if (root) {
+    root.textContent = 'native esm';
+  }
+}
@@ -0,0 +1,6 @@
+import { b } from './b';
+
+export function a() {
+  import('./lazy').then(({ lazy }) => lazy());
+  return b();
+}
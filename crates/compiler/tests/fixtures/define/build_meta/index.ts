@@ -0,0 +1,8 @@
+const version = import.meta.env.PACKAGE_VERSION;
+const name = process.env.PACKAGE_NAME;
+const commit = import.meta.env.GIT_COMMIT;
+const time = import.meta.env.BUILD_TIME;
+const mode = import.meta.env.MODE;
+const { PACKAGE_VERSION } = import.meta.env;
+
+export { version, name, commit, time, mode, PACKAGE_VERSION };
@@ -0,0 +1,3 @@
+import { mode, apiUrl } from './shared';
+
+console.log('admin', mode, apiUrl);
@@ -0,0 +1,2 @@
+export const mode = ADMIN_BUILD ? 'admin' : 'public';
+export const apiUrl = API_URL;
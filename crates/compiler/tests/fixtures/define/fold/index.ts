@@ -0,0 +1,15 @@
+if (typeof window !== 'undefined') {
+  import('./browser');
+}
+
+const mode = process.env.NODE_ENV !== 'production' ? 'development' : 'production';
+const length = process.env.NODE_ENV.length;
+const sha = __BUILD_INFO__.sha;
+const time = __BUILD_INFO__['time'];
+const info = __BUILD_INFO__;
+
+if (__DEV__) {
+  var devOnly = true;
+}
+
+export { mode, length, sha, time, info, devOnly };
@@ -0,0 +1,11 @@
+const env = process.env.NODE_ENV;
+const apiUrl = import.meta.env.FARM_API_URL;
+const apiUrlFromGlobal = globalThis.process.env.FARM_API_URL;
+const notDefined = process.env.FARM_API_URL_V2;
+const str = 'process.env.NODE_ENV';
+
+function shadowed(process: any) {
+  return process.env.NODE_ENV;
+}
+
+export default { env, apiUrl, apiUrlFromGlobal, notDefined, str, shadowed, __DEV__ };
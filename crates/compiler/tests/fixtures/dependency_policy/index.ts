@@ -0,0 +1,3 @@
+import { render } from './src/ui/app';
+
+render();
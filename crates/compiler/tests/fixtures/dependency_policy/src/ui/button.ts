@@ -0,0 +1,3 @@
+import { query } from '../server/db';
+
+export const button = () => `button ${query()}`;
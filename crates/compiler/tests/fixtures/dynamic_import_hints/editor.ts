@@ -0,0 +1 @@
+export const editor = 'editor';
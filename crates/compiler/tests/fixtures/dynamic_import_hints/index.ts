@@ -0,0 +1,3 @@
+import(/* farmPreload: true */ './editor');
+import(/* farmPrefetch: true */ './help');
+import(/* farmChunkName: "user-settings" */ './settings');
@@ -0,0 +1,3 @@
+import { greet } from 'shared-dep';
+
+export const Button = () => greet('button');
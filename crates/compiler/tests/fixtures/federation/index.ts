@@ -0,0 +1,5 @@
+import { greet } from 'shared-dep';
+
+console.log(greet('app'));
+
+import('app2/Header').then(({ Header }) => Header());
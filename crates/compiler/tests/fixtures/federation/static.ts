@@ -0,0 +1,3 @@
+import { Header } from 'app2/Header';
+
+Header();
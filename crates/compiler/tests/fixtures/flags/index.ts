@@ -0,0 +1,14 @@
+import { shared } from './shared';
+
+if (FARM_FLAGS.enterprise) {
+  import('./enterprise');
+  import('./shared');
+}
+
+export const edition = FARM_FLAGS.enterprise ? 'enterprise' : 'community';
+
+if (!FARM_FLAGS.beta) {
+  console.log('stable', shared);
+} else {
+  require('./beta');
+}
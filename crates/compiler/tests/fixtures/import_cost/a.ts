@@ -0,0 +1,4 @@
+import { b } from './b';
+import { shared } from './shared';
+
+export const a = `a ${b} ${shared}`;
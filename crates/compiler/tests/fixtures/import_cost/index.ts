@@ -0,0 +1,5 @@
+import { a } from './a';
+import { shared } from './shared';
+
+console.log(a, shared);
+import('./lazy').then((m) => console.log(m.lazy));
@@ -0,0 +1,4 @@
+import { permissive } from 'permissive';
+import { copyleft } from 'copyleft';
+
+console.log(permissive, copyleft);
@@ -0,0 +1,6 @@
+/**
+ * @license dep v2.0.0
+ * Copyright (c) dep authors
+ */
+// a normal comment
+export const dep = 'dep';
@@ -0,0 +1,4 @@
+/*! index v1.0.0 | MIT */
+import { dep } from './dep';
+
+console.log(dep);
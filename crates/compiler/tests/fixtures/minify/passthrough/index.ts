@@ -0,0 +1,4 @@
+import { vendor } from './vendor.min.js';
+
+const message = 'index ' + vendor();
+console.log(message);
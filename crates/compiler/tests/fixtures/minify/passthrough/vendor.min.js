@@ -0,0 +1,4 @@
+export function vendor() {
+  const keptVendorName = 'vendor';
+  return keptVendorName;
+}
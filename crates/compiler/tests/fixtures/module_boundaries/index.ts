@@ -0,0 +1,3 @@
+import { getUser } from './utils';
+
+console.log(getUser());
@@ -0,0 +1,3 @@
+import 'server-only';
+
+export const secret = 'SECRET_TOKEN';
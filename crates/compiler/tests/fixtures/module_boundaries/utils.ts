@@ -0,0 +1,5 @@
+import { secret } from './secret';
+
+export function getUser() {
+  return { name: 'farm', token: secret };
+}
@@ -0,0 +1,4 @@
+import './system.js';
+import { a } from './normalized.js';
+
+console.log(a);
@@ -0,0 +1,2 @@
+// system
+export const a = 1;
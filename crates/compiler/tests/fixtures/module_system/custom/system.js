@@ -0,0 +1,3 @@
+System.register([], function () {
+  return { execute: function () {} };
+});
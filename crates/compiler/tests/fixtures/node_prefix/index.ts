@@ -0,0 +1,3 @@
+import { join } from './utils';
+
+console.log(join('a', 'b'));
@@ -0,0 +1,6 @@
+import path from 'node:path';
+import { readFileSync } from 'node:fs';
+
+export function join(a: string, b: string) {
+  return readFileSync ? path.join(a, b) : a + b;
+}
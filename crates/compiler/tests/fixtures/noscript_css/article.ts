@@ -0,0 +1,3 @@
+import './article.css';
+
+export const article = 'article';
@@ -0,0 +1,3 @@
+import './comments.css';
+
+export const comments = 'comments';
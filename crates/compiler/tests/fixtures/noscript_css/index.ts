@@ -0,0 +1,4 @@
+import './index.css';
+
+import(/* farmChunkName: "article" */ './article');
+import(/* farmChunkName: "comments" */ './comments');
@@ -0,0 +1,3 @@
+import { message } from './panic';
+
+console.log(message);
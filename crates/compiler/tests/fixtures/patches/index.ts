@@ -0,0 +1,3 @@
+import { add, label } from 'broken';
+
+console.log(add(1, 2), label);
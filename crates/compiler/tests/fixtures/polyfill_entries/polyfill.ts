@@ -0,0 +1 @@
+(globalThis as any).__polyfilled = true;
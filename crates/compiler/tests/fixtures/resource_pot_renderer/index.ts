@@ -0,0 +1,3 @@
+import './hello.txt';
+
+console.log('index');
@@ -0,0 +1,4 @@
+import { button } from 'design-system';
+import { lib } from 'lib';
+
+console.log(button, lib);
@@ -0,0 +1,3 @@
+import { greeting } from './App.sfc';
+
+console.log(greeting);
@@ -91,7 +91,12 @@ index_js_cjs();
     if (previousStyle) {
         previousStyle.replaceWith(style);
     } else {
-        document.head.appendChild(style);
+        const styleAnchor = document.querySelector('[data-farm-styles]') || Array.prototype.find.call(document.head.childNodes, (node)=>node.nodeType === 8 && node.data.trim() === 'farm-styles');
+        if (styleAnchor) {
+            styleAnchor.parentNode.insertBefore(style, styleAnchor);
+        } else {
+            document.head.appendChild(style);
+        }
     }
     if (module.meta.hot) {
         module.meta.hot.accept();
@@ -116,7 +121,12 @@ index_js_cjs();
     if (previousStyle) {
         previousStyle.replaceWith(style);
     } else {
-        document.head.appendChild(style);
+        const styleAnchor = document.querySelector('[data-farm-styles]') || Array.prototype.find.call(document.head.childNodes, (node)=>node.nodeType === 8 && node.data.trim() === 'farm-styles');
+        if (styleAnchor) {
+            styleAnchor.parentNode.insertBefore(style, styleAnchor);
+        } else {
+            document.head.appendChild(style);
+        }
     }
     if (module.meta.hot) {
         module.meta.hot.accept();
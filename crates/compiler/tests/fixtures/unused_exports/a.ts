@@ -0,0 +1,4 @@
+export const a = 1;
+export const unusedA = 2;
+
+export default 3;
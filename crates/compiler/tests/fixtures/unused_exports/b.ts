@@ -0,0 +1,7 @@
+export function b() {
+  return 'b';
+}
+
+export function unusedB() {
+  return 'unusedB';
+}
@@ -0,0 +1,6 @@
+import { a } from './a';
+
+export { b } from './b';
+export * from './c';
+
+console.log(a);
@@ -0,0 +1,3 @@
+import { value } from './dep';
+
+console.log(value);
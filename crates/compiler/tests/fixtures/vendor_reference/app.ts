@@ -0,0 +1,3 @@
+import { greet } from './lib.ts';
+
+console.log(greet('app'));
@@ -0,0 +1,4 @@
+import { greet } from './lib.ts';
+import { greet as aliasedGreet } from 'greeter';
+
+console.log(greet('provider'), aliasedGreet('alias'));
@@ -0,0 +1,3 @@
+const worker = new Worker(new URL('./worker.ts', import.meta.url));
+
+worker.postMessage('ping');
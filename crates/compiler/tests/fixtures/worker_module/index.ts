@@ -0,0 +1,5 @@
+const worker = new Worker(new URL('./worker.ts', import.meta.url), {
+  type: 'module'
+});
+
+worker.postMessage('ping');
@@ -0,0 +1,71 @@
+use std::collections::{BTreeMap, HashMap};
+
+use farmfe_core::{
+  config::flags::{FlagPrunedImport, FlagsConfig, FlagsReport},
+  serde_json,
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[test]
+fn flags_prune_dead_branches() {
+  fixture!("tests/fixtures/flags/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler =
+      create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        config.flags = Some(Box::new(FlagsConfig {
+          values: BTreeMap::from([
+            ("enterprise".to_string(), false),
+            ("beta".to_string(), false),
+          ]),
+          ..Default::default()
+        }));
+        (config, plugins)
+      });
+    compiler.compile().unwrap();
+
+    let module_graph = compiler.context().module_graph.read();
+    assert!(!module_graph.has_module(&"enterprise.ts".into()));
+    assert!(!module_graph.has_module(&"beta.ts".into()));
+    assert!(module_graph.has_module(&"shared.ts".into()));
+    drop(module_graph);
+
+    let resources_map = compiler.context().resources_map.clone().into_read_only();
+    let index = String::from_utf8_lossy(&resources_map.get("index.js").unwrap().bytes);
+    assert!(index.contains("'community'"));
+    assert!(!index.contains("'enterprise'"));
+
+    let report: FlagsReport =
+      serde_json::from_slice(&resources_map.get("flags-report.json").unwrap().bytes).unwrap();
+
+    let enterprise = &report["enterprise"];
+    assert!(!enterprise.value);
+    assert_eq!(
+      enterprise.pruned_imports,
+      vec![
+        FlagPrunedImport {
+          importer: "index.ts".to_string(),
+          source: "./enterprise".to_string(),
+          module_id: Some("enterprise.ts".to_string()),
+        },
+        FlagPrunedImport {
+          importer: "index.ts".to_string(),
+          source: "./shared".to_string(),
+          module_id: Some("shared.ts".to_string()),
+        },
+      ]
+    );
+    // shared.ts is still imported statically
+    assert_eq!(enterprise.removed_modules, vec!["enterprise.ts"]);
+    assert_eq!(enterprise.removed_chunks, vec!["enterprise.ts"]);
+
+    let beta = &report["beta"];
+    assert_eq!(beta.removed_modules, vec!["beta.ts"]);
+    // removed by a require, no chunk was generated for it
+    assert!(beta.removed_chunks.is_empty());
+  });
+}
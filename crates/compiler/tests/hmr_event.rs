@@ -0,0 +1,73 @@
+use std::sync::{Arc, Mutex};
+
+use farmfe_compiler::testing::TestProject;
+use farmfe_core::{
+  context::{hmr_channel::HmrCustomEvent, CompilationContext},
+  error::Result,
+  plugin::Plugin,
+  serde_json::json,
+};
+
+/// Replies the route inspector of the client with the routes
+struct RoutesInspectorPlugin;
+
+impl Plugin for RoutesInspectorPlugin {
+  fn name(&self) -> &str {
+    "RoutesInspectorPlugin"
+  }
+
+  fn hmr_event(
+    &self,
+    event: &HmrCustomEvent,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if event.event != "routes-inspector:get" {
+      return Ok(None);
+    }
+
+    context.hmr_channel.send(
+      "routes-inspector:routes",
+      json!({ "routes": ["/"], "requestId": event.data["requestId"] }),
+    );
+
+    Ok(Some(()))
+  }
+}
+
+#[test]
+fn hmr_events_between_plugins_and_clients() {
+  let result = TestProject::new()
+    .file("index.ts", "console.log('index');\n")
+    .input("index", "./index.ts")
+    .plugin(Arc::new(RoutesInspectorPlugin))
+    .compile()
+    .unwrap();
+  let context = result.compiler().context();
+
+  let sent = Arc::new(Mutex::new(vec![]));
+  let sent_clone = sent.clone();
+  context
+    .hmr_channel
+    .subscribe(move |event| sent_clone.lock().unwrap().push(event.clone()));
+
+  for event in ["other-plugin:event", "routes-inspector:get"] {
+    context
+      .plugin_driver
+      .hmr_event(
+        &HmrCustomEvent {
+          event: event.to_string(),
+          data: json!({ "requestId": 1 }),
+        },
+        context,
+      )
+      .unwrap();
+  }
+
+  assert_eq!(
+    *sent.lock().unwrap(),
+    vec![HmrCustomEvent {
+      event: "routes-inspector:routes".to_string(),
+      data: json!({ "routes": ["/"], "requestId": 1 }),
+    }]
+  );
+}
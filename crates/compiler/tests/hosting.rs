@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use farmfe_core::config::hosting::{
+  HostingConfig, HostingProvider, HostingRedirect, HostingRewrite,
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[test]
+fn hosting_config() {
+  fixture!("tests/fixtures/hosting/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap().to_path_buf();
+    let compiler = create_compiler_with_args(cwd, crate_path, |mut config, plugins| {
+      config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+      config.hosting = Some(Box::new(HostingConfig {
+        redirects: vec![HostingRedirect {
+          from: "/docs/*".to_string(),
+          to: "/guide/:splat".to_string(),
+          ..Default::default()
+        }],
+        rewrites: vec![HostingRewrite {
+          from: "/api/*".to_string(),
+          to: "/api.html".to_string(),
+        }],
+        immutable: vec!["/assets/*".to_string()],
+        precompressed: true,
+        ..Default::default()
+      }));
+      (config, plugins)
+    });
+    compiler.compile().unwrap();
+
+    let resources_map = compiler.context().resources_map.clone().into_read_only();
+    let content =
+      |name: &str| String::from_utf8(resources_map.get(name).unwrap().bytes.clone()).unwrap();
+
+    assert_eq!(
+      content("_redirects"),
+      "/docs/* /guide/:splat 301\n/api/* /api.html 200\n/* /index.html 200\n"
+    );
+    assert_eq!(
+      content("_headers"),
+      "/assets/*\n  Cache-Control: public, max-age=31536000, immutable\n"
+    );
+
+    let vercel: farmfe_core::serde_json::Value =
+      farmfe_core::serde_json::from_str(&content("vercel.json")).unwrap();
+    assert_eq!(vercel["redirects"][0]["source"], "/docs/:splat*");
+    assert_eq!(vercel["redirects"][0]["destination"], "/guide/:splat*");
+    assert_eq!(vercel["redirects"][0]["statusCode"], 301);
+    assert_eq!(vercel["rewrites"][1]["source"], "/:splat*");
+    assert_eq!(vercel["rewrites"][1]["destination"], "/index.html");
+    assert_eq!(vercel["headers"][0]["source"], "/assets/:splat*");
+
+    let nginx = content("nginx.conf");
+    assert!(nginx.contains("gzip_static on;"));
+    assert!(nginx.contains("location ~ ^/docs/(.*)$ {\n  return 301 /guide/$1;\n}"));
+    assert!(nginx.contains("rewrite ^/api/(.*)$ /api.html last;"));
+    assert!(nginx.contains("location ^~ /assets/ {"));
+    assert!(nginx.contains("try_files $uri $uri/ /index.html;"));
+  });
+}
+
+#[test]
+fn hosting_config_of_providers() {
+  fixture!("tests/fixtures/hosting/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap().to_path_buf();
+    let compiler = create_compiler_with_args(cwd, crate_path, |mut config, plugins| {
+      config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+      config.hosting = Some(Box::new(HostingConfig {
+        providers: vec![HostingProvider::Netlify],
+        ..Default::default()
+      }));
+      (config, plugins)
+    });
+    compiler.compile().unwrap();
+
+    let resources_map = compiler.context().resources_map.clone().into_read_only();
+    assert!(resources_map.contains_key("_redirects"));
+    // nothing to cache
+    assert!(!resources_map.contains_key("_headers"));
+    assert!(!resources_map.contains_key("vercel.json"));
+    assert!(!resources_map.contains_key("nginx.conf"));
+  });
+}
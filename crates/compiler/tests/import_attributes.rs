@@ -0,0 +1,46 @@
+use farmfe_compiler::testing::TestProject;
+use farmfe_core::config::{config_regex::ConfigRegex, ModuleFormat, TargetEnv};
+
+#[test]
+fn import_attributes_of_externals() {
+  let result = TestProject::new()
+    .file(
+      "index.ts",
+      r#"import data from "ext-data.json" with { type: "json" };
+import plain from "ext-plain.json";
+import local from "./data.txt" with { type: "json" };
+
+console.log(data, plain, local.hello, import("ext-lazy.json", { with: { type: "json" } }));
+"#,
+    )
+    .file("data.txt", r#"{ "hello": "farm" }"#)
+    .input("index", "./index.ts")
+    .config(|config| {
+      config.output.target_env = TargetEnv::Node;
+      config.output.format = ModuleFormat::EsModule;
+      config.external = vec![ConfigRegex::new("^ext-")];
+    })
+    .compile()
+    .unwrap();
+
+  let code = result.resource("index.js").unwrap();
+
+  // only the externals imported with the attribute keep it
+  assert!(
+    code.contains(r#"from "ext-data.json" with { type: "json" }"#),
+    "{code}"
+  );
+  assert!(code.contains(r#"from "ext-plain.json";"#), "{code}");
+  assert!(code.contains(r#"import("ext-lazy.json", {"#), "{code}");
+  // the query is only used to tell the externals apart in the module system
+  assert!(
+    !code.contains(r#"from "ext-data.json?type=json""#),
+    "{code}"
+  );
+  assert!(
+    !code.contains(r#"import("ext-lazy.json?type=json""#),
+    "{code}"
+  );
+  // the local module is loaded by the json pipeline whatever its extension is
+  assert!(code.contains(r#""farm""#), "{code}");
+}
@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[test]
+fn import_cost() {
+  fixture!("tests/fixtures/import_cost/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap().to_path_buf();
+    let compiler = create_compiler_with_args(cwd.clone(), crate_path, |mut config, plugins| {
+      config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+      (config, plugins)
+    });
+    compiler.compile().unwrap();
+
+    let cost = compiler.import_cost("./a", "index.ts").unwrap();
+    assert_eq!(cost.module_id, "a.ts".into());
+    assert_eq!(cost.modules, vec!["a.ts".into(), "b.ts".into()]);
+    assert_eq!(cost.shared_modules, vec!["shared.ts".into()]);
+    assert!(cost.size > 0);
+    assert!(cost.gzip_size > 0);
+
+    // shared.ts is bundled by importing a.ts anyway
+    let importer = cwd.join("index.ts").to_string_lossy().to_string();
+    let cost = compiler.import_cost("./shared", &importer).unwrap();
+    assert!(cost.modules.is_empty());
+    assert_eq!(cost.shared_modules, vec!["shared.ts".into()]);
+    assert_eq!(cost.size, 0);
+
+    let cost = compiler.import_cost("./lazy", &importer).unwrap();
+    assert_eq!(cost.modules, vec!["lazy.ts".into()]);
+
+    assert!(compiler.import_cost("./a", "missing.ts").is_err());
+  });
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use farmfe_compiler::testing::TestProject;
+use farmfe_core::{
+  config::{licenses::LicensesConfig, persistent_cache::PersistentCacheConfig},
+  serde_json,
+  stats::PackageLicenseStats,
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[test]
+fn licenses_report() {
+  fixture!("tests/fixtures/licenses/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler =
+      create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        config.licenses = Some(Box::default());
+        (config, plugins)
+      });
+    compiler.compile().unwrap();
+
+    let resources_map = compiler.context().resources_map.clone().into_read_only();
+    let report: Vec<PackageLicenseStats> =
+      serde_json::from_slice(&resources_map.get("licenses.json").unwrap().bytes).unwrap();
+
+    assert_eq!(
+      report,
+      vec![
+        PackageLicenseStats {
+          name: "copyleft".to_string(),
+          version: "2.0.0".to_string(),
+          license: "GPL-3.0".to_string(),
+          license_file: None,
+        },
+        PackageLicenseStats {
+          name: "permissive".to_string(),
+          version: "1.0.0".to_string(),
+          license: "MIT".to_string(),
+          license_file: Some("node_modules/permissive/LICENSE".to_string()),
+        },
+      ]
+    );
+
+    let notices = String::from_utf8(
+      resources_map
+        .get("THIRD-PARTY-NOTICES.txt")
+        .unwrap()
+        .bytes
+        .clone(),
+    )
+    .unwrap();
+    assert_eq!(
+      notices,
+      "copyleft@2.0.0\nLicense: GPL-3.0\n\n---\n\npermissive@1.0.0\nLicense: MIT\n\nMIT License\n\nCopyright (c) permissive authors\n"
+    );
+  });
+}
+
+#[test]
+fn licenses_deny() {
+  fixture!("tests/fixtures/licenses/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler =
+      create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        config.licenses = Some(Box::new(LicensesConfig {
+          deny: vec!["GPL-3.0".to_string()],
+          ..Default::default()
+        }));
+        (config, plugins)
+      });
+    let err = compiler.compile().unwrap_err().to_string();
+
+    assert!(err.contains("copyleft@2.0.0: GPL-3.0"));
+    assert!(!err.contains("permissive"));
+  });
+}
+
+#[test]
+fn licenses_report_with_persistent_cache() {
+  let result = TestProject::new()
+    .dir("tests/fixtures/licenses")
+    .unwrap()
+    .input("index", "./index.ts")
+    .config(|config| {
+      config.persistent_cache = Box::new(PersistentCacheConfig::Bool(true));
+      config.licenses = Some(Box::default());
+    })
+    .compile()
+    .unwrap();
+
+  // the reports are not generated by modules, they are never pruned as resources of removed modules
+  assert!(result.resource("licenses.json").is_some());
+  assert!(result.resource("THIRD-PARTY-NOTICES.txt").is_some());
+}
@@ -0,0 +1,33 @@
+use farmfe_compiler::testing::TestProject;
+
+#[test]
+fn metrics() {
+  let result = TestProject::new()
+    .file("index.ts", "import './a';\nconsole.log('index');\n")
+    .file("a.ts", "console.log('a');\n")
+    .input("index", "./index.ts")
+    .compile()
+    .unwrap();
+
+  let metrics = result.compiler().metrics();
+  let lines = metrics.lines().collect::<Vec<_>>();
+  assert!(lines.contains(&"farm_builds_total 1"), "{metrics}");
+  assert!(lines.contains(&"farm_build_failures_total 0"), "{metrics}");
+  assert!(lines.contains(&"farm_hmr_updates_total 0"), "{metrics}");
+  assert!(
+    lines.contains(&"farm_build_duration_seconds_count 1"),
+    "{metrics}"
+  );
+
+  let modules = result
+    .compiler()
+    .context()
+    .module_graph
+    .read()
+    .modules()
+    .len();
+  assert!(
+    lines.contains(&format!("farm_modules {modules}").as_str()),
+    "{metrics}"
+  );
+}
@@ -1,10 +1,19 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
+use farmfe_compiler::testing::{TestCompileResult, TestProject};
+use farmfe_core::{
+  config::{bool_or_obj::BoolOrObj, comments::CommentsConfig},
+  context::CompilationContext,
+  error::Result,
+  plugin::Plugin,
+  resource::resource_pot::{ResourcePot, ResourcePotType},
+  serde_json::json,
+};
 use farmfe_testing_helpers::fixture;
 
 mod common;
 
-use common::{assert_compiler_result, create_compiler};
+use common::{assert_compiler_result, create_compiler, create_compiler_with_args};
 
 #[test]
 fn minify_script_test() {
@@ -71,3 +80,191 @@ fn minify_html_test() {
     }
   );
 }
+
+#[test]
+fn extract_license_comments_test() {
+  fixture!(
+    "tests/fixtures/minify/license_comments/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          config.minify = Box::new(BoolOrObj::from(true));
+          config.output.extract_license_comments = true;
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let index =
+        String::from_utf8_lossy(&resources_map.get("index.js").unwrap().bytes).to_string();
+      assert!(!index.contains("index v1.0.0"));
+      assert!(!index.contains("@license"));
+
+      let license = resources_map.get("index.js.LICENSE.txt").unwrap();
+      let license = String::from_utf8_lossy(&license.bytes);
+      assert!(license.contains("/*! index v1.0.0 | MIT */"));
+      assert!(license.contains("@license dep v2.0.0"));
+      assert!(!license.contains("a normal comment"));
+      // dep.ts is executed before index.ts
+      assert!(license.find("@license dep").unwrap() < license.find("index v1.0.0").unwrap());
+    }
+  );
+}
+
+#[test]
+fn comments_policy_test() {
+  let compile = |comments: CommentsConfig| {
+    TestProject::new()
+      .file(
+        "index.ts",
+        "/*! index v1.0.0 | MIT */\n// a normal comment\nimport './index.css';\nconsole.log('index');\n",
+      )
+      .file(
+        "index.css",
+        "/*! style v1.0.0 | MIT */\n/* a normal css comment */\n.a { color: red; }\n",
+      )
+      .input("index", "./index.ts")
+      .config(|config| {
+        config.minify = Box::new(BoolOrObj::from(true));
+        config.comments = Box::new(comments);
+      })
+      .compile()
+      .unwrap()
+  };
+  let css = |result: &TestCompileResult| {
+    result
+      .resources()
+      .into_iter()
+      .find(|(name, _)| name.ends_with(".css"))
+      .unwrap()
+      .1
+  };
+
+  // the comments are filtered when rendering, so the license comments survive minification
+  let result = compile(CommentsConfig::License);
+  let index = result.resource("index.js").unwrap();
+  assert!(index.contains("index v1.0.0"));
+  assert!(!index.contains("a normal comment"));
+  assert!(css(&result).contains("/*! style v1.0.0 | MIT */"));
+  assert!(!css(&result).contains("a normal css comment"));
+
+  let result = compile(CommentsConfig::All);
+  assert!(result
+    .resource("index.js")
+    .unwrap()
+    .contains("a normal comment"));
+
+  let result = compile(CommentsConfig::None);
+  assert!(!result
+    .resource("index.js")
+    .unwrap()
+    .contains("index v1.0.0"));
+  assert!(!css(&result).contains("style v1.0.0"));
+}
+
+#[test]
+fn minify_passthrough_test() {
+  fixture!(
+    "tests/fixtures/minify/passthrough/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          config.minify = Box::new(BoolOrObj::Obj(json!({ "mode": "minify-resource-pot" })));
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let passthrough = resources_map
+        .values()
+        .find(|r| r.name.contains("_passthrough") && r.name.ends_with(".js"))
+        .unwrap();
+      // excluded by the default `minify.exclude`, so the names are not mangled
+      assert!(String::from_utf8_lossy(&passthrough.bytes).contains("keptVendorName"));
+
+      let index = String::from_utf8_lossy(&resources_map.get("index.js").unwrap().bytes);
+      assert!(!index.contains("keptVendorName"));
+      assert!(!index.contains("const message"));
+    }
+  );
+}
+
+struct CommentMinifierPlugin;
+
+impl Plugin for CommentMinifierPlugin {
+  fn name(&self) -> &str {
+    "CommentMinifierPlugin"
+  }
+
+  fn priority(&self) -> i32 {
+    101
+  }
+
+  fn minify_resource_pot(
+    &self,
+    resource_pot: &mut ResourcePot,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if !matches!(resource_pot.resource_pot_type, ResourcePotType::Js) {
+      return Ok(None);
+    }
+
+    resource_pot.meta.rendered_content = Arc::new(format!(
+      "/* custom minifier */{}",
+      resource_pot.meta.rendered_content
+    ));
+
+    Ok(Some(()))
+  }
+}
+
+#[test]
+fn minify_resource_pot_hook_test() {
+  fixture!(
+    "tests/fixtures/minify/passthrough/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, mut plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          config.minify = Box::new(BoolOrObj::Obj(json!({ "mode": "minify-resource-pot" })));
+          plugins.push(Arc::new(CommentMinifierPlugin) as _);
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let index = String::from_utf8_lossy(&resources_map.get("index.js").unwrap().bytes);
+      // the custom minifier replaces the builtin one
+      assert!(index.contains("/* custom minifier */"));
+      assert!(index.contains("const message"));
+    }
+  );
+}
+
+#[test]
+fn minify_disable_js_test() {
+  fixture!(
+    "tests/fixtures/minify/passthrough/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          config.minify = Box::new(BoolOrObj::Obj(
+            json!({ "mode": "minify-resource-pot", "js": false }),
+          ));
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let index = String::from_utf8_lossy(&resources_map.get("index.js").unwrap().bytes);
+      assert!(index.contains("const message"));
+    }
+  );
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use farmfe_core::config::{config_regex::ConfigRegex, Config, TargetEnv};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+fn with_boundaries(mut config: Config, target_env: TargetEnv) -> Config {
+  config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+  config.output.target_env = target_env;
+  config.module_boundaries = Some(Box::default());
+  config
+}
+
+#[test]
+fn server_only_modules_imported_by_browser_entries() {
+  fixture!(
+    "tests/fixtures/module_boundaries/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler = create_compiler_with_args(cwd.to_path_buf(), crate_path, |config, plugins| {
+        (with_boundaries(config, TargetEnv::Browser), plugins)
+      });
+      let err = compiler.compile().unwrap_err().to_string();
+
+      assert!(err.contains("Server only modules are imported by browser entries"));
+      assert!(err.contains("index.ts -> utils.ts -> secret.ts -> server-only"));
+    }
+  );
+}
+
+#[test]
+fn client_only_modules_imported_by_node_entries() {
+  fixture!(
+    "tests/fixtures/module_boundaries/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler = create_compiler_with_args(cwd.to_path_buf(), crate_path, |config, plugins| {
+        let mut config = with_boundaries(config, TargetEnv::Node);
+        config.module_boundaries.as_mut().unwrap().client_only =
+          vec![ConfigRegex::new("utils\\.ts$")];
+        (config, plugins)
+      });
+      let err = compiler.compile().unwrap_err().to_string();
+
+      assert!(err.contains("index.ts -> utils.ts"));
+      assert!(!err.contains("secret.ts"));
+    }
+  );
+}
@@ -0,0 +1,125 @@
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::Result,
+  module::{ModuleId, ModuleSystem},
+  plugin::{
+    Plugin, PluginDetectModuleSystemHookParam, PluginModuleSystemInteropHookParam,
+    PluginNormalizeModuleSystemHookParam, PluginRenderModuleSystemHookParam,
+  },
+  swc_ecma_parser::Syntax,
+};
+use farmfe_testing_helpers::fixture;
+use farmfe_toolkit::script::{parse_module, swc_try_with::resolve_module_mark};
+
+use common::create_compiler_with_plugins;
+
+mod common;
+
+/// SystemJS modules, the built-in script plugin does not know them
+struct SystemPlugin;
+
+impl Plugin for SystemPlugin {
+  fn name(&self) -> &str {
+    "system"
+  }
+
+  fn detect_module_system(
+    &self,
+    param: &PluginDetectModuleSystemHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<String>> {
+    if param.content.contains("System.register(") || param.content.starts_with("// system") {
+      return Ok(Some("system".to_string()));
+    }
+
+    Ok(None)
+  }
+
+  fn normalize_module_system(
+    &self,
+    param: &mut PluginNormalizeModuleSystemHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    assert_eq!(param.module_system, "system");
+
+    // normalized.js is already written in esm
+    if param.module_id.to_string().ends_with("normalized.js") {
+      return Ok(Some(()));
+    }
+
+    Ok(None)
+  }
+
+  fn render_module_system(
+    &self,
+    param: &mut PluginRenderModuleSystemHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    let mut ast = parse_module(
+      &param.module_id.to_string(),
+      "module.exports = { system: true };",
+      Syntax::Es(Default::default()),
+      Default::default(),
+    )?
+    .ast;
+    (param.unresolved_mark, param.top_level_mark) = resolve_module_mark(&mut ast, false, context);
+    *param.ast = ast;
+
+    Ok(Some(()))
+  }
+
+  fn module_system_interop(
+    &self,
+    _param: &PluginModuleSystemInteropHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<ModuleSystem>> {
+    Ok(Some(ModuleSystem::CommonJs))
+  }
+}
+
+#[test]
+fn custom_module_system() {
+  fixture!(
+    "tests/fixtures/module_system/custom/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler = create_compiler_with_plugins(
+        HashMap::from([("index".to_string(), "./index.ts".to_string())]),
+        cwd.to_path_buf(),
+        crate_path,
+        false,
+        vec![Arc::new(SystemPlugin) as Arc<dyn Plugin>],
+      );
+      compiler.compile().unwrap();
+
+      let module_graph = compiler.context().module_graph.read();
+      let module_system = |id: &str| {
+        module_graph
+          .module(&ModuleId::from(id))
+          .unwrap()
+          .meta
+          .as_script()
+          .module_system
+          .clone()
+      };
+
+      assert_eq!(
+        module_system("system.js"),
+        ModuleSystem::Custom("system".to_string())
+      );
+      assert_eq!(module_system("normalized.js"), ModuleSystem::EsModule);
+
+      // system.js is rendered by the plugin as commonjs
+      let code = compiler
+        .context()
+        .resources_map
+        .iter()
+        .map(|r| String::from_utf8_lossy(&r.bytes).to_string())
+        .collect::<String>();
+      assert!(code.contains("system: true"), "{code}");
+      assert!(!code.contains("System.register"), "{code}");
+    }
+  );
+}
@@ -0,0 +1,106 @@
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_compiler::testing::TestProject;
+use farmfe_core::{
+  config::module_types::{CustomModuleTypeConfig, CustomModuleTypeTreatAs},
+  context::CompilationContext,
+  error::Result,
+  module::ModuleType,
+  plugin::{Plugin, PluginTransformHookParam, PluginTransformHookResult},
+  serde_json,
+};
+
+/// Exports the source of the shaders as a string
+struct ShaderPlugin;
+
+impl Plugin for ShaderPlugin {
+  fn name(&self) -> &str {
+    "ShaderPlugin"
+  }
+
+  fn transform(
+    &self,
+    param: &PluginTransformHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<PluginTransformHookResult>> {
+    if param.module_type != ModuleType::Custom("glsl".to_string()) {
+      return Ok(None);
+    }
+
+    Ok(Some(PluginTransformHookResult {
+      content: format!(
+        "export default {};",
+        serde_json::to_string(&param.content).unwrap()
+      ),
+      ..Default::default()
+    }))
+  }
+}
+
+fn module_types() -> HashMap<String, CustomModuleTypeConfig> {
+  HashMap::from([
+    (
+      "glsl".to_string(),
+      CustomModuleTypeConfig {
+        treat_as: CustomModuleTypeTreatAs::JsViaPlugin,
+      },
+    ),
+    (
+      ".obj".to_string(),
+      CustomModuleTypeConfig {
+        treat_as: CustomModuleTypeTreatAs::Asset,
+      },
+    ),
+  ])
+}
+
+#[test]
+fn custom_module_types() {
+  let result = TestProject::new()
+    .file(
+      "index.ts",
+      "import shader from './shader.glsl';\nimport model from './model.OBJ';\nconsole.log(shader, model);\n",
+    )
+    .file("shader.glsl", "void main() { gl_FragColor = vec4(1.0); }\n")
+    .file("model.OBJ", "v 0.0 0.0 0.0\n")
+    .input("index", "./index.ts")
+    .config(|config| config.module_types = module_types())
+    .plugin(Arc::new(ShaderPlugin))
+    .compile()
+    .unwrap();
+
+  let index = result.resource("index.js").unwrap();
+  assert!(index.contains("gl_FragColor = vec4(1.0);"));
+
+  let module_graph = result.compiler().context().module_graph.read();
+  assert_eq!(
+    module_graph
+      .module(&"shader.glsl".into())
+      .unwrap()
+      .module_type,
+    ModuleType::Js
+  );
+  drop(module_graph);
+
+  assert!(result
+    .resources()
+    .keys()
+    .any(|name| name.starts_with("model") && name.ends_with(".OBJ")));
+}
+
+#[test]
+fn undeclared_module_type() {
+  let err = TestProject::new()
+    .file(
+      "index.ts",
+      "import shader from './shader.glsl';\nconsole.log(shader);\n",
+    )
+    .file("shader.glsl", "void main() {}\n")
+    .input("index", "./index.ts")
+    .plugin(Arc::new(ShaderPlugin))
+    .compile()
+    .err()
+    .unwrap();
+
+  assert!(err.to_string().contains("shader.glsl"));
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use farmfe_core::config::{
+  config_regex::ConfigRegex,
+  node_prefix::{NodePrefixAction, NodePrefixConfig},
+  Config,
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+fn with_node_prefix(mut config: Config, default: NodePrefixAction) -> Config {
+  config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+  config.resolve.node_prefix = NodePrefixConfig {
+    default,
+    builtins: HashMap::from([(
+      "path".to_string(),
+      NodePrefixAction::Polyfill("path-browserify".to_string()),
+    )]),
+  };
+  config
+}
+
+#[test]
+fn node_prefix_external_by_default() {
+  fixture!("tests/fixtures/node_prefix/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler =
+      create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        config.external = vec![ConfigRegex::new("^node:")];
+        (config, plugins)
+      });
+    compiler.compile().unwrap();
+
+    let module_graph = compiler.context().module_graph.read();
+    assert!(module_graph.module(&"node:fs".into()).unwrap().external);
+    assert!(module_graph.module(&"node:path".into()).unwrap().external);
+  });
+}
+
+#[test]
+fn node_prefix_empty_and_polyfill() {
+  fixture!("tests/fixtures/node_prefix/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler = create_compiler_with_args(cwd.to_path_buf(), crate_path, |config, plugins| {
+      (with_node_prefix(config, NodePrefixAction::Empty), plugins)
+    });
+    compiler.compile().unwrap();
+
+    let module_graph = compiler.context().module_graph.read();
+    assert!(!module_graph.module(&"node:fs".into()).unwrap().external);
+    assert!(!module_graph.has_module(&"node:path".into()));
+    assert!(module_graph
+      .modules()
+      .iter()
+      .any(|m| m.id.to_string().contains("path-browserify")));
+  });
+}
+
+#[test]
+fn node_prefix_error() {
+  fixture!("tests/fixtures/node_prefix/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler = create_compiler_with_args(cwd.to_path_buf(), crate_path, |config, plugins| {
+      (with_node_prefix(config, NodePrefixAction::Error), plugins)
+    });
+    let err = compiler.compile().unwrap_err().to_string();
+
+    assert!(err.contains("`node:fs` is not available in browser builds"));
+    assert!(err.contains("index.ts -> utils.ts -> node:fs"));
+    assert!(!err.contains("node:path"));
+  });
+}
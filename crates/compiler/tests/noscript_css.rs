@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use farmfe_core::config::{
+  config_regex::ConfigRegex,
+  html::{HtmlNoscriptCssConfig, HtmlNoscriptCssInject},
+};
+use farmfe_testing_helpers::fixture;
+mod common;
+
+use crate::common::create_compiler_with_args;
+
+#[test]
+fn noscript_css() {
+  fixture!(
+    "tests/fixtures/noscript_css/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compile = |inject: HtmlNoscriptCssInject| {
+        let compiler = create_compiler_with_args(
+          cwd.to_path_buf(),
+          crate_path.clone(),
+          |mut config, plugins| {
+            config.input = HashMap::from([("index".to_string(), "./index.html".to_string())]);
+            config.html.noscript_css = Some(HtmlNoscriptCssConfig {
+              include: vec![ConfigRegex::new("article\\.css$")],
+              inject,
+            });
+            (config, plugins)
+          },
+        );
+        compiler.compile().unwrap();
+
+        let resources_map = compiler.context().resources_map.clone().into_read_only();
+        String::from_utf8_lossy(&resources_map.get("index.html").unwrap().bytes).to_string()
+      };
+
+      let html = compile(HtmlNoscriptCssInject::Noscript);
+      let noscript = html
+        .split("<noscript>")
+        .nth(1)
+        .and_then(|rest| rest.split("</noscript>").next())
+        .unwrap_or_else(|| panic!("{html}"));
+      assert!(noscript.contains("rel=\"stylesheet\""), "{html}");
+      assert!(noscript.contains("article"), "{html}");
+      // the css not matched by `include` is loaded with its dynamic import only
+      assert!(!noscript.contains("comments"), "{html}");
+      assert!(
+        !html
+          .split("<link")
+          .skip(1)
+          .any(|link| link.split('>').next().unwrap().contains("comments")),
+        "{html}"
+      );
+
+      let html = compile(HtmlNoscriptCssInject::Link);
+      assert!(!html.contains("<noscript>"), "{html}");
+      let initial_loaded_resources = html
+        .split("setInitialLoadedResources([")
+        .nth(1)
+        .and_then(|rest| rest.split("])").next())
+        .unwrap_or_else(|| panic!("{html}"));
+      assert!(initial_loaded_resources.contains("article"), "{html}");
+      assert!(
+        html
+          .split("<link")
+          .skip(1)
+          .any(|link| link.contains("rel=\"stylesheet\"") && link.contains("article")),
+        "{html}"
+      );
+    }
+  );
+}
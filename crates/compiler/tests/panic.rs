@@ -0,0 +1,107 @@
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_core::{
+  context::{CompilationContext, PANIC_DIAGNOSTIC_CODE},
+  error::Result,
+  plugin::{Plugin, PluginTransformHookParam, PluginTransformHookResult},
+  resource::resource_pot::ResourcePot,
+};
+use farmfe_testing_helpers::fixture;
+
+mod common;
+
+use common::create_compiler_with_args;
+
+struct PanicPlugin {
+  panic_in_render: bool,
+}
+
+impl Plugin for PanicPlugin {
+  fn name(&self) -> &str {
+    "PanicPlugin"
+  }
+
+  fn transform(
+    &self,
+    param: &PluginTransformHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<PluginTransformHookResult>> {
+    if !self.panic_in_render && param.resolved_path.ends_with("panic.ts") {
+      panic!("failed to transform panic.ts");
+    }
+
+    Ok(None)
+  }
+
+  fn optimize_resource_pot(
+    &self,
+    resource_pot: &mut ResourcePot,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    // resource pots are rendered in parallel, only panic in the pot of index.ts so the failed pot is always the same
+    if self.panic_in_render
+      && resource_pot
+        .modules()
+        .iter()
+        .any(|m| m.relative_path() == "index.ts")
+    {
+      panic!("failed to optimize resource pot");
+    }
+
+    Ok(None)
+  }
+}
+
+fn compile_with_panic_plugin(panic_in_render: bool, assert_result: impl Fn(String, Vec<String>)) {
+  fixture!("tests/fixtures/panic/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler =
+      create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, mut plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        plugins.push(Arc::new(PanicPlugin { panic_in_render }) as _);
+        (config, plugins)
+      });
+
+    let err = compiler.compile().unwrap_err();
+    let diagnostics = compiler.context().diagnostics.lock();
+    let modules = diagnostics
+      .diagnostics()
+      .iter()
+      .filter(|d| d.code == PANIC_DIAGNOSTIC_CODE)
+      .flat_map(|d| d.modules.iter().map(|m| m.to_string()))
+      .collect();
+
+    assert_result(err.to_string(), modules);
+
+    // the half rendered meta of the panicking resource pot is reset
+    let resource_pot_map = compiler.context().resource_pot_map.read();
+    assert!(resource_pot_map
+      .resource_pots()
+      .into_iter()
+      .filter(|pot| pot
+        .modules()
+        .iter()
+        .any(|m| m.relative_path() == "index.ts"))
+      .all(|pot| pot.meta.rendered_content.is_empty()));
+  });
+}
+
+#[test]
+fn panic_in_module_build() {
+  compile_with_panic_plugin(false, |err, modules| {
+    assert!(err.contains("building module `panic.ts`"));
+    assert!(err.contains("in plugin `PanicPlugin`"));
+    assert!(err.contains("failed to transform panic.ts"));
+    assert_eq!(modules, vec!["panic.ts".to_string()]);
+  });
+}
+
+#[test]
+fn panic_in_resource_pot_render() {
+  compile_with_panic_plugin(true, |err, modules| {
+    assert!(err.contains("rendering resource pot"));
+    assert!(err.contains("in plugin `PanicPlugin`"));
+    assert!(err.contains("failed to optimize resource pot"));
+    assert!(modules.contains(&"index.ts".to_string()));
+  });
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use farmfe_core::config::{
+  config_regex::ConfigRegex,
+  patches::{PatchConfig, PatchReplacement},
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[test]
+fn patches_diff_and_replace() {
+  fixture!("tests/fixtures/patches/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler =
+      create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        config.patches = vec![PatchConfig {
+          test: vec![ConfigRegex::new("node_modules/broken/index\\.js$")],
+          diff: Some("patches/broken+1.0.0.patch".to_string()),
+          replace: vec![PatchReplacement {
+            search: "'broken'".to_string(),
+            replace: "'patched'".to_string(),
+          }],
+        }];
+        (config, plugins)
+      });
+    compiler.compile().unwrap();
+
+    let resources_map = compiler.context().resources_map.clone().into_read_only();
+    // the patched package is bundled into the immutable resource of node_modules
+    let js = resources_map
+      .values()
+      .filter(|r| r.name.ends_with(".js"))
+      .map(|r| String::from_utf8_lossy(&r.bytes).to_string())
+      .collect::<String>();
+
+    assert!(js.contains("return a + b;"));
+    assert!(!js.contains("return a - b;"));
+    assert!(js.contains("'patched'"));
+  });
+}
+
+#[test]
+fn patches_not_found() {
+  fixture!("tests/fixtures/patches/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler =
+      create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        config.patches = vec![PatchConfig {
+          test: vec![ConfigRegex::new("node_modules/broken/")],
+          replace: vec![PatchReplacement {
+            search: "return a * b;".to_string(),
+            replace: "return a + b;".to_string(),
+          }],
+          ..Default::default()
+        }];
+        (config, plugins)
+      });
+    let err = compiler.compile().unwrap_err().to_string();
+
+    assert!(err.contains("`return a * b;` is not found"));
+  });
+}
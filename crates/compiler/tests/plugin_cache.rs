@@ -0,0 +1,85 @@
+use std::sync::{Arc, Mutex};
+
+use farmfe_core::{
+  config::Config,
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  plugin::Plugin,
+};
+
+/// Keeps the loaded cache and writes `cache`, or fails like a plugin reading the cache of another version
+struct CachePlugin {
+  name: &'static str,
+  broken: bool,
+  loaded: Mutex<Option<Vec<u8>>>,
+}
+
+impl CachePlugin {
+  fn new(name: &'static str, broken: bool) -> Arc<Self> {
+    Arc::new(Self {
+      name,
+      broken,
+      loaded: Mutex::new(None),
+    })
+  }
+}
+
+impl Plugin for CachePlugin {
+  fn name(&self) -> &str {
+    self.name
+  }
+
+  fn plugin_cache_loaded(
+    &self,
+    cache: &Vec<u8>,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if self.broken {
+      panic!("incompatible cache");
+    }
+
+    *self.loaded.lock().unwrap() = Some(cache.clone());
+    Ok(Some(()))
+  }
+
+  fn write_plugin_cache(&self, _context: &Arc<CompilationContext>) -> Result<Option<Vec<u8>>> {
+    if self.broken {
+      return Err(CompilationError::GenericError(
+        "cannot serialize".to_string(),
+      ));
+    }
+
+    Ok(Some(b"cache".to_vec()))
+  }
+}
+
+#[test]
+fn plugin_cache_failures_are_isolated() {
+  let broken = CachePlugin::new("broken", true);
+  let healthy = CachePlugin::new("healthy", false);
+  let context = Arc::new(
+    CompilationContext::new(
+      Config::default(),
+      vec![broken as Arc<dyn Plugin>, healthy.clone()],
+    )
+    .unwrap(),
+  );
+  let plugin_cache = &context.cache_manager.plugin_cache;
+
+  plugin_cache.set_cache("broken", b"old".to_vec());
+  plugin_cache.set_cache("healthy", b"old".to_vec());
+
+  context.plugin_driver.plugin_cache_loaded(&context).unwrap();
+
+  assert_eq!(*healthy.loaded.lock().unwrap(), Some(b"old".to_vec()));
+  assert!(plugin_cache.read_cache("broken").is_none());
+  assert!(plugin_cache.read_cache("healthy").is_some());
+
+  context.plugin_driver.write_plugin_cache(&context).unwrap();
+
+  assert!(plugin_cache.read_cache("broken").is_none());
+  assert_eq!(
+    plugin_cache.read_cache("healthy").unwrap().value(),
+    b"cache"
+  );
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use farmfe_core::module::ModuleId;
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[test]
+fn polyfill_entries() {
+  fixture!(
+    "tests/fixtures/polyfill_entries/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          config.polyfill_entries = vec!["./polyfill.ts".to_string()];
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let module_graph = compiler.context().module_graph.read();
+      let index: ModuleId = "index.ts".into();
+      let polyfill: ModuleId = "polyfill.ts".into();
+
+      // the polyfill is the first dependency of the entry
+      let edge = module_graph.edge_info(&index, &polyfill).unwrap();
+      assert_eq!(edge.items()[0].order, 0);
+      assert!(module_graph.has_edge(&index, &"dep.ts".into()));
+      assert!(module_graph.dependencies_ids(&polyfill).is_empty());
+    }
+  );
+}
+
+#[test]
+fn polyfill_entries_in_separate_directories() {
+  fixture!(
+    "tests/fixtures/polyfill_entries/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([
+            ("index".to_string(), "./index.ts".to_string()),
+            ("about".to_string(), "./nested/pages/about.ts".to_string()),
+          ]);
+          config.polyfill_entries = vec!["./polyfill.ts".to_string()];
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      // the relative polyfill is resolved against the root for every entry
+      let module_graph = compiler.context().module_graph.read();
+      let polyfill: ModuleId = "polyfill.ts".into();
+      assert!(module_graph.has_edge(&"index.ts".into(), &polyfill));
+      assert!(module_graph.has_edge(&"nested/pages/about.ts".into(), &polyfill));
+    }
+  );
+}
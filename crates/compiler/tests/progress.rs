@@ -0,0 +1,54 @@
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex},
+};
+
+use farmfe_core::context::progress::BuildPhase;
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[test]
+fn report_build_progress() {
+  fixture!(
+    "tests/fixtures/bundle_stats/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          (config, plugins)
+        });
+      let phases = Arc::new(Mutex::new(vec![]));
+      let phases_clone = phases.clone();
+      compiler
+        .context()
+        .progress
+        .subscribe(move |progress| phases_clone.lock().unwrap().push(progress.phase));
+      compiler.compile().unwrap();
+
+      let mut phases = phases.lock().unwrap().clone();
+      phases.dedup();
+      assert_eq!(
+        phases,
+        vec![
+          BuildPhase::Build,
+          BuildPhase::Generate,
+          BuildPhase::Write,
+          BuildPhase::Finished
+        ]
+      );
+
+      let progress = compiler.context().progress.progress();
+      // modules removed by tree shaking are counted as well
+      let module_count = compiler.context().module_graph.read().modules().len();
+      assert!(progress.modules_resolved >= module_count);
+      assert_eq!(progress.modules_loaded, progress.modules_transformed);
+      assert!(progress.modules_transformed > 0);
+      assert_eq!(progress.resource_pots_rendered, progress.resource_pots);
+      assert!(progress.resources_written > 0);
+    }
+  );
+}
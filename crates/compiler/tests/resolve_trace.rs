@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use farmfe_compiler::testing::TestProject;
+use farmfe_core::{module::ModuleId, plugin::ResolveTrace};
+
+#[test]
+fn resolve_trace() {
+  let result = TestProject::new()
+    .file(
+      "index.ts",
+      "import { a } from 'pkg';\nimport { b } from 'legacy';\nimport { c } from 'aliased';\nimport { d } from './d';\nconsole.log(a, b, c, d);\n",
+    )
+    .file("d.ts", "export const d = 'd';\n")
+    .file(
+      "node_modules/pkg/package.json",
+      r#"{ "name": "pkg", "version": "1.2.3", "exports": { ".": { "import": "./esm.js", "default": "./cjs.js" } } }"#,
+    )
+    .file("node_modules/pkg/esm.js", "export const a = 'a';\n")
+    .file("node_modules/pkg/cjs.js", "exports.a = 'a';\n")
+    .file(
+      "node_modules/legacy/package.json",
+      r#"{ "name": "legacy", "version": "0.1.0", "main": "./main.js" }"#,
+    )
+    .file("node_modules/legacy/main.js", "export const b = 'b';\n")
+    .file(
+      "node_modules/other/package.json",
+      r#"{ "name": "other", "version": "2.0.0" }"#,
+    )
+    .file("node_modules/other/index.js", "export const c = 'c';\n")
+    .input("index", "./index.ts")
+    .config(|config| {
+      config.resolve.alias = HashMap::from([("aliased".to_string(), "other".to_string())]);
+    })
+    .compile()
+    .unwrap();
+
+  let module_graph = result.compiler().context().module_graph.read();
+  let traces = module_graph
+    .dependencies(&"index.ts".into())
+    .into_iter()
+    .flat_map(|(dependency, edge)| {
+      edge
+        .iter()
+        .map(|item| {
+          (
+            item.source.clone(),
+            (dependency.clone(), item.resolve_trace.clone()),
+          )
+        })
+        .collect::<Vec<_>>()
+    })
+    .collect::<HashMap<_, _>>();
+  let trace = |source: &str| traces[source].1.clone().unwrap();
+
+  let exports = trace("pkg");
+  assert_eq!(exports.resolved_by, "exports");
+  assert!(exports.conditions.contains(&"import".to_string()));
+  assert!(exports.conditions.contains(&"default".to_string()));
+  assert_eq!(traces["pkg"].0, ModuleId::from("node_modules/pkg/esm.js"));
+
+  assert_eq!(trace("legacy").resolved_by, "main");
+  assert_eq!(
+    trace("aliased"),
+    ResolveTrace {
+      resolved_by: "alias".to_string(),
+      conditions: vec![],
+    }
+  );
+  assert_eq!(trace("./d").resolved_by, "path");
+  drop(module_graph);
+
+  let chains = result
+    .compiler()
+    .import_chains(&"node_modules/pkg/esm.js".into());
+  assert_eq!(chains.len(), 1);
+  let step = &chains[0][0];
+  assert_eq!(step.package.as_deref(), Some("pkg@1.2.3"));
+  assert_eq!(step.resolve_traces, vec![Some(exports)]);
+
+  let chains = result.compiler().import_chains(&"d.ts".into());
+  assert_eq!(chains[0][0].package, None);
+}
@@ -0,0 +1,139 @@
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::Result,
+  module::{EmptyModuleMetaData, ModuleMetaData, ModuleType},
+  plugin::{
+    resource_pot_renderer::ResourcePotRenderer, Plugin, PluginGenerateResourcesHookResult,
+    PluginHookContext, PluginLoadHookParam, PluginLoadHookResult, PluginParseHookParam,
+  },
+  resource::{
+    resource_pot::{ResourcePot, ResourcePotMetaData, ResourcePotType},
+    Resource, ResourceOrigin, ResourceType,
+  },
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_plugins;
+
+mod common;
+
+const TXT: &str = "txt";
+
+struct TxtPlugin;
+
+impl Plugin for TxtPlugin {
+  fn name(&self) -> &str {
+    "txt"
+  }
+
+  fn resource_pot_renderers(&self) -> Vec<Arc<dyn ResourcePotRenderer>> {
+    vec![Arc::new(TxtRenderer)]
+  }
+
+  fn load(
+    &self,
+    param: &PluginLoadHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginLoadHookResult>> {
+    if !param.resolved_path.ends_with(".txt") {
+      return Ok(None);
+    }
+
+    Ok(Some(PluginLoadHookResult {
+      content: std::fs::read_to_string(param.resolved_path).unwrap(),
+      module_type: ModuleType::Custom(TXT.to_string()),
+      source_map: None,
+    }))
+  }
+
+  fn parse(
+    &self,
+    param: &PluginParseHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<ModuleMetaData>> {
+    if param.module_type != ModuleType::Custom(TXT.to_string()) {
+      return Ok(None);
+    }
+
+    Ok(Some(ModuleMetaData::Custom(
+      Box::new(EmptyModuleMetaData) as _
+    )))
+  }
+}
+
+struct TxtRenderer;
+
+impl ResourcePotRenderer for TxtRenderer {
+  fn resource_pot_type(&self) -> ResourcePotType {
+    ResourcePotType::Custom(TXT.to_string())
+  }
+
+  fn render_resource_pot_modules(
+    &self,
+    resource_pot: &ResourcePot,
+    context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<ResourcePotMetaData> {
+    let module_graph = context.module_graph.read();
+    let content = resource_pot
+      .modules()
+      .into_iter()
+      .map(|id| module_graph.module(id).unwrap().content.to_string())
+      .collect::<Vec<_>>()
+      .join("\n");
+
+    Ok(ResourcePotMetaData {
+      rendered_content: Arc::new(content),
+      ..Default::default()
+    })
+  }
+
+  fn generate_resources(
+    &self,
+    resource_pot: &mut ResourcePot,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<PluginGenerateResourcesHookResult> {
+    Ok(PluginGenerateResourcesHookResult {
+      resource: Resource {
+        name: resource_pot.name.clone(),
+        bytes: resource_pot.meta.rendered_content.as_bytes().to_vec(),
+        resource_type: ResourceType::Custom(TXT.to_string()),
+        origin: ResourceOrigin::ResourcePot(resource_pot.id.clone()),
+        ..Default::default()
+      },
+      source_map: None,
+    })
+  }
+}
+
+#[test]
+fn custom_resource_pot_renderer() {
+  fixture!(
+    "tests/fixtures/resource_pot_renderer/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler = create_compiler_with_plugins(
+        HashMap::from([("index".to_string(), "./index.ts".to_string())]),
+        cwd.to_path_buf(),
+        crate_path,
+        false,
+        vec![Arc::new(TxtPlugin) as Arc<dyn Plugin>],
+      );
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let txt = resources_map
+        .values()
+        .find(|r| matches!(&r.resource_type, ResourceType::Custom(ty) if ty == TXT))
+        .unwrap();
+
+      assert_eq!(String::from_utf8_lossy(&txt.bytes), "hello");
+      assert!(txt.name.ends_with(".txt"));
+    }
+  );
+}
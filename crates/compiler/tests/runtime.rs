@@ -1,5 +1,6 @@
 use std::{collections::HashMap, path::PathBuf};
 
+use farmfe_compiler::testing::{TestCompileResult, TestProject};
 use farmfe_core::config::{
   bool_or_obj::BoolOrObj, config_regex::ConfigRegex,
   partial_bundling::PartialBundlingEnforceResourceConfig, Mode, TargetEnv,
@@ -89,3 +90,83 @@ fn test(file: String, crate_path: String) {
 farmfe_testing::testing! {"tests/fixtures/runtime/bundle/**/index.ts", test}
 // farmfe_testing::testing! {"tests/fixtures/runtime/bundle/cjs/export/entryExportStar/**/index.ts", test}
 // farmfe_testing::testing! {"tests/fixtures/runtime/bundle/external/import/namespace/**/index.ts", test}
+
+#[test]
+fn runtime_helpers_resource() {
+  let crate_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+  let cwd = crate_path.join("tests").join("fixtures").join("runtime");
+  let compiler = create_compiler_with_args(cwd, crate_path, |mut config, plugins| {
+    config.runtime.helpers_resource = Some("FARM_HELPERS".to_string());
+    (config, plugins)
+  });
+
+  let config = &compiler.context().config;
+  let helpers = config
+    .partial_bundling
+    .enforce_resources
+    .iter()
+    .find(|r| r.name == "FARM_HELPERS")
+    .unwrap();
+  let is_helper = |id: &str| helpers.test.iter().any(|t| t.is_match(id));
+
+  assert!(is_helper(
+    "node_modules/@swc/helpers/esm/_class_call_check.js"
+  ));
+  assert!(is_helper("../_internal/swc_helpers/lib/_export_star.js"));
+  assert!(!is_helper("src/helpers/index.ts"));
+}
+
+/// A project whose runtime imports a helper module partially and a module only for its side effects
+fn runtime_project() -> TestProject {
+  TestProject::new()
+    .file("index.ts", "console.log('index');\n")
+    // the runtime package is side effectful as it does not declare `sideEffects`
+    .file("__farm_runtime/package.json", "{ \"name\": \"runtime\" }\n")
+    .file(
+      "__farm_runtime/index.js",
+      "import { used } from './helpers';\nimport './setup';\nused();\n",
+    )
+    .file(
+      "__farm_runtime/helpers.js",
+      "export function used() { return 'used_helper'; }\nexport function unused() { return 'unused_helper'; }\n",
+    )
+    .file(
+      "__farm_runtime/custom-helpers.js",
+      "export function used() { return 'custom_helper'; }\n",
+    )
+    .file("__farm_runtime/setup.js", "console.log('runtime_setup');\n")
+    .input("index", "./index.ts")
+}
+
+fn runtime_resource(result: &TestCompileResult) -> String {
+  result.resource("FARM_RUNTIME_runtime").unwrap()
+}
+
+#[test]
+fn runtime_tree_shake() {
+  let result = runtime_project().compile().unwrap();
+  let runtime = runtime_resource(&result);
+
+  assert!(runtime.contains("used_helper"), "{runtime}");
+  assert!(!runtime.contains("unused_helper"), "{runtime}");
+  // the module imported for its side effects is kept
+  assert!(runtime.contains("runtime_setup"), "{runtime}");
+}
+
+#[test]
+fn runtime_alias() {
+  let result = runtime_project()
+    .config(|config| {
+      config
+        .runtime
+        .alias
+        .insert("./helpers".to_string(), "./custom-helpers".to_string());
+    })
+    .compile()
+    .unwrap();
+  let runtime = runtime_resource(&result);
+
+  assert!(runtime.contains("custom_helper"), "{runtime}");
+  assert!(!runtime.contains("used_helper"), "{runtime}");
+  assert!(runtime.contains("runtime_setup"), "{runtime}");
+}
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use farmfe_core::resource::ResourceType;
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[test]
+fn runtime_public_path() {
+  fixture!(
+    "tests/fixtures/runtime_public_path/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let compiler = create_compiler_with_args(cwd, crate_path, |mut config, plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        config.output.public_path = "runtime".to_string();
+        (config, plugins)
+      });
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let code_of = |is_type: fn(&ResourceType) -> bool| {
+        resources_map
+          .values()
+          .filter(|r| is_type(&r.resource_type))
+          .map(|r| String::from_utf8_lossy(&r.bytes).to_string())
+          .collect::<Vec<_>>()
+          .join("\n")
+      };
+
+      // the script resolves the asset url against the public path of the page
+      assert!(
+        code_of(|t| matches!(t, ResourceType::Js)).contains(r#"__farm_public_path__ + "hello"#)
+      );
+      // the emitted css references the asset relative to the css file
+      assert!(code_of(|t| matches!(t, ResourceType::Css)).contains(r#"url("hello"#));
+    }
+  );
+}
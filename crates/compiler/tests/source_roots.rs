@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+use farmfe_core::module::ModuleId;
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[test]
+fn source_roots_modules_are_mutable() {
+  fixture!(
+    "tests/fixtures/source_roots/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          config.source_roots = vec!["node_modules/design-system".to_string()];
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let module_graph = compiler.context().module_graph.read();
+      let is_immutable = |id: &str| module_graph.module(&ModuleId::from(id)).unwrap().immutable;
+
+      assert!(!is_immutable("index.ts"));
+      assert!(!is_immutable("node_modules/design-system/index.ts"));
+      assert!(is_immutable("node_modules/lib/index.js"));
+    }
+  );
+}
@@ -0,0 +1,134 @@
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::Result,
+  module::{sub_module::SubModule, ModuleId, ModuleType},
+  plugin::{
+    Plugin, PluginHookContext, PluginLoadHookParam, PluginLoadHookResult,
+    PluginSplitModuleHookParam, PluginSplitModuleHookResult,
+  },
+};
+use farmfe_testing_helpers::fixture;
+use farmfe_toolkit::sourcemap::SourceMap;
+
+mod common;
+
+use common::create_compiler_with_args;
+
+/// Splits the `<script>` and `<style>` blocks of `.sfc` files
+struct SfcPlugin;
+
+impl SfcPlugin {
+  fn block(content: &str, kind: &str, module_type: ModuleType) -> Option<SubModule> {
+    let open = format!("<{kind}>");
+    let start = content.find(&open)? + open.len();
+    let end = content.find(&format!("</{kind}>"))?;
+
+    Some(SubModule {
+      kind: kind.to_string(),
+      index: 0,
+      start,
+      end,
+      module_type,
+    })
+  }
+}
+
+impl Plugin for SfcPlugin {
+  fn name(&self) -> &str {
+    "SfcPlugin"
+  }
+
+  fn load(
+    &self,
+    param: &PluginLoadHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginLoadHookResult>> {
+    if !param.resolved_path.ends_with(".sfc") {
+      return Ok(None);
+    }
+
+    Ok(Some(PluginLoadHookResult {
+      content: std::fs::read_to_string(param.resolved_path).unwrap(),
+      module_type: ModuleType::Custom("sfc".to_string()),
+      source_map: None,
+    }))
+  }
+
+  fn split_module(
+    &self,
+    param: &PluginSplitModuleHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<PluginSplitModuleHookResult>> {
+    if *param.module_type != ModuleType::Custom("sfc".to_string()) {
+      return Ok(None);
+    }
+
+    let sub_modules = vec![
+      Self::block(param.content, "script", ModuleType::Js).unwrap(),
+      Self::block(param.content, "style", ModuleType::Css).unwrap(),
+    ];
+    let content = format!(
+      "import '{}';\nexport * from '{}';\n",
+      sub_modules[1].source(param.resolved_path),
+      sub_modules[0].source(param.resolved_path)
+    );
+
+    Ok(Some(PluginSplitModuleHookResult {
+      content,
+      module_type: ModuleType::Js,
+      sub_modules,
+    }))
+  }
+}
+
+#[test]
+fn sub_module_split() {
+  fixture!("tests/fixtures/sub_module/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler =
+      create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, mut plugins| {
+        config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+        plugins.push(Arc::new(SfcPlugin) as _);
+        (config, plugins)
+      });
+    compiler.compile().unwrap();
+
+    let module_graph = compiler.context().module_graph.read();
+    let parent_id: ModuleId = "App.sfc".into();
+    let script_id: ModuleId = "App.sfc?farm_sub_module=script.0".into();
+    let style_id: ModuleId = "App.sfc?farm_sub_module=style.0".into();
+
+    assert_eq!(
+      module_graph.module(&script_id).unwrap().module_type,
+      ModuleType::Js
+    );
+    assert_eq!(
+      module_graph.module(&style_id).unwrap().module_type,
+      ModuleType::Css
+    );
+    assert_eq!(script_id.sub_module_parent(), Some(parent_id.clone()));
+    assert!(!parent_id.is_sub_module());
+
+    let mut deps = module_graph
+      .dependencies_ids(&parent_id)
+      .into_iter()
+      .map(|id| id.to_string())
+      .collect::<Vec<_>>();
+    deps.sort();
+    assert_eq!(deps, vec![script_id.to_string(), style_id.to_string()]);
+
+    // the block is mapped back to its position in the sfc file
+    let script = module_graph.module(&script_id).unwrap();
+    let source_map = SourceMap::from_slice(script.source_map_chain[0].as_bytes()).unwrap();
+    let token = source_map.lookup_token(1, 0).unwrap();
+    assert_eq!(token.get_src_line(), 5);
+    assert!(token.get_source().unwrap().ends_with("App.sfc"));
+
+    let resources_map = compiler.context().resources_map.clone().into_read_only();
+    let index = String::from_utf8_lossy(&resources_map.get("index.js").unwrap().bytes);
+    assert!(index.contains("hello sfc"));
+  });
+}
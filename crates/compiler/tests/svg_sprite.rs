@@ -0,0 +1,58 @@
+use farmfe_compiler::testing::TestProject;
+use farmfe_core::{
+  config::{asset::SvgSpriteConfig, Mode},
+  plugin::UpdateType,
+};
+
+fn icon(path: &str) -> String {
+  format!(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="{path}"/></svg>"#)
+}
+
+#[test]
+fn svg_sprite_of_imported_icons() {
+  let result = TestProject::new()
+    .file(
+      "index.ts",
+      "import home from './icons/home.svg?sprite';\nimport user from './icons/user.svg?sprite';\nconsole.log(home, user);\n",
+    )
+    .file("icons/home.svg", icon("M1 1"))
+    .file("icons/user.svg", icon("M2 2"))
+    .input("index", "./index.ts")
+    .config(|config| {
+      config.mode = Mode::Development;
+      config.assets.sprite = Some(SvgSpriteConfig::default());
+    })
+    .compile()
+    .unwrap();
+
+  let sprite = result.resource("sprite.svg").unwrap();
+  assert_eq!(
+    sprite,
+    r#"<svg xmlns="http://www.w3.org/2000/svg"><symbol id="icon-home" viewBox="0 0 24 24"><path d="M1 1"/></symbol><symbol id="icon-user" viewBox="0 0 24 24"><path d="M2 2"/></symbol></svg>"#
+  );
+
+  let index = result.resource("index.js").unwrap();
+  assert!(index.contains(r#"id: "icon-home""#));
+  assert!(index.contains(r##""#icon-user""##));
+
+  // the sprite is rebuilt when any of the icons changes
+  let home = result.root().join("icons/home.svg");
+  std::fs::write(&home, icon("M3 3")).unwrap();
+  result
+    .compiler()
+    .update(
+      vec![(
+        format!("{}?sprite", home.to_string_lossy()),
+        UpdateType::Updated,
+      )],
+      || {},
+      true,
+      true,
+    )
+    .unwrap();
+
+  let sprite = result.resource("sprite.svg").unwrap();
+  assert!(sprite.contains(r#"<path d="M3 3"/>"#));
+  assert!(!sprite.contains(r#"<path d="M1 1"/>"#));
+  assert!(sprite.contains(r#"<path d="M2 2"/>"#));
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use farmfe_compiler::testing::{assert_snapshot, TestProject};
+use farmfe_core::{
+  context::CompilationContext,
+  error::Result,
+  plugin::{Plugin, PluginTransformHookParam, PluginTransformHookResult},
+};
+
+struct UppercasePlugin;
+
+impl Plugin for UppercasePlugin {
+  fn name(&self) -> &str {
+    "UppercasePlugin"
+  }
+
+  fn transform(
+    &self,
+    param: &PluginTransformHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<PluginTransformHookResult>> {
+    if !param.resolved_path.ends_with("a.ts") {
+      return Ok(None);
+    }
+
+    Ok(Some(PluginTransformHookResult {
+      content: param.content.replace("hello", "HELLO"),
+      ..Default::default()
+    }))
+  }
+}
+
+#[test]
+fn compile_test_project() {
+  let result = TestProject::new()
+    .file(
+      "src/index.ts",
+      "import { a } from './a';\nimport('./b');\nconsole.log(a);\n",
+    )
+    .file("src/a.ts", "export const a = 'hello';\n")
+    .file("src/b.ts", "export const b = 'b';\n")
+    .input("index", "./src/index.ts")
+    .plugin(Arc::new(UppercasePlugin))
+    .compile()
+    .unwrap();
+  let root = result.root().to_path_buf();
+
+  assert_eq!(
+    result.module_ids(),
+    vec!["src/a.ts", "src/b.ts", "src/index.ts"]
+  );
+  assert_eq!(
+    result.dependencies("src/index.ts"),
+    vec![
+      "./a -> src/a.ts (Import)",
+      "./b -> src/b.ts (DynamicImport)"
+    ]
+  );
+  assert!(result.resource("index.js").unwrap().contains("HELLO"));
+  // the dynamically imported module is in its own resource
+  assert!(result.resources().keys().any(|name| name != "index.js"));
+
+  let snapshot = root.with_extension("snap");
+  assert_snapshot(&snapshot, &result.module_graph_snapshot());
+  result.assert_module_graph_snapshot(&snapshot);
+  std::fs::remove_file(&snapshot).unwrap();
+
+  drop(result);
+  assert!(!root.exists());
+}
+
+#[test]
+fn compile_test_project_error() {
+  let err = TestProject::new()
+    .file("index.ts", "import './missing';\n")
+    .input("index", "./index.ts")
+    .compile()
+    .err()
+    .unwrap();
+
+  assert!(err.to_string().contains("Can not resolve `./missing`"));
+}
+
+#[test]
+#[should_panic(expected = "does not match")]
+fn assert_snapshot_mismatch() {
+  let snapshot = std::env::temp_dir().join(format!(
+    "farm-test-snapshot-mismatch-{}.snap",
+    std::process::id()
+  ));
+  std::fs::write(&snapshot, "expected").unwrap();
+
+  let result = std::panic::catch_unwind(|| assert_snapshot(&snapshot, "actual"));
+  std::fs::remove_file(&snapshot).unwrap();
+
+  if let Err(e) = result {
+    std::panic::resume_unwind(e);
+  }
+}
@@ -0,0 +1,126 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use farmfe_core::{
+  config::Mode,
+  context::CompilationContext,
+  error::Result,
+  parking_lot::Mutex,
+  plugin::{AssetVariant, Plugin, PluginTransformAssetHookParam},
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+/// Converts txt to md in production
+#[derive(Default)]
+struct UppercasePlugin {
+  calls: Mutex<Vec<AssetVariant>>,
+}
+
+impl Plugin for UppercasePlugin {
+  fn name(&self) -> &str {
+    "uppercase"
+  }
+
+  fn transform_asset(
+    &self,
+    param: &mut PluginTransformAssetHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    self.calls.lock().push(param.variant);
+
+    if param.variant == AssetVariant::Original || param.ext != "txt" {
+      return Ok(None);
+    }
+
+    // streamed assets are read from the disk
+    if let Some(source_path) = &param.source_path {
+      param.content = std::fs::read(source_path).unwrap();
+    }
+
+    param.content = param.content.to_ascii_uppercase();
+    param.ext = "md".to_string();
+
+    Ok(Some(()))
+  }
+}
+
+fn emitted_assets(
+  cwd: PathBuf,
+  crate_path: PathBuf,
+  mode: Mode,
+  streamed: bool,
+) -> (Vec<(String, Vec<u8>)>, Vec<AssetVariant>) {
+  let plugin = Arc::new(UppercasePlugin::default());
+  let compiler = create_compiler_with_args(cwd, crate_path, |mut config, mut plugins| {
+    config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+    config.mode = mode;
+    if streamed {
+      config.assets.stream_threshold = 1;
+    }
+    plugins.push(plugin.clone());
+    (config, plugins)
+  });
+  compiler.compile().unwrap();
+
+  let assets = compiler
+    .context()
+    .resources_map
+    .iter()
+    .filter(|r| r.name.starts_with("hello"))
+    .map(|r| (r.name.clone(), r.bytes.clone()))
+    .collect::<Vec<_>>();
+  let calls = plugin.calls.lock().clone();
+
+  (assets, calls)
+}
+
+#[test]
+fn transform_asset_production() {
+  fixture!(
+    "tests/fixtures/transform_asset/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let (assets, calls) = emitted_assets(cwd, crate_path, Mode::Production, false);
+
+      assert_eq!(calls, vec![AssetVariant::Optimized]);
+      assert_eq!(assets.len(), 1);
+      assert!(assets[0].0.ends_with(".md"));
+      assert_eq!(assets[0].1, b"HELLO\n");
+    }
+  );
+}
+
+#[test]
+fn transform_asset_development() {
+  fixture!(
+    "tests/fixtures/transform_asset/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let (assets, calls) = emitted_assets(cwd, crate_path, Mode::Development, false);
+
+      assert_eq!(calls, vec![AssetVariant::Original]);
+      assert_eq!(assets.len(), 1);
+      assert!(assets[0].0.ends_with(".txt"));
+      assert_eq!(assets[0].1, b"hello\n");
+    }
+  );
+}
+
+#[test]
+fn transform_streamed_asset() {
+  fixture!(
+    "tests/fixtures/transform_asset/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let (assets, calls) = emitted_assets(cwd, crate_path, Mode::Production, true);
+
+      assert_eq!(calls, vec![AssetVariant::Optimized]);
+      assert_eq!(assets.len(), 1);
+      assert!(assets[0].0.ends_with(".md"));
+      assert_eq!(assets[0].1, b"HELLO\n");
+    }
+  );
+}
@@ -2,16 +2,26 @@ use std::collections::HashMap;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use common::generate_runtime;
 use farmfe_compiler::{Compiler, DYNAMIC_VIRTUAL_SUFFIX};
+use farmfe_core::config::adaptive_rebuild::AdaptiveRebuildConfig;
 use farmfe_core::config::bool_or_obj::BoolOrObj;
 use farmfe_core::config::config_regex::ConfigRegex;
 use farmfe_core::config::persistent_cache::PersistentCacheConfig;
 use farmfe_core::config::TargetEnv;
 use farmfe_core::config::{preset_env::PresetEnvConfig, Config, Mode, SourcemapConfig};
-use farmfe_core::plugin::UpdateType;
+use farmfe_core::context::update_schedule::{UpdateSchedule, UpdateSchedulePolicy};
+use farmfe_core::context::{CompilationContext, EmitFileParams, InvalidateModuleOptions};
+use farmfe_core::plugin::{
+  Plugin, PluginTransformHookParam, PluginTransformHookResult, PluginWatchChangeHookParams,
+  UpdateType, WatchChangeEvent,
+};
+use farmfe_core::resource::ResourceType;
 use farmfe_testing_helpers::{fixture, is_update_snapshot_from_env};
+use farmfe_toolkit::hash::base64_decode;
 
 mod common;
 
@@ -22,6 +32,7 @@ fn create_compiler_internal(
   minify: bool,
   lazy_compilation: bool,
   target_env: TargetEnv,
+  plugins: Vec<Arc<dyn Plugin>>,
 ) -> Compiler {
   let compiler = Compiler::new(
     Config {
@@ -46,7 +57,7 @@ fn create_compiler_internal(
       persistent_cache: Box::new(PersistentCacheConfig::Bool(false)),
       ..Default::default()
     },
-    vec![],
+    plugins,
   )
   .unwrap();
 
@@ -59,7 +70,15 @@ fn create_update_compiler(
   crate_path: PathBuf,
   minify: bool,
 ) -> Compiler {
-  create_compiler_internal(input, cwd, crate_path, minify, false, TargetEnv::Browser)
+  create_compiler_internal(
+    input,
+    cwd,
+    crate_path,
+    minify,
+    false,
+    TargetEnv::Browser,
+    vec![],
+  )
 }
 
 fn create_lazy_update_compiler(
@@ -69,7 +88,7 @@ fn create_lazy_update_compiler(
   minify: bool,
   target_env: TargetEnv,
 ) -> Compiler {
-  create_compiler_internal(input, cwd, crate_path, minify, true, target_env)
+  create_compiler_internal(input, cwd, crate_path, minify, true, target_env, vec![])
 }
 
 fn asset_update_result_code(
@@ -133,12 +152,142 @@ fn update_without_dependencies_change() {
       assert_eq!(result.added_module_ids.len(), 0);
       assert_eq!(result.updated_module_ids, vec!["index.ts".into()]);
       assert_eq!(result.removed_module_ids.len(), 0);
+      assert!(result.updated_html_resources.is_empty());
 
       asset_update_result_code(cwd, &result, Some("update0"));
     }
   );
 }
 
+struct RemapWatchChangePlugin;
+
+impl Plugin for RemapWatchChangePlugin {
+  fn name(&self) -> &str {
+    "RemapWatchChangePlugin"
+  }
+
+  fn watch_change(
+    &self,
+    params: &mut PluginWatchChangeHookParams,
+    _context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<Option<()>> {
+    // the generated index.ts depends on the external config
+    if params.id.ends_with("codegen.config.json") && params.event == WatchChangeEvent::Update {
+      let index = params.id.replace("codegen.config.json", "index.ts");
+      params.paths = vec![(index, UpdateType::Updated)];
+      return Ok(Some(()));
+    }
+
+    Ok(None)
+  }
+}
+
+#[test]
+fn update_with_watch_change_remap() {
+  fixture!(
+    "tests/fixtures/update/basic/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let compiler = create_compiler_internal(
+        HashMap::from([("index".to_string(), "./index.html".to_string())]),
+        cwd.clone(),
+        crate_path,
+        false,
+        false,
+        TargetEnv::Browser,
+        vec![Arc::new(RemapWatchChangePlugin)],
+      );
+
+      compiler.compile().unwrap();
+
+      let changed_file = cwd
+        .join("codegen.config.json")
+        .to_string_lossy()
+        .to_string();
+      let result = compiler
+        .update(vec![(changed_file, UpdateType::Updated)], || {}, true, true)
+        .unwrap();
+
+      assert_eq!(result.updated_module_ids, vec!["index.ts".into()]);
+      assert_eq!(result.removed_module_ids.len(), 0);
+    }
+  );
+}
+
+#[test]
+fn update_with_inline_source_map() {
+  fixture!(
+    "tests/fixtures/update/basic/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let compiler = Compiler::new(
+        Config {
+          input: HashMap::from([("index".to_string(), "./index.html".to_string())]),
+          root: cwd.to_string_lossy().to_string(),
+          runtime: generate_runtime(crate_path),
+          output: Box::new(farmfe_core::config::OutputConfig {
+            filename: "[resourceName].[ext]".to_string(),
+            ..Default::default()
+          }),
+          mode: Mode::Development,
+          sourcemap: Box::new(SourcemapConfig::Bool(true)),
+          progress: false,
+          lazy_compilation: false,
+          minify: Box::new(BoolOrObj::from(false)),
+          preset_env: Box::new(PresetEnvConfig::Bool(false)),
+          persistent_cache: Box::new(PersistentCacheConfig::Bool(false)),
+          ..Default::default()
+        },
+        vec![],
+      )
+      .unwrap();
+
+      compiler.compile().unwrap();
+
+      let update_file = cwd.join("index.ts").to_string_lossy().to_string();
+      let result = compiler
+        .update(vec![(update_file, UpdateType::Updated)], || {}, true, true)
+        .unwrap();
+
+      // the source map of the updated modules is inlined, so the hot updated code maps back to index.ts
+      let prefix = "//# sourceMappingURL=data:application/json;charset=utf-8;base64,";
+      let inline_map = result
+        .mutable_resources
+        .lines()
+        .find_map(|line| line.strip_prefix(prefix))
+        .unwrap();
+      let map = base64_decode(inline_map.as_bytes());
+      assert!(map.contains("index.ts"));
+    }
+  );
+}
+
+#[test]
+fn update_invalidated_module() {
+  fixture!(
+    "tests/fixtures/update/basic/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let compiler = create_update_compiler(
+        HashMap::from([("index".to_string(), "./index.html".to_string())]),
+        cwd.clone(),
+        crate_path,
+        false,
+      );
+
+      compiler.compile().unwrap();
+
+      compiler
+        .context()
+        .invalidate_module(&"index.ts".into(), InvalidateModuleOptions::default());
+      let result = compiler.update(vec![], || {}, true, true).unwrap();
+
+      assert_eq!(result.updated_module_ids, vec!["index.ts".into()]);
+      assert!(compiler.context().invalidated_modules.lock().is_empty());
+    }
+  );
+}
+
 #[test]
 fn update_without_dependencies_change_css() {
   fixture!(
@@ -172,6 +321,8 @@ fn update_without_dependencies_change_css() {
       assert_eq!(result.added_module_ids.len(), 0);
       assert_eq!(result.updated_module_ids, vec!["index.css".into()]);
       assert_eq!(result.removed_module_ids.len(), 0);
+      // the css text is sent without re-executing the module
+      assert!(result.css_updates[&"index.css".into()].contains('{'));
 
       asset_update_result_code(cwd.clone(), &result, Some("update1"));
 
@@ -414,3 +565,321 @@ fn update_lazy_compilation_node() {
     }
   );
 }
+
+#[test]
+fn update_top_level_await() {
+  fixture!(
+    "tests/fixtures/update/top-level-await/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let compiler = create_update_compiler(
+        HashMap::from([("index".to_string(), "./index.html".to_string())]),
+        cwd.clone(),
+        crate_path,
+        false,
+      );
+
+      compiler.compile().unwrap();
+
+      let update_file = cwd.join("dep.ts").to_string_lossy().to_string();
+      let original_dep = std::fs::read_to_string(&update_file).unwrap();
+      std::fs::write(
+        &update_file,
+        format!("await Promise.resolve();\n{original_dep}"),
+      )
+      .unwrap();
+
+      let result = compiler
+        .update(
+          vec![(update_file.clone(), UpdateType::Updated)],
+          || {},
+          true,
+          true,
+        )
+        .unwrap();
+
+      // index.ts imports dep.ts, it becomes async too and must be rendered again
+      assert_eq!(
+        result.updated_module_ids,
+        vec!["dep.ts".into(), "index.ts".into()]
+      );
+      assert_eq!(
+        result.mutable_resources.matches("async function").count(),
+        2
+      );
+
+      std::fs::write(&update_file, original_dep).unwrap();
+
+      let result = compiler
+        .update(vec![(update_file, UpdateType::Updated)], || {}, true, true)
+        .unwrap();
+
+      assert_eq!(
+        result.updated_module_ids,
+        vec!["dep.ts".into(), "index.ts".into()]
+      );
+      assert!(!result.mutable_resources.contains("async function"));
+    }
+  );
+}
+
+fn create_adaptive_rebuild_compiler(
+  cwd: PathBuf,
+  crate_path: PathBuf,
+  adaptive_rebuild: AdaptiveRebuildConfig,
+) -> Compiler {
+  Compiler::new(
+    Config {
+      input: HashMap::from([("index".to_string(), "./index.html".to_string())]),
+      root: cwd.to_string_lossy().to_string(),
+      runtime: generate_runtime(crate_path),
+      output: Box::new(farmfe_core::config::OutputConfig {
+        filename: "[resourceName].[ext]".to_string(),
+        ..Default::default()
+      }),
+      mode: Mode::Development,
+      sourcemap: Box::new(SourcemapConfig::Bool(false)),
+      progress: false,
+      minify: Box::new(BoolOrObj::from(false)),
+      preset_env: Box::new(PresetEnvConfig::Bool(false)),
+      persistent_cache: Box::new(PersistentCacheConfig::Bool(false)),
+      adaptive_rebuild: Some(Box::new(adaptive_rebuild)),
+      ..Default::default()
+    },
+    vec![],
+  )
+  .unwrap()
+}
+
+#[test]
+fn update_adaptive_rebuild() {
+  fixture!(
+    "tests/fixtures/update/basic/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let update_file = cwd.join("index.ts").to_string_lossy().to_string();
+
+      // any change is escalated
+      let compiler = create_adaptive_rebuild_compiler(
+        cwd.clone(),
+        crate_path.clone(),
+        AdaptiveRebuildConfig {
+          threshold: 0.0,
+          min_changed_modules: 1,
+        },
+      );
+      compiler.compile().unwrap();
+
+      let result = compiler
+        .update(
+          vec![(update_file.clone(), UpdateType::Updated)],
+          || {},
+          true,
+          true,
+        )
+        .unwrap();
+
+      let reason = result.full_rebuild_reason.unwrap();
+      assert!(reason.starts_with("1 of "), "{reason}");
+      assert_eq!(result.immutable_resources, "window.location.reload()");
+      assert!(result.removed_resources.is_empty());
+
+      let context = compiler.context();
+      assert!(context.module_graph.read().has_module(&"index.ts".into()));
+      assert!(context.resources_map.contains_key("index.html"));
+
+      // the update changes fewer modules than min_changed_modules
+      let compiler = create_adaptive_rebuild_compiler(
+        cwd,
+        crate_path,
+        AdaptiveRebuildConfig {
+          threshold: 0.0,
+          min_changed_modules: 2,
+        },
+      );
+      compiler.compile().unwrap();
+
+      let result = compiler
+        .update(vec![(update_file, UpdateType::Updated)], || {}, true, true)
+        .unwrap();
+
+      assert!(result.full_rebuild_reason.is_none());
+      assert_eq!(result.updated_module_ids, vec!["index.ts".into()]);
+    }
+  );
+}
+
+/// Emits a file and adds a watch file every time index.ts is transformed
+#[derive(Default)]
+struct EmitOnTransformPlugin {
+  transforms: AtomicUsize,
+}
+
+impl Plugin for EmitOnTransformPlugin {
+  fn name(&self) -> &str {
+    "EmitOnTransformPlugin"
+  }
+
+  fn transform(
+    &self,
+    param: &PluginTransformHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<Option<PluginTransformHookResult>> {
+    if !param.resolved_path.ends_with("index.ts") {
+      return Ok(None);
+    }
+
+    let n = self.transforms.fetch_add(1, Ordering::SeqCst);
+    context.emit_file(EmitFileParams {
+      resolved_path: param.resolved_path.to_string(),
+      name: format!("transformed-{n}.txt"),
+      content: vec![],
+      resource_type: ResourceType::Asset("txt".to_string()),
+      scope: Default::default(),
+    });
+    context.add_watch_files(
+      param.module_id.as_str().into(),
+      vec![format!("watched-{n}.json").as_str().into()],
+    )?;
+
+    Ok(None)
+  }
+}
+
+#[test]
+fn plan_update_without_applying() {
+  fixture!(
+    "tests/fixtures/update/basic/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let plugin = Arc::new(EmitOnTransformPlugin::default());
+      let compiler = create_compiler_internal(
+        HashMap::from([("index".to_string(), "./index.html".to_string())]),
+        cwd.clone(),
+        crate_path,
+        false,
+        false,
+        TargetEnv::Browser,
+        vec![plugin.clone()],
+      );
+
+      compiler.compile().unwrap();
+
+      let context = compiler.context();
+      let module_count = context.module_graph.read().modules().len();
+      let resource_count = context.resources_map.len();
+      let watch_count = context.watch_graph.read().modules().len();
+
+      let update_file = cwd.join("index.ts").to_string_lossy().to_string();
+      let plan = compiler
+        .plan_update(vec![(update_file.clone(), UpdateType::Updated)])
+        .unwrap();
+
+      assert_eq!(plan.updated_module_ids, vec!["index.ts".into()]);
+      assert!(plan.diff_result.is_empty());
+      assert_eq!(plan.affected_module_groups, vec!["index.html".into()]);
+      assert!(!plan.affected_resource_pots.is_empty());
+      assert!(plan.full_rebuild_reason.is_none());
+
+      // nothing is applied, the hooks called by the plan write to an isolated context
+      assert_eq!(plugin.transforms.load(Ordering::SeqCst), 2);
+      assert_eq!(context.module_graph.read().modules().len(), module_count);
+      assert_eq!(context.resources_map.len(), resource_count);
+      assert!(!context.resources_map.contains_key("transformed-1.txt"));
+      assert_eq!(context.watch_graph.read().modules().len(), watch_count);
+
+      let result = compiler
+        .update(vec![(update_file, UpdateType::Updated)], || {}, true, true)
+        .unwrap();
+      assert_eq!(result.updated_module_ids, plan.updated_module_ids);
+    }
+  );
+}
+
+#[test]
+fn update_with_deferred_schedule() {
+  fixture!(
+    "tests/fixtures/update/basic/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let compiler = create_update_compiler(
+        HashMap::from([("index".to_string(), "./index.html".to_string())]),
+        cwd.clone(),
+        crate_path,
+        false,
+      );
+
+      compiler.compile().unwrap();
+      compiler.set_update_schedule_policy(UpdateSchedulePolicy {
+        module_change: UpdateSchedule::Deferred,
+        ..Default::default()
+      });
+
+      let regenerated = Arc::new(AtomicBool::new(false));
+      let cloned_regenerated = regenerated.clone();
+      let update_file = cwd.join("index.ts").to_string_lossy().to_string();
+      let result = compiler
+        .update(
+          vec![(update_file, UpdateType::Updated)],
+          move || cloned_regenerated.store(true, Ordering::SeqCst),
+          false,
+          true,
+        )
+        .unwrap();
+
+      assert_eq!(result.updated_module_ids, vec!["index.ts".into()]);
+      assert!(!regenerated.load(Ordering::SeqCst));
+
+      assert_eq!(compiler.flush_deferred_updates(), 1);
+      assert!(regenerated.load(Ordering::SeqCst));
+      assert_eq!(compiler.flush_deferred_updates(), 0);
+    }
+  );
+}
+
+#[test]
+fn update_html_entry_resources() {
+  fixture!(
+    "tests/fixtures/update/html-entry/index.html",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let compiler = create_update_compiler(
+        HashMap::from([("index".to_string(), "./index.html".to_string())]),
+        cwd.clone(),
+        crate_path,
+        false,
+      );
+
+      compiler.compile().unwrap();
+
+      let update_file = cwd.join("index.ts").to_string_lossy().to_string();
+      let original_index = std::fs::read_to_string(&update_file).unwrap();
+      let update = |code: String| {
+        std::fs::write(&update_file, code).unwrap();
+        compiler
+          .update(
+            vec![(update_file.clone(), UpdateType::Updated)],
+            || {},
+            true,
+            true,
+          )
+          .unwrap()
+      };
+
+      // a new dynamic import only changes the inline dynamic resources map of the html
+      let result = update(format!("import('./lazy');\n{original_index}"));
+      assert_eq!(result.added_module_ids, vec!["lazy.ts".into()]);
+      assert!(result.updated_html_resources.is_empty());
+
+      // a new static import changes the modules of the entry resource pot, so the injected script changes
+      let result = update(format!("import './dep';\n{original_index}"));
+      assert_eq!(result.added_module_ids, vec!["dep.ts".into()]);
+      assert_eq!(
+        result.updated_html_resources,
+        vec!["index.html".to_string()]
+      );
+
+      std::fs::write(&update_file, original_index).unwrap();
+    }
+  );
+}
@@ -0,0 +1,123 @@
+use std::collections::{BTreeMap, HashMap};
+
+use farmfe_core::{
+  config::vendor_reference::{
+    VendorProviderConfig, VendorReferenceConfig, VendorReferenceManifest,
+  },
+  module::ModuleId,
+  resource::ResourceType,
+  serde_json,
+};
+use farmfe_testing_helpers::fixture;
+
+use common::create_compiler_with_args;
+
+mod common;
+
+#[test]
+fn vendor_reference_build() {
+  fixture!(
+    "tests/fixtures/vendor_reference/lib.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("lib".to_string(), "./lib.ts".to_string())]);
+          config.vendor_reference = Some(Box::new(VendorReferenceConfig {
+            manifest: Some("vendor-manifest.json".to_string()),
+            ..Default::default()
+          }));
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let manifest: VendorReferenceManifest =
+        serde_json::from_slice(&resources_map.get("vendor-manifest.json").unwrap().bytes).unwrap();
+
+      // the ids of the modules registered by the runtime depend on the mode
+      let lib_id = ModuleId::from("lib.ts").id(compiler.context().config.mode.clone());
+      assert_eq!(manifest.modules["./lib.ts"], lib_id);
+      assert_eq!(manifest.resources, vec!["lib.js".to_string()]);
+      assert!(!resources_map
+        .values()
+        .any(|r| matches!(r.resource_type, ResourceType::Runtime)));
+
+      let lib = String::from_utf8(resources_map.get("lib.js").unwrap().bytes.clone()).unwrap();
+      assert!(lib.contains(&format!(
+        r#"register("./lib.ts",function(m,e,r){{m.exports=r("{lib_id}")}})"#
+      )));
+    }
+  );
+}
+
+#[test]
+fn vendor_reference_link() {
+  fixture!(
+    "tests/fixtures/vendor_reference/app.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("app".to_string(), "./app.ts".to_string())]);
+          config.vendor_reference = Some(Box::new(VendorReferenceConfig {
+            references: vec!["vendor-manifest.json".to_string()],
+            ..Default::default()
+          }));
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let module_graph = compiler.context().module_graph.read();
+      let lib = module_graph.module(&ModuleId::from("./lib.ts")).unwrap();
+      assert!(lib.external);
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let app = String::from_utf8(resources_map.get("app.js").unwrap().bytes.clone()).unwrap();
+      assert!(!app.contains("vendor greets"));
+    }
+  );
+}
+
+#[test]
+fn vendor_reference_provider() {
+  fixture!(
+    "tests/fixtures/vendor_reference/provider.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("provider".to_string(), "./provider.ts".to_string())]);
+          config.vendor_reference = Some(Box::new(VendorReferenceConfig {
+            providers: vec![VendorProviderConfig {
+              manifest: "vendor-manifest.json".to_string(),
+              public_path: Some("https://cdn.example.com/vendor/".to_string()),
+              alias: BTreeMap::from([("greeter".to_string(), "./lib.ts".to_string())]),
+            }],
+            ..Default::default()
+          }));
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let module_graph = compiler.context().module_graph.read();
+      let mut provided = module_graph
+        .modules()
+        .into_iter()
+        .map(|m| m.id.to_string())
+        .filter(|id| id.ends_with(".farm_vendor_provided"))
+        .collect::<Vec<_>>();
+      provided.sort();
+      assert_eq!(provided.len(), 2);
+      assert!(provided[1].starts_with("greeter"));
+      assert!(!module_graph.has_module(&ModuleId::from("lib.ts")));
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let provider =
+        String::from_utf8(resources_map.get("provider.js").unwrap().bytes.clone()).unwrap();
+      assert!(provider.contains(r#""https://cdn.example.com/vendor/lib.js""#));
+      assert!(provider.contains(r#"const moduleId = "lib.ts";"#));
+      assert!(!provider.contains("vendor greets"));
+    }
+  );
+}
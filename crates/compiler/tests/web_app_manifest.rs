@@ -0,0 +1,57 @@
+use farmfe_compiler::testing::TestProject;
+use farmfe_core::{
+  config::html::{WebAppManifestConfig, WebAppManifestIcon},
+  serde_json::{self, Value},
+};
+
+#[test]
+fn web_app_manifest() {
+  let result = TestProject::new()
+    .file(
+      "index.html",
+      "<!DOCTYPE html><html><head><title>app</title></head><body><script src=\"./index.ts\"></script></body></html>",
+    )
+    .file("index.ts", "console.log('app');\n")
+    .file(
+      "icons/logo.svg",
+      r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24"><path d="M1 1"/></svg>"#,
+    )
+    .input("index", "./index.html")
+    .config(|config| {
+      config.html.manifest = Some(WebAppManifestConfig {
+        name: "Farm App".to_string(),
+        theme_color: Some("#ff0000".to_string()),
+        icons: vec![WebAppManifestIcon {
+          src: "icons/logo.svg".to_string(),
+          ..Default::default()
+        }],
+        ..Default::default()
+      });
+    })
+    .compile()
+    .unwrap();
+
+  let manifest: Value =
+    serde_json::from_str(&result.resource("manifest.webmanifest").unwrap()).unwrap();
+  assert_eq!(manifest["name"], "Farm App");
+  assert_eq!(manifest["start_url"], "/");
+  assert_eq!(manifest["display"], "standalone");
+  assert_eq!(manifest["theme_color"], "#ff0000");
+
+  let icon = &manifest["icons"][0];
+  assert_eq!(icon["sizes"], "any");
+  assert_eq!(icon["type"], "image/svg+xml");
+
+  let icon_src = icon["src"].as_str().unwrap();
+  let icon_name = icon_src.trim_start_matches('/');
+  assert!(icon_name.starts_with("logo"), "{icon_src}");
+  assert!(result.resource(icon_name).is_some(), "{icon_src}");
+
+  let html = result.resource("index.html").unwrap();
+  assert!(html.contains(r#"rel="manifest""#), "{html}");
+  assert!(html.contains("manifest.webmanifest"), "{html}");
+  assert!(
+    html.contains(r##"name="theme-color" content="#ff0000""##),
+    "{html}"
+  );
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use farmfe_core::{
+  resource::{ResourceOrigin, ResourceType},
+  serde_json::{self, Value},
+};
+use farmfe_testing_helpers::fixture;
+
+use common::{create_compiler_with_args, create_compiler_with_plugins};
+
+mod common;
+
+#[test]
+fn bundle_worker_as_separate_resource() {
+  fixture!("tests/fixtures/worker/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap();
+    let compiler = create_compiler_with_plugins(
+      HashMap::from([("index".to_string(), "./index.ts".to_string())]),
+      cwd.to_path_buf(),
+      crate_path,
+      false,
+      vec![],
+    );
+    compiler.compile().unwrap();
+
+    let module_graph = compiler.context().module_graph.read();
+    assert!(module_graph.is_worker(&"worker.ts".into()));
+
+    let module_group_graph = compiler.context().module_group_graph.read();
+    assert!(module_group_graph.has(&"worker.ts".into()));
+
+    let resources_map = compiler.context().resources_map.clone().into_read_only();
+    let worker_resource = resources_map
+      .values()
+      .find(|r| {
+        matches!(r.resource_type, ResourceType::Js)
+          && matches!(&r.origin, ResourceOrigin::Module(id) if id == &"worker.ts".into())
+      })
+      .unwrap();
+    assert!(worker_resource.name.contains(".worker"));
+
+    // the worker url is replaced with the url of the worker resource
+    for resource in resources_map.values() {
+      let code = String::from_utf8_lossy(&resource.bytes);
+      assert!(!code.contains("__FARM_WORKER_URL__"));
+    }
+    assert!(resources_map.values().any(|r| {
+      r.name != worker_resource.name
+        && String::from_utf8_lossy(&r.bytes).contains(&worker_resource.name)
+    }));
+  });
+}
+
+#[test]
+fn module_worker_with_cross_origin_public_path() {
+  fixture!(
+    "tests/fixtures/worker_module/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let compiler =
+        create_compiler_with_args(cwd.to_path_buf(), crate_path, |mut config, plugins| {
+          config.input = HashMap::from([("index".to_string(), "./index.ts".to_string())]);
+          config.output.public_path = "https://cdn.example.com/".to_string();
+          config.output.cross_origin_isolated = true;
+          (config, plugins)
+        });
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.clone().into_read_only();
+      let worker_resource = resources_map
+        .values()
+        .find(|r| matches!(&r.origin, ResourceOrigin::Module(id) if id == &"worker.ts".into()))
+        .unwrap();
+      let worker_url = format!("https://cdn.example.com/{}", worker_resource.name);
+
+      // the cross origin worker is created from a same-origin blob url that imports the worker resource
+      let index =
+        String::from_utf8_lossy(&resources_map.get("index.js").unwrap().bytes).to_string();
+      assert!(!index.contains("__FARM_WORKER_URL__"));
+      assert!(index.contains("URL.createObjectURL"));
+      assert!(index.contains(r#""import " + JSON.stringify(u)"#));
+      assert!(index.contains(&format!("{worker_url:?}")));
+
+      let manifest: Value =
+        serde_json::from_slice(&resources_map.get("workers-manifest.json").unwrap().bytes).unwrap();
+      assert_eq!(
+        manifest["headers"]["Cross-Origin-Embedder-Policy"],
+        "require-corp"
+      );
+      assert_eq!(manifest["workers"]["worker.ts"]["url"], worker_url.as_str());
+      assert_eq!(manifest["workers"]["worker.ts"]["type"], "module");
+    }
+  );
+}
@@ -0,0 +1,225 @@
+//! Portable archives of the persistent cache, so CI can save the cache of a pipeline stage as a single artifact and restore it
+//! in another stage or on another machine. The files are stored by their paths relative to the cache directory and the cache keys
+//! are root relative module ids, so the archive is valid wherever the project is checked out.
+
+use std::{
+  collections::HashMap,
+  io::{Error, ErrorKind, Result},
+  path::{Component, Path, PathBuf},
+};
+
+use super::cache_store::FARM_CACHE_MANIFEST_FILE;
+
+const CACHE_ARCHIVE_MAGIC: &[u8] = b"FARMCACHEARCHIVE";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CacheArchiveResult {
+  /// number of the exported files, or the imported files that are written or merged
+  pub files: usize,
+  pub bytes: u64,
+}
+
+/// Pack all the files under `cache_dir` into the archive file, e.g. `node_modules/.farm/cache`
+pub fn export_cache(cache_dir: &Path, archive: &Path) -> Result<CacheArchiveResult> {
+  let mut files = vec![];
+  collect_files(cache_dir, cache_dir, &mut files)?;
+  files.sort();
+
+  let mut result = CacheArchiveResult::default();
+  let mut encoded = CACHE_ARCHIVE_MAGIC.to_vec();
+
+  for relative_path in files {
+    let bytes = std::fs::read(cache_dir.join(&relative_path))?;
+    let relative_path = relative_path.as_bytes();
+
+    encoded.extend_from_slice(&(relative_path.len() as u32).to_le_bytes());
+    encoded.extend_from_slice(relative_path);
+    encoded.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+    encoded.extend_from_slice(&bytes);
+
+    result.files += 1;
+    result.bytes += bytes.len() as u64;
+  }
+
+  if let Some(parent) = archive.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+
+  std::fs::write(archive, encoded)?;
+
+  Ok(result)
+}
+
+/// Unpack the archive into `cache_dir` and merge it with the existing cache. The existing artifacts and manifest entries
+/// are kept, only the missing ones are added
+pub fn import_cache(archive: &Path, cache_dir: &Path) -> Result<CacheArchiveResult> {
+  let encoded = std::fs::read(archive)?;
+  let mut result = CacheArchiveResult::default();
+
+  for (relative_path, bytes) in decode_archive(&encoded)? {
+    let path = cache_dir.join(&relative_path);
+
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+
+    let is_manifest = path
+      .file_name()
+      .is_some_and(|name| name == FARM_CACHE_MANIFEST_FILE);
+
+    if is_manifest && path.is_file() {
+      let merged = merge_manifest(&std::fs::read(&path)?, bytes)?;
+      std::fs::write(&path, merged)?;
+    } else if !path.exists() {
+      std::fs::write(&path, bytes)?;
+    } else {
+      continue;
+    }
+
+    result.files += 1;
+    result.bytes += bytes.len() as u64;
+  }
+
+  Ok(result)
+}
+
+fn collect_files(dir: &Path, cache_dir: &Path, files: &mut Vec<String>) -> Result<()> {
+  if !dir.is_dir() {
+    return Ok(());
+  }
+
+  for entry in std::fs::read_dir(dir)? {
+    let path = entry?.path();
+
+    if path.is_dir() {
+      collect_files(&path, cache_dir, files)?;
+    } else if !path.extension().is_some_and(|ext| ext == "tmp") {
+      let relative_path = path.strip_prefix(cache_dir).unwrap();
+      files.push(
+        relative_path
+          .components()
+          .map(|c| c.as_os_str().to_string_lossy())
+          .collect::<Vec<_>>()
+          .join("/"),
+      );
+    }
+  }
+
+  Ok(())
+}
+
+fn invalid_archive(reason: &str) -> Error {
+  Error::new(
+    ErrorKind::InvalidData,
+    format!("invalid cache archive: {reason}"),
+  )
+}
+
+fn decode_archive(encoded: &[u8]) -> Result<Vec<(PathBuf, &[u8])>> {
+  let Some(mut rest) = encoded.strip_prefix(CACHE_ARCHIVE_MAGIC) else {
+    return Err(invalid_archive("not a farm cache archive"));
+  };
+  let mut entries = vec![];
+
+  while !rest.is_empty() {
+    let path_len = u32::from_le_bytes(take(&mut rest, 4)?.try_into().unwrap()) as usize;
+    let relative_path = String::from_utf8_lossy(take(&mut rest, path_len)?).to_string();
+    let bytes_len = u64::from_le_bytes(take(&mut rest, 8)?.try_into().unwrap()) as usize;
+    let bytes = take(&mut rest, bytes_len)?;
+
+    let relative_path = PathBuf::from(relative_path);
+    // never write outside of the cache directory
+    if !relative_path
+      .components()
+      .all(|c| matches!(c, Component::Normal(_)))
+    {
+      return Err(invalid_archive(&format!(
+        "path {relative_path:?} is not relative to the cache directory"
+      )));
+    }
+
+    entries.push((relative_path, bytes));
+  }
+
+  Ok(entries)
+}
+
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+  if rest.len() < len {
+    return Err(invalid_archive("unexpected end of the archive"));
+  }
+
+  let (taken, remaining) = rest.split_at(len);
+  *rest = remaining;
+  Ok(taken)
+}
+
+/// The entries of the existing manifest take precedence over the imported ones
+fn merge_manifest(existing: &[u8], imported: &[u8]) -> Result<Vec<u8>> {
+  let existing = serde_json::from_slice::<HashMap<String, String>>(existing)?;
+  let mut merged = serde_json::from_slice::<HashMap<String, String>>(imported)?;
+  merged.extend(existing);
+
+  Ok(serde_json::to_vec(&merged)?)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{export_cache, import_cache, CacheArchiveResult};
+
+  #[test]
+  fn export_and_import_cache() {
+    let dir = std::env::temp_dir().join(format!("farm-cache-archive-test-{}", std::process::id()));
+    let source = dir.join("source");
+    let target = dir.join("target");
+    let store = "0.4.23-hash/farm-cache/production/mutable-modules";
+
+    std::fs::create_dir_all(source.join(store)).unwrap();
+    std::fs::write(
+      source.join(store).join("farm-cache.json"),
+      r#"{"a.ts":"a-1","b.ts":"b-1"}"#,
+    )
+    .unwrap();
+    std::fs::write(source.join(store).join("a-1"), "a").unwrap();
+    std::fs::write(source.join(store).join("b-1"), "b").unwrap();
+    std::fs::write(source.join(store).join("c.1.tmp"), "partial").unwrap();
+
+    // the target machine already compiled a.ts
+    std::fs::create_dir_all(target.join(store)).unwrap();
+    std::fs::write(
+      target.join(store).join("farm-cache.json"),
+      r#"{"a.ts":"a-2"}"#,
+    )
+    .unwrap();
+    std::fs::write(target.join(store).join("a-2"), "a2").unwrap();
+
+    let archive = dir.join("cache.farm");
+    let exported = export_cache(&source, &archive).unwrap();
+    assert_eq!(
+      exported,
+      CacheArchiveResult {
+        files: 3,
+        bytes: 29,
+      }
+    );
+
+    let imported = import_cache(&archive, &target).unwrap();
+    assert_eq!(imported.files, 3);
+
+    let manifest = std::fs::read_to_string(target.join(store).join("farm-cache.json")).unwrap();
+    let manifest =
+      serde_json::from_str::<std::collections::HashMap<String, String>>(&manifest).unwrap();
+    assert_eq!(manifest["a.ts"], "a-2");
+    assert_eq!(manifest["b.ts"], "b-1");
+    assert_eq!(
+      std::fs::read_to_string(target.join(store).join("b-1")).unwrap(),
+      "b"
+    );
+    assert!(!target.join(store).join("c.1.tmp").exists());
+
+    std::fs::write(&archive, "not an archive").unwrap();
+    assert!(import_cache(&archive, &target).is_err());
+
+    std::fs::remove_dir_all(dir).ok();
+  }
+}
@@ -5,20 +5,33 @@ use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use std::{
   collections::HashMap,
   path::{Path, PathBuf},
+  sync::atomic::{AtomicBool, Ordering},
 };
 
 use crate::config::Mode;
 
-const FARM_CACHE_VERSION: &str = "0.4.20";
-const FARM_CACHE_MANIFEST_FILE: &str = "farm-cache.json";
+use super::migration::{
+  decode_schema_version, encode_schema_version, CacheMigrations, FARM_CACHE_SCHEMA_VERSION,
+};
+
+/// version of the cache directory, bumped when the cached structures change in a way that can't be migrated,
+/// see [super::migration] for the changes that can
+pub const FARM_CACHE_VERSION: &str = "0.4.25";
+pub(crate) const FARM_CACHE_MANIFEST_FILE: &str = "farm-cache.json";
 
 // TODO make CacheStore a trait and implement DiskCacheStore or RemoteCacheStore or more.
 #[derive(Default)]
 pub struct CacheStore {
   cache_dir: PathBuf,
+  /// name of the store, e.g. `mutable-modules`
+  name: String,
+  /// migrations of the artifacts written in older schema versions
+  migrations: CacheMigrations,
   /// name -> cache key manifest of this store.
   /// it will be stored in a separate file
   manifest: DashMap<String, String>,
+  /// the manifest file is only rewritten when a cache item is added or removed
+  manifest_changed: AtomicBool,
 }
 
 impl CacheStore {
@@ -65,10 +78,18 @@ impl CacheStore {
 
     Self {
       cache_dir,
+      name: name.to_string(),
+      migrations: CacheMigrations::default(),
       manifest,
+      manifest_changed: AtomicBool::new(false),
     }
   }
 
+  pub fn with_migrations(mut self, migrations: &CacheMigrations) -> Self {
+    self.migrations = migrations.clone();
+    self
+  }
+
   pub fn has_cache(&self, name: &str) -> bool {
     self.manifest.contains_key(name)
   }
@@ -97,7 +118,7 @@ impl CacheStore {
     let cache_file_dir = &self.cache_dir;
 
     if !cache_file_dir.exists() {
-      std::fs::create_dir_all(cache_file_dir).unwrap();
+      std::fs::create_dir_all(cache_file_dir)?;
     }
 
     if self.is_cache_changed(&store_key) {
@@ -112,7 +133,9 @@ impl CacheStore {
       self
         .manifest
         .insert(store_key.name.clone(), store_key.key.clone());
+      self.manifest_changed.store(true, Ordering::SeqCst);
       let cache_file_path = cache_file_dir.join(store_key.key);
+      let bytes = encode_schema_version(FARM_CACHE_SCHEMA_VERSION, &bytes);
       std::fs::write(&cache_file_path, bytes).map_err(|e| {
         std::io::Error::new(
           e.kind(),
@@ -128,6 +151,10 @@ impl CacheStore {
   }
 
   pub fn write_manifest(&self) {
+    if !self.manifest_changed.swap(false, Ordering::SeqCst) {
+      return;
+    }
+
     let manifest = self.manifest.clone().into_iter().collect::<HashMap<_, _>>();
 
     if !self.cache_dir.exists() {
@@ -168,11 +195,31 @@ impl CacheStore {
     let cache_key = self.manifest.get(name).unwrap().value().clone();
     let cache_file = self.cache_dir.join(cache_key);
 
-    if cache_file.exists() && cache_file.is_file() {
-      return Some(std::fs::read(cache_file).unwrap());
+    if !cache_file.exists() || !cache_file.is_file() {
+      return None;
     }
 
-    None
+    let (version, bytes) = decode_schema_version(std::fs::read(&cache_file).unwrap());
+
+    if version == FARM_CACHE_SCHEMA_VERSION {
+      return Some(bytes);
+    }
+
+    match self.migrations.migrate(&self.name, name, version, bytes) {
+      Some(bytes) => {
+        // the migrated artifact is written back so it's only migrated once
+        std::fs::write(
+          &cache_file,
+          encode_schema_version(FARM_CACHE_SCHEMA_VERSION, &bytes),
+        )
+        .ok();
+        Some(bytes)
+      }
+      None => {
+        self.remove_cache(name);
+        None
+      }
+    }
   }
 
   pub fn remove_cache(&self, name: &str) {
@@ -181,6 +228,7 @@ impl CacheStore {
     }
 
     let (_, cache_key) = self.manifest.remove(name).unwrap();
+    self.manifest_changed.store(true, Ordering::SeqCst);
     let cache_file = self.cache_dir.join(cache_key);
 
     if cache_file.exists() && cache_file.is_file() {
@@ -196,3 +244,124 @@ pub struct CacheStoreKey {
   pub name: String,
   pub key: String,
 }
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::HashMap, sync::Arc};
+
+  use crate::{
+    cache::migration::{
+      decode_schema_version, encode_schema_version, CacheMigration, CacheMigrations,
+      FARM_CACHE_SCHEMA_VERSION,
+    },
+    config::Mode,
+  };
+
+  use super::{CacheStore, CacheStoreKey, FARM_CACHE_MANIFEST_FILE, FARM_CACHE_VERSION};
+
+  #[test]
+  fn write_manifest_only_when_changed() {
+    let dir = std::env::temp_dir().join(format!("farm-cache-store-test-{}", std::process::id()));
+    let store = CacheStore::new(dir.to_str().unwrap(), "", Mode::Development, "test");
+    let manifest_file = dir
+      .parent()
+      .unwrap()
+      .join(format!(
+        "{FARM_CACHE_VERSION}-{}",
+        dir.file_name().unwrap().to_string_lossy()
+      ))
+      .join("development")
+      .join("test")
+      .join(FARM_CACHE_MANIFEST_FILE);
+    let store_key = CacheStoreKey {
+      name: "a".to_string(),
+      key: "a-1".to_string(),
+    };
+
+    store.write_cache(HashMap::from([(store_key.clone(), vec![1])]));
+    assert!(manifest_file.exists());
+
+    // unchanged items don't touch the manifest
+    std::fs::remove_file(&manifest_file).unwrap();
+    store.write_cache(HashMap::from([(store_key, vec![1])]));
+    assert!(!manifest_file.exists());
+
+    store.remove_cache("a");
+    store.write_manifest();
+    assert!(manifest_file.exists());
+    assert!(!store.has_cache("a"));
+
+    std::fs::remove_dir_all(manifest_file.parent().unwrap()).unwrap();
+  }
+
+  struct AppendMigration;
+
+  impl CacheMigration for AppendMigration {
+    fn store(&self) -> &str {
+      "test"
+    }
+
+    fn source_version(&self) -> u32 {
+      FARM_CACHE_SCHEMA_VERSION - 1
+    }
+
+    fn migrate(&self, _name: &str, mut bytes: Vec<u8>) -> Option<Vec<u8>> {
+      bytes.extend_from_slice(b"-migrated");
+      Some(bytes)
+    }
+  }
+
+  #[test]
+  fn migrate_older_schema_versions() {
+    let dir =
+      std::env::temp_dir().join(format!("farm-cache-migration-test-{}", std::process::id()));
+    let store = CacheStore::new(dir.to_str().unwrap(), "", Mode::Development, "test")
+      .with_migrations(&CacheMigrations::new(vec![Arc::new(AppendMigration)]));
+    let store_dir = dir
+      .parent()
+      .unwrap()
+      .join(format!(
+        "{FARM_CACHE_VERSION}-{}",
+        dir.file_name().unwrap().to_string_lossy()
+      ))
+      .join("development")
+      .join("test");
+    let store_keys = ["old", "new", "legacy", "newer"].map(|name| CacheStoreKey {
+      name: name.to_string(),
+      key: format!("{name}-1"),
+    });
+
+    store.write_cache(
+      store_keys
+        .iter()
+        .map(|store_key| (store_key.clone(), b"cache".to_vec()))
+        .collect(),
+    );
+    assert_eq!(store.read_cache("new"), Some(b"cache".to_vec()));
+
+    let write = |key: &str, bytes: Vec<u8>| std::fs::write(store_dir.join(key), bytes).unwrap();
+    write(
+      "old-1",
+      encode_schema_version(FARM_CACHE_SCHEMA_VERSION - 1, b"cache"),
+    );
+    write("legacy-1", b"cache".to_vec());
+    write(
+      "newer-1",
+      encode_schema_version(FARM_CACHE_SCHEMA_VERSION + 1, b"cache"),
+    );
+
+    assert_eq!(store.read_cache("old"), Some(b"cache-migrated".to_vec()));
+    // the migrated artifact is written back in the current version
+    assert_eq!(
+      decode_schema_version(std::fs::read(store_dir.join("old-1")).unwrap()),
+      (FARM_CACHE_SCHEMA_VERSION, b"cache-migrated".to_vec())
+    );
+    // artifacts without the schema version are written in the first version
+    assert_eq!(store.read_cache("legacy"), Some(b"cache".to_vec()));
+    // artifacts of unknown versions are discarded
+    assert_eq!(store.read_cache("newer"), None);
+    assert!(!store.has_cache("newer"));
+
+    std::fs::remove_dir_all(&store_dir).unwrap();
+  }
+}
@@ -0,0 +1,311 @@
+//! Machine global cache of immutable modules, shared by all projects on the machine.
+//! A cached package is keyed by its name, version, location in node_modules and the transform options,
+//! so projects with the same dependencies and build options reuse the transformed node_modules.
+//! The rendered code of the immutable modules is cached too, so resource pots of them are not rendered again.
+//! Cached entries are immutable, the least recently used ones are removed when the cache exceeds the max size.
+use std::{
+  fs::{File, FileTimes},
+  path::{Path, PathBuf},
+  time::{Duration, SystemTime},
+};
+
+use dashmap::DashMap;
+use farmfe_utils::hash::sha256;
+
+use crate::{cache::cache_store::FARM_CACHE_VERSION, config::persistent_cache::GlobalCacheConfig};
+
+const GLOBAL_CACHE_FILE_EXT: &str = "farm-package";
+/// modified time of this file is the last time the cache is collected by any project
+const GLOBAL_CACHE_GC_MARKER: &str = ".last-gc";
+/// scanning the whole cache directory is slow, it's collected at most once in this interval
+const GLOBAL_CACHE_GC_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// name, version and directory of a package
+#[derive(Debug, Clone)]
+pub struct GlobalCachePackage {
+  pub name: String,
+  pub version: String,
+  pub dir: PathBuf,
+}
+
+pub struct GlobalCacheStore {
+  dir: PathBuf,
+  max_size: u64,
+  /// hash of the options that affect the transformed result
+  options_hash: String,
+  /// package directory -> package info, None if the directory has no valid package.json
+  packages: DashMap<PathBuf, Option<GlobalCachePackage>>,
+}
+
+impl GlobalCacheStore {
+  pub fn new(config: &GlobalCacheConfig, options_hash: String) -> Self {
+    Self {
+      dir: config.resolved_dir(),
+      max_size: config.max_size,
+      options_hash,
+      packages: DashMap::new(),
+    }
+  }
+
+  /// Find the closest package of the file, return None if the file is not in node_modules
+  pub fn find_package(&self, file: &Path) -> Option<GlobalCachePackage> {
+    if !file.components().any(|c| c.as_os_str() == "node_modules") {
+      return None;
+    }
+
+    let mut dir = file.parent();
+
+    while let Some(d) = dir {
+      let cached = self.packages.get(d).map(|p| p.clone());
+      let package = cached.unwrap_or_else(|| {
+        let package = read_package_json(d);
+        self.packages.insert(d.to_path_buf(), package.clone());
+        package
+      });
+
+      if package.is_some() {
+        return package;
+      }
+
+      if d.file_name().is_some_and(|n| n == "node_modules") {
+        return None;
+      }
+
+      dir = d.parent();
+    }
+
+    None
+  }
+
+  /// The relative directory of the package is part of the key, so the module ids in the cached package are valid for the project
+  pub fn gen_key(&self, package: &GlobalCachePackage, root: &str) -> String {
+    let relative_dir = farmfe_utils::relative(root, &package.dir.to_string_lossy());
+
+    sha256(
+      format!(
+        "{}@{}|{relative_dir}|{}|{FARM_CACHE_VERSION}",
+        package.name, package.version, self.options_hash
+      )
+      .as_bytes(),
+      32,
+    )
+  }
+
+  /// Key of a rendered module of the package, `render_hash` covers everything else the rendered code depends on,
+  /// e.g. the content and used exports of the module
+  pub fn gen_rendered_module_key(
+    &self,
+    package: &GlobalCachePackage,
+    root: &str,
+    module_id: &str,
+    render_hash: &str,
+  ) -> String {
+    sha256(
+      format!(
+        "{}|rendered|{module_id}|{render_hash}",
+        self.gen_key(package, root)
+      )
+      .as_bytes(),
+      32,
+    )
+  }
+
+  fn file_path(&self, key: &str) -> PathBuf {
+    self.dir.join(format!("{key}.{GLOBAL_CACHE_FILE_EXT}"))
+  }
+
+  /// Read the cached package and mark it as recently used
+  pub fn read(&self, key: &str) -> Option<Vec<u8>> {
+    let path = self.file_path(key);
+    let bytes = std::fs::read(&path).ok()?;
+
+    if let Ok(file) = File::options().write(true).open(&path) {
+      file
+        .set_times(FileTimes::new().set_modified(SystemTime::now()))
+        .ok();
+    }
+
+    Some(bytes)
+  }
+
+  /// Cached packages are immutable, the existing cache is never overwritten
+  pub fn write(&self, key: &str, bytes: &[u8]) {
+    let path = self.file_path(key);
+
+    if path.exists() {
+      return;
+    }
+
+    if std::fs::create_dir_all(&self.dir).is_err() {
+      return;
+    }
+
+    // write to a temp file first so other projects never read a partial file
+    let tmp_path = self.dir.join(format!("{key}.{}.tmp", std::process::id()));
+
+    if std::fs::write(&tmp_path, bytes).is_ok() && std::fs::rename(&tmp_path, &path).is_err() {
+      std::fs::remove_file(&tmp_path).ok();
+    }
+  }
+
+  pub fn gc(&self) -> GlobalCacheGcResult {
+    gc_global_cache(&self.dir, self.max_size)
+  }
+
+  /// [GlobalCacheStore::gc] if the cache is not collected by any project in the last [GLOBAL_CACHE_GC_INTERVAL], None if skipped
+  pub fn throttled_gc(&self) -> Option<GlobalCacheGcResult> {
+    let marker = self.dir.join(GLOBAL_CACHE_GC_MARKER);
+    let last_gc = std::fs::metadata(&marker).and_then(|m| m.modified()).ok();
+
+    if last_gc
+      .and_then(|last_gc| last_gc.elapsed().ok())
+      .is_some_and(|elapsed| elapsed < GLOBAL_CACHE_GC_INTERVAL)
+    {
+      return None;
+    }
+
+    std::fs::write(&marker, []).ok();
+
+    Some(self.gc())
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct GlobalCacheGcResult {
+  pub removed: usize,
+  pub freed_bytes: u64,
+}
+
+/// Remove the least recently used packages until the total size of the cache is not larger than `max_size`
+pub fn gc_global_cache(dir: &Path, max_size: u64) -> GlobalCacheGcResult {
+  let mut result = GlobalCacheGcResult::default();
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return result;
+  };
+
+  let mut files = entries
+    .flatten()
+    .filter(|e| {
+      e.path()
+        .extension()
+        .is_some_and(|ext| ext == GLOBAL_CACHE_FILE_EXT)
+    })
+    .filter_map(|e| {
+      let metadata = e.metadata().ok()?;
+      Some((
+        e.path(),
+        metadata.len(),
+        metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+      ))
+    })
+    .collect::<Vec<_>>();
+
+  let mut total_size = files.iter().map(|(_, size, _)| size).sum::<u64>();
+  // least recently used first
+  files.sort_by_key(|(_, _, modified)| *modified);
+
+  for (path, size, _) in files {
+    if total_size <= max_size {
+      break;
+    }
+
+    if std::fs::remove_file(path).is_ok() {
+      total_size -= size;
+      result.removed += 1;
+      result.freed_bytes += size;
+    }
+  }
+
+  result
+}
+
+fn read_package_json(dir: &Path) -> Option<GlobalCachePackage> {
+  let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+  let value = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+
+  Some(GlobalCachePackage {
+    name: value.get("name")?.as_str()?.to_string(),
+    version: value.get("version")?.as_str()?.to_string(),
+    dir: dir.to_path_buf(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+  };
+
+  use super::{
+    gc_global_cache, GlobalCacheGcResult, GlobalCachePackage, GlobalCacheStore,
+    GLOBAL_CACHE_FILE_EXT,
+  };
+  use crate::config::persistent_cache::GlobalCacheConfig;
+
+  #[test]
+  fn test_gen_rendered_module_key() {
+    let store = GlobalCacheStore::new(&GlobalCacheConfig::default(), "options".to_string());
+    let package = GlobalCachePackage {
+      name: "react".to_string(),
+      version: "18.2.0".to_string(),
+      dir: PathBuf::from("/project/node_modules/react"),
+    };
+    let key = |module_id, render_hash| {
+      store.gen_rendered_module_key(&package, "/project", module_id, render_hash)
+    };
+
+    let index = key("node_modules/react/index.js", "a");
+    assert_eq!(index, key("node_modules/react/index.js", "a"));
+    assert_ne!(index, key("node_modules/react/index.js", "b"));
+    assert_ne!(index, key("node_modules/react/jsx-runtime.js", "a"));
+    assert_ne!(index, store.gen_key(&package, "/project"));
+  }
+
+  #[test]
+  fn test_throttled_gc() {
+    let dir = std::env::temp_dir().join(format!(
+      "farm-global-cache-throttle-test-{}",
+      std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    let store = GlobalCacheStore::new(
+      &GlobalCacheConfig {
+        dir: dir.to_string_lossy().to_string(),
+        ..Default::default()
+      },
+      "options".to_string(),
+    );
+
+    assert_eq!(store.throttled_gc(), Some(GlobalCacheGcResult::default()));
+    assert_eq!(store.throttled_gc(), None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn test_gc_global_cache() {
+    let dir = std::env::temp_dir().join(format!("farm-global-cache-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    for (i, name) in ["a", "b", "c"].iter().enumerate() {
+      let path = dir.join(format!("{name}.{GLOBAL_CACHE_FILE_EXT}"));
+      std::fs::write(&path, vec![0u8; 10]).unwrap();
+      let file = std::fs::File::options().write(true).open(&path).unwrap();
+      file
+        .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(i as u64 + 1))
+        .unwrap();
+    }
+
+    assert_eq!(
+      gc_global_cache(&dir, 15),
+      GlobalCacheGcResult {
+        removed: 2,
+        freed_bytes: 20
+      }
+    );
+    assert!(dir.join(format!("c.{GLOBAL_CACHE_FILE_EXT}")).exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
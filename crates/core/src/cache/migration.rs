@@ -0,0 +1,78 @@
+//! Schema versions of the persistent cache artifacts. Every artifact written by [super::cache_store::CacheStore] starts with
+//! the schema version it's written in, artifacts of older versions are migrated by the registered [CacheMigration]s when
+//! they are read, instead of discarding the whole cache when the format of some cached structures changes.
+
+use std::sync::Arc;
+
+/// version of the format of the cache artifacts, bumped with a [CacheMigration] of the changed store when the cached structures
+/// change. Breaking changes that can't be migrated bump [super::cache_store::FARM_CACHE_VERSION] instead
+pub const FARM_CACHE_SCHEMA_VERSION: u32 = 1;
+const SCHEMA_VERSION_MAGIC: &[u8] = b"FARMCACHE";
+
+/// Migrate the artifacts of a cache store from [CacheMigration::source_version] to the next schema version.
+/// Registered by [crate::plugin::Plugin::cache_migrations]
+pub trait CacheMigration: Send + Sync {
+  /// name of the migrated cache store, e.g. `mutable-modules`, `immutable-modules`, `resource`, `plugin` or `custom`
+  fn store(&self) -> &str;
+
+  fn source_version(&self) -> u32;
+
+  /// The migrated bytes of the artifact `name`, [None] if it can't be migrated and should be discarded
+  fn migrate(&self, name: &str, bytes: Vec<u8>) -> Option<Vec<u8>>;
+}
+
+#[derive(Default, Clone)]
+pub struct CacheMigrations {
+  migrations: Arc<Vec<Arc<dyn CacheMigration>>>,
+}
+
+impl CacheMigrations {
+  pub fn new(migrations: Vec<Arc<dyn CacheMigration>>) -> Self {
+    Self {
+      migrations: Arc::new(migrations),
+    }
+  }
+
+  /// Migrate the artifact from `version` to [FARM_CACHE_SCHEMA_VERSION] step by step,
+  /// [None] if a step is not registered or fails, or the artifact is written by a newer version
+  pub fn migrate(&self, store: &str, name: &str, version: u32, bytes: Vec<u8>) -> Option<Vec<u8>> {
+    let mut bytes = bytes;
+
+    for from_version in version..FARM_CACHE_SCHEMA_VERSION {
+      let migration = self
+        .migrations
+        .iter()
+        .find(|m| m.store() == store && m.source_version() == from_version)?;
+      bytes = migration.migrate(name, bytes)?;
+    }
+
+    (version <= FARM_CACHE_SCHEMA_VERSION).then_some(bytes)
+  }
+}
+
+/// Prepend the schema version to the bytes of the artifact
+pub(crate) fn encode_schema_version(version: u32, bytes: &[u8]) -> Vec<u8> {
+  let mut encoded = Vec::with_capacity(SCHEMA_VERSION_MAGIC.len() + 4 + bytes.len());
+  encoded.extend_from_slice(SCHEMA_VERSION_MAGIC);
+  encoded.extend_from_slice(&version.to_le_bytes());
+  encoded.extend_from_slice(bytes);
+  encoded
+}
+
+/// The schema version and the bytes of the artifact. The artifacts written before the schema version is embedded
+/// have the same format as the first version
+pub(crate) fn decode_schema_version(encoded: Vec<u8>) -> (u32, Vec<u8>) {
+  let header_len = SCHEMA_VERSION_MAGIC.len() + 4;
+
+  if encoded.len() < header_len || !encoded.starts_with(SCHEMA_VERSION_MAGIC) {
+    return (1, encoded);
+  }
+
+  let version = u32::from_le_bytes(
+    encoded[SCHEMA_VERSION_MAGIC.len()..header_len]
+      .try_into()
+      .unwrap(),
+  );
+
+  (version, encoded[header_len..].to_vec())
+}
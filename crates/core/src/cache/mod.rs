@@ -1,10 +1,18 @@
+use std::sync::Arc;
+
 use parking_lot::Mutex;
 
 use crate::config::Mode;
 
-use self::{cache_store::CacheStore, plugin_cache::PluginCacheManager};
+use self::{
+  cache_store::CacheStore, global_cache::GlobalCacheStore, migration::CacheMigrations,
+  plugin_cache::PluginCacheManager,
+};
 
+pub mod archive;
 pub mod cache_store;
+pub mod global_cache;
+pub mod migration;
 pub mod module_cache;
 pub mod plugin_cache;
 pub mod resource_cache;
@@ -21,23 +29,30 @@ pub struct CacheManager {
   pub lazy_compile_store: CacheStore,
   /// cache store for custom caches
   pub custom: CacheStore,
+  /// machine global cache shared with other projects, set when `persistentCache.globalCache` is configured
+  pub global_cache: Option<Arc<GlobalCacheStore>>,
   /// lock for cache manager
   pub lock: Mutex<bool>,
 }
 
 impl CacheManager {
-  pub fn new(cache_dir: &str, namespace: &str, mode: Mode) -> Self {
-    let module_cache = module_cache::ModuleCacheManager::new(cache_dir, namespace, mode.clone());
+  /// The artifacts written in older schema versions are migrated by `migrations` when they are read, see [migration]
+  pub fn new(cache_dir: &str, namespace: &str, mode: Mode, migrations: CacheMigrations) -> Self {
+    let module_cache =
+      module_cache::ModuleCacheManager::new(cache_dir, namespace, mode.clone(), &migrations);
     let resource_cache =
-      resource_cache::ResourceCacheManager::new(cache_dir, namespace, mode.clone());
+      resource_cache::ResourceCacheManager::new(cache_dir, namespace, mode.clone(), &migrations);
 
     Self {
       module_cache,
       resource_cache,
       // plugin cache is not initialized here. it will be initialized when compile starts.
-      plugin_cache: PluginCacheManager::new(cache_dir, namespace, mode.clone()),
-      custom: CacheStore::new(cache_dir, namespace, mode.clone(), "custom"),
-      lazy_compile_store: CacheStore::new(cache_dir, namespace, mode, "lazy-compilation"),
+      plugin_cache: PluginCacheManager::new(cache_dir, namespace, mode.clone(), &migrations),
+      custom: CacheStore::new(cache_dir, namespace, mode.clone(), "custom")
+        .with_migrations(&migrations),
+      lazy_compile_store: CacheStore::new(cache_dir, namespace, mode, "lazy-compilation")
+        .with_migrations(&migrations),
+      global_cache: None,
       lock: Mutex::new(false),
     }
   }
@@ -2,10 +2,11 @@ use dashmap::mapref::one::{Ref, RefMut};
 
 use farmfe_macro_cache_item::cache_item;
 
+use crate::cache::migration::CacheMigrations;
 use crate::config::Mode;
 use crate::module::module_graph::ModuleGraphEdge;
 use crate::module::{Module, ModuleId};
-use crate::plugin::PluginAnalyzeDepsHookResultEntry;
+use crate::plugin::{PluginAnalyzeDepsHookResultEntry, ResolveTrace};
 
 use immutable_modules::ImmutableModulesMemoryStore;
 use module_memory_store::ModuleMemoryStore;
@@ -34,6 +35,8 @@ pub struct CachedWatchDependency {
   pub dependency: ModuleId,
   pub timestamp: u128,
   pub hash: String,
+  /// hash of the exports of the dependency if it's a script module, see [crate::config::persistent_cache::PersistentModuleCacheKeyStrategy::export_signature]
+  pub export_signature: Option<String>,
 }
 
 #[cache_item]
@@ -53,9 +56,14 @@ pub struct CachedModule {
 }
 
 impl CachedModule {
+  /// The dependencies with the cached module ids, and how they were resolved
   pub fn dep_sources(
     dependencies: Vec<CachedModuleDependency>,
-  ) -> Vec<(PluginAnalyzeDepsHookResultEntry, Option<ModuleId>)> {
+  ) -> Vec<(
+    PluginAnalyzeDepsHookResultEntry,
+    Option<ModuleId>,
+    Option<ResolveTrace>,
+  )> {
     dependencies
       .into_iter()
       .flat_map(|dep| {
@@ -65,7 +73,7 @@ impl CachedModule {
           .edge_info
           .0
           .into_iter()
-          .map(|item| (item.source, item.kind, item.order))
+          .map(|item| (item.source, item.kind, item.order, item.resolve_trace))
           .collect::<Vec<_>>();
         sorted_dep.sort_by(|a, b| a.2.cmp(&b.2));
 
@@ -76,6 +84,7 @@ impl CachedModule {
               kind: item.1,
             },
             Some(cloned_dep.clone()),
+            item.3,
           )
         })
       })
@@ -84,10 +93,25 @@ impl CachedModule {
 }
 
 impl ModuleCacheManager {
-  pub fn new(cache_dir_str: &str, namespace: &str, mode: Mode) -> Self {
+  pub fn new(
+    cache_dir_str: &str,
+    namespace: &str,
+    mode: Mode,
+    migrations: &CacheMigrations,
+  ) -> Self {
     Self {
-      mutable_modules_store: MutableModulesMemoryStore::new(cache_dir_str, namespace, mode.clone()),
-      immutable_modules_store: ImmutableModulesMemoryStore::new(cache_dir_str, namespace, mode),
+      mutable_modules_store: MutableModulesMemoryStore::new(
+        cache_dir_str,
+        namespace,
+        mode.clone(),
+        migrations,
+      ),
+      immutable_modules_store: ImmutableModulesMemoryStore::new(
+        cache_dir_str,
+        namespace,
+        mode,
+        migrations,
+      ),
     }
   }
 
@@ -1,6 +1,10 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+  collections::{HashMap, HashSet},
+  path::Path,
+  sync::Arc,
+};
 
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use farmfe_macro_cache_item::cache_item;
 use farmfe_utils::hash::sha256;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
@@ -9,7 +13,9 @@ use rkyv::Deserialize;
 use crate::{
   cache::{
     cache_store::{CacheStore, CacheStoreKey},
-    utils::cache_panic,
+    global_cache::GlobalCacheStore,
+    migration::CacheMigrations,
+    utils::{cache_panic, take_dirty},
   },
   config::Mode,
   module::ModuleId,
@@ -46,11 +52,23 @@ pub struct ImmutableModulesMemoryStore {
   /// moduleId -> PackageKey
   manifest: DashMap<ModuleId, String>,
   manifest_reversed: DashMap<String, HashSet<ModuleId>>,
+  /// machine global cache shared with other projects, (store, project root)
+  global: Option<(Arc<GlobalCacheStore>, String)>,
+  /// global cache keys that are already read
+  global_read_keys: DashMap<String, bool>,
+  /// modules that are set or mutated since last write, only the packages of them are written
+  dirty_modules: DashSet<ModuleId>,
 }
 
 impl ImmutableModulesMemoryStore {
-  pub fn new(cache_dir_str: &str, namespace: &str, mode: Mode) -> Self {
-    let store = CacheStore::new(cache_dir_str, namespace, mode, "immutable-modules");
+  pub fn new(
+    cache_dir_str: &str,
+    namespace: &str,
+    mode: Mode,
+    migrations: &CacheMigrations,
+  ) -> Self {
+    let store = CacheStore::new(cache_dir_str, namespace, mode, "immutable-modules")
+      .with_migrations(migrations);
 
     let manifest_bytes = store.read_cache(MANIFEST_KEY).unwrap_or_default();
     let manifest: HashMap<String, String> =
@@ -75,22 +93,74 @@ impl ImmutableModulesMemoryStore {
       manifest: manifest.into_iter().collect(),
       manifest_reversed,
       cache_dir: cache_dir_str.to_string(),
+      global: None,
+      global_read_keys: DashMap::new(),
+      dirty_modules: DashSet::new(),
+    }
+  }
+
+  pub fn enable_global_cache(&mut self, global: Arc<GlobalCacheStore>, root: String) {
+    self.global = Some((global, root));
+  }
+
+  /// Read the package of the module from the global cache. The package is ignored if any dependency of it does not exist in current project
+  fn read_global_package(&self, module_id: &ModuleId) -> Option<()> {
+    let (global, root) = self.global.as_ref()?;
+    let package = global.find_package(Path::new(&module_id.resolved_path(root)))?;
+    let key = global.gen_key(&package, root);
+
+    if self.global_read_keys.insert(key.clone(), true).is_some() {
+      return None;
+    }
+
+    let bytes = global.read(&key)?;
+    // the global cache is shared by other projects and versions of farm, invalid packages are treated as misses
+    let package = crate::try_deserialize!(&bytes, CachedPackage)?;
+
+    let is_valid = package.list.iter().all(|cm| {
+      cm.dependencies
+        .iter()
+        .all(|dep| Path::new(&dep.dependency.resolved_path(root)).exists())
+    });
+
+    if !is_valid || !package.list.iter().any(|cm| &cm.module.id == module_id) {
+      return None;
+    }
+
+    // packages of the global cache are written to the store of current project too
+    for module in package.list {
+      self.dirty_modules.insert(module.module.id.clone());
+      self.cached_modules.insert(module.module.id.clone(), module);
+    }
+
+    Some(())
+  }
+
+  fn write_global_package(&self, module_id: &ModuleId, package_bytes: &[u8]) {
+    if let Some((global, root)) = self.global.as_ref() {
+      if let Some(package) = global.find_package(Path::new(&module_id.resolved_path(root))) {
+        global.write(&global.gen_key(&package, root), package_bytes);
+      }
     }
   }
 
-  fn read_cached_package(&self, package_key: &str) -> CachedPackage {
+  /// [None] if the cached package is invalid, e.g. written by another version of farm
+  fn read_cached_package(&self, package_key: &str) -> Option<CachedPackage> {
     let cache = self
       .store
       .read_cache(package_key)
       .expect("Cache broken, please remove node_modules/.farm and retry.");
 
-    crate::deserialize!(&cache, CachedPackage)
+    crate::try_deserialize!(&cache, CachedPackage)
   }
 
   fn read_package(&self, module_id: &ModuleId) -> Option<()> {
-    if let Some(package_key) = self.manifest.get(module_id) {
-      let package = self.read_cached_package(package_key.value());
+    let package = self
+      .manifest
+      .get(module_id)
+      .and_then(|package_key| self.read_cached_package(package_key.value()));
 
+    if let Some(package) = package {
       for module in package.list {
         self.cached_modules.insert(module.module.id.clone(), module);
       }
@@ -98,7 +168,7 @@ impl ImmutableModulesMemoryStore {
       return Some(());
     }
 
-    None
+    self.read_global_package(module_id)
   }
 }
 
@@ -108,6 +178,7 @@ impl ModuleMemoryStore for ImmutableModulesMemoryStore {
   }
 
   fn set_cache(&self, key: crate::module::ModuleId, module: super::CachedModule) {
+    self.dirty_modules.insert(key.clone());
     self.cached_modules.insert(key, module);
   }
 
@@ -153,6 +224,8 @@ impl ModuleMemoryStore for ImmutableModulesMemoryStore {
     &self,
     key: &crate::module::ModuleId,
   ) -> Option<dashmap::mapref::one::RefMut<'_, crate::module::ModuleId, super::CachedModule>> {
+    self.dirty_modules.insert(key.clone());
+
     if self.cached_modules.contains_key(key) {
       return Some(self.cached_modules.get_mut(key).unwrap());
     }
@@ -170,6 +243,20 @@ impl ModuleMemoryStore for ImmutableModulesMemoryStore {
   }
 
   fn write_cache(&self) {
+    let dirty_packages = take_dirty(&self.dirty_modules)
+      .into_iter()
+      .filter_map(|module_id| {
+        self
+          .cached_modules
+          .get(&module_id)
+          .map(|m| CachedPackage::gen_key(&m.module.package_name, &m.module.package_version))
+      })
+      .collect::<HashSet<_>>();
+
+    if dirty_packages.is_empty() {
+      return;
+    }
+
     let mut packages = HashMap::new();
     let mut pending_remove_modules = HashSet::new();
     let mut maybe_remove_package = HashSet::new();
@@ -180,6 +267,11 @@ impl ModuleMemoryStore for ImmutableModulesMemoryStore {
       let package_key =
         CachedPackage::gen_key(&module.module.package_name, &module.module.package_version);
 
+      // packages without dirty modules are not changed since last write
+      if !dirty_packages.contains(&package_key) {
+        continue;
+      }
+
       if module.is_expired {
         pending_remove_modules.insert(item.key().clone());
         maybe_remove_package.insert(package_key);
@@ -236,16 +328,19 @@ impl ModuleMemoryStore for ImmutableModulesMemoryStore {
           let modules_in_package = self.manifest_reversed.get(&key).unwrap();
           let mut added_modules = vec![];
 
-          for module_id in modules {
-            if modules_in_package.contains(&module_id) {
+          for module_id in &modules {
+            if modules_in_package.contains(module_id) {
               continue;
             }
-            added_modules.push(module_id);
+            added_modules.push(module_id.clone());
           }
 
-          // add the new modules to the package
-          if !added_modules.is_empty() {
-            let mut package = self.read_cached_package(&key);
+          if added_modules.is_empty() {
+            return None;
+          }
+
+          // add the new modules to the package, an invalid package is written again from the modules below
+          if let Some(mut package) = self.read_cached_package(&key) {
             package.list.extend(
               added_modules
                 .into_par_iter()
@@ -264,9 +359,9 @@ impl ModuleMemoryStore for ImmutableModulesMemoryStore {
               .map(|cm| cm.module.id.to_string())
               .collect::<Vec<_>>();
             let package_bytes = crate::serialize!(&package);
+            self.write_global_package(&package.list[0].module.id, &package_bytes);
             return Some((gen_cache_store_key(modules), package_bytes));
           }
-          return None;
         }
 
         let module_strings = modules.iter().map(|m| m.to_string()).collect::<Vec<_>>();
@@ -286,6 +381,7 @@ impl ModuleMemoryStore for ImmutableModulesMemoryStore {
         };
 
         let package_bytes = crate::serialize!(&package);
+        self.write_global_package(&package.list[0].module.id, &package_bytes);
         Some((gen_cache_store_key(module_strings), package_bytes))
       })
       .collect::<HashMap<CacheStoreKey, Vec<u8>>>();
@@ -299,6 +395,10 @@ impl ModuleMemoryStore for ImmutableModulesMemoryStore {
     );
 
     self.store.write_cache(cache_map);
+
+    if let Some((global, _)) = self.global.as_ref() {
+      global.throttled_gc();
+    }
   }
 
   fn invalidate_cache(&self, key: &ModuleId) {
@@ -1,12 +1,16 @@
 use std::collections::HashMap;
 
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use farmfe_utils::hash::sha256;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use rkyv::Deserialize;
 
 use crate::{
-  cache::cache_store::{CacheStore, CacheStoreKey},
+  cache::{
+    cache_store::{CacheStore, CacheStoreKey},
+    migration::CacheMigrations,
+    utils::take_dirty,
+  },
   config::Mode,
   deserialize,
   module::ModuleId,
@@ -21,13 +25,22 @@ pub struct MutableModulesMemoryStore {
   store: CacheStore,
   /// ModuleId -> Cached Module
   cached_modules: DashMap<ModuleId, CachedModule>,
+  /// modules that are set or mutated since last write, other modules are not checked when writing the cache
+  dirty_modules: DashSet<ModuleId>,
 }
 // TODO: cache unit test
 impl MutableModulesMemoryStore {
-  pub fn new(cache_dir_str: &str, namespace: &str, mode: Mode) -> Self {
+  pub fn new(
+    cache_dir_str: &str,
+    namespace: &str,
+    mode: Mode,
+    migrations: &CacheMigrations,
+  ) -> Self {
     Self {
-      store: CacheStore::new(cache_dir_str, namespace, mode, "mutable-modules"),
+      store: CacheStore::new(cache_dir_str, namespace, mode, "mutable-modules")
+        .with_migrations(migrations),
       cached_modules: DashMap::new(),
+      dirty_modules: DashSet::new(),
     }
   }
 
@@ -62,6 +75,7 @@ impl ModuleMemoryStore for MutableModulesMemoryStore {
   }
 
   fn set_cache(&self, key: ModuleId, module: CachedModule) {
+    self.dirty_modules.insert(key.clone());
     self.cached_modules.insert(key, module);
   }
 
@@ -104,6 +118,8 @@ impl ModuleMemoryStore for MutableModulesMemoryStore {
     &self,
     key: &ModuleId,
   ) -> Option<dashmap::mapref::one::RefMut<'_, ModuleId, CachedModule>> {
+    self.dirty_modules.insert(key.clone());
+
     if let Some(module) = self.cached_modules.get_mut(key) {
       return Some(module);
     }
@@ -122,7 +138,12 @@ impl ModuleMemoryStore for MutableModulesMemoryStore {
   fn write_cache(&self) {
     let mut cache_map = HashMap::new();
     let mut pending_removed_modules = vec![];
-    for entry in self.cached_modules.iter() {
+    let dirty_modules = take_dirty(&self.dirty_modules);
+
+    for module_id in dirty_modules {
+      let Some(entry) = self.cached_modules.get(&module_id) else {
+        continue;
+      };
       let module = entry.value();
       if module.is_expired {
         pending_removed_modules.push(module.module.id.clone());
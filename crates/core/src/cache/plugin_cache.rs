@@ -1,24 +1,31 @@
 use std::collections::HashMap;
 
-use dashmap::{mapref::one::Ref, DashMap};
+use dashmap::{mapref::one::Ref, DashMap, DashSet};
 use farmfe_utils::hash::sha256;
 
 use crate::config::Mode;
 
-use super::cache_store::{CacheStore, CacheStoreKey};
+use super::{
+  cache_store::{CacheStore, CacheStoreKey},
+  migration::CacheMigrations,
+  utils::take_dirty,
+};
 
 #[derive(Default)]
 pub struct PluginCacheManager {
   store: CacheStore,
   cache: DashMap<String, Vec<u8>>,
+  /// plugins that set cache since last write
+  dirty_plugins: DashSet<String>,
 }
 
 impl PluginCacheManager {
-  pub fn new(cache_dir: &str, namespace: &str, mode: Mode) -> Self {
-    let store = CacheStore::new(cache_dir, namespace, mode, "plugin");
+  pub fn new(cache_dir: &str, namespace: &str, mode: Mode, migrations: &CacheMigrations) -> Self {
+    let store = CacheStore::new(cache_dir, namespace, mode, "plugin").with_migrations(migrations);
     Self {
       store,
       cache: DashMap::new(),
+      dirty_plugins: DashSet::new(),
     }
   }
 
@@ -50,15 +57,23 @@ impl PluginCacheManager {
   }
 
   pub fn set_cache(&self, plugin_name: &str, cache: Vec<u8>) {
-    self
-      .cache
-      .insert(self.normalize_plugin_name(plugin_name), cache);
+    let plugin_name = self.normalize_plugin_name(plugin_name);
+    self.dirty_plugins.insert(plugin_name.clone());
+    self.cache.insert(plugin_name, cache);
+  }
+
+  /// Discard the cache of the plugin in memory and on disk, e.g. the cache is written by an incompatible version of the plugin
+  pub fn invalidate_cache(&self, plugin_name: &str) {
+    let plugin_name = self.normalize_plugin_name(plugin_name);
+    self.dirty_plugins.remove(&plugin_name);
+    self.cache.remove(&plugin_name);
+    self.store.remove_cache(&plugin_name);
   }
 
   pub fn write_cache_to_disk(&self) {
-    let cache = self
-      .cache
-      .iter()
+    let cache = take_dirty(&self.dirty_plugins)
+      .into_iter()
+      .filter_map(|name| self.cache.get(&name))
       .map(|entry| {
         (
           CacheStoreKey {
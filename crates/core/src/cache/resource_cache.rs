@@ -1,5 +1,7 @@
 use crate::config::Mode;
 
+use super::migration::CacheMigrations;
+
 use self::resource_memory_store::{CachedResourcePot, ResourceMemoryStore};
 use self::resource_pot::ResourcePotMemoryStore;
 
@@ -11,9 +13,14 @@ pub struct ResourceCacheManager {
 }
 
 impl ResourceCacheManager {
-  pub fn new(cache_dir_str: &str, namespace: &str, mode: Mode) -> Self {
+  pub fn new(
+    cache_dir_str: &str,
+    namespace: &str,
+    mode: Mode,
+    migrations: &CacheMigrations,
+  ) -> Self {
     Self {
-      resource_pot_store: ResourcePotMemoryStore::new(cache_dir_str, namespace, mode),
+      resource_pot_store: ResourcePotMemoryStore::new(cache_dir_str, namespace, mode, migrations),
     }
   }
 
@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use rkyv::Deserialize;
 
 use crate::{
-  cache::cache_store::{CacheStore, CacheStoreKey},
+  cache::{
+    cache_store::{CacheStore, CacheStoreKey},
+    migration::CacheMigrations,
+    utils::take_dirty,
+  },
   config::Mode,
   deserialize, serialize,
 };
@@ -18,13 +22,22 @@ pub struct ResourcePotMemoryStore {
   store: CacheStore,
   /// resource pot id -> Cached Resource Pot
   cached_resources: DashMap<String, CachedResourcePot>,
+  /// resource pots that are set since last write
+  dirty_resources: DashSet<String>,
 }
 
 impl ResourcePotMemoryStore {
-  pub fn new(cache_dir_str: &str, namespace: &str, mode: Mode) -> Self {
+  pub fn new(
+    cache_dir_str: &str,
+    namespace: &str,
+    mode: Mode,
+    migrations: &CacheMigrations,
+  ) -> Self {
     Self {
-      store: CacheStore::new(cache_dir_str, namespace, mode, "resource"),
+      store: CacheStore::new(cache_dir_str, namespace, mode, "resource")
+        .with_migrations(migrations),
       cached_resources: DashMap::new(),
+      dirty_resources: DashSet::new(),
     }
   }
 
@@ -45,6 +58,7 @@ impl ResourceMemoryStore for ResourcePotMemoryStore {
   }
 
   fn set_cache(&self, name: &str, resource: CachedResourcePot) {
+    self.dirty_resources.insert(name.to_string());
     self.cached_resources.insert(name.to_string(), resource);
   }
 
@@ -69,7 +83,10 @@ impl ResourceMemoryStore for ResourcePotMemoryStore {
   fn write_cache(&self) {
     let mut cache_map = HashMap::new();
 
-    for entry in self.cached_resources.iter() {
+    for name in take_dirty(&self.dirty_resources) {
+      let Some(entry) = self.cached_resources.get(&name) else {
+        continue;
+      };
       let store_key = CacheStoreKey {
         name: entry.key().clone(),
         key: entry.value().hash.clone(),
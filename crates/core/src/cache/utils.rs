@@ -1,3 +1,16 @@
+use dashmap::DashSet;
+
 pub fn cache_panic(key: &str, cache_dir: &str) -> ! {
   panic!("Cache broken: {key} not found, please remove {cache_dir} and retry.")
 }
+
+/// Take the dirty keys out of `dirty`, keys that become dirty while writing are kept for next write
+pub fn take_dirty<K: Eq + std::hash::Hash + Clone>(dirty: &DashSet<K>) -> Vec<K> {
+  let keys = dirty.iter().map(|k| k.key().clone()).collect::<Vec<_>>();
+
+  for key in &keys {
+    dirty.remove(key);
+  }
+
+  keys
+}
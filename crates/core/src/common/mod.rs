@@ -56,6 +56,33 @@ impl PackageJsonInfo {
     self.raw_map.as_ref().unwrap()
   }
 
+  /// Get the license of the package, both `"license": "MIT"` and legacy `"license": { "type": "MIT" }`, `"licenses": [{ "type": "MIT" }]` are supported
+  pub fn license(&self) -> Option<String> {
+    let raw_map = self.raw_map.as_ref()?;
+    let license_type = |v: &Value| match v {
+      Value::String(s) => Some(s.to_string()),
+      Value::Object(obj) => obj.get("type").and_then(|t| t.as_str()).map(|t| t.to_string()),
+      _ => None,
+    };
+
+    if let Some(license) = raw_map.get("license") {
+      return license_type(license);
+    }
+
+    let licenses = raw_map
+      .get("licenses")?
+      .as_array()?
+      .iter()
+      .filter_map(license_type)
+      .collect::<Vec<_>>();
+
+    if licenses.is_empty() {
+      None
+    } else {
+      Some(format!("({})", licenses.join(" OR ")))
+    }
+  }
+
   pub fn set_dir(&mut self, dir: String) {
     self.dir = Some(dir);
   }
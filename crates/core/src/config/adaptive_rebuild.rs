@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Escalate an update to a full compilation when it changes a large part of the module graph, e.g. after switching branches
+/// or installing dependencies. Compiling again with the cached modules is often faster than thousands of incremental diffs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AdaptiveRebuildConfig {
+  /// ratio of the changed modules to all modules of the module graph above which the update is escalated, from 0 to 1
+  pub threshold: f64,
+  /// updates changing fewer modules are never escalated, so small projects are always updated incrementally
+  pub min_changed_modules: usize,
+}
+
+impl Default for AdaptiveRebuildConfig {
+  fn default() -> Self {
+    Self {
+      threshold: 0.3,
+      min_changed_modules: 50,
+    }
+  }
+}
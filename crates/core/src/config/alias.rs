@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// An alias of `resolve.aliasEntries`. `find` prefixed with `$__farm_regex:` is a regex whose capture groups are referenced by `$1`
+/// in `replacement`, `find` ending with `$` matches the source exactly, otherwise `find` is replaced as a prefix of the source
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AliasEntry {
+  pub find: String,
+  pub replacement: String,
+}
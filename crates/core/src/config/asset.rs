@@ -1,6 +1,8 @@
+use farmfe_utils::hash::sha256;
 use serde::{Deserialize, Serialize};
 
-use super::TargetEnv;
+use super::{Mode, TargetEnv};
+use crate::module::ModuleId;
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -19,13 +21,103 @@ impl From<TargetEnv> for AssetFormatMode {
   }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+/// 10MB
+pub const DEFAULT_STREAM_THRESHOLD: usize = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
 pub struct AssetsConfig {
   pub include: Vec<String>,
   /// Used internally, this option will be not exposed to user.
   pub public_dir: Option<String>,
+  /// Assets larger than this size in bytes are copied from the source file to the output directory instead of being loaded into memory.
+  /// `0` means always loading assets into memory
+  pub stream_threshold: usize,
+  /// Binary files like native addons and models that are emitted beside the bundle for node target
+  pub binary: BinaryAssetsConfig,
+  /// Targets of the asset variants in production, e.g. `["web", "webview"]`. Each asset is transformed by the `transform_asset`
+  /// hook once per target and emitted to `<target>/`, the resources reference it by [asset_target_placeholder], which is replaced
+  /// by the asset of the target when the output variant of the target is written
+  pub targets: Vec<String>,
+  /// Collect the svg icons imported with `?sprite` into a single svg sprite, disabled if [None]
+  pub sprite: Option<SvgSpriteConfig>,
+  /// Import `.wasm` files with ESM integration: the default export is the exports object of the instance and every export
+  /// is a named export. When disabled, the default export is the url like other assets
+  pub wasm_esm_integration: bool,
   // TODO: v2
   // for ssr mode, should specify asset path format, default from `output.targetEnv`
   // pub mode: Option<AssetFormatMode>,
 }
+
+impl Default for AssetsConfig {
+  fn default() -> Self {
+    Self {
+      include: vec![],
+      public_dir: None,
+      stream_threshold: DEFAULT_STREAM_THRESHOLD,
+      binary: BinaryAssetsConfig::default(),
+      targets: vec![],
+      sprite: None,
+      wasm_esm_integration: false,
+    }
+  }
+}
+
+/// placeholder key -> target -> name of the asset emitted for the target, see [AssetsConfig::targets]
+pub const ASSET_TARGETS_MANIFEST: &str = "asset-targets.json";
+
+impl AssetsConfig {
+  /// Whether the assets are emitted per target, the targets are ignored in development
+  pub fn has_targets(&self, mode: &Mode) -> bool {
+    !self.targets.is_empty() && matches!(mode, Mode::Production)
+  }
+}
+
+/// Key of the output variant variable that stands for the name of the asset emitted per target, e.g. `ASSET_1f2e3d4c5b6a7980`
+pub fn asset_target_key(module_id: &ModuleId) -> String {
+  format!("ASSET_{}", sha256(module_id.to_string().as_bytes(), 16))
+}
+
+/// The `__FARM_VARIANT_<KEY>__` placeholder of [asset_target_key]
+pub fn asset_target_placeholder(module_id: &ModuleId) -> String {
+  format!("__FARM_VARIANT_{}__", asset_target_key(module_id))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BinaryAssetsConfig {
+  /// Extensions of binary files, `.node` files are loaded as native addons, others are resolved to their absolute path at runtime
+  pub include: Vec<String>,
+  /// Whether to add the content hash to the emitted filename. Some native addons are loaded by their filename, set it to false to keep the original filename.
+  /// The build fails if binary files of different directories have the same filename when it is false
+  pub hash: bool,
+}
+
+impl Default for BinaryAssetsConfig {
+  fn default() -> Self {
+    Self {
+      include: vec!["node".to_string(), "onnx".to_string()],
+      hash: true,
+    }
+  }
+}
+
+/// `import icon from './home.svg?sprite'` adds the icon as a `<symbol>` of the sprite and returns `{ id, url }`,
+/// `url` is the sprite url with the symbol id as the fragment, e.g. `/sprite.svg#icon-home`, which is used by `<use href={icon.url} />`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SvgSpriteConfig {
+  /// name of the sprite resource, it's not content hashed as the modules of the icons reference it before it's generated
+  pub filename: String,
+  /// the symbol id of an icon is the prefix followed by its filename, e.g. `icon-home`
+  pub symbol_id_prefix: String,
+}
+
+impl Default for SvgSpriteConfig {
+  fn default() -> Self {
+    Self {
+      filename: "sprite.svg".to_string(),
+      symbol_id_prefix: "icon-".to_string(),
+    }
+  }
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// Build metadata exposed to the modules as `import.meta.env.<KEY>` and `process.env.<KEY>` defines,
+/// every kind of metadata can be turned off. The defines configured by `define` are not overridden
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BuildMetaConfig {
+  /// `PACKAGE_NAME` and `PACKAGE_VERSION` from the package.json of the root
+  pub package: bool,
+  /// `GIT_COMMIT`, the sha of `HEAD` of the git repository of the root, empty if it's not a git repository
+  pub git_commit: bool,
+  /// `BUILD_TIME`, the milliseconds since the unix epoch when the compilation is created
+  pub build_time: bool,
+  /// `MODE`, `development` or `production`
+  pub mode: bool,
+}
+
+impl Default for BuildMetaConfig {
+  fn default() -> Self {
+    Self {
+      package: true,
+      git_commit: true,
+      build_time: true,
+      mode: true,
+    }
+  }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+/// Emit a json report of the generated resources, see [crate::stats::BundleStats]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BundleStatsConfig {
+  /// file name of the emitted report
+  pub filename: String,
+  /// gzip every resource to report its gzip size, which is slow for large bundles
+  pub gzip_size: bool,
+  /// include the exported names of each es module, collected before tree shaking
+  pub exports: bool,
+}
+
+impl Default for BundleStatsConfig {
+  fn default() -> Self {
+    Self {
+      filename: "bundle-stats.json".to_string(),
+      gzip_size: true,
+      exports: false,
+    }
+  }
+}
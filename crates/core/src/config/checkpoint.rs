@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+/// Persist the module graph after the build stage, so an interrupted build resumes at the generate stage
+/// instead of rebuilding the whole module graph. The checkpoint is discarded when any of the built files changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CheckpointConfig {
+  /// directory of the checkpoints, relative to the root
+  pub dir: String,
+}
+
+impl Default for CheckpointConfig {
+  fn default() -> Self {
+    Self {
+      dir: "node_modules/.farm/checkpoint".to_string(),
+    }
+  }
+}
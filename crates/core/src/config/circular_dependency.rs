@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use super::config_regex::ConfigRegex;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CircularDependencySeverity {
+  #[default]
+  Warn,
+  /// fail the build when a cycle is found
+  Error,
+  Ignore,
+}
+
+/// Detect import cycles after the module graph is built, cycles usually show up as `undefined` exports at runtime.
+/// A cycle is reported when none of its modules matches `exclude`, and one of its modules matches `include` if it is not empty
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CircularDependencyConfig {
+  pub severity: CircularDependencySeverity,
+  pub include: Vec<ConfigRegex>,
+  pub exclude: Vec<ConfigRegex>,
+  /// dynamic imports do not execute the imported module synchronously, so they can not cause `undefined` exports
+  pub ignore_dynamic_imports: bool,
+  /// ignore the cycles between modules of node_modules, which can not be fixed by the project
+  pub ignore_node_modules: bool,
+}
+
+impl Default for CircularDependencyConfig {
+  fn default() -> Self {
+    Self {
+      severity: CircularDependencySeverity::default(),
+      include: vec![],
+      exclude: vec![],
+      ignore_dynamic_imports: true,
+      ignore_node_modules: true,
+    }
+  }
+}
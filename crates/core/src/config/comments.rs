@@ -1,21 +1,85 @@
 use serde::{Deserialize, Serialize};
+use swc_common::comments::{Comment, CommentKind};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(untagged)]
+/// The policy of preserving comments in the output, it's applied when the modules are rendered so it composes with
+/// minification, the comments are always kept in the parsed modules
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub enum CommentsConfig {
-  /// true: preserve all comments. false: remove all comments
-  Bool(bool),
+  /// preserve all comments
+  #[serde(rename = "all")]
+  All,
   /// Only preserve license comments
   #[serde(rename = "license")]
   #[default]
   License,
+  /// remove all comments
+  #[serde(rename = "none")]
+  None,
+  /// true: preserve all comments. false: remove all comments
+  #[serde(untagged)]
+  Bool(bool),
 }
 
 impl CommentsConfig {
   pub fn enabled(&self) -> bool {
+    !matches!(self, CommentsConfig::None | CommentsConfig::Bool(false))
+  }
+
+  /// whether the comment is preserved by this policy
+  pub fn preserves(&self, comment: &Comment) -> bool {
     match self {
-      CommentsConfig::Bool(b) => *b,
-      CommentsConfig::License => true,
+      CommentsConfig::All | CommentsConfig::Bool(true) => true,
+      CommentsConfig::License => is_license_comment(comment),
+      CommentsConfig::None | CommentsConfig::Bool(false) => false,
+    }
+  }
+}
+
+/// See https://github.com/terser/terser/blob/798135e04baddd94fea403cfaab4ba8b22b1b524/lib/output.js#L175-L181
+pub fn is_license_comment(c: &Comment) -> bool {
+  c.text.contains("@lic")
+    || c.text.contains("@preserve")
+    || c.text.contains("@copyright")
+    || c.text.contains("@cc_on")
+    || (c.kind == CommentKind::Block && c.text.starts_with('!'))
+}
+
+#[cfg(test)]
+mod tests {
+  use swc_common::DUMMY_SP;
+
+  use super::*;
+
+  #[test]
+  fn comments_policy() {
+    let license = Comment {
+      kind: CommentKind::Block,
+      span: DUMMY_SP,
+      text: "! MIT".into(),
+    };
+    let normal = Comment {
+      kind: CommentKind::Line,
+      span: DUMMY_SP,
+      text: " normal".into(),
+    };
+
+    for (json, config) in [
+      ("\"all\"", CommentsConfig::All),
+      ("\"license\"", CommentsConfig::License),
+      ("\"none\"", CommentsConfig::None),
+      ("true", CommentsConfig::Bool(true)),
+      ("false", CommentsConfig::Bool(false)),
+    ] {
+      assert_eq!(
+        serde_json::from_str::<CommentsConfig>(json).unwrap(),
+        config
+      );
     }
+
+    assert!(CommentsConfig::All.preserves(&normal));
+    assert!(CommentsConfig::License.preserves(&license));
+    assert!(!CommentsConfig::License.preserves(&normal));
+    assert!(!CommentsConfig::None.preserves(&license));
+    assert!(!CommentsConfig::None.enabled());
   }
 }
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use super::config_regex::ConfigRegex;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DependencyRuleSeverity {
+  Warn,
+  /// fail the build when the rule is violated
+  #[default]
+  Error,
+}
+
+/// Rules of the imports allowed between modules, e.g. modules under `src/ui/` must not import `src/server/`,
+/// or `lodash` must not be imported in favor of `lodash-es`. They are checked after the module graph is built and updated
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DependencyPolicyConfig {
+  pub rules: Vec<DependencyRule>,
+}
+
+/// An import is matched by the patterns if its source or the id of the imported module matches one of them,
+/// e.g. `^lodash(/|$)` matches `import get from 'lodash/get'` and `src/server/` matches `import db from '../server/db'`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DependencyRule {
+  /// importers the rule applies to, all modules if empty
+  pub from: Vec<ConfigRegex>,
+  /// the imports that are not allowed
+  pub deny: Vec<ConfigRegex>,
+  /// the only imports that are allowed if not empty
+  pub allow: Vec<ConfigRegex>,
+  pub severity: DependencyRuleSeverity,
+  /// explanation appended to the diagnostic, e.g. `use lodash-es instead`
+  pub message: Option<String>,
+}
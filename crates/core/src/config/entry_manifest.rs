@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// Emit a manifest of the resources of the script entries, so a host page that is not built by farm, e.g. the template of a CMS,
+/// can include the tags of the entries. The css of the entries is extracted to css resources like the css of html entries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EntryManifestConfig {
+  /// file name of the emitted manifest, which maps an entry name to its js and css resources
+  pub filename: String,
+  /// emit a html snippet of the tags of each entry, `[entryName]` is replaced by the name of the entry,
+  /// e.g. `[entryName].tags.html`. Not emitted if [None]
+  pub loader: Option<String>,
+}
+
+impl Default for EntryManifestConfig {
+  fn default() -> Self {
+    Self {
+      filename: "entry-manifest.json".to_string(),
+      loader: None,
+    }
+  }
+}
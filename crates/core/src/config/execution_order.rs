@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// How the dependencies of a module are ordered when the execution order of the modules is computed,
+/// see [crate::module::module_graph::ModuleGraph::update_execution_order_for_modules_by]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExecutionOrderTieBreak {
+  /// by the order of the import statements, the dependencies of the same order (e.g. added by plugins) are sorted by module id
+  #[default]
+  ImportOrder,
+  /// by module id, so the order doesn't change when the transforms of development and production reorder the imports
+  Path,
+}
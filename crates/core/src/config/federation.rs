@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// [crate::resource::ResourceType::Custom] of the manifest emitted by a federation build
+pub const FEDERATION_MANIFEST_RESOURCE_TYPE: &str = "federation-manifest";
+
+/// Expose modules of the build to other builds and consume the modules exposed by remote builds at runtime.
+/// Remote modules are imported dynamically by `<remote>/<expose>`, e.g. `import('app2/Button')`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FederationConfig {
+  /// name of the build, also the name of the container entry that dynamically imports the exposed modules
+  pub name: String,
+  /// file name of the emitted manifest
+  pub manifest: String,
+  /// expose name -> path of the exposed module relative to the root, e.g. `{ "./Button": "./src/Button.tsx" }`.
+  /// Every exposed module is placed in its own resource pot named `<name>_<expose>`
+  pub exposes: BTreeMap<String, String>,
+  /// remote name -> url of the manifest of the remote build, e.g. `{ "app2": "http://localhost:3001/federation-manifest.json" }`
+  pub remotes: BTreeMap<String, String>,
+  /// import sources that are shared with the remote builds, e.g. `{ "react": { "singleton": true } }`
+  pub shared: BTreeMap<String, FederationSharedConfig>,
+}
+
+impl Default for FederationConfig {
+  fn default() -> Self {
+    Self {
+      name: String::new(),
+      manifest: "federation-manifest.json".to_string(),
+      exposes: BTreeMap::new(),
+      remotes: BTreeMap::new(),
+      shared: BTreeMap::new(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FederationSharedConfig {
+  /// always use the module of the consumer, whatever the versions are
+  pub singleton: bool,
+  /// semver range the version of the consumer should satisfy, e.g. `^18.0.0`. Defaults to the caret range of the own version
+  pub required_version: Option<String>,
+}
+
+impl FederationConfig {
+  /// `<remote>/<expose>` -> (remote, expose), e.g. `app2/Button` -> (`app2`, `./Button`) and `app2` -> (`app2`, `.`)
+  pub fn parse_remote_source<'a>(&'a self, source: &'a str) -> Option<(&'a str, String)> {
+    self.remotes.keys().find_map(|remote| {
+      let rest = source.strip_prefix(remote.as_str())?;
+
+      if rest.is_empty() {
+        Some((remote.as_str(), ".".to_string()))
+      } else {
+        rest
+          .strip_prefix('/')
+          .map(|expose| (remote.as_str(), format!("./{expose}")))
+      }
+    })
+  }
+
+  /// name of the resource pot of the exposed module, e.g. `app_Button` for `./Button` and `app_index` for `.`
+  pub fn expose_resource_pot_name(&self, expose: &str) -> String {
+    let expose = expose
+      .trim_start_matches('.')
+      .trim_start_matches('/')
+      .replace(['/', '.'], "_");
+
+    if expose.is_empty() {
+      format!("{}_index", self.name)
+    } else {
+      format!("{}_{expose}", self.name)
+    }
+  }
+}
+
+/// Emitted by a federation build, loaded by the consumers to load the exposed modules and negotiate the shared modules
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FederationManifest {
+  pub name: String,
+  /// public path the resources of the build are served from
+  pub public_path: String,
+  /// `runtime.namespace` of the build, the exposed modules are registered in the module system of this namespace
+  pub namespace: String,
+  /// resources of the container entry, they set up the module system of the build and are loaded before the exposed modules
+  pub entry: Vec<FederationResource>,
+  pub exposes: BTreeMap<String, FederationExpose>,
+  /// shared-dependency negotiation table, import source -> the module provided by the build
+  pub shared: BTreeMap<String, FederationShared>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FederationExpose {
+  /// id of the exposed module registered in the runtime
+  pub module_id: String,
+  /// js and css resources that should be loaded before requiring the module
+  pub resources: Vec<FederationResource>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FederationResource {
+  pub path: String,
+  /// 0: script, 1: link, same as the resources of the runtime
+  #[serde(rename = "type")]
+  pub resource_type: u8,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FederationShared {
+  /// id of the module the import source is resolved to
+  pub module_id: String,
+  /// version of the package of the module
+  pub version: String,
+  pub singleton: bool,
+  pub required_version: String,
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeMap;
+
+  use super::FederationConfig;
+
+  #[test]
+  fn parse_remote_source() {
+    let config = FederationConfig {
+      name: "app".to_string(),
+      remotes: BTreeMap::from([("app2".to_string(), "/app2/manifest.json".to_string())]),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      config.parse_remote_source("app2/Button"),
+      Some(("app2", "./Button".to_string()))
+    );
+    assert_eq!(
+      config.parse_remote_source("app2"),
+      Some(("app2", ".".to_string()))
+    );
+    assert_eq!(config.parse_remote_source("app23/Button"), None);
+    assert_eq!(config.parse_remote_source("react"), None);
+
+    assert_eq!(config.expose_resource_pot_name("./Button"), "app_Button");
+    assert_eq!(
+      config.expose_resource_pot_name("./components/Card.tsx"),
+      "app_components_Card_tsx"
+    );
+    assert_eq!(config.expose_resource_pot_name("."), "app_index");
+  }
+}
@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the defines of the flags, the flag `enterprise` is used as `FARM_FLAGS.enterprise`
+pub const FLAGS_DEFINE_PREFIX: &str = "FARM_FLAGS.";
+
+/// Named boolean flags of the build, e.g. `{ "values": { "enterprise": false } }`.
+/// Every flag is applied as the define `FARM_FLAGS.<name>`, the branches that are dead for the flag values are removed
+/// and the modules only imported by the removed branches are reported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FlagsConfig {
+  /// flag name -> value
+  pub values: BTreeMap<String, bool>,
+  /// file name of the emitted report of the removed modules per flag
+  pub report: String,
+}
+
+impl Default for FlagsConfig {
+  fn default() -> Self {
+    Self {
+      values: BTreeMap::new(),
+      report: "flags-report.json".to_string(),
+    }
+  }
+}
+
+impl FlagsConfig {
+  /// define key -> value of the flags
+  pub fn defines(&self) -> impl Iterator<Item = (String, bool)> + '_ {
+    self
+      .values
+      .iter()
+      .map(|(name, value)| (format!("{FLAGS_DEFINE_PREFIX}{name}"), *value))
+  }
+
+  /// name of the flag of the define key, [None] if the define is not a flag
+  pub fn flag_of_define<'a>(&self, define: &'a str) -> Option<&'a str> {
+    define
+      .strip_prefix(FLAGS_DEFINE_PREFIX)
+      .filter(|name| self.values.contains_key(*name))
+  }
+}
+
+/// The report emitted to [FlagsConfig::report], flag name -> [FlagReport]
+pub type FlagsReport = BTreeMap<String, FlagReport>;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlagReport {
+  pub value: bool,
+  /// dynamic imports and requires removed with the dead branches of the flag
+  pub pruned_imports: Vec<FlagPrunedImport>,
+  /// modules that are not in the module graph as they were only imported by the removed branches
+  pub removed_modules: Vec<String>,
+  /// the removed modules that were dynamically imported, so their chunks are not generated
+  pub removed_chunks: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlagPrunedImport {
+  /// id of the module containing the removed branch
+  pub importer: String,
+  pub source: String,
+  /// id of the imported module, [None] if it can not be resolved
+  pub module_id: Option<String>,
+}
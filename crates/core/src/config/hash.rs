@@ -0,0 +1,109 @@
+use std::io::Read;
+
+use farmfe_utils::hash::{blake3, blake3_reader, sha256, sha256_reader, xxh3, xxh3_reader};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+  Xxh3,
+  Blake3,
+  #[default]
+  Sha256,
+}
+
+pub const MIN_HASH_LENGTH: usize = 6;
+/// xxh3 generates 128 bits hashes
+pub const MAX_HASH_LENGTH: usize = 32;
+
+/// The hash function used for resource names, cache keys and resource pot ids.
+/// Changing [HashConfig::seed] changes every hash, which can be used to bust the cache of all resources.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HashConfig {
+  pub algorithm: HashAlgorithm,
+  /// salt prepended to the hashed content, empty by default
+  pub seed: String,
+  /// length of the content hash of resource names, use [HashConfig::length] to get the clamped value
+  pub length: usize,
+}
+
+impl Default for HashConfig {
+  fn default() -> Self {
+    Self {
+      algorithm: Default::default(),
+      seed: String::new(),
+      length: 8,
+    }
+  }
+}
+
+impl HashConfig {
+  /// The length of the content hash of resource names, between [MIN_HASH_LENGTH] and [MAX_HASH_LENGTH]
+  pub fn length(&self) -> usize {
+    self.length.clamp(MIN_HASH_LENGTH, MAX_HASH_LENGTH)
+  }
+
+  pub fn hash(&self, bytes: &[u8], len: usize) -> String {
+    if self.seed.is_empty() {
+      return self.hash_bytes(bytes, len);
+    }
+
+    let mut salted = Vec::with_capacity(self.seed.len() + bytes.len());
+    salted.extend_from_slice(self.seed.as_bytes());
+    salted.extend_from_slice(bytes);
+
+    self.hash_bytes(&salted, len)
+  }
+
+  /// Same as [HashConfig::hash] but the content is read in chunks
+  pub fn hash_reader<R: Read>(&self, reader: R, len: usize) -> std::io::Result<String> {
+    let reader = self.seed.as_bytes().chain(reader);
+
+    match self.algorithm {
+      HashAlgorithm::Xxh3 => xxh3_reader(reader, len),
+      HashAlgorithm::Blake3 => blake3_reader(reader, len),
+      HashAlgorithm::Sha256 => sha256_reader(reader, len),
+    }
+  }
+
+  fn hash_bytes(&self, bytes: &[u8], len: usize) -> String {
+    match self.algorithm {
+      HashAlgorithm::Xxh3 => xxh3(bytes, len),
+      HashAlgorithm::Blake3 => blake3(bytes, len),
+      HashAlgorithm::Sha256 => sha256(bytes, len),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{HashAlgorithm, HashConfig};
+
+  #[test]
+  fn hash_with_algorithm_and_seed() {
+    let default = HashConfig::default();
+    assert_eq!(
+      default.hash(b"content", 8),
+      farmfe_utils::hash::sha256(b"content", 8)
+    );
+
+    for algorithm in [
+      HashAlgorithm::Xxh3,
+      HashAlgorithm::Blake3,
+      HashAlgorithm::Sha256,
+    ] {
+      let config = HashConfig {
+        algorithm,
+        seed: "v2".to_string(),
+        ..Default::default()
+      };
+
+      assert_ne!(config.hash(b"content", 8), default.hash(b"content", 8));
+      assert_eq!(
+        config.hash(b"content", 8),
+        config.hash_reader(&b"content"[..], 8).unwrap()
+      );
+    }
+  }
+}
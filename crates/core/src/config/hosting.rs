@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+/// Emit the config of hosting providers for the spa fallback, redirects, rewrites, cache headers and precompressed files.
+/// Paths are matched from the site root, a trailing `*` matches the rest of the path, which is referenced by `:splat` in the targets,
+/// e.g. `{ from: "/docs/*", to: "/guide/:splat" }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HostingConfig {
+  /// providers to emit the config for
+  pub providers: Vec<HostingProvider>,
+  /// html served for the paths that match no file, e.g. `/index.html`, no fallback if [None]
+  pub spa_fallback: Option<String>,
+  pub redirects: Vec<HostingRedirect>,
+  /// serve `to` for the paths matching `from` without redirecting
+  pub rewrites: Vec<HostingRewrite>,
+  /// paths of the resources with the content hash in the names, served with [HostingConfig::immutable_cache_control]
+  pub immutable: Vec<String>,
+  pub immutable_cache_control: String,
+  /// serve the `.gz` files next to the requested files, and the `.br` files once the ngx_brotli module is enabled in the snippet.
+  /// Only needed by nginx, the other providers compress the responses themselves
+  pub precompressed: bool,
+}
+
+impl Default for HostingConfig {
+  fn default() -> Self {
+    Self {
+      providers: vec![
+        HostingProvider::Netlify,
+        HostingProvider::Vercel,
+        HostingProvider::Nginx,
+      ],
+      spa_fallback: Some("/index.html".to_string()),
+      redirects: vec![],
+      rewrites: vec![],
+      immutable: vec![],
+      immutable_cache_control: "public, max-age=31536000, immutable".to_string(),
+      precompressed: false,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HostingProvider {
+  /// `_redirects` and `_headers`
+  Netlify,
+  /// `vercel.json`
+  Vercel,
+  /// `nginx.conf`, a snippet included in the `server` block
+  Nginx,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HostingRedirect {
+  pub from: String,
+  pub to: String,
+  /// 301, 302, 307 or 308
+  pub status: u16,
+}
+
+impl Default for HostingRedirect {
+  fn default() -> Self {
+    Self {
+      from: String::new(),
+      to: String::new(),
+      status: 301,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HostingRewrite {
+  pub from: String,
+  pub to: String,
+}
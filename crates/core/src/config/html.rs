@@ -1,7 +1,89 @@
 use serde::{Deserialize, Serialize};
 
+use super::config_regex::ConfigRegex;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase", default)]
 pub struct HtmlConfig {
   pub base: Option<String>,
+  /// Evaluate EJS-like template tags like `<%= env.TITLE %>` (html escaped), `<%- env.SNIPPET %>` (raw) and `<% if (mode === 'production') { %>` in html entries
+  pub template: bool,
+  /// Generate favicon and app icons from a single source image and link them in html entries
+  pub icons: Option<HtmlIconsConfig>,
+  /// Reference the css of the dynamically imported modules in html entries, for progressive-enhancement pages that should be styled without js
+  pub noscript_css: Option<HtmlNoscriptCssConfig>,
+  /// Emit a web app manifest and link it in html entries, with the theme color meta tag
+  pub manifest: Option<WebAppManifestConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HtmlIconsConfig {
+  /// path of the source image relative to root, a square image larger than 512x512 is recommended
+  pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebAppManifestConfig {
+  /// name of the manifest resource
+  pub filename: String,
+  pub name: String,
+  pub short_name: Option<String>,
+  pub description: Option<String>,
+  pub start_url: String,
+  pub scope: Option<String>,
+  /// `fullscreen`, `standalone`, `minimal-ui` or `browser`
+  pub display: String,
+  /// also injected as `<meta name="theme-color">`
+  pub theme_color: Option<String>,
+  pub background_color: Option<String>,
+  /// icons emitted as assets, the manifest icons generated by [HtmlConfig::icons] are added too
+  pub icons: Vec<WebAppManifestIcon>,
+}
+
+impl Default for WebAppManifestConfig {
+  fn default() -> Self {
+    Self {
+      filename: "manifest.webmanifest".to_string(),
+      name: String::new(),
+      short_name: None,
+      description: None,
+      start_url: "/".to_string(),
+      scope: None,
+      display: "standalone".to_string(),
+      theme_color: None,
+      background_color: None,
+      icons: vec![],
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebAppManifestIcon {
+  /// path of the image relative to root, it's transformed by the `transform_asset` hook and emitted like other assets
+  pub src: String,
+  /// e.g. `192x192`, read from the image if [None], `any` for svg
+  pub sizes: Option<String>,
+  /// e.g. `any maskable`
+  pub purpose: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HtmlNoscriptCssConfig {
+  /// regex of the css module ids, the css resources containing the matched modules are referenced
+  pub include: Vec<ConfigRegex>,
+  pub inject: HtmlNoscriptCssInject,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum HtmlNoscriptCssInject {
+  /// `<link>` inside a `<noscript>` block, only applied when js is disabled. With js the css is still loaded with the dynamic import
+  #[default]
+  Noscript,
+  /// `<link>` that is always applied, the css is marked as loaded so the dynamic import doesn't load it again
+  Link,
 }
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+/// Collect the licenses of the bundled third party packages, see [crate::stats::PackageLicenseStats].
+/// A json report and a third party notices file with the license texts are emitted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LicensesConfig {
+  /// file name of the emitted json report
+  pub filename: String,
+  /// file name of the emitted notices containing the license files of the packages, not emitted if empty
+  pub notices: String,
+  /// spdx license ids that fail the build, e.g. `GPL-3.0`
+  pub deny: Vec<String>,
+  /// the only spdx license ids that are allowed if not empty, packages without a license fail the build too
+  pub allow: Vec<String>,
+}
+
+impl Default for LicensesConfig {
+  fn default() -> Self {
+    Self {
+      filename: "licenses.json".to_string(),
+      notices: "THIRD-PARTY-NOTICES.txt".to_string(),
+      deny: vec![],
+      allow: vec![],
+    }
+  }
+}
+
+impl LicensesConfig {
+  /// Whether a package of the spdx license expression can be bundled. For `OR` one of the alternatives must be allowed,
+  /// for `AND` all of the licenses must be allowed, e.g. `(MIT OR GPL-3.0)` is allowed when `GPL-3.0` is denied
+  pub fn is_allowed(&self, expression: &str) -> bool {
+    let is_license_allowed = |license: &str| {
+      let license = license.trim();
+
+      !self.deny.iter().any(|l| l.eq_ignore_ascii_case(license))
+        && (self.allow.is_empty() || self.allow.iter().any(|l| l.eq_ignore_ascii_case(license)))
+    };
+
+    if expression.trim().is_empty() {
+      return self.allow.is_empty();
+    }
+
+    expression
+      .replace(['(', ')'], " ")
+      .split(" OR ")
+      .any(|alternative| alternative.split(" AND ").all(is_license_allowed))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::LicensesConfig;
+
+  #[test]
+  fn licenses_is_allowed() {
+    let config = LicensesConfig {
+      deny: vec!["GPL-3.0".to_string()],
+      ..Default::default()
+    };
+    assert!(config.is_allowed("MIT"));
+    assert!(config.is_allowed(""));
+    assert!(!config.is_allowed("GPL-3.0"));
+    assert!(config.is_allowed("(MIT OR GPL-3.0)"));
+    assert!(!config.is_allowed("MIT AND GPL-3.0"));
+
+    let config = LicensesConfig {
+      allow: vec!["MIT".to_string(), "ISC".to_string()],
+      ..Default::default()
+    };
+    assert!(config.is_allowed("ISC"));
+    assert!(!config.is_allowed(""));
+    assert!(!config.is_allowed("Apache-2.0"));
+    assert!(config.is_allowed("(Apache-2.0 OR MIT)"));
+  }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Severity of a log record. A record is emitted when its level is not more verbose than the configured level,
+/// [LogLevel::Silent] is only used as a configured level to disable the logs
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+  Silent,
+  Error,
+  Warn,
+  #[default]
+  Info,
+  Debug,
+  Trace,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+  /// human readable lines, e.g. `[farm] WARN farmfe_compiler: message`
+  #[default]
+  Text,
+  /// one json object per line, for CI log ingestion
+  Json,
+}
+
+/// Logs of the compiler and the plugins, see [crate::context::logger::Logger]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LoggingConfig {
+  pub level: LogLevel,
+  pub format: LogFormat,
+  /// level of the records whose target starts with the key, e.g. `{ "farmfe_plugin_css": "debug" }`.
+  /// Targets of plugin logs are the plugin names. The longest matching key wins
+  pub filters: HashMap<String, LogLevel>,
+}
+
+impl LoggingConfig {
+  /// The configured level of the target
+  pub fn level_of(&self, target: &str) -> LogLevel {
+    self
+      .filters
+      .iter()
+      .filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+      .max_by_key(|(prefix, _)| prefix.len())
+      .map(|(_, level)| *level)
+      .unwrap_or(self.level)
+  }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Build time macros. Functions imported from a macro module are executed at build time by the js host,
+/// and the calls are replaced by the returned values.
+/// A module is a macro module if its source is listed in [MacrosConfig::modules] or it's imported with `with { type: "macro" }`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MacrosConfig {
+  /// import sources of macro modules, e.g. `./macros/i18n.js`
+  pub modules: Vec<String>,
+}
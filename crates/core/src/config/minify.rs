@@ -21,6 +21,10 @@ pub struct MinifyOptions {
   pub exclude: Vec<ConfigRegex>,
   pub mode: MinifyMode,
   pub module_decls: bool,
+  /// minify js resource pots and modules
+  pub js: bool,
+  /// minify css resource pots and modules
+  pub css: bool,
 }
 
 impl Default for MinifyOptions {
@@ -32,6 +36,8 @@ impl Default for MinifyOptions {
       exclude: vec![ConfigRegex::new(".+\\.min\\.(js|css|html)$")],
       mode: MinifyMode::Module,
       module_decls: false,
+      js: true,
+      css: true,
     }
   }
 }
@@ -1,10 +1,15 @@
-use std::collections::HashMap;
+use std::{
+  collections::HashMap,
+  path::{Component, Path, PathBuf},
+};
 
 use serde::{Deserialize, Serialize};
 use swc_css_prefixer::options::Targets;
 
 use swc_ecma_parser::{EsSyntax as EsConfig, TsSyntax as TsConfig};
 
+use crate::module::ModuleId;
+
 use self::{
   bool_or_obj::BoolOrObj, comments::CommentsConfig, config_regex::ConfigRegex, html::HtmlConfig,
   partial_bundling::PartialBundlingConfig, preset_env::PresetEnvConfig, script::ScriptConfig,
@@ -18,21 +23,45 @@ pub const FARM_REQUIRE: &str = "farmRequire";
 pub const FARM_MODULE: &str = "module";
 pub const FARM_MODULE_EXPORT: &str = "exports";
 
+pub mod adaptive_rebuild;
+pub mod alias;
 pub mod asset;
 pub mod bool_or_obj;
+pub mod build_meta;
+pub mod bundle_stats;
+pub mod checkpoint;
+pub mod circular_dependency;
 pub mod comments;
 pub mod config_regex;
 pub mod css;
 pub mod custom;
+pub mod dependency_policy;
+pub mod entry_manifest;
+pub mod execution_order;
 pub mod external;
+pub mod federation;
+pub mod flags;
+pub mod hash;
+pub mod hosting;
 pub mod html;
+pub mod licenses;
+pub mod logging;
+pub mod macros;
 pub mod minify;
+pub mod module_boundaries;
+pub mod module_types;
+pub mod node_prefix;
 mod output;
 pub mod partial_bundling;
+pub mod patches;
+pub mod path_mapping;
 pub mod persistent_cache;
 pub mod preset_env;
+pub mod routes;
 pub mod script;
 pub mod tree_shaking;
+pub mod unused_exports;
+pub mod vendor_reference;
 
 use asset::AssetsConfig;
 
@@ -44,16 +73,27 @@ pub struct Config {
   pub input: HashMap<String, String>,
   pub output: Box<OutputConfig>,
   pub root: String,
+  /// directories of first party sources outside the root, e.g. a design system shared in the monorepo, relative to the root.
+  /// Their modules are mutable and watched like the modules under the root even if they are linked into node_modules
+  pub source_roots: Vec<String>,
   pub mode: Mode,
   pub resolve: Box<ResolveConfig>,
   pub external: Vec<ConfigRegex>,
   pub define: HashMap<String, serde_json::Value>,
+  /// entry name -> defines overriding [Config::define] for the modules of the entry, e.g. `ADMIN_BUILD: true` only for the admin entry.
+  /// The modules shared by the entries are compiled once for each entry with overrides
+  pub entry_define: HashMap<String, HashMap<String, serde_json::Value>>,
   pub runtime: Box<RuntimeConfig>,
   pub script: Box<ScriptConfig>,
   pub assets: Box<AssetsConfig>,
   pub css: Box<CssConfig>,
   pub html: Box<HtmlConfig>,
   pub sourcemap: Box<SourcemapConfig>,
+  /// base directory of the `sources` of the generated source maps, see [SourcemapBase]
+  pub sourcemap_base: Option<SourcemapBase>,
+  /// directories mapped between the compiler and the host when the compiler runs in a container or on a remote machine,
+  /// the `sources` of the source maps are the host paths
+  pub path_mappings: Vec<path_mapping::PathMapping>,
   pub partial_bundling: Box<PartialBundlingConfig>,
   pub lazy_compilation: bool,
   pub core_lib_path: Option<String>,
@@ -69,6 +109,51 @@ pub struct Config {
   pub concatenate_modules: bool,
   /// comments config for script, css and html
   pub comments: Box<CommentsConfig>,
+  /// file based routing, disabled by default
+  pub routes: Option<Box<routes::RoutesConfig>>,
+  /// build time macros, disabled by default
+  pub macros: Option<Box<macros::MacrosConfig>>,
+  /// hash function used for resource names, cache keys and resource pot ids
+  pub hash: Box<hash::HashConfig>,
+  /// modules imported before the code of every entry, e.g. `core-js/stable` or `./src/polyfills.ts`,
+  /// so they are executed before all entries
+  pub polyfill_entries: Vec<String>,
+  /// how the dependencies of a module are ordered when the execution order of the modules is computed, e.g. the order of the css
+  pub execution_order_tie_break: execution_order::ExecutionOrderTieBreak,
+  /// source patches of dependency files applied when they are loaded
+  pub patches: Vec<patches::PatchConfig>,
+  /// extension without the dot -> how the files of the extension are built, e.g. `glsl`
+  pub module_types: HashMap<String, module_types::CustomModuleTypeConfig>,
+  /// emit a json report of the generated resources for bundle analysis, disabled by default
+  pub bundle_stats: Option<Box<bundle_stats::BundleStatsConfig>>,
+  /// report server only modules imported by browser entries and client only modules imported by node entries, disabled by default
+  pub module_boundaries: Option<Box<module_boundaries::ModuleBoundariesConfig>>,
+  /// emit a json report of the exports that are never imported by other modules, disabled by default
+  pub unused_exports: Option<Box<unused_exports::UnusedExportsConfig>>,
+  /// emit a json report and the notices of the licenses of the bundled packages, disabled by default
+  pub licenses: Option<Box<licenses::LicensesConfig>>,
+  /// emit or link against prebuilt vendor resources, disabled by default
+  pub vendor_reference: Option<Box<vendor_reference::VendorReferenceConfig>>,
+  /// report import cycles of the module graph, disabled by default
+  pub circular_dependency: Option<Box<circular_dependency::CircularDependencyConfig>>,
+  /// rules of the imports allowed between modules, disabled by default
+  pub dependency_policy: Option<Box<dependency_policy::DependencyPolicyConfig>>,
+  /// expose modules to other builds and load the modules of remote builds at runtime, disabled by default
+  pub federation: Option<Box<federation::FederationConfig>>,
+  /// named boolean flags applied as defines, the removed modules are reported per flag, disabled by default
+  pub flags: Option<Box<flags::FlagsConfig>>,
+  /// package version, git commit, build time and mode exposed as `import.meta.env` defines, disabled by default
+  pub build_meta: Option<Box<build_meta::BuildMetaConfig>>,
+  /// resume an interrupted build at the generate stage from the module graph persisted after the build stage, disabled by default
+  pub checkpoint: Option<Box<checkpoint::CheckpointConfig>>,
+  /// escalate updates that change a large part of the module graph to a full compilation, disabled by default
+  pub adaptive_rebuild: Option<Box<adaptive_rebuild::AdaptiveRebuildConfig>>,
+  /// emit the redirects, rewrites and headers config of hosting providers in production, disabled by default
+  pub hosting: Option<Box<hosting::HostingConfig>>,
+  /// emit a manifest of the js and css resources of the script entries for host pages without a html entry, disabled by default
+  pub entry_manifest: Option<Box<entry_manifest::EntryManifestConfig>>,
+  /// log level and format of the compiler and plugin logs
+  pub logging: Box<logging::LoggingConfig>,
   /// preserved for future compatibility usage when there are more config options
   pub custom: Box<HashMap<String, String>>,
 }
@@ -83,10 +168,12 @@ impl Default for Config {
     Self {
       input: HashMap::from([("index".to_string(), "./index.html".to_string())]),
       root: root.clone(),
+      source_roots: vec![],
       output: Default::default(),
       mode: Mode::Development,
       resolve: Default::default(),
       define: HashMap::new(),
+      entry_define: HashMap::new(),
       external: Default::default(),
       runtime: Default::default(),
       script: Default::default(),
@@ -94,6 +181,8 @@ impl Default for Config {
       html: Box::default(),
       assets: Default::default(),
       sourcemap: Default::default(),
+      sourcemap_base: None,
+      path_mappings: vec![],
       partial_bundling: Default::default(),
       lazy_compilation: true,
       core_lib_path: None,
@@ -108,11 +197,73 @@ impl Default for Config {
       ),
       concatenate_modules: false,
       comments: Box::default(),
+      routes: None,
+      macros: None,
+      hash: Box::default(),
+      polyfill_entries: vec![],
+      execution_order_tie_break: Default::default(),
+      patches: vec![],
+      module_types: HashMap::new(),
+      bundle_stats: None,
+      module_boundaries: None,
+      unused_exports: None,
+      licenses: None,
+      vendor_reference: None,
+      circular_dependency: None,
+      dependency_policy: None,
+      federation: None,
+      flags: None,
+      build_meta: None,
+      checkpoint: None,
+      adaptive_rebuild: None,
+      hosting: None,
+      entry_manifest: None,
+      logging: Box::default(),
       custom: Box::<HashMap<String, String>>::default(),
     }
   }
 }
 
+impl Config {
+  /// Whether the module is under one of [Config::source_roots]
+  pub fn is_in_source_roots(&self, module_id: &ModuleId) -> bool {
+    if self.source_roots.is_empty() {
+      return false;
+    }
+
+    let resolved_path = PathBuf::from(module_id.resolved_path(&self.root));
+    // modules linked into node_modules are matched by their real paths
+    let resolved_path = resolved_path.canonicalize().unwrap_or(resolved_path);
+
+    self.source_roots.iter().any(|source_root| {
+      let source_root = Path::new(&self.root).join(source_root);
+      let source_root = source_root.canonicalize().unwrap_or(source_root);
+      resolved_path.starts_with(source_root)
+    })
+  }
+
+  /// Whether the module is matched by `partialBundling.immutableModules` and is not under the source roots
+  pub fn is_immutable_module(&self, module_id: &ModuleId) -> bool {
+    let module_id_str = module_id.to_string();
+
+    self
+      .partial_bundling
+      .immutable_modules
+      .iter()
+      .any(|im| im.is_match(&module_id_str))
+      && !self.is_in_source_roots(module_id)
+  }
+
+  /// How the files of the extension are built if it's declared in [Config::module_types], the extension is matched case insensitively
+  pub fn custom_module_type(&self, ext: &str) -> Option<module_types::CustomModuleTypeTreatAs> {
+    self
+      .module_types
+      .iter()
+      .find(|(e, _)| e.trim_start_matches('.').eq_ignore_ascii_case(ext))
+      .map(|(_, config)| config.treat_as)
+  }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash, Default)]
 pub enum TargetEnv {
   #[serde(rename = "browser")]
@@ -181,7 +332,11 @@ impl ToString for Mode {
 pub struct CssModulesConfig {
   /// The paths regex to match css modules
   pub paths: Vec<String>,
+  /// Pattern of the generated class names. Supported placeholders:
+  /// `[name]` or `[local]` for the original class name, `[file]` for the file name without extension,
+  /// `[package]` for the package name of the file, `[hash]` or `[hash:N]` for a hash of N (default 8) characters
   pub indent_name: String,
+  pub hash_strategy: CssModulesHashStrategy,
 }
 
 impl Default for CssModulesConfig {
@@ -189,10 +344,23 @@ impl Default for CssModulesConfig {
     Self {
       paths: vec![String::from("\\.module\\.(css|less|sass|scss)$")],
       indent_name: String::from("[name]-[hash]"),
+      hash_strategy: Default::default(),
     }
   }
 }
 
+/// What `[hash]` of the css modules class names is computed from.
+/// Both are independent of the machine, so the class names are stable across builds
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CssModulesHashStrategy {
+  /// the path of the file relative to root
+  #[default]
+  Path,
+  /// the content of the file, class names do not change when the file is moved
+  Content,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase", default)]
 pub struct CssPrefixerConfig {
@@ -227,6 +395,8 @@ pub struct ScriptParserConfig {
 #[serde(rename_all = "camelCase", default)]
 pub struct ResolveConfig {
   pub alias: HashMap<String, String>,
+  /// aliases evaluated in the declaration order before [ResolveConfig::alias], see [ResolveConfig::ordered_alias]
+  pub alias_entries: Vec<alias::AliasEntry>,
   pub main_fields: Vec<String>,
   pub main_files: Vec<String>,
   pub extensions: Vec<String>,
@@ -234,12 +404,15 @@ pub struct ResolveConfig {
   pub symlinks: bool,
   pub strict_exports: bool,
   pub auto_external_failed_resolve: bool,
+  /// how `node:` prefixed imports are handled when the target env is browser
+  pub node_prefix: node_prefix::NodePrefixConfig,
 }
 
 impl Default for ResolveConfig {
   fn default() -> Self {
     Self {
       alias: HashMap::new(),
+      alias_entries: vec![],
       main_fields: vec![
         String::from("browser"),
         String::from("module"),
@@ -269,10 +442,31 @@ impl Default for ResolveConfig {
       symlinks: true,
       strict_exports: false,
       auto_external_failed_resolve: false,
+      node_prefix: Default::default(),
     }
   }
 }
 
+impl ResolveConfig {
+  /// (find, replacement) of the aliases in the evaluation order: [ResolveConfig::alias_entries] in the declaration order,
+  /// then [ResolveConfig::alias] from the longest to the shortest. The first alias whose replaced source is resolved wins
+  pub fn ordered_alias(&self) -> Vec<(&str, &str)> {
+    let mut alias = self
+      .alias
+      .iter()
+      .map(|(find, replacement)| (find.as_str(), replacement.as_str()))
+      .collect::<Vec<_>>();
+    alias.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(b.0)));
+
+    self
+      .alias_entries
+      .iter()
+      .map(|entry| (entry.find.as_str(), entry.replacement.as_str()))
+      .chain(alias)
+      .collect()
+  }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
 pub struct RuntimeConfig {
@@ -282,8 +476,19 @@ pub struct RuntimeConfig {
   pub plugins: Vec<String>,
   /// swc helpers path
   pub swc_helpers_path: String,
+  /// bundle the swc helpers (`@swc/helpers`) injected by the syntax lowering and module system transforms into one resource with this name,
+  /// so they are shared by all resource pots instead of bundled with the modules using them. The unused helpers are still tree shaken
+  pub helpers_resource: Option<String>,
   /// namespace for the runtime
   pub namespace: String,
+  /// replace individual runtime modules, the key is the import source in the runtime modules or the absolute path of the runtime module,
+  /// the value is the absolute path of the replacement
+  pub alias: HashMap<String, String>,
+  /// retry of the resources that are failed to load, e.g. dynamic imported chunks served by an unstable CDN
+  pub retry: RuntimeRetryConfig,
+  /// create the script urls, the evaluated hmr code and the html of the hmr overlays through a Trusted Types policy,
+  /// so apps enforcing `require-trusted-types-for 'script'` run without violations. Disabled by default
+  pub trusted_types: Option<RuntimeTrustedTypesConfig>,
 }
 
 impl Default for RuntimeConfig {
@@ -292,7 +497,47 @@ impl Default for RuntimeConfig {
       path: String::from(""),
       plugins: vec![],
       swc_helpers_path: String::from(""),
+      helpers_resource: None,
       namespace: String::from("__farm_default_namespace__"),
+      alias: HashMap::new(),
+      retry: Default::default(),
+      trusted_types: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RuntimeRetryConfig {
+  /// how many times a failed resource is retried before trying the next public path, 0 means no retry
+  pub count: u32,
+  /// delay in milliseconds before the first retry, doubled after each retry
+  pub delay: u32,
+  /// append a timestamp query to the url of the retried resource, so a cached failed response is not reused
+  pub cache_busting: bool,
+}
+
+impl Default for RuntimeRetryConfig {
+  fn default() -> Self {
+    Self {
+      count: 0,
+      delay: 500,
+      cache_busting: true,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RuntimeTrustedTypesConfig {
+  /// name of the policy, it should be allowed by the `trusted-types` directive of the content security policy
+  pub policy_name: String,
+}
+
+impl Default for RuntimeTrustedTypesConfig {
+  fn default() -> Self {
+    Self {
+      policy_name: "farm".to_string(),
     }
   }
 }
@@ -346,6 +591,54 @@ impl SourcemapConfig {
   }
 }
 
+/// By default the sources of source maps are `/{path relative to root}`, which are served by the dev server.
+/// When a base is configured, the sources are relative to the base, so error trackers and IDEs can resolve them in monorepos.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SourcemapBase {
+  /// the closest directory containing `package.json` from root
+  #[serde(rename = "package")]
+  Package,
+  /// the repository root, the closest directory containing `.git` or `pnpm-workspace.yaml` from root
+  #[serde(rename = "workspace")]
+  Workspace,
+  /// a directory relative to root
+  #[serde(untagged)]
+  Custom(String),
+}
+
+impl SourcemapBase {
+  /// Resolve the absolute base directory, fallback to root if the directory is not found
+  pub fn resolve_dir(&self, root: &str) -> String {
+    let root_path = Path::new(root);
+    let find_closest = |markers: &[&str]| {
+      root_path
+        .ancestors()
+        .find(|dir| markers.iter().any(|m| dir.join(m).exists()))
+        .unwrap_or(root_path)
+        .to_path_buf()
+    };
+
+    let dir = match self {
+      Self::Package => find_closest(&["package.json"]),
+      Self::Workspace => find_closest(&[".git", "pnpm-workspace.yaml"]),
+      // normalize `..` so the base can be used to compute relative paths
+      Self::Custom(dir) => root_path
+        .join(dir)
+        .components()
+        .fold(PathBuf::new(), |mut path, c| {
+          if c == Component::ParentDir {
+            path.pop();
+          } else {
+            path.push(c);
+          }
+          path
+        }),
+    };
+
+    dir.to_string_lossy().to_string()
+  }
+}
+
 mod tests {
 
   #[test]
@@ -370,6 +663,15 @@ mod tests {
     let config: SourcemapConfig = serde_json::from_str("\"all\"").expect("failed to parse");
 
     assert!(matches!(config, SourcemapConfig::All));
+
+    let config: super::SourcemapBase =
+      serde_json::from_str("\"workspace\"").expect("failed to parse");
+
+    assert_eq!(config, super::SourcemapBase::Workspace);
+
+    let config: super::SourcemapBase = serde_json::from_str("\"../..\"").expect("failed to parse");
+
+    assert_eq!(config, super::SourcemapBase::Custom("../..".to_string()));
   }
 
   #[test]
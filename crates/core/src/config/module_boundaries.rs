@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use super::config_regex::ConfigRegex;
+
+/// Modules that must never be bundled into the output of the wrong target env.
+/// Besides the patterns, a module can mark itself by importing `server-only` or `client-only`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModuleBoundariesConfig {
+  /// modules that must not be reachable from entries when the target env is browser, e.g. `src/server/`
+  pub server_only: Vec<ConfigRegex>,
+  /// modules that must not be reachable from entries when the target env is node
+  pub client_only: Vec<ConfigRegex>,
+}
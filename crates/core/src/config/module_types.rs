@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// How the files of an extension without a core plugin are built, so they don't fail with unknown module type errors,
+/// e.g. `{ "glsl": { treatAs: "js-via-plugin" } }`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomModuleTypeConfig {
+  pub treat_as: CustomModuleTypeTreatAs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CustomModuleTypeTreatAs {
+  /// emitted as a static asset like the extensions of `assets.include`
+  Asset,
+  /// loaded as text with the module type of the extension, the transform hooks of the plugins turn it into js,
+  /// which is parsed as a js module
+  JsViaPlugin,
+}
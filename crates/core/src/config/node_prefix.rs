@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// How a `node:` prefixed import is handled in browser builds
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NodePrefixAction {
+  /// keep the import as external
+  #[default]
+  External,
+  /// fail the build and report the import chains from the entries to the import
+  Error,
+  /// replace the builtin with an empty module
+  Empty,
+  /// resolve the builtin to the polyfill package, e.g. `{ "polyfill": "path-browserify" }`
+  Polyfill(String),
+}
+
+/// Handling of `node:` prefixed imports like `import path from 'node:path'` when the target env is browser
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NodePrefixConfig {
+  /// action of the builtins that are not configured in [NodePrefixConfig::builtins]
+  pub default: NodePrefixAction,
+  /// builtin name without the prefix -> action, e.g. `path` or `fs/promises`
+  pub builtins: HashMap<String, NodePrefixAction>,
+}
+
+impl NodePrefixConfig {
+  /// The action of `node:{builtin}`, a subpath like `fs/promises` falls back to the action of `fs`
+  pub fn action(&self, builtin: &str) -> &NodePrefixAction {
+    self
+      .builtins
+      .get(builtin)
+      .or_else(|| {
+        builtin
+          .split_once('/')
+          .and_then(|(name, _)| self.builtins.get(name))
+      })
+      .unwrap_or(&self.default)
+  }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use super::{ModuleFormat, TargetEnv};
+use crate::resource::resource_pot::ResourcePotType;
+
+use super::{config_regex::ConfigRegex, ModuleFormat, TargetEnv};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase", default)]
@@ -12,6 +14,93 @@ pub struct OutputConfig {
   pub assets_filename: String,
   pub target_env: TargetEnv,
   pub format: ModuleFormat,
+  /// Code injected into the rendered resource pots, e.g. copyright banners
+  pub injections: Vec<ResourcePotInjectionConfig>,
+  /// Resources whose name matches are high priority, they are injected with `fetchpriority="high"` and preloaded first.
+  /// Dynamic modules that have high priority resources are loaded eagerly when the browser is idle
+  pub high_priority: Vec<ConfigRegex>,
+  /// Emit classic scripts that only contain ES5 syntax for old webviews, the production build fails if any emitted js resource is not ES5
+  pub es5: bool,
+  /// The app is served with the COOP/COEP headers, a `workers-manifest.json` that lists the worker resources
+  /// and the required headers is emitted for the hosting setup
+  pub cross_origin_isolated: bool,
+  /// Extract the license comments (`@license`, `@preserve`, `/*!`) of the modules in each js resource pot to a sibling
+  /// `<resource>.LICENSE.txt`, the license comments are stripped from the minified output
+  pub extract_license_comments: bool,
+  /// Emit the js resource pots as native ES modules that import and export each other instead of registering their modules
+  /// to the runtime, the runtime is not emitted and html entries load the resources by `<script type="module">`.
+  /// Requires `format: esm`, other formats are rejected. Always enabled for library targets
+  pub native_esm: bool,
+  /// The `#!` line of the entries of node targets, see [ShebangConfig]
+  pub shebang: ShebangConfig,
+  /// Emit the entries of node targets as extension-less executables for cli tools, e.g. `dist/cli` instead of `dist/cli.js`.
+  /// `#!/usr/bin/env node` is inserted into the entries without a shebang
+  pub bin: bool,
+}
+
+/// Shebang of the entries of node targets. Resources starting with a shebang are written with executable permissions
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ShebangConfig {
+  Mode(ShebangMode),
+  /// inserted into the entries whose entry module has no shebang, e.g. `#!/usr/bin/env node`
+  Custom(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ShebangMode {
+  /// keep the shebang of the entry module
+  Preserve,
+  /// never emit a shebang
+  Remove,
+}
+
+impl Default for ShebangConfig {
+  fn default() -> Self {
+    Self::Mode(ShebangMode::Preserve)
+  }
+}
+
+impl ShebangConfig {
+  /// The shebang of an entry without `#!`, `module_shebang` is the shebang of its entry module
+  pub fn resolve(&self, module_shebang: Option<&str>, bin: bool) -> Option<String> {
+    let inserted = match self {
+      Self::Mode(ShebangMode::Remove) => return None,
+      Self::Mode(ShebangMode::Preserve) => None,
+      Self::Custom(shebang) => Some(shebang.trim_start_matches("#!").trim().to_string()),
+    };
+
+    module_shebang
+      .map(|shebang| shebang.to_string())
+      .or(inserted)
+      .or_else(|| bin.then(|| DEFAULT_SHEBANG.to_string()))
+  }
+}
+
+pub const DEFAULT_SHEBANG: &str = "/usr/bin/env node";
+
+/// Code injected into the resource pots that match both `resource_pot_types` and `name`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResourcePotInjectionConfig {
+  /// types of the matched resource pots, e.g. `js`, all types if empty
+  pub resource_pot_types: Vec<ResourcePotType>,
+  /// matches the name of the resource pot, all resource pots if not set
+  pub name: Option<ConfigRegex>,
+  /// prepended to the resource pot after it is optimized, comments in the banner are preserved
+  pub banner: Option<String>,
+  /// prepended to the resource pot before it is optimized
+  pub intro: Option<String>,
+  /// appended to the resource pot after it is optimized
+  pub footer: Option<String>,
+}
+
+impl ResourcePotInjectionConfig {
+  pub fn is_match(&self, resource_pot_type: &ResourcePotType, name: &str) -> bool {
+    (self.resource_pot_types.is_empty() || self.resource_pot_types.contains(resource_pot_type))
+      && self.name.as_ref().map_or(true, |n| n.is_match(name))
+  }
 }
 
 impl Default for OutputConfig {
@@ -26,6 +115,41 @@ impl Default for OutputConfig {
       path: "dist".to_string(),
       target_env: TargetEnv::default(),
       format: ModuleFormat::default(),
+      injections: vec![],
+      high_priority: vec![],
+      es5: false,
+      cross_origin_isolated: false,
+      extract_license_comments: false,
+      native_esm: false,
+      shebang: Default::default(),
+      bin: false,
+    }
+  }
+}
+
+/// `publicPath: "runtime"`, the asset urls in the scripts are prefixed with [FARM_PUBLIC_PATH_GLOBAL] at runtime and the urls
+/// in the emitted css and html files are relative, so the same build can be deployed under different url prefixes
+pub const RUNTIME_PUBLIC_PATH: &str = "runtime";
+/// global variable of the public path when it's resolved at runtime, the html entries set it to the directory of the document
+/// unless the page sets it before the resources are loaded, e.g. `window.__farm_public_path__ = '/v2/'`
+pub const FARM_PUBLIC_PATH_GLOBAL: &str = "__farm_public_path__";
+
+impl OutputConfig {
+  /// whether the resource pots are rendered as native ES modules without the runtime
+  pub fn is_native_esm(&self) -> bool {
+    self.target_env.is_library() || (self.native_esm && self.format == ModuleFormat::EsModule)
+  }
+
+  pub fn is_runtime_public_path(&self) -> bool {
+    self.public_path == RUNTIME_PUBLIC_PATH
+  }
+
+  /// The public path the urls are prefixed with at build time, `./` if the public path is resolved at runtime
+  pub fn static_public_path(&self) -> &str {
+    if self.is_runtime_public_path() {
+      "./"
+    } else {
+      &self.public_path
     }
   }
 }
@@ -29,6 +29,18 @@ pub struct PartialBundlingConfig {
   /// Default to 0.8, immutable module will have 80% request numbers.
   /// TODO check if it is between 0 and 1
   pub immutable_modules_weight: f32,
+  /// Route immutable modules into `vendor-permissive`, `vendor-copyleft` and `vendor-unknown` resources by the license of their packages,
+  /// and emit a `{name}.licenses.json` report for each of them. Like `enforceResources`, all other constraints are ignored for these modules.
+  pub license_groups: bool,
+  /// Regex array to match the names of giant packages, e.g. `echarts` or `@ant-design/icons`. The modules of the matched packages
+  /// are split by the top-level subpath they are imported through, e.g. `@ant-design/icons/UserOutlined`, and each subpath is placed
+  /// in its own resource, so importing one subpath doesn't pull the whole package. Modules shared by several subpaths stay together.
+  pub split_packages: Vec<ConfigRegex>,
+  /// Max depth of the dynamic imports that get their own resources, the entries are at depth 0 and their dynamic imports at depth 1.
+  /// The modules of deeper dynamic imports are placed in the resources of their ancestor at this depth, e.g. `1` keeps the route-level
+  /// chunks and folds the lazy components of the routes into them. The css of the folded dynamic imports is not folded, it's still emitted
+  /// as its own resource and loaded before the dynamic import is executed. Every dynamic import gets its own resources if [None]
+  pub max_async_depth: Option<usize>,
 }
 
 impl Default for PartialBundlingConfig {
@@ -45,6 +57,9 @@ impl Default for PartialBundlingConfig {
       enforce_target_min_size: false,
       immutable_modules: vec![ConfigRegex::default()],
       immutable_modules_weight: 0.8,
+      license_groups: false,
+      split_packages: vec![],
+      max_async_depth: None,
     }
   }
 }
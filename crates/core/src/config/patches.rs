@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use super::config_regex::ConfigRegex;
+
+/// A source patch of dependency files, applied to the loaded content before other transforms,
+/// so broken packages can be fixed without mutating node_modules. The patched modules are rebuilt when their patches change
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PatchConfig {
+  /// module ids of the files the replacements are applied to, e.g. `node_modules/lodash/merge\.js$`
+  pub test: Vec<ConfigRegex>,
+  /// unified diff file relative to the root, e.g. created by `git diff` or `patch-package`.
+  /// The files of the diff are matched by the end of their paths, e.g. `a/node_modules/lodash/merge.js`
+  pub diff: Option<String>,
+  /// string replacements applied in order, after the diff
+  pub replace: Vec<PatchReplacement>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PatchReplacement {
+  /// the replaced string, the build fails if it's not found so outdated patches are noticed
+  pub search: String,
+  pub replace: String,
+}
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// The same directory seen by the compiler and by the host, e.g. `/app` in a container mounted from `/Users/me/project`.
+/// Paths exposed to the editor and the browser devtools are mapped to the host paths
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PathMapping {
+  /// absolute directory seen by the compiler
+  pub compiler: String,
+  /// absolute directory seen by the host
+  pub host: String,
+}
+
+/// Map the absolute path seen by the compiler to the host path by the first mapping containing it, [None] if no mapping contains it
+pub fn to_host_path(mappings: &[PathMapping], path: &str) -> Option<String> {
+  mappings.iter().find_map(|mapping| {
+    let rest = Path::new(path).strip_prefix(&mapping.compiler).ok()?;
+    let host = mapping.host.trim_end_matches(['/', '\\']);
+
+    if rest.as_os_str().is_empty() {
+      return Some(host.to_string());
+    }
+
+    Some(format!(
+      "{host}/{}",
+      rest.to_string_lossy().replace('\\', "/")
+    ))
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{to_host_path, PathMapping};
+
+  #[test]
+  fn map_to_host_path() {
+    let mappings = vec![PathMapping {
+      compiler: "/app".to_string(),
+      host: "/Users/me/project/".to_string(),
+    }];
+
+    assert_eq!(
+      to_host_path(&mappings, "/app/src/index.ts").as_deref(),
+      Some("/Users/me/project/src/index.ts")
+    );
+    assert_eq!(
+      to_host_path(&mappings, "/app").as_deref(),
+      Some("/Users/me/project")
+    );
+    // only whole path components are matched
+    assert_eq!(to_host_path(&mappings, "/application/index.ts"), None);
+  }
+}
@@ -33,6 +33,13 @@ impl PersistentCacheConfig {
     }
   }
 
+  pub fn export_signature_enabled(&self) -> bool {
+    match self {
+      PersistentCacheConfig::Bool(b) => *b,
+      PersistentCacheConfig::Obj(obj) => obj.module_cache_key_strategy.export_signature,
+    }
+  }
+
   pub fn get_default_config(root: &str) -> Self {
     let cache_dir = RelativePath::new("node_modules/.farm/cache")
       .to_logical_path(root)
@@ -45,10 +52,12 @@ impl PersistentCacheConfig {
       module_cache_key_strategy: PersistentModuleCacheKeyStrategy {
         timestamp: true,
         hash: true,
+        export_signature: true,
       },
       // build dependencies are set by node side
       build_dependencies: vec![],
       envs: HashMap::new(),
+      global_cache: None,
     })
   }
 
@@ -139,6 +148,43 @@ pub struct PersistentCacheConfigObj {
   /// Note that farm will resolve the config file dependencies from node side
   pub build_dependencies: Vec<String>,
   pub envs: HashMap<String, String>,
+  /// Share transformed immutable modules with other projects on this machine, disabled by default
+  pub global_cache: Option<GlobalCacheConfig>,
+}
+
+/// 1GB
+pub const DEFAULT_GLOBAL_CACHE_MAX_SIZE: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GlobalCacheConfig {
+  /// Default to `~/.farm/global-cache`
+  pub dir: String,
+  /// Least recently used packages are removed when the cache is larger than this size in bytes
+  pub max_size: u64,
+}
+
+impl Default for GlobalCacheConfig {
+  fn default() -> Self {
+    Self {
+      dir: String::new(),
+      max_size: DEFAULT_GLOBAL_CACHE_MAX_SIZE,
+    }
+  }
+}
+
+impl GlobalCacheConfig {
+  pub fn resolved_dir(&self) -> PathBuf {
+    if !self.dir.is_empty() {
+      return PathBuf::from(&self.dir);
+    }
+
+    let home = std::env::var("HOME")
+      .or_else(|_| std::env::var("USERPROFILE"))
+      .unwrap_or_else(|_| std::env::temp_dir().to_string_lossy().to_string());
+
+    PathBuf::from(home).join(".farm").join("global-cache")
+  }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -146,6 +192,9 @@ pub struct PersistentCacheConfigObj {
 pub struct PersistentModuleCacheKeyStrategy {
   pub timestamp: bool,
   pub hash: bool,
+  /// key the cached modules by the export signature of their script watch dependencies instead of the content,
+  /// so edits that don't change the exports of a dependency don't invalidate the modules watching it
+  pub export_signature: bool,
 }
 
 impl Default for PersistentModuleCacheKeyStrategy {
@@ -153,6 +202,7 @@ impl Default for PersistentModuleCacheKeyStrategy {
     Self {
       timestamp: true,
       hash: true,
+      export_signature: true,
     }
   }
 }
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// File based routing, every file under [RoutesConfig::dirs] is a route.
+/// The routes can be imported from the virtual module `virtual:farm-routes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RoutesConfig {
+  /// page directories relative to root, e.g. `src/pages`
+  pub dirs: Vec<String>,
+  /// extensions of page files
+  pub extensions: Vec<String>,
+  /// file name of the emitted route manifest, which maps a route path to its resources
+  pub manifest_filename: String,
+}
+
+impl Default for RoutesConfig {
+  fn default() -> Self {
+    Self {
+      dirs: vec!["src/pages".to_string()],
+      extensions: vec![
+        "tsx".to_string(),
+        "ts".to_string(),
+        "jsx".to_string(),
+        "js".to_string(),
+      ],
+      manifest_filename: "routes-manifest.json".to_string(),
+    }
+  }
+}
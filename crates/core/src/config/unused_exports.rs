@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// Emit a json report of the exports that are never imported, see [crate::stats::UnusedExportsStats]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UnusedExportsConfig {
+  /// file name of the emitted report
+  pub filename: String,
+}
+
+impl Default for UnusedExportsConfig {
+  fn default() -> Self {
+    Self {
+      filename: "unused-exports.json".to_string(),
+    }
+  }
+}
@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use relative_path::RelativePath;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CompilationError, Result};
+
+/// Build the dependencies once as a vendor reference, then link other builds against it instead of bundling them again.
+/// A vendor reference build uses the import sources it provides as inputs, e.g. `{ "react": "react" }`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VendorReferenceConfig {
+  /// file name of the manifest emitted by a vendor reference build, e.g. `vendor-manifest.json`.
+  /// The runtime is not injected to the resources of a vendor reference build, they only register the modules
+  pub manifest: Option<String>,
+  /// paths of the manifests of the vendor reference builds to link against, relative to the root
+  pub references: Vec<String>,
+  /// vendor reference builds consumed as externals providers. Unlike `references`, their resources are not loaded by the html,
+  /// an import of a provided source is rewritten to load the resources of the provider build and require the module at runtime
+  pub providers: Vec<VendorProviderConfig>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VendorProviderConfig {
+  /// path of the manifest of the provider build, relative to the root
+  pub manifest: String,
+  /// url the resources of the provider build are served from, e.g. `https://cdn.example.com/vendor/`. Defaults to the public path of the manifest
+  pub public_path: Option<String>,
+  /// import source -> the import source provided by the manifest, e.g. `{ "react-dom/client": "react-dom" }`
+  pub alias: BTreeMap<String, String>,
+}
+
+/// Modules provided by a vendor reference build
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VendorReferenceManifest {
+  /// public path the resources of the vendor reference build are served from
+  pub public_path: String,
+  /// js resources that register the provided modules, loaded after the runtime and before the resources of the linked build
+  pub resources: Vec<String>,
+  /// import source -> id of the module registered in the runtime, the module is registered under the import source too
+  pub modules: BTreeMap<String, String>,
+}
+
+impl VendorReferenceManifest {
+  /// urls of the resources, e.g. `/vendor/vendor_0f1e.js`
+  pub fn resource_urls(&self) -> Vec<String> {
+    self
+      .resources
+      .iter()
+      .map(|name| format!("{}{name}", self.public_path))
+      .collect()
+  }
+}
+
+impl VendorReferenceConfig {
+  pub fn is_vendor_build(&self) -> bool {
+    self.manifest.is_some()
+  }
+
+  /// Read the manifests of `references`
+  pub fn load_references(&self, root: &str) -> Result<Vec<VendorReferenceManifest>> {
+    self
+      .references
+      .iter()
+      .map(|reference| load_manifest(root, reference))
+      .collect()
+  }
+
+  /// Read the manifests of `providers`, the public path of every manifest is overridden by the provider
+  pub fn load_providers(
+    &self,
+    root: &str,
+  ) -> Result<Vec<(&VendorProviderConfig, VendorReferenceManifest)>> {
+    self
+      .providers
+      .iter()
+      .map(|provider| {
+        let mut manifest = load_manifest(root, &provider.manifest)?;
+
+        if let Some(public_path) = &provider.public_path {
+          manifest.public_path.clone_from(public_path);
+        }
+
+        Ok((provider, manifest))
+      })
+      .collect()
+  }
+}
+
+fn load_manifest(root: &str, manifest: &str) -> Result<VendorReferenceManifest> {
+  let path = RelativePath::new(manifest).to_logical_path(root);
+  let content = std::fs::read_to_string(&path).map_err(|e| {
+    CompilationError::GenericError(format!(
+      "Failed to read vendor reference manifest {path:?}: {e}"
+    ))
+  })?;
+
+  serde_json::from_str(&content).map_err(|e| {
+    CompilationError::GenericError(format!(
+      "Failed to parse vendor reference manifest {path:?}: {e}"
+    ))
+  })
+}
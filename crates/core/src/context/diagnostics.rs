@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::module::ModuleId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticSeverity {
+  Warning,
+  Error,
+}
+
+/// A problem found by the analysis passes over the module graph, e.g. circular dependencies.
+/// Unlike the messages of [super::log_store::LogStore], diagnostics keep the related modules so tools can render or link them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+  /// kind of the diagnostic, e.g. `circular-dependency`
+  pub code: String,
+  pub severity: DiagnosticSeverity,
+  pub message: String,
+  /// modules related to the diagnostic, e.g. the import cycle
+  pub modules: Vec<ModuleId>,
+  /// name of the plugin that reported the diagnostic
+  #[serde(default)]
+  pub plugin: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct DiagnosticStore {
+  diagnostics: Vec<Diagnostic>,
+}
+
+impl DiagnosticStore {
+  pub fn new() -> Self {
+    Self {
+      diagnostics: vec![],
+    }
+  }
+
+  pub fn add(&mut self, diagnostic: Diagnostic) {
+    self.diagnostics.push(diagnostic);
+  }
+
+  pub fn diagnostics(&self) -> &Vec<Diagnostic> {
+    &self.diagnostics
+  }
+
+  pub fn has_errors(&self) -> bool {
+    self
+      .diagnostics
+      .iter()
+      .any(|d| d.severity == DiagnosticSeverity::Error)
+  }
+
+  /// Remove the diagnostics of `code`, analysis passes call it before reporting again on updates
+  pub fn clear_code(&mut self, code: &str) {
+    self.diagnostics.retain(|d| d.code != code);
+  }
+
+  /// Remove the diagnostics equal to one of `diagnostics`, e.g. the ones a plugin reported in the previous build
+  pub fn remove(&mut self, diagnostics: &[Diagnostic]) {
+    self.diagnostics.retain(|d| !diagnostics.contains(d));
+  }
+
+  pub fn clear(&mut self) {
+    self.diagnostics.clear();
+  }
+
+  /// Readable report of the diagnostics grouped by their first module and then by the plugin that reported them.
+  /// The modules with errors come first, at most `max_diagnostics` diagnostics are rendered and the report ends with the counts
+  pub fn report(&self, max_diagnostics: usize) -> String {
+    let mut groups = BTreeMap::<String, Vec<&Diagnostic>>::new();
+
+    for diagnostic in &self.diagnostics {
+      let module = diagnostic
+        .modules
+        .first()
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| "(no module)".to_string());
+      groups.entry(module).or_default().push(diagnostic);
+    }
+
+    let is_error = |d: &&Diagnostic| d.severity == DiagnosticSeverity::Error;
+    let mut groups = groups.into_iter().collect::<Vec<_>>();
+    // the sort is stable, the modules are still sorted by id in the errors and the warnings
+    groups.sort_by_key(|(_, diagnostics)| !diagnostics.iter().any(is_error));
+
+    let mut lines = vec![];
+    let mut rendered = 0;
+
+    for (module, mut diagnostics) in groups {
+      if rendered == max_diagnostics {
+        break;
+      }
+
+      diagnostics.sort_by_key(|d| (d.plugin.clone(), !is_error(d)));
+      lines.push(module);
+
+      let mut current_plugin = None;
+
+      for diagnostic in diagnostics.into_iter().take(max_diagnostics - rendered) {
+        if current_plugin != Some(&diagnostic.plugin) {
+          current_plugin = Some(&diagnostic.plugin);
+          lines.push(format!(
+            "  {}",
+            diagnostic.plugin.as_deref().unwrap_or("(unknown plugin)")
+          ));
+        }
+
+        let severity = match diagnostic.severity {
+          DiagnosticSeverity::Error => "error",
+          DiagnosticSeverity::Warning => "warning",
+        };
+        lines.push(format!(
+          "    {severity} [{}] {}",
+          diagnostic.code, diagnostic.message
+        ));
+        rendered += 1;
+      }
+    }
+
+    let errors = self.diagnostics.iter().filter(is_error).count();
+    let mut summary = format!(
+      "{errors} error(s) and {} warning(s)",
+      self.diagnostics.len() - errors
+    );
+
+    if rendered < self.diagnostics.len() {
+      summary.push_str(&format!(
+        ", {} of them are not shown",
+        self.diagnostics.len() - rendered
+      ));
+    }
+
+    lines.push(summary);
+    lines.join("\n")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn diagnostic(
+    code: &str,
+    severity: DiagnosticSeverity,
+    module: Option<&str>,
+    plugin: Option<&str>,
+  ) -> Diagnostic {
+    Diagnostic {
+      code: code.to_string(),
+      severity,
+      message: format!("{code} found"),
+      modules: module.into_iter().map(|m| m.into()).collect(),
+      plugin: plugin.map(|p| p.to_string()),
+    }
+  }
+
+  #[test]
+  fn report() {
+    let mut store = DiagnosticStore::new();
+    store.add(diagnostic(
+      "unused",
+      DiagnosticSeverity::Warning,
+      Some("a.ts"),
+      Some("lint"),
+    ));
+    store.add(diagnostic(
+      "cycle",
+      DiagnosticSeverity::Error,
+      Some("b.ts"),
+      Some("circular"),
+    ));
+    store.add(diagnostic(
+      "policy",
+      DiagnosticSeverity::Warning,
+      Some("b.ts"),
+      Some("policy"),
+    ));
+    store.add(diagnostic(
+      "cycle",
+      DiagnosticSeverity::Warning,
+      Some("b.ts"),
+      Some("circular"),
+    ));
+    store.add(diagnostic("panic", DiagnosticSeverity::Warning, None, None));
+
+    assert_eq!(
+      store.report(10),
+      [
+        "b.ts",
+        "  circular",
+        "    error [cycle] cycle found",
+        "    warning [cycle] cycle found",
+        "  policy",
+        "    warning [policy] policy found",
+        "(no module)",
+        "  (unknown plugin)",
+        "    warning [panic] panic found",
+        "a.ts",
+        "  lint",
+        "    warning [unused] unused found",
+        "1 error(s) and 4 warning(s)",
+      ]
+      .join("\n")
+    );
+
+    assert_eq!(
+      store.report(2),
+      [
+        "b.ts",
+        "  circular",
+        "    error [cycle] cycle found",
+        "    warning [cycle] cycle found",
+        "1 error(s) and 4 warning(s), 3 of them are not shown",
+      ]
+      .join("\n")
+    );
+  }
+}
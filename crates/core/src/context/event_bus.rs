@@ -0,0 +1,212 @@
+use std::{
+  collections::{HashMap, VecDeque},
+  marker::PhantomData,
+  sync::Arc,
+};
+
+use parking_lot::RwLock;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::{CompilationError, Result};
+
+/// max payloads retained per topic, the oldest payloads are dropped first so a long running dev server does not grow forever
+pub const MAX_RETAINED_EVENTS: usize = 1024;
+
+type Subscriber = Arc<dyn Fn(&serde_json::Value) + Send + Sync>;
+
+/// A topic of the [EventBus] whose payloads are `T`, declare it as a const shared by the cooperating plugins, e.g.
+/// `pub const ROUTES: EventTopic<Vec<Route>> = EventTopic::new("router:routes");`
+pub struct EventTopic<T> {
+  name: &'static str,
+  _payload: PhantomData<fn() -> T>,
+}
+
+impl<T> EventTopic<T> {
+  pub const fn new(name: &'static str) -> Self {
+    Self {
+      name,
+      _payload: PhantomData,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    self.name
+  }
+}
+
+/// Publish/subscribe channel for plugins to exchange data, e.g. a router plugin publishes the routes and an i18n plugin
+/// subscribes to them. Payloads are serialized to json so js plugins can publish and read them by the topic name.
+/// Published payloads are retained until the topic is cleared, so a plugin that runs later can still read them,
+/// at most [MAX_RETAINED_EVENTS] payloads are retained per topic.
+#[derive(Default)]
+pub struct EventBus {
+  /// topic -> subscribers
+  subscribers: RwLock<HashMap<String, Vec<Subscriber>>>,
+  /// topic -> payloads in publishing order
+  events: RwLock<HashMap<String, VecDeque<serde_json::Value>>>,
+}
+
+impl EventBus {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn publish<T: Serialize>(&self, topic: &EventTopic<T>, payload: &T) -> Result<()> {
+    let payload = serde_json::to_value(payload).map_err(|e| {
+      CompilationError::GenericError(format!(
+        "payload of topic {} is not serializable: {e}",
+        topic.name
+      ))
+    })?;
+    self.publish_value(topic.name, payload);
+
+    Ok(())
+  }
+
+  /// Publish a payload that is already serialized, used by js plugins
+  pub fn publish_value(&self, topic: &str, payload: serde_json::Value) {
+    {
+      let mut events = self.events.write();
+      let events = events.entry(topic.to_string()).or_default();
+
+      if events.len() == MAX_RETAINED_EVENTS {
+        events.pop_front();
+      }
+
+      events.push_back(payload.clone());
+    }
+
+    // subscribers may publish other events, the lock must not be held while they are called
+    let subscribers = self
+      .subscribers
+      .read()
+      .get(topic)
+      .cloned()
+      .unwrap_or_default();
+
+    for subscriber in subscribers {
+      subscriber(&payload);
+    }
+  }
+
+  /// Call `f` with every payload published to the topic after subscribing.
+  /// Payloads that can not be deserialized to `T`, e.g. published by a js plugin with another shape, are skipped
+  pub fn subscribe<T: DeserializeOwned>(
+    &self,
+    topic: &EventTopic<T>,
+    f: impl Fn(T) + Send + Sync + 'static,
+  ) {
+    self
+      .subscribers
+      .write()
+      .entry(topic.name.to_string())
+      .or_default()
+      .push(Arc::new(move |payload| {
+        if let Ok(payload) = T::deserialize(payload) {
+          f(payload);
+        }
+      }));
+  }
+
+  /// The payloads published to the topic so far
+  pub fn events<T: DeserializeOwned>(&self, topic: &EventTopic<T>) -> Vec<T> {
+    self
+      .event_values(topic.name)
+      .into_iter()
+      .filter_map(|payload| serde_json::from_value(payload).ok())
+      .collect()
+  }
+
+  pub fn event_values(&self, topic: &str) -> Vec<serde_json::Value> {
+    self
+      .events
+      .read()
+      .get(topic)
+      .map(|events| events.iter().cloned().collect())
+      .unwrap_or_default()
+  }
+
+  /// Remove the retained payloads of the topic, e.g. before the publisher publishes them again in a new build
+  pub fn clear(&self, topic: &str) {
+    self.events.write().remove(topic);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use serde::{Deserialize, Serialize};
+
+  use super::{EventBus, EventTopic, MAX_RETAINED_EVENTS};
+
+  #[derive(Debug, PartialEq, Serialize, Deserialize)]
+  struct Route {
+    path: String,
+  }
+
+  const ROUTES: EventTopic<Route> = EventTopic::new("router:routes");
+
+  #[test]
+  fn publish_and_subscribe() {
+    let bus = EventBus::new();
+    let received = Arc::new(Mutex::new(vec![]));
+    let received_clone = received.clone();
+
+    bus.subscribe(&ROUTES, move |route| {
+      received_clone.lock().unwrap().push(route)
+    });
+    bus
+      .publish(
+        &ROUTES,
+        &Route {
+          path: "/home".to_string(),
+        },
+      )
+      .unwrap();
+    // published by a js plugin
+    bus.publish_value(ROUTES.name(), serde_json::json!({ "path": "/about" }));
+    bus.publish_value(ROUTES.name(), serde_json::json!("invalid"));
+
+    let expected = vec![
+      Route {
+        path: "/home".to_string(),
+      },
+      Route {
+        path: "/about".to_string(),
+      },
+    ];
+    assert_eq!(*received.lock().unwrap(), expected);
+    assert_eq!(bus.events(&ROUTES), expected);
+    assert_eq!(bus.event_values(ROUTES.name()).len(), 3);
+
+    bus.clear(ROUTES.name());
+    assert!(bus.events(&ROUTES).is_empty());
+  }
+
+  #[test]
+  fn retained_events_are_capped() {
+    let bus = EventBus::new();
+
+    for i in 0..MAX_RETAINED_EVENTS + 2 {
+      bus.publish_value(
+        ROUTES.name(),
+        serde_json::json!({ "path": format!("/{i}") }),
+      );
+    }
+
+    let events = bus.events(&ROUTES);
+    assert_eq!(events.len(), MAX_RETAINED_EVENTS);
+    assert_eq!(events[0].path, "/2");
+  }
+
+  #[test]
+  fn publish_unserializable_payload() {
+    const MAP: EventTopic<std::collections::HashMap<Vec<u8>, u8>> = EventTopic::new("map");
+    let bus = EventBus::new();
+    let payload = std::collections::HashMap::from([(vec![1], 1)]);
+
+    assert!(bus.publish(&MAP, &payload).is_err());
+    assert!(bus.event_values(MAP.name()).is_empty());
+  }
+}
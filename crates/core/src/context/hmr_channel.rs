@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+type HmrEventListener = Arc<dyn Fn(&HmrCustomEvent) + Send + Sync>;
+
+/// A custom event between the plugins and the hmr clients, the event names should be prefixed by the plugin name,
+/// e.g. `my-plugin:routes`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HmrCustomEvent {
+  pub event: String,
+  pub data: serde_json::Value,
+}
+
+/// Custom events sent by the plugins to the hmr clients, which receive them by `import.meta.hot.on(event, cb)`.
+/// The dev server listens to the channel and forwards the events to the connected clients, the events sent when no
+/// dev server listens are dropped, e.g. in production builds. The events sent by the clients with `import.meta.hot.send`
+/// are passed to the `hmr_event` hook of the plugins
+#[derive(Default)]
+pub struct HmrChannel {
+  listeners: RwLock<Vec<HmrEventListener>>,
+}
+
+impl HmrChannel {
+  pub fn send(&self, event: &str, data: serde_json::Value) {
+    let event = HmrCustomEvent {
+      event: event.to_string(),
+      data,
+    };
+    // listeners may send other events, the lock must not be held while they are called
+    let listeners = self.listeners.read().clone();
+
+    for listener in listeners {
+      listener(&event);
+    }
+  }
+
+  /// Called by the dev server to forward the events to the clients
+  pub fn subscribe(&self, f: impl Fn(&HmrCustomEvent) + Send + Sync + 'static) {
+    self.listeners.write().push(Arc::new(f));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::{HmrChannel, HmrCustomEvent};
+
+  #[test]
+  fn send_hmr_events() {
+    let channel = HmrChannel::default();
+    // dropped, no dev server listens yet
+    channel.send("router:routes", serde_json::json!(["/"]));
+
+    let received = Arc::new(Mutex::new(vec![]));
+    let received_clone = received.clone();
+    channel.subscribe(move |event| received_clone.lock().unwrap().push(event.clone()));
+    channel.send("router:routes", serde_json::json!(["/", "/about"]));
+
+    assert_eq!(
+      *received.lock().unwrap(),
+      vec![HmrCustomEvent {
+        event: "router:routes".to_string(),
+        data: serde_json::json!(["/", "/about"]),
+      }]
+    );
+  }
+}
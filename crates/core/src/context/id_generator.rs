@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use dashmap::DashMap;
+
+use crate::config::hash::{HashConfig, MAX_HASH_LENGTH};
+
+/// Deterministic ids for plugins, e.g. css scope hashes or chunk salts.
+/// An id only depends on `hash.seed`, the namespace and the key, so the same input generates the same id across builds
+/// no matter in which order the ids are generated, unlike random ids or counters shared by parallel hooks.
+pub struct IdGenerator {
+  hash_config: HashConfig,
+  /// namespace -> id -> key, different keys never share an id in the same namespace
+  issued: DashMap<String, HashMap<String, String>>,
+}
+
+impl IdGenerator {
+  pub fn new(hash_config: &HashConfig) -> Self {
+    Self {
+      hash_config: hash_config.clone(),
+      issued: DashMap::new(),
+    }
+  }
+
+  /// Generate an id of `len` hex characters for `key` in `namespace`, the max length is [MAX_HASH_LENGTH].
+  /// When the id is already issued to another key, the key is rehashed until a free id is found
+  pub fn generate(&self, namespace: &str, key: &str, len: usize) -> String {
+    let len = len.clamp(1, MAX_HASH_LENGTH);
+    let mut issued = self.issued.entry(namespace.to_string()).or_default();
+    let mut attempt = 0;
+
+    loop {
+      let input = if attempt == 0 {
+        format!("{namespace}\0{key}")
+      } else {
+        format!("{namespace}\0{key}\0{attempt}")
+      };
+      let id = self.hash_config.hash(input.as_bytes(), len);
+
+      match issued.get(&id) {
+        Some(issued_key) if issued_key != key => attempt += 1,
+        _ => {
+          issued.insert(id.clone(), key.to_string());
+          return id;
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+
+  use crate::config::hash::HashConfig;
+
+  use super::IdGenerator;
+
+  #[test]
+  fn generate_deterministic_ids() {
+    let generator = IdGenerator::new(&HashConfig::default());
+    let id = generator.generate("css", "button.css", 8);
+
+    assert_eq!(id.len(), 8);
+    assert_eq!(generator.generate("css", "button.css", 8), id);
+    assert_eq!(
+      IdGenerator::new(&HashConfig::default()).generate("css", "button.css", 8),
+      id
+    );
+    assert_ne!(generator.generate("chunk", "button.css", 8), id);
+
+    let seeded = IdGenerator::new(&HashConfig {
+      seed: "v2".to_string(),
+      ..Default::default()
+    });
+    assert_ne!(seeded.generate("css", "button.css", 8), id);
+  }
+
+  #[test]
+  fn generate_unique_ids() {
+    let generator = IdGenerator::new(&HashConfig::default());
+    // there are only 16 ids of length 1
+    let ids = (0..16)
+      .map(|i| generator.generate("css", &i.to_string(), 1))
+      .collect::<HashSet<_>>();
+
+    assert_eq!(ids.len(), 16);
+  }
+}
@@ -0,0 +1,316 @@
+//! Locks of the [CompilationContext](super::CompilationContext) shared graphs.
+//! When the `lock_debug` feature is enabled, the wait time of every acquisition is recorded and the order the locks are acquired in
+//! is checked, a warning with the backtrace is printed when two locks are acquired in different orders by different code paths,
+//! which means a potential deadlock. A watchdog thread also reports the threads that are actually deadlocked.
+//! Without the feature, the locks are plain parking_lot locks.
+use std::ops::{Deref, DerefMut};
+
+use parking_lot::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+pub struct TrackedRwLock<T> {
+  #[cfg_attr(not(feature = "lock_debug"), allow(dead_code))]
+  name: &'static str,
+  lock: RwLock<T>,
+}
+
+impl<T> TrackedRwLock<T> {
+  pub fn new(name: &'static str, value: T) -> Self {
+    Self {
+      name,
+      lock: RwLock::new(value),
+    }
+  }
+
+  pub fn read(&self) -> TrackedGuard<RwLockReadGuard<'_, T>> {
+    TrackedGuard::acquire(self.name, LockKind::Read, || self.lock.read())
+  }
+
+  /// Read without waiting, [None] if the lock is held by a writer
+  pub fn try_read(&self) -> Option<TrackedGuard<RwLockReadGuard<'_, T>>> {
+    let guard = self.lock.try_read()?;
+    Some(TrackedGuard::acquire(self.name, LockKind::Read, || guard))
+  }
+
+  pub fn write(&self) -> TrackedGuard<RwLockWriteGuard<'_, T>> {
+    TrackedGuard::acquire(self.name, LockKind::Write, || self.lock.write())
+  }
+}
+
+pub struct TrackedMutex<T> {
+  #[cfg_attr(not(feature = "lock_debug"), allow(dead_code))]
+  name: &'static str,
+  lock: Mutex<T>,
+}
+
+impl<T> TrackedMutex<T> {
+  pub fn new(name: &'static str, value: T) -> Self {
+    Self {
+      name,
+      lock: Mutex::new(value),
+    }
+  }
+
+  pub fn lock(&self) -> TrackedGuard<MutexGuard<'_, T>> {
+    TrackedGuard::acquire(self.name, LockKind::Write, || self.lock.lock())
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockKind {
+  Read,
+  /// write lock of a rwlock or a mutex
+  Write,
+}
+
+pub struct TrackedGuard<G> {
+  guard: G,
+  #[cfg(feature = "lock_debug")]
+  id: usize,
+}
+
+impl<G> TrackedGuard<G> {
+  #[cfg(not(feature = "lock_debug"))]
+  #[inline]
+  fn acquire(_name: &'static str, _kind: LockKind, lock: impl FnOnce() -> G) -> Self {
+    Self { guard: lock() }
+  }
+
+  #[cfg(feature = "lock_debug")]
+  fn acquire(name: &'static str, kind: LockKind, lock: impl FnOnce() -> G) -> Self {
+    debug::check_order(name, kind);
+
+    let start = std::time::Instant::now();
+    let guard = lock();
+    debug::record_wait(name, start.elapsed());
+
+    Self {
+      guard,
+      id: debug::push_held(name, kind),
+    }
+  }
+}
+
+impl<G: Deref> Deref for TrackedGuard<G> {
+  type Target = G::Target;
+
+  fn deref(&self) -> &Self::Target {
+    &self.guard
+  }
+}
+
+impl<G: DerefMut> DerefMut for TrackedGuard<G> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.guard
+  }
+}
+
+#[cfg(feature = "lock_debug")]
+impl<G> Drop for TrackedGuard<G> {
+  fn drop(&mut self) {
+    debug::pop_held(self.id);
+  }
+}
+
+#[cfg(feature = "lock_debug")]
+pub use debug::{lock_stats, potential_deadlocks, print_lock_stats, LockStats};
+
+#[cfg(feature = "lock_debug")]
+mod debug {
+  use std::{
+    backtrace::Backtrace,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    sync::{
+      atomic::{AtomicUsize, Ordering},
+      Once, OnceLock,
+    },
+    time::Duration,
+  };
+
+  use parking_lot::Mutex;
+
+  use super::LockKind;
+
+  #[derive(Debug, Default, Clone, PartialEq, Eq)]
+  pub struct LockStats {
+    pub name: &'static str,
+    pub acquisitions: u64,
+    pub total_wait: Duration,
+    pub max_wait: Duration,
+  }
+
+  #[derive(Default)]
+  struct Tracker {
+    stats: HashMap<&'static str, LockStats>,
+    /// (held lock, acquired lock) pairs that have been observed
+    orders: HashSet<(&'static str, &'static str)>,
+    /// reported (held lock, acquired lock) pairs of potential deadlocks
+    deadlocks: HashSet<(&'static str, &'static str)>,
+  }
+
+  thread_local! {
+    /// locks held by the current thread, (guard id, lock name, kind)
+    static HELD: RefCell<Vec<(usize, &'static str, LockKind)>> = const { RefCell::new(vec![]) };
+  }
+
+  static NEXT_GUARD_ID: AtomicUsize = AtomicUsize::new(0);
+  static WATCHDOG: Once = Once::new();
+
+  fn tracker() -> &'static Mutex<Tracker> {
+    static TRACKER: OnceLock<Mutex<Tracker>> = OnceLock::new();
+
+    WATCHDOG.call_once(start_deadlock_watchdog);
+    TRACKER.get_or_init(Default::default)
+  }
+
+  pub(super) fn check_order(name: &'static str, kind: LockKind) {
+    let held = HELD.with(|held| held.borrow().clone());
+
+    if held.is_empty() {
+      return;
+    }
+
+    let mut tracker = tracker().lock();
+
+    for (_, held_name, held_kind) in held {
+      if held_name == name {
+        // reading a lock twice deadlocks only if a writer is waiting in between, which is still a bug
+        if kind == LockKind::Write || held_kind == LockKind::Write {
+          report(
+            &mut tracker,
+            (held_name, name),
+            format!("`{name}` is locked again by the thread that is holding it"),
+          );
+        }
+        continue;
+      }
+
+      tracker.orders.insert((held_name, name));
+
+      if tracker.orders.contains(&(name, held_name)) {
+        report(
+          &mut tracker,
+          (held_name, name),
+          format!(
+            "`{name}` is acquired while holding `{held_name}`, but `{held_name}` has been acquired while holding `{name}` before"
+          ),
+        );
+      }
+    }
+  }
+
+  fn report(tracker: &mut Tracker, pair: (&'static str, &'static str), msg: String) {
+    if tracker.deadlocks.insert(pair) {
+      eprintln!(
+        "[Farm] potential deadlock: {msg}\n{}",
+        Backtrace::force_capture()
+      );
+    }
+  }
+
+  pub(super) fn record_wait(name: &'static str, wait: Duration) {
+    let mut tracker = tracker().lock();
+    let stats = tracker.stats.entry(name).or_insert_with(|| LockStats {
+      name,
+      ..Default::default()
+    });
+
+    stats.acquisitions += 1;
+    stats.total_wait += wait;
+    stats.max_wait = stats.max_wait.max(wait);
+  }
+
+  pub(super) fn push_held(name: &'static str, kind: LockKind) -> usize {
+    let id = NEXT_GUARD_ID.fetch_add(1, Ordering::Relaxed);
+    HELD.with(|held| held.borrow_mut().push((id, name, kind)));
+    id
+  }
+
+  pub(super) fn pop_held(id: usize) {
+    // guards are not always dropped in the reverse order
+    HELD.with(|held| held.borrow_mut().retain(|(i, _, _)| *i != id));
+  }
+
+  /// Stats of the locks, sorted by the total wait time
+  pub fn lock_stats() -> Vec<LockStats> {
+    let mut stats = tracker().lock().stats.values().cloned().collect::<Vec<_>>();
+    stats.sort_by(|a, b| b.total_wait.cmp(&a.total_wait));
+    stats
+  }
+
+  /// (held lock, acquired lock) pairs that are acquired in different orders
+  pub fn potential_deadlocks() -> Vec<(&'static str, &'static str)> {
+    let mut deadlocks = tracker()
+      .lock()
+      .deadlocks
+      .iter()
+      .cloned()
+      .collect::<Vec<_>>();
+    deadlocks.sort();
+    deadlocks
+  }
+
+  pub fn print_lock_stats() {
+    for stats in lock_stats() {
+      eprintln!(
+        "[Farm] lock `{}`: {} acquisitions, total wait {:?}, max wait {:?}",
+        stats.name, stats.acquisitions, stats.total_wait, stats.max_wait
+      );
+    }
+  }
+
+  /// Report the deadlocked threads detected by parking_lot, it is the only way to find out why a hmr update hangs
+  fn start_deadlock_watchdog() {
+    std::thread::spawn(|| loop {
+      std::thread::sleep(Duration::from_secs(10));
+
+      for (i, threads) in parking_lot::deadlock::check_deadlock().iter().enumerate() {
+        eprintln!("[Farm] deadlock #{i} detected");
+
+        for thread in threads {
+          eprintln!("thread {:?}:\n{:?}", thread.thread_id(), thread.backtrace());
+        }
+      }
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::TrackedRwLock;
+
+  #[test]
+  fn tracked_rwlock() {
+    let lock = TrackedRwLock::new("test", vec![1]);
+    lock.write().push(2);
+
+    let a = lock.read();
+    let b = lock.read();
+    assert_eq!(*a, vec![1, 2]);
+    assert_eq!(b.len(), 2);
+  }
+
+  #[cfg(feature = "lock_debug")]
+  #[test]
+  fn detect_lock_order_inversion() {
+    use super::{lock_stats, potential_deadlocks};
+
+    let a = TrackedRwLock::new("order_a", ());
+    let b = TrackedRwLock::new("order_b", ());
+
+    {
+      let _a = a.read();
+      let _b = b.write();
+    }
+    assert!(!potential_deadlocks().contains(&("order_a", "order_b")));
+
+    {
+      let _b = b.read();
+      let _a = a.write();
+    }
+    assert!(potential_deadlocks().contains(&("order_b", "order_a")));
+    assert!(lock_stats()
+      .iter()
+      .any(|s| s.name == "order_a" && s.acquisitions == 2));
+  }
+}
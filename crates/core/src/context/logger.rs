@@ -0,0 +1,196 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::logging::{LogFormat, LogLevel, LoggingConfig};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRecord {
+  pub level: LogLevel,
+  /// the module path of the rust code, or the plugin name for plugin logs
+  pub target: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub plugin: Option<String>,
+  pub message: String,
+  /// milliseconds since the unix epoch
+  pub timestamp: u64,
+}
+
+/// Logging facade of the compilation, records are filtered by [LoggingConfig] and written to stderr.
+/// Logs of plugins are also captured so they can be reported per plugin, see [Logger::take_plugin_records]
+pub struct Logger {
+  config: LoggingConfig,
+  plugin_records: Mutex<Vec<LogRecord>>,
+}
+
+impl Logger {
+  pub fn new(config: &LoggingConfig) -> Self {
+    Self {
+      config: config.clone(),
+      plugin_records: Mutex::new(vec![]),
+    }
+  }
+
+  pub fn enabled(&self, level: LogLevel, target: &str) -> bool {
+    level != LogLevel::Silent && level <= self.config.level_of(target)
+  }
+
+  pub fn log(&self, level: LogLevel, target: &str, message: impl Into<String>) {
+    self.log_record(level, target, None, message.into());
+  }
+
+  pub fn error(&self, target: &str, message: impl Into<String>) {
+    self.log(LogLevel::Error, target, message);
+  }
+
+  pub fn warn(&self, target: &str, message: impl Into<String>) {
+    self.log(LogLevel::Warn, target, message);
+  }
+
+  pub fn info(&self, target: &str, message: impl Into<String>) {
+    self.log(LogLevel::Info, target, message);
+  }
+
+  pub fn debug(&self, target: &str, message: impl Into<String>) {
+    self.log(LogLevel::Debug, target, message);
+  }
+
+  pub fn trace(&self, target: &str, message: impl Into<String>) {
+    self.log(LogLevel::Trace, target, message);
+  }
+
+  /// Logger of a plugin, its records are attributed to the plugin and captured
+  pub fn plugin<'a>(&'a self, name: &'a str) -> PluginLogger<'a> {
+    PluginLogger { logger: self, name }
+  }
+
+  /// The captured plugin records since the last call
+  pub fn take_plugin_records(&self) -> Vec<LogRecord> {
+    std::mem::take(&mut *self.plugin_records.lock())
+  }
+
+  fn log_record(&self, level: LogLevel, target: &str, plugin: Option<&str>, message: String) {
+    if !self.enabled(level, target) {
+      return;
+    }
+
+    let record = LogRecord {
+      level,
+      target: target.to_string(),
+      plugin: plugin.map(|p| p.to_string()),
+      message,
+      timestamp: SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default(),
+    };
+
+    eprintln!("{}", self.format(&record));
+
+    if record.plugin.is_some() {
+      self.plugin_records.lock().push(record);
+    }
+  }
+
+  pub fn format(&self, record: &LogRecord) -> String {
+    match self.config.format {
+      LogFormat::Json => serde_json::to_string(record).unwrap(),
+      LogFormat::Text => {
+        let level = format!("{:?}", record.level).to_uppercase();
+
+        match &record.plugin {
+          Some(plugin) => format!("[farm] {level} [{plugin}] {}", record.message),
+          None => format!("[farm] {level} {}: {}", record.target, record.message),
+        }
+      }
+    }
+  }
+}
+
+pub struct PluginLogger<'a> {
+  logger: &'a Logger,
+  name: &'a str,
+}
+
+impl PluginLogger<'_> {
+  pub fn log(&self, level: LogLevel, message: impl Into<String>) {
+    self
+      .logger
+      .log_record(level, self.name, Some(self.name), message.into());
+  }
+
+  pub fn error(&self, message: impl Into<String>) {
+    self.log(LogLevel::Error, message);
+  }
+
+  pub fn warn(&self, message: impl Into<String>) {
+    self.log(LogLevel::Warn, message);
+  }
+
+  pub fn info(&self, message: impl Into<String>) {
+    self.log(LogLevel::Info, message);
+  }
+
+  pub fn debug(&self, message: impl Into<String>) {
+    self.log(LogLevel::Debug, message);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use crate::config::logging::{LogFormat, LogLevel, LoggingConfig};
+
+  use super::Logger;
+
+  #[test]
+  fn filter_records_by_target() {
+    let logger = Logger::new(&LoggingConfig {
+      level: LogLevel::Warn,
+      filters: HashMap::from([
+        ("farmfe_plugin_css".to_string(), LogLevel::Debug),
+        ("farmfe_plugin_css::modules".to_string(), LogLevel::Silent),
+        ("my-plugin".to_string(), LogLevel::Error),
+      ]),
+      ..Default::default()
+    });
+
+    assert!(logger.enabled(LogLevel::Warn, "farmfe_compiler"));
+    assert!(!logger.enabled(LogLevel::Info, "farmfe_compiler"));
+    assert!(logger.enabled(LogLevel::Debug, "farmfe_plugin_css::transform"));
+    assert!(!logger.enabled(LogLevel::Error, "farmfe_plugin_css::modules"));
+
+    logger.plugin("my-plugin").warn("filtered");
+    logger.plugin("my-plugin").error("kept");
+    logger.warn("farmfe_compiler", "not captured");
+
+    let records = logger.take_plugin_records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].plugin.as_deref(), Some("my-plugin"));
+    assert_eq!(records[0].message, "kept");
+    assert!(logger.take_plugin_records().is_empty());
+  }
+
+  #[test]
+  fn format_records() {
+    let text = Logger::new(&LoggingConfig::default());
+    let json = Logger::new(&LoggingConfig {
+      format: LogFormat::Json,
+      ..Default::default()
+    });
+
+    json.plugin("my-plugin").info("hello");
+    let record = json.take_plugin_records().remove(0);
+
+    assert_eq!(text.format(&record), "[farm] INFO [my-plugin] hello");
+
+    let value: serde_json::Value = serde_json::from_str(&json.format(&record)).unwrap();
+    assert_eq!(value["level"], "info");
+    assert_eq!(value["target"], "my-plugin");
+    assert_eq!(value["plugin"], "my-plugin");
+    assert_eq!(value["message"], "hello");
+  }
+}
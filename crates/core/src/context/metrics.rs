@@ -0,0 +1,259 @@
+use std::{
+  sync::atomic::{AtomicU64, Ordering},
+  time::Duration,
+};
+
+use parking_lot::Mutex;
+
+use super::progress::BuildProgress;
+
+/// upper bounds of the buckets of the duration histograms, in seconds
+const DURATION_BUCKETS: [f64; 10] = [0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Cumulative histogram of durations in seconds
+#[derive(Default)]
+struct Histogram {
+  buckets: [u64; DURATION_BUCKETS.len()],
+  count: u64,
+  sum: f64,
+}
+
+impl Histogram {
+  fn observe(&mut self, duration: Duration) {
+    let seconds = duration.as_secs_f64();
+
+    for (bucket, bound) in self.buckets.iter_mut().zip(DURATION_BUCKETS) {
+      if seconds <= bound {
+        *bucket += 1;
+      }
+    }
+
+    self.count += 1;
+    self.sum += seconds;
+  }
+
+  fn render(&self, name: &str, help: &str, out: &mut Vec<String>) {
+    out.push(format!("# HELP {name} {help}"));
+    out.push(format!("# TYPE {name} histogram"));
+
+    for (bucket, bound) in self.buckets.iter().zip(DURATION_BUCKETS) {
+      out.push(format!("{name}_bucket{{le=\"{bound}\"}} {bucket}"));
+    }
+
+    out.push(format!("{name}_bucket{{le=\"+Inf\"}} {}", self.count));
+    out.push(format!("{name}_sum {}", self.sum));
+    out.push(format!("{name}_count {}", self.count));
+  }
+}
+
+/// Current size of the compilation, read when the metrics are rendered
+#[derive(Debug, Clone, Default)]
+pub struct MetricsGauges {
+  pub modules: usize,
+  pub edges: usize,
+  pub resources: usize,
+  /// estimated bytes held by the compilation: the content of the modules and the bytes of the resources
+  pub memory_bytes: usize,
+}
+
+/// Counters and histograms of the builds and hmr updates, so the health of many long-running dev servers and daemons
+/// can be monitored centrally. They are rendered in the Prometheus text format by [CompilerMetrics::render]
+#[derive(Default)]
+pub struct CompilerMetrics {
+  builds: AtomicU64,
+  build_failures: AtomicU64,
+  updates: AtomicU64,
+  update_failures: AtomicU64,
+  cache_hits: AtomicU64,
+  cache_misses: AtomicU64,
+  build_duration: Mutex<Histogram>,
+  update_duration: Mutex<Histogram>,
+}
+
+impl CompilerMetrics {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Record a finished build, the modules restored from the persistent cache are the cache hits and the transformed ones the misses
+  pub fn build_finished(&self, duration: Duration, succeeded: bool, progress: &BuildProgress) {
+    self.builds.fetch_add(1, Ordering::Relaxed);
+
+    if !succeeded {
+      self.build_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    self
+      .cache_hits
+      .fetch_add(progress.modules_cached as u64, Ordering::Relaxed);
+    self
+      .cache_misses
+      .fetch_add(progress.modules_transformed as u64, Ordering::Relaxed);
+    self.build_duration.lock().observe(duration);
+  }
+
+  /// Record a finished hmr update, `duration` is the latency until the update result is returned
+  pub fn update_finished(&self, duration: Duration, succeeded: bool) {
+    self.updates.fetch_add(1, Ordering::Relaxed);
+
+    if !succeeded {
+      self.update_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    self.update_duration.lock().observe(duration);
+  }
+
+  /// The metrics and the gauges in the Prometheus text exposition format
+  pub fn render(&self, gauges: &MetricsGauges) -> String {
+    let mut out = vec![];
+    let mut metric = |name: &str, ty: &str, help: &str, value: String| {
+      out.push(format!("# HELP {name} {help}"));
+      out.push(format!("# TYPE {name} {ty}"));
+      out.push(format!("{name} {value}"));
+    };
+    let counter = |value: &AtomicU64| value.load(Ordering::Relaxed).to_string();
+
+    metric(
+      "farm_builds_total",
+      "counter",
+      "Number of the full builds",
+      counter(&self.builds),
+    );
+    metric(
+      "farm_build_failures_total",
+      "counter",
+      "Number of the full builds that failed",
+      counter(&self.build_failures),
+    );
+    metric(
+      "farm_hmr_updates_total",
+      "counter",
+      "Number of the hmr updates",
+      counter(&self.updates),
+    );
+    metric(
+      "farm_hmr_update_failures_total",
+      "counter",
+      "Number of the hmr updates that failed",
+      counter(&self.update_failures),
+    );
+
+    let hits = self.cache_hits.load(Ordering::Relaxed);
+    let misses = self.cache_misses.load(Ordering::Relaxed);
+    metric(
+      "farm_cache_hits_total",
+      "counter",
+      "Modules restored from the persistent cache by the builds",
+      hits.to_string(),
+    );
+    metric(
+      "farm_cache_misses_total",
+      "counter",
+      "Modules transformed by the builds",
+      misses.to_string(),
+    );
+    metric(
+      "farm_cache_hit_ratio",
+      "gauge",
+      "Ratio of the modules restored from the persistent cache by the builds",
+      if hits + misses == 0 {
+        "0".to_string()
+      } else {
+        (hits as f64 / (hits + misses) as f64).to_string()
+      },
+    );
+
+    metric(
+      "farm_modules",
+      "gauge",
+      "Modules of the module graph",
+      gauges.modules.to_string(),
+    );
+    metric(
+      "farm_module_graph_edges",
+      "gauge",
+      "Edges of the module graph",
+      gauges.edges.to_string(),
+    );
+    metric(
+      "farm_resources",
+      "gauge",
+      "Generated resources",
+      gauges.resources.to_string(),
+    );
+    metric(
+      "farm_memory_estimate_bytes",
+      "gauge",
+      "Estimated bytes of the module contents and the resources held in memory",
+      gauges.memory_bytes.to_string(),
+    );
+
+    self.build_duration.lock().render(
+      "farm_build_duration_seconds",
+      "Duration of the full builds",
+      &mut out,
+    );
+    self.update_duration.lock().render(
+      "farm_hmr_update_duration_seconds",
+      "Latency of the hmr updates",
+      &mut out,
+    );
+
+    out.push(String::new());
+    out.join("\n")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::{CompilerMetrics, MetricsGauges};
+  use crate::context::progress::BuildProgress;
+
+  #[test]
+  fn render() {
+    let metrics = CompilerMetrics::new();
+    metrics.build_finished(
+      Duration::from_millis(300),
+      true,
+      &BuildProgress {
+        modules_cached: 3,
+        modules_transformed: 1,
+        ..Default::default()
+      },
+    );
+    metrics.update_finished(Duration::from_millis(20), true);
+    metrics.update_finished(Duration::from_millis(80), false);
+
+    let rendered = metrics.render(&MetricsGauges {
+      modules: 4,
+      edges: 3,
+      resources: 2,
+      memory_bytes: 1024,
+    });
+    let lines = rendered.lines().collect::<Vec<_>>();
+
+    for line in [
+      "# TYPE farm_builds_total counter",
+      "farm_builds_total 1",
+      "farm_build_failures_total 0",
+      "farm_hmr_updates_total 2",
+      "farm_hmr_update_failures_total 1",
+      "farm_cache_hits_total 3",
+      "farm_cache_misses_total 1",
+      "farm_cache_hit_ratio 0.75",
+      "farm_modules 4",
+      "farm_memory_estimate_bytes 1024",
+      "# TYPE farm_build_duration_seconds histogram",
+      "farm_build_duration_seconds_bucket{le=\"0.25\"} 0",
+      "farm_build_duration_seconds_bucket{le=\"0.5\"} 1",
+      "farm_hmr_update_duration_seconds_bucket{le=\"0.025\"} 1",
+      "farm_hmr_update_duration_seconds_bucket{le=\"0.1\"} 2",
+      "farm_hmr_update_duration_seconds_bucket{le=\"+Inf\"} 2",
+      "farm_hmr_update_duration_seconds_count 2",
+    ] {
+      assert!(lines.contains(&line), "{line} is not in\n{rendered}");
+    }
+  }
+}
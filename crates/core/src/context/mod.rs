@@ -1,70 +1,215 @@
-use std::{any::Any, path::Path, sync::Arc};
+use std::{
+  any::Any,
+  cell::Cell,
+  panic::AssertUnwindSafe,
+  path::Path,
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  },
+  time::Duration,
+};
 
 use dashmap::DashMap;
+use farmfe_utils::hash::sha256;
 use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use swc_common::Globals;
 
 use crate::{
-  cache::CacheManager,
-  config::{persistent_cache::PersistentCacheConfig, Config},
-  error::Result,
+  cache::{global_cache::GlobalCacheStore, CacheManager},
+  config::{persistent_cache::PersistentCacheConfig, Config, Mode, ModuleFormat},
+  error::{CompilationError, Result},
   module::{
     module_graph::ModuleGraph, module_group::ModuleGroupGraph, watch_graph::WatchGraph, ModuleId,
   },
-  plugin::{plugin_driver::PluginDriver, Plugin, PluginResolveHookParam, PluginResolveHookResult},
-  resource::{resource_pot_map::ResourcePotMap, Resource, ResourceOrigin, ResourceType},
+  plugin::{
+    plugin_driver::{take_panicked_plugin, PluginDriver},
+    Plugin, PluginResolveHookParam, PluginResolveHookResult,
+  },
+  resource::{
+    emit_sink::{EmitSink, MemoryEmitSink},
+    resource_pot_map::ResourcePotMap,
+    Resource, ResourceOrigin, ResourceScope, ResourceType,
+  },
   stats::Stats,
 };
 
-use self::log_store::LogStore;
+use self::{
+  diagnostics::{Diagnostic, DiagnosticSeverity, DiagnosticStore},
+  event_bus::EventBus,
+  hmr_channel::HmrChannel,
+  id_generator::IdGenerator,
+  lock_tracker::TrackedRwLock,
+  log_store::LogStore,
+  logger::Logger,
+  metrics::CompilerMetrics,
+  module_graph_snapshot::ModuleGraphSnapshots,
+  progress::ProgressTracker,
+  update_schedule::{UpdateLock, UpdateScheduler},
+};
 
+pub mod diagnostics;
+pub mod event_bus;
+pub mod hmr_channel;
+pub mod id_generator;
+pub mod lock_tracker;
 pub mod log_store;
+pub mod logger;
+pub mod metrics;
+pub mod module_graph_snapshot;
+pub mod progress;
+pub mod update_schedule;
 pub(crate) const EMPTY_STR: &str = "";
 pub const IS_UPDATE: &str = "";
+/// suffix of the virtual css modules emitted by [CompilationContext::emit_extracted_css]
+pub const EXTRACTED_CSS_SUFFIX: &str = ".farm-extracted.css";
+/// code of the diagnostics reported by [CompilationContext::catch_panic]
+pub const PANIC_DIAGNOSTIC_CODE: &str = "panic";
 
 /// Shared context through the whole compilation.
 pub struct CompilationContext {
   pub config: Box<Config>,
-  pub watch_graph: Box<RwLock<WatchGraph>>,
-  pub module_graph: Box<RwLock<ModuleGraph>>,
-  pub module_group_graph: Box<RwLock<ModuleGroupGraph>>,
+  pub watch_graph: Box<TrackedRwLock<WatchGraph>>,
+  pub module_graph: Box<TrackedRwLock<ModuleGraph>>,
+  /// snapshots of the module graph for long-running queries, see [CompilationContext::module_graph_snapshot]
+  pub module_graph_snapshots: Box<ModuleGraphSnapshots>,
+  pub module_group_graph: Box<TrackedRwLock<ModuleGroupGraph>>,
   pub plugin_driver: Box<PluginDriver>,
-  pub resource_pot_map: Box<RwLock<ResourcePotMap>>,
-  pub resources_map: Box<Mutex<HashMap<String, Resource>>>,
+  pub resource_pot_map: Box<TrackedRwLock<ResourcePotMap>>,
+  /// A concurrent map sharded by the resource name, so the resources of different resource pots are inserted in parallel
+  /// and the dev server serves the resources while they are regenerated without waiting for a global lock
+  pub resources_map: Box<DashMap<String, Resource>>,
   pub cache_manager: Box<CacheManager>,
   pub meta: Box<ContextMetaData>,
   /// Record stats for the compilation, for example, compilation time, plugin hook time, etc.
   pub record_manager: Box<Stats>,
   pub log_store: Box<Mutex<LogStore>>,
+  /// leveled logs of the compiler and plugins, see [Logger]
+  pub logger: Box<Logger>,
+  /// structured problems found by analysis passes, see [DiagnosticStore]
+  pub diagnostics: Box<Mutex<DiagnosticStore>>,
   pub resolve_cache: Box<Mutex<HashMap<PluginResolveHookParam, PluginResolveHookResult>>>,
+  /// css extracted from script modules by css-in-js plugins, module id of the virtual css module -> css
+  pub extracted_css: Box<DashMap<String, String>>,
+  /// modules invalidated by [CompilationContext::invalidate_module] that are not recompiled yet, module id -> recompile dependents
+  pub invalidated_modules: Box<Mutex<HashMap<ModuleId, bool>>>,
+  /// number of running interactive updates, background compilations yield to them, see [UpdatePriority]
+  pub pending_interactive_updates: Box<AtomicUsize>,
+  /// deterministic ids for plugins, see [IdGenerator]
+  pub id_generator: Box<IdGenerator>,
+  /// typed topics for plugins to exchange data, see [EventBus]
+  pub event_bus: Box<EventBus>,
+  /// custom events between the plugins and the hmr clients, see [HmrChannel]
+  pub hmr_channel: Box<HmrChannel>,
+  /// where the finalized resources are emitted, defaults to [MemoryEmitSink]
+  pub emit_sink: Box<RwLock<Arc<dyn EmitSink>>>,
+  /// resource name -> fingerprint of the content last emitted to the sink, updates only emit the changed resources
+  pub emitted_resources: Box<DashMap<String, String>>,
+  /// progress of the current build, see [ProgressTracker]
+  pub progress: Box<ProgressTracker>,
+  /// counters and histograms of the builds and updates, see [CompilerMetrics]
+  pub metrics: Box<CompilerMetrics>,
+  /// when the resources of the updates are regenerated, see [UpdateScheduler]
+  pub update_scheduler: Box<UpdateScheduler>,
+  /// only one update runs at a time, see [UpdateLock]
+  pub update_lock: Arc<UpdateLock>,
   pub custom: Box<DashMap<String, Box<dyn Any + Send + Sync>>>,
 }
 
 impl CompilationContext {
+  /// The `config` hooks of the plugins are called before anything is created from the config,
+  /// and the `config_resolved` hooks after the config is normalized
   pub fn new(mut config: Config, plugins: Vec<Arc<dyn Plugin>>) -> Result<Self> {
+    let plugin_driver = Self::create_plugin_driver(plugins, config.record);
+    plugin_driver.config(&mut config)?;
+
+    Self::validate_native_esm_config(&config)?;
     let (cache_dir, namespace) = Self::normalize_persistent_cache_config(&mut config);
+    plugin_driver.config_resolved(&config)?;
+    let mut cache_manager = CacheManager::new(
+      &cache_dir,
+      &namespace,
+      config.mode.clone(),
+      plugin_driver.cache_migrations(),
+    );
 
-    Ok(Self {
-      watch_graph: Box::new(RwLock::new(WatchGraph::new())),
-      module_graph: Box::new(RwLock::new(ModuleGraph::new())),
-      module_group_graph: Box::new(RwLock::new(ModuleGroupGraph::new())),
-      resource_pot_map: Box::new(RwLock::new(ResourcePotMap::new())),
-      resources_map: Box::new(Mutex::new(HashMap::new())),
-      plugin_driver: Box::new(Self::create_plugin_driver(plugins, config.record)),
-      cache_manager: Box::new(CacheManager::new(
-        &cache_dir,
-        &namespace,
-        config.mode.clone(),
+    if config.persistent_cache.enabled() {
+      if let Some(global_cache) = &config.persistent_cache.as_raw_object().global_cache {
+        let options_hash = Self::global_cache_options_hash(&config, &plugin_driver);
+        let global_cache = Arc::new(GlobalCacheStore::new(global_cache, options_hash));
+        cache_manager
+          .module_cache
+          .immutable_modules_store
+          .enable_global_cache(global_cache.clone(), config.root.clone());
+        cache_manager.global_cache = Some(global_cache);
+      }
+    }
+
+    Ok(Self::from_parts(config, plugin_driver, cache_manager))
+  }
+
+  /// A context with the resolved config and the plugins of this context, but its own module graph, watch graph, resources and caches,
+  /// so the hooks called with it can not change this compilation, e.g. when an update is planned. The persistent cache is disabled
+  /// and the `config` hooks are not called again
+  pub fn isolated(&self) -> Self {
+    let mut config = self.config.as_ref().clone();
+    config.persistent_cache = Box::new(PersistentCacheConfig::Bool(false));
+    let plugin_driver = Self::create_plugin_driver(self.plugin_driver.plugins.clone(), false);
+    let cache_manager = CacheManager::new(
+      EMPTY_STR,
+      EMPTY_STR,
+      config.mode.clone(),
+      plugin_driver.cache_migrations(),
+    );
+
+    Self::from_parts(config, plugin_driver, cache_manager)
+  }
+
+  fn from_parts(config: Config, plugin_driver: PluginDriver, cache_manager: CacheManager) -> Self {
+    Self {
+      watch_graph: Box::new(TrackedRwLock::new("watch_graph", WatchGraph::new())),
+      module_graph: Box::new(TrackedRwLock::new("module_graph", ModuleGraph::new())),
+      module_graph_snapshots: Box::new(ModuleGraphSnapshots::new()),
+      module_group_graph: Box::new(TrackedRwLock::new(
+        "module_group_graph",
+        ModuleGroupGraph::new(),
       )),
+      resource_pot_map: Box::new(TrackedRwLock::new(
+        "resource_pot_map",
+        ResourcePotMap::new(),
+      )),
+      resources_map: Box::new(DashMap::new()),
+      plugin_driver: Box::new(plugin_driver),
+      id_generator: Box::new(IdGenerator::new(&config.hash)),
+      event_bus: Box::new(EventBus::new()),
+      hmr_channel: Box::new(HmrChannel::default()),
+      logger: Box::new(Logger::new(&config.logging)),
+      emit_sink: Box::new(RwLock::new(Arc::new(MemoryEmitSink))),
+      emitted_resources: Box::new(DashMap::new()),
+      progress: Box::new(ProgressTracker::new()),
+      metrics: Box::new(CompilerMetrics::new()),
+      update_scheduler: Box::new(UpdateScheduler::default()),
+      update_lock: Arc::new(UpdateLock::default()),
+      cache_manager: Box::new(cache_manager),
       config: Box::new(config),
       meta: Box::new(ContextMetaData::new()),
       record_manager: Box::new(Stats::new()),
       log_store: Box::new(Mutex::new(LogStore::new())),
+      diagnostics: Box::new(Mutex::new(DiagnosticStore::new())),
       resolve_cache: Box::new(Mutex::new(HashMap::new())),
+      extracted_css: Box::new(DashMap::new()),
+      invalidated_modules: Box::new(Mutex::new(HashMap::new())),
+      pending_interactive_updates: Box::new(AtomicUsize::new(0)),
       custom: Box::new(DashMap::new()),
-    })
+    }
+  }
+
+  /// Snapshot of the module graph without the meta data of the modules. Queries that walk the whole graph should use it
+  /// instead of holding the read lock of [CompilationContext::module_graph], which blocks the updates
+  pub fn module_graph_snapshot(&self) -> Arc<ModuleGraph> {
+    self.module_graph_snapshots.get(&self.module_graph)
   }
 
   pub fn set_update(&self) {
@@ -79,6 +224,59 @@ impl CompilationContext {
     PluginDriver::new(plugins, record)
   }
 
+  /// Hash of the options that affect the transformed result of node_modules, projects share the global cache only when they are the same.
+  /// The plugins are identified by their names and options, see [PluginDriver::plugins_cache_key]
+  fn global_cache_options_hash(config: &Config, plugin_driver: &PluginDriver) -> String {
+    let define = config.define.iter().collect::<BTreeMap<_, _>>();
+    let plugins = plugin_driver.plugins_cache_key();
+    let options = serde_json::to_string(&(
+      &config.mode,
+      &config.output.target_env,
+      &config.script,
+      &config.css,
+      &config.preset_env,
+      &config.minify,
+      &config.hash,
+      define,
+      plugins,
+    ))
+    .unwrap_or_default();
+
+    sha256(options.as_bytes(), 32)
+  }
+
+  /// `output.nativeEsm` renders the resource pots as ES modules without the runtime, so it requires `output.format: esm`
+  /// and the features that rely on the runtime can not be enabled with it
+  fn validate_native_esm_config(config: &Config) -> Result<()> {
+    if !config.output.native_esm {
+      return Ok(());
+    }
+
+    if config.output.format != ModuleFormat::EsModule {
+      return Err(CompilationError::GenericError(
+        "output.nativeEsm requires output.format to be esm, the resource pots are emitted as ES modules".to_string(),
+      ));
+    }
+
+    let runtime_features = [
+      (matches!(config.mode, Mode::Development), "development mode"),
+      (config.lazy_compilation, "lazyCompilation"),
+    ]
+    .into_iter()
+    .filter(|(enabled, _)| *enabled)
+    .map(|(_, feature)| feature)
+    .collect::<Vec<_>>();
+
+    if runtime_features.is_empty() {
+      return Ok(());
+    }
+
+    Err(CompilationError::GenericError(format!(
+      "output.nativeEsm can not be used with {}, they require the Farm runtime that native esm builds do not include",
+      runtime_features.join(" and ")
+    )))
+  }
+
   pub fn normalize_persistent_cache_config(config: &mut Config) -> (String, String) {
     if config.persistent_cache.enabled() {
       let cache_config_obj = config.persistent_cache.as_obj(&config.root);
@@ -123,11 +321,9 @@ impl CompilationContext {
   }
 
   pub fn emit_file(&self, params: EmitFileParams) {
-    let mut resources_map = self.resources_map.lock();
-
     let module_id = self.str_to_module_id(&params.resolved_path);
 
-    resources_map.insert(
+    self.resources_map.insert(
       params.name.clone(),
       Resource {
         name: params.name,
@@ -135,18 +331,91 @@ impl CompilationContext {
         emitted: false,
         resource_type: params.resource_type,
         origin: ResourceOrigin::Module(module_id),
+        source_path: None,
+        scope: params.scope,
         info: None,
       },
     );
   }
 
-  pub fn sourcemap_enabled(&self, id: &str) -> bool {
-    let immutable = self
-      .config
-      .partial_bundling
-      .immutable_modules
+  /// Replace the sink of the resources, e.g. [crate::resource::emit_sink::FsEmitSink] to write the resources to disk
+  /// or a custom sink that uploads them to a cdn. All the resources are emitted to the new sink again
+  pub fn set_emit_sink(&self, sink: Arc<dyn EmitSink>) {
+    *self.emit_sink.write() = sink;
+    self.emitted_resources.clear();
+  }
+
+  /// Move the resources out of `resources_map` for the hooks that change the whole map at once, e.g. `finalize_resources`.
+  /// The resources are not cloned, so readers do not see them until they are put back by [Self::put_resources]
+  pub fn take_resources(&self) -> HashMap<String, Resource> {
+    let names = self
+      .resources_map
       .iter()
-      .any(|im| im.is_match(id));
+      .map(|resource| resource.key().clone())
+      .collect::<Vec<_>>();
+
+    names
+      .into_iter()
+      .filter_map(|name| self.resources_map.remove(&name))
+      .collect()
+  }
+
+  /// Put back the resources taken by [Self::take_resources], resources inserted by other threads meanwhile are kept
+  pub fn put_resources(&self, resources: HashMap<String, Resource>) {
+    for (name, resource) in resources {
+      self.resources_map.insert(name, resource);
+    }
+  }
+
+  /// Emit a large file without loading it into memory, the file is copied from `source_path` when resources are written to disk.
+  /// `params.content` is ignored.
+  pub fn emit_file_from_path(&self, params: EmitFileParams, source_path: String) {
+    let module_id = self.str_to_module_id(&params.resolved_path);
+
+    self.resources_map.insert(
+      params.name.clone(),
+      Resource {
+        name: params.name,
+        bytes: vec![],
+        emitted: false,
+        resource_type: params.resource_type,
+        origin: ResourceOrigin::Module(module_id),
+        source_path: Some(source_path),
+        scope: params.scope,
+        info: None,
+      },
+    );
+  }
+
+  /// Emit css extracted from a script module, used by zero-runtime css-in-js plugins.
+  /// Returns the source of the virtual css module, the caller should import it from the transformed script, e.g. `import "${source}";`,
+  /// then the extracted css is handled by the css pipeline like a normal css file.
+  /// The source contains the hash of the css, so the css module is replaced when the css changes, which triggers css hmr.
+  pub fn emit_extracted_css(&self, importer: &ModuleId, css: String) -> String {
+    let resolved_path = format!(
+      "{}{EXTRACTED_CSS_SUFFIX}",
+      importer.resolved_path(&self.config.root)
+    );
+    let query = format!("?hash={}", self.config.hash.hash(css.as_bytes(), 8));
+    let module_id = ModuleId::new(&resolved_path, &query, &self.config.root);
+
+    self.extracted_css.insert(module_id.to_string(), css);
+
+    format!("{resolved_path}{query}")
+  }
+
+  /// Drop the extracted css whose virtual css module is not in the module graph any more,
+  /// e.g. the css of a removed importer or of a previous version of the css
+  pub fn prune_extracted_css(&self) {
+    let module_graph = self.module_graph.read();
+
+    self
+      .extracted_css
+      .retain(|module_id, _| module_graph.has_module(&module_id.as_str().into()));
+  }
+
+  pub fn sourcemap_enabled(&self, id: &str) -> bool {
+    let immutable = self.config.is_immutable_module(&id.into());
 
     self.config.sourcemap.enabled(immutable)
   }
@@ -164,14 +433,114 @@ impl CompilationContext {
     resolve_cache.insert(param, result);
   }
 
-  pub fn invalidate_module(&self, module_id: &ModuleId) {
+  /// Invalidate the cache of the module and schedule it to be recompiled by the next update, like the file is changed.
+  /// Useful for plugins whose outputs depend on external state
+  pub fn invalidate_module(&self, module_id: &ModuleId, options: InvalidateModuleOptions) {
     self.cache_manager.module_cache.invalidate_cache(module_id);
+
+    let mut invalidated_modules = self.invalidated_modules.lock();
+    let recompile_dependents = invalidated_modules
+      .entry(module_id.clone())
+      .or_insert(false);
+    *recompile_dependents |= options.recompile_dependents;
+  }
+
+  /// Take the modules scheduled by [CompilationContext::invalidate_module], module id -> recompile dependents
+  pub fn take_invalidated_modules(&self) -> HashMap<ModuleId, bool> {
+    std::mem::take(&mut *self.invalidated_modules.lock())
+  }
+
+  /// Mark an interactive update as running until the returned guard is dropped
+  pub fn begin_interactive_update(&self) -> InteractiveUpdateGuard<'_> {
+    self
+      .pending_interactive_updates
+      .fetch_add(1, Ordering::AcqRel);
+
+    InteractiveUpdateGuard {
+      pending: &self.pending_interactive_updates,
+    }
+  }
+
+  pub fn has_pending_interactive_updates(&self) -> bool {
+    self.pending_interactive_updates.load(Ordering::Acquire) > 0
+  }
+
+  /// Called by background compilations at module boundaries.
+  /// Runs other jobs of the thread pool, e.g. the modules of the interactive update, until no interactive update is running.
+  pub fn yield_to_interactive_updates(&self) {
+    thread_local! {
+      static YIELDING: Cell<bool> = const { Cell::new(false) };
+    }
+
+    // background jobs executed while yielding do not yield again, so the stack does not grow
+    if YIELDING.get() {
+      return;
+    }
+
+    YIELDING.set(true);
+
+    while self.has_pending_interactive_updates() {
+      if rayon::yield_now() != Some(rayon::Yield::Executed) {
+        std::thread::sleep(Duration::from_millis(1));
+      }
+    }
+
+    YIELDING.set(false);
   }
 
   pub fn clear_log_store(&self) {
     let mut log_store = self.log_store.lock();
     log_store.clear();
   }
+
+  /// Run `f` and convert its panic to a [CompilationError::PanicError] and an error diagnostic of `modules`,
+  /// so a problematic plugin fails the module or resource pot instead of aborting the whole process.
+  /// `target` describes the work, e.g. `building module src/index.ts`.
+  ///
+  /// Nothing that `f` mutated is rolled back. The locks of the context are not poisoned, their guards are released while unwinding,
+  /// so the shared state stays usable but may be half updated by the panicking plugin. Callers reset what they own:
+  /// a module that panics is dropped and stays a placeholder in the module graph like a module that fails with an error,
+  /// a resource pot that panics has its rendered meta reset. Panics are only caught when built with `panic = "unwind"`
+  pub fn catch_panic<R>(
+    &self,
+    target: impl FnOnce() -> String,
+    modules: impl FnOnce() -> Vec<ModuleId>,
+    f: impl FnOnce() -> Result<R>,
+  ) -> Result<R> {
+    let payload = match std::panic::catch_unwind(AssertUnwindSafe(f)) {
+      Ok(result) => return result,
+      Err(payload) => payload,
+    };
+
+    let msg = panic_message(payload);
+    let plugin = take_panicked_plugin();
+    let error = CompilationError::PanicError {
+      target: target(),
+      plugin: plugin.clone(),
+      msg,
+    };
+
+    self.diagnostics.lock().add(Diagnostic {
+      code: PANIC_DIAGNOSTIC_CODE.to_string(),
+      severity: DiagnosticSeverity::Error,
+      message: error.to_string(),
+      modules: modules(),
+      plugin,
+    });
+
+    Err(error)
+  }
+}
+
+/// The message of a panic caught by [std::panic::catch_unwind]
+pub fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+  if let Some(msg) = payload.downcast_ref::<&str>() {
+    msg.to_string()
+  } else if let Some(msg) = payload.downcast_ref::<String>() {
+    msg.clone()
+  } else {
+    "unknown panic".to_string()
+  }
 }
 
 impl Default for CompilationContext {
@@ -270,6 +639,35 @@ pub struct EmitFileParams {
   pub name: String,
   pub content: Vec<u8>,
   pub resource_type: ResourceType,
+  #[serde(default)]
+  pub scope: ResourceScope,
+}
+
+/// Interactive updates, e.g. hmr updates triggered by editing, preempt background compilations like lazy compilation,
+/// so editing latency stays low while the background compilation catches up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UpdatePriority {
+  #[default]
+  Interactive,
+  Background,
+}
+
+pub struct InteractiveUpdateGuard<'a> {
+  pending: &'a AtomicUsize,
+}
+
+impl Drop for InteractiveUpdateGuard<'_> {
+  fn drop(&mut self) {
+    self.pending.fetch_sub(1, Ordering::AcqRel);
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InvalidateModuleOptions {
+  /// recompile the modules that import the invalidated module as well
+  pub recompile_dependents: bool,
 }
 
 #[cfg(test)]
@@ -304,4 +702,113 @@ mod tests {
       assert_eq!(r, vec![&a, &vc]);
     }
   }
+
+  mod emit_extracted_css {
+    use crate::module::{Module, ModuleId};
+
+    use super::super::CompilationContext;
+
+    #[test]
+    fn hash_changes_with_css() {
+      let context = CompilationContext::default();
+      let importer: ModuleId = "src/button.tsx".into();
+
+      let source = context.emit_extracted_css(&importer, ".a { color: red }".to_string());
+      let (path, query) = source.split_once('?').unwrap();
+      assert!(path.ends_with("src/button.tsx.farm-extracted.css"));
+
+      let module_id = ModuleId::new(path, &format!("?{query}"), &context.config.root);
+      assert_eq!(
+        context
+          .extracted_css
+          .get(&module_id.to_string())
+          .unwrap()
+          .as_str(),
+        ".a { color: red }"
+      );
+
+      let updated = context.emit_extracted_css(&importer, ".a { color: blue }".to_string());
+      assert_ne!(source, updated);
+    }
+
+    #[test]
+    fn prune_removed_modules() {
+      let context = CompilationContext::default();
+      let importer: ModuleId = "src/button.tsx".into();
+
+      let stale = context.emit_extracted_css(&importer, ".a { color: red }".to_string());
+      let current = context.emit_extracted_css(&importer, ".a { color: blue }".to_string());
+      let module_id = |source: &str| {
+        let (path, query) = source.split_once('?').unwrap();
+        ModuleId::new(path, &format!("?{query}"), &context.config.root)
+      };
+      context
+        .module_graph
+        .write()
+        .add_module(Module::new(module_id(&current)));
+
+      context.prune_extracted_css();
+
+      assert!(!context
+        .extracted_css
+        .contains_key(&module_id(&stale).to_string()));
+      assert!(context
+        .extracted_css
+        .contains_key(&module_id(&current).to_string()));
+    }
+  }
+
+  mod interactive_updates {
+    use super::super::CompilationContext;
+
+    #[test]
+    fn guard_tracks_running_updates() {
+      let context = CompilationContext::default();
+      assert!(!context.has_pending_interactive_updates());
+
+      let guard = context.begin_interactive_update();
+      let another = context.begin_interactive_update();
+      assert!(context.has_pending_interactive_updates());
+
+      drop(guard);
+      assert!(context.has_pending_interactive_updates());
+      drop(another);
+      assert!(!context.has_pending_interactive_updates());
+
+      // returns immediately when there is no interactive update
+      context.yield_to_interactive_updates();
+    }
+  }
+
+  mod global_cache_options_hash {
+    use std::sync::Arc;
+
+    use crate::{config::Config, plugin::Plugin};
+
+    use super::super::CompilationContext;
+
+    struct OptionsPlugin(&'static str);
+
+    impl Plugin for OptionsPlugin {
+      fn name(&self) -> &str {
+        "OptionsPlugin"
+      }
+
+      fn cache_key(&self) -> Option<String> {
+        Some(self.0.to_string())
+      }
+    }
+
+    #[test]
+    fn hash_plugin_options() {
+      let hash = |options| {
+        let plugin_driver =
+          CompilationContext::create_plugin_driver(vec![Arc::new(OptionsPlugin(options))], false);
+        CompilationContext::global_cache_options_hash(&Config::default(), &plugin_driver)
+      };
+
+      assert_eq!(hash("a"), hash("a"));
+      assert_ne!(hash("a"), hash("b"));
+    }
+  }
 }
@@ -0,0 +1,91 @@
+use std::sync::{
+  atomic::{AtomicUsize, Ordering},
+  Arc,
+};
+
+use parking_lot::Mutex;
+
+use super::lock_tracker::TrackedRwLock;
+use crate::module::module_graph::ModuleGraph;
+
+/// Copy-on-write snapshots of the module graph for long-running analysis queries, e.g. import chains, graph visualization and stats,
+/// so they never block the updates waiting for the write lock of the module graph on big graphs.
+/// A snapshot is taken on demand and reused until the module graph is changed by a compilation or an update, see [ModuleGraphSnapshots::invalidate]
+#[derive(Default)]
+pub struct ModuleGraphSnapshots {
+  /// bumped every time the module graph is changed
+  generation: AtomicUsize,
+  /// (generation, snapshot) of the latest snapshot
+  latest: Mutex<Option<(usize, Arc<ModuleGraph>)>>,
+}
+
+impl ModuleGraphSnapshots {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn generation(&self) -> usize {
+    self.generation.load(Ordering::Acquire)
+  }
+
+  /// Mark the snapshots stale after the module graph is changed, the next query takes a new snapshot
+  pub fn invalidate(&self) {
+    self.generation.fetch_add(1, Ordering::AcqRel);
+  }
+
+  /// The snapshot of the current generation. When the module graph is being written by an update,
+  /// the stale snapshot is returned instead of waiting for the update
+  pub fn get(&self, module_graph: &TrackedRwLock<ModuleGraph>) -> Arc<ModuleGraph> {
+    let generation = self.generation();
+    let mut latest = self.latest.lock();
+
+    if let Some((latest_generation, snapshot)) = &*latest {
+      if *latest_generation == generation {
+        return snapshot.clone();
+      }
+    }
+
+    let module_graph = match (module_graph.try_read(), &*latest) {
+      (Some(module_graph), _) => module_graph,
+      (None, Some((_, snapshot))) => return snapshot.clone(),
+      (None, None) => module_graph.read(),
+    };
+
+    let snapshot = Arc::new(module_graph.snapshot());
+    *latest = Some((generation, snapshot.clone()));
+
+    snapshot
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use super::ModuleGraphSnapshots;
+  use crate::{
+    context::lock_tracker::TrackedRwLock,
+    module::{module_graph::ModuleGraph, Module},
+  };
+
+  #[test]
+  fn reuse_snapshot_until_invalidated() {
+    let module_graph = TrackedRwLock::new("module_graph", ModuleGraph::new());
+    module_graph.write().add_module(Module::new("a".into()));
+    let snapshots = ModuleGraphSnapshots::new();
+
+    let snapshot = snapshots.get(&module_graph);
+    assert!(snapshot.has_module(&"a".into()));
+    assert!(Arc::ptr_eq(&snapshot, &snapshots.get(&module_graph)));
+
+    let mut writer = module_graph.write();
+    writer.add_module(Module::new("b".into()));
+    snapshots.invalidate();
+    // the stale snapshot is returned while the module graph is being written
+    assert!(Arc::ptr_eq(&snapshot, &snapshots.get(&module_graph)));
+    drop(writer);
+
+    let snapshot = snapshots.get(&module_graph);
+    assert!(snapshot.has_module(&"b".into()));
+  }
+}
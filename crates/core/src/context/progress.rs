@@ -0,0 +1,205 @@
+use std::{
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  },
+  time::{Duration, Instant},
+};
+
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+
+/// listeners are called at most once per interval unless the phase changes
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+type ProgressListener = Arc<dyn Fn(&BuildProgress) + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BuildPhase {
+  /// resolving, loading and transforming the modules
+  #[default]
+  Build,
+  /// optimizing the module graph and rendering the resource pots
+  Generate,
+  /// emitting the resources to the emit sink
+  Write,
+  Finished,
+}
+
+/// Counts of the work done by the current build
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildProgress {
+  pub phase: BuildPhase,
+  pub modules_resolved: usize,
+  pub modules_loaded: usize,
+  pub modules_transformed: usize,
+  /// modules restored from the persistent cache, they are not loaded or transformed
+  pub modules_cached: usize,
+  /// number of the resource pots to render, known after partial bundling
+  pub resource_pots: usize,
+  pub resource_pots_rendered: usize,
+  pub resources_written: usize,
+}
+
+/// Tracks the progress of builds and reports it to the listeners, e.g. the cli spinner and the js api.
+/// The counters are reset at the start of every build, work done outside of builds like hmr updates is not counted
+#[derive(Default)]
+pub struct ProgressTracker {
+  phase: Mutex<BuildPhase>,
+  modules_resolved: AtomicUsize,
+  modules_loaded: AtomicUsize,
+  modules_transformed: AtomicUsize,
+  modules_cached: AtomicUsize,
+  resource_pots: AtomicUsize,
+  resource_pots_rendered: AtomicUsize,
+  resources_written: AtomicUsize,
+  last_report: Mutex<Option<Instant>>,
+  listeners: RwLock<Vec<ProgressListener>>,
+}
+
+impl ProgressTracker {
+  pub fn new() -> Self {
+    Self {
+      phase: Mutex::new(BuildPhase::Finished),
+      ..Default::default()
+    }
+  }
+
+  pub fn subscribe(&self, f: impl Fn(&BuildProgress) + Send + Sync + 'static) {
+    self.listeners.write().push(Arc::new(f));
+  }
+
+  pub fn reset(&self) {
+    for counter in [
+      &self.modules_resolved,
+      &self.modules_loaded,
+      &self.modules_transformed,
+      &self.modules_cached,
+      &self.resource_pots,
+      &self.resource_pots_rendered,
+      &self.resources_written,
+    ] {
+      counter.store(0, Ordering::Relaxed);
+    }
+
+    *self.last_report.lock() = None;
+    self.set_phase(BuildPhase::Build);
+  }
+
+  pub fn set_phase(&self, phase: BuildPhase) {
+    *self.phase.lock() = phase;
+    self.report(true);
+  }
+
+  pub fn module_resolved(&self) {
+    self.increase(&self.modules_resolved);
+  }
+
+  pub fn module_loaded(&self) {
+    self.increase(&self.modules_loaded);
+  }
+
+  pub fn module_transformed(&self) {
+    self.increase(&self.modules_transformed);
+  }
+
+  pub fn module_cached(&self) {
+    self.increase(&self.modules_cached);
+  }
+
+  pub fn set_resource_pots(&self, count: usize) {
+    self.resource_pots.store(count, Ordering::Relaxed);
+    self.report(false);
+  }
+
+  pub fn resource_pot_rendered(&self) {
+    self.increase(&self.resource_pots_rendered);
+  }
+
+  pub fn resource_written(&self) {
+    self.increase(&self.resources_written);
+  }
+
+  pub fn progress(&self) -> BuildProgress {
+    BuildProgress {
+      phase: *self.phase.lock(),
+      modules_resolved: self.modules_resolved.load(Ordering::Relaxed),
+      modules_loaded: self.modules_loaded.load(Ordering::Relaxed),
+      modules_transformed: self.modules_transformed.load(Ordering::Relaxed),
+      modules_cached: self.modules_cached.load(Ordering::Relaxed),
+      resource_pots: self.resource_pots.load(Ordering::Relaxed),
+      resource_pots_rendered: self.resource_pots_rendered.load(Ordering::Relaxed),
+      resources_written: self.resources_written.load(Ordering::Relaxed),
+    }
+  }
+
+  fn increase(&self, counter: &AtomicUsize) {
+    if *self.phase.lock() == BuildPhase::Finished {
+      return;
+    }
+
+    counter.fetch_add(1, Ordering::Relaxed);
+    self.report(false);
+  }
+
+  fn report(&self, force: bool) {
+    if self.listeners.read().is_empty() {
+      return;
+    }
+
+    {
+      let mut last_report = self.last_report.lock();
+      let now = Instant::now();
+
+      if !force && last_report.is_some_and(|last| now - last < REPORT_INTERVAL) {
+        return;
+      }
+
+      *last_report = Some(now);
+    }
+
+    // listeners may take a while, the locks must not be held while they are called
+    let listeners = self.listeners.read().clone();
+    let progress = self.progress();
+
+    for listener in listeners {
+      listener(&progress);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::{Arc, Mutex};
+
+  use super::{BuildPhase, ProgressTracker};
+
+  #[test]
+  fn report_progress() {
+    let tracker = ProgressTracker::new();
+    let reported = Arc::new(Mutex::new(vec![]));
+    let reported_clone = reported.clone();
+    tracker.subscribe(move |progress| reported_clone.lock().unwrap().push(progress.clone()));
+
+    tracker.reset();
+    tracker.module_resolved();
+    tracker.module_loaded();
+    tracker.set_phase(BuildPhase::Write);
+    tracker.resource_written();
+    tracker.set_phase(BuildPhase::Finished);
+    tracker.module_resolved();
+
+    let reported = reported.lock().unwrap();
+    // the counters are throttled, phase changes are always reported
+    assert_eq!(reported.len(), 3);
+    assert_eq!(reported[0].phase, BuildPhase::Build);
+    assert_eq!(reported[1].phase, BuildPhase::Write);
+    assert_eq!(reported[1].modules_resolved, 1);
+    assert_eq!(reported[1].modules_loaded, 1);
+    assert_eq!(tracker.progress().resources_written, 1);
+    // not counted after the build is finished
+    assert_eq!(tracker.progress().modules_resolved, 1);
+  }
+}
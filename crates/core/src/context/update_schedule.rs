@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use parking_lot::{Condvar, Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+
+type DeferredUpdate = Box<dyn FnOnce() + Send>;
+
+/// When the resources of an update are regenerated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UpdateSchedule {
+  /// regenerate the resources when they are needed: before the next update or when [UpdateScheduler::flush] is called
+  Deferred,
+  /// regenerate the resources in a background thread, the update returns without waiting for them
+  Async,
+  /// regenerate the resources before the update returns, so they exist when the caller responds
+  Sync,
+}
+
+/// The schedule of each kind of update trigger. An update may have several triggers, e.g. a changed module that adds a
+/// dynamic import, the most urgent schedule of them is used. Updates requested as `sync` by the caller are always [UpdateSchedule::Sync]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UpdateSchedulePolicy {
+  /// the changed modules of interactive updates, e.g. hmr updates triggered by editing
+  pub module_change: UpdateSchedule,
+  /// the changed modules of background updates, e.g. lazy compilation, see [super::UpdatePriority]
+  pub background: UpdateSchedule,
+  /// the update creates new module groups, e.g. a new dynamic import, whose resources are loaded by the client right after the update
+  pub new_module_group: UpdateSchedule,
+  /// the injected resources of html entries may change, the updated html is only reported when it's [UpdateSchedule::Sync]
+  pub html_entry: UpdateSchedule,
+  /// the update affects workers, which are reloaded by the client right after the update
+  pub worker: UpdateSchedule,
+}
+
+impl Default for UpdateSchedulePolicy {
+  fn default() -> Self {
+    Self {
+      module_change: UpdateSchedule::Async,
+      background: UpdateSchedule::Async,
+      new_module_group: UpdateSchedule::Sync,
+      html_entry: UpdateSchedule::Sync,
+      worker: UpdateSchedule::Sync,
+    }
+  }
+}
+
+/// Decides when the resources of the updates are regenerated by the [UpdateSchedulePolicy], and keeps the deferred regenerations.
+/// Embedders set the policy to make sure the resources exist before they respond to the request that triggers the update
+#[derive(Default)]
+pub struct UpdateScheduler {
+  policy: RwLock<UpdateSchedulePolicy>,
+  deferred: Mutex<Vec<DeferredUpdate>>,
+}
+
+impl UpdateScheduler {
+  pub fn policy(&self) -> UpdateSchedulePolicy {
+    self.policy.read().clone()
+  }
+
+  pub fn set_policy(&self, policy: UpdateSchedulePolicy) {
+    *self.policy.write() = policy;
+  }
+
+  /// Keep the regeneration of an [UpdateSchedule::Deferred] update until [UpdateScheduler::flush]
+  pub fn defer(&self, update: DeferredUpdate) {
+    self.deferred.lock().push(update);
+  }
+
+  pub fn has_deferred(&self) -> bool {
+    !self.deferred.lock().is_empty()
+  }
+
+  /// Run the deferred regenerations in the order they are deferred, returns the number of them
+  pub fn flush(&self) -> usize {
+    let deferred = std::mem::take(&mut *self.deferred.lock());
+    let count = deferred.len();
+
+    for update in deferred {
+      update();
+    }
+
+    count
+  }
+}
+
+/// Serializes the updates, only one update changes the module graph, the module group graph and the resources at a time.
+/// Interactive updates take it before they start, background updates build their modules first and take it at that safe point,
+/// so an interactive update preempts a background update that is still building its modules, see [super::UpdatePriority]
+#[derive(Default)]
+pub struct UpdateLock {
+  locked: Mutex<bool>,
+  released: Condvar,
+}
+
+impl UpdateLock {
+  /// Block until no other update holds the lock. The guard is `Send`, so it can be moved into the thread that regenerates the resources
+  pub fn acquire(self: &Arc<Self>) -> UpdateLockGuard {
+    let mut locked = self.locked.lock();
+
+    while *locked {
+      self.released.wait(&mut locked);
+    }
+
+    *locked = true;
+
+    UpdateLockGuard { lock: self.clone() }
+  }
+}
+
+pub struct UpdateLockGuard {
+  lock: Arc<UpdateLock>,
+}
+
+impl Drop for UpdateLockGuard {
+  fn drop(&mut self) {
+    *self.lock.locked.lock() = false;
+    self.lock.released.notify_one();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    sync::{
+      atomic::{AtomicUsize, Ordering},
+      Arc,
+    },
+    time::Duration,
+  };
+
+  use super::{UpdateLock, UpdateSchedule, UpdateScheduler};
+
+  #[test]
+  fn flush_deferred_updates() {
+    let scheduler = UpdateScheduler::default();
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    for i in 0..2 {
+      let runs = runs.clone();
+      scheduler.defer(Box::new(move || {
+        assert_eq!(runs.fetch_add(1, Ordering::SeqCst), i);
+      }));
+    }
+
+    assert!(scheduler.has_deferred());
+    assert_eq!(scheduler.flush(), 2);
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    assert!(!scheduler.has_deferred());
+    assert_eq!(scheduler.flush(), 0);
+
+    assert!(UpdateSchedule::Sync > UpdateSchedule::Async);
+    assert!(UpdateSchedule::Async > UpdateSchedule::Deferred);
+  }
+
+  #[test]
+  fn update_lock() {
+    let lock = Arc::new(UpdateLock::default());
+    let running = Arc::new(AtomicUsize::new(0));
+
+    let handles = (0..4)
+      .map(|_| {
+        let lock = lock.clone();
+        let running = running.clone();
+
+        std::thread::spawn(move || {
+          let guard = lock.acquire();
+          assert_eq!(running.fetch_add(1, Ordering::SeqCst), 0);
+          std::thread::sleep(Duration::from_millis(5));
+          running.fetch_sub(1, Ordering::SeqCst);
+          // the guard may be released by another thread
+          std::thread::spawn(move || drop(guard)).join().unwrap();
+        })
+      })
+      .collect::<Vec<_>>();
+
+    for handle in handles {
+      handle.join().unwrap();
+    }
+
+    drop(lock.acquire());
+  }
+}
@@ -83,6 +83,17 @@ pub enum CompilationError {
     source: Option<Box<dyn Error + Send + Sync>>,
   },
 
+  #[error(
+    "Panicked while {target}{}.\nPanic message: {msg}",
+    .plugin.as_ref().map(|p| format!(" in plugin `{p}`")).unwrap_or_default()
+  )]
+  PanicError {
+    /// e.g. `building module src/index.ts`
+    target: String,
+    plugin: Option<String>,
+    msg: String,
+  },
+
   #[error("generate sourcemap for module `{id}` failed")]
   GenerateSourceMapError {
     id: String,
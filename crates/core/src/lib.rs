@@ -87,3 +87,23 @@ macro_rules! deserialize {
     deserialized
   }};
 }
+
+/// Like [deserialize!] but the bytes are validated first, [None] if they are not a valid archive of the type,
+/// e.g. bytes written by another version or a corrupt write. Use it for bytes that are not written by this process
+#[macro_export]
+macro_rules! try_deserialize {
+  ($bytes:expr, $ty:ty) => {{
+    let bytes: &[u8] = $bytes;
+    // the archive is validated in place, it must be aligned
+    let mut aligned = rkyv::AlignedVec::with_capacity(bytes.len());
+    aligned.extend_from_slice(bytes);
+
+    rkyv::check_archived_root::<$ty>(&aligned)
+      .ok()
+      .and_then(|archived| -> Option<$ty> {
+        archived
+          .deserialize(&mut rkyv::de::deserializers::SharedDeserializeMap::new())
+          .ok()
+      })
+  }};
+}
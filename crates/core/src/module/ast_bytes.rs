@@ -0,0 +1,118 @@
+use std::fmt;
+
+use farmfe_utils::hash::xxh3;
+use rkyv::{
+  bytecheck::CheckBytes,
+  de::deserializers::SharedDeserializeMap,
+  ser::{serializers::AllocSerializer, ScratchSpace, Serializer},
+  validation::ArchiveContext,
+  vec::{ArchivedVec, VecResolver},
+  with::{ArchiveWith, DeserializeWith, SerializeWith},
+  AlignedVec, Archive, Deserialize, Fallible,
+};
+
+use crate::VERSION;
+
+/// length of the digest that prefixes the archived ast
+const DIGEST_LEN: usize = 32;
+
+/// Archive an ast as nested rkyv bytes prefixed by their digest.
+/// The html ast of swc does not implement [CheckBytes] and the script and css asts are too recursive to be validated,
+/// so they can't be validated with the archive of the module, the digest is validated instead, see [ArchivedAstBytes]
+pub struct AstBytes;
+
+/// The archived ast, the bytes are valid if the digest matches the version of farm and the archived ast
+#[repr(transparent)]
+pub struct ArchivedAstBytes(ArchivedVec<u8>);
+
+impl ArchivedAstBytes {
+  fn ast_bytes(&self) -> &[u8] {
+    &self.0[DIGEST_LEN..]
+  }
+}
+
+fn digest(ast_bytes: &[u8]) -> String {
+  xxh3(&[VERSION.as_bytes(), ast_bytes].concat(), DIGEST_LEN)
+}
+
+impl<T> ArchiveWith<T> for AstBytes {
+  type Archived = ArchivedAstBytes;
+  type Resolver = (VecResolver, usize);
+
+  unsafe fn resolve_with(
+    _: &T,
+    pos: usize,
+    (resolver, len): Self::Resolver,
+    out: *mut Self::Archived,
+  ) {
+    ArchivedVec::<u8>::resolve_from_len(len, pos, resolver, out.cast());
+  }
+}
+
+impl<T, S> SerializeWith<T, S> for AstBytes
+where
+  T: rkyv::Serialize<AllocSerializer<1024>>,
+  S: ScratchSpace + Serializer + ?Sized,
+{
+  fn serialize_with(field: &T, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+    let ast_bytes = crate::serialize!(field);
+    let mut bytes = digest(&ast_bytes).into_bytes();
+    bytes.extend(ast_bytes);
+
+    Ok((
+      ArchivedVec::serialize_from_slice(&bytes, serializer)?,
+      bytes.len(),
+    ))
+  }
+}
+
+impl<T, D> DeserializeWith<ArchivedAstBytes, T, D> for AstBytes
+where
+  T: Archive,
+  T::Archived: Deserialize<T, SharedDeserializeMap>,
+  D: Fallible + ?Sized,
+{
+  fn deserialize_with(field: &ArchivedAstBytes, _: &mut D) -> Result<T, D::Error> {
+    // the nested archive is not aligned in the archive of the module
+    let mut bytes = AlignedVec::with_capacity(field.ast_bytes().len());
+    bytes.extend_from_slice(field.ast_bytes());
+
+    Ok(crate::deserialize!(&bytes, T))
+  }
+}
+
+#[derive(Debug)]
+pub enum AstBytesCheckError<E> {
+  Bytes(E),
+  Digest,
+}
+
+impl<E: fmt::Display> fmt::Display for AstBytesCheckError<E> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AstBytesCheckError::Bytes(e) => write!(f, "invalid bytes of the ast: {e}"),
+      AstBytesCheckError::Digest => write!(f, "the digest of the ast does not match"),
+    }
+  }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for AstBytesCheckError<E> {}
+
+impl<C: ArchiveContext + ?Sized> CheckBytes<C> for ArchivedAstBytes
+where
+  C::Error: std::error::Error,
+{
+  type Error = AstBytesCheckError<<ArchivedVec<u8> as CheckBytes<C>>::Error>;
+
+  unsafe fn check_bytes<'a>(value: *const Self, context: &mut C) -> Result<&'a Self, Self::Error> {
+    ArchivedVec::<u8>::check_bytes(value.cast(), context).map_err(AstBytesCheckError::Bytes)?;
+    let value = &*value;
+
+    if value.0.len() < DIGEST_LEN || value.0[..DIGEST_LEN] != *digest(value.ast_bytes()).as_bytes()
+    {
+      return Err(AstBytesCheckError::Digest);
+    }
+
+    Ok(value)
+  }
+}
@@ -0,0 +1,286 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use farmfe_macro_cache_item::cache_item;
+use rkyv::{
+  collections::hash_map::{ArchivedHashMap, HashMapResolver},
+  ser::{ScratchSpace, Serializer},
+  string::ArchivedString,
+  vec::ArchivedVec,
+  with::{ArchiveWith, DeserializeWith, SerializeWith},
+  Deserialize, Fallible,
+};
+
+use super::SerializeCustomModuleMetaData;
+
+type CloneValue = fn(&dyn SerializeCustomModuleMetaData) -> Box<dyn SerializeCustomModuleMetaData>;
+
+/// Custom meta data of a module set by plugins, key -> value.
+/// Values inserted by [CustomMetaDataMap::insert] are cloned in memory, others are cloned through rkyv serialization.
+/// Values restored from the persistent cache are only deserialized when they are accessed.
+#[cache_item]
+#[derive(Default, Clone)]
+pub struct CustomMetaDataMap {
+  #[with(SerializedEntries)]
+  map: HashMap<String, CustomMetaDataEntry>,
+}
+
+impl CustomMetaDataMap {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.map.is_empty()
+  }
+
+  pub fn len(&self) -> usize {
+    self.map.len()
+  }
+
+  pub fn contains_key(&self, key: &str) -> bool {
+    self.map.contains_key(key)
+  }
+
+  pub fn keys(&self) -> impl Iterator<Item = &String> {
+    self.map.keys()
+  }
+
+  /// The value of `key` if it's a `T`
+  pub fn get<T: SerializeCustomModuleMetaData>(&self, key: &str) -> Option<&T> {
+    self.map.get(key)?.value().downcast_ref::<T>()
+  }
+
+  pub fn get_mut<T: SerializeCustomModuleMetaData>(&mut self, key: &str) -> Option<&mut T> {
+    self.map.get_mut(key)?.value_mut().downcast_mut::<T>()
+  }
+
+  pub fn get_boxed(&self, key: &str) -> Option<&dyn SerializeCustomModuleMetaData> {
+    self.map.get(key).map(|entry| entry.value())
+  }
+
+  pub fn insert<T: SerializeCustomModuleMetaData + Clone>(
+    &mut self,
+    key: impl Into<String>,
+    value: T,
+  ) {
+    self.map.insert(
+      key.into(),
+      CustomMetaDataEntry::new(Box::new(value), Some(clone_value::<T>)),
+    );
+  }
+
+  /// Insert a value whose type is not known statically, it's cloned through rkyv serialization
+  pub fn insert_boxed(
+    &mut self,
+    key: impl Into<String>,
+    value: Box<dyn SerializeCustomModuleMetaData>,
+  ) {
+    self
+      .map
+      .insert(key.into(), CustomMetaDataEntry::new(value, None));
+  }
+
+  pub fn remove(&mut self, key: &str) -> Option<Box<dyn SerializeCustomModuleMetaData>> {
+    self.map.remove(key).map(|entry| entry.into_value())
+  }
+}
+
+fn clone_value<T: SerializeCustomModuleMetaData + Clone>(
+  value: &dyn SerializeCustomModuleMetaData,
+) -> Box<dyn SerializeCustomModuleMetaData> {
+  Box::new(value.downcast_ref::<T>().unwrap().clone())
+}
+
+pub struct CustomMetaDataEntry {
+  value: OnceLock<Box<dyn SerializeCustomModuleMetaData>>,
+  /// rkyv bytes of the value, set when the value is restored from the persistent cache and cleared when it's mutably borrowed
+  bytes: Option<Vec<u8>>,
+  clone_value: Option<CloneValue>,
+}
+
+impl CustomMetaDataEntry {
+  fn new(value: Box<dyn SerializeCustomModuleMetaData>, clone_value: Option<CloneValue>) -> Self {
+    Self {
+      value: OnceLock::from(value),
+      bytes: None,
+      clone_value,
+    }
+  }
+
+  fn serialized(bytes: Vec<u8>) -> Self {
+    Self {
+      value: OnceLock::new(),
+      bytes: Some(bytes),
+      clone_value: None,
+    }
+  }
+
+  fn value(&self) -> &dyn SerializeCustomModuleMetaData {
+    self
+      .value
+      .get_or_init(|| {
+        let bytes = self.bytes.as_ref().unwrap();
+        crate::deserialize!(bytes, Box<dyn SerializeCustomModuleMetaData>)
+      })
+      .as_ref()
+  }
+
+  fn value_mut(&mut self) -> &mut Box<dyn SerializeCustomModuleMetaData> {
+    self.value();
+    // the bytes are stale once the value is mutated
+    self.bytes = None;
+    self.value.get_mut().unwrap()
+  }
+
+  fn into_value(self) -> Box<dyn SerializeCustomModuleMetaData> {
+    self.value();
+    self.value.into_inner().unwrap()
+  }
+
+  fn to_bytes(&self) -> Vec<u8> {
+    match &self.bytes {
+      Some(bytes) => bytes.clone(),
+      None => crate::serialize!(self.value.get().unwrap()),
+    }
+  }
+}
+
+impl Clone for CustomMetaDataEntry {
+  fn clone(&self) -> Self {
+    match (self.value.get(), self.clone_value) {
+      (Some(value), Some(clone_value)) => Self::new(clone_value(value.as_ref()), Some(clone_value)),
+      _ => Self::serialized(self.to_bytes()),
+    }
+  }
+}
+
+/// Archive the entries of [CustomMetaDataMap] as key -> rkyv bytes of the value, so the values are deserialized lazily
+pub struct SerializedEntries;
+
+impl ArchiveWith<HashMap<String, CustomMetaDataEntry>> for SerializedEntries {
+  type Archived = ArchivedHashMap<ArchivedString, ArchivedVec<u8>>;
+  type Resolver = HashMapResolver;
+
+  unsafe fn resolve_with(
+    field: &HashMap<String, CustomMetaDataEntry>,
+    pos: usize,
+    resolver: Self::Resolver,
+    out: *mut Self::Archived,
+  ) {
+    ArchivedHashMap::resolve_from_len(field.len(), pos, resolver, out);
+  }
+}
+
+impl<S: ScratchSpace + Serializer + ?Sized> SerializeWith<HashMap<String, CustomMetaDataEntry>, S>
+  for SerializedEntries
+{
+  fn serialize_with(
+    field: &HashMap<String, CustomMetaDataEntry>,
+    serializer: &mut S,
+  ) -> Result<Self::Resolver, S::Error> {
+    let entries = field
+      .iter()
+      .map(|(key, entry)| (key, entry.to_bytes()))
+      .collect::<Vec<_>>();
+
+    unsafe {
+      ArchivedHashMap::serialize_from_iter(
+        entries.iter().map(|(key, bytes)| (*key, bytes)),
+        serializer,
+      )
+    }
+  }
+}
+
+impl<D: Fallible + ?Sized>
+  DeserializeWith<
+    ArchivedHashMap<ArchivedString, ArchivedVec<u8>>,
+    HashMap<String, CustomMetaDataEntry>,
+    D,
+  > for SerializedEntries
+{
+  fn deserialize_with(
+    field: &ArchivedHashMap<ArchivedString, ArchivedVec<u8>>,
+    _: &mut D,
+  ) -> Result<HashMap<String, CustomMetaDataEntry>, D::Error> {
+    Ok(
+      field
+        .iter()
+        .map(|(key, bytes)| {
+          (
+            key.to_string(),
+            CustomMetaDataEntry::serialized(bytes.to_vec()),
+          )
+        })
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use farmfe_macro_cache_item::cache_item;
+  use rkyv::Deserialize;
+  use rkyv_dyn::archive_dyn;
+  use rkyv_typename::TypeName;
+
+  use super::CustomMetaDataMap;
+  use crate::module::{
+    CustomModuleMetaData, DeserializeCustomModuleMetaData, SerializeCustomModuleMetaData,
+  };
+
+  #[cache_item(CustomModuleMetaData)]
+  #[derive(Clone)]
+  pub struct RouteMetaData {
+    path: String,
+  }
+
+  #[test]
+  fn typed_access() {
+    let mut map = CustomMetaDataMap::new();
+    map.insert(
+      "route",
+      RouteMetaData {
+        path: "/home".to_string(),
+      },
+    );
+
+    assert_eq!(map.get::<RouteMetaData>("route").unwrap().path, "/home");
+    map.get_mut::<RouteMetaData>("route").unwrap().path = "/about".to_string();
+
+    let cloned = map.clone();
+    assert_eq!(cloned.get::<RouteMetaData>("route").unwrap().path, "/about");
+    assert!(map.remove("route").is_some());
+    assert!(map.is_empty());
+  }
+
+  #[test]
+  fn lazy_deserialization() {
+    let mut map = CustomMetaDataMap::new();
+    map.insert(
+      "route",
+      RouteMetaData {
+        path: "/home".to_string(),
+      },
+    );
+
+    let bytes = crate::serialize!(&map);
+    let archived = unsafe { rkyv::archived_root::<CustomMetaDataMap>(&bytes[..]) };
+    let mut deserialized: CustomMetaDataMap = archived
+      .deserialize(&mut rkyv::de::deserializers::SharedDeserializeMap::new())
+      .unwrap();
+
+    assert!(deserialized.map["route"].value.get().is_none());
+    // cloned without deserializing the value
+    let cloned = deserialized.clone();
+    assert!(cloned.map["route"].value.get().is_none());
+
+    assert_eq!(
+      deserialized.get::<RouteMetaData>("route").unwrap().path,
+      "/home"
+    );
+    deserialized.get_mut::<RouteMetaData>("route").unwrap().path = "/about".to_string();
+    assert!(deserialized.map["route"].bytes.is_none());
+    assert_eq!(cloned.get::<RouteMetaData>("route").unwrap().path, "/home");
+  }
+}
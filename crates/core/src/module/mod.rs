@@ -25,12 +25,16 @@ use swc_css_ast::Stylesheet;
 use swc_ecma_ast::Module as SwcModule;
 use swc_html_ast::Document;
 
-use crate::{config::Mode, resource::resource_pot::ResourcePotId};
+use crate::{config::Mode, plugin::ResolveKind, resource::resource_pot::ResourcePotId};
 
-use self::module_group::ModuleGroupId;
+use self::{custom_meta_data::CustomMetaDataMap, module_group::ModuleGroupId};
 
+pub mod ast_bytes;
+pub mod custom_meta_data;
 pub mod module_graph;
 pub mod module_group;
+pub mod module_statement;
+pub mod sub_module;
 pub mod watch_graph;
 
 pub const VIRTUAL_MODULE_PREFIX: &str = "virtual:";
@@ -38,6 +42,7 @@ pub const VIRTUAL_MODULE_PREFIX: &str = "virtual:";
 /// A [Module] is a basic compilation unit
 /// The [Module] is created by plugins in the parse hook of build stage
 #[cache_item]
+#[derive(Clone)]
 pub struct Module {
   /// the id of this module, generated from the resolved id.
   pub id: ModuleId,
@@ -75,47 +80,11 @@ pub struct Module {
   pub package_name: String,
   /// package version of this module
   pub package_version: String,
+  /// spdx license expression of the package of this module, empty if not specified
+  pub package_license: String,
 
   // custom meta map
-  pub custom: HashMap<String, Box<dyn SerializeCustomModuleMetaData>>,
-}
-
-impl Clone for Module {
-  fn clone(&self) -> Self {
-    let custom = if self.custom.is_empty() {
-      HashMap::new()
-    } else {
-      let mut custom = HashMap::new();
-      for (k, v) in self.custom.iter() {
-        let cloned_data = crate::serialize!(v);
-        let cloned_custom =
-          crate::deserialize!(&cloned_data, Box<dyn SerializeCustomModuleMetaData>);
-        custom.insert(k.clone(), cloned_custom);
-      }
-      custom
-    };
-
-    Self {
-      id: self.id.clone(),
-      module_type: self.module_type.clone(),
-      module_groups: self.module_groups.clone(),
-      resource_pot: self.resource_pot.clone(),
-      meta: self.meta.clone(),
-      side_effects: self.side_effects,
-      source_map_chain: self.source_map_chain.clone(),
-      external: self.external,
-      immutable: self.immutable,
-      execution_order: self.execution_order,
-      size: self.size,
-      content: self.content.clone(),
-      used_exports: self.used_exports.clone(),
-      last_update_timestamp: self.last_update_timestamp,
-      content_hash: self.content_hash.clone(),
-      package_name: self.package_name.clone(),
-      package_version: self.package_version.clone(),
-      custom,
-    }
-  }
+  pub custom: CustomMetaDataMap,
 }
 
 impl Module {
@@ -139,8 +108,18 @@ impl Module {
       content_hash: "".to_string(),
       package_name: "".to_string(),
       package_version: "".to_string(),
-      custom: HashMap::new(),
+      package_license: "".to_string(),
+      custom: Default::default(),
+    }
+  }
+
+  /// `name@version` of the package the module is resolved from, [None] if the module is not in node_modules
+  pub fn node_modules_package(&self) -> Option<String> {
+    if !self.id.to_string().contains("node_modules") || self.package_name.is_empty() {
+      return None;
     }
+
+    Some(format!("{}@{}", self.package_name, self.package_version))
   }
 }
 
@@ -320,7 +299,9 @@ impl From<CommentsMetaData> for SingleThreadedComments {
 
 /// Script specific meta data, for example, [swc_ecma_ast::Module]
 #[cache_item]
+#[derive(Clone)]
 pub struct ScriptModuleMetaData {
+  #[with(ast_bytes::AstBytes)]
   pub ast: SwcModule,
   pub top_level_mark: u32,
   pub unresolved_mark: u32,
@@ -329,7 +310,15 @@ pub struct ScriptModuleMetaData {
   pub hmr_self_accepted: bool,
   pub hmr_accepted_deps: HashSet<ModuleId>,
   pub comments: CommentsMetaData,
-  pub custom: HashMap<String, Box<dyn SerializeCustomModuleMetaData>>,
+  /// the custom module system the module is normalized from, e.g. `amd`
+  pub original_module_system: Option<String>,
+  /// source of the dynamic import -> hints from its magic comments, see [DynamicImportHints]
+  pub dynamic_import_hints: HashMap<String, DynamicImportHints>,
+  /// define key -> the replaced code, the module is rebuilt when one of them changes, see `FarmPluginDefine`
+  pub used_defines: HashMap<String, String>,
+  /// dynamic imports and requires removed with the dead branches folded by the defines, see [PrunedImport]
+  pub pruned_imports: Vec<PrunedImport>,
+  pub custom: CustomMetaDataMap,
 }
 
 impl Default for ScriptModuleMetaData {
@@ -346,39 +335,15 @@ impl Default for ScriptModuleMetaData {
       hmr_self_accepted: false,
       hmr_accepted_deps: Default::default(),
       comments: Default::default(),
+      original_module_system: None,
+      dynamic_import_hints: Default::default(),
+      used_defines: Default::default(),
+      pruned_imports: Default::default(),
       custom: Default::default(),
     }
   }
 }
 
-impl Clone for ScriptModuleMetaData {
-  fn clone(&self) -> Self {
-    let custom = if self.custom.is_empty() {
-      HashMap::new()
-    } else {
-      let mut custom = HashMap::new();
-      for (k, v) in self.custom.iter() {
-        let cloned_data = crate::serialize!(v);
-        let cloned_custom =
-          crate::deserialize!(&cloned_data, Box<dyn SerializeCustomModuleMetaData>);
-        custom.insert(k.clone(), cloned_custom);
-      }
-      custom
-    };
-
-    Self {
-      ast: self.ast.clone(),
-      top_level_mark: self.top_level_mark,
-      unresolved_mark: self.unresolved_mark,
-      module_system: self.module_system.clone(),
-      hmr_self_accepted: self.hmr_self_accepted,
-      hmr_accepted_deps: self.hmr_accepted_deps.clone(),
-      comments: self.comments.clone(),
-      custom,
-    }
-  }
-}
-
 impl ScriptModuleMetaData {
   pub fn take_ast(&mut self) -> SwcModule {
     std::mem::replace(
@@ -404,6 +369,29 @@ impl ScriptModuleMetaData {
   }
 }
 
+/// Hints of a dynamic import from magic comments, e.g. `import(/* farmChunkName: "settings", farmPrefetch: true */ './settings')`
+#[cache_item]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DynamicImportHints {
+  /// name of the resource pot of the dynamically imported module group
+  pub chunk_name: Option<String>,
+  /// the resources of the dynamically imported module group are prefetched when the browser is idle
+  pub prefetch: bool,
+  /// the resources of the dynamically imported module group are preloaded with the resources of the importer
+  pub preload: bool,
+}
+
+/// A dynamic import or require in a branch removed by `FarmPluginDefine`, e.g. `import('./enterprise')` of
+/// `if (FARM_FLAGS.enterprise) { ... }` when the flag is false
+#[cache_item]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrunedImport {
+  pub source: String,
+  pub kind: ResolveKind,
+  /// keys of the defines in the test of the removed branch
+  pub defines: Vec<String>,
+}
+
 #[cache_item]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ModuleSystem {
@@ -447,33 +435,12 @@ impl ModuleSystem {
 }
 
 #[cache_item]
+#[derive(Clone)]
 pub struct CssModuleMetaData {
+  #[with(ast_bytes::AstBytes)]
   pub ast: Stylesheet,
   pub comments: CommentsMetaData,
-  pub custom: HashMap<String, Box<dyn SerializeCustomModuleMetaData>>,
-}
-
-impl Clone for CssModuleMetaData {
-  fn clone(&self) -> Self {
-    let custom = if self.custom.is_empty() {
-      HashMap::new()
-    } else {
-      let mut custom = HashMap::new();
-      for (k, v) in self.custom.iter() {
-        let cloned_data = crate::serialize!(v);
-        let cloned_custom =
-          crate::deserialize!(&cloned_data, Box<dyn SerializeCustomModuleMetaData>);
-        custom.insert(k.clone(), cloned_custom);
-      }
-      custom
-    };
-
-    Self {
-      ast: self.ast.clone(),
-      comments: self.comments.clone(),
-      custom,
-    }
-  }
+  pub custom: CustomMetaDataMap,
 }
 
 impl CssModuleMetaData {
@@ -493,31 +460,11 @@ impl CssModuleMetaData {
 }
 
 #[cache_item]
+#[derive(Clone)]
 pub struct HtmlModuleMetaData {
+  #[with(ast_bytes::AstBytes)]
   pub ast: Document,
-  pub custom: HashMap<String, Box<dyn SerializeCustomModuleMetaData>>,
-}
-
-impl Clone for HtmlModuleMetaData {
-  fn clone(&self) -> Self {
-    let custom = if self.custom.is_empty() {
-      HashMap::new()
-    } else {
-      let mut custom = HashMap::new();
-      for (k, v) in self.custom.iter() {
-        let cloned_data = crate::serialize!(v);
-        let cloned_custom =
-          crate::deserialize!(&cloned_data, Box<dyn SerializeCustomModuleMetaData>);
-        custom.insert(k.clone(), cloned_custom);
-      }
-      custom
-    };
-
-    Self {
-      ast: self.ast.clone(),
-      custom,
-    }
-  }
+  pub custom: CustomMetaDataMap,
 }
 
 /// Internal support module types by the core plugins,
@@ -765,10 +712,134 @@ mod tests {
   use rkyv_typename::TypeName;
   use std::collections::HashSet;
 
+  use std::collections::HashMap;
+  use swc_common::{BytePos, DUMMY_SP};
+  use swc_css_ast::Stylesheet;
+  use swc_ecma_ast::Module as SwcModule;
+  use swc_html_ast::{Document, DocumentMode};
+
   use super::{
-    CustomModuleMetaData, DeserializeCustomModuleMetaData, Module, ModuleId, ModuleMetaData,
-    ModuleSystem, ModuleType, SerializeCustomModuleMetaData,
+    custom_meta_data::CustomMetaDataMap, CommentsMetaData, CommentsMetaDataItem, CssModuleMetaData,
+    CustomModuleMetaData, DeserializeCustomModuleMetaData, DynamicImportHints, HtmlModuleMetaData,
+    Module, ModuleId, ModuleMetaData, ModuleSystem, ModuleType, PrunedImport, ScriptModuleMetaData,
+    SerializeCustomModuleMetaData,
   };
+  use crate::plugin::ResolveKind;
+
+  #[cache_item(CustomModuleMetaData)]
+  #[derive(Clone)]
+  pub struct CloneTestData {
+    value: String,
+  }
+
+  /// The clone must serialize to the same bytes as the original, so a field dropped or reset by `clone` is caught
+  fn assert_clone_preserves_fields<T>(value: &T)
+  where
+    T: Clone + rkyv::Serialize<rkyv::ser::serializers::AllocSerializer<1024>>,
+  {
+    assert_eq!(crate::serialize!(&value.clone()), crate::serialize!(value));
+  }
+
+  fn custom_meta_data() -> CustomMetaDataMap {
+    let mut custom = CustomMetaDataMap::new();
+    custom.insert(
+      "typed",
+      CloneTestData {
+        value: "typed".to_string(),
+      },
+    );
+    custom.insert_boxed(
+      "boxed",
+      Box::new(CloneTestData {
+        value: "boxed".to_string(),
+      }),
+    );
+    custom
+  }
+
+  /// every field is set to a non default value explicitly, so a new field must be added here as well
+  fn script_meta_data() -> ScriptModuleMetaData {
+    ScriptModuleMetaData {
+      ast: SwcModule {
+        span: DUMMY_SP,
+        body: vec![],
+        shebang: Some("/usr/bin/env node".into()),
+      },
+      top_level_mark: 1,
+      unresolved_mark: 2,
+      module_system: ModuleSystem::CommonJs,
+      hmr_self_accepted: true,
+      hmr_accepted_deps: HashSet::from(["./dep".into()]),
+      comments: CommentsMetaData {
+        leading: vec![CommentsMetaDataItem {
+          byte_pos: BytePos(1),
+          comment: vec![],
+        }],
+        trailing: vec![],
+      },
+      original_module_system: Some("amd".to_string()),
+      dynamic_import_hints: HashMap::from([(
+        "./settings".to_string(),
+        DynamicImportHints {
+          chunk_name: Some("settings".to_string()),
+          prefetch: true,
+          preload: true,
+        },
+      )]),
+      used_defines: HashMap::from([("FLAG".to_string(), "true".to_string())]),
+      pruned_imports: vec![PrunedImport {
+        source: "./enterprise".to_string(),
+        kind: ResolveKind::DynamicImport,
+        defines: vec!["FLAG".to_string()],
+      }],
+      custom: custom_meta_data(),
+    }
+  }
+
+  #[test]
+  fn meta_data_clone_preserves_fields() {
+    assert_clone_preserves_fields(&script_meta_data());
+    assert_clone_preserves_fields(&CssModuleMetaData {
+      ast: Stylesheet {
+        span: DUMMY_SP,
+        rules: vec![],
+      },
+      comments: script_meta_data().comments,
+      custom: custom_meta_data(),
+    });
+    assert_clone_preserves_fields(&HtmlModuleMetaData {
+      ast: Document {
+        span: DUMMY_SP,
+        mode: DocumentMode::Quirks,
+        children: vec![],
+      },
+      custom: custom_meta_data(),
+    });
+    assert_clone_preserves_fields(&ModuleMetaData::Custom(Box::new(CloneTestData {
+      value: "custom".to_string(),
+    })));
+
+    let mut module = Module::new("/root/index.ts".into());
+    module.module_type = ModuleType::Ts;
+    module.module_groups = HashSet::from(["/root/index.ts".into()]);
+    module.resource_pot = Some("index_ts".to_string());
+    module.meta = Box::new(ModuleMetaData::Script(script_meta_data()));
+    module.side_effects = false;
+    module.source_map_chain = vec![std::sync::Arc::new("{}".to_string())];
+    module.external = true;
+    module.immutable = true;
+    module.execution_order = 1;
+    module.size = 2;
+    module.content = std::sync::Arc::new("export {}".to_string());
+    module.used_exports = vec!["default".to_string()];
+    module.last_update_timestamp = 3;
+    module.content_hash = "hash".to_string();
+    module.package_name = "pkg".to_string();
+    module.package_version = "1.0.0".to_string();
+    module.package_license = "MIT".to_string();
+    module.custom = custom_meta_data();
+    assert_clone_preserves_fields(&module);
+  }
 
   #[test]
   fn module_type() {
@@ -1,7 +1,5 @@
-use std::cmp::Ordering;
-
 use farmfe_macro_cache_item::cache_item;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use petgraph::{
   graph::{DefaultIx, NodeIndex},
@@ -11,8 +9,9 @@ use petgraph::{
 };
 
 use crate::{
+  config::execution_order::ExecutionOrderTieBreak,
   error::{CompilationError, Result},
-  plugin::ResolveKind,
+  plugin::{ResolveKind, ResolveTrace},
 };
 
 use super::{Module, ModuleId};
@@ -30,6 +29,9 @@ pub struct ModuleGraphEdgeDataItem {
   /// ```
   /// the edge `./a`'s order is 0 and `./b`'s order is 1 (starting from 0).
   pub order: usize,
+  /// how the source is resolved, [None] if the resolve plugin doesn't record it
+  #[serde(default)]
+  pub resolve_trace: Option<ResolveTrace>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -99,6 +101,42 @@ impl ModuleGraphEdge {
   pub fn is_empty(&self) -> bool {
     self.0.is_empty()
   }
+
+  /// The order of the first import statement of this edge, [usize::MAX] if the edge is empty
+  pub fn min_order(&self) -> usize {
+    self
+      .0
+      .iter()
+      .map(|item| item.order)
+      .min()
+      .unwrap_or(usize::MAX)
+  }
+}
+
+/// A step of an import chain, `from` imports `to`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportChainStep {
+  pub from: ModuleId,
+  pub to: ModuleId,
+  /// kinds of the imports of this edge, e.g. `import`, `dynamicImport` or `require`
+  pub kinds: Vec<ResolveKind>,
+  /// true if all the import statements of this edge are removed by tree shaking
+  pub tree_shaken: bool,
+  /// how the import statements of this edge are resolved, in the order of `kinds`
+  pub resolve_traces: Vec<Option<ResolveTrace>>,
+  /// `name@version` of the package `to` is resolved from, [None] if it's not in node_modules
+  pub package: Option<String>,
+}
+
+/// Modules of the diagrams of [ModuleGraph::to_dot] and [ModuleGraph::to_mermaid]
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModuleGraphFilter {
+  /// regex of the module ids the diagram starts from, e.g. `^src/features/checkout/`. The entries if [None]
+  pub pattern: Option<String>,
+  /// levels of the dependencies of the matched modules in the diagram, all the reachable modules if [None]
+  pub depth: Option<usize>,
 }
 
 pub struct ModuleGraph {
@@ -111,8 +149,13 @@ pub struct ModuleGraph {
   /// entry modules of this module graph.
   /// (Entry Module Id, Entry Name)
   pub entries: HashMap<ModuleId, String>,
+  /// (importer, imported) edges whose import statements are all removed by tree shaking
+  tree_shaken_edges: HashSet<(ModuleId, ModuleId)>,
 }
 
+/// (from, to, dynamic) edge between the modules selected by a [ModuleGraphFilter]
+type FilteredEdge = (ModuleId, ModuleId, bool);
+
 impl ModuleGraph {
   pub fn new() -> Self {
     Self {
@@ -120,6 +163,7 @@ impl ModuleGraph {
       id_index_map: HashMap::new(),
       file_module_ids_map: HashMap::new(),
       entries: HashMap::new(),
+      tree_shaken_edges: HashSet::new(),
     }
   }
 
@@ -444,16 +488,8 @@ impl ModuleGraph {
       deps.push((self.g[node_index].id.clone(), &self.g[edge_index]));
     }
 
-    deps.sort_by(|a, b| {
-      if a.1.is_empty() || b.1.is_empty() {
-        return Ordering::Equal;
-      }
-
-      let a_minimum_order = a.1.iter().map(|item| item.order).min().unwrap();
-      let b_minimum_order = b.1.iter().map(|item| item.order).min().unwrap();
-
-      a_minimum_order.cmp(&b_minimum_order)
-    });
+    // the dependencies of the same order are sorted by module id, so the order doesn't depend on the insertion history
+    deps.sort_by(|a, b| (a.1.min_order(), &a.0).cmp(&(b.1.min_order(), &b.0)));
 
     deps
   }
@@ -509,8 +545,17 @@ impl ModuleGraph {
   ///
   /// **Unsupported Situation**: if the two entries shares the same dependencies but the import order is not the same, may cause one entry don't keep original import order, this may bring problems in css as css depends on the order.
   pub fn toposort(&self) -> (Vec<ModuleId>, Vec<Vec<ModuleId>>) {
+    self.toposort_by(ExecutionOrderTieBreak::ImportOrder)
+  }
+
+  /// Same as [ModuleGraph::toposort], the dependencies of every module are visited in the order of `tie_break`
+  pub fn toposort_by(
+    &self,
+    tie_break: ExecutionOrderTieBreak,
+  ) -> (Vec<ModuleId>, Vec<Vec<ModuleId>>) {
     fn dfs(
       entry: &ModuleId,
+      tie_break: ExecutionOrderTieBreak,
       graph: &ModuleGraph,
       stack: &mut Vec<ModuleId>,
       visited: &mut HashSet<ModuleId>,
@@ -529,10 +574,14 @@ impl ModuleGraph {
       visited.insert(entry.clone());
       stack.push(entry.clone());
 
-      let deps = graph.dependencies(entry);
+      let mut deps = graph.dependencies(entry);
+
+      if tie_break == ExecutionOrderTieBreak::Path {
+        deps.sort_by(|a, b| a.0.cmp(&b.0));
+      }
 
       for (dep, _) in &deps {
-        dfs(dep, graph, stack, visited, result, cyclic)
+        dfs(dep, tie_break, graph, stack, visited, result, cyclic)
       }
 
       // visit current entry
@@ -551,7 +600,15 @@ impl ModuleGraph {
 
     for (entry, _) in entries {
       let mut res = vec![];
-      dfs(entry, self, &mut stack, &mut visited, &mut res, &mut cyclic);
+      dfs(
+        entry,
+        tie_break,
+        self,
+        &mut stack,
+        &mut visited,
+        &mut res,
+        &mut cyclic,
+      );
 
       result.extend(res);
     }
@@ -562,7 +619,13 @@ impl ModuleGraph {
   }
 
   pub fn update_execution_order_for_modules(&mut self) {
-    let (mut topo_sorted_modules, _) = self.toposort();
+    self.update_execution_order_for_modules_by(ExecutionOrderTieBreak::ImportOrder);
+  }
+
+  /// Set the execution order of the modules by the toposort of `tie_break`, the order only depends on the edges
+  /// and the entries of the graph, so the initial build and the updated graphs of hmr produce the same order
+  pub fn update_execution_order_for_modules_by(&mut self, tie_break: ExecutionOrderTieBreak) {
+    let (mut topo_sorted_modules, _) = self.toposort_by(tie_break);
 
     topo_sorted_modules.reverse();
 
@@ -665,6 +728,234 @@ impl ModuleGraph {
       .any(|(_, edge)| edge.is_dynamic())
   }
 
+  /// whether the module is the entry of a web worker, e.g. `new Worker(new URL('./worker.ts', import.meta.url))`
+  pub fn is_worker(&self, module_id: &ModuleId) -> bool {
+    self
+      .dependents(module_id)
+      .iter()
+      .any(|(_, edge)| edge.iter().any(|item| item.kind.is_worker()))
+  }
+
+  pub fn mark_edge_tree_shaken(&mut self, from: &ModuleId, to: &ModuleId) {
+    self.tree_shaken_edges.insert((from.clone(), to.clone()));
+  }
+
+  pub fn is_edge_tree_shaken(&self, from: &ModuleId, to: &ModuleId) -> bool {
+    self.tree_shaken_edges.contains(&(from.clone(), to.clone()))
+  }
+
+  /// All the shortest import chains from the entries to `module_id`, answers why the module is in the bundle.
+  /// Each chain starts from an entry, a chain is empty if the module is an entry itself.
+  /// Empty if the module is not reachable from any entry
+  pub fn import_chains(&self, module_id: &ModuleId) -> Vec<Vec<ImportChainStep>> {
+    if !self.has_module(module_id) {
+      return vec![];
+    }
+
+    // bfs from all the entries, the shortest-path predecessors of each module are recorded
+    let mut distances = HashMap::<ModuleId, usize>::new();
+    let mut predecessors = HashMap::<ModuleId, Vec<ModuleId>>::new();
+    let mut queue = VecDeque::new();
+    let mut entries = self.entries.keys().collect::<Vec<_>>();
+    entries.sort();
+
+    for entry in entries {
+      if self.has_module(entry) {
+        distances.insert(entry.clone(), 0);
+        queue.push_back(entry.clone());
+      }
+    }
+
+    while let Some(current) = queue.pop_front() {
+      let distance = distances[&current];
+
+      if distances.get(module_id).is_some_and(|d| *d <= distance) {
+        break;
+      }
+
+      let mut deps = self.dependencies_ids(&current);
+      deps.sort();
+
+      for dep in deps {
+        match distances.get(&dep) {
+          None => {
+            distances.insert(dep.clone(), distance + 1);
+            predecessors.insert(dep.clone(), vec![current.clone()]);
+            queue.push_back(dep);
+          }
+          Some(d) if *d == distance + 1 => {
+            predecessors.get_mut(&dep).unwrap().push(current.clone());
+          }
+          _ => {}
+        }
+      }
+    }
+
+    if !distances.contains_key(module_id) {
+      return vec![];
+    }
+
+    // walk back from the module to the entries through the predecessors
+    let mut chains = vec![];
+    let mut stack: Vec<(ModuleId, Vec<ImportChainStep>)> = vec![(module_id.clone(), vec![])];
+
+    while let Some((current, chain)) = stack.pop() {
+      let Some(preds) = predecessors.get(&current) else {
+        let mut chain = chain;
+        chain.reverse();
+        chains.push(chain);
+        continue;
+      };
+
+      for pred in preds.iter().rev() {
+        let edge = self.edge_info(pred, &current).unwrap();
+        let mut chain = chain.clone();
+        chain.push(ImportChainStep {
+          from: pred.clone(),
+          to: current.clone(),
+          kinds: edge.iter().map(|item| item.kind.clone()).collect(),
+          tree_shaken: self.is_edge_tree_shaken(pred, &current),
+          resolve_traces: edge.iter().map(|item| item.resolve_trace.clone()).collect(),
+          package: self.module(&current).unwrap().node_modules_package(),
+        });
+        stack.push((pred.clone(), chain));
+      }
+    }
+
+    chains
+  }
+
+  /// Graphviz DOT diagram of the modules selected by the filter, dynamic imports are dashed
+  pub fn to_dot(&self, filter: &ModuleGraphFilter) -> Result<String> {
+    let (modules, edges) = self.filtered_modules(filter)?;
+    let quote = |id: &ModuleId| {
+      format!(
+        "\"{}\"",
+        id.to_string().replace('\\', "\\\\").replace('"', "\\\"")
+      )
+    };
+    let mut dot = String::from("digraph modules {\n");
+
+    for module_id in &modules {
+      dot.push_str(&format!("  {};\n", quote(module_id)));
+    }
+
+    for (from, to, dynamic) in &edges {
+      let style = if *dynamic { " [style=dashed]" } else { "" };
+      dot.push_str(&format!("  {} -> {}{style};\n", quote(from), quote(to)));
+    }
+
+    dot.push_str("}\n");
+    Ok(dot)
+  }
+
+  /// Mermaid flowchart of the modules selected by the filter, dynamic imports are dotted
+  pub fn to_mermaid(&self, filter: &ModuleGraphFilter) -> Result<String> {
+    let (modules, edges) = self.filtered_modules(filter)?;
+    let node_ids = modules
+      .iter()
+      .enumerate()
+      .map(|(i, module_id)| (module_id, format!("m{i}")))
+      .collect::<HashMap<_, _>>();
+    let mut mermaid = String::from("graph LR\n");
+
+    for module_id in &modules {
+      mermaid.push_str(&format!(
+        "  {}[\"{}\"]\n",
+        node_ids[module_id],
+        module_id.to_string().replace('"', "#quot;")
+      ));
+    }
+
+    for (from, to, dynamic) in &edges {
+      let arrow = if *dynamic { "-.->" } else { "-->" };
+      mermaid.push_str(&format!("  {} {arrow} {}\n", node_ids[from], node_ids[to]));
+    }
+
+    Ok(mermaid)
+  }
+
+  /// Sorted modules selected by the filter and the (from, to, dynamic) edges between them
+  fn filtered_modules(
+    &self,
+    filter: &ModuleGraphFilter,
+  ) -> Result<(Vec<ModuleId>, Vec<FilteredEdge>)> {
+    let mut roots = match &filter.pattern {
+      Some(pattern) => {
+        let regex = regex::Regex::new(pattern).map_err(|e| {
+          CompilationError::GenericError(format!("invalid module graph filter `{pattern}`: {e}"))
+        })?;
+
+        self
+          .modules()
+          .into_iter()
+          .map(|module| &module.id)
+          .filter(|module_id| regex.is_match(&module_id.to_string()))
+          .cloned()
+          .collect::<Vec<_>>()
+      }
+      None => self.entries.keys().cloned().collect(),
+    };
+    roots.sort();
+
+    // bfs from the roots until the depth is reached
+    let mut depths = HashMap::<ModuleId, usize>::new();
+    let mut queue = VecDeque::new();
+
+    for root in roots {
+      if self.has_module(&root) && !depths.contains_key(&root) {
+        depths.insert(root.clone(), 0);
+        queue.push_back(root);
+      }
+    }
+
+    while let Some(current) = queue.pop_front() {
+      let depth = depths[&current];
+
+      if filter.depth.is_some_and(|max_depth| depth >= max_depth) {
+        continue;
+      }
+
+      for dep in self.dependencies_ids(&current) {
+        if !depths.contains_key(&dep) {
+          depths.insert(dep.clone(), depth + 1);
+          queue.push_back(dep);
+        }
+      }
+    }
+
+    let mut modules = depths.into_keys().collect::<Vec<_>>();
+    modules.sort();
+    let included = modules.iter().collect::<HashSet<_>>();
+    let mut edges = vec![];
+
+    for module_id in &modules {
+      for (dep, edge) in self.dependencies(module_id) {
+        if included.contains(&dep) {
+          edges.push((module_id.clone(), dep, edge.is_dynamic()));
+        }
+      }
+    }
+
+    edges.sort();
+    Ok((modules, edges))
+  }
+
+  /// Copy of the modules and edges without the meta data, content and source maps of the modules,
+  /// see [crate::context::CompilationContext::module_graph_snapshot]
+  pub fn snapshot(&self) -> Self {
+    Self {
+      g: self.g.map(
+        |_, module| module_without_meta(module),
+        |_, edge| edge.clone(),
+      ),
+      id_index_map: self.id_index_map.clone(),
+      file_module_ids_map: self.file_module_ids_map.clone(),
+      entries: self.entries.clone(),
+      tree_shaken_edges: self.tree_shaken_edges.clone(),
+    }
+  }
+
   pub fn copy_to(&self, other: &mut Self, overwrite: bool) -> Result<()> {
     let mut new_modules = Vec::<ModuleId>::new();
     for module in self.modules() {
@@ -684,6 +975,26 @@ impl ModuleGraph {
   }
 }
 
+fn module_without_meta(module: &Module) -> Module {
+  Module {
+    module_type: module.module_type.clone(),
+    module_groups: module.module_groups.clone(),
+    resource_pot: module.resource_pot.clone(),
+    side_effects: module.side_effects,
+    external: module.external,
+    immutable: module.immutable,
+    execution_order: module.execution_order,
+    size: module.size,
+    used_exports: module.used_exports.clone(),
+    last_update_timestamp: module.last_update_timestamp,
+    content_hash: module.content_hash.clone(),
+    package_name: module.package_name.clone(),
+    package_version: module.package_version.clone(),
+    package_license: module.package_license.clone(),
+    ..Module::new(module.id.clone())
+  }
+}
+
 impl Default for ModuleGraph {
   fn default() -> Self {
     Self::new()
@@ -695,11 +1006,12 @@ mod tests {
   use std::collections::HashMap;
 
   use crate::{
+    config::execution_order::ExecutionOrderTieBreak,
     module::{Module, ModuleId},
     plugin::ResolveKind,
   };
 
-  use super::{ModuleGraph, ModuleGraphEdge, ModuleGraphEdgeDataItem};
+  use super::{ModuleGraph, ModuleGraphEdge, ModuleGraphEdgeDataItem, ModuleGraphFilter};
 
   /// construct a test module graph like below:
   /// ```plain
@@ -736,6 +1048,7 @@ mod tests {
             source: format!("./{to}"),
             kind: ResolveKind::Import,
             order,
+            resolve_trace: None,
           },
         )
         .unwrap();
@@ -750,6 +1063,7 @@ mod tests {
             source: format!("./{to}"),
             kind: ResolveKind::DynamicImport,
             order,
+            resolve_trace: None,
           },
         )
         .unwrap();
@@ -763,6 +1077,7 @@ mod tests {
           source: "./F".to_string(),
           kind: ResolveKind::Import,
           order: 0,
+          resolve_trace: None,
         },
       )
       .unwrap();
@@ -787,6 +1102,69 @@ mod tests {
     );
   }
 
+  #[test]
+  fn update_execution_order_for_modules_by() {
+    let graph_of = |edges: Vec<(&str, &str, usize)>| {
+      let mut graph = ModuleGraph::new();
+
+      for id in ["index", "a", "b", "c"] {
+        graph.add_module(Module::new(id.into()));
+      }
+
+      for (from, to, order) in edges {
+        graph
+          .add_edge_item(
+            &from.into(),
+            &to.into(),
+            ModuleGraphEdgeDataItem {
+              source: format!("./{to}"),
+              kind: ResolveKind::Import,
+              order,
+              resolve_trace: None,
+            },
+          )
+          .unwrap();
+      }
+
+      graph.entries = HashMap::from([("index".into(), "index".to_string())]);
+      graph
+    };
+    let execution_orders = |graph: &ModuleGraph| {
+      ["index", "a", "b", "c"]
+        .into_iter()
+        .map(|id| graph.module(&id.into()).unwrap().execution_order)
+        .collect::<Vec<_>>()
+    };
+
+    // `b` and `c` are imported at the same order, e.g. injected by plugins
+    let mut graph = graph_of(vec![
+      ("index", "c", 1),
+      ("index", "b", 1),
+      ("index", "a", 0),
+    ]);
+    let mut reinserted = graph_of(vec![
+      ("index", "a", 0),
+      ("index", "b", 1),
+      ("index", "c", 1),
+    ]);
+
+    graph.update_execution_order_for_modules_by(ExecutionOrderTieBreak::ImportOrder);
+    reinserted.update_execution_order_for_modules_by(ExecutionOrderTieBreak::ImportOrder);
+    assert_eq!(execution_orders(&graph), vec![3, 0, 1, 2]);
+    assert_eq!(execution_orders(&graph), execution_orders(&reinserted));
+
+    let mut graph = graph_of(vec![
+      ("index", "c", 0),
+      ("index", "b", 1),
+      ("index", "a", 2),
+    ]);
+    graph.update_execution_order_for_modules_by(ExecutionOrderTieBreak::ImportOrder);
+    assert_eq!(execution_orders(&graph), vec![3, 2, 1, 0]);
+
+    graph.update_execution_order_for_modules_by(ExecutionOrderTieBreak::Path);
+    assert_eq!(execution_orders(&graph), vec![3, 0, 1, 2]);
+  }
+
   #[test]
   fn dependencies() {
     let graph = construct_test_module_graph();
@@ -800,7 +1178,8 @@ mod tests {
           &ModuleGraphEdge(vec![ModuleGraphEdgeDataItem {
             source: "./C".to_string(),
             kind: ResolveKind::Import,
-            order: 0
+            order: 0,
+            resolve_trace: None,
           }])
         ),
         (
@@ -808,7 +1187,8 @@ mod tests {
           &ModuleGraphEdge(vec![ModuleGraphEdgeDataItem {
             source: "./D".to_string(),
             kind: ResolveKind::DynamicImport,
-            order: 1
+            order: 1,
+            resolve_trace: None,
           }])
         ),
       ]
@@ -842,4 +1222,87 @@ mod tests {
     assert!(!graph.has_edge(&"A".into(), &"D".into()));
     assert!(graph.has_edge(&"B".into(), &"D".into()));
   }
+
+  #[test]
+  fn import_chains() {
+    let mut graph = construct_test_module_graph();
+    graph.mark_edge_tree_shaken(&"A".into(), &"C".into());
+
+    let chains = graph
+      .import_chains(&"F".into())
+      .into_iter()
+      .map(|chain| {
+        chain
+          .into_iter()
+          .map(|step| (step.from.to_string(), step.to.to_string(), step.tree_shaken))
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+    let step = |from: &str, to: &str, tree_shaken| (from.to_string(), to.to_string(), tree_shaken);
+
+    assert_eq!(
+      chains,
+      vec![
+        vec![step("A", "C", true), step("C", "F", false)],
+        vec![step("A", "D", false), step("D", "F", false)],
+        vec![step("B", "D", false), step("D", "F", false)],
+      ]
+    );
+    assert_eq!(
+      graph.import_chains(&"D".into())[0][0].kinds,
+      vec![ResolveKind::DynamicImport]
+    );
+    // entries are in the bundle by themselves
+    assert_eq!(graph.import_chains(&"A".into()), vec![vec![]]);
+
+    graph.add_module(Module::new("H".into()));
+    assert!(graph.import_chains(&"H".into()).is_empty());
+  }
+
+  #[test]
+  fn to_dot_and_mermaid() {
+    let graph = construct_test_module_graph();
+
+    let dot = graph
+      .to_dot(&ModuleGraphFilter {
+        pattern: Some("^D$".to_string()),
+        depth: Some(1),
+      })
+      .unwrap();
+    assert_eq!(
+      dot,
+      "digraph modules {\n  \"D\";\n  \"F\";\n  \"D\" -> \"F\" [style=dashed];\n}\n"
+    );
+
+    let mermaid = graph
+      .to_mermaid(&ModuleGraphFilter {
+        pattern: None,
+        depth: Some(1),
+      })
+      .unwrap();
+    assert_eq!(
+      mermaid,
+      [
+        "graph LR",
+        "  m0[\"A\"]",
+        "  m1[\"B\"]",
+        "  m2[\"C\"]",
+        "  m3[\"D\"]",
+        "  m4[\"E\"]",
+        "  m0 --> m2",
+        "  m0 -.-> m3",
+        "  m1 --> m3",
+        "  m1 --> m4",
+        "",
+      ]
+      .join("\n")
+    );
+
+    assert!(graph
+      .to_dot(&ModuleGraphFilter {
+        pattern: Some("(".to_string()),
+        depth: None,
+      })
+      .is_err());
+  }
 }
@@ -11,7 +11,7 @@ use crate::resource::{resource_pot::ResourcePotId, resource_pot_map::ResourcePot
 use super::{module_graph::ModuleGraph, ModuleId};
 
 /// A `entry_module_id -> ModuleGroup` map
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ModuleGroupGraph {
   /// internal graph
   g: StableDiGraph<ModuleGroup, ()>,
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// A top level statement of a script module with its imports, exports and the statements it depends on,
+/// e.g. framework plugins use them to re-evaluate only the changed component definitions on hot updates
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleStatement {
+  /// index of the statement in the body of the module
+  pub index: usize,
+  /// source of `import ... from 'x'` or `export ... from 'x'`
+  pub source: Option<String>,
+  /// local names of the imported bindings
+  pub imported: Vec<String>,
+  /// exported names, `default` for the default export and `*` for `export * from 'x'`
+  pub exported: Vec<String>,
+  /// top level names defined by the statement
+  pub defined: Vec<String>,
+  /// indexes of the statements that define the top level names used by this statement
+  pub dependencies: Vec<usize>,
+  pub side_effects: StatementSideEffectsKind,
+}
+
+/// Side effects of executing a [ModuleStatement]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StatementSideEffectsKind {
+  /// e.g. `const a = 2;` or `function foo() {}`
+  NoSideEffects,
+  /// writes top level variables of the module, e.g. `a.prototype.b = 3;`
+  WriteTopLevelVar,
+  /// reads top level variables of the module, e.g. `const p = a.prototype;`
+  ReadTopLevelVar,
+  /// may modify global variables, e.g. `console.log('1');` or `window.b = 3;`
+  WriteOrCallGlobalVar,
+  /// self executed statements that are not classified, e.g. `foo();`
+  UnclassifiedSelfExecuted,
+}
+
+/// A name exported by a script module, e.g. tools use them to generate auto import indexes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleExport {
+  /// exported name, `default` for the default export and `*` for `export * from 'x'`
+  pub name: String,
+  /// the exported local name, or the imported name of `export { a as b } from 'x'`.
+  /// [None] for the default export and namespace re-exports
+  pub local: Option<String>,
+  /// source of the re-export
+  pub source: Option<String>,
+  /// index of the exporting statement in the body of the module
+  pub statement: usize,
+}
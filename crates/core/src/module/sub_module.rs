@@ -0,0 +1,80 @@
+//! Virtual sub modules of single file components, e.g. the `<script>`, `<template>` and `<style>` blocks of a `.vue` file.
+//! A plugin splits the file in [crate::plugin::Plugin::split_module] and the loaded parent module imports its blocks by
+//! [SubModule::source], each block is then loaded, transformed and cached as a module of its own whose source map is
+//! mapped to the block in the parent file.
+
+use std::path::Path;
+
+use farmfe_utils::{parse_query, stringify_query};
+
+use super::{ModuleId, ModuleType};
+
+/// query of the sub modules, e.g. `./App.vue?farm_sub_module=style.0`
+pub const SUB_MODULE_QUERY: &str = "farm_sub_module";
+
+/// A block of a single file component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubModule {
+  /// kind of the block, e.g. `script`, `template` or `style`
+  pub kind: String,
+  /// index of the block among the blocks of the same kind
+  pub index: usize,
+  /// byte range of the block in the loaded content of the parent module
+  pub start: usize,
+  pub end: usize,
+  /// type of the block, e.g. [ModuleType::Css] for `<style>` or [ModuleType::Custom] for `<style lang="scss">`
+  pub module_type: ModuleType,
+}
+
+impl SubModule {
+  /// The import source of the sub module in the code of the parent module, e.g. `./App.vue?farm_sub_module=style.0`
+  pub fn source(&self, parent_resolved_path: &str) -> String {
+    let file_name = Path::new(parent_resolved_path)
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default();
+
+    format!(
+      "./{file_name}{}",
+      stringify_query(&vec![(
+        SUB_MODULE_QUERY.to_string(),
+        format!("{}.{}", self.kind, self.index)
+      )])
+    )
+  }
+}
+
+/// The kind and the index of the sub module, [None] if the query is not the query of a sub module
+pub fn sub_module_of_query(query: &[(String, String)]) -> Option<(String, usize)> {
+  let (_, value) = query.iter().find(|(key, _)| key == SUB_MODULE_QUERY)?;
+  let (kind, index) = value.rsplit_once('.')?;
+
+  Some((kind.to_string(), index.parse().ok()?))
+}
+
+/// The query of the parent module of the sub module
+pub fn parent_query(query: &[(String, String)]) -> Vec<(String, String)> {
+  query
+    .iter()
+    .filter(|(key, _)| key != SUB_MODULE_QUERY)
+    .cloned()
+    .collect()
+}
+
+impl ModuleId {
+  pub fn is_sub_module(&self) -> bool {
+    sub_module_of_query(&parse_query(self.query_string())).is_some()
+  }
+
+  /// The id of the single file component the sub module is split from, [None] if the module is not a sub module
+  pub fn sub_module_parent(&self) -> Option<ModuleId> {
+    let query = parse_query(self.query_string());
+    sub_module_of_query(&query)?;
+
+    Some(ModuleId::from(format!(
+      "{}{}",
+      self.relative_path(),
+      stringify_query(&parent_query(&query))
+    )))
+  }
+}
@@ -1 +1,3 @@
 pub const PLUGIN_BUILD_STAGE_META_RESOLVE_KIND: &str = "__PLUGIN_BUILD_STAGE_META_RESOLVE_KIND";
+/// [super::ResolveKind::Custom] of `new Worker(new URL('./worker.ts', import.meta.url))`, the worker is a dynamic module group
+pub const WORKER_RESOLVE_KIND: &str = "dynamic:worker";
@@ -2,14 +2,19 @@ use std::{any::Any, collections::HashMap, hash::Hash, sync::Arc};
 
 use farmfe_macro_cache_item::cache_item;
 use serde::{Deserialize, Serialize};
+use swc_common::Mark;
+use swc_ecma_ast::Module as SwcModule;
+use swc_html_ast::Document;
 
+use self::resource_pot_renderer::ResourcePotRenderer;
 use crate::{
-  config::Config,
-  context::CompilationContext,
+  cache::migration::CacheMigration,
+  config::{Config, Mode},
+  context::{diagnostics::Diagnostic, hmr_channel::HmrCustomEvent, CompilationContext},
   error::Result,
   module::{
-    module_graph::ModuleGraph, module_group::ModuleGroupGraph, Module, ModuleId, ModuleMetaData,
-    ModuleType,
+    module_graph::ModuleGraph, module_group::ModuleGroupGraph, sub_module::SubModule, Module,
+    ModuleId, ModuleMetaData, ModuleSystem, ModuleType,
   },
   resource::{
     resource_pot::{ResourcePot, ResourcePotInfo, ResourcePotMetaData},
@@ -20,6 +25,7 @@ use crate::{
 
 pub mod constants;
 pub mod plugin_driver;
+pub mod resource_pot_renderer;
 
 pub const DEFAULT_PRIORITY: i32 = 100;
 
@@ -30,10 +36,34 @@ pub trait Plugin: Any + Send + Sync {
     DEFAULT_PRIORITY
   }
 
+  /// Identity of the options of this plugin, e.g. a hash of the options. Persisted results that depend on the
+  /// output of the hooks, like the transformed assets and the global cache, are invalidated when it changes
+  fn cache_key(&self) -> Option<String> {
+    None
+  }
+
+  /// Modify the config before the compilation context is created from it, in the order of the priorities.
+  /// Called after the `config` and `configResolved` hooks of js plugins, so the changes are not visible to them
   fn config(&self, _config: &mut Config) -> Result<Option<()>> {
     Ok(None)
   }
 
+  /// Read the final config after the `config` hooks of all plugins are called and the config is normalized
+  fn config_resolved(&self, _config: &Config) -> Result<Option<()>> {
+    Ok(None)
+  }
+
+  /// Renderers of custom resource pot types provided by this plugin, see [ResourcePotRenderer]
+  fn resource_pot_renderers(&self) -> Vec<Arc<dyn ResourcePotRenderer>> {
+    vec![]
+  }
+
+  /// Migrations of the persistent cache artifacts written in older schema versions, e.g. the plugin cache of this plugin,
+  /// see [crate::cache::migration]
+  fn cache_migrations(&self) -> Vec<Arc<dyn CacheMigration>> {
+    vec![]
+  }
+
   fn plugin_cache_loaded(
     &self,
     _cache: &Vec<u8>,
@@ -64,6 +94,16 @@ pub trait Plugin: Any + Send + Sync {
     Ok(None)
   }
 
+  /// Split a single file component, e.g. a `.vue` file, into the virtual sub modules of its blocks, see [SubModule].
+  /// Called with the loaded content of the module, the module is loaded as the returned content that imports the sub modules
+  fn split_module(
+    &self,
+    _param: &PluginSplitModuleHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<PluginSplitModuleHookResult>> {
+    Ok(None)
+  }
+
   fn transform(
     &self,
     _param: &PluginTransformHookParam,
@@ -89,6 +129,47 @@ pub trait Plugin: Any + Send + Sync {
     Ok(None)
   }
 
+  /// Detect the module systems that Farm does not handle natively, e.g. AMD or SystemJS,
+  /// return the name of the module system. Called after `process_module`
+  fn detect_module_system(
+    &self,
+    _param: &PluginDetectModuleSystemHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<String>> {
+    Ok(None)
+  }
+
+  /// Normalize a module of the detected custom module system to esm or commonjs, so its dependencies can be analyzed.
+  /// The module keeps [crate::module::ModuleSystem::Custom] if no plugin normalizes it, see `render_module_system`
+  fn normalize_module_system(
+    &self,
+    _param: &mut PluginNormalizeModuleSystemHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    Ok(None)
+  }
+
+  /// Render a module of a custom module system that is not normalized when building, e.g. replace the AMD `define` call
+  /// with `module.exports`. The module is rendered as is if no plugin renders it
+  fn render_module_system(
+    &self,
+    _param: &mut PluginRenderModuleSystemHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    Ok(None)
+  }
+
+  /// The module system that the rendered module of a custom module system interops with other modules as, e.g.
+  /// [crate::module::ModuleSystem::CommonJs] if `render_module_system` assigns `module.exports`. The rendered module is
+  /// transformed like a module of the returned module system, e.g. its `require` calls are resolved for commonjs
+  fn module_system_interop(
+    &self,
+    _param: &PluginModuleSystemInteropHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<ModuleSystem>> {
+    Ok(None)
+  }
+
   fn analyze_deps(
     &self,
     _param: &mut PluginAnalyzeDepsHookParam,
@@ -105,6 +186,19 @@ pub trait Plugin: Any + Send + Sync {
     Ok(None)
   }
 
+  /// Transform the content of an asset before it's emitted, e.g. convert png to avif.
+  /// [AssetVariant::Original] is passed in development, plugins should pass the content through to keep hmr fast.
+  /// The results are cached per variant by the static assets plugin.
+  /// For assets larger than `assets.streamThreshold`, `content` is empty and `source_path` is set, plugins read the file
+  /// themselves and set `content` to replace it, otherwise the file is copied as is
+  fn transform_asset(
+    &self,
+    _param: &mut PluginTransformAssetHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    Ok(None)
+  }
+
   /// The module graph should be constructed and finalized here
   fn build_end(&self, _context: &Arc<CompilationContext>) -> Result<Option<()>> {
     Ok(None)
@@ -133,6 +227,17 @@ pub trait Plugin: Any + Send + Sync {
     Ok(None)
   }
 
+  /// Pin the module to the resource pot of the returned name, like `partialBundling.enforceResources`.
+  /// Pinned modules skip [Plugin::partial_bundling], and the assignment is kept by updates so hot updates never reshuffle pinned resource pots.
+  /// The resource pot map is locked when this hook is called.
+  fn partition_resource_pots(
+    &self,
+    _module: &Module,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<String>> {
+    Ok(None)
+  }
+
   /// partial bundling modules to [Vec<ResourcePot>]
   fn partial_bundling(
     &self,
@@ -186,6 +291,16 @@ pub trait Plugin: Any + Send + Sync {
     Ok(None)
   }
 
+  /// Inject code or comments into the rendered resource pot, e.g. copyright banners or runtime feature detection snippets.
+  /// The injected code of all plugins is concatenated in the plugin order, after the code of `output.injections`
+  fn inject_resource_pot_code(
+    &self,
+    _resource_pot_info: &ResourcePotInfo,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<PluginInjectResourcePotCodeHookResult>> {
+    Ok(None)
+  }
+
   /// Optimize the resource pot, for example, minimize
   fn optimize_resource_pot(
     &self,
@@ -195,6 +310,16 @@ pub trait Plugin: Any + Send + Sync {
     Ok(None)
   }
 
+  /// Minify the rendered resource pot after it's optimized, only called for the resource pots that should be minified by `minify` config.
+  /// The first plugin that returns `Some` wins, so a plugin can replace the builtin minifier for some or all resource pots
+  fn minify_resource_pot(
+    &self,
+    _resource_pot: &mut ResourcePot,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    Ok(None)
+  }
+
   /// Generate resources based on the [ResourcePot], return [Resource] and [Option<SourceMap>]
   fn generate_resources(
     &self,
@@ -234,6 +359,16 @@ pub trait Plugin: Any + Send + Sync {
     Ok(None)
   }
 
+  /// Analyze the final html of each html entry after the resources are injected, e.g. checking the missing `lang` attribute.
+  /// Plugins report problems by pushing to `param.diagnostics`, they are added to the diagnostics of the context
+  fn analyze_html(
+    &self,
+    _param: &mut PluginAnalyzeHtmlHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    Ok(None)
+  }
+
   fn generate_end(&self, _context: &Arc<CompilationContext>) -> Result<Option<()>> {
     Ok(None)
   }
@@ -242,6 +377,16 @@ pub trait Plugin: Any + Send + Sync {
     Ok(None)
   }
 
+  /// Called for each changed path before the update pipeline runs.
+  /// Plugins can invalidate virtual modules or remap the change to other modules by modifying `params.paths`
+  fn watch_change(
+    &self,
+    _params: &mut PluginWatchChangeHookParams,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    Ok(None)
+  }
+
   /// Called when calling compiler.update(module_paths).
   /// Useful to do some operations like clearing previous state or ignore some files when performing HMR
   fn update_modules(
@@ -268,6 +413,16 @@ pub trait Plugin: Any + Send + Sync {
     Ok(None)
   }
 
+  /// Called when a hmr client sends a custom event by `import.meta.hot.send(event, data)`.
+  /// Plugins reply or push events to the clients by [crate::context::hmr_channel::HmrChannel::send]
+  fn hmr_event(
+    &self,
+    _event: &HmrCustomEvent,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    Ok(None)
+  }
+
   // Called when hit persistent cache. return false to invalidate the cache
   fn handle_persistent_cached_module(
     &self,
@@ -326,6 +481,11 @@ impl ResolveKind {
   pub fn is_require(&self) -> bool {
     matches!(self, ResolveKind::Require)
   }
+
+  /// worker if self is [ResolveKind::Custom] of [constants::WORKER_RESOLVE_KIND]
+  pub fn is_worker(&self) -> bool {
+    matches!(self, ResolveKind::Custom(c) if c == constants::WORKER_RESOLVE_KIND)
+  }
 }
 
 impl From<&str> for ResolveKind {
@@ -398,6 +558,8 @@ pub struct PluginResolveHookResult {
   pub query: Vec<(String, String)>,
   /// the meta data passed between plugins and hooks
   pub meta: HashMap<String, String>,
+  /// how the source is resolved, recorded on the module graph edge for debugging the dependencies
+  pub resolve_trace: Option<ResolveTrace>,
 }
 
 impl Default for PluginResolveHookResult {
@@ -408,10 +570,23 @@ impl Default for PluginResolveHookResult {
       external: false,
       query: vec![],
       meta: Default::default(),
+      resolve_trace: None,
     }
   }
 }
 
+/// How the resolve plugin resolved a source, e.g. by an alias or by the conditions of the `exports` field
+#[derive(Debug, Clone, Hash, PartialEq, Eq, serde::Serialize, serde::Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+#[cache_item]
+pub struct ResolveTrace {
+  /// the resolver path that resolved the source: `alias`, `imports`, `exports`, `browser`,
+  /// the main field like `module` or `main`, `index` for the index file of the package, or `path`
+  pub resolved_by: String,
+  /// conditions the `exports` or `imports` field is resolved with, e.g. `["browser", "default", "import"]`
+  pub conditions: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PluginLoadHookParam<'a> {
@@ -437,6 +612,22 @@ pub struct PluginLoadHookResult {
   pub source_map: Option<String>,
 }
 
+pub struct PluginSplitModuleHookParam<'a> {
+  pub module_id: &'a ModuleId,
+  pub resolved_path: &'a str,
+  /// the loaded content of the module
+  pub content: &'a str,
+  pub module_type: &'a ModuleType,
+}
+
+pub struct PluginSplitModuleHookResult {
+  /// the content of the module, importing the sub modules by [SubModule::source]
+  pub content: String,
+  pub module_type: ModuleType,
+  /// the blocks of the loaded content
+  pub sub_modules: Vec<SubModule>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PluginTransformHookParam<'a> {
@@ -489,6 +680,38 @@ pub struct PluginProcessModuleHookParam<'a> {
   pub meta: &'a mut ModuleMetaData,
 }
 
+pub struct PluginDetectModuleSystemHookParam<'a> {
+  pub module_id: &'a ModuleId,
+  pub module_type: &'a ModuleType,
+  pub content: &'a Arc<String>,
+  pub meta: &'a ModuleMetaData,
+}
+
+pub struct PluginNormalizeModuleSystemHookParam<'a> {
+  pub module_id: &'a ModuleId,
+  pub module_type: &'a ModuleType,
+  /// name of the module system returned by `detect_module_system`
+  pub module_system: &'a str,
+  pub meta: &'a mut ModuleMetaData,
+}
+
+pub struct PluginRenderModuleSystemHookParam<'a> {
+  pub module_id: &'a ModuleId,
+  /// name of the custom module system, see `detect_module_system`
+  pub module_system: &'a str,
+  /// the cloned ast of the module, the ast of the module graph is not changed
+  pub ast: &'a mut SwcModule,
+  /// marks of the ast, a plugin that replaces the ast should resolve the marks of the new ast
+  pub unresolved_mark: Mark,
+  pub top_level_mark: Mark,
+}
+
+pub struct PluginModuleSystemInteropHookParam<'a> {
+  pub module_id: &'a ModuleId,
+  /// name of the custom module system, see `detect_module_system`
+  pub module_system: &'a str,
+}
+
 #[derive(Clone)]
 pub struct PluginAnalyzeDepsHookParam<'a> {
   pub module: &'a Module,
@@ -508,6 +731,46 @@ pub struct PluginFinalizeModuleHookParam<'a> {
   pub deps: &'a Vec<PluginAnalyzeDepsHookResultEntry>,
 }
 
+/// Which variant of an asset is emitted, [AssetVariant::Original] in development and [AssetVariant::Optimized] in production
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AssetVariant {
+  Original,
+  Optimized,
+}
+
+impl AssetVariant {
+  pub fn from_mode(mode: &Mode) -> Self {
+    match mode {
+      Mode::Development => Self::Original,
+      Mode::Production => Self::Optimized,
+    }
+  }
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Original => "original",
+      Self::Optimized => "optimized",
+    }
+  }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginTransformAssetHookParam {
+  pub module_id: ModuleId,
+  pub resolved_path: String,
+  pub variant: AssetVariant,
+  /// the target the asset is emitted for, e.g. `webview`, see [crate::config::asset::AssetsConfig::targets]
+  pub target: Option<String>,
+  /// extension of the emitted file, change it when the format of the content is changed
+  pub ext: String,
+  pub content: Vec<u8>,
+  /// path of the asset streamed from the disk, `content` is empty until a plugin fills it
+  #[serde(default)]
+  pub source_path: Option<String>,
+}
+
 #[derive(Default, Debug, serde::Serialize, serde::Deserialize, Clone)]
 pub struct WatchDiffResult {
   pub add: Vec<String>,
@@ -525,10 +788,54 @@ pub struct UpdateResult {
   /// This code string should be returned to the client side as MIME type `application/javascript`
   pub immutable_resources: String,
   pub mutable_resources: String,
+  /// css text of the updated css modules, the runtime patches their styles in place without re-executing them
+  pub css_updates: HashMap<ModuleId, String>,
   pub boundaries: HashMap<String, Vec<Vec<String>>>,
+  /// boundary module id -> the updated dependencies it accepts by `import.meta.hot.accept(deps, cb)`
+  pub accepted_deps: HashMap<String, Vec<String>>,
+  /// why the updated modules are not hot updated and the page is reloaded, [None] if all of them are accepted by the boundaries
+  pub hmr_full_reload: Option<HmrFullReload>,
   pub dynamic_resources_map: Option<HashMap<ModuleId, Vec<(String, ResourceType)>>>,
   pub extra_watch_result: WatchDiffResult,
+  /// names of the resources removed with the removed modules
+  pub removed_resources: Vec<String>,
+  /// names of the html resources whose injected resources changed, the page should be reloaded to apply them
+  pub updated_html_resources: Vec<String>,
+  /// ids of the web workers affected by the update, they are not hot updated and should be reloaded
+  pub updated_workers: Vec<ModuleId>,
+  /// why the update is escalated to a full compilation, the page should be reloaded to apply it, see `adaptiveRebuild`
+  pub full_rebuild_reason: Option<String>,
+}
+
+/// The propagation path of an update that is not accepted by any boundary, so the page is reloaded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HmrFullReload {
+  /// the updated module
+  pub module: String,
+  /// the importers from the updated module to the module that stops the propagation
+  pub path: Vec<String>,
+  pub reason: HmrFullReloadReason,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HmrFullReloadReason {
+  /// the update reaches an entry that doesn't accept it
+  Entry,
+  /// the last importer is not a script module, which can't accept the update
+  NonScriptImporter,
+}
+
+impl HmrFullReloadReason {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      HmrFullReloadReason::Entry => "entry",
+      HmrFullReloadReason::NonScriptImporter => "nonScriptImporter",
+    }
+  }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum UpdateType {
   // added a new module
@@ -539,6 +846,44 @@ pub enum UpdateType {
   Removed,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchChangeEvent {
+  Create,
+  Update,
+  Delete,
+}
+
+impl From<&UpdateType> for WatchChangeEvent {
+  fn from(value: &UpdateType) -> Self {
+    match value {
+      UpdateType::Added => WatchChangeEvent::Create,
+      UpdateType::Updated => WatchChangeEvent::Update,
+      UpdateType::Removed => WatchChangeEvent::Delete,
+    }
+  }
+}
+
+impl From<&WatchChangeEvent> for UpdateType {
+  fn from(value: &WatchChangeEvent) -> Self {
+    match value {
+      WatchChangeEvent::Create => UpdateType::Added,
+      WatchChangeEvent::Update => UpdateType::Updated,
+      WatchChangeEvent::Delete => UpdateType::Removed,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginWatchChangeHookParams {
+  /// the changed path
+  pub id: String,
+  pub event: WatchChangeEvent,
+  /// paths to update for this change, default to the changed path itself
+  pub paths: Vec<(String, UpdateType)>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", default)]
 pub struct PluginUpdateModulesHookParams {
@@ -575,6 +920,39 @@ pub struct PluginRenderResourcePotHookParam {
   pub resource_pot_info: ResourcePotInfo,
 }
 
+/// `intro` is prepended before the resource pot is optimized, so it is minified together with the resource pot.
+/// `banner` and `footer` are added after the resource pot is optimized, so comments like copyright banners are preserved
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PluginInjectResourcePotCodeHookResult {
+  pub banner: String,
+  pub intro: String,
+  pub footer: String,
+}
+
+impl PluginInjectResourcePotCodeHookResult {
+  /// Append the code of another injection, every piece of injected code takes its own lines
+  pub fn merge(&mut self, other: &PluginInjectResourcePotCodeHookResult) {
+    let push_line = |target: &mut String, code: &str| {
+      if !code.is_empty() {
+        target.push_str(code);
+
+        if !code.ends_with('\n') {
+          target.push('\n');
+        }
+      }
+    };
+
+    push_line(&mut self.banner, &other.banner);
+    push_line(&mut self.intro, &other.intro);
+    push_line(&mut self.footer, &other.footer);
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.banner.is_empty() && self.intro.is_empty() && self.footer.is_empty()
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PluginRenderResourcePotHookResult {
   pub content: String,
@@ -591,6 +969,15 @@ pub struct PluginFinalizeResourcesHookParams<'a> {
   pub config: &'a Config,
 }
 
+pub struct PluginAnalyzeHtmlHookParam<'a> {
+  /// the html entry module
+  pub module_id: &'a ModuleId,
+  /// name of the html resource, e.g. `index.html`
+  pub resource_name: &'a str,
+  pub ast: &'a Document,
+  pub diagnostics: Vec<Diagnostic>,
+}
+
 pub struct PluginHandleEntryResourceHookParams<'a> {
   pub resource: &'a mut Resource,
   pub module_graph: &'a ModuleGraph,
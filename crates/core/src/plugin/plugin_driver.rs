@@ -1,32 +1,73 @@
-use std::sync::Arc;
+use std::{cell::RefCell, collections::HashMap, panic::AssertUnwindSafe, sync::Arc};
 
+use farmfe_utils::hash::sha256;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
 use super::{
-  Plugin, PluginAnalyzeDepsHookParam, PluginDriverRenderResourcePotHookResult,
-  PluginFinalizeModuleHookParam, PluginFinalizeResourcesHookParams,
-  PluginGenerateResourcesHookResult, PluginHandleEntryResourceHookParams, PluginHookContext,
+  resource_pot_renderer::ResourcePotRenderer, Plugin, PluginAnalyzeDepsHookParam,
+  PluginAnalyzeHtmlHookParam, PluginDetectModuleSystemHookParam,
+  PluginDriverRenderResourcePotHookResult, PluginFinalizeModuleHookParam,
+  PluginFinalizeResourcesHookParams, PluginGenerateResourcesHookResult,
+  PluginHandleEntryResourceHookParams, PluginHookContext, PluginInjectResourcePotCodeHookResult,
   PluginLoadHookParam, PluginLoadHookResult, PluginModuleGraphUpdatedHookParams,
-  PluginParseHookParam, PluginProcessModuleHookParam, PluginRenderResourcePotHookParam,
-  PluginResolveHookParam, PluginResolveHookResult, PluginTransformHookParam,
-  PluginUpdateModulesHookParams,
+  PluginModuleSystemInteropHookParam, PluginNormalizeModuleSystemHookParam, PluginParseHookParam,
+  PluginProcessModuleHookParam, PluginRenderModuleSystemHookParam,
+  PluginRenderResourcePotHookParam, PluginResolveHookParam, PluginResolveHookResult,
+  PluginSplitModuleHookParam, PluginSplitModuleHookResult, PluginTransformAssetHookParam,
+  PluginTransformHookParam, PluginUpdateModulesHookParams, PluginWatchChangeHookParams,
 };
 use crate::{
+  cache::migration::CacheMigrations,
   config::Config,
-  context::CompilationContext,
+  context::{hmr_channel::HmrCustomEvent, panic_message, CompilationContext},
   error::Result,
   module::{
     module_graph::ModuleGraph, module_group::ModuleGroupGraph, Module, ModuleId, ModuleMetaData,
-    ModuleType,
+    ModuleSystem, ModuleType,
   },
-  resource::resource_pot::{ResourcePot, ResourcePotInfo, ResourcePotMetaData},
+  resource::resource_pot::{ResourcePot, ResourcePotInfo, ResourcePotMetaData, ResourcePotType},
   stats::{CompilationModuleGraphStats, CompilationPluginHookStats, Stats},
 };
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 
+thread_local! {
+  /// the plugin whose hook is running on the current thread, panics are attributed to it
+  static CURRENT_PLUGIN: RefCell<Option<Arc<dyn Plugin>>> = const { RefCell::new(None) };
+}
+
+/// Mark the plugin as running until the guard is dropped. The plugin is kept when its hook panics, see [take_panicked_plugin]
+struct CurrentPluginGuard {
+  previous: Option<Arc<dyn Plugin>>,
+}
+
+impl CurrentPluginGuard {
+  fn enter(plugin: &Arc<dyn Plugin>) -> Self {
+    Self {
+      previous: CURRENT_PLUGIN.with(|current| current.replace(Some(plugin.clone()))),
+    }
+  }
+}
+
+impl Drop for CurrentPluginGuard {
+  fn drop(&mut self) {
+    if !std::thread::panicking() {
+      CURRENT_PLUGIN.with(|current| *current.borrow_mut() = self.previous.take());
+    }
+  }
+}
+
+/// Name of the plugin whose hook panicked on the current thread, should be called after the panic is caught
+pub fn take_panicked_plugin() -> Option<String> {
+  CURRENT_PLUGIN
+    .with(|current| current.borrow_mut().take())
+    .map(|plugin| plugin.name().to_string())
+}
+
 pub struct PluginDriver {
   pub plugins: Vec<Arc<dyn Plugin>>,
+  /// registered renderers of custom resource pot types, the plugin with higher priority wins if a type is registered twice
+  resource_pot_renderers: HashMap<ResourcePotType, Arc<dyn ResourcePotRenderer>>,
   record: bool,
 }
 
@@ -38,6 +79,7 @@ macro_rules! hook_first {
   ) => {
       pub fn $func_name(&self, $($arg: $ty),*) -> $ret_ty {
           for plugin in &self.plugins {
+            let _guard = CurrentPluginGuard::enter(plugin);
               let ret = plugin.$func_name($($arg),*)?;
               if ret.is_some() {
                 return Ok(ret);
@@ -56,6 +98,7 @@ macro_rules! hook_first {
   ) => {
       pub fn $func_name(&self, $($arg: $ty),*) -> $ret_ty {
           for plugin in &self.plugins {
+            let _guard = CurrentPluginGuard::enter(plugin);
             if self.record {
               let start_time = SystemTime::now()
               .duration_since(UNIX_EPOCH)
@@ -88,6 +131,7 @@ macro_rules! hook_serial {
   ($func_name:ident, $param_ty:ty) => {
     pub fn $func_name(&self, param: $param_ty, context: &Arc<CompilationContext>) -> Result<()> {
       for plugin in &self.plugins {
+        let _guard = CurrentPluginGuard::enter(plugin);
         plugin.$func_name(param, context)?;
       }
 
@@ -98,6 +142,7 @@ macro_rules! hook_serial {
   ($func_name:ident, $param_ty:ty, $before_transformer:expr, $after_transformer:expr, $callback:expr) => {
     pub fn $func_name(&self, param: $param_ty, context: &Arc<CompilationContext>) -> Result<()> {
       for plugin in &self.plugins {
+        let _guard = CurrentPluginGuard::enter(plugin);
         if self.record {
           let start_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -139,6 +184,7 @@ macro_rules! hook_parallel {
         .plugins
         .par_iter()
         .try_for_each(|plugin| {
+          let _guard = CurrentPluginGuard::enter(plugin);
           let ret = plugin.$func_name(context).map(|_| ());
           return ret;
         })
@@ -151,6 +197,7 @@ macro_rules! hook_parallel {
         .plugins
         .par_iter()
         .try_for_each(|plugin| {
+          let _guard = CurrentPluginGuard::enter(plugin);
           let ret = plugin.$func_name(context).map(|_| ());
           if self.record {
             let plugin_name = plugin.name().to_string();
@@ -167,6 +214,7 @@ macro_rules! hook_parallel {
         .plugins
         .par_iter()
         .try_for_each(|plugin| {
+          let _guard = CurrentPluginGuard::enter(plugin);
           let ret = plugin.$func_name($($arg),+, context).map(|_| ());
           return ret;
         })
@@ -179,6 +227,7 @@ macro_rules! hook_parallel {
         .plugins
         .par_iter()
         .try_for_each(|plugin| {
+          let _guard = CurrentPluginGuard::enter(plugin);
           let ret = plugin.$func_name($($arg),+, context).map(|_| ());
           if self.record {
             let plugin_name = plugin.name().to_string();
@@ -194,16 +243,70 @@ impl PluginDriver {
   pub fn new(mut plugins: Vec<Arc<dyn Plugin>>, record: bool) -> Self {
     plugins.sort_by_key(|b| std::cmp::Reverse(b.priority()));
 
-    Self { plugins, record }
+    let mut resource_pot_renderers = HashMap::new();
+
+    for plugin in &plugins {
+      for renderer in plugin.resource_pot_renderers() {
+        resource_pot_renderers
+          .entry(renderer.resource_pot_type())
+          .or_insert(renderer);
+      }
+    }
+
+    Self {
+      plugins,
+      resource_pot_renderers,
+      record,
+    }
+  }
+
+  pub fn cache_migrations(&self) -> CacheMigrations {
+    CacheMigrations::new(
+      self
+        .plugins
+        .iter()
+        .flat_map(|plugin| plugin.cache_migrations())
+        .collect(),
+    )
+  }
+
+  /// Hash of the names and the [Plugin::cache_key] of the plugins, changes when a plugin is added, removed or reconfigured
+  pub fn plugins_cache_key(&self) -> String {
+    let keys = self
+      .plugins
+      .iter()
+      .map(|plugin| (plugin.name(), plugin.cache_key()))
+      .collect::<Vec<_>>();
+
+    sha256(
+      serde_json::to_string(&keys).unwrap_or_default().as_bytes(),
+      32,
+    )
+  }
+
+  pub fn resource_pot_renderer(
+    &self,
+    resource_pot_type: &ResourcePotType,
+  ) -> Option<&Arc<dyn ResourcePotRenderer>> {
+    self.resource_pot_renderers.get(resource_pot_type)
   }
 
   pub fn config(&self, config: &mut Config) -> Result<()> {
     for plugin in &self.plugins {
+      let _guard = CurrentPluginGuard::enter(plugin);
       plugin.config(config)?;
     }
     Ok(())
   }
 
+  pub fn config_resolved(&self, config: &Config) -> Result<()> {
+    for plugin in &self.plugins {
+      let _guard = CurrentPluginGuard::enter(plugin);
+      plugin.config_resolved(config)?;
+    }
+    Ok(())
+  }
+
   pub fn plugin_cache_loaded(&self, context: &Arc<CompilationContext>) -> Result<()> {
     let start_time = if context.config.record {
       SystemTime::now()
@@ -215,32 +318,53 @@ impl PluginDriver {
     };
 
     for plugin in &self.plugins {
-      if let Some(plugin_cache) = context.cache_manager.plugin_cache.read_cache(plugin.name()) {
-        plugin.plugin_cache_loaded(plugin_cache.value(), context)?;
-
-        if context.config.record {
-          let end_time = if context.config.record {
-            SystemTime::now()
-              .duration_since(UNIX_EPOCH)
-              .expect("hook_first get end_time failed")
-              .as_millis()
-          } else {
-            0
-          };
-          context
-            .record_manager
-            .add_plugin_hook_stats(CompilationPluginHookStats {
-              plugin_name: plugin.name().to_string(),
-              hook_name: "plugin_cache_loaded".to_string(),
-              hook_context: None,
-              module_id: "root".into(),
-              input: "".to_string(),
-              output: "".to_string(),
-              duration: end_time - start_time,
-              start_time,
-              end_time,
-            });
+      let _guard = CurrentPluginGuard::enter(plugin);
+      let result = match context.cache_manager.plugin_cache.read_cache(plugin.name()) {
+        Some(plugin_cache) => {
+          catch_plugin_cache_error(|| plugin.plugin_cache_loaded(plugin_cache.value(), context))
         }
+        None => continue,
+      };
+
+      // the cache of a plugin may be written by an incompatible version of it, only its own cache is discarded and
+      // rebuilt in this compilation, the caches of the other plugins are still used
+      if let Err(reason) = result {
+        context
+          .cache_manager
+          .plugin_cache
+          .invalidate_cache(plugin.name());
+        context.logger.warn(
+          module_path!(),
+          format!(
+            "failed to load the cache of plugin {}, it's discarded and will be rebuilt: {reason}",
+            plugin.name()
+          ),
+        );
+        continue;
+      }
+
+      if context.config.record {
+        let end_time = if context.config.record {
+          SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("hook_first get end_time failed")
+            .as_millis()
+        } else {
+          0
+        };
+        context
+          .record_manager
+          .add_plugin_hook_stats(CompilationPluginHookStats {
+            plugin_name: plugin.name().to_string(),
+            hook_name: "plugin_cache_loaded".to_string(),
+            hook_context: None,
+            module_id: "root".into(),
+            input: "".to_string(),
+            output: "".to_string(),
+            duration: end_time - start_time,
+            start_time,
+            end_time,
+          });
       }
     }
 
@@ -313,6 +437,13 @@ impl PluginDriver {
     _hook_context: &PluginHookContext
   );
 
+  hook_first!(
+    split_module,
+    Result<Option<PluginSplitModuleHookResult>>,
+    param: &PluginSplitModuleHookParam,
+    context: &Arc<CompilationContext>
+  );
+
   pub fn transform(
     &self,
     mut param: PluginTransformHookParam<'_>,
@@ -330,6 +461,7 @@ impl PluginDriver {
     transform_fn();
 
     for plugin in &self.plugins {
+      let _guard = CurrentPluginGuard::enter(plugin);
       let start_time = if self.record {
         Some(
           SystemTime::now()
@@ -450,6 +582,34 @@ impl PluginDriver {
     }
   );
 
+  hook_first!(
+    detect_module_system,
+    Result<Option<String>>,
+    param: &PluginDetectModuleSystemHookParam,
+    context: &Arc<CompilationContext>
+  );
+
+  hook_first!(
+    normalize_module_system,
+    Result<Option<()>>,
+    param: &mut PluginNormalizeModuleSystemHookParam,
+    context: &Arc<CompilationContext>
+  );
+
+  hook_first!(
+    render_module_system,
+    Result<Option<()>>,
+    param: &mut PluginRenderModuleSystemHookParam,
+    context: &Arc<CompilationContext>
+  );
+
+  hook_first!(
+    module_system_interop,
+    Result<Option<ModuleSystem>>,
+    param: &PluginModuleSystemInteropHookParam,
+    context: &Arc<CompilationContext>
+  );
+
   hook_serial!(
     analyze_deps,
     &mut PluginAnalyzeDepsHookParam,
@@ -484,6 +644,8 @@ impl PluginDriver {
 
   hook_serial!(finalize_module, &mut PluginFinalizeModuleHookParam);
 
+  hook_serial!(transform_asset, &mut PluginTransformAssetHookParam);
+
   hook_parallel!(build_end);
 
   hook_parallel!(generate_start);
@@ -552,6 +714,13 @@ impl PluginDriver {
     _hook_context: &PluginHookContext
   );
 
+  hook_first!(
+    partition_resource_pots,
+    Result<Option<String>>,
+    module: &Module,
+    context: &Arc<CompilationContext>
+  );
+
   hook_first!(
     partial_bundling,
     Result<Option<Vec<ResourcePot>>>,
@@ -656,6 +825,7 @@ impl PluginDriver {
     context: &Arc<CompilationContext>,
   ) -> Result<PluginDriverRenderResourcePotHookResult> {
     for plugin in &self.plugins {
+      let _guard = CurrentPluginGuard::enter(plugin);
       let start_time = if context.config.record {
         std::time::SystemTime::now()
           .duration_since(std::time::UNIX_EPOCH)
@@ -710,6 +880,7 @@ impl PluginDriver {
     let mut result: Option<String> = None;
 
     for plugin in &self.plugins {
+      let _guard = CurrentPluginGuard::enter(plugin);
       if let Some(plugin_result) = plugin.augment_resource_hash(render_pot_info, context)? {
         match result {
           Some(ref mut result) => {
@@ -725,6 +896,24 @@ impl PluginDriver {
     Ok(result)
   }
 
+  /// Code injected by all plugins, the injected code of different plugins is concatenated
+  pub fn inject_resource_pot_code(
+    &self,
+    resource_pot_info: &ResourcePotInfo,
+    context: &Arc<CompilationContext>,
+  ) -> Result<PluginInjectResourcePotCodeHookResult> {
+    let mut result = PluginInjectResourcePotCodeHookResult::default();
+
+    for plugin in &self.plugins {
+      let _guard = CurrentPluginGuard::enter(plugin);
+      if let Some(plugin_result) = plugin.inject_resource_pot_code(resource_pot_info, context)? {
+        result.merge(&plugin_result);
+      }
+    }
+
+    Ok(result)
+  }
+
   hook_serial!(
     optimize_resource_pot,
     &mut ResourcePot,
@@ -755,6 +944,13 @@ impl PluginDriver {
     }
   );
 
+  hook_first!(
+    minify_resource_pot,
+    Result<Option<()>>,
+    resource_pot: &mut ResourcePot,
+    context: &Arc<CompilationContext>
+  );
+
   hook_first!(
     generate_resources,
     Result<Option<PluginGenerateResourcesHookResult>>,
@@ -801,6 +997,8 @@ impl PluginDriver {
 
   hook_serial!(finalize_resources, &mut PluginFinalizeResourcesHookParams);
 
+  hook_serial!(analyze_html, &mut PluginAnalyzeHtmlHookParam);
+
   hook_parallel!(generate_end);
 
   hook_parallel!(
@@ -809,6 +1007,38 @@ impl PluginDriver {
     |_plugin_name: String, _context: &Arc<CompilationContext>| {}
   );
 
+  hook_serial!(
+    watch_change,
+    &mut PluginWatchChangeHookParams,
+    |before_params: &mut PluginWatchChangeHookParams| {
+      serde_json::to_string(&before_params).unwrap()
+    },
+    |after_params: &mut PluginWatchChangeHookParams| {
+      serde_json::to_string(&after_params).unwrap()
+    },
+    |plugin_name: String,
+     start_time: u128,
+     end_time: u128,
+     input: String,
+     output: String,
+     _: &mut PluginWatchChangeHookParams,
+     context: &Arc<CompilationContext>| {
+      context
+        .record_manager
+        .add_plugin_hook_stats(CompilationPluginHookStats {
+          plugin_name,
+          hook_name: "watch_change".to_string(),
+          hook_context: None,
+          module_id: "".into(),
+          input,
+          output,
+          duration: end_time - start_time,
+          start_time,
+          end_time,
+        })
+    }
+  );
+
   hook_serial!(
     update_modules,
     &mut PluginUpdateModulesHookParams,
@@ -848,6 +1078,8 @@ impl PluginDriver {
 
   hook_parallel!(update_finished);
 
+  hook_serial!(hmr_event, &HmrCustomEvent);
+
   hook_first!(
     handle_persistent_cached_module,
     Result<Option<bool>>,
@@ -882,6 +1114,7 @@ impl PluginDriver {
 
   pub fn write_plugin_cache(&self, context: &Arc<CompilationContext>) -> Result<()> {
     for plugin in &self.plugins {
+      let _guard = CurrentPluginGuard::enter(plugin);
       let start_time = if context.config.record {
         SystemTime::now()
           .duration_since(UNIX_EPOCH)
@@ -891,7 +1124,24 @@ impl PluginDriver {
         0
       };
 
-      let plugin_cache = plugin.write_plugin_cache(context)?;
+      let plugin_cache = match catch_plugin_cache_error(|| plugin.write_plugin_cache(context)) {
+        Ok(plugin_cache) => plugin_cache,
+        Err(reason) => {
+          // the stale cache is discarded as well, so it's not loaded by the next compilation
+          context
+            .cache_manager
+            .plugin_cache
+            .invalidate_cache(plugin.name());
+          context.logger.warn(
+            module_path!(),
+            format!(
+              "failed to write the cache of plugin {}, it's discarded: {reason}",
+              plugin.name()
+            ),
+          );
+          continue;
+        }
+      };
 
       if let Some(plugin_cache) = plugin_cache {
         context
@@ -930,6 +1180,15 @@ impl PluginDriver {
   }
 }
 
+/// Run a cache hook of a plugin, its error or panic, e.g. deserializing a cache of another version, is returned as the reason
+fn catch_plugin_cache_error<R>(f: impl FnOnce() -> Result<R>) -> std::result::Result<R, String> {
+  match std::panic::catch_unwind(AssertUnwindSafe(f)) {
+    Ok(Ok(result)) => Ok(result),
+    Ok(Err(err)) => Err(err.to_string()),
+    Err(payload) => Err(panic_message(payload)),
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct PluginDriverTransformHookResult {
   pub content: String,
@@ -975,6 +1234,7 @@ mod tests {
               side_effects: false,
               query: vec![],
               meta: HashMap::new(),
+              resolve_trace: None,
             }))
           } else {
             Ok(None)
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use crate::{
+  context::CompilationContext,
+  error::Result,
+  resource::resource_pot::{ResourcePot, ResourcePotMetaData, ResourcePotType},
+};
+
+use super::{PluginGenerateResourcesHookResult, PluginHookContext};
+
+/// Renderer of a resource pot type that is not supported by the core plugins, e.g. `wasm`, `json` or `worklet`.
+/// Renderers are registered by [super::Plugin::resource_pot_renderers]. Resource pots of the registered type are rendered and generated by the renderer,
+/// the `render_resource_pot_modules` and `generate_resources` hooks are skipped for them. Other hooks like `optimize_resource_pot` still run.
+///
+/// Modules of `ModuleType::Custom(name)` are bundled into resource pots of `ResourcePotType::Custom(name)`.
+pub trait ResourcePotRenderer: Send + Sync {
+  fn resource_pot_type(&self) -> ResourcePotType;
+
+  fn render_resource_pot_modules(
+    &self,
+    resource_pot: &ResourcePot,
+    context: &Arc<CompilationContext>,
+    hook_context: &PluginHookContext,
+  ) -> Result<ResourcePotMetaData>;
+
+  fn generate_resources(
+    &self,
+    resource_pot: &mut ResourcePot,
+    context: &Arc<CompilationContext>,
+    hook_context: &PluginHookContext,
+  ) -> Result<PluginGenerateResourcesHookResult>;
+}
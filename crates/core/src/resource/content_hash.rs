@@ -0,0 +1,457 @@
+//! Cascading content hashes of resource names.
+//!
+//! The content of a resource may contain the names of other resources, e.g. the dynamic resources map of an entry
+//! or the script tags of a html, so the final content hash of a resource is unknown until all resources are finalized.
+//! When a resource pot is rendered, the content hash in its name is a placeholder created by [create_hash_placeholder].
+//! After the `finalize_resources` hook, [replace_hash_placeholders] computes the hash of every resource from its own content
+//! and the hashes of the resources it references, then replaces all placeholders in names and contents.
+//! So when a resource changes, the names of all resources that reference it change too.
+//! Resources added by the `finalize_resources` hook take part in the cascade by using [CONTENT_HASH_TOKEN] in their names.
+//! The trailing source map comment of js and css is not hashed, so enabling, inlining or renaming source maps doesn't rename the resources.
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use regex::{bytes::Regex as BytesRegex, Regex};
+
+use crate::config::hash::HashConfig;
+
+use super::{Resource, ResourceType, LICENSE_RESOURCE_TYPE};
+
+pub const HASH_PLACEHOLDER_BOUNDARY: char = '~';
+/// Replaced by the content hash of the resource in the names of resources added by plugins, e.g. `manifest.[contenthash].json`
+pub const CONTENT_HASH_TOKEN: &str = "[contenthash]";
+/// Extensions of the assets whose content may reference other resources by name
+const TEXT_ASSET_EXTS: [&str; 7] = ["json", "html", "htm", "txt", "xml", "webmanifest", "map"];
+
+/// Create a placeholder that has the same length as the final hash, so the source maps are still correct after the placeholder is replaced.
+/// `key` should identify the resource pot and its rendered content, e.g. the resource pot id and the content
+pub fn create_hash_placeholder(hash_config: &HashConfig, key: &[u8]) -> String {
+  let len = hash_config.length();
+
+  format!(
+    "{HASH_PLACEHOLDER_BOUNDARY}{}{HASH_PLACEHOLDER_BOUNDARY}",
+    hash_config.hash(key, len - 2)
+  )
+}
+
+fn hash_placeholder_pattern(hash_config: &HashConfig) -> String {
+  format!(
+    "{HASH_PLACEHOLDER_BOUNDARY}[0-9a-f]{{{}}}{HASH_PLACEHOLDER_BOUNDARY}",
+    hash_config.length() - 2
+  )
+}
+
+impl Resource {
+  /// Hash of the content of the resource, large assets are hashed from [Resource::source_path] in chunks
+  pub fn content_hash(&self, hash_config: &HashConfig) -> String {
+    let len = hash_config.length();
+
+    if let Some(source_path) = &self.source_path {
+      if let Ok(hash) = std::fs::File::open(source_path)
+        .and_then(|file| hash_config.hash_reader(std::io::BufReader::new(file), len))
+      {
+        return hash;
+      }
+    }
+
+    hash_config.hash(self.hashed_bytes(), len)
+  }
+
+  /// The content without the trailing `sourceMappingURL` comment of js and css
+  fn hashed_bytes(&self) -> &[u8] {
+    let marker: &[u8] = match self.resource_type {
+      ResourceType::Js => b"\n//# sourceMappingURL=",
+      ResourceType::Css => b"\n/*# sourceMappingURL=",
+      _ => return &self.bytes,
+    };
+
+    let Some(start) = self
+      .bytes
+      .windows(marker.len())
+      .rposition(|window| window == marker)
+    else {
+      return &self.bytes;
+    };
+    // the comment must be the last line
+    let is_last_line = !self.bytes[start + 1..]
+      .iter()
+      .rev()
+      .skip_while(|b| b.is_ascii_whitespace())
+      .any(|b| *b == b'\n');
+
+    if is_last_line {
+      &self.bytes[..start]
+    } else {
+      &self.bytes
+    }
+  }
+
+  /// Whether the content may contain hash placeholders. Binary assets and wasm are emitted from the sources before resource pots
+  /// are rendered, but text assets like the routes manifest or the entry manifest are generated from the names of other resources
+  fn may_contain_hash_placeholders(&self) -> bool {
+    if self.source_path.is_some() {
+      return false;
+    }
+
+    match &self.resource_type {
+      ResourceType::Asset(ext) => TEXT_ASSET_EXTS.contains(&ext.as_str()),
+      ResourceType::Wasm => false,
+      _ => true,
+    }
+  }
+}
+
+/// Replace [CONTENT_HASH_TOKEN] in resource names with hash placeholders and key every resource by its name,
+/// so plugins can add, remove or rename resources by changing `resources_map` and [Resource::name] in the `finalize_resources` hook
+pub fn normalize_finalized_resources(
+  resources_map: &mut HashMap<String, Resource>,
+  hash_config: &HashConfig,
+) {
+  let needs_normalize = resources_map
+    .iter()
+    .any(|(key, resource)| key != &resource.name || resource.name.contains(CONTENT_HASH_TOKEN));
+
+  if !needs_normalize {
+    return;
+  }
+
+  let resources = std::mem::take(resources_map);
+
+  for (_, mut resource) in resources {
+    if resource.name.contains(CONTENT_HASH_TOKEN) {
+      // the name is part of the key, resources of the same content still get different placeholders
+      let mut key = resource.name.as_bytes().to_vec();
+      key.extend_from_slice(&resource.bytes);
+      let placeholder = create_hash_placeholder(hash_config, &key);
+      resource.name = resource.name.replace(CONTENT_HASH_TOKEN, &placeholder);
+    }
+
+    resources_map.insert(resource.name.clone(), resource);
+  }
+}
+
+/// Replace the hash placeholders in the names and contents of `resources_map`, return the renamed resources (old name -> new name).
+/// Resources that reference each other circularly are hashed from their own content
+pub fn replace_hash_placeholders(
+  resources_map: &mut HashMap<String, Resource>,
+  hash_config: &HashConfig,
+) -> HashMap<String, String> {
+  let name_regex = Regex::new(&hash_placeholder_pattern(hash_config)).unwrap();
+  let content_regex = BytesRegex::new(&hash_placeholder_pattern(hash_config)).unwrap();
+
+  // placeholder -> the resource that the placeholder is created for, source maps and extracted license comments follow the name of their resource
+  let mut owners = HashMap::new();
+
+  for resource in resources_map.values() {
+    if matches!(&resource.resource_type, ResourceType::SourceMap(_))
+      || matches!(&resource.resource_type, ResourceType::Custom(ty) if ty == LICENSE_RESOURCE_TYPE)
+    {
+      continue;
+    }
+
+    if let Some(placeholder) = name_regex.find(&resource.name) {
+      owners.insert(placeholder.as_str().to_string(), resource.name.clone());
+    }
+  }
+
+  if owners.is_empty() {
+    return HashMap::new();
+  }
+
+  let find_placeholders = |resource: &Resource| {
+    let mut placeholders = BTreeSet::new();
+
+    if resource.may_contain_hash_placeholders() {
+      for m in content_regex.find_iter(&resource.bytes) {
+        let placeholder = String::from_utf8_lossy(m.as_bytes()).to_string();
+
+        if owners.contains_key(&placeholder) {
+          placeholders.insert(placeholder);
+        }
+      }
+    }
+
+    placeholders
+  };
+
+  let mut resolver = HashResolver {
+    resources_map: &*resources_map,
+    owners: &owners,
+    hash_config,
+    dependencies: owners
+      .iter()
+      .map(|(placeholder, name)| {
+        let mut deps = find_placeholders(&resources_map[name]);
+        deps.remove(placeholder);
+        (placeholder.clone(), deps)
+      })
+      .collect(),
+    hashes: HashMap::new(),
+    visiting: HashSet::new(),
+  };
+
+  let mut placeholders = owners.keys().collect::<Vec<_>>();
+  // the hashes of circular references depend on the order of resolving
+  placeholders.sort();
+
+  for placeholder in placeholders {
+    resolver.resolve(placeholder);
+  }
+
+  let hashes = resolver.hashes;
+  let replace_name = |name: &str| {
+    name_regex
+      .replace_all(name, |caps: &regex::Captures| {
+        hashes
+          .get(&caps[0])
+          .cloned()
+          .unwrap_or_else(|| caps[0].to_string())
+      })
+      .to_string()
+  };
+
+  let mut renames = HashMap::new();
+
+  for resource in resources_map.values_mut() {
+    if resource.may_contain_hash_placeholders() && content_regex.is_match(&resource.bytes) {
+      resource.bytes = content_regex
+        .replace_all(&resource.bytes, |caps: &regex::bytes::Captures| {
+          let placeholder = String::from_utf8_lossy(&caps[0]).to_string();
+          hashes
+            .get(&placeholder)
+            .map(|hash| hash.as_bytes().to_vec())
+            .unwrap_or_else(|| caps[0].to_vec())
+        })
+        .to_vec();
+    }
+
+    let name = replace_name(&resource.name);
+
+    if name != resource.name {
+      renames.insert(resource.name.clone(), name.clone());
+      resource.name = name;
+    }
+  }
+
+  for (old_name, new_name) in &renames {
+    let resource = resources_map.remove(old_name).unwrap();
+    resources_map.insert(new_name.clone(), resource);
+  }
+
+  renames
+}
+
+struct HashResolver<'a> {
+  resources_map: &'a HashMap<String, Resource>,
+  owners: &'a HashMap<String, String>,
+  hash_config: &'a HashConfig,
+  /// placeholder -> placeholders referenced by the content of its resource
+  dependencies: HashMap<String, BTreeSet<String>>,
+  hashes: HashMap<String, String>,
+  visiting: HashSet<String>,
+}
+
+impl<'a> HashResolver<'a> {
+  fn resolve(&mut self, placeholder: &String) -> String {
+    if let Some(hash) = self.hashes.get(placeholder) {
+      return hash.clone();
+    }
+
+    let resource = &self.resources_map[&self.owners[placeholder]];
+    let content_hash = resource.content_hash(self.hash_config);
+
+    if !self.visiting.insert(placeholder.clone()) {
+      return content_hash;
+    }
+
+    let deps = self.dependencies[placeholder].clone();
+
+    let hash = if deps.is_empty() {
+      content_hash
+    } else {
+      let mut input = content_hash;
+
+      for dep in &deps {
+        input.push_str(&self.resolve(dep));
+      }
+
+      self
+        .hash_config
+        .hash(input.as_bytes(), self.hash_config.length())
+    };
+
+    self.visiting.remove(placeholder);
+    self.hashes.insert(placeholder.clone(), hash.clone());
+
+    hash
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use crate::{
+    config::hash::HashConfig,
+    resource::{Resource, ResourceType},
+  };
+
+  use super::{create_hash_placeholder, normalize_finalized_resources, replace_hash_placeholders};
+
+  fn js(name: &str, content: &str) -> (String, Resource) {
+    (
+      name.to_string(),
+      Resource {
+        name: name.to_string(),
+        bytes: content.as_bytes().to_vec(),
+        resource_type: ResourceType::Js,
+        ..Default::default()
+      },
+    )
+  }
+
+  fn build(dep_content: &str) -> HashMap<String, Resource> {
+    let hash_config = HashConfig::default();
+    let entry = create_hash_placeholder(&hash_config, b"entry");
+    let dep = create_hash_placeholder(&hash_config, b"dep");
+    assert_eq!(entry.len(), 8);
+
+    let mut resources_map = HashMap::from([
+      js(
+        &format!("entry.{entry}.js"),
+        &format!("import('./dep.{dep}.js');"),
+      ),
+      js(&format!("dep.{dep}.js"), dep_content),
+      js("index.html", &format!("<script src=\"/entry.{entry}.js\">")),
+    ]);
+    let renames = replace_hash_placeholders(&mut resources_map, &hash_config);
+    assert_eq!(renames.len(), 2);
+
+    resources_map
+  }
+
+  fn find<'a>(resources_map: &'a HashMap<String, Resource>, prefix: &str) -> &'a Resource {
+    resources_map
+      .values()
+      .find(|r| r.name.starts_with(prefix))
+      .unwrap()
+  }
+
+  #[test]
+  fn cascading_hashes() {
+    let first = build("console.log(1);");
+    let second = build("console.log(2);");
+
+    let dep = find(&first, "dep.");
+    let entry = find(&first, "entry.");
+    assert_eq!(
+      String::from_utf8_lossy(&entry.bytes),
+      format!("import('./{}');", dep.name)
+    );
+    assert_eq!(
+      String::from_utf8_lossy(&first["index.html"].bytes),
+      format!("<script src=\"/{}\">", entry.name)
+    );
+
+    // the entry is renamed when its dependency changes
+    assert_ne!(find(&second, "dep.").name, dep.name);
+    assert_ne!(find(&second, "entry.").name, entry.name);
+    // and stable when nothing changes
+    assert_eq!(find(&build("console.log(1);"), "entry.").name, entry.name);
+  }
+
+  #[test]
+  fn source_map_comment_not_hashed() {
+    let hash_config = HashConfig::default();
+    let placeholder = create_hash_placeholder(&hash_config, b"entry");
+    let name = format!("entry.{placeholder}.js");
+    let hashed_name = |content: &str| {
+      let mut resources_map = HashMap::from([js(&name, content)]);
+      replace_hash_placeholders(&mut resources_map, &hash_config);
+      resources_map.into_keys().next().unwrap()
+    };
+
+    let without_map = hashed_name("console.log(1);");
+    assert_eq!(
+      hashed_name(&format!(
+        "console.log(1);\n//# sourceMappingURL=entry.{placeholder}.js.map"
+      )),
+      without_map
+    );
+    assert_eq!(
+      hashed_name("console.log(1);\n//# sourceMappingURL=data:application/json;base64,e30="),
+      without_map
+    );
+    // only the trailing comment is excluded
+    assert_ne!(
+      hashed_name("console.log(1);\n//# sourceMappingURL=a.js.map\nconsole.log(2);"),
+      hashed_name("console.log(1);\n//# sourceMappingURL=b.js.map\nconsole.log(2);")
+    );
+  }
+
+  #[test]
+  fn content_hash_token() {
+    let hash_config = HashConfig::default();
+    let entry = create_hash_placeholder(&hash_config, b"entry");
+    let (key, mut renamed) = js(&format!("entry.{entry}.js"), "console.log(1);");
+    renamed.name = format!("main.{entry}.js");
+
+    let mut resources_map = HashMap::from([
+      (key, renamed),
+      js(
+        "manifest.[contenthash].json",
+        &format!("{{\"main\":\"main.{entry}.js\"}}"),
+      ),
+    ]);
+    normalize_finalized_resources(&mut resources_map, &hash_config);
+    assert!(resources_map.contains_key(&format!("main.{entry}.js")));
+
+    replace_hash_placeholders(&mut resources_map, &hash_config);
+    let main = find(&resources_map, "main.");
+    let manifest = find(&resources_map, "manifest.");
+    assert_eq!(
+      String::from_utf8_lossy(&manifest.bytes),
+      format!("{{\"main\":\"{}\"}}", main.name)
+    );
+    assert!(!manifest.name.contains('~') && !manifest.name.contains("[contenthash]"));
+  }
+
+  #[test]
+  fn asset_manifest() {
+    let hash_config = HashConfig::default();
+    let entry = create_hash_placeholder(&hash_config, b"entry");
+    let asset = |name: &str, ext: &str, content: &str| {
+      (
+        name.to_string(),
+        Resource {
+          name: name.to_string(),
+          bytes: content.as_bytes().to_vec(),
+          resource_type: ResourceType::Asset(ext.to_string()),
+          ..Default::default()
+        },
+      )
+    };
+    let placeholder_in_binary = format!("\0PNG main.{entry}.js");
+
+    let mut resources_map = HashMap::from([
+      js(&format!("main.{entry}.js"), "console.log(1);"),
+      asset(
+        "entry-manifest.json",
+        "json",
+        &format!("{{\"main\":\"main.{entry}.js\"}}"),
+      ),
+      asset("logo.png", "png", &placeholder_in_binary),
+    ]);
+    replace_hash_placeholders(&mut resources_map, &hash_config);
+
+    let main = find(&resources_map, "main.");
+    assert_eq!(
+      String::from_utf8_lossy(&resources_map["entry-manifest.json"].bytes),
+      format!("{{\"main\":\"{}\"}}", main.name)
+    );
+    // binary assets are left untouched
+    assert_eq!(
+      String::from_utf8_lossy(&resources_map["logo.png"].bytes),
+      placeholder_in_binary
+    );
+  }
+}
@@ -0,0 +1,172 @@
+use std::path::{Path, PathBuf};
+
+use relative_path::RelativePath;
+
+use crate::{
+  config::Config,
+  context::CompilationContext,
+  error::{CompilationError, Result},
+};
+
+use super::Resource;
+
+/// Where the generated resources go after the `finalize_resources` hook, see [CompilationContext::set_emit_sink].
+/// Only the resources that [Resource::should_write] are emitted. Every resource is emitted after the initial compilation,
+/// each update only emits the resources whose content changed since they were last emitted.
+pub trait EmitSink: Send + Sync {
+  fn name(&self) -> &str;
+
+  /// Emit a single resource. Resources backed by [Resource::source_path] have empty `bytes`,
+  /// the sink should read or copy them from the source path
+  fn emit(&self, resource: &Resource, context: &CompilationContext) -> Result<()>;
+
+  /// false if [EmitSink::emit] does nothing, the resources are not fingerprinted to find the changed ones then
+  fn is_noop(&self) -> bool {
+    false
+  }
+
+  /// Called after all the resources of the compilation are emitted
+  fn finish(&self, _context: &CompilationContext) -> Result<()> {
+    Ok(())
+  }
+}
+
+/// The default sink. Resources are kept in `resources_map` only, they are served by the dev server or written by the js side
+pub struct MemoryEmitSink;
+
+impl EmitSink for MemoryEmitSink {
+  fn name(&self) -> &str {
+    "MemoryEmitSink"
+  }
+
+  fn emit(&self, _resource: &Resource, _context: &CompilationContext) -> Result<()> {
+    Ok(())
+  }
+
+  fn is_noop(&self) -> bool {
+    true
+  }
+}
+
+/// Write the finalized resources to a directory, large assets are copied from their source path instead of being loaded into memory
+pub struct FsEmitSink {
+  dir: PathBuf,
+}
+
+impl FsEmitSink {
+  pub fn new(dir: impl Into<PathBuf>) -> Self {
+    Self { dir: dir.into() }
+  }
+
+  /// Write to `output.path`
+  pub fn from_config(config: &Config) -> Self {
+    Self::new(RelativePath::new(&config.output.path).to_logical_path(&config.root))
+  }
+
+  /// Output file of the resource, the query and hash of the name are removed
+  pub fn file_path(&self, name: &str) -> PathBuf {
+    let name = name.split(['?', '#']).next().unwrap_or(name);
+    RelativePath::new(name).to_logical_path(&self.dir)
+  }
+}
+
+impl EmitSink for FsEmitSink {
+  fn name(&self) -> &str {
+    "FsEmitSink"
+  }
+
+  fn emit(&self, resource: &Resource, _context: &CompilationContext) -> Result<()> {
+    let file_path = self.file_path(&resource.name);
+    let map_err = |e: std::io::Error| {
+      CompilationError::GenericError(format!(
+        "Failed to emit resource {} to {file_path:?}: {e}",
+        resource.name
+      ))
+    };
+
+    if let Some(parent) = file_path.parent() {
+      std::fs::create_dir_all(parent).map_err(map_err)?;
+    }
+
+    match &resource.source_path {
+      Some(source_path) => std::fs::copy(Path::new(source_path), &file_path)
+        .map(|_| ())
+        .map_err(map_err)?,
+      None => std::fs::write(&file_path, &resource.bytes).map_err(map_err)?,
+    }
+
+    #[cfg(unix)]
+    if resource.is_executable() {
+      use std::os::unix::fs::PermissionsExt;
+
+      std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o755))
+        .map_err(map_err)?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{config::Config, context::CompilationContext, resource::Resource};
+
+  use super::{EmitSink, FsEmitSink};
+
+  #[test]
+  fn fs_emit_sink() {
+    let dir = std::env::temp_dir().join(format!("farm-emit-sink-test-{}", std::process::id()));
+    let sink = FsEmitSink::new(&dir);
+    let context = CompilationContext::new(Config::default(), vec![]).unwrap();
+
+    assert_eq!(sink.file_path("assets/a.js?v=1"), dir.join("assets/a.js"));
+
+    let source_path = dir.join("source.png");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&source_path, b"png").unwrap();
+
+    let resources = [
+      Resource {
+        name: "assets/index.js".to_string(),
+        bytes: b"console.log(1)".to_vec(),
+        ..Default::default()
+      },
+      Resource {
+        name: "logo.png".to_string(),
+        source_path: Some(source_path.to_string_lossy().to_string()),
+        ..Default::default()
+      },
+      Resource {
+        name: "cli".to_string(),
+        bytes: b"#!/usr/bin/env node\nconsole.log(1)".to_vec(),
+        ..Default::default()
+      },
+    ];
+
+    for resource in &resources {
+      sink.emit(resource, &context).unwrap();
+    }
+
+    assert_eq!(
+      std::fs::read(dir.join("assets/index.js")).unwrap(),
+      b"console.log(1)"
+    );
+    assert_eq!(std::fs::read(dir.join("logo.png")).unwrap(), b"png");
+
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+
+      let mode = |name: &str| {
+        std::fs::metadata(dir.join(name))
+          .unwrap()
+          .permissions()
+          .mode()
+      };
+      assert_eq!(mode("cli") & 0o111, 0o111);
+      assert_eq!(mode("assets/index.js") & 0o111, 0);
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -1,16 +1,28 @@
+use std::borrow::Cow;
+
 use heck::AsLowerCamelCase;
 
 use farmfe_macro_cache_item::cache_item;
 use rkyv::with::Skip;
 use serde::{Deserialize, Serialize};
 
-use crate::module::ModuleId;
+use crate::{
+  config::{config_regex::ConfigRegex, Mode},
+  module::ModuleId,
+};
 
 use self::resource_pot::{ResourcePotId, ResourcePotInfo};
 
+pub mod content_hash;
+pub mod emit_sink;
 pub mod resource_pot;
 pub mod resource_pot_map;
 
+/// Key of [ResourcePotInfo::custom], plugins set it to `high` in the `render_resource_pot` hook to mark the resources of the resource pot as high priority
+pub const FETCH_PRIORITY: &str = "fetchpriority";
+/// [ResourceType::Custom] of the `<resource>.LICENSE.txt` that contains the license comments extracted from the resource
+pub const LICENSE_RESOURCE_TYPE: &str = "LICENSE";
+
 #[cache_item]
 #[derive(Debug, Clone)]
 pub enum ResourceType {
@@ -20,6 +32,8 @@ pub enum ResourceType {
   Html,
   SourceMap(String),
   Asset(String),
+  /// WebAssembly binary imported by a wasm module, see `import { add } from './add.wasm'`
+  Wasm,
   Custom(String),
 }
 
@@ -59,6 +73,7 @@ impl From<String> for ResourceType {
       "css" => Self::Css,
       "html" => Self::Html,
       "runtime" => Self::Runtime,
+      "wasm" => Self::Wasm,
       _ => Self::Custom(s),
     }
   }
@@ -73,6 +88,7 @@ impl ResourceType {
       ResourceType::Js => "js".to_string(),
       ResourceType::Css => "css".to_string(),
       ResourceType::Html => "html".to_string(),
+      ResourceType::Wasm => "wasm".to_string(),
       ResourceType::SourceMap(_) => "map".to_string(),
     }
   }
@@ -85,6 +101,7 @@ impl ResourceType {
       ResourceType::Js => "script".to_string(),
       ResourceType::Css => "link".to_string(),
       ResourceType::Html => "html".to_string(),
+      ResourceType::Wasm => "wasm".to_string(),
       ResourceType::SourceMap(_) => unreachable!(),
     }
   }
@@ -98,20 +115,68 @@ pub enum ResourceOrigin {
   ResourcePot(ResourcePotId),
   // The resource is generated by a Module. Usually by static assets like images.
   Module(ModuleId),
+  // The resource is derived from several modules, e.g. a sprite sheet built from many images.
+  // It's removed when any of the modules is updated or removed, and generated again by the plugin that derives it.
+  Modules(Vec<ModuleId>),
 }
 
 impl ResourceOrigin {
   pub fn as_resource_pot(&self) -> &ResourcePotId {
+    self
+      .try_as_resource_pot()
+      .expect("ResourceOrigin is not ResourceOrigin::ResourcePot")
+  }
+
+  pub fn as_module(&self) -> &ModuleId {
+    self
+      .try_as_module()
+      .expect("ResourceOrigin is not ResourceOrigin::Module")
+  }
+
+  pub fn try_as_resource_pot(&self) -> Option<&ResourcePotId> {
     match self {
-      ResourceOrigin::ResourcePot(id) => id,
-      ResourceOrigin::Module(_) => panic!("ResourceOrigin is not ResourceOrigin::ResourcePot"),
+      ResourceOrigin::ResourcePot(id) => Some(id),
+      _ => None,
     }
   }
 
-  pub fn as_module(&self) -> &ModuleId {
+  pub fn try_as_module(&self) -> Option<&ModuleId> {
+    match self {
+      ResourceOrigin::Module(id) => Some(id),
+      _ => None,
+    }
+  }
+
+  /// The modules the resource is generated from, empty for [ResourceOrigin::ResourcePot]
+  pub fn modules(&self) -> &[ModuleId] {
     match self {
-      ResourceOrigin::ResourcePot(_) => panic!("ResourceOrigin is not ResourceOrigin::Module"),
-      ResourceOrigin::Module(id) => id,
+      ResourceOrigin::ResourcePot(_) => &[],
+      ResourceOrigin::Module(id) => std::slice::from_ref(id),
+      ResourceOrigin::Modules(ids) => ids,
+    }
+  }
+}
+
+/// Where the resource is present. Resources that are not available in the current mode are removed after the `finalize_resources` hook,
+/// so dev only resources like the hmr client or debug panels never leak into the production output.
+#[cache_item]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResourceScope {
+  #[default]
+  All,
+  /// only served by the dev server in development, never written to disk
+  DevServer,
+  /// only present in production builds
+  Production,
+}
+
+impl ResourceScope {
+  pub fn is_available(&self, mode: &Mode) -> bool {
+    match self {
+      ResourceScope::All => true,
+      ResourceScope::DevServer => matches!(mode, Mode::Development),
+      ResourceScope::Production => matches!(mode, Mode::Production),
     }
   }
 }
@@ -122,11 +187,18 @@ impl ResourceOrigin {
 pub struct Resource {
   pub name: String,
   pub bytes: Vec<u8>,
-  /// whether this resource emitted, true means this resource will not present in the final production
+  /// true means this resource is consumed by other resources, e.g. the runtime inlined into the entry,
+  /// it is neither served nor written to disk on its own
   pub emitted: bool,
   pub resource_type: ResourceType,
   /// the origin that this resource generated from
   pub origin: ResourceOrigin,
+  /// Large assets are not loaded into memory, `bytes` is empty and the resource is copied from this path when written to disk.
+  /// Use [Resource::read_bytes] to read the content of any resource
+  #[serde(default)]
+  pub source_path: Option<String>,
+  #[serde(default)]
+  pub scope: ResourceScope,
 
   #[with(Skip)]
   pub info: Option<ResourcePotInfo>,
@@ -140,7 +212,95 @@ impl Default for Resource {
       emitted: false,
       resource_type: ResourceType::Custom("unknown".to_string()),
       origin: ResourceOrigin::Module("unknown".into()),
+      source_path: None,
+      scope: ResourceScope::All,
       info: None,
     }
   }
 }
+
+impl Resource {
+  /// Whether the resource is written to disk, dev server only resources are served from memory
+  pub fn should_write(&self) -> bool {
+    !self.emitted && self.scope != ResourceScope::DevServer
+  }
+
+  /// The content of the resource, resources backed by [Resource::source_path] are read from the source path
+  pub fn read_bytes(&self) -> std::io::Result<Cow<'_, [u8]>> {
+    match &self.source_path {
+      Some(source_path) => std::fs::read(source_path).map(Cow::Owned),
+      None => Ok(Cow::Borrowed(&self.bytes)),
+    }
+  }
+
+  /// Resources starting with a shebang are written with executable permissions, e.g. the entries of cli tools
+  pub fn is_executable(&self) -> bool {
+    self.bytes.starts_with(b"#!")
+  }
+
+  /// Whether the resource is matched by `output.highPriority` or marked as high priority by plugins, see [FETCH_PRIORITY]
+  pub fn is_high_priority(&self, high_priority: &[ConfigRegex]) -> bool {
+    high_priority.iter().any(|r| r.is_match(&self.name))
+      || self
+        .info
+        .as_ref()
+        .is_some_and(|info| info.custom.get(FETCH_PRIORITY).is_some_and(|p| p == "high"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::config::{config_regex::ConfigRegex, Mode};
+
+  use super::{Resource, ResourceScope};
+
+  #[test]
+  fn resource_scope() {
+    assert!(ResourceScope::All.is_available(&Mode::Production));
+    assert!(ResourceScope::DevServer.is_available(&Mode::Development));
+    assert!(!ResourceScope::DevServer.is_available(&Mode::Production));
+    assert!(!ResourceScope::Production.is_available(&Mode::Development));
+
+    let resource = Resource {
+      scope: ResourceScope::DevServer,
+      ..Default::default()
+    };
+    assert!(!resource.should_write());
+    assert!(Resource::default().should_write());
+  }
+
+  #[test]
+  fn resource_high_priority() {
+    let resource = Resource {
+      name: "landing.1a2b.js".to_string(),
+      ..Default::default()
+    };
+
+    assert!(resource.is_high_priority(&[ConfigRegex::new("^landing")]));
+    assert!(!resource.is_high_priority(&[ConfigRegex::new("^admin")]));
+    assert!(!resource.is_high_priority(&[]));
+  }
+
+  #[test]
+  fn resource_read_bytes() {
+    let resource = Resource {
+      bytes: b"console.log(1)".to_vec(),
+      ..Default::default()
+    };
+    assert_eq!(&*resource.read_bytes().unwrap(), b"console.log(1)");
+
+    let dir = std::env::temp_dir().join(format!("farm-read-bytes-test-{}", std::process::id()));
+    let source_path = dir.join("logo.png");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(&source_path, b"png").unwrap();
+
+    let resource = Resource {
+      source_path: Some(source_path.to_string_lossy().to_string()),
+      ..Default::default()
+    };
+    assert!(resource.bytes.is_empty());
+    assert_eq!(&*resource.read_bytes().unwrap(), b"png");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
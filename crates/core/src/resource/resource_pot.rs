@@ -8,6 +8,7 @@ use farmfe_macro_cache_item::cache_item;
 use crate::module::{module_group::ModuleGroupId, ModuleId, ModuleType};
 
 const DEFER_BUNDLE_MINIFY: &str = "DEFER_BUNDLE_MINIFY";
+const PASSTHROUGH: &str = "PASSTHROUGH";
 
 #[cache_item]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -111,6 +112,22 @@ impl ResourcePot {
       .get(DEFER_BUNDLE_MINIFY)
       .is_some_and(|v| v == "true")
   }
+
+  /// the modules of a passthrough resource pot are excluded from minification, so the resource pot is never minified as a whole
+  pub fn set_passthrough(&mut self) {
+    self
+      .meta
+      .custom_data
+      .insert(PASSTHROUGH.to_string(), "true".to_string());
+  }
+
+  pub fn is_passthrough(&self) -> bool {
+    self
+      .meta
+      .custom_data
+      .get(PASSTHROUGH)
+      .is_some_and(|v| v == "true")
+  }
 }
 
 pub type ResourcePotId = String;
@@ -5,9 +5,11 @@ use parking_lot::RwLock;
 use crate::{
   module::{
     module_graph::{ModuleGraph, ModuleGraphEdge},
+    module_statement::ModuleExport,
     ModuleId, ModuleType,
   },
-  plugin::PluginHookContext,
+  plugin::{PluginHookContext, ResolveKind, ResolveTrace},
+  resource::ResourceOrigin,
 };
 
 pub struct Stats {
@@ -15,6 +17,12 @@ pub struct Stats {
   pub initial_compilation_flow_stats: RwLock<CompilationStats>,
   /// Most 10 recent compilation flow stats
   pub hmr_compilation_flow_stats: RwLock<Vec<CompilationStats>>,
+  /// Bundle analysis of the last generate stage, only set when `bundleStats` is configured
+  pub bundle_stats: RwLock<Option<BundleStats>>,
+  /// Unused exports of the last generate stage, only set when `unusedExports` is configured
+  pub unused_exports: RwLock<Option<Vec<UnusedExportsStats>>>,
+  /// Exports of the es modules before tree shaking, only set when `bundleStats.exports` is enabled
+  pub module_exports: RwLock<Option<Vec<ModuleExportsStats>>>,
 }
 
 macro_rules! handle_compilation_stats {
@@ -52,6 +60,9 @@ impl Default for Stats {
     Self {
       initial_compilation_flow_stats: RwLock::new(CompilationStats::new()),
       hmr_compilation_flow_stats: RwLock::new(vec![]),
+      bundle_stats: RwLock::new(None),
+      unused_exports: RwLock::new(None),
+      module_exports: RwLock::new(None),
     }
   }
 }
@@ -116,6 +127,18 @@ impl Stats {
       compilation_stats.entries = entries;
     })
   }
+
+  pub fn set_bundle_stats(&self, bundle_stats: BundleStats) {
+    *self.bundle_stats.write() = Some(bundle_stats);
+  }
+
+  pub fn set_unused_exports(&self, unused_exports: Vec<UnusedExportsStats>) {
+    *self.unused_exports.write() = Some(unused_exports);
+  }
+
+  pub fn set_module_exports(&self, module_exports: Vec<ModuleExportsStats>) {
+    *self.module_exports.write() = Some(module_exports);
+  }
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Default)]
@@ -226,3 +249,85 @@ pub struct CompilationModuleStats {
   pub module_id: ModuleId,
   pub module_type: ModuleType,
 }
+
+/// Machine-readable report of the generated resources, used to debug bundle bloat or by visualizer plugins
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleStats {
+  pub resources: Vec<BundleResourceStats>,
+  /// module graph edges, which explain why a module is included in the bundle
+  pub edges: Vec<BundleEdgeStats>,
+  /// set when `unusedExports` is configured
+  #[serde(default)]
+  pub unused_exports: Vec<UnusedExportsStats>,
+  /// set when `bundleStats.exports` is enabled
+  #[serde(default)]
+  pub exports: Vec<ModuleExportsStats>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleResourceStats {
+  pub name: String,
+  pub resource_type: String,
+  pub size: usize,
+  pub gzip_size: usize,
+  pub origin: ResourceOrigin,
+  /// modules rendered into the resource, sorted by the rendered size
+  pub modules: Vec<BundleModuleStats>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleModuleStats {
+  pub id: ModuleId,
+  pub rendered_size: usize,
+  pub original_size: usize,
+  /// byte ranges `[start, end)` the module occupies in the emitted resource, resolved by the segments of the source map.
+  /// Empty if the source map of the resource is not generated
+  pub ranges: Vec<[usize; 2]>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleEdgeStats {
+  pub importer: ModuleId,
+  pub dependency: ModuleId,
+  pub source: String,
+  pub kind: ResolveKind,
+  /// how the source is resolved, e.g. by an alias or by the conditions of the `exports` field
+  pub resolve_trace: Option<ResolveTrace>,
+  /// `name@version` of the package the dependency is resolved from, [None] if it's not in node_modules
+  pub package: Option<String>,
+}
+
+/// Exports of a module that are never imported in the module graph
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnusedExportsStats {
+  pub id: ModuleId,
+  /// exports that are neither imported by other modules nor exposed by an entry
+  pub unused: Vec<String>,
+  /// exports that are not imported by other modules but exposed by an entry, they are the public api of the package
+  pub public: Vec<String>,
+}
+
+/// Exported names of an es module, e.g. used to generate auto import indexes or check named imports
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleExportsStats {
+  pub id: ModuleId,
+  pub exports: Vec<ModuleExport>,
+}
+
+/// A third party package of the bundled modules
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageLicenseStats {
+  pub name: String,
+  pub version: String,
+  /// spdx license expression of the package.json, empty if not specified
+  pub license: String,
+  /// the license file of the package, relative to the root
+  pub license_file: Option<String>,
+}
@@ -1,4 +1,4 @@
-use crate::{package_manager::PackageManager, template::Template};
+use crate::{package_manager::PackageManager, registry::TemplateSource, template::Template};
 use clap::{ArgAction, Parser};
 
 #[derive(Parser, Debug)]
@@ -15,6 +15,16 @@ pub struct Args {
   pub manager: Option<PackageManager>,
   #[arg(short, long, help = "Project template to use")]
   pub template: Option<Template>,
+  #[arg(
+    long,
+    help = "Create from a git or local template instead, e.g. github:user/repo/sub/dir#main or ./my-template",
+    conflicts_with = "template"
+  )]
+  pub from: Option<TemplateSource>,
+  #[arg(long, help = "Use the cached git template without fetching it", action = ArgAction::SetTrue)]
+  pub offline: bool,
+  #[arg(long, help = "Skip the post create hooks of the template", action = ArgAction::SetTrue)]
+  pub skip_hooks: bool,
   #[arg(short, long, help = "Force overwrite of existing files", action = ArgAction::SetTrue)]
   pub force: bool,
 }
@@ -25,6 +35,9 @@ impl Default for Args {
       project_name: Some("farm-project".to_string()),
       manager: Some(PackageManager::Npm),
       template: Some(Template::Vanilla),
+      from: None,
+      offline: false,
+      skip_hooks: false,
       force: false,
     }
   }
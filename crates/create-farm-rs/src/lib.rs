@@ -5,12 +5,14 @@ use utils::prompts;
 
 use crate::{
   package_manager::PackageManager,
+  registry::TemplateSource,
   template::{ElectronSubTemplate, TauriSubTemplate, Template},
   utils::colors::*,
 };
 
 mod args;
 mod package_manager;
+mod registry;
 mod template;
 pub mod utils;
 
@@ -48,6 +50,9 @@ where
     manager,
     project_name,
     template,
+    from,
+    offline,
+    skip_hooks,
     force,
   } = args;
 
@@ -102,8 +107,15 @@ where
 
   let templates_no_flavors = pkg_manager.templates_no_flavors();
 
+  // fetch before cleaning the target dir, so nothing is lost when the template is not available
+  let remote_template = match &from {
+    Some(source) => Some((source, source.fetch(offline)?)),
+    None => None,
+  };
+
   let template = match template {
     Some(template) => template,
+    None if remote_template.is_some() => defaults.template.context("default template not set")?,
     None => {
       let selected_template =
         prompts::select("Select a framework:", &templates_no_flavors, Some(0))?.unwrap();
@@ -166,7 +178,17 @@ where
   }
 
   // Render the template
-  template.render(&target_dir, pkg_manager, &project_name, &project_name)?;
+  match &remote_template {
+    Some((source, template_dir)) => {
+      registry::render_dir(template_dir, &target_dir, &project_name, &project_name)?;
+
+      let hooks = registry::post_create_hooks(template_dir, &project_name, &project_name)?;
+      if !skip_hooks && !hooks.is_empty() && confirm_hooks(source, &hooks, force)? {
+        registry::run_post_create_hooks(&hooks, &target_dir)?;
+      }
+    }
+    None => template.render(&target_dir, pkg_manager, &project_name, &project_name)?,
+  }
 
   handle_brand_text("\n >  Initial Farm Project created successfully ✨ ✨ \n");
 
@@ -188,6 +210,20 @@ where
   Ok(())
 }
 
+/// Hooks of the templates that are not local run arbitrary commands, list them before running
+fn confirm_hooks(source: &TemplateSource, hooks: &[String], force: bool) -> anyhow::Result<bool> {
+  if source.is_local() || force {
+    return Ok(true);
+  }
+
+  eprintln!("\n {BOLD}{YELLOW}!{RESET} Template {source} runs the following commands after creating the project:");
+  for hook in hooks {
+    eprintln!("    {hook}");
+  }
+
+  prompts::confirm("Run them?", false)
+}
+
 fn is_valid_pkg_name(project_name: &str) -> bool {
   let mut chars = project_name.chars().peekable();
   !project_name.is_empty()
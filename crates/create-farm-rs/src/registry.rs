@@ -0,0 +1,371 @@
+use std::{
+  fmt::Display,
+  fs,
+  path::{Path, PathBuf},
+  process::Command,
+  str::FromStr,
+};
+
+use anyhow::{bail, Context};
+
+use crate::{
+  template::{template_data, write_template_file},
+  utils::{colors::*, lte},
+};
+
+/// Directory of a remote template that holds its metadata, it is not copied to the project
+const TEMPLATE_META_DIR: &str = ".farm-template";
+/// Commands run in the created project, one per line. Lines starting with `#` are comments
+const POST_CREATE_HOOKS_FILE: &str = "post-create";
+
+/// A template outside of the embedded ones:
+/// * `github:user/repo[/sub/dir][#ref]` or `gitlab:user/repo[/sub/dir][#ref]`
+/// * a git url ending with `.git`, e.g. `https://example.com/templates.git#v1`
+/// * a local directory, e.g. `file:../my-template` or `./my-template`
+///
+/// Git templates are cloned to the cache directory and reused when the network is not available
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateSource {
+  Git {
+    url: String,
+    rev: Option<String>,
+    sub_dir: Option<String>,
+  },
+  Local(PathBuf),
+}
+
+impl FromStr for TemplateSource {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (source, rev) = match s.split_once('#') {
+      Some((source, rev)) if !rev.is_empty() => (source, Some(rev.to_string())),
+      _ => (s, None),
+    };
+
+    for (prefix, host) in [("github:", "github.com"), ("gitlab:", "gitlab.com")] {
+      if let Some(path) = source.strip_prefix(prefix) {
+        let mut segments = path.split('/').filter(|s| !s.is_empty());
+        let (Some(user), Some(repo)) = (segments.next(), segments.next()) else {
+          return Err(format!(
+            "{YELLOW}{s}{RESET} is not a valid template source, expected {GREEN}{prefix}user/repo{RESET}"
+          ));
+        };
+        let sub_dir = segments.collect::<Vec<_>>().join("/");
+
+        return Ok(TemplateSource::Git {
+          url: format!("https://{host}/{user}/{repo}.git"),
+          rev,
+          sub_dir: (!sub_dir.is_empty()).then_some(sub_dir),
+        });
+      }
+    }
+
+    if source.ends_with(".git") {
+      return Ok(TemplateSource::Git {
+        url: source.to_string(),
+        rev,
+        sub_dir: None,
+      });
+    }
+
+    Ok(TemplateSource::Local(PathBuf::from(
+      s.strip_prefix("file:").unwrap_or(s),
+    )))
+  }
+}
+
+impl Display for TemplateSource {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      TemplateSource::Git { url, rev, sub_dir } => {
+        write!(f, "{url}")?;
+        if let Some(sub_dir) = sub_dir {
+          write!(f, "/{sub_dir}")?;
+        }
+        if let Some(rev) = rev {
+          write!(f, "#{rev}")?;
+        }
+        Ok(())
+      }
+      TemplateSource::Local(path) => write!(f, "{}", path.display()),
+    }
+  }
+}
+
+impl TemplateSource {
+  pub(crate) fn is_local(&self) -> bool {
+    matches!(self, TemplateSource::Local(_))
+  }
+
+  /// Directory of the cloned repository, named after the url and the rev so they can be reused offline
+  fn cache_dir(url: &str, rev: &Option<String>) -> anyhow::Result<PathBuf> {
+    let name = format!("{url}#{}", rev.as_deref().unwrap_or("HEAD"))
+      .chars()
+      .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+      .collect::<String>();
+
+    Ok(templates_cache_dir()?.join(name))
+  }
+
+  /// Directory of the template, git templates are cloned or updated first.
+  /// When `offline` is set or the update fails, the cached clone is used
+  pub(crate) fn fetch(&self, offline: bool) -> anyhow::Result<PathBuf> {
+    let (url, rev, sub_dir) = match self {
+      TemplateSource::Local(path) => {
+        if !path.is_dir() {
+          bail!("Template directory {} does not exist", path.display());
+        }
+        return Ok(path.clone());
+      }
+      TemplateSource::Git { url, rev, sub_dir } => (url, rev, sub_dir),
+    };
+
+    let dir = Self::cache_dir(url, rev)?;
+    let cached = dir.join(".git").is_dir();
+
+    if offline {
+      if !cached {
+        bail!("Template {self} is not cached, it can not be created offline");
+      }
+    } else if let Err(e) = update_repo(&dir, url, rev, cached) {
+      if !cached {
+        return Err(e.context(format!("Failed to fetch template {self}")));
+      }
+      eprintln!(
+        "{BOLD}{YELLOW}!{RESET} Failed to update template {self}, using the cached one: {e:#}"
+      );
+    }
+
+    Ok(match sub_dir {
+      Some(sub_dir) => dir.join(sub_dir),
+      None => dir,
+    })
+  }
+}
+
+fn update_repo(dir: &Path, url: &str, rev: &Option<String>, cached: bool) -> anyhow::Result<()> {
+  if cached {
+    git(
+      Some(dir),
+      &[
+        "fetch",
+        "--depth",
+        "1",
+        "origin",
+        rev.as_deref().unwrap_or("HEAD"),
+      ],
+    )?;
+    return git(Some(dir), &["reset", "--hard", "FETCH_HEAD"]);
+  }
+
+  if dir.exists() {
+    fs::remove_dir_all(dir)?;
+  }
+  fs::create_dir_all(dir.parent().unwrap())?;
+
+  let dir = dir.to_string_lossy();
+  let mut args = vec!["clone", "--depth", "1"];
+  if let Some(rev) = rev {
+    args.extend(["--branch", rev.as_str()]);
+  }
+  args.extend([url, &*dir]);
+
+  git(None, &args)
+}
+
+fn git(cwd: Option<&Path>, args: &[&str]) -> anyhow::Result<()> {
+  let mut cmd = Command::new("git");
+  if let Some(cwd) = cwd {
+    cmd.current_dir(cwd);
+  }
+
+  let output = cmd
+    .args(args)
+    .output()
+    .context("Failed to run git, is it installed?")?;
+
+  if !output.status.success() {
+    bail!(
+      "git {} failed: {}",
+      args.join(" "),
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+  }
+
+  Ok(())
+}
+
+/// `$XDG_CACHE_HOME/create-farm/templates`, `~/.cache/create-farm/templates` or `%LOCALAPPDATA%\create-farm\templates`
+fn templates_cache_dir() -> anyhow::Result<PathBuf> {
+  let base = std::env::var_os("XDG_CACHE_HOME")
+    .map(PathBuf::from)
+    .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+    .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    .context("Failed to find the cache directory of the templates")?;
+
+  Ok(base.join("create-farm").join("templates"))
+}
+
+/// Copy a fetched template to the target dir, files are rendered the same way as the embedded templates
+pub(crate) fn render_dir(
+  template_dir: &Path,
+  target_dir: &Path,
+  project_name: &str,
+  package_name: &str,
+) -> anyhow::Result<()> {
+  let template_data = template_data(project_name, package_name);
+  let mut dirs = vec![template_dir.to_path_buf()];
+
+  while let Some(dir) = dirs.pop() {
+    for entry in fs::read_dir(&dir)?.flatten() {
+      let path = entry.path();
+      let name = entry.file_name();
+
+      if name == ".git" || name == TEMPLATE_META_DIR {
+        continue;
+      }
+
+      if entry.file_type()?.is_dir() {
+        dirs.push(path);
+        continue;
+      }
+
+      let relative = path.strip_prefix(template_dir)?;
+      write_template_file(
+        &target_dir.join(relative),
+        fs::read(&path)?,
+        &template_data,
+        project_name,
+      )?;
+    }
+  }
+
+  handle_brand_text("\n ✔️ Template copied Successfully! \n");
+  Ok(())
+}
+
+/// Commands of `.farm-template/post-create`, rendered with the template variables
+pub(crate) fn post_create_hooks(
+  template_dir: &Path,
+  project_name: &str,
+  package_name: &str,
+) -> anyhow::Result<Vec<String>> {
+  let hooks_file = template_dir
+    .join(TEMPLATE_META_DIR)
+    .join(POST_CREATE_HOOKS_FILE);
+
+  if !hooks_file.is_file() {
+    return Ok(vec![]);
+  }
+
+  let template_data = template_data(project_name, package_name);
+
+  let hooks = fs::read_to_string(&hooks_file)?
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    .map(|line| lte::render(line, &template_data))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  Ok(hooks)
+}
+
+/// Run the post create hooks in the project directory with the shell of the platform
+pub(crate) fn run_post_create_hooks(hooks: &[String], target_dir: &Path) -> anyhow::Result<()> {
+  for hook in hooks {
+    handle_brand_text(&format!("\n > {hook} \n"));
+
+    let status = if cfg!(windows) {
+      Command::new("cmd")
+        .args(["/C", hook])
+        .current_dir(target_dir)
+        .status()
+    } else {
+      Command::new("sh")
+        .args(["-c", hook])
+        .current_dir(target_dir)
+        .status()
+    }
+    .with_context(|| format!("Failed to run post create hook `{hook}`"))?;
+
+    if !status.success() {
+      bail!("Post create hook `{hook}` failed with {status}");
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use super::TemplateSource;
+
+  #[test]
+  fn parse_template_source() {
+    assert_eq!(
+      "github:farm-fe/templates/react#v2".parse::<TemplateSource>(),
+      Ok(TemplateSource::Git {
+        url: "https://github.com/farm-fe/templates.git".to_string(),
+        rev: Some("v2".to_string()),
+        sub_dir: Some("react".to_string()),
+      })
+    );
+    assert_eq!(
+      "https://example.com/template.git".parse::<TemplateSource>(),
+      Ok(TemplateSource::Git {
+        url: "https://example.com/template.git".to_string(),
+        rev: None,
+        sub_dir: None,
+      })
+    );
+    assert_eq!(
+      "file:../my-template".parse::<TemplateSource>(),
+      Ok(TemplateSource::Local(PathBuf::from("../my-template")))
+    );
+    assert!("github:farm-fe".parse::<TemplateSource>().is_err());
+  }
+
+  #[test]
+  fn render_local_template() {
+    let dir = std::env::temp_dir().join(format!("create-farm-registry-{}", std::process::id()));
+    let template_dir = dir.join("template");
+    let target_dir = dir.join("project");
+
+    std::fs::create_dir_all(template_dir.join("src")).unwrap();
+    std::fs::create_dir_all(template_dir.join(".farm-template")).unwrap();
+    std::fs::write(template_dir.join("gitignore"), "node_modules").unwrap();
+    std::fs::write(
+      template_dir.join("package.json.lte"),
+      r#"{ "name": "{% package_name %}" }"#,
+    )
+    .unwrap();
+    std::fs::write(template_dir.join("src/index.ts"), "// <FARM-TEMPLATE-NAME>").unwrap();
+    std::fs::write(
+      template_dir.join(".farm-template/post-create"),
+      "# initialize the repository\ngit init\necho {% project_name %}\n",
+    )
+    .unwrap();
+
+    super::render_dir(&template_dir, &target_dir, "my-app", "my-app").unwrap();
+
+    assert_eq!(
+      std::fs::read_to_string(target_dir.join("package.json")).unwrap(),
+      r#"{ "name": "my-app" }"#
+    );
+    assert_eq!(
+      std::fs::read_to_string(target_dir.join("src/index.ts")).unwrap(),
+      "// my-app"
+    );
+    assert!(target_dir.join(".gitignore").is_file());
+    assert!(!target_dir.join(".farm-template").exists());
+    assert_eq!(
+      super::post_create_hooks(&template_dir, "my-app", "my-app").unwrap(),
+      vec!["git init".to_string(), "echo my-app".to_string()]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
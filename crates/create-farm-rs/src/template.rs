@@ -287,19 +287,7 @@ impl<'a> Template {
     project_name: &str,
     package_name: &str,
   ) -> anyhow::Result<()> {
-    let lib_name = format!("{}_lib", package_name.replace('-', "_"));
-    let project_name_pascal_case = Self::transform_to_pascal_case(project_name.to_string());
-
-    let template_data: HashMap<&str, String> = [
-      ("project_name", project_name.to_string()),
-      (
-        "project_name_pascal_case",
-        project_name_pascal_case.to_string(),
-      ),
-      ("package_name", package_name.to_string()),
-      ("lib_name", lib_name),
-    ]
-    .into();
+    let template_data = template_data(project_name, package_name);
 
     let write_file =
       |file: &str, template_data: HashMap<&str, String>, skip_count: usize| -> anyhow::Result<()> {
@@ -311,49 +299,12 @@ impl<'a> Template {
           .iter()
           .collect::<path::PathBuf>();
 
-        let p = target_dir.join(p);
-        let file_name = p.file_name().unwrap().to_string_lossy();
-
-        let file_name = match &*file_name {
-          "gitignore" => ".gitignore",
-          // skip manifest
-          name if name.starts_with("%(") && name[1..].contains(")%") => {
-            let mut s = name.strip_prefix("%(").unwrap().split(")%");
-            let (mut _flags, _name) = (
-              s.next().unwrap().split('-').collect::<Vec<_>>(),
-              s.next().unwrap(),
-            );
-
-            // skip writing this file
-            return Ok(());
-          }
-          name => name,
-        };
-
-        let (file_data, file_name) = if let Some(new_name) = file_name.strip_suffix(".lte") {
-          let data = lte::render(
-            EMBEDDED_TEMPLATES::get(file).unwrap().data.to_vec(),
-            &template_data,
-          )?
-          .replace("<FARM-TEMPLATE-NAME>", project_name);
-          (data.into_bytes(), new_name)
-        } else {
-          let plain_data = EMBEDDED_TEMPLATES::get(file).unwrap().data.to_vec();
-          let data = String::from_utf8(plain_data.clone())
-            .map(|s| {
-              s.replace("<FARM-TEMPLATE-NAME>", &project_name)
-                .into_bytes()
-            })
-            .unwrap_or(plain_data);
-          (data, file_name)
-        };
-
-        let file_name = lte::render(file_name, &template_data)?;
-
-        let parent = p.parent().unwrap();
-        fs::create_dir_all(parent)?;
-        fs::write(parent.join(file_name), file_data)?;
-        Ok(())
+        write_template_file(
+          &target_dir.join(p),
+          EMBEDDED_TEMPLATES::get(file).unwrap().data.to_vec(),
+          &template_data,
+          project_name,
+        )
       };
 
     let current_template_name = match self {
@@ -382,6 +333,69 @@ impl<'a> Template {
   }
 }
 
+/// Variables available to the `.lte` files and file names of a template
+pub(crate) fn template_data(
+  project_name: &str,
+  package_name: &str,
+) -> HashMap<&'static str, String> {
+  let lib_name = format!("{}_lib", package_name.replace('-', "_"));
+  let project_name_pascal_case = Template::transform_to_pascal_case(project_name.to_string());
+
+  [
+    ("project_name", project_name.to_string()),
+    ("project_name_pascal_case", project_name_pascal_case),
+    ("package_name", package_name.to_string()),
+    ("lib_name", lib_name),
+  ]
+  .into()
+}
+
+/// Write a file of a template to `p`, shared by the embedded and the remote templates.
+/// `gitignore` is renamed to `.gitignore`, `.lte` files are rendered with `template_data` and
+/// `<FARM-TEMPLATE-NAME>` is replaced by the project name
+pub(crate) fn write_template_file(
+  p: &path::Path,
+  plain_data: Vec<u8>,
+  template_data: &HashMap<&str, String>,
+  project_name: &str,
+) -> anyhow::Result<()> {
+  let file_name = p.file_name().unwrap().to_string_lossy();
+
+  let file_name = match &*file_name {
+    "gitignore" => ".gitignore",
+    // skip manifest
+    name if name.starts_with("%(") && name[1..].contains(")%") => {
+      let mut s = name.strip_prefix("%(").unwrap().split(")%");
+      let (mut _flags, _name) = (
+        s.next().unwrap().split('-').collect::<Vec<_>>(),
+        s.next().unwrap(),
+      );
+
+      // skip writing this file
+      return Ok(());
+    }
+    name => name,
+  };
+
+  let (file_data, file_name) = if let Some(new_name) = file_name.strip_suffix(".lte") {
+    let data =
+      lte::render(plain_data, template_data)?.replace("<FARM-TEMPLATE-NAME>", project_name);
+    (data.into_bytes(), new_name)
+  } else {
+    let data = String::from_utf8(plain_data.clone())
+      .map(|s| s.replace("<FARM-TEMPLATE-NAME>", project_name).into_bytes())
+      .unwrap_or(plain_data);
+    (data, file_name)
+  };
+
+  let file_name = lte::render(file_name, template_data)?;
+
+  let parent = p.parent().unwrap();
+  fs::create_dir_all(parent)?;
+  fs::write(parent.join(file_name), file_data)?;
+  Ok(())
+}
+
 fn fmt_sub_template<T>(template: &T, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
 where
   T: Copy + PartialEq + Eq + 'static,
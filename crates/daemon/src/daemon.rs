@@ -0,0 +1,367 @@
+use std::{
+  collections::HashMap,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  time::Instant,
+};
+
+use farmfe_compiler::{multi_project::MultiProjectCompiler, Compiler};
+use farmfe_core::{
+  error::{CompilationError, Result},
+  module::ModuleType,
+  parking_lot::Mutex,
+  plugin::PluginTransformHookParam,
+  VERSION,
+};
+
+use crate::protocol::{
+  DaemonRequest, DaemonRequestPayload, DaemonResponse, DaemonResponsePayload, GraphQuery,
+  DAEMON_PROTOCOL_VERSION,
+};
+
+enum DaemonProjects {
+  Single(Arc<Compiler>),
+  /// the requests select the project by [DaemonRequest::project]
+  Multi(MultiProjectCompiler),
+}
+
+/// A long-lived compiler shared by all clients of the daemon, so the module graph and caches stay warm between requests.
+pub struct Daemon {
+  projects: DaemonProjects,
+  /// build and update mutate the context, they must not run at the same time
+  compile_lock: Mutex<()>,
+  shutdown: AtomicBool,
+}
+
+impl Daemon {
+  pub fn new(compiler: Compiler) -> Self {
+    Self::with_projects(DaemonProjects::Single(Arc::new(compiler)))
+  }
+
+  /// Host multiple projects, the requests are routed to the project named by [DaemonRequest::project]
+  /// or to the project mounted on the url of [DaemonRequestPayload::ResolveUrl]
+  pub fn new_multi_project(compiler: MultiProjectCompiler) -> Self {
+    Self::with_projects(DaemonProjects::Multi(compiler))
+  }
+
+  fn with_projects(projects: DaemonProjects) -> Self {
+    Self {
+      projects,
+      compile_lock: Mutex::new(()),
+      shutdown: AtomicBool::new(false),
+    }
+  }
+
+  pub fn is_shutdown(&self) -> bool {
+    self.shutdown.load(Ordering::SeqCst)
+  }
+
+  /// Metrics of the compiler in the Prometheus text format, see [Compiler::metrics]. They are served at `/metrics`,
+  /// or at `<mount>metrics` of each project if the daemon hosts multiple projects. [None] if nothing is served at `path`
+  pub fn metrics(&self, path: &str) -> Option<String> {
+    let path = path.split('?').next().unwrap_or_default();
+
+    match &self.projects {
+      DaemonProjects::Single(compiler) => (path == "/metrics").then(|| compiler.metrics()),
+      DaemonProjects::Multi(projects) => projects
+        .resolve_mount(path)
+        .filter(|mounted| mounted.path == "/metrics")
+        .map(|mounted| mounted.compiler.metrics()),
+    }
+  }
+
+  pub fn handle(&self, request: DaemonRequest) -> DaemonResponse {
+    match self.handle_payload(request.project.as_deref(), request.payload) {
+      Ok(result) => DaemonResponse::ok(request.id, result),
+      Err(e) => DaemonResponse::err(request.id, e.to_string()),
+    }
+  }
+
+  /// The compiler of the project the request is sent to
+  fn compiler(&self, project: Option<&str>) -> Result<&Arc<Compiler>> {
+    match (&self.projects, project) {
+      (DaemonProjects::Single(compiler), None) => Ok(compiler),
+      (DaemonProjects::Single(_), Some(project)) => Err(CompilationError::GenericError(format!(
+        "The daemon hosts a single project, the project {project} of the request is not found"
+      ))),
+      (DaemonProjects::Multi(projects), Some(project)) => projects
+        .get(project)
+        .ok_or_else(|| CompilationError::GenericError(format!("Project {project} is not found"))),
+      (DaemonProjects::Multi(projects), None) => Err(CompilationError::GenericError(format!(
+        "The daemon hosts multiple projects, the project of the request is required, one of {}",
+        projects
+          .projects()
+          .into_iter()
+          .map(|name| name.as_str())
+          .collect::<Vec<_>>()
+          .join(", ")
+      ))),
+    }
+  }
+
+  fn handle_payload(
+    &self,
+    project: Option<&str>,
+    payload: DaemonRequestPayload,
+  ) -> Result<DaemonResponsePayload> {
+    match payload {
+      DaemonRequestPayload::Handshake { version } => {
+        if version != DAEMON_PROTOCOL_VERSION {
+          return Err(CompilationError::GenericError(format!(
+            "Incompatible daemon protocol version: client {version}, daemon {DAEMON_PROTOCOL_VERSION}"
+          )));
+        }
+
+        Ok(DaemonResponsePayload::Handshake {
+          version: DAEMON_PROTOCOL_VERSION,
+          core_version: VERSION.to_string(),
+        })
+      }
+      // build all the projects if the daemon hosts multiple projects and the request names none of them
+      DaemonRequestPayload::Build => {
+        let _lock = self.compile_lock.lock();
+        let start = Instant::now();
+        let warnings = match (&self.projects, project) {
+          (DaemonProjects::Multi(projects), None) => {
+            projects.compile_all()?;
+            projects
+              .projects()
+              .into_iter()
+              .flat_map(|name| {
+                projects
+                  .get(name)
+                  .unwrap()
+                  .context()
+                  .log_store
+                  .lock()
+                  .warnings()
+                  .clone()
+              })
+              .collect()
+          }
+          _ => {
+            let compiler = self.compiler(project)?;
+            compiler.compile()?;
+            compiler.context().log_store.lock().warnings().clone()
+          }
+        };
+
+        Ok(DaemonResponsePayload::Build {
+          duration: start.elapsed().as_millis() as u64,
+          warnings,
+        })
+      }
+      DaemonRequestPayload::Update {
+        paths,
+        generate_update_resource,
+      } => {
+        let _lock = self.compile_lock.lock();
+        let result =
+          self
+            .compiler(project)?
+            .update(paths, || {}, true, generate_update_resource)?;
+
+        Ok(DaemonResponsePayload::Update(Box::new(result)))
+      }
+      DaemonRequestPayload::Transform {
+        module_id,
+        content,
+        module_type,
+      } => {
+        let context = self.compiler(project)?.context();
+        let id = context.str_to_module_id(&module_id);
+        let resolved_path = id.resolved_path(&context.config.root);
+        let result = context.plugin_driver.transform(
+          PluginTransformHookParam {
+            module_id,
+            content,
+            module_type: ModuleType::from(module_type),
+            resolved_path: &resolved_path,
+            query: vec![],
+            meta: HashMap::new(),
+            source_map_chain: vec![],
+          },
+          context,
+        )?;
+
+        Ok(DaemonResponsePayload::Transform {
+          content: result.content,
+          source_map_chain: result
+            .source_map_chain
+            .into_iter()
+            .map(|map| map.to_string())
+            .collect(),
+          module_type: result.module_type.map(|ty| ty.to_string()),
+        })
+      }
+      DaemonRequestPayload::Graph(query) => self.query_graph(self.compiler(project)?, query),
+      DaemonRequestPayload::Resources => {
+        let compiler = self.compiler(project)?;
+        compiler.flush_deferred_updates();
+
+        let mut names = compiler
+          .context()
+          .resources_map
+          .iter()
+          .map(|resource| resource.key().clone())
+          .collect::<Vec<_>>();
+        names.sort();
+
+        Ok(DaemonResponsePayload::Resources(names))
+      }
+      DaemonRequestPayload::UpdateSchedulePolicy => {
+        Ok(DaemonResponsePayload::UpdateSchedulePolicy(
+          self.compiler(project)?.update_schedule_policy(),
+        ))
+      }
+      DaemonRequestPayload::SetUpdateSchedulePolicy(policy) => {
+        self.compiler(project)?.set_update_schedule_policy(policy);
+        Ok(DaemonResponsePayload::Empty)
+      }
+      DaemonRequestPayload::FlushDeferredUpdates => Ok(DaemonResponsePayload::FlushedUpdates(
+        self.compiler(project)?.flush_deferred_updates(),
+      )),
+      DaemonRequestPayload::ImportCost { source, importer } => {
+        Ok(DaemonResponsePayload::ImportCost(Box::new(
+          self.compiler(project)?.import_cost(&source, &importer)?,
+        )))
+      }
+      DaemonRequestPayload::ResolveUrl { url } => match &self.projects {
+        DaemonProjects::Single(_) => Ok(DaemonResponsePayload::ResolvedUrl {
+          project: None,
+          path: url,
+        }),
+        DaemonProjects::Multi(projects) => {
+          let mounted = projects.resolve_mount(&url).ok_or_else(|| {
+            CompilationError::GenericError(format!("No project is mounted on {url}"))
+          })?;
+
+          Ok(DaemonResponsePayload::ResolvedUrl {
+            project: Some(mounted.name.to_string()),
+            path: mounted.path,
+          })
+        }
+      },
+      DaemonRequestPayload::Shutdown => {
+        self.shutdown.store(true, Ordering::SeqCst);
+        Ok(DaemonResponsePayload::Empty)
+      }
+    }
+  }
+
+  fn query_graph(&self, compiler: &Compiler, query: GraphQuery) -> Result<DaemonResponsePayload> {
+    let context = compiler.context();
+    let module_graph = context.module_graph.read();
+    let to_strings = |ids: Vec<farmfe_core::module::ModuleId>| {
+      ids.into_iter().map(|id| id.to_string()).collect::<Vec<_>>()
+    };
+    let get_module_id = |module_id: &str| {
+      let id = context.str_to_module_id(module_id);
+
+      if module_graph.has_module(&id) {
+        Ok(id)
+      } else {
+        Err(CompilationError::GenericError(format!(
+          "Module {module_id} is not found in the module graph"
+        )))
+      }
+    };
+
+    Ok(match query {
+      GraphQuery::Modules => DaemonResponsePayload::ModuleIds(to_strings(
+        module_graph
+          .modules()
+          .into_iter()
+          .map(|m| m.id.clone())
+          .collect(),
+      )),
+      GraphQuery::Dependencies { module_id } => DaemonResponsePayload::ModuleIds(to_strings(
+        module_graph.dependencies_ids(&get_module_id(&module_id)?),
+      )),
+      GraphQuery::Dependents { module_id } => DaemonResponsePayload::ModuleIds(to_strings(
+        module_graph.dependents_ids(&get_module_id(&module_id)?),
+      )),
+      GraphQuery::Entries => DaemonResponsePayload::Entries(
+        module_graph
+          .entries
+          .iter()
+          .map(|(id, name)| (id.to_string(), name.clone()))
+          .collect(),
+      ),
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use farmfe_compiler::multi_project::{MultiProjectCompiler, ProjectOptions};
+  use farmfe_core::config::Config;
+
+  use super::Daemon;
+  use crate::protocol::{DaemonRequest, DaemonRequestPayload, DaemonResponsePayload};
+
+  fn multi_project_daemon() -> Daemon {
+    let project = |name: &str, mount: &str| ProjectOptions {
+      name: name.to_string(),
+      mount: mount.to_string(),
+      config: Config::default(),
+      plugins: vec![],
+    };
+
+    Daemon::new_multi_project(
+      MultiProjectCompiler::new(vec![project("app1", "/app1"), project("app2", "/app2/")]).unwrap(),
+    )
+  }
+
+  fn request(project: Option<&str>, payload: DaemonRequestPayload) -> DaemonRequest {
+    DaemonRequest {
+      id: 1,
+      project: project.map(|p| p.to_string()),
+      payload,
+    }
+  }
+
+  #[test]
+  fn route_requests_to_projects() {
+    let daemon = multi_project_daemon();
+
+    let response = daemon.handle(request(Some("app2"), DaemonRequestPayload::Resources));
+    assert!(matches!(
+      response.result,
+      Some(DaemonResponsePayload::Resources(_))
+    ));
+
+    let response = daemon.handle(request(None, DaemonRequestPayload::Resources));
+    assert!(response.error.unwrap().contains("one of app1, app2"));
+
+    let response = daemon.handle(request(Some("app3"), DaemonRequestPayload::Resources));
+    assert_eq!(response.error.unwrap(), "Project app3 is not found");
+  }
+
+  #[test]
+  fn resolve_url_of_mounted_projects() {
+    let daemon = multi_project_daemon();
+    let resolve = |url: &str| {
+      daemon
+        .handle(request(
+          None,
+          DaemonRequestPayload::ResolveUrl {
+            url: url.to_string(),
+          },
+        ))
+        .result
+    };
+
+    assert!(matches!(
+      resolve("/app2/assets/index.js"),
+      Some(DaemonResponsePayload::ResolvedUrl { project: Some(ref project), ref path })
+        if project == "app2" && path == "/assets/index.js"
+    ));
+    assert!(resolve("/app10/index.js").is_none());
+
+    assert!(daemon.metrics("/app1/metrics").is_some());
+    assert!(daemon.metrics("/metrics").is_none());
+  }
+}
@@ -0,0 +1,7 @@
+#![deny(clippy::all)]
+
+pub mod daemon;
+pub mod protocol;
+pub mod transport;
+
+pub use daemon::Daemon;
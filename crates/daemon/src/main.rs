@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use farmfe_compiler::{
+  multi_project::{MultiProjectCompiler, ProjectOptions},
+  Compiler,
+};
+use farmfe_core::{config::Config, serde_json};
+use farmfe_daemon::{transport, Daemon};
+
+fn print_usage() {
+  eprintln!(
+    "Usage: farm-daemon (--config <resolved-config.json> | --project <name> <mount> <resolved-config.json>...) [--socket <host:port>] [--metrics <host:port>]"
+  );
+}
+
+fn read_config(config_path: &str) -> Config {
+  let content = std::fs::read_to_string(config_path)
+    .unwrap_or_else(|e| panic!("failed to read config {config_path}: {e:?}"));
+
+  serde_json::from_str(&content).unwrap_or_else(|e| panic!("invalid config {config_path}: {e:?}"))
+}
+
+fn main() {
+  let mut config_path = None;
+  let mut projects = vec![];
+  let mut socket = None;
+  let mut metrics = None;
+  let mut args = std::env::args().skip(1);
+
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--config" => config_path = args.next(),
+      "--project" => match (args.next(), args.next(), args.next()) {
+        (Some(name), Some(mount), Some(config_path)) => projects.push(ProjectOptions {
+          name,
+          mount,
+          config: read_config(&config_path),
+          plugins: vec![],
+        }),
+        _ => {
+          print_usage();
+          std::process::exit(1);
+        }
+      },
+      "--socket" => socket = args.next(),
+      "--metrics" => metrics = args.next(),
+      _ => {
+        print_usage();
+        std::process::exit(1);
+      }
+    }
+  }
+
+  let daemon = match (config_path, projects.is_empty()) {
+    (Some(config_path), true) => {
+      let compiler =
+        Compiler::new(read_config(&config_path), vec![]).unwrap_or_else(|e| panic!("{e}"));
+      Daemon::new(compiler)
+    }
+    (None, false) => {
+      let compiler = MultiProjectCompiler::new(projects).unwrap_or_else(|e| panic!("{e}"));
+      Daemon::new_multi_project(compiler)
+    }
+    _ => {
+      print_usage();
+      std::process::exit(1);
+    }
+  };
+  let daemon = Arc::new(daemon);
+
+  if let Some(metrics) = metrics {
+    if let Err(e) = transport::serve_metrics(daemon.clone(), metrics) {
+      eprintln!("[farm daemon] failed to serve metrics: {e:?}");
+      std::process::exit(1);
+    }
+  }
+
+  let result = if let Some(socket) = socket {
+    transport::serve_socket(daemon, socket)
+  } else {
+    transport::serve_stdio(daemon)
+  };
+
+  if let Err(e) = result {
+    eprintln!("[farm daemon] {e:?}");
+    std::process::exit(1);
+  }
+}
@@ -0,0 +1,223 @@
+//! The protocol between the daemon and its clients. Every message is a single line of json,
+//! a client sends [DaemonRequest] and the daemon replies a [DaemonResponse] with the same id.
+//! The `kind` of the result tells the client how to decode its `data`, e.g. `{"kind":"resources","data":["index.js"]}`.
+use std::collections::HashMap;
+
+use farmfe_compiler::import_cost::ImportCost;
+use farmfe_core::{
+  context::update_schedule::UpdateSchedulePolicy,
+  plugin::{UpdateResult, UpdateType},
+  serde::{Deserialize, Serialize},
+};
+
+/// Bump this version when the protocol is changed in an incompatible way.
+/// Clients should send [DaemonRequestPayload::Handshake] first to make sure the versions match.
+pub const DAEMON_PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+pub struct DaemonRequest {
+  pub id: u64,
+  /// name of the project the request is sent to, required if the daemon hosts multiple projects
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub project: Option<String>,
+  #[serde(flatten)]
+  pub payload: DaemonRequestPayload,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+  crate = "farmfe_core::serde",
+  tag = "method",
+  content = "params",
+  rename_all = "camelCase"
+)]
+pub enum DaemonRequestPayload {
+  Handshake {
+    version: u32,
+  },
+  /// full compilation of the project
+  Build,
+  /// hmr update of the changed paths
+  #[serde(rename_all = "camelCase")]
+  Update {
+    paths: Vec<(String, UpdateType)>,
+    #[serde(default)]
+    generate_update_resource: bool,
+  },
+  /// run the transform hooks of the plugins for the content
+  #[serde(rename_all = "camelCase")]
+  Transform {
+    module_id: String,
+    content: String,
+    module_type: String,
+  },
+  /// query the module graph
+  Graph(GraphQuery),
+  /// names of the generated resources, the deferred updates are regenerated first
+  Resources,
+  /// the policy that decides when the resources of the updates are regenerated
+  UpdateSchedulePolicy,
+  SetUpdateSchedulePolicy(UpdateSchedulePolicy),
+  /// regenerate the resources of the deferred updates now
+  FlushDeferredUpdates,
+  /// the modules bundled because of importing `source` from `importer` and their size, for import cost editor extensions
+  ImportCost {
+    source: String,
+    importer: String,
+  },
+  /// the project mounted on the url, so a dev server in front of the daemon can route the request to the project
+  ResolveUrl {
+    url: String,
+  },
+  /// stop the daemon, all clients will be disconnected
+  Shutdown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", tag = "kind", rename_all = "camelCase")]
+pub enum GraphQuery {
+  Modules,
+  #[serde(rename_all = "camelCase")]
+  Dependencies {
+    module_id: String,
+  },
+  #[serde(rename_all = "camelCase")]
+  Dependents {
+    module_id: String,
+  },
+  Entries,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+pub struct DaemonResponse {
+  pub id: u64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub result: Option<DaemonResponsePayload>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+impl DaemonResponse {
+  pub fn ok(id: u64, result: DaemonResponsePayload) -> Self {
+    Self {
+      id,
+      result: Some(result),
+      error: None,
+    }
+  }
+
+  pub fn err(id: u64, error: String) -> Self {
+    Self {
+      id,
+      result: None,
+      error: Some(error),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+  crate = "farmfe_core::serde",
+  tag = "kind",
+  content = "data",
+  rename_all = "camelCase"
+)]
+pub enum DaemonResponsePayload {
+  #[serde(rename_all = "camelCase")]
+  Handshake {
+    version: u32,
+    core_version: String,
+  },
+  Build {
+    duration: u64,
+    warnings: Vec<String>,
+  },
+  Update(Box<UpdateResult>),
+  #[serde(rename_all = "camelCase")]
+  Transform {
+    content: String,
+    source_map_chain: Vec<String>,
+    module_type: Option<String>,
+  },
+  ModuleIds(Vec<String>),
+  Entries(HashMap<String, String>),
+  Resources(Vec<String>),
+  UpdateSchedulePolicy(UpdateSchedulePolicy),
+  /// number of the regenerated deferred updates
+  FlushedUpdates(usize),
+  ImportCost(Box<ImportCost>),
+  /// `project` is null if the daemon hosts a single project, `path` is the url relative to the mount of the project
+  ResolvedUrl {
+    project: Option<String>,
+    path: String,
+  },
+  Empty,
+}
+
+#[cfg(test)]
+mod tests {
+  use farmfe_core::{context::update_schedule::UpdateSchedule, serde_json};
+
+  use super::{
+    DaemonRequest, DaemonRequestPayload, DaemonResponse, DaemonResponsePayload, GraphQuery,
+  };
+
+  #[test]
+  fn parse_request() {
+    let request: DaemonRequest = serde_json::from_str(
+      r#"{"id":1,"method":"graph","params":{"kind":"dependents","moduleId":"src/a.ts"}}"#,
+    )
+    .unwrap();
+
+    assert_eq!(request.id, 1);
+    assert_eq!(request.project, None);
+    assert!(matches!(
+      request.payload,
+      DaemonRequestPayload::Graph(GraphQuery::Dependents { ref module_id }) if module_id == "src/a.ts"
+    ));
+
+    let request: DaemonRequest =
+      serde_json::from_str(r#"{"id":2,"project":"app1","method":"build"}"#).unwrap();
+    assert_eq!(request.project.as_deref(), Some("app1"));
+    assert!(matches!(request.payload, DaemonRequestPayload::Build));
+
+    let request: DaemonRequest = serde_json::from_str(
+      r#"{"id":3,"method":"setUpdateSchedulePolicy","params":{"moduleChange":"deferred"}}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+      request.payload,
+      DaemonRequestPayload::SetUpdateSchedulePolicy(ref policy)
+        if policy.module_change == UpdateSchedule::Deferred && policy.worker == UpdateSchedule::Sync
+    ));
+  }
+
+  #[test]
+  fn serialize_response() {
+    // the module ids and the resource names are both lists of strings, the kind tells them apart
+    let response = DaemonResponse::ok(
+      3,
+      DaemonResponsePayload::Resources(vec!["index.js".to_string()]),
+    );
+    assert_eq!(
+      serde_json::to_string(&response).unwrap(),
+      r#"{"id":3,"result":{"kind":"resources","data":["index.js"]}}"#
+    );
+
+    let response: DaemonResponse =
+      serde_json::from_str(r#"{"id":4,"result":{"kind":"moduleIds","data":["src/a.ts"]}}"#)
+        .unwrap();
+    assert!(matches!(
+      response.result,
+      Some(DaemonResponsePayload::ModuleIds(ref ids)) if ids == &["src/a.ts"]
+    ));
+
+    let response = DaemonResponse::ok(5, DaemonResponsePayload::Empty);
+    assert_eq!(
+      serde_json::to_string(&response).unwrap(),
+      r#"{"id":5,"result":{"kind":"empty"}}"#
+    );
+  }
+}
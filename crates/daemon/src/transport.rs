@@ -0,0 +1,182 @@
+use std::{
+  io::{BufRead, BufReader, Read, Write},
+  net::{TcpListener, TcpStream, ToSocketAddrs},
+  sync::Arc,
+};
+
+use farmfe_core::serde_json;
+
+use crate::{
+  daemon::Daemon,
+  protocol::{DaemonRequest, DaemonResponse},
+};
+
+/// Read requests line by line from `reader` and write the responses to `writer` until the input is closed or the daemon is shutdown.
+pub fn serve_connection<R: BufRead, W: Write>(
+  daemon: &Daemon,
+  reader: R,
+  mut writer: W,
+) -> std::io::Result<()> {
+  for line in reader.lines() {
+    let line = line?;
+
+    if line.trim().is_empty() {
+      continue;
+    }
+
+    let response = match serde_json::from_str::<DaemonRequest>(&line) {
+      Ok(request) => daemon.handle(request),
+      // the id is unknown when the request is malformed
+      Err(e) => DaemonResponse::err(0, format!("Invalid request: {e}")),
+    };
+
+    writeln!(writer, "{}", serde_json::to_string(&response).unwrap())?;
+    writer.flush()?;
+
+    if daemon.is_shutdown() {
+      break;
+    }
+  }
+
+  Ok(())
+}
+
+/// Serve a single client through stdin and stdout, used when the daemon is spawned by the client.
+pub fn serve_stdio(daemon: Arc<Daemon>) -> std::io::Result<()> {
+  let stdin = std::io::stdin();
+  let stdout = std::io::stdout();
+
+  serve_connection(&daemon, stdin.lock(), stdout.lock())
+}
+
+/// Serve multiple clients through a socket, every client is handled in its own thread and all of them share the same daemon.
+pub fn serve_socket<A: ToSocketAddrs>(daemon: Arc<Daemon>, addr: A) -> std::io::Result<()> {
+  let listener = TcpListener::bind(addr)?;
+  println!("farm daemon listening on {}", listener.local_addr()?);
+
+  serve_listener(daemon, listener)
+}
+
+/// Accept the clients of `listener` until a client shuts the daemon down, the other clients are disconnected when the process exits.
+pub fn serve_listener(daemon: Arc<Daemon>, listener: TcpListener) -> std::io::Result<()> {
+  let addr = listener.local_addr()?;
+
+  for stream in listener.incoming() {
+    if daemon.is_shutdown() {
+      break;
+    }
+
+    let stream = stream?;
+    let daemon = daemon.clone();
+
+    std::thread::spawn(move || {
+      let reader = BufReader::new(stream.try_clone().expect("failed to clone tcp stream"));
+
+      if let Err(e) = serve_connection(&daemon, reader, stream) {
+        eprintln!("[farm daemon] client disconnected with error: {e:?}");
+      }
+
+      // wake up the accept loop, so it sees the shutdown and returns
+      if daemon.is_shutdown() {
+        let _ = TcpStream::connect(addr);
+      }
+    });
+  }
+
+  Ok(())
+}
+
+/// Serve the metrics of the daemon at `GET /metrics` over http in a background thread, so the compilers of many daemons
+/// can be scraped by Prometheus. The metrics of the projects of a multi-project daemon are served at `GET <mount>metrics`,
+/// see [Daemon::metrics]. Other paths respond with 404.
+pub fn serve_metrics<A: ToSocketAddrs>(daemon: Arc<Daemon>, addr: A) -> std::io::Result<()> {
+  let listener = TcpListener::bind(addr)?;
+  println!(
+    "farm daemon metrics on http://{}/metrics",
+    listener.local_addr()?
+  );
+
+  std::thread::spawn(move || {
+    for stream in listener.incoming() {
+      let Ok(mut stream) = stream else {
+        continue;
+      };
+
+      if let Err(e) = respond_metrics(&daemon, &mut stream) {
+        eprintln!("[farm daemon] failed to serve metrics: {e:?}");
+      }
+    }
+  });
+
+  Ok(())
+}
+
+fn respond_metrics<S: Read + Write>(daemon: &Daemon, stream: S) -> std::io::Result<()> {
+  let mut reader = BufReader::new(stream);
+  let mut request_line = String::new();
+  reader.read_line(&mut request_line)?;
+
+  // skip the headers, the requests have no body
+  let mut header = String::new();
+  while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+    header.clear();
+  }
+
+  let metrics = match request_line.split_whitespace().take(2).collect::<Vec<_>>()[..] {
+    ["GET", path] => daemon.metrics(path),
+    _ => None,
+  };
+  let (status, content_type, body) = match metrics {
+    Some(metrics) => (
+      "200 OK",
+      "text/plain; version=0.0.4; charset=utf-8",
+      metrics,
+    ),
+    None => (
+      "404 Not Found",
+      "text/plain; charset=utf-8",
+      "Not Found".to_string(),
+    ),
+  };
+
+  let stream = reader.get_mut();
+  write!(
+    stream,
+    "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+    body.len()
+  )?;
+  stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::Arc,
+  };
+
+  use farmfe_compiler::Compiler;
+  use farmfe_core::config::Config;
+
+  use super::serve_listener;
+  use crate::Daemon;
+
+  #[test]
+  fn shutdown_stops_accepting_clients() {
+    let daemon = Arc::new(Daemon::new(
+      Compiler::new(Config::default(), vec![]).unwrap(),
+    ));
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || serve_listener(daemon, listener));
+
+    let mut client = TcpStream::connect(addr).unwrap();
+    writeln!(client, r#"{{"id":1,"method":"shutdown"}}"#).unwrap();
+    let mut response = String::new();
+    BufReader::new(&client).read_line(&mut response).unwrap();
+
+    assert_eq!(response.trim(), r#"{"id":1,"result":{"kind":"empty"}}"#);
+    server.join().unwrap().unwrap();
+  }
+}
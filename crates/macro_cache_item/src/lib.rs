@@ -24,12 +24,18 @@ pub fn cache_item(attr: TokenStream, input: TokenStream) -> TokenStream {
       use rkyv::*;
 
       #[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+      #[archive(check_bytes)]
       #[archive_attr(derive(TypeName))]
       #item
 
-      #[archive_dyn(deserialize)]
-      impl #args for #item_ident {}
-      impl #args for rkyv::Archived<#item_ident> {}
+      // `archive_dyn` registers the impl inside nested anonymous consts, which is reported as a non-local impl
+      // wherever the item is declared
+      #[allow(non_local_definitions)]
+      const _: () = {
+        #[archive_dyn(deserialize)]
+        impl #args for #item_ident {}
+        impl #args for rkyv::Archived<#item_ident> {}
+      };
     };
 
     return derives.into();
@@ -39,6 +45,7 @@ pub fn cache_item(attr: TokenStream, input: TokenStream) -> TokenStream {
     use rkyv::*;
 
     #[derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)]
+    #[archive(check_bytes)]
     #item
   };
 
@@ -13,28 +13,35 @@ pub mod plugin_toolkit;
 pub mod profile_gui;
 
 use farmfe_core::{
-  config::{Config, Mode},
-  module::ModuleId,
+  cache::archive::CacheArchiveResult,
+  config::{persistent_cache::GlobalCacheConfig, Config, Mode},
+  context::{
+    hmr_channel::HmrCustomEvent, progress::BuildProgress, InvalidateModuleOptions, UpdatePriority,
+  },
+  module::{module_graph::ModuleGraphFilter, ModuleId},
   plugin::UpdateType,
 };
 
 #[cfg(feature = "file_watcher")]
 use farmfe_core::resource::Resource;
-#[cfg(feature = "file_watcher")]
-use napi::threadsafe_function::ThreadSafeCallContext;
-
 use napi::{
   bindgen_prelude::{Buffer, FromNapiValue},
-  threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode},
+  threadsafe_function::{
+    ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+  },
   Env, JsFunction, JsObject, JsUndefined, JsUnknown, NapiRaw, Status,
 };
 
+use farmfe_plugin_macro::FarmPluginMacro;
 #[cfg(feature = "file_watcher")]
 use notify::{
   event::{AccessKind, ModifyKind},
   EventKind, RecommendedWatcher, Watcher,
 };
-use plugin_adapters::{js_plugin_adapter::JsPluginAdapter, rust_plugin_adapter::RustPluginAdapter};
+use plugin_adapters::{
+  js_macro_evaluator::JsMacroEvaluator, js_plugin_adapter::JsPluginAdapter,
+  rust_plugin_adapter::RustPluginAdapter,
+};
 
 // pub use farmfe_toolkit_plugin;
 
@@ -90,9 +97,25 @@ pub struct JsUpdateResult {
   pub removed: Vec<String>,
   pub immutable_modules: String,
   pub mutable_modules: String,
+  pub css_updates: HashMap<String, String>,
   pub boundaries: HashMap<String, Vec<Vec<String>>>,
+  pub accepted_deps: HashMap<String, Vec<String>>,
+  pub hmr_full_reload: Option<JsHmrFullReload>,
   pub dynamic_resources_map: Option<HashMap<String, Vec<Vec<String>>>>,
   pub extra_watch_result: WatchDiffResult,
+  pub removed_resources: Vec<String>,
+  pub updated_html_resources: Vec<String>,
+  pub updated_workers: Vec<String>,
+  pub full_rebuild_reason: Option<String>,
+}
+
+/// see [farmfe_core::plugin::HmrFullReload]
+#[napi(object)]
+pub struct JsHmrFullReload {
+  pub module: String,
+  pub path: Vec<String>,
+  /// `entry` or `nonScriptImporter`
+  pub reason: String,
 }
 
 #[napi(js_name = "Compiler")]
@@ -126,6 +149,12 @@ impl JsCompiler {
       .expect("rustPlugins should be an array of js strings")
     };
 
+    let macro_host = config
+      .get_named_property::<JsUnknown>("macroHost")
+      .ok()
+      .filter(|f| matches!(f.get_type(), Ok(napi::ValueType::Function)))
+      .map(|f| unsafe { f.cast::<JsFunction>() });
+
     let config: Config = env
       .from_js_value(
         config
@@ -136,6 +165,14 @@ impl JsCompiler {
 
     let mut plugins_adapters = vec![];
 
+    // build time macros are executed by the js host
+    if let (Some(_), Some(macro_host)) = (&config.macros, macro_host) {
+      plugins_adapters.push(Arc::new(FarmPluginMacro::new(
+        &config,
+        Arc::new(JsMacroEvaluator::new(&env, macro_host)),
+      )) as _);
+    }
+
     for js_plugin_object in js_plugins {
       let js_plugin = Arc::new(
         JsPluginAdapter::new(&env, js_plugin_object)
@@ -148,6 +185,11 @@ impl JsCompiler {
       let rust_plugin_path = rust_plugin[0].clone();
       let rust_plugin_options = rust_plugin[1].clone();
 
+      if rust_plugin_path.ends_with(".wasm") {
+        plugins_adapters.push(load_wasm_plugin(&rust_plugin_path, rust_plugin_options));
+        continue;
+      }
+
       let rust_plugin = Arc::new(
         RustPluginAdapter::new(&rust_plugin_path, &config, rust_plugin_options)
           .unwrap_or_else(|e| panic!("load rust plugin error: {e:?}")),
@@ -233,6 +275,30 @@ impl JsCompiler {
     Ok(result)
   }
 
+  /// async compile of the subgraph reachable from `input` (entry name -> source), return promise
+  #[napi]
+  pub fn compile_subset(&self, e: Env, input: HashMap<String, String>) -> napi::Result<JsObject> {
+    let (promise, result) =
+      e.create_deferred::<JsUndefined, Box<dyn FnOnce(Env) -> napi::Result<JsUndefined>>>()?;
+
+    let compiler = self.compiler.clone();
+    self.compiler.thread_pool.spawn(move || {
+      match compiler
+        .compile_subset(&input)
+        .map_err(|e| napi::Error::new(Status::GenericFailure, format!("{e}")))
+      {
+        Ok(_) => {
+          promise.resolve(Box::new(|e| e.get_undefined()));
+        }
+        Err(err) => {
+          promise.reject(err);
+        }
+      }
+    });
+
+    Ok(result)
+  }
+
   /// sync compile
   #[napi]
   pub fn compile_sync(&self) -> napi::Result<()> {
@@ -258,6 +324,52 @@ impl JsCompiler {
     Ok(())
   }
 
+  /// Call `callback` with the progress of the builds, see [farmfe_core::context::progress::BuildProgress].
+  /// The callback doesn't keep the process alive
+  #[napi]
+  pub fn on_progress(&self, e: Env, callback: JsFunction) -> napi::Result<()> {
+    let mut thread_safe_callback: ThreadsafeFunction<BuildProgress, ErrorStrategy::Fatal> =
+      callback.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<BuildProgress>| {
+        ctx.env.to_js_value(&ctx.value).map(|v| vec![v])
+      })?;
+    thread_safe_callback.unref(&e)?;
+
+    self.compiler.context().progress.subscribe(move |progress| {
+      thread_safe_callback.call(progress.clone(), ThreadsafeFunctionCallMode::NonBlocking);
+    });
+
+    Ok(())
+  }
+
+  /// Call `callback` with the custom events sent by the plugins to the hmr clients, see [farmfe_core::context::hmr_channel::HmrChannel].
+  /// The callback doesn't keep the process alive
+  #[napi]
+  pub fn on_hmr_event(&self, e: Env, callback: JsFunction) -> napi::Result<()> {
+    let mut thread_safe_callback: ThreadsafeFunction<HmrCustomEvent, ErrorStrategy::Fatal> =
+      callback.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<HmrCustomEvent>| {
+        ctx.env.to_js_value(&ctx.value).map(|v| vec![v])
+      })?;
+    thread_safe_callback.unref(&e)?;
+
+    self.compiler.context().hmr_channel.subscribe(move |event| {
+      thread_safe_callback.call(event.clone(), ThreadsafeFunctionCallMode::NonBlocking);
+    });
+
+    Ok(())
+  }
+
+  /// Pass a custom event sent by a hmr client to the `hmr_event` hook of the plugins
+  #[napi]
+  pub fn hmr_event(&self, e: Env, event: String, data: JsUnknown) -> napi::Result<()> {
+    let data = e.from_js_value(data)?;
+    let context = self.compiler.context();
+
+    context
+      .plugin_driver
+      .hmr_event(&HmrCustomEvent { event, data }, context)
+      .map_err(|e| napi::Error::new(Status::GenericFailure, format!("{e}")))
+  }
+
   /// TODO: usage example
   #[napi]
   pub fn update(
@@ -267,8 +379,14 @@ impl JsCompiler {
     callback: JsFunction,
     sync: bool,
     generate_update_resource: bool,
+    priority: Option<String>,
   ) -> napi::Result<JsObject> {
     let context = self.compiler.context().clone();
+    // background updates, e.g. lazy compilation, yield to interactive hmr updates
+    let priority = match priority.as_deref() {
+      Some("background") => UpdatePriority::Background,
+      _ => UpdatePriority::Interactive,
+    };
     let thread_safe_callback: ThreadsafeFunction<(), ErrorStrategy::Fatal> =
       callback.create_threadsafe_function(0, |ctx| ctx.env.get_undefined().map(|v| vec![v]))?;
 
@@ -278,7 +396,7 @@ impl JsCompiler {
     let compiler = self.compiler.clone();
     self.compiler.thread_pool.spawn(move || {
       match compiler
-        .update(
+        .update_with_priority(
           paths
             .into_iter()
             .map(|p| (p, UpdateType::Updated))
@@ -288,6 +406,7 @@ impl JsCompiler {
           },
           sync,
           generate_update_resource,
+          priority,
         )
         .map_err(|e| napi::Error::new(Status::GenericFailure, format!("{e}")))
       {
@@ -310,7 +429,18 @@ impl JsCompiler {
               .collect(),
             immutable_modules: res.immutable_resources,
             mutable_modules: res.mutable_resources,
+            css_updates: res
+              .css_updates
+              .into_iter()
+              .map(|(id, css)| (id.id(Mode::Development), css))
+              .collect(),
             boundaries: res.boundaries,
+            accepted_deps: res.accepted_deps,
+            hmr_full_reload: res.hmr_full_reload.map(|full_reload| JsHmrFullReload {
+              module: full_reload.module,
+              path: full_reload.path,
+              reason: full_reload.reason.as_str().to_string(),
+            }),
             dynamic_resources_map: res.dynamic_resources_map.map(|dynamic_resources_map| {
               dynamic_resources_map
                 .into_iter()
@@ -339,6 +469,14 @@ impl JsCompiler {
                 .map(|path| ModuleId::new(&path, "", &context.config.root).id(Mode::Development))
                 .collect(),
             },
+            removed_resources: res.removed_resources,
+            updated_html_resources: res.updated_html_resources,
+            updated_workers: res
+              .updated_workers
+              .into_iter()
+              .map(|id| id.id(Mode::Development))
+              .collect(),
+            full_rebuild_reason: res.full_rebuild_reason,
           };
 
           promise.resolve(Box::new(move |_| Ok(js_update_result)));
@@ -407,16 +545,26 @@ impl JsCompiler {
       .collect()
   }
 
+  /// Resources served by the dev server, or written to disk when `for_disk` is true.
+  /// Dev server only resources are excluded when writing to disk
   #[napi]
-  pub fn resources(&self) -> HashMap<String, Buffer> {
+  pub fn resources(&self, for_disk: Option<bool>) -> HashMap<String, Buffer> {
+    self.compiler.flush_deferred_updates();
+
     let context = self.compiler.context();
-    let resources = context.resources_map.lock();
+    let for_disk = for_disk.unwrap_or(false);
 
     let mut result = HashMap::new();
 
-    for resource in resources.values() {
-      // only write expose non-emitted resource
-      if !resource.emitted {
+    for resource in context.resources_map.iter() {
+      let exposed = if for_disk {
+        resource.should_write()
+      } else {
+        !resource.emitted
+      };
+
+      // only write expose non-emitted resource, streamed resources are exposed by `streamed_resources`
+      if exposed && resource.source_path.is_none() {
         result.insert(resource.name.clone(), resource.bytes.clone().into());
       }
     }
@@ -424,14 +572,41 @@ impl JsCompiler {
     result
   }
 
+  /// Large resources that are not loaded into memory, returns resource name -> source path
+  #[napi]
+  pub fn streamed_resources(&self) -> HashMap<String, String> {
+    self.compiler.flush_deferred_updates();
+
+    let context = self.compiler.context();
+
+    context
+      .resources_map
+      .iter()
+      .filter(|r| r.should_write())
+      .filter_map(|r| Some((r.name.clone(), r.source_path.clone()?)))
+      .collect()
+  }
+
   #[napi]
   pub fn resources_map(&self, e: Env) -> HashMap<String, JsUnknown> {
+    self.compiler.flush_deferred_updates();
+
     let context = self.compiler.context();
-    let resources = context.resources_map.lock();
     let mut resources_map = HashMap::new();
 
-    for (name, resource) in resources.iter() {
-      resources_map.insert(name.clone(), e.to_js_value(resource).unwrap());
+    for resource in context.resources_map.iter() {
+      // js plugins read the content of large assets from `bytes` as well
+      let value = if resource.source_path.is_some() {
+        let mut resource = resource.value().clone();
+        resource.bytes = resource
+          .read_bytes()
+          .map(|bytes| bytes.into_owned())
+          .unwrap_or_default();
+        e.to_js_value(&resource)
+      } else {
+        e.to_js_value(resource.value())
+      };
+      resources_map.insert(resource.key().clone(), value.unwrap());
     }
 
     resources_map
@@ -464,10 +639,26 @@ impl JsCompiler {
 
   #[napi]
   pub fn resource(&self, name: String) -> Option<Buffer> {
+    self.compiler.flush_deferred_updates();
+
+    let context = self.compiler.context();
+
+    context
+      .resources_map
+      .get(&name)
+      .and_then(|r| r.read_bytes().ok().map(|bytes| bytes.into_owned().into()))
+  }
+
+  #[napi]
+  pub fn resource_source_path(&self, name: String) -> Option<String> {
+    self.compiler.flush_deferred_updates();
+
     let context = self.compiler.context();
-    let resources = context.resources_map.lock();
 
-    resources.get(&name).map(|r| r.bytes.clone().into())
+    context
+      .resources_map
+      .get(&name)
+      .and_then(|r| r.source_path.clone())
   }
 
   #[napi]
@@ -476,17 +667,268 @@ impl JsCompiler {
     context.record_manager.to_string()
   }
 
+  /// Json report of the generated resources, null if `bundleStats` is not configured
+  #[napi]
+  pub fn bundle_stats(&self) -> Option<String> {
+    let context = self.compiler.context();
+    let bundle_stats = context.record_manager.bundle_stats.read();
+
+    bundle_stats
+      .as_ref()
+      .map(|stats| farmfe_core::serde_json::to_string(stats).unwrap())
+  }
+
+  /// Json array of the diagnostics reported by the analysis passes, e.g. circular dependencies
+  #[napi]
+  pub fn diagnostics(&self) -> String {
+    let context = self.compiler.context();
+    let diagnostics = context.diagnostics.lock();
+
+    farmfe_core::serde_json::to_string(diagnostics.diagnostics()).unwrap()
+  }
+
+  /// Readable report of the diagnostics grouped by module and plugin, at most `max_diagnostics` of them are rendered
   #[napi]
-  pub fn invalidate_module(&self, module_id: String) {
-    invalidate_module(self, module_id);
+  pub fn diagnostics_report(&self, max_diagnostics: u32) -> String {
+    let context = self.compiler.context();
+    let diagnostics = context.diagnostics.lock();
+
+    diagnostics.report(max_diagnostics as usize)
   }
+
+  /// Counters and histograms of the builds and hmr updates in the Prometheus text format
+  #[napi]
+  pub fn metrics(&self) -> String {
+    self.compiler.metrics()
+  }
+
+  /// Json array of the shortest import chains from the entries to the module
+  #[napi]
+  pub fn import_chains(&self, module_id: String) -> String {
+    let context = self.compiler.context();
+    let module_id = context.str_to_module_id(&module_id);
+
+    farmfe_core::serde_json::to_string(&self.compiler.import_chains(&module_id)).unwrap()
+  }
+
+  /// Graphviz DOT diagram of the modules selected by the filter, all the modules reachable from the entries by default
+  #[napi]
+  pub fn module_graph_to_dot(&self, filter: Option<JsModuleGraphFilter>) -> napi::Result<String> {
+    self
+      .compiler
+      .module_graph_to_dot(&filter.unwrap_or_default().into())
+      .map_err(|e| napi::Error::new(Status::GenericFailure, format!("{e}")))
+  }
+
+  /// Mermaid flowchart of the modules selected by the filter, all the modules reachable from the entries by default
+  #[napi]
+  pub fn module_graph_to_mermaid(
+    &self,
+    filter: Option<JsModuleGraphFilter>,
+  ) -> napi::Result<String> {
+    self
+      .compiler
+      .module_graph_to_mermaid(&filter.unwrap_or_default().into())
+      .map_err(|e| napi::Error::new(Status::GenericFailure, format!("{e}")))
+  }
+
+  /// Json of the changes the update of the paths would apply, the module graph and the resources are not changed
+  #[napi]
+  pub fn plan_update(&self, e: Env, paths: Vec<String>) -> napi::Result<JsObject> {
+    let (promise, result) =
+      e.create_deferred::<String, Box<dyn FnOnce(Env) -> napi::Result<String>>>()?;
+
+    let compiler = self.compiler.clone();
+    self.compiler.thread_pool.spawn(move || {
+      match compiler
+        .plan_update(
+          paths
+            .into_iter()
+            .map(|p| (p, UpdateType::Updated))
+            .collect(),
+        )
+        .map_err(|e| napi::Error::new(Status::GenericFailure, format!("{e}")))
+      {
+        Ok(plan) => {
+          let plan = farmfe_core::serde_json::to_string(&plan).unwrap();
+          promise.resolve(Box::new(|_| Ok(plan)));
+        }
+        Err(err) => {
+          promise.reject(err);
+        }
+      }
+    });
+
+    Ok(result)
+  }
+
+  /// Json array of the top level statements of the script module, `null` if it's not a script module
+  #[napi]
+  pub fn module_statements(&self, module_id: String) -> String {
+    let context = self.compiler.context();
+    let module_id = context.str_to_module_id(&module_id);
+
+    farmfe_core::serde_json::to_string(&self.compiler.module_statements(&module_id)).unwrap()
+  }
+
+  /// Json array of the exported names of the script module, `null` if it's not a script module
+  #[napi]
+  pub fn module_exports(&self, module_id: String) -> String {
+    let context = self.compiler.context();
+    let module_id = context.str_to_module_id(&module_id);
+
+    farmfe_core::serde_json::to_string(&self.compiler.module_exports(&module_id)).unwrap()
+  }
+
+  /// Schedule the module to be recompiled by the next update, like the file is changed
+  #[napi]
+  pub fn invalidate_module(&self, module_id: String, options: Option<JsInvalidateModuleOptions>) {
+    invalidate_module(self, module_id, options.unwrap_or_default());
+  }
+
+  /// Resolved paths of the invalidated modules that are not recompiled yet
+  #[napi]
+  pub fn invalidated_modules(&self) -> Vec<String> {
+    let context = self.compiler.context();
+
+    context
+      .invalidated_modules
+      .lock()
+      .keys()
+      .map(|id| id.resolved_path_with_query(&context.config.root))
+      .collect()
+  }
+
+  /// The policy that decides when the resources of the updates are regenerated, see [farmfe_core::context::update_schedule::UpdateSchedulePolicy]
+  #[napi]
+  pub fn update_schedule_policy(&self, e: Env) -> napi::Result<JsUnknown> {
+    e.to_js_value(&self.compiler.update_schedule_policy())
+  }
+
+  #[napi]
+  pub fn set_update_schedule_policy(&self, e: Env, policy: JsUnknown) -> napi::Result<()> {
+    let policy = e.from_js_value(policy)?;
+    self.compiler.set_update_schedule_policy(policy);
+
+    Ok(())
+  }
+
+  /// Regenerate the resources of the deferred updates now, returns the number of the regenerated updates.
+  /// The resources are flushed before they are read by `resources` and `resource` as well
+  #[napi]
+  pub fn flush_deferred_updates(&self) -> u32 {
+    self.compiler.flush_deferred_updates() as u32
+  }
+}
+
+#[napi(object)]
+#[derive(Default)]
+pub struct JsInvalidateModuleOptions {
+  pub recompile_dependents: Option<bool>,
+}
+
+#[napi(object)]
+#[derive(Default)]
+pub struct JsModuleGraphFilter {
+  pub pattern: Option<String>,
+  pub depth: Option<u32>,
+}
+
+impl From<JsModuleGraphFilter> for ModuleGraphFilter {
+  fn from(filter: JsModuleGraphFilter) -> Self {
+    Self {
+      pattern: filter.pattern,
+      depth: filter.depth.map(|depth| depth as usize),
+    }
+  }
+}
+
+#[napi(object)]
+pub struct JsGlobalCacheGcResult {
+  pub removed: u32,
+  pub freed_bytes: i64,
+}
+
+/// Remove the least recently used packages of the global cache until it's not larger than `max_size`
+#[napi]
+pub fn gc_global_cache(dir: Option<String>, max_size: Option<i64>) -> JsGlobalCacheGcResult {
+  let mut config = GlobalCacheConfig::default();
+
+  if let Some(dir) = dir {
+    config.dir = dir;
+  }
+
+  if let Some(max_size) = max_size {
+    config.max_size = max_size.max(0) as u64;
+  }
+
+  let result =
+    farmfe_core::cache::global_cache::gc_global_cache(&config.resolved_dir(), config.max_size);
+
+  JsGlobalCacheGcResult {
+    removed: result.removed as u32,
+    freed_bytes: result.freed_bytes as i64,
+  }
+}
+
+#[napi(object)]
+pub struct JsCacheArchiveResult {
+  pub files: u32,
+  pub bytes: i64,
+}
+
+impl From<CacheArchiveResult> for JsCacheArchiveResult {
+  fn from(result: CacheArchiveResult) -> Self {
+    Self {
+      files: result.files as u32,
+      bytes: result.bytes as i64,
+    }
+  }
+}
+
+/// Pack the persistent cache under `cache_dir` into a portable archive file
+#[napi]
+pub fn export_cache(cache_dir: String, archive: String) -> napi::Result<JsCacheArchiveResult> {
+  farmfe_core::cache::archive::export_cache(Path::new(&cache_dir), Path::new(&archive))
+    .map(Into::into)
+    .map_err(|e| napi::Error::new(Status::GenericFailure, format!("{e}")))
 }
 
-fn invalidate_module(js_compiler: &JsCompiler, module_id: String) {
+/// Merge the persistent cache packed by `export_cache` into `cache_dir`, the existing cache entries are kept
+#[napi]
+pub fn import_cache(archive: String, cache_dir: String) -> napi::Result<JsCacheArchiveResult> {
+  farmfe_core::cache::archive::import_cache(Path::new(&archive), Path::new(&cache_dir))
+    .map(Into::into)
+    .map_err(|e| napi::Error::new(Status::GenericFailure, format!("{e}")))
+}
+
+fn invalidate_module(
+  js_compiler: &JsCompiler,
+  module_id: String,
+  options: JsInvalidateModuleOptions,
+) {
   let context = js_compiler.compiler.context();
-  let module_id = ModuleId::new(&module_id, "", &context.config.root);
+  let module_id = context.str_to_module_id(&module_id);
+
+  context.invalidate_module(
+    &module_id,
+    InvalidateModuleOptions {
+      recompile_dependents: options.recompile_dependents.unwrap_or(false),
+    },
+  );
+}
+
+#[cfg(feature = "wasm_plugin")]
+fn load_wasm_plugin(plugin_path: &str, options: String) -> Arc<dyn farmfe_core::plugin::Plugin> {
+  Arc::new(
+    plugin_adapters::wasm_plugin_adapter::WasmPluginAdapter::new(plugin_path, options)
+      .unwrap_or_else(|e| panic!("load wasm plugin error: {e:?}")),
+  )
+}
 
-  context.invalidate_module(&module_id);
+#[cfg(not(feature = "wasm_plugin"))]
+fn load_wasm_plugin(plugin_path: &str, _options: String) -> Arc<dyn farmfe_core::plugin::Plugin> {
+  panic!("load wasm plugin error: {plugin_path} is a wasm plugin but farm is built without the `wasm_plugin` feature")
 }
 
 #[cfg(feature = "file_watcher")]
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::Result,
+  serde::{Deserialize, Serialize},
+  serde_json::Value,
+};
+use farmfe_plugin_macro::{MacroCall, MacroEvaluator};
+use napi::{Env, JsFunction};
+
+use super::js_plugin_adapter::thread_safe_js_plugin_hook::ThreadSafeJsPluginHook;
+
+/// Result of the js macro host. `undefined` can not be represented by json, so it's flagged separately.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+struct JsMacroResult {
+  #[serde(default)]
+  value: Value,
+  #[serde(default)]
+  undefined: bool,
+}
+
+/// Execute macros by the `macroHost` function passed from the js side, which runs macro modules in a sandbox.
+pub struct JsMacroEvaluator {
+  tsfn: ThreadSafeJsPluginHook,
+}
+
+impl JsMacroEvaluator {
+  pub fn new(env: &Env, func: JsFunction) -> Self {
+    Self {
+      tsfn: ThreadSafeJsPluginHook::new::<MacroCall, JsMacroResult>(env, func),
+    }
+  }
+}
+
+impl MacroEvaluator for JsMacroEvaluator {
+  fn evaluate(&self, call: &MacroCall, context: &Arc<CompilationContext>) -> Result<Option<Value>> {
+    let result = self
+      .tsfn
+      .call::<MacroCall, JsMacroResult>(call.clone(), context.clone(), None)?;
+
+    Ok(result.and_then(|r| (!r.undefined).then_some(r.value)))
+  }
+}
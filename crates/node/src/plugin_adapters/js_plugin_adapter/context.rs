@@ -13,20 +13,33 @@ use napi::{
   Env, Error, JsFunction, JsObject, JsUnknown, NapiRaw, Status,
 };
 
+use farmfe_compiler::{module_exports, module_statements};
 use farmfe_core::{
-  context::{CompilationContext, EmitFileParams},
+  context::{CompilationContext, EmitFileParams, InvalidateModuleOptions},
   module::ModuleId,
   // swc_ecma_ast::EsVersion,
   plugin::{PluginHookContext, PluginResolveHookParam},
+  serde::{Deserialize, Serialize},
+  serde_json,
 };
+use farmfe_toolkit::source_editor::{utf16_to_byte_offset, SourceEditor};
 
 const RESOLVE: &str = "resolve";
 const ADD_WATCH_FILE: &str = "addWatchFile";
 const EMIT_FILE: &str = "emitFile";
+const EMIT_EXTRACTED_CSS: &str = "emitExtractedCss";
 const GET_WATCH_FILES: &str = "getWatchFiles";
+const INVALIDATE_MODULE: &str = "invalidateModule";
 const WARN: &str = "warn";
 const ERROR: &str = "error";
 const SOURCE_MAP_ENABLED: &str = "sourceMapEnabled";
+const GENERATE_ID: &str = "generateId";
+const PUBLISH_EVENT: &str = "publishEvent";
+const GET_EVENTS: &str = "getEvents";
+const SEND_HMR_EVENT: &str = "sendHmrEvent";
+const GET_MODULE_STATEMENTS: &str = "getModuleStatements";
+const GET_MODULE_EXPORTS: &str = "getModuleExports";
+const EDIT_SOURCE: &str = "editSource";
 
 /// These functions are used to make farm js plugin compatible with Vite plugin
 use super::context_methods::vite_get_importers::{vite_get_importers, VITE_GET_IMPORTERS};
@@ -60,10 +73,19 @@ pub unsafe fn create_js_context(raw_env: napi_env, ctx: Arc<CompilationContext>)
     // (PARSE, parse),
     (ADD_WATCH_FILE, add_watch_file),
     (EMIT_FILE, emit_file),
+    (EMIT_EXTRACTED_CSS, emit_extracted_css),
     (GET_WATCH_FILES, get_watch_files),
+    (INVALIDATE_MODULE, invalidate_module),
     (WARN, warn),
     (ERROR, error),
     (SOURCE_MAP_ENABLED, source_map_enabled),
+    (GENERATE_ID, generate_id),
+    (PUBLISH_EVENT, publish_event),
+    (GET_EVENTS, get_events),
+    (SEND_HMR_EVENT, send_hmr_event),
+    (GET_MODULE_STATEMENTS, get_module_statements),
+    (GET_MODULE_EXPORTS, get_module_exports),
+    (EDIT_SOURCE, edit_source),
     (VITE_GET_IMPORTERS, vite_get_importers),
     (VITE_GET_MODULES_BY_FILE, vite_get_modules_by_file),
     (VITE_GET_MODULE_BY_ID, vite_get_module_by_id),
@@ -204,6 +226,24 @@ unsafe extern "C" fn emit_file(env: napi_env, info: napi_callback_info) -> napi_
   Env::from_raw(env).get_undefined().unwrap().raw()
 }
 
+unsafe extern "C" fn emit_extracted_css(env: napi_env, info: napi_callback_info) -> napi_value {
+  let ArgvAndContext { argv, ctx } = get_argv_and_context_from_cb_info(env, info);
+
+  let module_id: String = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[0]).unwrap())
+    .expect("Argument 0 should be a module id string when calling emitExtractedCss");
+  let css: String = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[1]).unwrap())
+    .expect("Argument 1 should be a css string when calling emitExtractedCss");
+
+  let source = ctx.emit_extracted_css(&ctx.str_to_module_id(&module_id), css);
+
+  Env::from_raw(env)
+    .create_string(&source)
+    .unwrap()
+    .raw()
+}
+
 unsafe extern "C" fn get_watch_files(env: napi_env, info: napi_callback_info) -> napi_value {
   let ArgvAndContext { argv: _, ctx } = get_argv_and_context_from_cb_info(env, info);
 
@@ -225,14 +265,39 @@ unsafe extern "C" fn get_watch_files(env: napi_env, info: napi_callback_info) ->
   Env::from_raw(env).to_js_value(&modules).unwrap().raw()
 }
 
+unsafe extern "C" fn invalidate_module(env: napi_env, info: napi_callback_info) -> napi_value {
+  let ArgvAndContext { argv, ctx } = get_argv_and_context_from_cb_info(env, info);
+
+  let module_id: String = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[0]).unwrap())
+    .expect("Argument 0 should be a module id string when calling invalidateModule");
+  let options: Option<InvalidateModuleOptions> = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[1]).unwrap())
+    .expect("Argument 1 should be { recompileDependents } when calling invalidateModule");
+
+  ctx.invalidate_module(
+    &ctx.str_to_module_id(&module_id),
+    options.unwrap_or_default(),
+  );
+
+  Env::from_raw(env).get_undefined().unwrap().raw()
+}
+
 unsafe extern "C" fn warn(env: napi_env, info: napi_callback_info) -> napi_value {
   let ArgvAndContext { argv, ctx } = get_argv_and_context_from_cb_info(env, info);
 
   let message: String = Env::from_raw(env)
     .from_js_value(JsUnknown::from_napi_value(env, argv[0]).unwrap())
     .expect("Argument 0 should be a string when calling warn");
+  let plugin_name: Option<String> = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[1]).unwrap())
+    .expect("Argument 1 should be a plugin name when calling warn");
 
-  ctx.log_store.lock().add_warning(message);
+  // warnings of named plugins are logged and captured directly, others are reported at the end of the build
+  match plugin_name {
+    Some(plugin_name) => ctx.logger.plugin(&plugin_name).warn(message),
+    None => ctx.log_store.lock().add_warning(message),
+  }
 
   Env::from_raw(env).get_undefined().unwrap().raw()
 }
@@ -243,6 +308,13 @@ unsafe extern "C" fn error(env: napi_env, info: napi_callback_info) -> napi_valu
   let message: String = Env::from_raw(env)
     .from_js_value(JsUnknown::from_napi_value(env, argv[0]).unwrap())
     .expect("Argument 0 should be a string when calling error");
+  let plugin_name: Option<String> = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[1]).unwrap())
+    .expect("Argument 1 should be a plugin name when calling error");
+
+  if let Some(plugin_name) = &plugin_name {
+    ctx.logger.plugin(plugin_name).error(message.as_str());
+  }
 
   ctx.log_store.lock().add_error(message);
 
@@ -260,3 +332,154 @@ unsafe extern "C" fn source_map_enabled(env: napi_env, info: napi_callback_info)
 
   Env::from_raw(env).to_js_value(&enabled).unwrap().raw()
 }
+
+unsafe extern "C" fn generate_id(env: napi_env, info: napi_callback_info) -> napi_value {
+  let ArgvAndContext { argv, ctx } = get_argv_and_context_from_cb_info(env, info);
+
+  let namespace: String = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[0]).unwrap())
+    .expect("Argument 0 should be a namespace string when calling generateId");
+  let key: String = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[1]).unwrap())
+    .expect("Argument 1 should be a key string when calling generateId");
+
+  let id = ctx
+    .id_generator
+    .generate(&namespace, &key, ctx.config.hash.length());
+
+  Env::from_raw(env).create_string(&id).unwrap().raw()
+}
+
+unsafe extern "C" fn publish_event(env: napi_env, info: napi_callback_info) -> napi_value {
+  let ArgvAndContext { argv, ctx } = get_argv_and_context_from_cb_info(env, info);
+
+  let topic: String = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[0]).unwrap())
+    .expect("Argument 0 should be a topic string when calling publishEvent");
+  let payload: serde_json::Value = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[1]).unwrap())
+    .expect("Argument 1 should be a serializable payload when calling publishEvent");
+
+  ctx.event_bus.publish_value(&topic, payload);
+
+  Env::from_raw(env).get_undefined().unwrap().raw()
+}
+
+unsafe extern "C" fn get_events(env: napi_env, info: napi_callback_info) -> napi_value {
+  let ArgvAndContext { argv, ctx } = get_argv_and_context_from_cb_info(env, info);
+
+  let topic: String = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[0]).unwrap())
+    .expect("Argument 0 should be a topic string when calling getEvents");
+
+  Env::from_raw(env)
+    .to_js_value(&ctx.event_bus.event_values(&topic))
+    .unwrap()
+    .raw()
+}
+
+unsafe extern "C" fn send_hmr_event(env: napi_env, info: napi_callback_info) -> napi_value {
+  let ArgvAndContext { argv, ctx } = get_argv_and_context_from_cb_info(env, info);
+
+  let event: String = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[0]).unwrap())
+    .expect("Argument 0 should be an event string when calling sendHmrEvent");
+  let data: serde_json::Value = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[1]).unwrap())
+    .expect("Argument 1 should be a serializable data when calling sendHmrEvent");
+
+  ctx.hmr_channel.send(&event, data);
+
+  Env::from_raw(env).get_undefined().unwrap().raw()
+}
+
+unsafe extern "C" fn get_module_statements(env: napi_env, info: napi_callback_info) -> napi_value {
+  let ArgvAndContext { argv, ctx } = get_argv_and_context_from_cb_info(env, info);
+
+  let module_id: String = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[0]).unwrap())
+    .expect("Argument 0 should be a module id string when calling getModuleStatements");
+  let statements = module_statements(&ctx, &ctx.str_to_module_id(&module_id));
+
+  Env::from_raw(env).to_js_value(&statements).unwrap().raw()
+}
+
+unsafe extern "C" fn get_module_exports(env: napi_env, info: napi_callback_info) -> napi_value {
+  let ArgvAndContext { argv, ctx } = get_argv_and_context_from_cb_info(env, info);
+
+  let module_id: String = Env::from_raw(env)
+    .from_js_value(JsUnknown::from_napi_value(env, argv[0]).unwrap())
+    .expect("Argument 0 should be a module id string when calling getModuleExports");
+  let exports = module_exports(&ctx, &ctx.str_to_module_id(&module_id));
+
+  Env::from_raw(env).to_js_value(&exports).unwrap().raw()
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "farmfe_core::serde")]
+struct SourceEdit {
+  start: usize,
+  end: usize,
+  content: String,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "farmfe_core::serde")]
+struct EditSourceParams {
+  code: String,
+  filename: String,
+  edits: Vec<SourceEdit>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+struct EditSourceResult {
+  content: String,
+  source_map: String,
+}
+
+/// Apply the edits with [SourceEditor], the positions of the edits are utf-16 indexes of the js string
+fn apply_source_edits(params: EditSourceParams) -> Result<EditSourceResult, String> {
+  let mut editor = SourceEditor::new(params.code, params.filename);
+
+  for edit in params.edits {
+    let offset = |index| {
+      utf16_to_byte_offset(editor.original(), index)
+        .ok_or_else(|| format!("invalid index {index} of the edited source"))
+    };
+    let (start, end) = (offset(edit.start)?, offset(edit.end)?);
+
+    let result = if start == end {
+      editor.insert(start, &edit.content).map(|_| ())
+    } else {
+      editor.overwrite(start, end, &edit.content).map(|_| ())
+    };
+    result.map_err(|e| e.to_string())?;
+  }
+
+  let (content, map) = editor.generate();
+  let mut buf = vec![];
+  map.to_writer(&mut buf).map_err(|e| e.to_string())?;
+
+  Ok(EditSourceResult {
+    content,
+    source_map: String::from_utf8(buf).unwrap(),
+  })
+}
+
+unsafe extern "C" fn edit_source(env: napi_env, info: napi_callback_info) -> napi_value {
+  let ArgvAndContext { argv, .. } = get_argv_and_context_from_cb_info(env, info);
+  let env = Env::from_raw(env);
+
+  let params: EditSourceParams = env
+    .from_js_value(JsUnknown::from_napi_value(env.raw(), argv[0]).unwrap())
+    .expect("Argument 0 should be the params object when calling editSource");
+
+  match apply_source_edits(params) {
+    Ok(result) => env.to_js_value(&result).unwrap().raw(),
+    Err(message) => {
+      env.throw_error(&message, None).unwrap();
+      env.get_undefined().unwrap().raw()
+    }
+  }
+}
@@ -23,13 +23,40 @@ pub struct JsPluginFinalizeResourcesHookParams {
 
 impl<'a> From<&mut PluginFinalizeResourcesHookParams<'a>> for JsPluginFinalizeResourcesHookParams {
   fn from(value: &mut PluginFinalizeResourcesHookParams) -> Self {
+    let mut resources_map = value.resources_map.clone();
+
+    // large assets are not loaded into memory, js plugins read their content from `bytes` as well
+    for resource in resources_map.values_mut() {
+      if resource.source_path.is_some() {
+        resource.bytes = resource
+          .read_bytes()
+          .map(|bytes| bytes.into_owned())
+          .unwrap_or_default();
+      }
+    }
+
     Self {
-      resources_map: value.resources_map.clone(),
+      resources_map,
       config: value.config.clone(),
     }
   }
 }
 
+/// Large assets that are not changed by js plugins are still copied from the source path,
+/// the changed ones are emitted from the new content
+pub fn unload_unchanged_streamed_resources(resources_map: &mut HashMap<String, Resource>) {
+  for resource in resources_map.values_mut() {
+    if resource.source_path.is_none() {
+      continue;
+    }
+
+    match resource.read_bytes() {
+      Ok(source) if *source == resource.bytes => resource.bytes = vec![],
+      _ => resource.source_path = None,
+    }
+  }
+}
+
 impl JsPluginFinalizeResourcesHook {
   pub fn new(env: &napi::Env, obj: napi::JsObject) -> Self {
     let func = obj
@@ -4,13 +4,16 @@ pub mod build_start;
 pub mod finalize_resources;
 pub mod finish;
 pub mod load;
+pub mod module_system;
 pub mod plugin_cache_loaded;
 pub mod render_resource_pot;
 pub mod render_start;
 pub mod resolve;
 pub mod transform;
+pub mod transform_asset;
 pub mod transform_html;
 pub mod update_finished;
 pub mod update_modules;
+pub mod watch_change;
 pub mod write_plugin_cache;
 pub mod process_module;
\ No newline at end of file
@@ -0,0 +1,279 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  module::{ModuleId, ModuleMetaData, ModuleSystem, ModuleType},
+  plugin::{
+    PluginDetectModuleSystemHookParam, PluginModuleSystemInteropHookParam,
+    PluginNormalizeModuleSystemHookParam, PluginRenderModuleSystemHookParam,
+  },
+  serde::{Deserialize, Serialize},
+  swc_common::SourceMap,
+  swc_ecma_ast::{EsVersion, Module as SwcModule},
+  swc_ecma_parser::{EsSyntax, Syntax},
+};
+use farmfe_toolkit::script::{
+  codegen_module, parse_module, swc_try_with::resolve_module_mark, ParseScriptModuleResult,
+};
+use napi::{bindgen_prelude::FromNapiValue, NapiRaw};
+
+use super::process_module::{JsPluginProcessModuleHookFilters, PluginProcessModuleHookFilters};
+use crate::{
+  new_js_plugin_hook,
+  plugin_adapters::js_plugin_adapter::thread_safe_js_plugin_hook::ThreadSafeJsPluginHook,
+};
+
+/// The hooks are only called for the modules of these custom module systems
+#[napi(object)]
+pub struct JsPluginModuleSystemHookFilters {
+  pub module_systems: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+struct CompatiblePluginDetectModuleSystemHookParams {
+  module_id: ModuleId,
+  module_type: ModuleType,
+  content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+struct CompatiblePluginModuleSystemHookParams {
+  module_id: ModuleId,
+  module_system: String,
+  /// the code of the module, only for the normalize and render hooks
+  #[serde(skip_serializing_if = "Option::is_none")]
+  content: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+pub struct PluginModuleSystemHookResult {
+  content: String,
+}
+
+pub struct JsPluginDetectModuleSystemHook {
+  tsfn: ThreadSafeJsPluginHook,
+  filters: PluginProcessModuleHookFilters,
+}
+
+impl JsPluginDetectModuleSystemHook {
+  new_js_plugin_hook!(
+    PluginProcessModuleHookFilters,
+    JsPluginProcessModuleHookFilters,
+    CompatiblePluginDetectModuleSystemHookParams,
+    String
+  );
+
+  pub fn call(
+    &self,
+    param: &PluginDetectModuleSystemHookParam,
+    ctx: Arc<CompilationContext>,
+  ) -> Result<Option<String>> {
+    if !self.filters.module_types.contains(param.module_type)
+      && !self
+        .filters
+        .resolved_paths
+        .iter()
+        .any(|m| m.is_match(param.module_id.to_string().as_str()))
+    {
+      return Ok(None);
+    }
+
+    self.tsfn.call(
+      CompatiblePluginDetectModuleSystemHookParams {
+        module_id: param.module_id.clone(),
+        module_type: param.module_type.clone(),
+        content: param.content.to_string(),
+      },
+      ctx,
+      None,
+    )
+  }
+}
+
+pub struct JsPluginNormalizeModuleSystemHook {
+  tsfn: ThreadSafeJsPluginHook,
+  filters: JsPluginModuleSystemHookFilters,
+}
+
+impl JsPluginNormalizeModuleSystemHook {
+  new_js_plugin_hook!(
+    JsPluginModuleSystemHookFilters,
+    JsPluginModuleSystemHookFilters,
+    CompatiblePluginModuleSystemHookParams,
+    PluginModuleSystemHookResult
+  );
+
+  pub fn call(
+    &self,
+    param: &mut PluginNormalizeModuleSystemHookParam,
+    ctx: Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    let module_system = param.module_system.to_string();
+
+    if !self.filters.module_systems.contains(&module_system)
+      || !matches!(param.meta, ModuleMetaData::Script(_))
+    {
+      return Ok(None);
+    }
+
+    let Some(result) = self.tsfn.call::<_, PluginModuleSystemHookResult>(
+      CompatiblePluginModuleSystemHookParams {
+        module_id: param.module_id.clone(),
+        module_system,
+        content: Some(codegen_script(&param.meta.as_script().ast)?),
+      },
+      ctx.clone(),
+      None,
+    )?
+    else {
+      return Ok(None);
+    };
+
+    let (mut ast, comments) = parse_script(param.module_id, param.module_type, &result.content)?;
+    let (unresolved_mark, top_level_mark) =
+      resolve_module_mark(&mut ast, param.module_type.is_typescript(), &ctx);
+    let script = param.meta.as_script_mut();
+    script.ast = ast;
+    script.comments = comments;
+    script.unresolved_mark = unresolved_mark.as_u32();
+    script.top_level_mark = top_level_mark.as_u32();
+
+    Ok(Some(()))
+  }
+}
+
+pub struct JsPluginRenderModuleSystemHook {
+  tsfn: ThreadSafeJsPluginHook,
+  filters: JsPluginModuleSystemHookFilters,
+}
+
+impl JsPluginRenderModuleSystemHook {
+  new_js_plugin_hook!(
+    JsPluginModuleSystemHookFilters,
+    JsPluginModuleSystemHookFilters,
+    CompatiblePluginModuleSystemHookParams,
+    PluginModuleSystemHookResult
+  );
+
+  pub fn call(
+    &self,
+    param: &mut PluginRenderModuleSystemHookParam,
+    ctx: Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    let module_system = param.module_system.to_string();
+
+    if !self.filters.module_systems.contains(&module_system) {
+      return Ok(None);
+    }
+
+    let Some(result) = self.tsfn.call::<_, PluginModuleSystemHookResult>(
+      CompatiblePluginModuleSystemHookParams {
+        module_id: param.module_id.clone(),
+        module_system,
+        content: Some(codegen_script(param.ast)?),
+      },
+      ctx.clone(),
+      None,
+    )?
+    else {
+      return Ok(None);
+    };
+
+    // the ast is rendered without comments, its spans do not match the source of the module anymore
+    let (mut ast, _) = parse_script(param.module_id, &ModuleType::Js, &result.content)?;
+    let (unresolved_mark, top_level_mark) = resolve_module_mark(&mut ast, false, &ctx);
+    *param.ast = ast;
+    param.unresolved_mark = unresolved_mark;
+    param.top_level_mark = top_level_mark;
+
+    Ok(Some(()))
+  }
+}
+
+pub struct JsPluginModuleSystemInteropHook {
+  tsfn: ThreadSafeJsPluginHook,
+  filters: JsPluginModuleSystemHookFilters,
+}
+
+impl JsPluginModuleSystemInteropHook {
+  new_js_plugin_hook!(
+    JsPluginModuleSystemHookFilters,
+    JsPluginModuleSystemHookFilters,
+    CompatiblePluginModuleSystemHookParams,
+    String
+  );
+
+  pub fn call(
+    &self,
+    param: &PluginModuleSystemInteropHookParam,
+    ctx: Arc<CompilationContext>,
+  ) -> Result<Option<ModuleSystem>> {
+    let module_system = param.module_system.to_string();
+
+    if !self.filters.module_systems.contains(&module_system) {
+      return Ok(None);
+    }
+
+    let Some(interop) = self.tsfn.call::<_, String>(
+      CompatiblePluginModuleSystemHookParams {
+        module_id: param.module_id.clone(),
+        module_system,
+        content: None,
+      },
+      ctx,
+      None,
+    )?
+    else {
+      return Ok(None);
+    };
+
+    match interop.as_str() {
+      "esm" => Ok(Some(ModuleSystem::EsModule)),
+      "commonjs" => Ok(Some(ModuleSystem::CommonJs)),
+      "hybrid" => Ok(Some(ModuleSystem::Hybrid)),
+      _ => Err(CompilationError::GenericError(format!(
+        "moduleSystemInterop of {} returns unknown module system {interop}, expected esm, commonjs or hybrid",
+        param.module_id.relative_path()
+      ))),
+    }
+  }
+}
+
+fn codegen_script(ast: &SwcModule) -> Result<String> {
+  let code = codegen_module(
+    ast,
+    EsVersion::latest(),
+    Arc::new(SourceMap::default()),
+    None,
+    false,
+    None,
+  )
+  .map_err(|err| CompilationError::GenericError(err.to_string()))?;
+
+  Ok(String::from_utf8_lossy(&code).to_string())
+}
+
+fn parse_script(
+  module_id: &ModuleId,
+  module_type: &ModuleType,
+  code: &str,
+) -> Result<(SwcModule, farmfe_core::module::CommentsMetaData)> {
+  let ParseScriptModuleResult { ast, comments } = parse_module(
+    &module_id.to_string(),
+    code,
+    match module_type {
+      ModuleType::Jsx | ModuleType::Tsx => Syntax::Es(EsSyntax {
+        jsx: true,
+        ..Default::default()
+      }),
+      _ => Syntax::Es(Default::default()),
+    },
+    Default::default(),
+  )?;
+
+  Ok((ast, comments.into()))
+}
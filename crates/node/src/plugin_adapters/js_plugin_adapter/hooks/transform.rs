@@ -1,33 +1,74 @@
-use farmfe_core::{config::config_regex::ConfigRegex, module::ModuleType};
+use farmfe_core::{
+  cache::cache_store::CacheStoreKey, config::config_regex::ConfigRegex, module::ModuleType,
+};
 use farmfe_core::{
   context::CompilationContext,
   error::Result,
   module::ModuleId,
   plugin::{PluginTransformHookParam, PluginTransformHookResult},
+  serde_json,
 };
-use napi::{bindgen_prelude::FromNapiValue, NapiRaw};
+use napi::{bindgen_prelude::FromNapiValue, JsObject, NapiRaw};
 use std::sync::Arc;
 
-use crate::{
-  new_js_plugin_hook,
-  plugin_adapters::js_plugin_adapter::thread_safe_js_plugin_hook::ThreadSafeJsPluginHook,
-};
+use crate::plugin_adapters::js_plugin_adapter::thread_safe_js_plugin_hook::ThreadSafeJsPluginHook;
 
 pub struct JsPluginTransformHook {
   tsfn: ThreadSafeJsPluginHook,
   filters: PluginTransformHookFilters,
+  /// the transform result only depends on the content, module id and [Self::cache_key],
+  /// so the result can be stored in the persistent cache and the js hook can be skipped when the input is not changed
+  pure: bool,
+  /// extra cache key provided by the js plugin, usually the serialized options of the plugin
+  cache_key: String,
 }
 
 impl JsPluginTransformHook {
-  new_js_plugin_hook!(
-    PluginTransformHookFilters,
-    JsPluginTransformHookFilters,
-    PluginTransformHookParam,
-    PluginTransformHookResult
-  );
+  pub fn new(env: &napi::Env, obj: JsObject) -> Self {
+    let filters: PluginTransformHookFilters = unsafe {
+      JsPluginTransformHookFilters::from_napi_value(
+        env.raw(),
+        obj
+          .get_named_property::<JsObject>("filters")
+          .expect("filters should be checked in js side")
+          .raw(),
+      )
+      .unwrap()
+      .into()
+    };
+
+    let func = obj
+      .get_named_property::<napi::JsFunction>("executor")
+      .expect("executor should be checked in js side");
+
+    let pure = obj
+      .get_named_property::<Option<bool>>("pure")
+      .ok()
+      .flatten()
+      .unwrap_or(false);
+    let cache_key = obj
+      .get_named_property::<Option<String>>("cacheKey")
+      .ok()
+      .flatten()
+      .unwrap_or_default();
+
+    Self {
+      tsfn: ThreadSafeJsPluginHook::new::<PluginTransformHookParam, PluginTransformHookResult>(
+        env, func,
+      ),
+      filters,
+      pure,
+      cache_key,
+    }
+  }
+
+  pub fn cache_key(&self) -> Option<&str> {
+    (!self.cache_key.is_empty()).then_some(self.cache_key.as_str())
+  }
 
   pub fn call(
     &self,
+    plugin_name: &str,
     param: PluginTransformHookParam,
     ctx: Arc<CompilationContext>,
   ) -> Result<Option<PluginTransformHookResult>> {
@@ -41,13 +82,87 @@ impl JsPluginTransformHook {
       .iter()
       .any(|ty| &param.module_type == ty)
     {
-      self
-        .tsfn
-        .call::<PluginTransformHookParam, PluginTransformHookResult>(param, ctx, None)
+      if !self.pure || !ctx.config.persistent_cache.enabled() {
+        return self
+          .tsfn
+          .call::<PluginTransformHookParam, PluginTransformHookResult>(param, ctx, None);
+      }
+
+      let store_key = self.cache_store_key(plugin_name, &param, &ctx);
+
+      cached_transform(store_key, &ctx.clone(), || {
+        self
+          .tsfn
+          .call::<PluginTransformHookParam, PluginTransformHookResult>(param, ctx, None)
+      })
     } else {
       Ok(None)
     }
   }
+
+  /// The cache key of a pure transform is determined by the plugin, the module id, the content and the options of the plugin.
+  /// The source map chain is not considered as it's only used for generating the final source map.
+  fn cache_store_key(
+    &self,
+    plugin_name: &str,
+    param: &PluginTransformHookParam,
+    ctx: &Arc<CompilationContext>,
+  ) -> CacheStoreKey {
+    CacheStoreKey {
+      name: format!("{}-js-plugin-transform-{}", param.module_id, plugin_name),
+      key: ctx.config.hash.hash(
+        format!(
+          "{}_{}_{}_{}_{}",
+          plugin_name,
+          param.module_id,
+          param.module_type.to_string(),
+          self.cache_key,
+          param.content
+        )
+        .as_bytes(),
+        32,
+      ),
+    }
+  }
+}
+
+/// Return the cached result of a pure transform if its input is not changed, otherwise call `transform` and cache the result.
+/// The cache is only an optimization, a result that fails to be cached is reported as a warning
+fn cached_transform(
+  store_key: CacheStoreKey,
+  ctx: &Arc<CompilationContext>,
+  transform: impl FnOnce() -> Result<Option<PluginTransformHookResult>>,
+) -> Result<Option<PluginTransformHookResult>> {
+  let cache_manager = &ctx.cache_manager;
+
+  if cache_manager.custom.has_cache(&store_key.name)
+    && !cache_manager.custom.is_cache_changed(&store_key)
+  {
+    if let Some(cache) = cache_manager.custom.read_cache(&store_key.name) {
+      if let Ok(result) = serde_json::from_slice::<Option<PluginTransformHookResult>>(&cache) {
+        return Ok(result);
+      }
+    }
+  }
+
+  let result = transform()?;
+  let name = store_key.name.clone();
+  let written = serde_json::to_vec(&result)
+    .map_err(|e| e.to_string())
+    .and_then(|bytes| {
+      cache_manager
+        .custom
+        .write_single_cache(store_key, bytes)
+        .map_err(|e| e.to_string())
+    });
+
+  if let Err(e) = written {
+    ctx.log_store.lock().add_warning(format!(
+      "Failed to cache the js plugin transform result of {name}: {e}"
+    ));
+  }
+
+  Ok(result)
 }
 
 #[napi(object)]
@@ -74,3 +189,98 @@ impl From<JsPluginTransformHookFilters> for PluginTransformHookFilters {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::{cell::Cell, sync::Arc};
+
+  use farmfe_core::{
+    cache::cache_store::CacheStoreKey,
+    config::{persistent_cache::PersistentCacheConfig, Config},
+    context::CompilationContext,
+    plugin::PluginTransformHookResult,
+  };
+
+  use super::cached_transform;
+
+  fn context(root: &std::path::Path) -> Arc<CompilationContext> {
+    let config = Config {
+      root: root.to_string_lossy().to_string(),
+      persistent_cache: Box::new(PersistentCacheConfig::Bool(true)),
+      ..Default::default()
+    };
+
+    Arc::new(CompilationContext::new(config, vec![]).unwrap())
+  }
+
+  fn store_key(key: &str) -> CacheStoreKey {
+    CacheStoreKey {
+      name: "index.ts-js-plugin-transform-test".to_string(),
+      key: key.to_string(),
+    }
+  }
+
+  fn transform(
+    calls: &Cell<usize>,
+    content: &str,
+  ) -> farmfe_core::error::Result<Option<PluginTransformHookResult>> {
+    calls.set(calls.get() + 1);
+
+    Ok(Some(PluginTransformHookResult {
+      content: content.to_string(),
+      ..Default::default()
+    }))
+  }
+
+  #[test]
+  fn transform_cache() {
+    let root = std::env::temp_dir().join(format!("farm-js-transform-cache-{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    let ctx = context(&root);
+    let calls = Cell::new(0);
+
+    // miss, the js hook is called and its result is cached
+    let result = cached_transform(store_key("a"), &ctx, || transform(&calls, "a")).unwrap();
+    assert_eq!(result.unwrap().content, "a");
+    assert_eq!(calls.get(), 1);
+
+    // hit, the js hook is skipped
+    let result = cached_transform(store_key("a"), &ctx, || transform(&calls, "b")).unwrap();
+    assert_eq!(result.unwrap().content, "a");
+    assert_eq!(calls.get(), 1);
+
+    // the input is changed, the cache is invalidated
+    let result = cached_transform(store_key("b"), &ctx, || transform(&calls, "b")).unwrap();
+    assert_eq!(result.unwrap().content, "b");
+    assert_eq!(calls.get(), 2);
+
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+
+  #[test]
+  fn transform_cache_write_error() {
+    let root = std::env::temp_dir().join(format!(
+      "farm-js-transform-cache-error-{}",
+      std::process::id()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    let ctx = context(&root);
+    // the cache dir can not be created as a file takes its place
+    std::fs::create_dir_all(root.join("node_modules/.farm")).unwrap();
+    std::fs::write(root.join("node_modules/.farm/cache"), "").unwrap();
+    let calls = Cell::new(0);
+
+    let result = cached_transform(store_key("a"), &ctx, || transform(&calls, "a")).unwrap();
+    assert_eq!(result.unwrap().content, "a");
+
+    let warnings = ctx.log_store.lock().warnings().clone();
+    assert!(
+      warnings
+        .iter()
+        .any(|w| w.contains("Failed to cache the js plugin transform result")),
+      "{warnings:?}"
+    );
+
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+}
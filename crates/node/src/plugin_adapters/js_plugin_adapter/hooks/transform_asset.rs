@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  config::config_regex::ConfigRegex,
+  context::CompilationContext,
+  error::Result,
+  plugin::PluginTransformAssetHookParam,
+  serde::{Deserialize, Serialize},
+};
+use napi::{bindgen_prelude::FromNapiValue, NapiRaw};
+
+use crate::{
+  new_js_plugin_hook,
+  plugin_adapters::js_plugin_adapter::thread_safe_js_plugin_hook::ThreadSafeJsPluginHook,
+};
+
+#[napi(object)]
+pub struct JsPluginTransformAssetHookFilters {
+  pub resolved_paths: Vec<String>,
+}
+
+pub struct PluginTransformAssetHookFilters {
+  pub resolved_paths: Vec<ConfigRegex>,
+}
+
+impl From<JsPluginTransformAssetHookFilters> for PluginTransformAssetHookFilters {
+  fn from(value: JsPluginTransformAssetHookFilters) -> Self {
+    Self {
+      resolved_paths: value
+        .resolved_paths
+        .into_iter()
+        .map(|p| ConfigRegex::new(&p))
+        .collect(),
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+pub struct PluginTransformAssetHookResult {
+  content: Vec<u8>,
+  /// keep the extension if not provided
+  ext: Option<String>,
+}
+
+pub struct JsPluginTransformAssetHook {
+  tsfn: ThreadSafeJsPluginHook,
+  filters: PluginTransformAssetHookFilters,
+}
+
+impl JsPluginTransformAssetHook {
+  new_js_plugin_hook!(
+    PluginTransformAssetHookFilters,
+    JsPluginTransformAssetHookFilters,
+    PluginTransformAssetHookParam,
+    PluginTransformAssetHookResult
+  );
+
+  pub fn call(
+    &self,
+    param: &mut PluginTransformAssetHookParam,
+    ctx: Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if !self
+      .filters
+      .resolved_paths
+      .iter()
+      .any(|m| m.is_match(&param.resolved_path))
+    {
+      return Ok(None);
+    }
+
+    let Some(result) = self
+      .tsfn
+      .call::<PluginTransformAssetHookParam, PluginTransformAssetHookResult>(
+        param.clone(),
+        ctx,
+        None,
+      )?
+    else {
+      return Ok(None);
+    };
+
+    param.content = result.content;
+
+    if let Some(ext) = result.ext {
+      param.ext = ext;
+    }
+
+    Ok(Some(()))
+  }
+}
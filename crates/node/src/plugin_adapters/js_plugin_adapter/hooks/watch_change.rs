@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use farmfe_core::{context::CompilationContext, plugin::PluginWatchChangeHookParams};
+
+use crate::plugin_adapters::js_plugin_adapter::thread_safe_js_plugin_hook::ThreadSafeJsPluginHook;
+
+pub struct JsPluginWatchChangeHook {
+  tsfn: ThreadSafeJsPluginHook,
+}
+
+impl JsPluginWatchChangeHook {
+  pub fn new(env: &napi::Env, obj: napi::JsObject) -> Self {
+    let func = obj
+      .get_named_property::<napi::JsFunction>("executor")
+      .expect("executor should be checked in js side");
+
+    Self {
+      tsfn: ThreadSafeJsPluginHook::new::<PluginWatchChangeHookParams, Vec<String>>(env, func),
+    }
+  }
+
+  pub fn call(
+    &self,
+    param: PluginWatchChangeHookParams,
+    ctx: Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<Option<Vec<String>>> {
+    self
+      .tsfn
+      .call::<PluginWatchChangeHookParams, Vec<String>>(param, ctx, None)
+  }
+}
@@ -19,27 +19,33 @@ use self::hooks::{
   augment_resource_hash::JsPluginAugmentResourceHashHook,
   build_end::JsPluginBuildEndHook,
   build_start::JsPluginBuildStartHook,
-  finalize_resources::JsPluginFinalizeResourcesHook,
+  finalize_resources::{unload_unchanged_streamed_resources, JsPluginFinalizeResourcesHook},
   finish::JsPluginFinishHook,
   load::JsPluginLoadHook,
+  module_system::{
+    JsPluginDetectModuleSystemHook, JsPluginModuleSystemInteropHook,
+    JsPluginNormalizeModuleSystemHook, JsPluginRenderModuleSystemHook,
+  },
   plugin_cache_loaded::JsPluginPluginCacheLoadedHook,
   process_module::JsPluginProcessModuleHook,
   render_resource_pot::JsPluginRenderResourcePotHook,
   render_start::JsPluginRenderStartHook,
   resolve::JsPluginResolveHook,
   transform::JsPluginTransformHook,
+  transform_asset::JsPluginTransformAssetHook,
   transform_html::{
     JsPluginTransformHtmlHook, JsPluginTransformHtmlHookOrder, JsPluginTransformHtmlHookParams,
   },
   update_finished::JsPluginUpdateFinishedHook,
   update_modules::JsPluginUpdateModulesHook,
+  watch_change::JsPluginWatchChangeHook,
   write_plugin_cache::JsPluginWritePluginCacheHook,
 };
 
 pub mod context;
 mod context_methods;
 mod hooks;
-mod thread_safe_js_plugin_hook;
+pub(crate) mod thread_safe_js_plugin_hook;
 
 pub struct JsPluginAdapter {
   name: String,
@@ -50,6 +56,7 @@ pub struct JsPluginAdapter {
   js_transform_hook: Option<JsPluginTransformHook>,
   js_build_end_hook: Option<JsPluginBuildEndHook>,
   js_finish_hook: Option<JsPluginFinishHook>,
+  js_watch_change_hook: Option<JsPluginWatchChangeHook>,
   js_update_modules_hook: Option<JsPluginUpdateModulesHook>,
   js_plugin_cache_loaded: Option<JsPluginPluginCacheLoadedHook>,
   js_write_plugin_cache: Option<JsPluginWritePluginCacheHook>,
@@ -60,6 +67,11 @@ pub struct JsPluginAdapter {
   js_transform_html_hook: Option<JsPluginTransformHtmlHook>,
   js_update_finished_hook: Option<JsPluginUpdateFinishedHook>,
   js_process_module_hook: Option<JsPluginProcessModuleHook>,
+  js_transform_asset_hook: Option<JsPluginTransformAssetHook>,
+  js_detect_module_system_hook: Option<JsPluginDetectModuleSystemHook>,
+  js_normalize_module_system_hook: Option<JsPluginNormalizeModuleSystemHook>,
+  js_render_module_system_hook: Option<JsPluginRenderModuleSystemHook>,
+  js_module_system_interop_hook: Option<JsPluginModuleSystemInteropHook>,
 }
 
 impl JsPluginAdapter {
@@ -77,6 +89,8 @@ impl JsPluginAdapter {
     let build_end_hook_obj =
       get_named_property::<JsObject>(env, &js_plugin_object, "buildEnd").ok();
     let finish_hook_obj = get_named_property::<JsObject>(env, &js_plugin_object, "finish").ok();
+    let watch_change_hook_obj =
+      get_named_property::<JsObject>(env, &js_plugin_object, "watchChange").ok();
     let update_modules_hook_obj =
       get_named_property::<JsObject>(env, &js_plugin_object, "updateModules").ok();
     let plugin_cache_loaded_obj =
@@ -97,6 +111,16 @@ impl JsPluginAdapter {
       get_named_property::<JsObject>(env, &js_plugin_object, "updateFinished").ok();
     let process_module_obj =
       get_named_property::<JsObject>(env, &js_plugin_object, "processModule").ok();
+    let transform_asset_obj =
+      get_named_property::<JsObject>(env, &js_plugin_object, "transformAsset").ok();
+    let detect_module_system_obj =
+      get_named_property::<JsObject>(env, &js_plugin_object, "detectModuleSystem").ok();
+    let normalize_module_system_obj =
+      get_named_property::<JsObject>(env, &js_plugin_object, "normalizeModuleSystem").ok();
+    let render_module_system_obj =
+      get_named_property::<JsObject>(env, &js_plugin_object, "renderModuleSystem").ok();
+    let module_system_interop_obj =
+      get_named_property::<JsObject>(env, &js_plugin_object, "moduleSystemInterop").ok();
 
     Ok(Self {
       name,
@@ -107,6 +131,7 @@ impl JsPluginAdapter {
       js_transform_hook: transform_hook_obj.map(|obj| JsPluginTransformHook::new(env, obj)),
       js_build_end_hook: build_end_hook_obj.map(|obj| JsPluginBuildEndHook::new(env, obj)),
       js_finish_hook: finish_hook_obj.map(|obj| JsPluginFinishHook::new(env, obj)),
+      js_watch_change_hook: watch_change_hook_obj.map(|obj| JsPluginWatchChangeHook::new(env, obj)),
       js_update_modules_hook: update_modules_hook_obj
         .map(|obj| JsPluginUpdateModulesHook::new(env, obj)),
       js_plugin_cache_loaded: plugin_cache_loaded_obj
@@ -126,6 +151,16 @@ impl JsPluginAdapter {
         .map(|obj| JsPluginUpdateFinishedHook::new(env, obj)),
       js_process_module_hook: process_module_obj
         .map(|obj| JsPluginProcessModuleHook::new(env, obj)),
+      js_transform_asset_hook: transform_asset_obj
+        .map(|obj| JsPluginTransformAssetHook::new(env, obj)),
+      js_detect_module_system_hook: detect_module_system_obj
+        .map(|obj| JsPluginDetectModuleSystemHook::new(env, obj)),
+      js_normalize_module_system_hook: normalize_module_system_obj
+        .map(|obj| JsPluginNormalizeModuleSystemHook::new(env, obj)),
+      js_render_module_system_hook: render_module_system_obj
+        .map(|obj| JsPluginRenderModuleSystemHook::new(env, obj)),
+      js_module_system_interop_hook: module_system_interop_obj
+        .map(|obj| JsPluginModuleSystemInteropHook::new(env, obj)),
     })
   }
 
@@ -154,6 +189,14 @@ impl Plugin for JsPluginAdapter {
     self.priority
   }
 
+  fn cache_key(&self) -> Option<String> {
+    self
+      .js_transform_hook
+      .as_ref()
+      .and_then(|hook| hook.cache_key())
+      .map(|cache_key| cache_key.to_string())
+  }
+
   fn resolve(
     &self,
     param: &PluginResolveHookParam,
@@ -238,7 +281,7 @@ impl Plugin for JsPluginAdapter {
         content: result.unwrap_or(cloned_param.content),
         ..cloned_param
       };
-      js_transform_hook.call(cp, context.clone())
+      js_transform_hook.call(&self.name, cp, context.clone())
     } else if let Some(result) = result {
       Ok(Some(PluginTransformHookResult {
         content: result,
@@ -261,6 +304,66 @@ impl Plugin for JsPluginAdapter {
     Ok(None)
   }
 
+  fn detect_module_system(
+    &self,
+    param: &farmfe_core::plugin::PluginDetectModuleSystemHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<String>> {
+    if let Some(ref js_detect_module_system_hook) = self.js_detect_module_system_hook {
+      return js_detect_module_system_hook.call(param, context.clone());
+    }
+
+    Ok(None)
+  }
+
+  fn normalize_module_system(
+    &self,
+    param: &mut farmfe_core::plugin::PluginNormalizeModuleSystemHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if let Some(ref js_normalize_module_system_hook) = self.js_normalize_module_system_hook {
+      return js_normalize_module_system_hook.call(param, context.clone());
+    }
+
+    Ok(None)
+  }
+
+  fn render_module_system(
+    &self,
+    param: &mut farmfe_core::plugin::PluginRenderModuleSystemHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if let Some(ref js_render_module_system_hook) = self.js_render_module_system_hook {
+      return js_render_module_system_hook.call(param, context.clone());
+    }
+
+    Ok(None)
+  }
+
+  fn module_system_interop(
+    &self,
+    param: &farmfe_core::plugin::PluginModuleSystemInteropHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<farmfe_core::module::ModuleSystem>> {
+    if let Some(ref js_module_system_interop_hook) = self.js_module_system_interop_hook {
+      return js_module_system_interop_hook.call(param, context.clone());
+    }
+
+    Ok(None)
+  }
+
+  fn transform_asset(
+    &self,
+    param: &mut farmfe_core::plugin::PluginTransformAssetHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if let Some(ref js_transform_asset_hook) = self.js_transform_asset_hook {
+      return js_transform_asset_hook.call(param, context.clone());
+    }
+
+    Ok(None)
+  }
+
   fn build_end(&self, context: &Arc<CompilationContext>) -> Result<Option<()>> {
     if let Some(js_build_end_hook) = &self.js_build_end_hook {
       js_build_end_hook.call(EmptyPluginHookParam {}, context.clone())?;
@@ -270,6 +373,36 @@ impl Plugin for JsPluginAdapter {
     }
   }
 
+  fn watch_change(
+    &self,
+    params: &mut farmfe_core::plugin::PluginWatchChangeHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if let Some(js_watch_change_hook) = &self.js_watch_change_hook {
+      // the returned paths replace the change, return nothing to keep it
+      if let Some(result) = js_watch_change_hook.call(params.clone(), context.clone())? {
+        // the paths keep their update types, the paths the change is remapped to get the type of the change
+        let change_type = UpdateType::from(&params.event);
+        params.paths = result
+          .into_iter()
+          .map(|path| {
+            let update_type = params
+              .paths
+              .iter()
+              .find(|(p, _)| *p == path)
+              .map_or_else(|| change_type.clone(), |(_, t)| t.clone());
+
+            (path, update_type)
+          })
+          .collect();
+      }
+
+      Ok(Some(()))
+    } else {
+      Ok(None)
+    }
+  }
+
   fn update_modules(
     &self,
     params: &mut farmfe_core::plugin::PluginUpdateModulesHookParams,
@@ -376,7 +509,8 @@ impl Plugin for JsPluginAdapter {
     context: &Arc<CompilationContext>,
   ) -> Result<Option<()>> {
     if let Some(js_finalize_resources_hook) = &self.js_finalize_resources_hook {
-      if let Some(result) = js_finalize_resources_hook.call(params.into(), context.clone())? {
+      if let Some(mut result) = js_finalize_resources_hook.call(params.into(), context.clone())? {
+        unload_unchanged_streamed_resources(&mut result);
         params.resources_map.clear();
         params.resources_map.extend(result);
       };
@@ -1,2 +1,5 @@
+pub mod js_macro_evaluator;
 pub mod js_plugin_adapter;
 pub mod rust_plugin_adapter;
+#[cfg(feature = "wasm_plugin")]
+pub mod wasm_plugin_adapter;
@@ -4,12 +4,14 @@ use farmfe_core::{
   config::Config,
   context::CompilationContext,
   error::{CompilationError, Result},
-  module::{module_graph::ModuleGraph, ModuleId, ModuleMetaData},
+  module::{module_graph::ModuleGraph, ModuleId, ModuleMetaData, ModuleSystem},
   plugin::{
-    Plugin, PluginFinalizeResourcesHookParams, PluginGenerateResourcesHookResult,
-    PluginHookContext, PluginLoadHookParam, PluginLoadHookResult, PluginProcessModuleHookParam,
-    PluginResolveHookParam, PluginResolveHookResult, PluginTransformHookParam,
-    PluginTransformHookResult,
+    Plugin, PluginDetectModuleSystemHookParam, PluginFinalizeResourcesHookParams,
+    PluginGenerateResourcesHookResult, PluginHookContext, PluginLoadHookParam,
+    PluginLoadHookResult, PluginModuleSystemInteropHookParam, PluginNormalizeModuleSystemHookParam,
+    PluginProcessModuleHookParam, PluginRenderModuleSystemHookParam, PluginResolveHookParam,
+    PluginResolveHookResult, PluginSplitModuleHookParam, PluginSplitModuleHookResult,
+    PluginTransformAssetHookParam, PluginTransformHookParam, PluginTransformHookResult,
   },
   resource::resource_pot::ResourcePot,
 };
@@ -25,17 +27,23 @@ pub struct RustPluginAdapter {
   plugin: Arc<dyn Plugin>,
   /// dynamic lib of this plugin, this lib should created and destroyed with the plugin instance as the same time
   _lib: Library,
+  /// options passed to the plugin, used as the cache key if the plugin doesn't provide one
+  options: String,
 }
 
 impl RustPluginAdapter {
   pub fn new(plugin_path: &String, config: &Config, options: String) -> Result<Self> {
     let (plugin, _lib) = unsafe {
-      load_rust_plugin(plugin_path, config, options).map_err(|e| {
+      load_rust_plugin(plugin_path, config, options.clone()).map_err(|e| {
         CompilationError::GenericError(format!("Load rust plugin {plugin_path} failed. {e:?}"))
       })?
     };
 
-    Ok(Self { plugin, _lib })
+    Ok(Self {
+      plugin,
+      _lib,
+      options,
+    })
   }
 }
 
@@ -49,6 +57,23 @@ impl Plugin for RustPluginAdapter {
     self.plugin.priority()
   }
 
+  fn cache_key(&self) -> Option<String> {
+    self
+      .plugin
+      .cache_key()
+      .or_else(|| Some(self.options.clone()))
+  }
+
+  fn resource_pot_renderers(
+    &self,
+  ) -> Vec<Arc<dyn farmfe_core::plugin::resource_pot_renderer::ResourcePotRenderer>> {
+    self.plugin.resource_pot_renderers()
+  }
+
+  fn cache_migrations(&self) -> Vec<Arc<dyn farmfe_core::cache::migration::CacheMigration>> {
+    self.plugin.cache_migrations()
+  }
+
   fn plugin_cache_loaded(
     &self,
     cache: &Vec<u8>,
@@ -79,6 +104,14 @@ impl Plugin for RustPluginAdapter {
     self.plugin.load(param, context, hook_context)
   }
 
+  fn split_module(
+    &self,
+    param: &PluginSplitModuleHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<PluginSplitModuleHookResult>> {
+    self.plugin.split_module(param, context)
+  }
+
   fn transform(
     &self,
     param: &PluginTransformHookParam,
@@ -104,6 +137,38 @@ impl Plugin for RustPluginAdapter {
     self.plugin.process_module(param, context)
   }
 
+  fn detect_module_system(
+    &self,
+    param: &PluginDetectModuleSystemHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<String>> {
+    self.plugin.detect_module_system(param, context)
+  }
+
+  fn normalize_module_system(
+    &self,
+    param: &mut PluginNormalizeModuleSystemHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    self.plugin.normalize_module_system(param, context)
+  }
+
+  fn render_module_system(
+    &self,
+    param: &mut PluginRenderModuleSystemHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    self.plugin.render_module_system(param, context)
+  }
+
+  fn module_system_interop(
+    &self,
+    param: &PluginModuleSystemInteropHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<ModuleSystem>> {
+    self.plugin.module_system_interop(param, context)
+  }
+
   fn analyze_deps(
     &self,
     param: &mut farmfe_core::plugin::PluginAnalyzeDepsHookParam,
@@ -112,6 +177,14 @@ impl Plugin for RustPluginAdapter {
     self.plugin.analyze_deps(param, context)
   }
 
+  fn transform_asset(
+    &self,
+    param: &mut PluginTransformAssetHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    self.plugin.transform_asset(param, context)
+  }
+
   fn build_end(&self, context: &Arc<CompilationContext>) -> Result<Option<()>> {
     self.plugin.build_end(context)
   }
@@ -187,6 +260,16 @@ impl Plugin for RustPluginAdapter {
     self.plugin.augment_resource_hash(render_pot_info, context)
   }
 
+  fn inject_resource_pot_code(
+    &self,
+    resource_pot_info: &farmfe_core::resource::resource_pot::ResourcePotInfo,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<farmfe_core::plugin::PluginInjectResourcePotCodeHookResult>> {
+    self
+      .plugin
+      .inject_resource_pot_code(resource_pot_info, context)
+  }
+
   fn optimize_resource_pot(
     &self,
     resource_pot: &mut farmfe_core::resource::resource_pot::ResourcePot,
@@ -195,6 +278,14 @@ impl Plugin for RustPluginAdapter {
     self.plugin.optimize_resource_pot(resource_pot, context)
   }
 
+  fn minify_resource_pot(
+    &self,
+    resource_pot: &mut farmfe_core::resource::resource_pot::ResourcePot,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    self.plugin.minify_resource_pot(resource_pot, context)
+  }
+
   fn generate_resources(
     &self,
     resource_pot: &mut farmfe_core::resource::resource_pot::ResourcePot,
@@ -230,6 +321,14 @@ impl Plugin for RustPluginAdapter {
     self.plugin.finalize_resources(param, context)
   }
 
+  fn analyze_html(
+    &self,
+    param: &mut farmfe_core::plugin::PluginAnalyzeHtmlHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    self.plugin.analyze_html(param, context)
+  }
+
   fn generate_end(&self, context: &Arc<CompilationContext>) -> Result<Option<()>> {
     self.plugin.generate_end(context)
   }
@@ -250,6 +349,10 @@ impl Plugin for RustPluginAdapter {
     self.plugin.config(config)
   }
 
+  fn config_resolved(&self, config: &Config) -> Result<Option<()>> {
+    self.plugin.config_resolved(config)
+  }
+
   fn finalize_module(
     &self,
     param: &mut farmfe_core::plugin::PluginFinalizeModuleHookParam,
@@ -258,6 +361,14 @@ impl Plugin for RustPluginAdapter {
     self.plugin.finalize_module(param, context)
   }
 
+  fn watch_change(
+    &self,
+    params: &mut farmfe_core::plugin::PluginWatchChangeHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    self.plugin.watch_change(params, context)
+  }
+
   fn update_modules(
     &self,
     params: &mut farmfe_core::plugin::PluginUpdateModulesHookParams,
@@ -278,6 +389,14 @@ impl Plugin for RustPluginAdapter {
     self.plugin.update_finished(context)
   }
 
+  fn hmr_event(
+    &self,
+    event: &farmfe_core::context::hmr_channel::HmrCustomEvent,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    self.plugin.hmr_event(event, context)
+  }
+
   fn handle_persistent_cached_module(
     &self,
     module: &farmfe_core::module::Module,
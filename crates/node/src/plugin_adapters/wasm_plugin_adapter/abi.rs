@@ -0,0 +1,86 @@
+//! The ABI between the host and a wasm plugin. All data is passed through the linear memory of the wasm instance as json bytes.
+//!
+//! A wasm plugin must export:
+//! * `memory`: the linear memory of the plugin
+//! * `farm_plugin_abi_version() -> u32`: the abi version the plugin is built with, must equal to [FARM_WASM_PLUGIN_ABI_VERSION]
+//! * `farm_alloc(len: u32) -> u32`: allocate `len` bytes in the plugin memory and return the pointer
+//! * `farm_dealloc(ptr: u32, len: u32)`: free the memory allocated by `farm_alloc` or returned by a hook
+//! * `farm_plugin_create(ptr: u32, len: u32) -> u64`: create the plugin with the options json and return [WasmPluginMeta]
+//!
+//! And optionally the hooks listed in [WasmPluginHook]. A hook receives the json serialized hook param and returns
+//! a packed `(ptr << 32) | len` pointing to the json serialized [WasmPluginHookResult].
+use farmfe_core::serde::{Deserialize, Serialize};
+
+/// Bump this version when the abi is changed in an incompatible way
+pub const FARM_WASM_PLUGIN_ABI_VERSION: u32 = 1;
+
+pub const EXPORT_MEMORY: &str = "memory";
+pub const EXPORT_ABI_VERSION: &str = "farm_plugin_abi_version";
+pub const EXPORT_ALLOC: &str = "farm_alloc";
+pub const EXPORT_DEALLOC: &str = "farm_dealloc";
+pub const EXPORT_PLUGIN_CREATE: &str = "farm_plugin_create";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WasmPluginHook {
+  BuildStart,
+  Resolve,
+  Load,
+  Transform,
+  BuildEnd,
+  Finish,
+}
+
+impl WasmPluginHook {
+  pub fn all() -> [WasmPluginHook; 6] {
+    [
+      Self::BuildStart,
+      Self::Resolve,
+      Self::Load,
+      Self::Transform,
+      Self::BuildEnd,
+      Self::Finish,
+    ]
+  }
+
+  pub fn export_name(&self) -> &'static str {
+    match self {
+      Self::BuildStart => "farm_plugin_build_start",
+      Self::Resolve => "farm_plugin_resolve",
+      Self::Load => "farm_plugin_load",
+      Self::Transform => "farm_plugin_transform",
+      Self::BuildEnd => "farm_plugin_build_end",
+      Self::Finish => "farm_plugin_finish",
+    }
+  }
+}
+
+/// Returned by `farm_plugin_create`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+pub struct WasmPluginMeta {
+  pub name: String,
+  pub priority: Option<i32>,
+}
+
+/// Result of a hook of the wasm plugin. `value` is `None` means the plugin skips this hook.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+pub struct WasmPluginHookResult<T> {
+  pub value: Option<T>,
+  pub error: Option<String>,
+}
+
+pub fn unpack_ptr_len(packed: u64) -> (u32, u32) {
+  ((packed >> 32) as u32, packed as u32)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::unpack_ptr_len;
+
+  #[test]
+  fn unpack_packed_ptr_len() {
+    assert_eq!(unpack_ptr_len((16 << 32) | 38), (16, 38));
+    assert_eq!(unpack_ptr_len(u32::MAX as u64), (0, u32::MAX));
+  }
+}
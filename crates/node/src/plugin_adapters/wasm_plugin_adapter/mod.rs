@@ -0,0 +1,360 @@
+use std::{collections::HashSet, sync::Arc};
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  parking_lot::Mutex,
+  plugin::{
+    EmptyPluginHookParam, Plugin, PluginHookContext, PluginLoadHookParam, PluginLoadHookResult,
+    PluginResolveHookParam, PluginResolveHookResult, PluginTransformHookParam,
+    PluginTransformHookResult, DEFAULT_PRIORITY,
+  },
+  serde::{de::DeserializeOwned, Serialize},
+  serde_json,
+};
+use wasmtime::{Config, Engine, Instance, Memory, Module, Store, Trap, TypedFunc};
+
+use self::abi::{
+  unpack_ptr_len, WasmPluginHook, WasmPluginHookResult, WasmPluginMeta, EXPORT_ABI_VERSION,
+  EXPORT_ALLOC, EXPORT_DEALLOC, EXPORT_MEMORY, EXPORT_PLUGIN_CREATE, FARM_WASM_PLUGIN_ABI_VERSION,
+};
+
+pub mod abi;
+
+/// The fuel of each call into the plugin, roughly the number of executed wasm instructions.
+/// A plugin that runs out of fuel, e.g. loops forever, fails the hook instead of hanging the build
+const WASM_PLUGIN_CALL_FUEL: u64 = 10_000_000_000;
+
+struct WasmPluginInstance {
+  store: Store<()>,
+  fuel: u64,
+  instance: Instance,
+  memory: Memory,
+  alloc: TypedFunc<u32, u32>,
+  dealloc: TypedFunc<(u32, u32), ()>,
+}
+
+impl WasmPluginInstance {
+  fn write_bytes(&mut self, bytes: &[u8]) -> wasmtime::Result<(u32, u32)> {
+    let len = bytes.len() as u32;
+    let ptr = self.alloc.call(&mut self.store, len)?;
+    self.memory.write(&mut self.store, ptr as usize, bytes)?;
+    Ok((ptr, len))
+  }
+
+  fn read_bytes(&mut self, ptr: u32, len: u32) -> wasmtime::Result<Vec<u8>> {
+    let mut buf = vec![0; len as usize];
+    self.memory.read(&self.store, ptr as usize, &mut buf)?;
+    self.dealloc.call(&mut self.store, (ptr, len))?;
+    Ok(buf)
+  }
+
+  /// write `input` to the plugin memory, call `export` and read the returned bytes back
+  fn call_raw(&mut self, export: &str, input: &[u8]) -> wasmtime::Result<Vec<u8>> {
+    self.store.set_fuel(self.fuel)?;
+    let func = self
+      .instance
+      .get_typed_func::<(u32, u32), u64>(&mut self.store, export)?;
+    let (ptr, len) = self.write_bytes(input)?;
+    let packed = func.call(&mut self.store, (ptr, len))?;
+    let (ret_ptr, ret_len) = unpack_ptr_len(packed);
+
+    self.read_bytes(ret_ptr, ret_len)
+  }
+}
+
+/// Adapter of the plugins compiled to WebAssembly. The plugin is instantiated once and the hooks are called
+/// serially as a wasm instance can not be shared between threads.
+pub struct WasmPluginAdapter {
+  name: String,
+  priority: i32,
+  plugin_path: String,
+  hooks: HashSet<WasmPluginHook>,
+  instance: Mutex<WasmPluginInstance>,
+}
+
+impl WasmPluginAdapter {
+  pub fn new(plugin_path: &str, options: String) -> Result<Self> {
+    Self::with_fuel(plugin_path, options, WASM_PLUGIN_CALL_FUEL)
+  }
+
+  fn with_fuel(plugin_path: &str, options: String, fuel: u64) -> Result<Self> {
+    let load_error = |e: wasmtime::Error| {
+      CompilationError::GenericError(format!(
+        "Load wasm plugin {plugin_path} failed. {}",
+        describe_error(e)
+      ))
+    };
+
+    let engine = Engine::new(Config::new().consume_fuel(true)).map_err(load_error)?;
+    let module = Module::from_file(&engine, plugin_path).map_err(load_error)?;
+    let mut store = Store::new(&engine, ());
+    store.set_fuel(fuel).map_err(load_error)?;
+    // wasm plugins are sandboxed, no host functions are provided
+    let instance = Instance::new(&mut store, &module, &[]).map_err(load_error)?;
+
+    let abi_version = instance
+      .get_typed_func::<(), u32>(&mut store, EXPORT_ABI_VERSION)
+      .and_then(|f| f.call(&mut store, ()))
+      .map_err(load_error)?;
+
+    if abi_version != FARM_WASM_PLUGIN_ABI_VERSION {
+      return Err(CompilationError::GenericError(format!(
+        "Incompatible Wasm Plugin: the abi version of the plugin({abi_version}) is not compatible with current host abi version({FARM_WASM_PLUGIN_ABI_VERSION}), plugin path: ({plugin_path}). Please upgrade or downgrade the plugin or @farmfe/core to make the versions match."
+      )));
+    }
+
+    let memory = instance
+      .get_memory(&mut store, EXPORT_MEMORY)
+      .ok_or_else(|| {
+        CompilationError::GenericError(format!(
+          "Load wasm plugin {plugin_path} failed. The plugin does not export `{EXPORT_MEMORY}`"
+        ))
+      })?;
+    let alloc = instance
+      .get_typed_func::<u32, u32>(&mut store, EXPORT_ALLOC)
+      .map_err(load_error)?;
+    let dealloc = instance
+      .get_typed_func::<(u32, u32), ()>(&mut store, EXPORT_DEALLOC)
+      .map_err(load_error)?;
+
+    let hooks = WasmPluginHook::all()
+      .into_iter()
+      .filter(|hook| instance.get_func(&mut store, hook.export_name()).is_some())
+      .collect();
+
+    let mut wasm_instance = WasmPluginInstance {
+      store,
+      fuel,
+      instance,
+      memory,
+      alloc,
+      dealloc,
+    };
+
+    let meta_bytes = wasm_instance
+      .call_raw(EXPORT_PLUGIN_CREATE, options.as_bytes())
+      .map_err(load_error)?;
+    let meta: WasmPluginMeta = serde_json::from_slice(&meta_bytes).map_err(|e| {
+      CompilationError::GenericError(format!(
+        "Load wasm plugin {plugin_path} failed. Invalid plugin meta: {e:?}"
+      ))
+    })?;
+
+    Ok(Self {
+      name: meta.name,
+      priority: meta.priority.unwrap_or(DEFAULT_PRIORITY),
+      plugin_path: plugin_path.to_string(),
+      hooks,
+      instance: Mutex::new(wasm_instance),
+    })
+  }
+
+  fn call_hook<P: Serialize, T: DeserializeOwned>(
+    &self,
+    hook: WasmPluginHook,
+    param: &P,
+  ) -> Result<Option<T>> {
+    if !self.hooks.contains(&hook) {
+      return Ok(None);
+    }
+
+    let hook_error = |e: String| {
+      CompilationError::GenericError(format!(
+        "Call hook {} of wasm plugin {}({}) failed. {e}",
+        hook.export_name(),
+        self.name,
+        self.plugin_path
+      ))
+    };
+
+    let input = serde_json::to_vec(param).map_err(|e| hook_error(format!("{e:?}")))?;
+    let output = self
+      .instance
+      .lock()
+      .call_raw(hook.export_name(), &input)
+      .map_err(|e| hook_error(describe_error(e)))?;
+    let result: WasmPluginHookResult<T> =
+      serde_json::from_slice(&output).map_err(|e| hook_error(format!("{e:?}")))?;
+
+    if let Some(error) = result.error {
+      return Err(hook_error(error));
+    }
+
+    Ok(result.value)
+  }
+}
+
+fn describe_error(e: wasmtime::Error) -> String {
+  if e.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) {
+    return "The plugin runs out of fuel, it may loop forever.".to_string();
+  }
+
+  format!("{e:?}")
+}
+
+impl Plugin for WasmPluginAdapter {
+  fn name(&self) -> &str {
+    &self.name
+  }
+
+  fn priority(&self) -> i32 {
+    self.priority
+  }
+
+  fn build_start(&self, _context: &Arc<CompilationContext>) -> Result<Option<()>> {
+    self.call_hook(WasmPluginHook::BuildStart, &EmptyPluginHookParam {})
+  }
+
+  fn resolve(
+    &self,
+    param: &PluginResolveHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginResolveHookResult>> {
+    self.call_hook(WasmPluginHook::Resolve, param)
+  }
+
+  fn load(
+    &self,
+    param: &PluginLoadHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginLoadHookResult>> {
+    self.call_hook(WasmPluginHook::Load, param)
+  }
+
+  fn transform(
+    &self,
+    param: &PluginTransformHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<PluginTransformHookResult>> {
+    self.call_hook(WasmPluginHook::Transform, param)
+  }
+
+  fn build_end(&self, _context: &Arc<CompilationContext>) -> Result<Option<()>> {
+    self.call_hook(WasmPluginHook::BuildEnd, &EmptyPluginHookParam {})
+  }
+
+  fn finish(
+    &self,
+    _stat: &farmfe_core::stats::Stats,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    self.call_hook(WasmPluginHook::Finish, &EmptyPluginHookParam {})
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+  use farmfe_core::{
+    config::Config,
+    context::CompilationContext,
+    module::ModuleType,
+    plugin::{
+      Plugin, PluginHookContext, PluginLoadHookParam, PluginResolveHookParam,
+      PluginTransformHookParam, ResolveKind,
+    },
+  };
+
+  use super::WasmPluginAdapter;
+
+  /// compile the wat fixture to a wasm plugin in a temp dir
+  fn fixture_plugin() -> PathBuf {
+    let wat =
+      PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/wasm_plugin/plugin.wat");
+    let dir = std::env::temp_dir().join(format!("farm-wasm-plugin-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let plugin_path = dir.join("plugin.wasm");
+    std::fs::write(&plugin_path, wat::parse_file(wat).unwrap()).unwrap();
+
+    plugin_path
+  }
+
+  fn transform_param() -> PluginTransformHookParam<'static> {
+    PluginTransformHookParam {
+      module_id: "index.ts".to_string(),
+      content: "export default 1;".to_string(),
+      module_type: ModuleType::Ts,
+      resolved_path: "index.ts",
+      query: vec![],
+      meta: HashMap::new(),
+      source_map_chain: vec![],
+    }
+  }
+
+  fn hook_context() -> PluginHookContext {
+    PluginHookContext {
+      caller: None,
+      meta: HashMap::new(),
+    }
+  }
+
+  #[test]
+  fn wasm_plugin_hooks() {
+    let plugin_path = fixture_plugin();
+    let plugin = WasmPluginAdapter::new(plugin_path.to_str().unwrap(), "{}".to_string()).unwrap();
+    let context = Arc::new(CompilationContext::new(Config::default(), vec![]).unwrap());
+
+    assert_eq!(plugin.name(), "wasm-fixture");
+    assert_eq!(plugin.priority(), 101);
+
+    let result = plugin
+      .transform(&transform_param(), &context)
+      .unwrap()
+      .unwrap();
+    assert_eq!(result.content, "export default 'from wasm';");
+
+    // errors returned by the plugin fail the hook
+    let err = plugin
+      .resolve(
+        &PluginResolveHookParam {
+          source: "./index".to_string(),
+          importer: None,
+          kind: ResolveKind::Entry("index".to_string()),
+        },
+        &context,
+        &hook_context(),
+      )
+      .unwrap_err()
+      .to_string();
+    assert!(err.contains("can not resolve in wasm"), "{err}");
+
+    // hooks that are not exported are skipped
+    assert!(plugin.build_start(&context).unwrap().is_none());
+  }
+
+  #[test]
+  fn wasm_plugin_out_of_fuel() {
+    let plugin_path = fixture_plugin();
+    let plugin =
+      WasmPluginAdapter::with_fuel(plugin_path.to_str().unwrap(), "{}".to_string(), 1_000_000)
+        .unwrap();
+    let context = Arc::new(CompilationContext::new(Config::default(), vec![]).unwrap());
+
+    // the load hook loops forever
+    let err = plugin
+      .load(
+        &PluginLoadHookParam {
+          module_id: "index.ts".to_string(),
+          resolved_path: "index.ts",
+          query: vec![],
+          meta: HashMap::new(),
+        },
+        &context,
+        &hook_context(),
+      )
+      .unwrap_err()
+      .to_string();
+    assert!(err.contains("runs out of fuel"), "{err}");
+
+    // the fuel is refilled for the next call
+    let result = plugin
+      .transform(&transform_param(), &context)
+      .unwrap()
+      .unwrap();
+    assert_eq!(result.content, "export default 'from wasm';");
+  }
+}
@@ -0,0 +1,39 @@
+;; A wasm plugin used by the tests of the wasm plugin adapter, it is compiled to wasm by the tests.
+;; Memory is allocated by a bump allocator and never freed.
+(module
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 4096))
+
+  (data (i32.const 16) "{\"name\":\"wasm-fixture\",\"priority\":101}")
+  (data (i32.const 256) "{\"value\":{\"content\":\"export default 'from wasm';\"}}")
+  (data (i32.const 512) "{\"value\":null,\"error\":\"can not resolve in wasm\"}")
+
+  (func (export "farm_plugin_abi_version") (result i32)
+    (i32.const 1))
+
+  (func (export "farm_alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $heap))
+    (global.set $heap (i32.add (global.get $heap) (local.get $len)))
+    (local.get $ptr))
+
+  (func (export "farm_dealloc") (param $ptr i32) (param $len i32))
+
+  ;; returns the meta at 16
+  (func (export "farm_plugin_create") (param $ptr i32) (param $len i32) (result i64)
+    (i64.const 68719476774))
+
+  ;; returns the transformed content at 256
+  (func (export "farm_plugin_transform") (param $ptr i32) (param $len i32) (result i64)
+    (i64.const 1099511627827))
+
+  ;; returns the error at 512
+  (func (export "farm_plugin_resolve") (param $ptr i32) (param $len i32) (result i64)
+    (i64.const 2199023255600))
+
+  ;; never returns, the host must interrupt it
+  (func (export "farm_plugin_load") (param $ptr i32) (param $len i32) (result i64)
+    (loop $forever
+      (br $forever))
+    (i64.const 0))
+)
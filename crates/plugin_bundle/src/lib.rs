@@ -4,12 +4,13 @@
 use std::{collections::HashMap, sync::Arc};
 
 use farmfe_core::{
+  context::CompilationContext,
   enhanced_magic_string::bundle::Bundle,
   parking_lot::Mutex,
-  plugin::Plugin,
+  plugin::{Plugin, PluginFinalizeResourcesHookParams},
   resource::resource_pot::{ResourcePotMetaData, ResourcePotType},
 };
-use resource_pot_to_bundle::{Polyfill, SharedBundle};
+use resource_pot_to_bundle::{replace_bundle_reference_slots, Polyfill, SharedBundle};
 
 pub mod resource_pot_to_bundle;
 
@@ -51,7 +52,7 @@ impl Plugin for FarmPluginBundle {
     let r = resource_pots
       .iter()
       .filter(|item| {
-        context.config.output.target_env.is_library()
+        context.config.output.is_native_esm()
           || matches!(item.resource_pot_type, ResourcePotType::Runtime)
       })
       .map(|item| &**item)
@@ -61,7 +62,9 @@ impl Plugin for FarmPluginBundle {
     let inject_resource_pot_id = resource_pots
       .iter()
       .find(|item| {
-        (context.config.output.target_env.is_library() && item.entry_module.is_some())
+        (context.config.output.is_native_esm()
+          && item.entry_module.is_some()
+          && matches!(item.resource_pot_type, ResourcePotType::Js))
           || matches!(item.resource_pot_type, ResourcePotType::Runtime)
       })
       .map(|i| i.id.clone());
@@ -83,7 +86,7 @@ impl Plugin for FarmPluginBundle {
     let mut defer_minify = vec![];
     for resource_pot in resource_pots.iter() {
       if matches!(resource_pot.resource_pot_type, ResourcePotType::Runtime)
-        || (context.config.output.target_env.is_library()
+        || (context.config.output.is_native_esm()
           && resource_pot.resource_pot_type == ResourcePotType::Js)
       {
         let resource_pot_id = resource_pot.id.clone();
@@ -135,4 +138,19 @@ impl Plugin for FarmPluginBundle {
 
     Ok(None)
   }
+
+  /// the bundles of native esm builds import each other by their resource pot ids until the resource names are known
+  fn finalize_resources(
+    &self,
+    param: &mut PluginFinalizeResourcesHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<Option<()>> {
+    if !context.config.output.is_native_esm() {
+      return Ok(None);
+    }
+
+    replace_bundle_reference_slots(param.resources_map);
+
+    Ok(Some(()))
+  }
 }
@@ -1094,7 +1094,7 @@ impl<'a> BundleAnalyzer<'a> {
       bundle.append("})());", None);
     };
 
-    let injectable_resource_pot = (config.output.target_env.is_library()
+    let injectable_resource_pot = (config.output.is_native_esm()
       && self.resource_pot.entry_module.is_some())
       || matches!(
         self.resource_pot.resource_pot_type,
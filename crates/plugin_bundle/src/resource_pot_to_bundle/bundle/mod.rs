@@ -957,6 +957,10 @@ mod tests {
           hmr_self_accepted: false,
           hmr_accepted_deps: HashSet::default(),
           comments: Default::default(),
+          original_module_system: None,
+          dynamic_import_hints: Default::default(),
+          used_defines: Default::default(),
+          pruned_imports: Default::default(),
           custom: Default::default(),
         }));
       })
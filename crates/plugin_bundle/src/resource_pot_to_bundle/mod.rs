@@ -19,6 +19,7 @@ use farmfe_core::{
 pub use polyfill::{Polyfill, SimplePolyfill};
 
 pub use crate::resource_pot_to_bundle::bundle::bundle_analyzer::BundleAnalyzer;
+pub use targets::bundle_reference_slot::replace_bundle_reference_slots;
 
 use self::{
   bundle::ModuleAnalyzerManager, modules_analyzer::module_analyzer::ModuleAnalyzer,
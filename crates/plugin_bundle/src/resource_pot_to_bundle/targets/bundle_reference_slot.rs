@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use farmfe_core::{
+  regex::{Captures, Regex},
+  resource::{Resource, ResourceOrigin, ResourceType},
+};
+use farmfe_toolkit::lazy_static::lazy_static;
+
+const FARM_BUNDLE_REFERENCE_SLOT_PREFIX: &str = "__FARM_BUNDLE_REFERENCE_SLOT__:";
+
+lazy_static! {
+  static ref BUNDLE_REFERENCE_SLOT_REGEX: Regex =
+    Regex::new(&format!(r#"{FARM_BUNDLE_REFERENCE_SLOT_PREFIX}([^"'`]+)"#)).unwrap();
+}
+
+/// The import source of another bundle, it's replaced by the path of the bundle's resource when the resource names are known
+pub fn with_bundle_reference_slot_name(bundle_id: &str) -> String {
+  format!("{FARM_BUNDLE_REFERENCE_SLOT_PREFIX}{bundle_id}")
+}
+
+/// Replace the bundle reference slots in the js resources by the relative paths of the referenced resources,
+/// e.g. `import("__FARM_BUNDLE_REFERENCE_SLOT__:lazy_js")` in `index.js` becomes `import("./lazy.a1b2.js")`
+pub fn replace_bundle_reference_slots(resources_map: &mut HashMap<String, Resource>) {
+  let bundle_resources = resources_map
+    .values()
+    .filter(|resource| matches!(resource.resource_type, ResourceType::Js))
+    .filter_map(|resource| match &resource.origin {
+      ResourceOrigin::ResourcePot(id) => Some((id.clone(), resource.name.clone())),
+      _ => None,
+    })
+    .collect::<HashMap<_, _>>();
+
+  for resource in resources_map.values_mut() {
+    if !matches!(resource.resource_type, ResourceType::Js) {
+      continue;
+    }
+
+    let Ok(content) = std::str::from_utf8(&resource.bytes) else {
+      continue;
+    };
+
+    if !content.contains(FARM_BUNDLE_REFERENCE_SLOT_PREFIX) {
+      continue;
+    }
+
+    let replaced = BUNDLE_REFERENCE_SLOT_REGEX.replace_all(content, |caps: &Captures| {
+      match bundle_resources.get(&caps[1]) {
+        Some(name) => relative_import_source(&resource.name, name),
+        None => caps[0].to_string(),
+      }
+    });
+
+    resource.bytes = replaced.into_owned().into_bytes();
+  }
+}
+
+/// Import source of the resource `to` in the resource `from`, the resource names are separated by `/`
+fn relative_import_source(from: &str, to: &str) -> String {
+  let from_dir = from.split('/').collect::<Vec<_>>();
+  let from_dir = &from_dir[..from_dir.len() - 1];
+  let to = to.split('/').collect::<Vec<_>>();
+  let common = from_dir
+    .iter()
+    .zip(to.iter())
+    .take_while(|(a, b)| a == b)
+    .count();
+
+  let mut segments = vec![if common == from_dir.len() { "." } else { ".." }];
+  segments.extend(std::iter::repeat("..").take(from_dir.len().saturating_sub(common + 1)));
+  segments.extend(&to[common..]);
+
+  segments.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use farmfe_core::resource::{Resource, ResourceOrigin, ResourceType};
+
+  use super::{replace_bundle_reference_slots, with_bundle_reference_slot_name};
+
+  fn js_resource(name: &str, resource_pot: &str, content: String) -> (String, Resource) {
+    (
+      name.to_string(),
+      Resource {
+        name: name.to_string(),
+        bytes: content.into_bytes(),
+        resource_type: ResourceType::Js,
+        origin: ResourceOrigin::ResourcePot(resource_pot.to_string()),
+        ..Default::default()
+      },
+    )
+  }
+
+  #[test]
+  fn replace_slots() {
+    let mut resources_map = HashMap::from([
+      js_resource(
+        "index.js",
+        "index_js",
+        format!(
+          "import {{ a }} from \"{}\";\nimport(\"{}\");\nimport(\"{}\");\n",
+          with_bundle_reference_slot_name("shared_js"),
+          with_bundle_reference_slot_name("lazy_js"),
+          with_bundle_reference_slot_name("missing_js"),
+        ),
+      ),
+      js_resource(
+        "chunks/lazy.a1b2.js",
+        "lazy_js",
+        format!(
+          "import {{ a }} from \"{}\";\n",
+          with_bundle_reference_slot_name("shared_js")
+        ),
+      ),
+      js_resource(
+        "shared.js",
+        "shared_js",
+        "export const a = 1;\n".to_string(),
+      ),
+    ]);
+
+    replace_bundle_reference_slots(&mut resources_map);
+
+    let content = |name: &str| String::from_utf8(resources_map[name].bytes.clone()).unwrap();
+    assert_eq!(
+      content("index.js"),
+      format!(
+        "import {{ a }} from \"./shared.js\";\nimport(\"./chunks/lazy.a1b2.js\");\nimport(\"{}\");\n",
+        with_bundle_reference_slot_name("missing_js")
+      )
+    );
+    assert_eq!(
+      content("chunks/lazy.a1b2.js"),
+      "import { a } from \"../shared.js\";\n"
+    );
+  }
+}
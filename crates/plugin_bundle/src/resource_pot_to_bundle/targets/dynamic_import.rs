@@ -3,16 +3,14 @@ use farmfe_core::{
   swc_common::DUMMY_SP,
   swc_ecma_ast::{CallExpr, Callee, Expr, ExprOrSpread, Ident, Lit, MemberExpr, MemberProp},
 };
-use farmfe_toolkit::swc_ecma_visit::{VisitMut, VisitMutWith};
+use farmfe_toolkit::{
+  script::import_attributes::{is_json_import_source, strip_json_import_query},
+  swc_ecma_visit::{VisitMut, VisitMutWith},
+};
 
 use crate::resource_pot_to_bundle::{bundle::ModuleAnalyzerManager, uniq_name::BundleVariable};
 
-const FARM_BUNDLE_REFERENCE_SLOT_PREFIX: &str = "__FARM_BUNDLE_REFERENCE_SLOT__:";
-
-// TODO: replace bundle reference slot
-fn with_bundle_reference_slot_name(bundle_id: &String) -> String {
-  format!("{FARM_BUNDLE_REFERENCE_SLOT_PREFIX}{bundle_id}")
-}
+use super::bundle_reference_slot::with_bundle_reference_slot_name;
 
 ///
 /// ```ts
@@ -55,7 +53,7 @@ impl<'a> ReplaceDynamicVisit<'a> {
       return None;
     }
 
-    let box Expr::Lit(Lit::Str(str)) = &arg.expr else {
+    let box Expr::Lit(Lit::Str(str)) = &mut arg.expr else {
       return None;
     };
 
@@ -63,7 +61,12 @@ impl<'a> ReplaceDynamicVisit<'a> {
       .module_manager
       .module_analyzer_by_source(self.module_id, str.value.as_ref())
     else {
-      // external
+      // external, `import("./data.json", { with: { type: "json" } })` is kept without the query marking the attribute
+      if is_json_import_source(&str.value) {
+        str.value = strip_json_import_query(&str.value).into();
+        str.raw = None;
+      }
+
       return None;
     };
 
@@ -11,11 +11,16 @@ use farmfe_core::{
   },
 };
 
+use farmfe_toolkit::script::import_attributes::{
+  create_json_import_attributes, is_json_import_source, strip_json_import_query,
+};
+
 use crate::resource_pot_to_bundle::{
   bundle::{
     bundle_external::{ExternalReferenceExport, ExternalReferenceImport, ReferenceKind},
     ModuleAnalyzerManager,
   },
+  targets::bundle_reference_slot::with_bundle_reference_slot_name,
   uniq_name::BundleVariable,
 };
 
@@ -143,17 +148,26 @@ impl EsmGenerate {
         continue;
       }
 
+      // preserve `with { type: "json" }` of external json modules
+      let is_external_json =
+        matches!(source, ReferenceKind::Module(_)) && is_json_import_source(&source.to_string());
+      let import_source = match source {
+        ReferenceKind::Bundle(bundle_id) => with_bundle_reference_slot_name(bundle_id),
+        _ if is_external_json => strip_json_import_query(&source.to_string()),
+        _ => source.to_string(),
+      };
+
       let create_import = |specifiers: Vec<farmfe_core::swc_ecma_ast::ImportSpecifier>| {
         ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
           span: DUMMY_SP,
           specifiers,
           src: Box::new(Str {
             span: DUMMY_SP,
-            value: source.to_string().as_str().into(),
+            value: import_source.as_str().into(),
             raw: None,
           }),
           type_only: false,
-          with: None,
+          with: is_external_json.then(create_json_import_attributes),
           phase: farmfe_core::swc_ecma_ast::ImportPhase::Evaluation,
         }))
       };
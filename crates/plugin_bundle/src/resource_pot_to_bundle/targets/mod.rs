@@ -1,3 +1,4 @@
+pub mod bundle_reference_slot;
 pub mod cjs;
 pub mod dynamic_import;
 pub mod esm;
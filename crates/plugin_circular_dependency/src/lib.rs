@@ -0,0 +1,206 @@
+use std::{
+  collections::{HashMap, HashSet, VecDeque},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  config::{
+    circular_dependency::{CircularDependencyConfig, CircularDependencySeverity},
+    config_regex::ConfigRegex,
+    Config,
+  },
+  context::{
+    diagnostics::{Diagnostic, DiagnosticSeverity},
+    CompilationContext,
+  },
+  error::{CompilationError, Result},
+  module::{module_graph::ModuleGraph, ModuleId},
+  petgraph::{algo::tarjan_scc, graph::DiGraph},
+  plugin::{Plugin, PluginModuleGraphUpdatedHookParams},
+};
+
+/// [Diagnostic::code] of the reported cycles
+pub const CIRCULAR_DEPENDENCY_CODE: &str = "circular-dependency";
+const PLUGIN_NAME: &str = "FarmPluginCircularDependency";
+
+/// Report the import cycles of the module graph after it is built and after every update.
+/// Every cycle is added to the diagnostics of the context, the build fails if the severity is `error`.
+pub struct FarmPluginCircularDependency {
+  config: CircularDependencyConfig,
+}
+
+impl FarmPluginCircularDependency {
+  pub fn new(config: &Config) -> Self {
+    Self {
+      config: config
+        .circular_dependency
+        .as_ref()
+        .map(|c| *c.clone())
+        .unwrap_or_default(),
+    }
+  }
+
+  fn is_reported(&self, cycle: &[ModuleId]) -> bool {
+    let matches = |id: &ModuleId, patterns: &[ConfigRegex]| {
+      let id = id.to_string();
+      patterns.iter().any(|p| p.is_match(&id))
+    };
+
+    (self.config.include.is_empty() || cycle.iter().any(|id| matches(id, &self.config.include)))
+      && !cycle.iter().any(|id| matches(id, &self.config.exclude))
+  }
+
+  fn check(&self, context: &Arc<CompilationContext>) -> Result<()> {
+    let severity = match self.config.severity {
+      CircularDependencySeverity::Warn => DiagnosticSeverity::Warning,
+      CircularDependencySeverity::Error => DiagnosticSeverity::Error,
+      CircularDependencySeverity::Ignore => return Ok(()),
+    };
+
+    let cycles = find_cycles(&context.module_graph.read(), &self.config)
+      .into_iter()
+      .filter(|cycle| self.is_reported(cycle))
+      .collect::<Vec<_>>();
+
+    let mut diagnostics = context.diagnostics.lock();
+    diagnostics.clear_code(CIRCULAR_DEPENDENCY_CODE);
+
+    let mut messages = vec![];
+
+    for cycle in cycles {
+      let message = format!("Circular dependency: {}", render_cycle(&cycle));
+      messages.push(message.clone());
+      diagnostics.add(Diagnostic {
+        code: CIRCULAR_DEPENDENCY_CODE.to_string(),
+        severity,
+        message,
+        modules: cycle,
+        plugin: Some(PLUGIN_NAME.to_string()),
+      });
+    }
+
+    if severity == DiagnosticSeverity::Error && !messages.is_empty() {
+      return Err(CompilationError::GenericError(messages.join("\n")));
+    }
+
+    Ok(())
+  }
+}
+
+/// `a.ts -> b.ts -> a.ts`
+pub fn render_cycle(cycle: &[ModuleId]) -> String {
+  cycle
+    .iter()
+    .chain(cycle.first())
+    .map(|id| id.to_string())
+    .collect::<Vec<_>>()
+    .join(" -> ")
+}
+
+/// Find a shortest cycle of every strongly connected component of the module graph, starting from the smallest module id of the component.
+/// The first module of the cycle imports the second one, and the last module imports the first one
+pub fn find_cycles(
+  module_graph: &ModuleGraph,
+  config: &CircularDependencyConfig,
+) -> Vec<Vec<ModuleId>> {
+  let is_ignored =
+    |id: &ModuleId| config.ignore_node_modules && id.relative_path().contains("node_modules/");
+
+  let mut graph = DiGraph::<ModuleId, ()>::new();
+  let mut indexes = HashMap::new();
+  let mut modules = module_graph
+    .modules()
+    .into_iter()
+    .filter(|m| !m.external && !is_ignored(&m.id))
+    .map(|m| m.id.clone())
+    .collect::<Vec<_>>();
+  modules.sort();
+
+  for id in &modules {
+    indexes.insert(id.clone(), graph.add_node(id.clone()));
+  }
+
+  for id in &modules {
+    let mut deps = module_graph.dependencies(id);
+    deps.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (dep, edge) in deps {
+      if config.ignore_dynamic_imports && edge.is_dynamic() {
+        continue;
+      }
+
+      if let Some(dep) = indexes.get(&dep) {
+        graph.add_edge(indexes[id], *dep, ());
+      }
+    }
+  }
+
+  let mut cycles = tarjan_scc(&graph)
+    .into_iter()
+    .filter(|scc| scc.len() > 1 || graph.contains_edge(scc[0], scc[0]))
+    .map(|scc| {
+      let members = scc.iter().copied().collect::<HashSet<_>>();
+      let start = *scc.iter().min_by_key(|i| &graph[**i]).unwrap();
+      let mut importers = HashMap::new();
+      let mut queue = VecDeque::from([start]);
+
+      // bfs inside the component until the start module is imported again
+      'bfs: while let Some(node) = queue.pop_front() {
+        let mut deps = graph.neighbors(node).collect::<Vec<_>>();
+        deps.sort_by_key(|i| &graph[*i]);
+
+        for dep in deps {
+          if !members.contains(&dep) || importers.contains_key(&dep) {
+            continue;
+          }
+
+          importers.insert(dep, node);
+
+          if dep == start {
+            break 'bfs;
+          }
+
+          queue.push_back(dep);
+        }
+      }
+
+      let mut cycle = vec![];
+      let mut node = importers[&start];
+
+      while node != start {
+        cycle.push(graph[node].clone());
+        node = importers[&node];
+      }
+
+      cycle.push(graph[start].clone());
+      cycle.reverse();
+      cycle
+    })
+    .collect::<Vec<_>>();
+  cycles.sort();
+
+  cycles
+}
+
+impl Plugin for FarmPluginCircularDependency {
+  fn name(&self) -> &str {
+    PLUGIN_NAME
+  }
+
+  fn build_end(&self, context: &Arc<CompilationContext>) -> Result<Option<()>> {
+    self.check(context)?;
+
+    Ok(Some(()))
+  }
+
+  /// a module updated by hmr may add or remove cycles
+  fn module_graph_updated(
+    &self,
+    _param: &PluginModuleGraphUpdatedHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    self.check(context)?;
+
+    Ok(Some(()))
+  }
+}
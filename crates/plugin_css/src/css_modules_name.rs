@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+
+use farmfe_core::config::{
+  hash::{HashConfig, MAX_HASH_LENGTH},
+  CssModulesConfig, CssModulesHashStrategy,
+};
+use farmfe_toolkit::{
+  lazy_static::lazy_static,
+  regex::{Captures, Regex},
+  resolve::load_package_json,
+  swc_atoms::JsWord,
+  swc_css_modules::TransformConfig,
+};
+
+lazy_static! {
+  static ref PLACEHOLDER: Regex = Regex::new(r"\[(\w+)(?::(\d+))?\]").unwrap();
+}
+
+const DEFAULT_HASH_LENGTH: usize = 8;
+
+/// Generate class names of css modules from `css.modules.indentName`, see [CssModulesConfig::indent_name]
+pub struct CssModulesNameGenerator {
+  pub indent_name: String,
+  pub hash_config: HashConfig,
+  /// the hashed content of `[hash]`, see [CssModulesHashStrategy]
+  pub hash_input: String,
+  pub file: String,
+  pub package: String,
+}
+
+impl CssModulesNameGenerator {
+  pub fn new(
+    config: &CssModulesConfig,
+    hash_config: &HashConfig,
+    module_id: &str,
+    resolved_path: &str,
+    content: &str,
+  ) -> Self {
+    let hash_input = match config.hash_strategy {
+      // module ids are relative to root, the separator is normalized so windows and unix generate the same names
+      CssModulesHashStrategy::Path => module_id.replace('\\', "/"),
+      CssModulesHashStrategy::Content => content.to_string(),
+    };
+    let file = Path::new(resolved_path)
+      .file_name()
+      .map(|name| name.to_string_lossy())
+      .and_then(|name| name.split('.').next().map(|s| s.to_string()))
+      .unwrap_or_default();
+    // loading package.json is skipped when the package is not used
+    let package = if config.indent_name.contains("[package]") {
+      load_package_json(PathBuf::from(resolved_path), Default::default())
+        .ok()
+        .and_then(|info| info.name)
+        .unwrap_or_default()
+    } else {
+      String::new()
+    };
+
+    Self {
+      indent_name: config.indent_name.clone(),
+      hash_config: hash_config.clone(),
+      hash_input,
+      file: sanitize(&file),
+      package: sanitize(&package),
+    }
+  }
+
+  pub fn generate(&self, local: &str) -> String {
+    PLACEHOLDER
+      .replace_all(&self.indent_name, |caps: &Captures| match &caps[1] {
+        "name" | "local" => local.to_string(),
+        "file" => self.file.clone(),
+        "package" => self.package.clone(),
+        "hash" => {
+          // every hash algorithm generates at least MAX_HASH_LENGTH hex digits
+          let len = caps
+            .get(2)
+            .and_then(|len| len.as_str().parse::<usize>().ok())
+            .unwrap_or(DEFAULT_HASH_LENGTH)
+            .clamp(1, MAX_HASH_LENGTH);
+          self.hash_config.hash(self.hash_input.as_bytes(), len)
+        }
+        _ => caps[0].to_string(),
+      })
+      .to_string()
+  }
+}
+
+impl TransformConfig for CssModulesNameGenerator {
+  fn new_name_for(&self, local: &JsWord) -> JsWord {
+    self.generate(local).into()
+  }
+}
+
+/// Characters like `@` and `/` of scoped package names are not valid in class names
+fn sanitize(name: &str) -> String {
+  name
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect::<String>()
+    .trim_start_matches('_')
+    .to_string()
+}
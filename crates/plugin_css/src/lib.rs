@@ -3,6 +3,7 @@
 use std::collections::HashMap;
 use std::{path::PathBuf, sync::Arc};
 
+use css_modules_name::CssModulesNameGenerator;
 use dep_analyzer::DepAnalyzer;
 use farmfe_core::config::css::NameConversion;
 use farmfe_core::config::custom::get_config_css_modules_local_conversion;
@@ -10,7 +11,8 @@ use farmfe_core::config::minify::MinifyOptions;
 use farmfe_core::module::CommentsMetaData;
 use farmfe_core::{
   config::{Config, CssPrefixerConfig, TargetEnv},
-  context::CompilationContext,
+  context::{CompilationContext, EXTRACTED_CSS_SUFFIX},
+  dashmap::DashMap,
   deserialize,
   enhanced_magic_string::{
     bundle::{Bundle, BundleOptions},
@@ -42,21 +44,20 @@ use farmfe_toolkit::minify::minify_css_module;
 use farmfe_toolkit::resolve::DYNAMIC_EXTENSION_PRIORITY;
 use farmfe_toolkit::script::swc_try_with::try_with;
 use farmfe_toolkit::{
-  common::Source,
-  css::{codegen_css_stylesheet, parse_css_stylesheet},
+  common::{Source, SourcemapSources},
+  css::{codegen_css_license_comments, codegen_css_stylesheet, parse_css_stylesheet},
   fs::read_file_utf8,
   hash::sha256,
   regex::Regex,
   script::module_type_from_id,
   sourcemap::SourceMap,
-  swc_atoms::JsWord,
-  swc_css_modules::{compile, CssClassName, TransformConfig},
+  swc_css_modules::{compile, CssClassName},
   swc_css_prefixer,
   swc_css_visit::{VisitMut, VisitMutWith, VisitWith},
 };
-use farmfe_utils::{parse_query, relative, stringify_query};
+use farmfe_utils::{parse_query, stringify_query};
 use rkyv::Deserialize;
-use source_replacer::SourceReplacer;
+use source_replacer::{asset_targets, url_prefix, SourceReplacer};
 
 pub const FARM_CSS_MODULES: &str = "farm_css_modules";
 
@@ -65,6 +66,7 @@ lazy_static! {
     Regex::new(&format!("(?:\\?|&){FARM_CSS_MODULES}")).unwrap();
 }
 
+pub mod css_modules_name;
 mod dep_analyzer;
 mod source_replacer;
 pub mod transform_css_to_script;
@@ -94,7 +96,16 @@ impl Plugin for FarmPluginCssResolve {
       }
     }
 
-    if is_farm_css_modules(&param.source) {
+    if is_extracted_css(&param.source) {
+      // css emitted by `context.emit_extracted_css`, e.g. `/root/src/button.tsx.farm-extracted.css?hash=xxx`
+      let (path, _) = param.source.split_once('?').unwrap_or((&param.source, ""));
+
+      return Ok(Some(farmfe_core::plugin::PluginResolveHookResult {
+        resolved_path: path.to_string(),
+        query: parse_query(&param.source),
+        ..Default::default()
+      }));
+    } else if is_farm_css_modules(&param.source) {
       let split = param.source.split('?').collect::<Vec<&str>>();
       let strip_query_path = split[0].to_string();
       let query = parse_query(&param.source);
@@ -166,6 +177,7 @@ impl Plugin for FarmPluginCssResolve {
 struct CssModulesCache {
   content_map: HashMap<String, String>,
   sourcemap_map: HashMap<String, String>,
+  extracted_css_map: HashMap<String, String>,
 }
 
 pub struct FarmPluginCss {
@@ -197,9 +209,13 @@ impl Plugin for FarmPluginCss {
   fn plugin_cache_loaded(
     &self,
     cache: &Vec<u8>,
-    _context: &Arc<CompilationContext>,
+    context: &Arc<CompilationContext>,
   ) -> farmfe_core::error::Result<Option<()>> {
     let cache = deserialize!(cache, CssModulesCache);
+
+    for (k, v) in cache.extracted_css_map {
+      context.extracted_css.insert(k, v);
+    }
     let mut content_map = self.content_map.lock();
 
     for (k, v) in cache.content_map {
@@ -218,9 +234,29 @@ impl Plugin for FarmPluginCss {
   fn load(
     &self,
     param: &PluginLoadHookParam,
-    _context: &Arc<CompilationContext>,
+    context: &Arc<CompilationContext>,
     _hook_context: &PluginHookContext,
   ) -> farmfe_core::error::Result<Option<PluginLoadHookResult>> {
+    if is_extracted_css(param.resolved_path) {
+      let content = context
+        .extracted_css
+        .get(&param.module_id)
+        .map(|css| css.value().clone())
+        .ok_or_else(|| CompilationError::LoadError {
+          resolved_path: param.module_id.clone(),
+          source: Some(Box::new(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "extracted css is not emitted by `emit_extracted_css`",
+          ))),
+        })?;
+
+      return Ok(Some(PluginLoadHookResult {
+        content,
+        module_type: ModuleType::Css,
+        source_map: None,
+      }));
+    }
+
     if is_farm_css_modules(&param.module_id) {
       return Ok(Some(PluginLoadHookResult {
         content: self
@@ -291,17 +327,13 @@ impl Plugin for FarmPluginCss {
         // next, get ident from ast and export through JS
         let stylesheet = compile(
           &mut css_stylesheet,
-          CssModuleRename {
-            indent_name: context
-              .config
-              .css
-              .modules
-              .as_ref()
-              .unwrap()
-              .indent_name
-              .clone(),
-            hash: sha256(css_modules_module_id.to_string().as_bytes(), 8),
-          },
+          CssModulesNameGenerator::new(
+            context.config.css.modules.as_ref().unwrap(),
+            &context.config.hash,
+            &css_modules_module_id.to_string(),
+            param.resolved_path,
+            &param.content,
+          ),
         );
 
         // we can not use css_modules_resolved_path here because of the compatibility of windows. eg: \\ vs \\\\
@@ -374,11 +406,11 @@ impl Plugin for FarmPluginCss {
             .iter()
             .map(|s| SourceMap::from_slice(s.as_bytes()).expect("failed to parse sourcemap"))
             .collect::<Vec<_>>();
-          let root = context.config.root.clone();
+          let sources = SourcemapSources::new(&context.config);
           let collapsed_sourcemap = collapse_sourcemap_chain(
             source_map_chain,
             CollapseSourcemapOptions {
-              remap_source: Some(Box::new(move |src| format!("/{}", relative(&root, src)))),
+              remap_source: Some(Box::new(move |src| sources.remap(src))),
               ..Default::default()
             },
           );
@@ -518,6 +550,10 @@ impl Plugin for FarmPluginCss {
     module_ids.extend(param.added_modules_ids.clone());
     transform_css_to_script::transform_css_to_script_modules(module_ids, context)?;
 
+    if !param.removed_modules_ids.is_empty() {
+      context.prune_extracted_css();
+    }
+
     Ok(Some(()))
   }
 
@@ -541,7 +577,8 @@ impl Plugin for FarmPluginCss {
       let minify_enabled = matches!(
         minify_options.mode,
         farmfe_core::config::minify::MinifyMode::Module
-      ) && context.config.minify.enabled();
+      ) && minify_options.css
+        && context.config.minify.enabled();
 
       let is_minify_enabled = |module_id: &ModuleId| {
         minify_enabled && filter.execute(&module_id.resolved_path(&context.config.root))
@@ -557,8 +594,6 @@ impl Plugin for FarmPluginCss {
       }
 
       // modules.sort_by_key(|module| module.execution_order);
-      let resources_map = context.resources_map.lock();
-
       let rendered_modules = Mutex::new(Vec::with_capacity(modules.len()));
       modules.into_par_iter().try_for_each(|module| {
         let (cm, _) = create_swc_source_map(Source {
@@ -573,8 +608,9 @@ impl Plugin for FarmPluginCss {
             &mut css_stylesheet,
             &module.id,
             &module_graph,
-            &resources_map,
-            context.config.output.public_path.clone(),
+            &context.resources_map,
+            url_prefix(&context.config.output, false),
+            asset_targets(&context.config),
             context.config.resolve.alias.clone(),
           );
 
@@ -617,10 +653,10 @@ impl Plugin for FarmPluginCss {
       });
 
       for rendered in &rendered_modules {
+        let module = module_graph.module(&rendered.id).unwrap();
         let mut source_map_chain = vec![];
 
         if source_map_enabled {
-          let module = module_graph.module(&rendered.id).unwrap();
           source_map_chain = module.source_map_chain.clone();
 
           if let Some(map) = &rendered.rendered_map {
@@ -628,7 +664,7 @@ impl Plugin for FarmPluginCss {
           }
         }
 
-        let magic_module = MagicString::new(
+        let mut magic_module = MagicString::new(
           rendered.rendered_content.as_str(),
           Some(MagicStringOptions {
             source_map_chain,
@@ -636,6 +672,15 @@ impl Plugin for FarmPluginCss {
             ..Default::default()
           }),
         );
+        let license_comments = codegen_css_license_comments(
+          &module.meta.as_css().comments.clone().into(),
+          &context.config.comments,
+        );
+
+        if !license_comments.is_empty() {
+          magic_module.prepend(&license_comments);
+        }
+
         bundle.add_source(magic_module, None).map_err(|e| {
           CompilationError::GenericError(format!("failed to add source to bundle: {e:?}"))
         })?;
@@ -643,12 +688,12 @@ impl Plugin for FarmPluginCss {
 
       let rendered_content = Arc::new(bundle.to_string());
       let rendered_map = if source_map_enabled {
-        let root = context.config.root.clone();
+        let sources = SourcemapSources::new(&context.config);
         Some(
           bundle
             .generate_map(SourceMapOptions {
               include_content: Some(true),
-              remap_source: Some(Box::new(move |src| format!("/{}", relative(&root, src)))),
+              remap_source: Some(Box::new(move |src| sources.remap(src))),
               ..Default::default()
             })
             .map_err(|e| {
@@ -691,6 +736,8 @@ impl Plugin for FarmPluginCss {
         emitted: false,
         resource_type: ResourceType::Css,
         origin: ResourceOrigin::ResourcePot(resource_pot.id.clone()),
+        source_path: None,
+        scope: Default::default(),
         info: None,
       };
       let mut source_map = None;
@@ -718,6 +765,8 @@ impl Plugin for FarmPluginCss {
             emitted: false,
             resource_type,
             origin: ResourceOrigin::ResourcePot(resource_pot.id.clone()),
+            source_path: None,
+            scope: Default::default(),
             info: None,
           });
         }
@@ -734,12 +783,23 @@ impl Plugin for FarmPluginCss {
 
   fn write_plugin_cache(
     &self,
-    _context: &Arc<CompilationContext>,
+    context: &Arc<CompilationContext>,
   ) -> farmfe_core::error::Result<Option<Vec<u8>>> {
-    if !self.content_map.lock().is_empty() || !self.sourcemap_map.lock().is_empty() {
+    // the css of the removed importers is not persisted
+    context.prune_extracted_css();
+
+    if !self.content_map.lock().is_empty()
+      || !self.sourcemap_map.lock().is_empty()
+      || !context.extracted_css.is_empty()
+    {
       let cache = CssModulesCache {
         content_map: self.content_map.lock().clone(),
         sourcemap_map: self.sourcemap_map.lock().clone(),
+        extracted_css_map: context
+          .extracted_css
+          .iter()
+          .map(|e| (e.key().clone(), e.value().clone()))
+          .collect(),
       };
 
       Ok(Some(serialize!(&cache)))
@@ -779,28 +839,11 @@ impl FarmPluginCss {
   }
 }
 
-struct CssModuleRename {
-  indent_name: String,
-  hash: String,
-}
-
-impl TransformConfig for CssModuleRename {
-  fn new_name_for(&self, local: &JsWord) -> JsWord {
-    let name = local.to_string();
-    let r: HashMap<String, &String> = [("name".into(), &name), ("hash".into(), &self.hash)]
-      .into_iter()
-      .collect();
-    transform_css_module_indent_name(self.indent_name.clone(), r).into()
-  }
-}
-
-fn transform_css_module_indent_name(
-  indent_name: String,
-  context: HashMap<String, &String>,
-) -> String {
-  context.iter().fold(indent_name, |acc, (key, value)| {
-    acc.replace(&format!("[{key}]"), value)
-  })
+fn is_extracted_css(path: &str) -> bool {
+  path
+    .split('?')
+    .next()
+    .is_some_and(|p| p.ends_with(EXTRACTED_CSS_SUFFIX))
 }
 
 fn is_farm_css_modules(path: &str) -> bool {
@@ -819,15 +862,17 @@ pub fn source_replace(
   stylesheet: &mut Stylesheet,
   module_id: &ModuleId,
   module_graph: &ModuleGraph,
-  resources_map: &HashMap<String, Resource>,
-  public_path: String,
+  resources_map: &DashMap<String, Resource>,
+  url_prefix: String,
+  asset_targets: Vec<String>,
   alias: HashMap<String, String>,
 ) {
   let mut source_replacer = SourceReplacer::new(
     module_id.clone(),
     module_graph,
     resources_map,
-    public_path,
+    url_prefix,
+    asset_targets,
     alias,
   );
   stylesheet.visit_mut_with(&mut source_replacer);
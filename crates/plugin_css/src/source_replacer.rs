@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 
 use farmfe_core::{
+  config::{asset::asset_target_placeholder, Config, OutputConfig, FARM_PUBLIC_PATH_GLOBAL},
+  dashmap::DashMap,
   module::{module_graph::ModuleGraph, ModuleId},
   plugin::ResolveKind,
-  resource::{Resource, ResourceOrigin},
+  resource::{Resource, ResourceOrigin, ResourceType},
   swc_common::DUMMY_SP,
   swc_css_ast::{AtRulePrelude, ImportHref, Rule, Str, Stylesheet, Url, UrlValue},
 };
@@ -17,29 +19,83 @@ use crate::dep_analyzer::is_source_ignored;
 pub struct SourceReplacer<'a> {
   module_id: ModuleId,
   module_graph: &'a ModuleGraph,
-  resources_map: &'a HashMap<String, Resource>,
-  public_path: String,
+  resources_map: &'a DashMap<String, Resource>,
+  /// prefix of the replaced asset urls, see [url_prefix]
+  url_prefix: String,
+  /// the assets of these targets are referenced by their placeholders, see [asset_targets]
+  asset_targets: Vec<String>,
   alias: HashMap<String, String>,
 }
 
+/// The targets of the assets emitted per target, empty in development or if no target is configured
+pub fn asset_targets(config: &Config) -> Vec<String> {
+  if config.assets.has_targets(&config.mode) {
+    config.assets.targets.clone()
+  } else {
+    vec![]
+  }
+}
+
+/// The prefix of the asset urls in the css. With `publicPath: "runtime"`, the css injected by scripts is
+/// wrapped in a template literal and the urls are prefixed with [FARM_PUBLIC_PATH_GLOBAL], the urls in the emitted
+/// css files are relative to the directory of the css file
+pub fn url_prefix(output: &OutputConfig, in_script: bool) -> String {
+  if output.is_runtime_public_path() {
+    if in_script {
+      return format!("${{{FARM_PUBLIC_PATH_GLOBAL}}}");
+    }
+
+    let depth = output
+      .filename
+      .split('/')
+      .filter(|s| !s.is_empty() && *s != ".")
+      .count()
+      .saturating_sub(1);
+
+    return "../".repeat(depth);
+  }
+
+  // fix #1076. url prefixed by publicPath
+  let normalized_public_path = output.public_path.trim_end_matches('/');
+
+  if normalized_public_path.is_empty() {
+    "/".to_string()
+  } else {
+    format!("{normalized_public_path}/")
+  }
+}
+
 impl<'a> SourceReplacer<'a> {
   pub fn new(
     module_id: ModuleId,
     module_graph: &'a ModuleGraph,
-    resources_map: &'a HashMap<String, Resource>,
-    public_path: String,
+    resources_map: &'a DashMap<String, Resource>,
+    url_prefix: String,
+    asset_targets: Vec<String>,
     alias: HashMap<String, String>,
   ) -> Self {
     Self {
       module_id,
       module_graph,
       resources_map,
-      public_path,
+      url_prefix,
+      asset_targets,
       alias,
     }
   }
 }
 
+impl<'a> SourceReplacer<'a> {
+  /// assets of the targets are emitted to `<target>/`
+  fn is_target_asset(&self, resource: &Resource) -> bool {
+    matches!(resource.resource_type, ResourceType::Asset(_))
+      && resource
+        .name
+        .split_once('/')
+        .is_some_and(|(target, _)| self.asset_targets.iter().any(|t| t == target))
+  }
+}
+
 impl<'a> VisitMut for SourceReplacer<'a> {
   fn visit_mut_url(&mut self, url: &mut Url) {
     if let Some(name) = &url.name.raw {
@@ -55,23 +111,14 @@ impl<'a> VisitMut for SourceReplacer<'a> {
               Some(ResolveKind::CssUrl),
             );
 
-            for resource in self.resources_map.values() {
+            for resource in self.resources_map.iter() {
               if let ResourceOrigin::Module(m_id) = &resource.origin {
                 if &dep_module == m_id {
-                  // fix #1076. url prefixed by publicPath
-                  let normalized_public_path = if self.public_path.is_empty() {
-                    ""
-                  } else {
-                    self.public_path.trim_end_matches('/')
-                  };
-
-                  let normalized_public_path = if normalized_public_path.is_empty() {
-                    "/".to_string()
-                  } else {
-                    format!("{normalized_public_path}/")
-                  };
-
-                  return format!("{normalized_public_path}{}", resource.name);
+                  if self.is_target_asset(resource.value()) {
+                    return format!("{}{}", self.url_prefix, asset_target_placeholder(m_id));
+                  }
+
+                  return format!("{}{}", self.url_prefix, resource.name);
                 }
               }
             }
@@ -4,6 +4,7 @@ use std::{path::PathBuf, sync::Arc};
 use farmfe_core::{
   cache::cache_store::CacheStoreKey,
   context::CompilationContext,
+  dashmap::DashMap,
   deserialize,
   enhanced_magic_string::collapse_sourcemap::{collapse_sourcemap_chain, CollapseSourcemapOptions},
   module::{
@@ -18,7 +19,7 @@ use farmfe_core::{
   swc_ecma_parser::Syntax,
 };
 use farmfe_toolkit::{
-  common::{create_swc_source_map, Source},
+  common::{create_swc_source_map, Source, SourcemapSources},
   css::codegen_css_stylesheet,
   hash::base64_encode,
   script::{parse_module, swc_try_with::try_with, ParseScriptModuleResult},
@@ -26,9 +27,42 @@ use farmfe_toolkit::{
   swc_ecma_transforms_base::resolver,
   swc_ecma_visit::VisitMutWith,
 };
-use farmfe_utils::{hash::sha256, relative};
 
-use crate::source_replace;
+use crate::{
+  source_replace,
+  source_replacer::{asset_targets, url_prefix},
+};
+
+/// `context.custom` key of [CssHmrStyles]
+pub const CSS_HMR_STYLES: &str = "css_hmr_styles";
+
+/// The latest css text of the css modules that are transformed to script modules.
+/// Hmr updates of these modules are sent as css text too, so the runtime patches the style in place instead of re-executing the module
+#[derive(Default)]
+pub struct CssHmrStyles {
+  styles: DashMap<ModuleId, String>,
+}
+
+impl CssHmrStyles {
+  pub fn get(&self, module_id: &ModuleId) -> Option<String> {
+    self.styles.get(module_id).map(|css| css.value().clone())
+  }
+
+  fn set(module_id: &ModuleId, css: Option<String>, context: &Arc<CompilationContext>) {
+    let styles = context
+      .custom
+      .entry(CSS_HMR_STYLES.to_string())
+      .or_insert_with(|| Box::<CssHmrStyles>::default())
+      .downgrade();
+    let styles = styles.downcast_ref::<CssHmrStyles>().unwrap();
+
+    if let Some(css) = css {
+      styles.styles.insert(module_id.clone(), css);
+    } else {
+      styles.styles.remove(module_id);
+    }
+  }
+}
 
 pub fn transform_css_to_script_modules(
   module_ids: Vec<ModuleId>,
@@ -53,7 +87,7 @@ pub fn transform_css_to_script_modules(
         // try read custom css transform cache
         let store_key = CacheStoreKey {
           name: module_id.to_string() + "-transform_css_to_script_modules",
-          key: sha256(
+          key: context.config.hash.hash(
             format!(
               "transform_css_to_script_modules_{}_{}",
               content_hash,
@@ -78,6 +112,8 @@ pub fn transform_css_to_script_modules(
           module.meta.as_script_mut().unresolved_mark = 0;
           module.module_type = ModuleType::Js;
           drop(module_graph);
+          // the css text is unknown when using cache, the update is sent as script
+          CssHmrStyles::set(&module_id, None, context);
           // update css dependency kind to ResolveKind:Import
           transform_css_deps(&module_id, context);
           return Ok(());
@@ -107,7 +143,7 @@ pub fn transform_css_to_script_modules(
       let mut source_map_chain = m.source_map_chain.clone();
       drop(module_graph);
       if let Some(sm) = src_map {
-        let root = context.config.root.clone();
+        let sources = SourcemapSources::new(&context.config);
         source_map_chain.push(Arc::new(sm));
         let map = collapse_sourcemap_chain(
           source_map_chain
@@ -115,7 +151,7 @@ pub fn transform_css_to_script_modules(
             .map(|s| SourceMap::from_slice(s.as_bytes()).unwrap())
             .collect(),
           CollapseSourcemapOptions {
-            remap_source: Some(Box::new(move |src| format!("/{}", relative(&root, src)))),
+            remap_source: Some(Box::new(move |src| sources.remap(src))),
             inline_content: true,
           },
         );
@@ -131,6 +167,11 @@ pub fn transform_css_to_script_modules(
           .source_map_chain = vec![];
       }
 
+      CssHmrStyles::set(
+        &module_id,
+        Some(format!("{css_code}\n{}", source_map_comment(&src_map))),
+        context,
+      );
       let css_code = wrapper_style_load(&css_code, module_id.to_string(), &css_deps, src_map);
       let css_code = Arc::new(css_code);
       let (cm, _) = create_swc_source_map(Source {
@@ -170,6 +211,10 @@ pub fn transform_css_to_script_modules(
           hmr_self_accepted: true,
           hmr_accepted_deps: Default::default(),
           comments: CommentsMetaData::from(comments),
+          original_module_system: None,
+          dynamic_import_hints: Default::default(),
+          used_defines: Default::default(),
+          pruned_imports: Default::default(),
           custom: Default::default(),
         }));
 
@@ -199,13 +244,13 @@ pub fn transform_css_stylesheet(
     module.meta.as_css_mut().take_ast()
   };
 
-  let resources_map = context.resources_map.lock();
   source_replace(
     &mut stylesheet,
     module_id,
     &module_graph,
-    &resources_map,
-    context.config.output.public_path.clone(),
+    &context.resources_map,
+    url_prefix(&context.config.output, true),
+    asset_targets(&context.config),
     context.config.resolve.alias.clone(),
   );
 
@@ -267,8 +312,13 @@ style.innerHTML = cssCode;
 if (previousStyle) {{
 previousStyle.replaceWith(style);
 }} else {{
+const styleAnchor = document.querySelector('[data-farm-styles]') || Array.prototype.find.call(document.head.childNodes, (node) => node.nodeType === 8 && node.data.trim() === 'farm-styles');
+if (styleAnchor) {{
+styleAnchor.parentNode.insertBefore(style, styleAnchor);
+}} else {{
 document.head.appendChild(style);
 }}
+}}
 
 if (module.meta.hot) {{
   module.meta.hot.accept();
@@ -280,16 +330,20 @@ if (module.meta.hot) {{
     format!(
       "{}\n{}",
       code.replace('`', "'").replace('\\', "\\\\"),
-      if let Some(src_map) = src_map {
-        format!(
-          r#"/*# sourceMappingURL=data:application/json;charset=utf-8;base64,{} */"#,
-          base64_encode(src_map.as_bytes())
-        )
-      } else {
-        "".to_string()
-      }
+      source_map_comment(&src_map)
     ),
     id.replace('\\', "\\\\"),
     css_deps,
   )
 }
+
+fn source_map_comment(src_map: &Option<String>) -> String {
+  if let Some(src_map) = src_map {
+    format!(
+      r#"/*# sourceMappingURL=data:application/json;charset=utf-8;base64,{} */"#,
+      base64_encode(src_map.as_bytes())
+    )
+  } else {
+    "".to_string()
+  }
+}
@@ -0,0 +1,90 @@
+use farmfe_core::config::{
+  hash::{HashConfig, MAX_HASH_LENGTH},
+  CssModulesConfig, CssModulesHashStrategy,
+};
+use farmfe_plugin_css::css_modules_name::CssModulesNameGenerator;
+
+fn generator(
+  indent_name: &str,
+  hash_strategy: CssModulesHashStrategy,
+  module_id: &str,
+) -> CssModulesNameGenerator {
+  CssModulesNameGenerator::new(
+    &CssModulesConfig {
+      indent_name: indent_name.to_string(),
+      hash_strategy,
+      ..Default::default()
+    },
+    &HashConfig::default(),
+    module_id,
+    "/root/src/button.module.css",
+    ".btn { color: red; }",
+  )
+}
+
+#[test]
+fn css_modules_name_patterns() {
+  let hash = HashConfig::default().hash(b"src/button.module.css", 6);
+  let g = generator(
+    "[file]_[local]_[hash:6]",
+    CssModulesHashStrategy::Path,
+    "src/button.module.css",
+  );
+
+  assert_eq!(g.generate("btn"), format!("button_btn_{hash}"));
+  assert_eq!(
+    generator(
+      "[name]-[hash]",
+      CssModulesHashStrategy::Path,
+      "src/button.module.css"
+    )
+    .generate("btn"),
+    format!(
+      "btn-{}",
+      HashConfig::default().hash(b"src/button.module.css", 8)
+    )
+  );
+}
+
+#[test]
+fn css_modules_hash_is_stable() {
+  // the same file on windows and unix
+  assert_eq!(
+    generator(
+      "[hash]",
+      CssModulesHashStrategy::Path,
+      "src\\button.module.css"
+    )
+    .generate("btn"),
+    generator(
+      "[hash]",
+      CssModulesHashStrategy::Path,
+      "src/button.module.css"
+    )
+    .generate("btn")
+  );
+  // content hash does not depend on the path
+  assert_eq!(
+    generator("[hash]", CssModulesHashStrategy::Content, "a.module.css").generate("btn"),
+    generator("[hash]", CssModulesHashStrategy::Content, "b/a.module.css").generate("btn")
+  );
+}
+
+#[test]
+fn css_modules_hash_length_is_clamped() {
+  let name = generator(
+    "[hash:1000]",
+    CssModulesHashStrategy::Path,
+    "src/button.module.css",
+  )
+  .generate("btn");
+  assert_eq!(name.len(), MAX_HASH_LENGTH);
+
+  let name = generator(
+    "[local]_[hash:0]",
+    CssModulesHashStrategy::Path,
+    "src/button.module.css",
+  )
+  .generate("btn");
+  assert_eq!(name.len(), "btn_".len() + 1);
+}
@@ -0,0 +1,112 @@
+use std::{
+  borrow::Cow,
+  collections::{BTreeMap, HashMap},
+  path::PathBuf,
+  process::Command,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use farmfe_core::{
+  config::{build_meta::BuildMetaConfig, Config, Mode},
+  serde_json::{self, Value},
+};
+use farmfe_toolkit::resolve::load_package_json;
+
+const ENV_DEFINE_PREFIXES: [&str; 2] = ["import.meta.env.", "process.env."];
+const IMPORT_META_ENV: &str = "import.meta.env";
+/// changes on every build, see [stable_define_code]
+const BUILD_TIME: &str = "BUILD_TIME";
+
+/// key -> value of the enabled build metadata, e.g. `PACKAGE_VERSION` -> `"1.0.0"`
+pub fn build_meta_env(build_meta: &BuildMetaConfig, config: &Config) -> BTreeMap<String, Value> {
+  let mut env = BTreeMap::new();
+
+  if build_meta.package {
+    let package_info =
+      load_package_json(PathBuf::from(&config.root), Default::default()).unwrap_or_default();
+    env.insert(
+      "PACKAGE_NAME".to_string(),
+      Value::String(package_info.name.unwrap_or_default()),
+    );
+    env.insert(
+      "PACKAGE_VERSION".to_string(),
+      Value::String(package_info.version.unwrap_or_default()),
+    );
+  }
+
+  if build_meta.git_commit {
+    let sha = Command::new("git")
+      .args(["rev-parse", "HEAD"])
+      .current_dir(&config.root)
+      .output()
+      .ok()
+      .filter(|output| output.status.success())
+      .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+      .unwrap_or_default();
+    env.insert("GIT_COMMIT".to_string(), Value::String(sha));
+  }
+
+  if build_meta.build_time {
+    let millis = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .unwrap()
+      .as_millis() as u64;
+    env.insert("BUILD_TIME".to_string(), Value::from(millis));
+  }
+
+  if build_meta.mode {
+    let mode = match config.mode {
+      Mode::Development => "development",
+      Mode::Production => "production",
+    };
+    env.insert("MODE".to_string(), Value::String(mode.to_string()));
+  }
+
+  env
+}
+
+/// Add `import.meta.env.<KEY>` and `process.env.<KEY>` of the build metadata to the defines without overriding the configured ones.
+/// The metadata is also merged into the `import.meta.env` object, so destructuring it works
+pub fn extend_define(define: &mut HashMap<String, Value>, env: &BTreeMap<String, Value>) {
+  for (key, value) in env {
+    // the defines are replaced by code, strings must be quoted
+    let code = Value::String(value.to_string());
+
+    for prefix in ENV_DEFINE_PREFIXES {
+      define
+        .entry(format!("{prefix}{key}"))
+        .or_insert_with(|| code.clone());
+    }
+  }
+
+  if let Some(Value::String(code)) = define.get_mut(IMPORT_META_ENV) {
+    if let Ok(Value::Object(mut object)) = serde_json::from_str::<Value>(code) {
+      for (key, value) in env {
+        object.entry(key.clone()).or_insert_with(|| value.clone());
+      }
+
+      *code = Value::Object(object).to_string();
+    }
+  }
+}
+
+/// The code of the define without the build time, which changes on every build. The defines of the persistent cached modules
+/// are compared with it, otherwise every module using the build time or the merged `import.meta.env` would be invalidated on every build
+pub fn stable_define_code<'a>(key: &str, code: &'a str) -> Cow<'a, str> {
+  if ENV_DEFINE_PREFIXES
+    .iter()
+    .any(|prefix| key.strip_prefix(prefix) == Some(BUILD_TIME))
+  {
+    return Cow::Borrowed("");
+  }
+
+  if key == IMPORT_META_ENV {
+    if let Ok(Value::Object(mut object)) = serde_json::from_str::<Value>(code) {
+      if object.remove(BUILD_TIME).is_some() {
+        return Cow::Owned(Value::Object(object).to_string());
+      }
+    }
+  }
+
+  Cow::Borrowed(code)
+}
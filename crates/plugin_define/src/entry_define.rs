@@ -0,0 +1,89 @@
+use std::{collections::HashSet, sync::Arc};
+
+use farmfe_core::{
+  config::Config,
+  context::CompilationContext,
+  error::Result,
+  module::ModuleId,
+  plugin::{
+    Plugin, PluginHookContext, PluginResolveHookParam, PluginResolveHookResult, ResolveKind,
+  },
+};
+use farmfe_utils::parse_query;
+
+const PLUGIN_NAME: &str = "FarmPluginEntryDefine";
+/// query of the modules compiled with the define overrides of an entry, e.g. `./shared.ts?farm_entry_define=admin`
+const ENTRY_DEFINE_QUERY: &str = "farm_entry_define";
+
+/// Tag the modules of the entries in [Config::entry_define] with the entry name, so the modules shared by the entries
+/// get a module id, and a cache key, for each entry and the entry overrides are applied by [crate::FarmPluginDefine]
+pub struct FarmPluginEntryDefine {
+  entries: HashSet<String>,
+}
+
+impl FarmPluginEntryDefine {
+  pub fn new(config: &Config) -> Self {
+    Self {
+      entries: config.entry_define.keys().cloned().collect(),
+    }
+  }
+}
+
+pub(crate) fn entry_of_query(query: &[(String, String)]) -> Option<String> {
+  query
+    .iter()
+    .find(|(key, _)| key == ENTRY_DEFINE_QUERY)
+    .map(|(_, entry)| entry.clone())
+}
+
+pub(crate) fn entry_of_module(module_id: &ModuleId) -> Option<String> {
+  entry_of_query(&parse_query(module_id.query_string()))
+}
+
+impl Plugin for FarmPluginEntryDefine {
+  fn name(&self) -> &str {
+    PLUGIN_NAME
+  }
+
+  /// Make sure the resolve results of the other plugins are tagged
+  fn priority(&self) -> i32 {
+    i32::MAX
+  }
+
+  fn resolve(
+    &self,
+    param: &PluginResolveHookParam,
+    context: &Arc<CompilationContext>,
+    hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginResolveHookResult>> {
+    if hook_context.contain_caller(PLUGIN_NAME) {
+      return Ok(None);
+    }
+
+    let entry = match &param.kind {
+      ResolveKind::Entry(name) if self.entries.contains(name) => Some(name.clone()),
+      ResolveKind::Entry(_) => None,
+      _ => param.importer.as_ref().and_then(entry_of_module),
+    };
+    let Some(entry) = entry else {
+      return Ok(None);
+    };
+
+    let result = context.plugin_driver.resolve(
+      param,
+      context,
+      &PluginHookContext {
+        caller: hook_context.add_caller(PLUGIN_NAME),
+        ..hook_context.clone()
+      },
+    )?;
+
+    Ok(result.map(|mut result| {
+      if !result.external && entry_of_query(&result.query).is_none() {
+        result.query.push((ENTRY_DEFINE_QUERY.to_string(), entry));
+      }
+
+      result
+    }))
+  }
+}
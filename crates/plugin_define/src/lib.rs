@@ -1,13 +1,38 @@
 #![feature(path_file_prefix)]
 
+use std::{collections::HashMap, sync::Arc};
+
 use farmfe_core::{
-  config::Config,
+  config::{
+    flags::{FlagPrunedImport, FlagReport, FlagsConfig, FlagsReport},
+    Config,
+  },
+  context::CompilationContext,
+  error::Result,
+  module::{Module, ModuleId, ModuleMetaData},
   parking_lot::RwLock,
-  plugin::Plugin,
+  plugin::{
+    Plugin, PluginFinalizeResourcesHookParams, PluginHookContext, PluginProcessModuleHookParam,
+    PluginResolveHookParam, ResolveKind,
+  },
   regex::Regex,
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde::{Deserialize, Serialize},
   serde_json::{self, Value},
+  swc_common::{Mark, GLOBALS},
 };
-use farmfe_toolkit::lazy_static::lazy_static;
+use farmfe_toolkit::{lazy_static::lazy_static, swc_ecma_visit::VisitMutWith};
+use farmfe_utils::stringify_query;
+
+use build_meta::{build_meta_env, extend_define, stable_define_code};
+use entry_define::{entry_of_module, entry_of_query};
+use replace_defines::{Define, DefineReplacer};
+
+pub use entry_define::FarmPluginEntryDefine;
+
+mod build_meta;
+mod entry_define;
+mod replace_defines;
 
 // Default supported static assets: png, jpg, jpeg, gif, svg, webp, mp4, webm, wav, mp3, wma, m4a, aac, ico, ttf, woff, woff2
 lazy_static! {
@@ -23,17 +48,117 @@ const DEFAULT_DEFINE_PROCESS_ENV: &str = "FARM_PROCESS_ENV";
 
 pub struct FarmPluginDefine {
   /// Sort define by key len desc
-  sorted_define: RwLock<Vec<(String, Value)>>,
+  sorted_define: RwLock<Vec<Define>>,
+  /// entry name -> sorted defines with the overrides of [Config::entry_define] applied
+  entry_define: RwLock<HashMap<String, Vec<Define>>>,
+  /// the define maps of the previous build, loaded from the persistent cache
+  cached_define: RwLock<Option<DefineCache>>,
+  /// the flags are applied as defines and reported in [Plugin::finalize_resources]
+  flags: RwLock<Option<FlagsConfig>>,
 }
 
 impl FarmPluginDefine {
   pub fn new(_: &Config) -> Self {
     Self {
       sorted_define: RwLock::new(vec![]),
+      entry_define: RwLock::new(HashMap::new()),
+      cached_define: RwLock::new(None),
+      flags: RwLock::new(None),
+    }
+  }
+
+  /// Call `f` with the defines of the module, the modules of the entries with overrides are tagged by [FarmPluginEntryDefine]
+  fn with_define<R>(&self, entry: Option<String>, f: impl FnOnce(&[Define]) -> R) -> R {
+    if let Some(entry) = entry {
+      if let Some(define) = self.entry_define.read().get(&entry) {
+        return f(define);
+      }
+    }
+
+    f(&self.sorted_define.read())
+  }
+
+  fn define_cache(&self) -> DefineCache {
+    DefineCache {
+      define: define_map(&self.sorted_define.read()),
+      entry_define: self
+        .entry_define
+        .read()
+        .iter()
+        .map(|(entry, define)| (entry.clone(), define_map(define)))
+        .collect(),
     }
   }
 }
 
+/// define key -> code
+fn define_map(define: &[Define]) -> HashMap<String, String> {
+  define
+    .iter()
+    .map(|define| (define.key.clone(), define.code.clone()))
+    .collect()
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+struct DefineCache {
+  define: HashMap<String, String>,
+  entry_define: HashMap<String, HashMap<String, String>>,
+}
+
+impl DefineCache {
+  fn define_of(&self, entry: Option<String>) -> Option<&HashMap<String, String>> {
+    match entry {
+      Some(entry) => self.entry_define.get(&entry),
+      None => Some(&self.define),
+    }
+  }
+}
+
+/// Whether the define code of the cached module is the same as the current one, ignoring the build time
+fn is_same_define(key: &str, cached: Option<&String>, current: Option<&String>) -> bool {
+  match (cached, current) {
+    (Some(cached), Some(current)) => {
+      stable_define_code(key, cached) == stable_define_code(key, current)
+    }
+    (cached, current) => cached == current,
+  }
+}
+
+fn value_to_code(value: &Value) -> String {
+  match value {
+    serde_json::Value::Null => "null".to_string(),
+    serde_json::Value::Bool(b) => (if *b { "true" } else { "false" }).to_string(),
+    serde_json::Value::Number(num) => num.to_string(),
+    serde_json::Value::String(str) => str.to_string(),
+    serde_json::Value::Array(arr) => serde_json::to_string(arr).unwrap(),
+    serde_json::Value::Object(obj) => serde_json::to_string(obj).unwrap(),
+  }
+}
+
+/// Sort the defines by key len desc, the internal defines are processed at last
+fn sort_define(define: HashMap<String, Value>) -> Vec<Define> {
+  let mut sorted_define = define.into_iter().collect::<Vec<_>>();
+  sorted_define.sort_by_key(|b| std::cmp::Reverse(b.0.len()));
+
+  let mut result = vec![];
+  let mut delayed_define = vec![];
+
+  for (key, value) in sorted_define {
+    let regex = key.strip_prefix(REGEX_PREFIX).map(|r| r.to_string());
+    let define = Define::new(key.clone(), regex.as_deref(), value_to_code(&value));
+
+    if key == *DEFAULT_DEFINE_PROCESS_ENV {
+      delayed_define.push(define);
+    } else {
+      result.push(define);
+    }
+  }
+
+  result.extend(delayed_define);
+  result
+}
+
 impl Plugin for FarmPluginDefine {
   fn name(&self) -> &str {
     PLUGIN_NAME
@@ -43,72 +168,273 @@ impl Plugin for FarmPluginDefine {
     -99
   }
 
-  fn config(&self, config: &mut Config) -> farmfe_core::error::Result<Option<()>> {
-    let define = config.define.clone();
-    let mut sorted_define = define.into_iter().collect::<Vec<_>>();
-    sorted_define.sort_by_key(|b| std::cmp::Reverse(b.0.len()));
-
-    let mut self_sorted_define = self.sorted_define.write();
-    // make sure internal defines are processed at last
-    let mut delayed_define = vec![];
+  fn config(&self, config: &mut Config) -> Result<Option<()>> {
+    let mut define = config.define.clone();
 
-    for item in sorted_define {
-      if item.0 == *DEFAULT_DEFINE_PROCESS_ENV {
-        delayed_define.push(item);
-      } else {
-        self_sorted_define.push(item);
-      }
+    if let Some(flags) = &config.flags {
+      define.extend(
+        flags
+          .defines()
+          .map(|(key, value)| (key, Value::Bool(value))),
+      );
+      *self.flags.write() = Some(*flags.clone());
     }
 
-    for d in delayed_define {
-      self_sorted_define.push(d);
+    if let Some(build_meta) = &config.build_meta {
+      extend_define(&mut define, &build_meta_env(build_meta, config));
     }
 
+    let entry_define = config
+      .entry_define
+      .iter()
+      .map(|(entry, overrides)| {
+        let mut define = define.clone();
+        define.extend(overrides.clone());
+        (entry.clone(), sort_define(define))
+      })
+      .collect();
+
+    *self.sorted_define.write() = sort_define(define);
+    *self.entry_define.write() = entry_define;
+
     Ok(Some(()))
   }
 
+  /// Script modules are replaced at the ast level in [Plugin::process_module], other modules are replaced as text
   fn transform(
     &self,
     param: &farmfe_core::plugin::PluginTransformHookParam,
-    _context: &std::sync::Arc<farmfe_core::context::CompilationContext>,
-  ) -> farmfe_core::error::Result<Option<farmfe_core::plugin::PluginTransformHookResult>> {
-    let define = self.sorted_define.read();
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<farmfe_core::plugin::PluginTransformHookResult>> {
+    if param.module_type.is_script() {
+      return Ok(None);
+    }
+
+    self.with_define(entry_of_query(&param.query), |define| {
+      if define.is_empty() {
+        return Ok(None);
+      }
 
-    if !define.is_empty() {
       let mut content = String::new();
 
-      for (key, value) in &*define {
-        let value = match value {
-          serde_json::Value::Null => "null".to_string(),
-          serde_json::Value::Bool(b) => (if *b { "true" } else { "false" }).to_string(),
-          serde_json::Value::Number(num) => num.to_string(),
-          serde_json::Value::String(str) => str.to_string(),
-          serde_json::Value::Array(arr) => serde_json::to_string(arr).unwrap(),
-          serde_json::Value::Object(obj) => serde_json::to_string(obj).unwrap(),
-        };
+      for Define { key, code, .. } in define {
         if let Some(reg) = key.strip_prefix(REGEX_PREFIX) {
           let regex = Regex::new(reg).unwrap();
           if content.is_empty() {
-            content = regex.replace_all(&param.content, &value).to_string();
+            content = regex.replace_all(&param.content, code).to_string();
           } else {
-            content = regex.replace_all(&content, &value).to_string();
+            content = regex.replace_all(&content, code).to_string();
           }
         } else {
           if content.is_empty() {
-            content = param.content.replace(key, &value);
+            content = param.content.replace(key, code);
           } else {
-            content = content.replace(key, &value);
+            content = content.replace(key, code);
           }
         };
       }
 
-      return Ok(Some(farmfe_core::plugin::PluginTransformHookResult {
+      Ok(Some(farmfe_core::plugin::PluginTransformHookResult {
         content,
         // TODO support source map
         ..Default::default()
-      }));
+      }))
+    })
+  }
+
+  fn process_module(
+    &self,
+    param: &mut PluginProcessModuleHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if !param.module_type.is_script() {
+      return Ok(None);
+    }
+
+    self.with_define(entry_of_module(param.module_id), |define| {
+      if define.is_empty() {
+        return Ok(None);
+      }
+
+      let script = param.meta.as_script_mut();
+      let unresolved_mark = Mark::from_u32(script.unresolved_mark);
+
+      // the unresolved mark is applied to the replaced code
+      let (used_defines, pruned_imports) = GLOBALS.set(&context.meta.script.globals, || {
+        let mut replacer = DefineReplacer::new(define, &script.ast, unresolved_mark);
+        script.ast.visit_mut_with(&mut replacer);
+        (replacer.used_defines, replacer.pruned_imports)
+      });
+
+      script.used_defines = used_defines;
+      script.pruned_imports = pruned_imports;
+
+      Ok(Some(()))
+    })
+  }
+
+  /// Only the modules using the changed defines are rebuilt, unless a define is added as any module may use it
+  fn handle_persistent_cached_module(
+    &self,
+    module: &Module,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<bool>> {
+    let entry = entry_of_module(&module.id);
+    let define = self.with_define(entry.clone(), define_map);
+    let cached_define = self.cached_define.read();
+    // the overrides of the entry may be added since the previous build
+    let no_define = HashMap::new();
+    let cached_define = cached_define
+      .as_ref()
+      .map(|cached_define| cached_define.define_of(entry).unwrap_or(&no_define));
+
+    let should_invalidate = match &*module.meta {
+      ModuleMetaData::Script(script) => {
+        script
+          .used_defines
+          .iter()
+          .any(|(key, code)| !is_same_define(key, define.get(key), Some(code)))
+          || cached_define
+            .is_some_and(|cached_define| define.keys().any(|key| !cached_define.contains_key(key)))
+      }
+      // defines of non script modules are replaced as text and not recorded
+      _ => cached_define.is_some_and(|cached_define| {
+        cached_define.len() != define.len()
+          || define
+            .iter()
+            .any(|(key, code)| !is_same_define(key, cached_define.get(key), Some(code)))
+      }),
+    };
+
+    Ok(should_invalidate.then_some(true))
+  }
+
+  /// Emit the report of the imports removed with the dead branches of the flags, the imported modules that are not
+  /// in the module graph are removed from the build entirely
+  fn finalize_resources(
+    &self,
+    param: &mut PluginFinalizeResourcesHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    let flags = self.flags.read();
+    let Some(flags) = flags.as_ref() else {
+      return Ok(None);
+    };
+
+    let mut report: FlagsReport = flags
+      .values
+      .iter()
+      .map(|(name, value)| {
+        (
+          name.clone(),
+          FlagReport {
+            value: *value,
+            ..Default::default()
+          },
+        )
+      })
+      .collect();
+    let module_graph = context.module_graph.read();
+
+    for module in module_graph.modules() {
+      let ModuleMetaData::Script(script) = &*module.meta else {
+        continue;
+      };
+
+      for pruned in &script.pruned_imports {
+        let flag_names = pruned
+          .defines
+          .iter()
+          .filter_map(|define| flags.flag_of_define(define))
+          .collect::<Vec<_>>();
+
+        if flag_names.is_empty() {
+          continue;
+        }
+
+        let module_id = context
+          .plugin_driver
+          .resolve(
+            &PluginResolveHookParam {
+              source: pruned.source.clone(),
+              importer: Some(module.id.clone()),
+              kind: pruned.kind.clone(),
+            },
+            context,
+            &PluginHookContext {
+              caller: Some(PLUGIN_NAME.to_string()),
+              meta: HashMap::new(),
+            },
+          )
+          .ok()
+          .flatten()
+          .map(|result| {
+            ModuleId::new(
+              &result.resolved_path,
+              &stringify_query(&result.query),
+              &context.config.root,
+            )
+          });
+        let removed = module_id
+          .as_ref()
+          .filter(|module_id| !module_graph.has_module(module_id));
+
+        for name in flag_names {
+          let flag = report.get_mut(name).unwrap();
+          flag.pruned_imports.push(FlagPrunedImport {
+            importer: module.id.to_string(),
+            source: pruned.source.clone(),
+            module_id: module_id.as_ref().map(|id| id.to_string()),
+          });
+
+          if let Some(removed) = removed {
+            flag.removed_modules.push(removed.to_string());
+
+            if pruned.kind == ResolveKind::DynamicImport {
+              flag.removed_chunks.push(removed.to_string());
+            }
+          }
+        }
+      }
     }
 
-    Ok(None)
+    for flag in report.values_mut() {
+      flag
+        .pruned_imports
+        .sort_by(|a, b| (&a.importer, &a.source).cmp(&(&b.importer, &b.source)));
+      flag.removed_modules.sort();
+      flag.removed_modules.dedup();
+      flag.removed_chunks.sort();
+      flag.removed_chunks.dedup();
+    }
+
+    param.resources_map.insert(
+      flags.report.clone(),
+      Resource {
+        name: flags.report.clone(),
+        bytes: serde_json::to_vec_pretty(&report).unwrap(),
+        resource_type: ResourceType::Custom("json".to_string()),
+        origin: ResourceOrigin::ResourcePot(flags.report.clone()),
+        ..Default::default()
+      },
+    );
+
+    Ok(Some(()))
+  }
+
+  fn plugin_cache_loaded(
+    &self,
+    cache: &Vec<u8>,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if let Ok(cached_define) = serde_json::from_slice(cache) {
+      *self.cached_define.write() = Some(cached_define);
+    }
+
+    Ok(Some(()))
+  }
+
+  fn write_plugin_cache(&self, _context: &Arc<CompilationContext>) -> Result<Option<Vec<u8>>> {
+    Ok(Some(serde_json::to_vec(&self.define_cache()).unwrap()))
   }
 }
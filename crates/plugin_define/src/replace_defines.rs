@@ -0,0 +1,528 @@
+use std::collections::HashMap;
+
+use farmfe_core::{
+  module::PrunedImport,
+  plugin::ResolveKind,
+  regex::Regex,
+  swc_common::{util::take::Take, BytePos, Mark, Span, SyntaxContext, DUMMY_SP},
+  swc_ecma_ast::{
+    ArrowExpr, BinExpr, BinaryOp, CallExpr, Callee, ComputedPropName, CondExpr, EmptyStmt, Expr,
+    ExprOrSpread, Function, Ident, IfStmt, KeyValueProp, Lit, MemberExpr, MemberProp, MetaPropExpr,
+    MetaPropKind, Module as SwcModule, ModuleItem, ObjectLit, Prop, PropName, PropOrSpread, Stmt,
+    UnaryExpr, UnaryOp, VarDecl, VarDeclKind,
+  },
+  swc_ecma_parser::{EsSyntax, Parser, StringInput, Syntax},
+};
+use farmfe_toolkit::swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+/// A `define` entry, e.g. `process.env.NODE_ENV`, `typeof window` or `$__farm_regex:(global(This)?\.)?process\.env\.API_URL`
+pub struct Define {
+  pub key: String,
+  matcher: DefineMatcher,
+  /// the replaced code
+  pub code: String,
+  /// [None] if the code is not an expression, the define is only applied to non script modules
+  expr: Option<Box<Expr>>,
+}
+
+enum DefineMatcher {
+  Path(String),
+  Regex(Regex),
+}
+
+impl Define {
+  pub fn new(key: String, regex: Option<&str>, code: String) -> Self {
+    let matcher = match regex {
+      // the whole path of the expression should match
+      Some(regex) => DefineMatcher::Regex(Regex::new(&format!("^(?:{regex})$")).unwrap()),
+      None => DefineMatcher::Path(key.clone()),
+    };
+    let expr = Parser::new(
+      Syntax::Es(EsSyntax::default()),
+      StringInput::new(&code, BytePos(0), BytePos(code.len() as u32)),
+      None,
+    )
+    .parse_expr()
+    .ok();
+
+    Self {
+      key,
+      matcher,
+      code,
+      expr,
+    }
+  }
+
+  fn matches(&self, path: &str) -> bool {
+    match &self.matcher {
+      DefineMatcher::Path(key) => key == path,
+      DefineMatcher::Regex(regex) => regex.is_match(path),
+    }
+  }
+}
+
+/// Replace the expressions matching the defines, e.g. `process.env.NODE_ENV`, `typeof window` or `__DEV__`.
+/// Only globals are replaced, the expressions whose root identifier is declared in the module are kept.
+/// `import.meta` only exists in ES modules, so the `import.meta.*` defines are not applied to CommonJS modules.
+///
+/// Members of object-shaped defines are replaced by the member values, e.g. `__BUILD_INFO__.sha` with `"abc"` for
+/// `__BUILD_INFO__: { sha: "abc" }`. Then the `if` statements and conditional expressions whose tests become constants
+/// are folded, so the dependencies of the removed branches are not analyzed
+pub struct DefineReplacer<'a> {
+  defines: &'a [Define],
+  unresolved_mark: Mark,
+  unresolved_ctxt: SyntaxContext,
+  allow_import_meta: bool,
+  /// keys of the defines of the replaced expressions in order, used to fold only the tests containing defines
+  replaced: Vec<String>,
+  /// define key -> the replaced code
+  pub used_defines: HashMap<String, String>,
+  /// dynamic imports and requires of the removed branches
+  pub pruned_imports: Vec<PrunedImport>,
+}
+
+impl<'a> DefineReplacer<'a> {
+  /// Should be created inside the globals of the compilation as the unresolved mark is applied to the replaced code
+  pub fn new(defines: &'a [Define], ast: &SwcModule, unresolved_mark: Mark) -> Self {
+    let mut detector = CommonJsDetector {
+      unresolved_mark,
+      is_commonjs: false,
+    };
+
+    if !ast
+      .body
+      .iter()
+      .any(|item| matches!(item, ModuleItem::ModuleDecl(_)))
+    {
+      ast.visit_with(&mut detector);
+    }
+
+    Self {
+      defines,
+      unresolved_mark,
+      unresolved_ctxt: SyntaxContext::empty().apply_mark(unresolved_mark),
+      allow_import_meta: !detector.is_commonjs,
+      replaced: vec![],
+      used_defines: HashMap::new(),
+      pruned_imports: vec![],
+    }
+  }
+
+  /// `process.env.NODE_ENV` for the member expression, [None] if the root is not a global or the property is computed
+  fn expr_path(&self, expr: &Expr) -> Option<String> {
+    match expr {
+      Expr::Ident(ident) if ident.span.ctxt.outer() == self.unresolved_mark => {
+        Some(ident.sym.to_string())
+      }
+      Expr::MetaProp(MetaPropExpr {
+        kind: MetaPropKind::ImportMeta,
+        ..
+      }) if self.allow_import_meta => Some("import.meta".to_string()),
+      Expr::Member(MemberExpr { obj, prop, .. }) => {
+        let prop = match prop {
+          MemberProp::Ident(ident) => ident.sym.to_string(),
+          MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+            Expr::Lit(Lit::Str(str)) => str.value.to_string(),
+            _ => return None,
+          },
+          MemberProp::PrivateName(_) => return None,
+        };
+
+        Some(format!("{}.{prop}", self.expr_path(obj)?))
+      }
+      _ => None,
+    }
+  }
+
+  /// `typeof window` for `typeof window`, the path of other expressions is returned by [Self::expr_path]
+  fn define_path(&self, expr: &Expr) -> Option<String> {
+    match expr {
+      Expr::Unary(UnaryExpr {
+        op: UnaryOp::TypeOf,
+        arg,
+        ..
+      }) => Some(format!("typeof {}", self.expr_path(arg)?)),
+      _ => self.expr_path(expr),
+    }
+  }
+
+  fn replacement(&mut self, expr: &Expr) -> Option<Expr> {
+    let path = self.define_path(expr)?;
+    let defines = self.defines;
+    let (define, replacement) = match defines
+      .iter()
+      .find(|define| define.expr.is_some() && define.matches(&path))
+    {
+      Some(define) => (define, define.expr.as_deref().unwrap()),
+      None => self.object_member_replacement(&path)?,
+    };
+
+    self
+      .used_defines
+      .insert(define.key.clone(), define.code.clone());
+    self.replaced.push(define.key.clone());
+
+    let mut replacement = replacement.clone();
+    replacement.visit_mut_with(&mut ReplacementSpanResetter {
+      unresolved_ctxt: self.unresolved_ctxt,
+    });
+
+    Some(replacement)
+  }
+
+  /// `__BUILD_INFO__.sha` is replaced by the `sha` property of the `__BUILD_INFO__` define if it's an object literal
+  fn object_member_replacement(&self, path: &str) -> Option<(&'a Define, &'a Expr)> {
+    self.defines.iter().find_map(|define| {
+      let DefineMatcher::Path(key) = &define.matcher else {
+        return None;
+      };
+      let members = path.strip_prefix(key.as_str())?.strip_prefix('.')?;
+
+      members
+        .split('.')
+        .try_fold(define.expr.as_deref()?, |expr, member| match expr {
+          Expr::Object(obj) => object_prop(obj, member),
+          _ => None,
+        })
+        .map(|expr| (define, expr))
+    })
+  }
+
+  /// The value of the test and the keys of the defines in it, [None] if the test does not contain defines or is not constant
+  fn fold_test(&mut self, test: &mut Expr) -> Option<(bool, Vec<String>)> {
+    let replaced = self.replaced.len();
+    test.visit_mut_with(self);
+
+    if self.replaced.len() == replaced {
+      return None;
+    }
+
+    let value = ConstValue::eval(test, self.unresolved_mark)?.is_truthy();
+    let mut defines = self.replaced[replaced..].to_vec();
+    defines.sort();
+    defines.dedup();
+
+    Some((value, defines))
+  }
+
+  fn prune<N: VisitWith<PrunedImportsCollector>>(&mut self, removed: &N, defines: Vec<String>) {
+    let mut collector = PrunedImportsCollector {
+      unresolved_mark: self.unresolved_mark,
+      imports: vec![],
+    };
+    removed.visit_with(&mut collector);
+
+    self.pruned_imports.extend(
+      collector
+        .imports
+        .into_iter()
+        .map(|(source, kind)| PrunedImport {
+          source,
+          kind,
+          defines: defines.clone(),
+        }),
+    );
+  }
+}
+
+impl VisitMut for DefineReplacer<'_> {
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    if matches!(
+      expr,
+      Expr::Ident(_)
+        | Expr::Member(_)
+        | Expr::Unary(UnaryExpr {
+          op: UnaryOp::TypeOf,
+          ..
+        })
+    ) {
+      if let Some(replacement) = self.replacement(expr) {
+        *expr = replacement;
+        return;
+      }
+    }
+
+    if let Expr::Cond(CondExpr {
+      test, cons, alt, ..
+    }) = expr
+    {
+      if let Some((test, defines)) = self.fold_test(test) {
+        self.prune(if test { &**alt } else { &**cons }, defines);
+        *expr = if test { *cons.take() } else { *alt.take() };
+        // the kept branch is visited as a whole expression, it may be a define itself
+        self.visit_mut_expr(expr);
+      } else {
+        cons.visit_mut_with(self);
+        alt.visit_mut_with(self);
+      }
+
+      return;
+    }
+
+    expr.visit_mut_children_with(self);
+  }
+
+  fn visit_mut_stmt(&mut self, stmt: &mut Stmt) {
+    if let Stmt::If(IfStmt {
+      test, cons, alt, ..
+    }) = stmt
+    {
+      // `var` declarations are hoisted, removing them would change the bindings of the function
+      let folded_test = self.fold_test(test).filter(|(test, _)| {
+        let removed = if *test { alt.as_deref() } else { Some(&**cons) };
+        !removed.is_some_and(contains_var_decl)
+      });
+
+      if let Some((test, defines)) = folded_test {
+        if let Some(removed) = if test { alt.as_deref() } else { Some(&**cons) } {
+          self.prune(removed, defines);
+        }
+
+        *stmt = if test {
+          *cons.take()
+        } else {
+          alt
+            .take()
+            .map(|alt| *alt)
+            .unwrap_or(Stmt::Empty(EmptyStmt { span: DUMMY_SP }))
+        };
+        self.visit_mut_stmt(stmt);
+      } else {
+        cons.visit_mut_with(self);
+        alt.visit_mut_with(self);
+      }
+
+      return;
+    }
+
+    stmt.visit_mut_children_with(self);
+  }
+
+  fn visit_mut_prop(&mut self, prop: &mut Prop) {
+    // `{ __DEV__ }` -> `{ __DEV__: true }`
+    if let Prop::Shorthand(ident) = prop {
+      if let Some(replacement) = self.replacement(&Expr::Ident(ident.clone())) {
+        *prop = Prop::KeyValue(KeyValueProp {
+          key: PropName::Ident(Ident::new(ident.sym.clone(), ident.span)),
+          value: Box::new(replacement),
+        });
+        return;
+      }
+    }
+
+    prop.visit_mut_children_with(self);
+  }
+}
+
+fn object_prop<'a>(obj: &'a ObjectLit, name: &str) -> Option<&'a Expr> {
+  obj.props.iter().rev().find_map(|prop| match prop {
+    PropOrSpread::Prop(prop) => match &**prop {
+      Prop::KeyValue(KeyValueProp { key, value }) => {
+        let key = match key {
+          PropName::Ident(ident) => ident.sym.to_string(),
+          PropName::Str(str) => str.value.to_string(),
+          _ => return None,
+        };
+
+        (key == name).then_some(&**value)
+      }
+      _ => None,
+    },
+    PropOrSpread::Spread(_) => None,
+  })
+}
+
+/// Constant value of the folded tests, e.g. `"production" !== "production"` or `typeof "undefined" === "object"`
+#[derive(Debug, PartialEq)]
+enum ConstValue {
+  Bool(bool),
+  Str(String),
+  Num(f64),
+  Null,
+  Undefined,
+  /// objects, arrays and functions, only their types and truthiness are known
+  Object(&'static str),
+}
+
+impl ConstValue {
+  fn eval(expr: &Expr, unresolved_mark: Mark) -> Option<Self> {
+    let eval = |expr: &Expr| Self::eval(expr, unresolved_mark);
+
+    match expr {
+      Expr::Lit(Lit::Bool(b)) => Some(Self::Bool(b.value)),
+      Expr::Lit(Lit::Str(str)) => Some(Self::Str(str.value.to_string())),
+      Expr::Lit(Lit::Num(num)) => Some(Self::Num(num.value)),
+      Expr::Lit(Lit::Null(_)) => Some(Self::Null),
+      Expr::Ident(ident)
+        if &*ident.sym == "undefined" && ident.span.ctxt.outer() == unresolved_mark =>
+      {
+        Some(Self::Undefined)
+      }
+      Expr::Object(_) | Expr::Array(_) => Some(Self::Object("object")),
+      Expr::Fn(_) | Expr::Arrow(_) => Some(Self::Object("function")),
+      Expr::Paren(paren) => eval(&paren.expr),
+      Expr::Unary(UnaryExpr { op, arg, .. }) => {
+        let arg = eval(arg)?;
+
+        match op {
+          UnaryOp::Bang => Some(Self::Bool(!arg.is_truthy())),
+          UnaryOp::TypeOf => Some(Self::Str(arg.type_of().to_string())),
+          UnaryOp::Void => Some(Self::Undefined),
+          _ => None,
+        }
+      }
+      Expr::Bin(BinExpr {
+        op, left, right, ..
+      }) => {
+        let left = eval(left)?;
+
+        match op {
+          BinaryOp::LogicalAnd if !left.is_truthy() => Some(left),
+          BinaryOp::LogicalOr if left.is_truthy() => Some(left),
+          BinaryOp::LogicalAnd | BinaryOp::LogicalOr => eval(right),
+          BinaryOp::EqEqEq => left.strict_equals(&eval(right)?).map(Self::Bool),
+          BinaryOp::NotEqEq => left.strict_equals(&eval(right)?).map(|eq| Self::Bool(!eq)),
+          BinaryOp::EqEq => left.loose_equals(&eval(right)?).map(Self::Bool),
+          BinaryOp::NotEq => left.loose_equals(&eval(right)?).map(|eq| Self::Bool(!eq)),
+          _ => None,
+        }
+      }
+      _ => None,
+    }
+  }
+
+  fn is_truthy(&self) -> bool {
+    match self {
+      Self::Bool(b) => *b,
+      Self::Str(str) => !str.is_empty(),
+      Self::Num(num) => *num != 0.0 && !num.is_nan(),
+      Self::Null | Self::Undefined => false,
+      Self::Object(_) => true,
+    }
+  }
+
+  fn type_of(&self) -> &'static str {
+    match self {
+      Self::Bool(_) => "boolean",
+      Self::Str(_) => "string",
+      Self::Num(_) => "number",
+      Self::Null => "object",
+      Self::Undefined => "undefined",
+      Self::Object(ty) => ty,
+    }
+  }
+
+  /// [None] if the result is unknown, e.g. comparing two objects
+  fn strict_equals(&self, other: &Self) -> Option<bool> {
+    match (self, other) {
+      (Self::Object(_), _) | (_, Self::Object(_)) => None,
+      _ => Some(self == other),
+    }
+  }
+
+  fn loose_equals(&self, other: &Self) -> Option<bool> {
+    match (self, other) {
+      (Self::Null | Self::Undefined, Self::Null | Self::Undefined) => Some(true),
+      (Self::Null | Self::Undefined, _) | (_, Self::Null | Self::Undefined) => Some(false),
+      _ if self.type_of() == other.type_of() => self.strict_equals(other),
+      // the type coercions are not evaluated
+      _ => None,
+    }
+  }
+}
+
+/// Dynamic imports and requires with string sources
+struct PrunedImportsCollector {
+  unresolved_mark: Mark,
+  imports: Vec<(String, ResolveKind)>,
+}
+
+impl PrunedImportsCollector {
+  fn is_require(&self, expr: &Expr) -> bool {
+    matches!(expr, Expr::Ident(ident) if &*ident.sym == "require" && ident.span.ctxt.outer() == self.unresolved_mark)
+  }
+}
+
+impl Visit for PrunedImportsCollector {
+  fn visit_call_expr(&mut self, call: &CallExpr) {
+    call.visit_children_with(self);
+
+    let kind = match &call.callee {
+      Callee::Import(_) => ResolveKind::DynamicImport,
+      Callee::Expr(expr) if self.is_require(expr) => ResolveKind::Require,
+      _ => return,
+    };
+
+    if let Some(ExprOrSpread { spread: None, expr }) = call.args.first() {
+      if let Expr::Lit(Lit::Str(str)) = &**expr {
+        self.imports.push((str.value.to_string(), kind));
+      }
+    }
+  }
+}
+
+/// Whether the statement declares `var` bindings outside of nested functions
+fn contains_var_decl(stmt: &Stmt) -> bool {
+  let mut detector = VarDeclDetector { found: false };
+  stmt.visit_with(&mut detector);
+  detector.found
+}
+
+struct VarDeclDetector {
+  found: bool,
+}
+
+impl Visit for VarDeclDetector {
+  fn visit_var_decl(&mut self, decl: &VarDecl) {
+    self.found |= decl.kind == VarDeclKind::Var;
+  }
+
+  fn visit_function(&mut self, _: &Function) {}
+
+  fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+}
+
+/// The replaced code is parsed from the define value, reset its spans so they do not point into the current module.
+/// Identifiers of the replaced code are globals
+struct ReplacementSpanResetter {
+  unresolved_ctxt: SyntaxContext,
+}
+
+impl VisitMut for ReplacementSpanResetter {
+  fn visit_mut_span(&mut self, span: &mut Span) {
+    *span = DUMMY_SP;
+  }
+
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    expr.visit_mut_children_with(self);
+
+    if let Expr::Ident(ident) = expr {
+      ident.span.ctxt = self.unresolved_ctxt;
+    }
+  }
+}
+
+/// A module without module declarations that uses `module`, `exports` or `require`
+struct CommonJsDetector {
+  unresolved_mark: Mark,
+  is_commonjs: bool,
+}
+
+impl Visit for CommonJsDetector {
+  fn visit_expr(&mut self, expr: &Expr) {
+    if self.is_commonjs {
+      return;
+    }
+
+    if let Expr::Ident(ident) = expr {
+      if ident.span.ctxt.outer() == self.unresolved_mark
+        && matches!(&*ident.sym, "module" | "exports" | "require")
+      {
+        self.is_commonjs = true;
+      }
+      return;
+    }
+
+    expr.visit_children_with(self);
+  }
+}
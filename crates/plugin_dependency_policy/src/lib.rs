@@ -0,0 +1,232 @@
+use std::{
+  collections::{HashMap, HashSet, VecDeque},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  config::{
+    config_regex::ConfigRegex,
+    dependency_policy::{DependencyPolicyConfig, DependencyRule, DependencyRuleSeverity},
+    Config,
+  },
+  context::{
+    diagnostics::{Diagnostic, DiagnosticSeverity},
+    CompilationContext,
+  },
+  error::{CompilationError, Result},
+  module::{module_graph::ModuleGraph, ModuleId},
+  plugin::{Plugin, PluginModuleGraphUpdatedHookParams},
+};
+
+/// [Diagnostic::code] of the reported violations
+pub const DEPENDENCY_POLICY_CODE: &str = "dependency-policy";
+const PLUGIN_NAME: &str = "FarmPluginDependencyPolicy";
+
+/// Check the imports of the module graph against the rules of `dependencyPolicy` after it is built and after every update.
+/// Every violation is added to the diagnostics of the context with the import chain from an entry,
+/// the build fails if a violated rule has the severity `error`.
+pub struct FarmPluginDependencyPolicy {
+  config: DependencyPolicyConfig,
+}
+
+/// An import that is not allowed by a rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyViolation {
+  /// index of the violated rule
+  pub rule: usize,
+  pub source: String,
+  /// the import chain from an entry to the imported module, the last two modules are the importer and the imported module
+  pub chain: Vec<ModuleId>,
+}
+
+impl FarmPluginDependencyPolicy {
+  pub fn new(config: &Config) -> Self {
+    Self {
+      config: config
+        .dependency_policy
+        .as_ref()
+        .map(|c| *c.clone())
+        .unwrap_or_default(),
+    }
+  }
+
+  fn check(&self, context: &Arc<CompilationContext>) -> Result<()> {
+    let violations = find_violations(&context.module_graph.read(), &self.config.rules);
+
+    let mut diagnostics = context.diagnostics.lock();
+    diagnostics.clear_code(DEPENDENCY_POLICY_CODE);
+
+    let mut errors = vec![];
+
+    for violation in violations {
+      let rule = &self.config.rules[violation.rule];
+      let importer = &violation.chain[violation.chain.len() - 2];
+      let mut message = format!(
+        "Import `{}` of `{}` is not allowed by the dependency policy",
+        violation.source,
+        importer.to_string()
+      );
+
+      if let Some(explanation) = &rule.message {
+        message.push_str(&format!(", {explanation}"));
+      }
+
+      message.push_str(&format!(
+        ":\n  {}",
+        violation
+          .chain
+          .iter()
+          .map(|id| id.to_string())
+          .collect::<Vec<_>>()
+          .join(" -> ")
+      ));
+
+      let severity = match rule.severity {
+        DependencyRuleSeverity::Warn => DiagnosticSeverity::Warning,
+        DependencyRuleSeverity::Error => {
+          errors.push(message.clone());
+          DiagnosticSeverity::Error
+        }
+      };
+
+      diagnostics.add(Diagnostic {
+        code: DEPENDENCY_POLICY_CODE.to_string(),
+        severity,
+        message,
+        modules: violation.chain,
+        plugin: Some(PLUGIN_NAME.to_string()),
+      });
+    }
+
+    if !errors.is_empty() {
+      return Err(CompilationError::GenericError(errors.join("\n")));
+    }
+
+    Ok(())
+  }
+}
+
+fn is_match(patterns: &[ConfigRegex], values: &[&str]) -> bool {
+  patterns
+    .iter()
+    .any(|p| values.iter().any(|value| p.is_match(value)))
+}
+
+/// Whether the rule forbids the import of `source` resolved to `dep` by `importer`
+fn is_violated(rule: &DependencyRule, importer: &str, source: &str, dep: &str) -> bool {
+  if !rule.from.is_empty() && !is_match(&rule.from, &[importer]) {
+    return false;
+  }
+
+  (!rule.deny.is_empty() && is_match(&rule.deny, &[source, dep]))
+    || (!rule.allow.is_empty() && !is_match(&rule.allow, &[source, dep]))
+}
+
+/// Find the imports violating the rules, sorted by the importer, the source and the rule.
+/// The chain of every violation goes through the shortest path from the entries to the importer
+pub fn find_violations(
+  module_graph: &ModuleGraph,
+  rules: &[DependencyRule],
+) -> Vec<DependencyViolation> {
+  let importers = shortest_importers(module_graph);
+  let chain_to = |module_id: &ModuleId| {
+    let mut chain = vec![module_id.clone()];
+
+    while let Some(Some(importer)) = importers.get(chain.last().unwrap()) {
+      chain.push(importer.clone());
+    }
+
+    chain.reverse();
+    chain
+  };
+
+  let mut modules = module_graph
+    .modules()
+    .into_iter()
+    .map(|m| m.id.clone())
+    .collect::<Vec<_>>();
+  modules.sort();
+
+  let mut violations = vec![];
+
+  for module_id in modules {
+    let importer = module_id.to_string();
+    let mut deps = module_graph.dependencies(&module_id);
+    deps.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut reported = HashSet::new();
+
+    for (dep, edge) in deps {
+      let dep_id = dep.to_string();
+
+      for item in edge.items() {
+        for (index, rule) in rules.iter().enumerate() {
+          if is_violated(rule, &importer, &item.source, &dep_id)
+            && reported.insert((index, item.source.clone()))
+          {
+            let mut chain = chain_to(&module_id);
+            chain.push(dep.clone());
+
+            violations.push(DependencyViolation {
+              rule: index,
+              source: item.source.clone(),
+              chain,
+            });
+          }
+        }
+      }
+    }
+  }
+
+  violations
+}
+
+/// module id -> its importer on the shortest path from the entries, [None] for the entries
+fn shortest_importers(module_graph: &ModuleGraph) -> HashMap<ModuleId, Option<ModuleId>> {
+  let mut importers = HashMap::new();
+  let mut queue = VecDeque::new();
+
+  let mut entries = module_graph.entries.keys().cloned().collect::<Vec<_>>();
+  entries.sort();
+
+  for entry in entries {
+    importers.insert(entry.clone(), None);
+    queue.push_back(entry);
+  }
+
+  while let Some(module_id) = queue.pop_front() {
+    let mut deps = module_graph.dependencies_ids(&module_id);
+    deps.sort();
+
+    for dep in deps {
+      if !importers.contains_key(&dep) {
+        importers.insert(dep.clone(), Some(module_id.clone()));
+        queue.push_back(dep);
+      }
+    }
+  }
+
+  importers
+}
+
+impl Plugin for FarmPluginDependencyPolicy {
+  fn name(&self) -> &str {
+    PLUGIN_NAME
+  }
+
+  fn build_end(&self, context: &Arc<CompilationContext>) -> Result<Option<()>> {
+    self.check(context)?;
+
+    Ok(Some(()))
+  }
+
+  /// a module updated by hmr may add or remove violating imports
+  fn module_graph_updated(
+    &self,
+    _param: &PluginModuleGraphUpdatedHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    self.check(context)?;
+
+    Ok(Some(()))
+  }
+}
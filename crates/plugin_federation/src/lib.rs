@@ -0,0 +1,477 @@
+use std::{
+  collections::{BTreeMap, HashMap},
+  fmt::Write,
+  path::Path,
+  sync::Arc,
+};
+
+use farmfe_core::{
+  config::{
+    federation::{
+      FederationConfig, FederationExpose, FederationManifest, FederationResource, FederationShared,
+      FEDERATION_MANIFEST_RESOURCE_TYPE,
+    },
+    Config, FARM_MODULE_SYSTEM,
+  },
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  module::{Module, ModuleId, ModuleType},
+  parking_lot::RwLock,
+  plugin::{
+    Plugin, PluginFinalizeResourcesHookParams, PluginHookContext, PluginLoadHookParam,
+    PluginLoadHookResult, PluginResolveHookParam, PluginResolveHookResult, ResolveKind,
+  },
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde_json,
+};
+use farmfe_toolkit::{
+  get_dynamic_resources_map::get_dynamic_resources_map, html::get_farm_global_this,
+};
+
+const PLUGIN_NAME: &str = "FarmPluginFederation";
+/// suffix of the container entry that dynamically imports the exposed modules
+pub const FEDERATION_CONTAINER_SUFFIX: &str = ".farm_federation_container";
+/// suffix of the virtual module that loads a module exposed by a remote build
+pub const FEDERATION_REMOTE_SUFFIX: &str = ".farm_federation_remote";
+
+/// Module federation, see [FederationConfig].
+/// The exposed modules are dynamically imported by a container entry, so each of them is a module group whose resources can be
+/// loaded independently, and every exposed module is pinned to its own resource pot. The manifest lists the resources of the exposed
+/// modules and the shared-dependency negotiation table. Dynamic imports of remote modules are resolved to virtual modules that load
+/// the manifest of the remote build, load the container entry and the resources of the exposed module, and require it from the
+/// module system of the remote build at runtime. Each build keeps its own `runtime.namespace`, so their module ids never collide.
+pub struct FarmPluginFederation {
+  config: FederationConfig,
+  /// exposed module -> expose name, collected when the build ends
+  exposed_modules: RwLock<HashMap<ModuleId, String>>,
+}
+
+impl FarmPluginFederation {
+  pub fn new(config: &Config) -> Self {
+    Self {
+      config: config
+        .federation
+        .as_ref()
+        .map(|c| *c.clone())
+        .unwrap_or_default(),
+      exposed_modules: RwLock::new(HashMap::new()),
+    }
+  }
+
+  fn container_source(&self) -> String {
+    format!("{}{FEDERATION_CONTAINER_SUFFIX}", self.config.name)
+  }
+
+  /// absolute path of the exposed module, it's also the source the container imports it by
+  fn expose_source(&self, path: &str, root: &str) -> String {
+    Path::new(root).join(path).to_string_lossy().to_string()
+  }
+
+  fn container_code(&self, root: &str) -> String {
+    let mut code = String::from("export default {\n");
+
+    for (expose, path) in &self.config.exposes {
+      let _ = writeln!(
+        code,
+        "  {}: () => import({}),",
+        serde_json::to_string(expose).unwrap(),
+        serde_json::to_string(&self.expose_source(path, root)).unwrap()
+      );
+    }
+
+    code.push_str("};\n");
+    code
+  }
+
+  fn remote_code(&self, source: &str, context: &Arc<CompilationContext>) -> Option<String> {
+    let (remote, expose) = self.config.parse_remote_source(source)?;
+    let farm_global_this = get_farm_global_this(
+      &context.config.runtime.namespace,
+      &context.config.output.target_env,
+    );
+    let global_this = if context.config.output.target_env.is_node() {
+      "global"
+    } else {
+      "window"
+    };
+
+    Some(
+      include_str!("remote_module.ts")
+        .replace(
+          "'FARM_MODULE_SYSTEM'",
+          &format!("{farm_global_this}.{FARM_MODULE_SYSTEM}"),
+        )
+        .replace("'FARM_GLOBAL_THIS'", global_this)
+        .replace(
+          "'FARM_MODULE_SYSTEM_KEY'",
+          &serde_json::to_string(FARM_MODULE_SYSTEM).unwrap(),
+        )
+        .replace(
+          "'REMOTE_URL'",
+          &serde_json::to_string(&self.config.remotes[remote]).unwrap(),
+        )
+        .replace("'EXPOSE'", &serde_json::to_string(&expose).unwrap()),
+    )
+  }
+
+  /// import source in `shared` -> the module it's resolved to in this build
+  fn shared_modules(
+    &self,
+    context: &Arc<CompilationContext>,
+  ) -> BTreeMap<String, FederationShared> {
+    let module_graph = context.module_graph.read();
+    let mut shared = BTreeMap::new();
+
+    for module in module_graph.modules() {
+      for (dep, edge) in module_graph.dependencies(&module.id) {
+        for item in edge.items() {
+          let Some(config) = self.config.shared.get(&item.source) else {
+            continue;
+          };
+
+          if shared.contains_key(&item.source) {
+            continue;
+          }
+
+          let dep = module_graph.module(&dep).unwrap();
+          let version = dep.package_version.clone();
+
+          shared.insert(
+            item.source.clone(),
+            FederationShared {
+              module_id: dep.id.id(context.config.mode.clone()),
+              required_version: config
+                .required_version
+                .clone()
+                .unwrap_or_else(|| format!("^{version}")),
+              version,
+              singleton: config.singleton,
+            },
+          );
+        }
+      }
+    }
+
+    shared
+  }
+
+  /// js and css resources of the container entry, including the runtime of the build
+  fn entry_resources(
+    &self,
+    resources_map: &HashMap<String, Resource>,
+    context: &Arc<CompilationContext>,
+  ) -> Vec<FederationResource> {
+    let module_graph = context.module_graph.read();
+    let module_group_graph = context.module_group_graph.read();
+    let resource_pot_map = context.resource_pot_map.read();
+    let Some(module_group) = module_graph
+      .entries
+      .iter()
+      .find(|(_, name)| **name == self.config.name)
+      .and_then(|(container, _)| module_group_graph.module_group(container))
+    else {
+      return vec![];
+    };
+
+    let mut resources = module_group
+      .resource_pots()
+      .iter()
+      .filter_map(|id| resource_pot_map.resource_pot(id))
+      .flat_map(|resource_pot| resource_pot.resources())
+      .filter_map(|name| {
+        let resource = resources_map.get(name)?;
+        matches!(resource.resource_type, ResourceType::Js | ResourceType::Css)
+          .then(|| federation_resource(name, &resource.resource_type))
+      })
+      .collect::<Vec<_>>();
+    // stylesheets first, the same as the injected resources of the entries
+    resources.sort_by(|a, b| (b.resource_type, &a.path).cmp(&(a.resource_type, &b.path)));
+
+    resources
+  }
+
+  fn exposes(
+    &self,
+    resources_map: &HashMap<String, Resource>,
+    context: &Arc<CompilationContext>,
+  ) -> BTreeMap<String, FederationExpose> {
+    let module_graph = context.module_graph.read();
+    let module_group_graph = context.module_group_graph.read();
+    let resource_pot_map = context.resource_pot_map.read();
+    let Some((container, _)) = module_graph
+      .entries
+      .iter()
+      .find(|(_, name)| **name == self.config.name)
+    else {
+      return BTreeMap::new();
+    };
+
+    let dynamic_resources_map = get_dynamic_resources_map(
+      &module_group_graph,
+      container,
+      &resource_pot_map,
+      |name| resources_map.get(name).map(|r| r.resource_type.clone()),
+      &module_graph,
+    );
+
+    self
+      .exposed_modules
+      .read()
+      .iter()
+      .map(|(module_id, expose)| {
+        let resources = dynamic_resources_map
+          .get(module_id)
+          .into_iter()
+          .flatten()
+          .map(|(path, resource_type)| federation_resource(path, resource_type))
+          .collect();
+
+        (
+          expose.clone(),
+          FederationExpose {
+            module_id: module_id.id(context.config.mode.clone()),
+            resources,
+          },
+        )
+      })
+      .collect()
+  }
+
+  /// The remote modules replace their shared modules by the modules of the consumer, so the table of the consumer is set to
+  /// the module system by the initial js resources of the entries, including the scripts of the html entries
+  fn inject_shared_table(
+    &self,
+    shared: &BTreeMap<String, FederationShared>,
+    param: &mut PluginFinalizeResourcesHookParams,
+    context: &Arc<CompilationContext>,
+  ) {
+    let module_graph = context.module_graph.read();
+    let module_group_graph = context.module_group_graph.read();
+    let resource_pot_map = context.resource_pot_map.read();
+    let farm_global_this = get_farm_global_this(
+      &context.config.runtime.namespace,
+      &context.config.output.target_env,
+    );
+    let code = format!(
+      "{farm_global_this}.{FARM_MODULE_SYSTEM}.federationShared={};",
+      serde_json::to_string(shared).unwrap()
+    );
+
+    for entry in module_graph.entries.keys() {
+      let Some(module_group) = module_group_graph.module_group(entry) else {
+        continue;
+      };
+
+      for resource_pot_id in module_group.resource_pots() {
+        let Some(resource_pot) = resource_pot_map.resource_pot(resource_pot_id) else {
+          continue;
+        };
+
+        for resource_name in resource_pot.resources() {
+          if let Some(resource) = param.resources_map.get_mut(resource_name) {
+            if matches!(resource.resource_type, ResourceType::Js) {
+              resource.bytes.extend(code.as_bytes());
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+fn federation_resource(path: &str, resource_type: &ResourceType) -> FederationResource {
+  FederationResource {
+    path: path.to_string(),
+    resource_type: if matches!(resource_type, ResourceType::Css) {
+      1
+    } else {
+      0
+    },
+  }
+}
+
+impl Plugin for FarmPluginFederation {
+  fn name(&self) -> &str {
+    PLUGIN_NAME
+  }
+
+  fn config(&self, config: &mut Config) -> Result<Option<()>> {
+    if self.config.exposes.is_empty() {
+      return Ok(None);
+    }
+
+    if self.config.name.is_empty() {
+      return Err(CompilationError::GenericError(
+        "federation.name is required to expose modules".to_string(),
+      ));
+    }
+
+    config
+      .input
+      .insert(self.config.name.clone(), self.container_source());
+
+    Ok(Some(()))
+  }
+
+  fn resolve(
+    &self,
+    param: &PluginResolveHookParam,
+    context: &Arc<CompilationContext>,
+    hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginResolveHookResult>> {
+    if hook_context.contain_caller(PLUGIN_NAME) {
+      return Ok(None);
+    }
+
+    if param.source.ends_with(FEDERATION_CONTAINER_SUFFIX) {
+      return Ok(Some(PluginResolveHookResult {
+        resolved_path: param.source.clone(),
+        ..Default::default()
+      }));
+    }
+
+    if param
+      .importer
+      .as_ref()
+      .is_some_and(|i| i.to_string().ends_with(FEDERATION_CONTAINER_SUFFIX))
+    {
+      // resolve the exposed modules as static imports, so they are not compiled lazily
+      return context.plugin_driver.resolve(
+        &PluginResolveHookParam {
+          kind: ResolveKind::Import,
+          ..param.clone()
+        },
+        context,
+        &PluginHookContext {
+          caller: hook_context.add_caller(PLUGIN_NAME),
+          ..hook_context.clone()
+        },
+      );
+    }
+
+    if self.config.parse_remote_source(&param.source).is_none() {
+      return Ok(None);
+    }
+
+    // the remote module is loaded asynchronously at runtime
+    if !matches!(param.kind, ResolveKind::DynamicImport) {
+      return Err(CompilationError::GenericError(format!(
+        "Remote module `{}` should be imported dynamically, e.g. `import('{}')`",
+        param.source, param.source
+      )));
+    }
+
+    Ok(Some(PluginResolveHookResult {
+      resolved_path: format!("{}{FEDERATION_REMOTE_SUFFIX}", param.source),
+      ..Default::default()
+    }))
+  }
+
+  fn load(
+    &self,
+    param: &PluginLoadHookParam,
+    context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginLoadHookResult>> {
+    if param.resolved_path.ends_with(FEDERATION_CONTAINER_SUFFIX) {
+      return Ok(Some(PluginLoadHookResult {
+        content: self.container_code(&context.config.root),
+        module_type: ModuleType::Js,
+        source_map: None,
+      }));
+    }
+
+    let Some(source) = param.resolved_path.strip_suffix(FEDERATION_REMOTE_SUFFIX) else {
+      return Ok(None);
+    };
+
+    Ok(
+      self
+        .remote_code(source, context)
+        .map(|content| PluginLoadHookResult {
+          content,
+          module_type: ModuleType::Ts,
+          source_map: None,
+        }),
+    )
+  }
+
+  fn build_end(&self, context: &Arc<CompilationContext>) -> Result<Option<()>> {
+    if self.config.exposes.is_empty() {
+      return Ok(None);
+    }
+
+    let module_graph = context.module_graph.read();
+    let Some((container, _)) = module_graph
+      .entries
+      .iter()
+      .find(|(_, name)| **name == self.config.name)
+    else {
+      return Ok(None);
+    };
+    let sources = self
+      .config
+      .exposes
+      .iter()
+      .map(|(expose, path)| (self.expose_source(path, &context.config.root), expose))
+      .collect::<HashMap<_, _>>();
+    let mut exposed_modules = self.exposed_modules.write();
+
+    for (dep, edge) in module_graph.dependencies(container) {
+      if let Some(expose) = edge.items().iter().find_map(|i| sources.get(&i.source)) {
+        exposed_modules.insert(dep, expose.to_string());
+      }
+    }
+
+    Ok(Some(()))
+  }
+
+  fn partition_resource_pots(
+    &self,
+    module: &Module,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<String>> {
+    Ok(
+      self
+        .exposed_modules
+        .read()
+        .get(&module.id)
+        .map(|expose| self.config.expose_resource_pot_name(expose)),
+    )
+  }
+
+  fn finalize_resources(
+    &self,
+    param: &mut PluginFinalizeResourcesHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    let shared = self.shared_modules(context);
+
+    if !self.config.remotes.is_empty() && !shared.is_empty() {
+      self.inject_shared_table(&shared, param, context);
+    }
+
+    let manifest = FederationManifest {
+      name: self.config.name.clone(),
+      public_path: context.config.output.public_path.clone(),
+      namespace: context.config.runtime.namespace.clone(),
+      entry: self.entry_resources(param.resources_map, context),
+      exposes: self.exposes(param.resources_map, context),
+      shared,
+    };
+    let filename = &self.config.manifest;
+
+    param.resources_map.insert(
+      filename.to_string(),
+      Resource {
+        name: filename.to_string(),
+        bytes: serde_json::to_vec_pretty(&manifest).unwrap(),
+        resource_type: ResourceType::Custom(FEDERATION_MANIFEST_RESOURCE_TYPE.to_string()),
+        origin: ResourceOrigin::ResourcePot(filename.to_string()),
+        ..Default::default()
+      },
+    );
+
+    Ok(Some(()))
+  }
+}
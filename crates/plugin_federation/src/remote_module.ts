@@ -0,0 +1,183 @@
+interface Resource {
+  path: string;
+  type: 0 | 1; // 0: script, 1: link
+}
+
+interface FederationShared {
+  moduleId: string;
+  version: string;
+  singleton: boolean;
+  requiredVersion: string;
+}
+
+interface FederationManifest {
+  name: string;
+  publicPath: string;
+  namespace: string;
+  entry: Resource[];
+  exposes: Record<string, { moduleId: string; resources: Resource[] }>;
+  shared: Record<string, FederationShared>;
+}
+
+// Inject during compile time
+const FarmModuleSystem: any = 'FARM_MODULE_SYSTEM';
+const farmGlobalThis: any = 'FARM_GLOBAL_THIS';
+const moduleSystemKey: string = 'FARM_MODULE_SYSTEM_KEY';
+const remoteUrl: string = 'REMOTE_URL';
+const expose: string = 'EXPOSE';
+
+if (FarmModuleSystem.federationManifests === undefined) {
+  FarmModuleSystem.federationManifests = new Map<
+    string,
+    Promise<FederationManifest>
+  >();
+}
+
+function fetchManifest(url: string): Promise<FederationManifest> {
+  const manifests = FarmModuleSystem.federationManifests;
+
+  if (!manifests.has(url)) {
+    manifests.set(
+      url,
+      fetch(url).then((res) => {
+        if (!res.ok) {
+          throw new Error(
+            `[Farm] Failed to load federation manifest "${url}": ${res.status}`
+          );
+        }
+
+        return res.json();
+      })
+    );
+  }
+
+  return manifests.get(url);
+}
+
+function parseVersion(version: string): number[] {
+  return version
+    .replace(/^[=v\s]+/, '')
+    .split('-')[0]
+    .split('.')
+    .map((n) => parseInt(n, 10) || 0);
+}
+
+function compareVersion(a: number[], b: number[]): number {
+  for (let i = 0; i < 3; i++) {
+    if ((a[i] || 0) !== (b[i] || 0)) {
+      return (a[i] || 0) - (b[i] || 0);
+    }
+  }
+
+  return 0;
+}
+
+// supports `*`, `^1.2.3`, `~1.2.3`, `>=1.2.3`, `>1.2.3`, `<=1.2.3`, `<1.2.3` and `1.2.3`
+function satisfies(version: string, range: string): boolean {
+  if (!range || range === '*' || range === '^') {
+    return true;
+  }
+
+  const op = /^(\^|~|>=|>|<=|<|=)?/.exec(range)![0];
+  const v = parseVersion(version);
+  const r = parseVersion(range.slice(op.length));
+  const cmp = compareVersion(v, r);
+
+  switch (op) {
+    case '^':
+      if (r[0] > 0) {
+        return cmp >= 0 && v[0] === r[0];
+      }
+
+      return cmp >= 0 && v[0] === 0 && v[1] === r[1];
+    case '~':
+      return cmp >= 0 && v[0] === r[0] && v[1] === r[1];
+    case '>=':
+      return cmp >= 0;
+    case '>':
+      return cmp > 0;
+    case '<=':
+      return cmp <= 0;
+    case '<':
+      return cmp < 0;
+    default:
+      return cmp === 0;
+  }
+}
+
+// the shared modules of the remote are replaced by the modules of the consumer when the versions are compatible,
+// singletons are always replaced
+function negotiateShared(manifest: FederationManifest, remoteModuleSystem: any) {
+  const localShared: Record<string, FederationShared> =
+    FarmModuleSystem.federationShared || {};
+
+  for (const source of Object.keys(manifest.shared)) {
+    const remote = manifest.shared[source];
+    const local = localShared[source];
+
+    if (!local) {
+      continue;
+    }
+
+    const compatible = satisfies(local.version, remote.requiredVersion);
+
+    if (!compatible && (local.singleton || remote.singleton)) {
+      console.warn(
+        `[Farm] Shared singleton "${source}@${local.version}" does not satisfy "${remote.requiredVersion}" required by remote "${manifest.name}"`
+      );
+    }
+
+    if (compatible || local.singleton || remote.singleton) {
+      // the module of the consumer lives in the module system of the consumer
+      remoteModuleSystem.update(remote.moduleId, function (module: any) {
+        module.exports = FarmModuleSystem.require(local.moduleId);
+      });
+    }
+  }
+}
+
+const baseUrl =
+  typeof location !== 'undefined' ? location.href : 'http://localhost/';
+const manifestUrl = new URL(remoteUrl, baseUrl).href;
+
+const promise = fetchManifest(manifestUrl).then((manifest) => {
+  const exposed = manifest.exposes[expose];
+
+  if (!exposed) {
+    throw new Error(
+      `[Farm] Module "${expose}" is not exposed by remote "${manifest.name}"`
+    );
+  }
+
+  const publicPath = new URL(manifest.publicPath || './', manifestUrl).href;
+  const load = (resource: Resource) =>
+    FarmModuleSystem.resourceLoader.load({
+      ...resource,
+      path: new URL(resource.path, publicPath).href
+    });
+
+  // the container entry sets up the module system of the remote build, the exposed modules are registered in it
+  return (manifest.entry || [])
+    .reduce(
+      (prev, resource) => prev.then(() => load(resource)),
+      Promise.resolve()
+    )
+    .then(() => Promise.all(exposed.resources.map(load)))
+    .then(() => {
+      const remoteModuleSystem =
+        farmGlobalThis[manifest.namespace] &&
+        farmGlobalThis[manifest.namespace][moduleSystemKey];
+
+      if (!remoteModuleSystem) {
+        throw new Error(
+          `[Farm] The module system of remote "${manifest.name}" is not initialized, namespace: "${manifest.namespace}"`
+        );
+      }
+
+      negotiateShared(manifest, remoteModuleSystem);
+      return remoteModuleSystem.require(exposed.moduleId);
+    });
+});
+
+export const __farm_async = true;
+export default promise;
@@ -0,0 +1,295 @@
+//! Generate the favicon / apple touch icon / manifest icon set from a single source image configured by `html.icons`,
+//! and inject the corresponding `<link>` tags into html entries. The source image is watched by the html entries,
+//! and the icons are only generated again when the content of the source image changes.
+use std::{collections::HashMap, io::Cursor, path::PathBuf, sync::Arc};
+
+use farmfe_core::{
+  config::html::HtmlIconsConfig,
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  module::ModuleId,
+  relative_path::RelativePath,
+  resource::{Resource, ResourceOrigin, ResourceType},
+  swc_html_ast::{Child, Document, Element},
+};
+use farmfe_toolkit::{
+  fs::transform_output_filename_with_hash,
+  html::create_element,
+  swc_html_visit::{VisitMut, VisitMutWith},
+};
+use image::{imageops::FilterType, ImageFormat};
+
+#[derive(Debug, Clone)]
+pub struct GeneratedIcon {
+  pub resource_name: String,
+  pub rel: &'static str,
+  pub size: u32,
+  /// linked in html entries, the manifest icons are referenced by the web app manifest instead
+  pub injected: bool,
+}
+
+impl GeneratedIcon {
+  fn to_link(&self, public_path: &str) -> Element {
+    let sizes = format!("{0}x{0}", self.size);
+    let href = format!("{public_path}{}", self.resource_name);
+    let mut attrs = vec![
+      ("rel", self.rel),
+      ("sizes", sizes.as_str()),
+      ("href", &href),
+    ];
+
+    if self.rel == "icon" {
+      attrs.insert(1, ("type", "image/png"));
+    }
+
+    create_element("link", None, attrs)
+  }
+}
+
+/// (file name, rel, size, injected into html)
+const ICON_SET: [(&str, &str, u32, bool); 5] = [
+  ("favicon-16x16", "icon", 16, true),
+  ("favicon-32x32", "icon", 32, true),
+  ("apple-touch-icon", "apple-touch-icon", 180, true),
+  // manifest icons are emitted but not linked, they are referenced by the web app manifest
+  ("icon-192x192", "manifest-icon", 192, false),
+  ("icon-512x512", "manifest-icon", 512, false),
+];
+
+/// The icons generated from the source image of the previous build
+pub struct CachedIcons {
+  source_hash: String,
+  icons: Vec<GeneratedIcon>,
+  resources: HashMap<String, Resource>,
+}
+
+fn icons_source_path(config: &HtmlIconsConfig, context: &Arc<CompilationContext>) -> PathBuf {
+  RelativePath::new(&config.source).to_logical_path(&context.config.root)
+}
+
+/// Add the source image to the watch graph of the html entry, so editing it updates the html entry and generates the icons again
+pub fn watch_icons_source(
+  config: &HtmlIconsConfig,
+  html_resolved_path: &str,
+  context: &Arc<CompilationContext>,
+) -> Result<()> {
+  let root = &context.config.root;
+  let source_path = icons_source_path(config, context);
+
+  context.add_watch_files(
+    ModuleId::new(html_resolved_path, "", root),
+    vec![ModuleId::new(&source_path.to_string_lossy(), "", root)],
+  )
+}
+
+/// Generate the icons, or reuse the icons in `cache` if the content of the source image is not changed
+pub fn generate_icons(
+  config: &HtmlIconsConfig,
+  cache: &mut Option<CachedIcons>,
+  context: &Arc<CompilationContext>,
+) -> Result<(Vec<GeneratedIcon>, HashMap<String, Resource>)> {
+  let generate_error = |msg: String| {
+    CompilationError::GenericError(format!(
+      "Generate icons from {} failed: {msg}",
+      config.source
+    ))
+  };
+  let source_bytes = std::fs::read(icons_source_path(config, context))
+    .map_err(|e| generate_error(format!("{e:?}")))?;
+  let source_hash = context.config.hash.hash(&source_bytes, 16);
+
+  if let Some(cached) = cache.as_ref().filter(|c| c.source_hash == source_hash) {
+    return Ok((cached.icons.clone(), cached.resources.clone()));
+  }
+
+  let source =
+    image::load_from_memory(&source_bytes).map_err(|e| generate_error(format!("{e:?}")))?;
+
+  let mut icons = vec![];
+  let mut resources = HashMap::new();
+
+  for (name, rel, size, inject) in ICON_SET {
+    let mut bytes = Cursor::new(vec![]);
+    source
+      .resize_exact(size, size, FilterType::Lanczos3)
+      .write_to(&mut bytes, ImageFormat::Png)
+      .map_err(|e| generate_error(format!("{e:?}")))?;
+    let bytes = bytes.into_inner();
+
+    let resource_name = transform_output_filename_with_hash(
+      context.config.output.assets_filename.clone(),
+      name,
+      || context.config.hash.hash(&bytes, 8),
+      "png",
+    );
+
+    icons.push(GeneratedIcon {
+      resource_name: resource_name.clone(),
+      rel,
+      size,
+      injected: inject,
+    });
+
+    resources.insert(
+      resource_name.clone(),
+      Resource {
+        name: resource_name.clone(),
+        bytes,
+        emitted: false,
+        resource_type: ResourceType::Asset("png".to_string()),
+        origin: ResourceOrigin::ResourcePot(resource_name),
+        source_path: None,
+        scope: Default::default(),
+        info: None,
+      },
+    );
+  }
+
+  *cache = Some(CachedIcons {
+    source_hash,
+    icons: icons.clone(),
+    resources: resources.clone(),
+  });
+
+  Ok((icons, resources))
+}
+
+pub struct IconsInjector<'a> {
+  pub icons: &'a Vec<GeneratedIcon>,
+  pub public_path: &'a str,
+}
+
+impl<'a> IconsInjector<'a> {
+  pub fn inject(&mut self, ast: &mut Document) {
+    ast.visit_mut_with(self);
+  }
+}
+
+impl<'a> VisitMut for IconsInjector<'a> {
+  fn visit_mut_element(&mut self, element: &mut Element) {
+    if element.tag_name == "head" {
+      for icon in self.icons.iter().filter(|icon| icon.injected) {
+        element
+          .children
+          .push(Child::Element(icon.to_link(self.public_path)));
+      }
+
+      return;
+    }
+
+    element.visit_mut_children_with(self);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+  };
+
+  use farmfe_core::{
+    config::{html::HtmlIconsConfig, Config},
+    context::CompilationContext,
+    module::ModuleId,
+  };
+  use image::{ImageFormat, Rgb, RgbImage};
+
+  use super::{generate_icons, watch_icons_source};
+
+  fn write_source(root: &Path, color: [u8; 3]) {
+    RgbImage::from_pixel(64, 64, Rgb(color))
+      .save_with_format(root.join("logo.png"), ImageFormat::Png)
+      .unwrap();
+  }
+
+  fn create_context(name: &str) -> (PathBuf, Arc<CompilationContext>) {
+    let root = std::env::temp_dir().join(format!("farm-html-icons-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    let context = CompilationContext::new(
+      Config {
+        root: root.to_string_lossy().to_string(),
+        ..Default::default()
+      },
+      vec![],
+    )
+    .unwrap();
+
+    (root, Arc::new(context))
+  }
+
+  fn config() -> HtmlIconsConfig {
+    HtmlIconsConfig {
+      source: "logo.png".to_string(),
+    }
+  }
+
+  #[test]
+  fn generate_icon_set() {
+    let (root, context) = create_context("generate");
+    write_source(&root, [255, 0, 0]);
+
+    let (icons, resources) = generate_icons(&config(), &mut None, &context).unwrap();
+
+    assert_eq!(
+      icons
+        .iter()
+        .map(|icon| (icon.resource_name.as_str(), icon.rel, icon.injected))
+        .collect::<Vec<_>>(),
+      vec![
+        ("favicon-16x16.png", "icon", true),
+        ("favicon-32x32.png", "icon", true),
+        ("apple-touch-icon.png", "apple-touch-icon", true),
+        ("icon-192x192.png", "manifest-icon", false),
+        ("icon-512x512.png", "manifest-icon", false),
+      ]
+    );
+
+    for icon in &icons {
+      let image = image::load_from_memory(&resources[&icon.resource_name].bytes).unwrap();
+      assert_eq!((image.width(), image.height()), (icon.size, icon.size));
+    }
+
+    std::fs::remove_dir_all(root).unwrap();
+  }
+
+  #[test]
+  fn reuse_icons_of_unchanged_source() {
+    let (root, context) = create_context("cache");
+    write_source(&root, [255, 0, 0]);
+
+    let mut cache = None;
+    let (_, resources) = generate_icons(&config(), &mut cache, &context).unwrap();
+    let red_hash = cache.as_ref().unwrap().source_hash.clone();
+
+    // the cached icons are returned as long as the source is not changed
+    cache.as_mut().unwrap().icons.truncate(1);
+    let (icons, _) = generate_icons(&config(), &mut cache, &context).unwrap();
+    assert_eq!(icons.len(), 1);
+
+    write_source(&root, [0, 0, 255]);
+    let (icons, new_resources) = generate_icons(&config(), &mut cache, &context).unwrap();
+    assert_eq!(icons.len(), 5);
+    assert_ne!(cache.as_ref().unwrap().source_hash, red_hash);
+    assert_ne!(
+      new_resources["favicon-16x16.png"].bytes,
+      resources["favicon-16x16.png"].bytes
+    );
+
+    std::fs::remove_dir_all(root).unwrap();
+  }
+
+  #[test]
+  fn watch_source_image() {
+    let (root, context) = create_context("watch");
+    let html = root.join("index.html").to_string_lossy().to_string();
+
+    watch_icons_source(&config(), &html, &context).unwrap();
+
+    let watch_graph = context.watch_graph.read();
+    assert_eq!(
+      watch_graph.dependencies(&ModuleId::new(&html, "", &context.config.root)),
+      vec![&ModuleId::new("logo.png", "", &context.config.root)]
+    );
+  }
+}
@@ -0,0 +1,278 @@
+//! Expand `<!-- @include ./partials/head.html -->` in html entries with the content of the partial.
+//! Partials are resolved by the resolve hook with [HTML_INCLUDE_RESOLVE_KIND] first, so plugins can provide their own include resolver,
+//! and fallback to the path relative to the including file. All partials are added to the watch graph of the html module.
+use std::{
+  path::{Component, Path, PathBuf},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  module::ModuleId,
+  plugin::{PluginHookContext, PluginResolveHookParam, ResolveKind},
+  regex::Regex,
+};
+use farmfe_toolkit::{fs::read_file_utf8, lazy_static::lazy_static};
+
+pub const HTML_INCLUDE_RESOLVE_KIND: &str = "html:include";
+
+lazy_static! {
+  static ref INCLUDE_REGEX: Regex = Regex::new(r"<!--\s*@include\s+(\S+)\s*-->").unwrap();
+}
+
+pub fn expand_html_includes(
+  content: &str,
+  html_resolved_path: &str,
+  context: &Arc<CompilationContext>,
+) -> Result<String> {
+  if !INCLUDE_REGEX.is_match(content) {
+    return Ok(content.to_string());
+  }
+
+  let mut partials = vec![];
+  let mut stack = vec![normalize_path(Path::new(html_resolved_path))];
+  let result = expand(
+    content,
+    html_resolved_path,
+    context,
+    &mut stack,
+    &mut partials,
+  )?;
+
+  let html_id = ModuleId::new(html_resolved_path, "", &context.config.root);
+  context.add_watch_files(
+    html_id,
+    partials
+      .iter()
+      .map(|p: &PathBuf| ModuleId::new(&p.to_string_lossy(), "", &context.config.root))
+      .collect(),
+  )?;
+
+  Ok(result)
+}
+
+fn expand(
+  content: &str,
+  importer: &str,
+  context: &Arc<CompilationContext>,
+  stack: &mut Vec<PathBuf>,
+  partials: &mut Vec<PathBuf>,
+) -> Result<String> {
+  let mut result = String::with_capacity(content.len());
+  let mut last = 0;
+
+  for cap in INCLUDE_REGEX.captures_iter(content) {
+    let whole = cap.get(0).unwrap();
+    let source = &cap[1];
+    result.push_str(&content[last..whole.start()]);
+    last = whole.end();
+
+    let partial = resolve_partial(source, importer, context)?;
+
+    if stack.contains(&partial) {
+      return Err(CompilationError::TransformError {
+        resolved_path: importer.to_string(),
+        msg: format!(
+          "Circular html include: {} -> {}",
+          stack
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join(" -> "),
+          partial.to_string_lossy()
+        ),
+      });
+    }
+
+    let partial_path = partial.to_string_lossy().to_string();
+    let partial_content = read_file_utf8(&partial_path)?;
+
+    if !partials.contains(&partial) {
+      partials.push(partial.clone());
+    }
+
+    stack.push(partial);
+    result.push_str(&expand(
+      &partial_content,
+      &partial_path,
+      context,
+      stack,
+      partials,
+    )?);
+    stack.pop();
+  }
+
+  result.push_str(&content[last..]);
+
+  Ok(result)
+}
+
+fn resolve_partial(
+  source: &str,
+  importer: &str,
+  context: &Arc<CompilationContext>,
+) -> Result<PathBuf> {
+  let resolved = context.plugin_driver.resolve(
+    &PluginResolveHookParam {
+      source: source.to_string(),
+      importer: Some(ModuleId::new(importer, "", &context.config.root)),
+      kind: ResolveKind::Custom(HTML_INCLUDE_RESOLVE_KIND.to_string()),
+    },
+    context,
+    &PluginHookContext::default(),
+  );
+
+  if let Ok(Some(resolved)) = resolved {
+    if !resolved.external {
+      return Ok(normalize_path(Path::new(&resolved.resolved_path)));
+    }
+  }
+
+  let base = Path::new(importer).parent().unwrap_or(Path::new(""));
+  let partial = normalize_path(&base.join(source));
+
+  if partial.exists() {
+    Ok(partial)
+  } else {
+    Err(CompilationError::TransformError {
+      resolved_path: importer.to_string(),
+      msg: format!("Can not resolve html include `{source}`"),
+    })
+  }
+}
+
+/// Remove the `.` and `..` components lexically, so the same partial included by different relative paths, e.g. `./b.html`
+/// and `./partials/../b.html`, is detected as a cycle
+fn normalize_path(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+
+  for component in path.components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir
+        if matches!(
+          normalized.components().next_back(),
+          Some(Component::Normal(_))
+        ) =>
+      {
+        normalized.pop();
+      }
+      component => normalized.push(component),
+    }
+  }
+
+  normalized
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{path::PathBuf, sync::Arc};
+
+  use farmfe_core::{config::Config, context::CompilationContext, module::ModuleId};
+
+  use super::expand_html_includes;
+
+  fn create_context(name: &str, files: &[(&str, &str)]) -> (PathBuf, Arc<CompilationContext>) {
+    let root =
+      std::env::temp_dir().join(format!("farm-html-include-{name}-{}", std::process::id()));
+
+    for (path, content) in files {
+      let path = root.join(path);
+      std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+      std::fs::write(path, content).unwrap();
+    }
+
+    let context = CompilationContext::new(
+      Config {
+        root: root.to_string_lossy().to_string(),
+        ..Default::default()
+      },
+      vec![],
+    )
+    .unwrap();
+
+    (root, Arc::new(context))
+  }
+
+  #[test]
+  fn expand_nested_includes() {
+    let (root, context) = create_context(
+      "nested",
+      &[
+        (
+          "partials/head.html",
+          "<title>farm</title><!-- @include ./meta.html -->",
+        ),
+        ("partials/meta.html", r#"<meta charset="utf-8">"#),
+      ],
+    );
+    let html = root.join("index.html").to_string_lossy().to_string();
+
+    let content = expand_html_includes(
+      "<head><!-- @include ./partials/head.html --></head><body></body>",
+      &html,
+      &context,
+    )
+    .unwrap();
+    assert_eq!(
+      content,
+      r#"<head><title>farm</title><meta charset="utf-8"></head><body></body>"#
+    );
+
+    // the nested partials are watched by the html entry as well
+    let watch_graph = context.watch_graph.read();
+    let mut watched = watch_graph
+      .dependencies(&ModuleId::new(&html, "", &context.config.root))
+      .into_iter()
+      .map(|id| id.relative_path().to_string())
+      .collect::<Vec<_>>();
+    watched.sort();
+    assert_eq!(watched, vec!["partials/head.html", "partials/meta.html"]);
+
+    drop(watch_graph);
+    std::fs::remove_dir_all(root).unwrap();
+  }
+
+  #[test]
+  fn include_errors() {
+    let (root, context) = create_context(
+      "errors",
+      &[
+        ("a.html", "<!-- @include ./b.html -->"),
+        ("b.html", "<!-- @include ./a.html -->"),
+        ("c.html", "<!-- @include ./partials/../c.html -->"),
+        ("partials/d.html", ""),
+      ],
+    );
+    let html = root.join("index.html").to_string_lossy().to_string();
+
+    let err = expand_html_includes("<!-- @include ./a.html -->", &html, &context)
+      .unwrap_err()
+      .to_string();
+    assert!(err.contains("Circular html include"), "{err}");
+
+    // the cycle is detected when the partial is included by another relative path
+    let err = expand_html_includes("<!-- @include ./c.html -->", &html, &context)
+      .unwrap_err()
+      .to_string();
+    assert!(err.contains("Circular html include"), "{err}");
+    assert!(!err.contains(".."), "{err}");
+
+    let err = expand_html_includes("<!-- @include ./missing.html -->", &html, &context)
+      .unwrap_err()
+      .to_string();
+    assert!(
+      err.contains("Can not resolve html include `./missing.html`"),
+      "{err}"
+    );
+
+    // html without includes is returned as is
+    assert_eq!(
+      expand_html_includes("<div></div>", &html, &context).unwrap(),
+      "<div></div>"
+    );
+
+    std::fs::remove_dir_all(root).unwrap();
+  }
+}
@@ -5,39 +5,54 @@ use std::{collections::HashMap, path::PathBuf};
 
 use absolute_path_handler::AbsolutePathHandler;
 use deps_analyzer::{DepsAnalyzer, HtmlInlineModule, HTML_INLINE_ID_PREFIX};
+use icons::{generate_icons, watch_icons_source, CachedIcons, IconsInjector};
+use include::expand_html_includes;
+use manifest::{generate_manifest, ManifestInjector};
 // use farmfe_core::config::minify::MinifyOptions;
 use farmfe_core::parking_lot::Mutex;
 use farmfe_core::{cache_item, deserialize, serialize};
 use farmfe_core::{
-  config::Config,
-  context::CompilationContext,
+  config::{html::HtmlNoscriptCssConfig, Config},
+  context::{
+    diagnostics::{Diagnostic, DiagnosticSeverity},
+    CompilationContext,
+  },
   error::CompilationError,
   module::{HtmlModuleMetaData, ModuleId, ModuleMetaData, ModuleType},
   plugin::{
-    Plugin, PluginAnalyzeDepsHookParam, PluginFinalizeResourcesHookParams,
-    PluginGenerateResourcesHookResult, PluginHookContext, PluginLoadHookParam,
-    PluginLoadHookResult, PluginParseHookParam, PluginResolveHookParam, PluginResolveHookResult,
-    PluginTransformHookResult, ResolveKind,
+    Plugin, PluginAnalyzeDepsHookParam, PluginAnalyzeHtmlHookParam,
+    PluginFinalizeResourcesHookParams, PluginGenerateResourcesHookResult, PluginHookContext,
+    PluginLoadHookParam, PluginLoadHookResult, PluginParseHookParam, PluginResolveHookParam,
+    PluginResolveHookResult, PluginTransformHookResult, ResolveKind,
   },
   relative_path::RelativePath,
   resource::{
     resource_pot::{RenderedModule, ResourcePot, ResourcePotMetaData, ResourcePotType},
+    resource_pot_map::ResourcePotMap,
     Resource, ResourceOrigin, ResourceType,
   },
+  serde_json::Value,
 };
 use farmfe_toolkit::common::{create_swc_source_map, MinifyBuilder, Source};
 use farmfe_toolkit::minify::minify_html_module;
 use farmfe_toolkit::{
   fs::read_file_utf8,
-  get_dynamic_resources_map::get_dynamic_resources_map,
+  get_dynamic_resources_map::{
+    get_dynamic_import_hints, get_dynamic_resources_map, get_high_priority_resources,
+  },
   html::{codegen_html_document, parse_html_document},
   script::{module_type_from_id, swc_try_with::try_with},
 };
 use resources_injector::{ResourcesInjector, ResourcesInjectorOptions};
+use template::{render_html_template, HtmlTemplateData};
 
 mod absolute_path_handler;
 mod deps_analyzer;
+mod icons;
+mod include;
+mod manifest;
 mod resources_injector;
+mod template;
 mod utils;
 
 const BASE_HTML_CHILDREN_PLACEHOLDER: &str = "{{children}}";
@@ -137,7 +152,7 @@ impl Plugin for FarmPluginHtml {
     }
   }
 
-  /// Inherit base html
+  /// Inherit base html, expand includes and render templates
   fn transform(
     &self,
     param: &farmfe_core::plugin::PluginTransformHookParam,
@@ -147,6 +162,10 @@ impl Plugin for FarmPluginHtml {
       return Ok(None);
     }
 
+    if let Some(icons_config) = &context.config.html.icons {
+      watch_icons_source(icons_config, param.resolved_path, context)?;
+    }
+
     if let Some(base) = &context.config.html.base {
       let base_html = self
         .load(
@@ -171,10 +190,24 @@ impl Plugin for FarmPluginHtml {
           msg: format!("Load base html({base}) fail: Base html file does not exist"),
         })?;
 
+      let content = base_html
+        .content
+        .replace(BASE_HTML_CHILDREN_PLACEHOLDER, &param.content);
+      let content = expand_html_includes(&content, param.resolved_path, context)?;
+
+      return Ok(Some(PluginTransformHookResult {
+        content: self.render_template(content, param.resolved_path, context)?,
+        module_type: None,
+        source_map: None,
+        ignore_previous_source_map: false,
+      }));
+    }
+
+    let content = expand_html_includes(&param.content, param.resolved_path, context)?;
+
+    if context.config.html.template || content != param.content {
       return Ok(Some(PluginTransformHookResult {
-        content: base_html
-          .content
-          .replace(BASE_HTML_CHILDREN_PLACEHOLDER, &param.content),
+        content: self.render_template(content, param.resolved_path, context)?,
         module_type: None,
         source_map: None,
         ignore_previous_source_map: false,
@@ -284,6 +317,8 @@ impl Plugin for FarmPluginHtml {
           emitted: false,
           resource_type: ResourceType::Html,
           origin: ResourceOrigin::ResourcePot(resource_pot.id.clone()),
+          source_path: None,
+          scope: Default::default(),
           info: None,
         },
         source_map: None,
@@ -333,10 +368,78 @@ impl FarmPluginHtml {
       inline_module_map: Mutex::new(HashMap::new()),
     }
   }
+
+  fn render_template(
+    &self,
+    content: String,
+    resolved_path: &str,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<String> {
+    if !context.config.html.template {
+      return Ok(content);
+    }
+
+    // envs are injected into define as `FARM_PROCESS_ENV` by the node side
+    let env = match context.config.define.get("FARM_PROCESS_ENV") {
+      Some(Value::Object(env)) => env.clone().into_iter().collect(),
+      _ => HashMap::new(),
+    };
+    let data = HtmlTemplateData {
+      mode: context.config.mode.to_string(),
+      env,
+    };
+
+    render_html_template(&content, &data).map_err(|msg| CompilationError::TransformError {
+      resolved_path: resolved_path.to_string(),
+      msg: format!("Render html template failed: {msg}"),
+    })
+  }
+}
+
+/// The dynamically loaded css resources that contain the modules matched by [HtmlNoscriptCssConfig::include], sorted by name
+fn get_noscript_css_resources(
+  config: &HtmlNoscriptCssConfig,
+  dynamic_resources_map: &HashMap<ModuleId, Vec<(String, ResourceType)>>,
+  resources_map: &HashMap<String, Resource>,
+  resource_pot_map: &ResourcePotMap,
+) -> Vec<String> {
+  let mut noscript_css_resources = dynamic_resources_map
+    .values()
+    .flatten()
+    .filter(|(_, resource_type)| matches!(resource_type, ResourceType::Css))
+    .filter(|(name, _)| {
+      let Some(ResourceOrigin::ResourcePot(resource_pot_id)) =
+        resources_map.get(name).map(|r| &r.origin)
+      else {
+        return false;
+      };
+
+      resource_pot_map
+        .resource_pot(resource_pot_id)
+        .is_some_and(|resource_pot| {
+          resource_pot.modules().into_iter().any(|module_id| {
+            let module_id = module_id.to_string();
+            config
+              .include
+              .iter()
+              .any(|regex| regex.is_match(&module_id))
+          })
+        })
+    })
+    .map(|(name, _)| name.clone())
+    .collect::<Vec<_>>();
+
+  noscript_css_resources.sort();
+  noscript_css_resources.dedup();
+  noscript_css_resources
 }
 
 pub struct FarmPluginTransformHtml {
   minify_config: MinifyBuilder,
+  /// diagnostics of the `analyze_html` hook in the previous build, replaced when the html entries are finalized again
+  reported_diagnostics: Mutex<Vec<Diagnostic>>,
+  /// icons of `html.icons`, generated again only when the source image changes
+  cached_icons: Mutex<Option<CachedIcons>>,
 }
 
 impl Plugin for FarmPluginTransformHtml {
@@ -416,19 +519,86 @@ impl Plugin for FarmPluginTransformHtml {
         &module_group_graph,
         &module_group_id,
         &resource_pot_map,
-        &params.resources_map,
+        |name| {
+          params
+            .resources_map
+            .get(name)
+            .map(|r| r.resource_type.clone())
+        },
         &module_graph,
       );
 
+      let dynamic_import_hints = dynamic_resources_map
+        .keys()
+        .map(|module_id| {
+          (
+            module_id.clone(),
+            get_dynamic_import_hints(&module_graph, module_id),
+          )
+        })
+        .filter(|(_, hints)| hints.prefetch || hints.preload)
+        .collect::<HashMap<_, _>>();
+
+      let noscript_css_resources = match &context.config.html.noscript_css {
+        Some(config) => get_noscript_css_resources(
+          config,
+          &dynamic_resources_map,
+          params.resources_map,
+          &resource_pot_map,
+        ),
+        None => vec![],
+      };
+
       resources_to_inject.insert(
         html_entry_resource.unwrap(),
-        (dep_resources, dynamic_resources_map),
+        (
+          dep_resources,
+          dynamic_resources_map,
+          dynamic_import_hints,
+          noscript_css_resources,
+        ),
       );
     }
 
     let mut already_injected_resources = Vec::new();
+    let mut html_diagnostics = vec![];
+    let icons = if let Some(icons_config) = &context.config.html.icons {
+      let (icons, icon_resources) =
+        generate_icons(icons_config, &mut self.cached_icons.lock(), context)?;
+      params.resources_map.extend(icon_resources);
+      icons
+    } else {
+      vec![]
+    };
+    let manifest_href = match &context.config.html.manifest {
+      Some(manifest_config) => {
+        let (manifest_name, manifest_resources) =
+          generate_manifest(manifest_config, &icons, context)?;
+        params.resources_map.extend(manifest_resources);
+        Some(format!(
+          "{}{manifest_name}",
+          context.config.output.static_public_path()
+        ))
+      }
+      None => None,
+    };
+
+    let high_priority_resources =
+      get_high_priority_resources(params.resources_map, &context.config.output.high_priority);
+    let vendor_resources = match &context.config.vendor_reference {
+      Some(config) => config
+        .load_references(&context.config.root)?
+        .iter()
+        .flat_map(|manifest| manifest.resource_urls())
+        .collect(),
+      None => vec![],
+    };
 
-    for (html_resource_name, (dep_resources, dynamic_resources_map)) in resources_to_inject {
+    for (
+      html_resource_name,
+      (dep_resources, dynamic_resources_map, dynamic_import_hints, noscript_css_resources),
+    ) in resources_to_inject
+    {
       let mut resource_pot_map = context.resource_pot_map.write();
       let mut script_resources: Vec<String> = vec![];
       let mut css_resources: Vec<String> = vec![];
@@ -452,9 +622,10 @@ impl Plugin for FarmPluginTransformHtml {
       let current_html_id = resource_pot_map
         .resource_pot(html_resource.origin.as_resource_pot())
         .unwrap()
-        .modules()[0];
+        .modules()[0]
+        .clone();
       let script_entries = module_graph
-        .dependencies(current_html_id)
+        .dependencies(&current_html_id)
         .into_iter()
         .filter_map(|dep| {
           let dep_module = module_graph.module(&dep.0).unwrap();
@@ -477,9 +648,13 @@ impl Plugin for FarmPluginTransformHtml {
         dynamic_resources_map,
         ResourcesInjectorOptions {
           mode: context.config.mode.clone(),
-          public_path: context.config.output.public_path.clone(),
+          public_path: context.config.output.static_public_path().to_string(),
           namespace: context.config.runtime.namespace.clone(),
           current_html_id: current_html_id.clone(),
+          high_priority_resources: high_priority_resources.clone(),
+          vendor_resources: vendor_resources.clone(),
+          dynamic_import_hints,
+          noscript_css_resources,
           context: context.clone(),
         },
         &mut already_injected_resources,
@@ -493,12 +668,46 @@ impl Plugin for FarmPluginTransformHtml {
 
       resources_injector.inject(&mut html_ast);
 
+      if !icons.is_empty() {
+        IconsInjector {
+          icons: &icons,
+          public_path: context.config.output.static_public_path(),
+        }
+        .inject(&mut html_ast);
+      }
+
+      if let Some(href) = &manifest_href {
+        ManifestInjector {
+          href: href.clone(),
+          theme_color: context
+            .config
+            .html
+            .manifest
+            .as_ref()
+            .and_then(|config| config.theme_color.as_deref()),
+        }
+        .inject(&mut html_ast);
+      }
+
       // set publicPath prefix
       let mut absolute_path_handler = AbsolutePathHandler {
-        public_path: context.config.output.public_path.clone(),
+        public_path: context.config.output.static_public_path().to_string(),
       };
       absolute_path_handler.add_public_path_prefix(&mut html_ast);
 
+      // the hook may read the resource pots of the context
+      drop(resource_pot_map);
+      let mut analyze_html_param = PluginAnalyzeHtmlHookParam {
+        module_id: &current_html_id,
+        resource_name: &html_resource_name,
+        ast: &html_ast,
+        diagnostics: vec![],
+      };
+      context
+        .plugin_driver
+        .analyze_html(&mut analyze_html_param, context)?;
+      html_diagnostics.extend(analyze_html_param.diagnostics);
+
       let code = codegen_html_document(
         &html_ast,
         self.minify_config.is_enabled(&html_resource.name),
@@ -508,6 +717,8 @@ impl Plugin for FarmPluginTransformHtml {
       resources_injector.update_resource(params.resources_map);
     }
 
+    self.report_html_diagnostics(html_diagnostics, context)?;
+
     Ok(None)
   }
 }
@@ -516,8 +727,39 @@ impl FarmPluginTransformHtml {
   pub fn new(config: &Config) -> Self {
     Self {
       minify_config: MinifyBuilder::create_builder(&config.minify, None),
+      reported_diagnostics: Mutex::new(vec![]),
+      cached_icons: Mutex::new(None),
     }
   }
+
+  /// Replace the diagnostics of the previous build, the build fails if any of them is an error
+  fn report_html_diagnostics(
+    &self,
+    diagnostics: Vec<Diagnostic>,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<()> {
+    let mut store = context.diagnostics.lock();
+    let mut reported = self.reported_diagnostics.lock();
+    store.remove(&reported);
+
+    let errors = diagnostics
+      .iter()
+      .filter(|d| d.severity == DiagnosticSeverity::Error)
+      .map(|d| d.message.clone())
+      .collect::<Vec<_>>();
+
+    for diagnostic in &diagnostics {
+      store.add(diagnostic.clone());
+    }
+
+    *reported = diagnostics;
+
+    if !errors.is_empty() {
+      return Err(CompilationError::GenericError(errors.join("\n")));
+    }
+
+    Ok(())
+  }
 }
 
 pub struct FarmPluginMinifyHtml {
@@ -0,0 +1,214 @@
+//! Generate the web app manifest configured by `html.manifest`, and inject `<link rel="manifest">` and
+//! `<meta name="theme-color">` into html entries. The configured icons are transformed by the `transform_asset` hook
+//! and emitted like other assets, the manifest icons generated by `html.icons` are added to the manifest too.
+use std::{collections::HashMap, io::Cursor, path::Path, sync::Arc};
+
+use farmfe_core::{
+  config::html::WebAppManifestConfig,
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  plugin::{AssetVariant, PluginTransformAssetHookParam},
+  relative_path::RelativePath,
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde_json::{self, json, Map, Value},
+  swc_html_ast::{Child, Document, Element},
+};
+use farmfe_toolkit::{
+  fs::transform_output_filename_with_hash,
+  html::create_element,
+  swc_html_visit::{VisitMut, VisitMutWith},
+};
+
+use crate::icons::GeneratedIcon;
+
+/// Returns the name of the manifest resource, and the manifest with its icons
+pub fn generate_manifest(
+  config: &WebAppManifestConfig,
+  generated_icons: &[GeneratedIcon],
+  context: &Arc<CompilationContext>,
+) -> Result<(String, HashMap<String, Resource>)> {
+  let public_path = context.config.output.static_public_path();
+  let mut resources = HashMap::new();
+  let mut icons = vec![];
+
+  for icon in &config.icons {
+    let (resource, sizes) = emit_icon(&icon.src, context)?;
+    let mut manifest_icon = Map::new();
+    manifest_icon.insert(
+      "src".to_string(),
+      json!(format!("{public_path}{}", resource.name)),
+    );
+    manifest_icon.insert(
+      "sizes".to_string(),
+      json!(icon.sizes.clone().unwrap_or(sizes)),
+    );
+    manifest_icon.insert("type".to_string(), json!(icon_mime_type(&resource)));
+
+    if let Some(purpose) = &icon.purpose {
+      manifest_icon.insert("purpose".to_string(), json!(purpose));
+    }
+
+    icons.push(Value::Object(manifest_icon));
+    resources.insert(resource.name.clone(), resource);
+  }
+
+  for icon in generated_icons.iter().filter(|icon| !icon.injected) {
+    icons.push(json!({
+      "src": format!("{public_path}{}", icon.resource_name),
+      "sizes": format!("{0}x{0}", icon.size),
+      "type": "image/png",
+    }));
+  }
+
+  let mut manifest = Map::new();
+  manifest.insert("name".to_string(), json!(config.name));
+
+  for (key, value) in [
+    ("short_name", &config.short_name),
+    ("description", &config.description),
+  ] {
+    if let Some(value) = value {
+      manifest.insert(key.to_string(), json!(value));
+    }
+  }
+
+  manifest.insert("start_url".to_string(), json!(config.start_url));
+
+  for (key, value) in [
+    ("scope", &config.scope),
+    ("theme_color", &config.theme_color),
+    ("background_color", &config.background_color),
+  ] {
+    if let Some(value) = value {
+      manifest.insert(key.to_string(), json!(value));
+    }
+  }
+
+  manifest.insert("display".to_string(), json!(config.display));
+
+  if !icons.is_empty() {
+    manifest.insert("icons".to_string(), Value::Array(icons));
+  }
+
+  resources.insert(
+    config.filename.clone(),
+    Resource {
+      name: config.filename.clone(),
+      bytes: serde_json::to_vec_pretty(&manifest).unwrap(),
+      emitted: false,
+      resource_type: ResourceType::Asset("webmanifest".to_string()),
+      origin: ResourceOrigin::ResourcePot(config.filename.clone()),
+      source_path: None,
+      scope: Default::default(),
+      info: None,
+    },
+  );
+
+  Ok((config.filename.clone(), resources))
+}
+
+/// Transform the icon by the `transform_asset` hook and name it by `output.assetsFilename`,
+/// returns the resource and the sizes of the image, `any` if the sizes are unknown, e.g. svg
+fn emit_icon(src: &str, context: &Arc<CompilationContext>) -> Result<(Resource, String)> {
+  let path = RelativePath::new(src).to_logical_path(&context.config.root);
+  let content = std::fs::read(&path).map_err(|e| {
+    CompilationError::GenericError(format!("Read the web app manifest icon {src} failed: {e}"))
+  })?;
+  let ext = path
+    .extension()
+    .and_then(|e| e.to_str())
+    .unwrap_or_default()
+    .to_string();
+
+  let mut param = PluginTransformAssetHookParam {
+    module_id: src.into(),
+    resolved_path: path.to_string_lossy().to_string(),
+    variant: AssetVariant::from_mode(&context.config.mode),
+    target: None,
+    ext,
+    content,
+    source_path: None,
+  };
+  context.plugin_driver.transform_asset(&mut param, context)?;
+
+  let sizes = image::io::Reader::new(Cursor::new(&param.content))
+    .with_guessed_format()
+    .ok()
+    .and_then(|reader| reader.into_dimensions().ok())
+    .map(|(width, height)| format!("{width}x{height}"))
+    .unwrap_or_else(|| "any".to_string());
+  let name = Path::new(src)
+    .file_stem()
+    .and_then(|s| s.to_str())
+    .unwrap_or("icon");
+  let resource_name = transform_output_filename_with_hash(
+    context.config.output.assets_filename.clone(),
+    name,
+    || context.config.hash.hash(&param.content, 8),
+    &param.ext,
+  );
+
+  Ok((
+    Resource {
+      name: resource_name.clone(),
+      bytes: param.content,
+      emitted: false,
+      resource_type: ResourceType::Asset(param.ext),
+      origin: ResourceOrigin::ResourcePot(resource_name),
+      source_path: None,
+      scope: Default::default(),
+      info: None,
+    },
+    sizes,
+  ))
+}
+
+fn icon_mime_type(resource: &Resource) -> &'static str {
+  let ResourceType::Asset(ext) = &resource.resource_type else {
+    return "image/png";
+  };
+
+  match ext.to_ascii_lowercase().as_str() {
+    "svg" => "image/svg+xml",
+    "jpg" | "jpeg" => "image/jpeg",
+    "webp" => "image/webp",
+    "ico" => "image/x-icon",
+    "gif" => "image/gif",
+    _ => "image/png",
+  }
+}
+
+pub struct ManifestInjector<'a> {
+  pub href: String,
+  pub theme_color: Option<&'a str>,
+}
+
+impl<'a> ManifestInjector<'a> {
+  pub fn inject(&mut self, ast: &mut Document) {
+    ast.visit_mut_with(self);
+  }
+}
+
+impl<'a> VisitMut for ManifestInjector<'a> {
+  fn visit_mut_element(&mut self, element: &mut Element) {
+    if element.tag_name == "head" {
+      element.children.push(Child::Element(create_element(
+        "link",
+        None,
+        vec![("rel", "manifest"), ("href", &self.href)],
+      )));
+
+      if let Some(theme_color) = self.theme_color {
+        element.children.push(Child::Element(create_element(
+          "meta",
+          None,
+          vec![("name", "theme-color"), ("content", theme_color)],
+        )));
+      }
+
+      return;
+    }
+
+    element.visit_mut_children_with(self);
+  }
+}
@@ -1,21 +1,32 @@
-use std::{borrow::Cow, collections::HashMap, rc::Rc, sync::Arc};
+use std::{
+  borrow::Cow,
+  collections::{BTreeMap, HashMap, HashSet},
+  rc::Rc,
+  sync::Arc,
+};
 
 use farmfe_core::{
-  config::{custom::get_config_runtime_isolate, Mode, FARM_MODULE_SYSTEM},
+  config::{
+    custom::get_config_runtime_isolate, html::HtmlNoscriptCssInject, Mode, FARM_MODULE_SYSTEM,
+    FARM_PUBLIC_PATH_GLOBAL,
+  },
   context::CompilationContext,
-  module::ModuleId,
+  module::{DynamicImportHints, ModuleId},
   resource::{Resource, ResourceType},
   swc_html_ast::{Child, Document, Element},
 };
 use farmfe_toolkit::{
-  get_dynamic_resources_map::get_dynamic_resources_code,
+  get_dynamic_resources_map::{
+    get_dynamic_resources_code, get_high_priority_modules_code, get_resource_load_retry_code,
+    get_trusted_types_code,
+  },
   html::{create_element, get_farm_global_this},
   swc_html_visit::{VisitMut, VisitMutWith},
 };
 
 use crate::utils::{
   create_farm_runtime_output_resource, is_link_css_or_code, is_script_resource,
-  is_script_src_or_type_module_code, FARM_RESOURCE,
+  is_script_src_or_type_module_code, is_styles_anchor, FARM_RESOURCE,
 };
 
 pub struct ResourcesInjectorOptions {
@@ -23,6 +34,14 @@ pub struct ResourcesInjectorOptions {
   pub public_path: String,
   pub namespace: String,
   pub current_html_id: ModuleId,
+  /// names of the resources that are injected with `fetchpriority="high"`
+  pub high_priority_resources: HashSet<String>,
+  /// urls of the resources of the linked vendor reference builds, see [farmfe_core::config::vendor_reference]
+  pub vendor_resources: Vec<String>,
+  /// hints of the dynamically imported modules that are prefetched or preloaded, see [DynamicImportHints]
+  pub dynamic_import_hints: HashMap<ModuleId, DynamicImportHints>,
+  /// dynamically loaded css resources referenced in the html, see [farmfe_core::config::html::HtmlNoscriptCssConfig]
+  pub noscript_css_resources: Vec<String>,
   pub context: Arc<CompilationContext>,
 }
 
@@ -110,6 +129,12 @@ impl<'a> ResourcesInjector<'a> {
     let mut initial_resources = vec![];
     initial_resources.extend(self.script_resources.clone());
     initial_resources.extend(self.css_resources.clone());
+
+    // the always applied css of dynamic imports is loaded already
+    if self.noscript_css_inject() == Some(HtmlNoscriptCssInject::Link) {
+      initial_resources.extend(self.noscript_css_resources());
+    }
+
     initial_resources.sort();
 
     let initial_resources_code = initial_resources
@@ -156,8 +181,21 @@ impl<'a> ResourcesInjector<'a> {
     }
 
     let finalize_code = format!(
-      r#"{}.{}.setDynamicModuleResourcesMap({},{});"#,
-      self.farm_global_this, FARM_MODULE_SYSTEM, dynamic_resources, dynamic_module_resources_map
+      r#"{}.{}.setDynamicModuleResourcesMap({},{});{}{}"#,
+      self.farm_global_this,
+      FARM_MODULE_SYSTEM,
+      dynamic_resources,
+      dynamic_module_resources_map,
+      get_high_priority_modules_code(
+        &self.dynamic_resources_map,
+        &self.options.high_priority_resources,
+        &self.farm_global_this,
+        self.options.mode.clone(),
+      ),
+      get_resource_load_retry_code(
+        &self.options.context.config.runtime.retry,
+        &self.farm_global_this
+      )
     );
 
     if get_config_runtime_isolate(&self.options.context) {
@@ -221,12 +259,36 @@ impl<'a> ResourcesInjector<'a> {
     }
   }
 
+  /// With `publicPath: "runtime"` the public path is the directory of the document unless the page sets
+  /// [FARM_PUBLIC_PATH_GLOBAL] before the resources are loaded
+  fn set_public_paths_code(&self) -> String {
+    if self.options.context.config.output.is_runtime_public_path() {
+      return format!(
+        r#"globalThis.{FARM_PUBLIC_PATH_GLOBAL} = globalThis.{FARM_PUBLIC_PATH_GLOBAL} || new URL('./', document.baseURI).pathname;{}.{}.setPublicPaths([{FARM_PUBLIC_PATH_GLOBAL}]);"#,
+        self.farm_global_this, FARM_MODULE_SYSTEM
+      );
+    }
+
+    format!(
+      r#"{}.{}.setPublicPaths(['{}']);"#,
+      self.farm_global_this, FARM_MODULE_SYSTEM, self.options.public_path
+    )
+  }
+
+  fn trusted_types_code(&self) -> String {
+    get_trusted_types_code(
+      &self.options.context.config.runtime.trusted_types,
+      &self.farm_global_this,
+    )
+  }
+
   fn inject_other_entry_file(&self, element: &mut Element) {
     element.children.push(Child::Element(create_element(
       "script",
       Some(&format!(
-        r#"{}.{}.setPublicPaths(['{}']);"#,
-        self.farm_global_this, FARM_MODULE_SYSTEM, self.options.public_path
+        "{}{}",
+        self.set_public_paths_code(),
+        self.trusted_types_code()
       )),
       vec![],
     )));
@@ -252,12 +314,123 @@ impl<'a> ResourcesInjector<'a> {
     }
   }
 
+  /// Preload or prefetch the resources of the dynamic imports marked by `farmPreload` or `farmPrefetch` magic comments,
+  /// e.g. `import(/* farmPrefetch: true */ './settings')`. Preload wins if a resource is shared by both kinds of imports
+  fn inject_dynamic_import_hints(&self, element: &mut Element) {
+    let mut hints = BTreeMap::new();
+
+    for (module_id, module_hints) in &self.options.dynamic_import_hints {
+      let Some(resources) = self.dynamic_resources_map.get(module_id) else {
+        continue;
+      };
+
+      for (name, resource_type) in resources {
+        if self.script_resources.contains(name) || self.css_resources.contains(name) {
+          continue;
+        }
+
+        let preload = hints.get(name).is_some_and(|(_, preload)| *preload);
+        hints.insert(name, (resource_type, preload || module_hints.preload));
+      }
+    }
+
+    for (name, (resource_type, preload)) in hints {
+      let href = format!("{}{}", self.options.public_path, name);
+      let attrs = if preload {
+        let as_type = match resource_type {
+          ResourceType::Css => "style",
+          _ => "script",
+        };
+        vec![("rel", "preload"), ("as", as_type), ("href", href.as_str())]
+      } else {
+        vec![("rel", "prefetch"), ("href", href.as_str())]
+      };
+
+      element
+        .children
+        .push(Child::Element(create_element("link", None, attrs)));
+    }
+  }
+
+  /// Preload the high priority resources before the other resources are requested
+  fn inject_high_priority_preloads(&self, element: &mut Element) {
+    let preloads = self
+      .script_resources
+      .iter()
+      .map(|r| (r, "script"))
+      .chain(self.css_resources.iter().map(|r| (r, "style")))
+      .filter(|(r, _)| self.is_high_priority(r));
+
+    for (resource, as_type) in preloads {
+      element.children.push(Child::Element(create_element(
+        "link",
+        None,
+        vec![
+          ("rel", "preload"),
+          ("as", as_type),
+          ("href", &format!("{}{}", self.options.public_path, resource)),
+          ("fetchpriority", "high"),
+        ],
+      )));
+    }
+  }
+
+  fn noscript_css_inject(&self) -> Option<HtmlNoscriptCssInject> {
+    let config = self.options.context.config.html.noscript_css.as_ref()?;
+    Some(config.inject)
+  }
+
+  /// The referenced css resources of dynamic imports that are not linked as initial resources
+  fn noscript_css_resources(&self) -> Vec<String> {
+    self
+      .options
+      .noscript_css_resources
+      .iter()
+      .filter(|r| !self.css_resources.contains(r))
+      .cloned()
+      .collect()
+  }
+
+  /// Link the css of dynamic imports for the pages that should be styled without js, inside `<noscript>` or always
+  fn inject_noscript_css(&self, element: &mut Element, index: usize) {
+    let Some(inject) = self.noscript_css_inject() else {
+      return;
+    };
+
+    for (i, css) in self.noscript_css_resources().into_iter().enumerate() {
+      let href = format!("{}{}", self.options.public_path, css);
+      let link = create_element("link", None, vec![("rel", "stylesheet"), ("href", &href)]);
+      let child = match inject {
+        HtmlNoscriptCssInject::Link => link,
+        HtmlNoscriptCssInject::Noscript => {
+          let mut noscript = create_element("noscript", None, vec![]);
+          noscript.children.push(Child::Element(link));
+          noscript
+        }
+      };
+
+      element.children.insert(index + i, Child::Element(child));
+    }
+  }
+
+  fn is_high_priority(&self, resource: &str) -> bool {
+    self.options.high_priority_resources.contains(resource)
+  }
+
+  /// High priority resources come first, the relative order of the other resources is preserved
+  fn sort_by_priority(&self, resources: &[String]) -> Vec<String> {
+    let (mut sorted, rest): (Vec<_>, Vec<_>) = resources
+      .iter()
+      .cloned()
+      .partition(|r| self.is_high_priority(r));
+    sorted.extend(rest);
+    sorted
+  }
+
   fn inject_resource_separate_file(&mut self, element: &mut Element) {
     let mut finalize_code = String::new();
-    finalize_code.push_str(&format!(
-      r#"{}.{}.setPublicPaths(['{}']);"#,
-      self.farm_global_this, FARM_MODULE_SYSTEM, self.options.public_path
-    ));
+    finalize_code.push_str(&self.set_public_paths_code());
+    finalize_code.push_str(&self.trusted_types_code());
     finalize_code.push_str(&format!(
       r#"{}.{}.bootstrap();"#,
       self.farm_global_this, FARM_MODULE_SYSTEM
@@ -316,43 +489,83 @@ impl<'a> VisitMut for ResourcesInjector<'a> {
       });
     }
 
+    // native ES modules import their dependencies and execute the entries themselves, there is no runtime to bootstrap
+    let native_esm = self.options.context.config.output.is_native_esm();
+
     if element.tag_name.to_string() == "head" {
-      // inject global this
-      self.inject_global_this(element);
-
-      // inject runtime <script>
-      self.inject_runtime_resources(element);
-
-      // inject css <link>
-      for css in &self.css_resources {
-        element.children.push(Child::Element(create_element(
-          "link",
-          None,
-          vec![
-            ("rel", "stylesheet"),
-            ("href", &format!("{}{}", self.options.public_path, css)),
-          ],
-        )));
+      if !native_esm {
+        // inject global this
+        self.inject_global_this(element);
+
+        // inject runtime <script>
+        self.inject_runtime_resources(element);
       }
+
+      self.inject_high_priority_preloads(element);
+      self.inject_dynamic_import_hints(element);
+
+      // inject css <link>, before the styles anchor if any
+      let mut css_index = element
+        .children
+        .iter()
+        .position(is_styles_anchor)
+        .unwrap_or(element.children.len());
+
+      for css in self.sort_by_priority(&self.css_resources) {
+        let href = format!("{}{}", self.options.public_path, css);
+        let mut attrs = vec![("rel", "stylesheet"), ("href", href.as_str())];
+
+        if self.is_high_priority(&css) {
+          attrs.push(("fetchpriority", "high"));
+        }
+
+        element.children.insert(
+          css_index,
+          Child::Element(create_element("link", None, attrs)),
+        );
+        css_index += 1;
+      }
+
+      self.inject_noscript_css(element, css_index);
     } else if element.tag_name.to_string() == "body" {
-      for script in &self.script_resources {
-        element.children.push(Child::Element(create_element(
-          "script",
-          None,
-          vec![
-            ("src", &format!("{}{}", self.options.public_path, script)),
-            (FARM_RESOURCE, "true"),
-          ],
-        )));
+      // the vendor resources register the modules required by the resources below
+      if !native_esm {
+        for src in &self.options.vendor_resources {
+          element.children.push(Child::Element(create_element(
+            "script",
+            None,
+            vec![("src", src.as_str()), (FARM_RESOURCE, "true")],
+          )));
+        }
       }
 
-      self.inject_initial_loaded_resources(element);
-      self.inject_dynamic_resources_map(element);
+      for script in self.sort_by_priority(&self.script_resources) {
+        let src = format!("{}{}", self.options.public_path, script);
+        let mut attrs = vec![("src", src.as_str()), (FARM_RESOURCE, "true")];
 
-      if get_config_runtime_isolate(&self.options.context) {
-        self.inject_resource_separate_file(element);
-      } else {
-        self.inject_other_entry_file(element);
+        if native_esm {
+          attrs.push(("type", "module"));
+        }
+
+        if self.is_high_priority(&script) {
+          attrs.push(("fetchpriority", "high"));
+        }
+
+        element
+          .children
+          .push(Child::Element(create_element("script", None, attrs)));
+      }
+
+      // the dynamic imports of native esm builds are rendered as native `import()`, so they do not need the dynamic resources map
+      if !native_esm {
+        self.inject_initial_loaded_resources(element);
+        self.inject_dynamic_resources_map(element);
+
+        if get_config_runtime_isolate(&self.options.context) {
+          self.inject_resource_separate_file(element);
+        } else {
+          self.inject_other_entry_file(element);
+        }
       }
     };
 
@@ -0,0 +1,330 @@
+//! A minimal EJS-like template for html entries, supports:
+//! * `<%= env.TITLE %>`: output the html escaped value of an expression
+//! * `<%- env.SNIPPET %>`: output the raw value of an expression, only use it for trusted markup
+//! * `<% if (mode === 'production') { %> ... <% } else if (env.X) { %> ... <% } else { %> ... <% } %>`: conditional blocks
+//!
+//! An expression is a literal (string, number, boolean) or a path starting with `env` or `mode`.
+//! A condition is an expression, a negated expression `!expr`, or a comparison with `===`, `!==`, `==` or `!=`.
+use std::collections::HashMap;
+
+use farmfe_core::serde_json::Value;
+
+const TAG_START: &str = "<%";
+const TAG_END: &str = "%>";
+
+pub struct HtmlTemplateData {
+  pub mode: String,
+  pub env: HashMap<String, Value>,
+}
+
+impl HtmlTemplateData {
+  fn lookup(&self, path: &str) -> Result<Value, String> {
+    let mut segments = path.split('.');
+
+    match segments.next() {
+      Some("mode") if path == "mode" => Ok(Value::String(self.mode.clone())),
+      Some("env") => {
+        let key = segments.collect::<Vec<_>>().join(".");
+
+        if key.is_empty() {
+          return Err("`env` must be followed by a key, e.g. `env.TITLE`".to_string());
+        }
+
+        Ok(self.env.get(&key).cloned().unwrap_or(Value::Null))
+      }
+      _ => Err(format!(
+        "unknown variable `{path}`, only `env.*` and `mode` are supported"
+      )),
+    }
+  }
+
+  fn eval_expr(&self, expr: &str) -> Result<Value, String> {
+    let expr = expr.trim();
+
+    if expr.len() >= 2
+      && ((expr.starts_with('\'') && expr.ends_with('\''))
+        || (expr.starts_with('"') && expr.ends_with('"')))
+    {
+      return Ok(Value::String(expr[1..expr.len() - 1].to_string()));
+    }
+
+    match expr {
+      "true" => return Ok(Value::Bool(true)),
+      "false" => return Ok(Value::Bool(false)),
+      "null" | "undefined" => return Ok(Value::Null),
+      _ => {}
+    }
+
+    if let Ok(n) = expr.parse::<f64>() {
+      return Ok(Value::from(n));
+    }
+
+    self.lookup(expr)
+  }
+
+  fn eval_condition(&self, cond: &str) -> Result<bool, String> {
+    let cond = cond.trim();
+
+    for (op, negate) in [("===", false), ("!==", true), ("==", false), ("!=", true)] {
+      if let Some((left, right)) = cond.split_once(op) {
+        let equal = loose_eq(&self.eval_expr(left)?, &self.eval_expr(right)?);
+        return Ok(equal != negate);
+      }
+    }
+
+    if let Some(rest) = cond.strip_prefix('!') {
+      return Ok(!self.eval_condition(rest)?);
+    }
+
+    Ok(is_truthy(&self.eval_expr(cond)?))
+  }
+}
+
+fn loose_eq(a: &Value, b: &Value) -> bool {
+  match (a, b) {
+    (Value::String(s), other) | (other, Value::String(s)) if !other.is_string() => {
+      stringify(other) == *s
+    }
+    _ => a == b,
+  }
+}
+
+fn is_truthy(v: &Value) -> bool {
+  match v {
+    Value::Null => false,
+    Value::Bool(b) => *b,
+    Value::Number(n) => n.as_f64().map(|n| n != 0.0).unwrap_or(true),
+    Value::String(s) => !s.is_empty(),
+    _ => true,
+  }
+}
+
+fn stringify(v: &Value) -> String {
+  match v {
+    Value::Null => String::new(),
+    Value::String(s) => s.clone(),
+    _ => v.to_string(),
+  }
+}
+
+struct Block {
+  /// whether the enclosing block is rendered
+  parent_active: bool,
+  /// whether a previous branch of this if-chain is already taken
+  taken: bool,
+  active: bool,
+}
+
+enum Statement<'a> {
+  If(&'a str),
+  ElseIf(&'a str),
+  Else,
+  End,
+}
+
+fn parse_statement(stmt: &str) -> Result<Statement<'_>, String> {
+  let stmt = stmt.trim();
+  let invalid = || format!("invalid statement `{stmt}`");
+
+  if stmt == "}" {
+    return Ok(Statement::End);
+  }
+
+  if let Some(rest) = stmt.strip_prefix('}') {
+    let rest = rest.trim_start();
+
+    if let Some(cond) = rest.strip_prefix("else if") {
+      return parse_condition(cond)
+        .map(Statement::ElseIf)
+        .ok_or_else(invalid);
+    }
+
+    if rest.strip_prefix("else").map(|r| r.trim() == "{") == Some(true) {
+      return Ok(Statement::Else);
+    }
+  }
+
+  if let Some(cond) = stmt.strip_prefix("if") {
+    return parse_condition(cond).map(Statement::If).ok_or_else(invalid);
+  }
+
+  Err(format!("unsupported statement `{stmt}`"))
+}
+
+/// ` (cond) {` -> `cond`
+fn parse_condition(s: &str) -> Option<&str> {
+  s.trim()
+    .strip_suffix('{')?
+    .trim_end()
+    .strip_prefix('(')?
+    .strip_suffix(')')
+}
+
+/// Escape the characters that start markup or end an attribute value, like the `<%=` tag of EJS
+fn escape_html(s: &str) -> String {
+  let mut escaped = String::with_capacity(s.len());
+
+  for c in s.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&#34;"),
+      '\'' => escaped.push_str("&#39;"),
+      _ => escaped.push(c),
+    }
+  }
+
+  escaped
+}
+
+/// Render the template. Returns the original content if there is no template tag.
+pub fn render_html_template(content: &str, data: &HtmlTemplateData) -> Result<String, String> {
+  if !content.contains(TAG_START) {
+    return Ok(content.to_string());
+  }
+
+  let mut result = String::with_capacity(content.len());
+  let mut stack: Vec<Block> = vec![];
+  let mut rest = content;
+  let active = |stack: &Vec<Block>| stack.last().map(|b| b.active).unwrap_or(true);
+
+  while let Some(start) = rest.find(TAG_START) {
+    if active(&stack) {
+      result.push_str(&rest[..start]);
+    }
+
+    let tag = &rest[start + TAG_START.len()..];
+    let end = tag
+      .find(TAG_END)
+      .ok_or_else(|| "unclosed template tag `<%`".to_string())?;
+    let body = &tag[..end];
+    rest = &tag[end + TAG_END.len()..];
+
+    if let Some(expr) = body.strip_prefix('=') {
+      if active(&stack) {
+        result.push_str(&escape_html(&stringify(&data.eval_expr(expr)?)));
+      }
+      continue;
+    }
+
+    if let Some(expr) = body.strip_prefix('-') {
+      if active(&stack) {
+        result.push_str(&stringify(&data.eval_expr(expr)?));
+      }
+      continue;
+    }
+
+    match parse_statement(body)? {
+      Statement::If(cond) => {
+        let parent_active = active(&stack);
+        let matched = parent_active && data.eval_condition(cond)?;
+        stack.push(Block {
+          parent_active,
+          taken: matched,
+          active: matched,
+        });
+      }
+      Statement::ElseIf(cond) => {
+        let block = stack
+          .last_mut()
+          .ok_or_else(|| "`else if` without `if`".to_string())?;
+        let matched = block.parent_active && !block.taken && data.eval_condition(cond)?;
+        block.taken |= matched;
+        block.active = matched;
+      }
+      Statement::Else => {
+        let block = stack
+          .last_mut()
+          .ok_or_else(|| "`else` without `if`".to_string())?;
+        block.active = block.parent_active && !block.taken;
+        block.taken = true;
+      }
+      Statement::End => {
+        stack
+          .pop()
+          .ok_or_else(|| "unexpected `}` without `if`".to_string())?;
+      }
+    }
+  }
+
+  if !stack.is_empty() {
+    return Err("unclosed `if` block".to_string());
+  }
+
+  result.push_str(rest);
+
+  Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use farmfe_core::serde_json::Value;
+
+  use super::{render_html_template, HtmlTemplateData};
+
+  fn data() -> HtmlTemplateData {
+    HtmlTemplateData {
+      mode: "production".to_string(),
+      env: HashMap::from([
+        ("TITLE".to_string(), Value::String("Farm".to_string())),
+        ("ANALYTICS".to_string(), Value::String("true".to_string())),
+      ]),
+    }
+  }
+
+  #[test]
+  fn render_expressions_and_conditions() {
+    let content = r#"<title><%= env.TITLE %></title><% if (mode === 'production') { %><script src="/analytics.js"></script><% } else { %><!-- dev --><% } %><% if (env.MISSING) { %>missing<% } else if (env.ANALYTICS == true) { %>on<% } %>"#;
+
+    assert_eq!(
+      render_html_template(content, &data()).unwrap(),
+      r#"<title>Farm</title><script src="/analytics.js"></script>on"#
+    );
+  }
+
+  #[test]
+  fn render_escaped_expressions() {
+    let data = HtmlTemplateData {
+      mode: "production".to_string(),
+      env: HashMap::from([(
+        "TITLE".to_string(),
+        Value::String("</title><script>alert('x')</script>".to_string()),
+      )]),
+    };
+
+    assert_eq!(
+      render_html_template(
+        r#"<title><%= env.TITLE %></title><meta content="<%= env.TITLE %>">"#,
+        &data
+      )
+      .unwrap(),
+      r#"<title>&lt;/title&gt;&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt;</title><meta content="&lt;/title&gt;&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt;">"#
+    );
+  }
+
+  #[test]
+  fn render_raw_expressions() {
+    let data = HtmlTemplateData {
+      mode: "production".to_string(),
+      env: HashMap::from([(
+        "SNIPPET".to_string(),
+        Value::String(r#"<script src="/analytics.js"></script>"#.to_string()),
+      )]),
+    };
+
+    assert_eq!(
+      render_html_template("<head><%- env.SNIPPET %></head>", &data).unwrap(),
+      r#"<head><script src="/analytics.js"></script></head>"#
+    );
+  }
+
+  #[test]
+  fn render_errors() {
+    assert!(render_html_template("<% if (mode) { %>", &data()).is_err());
+    assert!(render_html_template("<%= config.root %>", &data()).is_err());
+    assert!(render_html_template("<%= env.TITLE", &data()).is_err());
+  }
+}
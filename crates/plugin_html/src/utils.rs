@@ -4,15 +4,17 @@ use farmfe_core::{
   context::CompilationContext,
   module::ModuleId,
   resource::{Resource, ResourceOrigin, ResourceType},
-  swc_html_ast::Element,
+  swc_html_ast::{Child, Element},
 };
-use farmfe_toolkit::fs::transform_output_filename;
+use farmfe_toolkit::fs::transform_output_filename_with_hash;
 
 use crate::deps_analyzer::{
   get_href_link_value, get_link_css_code, get_script_src_value, get_script_type_module_code,
 };
 
 pub const FARM_RESOURCE: &str = "data-farm-resource";
+const FARM_STYLES_ANCHOR: &str = "farm-styles";
+const FARM_STYLES_ANCHOR_ATTR: &str = "data-farm-styles";
 
 fn is_external_module(
   source: String,
@@ -67,16 +69,29 @@ pub fn is_script_resource(element: &Element) -> bool {
   false
 }
 
+/// `<!-- farm-styles -->` or an element with the `data-farm-styles` attribute, the styles of the modules are injected
+/// before it in both development and production, so the stylesheets after it take precedence over them
+pub fn is_styles_anchor(child: &Child) -> bool {
+  match child {
+    Child::Comment(comment) => comment.data.trim() == FARM_STYLES_ANCHOR,
+    Child::Element(element) => element
+      .attributes
+      .iter()
+      .any(|attr| attr.name == FARM_STYLES_ANCHOR_ATTR),
+    _ => false,
+  }
+}
+
 pub fn create_farm_runtime_output_resource(
   bytes: Cow<[u8]>,
   resource_name: &str,
   context: &Arc<CompilationContext>,
   already_inject_resources: &Vec<String>,
 ) -> (String, Option<Resource>) {
-  let name = transform_output_filename(
+  let name = transform_output_filename_with_hash(
     context.config.output.filename.clone(),
     resource_name,
-    &bytes,
+    || context.config.hash.hash(&bytes, 8),
     "js", // todo: support configuring extension
           // match context.config.output.format {
           //   ModuleFormat::EsModule => "mjs",
@@ -96,6 +111,8 @@ pub fn create_farm_runtime_output_resource(
       emitted: false,
       resource_type: ResourceType::Js,
       origin: ResourceOrigin::ResourcePot(name),
+      source_path: None,
+      scope: Default::default(),
       info: None,
     }),
   )
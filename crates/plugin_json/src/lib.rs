@@ -4,7 +4,10 @@ use farmfe_core::{
   plugin::{Plugin, PluginLoadHookResult},
   serde_json,
 };
-use farmfe_toolkit::fs;
+use farmfe_toolkit::{
+  fs,
+  script::import_attributes::{IMPORT_ATTRIBUTE_TYPE, IMPORT_ATTRIBUTE_TYPE_JSON},
+};
 
 pub fn add(left: usize, right: usize) -> usize {
   left + right
@@ -22,6 +25,13 @@ fn match_json_file(file_name: &str) -> bool {
   file_name.ends_with(".json")
 }
 
+/// `import data from './data.txt' with { type: "json" }` is resolved to `./data.txt?type=json`
+fn match_json_import_attribute(query: &[(String, String)]) -> bool {
+  query
+    .iter()
+    .any(|(k, v)| k == IMPORT_ATTRIBUTE_TYPE && v == IMPORT_ATTRIBUTE_TYPE_JSON)
+}
+
 impl Plugin for FarmPluginJson {
   fn name(&self) -> &str {
     "FarmPluginJson"
@@ -33,7 +43,7 @@ impl Plugin for FarmPluginJson {
     _context: &std::sync::Arc<farmfe_core::context::CompilationContext>,
     _hook_context: &farmfe_core::plugin::PluginHookContext,
   ) -> farmfe_core::error::Result<Option<farmfe_core::plugin::PluginLoadHookResult>> {
-    if match_json_file(param.resolved_path) {
+    if match_json_file(param.resolved_path) || match_json_import_attribute(&param.query) {
       return Ok(Some(PluginLoadHookResult {
         content: fs::read_file_utf8(param.resolved_path)?,
         module_type: ModuleType::Custom(String::from("json")),
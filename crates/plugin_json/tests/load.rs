@@ -41,3 +41,43 @@ fn load_json() {
     assert!(loaded.content.contains("\"hello\""));
   });
 }
+
+#[test]
+fn load_json_with_import_attributes() {
+  fixture!("tests/fixtures/load_with_attributes/data.txt", |file, _| {
+    let config = Config::default();
+    let plugin_json = farmfe_plugin_json::FarmPluginJson::new(&config);
+    let context = Arc::new(CompilationContext::new(config, vec![]).unwrap());
+
+    let id = file.to_string_lossy().to_string();
+
+    let hook_context = PluginHookContext {
+      caller: None,
+      meta: HashMap::new(),
+    };
+
+    let load = |query: Vec<(String, String)>| {
+      plugin_json
+        .load(
+          &farmfe_core::plugin::PluginLoadHookParam {
+            resolved_path: &id,
+            query,
+            meta: HashMap::new(),
+            module_id: id.clone(),
+          },
+          &context,
+          &hook_context,
+        )
+        .unwrap()
+    };
+
+    assert!(load(vec![]).is_none());
+
+    let loaded = load(vec![("type".to_string(), "json".to_string())]).unwrap();
+    assert_eq!(
+      loaded.module_type,
+      farmfe_core::module::ModuleType::Custom("json".into())
+    );
+    assert!(loaded.content.contains("\"hello\""));
+  });
+}
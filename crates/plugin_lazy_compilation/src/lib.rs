@@ -73,6 +73,7 @@ impl Plugin for FarmPluginLazyCompilation {
             side_effects: false,
             query: vec![],
             meta: HashMap::new(),
+            resolve_trace: None,
           }));
         }
       }
@@ -110,6 +111,7 @@ impl Plugin for FarmPluginLazyCompilation {
           side_effects: false,
           query: vec![],
           meta: HashMap::from([(ORIGINAL_RESOLVED_PATH.to_string(), resolved_path)]),
+          resolve_trace: None,
         }));
       }
     }
@@ -138,6 +140,7 @@ impl Plugin for FarmPluginLazyCompilation {
           side_effects: resolve_result.side_effects,
           query: resolve_result.query,
           meta: resolve_result.meta,
+          resolve_trace: resolve_result.resolve_trace,
         }))
       } else {
         Ok(None)
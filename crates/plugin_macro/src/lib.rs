@@ -0,0 +1,283 @@
+#![feature(box_patterns)]
+
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_core::{
+  config::{macros::MacrosConfig, Config},
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  module::ModuleId,
+  plugin::{
+    Plugin, PluginHookContext, PluginProcessModuleHookParam, PluginResolveHookParam, ResolveKind,
+  },
+  serde::{Deserialize, Serialize},
+  serde_json::Value,
+  swc_ecma_ast::{
+    Callee, Expr, Id, Ident, ImportSpecifier, MemberProp, ModuleDecl, ModuleExportName, ModuleItem,
+    ObjectLit,
+  },
+};
+use farmfe_toolkit::{
+  script::import_attributes::{get_import_attributes, IMPORT_ATTRIBUTE_TYPE},
+  swc_ecma_visit::{VisitMut, VisitMutWith},
+};
+
+use value::{expr_to_value, value_to_expr};
+
+mod value;
+
+pub const MACRO_IMPORT_ATTRIBUTE_TYPE: &str = "macro";
+const PLUGIN_NAME: &str = "FarmPluginMacro";
+
+/// A call of a macro function, e.g. `t('hello', { count: 1 })`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+pub struct MacroCall {
+  /// resolved path of the macro module
+  pub module: String,
+  /// name of the called export, `default` for default import
+  pub export_name: String,
+  /// arguments of the call, only literals are allowed
+  pub args: Vec<Value>,
+  /// the module calling the macro
+  pub importer: String,
+}
+
+/// Execute macros at build time. Returns [None] if the macro returns `undefined`.
+pub trait MacroEvaluator: Send + Sync {
+  fn evaluate(&self, call: &MacroCall, context: &Arc<CompilationContext>) -> Result<Option<Value>>;
+}
+
+/// Inline the results of macro calls as constants and remove the macro imports, so macro modules never reach the module graph.
+pub struct FarmPluginMacro {
+  config: MacrosConfig,
+  evaluator: Arc<dyn MacroEvaluator>,
+}
+
+impl FarmPluginMacro {
+  pub fn new(config: &Config, evaluator: Arc<dyn MacroEvaluator>) -> Self {
+    Self {
+      config: config
+        .macros
+        .as_ref()
+        .map(|m| *m.clone())
+        .unwrap_or_default(),
+      evaluator,
+    }
+  }
+
+  fn is_macro_import(&self, source: &str, with: &Option<Box<ObjectLit>>) -> bool {
+    self.config.modules.iter().any(|m| m == source)
+      || with.as_ref().is_some_and(|with| {
+        get_import_attributes(with).into_iter().any(|(k, v)| {
+          k == IMPORT_ATTRIBUTE_TYPE && v.as_deref() == Some(MACRO_IMPORT_ATTRIBUTE_TYPE)
+        })
+      })
+  }
+
+  fn resolve_macro_module(
+    &self,
+    source: &str,
+    module_id: &ModuleId,
+    context: &Arc<CompilationContext>,
+  ) -> Result<String> {
+    let resolved = context.plugin_driver.resolve(
+      &PluginResolveHookParam {
+        source: source.to_string(),
+        importer: Some(module_id.clone()),
+        kind: ResolveKind::Import,
+      },
+      context,
+      &PluginHookContext::default(),
+    )?;
+
+    match resolved {
+      Some(resolved) if !resolved.external => Ok(resolved.resolved_path),
+      _ => Err(CompilationError::TransformError {
+        resolved_path: module_id.to_string(),
+        msg: format!("Can not resolve macro module `{source}`"),
+      }),
+    }
+  }
+}
+
+impl Plugin for FarmPluginMacro {
+  fn name(&self) -> &str {
+    PLUGIN_NAME
+  }
+
+  fn process_module(
+    &self,
+    param: &mut PluginProcessModuleHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if !param.module_type.is_script() {
+      return Ok(None);
+    }
+
+    let ast = &mut param.meta.as_script_mut().ast;
+    let mut bindings = HashMap::new();
+    let mut macro_modules = vec![];
+    let mut macro_import_indexes = vec![];
+
+    for (index, item) in ast.body.iter().enumerate() {
+      let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+        continue;
+      };
+
+      if !self.is_macro_import(&import.src.value, &import.with) {
+        continue;
+      }
+
+      let module = self.resolve_macro_module(&import.src.value, param.module_id, context)?;
+
+      for specifier in &import.specifiers {
+        let (local, binding) = match specifier {
+          ImportSpecifier::Named(named) => {
+            let export_name = match &named.imported {
+              Some(ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+              Some(ModuleExportName::Str(str)) => str.value.to_string(),
+              None => named.local.sym.to_string(),
+            };
+            (&named.local, MacroBinding::Export(module.clone(), export_name))
+          }
+          ImportSpecifier::Default(default) => (
+            &default.local,
+            MacroBinding::Export(module.clone(), "default".to_string()),
+          ),
+          ImportSpecifier::Namespace(ns) => (&ns.local, MacroBinding::Namespace(module.clone())),
+        };
+
+        bindings.insert(local.to_id(), binding);
+      }
+
+      macro_modules.push(module);
+      macro_import_indexes.push(index);
+    }
+
+    if macro_import_indexes.is_empty() {
+      return Ok(None);
+    }
+
+    // macro modules are executed at build time, remove the imports so they are not bundled
+    for index in macro_import_indexes.into_iter().rev() {
+      ast.body.remove(index);
+    }
+
+    let mut visitor = MacroCallVisitor {
+      bindings: &bindings,
+      importer: param.module_id.to_string(),
+      evaluator: &self.evaluator,
+      context,
+      errors: vec![],
+    };
+    ast.visit_mut_with(&mut visitor);
+
+    if !visitor.errors.is_empty() {
+      return Err(CompilationError::TransformError {
+        resolved_path: param.module_id.to_string(),
+        msg: visitor.errors.join("\n"),
+      });
+    }
+
+    // rebuild the module when its macro modules change
+    context.add_watch_files(
+      param.module_id.clone(),
+      macro_modules
+        .iter()
+        .map(|m| ModuleId::new(m, "", &context.config.root))
+        .collect(),
+    )?;
+
+    Ok(Some(()))
+  }
+}
+
+enum MacroBinding {
+  /// (macro module, export name)
+  Export(String, String),
+  /// `import * as m from './macro'`
+  Namespace(String),
+}
+
+struct MacroCallVisitor<'a> {
+  bindings: &'a HashMap<Id, MacroBinding>,
+  importer: String,
+  evaluator: &'a Arc<dyn MacroEvaluator>,
+  context: &'a Arc<CompilationContext>,
+  errors: Vec<String>,
+}
+
+impl<'a> MacroCallVisitor<'a> {
+  /// return (macro module, export name) if the callee is a macro function
+  fn macro_callee(&self, callee: &Callee) -> Option<(String, String)> {
+    match callee {
+      Callee::Expr(box Expr::Ident(ident)) => match self.bindings.get(&ident.to_id()) {
+        Some(MacroBinding::Export(module, export_name)) => {
+          Some((module.clone(), export_name.clone()))
+        }
+        _ => None,
+      },
+      Callee::Expr(box Expr::Member(member)) => match (&*member.obj, &member.prop) {
+        (Expr::Ident(obj), MemberProp::Ident(prop)) => match self.bindings.get(&obj.to_id()) {
+          Some(MacroBinding::Namespace(module)) => Some((module.clone(), prop.sym.to_string())),
+          _ => None,
+        },
+        _ => None,
+      },
+      _ => None,
+    }
+  }
+}
+
+impl<'a> VisitMut for MacroCallVisitor<'a> {
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    let Expr::Call(call_expr) = expr else {
+      expr.visit_mut_children_with(self);
+      return;
+    };
+
+    let Some((module, export_name)) = self.macro_callee(&call_expr.callee) else {
+      expr.visit_mut_children_with(self);
+      return;
+    };
+
+    let args = call_expr
+      .args
+      .iter()
+      .map(|arg| arg.spread.is_none().then(|| expr_to_value(&arg.expr)).flatten())
+      .collect::<Option<Vec<_>>>();
+
+    let Some(args) = args else {
+      self.errors.push(format!(
+        "Arguments of macro `{export_name}` must be literals, because macros are executed at build time"
+      ));
+      return;
+    };
+
+    let call = MacroCall {
+      module,
+      export_name,
+      args,
+      importer: self.importer.clone(),
+    };
+
+    match self.evaluator.evaluate(&call, self.context) {
+      Ok(value) => *expr = value_to_expr(value.as_ref()),
+      Err(e) => self.errors.push(format!(
+        "Failed to execute macro `{}` of {}: {e}",
+        call.export_name, call.module
+      )),
+    }
+  }
+
+  fn visit_mut_ident(&mut self, ident: &mut Ident) {
+    // macro functions can only be called directly, any other reference can not be inlined
+    if self.bindings.contains_key(&ident.to_id()) {
+      self.errors.push(format!(
+        "Macro `{}` can only be called directly with literal arguments",
+        ident.sym
+      ));
+    }
+  }
+}
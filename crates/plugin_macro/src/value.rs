@@ -0,0 +1,156 @@
+//! Conversion between literal expressions and json values, macro arguments and results are passed to the js host as json.
+use farmfe_core::{
+  serde_json::{Map, Number, Value},
+  swc_common::DUMMY_SP,
+  swc_ecma_ast::{
+    ArrayLit, Bool, Expr, ExprOrSpread, KeyValueProp, Lit, Null, Number as NumLit, ObjectLit,
+    Prop, PropName, PropOrSpread, Str, UnaryExpr, UnaryOp,
+  },
+};
+
+/// Whole numbers are integers in json, so `1` is passed to the js host as `1` rather than `1.0`
+fn number_to_value(num: f64) -> Option<Value> {
+  if num.fract() == 0.0 && num.abs() < i64::MAX as f64 {
+    return Some(Value::Number(Number::from(num as i64)));
+  }
+
+  Number::from_f64(num).map(Value::Number)
+}
+
+/// Convert a literal expression to json value, return [None] if the expression is not a literal.
+pub fn expr_to_value(expr: &Expr) -> Option<Value> {
+  match expr {
+    Expr::Lit(Lit::Str(str)) => Some(Value::String(str.value.to_string())),
+    Expr::Lit(Lit::Bool(b)) => Some(Value::Bool(b.value)),
+    Expr::Lit(Lit::Null(_)) => Some(Value::Null),
+    Expr::Lit(Lit::Num(num)) => number_to_value(num.value),
+    Expr::Unary(UnaryExpr {
+      op: UnaryOp::Minus,
+      arg,
+      ..
+    }) => match &**arg {
+      Expr::Lit(Lit::Num(num)) => number_to_value(-num.value),
+      _ => None,
+    },
+    Expr::Tpl(tpl) if tpl.exprs.is_empty() => tpl
+      .quasis
+      .first()
+      .map(|q| Value::String(q.cooked.as_ref().unwrap_or(&q.raw).to_string())),
+    Expr::Paren(paren) => expr_to_value(&paren.expr),
+    Expr::Array(ArrayLit { elems, .. }) => elems
+      .iter()
+      .map(|elem| match elem {
+        Some(ExprOrSpread { spread: None, expr }) => expr_to_value(expr),
+        _ => None,
+      })
+      .collect::<Option<Vec<_>>>()
+      .map(Value::Array),
+    Expr::Object(ObjectLit { props, .. }) => props
+      .iter()
+      .map(|prop| match prop {
+        PropOrSpread::Prop(box Prop::KeyValue(KeyValueProp { key, value })) => {
+          let key = match key {
+            PropName::Ident(ident) => ident.sym.to_string(),
+            PropName::Str(str) => str.value.to_string(),
+            PropName::Num(num) => num.value.to_string(),
+            _ => return None,
+          };
+
+          expr_to_value(value).map(|value| (key, value))
+        }
+        _ => None,
+      })
+      .collect::<Option<Map<_, _>>>()
+      .map(Value::Object),
+    _ => None,
+  }
+}
+
+/// Convert a json value to a literal expression. `void 0` is returned for `undefined`.
+pub fn value_to_expr(value: Option<&Value>) -> Expr {
+  let Some(value) = value else {
+    return Expr::Unary(UnaryExpr {
+      span: DUMMY_SP,
+      op: UnaryOp::Void,
+      arg: Box::new(Expr::Lit(Lit::Num(NumLit {
+        span: DUMMY_SP,
+        value: 0.0,
+        raw: None,
+      }))),
+    });
+  };
+
+  match value {
+    Value::Null => Expr::Lit(Lit::Null(Null { span: DUMMY_SP })),
+    Value::Bool(b) => Expr::Lit(Lit::Bool(Bool {
+      span: DUMMY_SP,
+      value: *b,
+    })),
+    Value::Number(num) => {
+      let num = num.as_f64().unwrap_or(f64::NAN);
+      let lit = Expr::Lit(Lit::Num(NumLit {
+        span: DUMMY_SP,
+        value: num.abs(),
+        raw: None,
+      }));
+
+      if num.is_sign_negative() {
+        Expr::Unary(UnaryExpr {
+          span: DUMMY_SP,
+          op: UnaryOp::Minus,
+          arg: Box::new(lit),
+        })
+      } else {
+        lit
+      }
+    }
+    Value::String(s) => Expr::Lit(Lit::Str(Str {
+      span: DUMMY_SP,
+      value: s.as_str().into(),
+      raw: None,
+    })),
+    Value::Array(arr) => Expr::Array(ArrayLit {
+      span: DUMMY_SP,
+      elems: arr
+        .iter()
+        .map(|v| {
+          Some(ExprOrSpread {
+            spread: None,
+            expr: Box::new(value_to_expr(Some(v))),
+          })
+        })
+        .collect(),
+    }),
+    Value::Object(obj) => Expr::Object(ObjectLit {
+      span: DUMMY_SP,
+      props: obj
+        .iter()
+        .map(|(k, v)| {
+          PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+            key: PropName::Str(Str {
+              span: DUMMY_SP,
+              value: k.as_str().into(),
+              raw: None,
+            }),
+            value: Box::new(value_to_expr(Some(v))),
+          })))
+        })
+        .collect(),
+    }),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use farmfe_core::serde_json::json;
+
+  use super::{expr_to_value, value_to_expr};
+
+  #[test]
+  fn round_trip() {
+    let value = json!({ "a": [1, -2.5, "b", true, null], "c": { "d": "e" } });
+    let expr = value_to_expr(Some(&value));
+
+    assert_eq!(expr_to_value(&expr), Some(value));
+  }
+}
@@ -639,11 +639,13 @@ export { hello1, hello2, default as world1 } from './dep';
               source: "./dep".to_string(),
               kind: ResolveKind::Import,
               order: 0,
+              resolve_trace: None,
             },
             ModuleGraphEdgeDataItem {
               source: "./dep".to_string(),
               kind: ResolveKind::ExportFrom,
               order: 2,
+              resolve_trace: None,
             },
           ]),
         )
@@ -657,11 +659,13 @@ export { hello1, hello2, default as world1 } from './dep';
               source: "./dep1".to_string(),
               kind: ResolveKind::Import,
               order: 2,
+              resolve_trace: None,
             },
             ModuleGraphEdgeDataItem {
               source: "./dep1".to_string(),
               kind: ResolveKind::ExportFrom,
               order: 1,
+              resolve_trace: None,
             },
           ]),
         )
@@ -674,6 +678,7 @@ export { hello1, hello2, default as world1 } from './dep';
             source: "./dep2".to_string(),
             kind: ResolveKind::Import,
             order: 0,
+            resolve_trace: None,
           }]),
         )
         .unwrap();
@@ -6,10 +6,7 @@ use std::{
 
 use exports_minifier::ExportsMinifier;
 use farmfe_core::{
-  config::{
-    minify::{MinifyMode, MinifyOptions},
-    Config,
-  },
+  config::{minify::MinifyOptions, Config},
   context::CompilationContext,
   error::Result,
   module::ModuleId,
@@ -316,35 +313,21 @@ impl Plugin for FarmPluginMinify {
     Ok(Some(()))
   }
 
-  fn optimize_resource_pot(
+  /// the resource pots to minify are filtered by the compiler, see `minify.mode`, `minify.js`, `minify.css` and `minify.exclude`
+  fn minify_resource_pot(
     &self,
     resource_pot: &mut ResourcePot,
     context: &Arc<CompilationContext>,
   ) -> farmfe_core::error::Result<Option<()>> {
-    let enable_minify = context.config.minify.enabled();
-
-    if !enable_minify {
-      return Ok(None);
-    }
-
-    // if defer minify, ignore match mode
-    if !resource_pot.is_defer_minify_as_resource_pot() {
-      if !matches!(self.minify_options.mode, MinifyMode::ResourcePot) {
-        return Ok(None);
+    match resource_pot.resource_pot_type {
+      ResourcePotType::Js | ResourcePotType::Runtime => {
+        minify_js(resource_pot, &self.minify_options, context)?
       }
-    }
-
-    if matches!(
-      resource_pot.resource_pot_type,
-      ResourcePotType::Js | ResourcePotType::Runtime
-    ) {
-      minify_js(resource_pot, &self.minify_options, context)?;
-    } else if matches!(resource_pot.resource_pot_type, ResourcePotType::Css) {
-      minify_css(resource_pot, context)?;
-    } else if matches!(resource_pot.resource_pot_type, ResourcePotType::Html) {
+      ResourcePotType::Css => minify_css(resource_pot, context)?,
       // html minify is handled in plugin html after all resources are injected in finalize_resources hook
+      _ => return Ok(None),
     }
 
-    Ok(None)
+    Ok(Some(()))
   }
 }
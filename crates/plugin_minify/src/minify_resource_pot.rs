@@ -3,6 +3,7 @@ use std::{path::PathBuf, sync::Arc};
 use farmfe_core::{
   config::minify::MinifyOptions,
   context::CompilationContext,
+  enhanced_magic_string::{magic_string::MagicString, types::SourceMapOptions},
   error::Result,
   resource::resource_pot::ResourcePot,
   swc_common::{util::take::Take, Mark},
@@ -10,8 +11,11 @@ use farmfe_core::{
   swc_ecma_parser::Syntax,
 };
 use farmfe_toolkit::{
-  common::{build_source_map, create_swc_source_map, Source},
-  css::{codegen_css_stylesheet, parse_css_stylesheet, ParseCssModuleResult},
+  common::{build_source_map, create_swc_source_map, remove_license_comments, Source},
+  css::{
+    codegen_css_license_comments, codegen_css_stylesheet, parse_css_stylesheet,
+    ParseCssModuleResult,
+  },
   minify::config::NormalizedMinifyOptions,
   script::{
     codegen_module, parse_module, swc_try_with::try_with, CodeGenCommentsConfig,
@@ -75,6 +79,11 @@ pub fn minify_js(
 
     ast.visit_mut_with(&mut fixer(Some(&comments)));
 
+    // the license comments are emitted to a separate resource instead
+    if context.config.output.extract_license_comments {
+      remove_license_comments(&comments);
+    }
+
     let sourcemap_enabled = context.config.sourcemap.enabled(resource_pot.immutable);
 
     let mut src_map = vec![];
@@ -118,10 +127,7 @@ pub fn minify_css(resource_pot: &mut ResourcePot, context: &Arc<CompilationConte
   });
 
   try_with(cm.clone(), &context.meta.css.globals, || {
-    let ParseCssModuleResult {
-      mut ast,
-      comments: _,
-    } = parse_css_stylesheet(
+    let ParseCssModuleResult { mut ast, comments } = parse_css_stylesheet(
       &resource_pot.name,
       resource_pot.meta.rendered_content.clone(),
     )
@@ -150,5 +156,31 @@ pub fn minify_css(resource_pot: &mut ResourcePot, context: &Arc<CompilationConte
     if let Some(map) = map {
       resource_pot.meta.rendered_map_chain.push(Arc::new(map));
     }
+
+    // the license comments of the rendered modules are dropped by the css codegen, keep them before the minified code
+    let license_comments = codegen_css_license_comments(&comments, &context.config.comments);
+
+    if !license_comments.is_empty() {
+      let content = resource_pot.meta.rendered_content.clone();
+      let mut magic_string = MagicString::new(&content, None);
+      magic_string.prepend(&license_comments);
+      resource_pot.meta.rendered_content = Arc::new(magic_string.to_string());
+
+      if sourcemap_enabled {
+        let map = magic_string
+          .generate_map(SourceMapOptions {
+            include_content: Some(true),
+            ..Default::default()
+          })
+          .expect("failed to generate sourcemap");
+        let mut buf = vec![];
+        map.to_writer(&mut buf).expect("failed to write sourcemap");
+
+        resource_pot
+          .meta
+          .rendered_map_chain
+          .push(Arc::new(String::from_utf8(buf).unwrap()));
+      }
+    }
   })
 }
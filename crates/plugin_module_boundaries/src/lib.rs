@@ -0,0 +1,196 @@
+use std::{
+  collections::{HashMap, VecDeque},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  config::{config_regex::ConfigRegex, module_boundaries::ModuleBoundariesConfig, Config},
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  module::{module_graph::ModuleGraph, ModuleId, ModuleType},
+  plugin::{
+    Plugin, PluginHookContext, PluginLoadHookParam, PluginLoadHookResult,
+    PluginModuleGraphUpdatedHookParams, PluginResolveHookParam, PluginResolveHookResult,
+  },
+};
+
+/// Importing this module marks the importer as server only
+pub const SERVER_ONLY_MODULE_ID: &str = "server-only";
+/// Importing this module marks the importer as client only
+pub const CLIENT_ONLY_MODULE_ID: &str = "client-only";
+const PLUGIN_NAME: &str = "FarmPluginModuleBoundaries";
+
+/// Fail the build when a server only module is reachable from the entries of a browser build,
+/// or a client only module is reachable from the entries of a node build. The import chain is reported for every violation.
+/// `server-only` and `client-only` are resolved to empty modules, so they don't need to be installed.
+pub struct FarmPluginModuleBoundaries {
+  config: ModuleBoundariesConfig,
+}
+
+impl FarmPluginModuleBoundaries {
+  pub fn new(config: &Config) -> Self {
+    Self {
+      config: config
+        .module_boundaries
+        .as_ref()
+        .map(|c| *c.clone())
+        .unwrap_or_default(),
+    }
+  }
+
+  /// The module id marker, patterns and error message of the forbidden modules of current target env
+  fn forbidden(&self, config: &Config) -> Option<(&'static str, &Vec<ConfigRegex>, &'static str)> {
+    if config.output.target_env.is_browser() {
+      Some((
+        SERVER_ONLY_MODULE_ID,
+        &self.config.server_only,
+        "Server only modules are imported by browser entries",
+      ))
+    } else if config.output.target_env.is_node() {
+      Some((
+        CLIENT_ONLY_MODULE_ID,
+        &self.config.client_only,
+        "Client only modules are imported by node entries",
+      ))
+    } else {
+      None
+    }
+  }
+
+  fn check(&self, context: &Arc<CompilationContext>) -> Result<()> {
+    let Some((marker, patterns, message)) = self.forbidden(&context.config) else {
+      return Ok(());
+    };
+
+    let module_graph = context.module_graph.read();
+    let is_forbidden = |module_id: &ModuleId| {
+      let id = module_id.to_string();
+      id == marker || patterns.iter().any(|p| p.is_match(&id))
+    };
+    let chains = find_import_chains(&module_graph, is_forbidden);
+
+    if chains.is_empty() {
+      return Ok(());
+    }
+
+    let chains = chains
+      .into_iter()
+      .map(|chain| {
+        format!(
+          "  {}",
+          chain
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ")
+        )
+      })
+      .collect::<Vec<_>>()
+      .join("\n");
+
+    Err(CompilationError::GenericError(format!(
+      "{message}:\n{chains}"
+    )))
+  }
+}
+
+/// Find the shortest import chain from the entries to every forbidden module, the dependencies of forbidden modules are not visited
+pub fn find_import_chains<F: Fn(&ModuleId) -> bool>(
+  module_graph: &ModuleGraph,
+  is_forbidden: F,
+) -> Vec<Vec<ModuleId>> {
+  let mut importers: HashMap<ModuleId, Option<ModuleId>> = HashMap::new();
+  let mut queue = VecDeque::new();
+  let mut chains = vec![];
+
+  let mut entries = module_graph.entries.keys().cloned().collect::<Vec<_>>();
+  entries.sort();
+
+  for entry in entries {
+    importers.insert(entry.clone(), None);
+    queue.push_back(entry);
+  }
+
+  while let Some(module_id) = queue.pop_front() {
+    if is_forbidden(&module_id) {
+      let mut chain = vec![module_id.clone()];
+
+      while let Some(Some(importer)) = importers.get(chain.last().unwrap()) {
+        chain.push(importer.clone());
+      }
+
+      chain.reverse();
+      chains.push(chain);
+      continue;
+    }
+
+    let mut deps = module_graph.dependencies_ids(&module_id);
+    deps.sort();
+
+    for dep in deps {
+      if !importers.contains_key(&dep) {
+        importers.insert(dep.clone(), Some(module_id.clone()));
+        queue.push_back(dep);
+      }
+    }
+  }
+
+  chains
+}
+
+impl Plugin for FarmPluginModuleBoundaries {
+  fn name(&self) -> &str {
+    PLUGIN_NAME
+  }
+
+  fn resolve(
+    &self,
+    param: &PluginResolveHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginResolveHookResult>> {
+    if param.source == SERVER_ONLY_MODULE_ID || param.source == CLIENT_ONLY_MODULE_ID {
+      return Ok(Some(PluginResolveHookResult {
+        resolved_path: param.source.clone(),
+        ..Default::default()
+      }));
+    }
+
+    Ok(None)
+  }
+
+  fn load(
+    &self,
+    param: &PluginLoadHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginLoadHookResult>> {
+    if param.resolved_path != SERVER_ONLY_MODULE_ID && param.resolved_path != CLIENT_ONLY_MODULE_ID
+    {
+      return Ok(None);
+    }
+
+    Ok(Some(PluginLoadHookResult {
+      content: "export {};".to_string(),
+      module_type: ModuleType::Js,
+      source_map: None,
+    }))
+  }
+
+  fn build_end(&self, context: &Arc<CompilationContext>) -> Result<Option<()>> {
+    self.check(context)?;
+
+    Ok(Some(()))
+  }
+
+  /// a module updated by hmr may import a forbidden module
+  fn module_graph_updated(
+    &self,
+    _param: &PluginModuleGraphUpdatedHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    self.check(context)?;
+
+    Ok(Some(()))
+  }
+}
@@ -1,11 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use farmfe_core::{
   config::partial_bundling::PartialBundlingGroupConfigResourceType,
   module::{
     module_graph::ModuleGraph,
     module_group::{ModuleGroupGraph, ModuleGroupId},
-    ModuleId,
+    ModuleId, ModuleType,
   },
 };
 
@@ -48,10 +48,74 @@ impl ModuleGroupBuckets {
   }
 }
 
-/// Generate module buckets from modules.
+/// Map the async module groups nested deeper than `max_async_depth` to their ancestor at `max_async_depth`. Entries are at depth 0
+/// and the groups of their dynamic imports at depth 1. A group reached by several paths uses the shortest one.
+pub fn fold_module_groups_by_async_depth(
+  module_group_graph: &ModuleGroupGraph,
+  module_graph: &ModuleGraph,
+  max_async_depth: usize,
+) -> HashMap<ModuleGroupId, ModuleGroupId> {
+  let mut entries = module_graph
+    .entries
+    .iter()
+    .map(|m| m.0.clone())
+    .collect::<Vec<_>>();
+  entries.sort();
+
+  let mut depths = HashMap::<ModuleGroupId, usize>::new();
+  let mut parents = HashMap::<ModuleGroupId, ModuleGroupId>::new();
+  let mut queue = VecDeque::new();
+
+  for entry in entries {
+    if module_group_graph.has(&entry) && !depths.contains_key(&entry) {
+      depths.insert(entry.clone(), 0);
+      queue.push_back(entry);
+    }
+  }
+
+  while let Some(module_group_id) = queue.pop_front() {
+    let depth = depths[&module_group_id];
+    let mut dependencies = module_group_graph.dependencies_ids(&module_group_id);
+    // Sort the dependencies to make sure the parents are stable.
+    dependencies.sort();
+
+    for dep in dependencies {
+      if depths.contains_key(&dep) {
+        continue;
+      }
+
+      depths.insert(dep.clone(), depth + 1);
+      parents.insert(dep.clone(), module_group_id.clone());
+      queue.push_back(dep);
+    }
+  }
+
+  let mut folded = HashMap::new();
+
+  for (module_group_id, depth) in &depths {
+    if *depth <= max_async_depth {
+      continue;
+    }
+
+    let mut ancestor = module_group_id;
+
+    for _ in max_async_depth..*depth {
+      ancestor = &parents[ancestor];
+    }
+
+    folded.insert(module_group_id.clone(), ancestor.clone());
+  }
+
+  folded
+}
+
+/// Generate module buckets from modules. The module groups in `folded_module_groups` are replaced by the groups they are folded into,
+/// so their modules are placed in the resources of the ancestor groups. Css modules are never folded, the css of a folded group
+/// is still emitted as its own resource and loaded with the dynamic import instead of being applied with the ancestor.
 pub fn generate_module_buckets_map(
   modules: &Vec<ModuleId>,
   module_graph: &ModuleGraph,
+  folded_module_groups: &HashMap<ModuleGroupId, ModuleGroupId>,
 ) -> HashMap<String, ModuleBucket> {
   let mut module_buckets_map = HashMap::<String, ModuleBucket>::new();
 
@@ -62,12 +126,21 @@ pub fn generate_module_buckets_map(
       continue;
     }
 
-    let key = ModuleBucket::id(module);
+    let module_groups = if module.module_type == ModuleType::Css {
+      module.module_groups.clone()
+    } else {
+      module
+        .module_groups
+        .iter()
+        .map(|id| folded_module_groups.get(id).unwrap_or(id).clone())
+        .collect::<HashSet<_>>()
+    };
+    let key = ModuleBucket::id(module, &module_groups);
 
     if let Some(module_bucket) = module_buckets_map.get_mut(&key) {
       module_bucket.add_module(module);
     } else {
-      let module_bucket = ModuleBucket::new(key.clone(), module);
+      let module_bucket = ModuleBucket::new(key.clone(), module, module_groups);
       module_buckets_map.insert(key, module_bucket);
     }
   }
@@ -126,7 +199,6 @@ pub fn group_module_buckets_by_module_group(
 #[cfg(test)]
 mod tests {
   use farmfe_testing_helpers::construct_test_module_graph_complex;
-  use std::collections::HashSet;
 
   use crate::module_group_graph_from_entries;
 
@@ -142,7 +214,7 @@ mod tests {
       .collect::<Vec<_>>();
     modules.sort();
 
-    generate_module_buckets_map(&modules, module_graph)
+    generate_module_buckets_map(&modules, module_graph, &HashMap::new())
   }
 
   #[test]
@@ -282,4 +354,75 @@ mod tests {
       ])
     );
   }
+
+  #[test]
+  fn test_fold_module_groups_by_async_depth() {
+    let mut module_graph = construct_test_module_graph_complex();
+    let entries = module_graph.entries.clone().into_keys().collect::<Vec<_>>();
+    let module_group_graph = module_group_graph_from_entries(&entries, &mut module_graph);
+
+    assert!(fold_module_groups_by_async_depth(&module_group_graph, &module_graph, 1).is_empty());
+
+    let folded = fold_module_groups_by_async_depth(&module_group_graph, &module_graph, 0);
+    assert_eq!(
+      folded,
+      HashMap::from([
+        ("D".into(), "A".into()),
+        ("F".into(), "A".into()),
+        ("G".into(), "B".into()),
+      ])
+    );
+
+    let mut modules = module_graph
+      .modules()
+      .iter()
+      .map(|m| m.id.clone())
+      .collect::<Vec<_>>();
+    modules.sort();
+    let module_buckets_map = generate_module_buckets_map(&modules, &module_graph, &folded);
+
+    assert_eq!(module_buckets_map.len(), 3);
+    assert_eq!(
+      module_buckets_map["__farm_unknown_false_A"].modules(),
+      &HashSet::from(["A".into(), "C".into(), "F".into()])
+    );
+    assert_eq!(
+      module_buckets_map["__farm_unknown_false_B"].modules(),
+      &HashSet::from(["B".into(), "E".into(), "G".into()])
+    );
+    assert_eq!(
+      module_buckets_map["__farm_unknown_false_A_B"].modules(),
+      &HashSet::from(["D".into(), "H".into()])
+    );
+  }
+
+  #[test]
+  fn test_fold_module_groups_by_async_depth_css() {
+    let mut module_graph = construct_test_module_graph_complex();
+    module_graph.module_mut(&"F".into()).unwrap().module_type = ModuleType::Css;
+    let entries = module_graph.entries.clone().into_keys().collect::<Vec<_>>();
+    let module_group_graph = module_group_graph_from_entries(&entries, &mut module_graph);
+    let folded = fold_module_groups_by_async_depth(&module_group_graph, &module_graph, 0);
+
+    let mut modules = module_graph
+      .modules()
+      .iter()
+      .map(|m| m.id.clone())
+      .collect::<Vec<_>>();
+    modules.sort();
+    let module_buckets_map = generate_module_buckets_map(&modules, &module_graph, &folded);
+
+    assert_eq!(
+      module_buckets_map["__farm_unknown_false_A"].modules(),
+      &HashSet::from(["A".into(), "C".into()])
+    );
+    assert_eq!(
+      module_buckets_map["css_false_F"].modules(),
+      &HashSet::from(["F".into()])
+    );
+    assert_eq!(
+      module_buckets_map["css_false_F"].module_groups(),
+      &HashSet::from(["F".into()])
+    );
+  }
 }
@@ -14,10 +14,13 @@ pub fn generate_module_pots(
   resource_type: ResourceType,
 ) -> Vec<ModulePot> {
   let mut module_pot_map = HashMap::<String, ModulePot>::new();
+  let package_subpaths = get_package_subpaths(modules, module_graph, config);
 
   for module_id in modules {
     let module = module_graph.module(module_id).unwrap();
-    let module_pot_name = generate_module_pot_name(module, config, resource_type.clone());
+    let package_subpath = package_subpaths.get(module_id);
+    let module_pot_name =
+      generate_module_pot_name(module, config, resource_type.clone(), package_subpath);
     let module_pot_id = ModulePot::gen_id(
       &module_pot_name,
       module.module_type.clone(),
@@ -32,6 +35,7 @@ pub fn generate_module_pots(
       )
     });
 
+    module_pot.split = package_subpath.is_some();
     module_pot.add_module(module_id.clone(), module.size, module.execution_order);
   }
 
@@ -48,6 +52,7 @@ pub fn generate_module_pots(
     let new_module_pot_numbers = (module_pot.size / config.target_max_size) + 1;
     let module_pot_name = module_pot.name.clone();
     let immutable = module_pot.immutable;
+    let split = module_pot.split;
     let ty = module_pot.module_type.clone();
     let mut modules = module_pot.take_modules().into_iter().collect::<Vec<_>>();
     modules.sort_by_key(|m| m.to_string());
@@ -60,6 +65,7 @@ pub fn generate_module_pots(
       let new_module_pot = module_pot_map
         .entry(new_module_pot_id)
         .or_insert_with(|| ModulePot::new(new_module_pot_name, ty.clone(), immutable));
+      new_module_pot.split = split;
 
       let start = i * page_size;
       let end = if i == new_module_pot_numbers - 1 {
@@ -89,6 +95,7 @@ fn generate_module_pot_name(
   module: &Module,
   config: &PartialBundlingConfig,
   resource_type: ResourceType,
+  package_subpath: Option<&String>,
 ) -> String {
   // 1. get name from partialBundling.groups
   for group_config in &config.groups {
@@ -106,14 +113,100 @@ fn generate_module_pot_name(
     }
   }
 
-  // 2. get name from immutable package
+  // 2. get name from immutable package, and the subpath of the split packages
   if module.immutable {
+    if let Some(package_subpath) = package_subpath {
+      return format!(
+        "{}@{}/{}",
+        module.package_name, module.package_version, package_subpath
+      );
+    }
+
     return format!("{}@{}", module.package_name, module.package_version);
   }
 
   module.id.to_string()
 }
 
+/// Get the top-level subpath of the package that the modules of partialBundling.splitPackages are imported through,
+/// e.g. `UserOutlined` for the modules of `@ant-design/icons` that are only reachable from `@ant-design/icons/UserOutlined`.
+/// The subpaths are derived from the module graph only, so the module pots are the same for the same modules when updating.
+/// Modules reachable from several subpaths are not returned and stay in the module pot of the package.
+fn get_package_subpaths(
+  modules: &HashSet<ModuleId>,
+  module_graph: &ModuleGraph,
+  config: &PartialBundlingConfig,
+) -> HashMap<ModuleId, String> {
+  let mut package_subpaths = HashMap::new();
+
+  if config.split_packages.is_empty() {
+    return package_subpaths;
+  }
+
+  let is_same_package = |a: &Module, b: &Module| {
+    a.package_name == b.package_name && a.package_version == b.package_version
+  };
+
+  for module_id in modules {
+    let module = module_graph.module(module_id).unwrap();
+
+    if !module.immutable
+      || module.package_name.is_empty()
+      || !config
+        .split_packages
+        .iter()
+        .any(|c| c.is_match(&module.package_name))
+    {
+      continue;
+    }
+
+    // find the modules of the package that are imported from outside of the package
+    let mut subpaths = HashSet::new();
+    let mut visited = HashSet::from([module_id.clone()]);
+    let mut stack = vec![module_id.clone()];
+
+    while let Some(id) = stack.pop() {
+      let dependents = module_graph.dependents_ids(&id);
+      let mut imported_outside = dependents.is_empty() || module_graph.entries.contains_key(&id);
+
+      for dependent in dependents {
+        if !is_same_package(module_graph.module(&dependent).unwrap(), module) {
+          imported_outside = true;
+        } else if visited.insert(dependent.clone()) {
+          stack.push(dependent);
+        }
+      }
+
+      if imported_outside {
+        subpaths.insert(get_package_subpath(&id, &module.package_name));
+      }
+    }
+
+    if subpaths.len() == 1 {
+      package_subpaths.insert(module_id.clone(), subpaths.into_iter().next().unwrap());
+    }
+  }
+
+  package_subpaths
+}
+
+/// `node_modules/@ant-design/icons/UserOutlined.js` -> `UserOutlined`, `node_modules/echarts/index.js` -> `index`
+fn get_package_subpath(module_id: &ModuleId, package_name: &str) -> String {
+  let relative_path = module_id.relative_path();
+  let package_dir = format!("node_modules/{package_name}/");
+  let subpath = relative_path
+    .rfind(&package_dir)
+    .map(|i| &relative_path[i + package_dir.len()..])
+    .unwrap_or(relative_path);
+  let subpath = subpath.split('/').next().unwrap_or_default();
+
+  subpath
+    .rsplit_once('.')
+    .map(|(stem, _)| stem)
+    .unwrap_or(subpath)
+    .to_string()
+}
+
 #[cfg(test)]
 mod tests {
   use farmfe_core::{
@@ -124,7 +217,10 @@ mod tests {
         PartialBundlingGroupConfigResourceType,
       },
     },
-    module::{module_graph::ModuleGraph, Module, ModuleType},
+    module::{
+      module_graph::{ModuleGraph, ModuleGraphEdgeDataItem},
+      Module, ModuleType,
+    },
   };
   use farmfe_testing_helpers::fixture;
   use std::collections::HashSet;
@@ -359,4 +455,87 @@ mod tests {
 
     assert_group_works(module_pots);
   }
+
+  #[test]
+  fn test_generate_module_pots_split_packages() {
+    let mut module_graph = ModuleGraph::new();
+    let index = "src/index.ts";
+    let user = "node_modules/@ant-design/icons/UserOutlined.js";
+    let user_svg = "node_modules/@ant-design/icons/lib/icons/UserOutlined.js";
+    let home = "node_modules/@ant-design/icons/HomeOutlined.js";
+    let common = "node_modules/@ant-design/icons/lib/components/AntdIcon.js";
+
+    for (i, id) in [index, user, user_svg, home, common]
+      .into_iter()
+      .enumerate()
+    {
+      let mut module = Module::new(id.into());
+      module.module_type = ModuleType::Js;
+      module.size = 1024;
+      module.execution_order = i;
+
+      if id != index {
+        module.immutable = true;
+        module.package_name = "@ant-design/icons".to_string();
+        module.package_version = "5.0.0".to_string();
+      }
+
+      module_graph.add_module(module);
+    }
+
+    for (from, to) in [
+      (index, user),
+      (index, home),
+      (user, user_svg),
+      (user, common),
+      (home, common),
+    ] {
+      module_graph
+        .add_edge_item(&from.into(), &to.into(), ModuleGraphEdgeDataItem::default())
+        .unwrap();
+    }
+
+    let modules = module_graph
+      .modules()
+      .iter()
+      .map(|m| m.id.clone())
+      .collect::<HashSet<_>>();
+
+    let module_pots = generate_module_pots(
+      &modules,
+      &module_graph,
+      &Default::default(),
+      ResourceType::Initial,
+    );
+    assert_eq!(module_pots.len(), 2);
+    assert_eq!(module_pots[1].name, "@ant-design/icons@5.0.0");
+    assert_eq!(module_pots[1].modules().len(), 4);
+    assert!(!module_pots[1].split);
+
+    let config = PartialBundlingConfig {
+      split_packages: vec![ConfigRegex::new("@ant-design/icons")],
+      ..Default::default()
+    };
+    let module_pots = generate_module_pots(&modules, &module_graph, &config, ResourceType::Initial);
+    assert_eq!(module_pots.len(), 4);
+
+    assert_eq!(module_pots[0].name, "src/index.ts");
+    assert!(!module_pots[0].split);
+
+    assert_eq!(module_pots[1].name, "@ant-design/icons@5.0.0/UserOutlined");
+    assert!(module_pots[1].split);
+    assert_eq!(
+      module_pots[1].modules(),
+      &HashSet::from([user.into(), user_svg.into()])
+    );
+
+    assert_eq!(module_pots[2].name, "@ant-design/icons@5.0.0/HomeOutlined");
+    assert!(module_pots[2].split);
+    assert_eq!(module_pots[2].modules(), &HashSet::from([home.into()]));
+
+    // shared by both subpaths
+    assert_eq!(module_pots[3].name, "@ant-design/icons@5.0.0");
+    assert!(!module_pots[3].split);
+    assert_eq!(module_pots[3].modules(), &HashSet::from([common.into()]));
+  }
 }
@@ -2,10 +2,11 @@ use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 use farmfe_core::{
-  config::partial_bundling::PartialBundlingConfig,
+  config::{hash::HashConfig, partial_bundling::PartialBundlingConfig},
   module::{module_graph::ModuleGraph, module_group::ModuleGroupId},
   resource::resource_pot::ResourcePot,
 };
+use farmfe_toolkit::get_dynamic_resources_map::get_dynamic_import_hints;
 
 use crate::{
   generate_module_buckets::ModuleGroupBuckets,
@@ -24,6 +25,7 @@ pub fn generate_resource_pots(
   mut module_buckets_map: HashMap<String, ModuleBucket>,
   module_graph: &ModuleGraph,
   config: &PartialBundlingConfig,
+  hash: &HashConfig,
 ) -> Vec<ResourcePot> {
   let mut resource_pots = vec![];
   let mut handled_module_group_buckets = HashSet::new();
@@ -74,6 +76,7 @@ pub fn generate_resource_pots(
       config,
       &base_resource_pot_name,
       module_graph,
+      hash,
     );
 
     resource_pots.extend(merged_resource_pots);
@@ -84,7 +87,9 @@ pub fn generate_resource_pots(
 
 /// Generate resource pot id from module group id.
 /// 1. If module_group_id is entry module group, then the resource pot id is the name defined in config.
-/// 2. If module_group_id is not entry module group, then the resource pot id is the module group id's filename(without extension).
+/// 2. If module_group_id is dynamically imported with a chunk name hint like `import(/* farmChunkName: "settings" */ './settings')`,
+///    then the resource pot id is the chunk name if it is not used by other resource pot.
+/// 3. Otherwise, the resource pot id is the module group id's filename(without extension).
 ///    If the filename is used by other resource pot, try use its parent dir util we find a unique name.
 fn generate_resource_pot_name(
   module_group_id: ModuleGroupId,
@@ -95,6 +100,12 @@ fn generate_resource_pot_name(
     return name.clone();
   }
 
+  if let Some(chunk_name) = get_dynamic_import_hints(module_graph, &module_group_id).chunk_name {
+    if !used_resource_pot_names.contains(&chunk_name) {
+      return chunk_name;
+    }
+  }
+
   let mut path = PathBuf::from(module_group_id.to_string());
   let mut name = try_get_filename(path.clone());
 
@@ -121,8 +132,15 @@ fn generate_resource_pot_name(
 
 #[cfg(test)]
 mod tests {
-  use farmfe_core::module::{module_graph::ModuleGraph, module_group::ModuleGroupId, Module};
-  use std::collections::HashSet;
+  use farmfe_core::{
+    module::{
+      module_graph::{ModuleGraph, ModuleGraphEdgeDataItem},
+      module_group::ModuleGroupId,
+      DynamicImportHints, Module, ModuleMetaData, ScriptModuleMetaData,
+    },
+    plugin::ResolveKind,
+  };
+  use std::collections::{HashMap, HashSet};
 
   use crate::generate_resource_pots::generate_resource_pot_name;
 
@@ -164,4 +182,51 @@ mod tests {
       "test_src_api"
     );
   }
+
+  #[test]
+  fn test_generate_resource_pot_name_with_chunk_name() {
+    let mut module_graph = ModuleGraph::new();
+    let mut index = Module::new("src/index.ts".into());
+    index.meta = Box::new(ModuleMetaData::Script(ScriptModuleMetaData {
+      dynamic_import_hints: HashMap::from([(
+        "./settings".to_string(),
+        DynamicImportHints {
+          chunk_name: Some("user-settings".to_string()),
+          ..Default::default()
+        },
+      )]),
+      ..Default::default()
+    }));
+    module_graph
+      .entries
+      .insert(index.id.clone(), "index".to_string());
+    module_graph.add_module(index);
+    module_graph.add_module(Module::new("src/settings.ts".into()));
+    module_graph
+      .add_edge_item(
+        &"src/index.ts".into(),
+        &"src/settings.ts".into(),
+        ModuleGraphEdgeDataItem {
+          source: "./settings".to_string(),
+          kind: ResolveKind::DynamicImport,
+          order: 0,
+          resolve_trace: None,
+        },
+      )
+      .unwrap();
+
+    let group_id: ModuleGroupId = "src/settings.ts".into();
+    let mut used_resource_pot_names = HashSet::new();
+    assert_eq!(
+      generate_resource_pot_name(group_id.clone(), &used_resource_pot_names, &module_graph),
+      "user-settings"
+    );
+
+    // fallback to the filename when the chunk name is used
+    used_resource_pot_names.insert("user-settings".to_string());
+    assert_eq!(
+      generate_resource_pot_name(group_id, &used_resource_pot_names, &module_graph),
+      "settings"
+    );
+  }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::{collections::VecDeque, sync::Arc};
 
 use farmfe_core::{
@@ -12,7 +12,10 @@ use farmfe_core::{
   plugin::{Plugin, PluginHookContext},
   resource::resource_pot::ResourcePot,
 };
-use generate_module_buckets::{generate_module_buckets_map, group_module_buckets_by_module_group};
+use generate_module_buckets::{
+  fold_module_groups_by_async_depth, generate_module_buckets_map,
+  group_module_buckets_by_module_group,
+};
 use generate_resource_pots::generate_resource_pots;
 
 // mod module_bucket;
@@ -69,8 +72,15 @@ impl Plugin for FarmPluginPartialBundling {
     // 1. get module group graph and module graph
     let module_graph = context.module_graph.read();
     let module_group_graph = context.module_group_graph.read();
-    // 2. generate module buckets and group by module group
-    let module_buckets_map = generate_module_buckets_map(modules, &module_graph);
+    // 2. generate module buckets and group by module group, the async groups deeper than maxAsyncDepth are folded into their ancestors
+    let folded_module_groups = match context.config.partial_bundling.max_async_depth {
+      Some(max_async_depth) => {
+        fold_module_groups_by_async_depth(&module_group_graph, &module_graph, max_async_depth)
+      }
+      None => HashMap::new(),
+    };
+    let module_buckets_map =
+      generate_module_buckets_map(modules, &module_graph, &folded_module_groups);
     let module_group_buckets =
       group_module_buckets_by_module_group(&module_buckets_map, &module_group_graph, &module_graph);
 
@@ -80,6 +90,7 @@ impl Plugin for FarmPluginPartialBundling {
       module_buckets_map,
       &module_graph,
       &context.config.partial_bundling,
+      &context.config.hash,
     );
 
     Ok(Some(resource_pots))
@@ -215,7 +226,6 @@ mod tests {
 
   use farmfe_core::{
     context::CompilationContext,
-    parking_lot::RwLock,
     plugin::{Plugin, PluginHookContext},
   };
   #[cfg(test)]
@@ -227,10 +237,10 @@ mod tests {
   #[test]
   fn analyze_module_graph() {
     let plugin = FarmPluginPartialBundling {};
-    let mut context = CompilationContext::new(Default::default(), vec![]).unwrap();
+    let context = CompilationContext::new(Default::default(), vec![]).unwrap();
     let graph = construct_test_module_graph();
 
-    let _ = std::mem::replace(&mut context.module_graph, Box::new(RwLock::new(graph)));
+    *context.module_graph.write() = graph;
     let context = Arc::new(context);
     let mut module_graph = context.module_graph.write();
 
@@ -5,7 +5,7 @@ use std::collections::{HashMap, HashSet};
 use std::{cmp::Ordering, usize};
 
 use farmfe_core::{
-  config::partial_bundling::PartialBundlingConfig,
+  config::{hash::HashConfig, partial_bundling::PartialBundlingConfig},
   module::{module_graph::ModuleGraph, module_group::ModuleGroupId, ModuleId, ModuleType},
   resource::resource_pot::{ResourcePot, ResourcePotType},
 };
@@ -108,6 +108,7 @@ pub fn merge_module_pots(
   config: &PartialBundlingConfig,
   base_resource_pot_name: &str,
   module_graph: &ModuleGraph,
+  hash: &HashConfig,
 ) -> Vec<ResourcePot> {
   // target_concurrent_requests = 0 means no limit
   let target_concurrent_requests = if config.target_concurrent_requests == 0 {
@@ -138,6 +139,7 @@ pub fn merge_module_pots(
     mutable_target_size,
     immutable_target_size,
     base_resource_pot_name,
+    hash,
   );
 
   let mut resource_pots_size_mp = HashMap::new();
@@ -156,6 +158,7 @@ pub fn merge_module_pots(
       &resource_pots_size_mp,
       config.target_min_size,
       base_resource_pot_name,
+      hash,
     );
   }
 
@@ -165,6 +168,7 @@ pub fn merge_module_pots(
       &resource_pots_size_mp,
       target_concurrent_requests,
       base_resource_pot_name,
+      hash,
     );
   }
 
@@ -177,11 +181,14 @@ fn merge_resource_pots_by_buckets(
   mutable_target_size: usize,
   immutable_target_size: usize,
   base_resource_pot_name: &str,
+  hash: &HashConfig,
 ) -> Vec<ResourcePot> {
   let mut final_resource_pots = vec![];
 
   for (_, module_pots) in module_pots_map {
-    let mut current_generation_map = HashMap::<(ModuleType, bool), CurrentGeneration>::new();
+    // module pots of split packages are only merged with the module pots of the same subpath
+    let mut current_generation_map =
+      HashMap::<(ModuleType, bool, Option<String>), CurrentGeneration>::new();
     let mut resource_pots = vec![];
 
     if module_pots.is_empty() {
@@ -195,7 +202,11 @@ fn merge_resource_pots_by_buckets(
     };
 
     for module_pot in module_pots {
-      let key = (module_pot.module_type.clone(), module_pot.immutable);
+      let key = (
+        module_pot.module_type.clone(),
+        module_pot.immutable,
+        module_pot.split.then(|| module_pot.name.clone()),
+      );
 
       if let Some(current_generation) = current_generation_map.get_mut(&key) {
         current_generation.add_module_pot(module_pot);
@@ -213,7 +224,7 @@ fn merge_resource_pots_by_buckets(
         let resource_pot_name = format!(
           "{}_{}",
           base_resource_pot_name,
-          hash_module_ids(&modules) // get_sorted_module_ids_str(&module_bucket.modules())
+          hash_module_ids(&modules, hash) // get_sorted_module_ids_str(&module_bucket.modules())
         );
         let resource_pot_type = ResourcePotType::from(module_pot.module_type.clone());
         let mut resource_pot = ResourcePot::new(resource_pot_name, resource_pot_type);
@@ -235,7 +246,7 @@ fn merge_resource_pots_by_buckets(
         let resource_pot_name = format!(
           "{}_{}",
           base_resource_pot_name,
-          hash_module_ids(&modules) // get_sorted_module_ids_str(&module_bucket.modules())
+          hash_module_ids(&modules, hash) // get_sorted_module_ids_str(&module_bucket.modules())
         );
         let resource_pot_type = ResourcePotType::from(current_generation.module_type().clone());
         let mut resource_pot = ResourcePot::new(resource_pot_name, resource_pot_type);
@@ -270,6 +281,7 @@ fn handle_enforce_target_min_size(
   resource_pots_size_mp: &HashMap<String, usize>,
   target_min_size: usize,
   base_resource_pot_name: &str,
+  hash: &HashConfig,
 ) -> Vec<ResourcePot> {
   let mut small_resource_pots_to_merge = vec![];
   let mut resource_pot_map = resource_pots
@@ -326,6 +338,7 @@ fn handle_enforce_target_min_size(
         resource_pot.immutable,
         base_resource_pot_name,
         &mut resource_pot_map,
+        hash,
       );
 
       resource_pot_map.insert(merged_resource_pot.id.clone(), merged_resource_pot);
@@ -386,6 +399,7 @@ fn handle_enforce_target_min_size(
           immutable,
           base_resource_pot_name,
           &mut resource_pot_map,
+          hash,
         );
         final_resource_pot_ids.push(merged_resource_pot.id.clone());
         resource_pot_map.insert(merged_resource_pot.id.clone(), merged_resource_pot);
@@ -405,6 +419,7 @@ fn create_merged_resource_pot(
   immutable: bool,
   base_resource_pot_name: &str,
   resource_pot_map: &mut HashMap<String, ResourcePot>,
+  hash: &HashConfig,
 ) -> ResourcePot {
   let mut modules = HashSet::new();
 
@@ -419,7 +434,7 @@ fn create_merged_resource_pot(
   let resource_pot_name = format!(
     "{}_{}",
     base_resource_pot_name,
-    hash_module_ids(&modules) // get_sorted_module_ids_str(&module_bucket.modules())
+    hash_module_ids(&modules, hash) // get_sorted_module_ids_str(&module_bucket.modules())
   );
 
   let mut merged_resource_pot = ResourcePot::new(resource_pot_name, resource_pot_type);
@@ -438,6 +453,7 @@ fn handle_enforce_target_concurrent_requests(
   resource_pots_size_mp: &HashMap<String, usize>,
   target_concurrent_requests: usize,
   base_resource_pot_name: &str,
+  hash: &HashConfig,
 ) -> Vec<ResourcePot> {
   if resource_pots.len() <= target_concurrent_requests {
     return resource_pots;
@@ -507,6 +523,7 @@ fn handle_enforce_target_concurrent_requests(
         resource_pot.immutable,
         base_resource_pot_name,
         &mut resource_pot_map,
+        hash,
       );
       resource_pot_map.insert(merged_resource_pot.id.clone(), merged_resource_pot);
     }
@@ -521,6 +538,7 @@ fn handle_enforce_target_concurrent_requests(
         immutable,
         base_resource_pot_name,
         &mut resource_pot_map,
+        hash,
       );
       resource_pot_map.insert(merged_resource_pot.id.clone(), merged_resource_pot);
     }
@@ -29,6 +29,7 @@ fn test_boundaries_enforce_min_size_not_enough_size() {
     },
     "B",
     &module_graph,
+    &Default::default(),
   );
 
   resource_pots.sort_by_key(|p| p.id.clone());
@@ -66,6 +67,7 @@ fn test_boundaries_enforce_min_size_0() {
     },
     "B",
     &module_graph,
+    &Default::default(),
   );
 
   resource_pots.sort_by_key(|p| p.id.clone());
@@ -89,6 +91,7 @@ fn test_boundaries_enforce_min_size_0() {
     },
     "B",
     &module_graph,
+    &Default::default(),
   );
 
   resource_pots.sort_by_key(|p| p.id.clone());
@@ -129,6 +132,7 @@ fn test_boundaries_enforce_concurrent_requests_0() {
     },
     "B",
     &module_graph,
+    &Default::default(),
   );
 
   resource_pots.sort_by_key(|p| p.id.clone());
@@ -152,6 +156,7 @@ fn test_boundaries_enforce_concurrent_requests_0() {
     },
     "B",
     &module_graph,
+    &Default::default(),
   );
 
   resource_pots.sort_by_key(|p| p.id.clone());
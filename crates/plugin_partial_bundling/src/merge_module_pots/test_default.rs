@@ -28,6 +28,7 @@ fn test_default() {
     },
     "B",
     &module_graph,
+    &Default::default(),
   );
 
   resource_pots.sort_by_key(|p| p.id.clone());
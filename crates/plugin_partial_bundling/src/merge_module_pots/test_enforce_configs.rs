@@ -29,6 +29,7 @@ fn test_enforce_configs_min_size() {
     },
     "B",
     &module_graph,
+    &Default::default(),
   );
 
   resource_pots.sort_by_key(|p| p.id.clone());
@@ -67,6 +68,7 @@ fn test_enforce_configs_concurrent_requests() {
     },
     "B",
     &module_graph,
+    &Default::default(),
   );
 
   resource_pots.sort_by_key(|p| p.id.clone());
@@ -23,22 +23,21 @@ pub struct ModuleBucket {
 }
 
 impl ModuleBucket {
-  pub fn new(id: String, module: &Module) -> Self {
+  pub fn new(id: String, module: &Module, module_groups: HashSet<ModuleId>) -> Self {
     // The fields will be filled later when add modules to this ModuleBucket.
     Self {
       id,
       modules: HashSet::from([module.id.clone()]),
-      module_groups: module.module_groups.clone(),
+      module_groups,
       size: module.size,
       module_type: module.module_type.clone(),
       immutable: module.immutable,
     }
   }
 
-  /// Generate the key of a ModuleBucket.
-  pub fn id(module: &Module) -> String {
-    let mut group_key = module
-      .module_groups
+  /// Generate the key of a ModuleBucket. `module_groups` are the module groups of the module after folding the deep async groups.
+  pub fn id(module: &Module, module_groups: &HashSet<ModuleId>) -> String {
+    let mut group_key = module_groups
       .iter()
       .map(|module_group_id| module_group_id.to_string())
       .collect::<Vec<String>>();
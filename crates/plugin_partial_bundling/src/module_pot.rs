@@ -13,6 +13,8 @@ pub struct ModulePot {
   pub module_type: ModuleType,
   pub immutable: bool,
   pub execution_order: usize,
+  /// Module pot of a subpath of a package matched by partialBundling.splitPackages, it's never merged with other module pots
+  pub split: bool,
 
   modules: HashSet<ModuleId>,
 }
@@ -26,6 +28,7 @@ impl ModulePot {
       module_type,
       immutable,
       execution_order: usize::MAX,
+      split: false,
     }
   }
 
@@ -1,8 +1,7 @@
 use std::collections::HashSet;
 use std::path::PathBuf;
 
-use farmfe_core::module::ModuleId;
-use farmfe_toolkit::hash::sha256;
+use farmfe_core::{config::hash::HashConfig, module::ModuleId};
 
 pub fn try_get_filename(path: PathBuf) -> String {
   path
@@ -21,8 +20,8 @@ pub fn get_sorted_module_ids_str(module_ids: &HashSet<ModuleId>) -> String {
     .join("_")
 }
 
-pub fn hash_module_ids(module_ids: &HashSet<ModuleId>) -> String {
+pub fn hash_module_ids(module_ids: &HashSet<ModuleId>, hash: &HashConfig) -> String {
   let str = get_sorted_module_ids_str(module_ids);
 
-  sha256(&str.into_bytes(), 4)
+  hash.hash(&str.into_bytes(), 4)
 }
@@ -0,0 +1,171 @@
+//! A minimal unified diff parser and applier. The hunks are located by their content instead of their line numbers,
+//! so a patch still applies when the lines before it are changed.
+
+/// The hunks of a file in a diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePatch {
+  /// path of the patched file, without the `a/` and `b/` prefixes
+  pub path: String,
+  pub hunks: Vec<Hunk>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+  /// the context and removed lines
+  pub old: Vec<String>,
+  /// the context and added lines
+  pub new: Vec<String>,
+}
+
+impl FilePatch {
+  /// Whether the diff patches the file, the path of the diff is relative to a parent directory of the file
+  pub fn is_match(&self, resolved_path: &str) -> bool {
+    let resolved_path = resolved_path.replace('\\', "/");
+
+    resolved_path == self.path || resolved_path.ends_with(&format!("/{}", self.path))
+  }
+
+  /// Apply the hunks in order, fails if the lines of a hunk are not found after the previous hunk
+  pub fn apply(&self, content: &str) -> Result<String, String> {
+    let mut lines = content
+      .split('\n')
+      .map(|l| l.to_string())
+      .collect::<Vec<_>>();
+    let mut start = 0;
+
+    for (index, hunk) in self.hunks.iter().enumerate() {
+      let position = if hunk.old.is_empty() {
+        Some(start)
+      } else {
+        (start..lines.len())
+          .find(|&i| lines[i..].starts_with(&hunk.old))
+          .or_else(|| {
+            // allow the same lines to be patched again when the hunks are out of order
+            (0..start).find(|&i| lines[i..].starts_with(&hunk.old))
+          })
+      };
+      let Some(position) = position else {
+        return Err(format!(
+          "hunk {} of `{}` does not match the content",
+          index + 1,
+          self.path
+        ));
+      };
+
+      lines.splice(position..position + hunk.old.len(), hunk.new.clone());
+      start = position + hunk.new.len();
+    }
+
+    Ok(lines.join("\n"))
+  }
+}
+
+/// Parse the files of a unified diff, e.g. created by `git diff` or `patch-package`
+pub fn parse_diff(diff: &str) -> Result<Vec<FilePatch>, String> {
+  let mut files: Vec<FilePatch> = vec![];
+  let mut lines = diff.lines();
+
+  while let Some(line) = lines.next() {
+    if let Some(path) = line.strip_prefix("+++ ") {
+      let path = path.split('\t').next().unwrap_or_default().trim();
+
+      if path == "/dev/null" {
+        return Err(format!("removing files is not supported: {line}"));
+      }
+
+      let path = path
+        .strip_prefix("b/")
+        .or_else(|| path.strip_prefix("a/"))
+        .unwrap_or(path);
+      files.push(FilePatch {
+        path: path.to_string(),
+        hunks: vec![],
+      });
+    } else if line.starts_with("@@") {
+      let Some(file) = files.last_mut() else {
+        return Err(format!("hunk without a file header: {line}"));
+      };
+      let (mut old_count, mut new_count) = parse_hunk_header(line)?;
+      let mut hunk = Hunk {
+        old: vec![],
+        new: vec![],
+      };
+
+      while old_count > 0 || new_count > 0 {
+        let Some(next) = lines.next() else {
+          return Err(format!("hunk of `{}` is truncated", file.path));
+        };
+
+        if let Some(removed) = next.strip_prefix('-') {
+          hunk.old.push(removed.to_string());
+          old_count = old_count.saturating_sub(1);
+        } else if let Some(added) = next.strip_prefix('+') {
+          hunk.new.push(added.to_string());
+          new_count = new_count.saturating_sub(1);
+        } else if next.starts_with('\\') {
+          // \ No newline at end of file
+        } else {
+          let context = next.strip_prefix(' ').unwrap_or(next);
+          hunk.old.push(context.to_string());
+          hunk.new.push(context.to_string());
+          old_count = old_count.saturating_sub(1);
+          new_count = new_count.saturating_sub(1);
+        }
+      }
+
+      file.hunks.push(hunk);
+    }
+  }
+
+  Ok(files)
+}
+
+/// The line counts of the old and new content of a hunk header like `@@ -1,3 +1,4 @@`, the count defaults to 1
+fn parse_hunk_header(line: &str) -> Result<(usize, usize), String> {
+  let count = |range: Option<&str>, prefix: char| {
+    let range = range.and_then(|r| r.strip_prefix(prefix))?;
+
+    match range.split_once(',') {
+      Some((_, count)) => count.parse::<usize>().ok(),
+      None => Some(1),
+    }
+  };
+  let mut ranges = line.trim_start_matches('@').split_whitespace();
+
+  match (count(ranges.next(), '-'), count(ranges.next(), '+')) {
+    (Some(old), Some(new)) => Ok((old, new)),
+    _ => Err(format!("invalid hunk header: {line}")),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::parse_diff;
+
+  #[test]
+  fn apply_diff() {
+    let diff = r#"diff --git a/node_modules/lib/index.js b/node_modules/lib/index.js
+--- a/node_modules/lib/index.js
++++ b/node_modules/lib/index.js
+@@ -1,3 +1,3 @@
+ export function add(a, b) {
+-  return a - b;
++  return a + b;
+ }
+"#;
+    let files = parse_diff(diff).unwrap();
+
+    assert_eq!(files.len(), 1);
+    assert!(files[0].is_match("/root/project/node_modules/lib/index.js"));
+    assert!(!files[0].is_match("/root/project/node_modules/other-lib/index.js"));
+    assert_eq!(
+      files[0]
+        .apply("// lib\nexport function add(a, b) {\n  return a - b;\n}\n")
+        .unwrap(),
+      "// lib\nexport function add(a, b) {\n  return a + b;\n}\n"
+    );
+    assert!(files[0]
+      .apply("export const add = (a, b) => a + b;\n")
+      .is_err());
+  }
+}
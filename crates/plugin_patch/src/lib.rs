@@ -0,0 +1,223 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use diff::{parse_diff, FilePatch};
+use farmfe_core::{
+  config::{
+    patches::{PatchConfig, PatchReplacement},
+    Config,
+  },
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  module::Module,
+  parking_lot::Mutex,
+  plugin::{Plugin, PluginTransformHookParam, PluginTransformHookResult},
+  serde_json,
+};
+use farmfe_utils::hash::sha256;
+
+pub mod diff;
+
+const PLUGIN_NAME: &str = "FarmPluginPatch";
+
+/// Apply the source patches of `patches` to the loaded content of the matched files before they are transformed by other plugins.
+/// The hash of the patches applied to every module is written to the plugin cache, so a cached module is rebuilt when its patches change
+pub struct FarmPluginPatch {
+  root: String,
+  patches: Vec<Patch>,
+  /// errors of reading or parsing the diff files, reported when the build starts
+  errors: Vec<String>,
+  /// module id -> hash of the patches applied to it
+  applied: Mutex<HashMap<String, String>>,
+  /// [Self::applied] of the previous build loaded from the plugin cache
+  cached_applied: Mutex<Option<HashMap<String, String>>>,
+}
+
+struct Patch {
+  index: usize,
+  config: PatchConfig,
+  files: Vec<FilePatch>,
+}
+
+impl FarmPluginPatch {
+  pub fn new(config: &Config) -> Self {
+    let mut errors = vec![];
+    let patches = config
+      .patches
+      .iter()
+      .enumerate()
+      .map(|(index, patch)| {
+        let files = match &patch.diff {
+          Some(diff) => std::fs::read_to_string(Path::new(&config.root).join(diff))
+            .map_err(|e| e.to_string())
+            .and_then(|content| parse_diff(&content))
+            .unwrap_or_else(|e| {
+              errors.push(format!("Failed to load patch `{diff}`: {e}"));
+              vec![]
+            }),
+          None => vec![],
+        };
+
+        Patch {
+          index,
+          config: patch.clone(),
+          files,
+        }
+      })
+      .collect();
+
+    Self {
+      root: config.root.clone(),
+      patches,
+      errors,
+      applied: Mutex::new(HashMap::new()),
+      cached_applied: Mutex::new(None),
+    }
+  }
+
+  /// The diffs and replacements of the module in order
+  fn patches_of<'a>(
+    &'a self,
+    module_id: &str,
+    resolved_path: &str,
+  ) -> Vec<(&'a Patch, Vec<&'a FilePatch>, &'a [PatchReplacement])> {
+    self
+      .patches
+      .iter()
+      .filter_map(|patch| {
+        let files = patch
+          .files
+          .iter()
+          .filter(|file| file.is_match(resolved_path))
+          .collect::<Vec<_>>();
+        let replacements = if patch.config.test.iter().any(|t| t.is_match(module_id)) {
+          patch.config.replace.as_slice()
+        } else {
+          &[]
+        };
+
+        (!files.is_empty() || !replacements.is_empty()).then_some((patch, files, replacements))
+      })
+      .collect()
+  }
+
+  /// Hash of the patches of the module, [None] if it's not patched
+  fn patch_hash(&self, module_id: &str, resolved_path: &str) -> Option<String> {
+    let patches = self.patches_of(module_id, resolved_path);
+
+    if patches.is_empty() {
+      return None;
+    }
+
+    let key = patches
+      .iter()
+      .map(|(patch, files, replacements)| format!("{}:{files:?}:{replacements:?}", patch.index))
+      .collect::<Vec<_>>()
+      .join("\n");
+
+    Some(sha256(key.as_bytes(), 32))
+  }
+}
+
+impl Plugin for FarmPluginPatch {
+  fn name(&self) -> &str {
+    PLUGIN_NAME
+  }
+
+  /// patches apply to the loaded content, before all other transforms
+  fn priority(&self) -> i32 {
+    102
+  }
+
+  fn build_start(&self, _context: &Arc<CompilationContext>) -> Result<Option<()>> {
+    if !self.errors.is_empty() {
+      return Err(CompilationError::GenericError(self.errors.join("\n")));
+    }
+
+    Ok(None)
+  }
+
+  fn transform(
+    &self,
+    param: &PluginTransformHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<PluginTransformHookResult>> {
+    let patches = self.patches_of(&param.module_id, param.resolved_path);
+
+    if patches.is_empty() {
+      self.applied.lock().remove(&param.module_id);
+      return Ok(None);
+    }
+
+    let error = |message: String| {
+      CompilationError::GenericError(format!("Failed to patch `{}`: {message}", param.module_id))
+    };
+    let mut content = param.content.clone();
+
+    for (_, files, replacements) in patches {
+      for file in files {
+        content = file.apply(&content).map_err(error)?;
+      }
+
+      for replacement in replacements {
+        if !content.contains(&replacement.search) {
+          return Err(error(format!(
+            "`{}` is not found in the content",
+            replacement.search
+          )));
+        }
+
+        content = content.replacen(&replacement.search, &replacement.replace, 1);
+      }
+    }
+
+    if let Some(hash) = self.patch_hash(&param.module_id, param.resolved_path) {
+      self.applied.lock().insert(param.module_id.clone(), hash);
+    }
+
+    Ok(Some(PluginTransformHookResult {
+      content,
+      ..Default::default()
+    }))
+  }
+
+  /// the content hash of a cached module does not cover the patches, invalidate it when its patches are changed
+  fn handle_persistent_cached_module(
+    &self,
+    module: &Module,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<bool>> {
+    let module_id = module.id.to_string();
+    let hash = self.patch_hash(&module_id, &module.id.resolved_path(&self.root));
+    let cached_hash = self
+      .cached_applied
+      .lock()
+      .as_ref()
+      .and_then(|cached| cached.get(&module_id).cloned());
+
+    if hash != cached_hash {
+      return Ok(Some(true));
+    }
+
+    if let Some(hash) = hash {
+      self.applied.lock().insert(module_id, hash);
+    }
+
+    Ok(None)
+  }
+
+  fn plugin_cache_loaded(
+    &self,
+    cache: &Vec<u8>,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if let Ok(cached_applied) = serde_json::from_slice(cache) {
+      *self.cached_applied.lock() = Some(cached_applied);
+    }
+
+    Ok(Some(()))
+  }
+
+  fn write_plugin_cache(&self, _context: &Arc<CompilationContext>) -> Result<Option<Vec<u8>>> {
+    Ok(Some(serde_json::to_vec(&*self.applied.lock()).unwrap()))
+  }
+}
@@ -1,13 +1,27 @@
 use farmfe_core::{
-  config::Config, context::CompilationContext, error::Result, parking_lot::Mutex, plugin::Plugin,
+  config::Config,
+  context::{
+    progress::{BuildPhase, BuildProgress},
+    CompilationContext,
+  },
+  error::Result,
+  parking_lot::Mutex,
+  plugin::Plugin,
 };
 use indicatif::{ProgressBar, ProgressStyle};
-use std::{sync::Arc, time::Duration};
+use std::{
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  time::Duration,
+};
 
+/// Show the progress reported by [farmfe_core::context::progress::ProgressTracker] of the first build in a spinner
 pub struct FarmPluginProgress {
-  module_count: Arc<Mutex<u32>>,
   progress_bar: ProgressBar,
   first_build: Mutex<bool>,
+  subscribed: AtomicBool,
 }
 
 impl FarmPluginProgress {
@@ -25,25 +39,34 @@ impl FarmPluginProgress {
     progress_bar.enable_steady_tick(Duration::from_millis(200));
 
     Self {
-      module_count: Arc::new(Mutex::new(0)),
       progress_bar,
       first_build: Mutex::new(true),
+      subscribed: AtomicBool::new(false),
     }
   }
+}
 
-  pub fn increment_module_count(&self) {
-    let mut count = self.module_count.lock();
-    *count += 1;
-  }
+fn progress_message(progress: &BuildProgress) -> String {
+  match progress.phase {
+    BuildPhase::Build => {
+      let mut message = format!(
+        "resolved {}, loaded {}, transformed {} modules",
+        progress.modules_resolved, progress.modules_loaded, progress.modules_transformed
+      );
 
-  pub fn reset_module_count(&self) {
-    let mut count = self.module_count.lock();
-    *count = 0;
-  }
+      if progress.modules_cached > 0 {
+        message += &format!(", {} modules from cache", progress.modules_cached);
+      }
 
-  pub fn get_module_count(&self) -> u32 {
-    let count = self.module_count.lock();
-    *count
+      message
+    }
+    BuildPhase::Generate if progress.resource_pots == 0 => "optimize module graph".to_string(),
+    BuildPhase::Generate => format!(
+      "render resource pots ({}/{})",
+      progress.resource_pots_rendered, progress.resource_pots
+    ),
+    BuildPhase::Write => format!("write resources ({})", progress.resources_written),
+    BuildPhase::Finished => "finished".to_string(),
   }
 }
 
@@ -62,44 +85,20 @@ impl Plugin for FarmPluginProgress {
     _context: &Arc<CompilationContext>,
   ) -> Result<Option<()>> {
     self.progress_bar.reset();
-    self.reset_module_count();
     Ok(None)
   }
 
-  fn build_start(&self, _context: &Arc<CompilationContext>) -> Result<Option<()>> {
-    self.reset_module_count();
-    Ok(None)
-  }
-
-  fn transform(
-    &self,
-    param: &farmfe_core::plugin::PluginTransformHookParam,
-    _context: &Arc<CompilationContext>,
-  ) -> Result<Option<farmfe_core::plugin::PluginTransformHookResult>> {
-    self.increment_module_count();
-    let count = self.get_module_count();
-    let module = &param.module_id;
-    self
-      .progress_bar
-      .set_message(format!("transform ({count}) {module}"));
-    self.progress_bar.inc(1);
-
-    Ok(None)
-  }
+  fn build_start(&self, context: &Arc<CompilationContext>) -> Result<Option<()>> {
+    if !self.subscribed.swap(true, Ordering::Relaxed) {
+      let progress_bar = self.progress_bar.clone();
 
-  fn handle_persistent_cached_module(
-    &self,
-    module: &farmfe_core::module::Module,
-    _context: &Arc<CompilationContext>,
-  ) -> Result<Option<bool>> {
-    self.increment_module_count();
-    let count = self.get_module_count();
-    let module = &module.id;
-    self.progress_bar.set_message(format!(
-      "load cached module({count}) {}",
-      module.to_string()
-    ));
-    self.progress_bar.inc(1);
+      context.progress.subscribe(move |progress| {
+        if !progress_bar.is_finished() {
+          progress_bar.set_message(progress_message(progress));
+          progress_bar.inc(1);
+        }
+      });
+    }
 
     Ok(None)
   }
@@ -118,106 +117,6 @@ impl Plugin for FarmPluginProgress {
     Ok(None)
   }
 
-  fn optimize_module_graph(
-    &self,
-    _module_graph: &mut farmfe_core::module::module_graph::ModuleGraph,
-    _context: &Arc<CompilationContext>,
-  ) -> Result<Option<()>> {
-    let first_build = self.first_build.lock();
-
-    if *first_build {
-      self.progress_bar.set_message("optimize module graph");
-      self.progress_bar.inc(1);
-    }
-
-    Ok(None)
-  }
-
-  fn render_resource_pot_modules(
-    &self,
-    resource_pot: &farmfe_core::resource::resource_pot::ResourcePot,
-    _context: &Arc<CompilationContext>,
-    _hook_context: &farmfe_core::plugin::PluginHookContext,
-  ) -> Result<Option<farmfe_core::resource::resource_pot::ResourcePotMetaData>> {
-    let first_build = self.first_build.lock();
-
-    if *first_build {
-      self
-        .progress_bar
-        .set_message(format!("render resource pot modules {}", resource_pot.name));
-      self.progress_bar.inc(1);
-    }
-
-    Ok(None)
-  }
-
-  fn render_resource_pot(
-    &self,
-    param: &farmfe_core::plugin::PluginRenderResourcePotHookParam,
-    _context: &Arc<CompilationContext>,
-  ) -> Result<Option<farmfe_core::plugin::PluginRenderResourcePotHookResult>> {
-    let first_build = self.first_build.lock();
-
-    if *first_build {
-      let name: &String = &param.resource_pot_info.name.clone();
-      self.progress_bar.set_message(format!("render {name}"));
-      self.progress_bar.inc(1);
-    }
-
-    Ok(None)
-  }
-
-  fn optimize_resource_pot(
-    &self,
-    resource: &mut farmfe_core::resource::resource_pot::ResourcePot,
-    _context: &Arc<CompilationContext>,
-  ) -> Result<Option<()>> {
-    let first_build = self.first_build.lock();
-
-    if *first_build {
-      self
-        .progress_bar
-        .set_message(format!("optimize resource pot {}", resource.name));
-      self.progress_bar.inc(1);
-    }
-
-    Ok(None)
-  }
-
-  fn generate_resources(
-    &self,
-    resource_pot: &mut farmfe_core::resource::resource_pot::ResourcePot,
-    _context: &Arc<CompilationContext>,
-    _hook_context: &farmfe_core::plugin::PluginHookContext,
-  ) -> Result<Option<farmfe_core::plugin::PluginGenerateResourcesHookResult>> {
-    let first_build = self.first_build.lock();
-
-    if *first_build {
-      self.progress_bar.set_message(format!(
-        "generate resources for resource pot {}",
-        resource_pot.name
-      ));
-      self.progress_bar.inc(1);
-    }
-
-    Ok(None)
-  }
-
-  fn finalize_resources(
-    &self,
-    _param: &mut farmfe_core::plugin::PluginFinalizeResourcesHookParams,
-    _context: &Arc<CompilationContext>,
-  ) -> Result<Option<()>> {
-    let first_build = self.first_build.lock();
-
-    if *first_build {
-      self.progress_bar.set_message("finalize resources...");
-      self.progress_bar.inc(1);
-    }
-
-    Ok(None)
-  }
-
   fn finish(
     &self,
     _stat: &farmfe_core::stats::Stats,
@@ -5,21 +5,29 @@ use std::{
 };
 
 use farmfe_core::{
-  config::{custom::get_config_resolve_dedupe, external::ExternalConfig, Config},
+  config::{
+    custom::get_config_resolve_dedupe, external::ExternalConfig, node_prefix::NodePrefixAction,
+    Config,
+  },
   context::CompilationContext,
-  error::Result,
+  error::{CompilationError, Result},
   farm_profile_function, farm_profile_scope,
+  module::ModuleType,
   plugin::{
-    Plugin, PluginHookContext, PluginResolveHookParam, PluginResolveHookResult, ResolveKind,
+    Plugin, PluginHookContext, PluginLoadHookParam, PluginLoadHookResult,
+    PluginModuleGraphUpdatedHookParams, PluginResolveHookParam, PluginResolveHookResult,
+    ResolveKind,
   },
   serde_json,
 };
 
 use farmfe_toolkit::resolve::DYNAMIC_EXTENSION_PRIORITY;
 use farmfe_utils::parse_query;
+use node_prefix::{check_node_prefix_imports, node_prefix_action, EMPTY_NODE_BUILTIN};
 use once_cell::sync::OnceCell;
 use resolver::{parse_package_source, ResolveOptions, Resolver};
 
+pub mod node_prefix;
 pub mod resolver;
 
 pub struct FarmPluginResolve {
@@ -77,6 +85,39 @@ impl Plugin for FarmPluginResolve {
         Path::new(&self.root).to_path_buf()
       };
 
+    if let Some(action) = node_prefix_action(source, context) {
+      match action {
+        NodePrefixAction::External => {}
+        NodePrefixAction::Error | NodePrefixAction::Empty => {
+          // loaded as an empty module, the error is reported with the import chains after the module graph is built
+          return Ok(Some(PluginResolveHookResult {
+            resolved_path: source.to_string(),
+            side_effects: false,
+            ..Default::default()
+          }));
+        }
+        NodePrefixAction::Polyfill(polyfill) => {
+          let resolve_options = ResolveOptions {
+            dynamic_extensions: None,
+          };
+          let result = self.resolver.resolve(
+            polyfill,
+            Path::new(&self.root).to_path_buf(),
+            &param.kind,
+            &resolve_options,
+            context,
+          );
+
+          return match result {
+            Some(result) => Ok(Some(PluginResolveHookResult { query, ..result })),
+            None => Err(CompilationError::GenericError(format!(
+              "Can not resolve polyfill `{polyfill}` of `{source}`, make sure it's installed"
+            ))),
+          };
+        }
+      }
+    }
+
     // Entry module and internal modules should not be external
     if !matches!(param.kind, ResolveKind::Entry(_)) {
       farm_profile_scope!("plugin_resolve::resolve::check_external".to_string());
@@ -86,8 +127,10 @@ impl Plugin for FarmPluginResolve {
           resolved_path: param.source.clone(),
           external: true,
           side_effects: false,
-          query,
+          // the query is kept in the resolved path, it should not be appended to the module id again
+          query: vec![],
           meta: HashMap::new(),
+          resolve_trace: None,
         }));
       }
     }
@@ -134,9 +177,46 @@ impl Plugin for FarmPluginResolve {
         side_effects: false,
         query: vec![],
         meta: HashMap::new(),
+        resolve_trace: None,
       });
     }
 
     Ok(resolve_result)
   }
+
+  fn load(
+    &self,
+    param: &PluginLoadHookParam,
+    context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginLoadHookResult>> {
+    if !matches!(
+      node_prefix_action(param.resolved_path, context),
+      Some(NodePrefixAction::Error | NodePrefixAction::Empty)
+    ) {
+      return Ok(None);
+    }
+
+    Ok(Some(PluginLoadHookResult {
+      content: EMPTY_NODE_BUILTIN.to_string(),
+      module_type: ModuleType::Js,
+      source_map: None,
+    }))
+  }
+
+  fn build_end(&self, context: &Arc<CompilationContext>) -> Result<Option<()>> {
+    check_node_prefix_imports(context)?;
+
+    Ok(Some(()))
+  }
+
+  fn module_graph_updated(
+    &self,
+    _param: &PluginModuleGraphUpdatedHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    check_node_prefix_imports(context)?;
+
+    Ok(Some(()))
+  }
 }
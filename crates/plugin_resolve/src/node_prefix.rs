@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  config::node_prefix::NodePrefixAction,
+  context::CompilationContext,
+  error::{CompilationError, Result},
+};
+
+pub const NODE_PREFIX: &str = "node:";
+/// `node:` imports replaced by an empty module, `node:` imports that are not allowed are loaded as empty modules as well
+pub const EMPTY_NODE_BUILTIN: &str = "module.exports = {};";
+
+/// The configured action of a `node:` prefixed import, [None] if it's not a `node:` import or the target env is not browser
+pub fn node_prefix_action<'a>(
+  source: &str,
+  context: &'a Arc<CompilationContext>,
+) -> Option<&'a NodePrefixAction> {
+  let builtin = source.strip_prefix(NODE_PREFIX)?;
+
+  if !context.config.output.target_env.is_browser() {
+    return None;
+  }
+
+  Some(context.config.resolve.node_prefix.action(builtin))
+}
+
+/// Fail if a `node:` import whose action is [NodePrefixAction::Error] is in the module graph,
+/// the import chains from the entries are reported for each of them
+pub fn check_node_prefix_imports(context: &Arc<CompilationContext>) -> Result<()> {
+  let module_graph = context.module_graph.read();
+  let mut forbidden = module_graph
+    .modules()
+    .into_iter()
+    .filter(|m| {
+      !m.external
+        && matches!(
+          node_prefix_action(&m.id.to_string(), context),
+          Some(NodePrefixAction::Error)
+        )
+    })
+    .map(|m| m.id.clone())
+    .collect::<Vec<_>>();
+
+  if forbidden.is_empty() {
+    return Ok(());
+  }
+
+  forbidden.sort();
+
+  let diagnostics = forbidden
+    .iter()
+    .map(|module_id| {
+      let chains = module_graph
+        .import_chains(module_id)
+        .into_iter()
+        .filter(|chain| !chain.is_empty())
+        .map(|chain| {
+          let mut ids = vec![chain[0].from.to_string()];
+          ids.extend(chain.iter().map(|step| step.to.to_string()));
+          format!("  {}", ids.join(" -> "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+      format!(
+        "`{}` is not available in browser builds, imported by:\n{chains}",
+        module_id.to_string()
+      )
+    })
+    .collect::<Vec<_>>()
+    .join("\n");
+
+  Err(CompilationError::GenericError(format!(
+    "{diagnostics}\nSet `compilation.resolve.nodePrefix` to replace it with an empty module or a polyfill"
+  )))
+}
@@ -11,7 +11,7 @@ use farmfe_core::{
   context::CompilationContext,
   farm_profile_function,
   parking_lot::Mutex,
-  plugin::{PluginResolveHookResult, ResolveKind},
+  plugin::{PluginResolveHookResult, ResolveKind, ResolveTrace},
   relative_path::RelativePath,
   serde_json::{from_str, Map, Value},
 };
@@ -20,7 +20,7 @@ use farmfe_toolkit::resolve::{follow_symlinks, load_package_json, package_json_l
 use farmfe_utils::relative;
 
 use crate::resolver::browser::try_browser_map;
-use crate::resolver::exports::resolve_exports_or_imports;
+use crate::resolver::exports::{resolve_conditions, resolve_exports_or_imports};
 use crate::resolver::utils::{
   get_field_value_from_package_json_info, is_double_source_dot, is_source_absolute, is_source_dot,
   is_source_relative, ParsePackageSourceResult,
@@ -119,6 +119,7 @@ impl Resolver {
 
     // 1. try `imports` field(https://nodejs.org/api/packages.html#subpath-imports).
     let resolved_imports = self.try_imports(source, base_dir.clone(), kind, context);
+    let is_imports = resolved_imports.is_some();
     let (source, base_dir) = if let Some((resolved_imports, package_dir)) = resolved_imports {
       (resolved_imports, PathBuf::from(&package_dir))
     } else {
@@ -126,7 +127,7 @@ impl Resolver {
     };
     let source = source.as_str();
 
-    self
+    let result = self
       .try_alias(source, base_dir.clone(), kind, options, context)
       .or_else(|| {
         self.try_relative_or_absolute_path(source, base_dir.clone(), kind, options, context)
@@ -140,7 +141,16 @@ impl Resolver {
           context,
         )
       })
-      .or_else(|| self.try_node_modules(source, base_dir, kind, options, context))
+      .or_else(|| self.try_node_modules(source, base_dir, kind, options, context));
+
+    if is_imports {
+      return result.map(|result| PluginResolveHookResult {
+        resolve_trace: Some(resolve_trace("imports", kind, context)),
+        ..result
+      });
+    }
+
+    result
   }
 
   fn try_browser(
@@ -201,7 +211,7 @@ impl Resolver {
 
             PluginResolveHookResult {
               side_effects,
-              ..result
+              ..with_resolved_by(result, "browser")
             }
           });
         }
@@ -210,6 +220,7 @@ impl Resolver {
             resolved_path: browser_map_type.to_string(),
             external: true,
             side_effects: false,
+            resolve_trace: Some(resolve_trace("browser", kind, context)),
             ..Default::default()
           });
         }
@@ -254,6 +265,7 @@ impl Resolver {
           resolved_path,
           external: false,
           side_effects,
+          resolve_trace: Some(resolve_trace("path", kind, context)),
           ..Default::default()
         }
       })
@@ -368,7 +380,7 @@ impl Resolver {
     if !skip_try_package {
       let res = self.try_package_entry(dir.to_path_buf(), kind, options, context);
 
-      if let Some(res) = res {
+      if let Some((res, _)) = res {
         return Some(res);
       }
     }
@@ -415,19 +427,15 @@ impl Resolver {
     context: &Arc<CompilationContext>,
   ) -> Option<PluginResolveHookResult> {
     farm_profile_function!("try_alias".to_string());
-    // sort the alias by length, so that the longest alias will be matched first
-    let mut alias_list: Vec<_> = context.config.resolve.alias.keys().collect();
-    alias_list.sort_by(|a, b| b.len().cmp(&a.len()));
 
-    for alias in alias_list {
-      let replaced = context.config.resolve.alias.get(alias).unwrap();
+    for (alias, replaced) in context.config.resolve.ordered_alias() {
       let mut result = None;
 
       // try regex alias first
       if let Some(alias) = alias.strip_prefix(REGEX_PREFIX) {
         let regex = regex::Regex::new(alias).unwrap();
         if regex.is_match(source) {
-          let replaced = regex.replace(source, replaced.as_str()).to_string();
+          let replaced = regex.replace(source, replaced).to_string();
           result = self.resolve(&replaced, base_dir.clone(), kind, options, context);
         }
       } else if alias.ends_with('$') && source == alias.trim_end_matches('$') {
@@ -456,8 +464,15 @@ impl Resolver {
         }
       }
 
-      if result.is_some() {
-        return result;
+      if let Some(result) = result {
+        context.logger.debug(
+          module_path!(),
+          format!(
+            "alias `{alias}` matched `{source}`, resolved to {}",
+            result.resolved_path
+          ),
+        );
+        return Some(with_resolved_by(result, "alias"));
       }
     }
 
@@ -555,12 +570,12 @@ impl Resolver {
           RelativePath::new(&package_name).to_logical_path(&maybe_node_modules_path)
         };
 
-        let resolved_path = if let Some(sub_path) = sub_path {
+        let resolved = if let Some(sub_path) = sub_path {
           self.try_package_subpath(&sub_path, package_path.clone(), kind, options, context)
         } else {
           self
             .try_package_entry(package_path.clone(), kind, options, context)
-            .map(|resolved_path| {
+            .map(|(resolved_path, trace)| {
               // browser map package entry
               let browser_map_type = BrowserMapType::ResolvedPath(resolved_path.clone());
               self
@@ -571,17 +586,18 @@ impl Resolver {
                   options,
                   context,
                 )
-                .map(|res| res.resolved_path)
-                .unwrap_or(resolved_path)
+                .map(|res| (res.resolved_path, resolve_trace("browser", kind, context)))
+                .unwrap_or((resolved_path, trace))
             })
         };
 
-        if let Some(resolved_path) = resolved_path {
+        if let Some((resolved_path, trace)) = resolved {
           let result = if resolved_path == BROWSER_SUBPATH_EXTERNAL_ID {
             PluginResolveHookResult {
               resolved_path,
               external: true,
               side_effects: false,
+              resolve_trace: Some(trace),
               ..Default::default()
             }
           } else {
@@ -598,6 +614,7 @@ impl Resolver {
               resolved_path,
               external: false,
               side_effects,
+              resolve_trace: Some(trace),
               ..Default::default()
             }
           };
@@ -620,7 +637,7 @@ impl Resolver {
     kind: &ResolveKind,
     options: &ResolveOptions,
     context: &Arc<CompilationContext>,
-  ) -> Option<String> {
+  ) -> Option<(String, ResolveTrace)> {
     farm_profile_function!("try_package_subpath".to_string());
 
     // try package.json under subpath, fix 1402
@@ -647,9 +664,9 @@ impl Resolver {
       },
     );
 
-    let relative_path = if let Ok(package_json_info) = package_json_info {
+    let (relative_path, resolved_by) = if let Ok(package_json_info) = package_json_info {
       resolve_exports_or_imports(&package_json_info, subpath, "exports", kind, context)
-        .map(|resolve_exports_path| resolve_exports_path.get(0).unwrap().to_string())
+        .map(|resolve_exports_path| (resolve_exports_path.first().unwrap().to_string(), "exports"))
         .or_else(|| {
           if context.config.output.target_env.is_browser() {
             try_browser_map(
@@ -657,22 +674,25 @@ impl Resolver {
               BrowserMapType::Source(subpath.to_string()),
             )
             .map(|browser_map_result| match browser_map_result {
-              BrowserMapResult::Str(mapped_value) => mapped_value,
-              BrowserMapResult::External => BROWSER_SUBPATH_EXTERNAL_ID.to_string(),
+              BrowserMapResult::Str(mapped_value) => (mapped_value, "browser"),
+              BrowserMapResult::External => (BROWSER_SUBPATH_EXTERNAL_ID.to_string(), "browser"),
             })
           } else {
             None
           }
         })
-        .unwrap_or(subpath.to_string())
+        .unwrap_or((subpath.to_string(), "path"))
     } else {
-      subpath.to_string()
+      (subpath.to_string(), "path")
     };
+    let trace = resolve_trace(resolved_by, kind, context);
 
     if relative_path == BROWSER_SUBPATH_EXTERNAL_ID {
-      Some(BROWSER_SUBPATH_EXTERNAL_ID.to_string())
+      Some((BROWSER_SUBPATH_EXTERNAL_ID.to_string(), trace))
     } else {
-      self.try_relative_path(&relative_path, package_path, kind, options, context)
+      self
+        .try_relative_path(&relative_path, package_path, kind, options, context)
+        .map(|resolved_path| (resolved_path, trace))
     }
   }
 
@@ -682,7 +702,7 @@ impl Resolver {
     kind: &ResolveKind,
     options: &ResolveOptions,
     context: &Arc<CompilationContext>,
-  ) -> Option<String> {
+  ) -> Option<(String, ResolveTrace)> {
     farm_profile_function!("try_package".to_string());
 
     let package_json_info = load_package_json(
@@ -708,7 +728,12 @@ impl Resolver {
           kind,
           context,
         )
-        .map(|exports_entries| exports_entries.get(0).unwrap().to_string())
+        .map(|exports_entries| {
+          (
+            exports_entries.first().unwrap().to_string(),
+            HIGHEST_PRIORITY_FIELD,
+          )
+        })
       })
       .or_else(|| {
         context
@@ -720,7 +745,7 @@ impl Resolver {
             if main_field == "browser" && !context.config.output.target_env.is_browser() {
               return None;
             }
-            raw_package_json_info
+            let entry_point = raw_package_json_info
               .get(main_field)
               .and_then(|field_value| match field_value {
                 Value::Object(_) if main_field == "browser" => {
@@ -742,27 +767,33 @@ impl Resolver {
                 }
                 Value::String(str) => Some(str.to_string()),
                 _ => None,
-              })
+              });
+
+            entry_point.map(|entry_point| (entry_point, main_field.as_str()))
           })
       });
-    if let Some(entry_point) = entry_point {
+    if let Some((entry_point, resolved_by)) = entry_point {
       let dir = package_json_info.dir();
       let entry_point = if !entry_point.starts_with("./") && !entry_point.starts_with("../") {
         format!("./{entry_point}")
       } else {
         entry_point
       };
-      return self.try_relative_path(&entry_point, PathBuf::from(dir), kind, options, context);
+      return self
+        .try_relative_path(&entry_point, PathBuf::from(dir), kind, options, context)
+        .map(|resolved_path| (resolved_path, resolve_trace(resolved_by, kind, context)));
     }
 
     // no main field found, try to resolve index.js file
-    self.try_directory(
-      Path::new(package_json_info.dir()),
-      kind,
-      true,
-      options,
-      context,
-    )
+    self
+      .try_directory(
+        Path::new(package_json_info.dir()),
+        kind,
+        true,
+        options,
+        context,
+      )
+      .map(|resolved_path| (resolved_path, resolve_trace("index", kind, context)))
   }
 
   fn try_imports(
@@ -811,3 +842,37 @@ impl Resolver {
     }
   }
 }
+
+/// How the source is resolved, the conditions are recorded if it's resolved by the `exports` or `imports` field
+fn resolve_trace(
+  resolved_by: &str,
+  kind: &ResolveKind,
+  context: &Arc<CompilationContext>,
+) -> ResolveTrace {
+  let conditions = if resolved_by == "exports" || resolved_by == "imports" {
+    resolve_conditions(kind, context)
+  } else {
+    vec![]
+  };
+
+  ResolveTrace {
+    resolved_by: resolved_by.to_string(),
+    conditions,
+  }
+}
+
+/// The source is resolved by `resolved_by` to another source first, e.g. an alias, the conditions of resolving the new source are kept
+fn with_resolved_by(result: PluginResolveHookResult, resolved_by: &str) -> PluginResolveHookResult {
+  let conditions = result
+    .resolve_trace
+    .map(|trace| trace.conditions)
+    .unwrap_or_default();
+
+  PluginResolveHookResult {
+    resolve_trace: Some(ResolveTrace {
+      resolved_by: resolved_by.to_string(),
+      conditions,
+    }),
+    ..result
+  }
+}
@@ -80,6 +80,26 @@ pub fn resolve_exports_or_imports(
   context: &Arc<CompilationContext>,
 ) -> Option<Vec<String>> {
   farm_profile_function!("resolve_exports_or_imports".to_string());
+  let condition_config = condition_options(kind, context);
+
+  if field_type == "imports" {
+    imports(package_json_info, key, &condition_config)
+  } else {
+    exports(package_json_info, key, &condition_config)
+  }
+}
+
+/// The sorted conditions the `exports` and `imports` fields are resolved with
+pub fn resolve_conditions(kind: &ResolveKind, context: &Arc<CompilationContext>) -> Vec<String> {
+  let mut conditions = conditions(&condition_options(kind, context))
+    .iter()
+    .map(|condition| condition.to_string())
+    .collect::<Vec<_>>();
+  conditions.sort();
+  conditions
+}
+
+fn condition_options(kind: &ResolveKind, context: &Arc<CompilationContext>) -> ConditionOptions {
   let mut additional_conditions: HashSet<String> =
     context.config.resolve.conditions.iter().cloned().collect();
 
@@ -98,20 +118,13 @@ pub fn resolve_exports_or_imports(
     ResolveKind::Require => true,
     _ => false,
   };
-  let condition_config = ConditionOptions {
+  ConditionOptions {
     browser: is_browser && !additional_conditions.contains(&String::from("node")),
     require: is_require && !additional_conditions.contains(&String::from("import")),
     conditions: additional_conditions,
     // set default unsafe_flag to insert require & import field
     unsafe_flag: false,
-  };
-
-  let result: Option<Vec<String>> = if field_type == "imports" {
-    imports(package_json_info, key, &condition_config)
-  } else {
-    exports(package_json_info, key, &condition_config)
-  };
-  return result;
+  }
 }
 
 fn exports(
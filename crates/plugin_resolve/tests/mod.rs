@@ -1,7 +1,7 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use farmfe_core::{
-  config::{Config, ResolveConfig},
+  config::{alias::AliasEntry, Config, ResolveConfig},
   context::CompilationContext,
   plugin::ResolveKind,
 };
@@ -300,6 +300,66 @@ fn resolve_alias() {
   });
 }
 
+#[test]
+fn resolve_alias_entries() {
+  fixture("tests/fixtures/resolve-alias/index.ts", |file, _| {
+    let cwd = file.parent().unwrap().to_path_buf();
+    let resolver = Resolver::new();
+    let entry = |find: &str, replacement: PathBuf| AliasEntry {
+      find: find.to_string(),
+      replacement: replacement.to_string_lossy().to_string(),
+    };
+    let context = Arc::new(
+      CompilationContext::new(
+        Config {
+          resolve: Box::new(ResolveConfig {
+            alias: HashMap::from([(
+              "@/button$".to_string(),
+              cwd.join("pages").join("a").to_string_lossy().to_string(),
+            )]),
+            alias_entries: vec![
+              entry("@", cwd.join("components")),
+              entry("@", cwd.clone()),
+              entry("$__farm_regex:^~(\\w+)/(.+)$", cwd.join("$1").join("$2")),
+            ],
+            ..Default::default()
+          }),
+          ..Default::default()
+        },
+        vec![],
+      )
+      .unwrap(),
+    );
+    let resolve = |source: &str| {
+      resolver
+        .resolve(
+          source,
+          cwd.clone(),
+          &ResolveKind::Import,
+          &ResolveOptions::default(),
+          &context,
+        )
+        .unwrap()
+        .resolved_path
+    };
+
+    // the entries are evaluated before `alias`, in the declaration order
+    assert_eq!(
+      resolve("@/button"),
+      cwd.join("components").join("button.tsx").to_string_lossy()
+    );
+    // the next entry is tried when the replaced source is not resolved
+    assert_eq!(
+      resolve("@/pages/a"),
+      cwd.join("pages").join("a.tsx").to_string_lossy()
+    );
+    assert_eq!(
+      resolve("~utils/index"),
+      cwd.join("utils").join("index.ts").to_string_lossy()
+    );
+  });
+}
+
 #[test]
 fn resolve_dot() {
   fixture!("tests/fixtures/resolve-dot/index.ts", |file, _| {
@@ -0,0 +1,202 @@
+use std::{collections::HashMap, sync::Arc};
+
+use farmfe_core::{
+  config::{routes::RoutesConfig, Config},
+  context::CompilationContext,
+  error::Result,
+  module::{ModuleId, ModuleType},
+  parking_lot::Mutex,
+  plugin::{
+    Plugin, PluginFinalizeResourcesHookParams, PluginHookContext, PluginLoadHookParam,
+    PluginLoadHookResult, PluginResolveHookParam, PluginResolveHookResult,
+    PluginUpdateModulesHookParams, UpdateType,
+  },
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde_json,
+};
+
+use scan::{is_page_file, scan_routes, Route};
+
+mod scan;
+
+pub const ROUTES_MODULE_ID: &str = "virtual:farm-routes";
+const PLUGIN_NAME: &str = "FarmPluginRoutes";
+
+/// Generate the virtual routes module from page directories, every route is lazy loaded by a dynamic import.
+/// A route manifest (route path -> resources) is emitted for server side preloading.
+pub struct FarmPluginRoutes {
+  config: RoutesConfig,
+  routes: Mutex<Vec<Route>>,
+}
+
+impl FarmPluginRoutes {
+  pub fn new(config: &Config) -> Self {
+    Self {
+      config: config
+        .routes
+        .as_ref()
+        .map(|r| *r.clone())
+        .unwrap_or_default(),
+      routes: Mutex::new(vec![]),
+    }
+  }
+
+  fn generate_routes_code(routes: &[Route]) -> String {
+    let items = routes
+      .iter()
+      .map(|route| {
+        format!(
+          "  {{ path: {}, component: () => import({}) }}",
+          serde_json::to_string(&route.path).unwrap(),
+          serde_json::to_string(&route.file).unwrap()
+        )
+      })
+      .collect::<Vec<_>>()
+      .join(",\n");
+
+    format!("export default [\n{items}\n];\n")
+  }
+}
+
+impl Plugin for FarmPluginRoutes {
+  fn name(&self) -> &str {
+    PLUGIN_NAME
+  }
+
+  fn resolve(
+    &self,
+    param: &PluginResolveHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginResolveHookResult>> {
+    if param.source == ROUTES_MODULE_ID {
+      return Ok(Some(PluginResolveHookResult {
+        resolved_path: ROUTES_MODULE_ID.to_string(),
+        ..Default::default()
+      }));
+    }
+
+    Ok(None)
+  }
+
+  fn load(
+    &self,
+    param: &PluginLoadHookParam,
+    context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginLoadHookResult>> {
+    if param.resolved_path != ROUTES_MODULE_ID {
+      return Ok(None);
+    }
+
+    let routes = scan_routes(&self.config, &context.config.root);
+    let content = Self::generate_routes_code(&routes);
+    *self.routes.lock() = routes;
+
+    Ok(Some(PluginLoadHookResult {
+      content,
+      module_type: ModuleType::Js,
+      source_map: None,
+    }))
+  }
+
+  /// Page files added or removed are not part of the module graph yet, regenerate the routes module instead.
+  fn update_modules(
+    &self,
+    params: &mut PluginUpdateModulesHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    let root = &context.config.root;
+    let known_files = self
+      .routes
+      .lock()
+      .iter()
+      .map(|r| r.file.clone())
+      .collect::<Vec<_>>();
+    let mut routes_changed = false;
+
+    params.paths.retain(|(path, update_type)| {
+      if !is_page_file(path, &self.config, root) {
+        return true;
+      }
+
+      match update_type {
+        UpdateType::Added | UpdateType::Removed => {
+          routes_changed = true;
+          false
+        }
+        UpdateType::Updated => {
+          if !known_files.contains(path) {
+            routes_changed = true;
+          }
+          true
+        }
+      }
+    });
+
+    if routes_changed
+      && !params
+        .paths
+        .iter()
+        .any(|(path, _)| path == ROUTES_MODULE_ID)
+    {
+      params
+        .paths
+        .push((ROUTES_MODULE_ID.to_string(), UpdateType::Updated));
+    }
+
+    Ok(Some(()))
+  }
+
+  fn finalize_resources(
+    &self,
+    param: &mut PluginFinalizeResourcesHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    let routes = self.routes.lock();
+
+    if routes.is_empty() {
+      return Ok(None);
+    }
+
+    let module_graph = context.module_graph.read();
+    let module_group_graph = context.module_group_graph.read();
+    let resource_pot_map = context.resource_pot_map.read();
+    let mut manifest = HashMap::new();
+
+    for route in routes.iter() {
+      let module_id = ModuleId::new(&route.file, "", &context.config.root);
+      let Some(module_group) = module_group_graph.module_group(&module_id) else {
+        continue;
+      };
+
+      let resources = module_group
+        .sorted_resource_pots(&module_graph, &resource_pot_map)
+        .iter()
+        .filter_map(|id| resource_pot_map.resource_pot(id))
+        .flat_map(|rp| rp.resources().into_iter().cloned())
+        .filter(|name| param.resources_map.contains_key(name))
+        .map(|name| format!("{}{}", context.config.output.static_public_path(), name))
+        .collect::<Vec<_>>();
+
+      manifest.insert(route.path.clone(), resources);
+    }
+
+    let name = self.config.manifest_filename.clone();
+    param.resources_map.insert(
+      name.clone(),
+      Resource {
+        name: name.clone(),
+        bytes: serde_json::to_vec_pretty(&manifest).unwrap(),
+        emitted: false,
+        resource_type: ResourceType::Asset("json".to_string()),
+        origin: ResourceOrigin::ResourcePot(name),
+        source_path: None,
+        scope: Default::default(),
+        info: None,
+      },
+    );
+
+    Ok(Some(()))
+  }
+}
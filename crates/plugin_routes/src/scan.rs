@@ -0,0 +1,112 @@
+use std::path::{Path, PathBuf};
+
+use farmfe_core::config::routes::RoutesConfig;
+use farmfe_core::relative_path::RelativePath;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+  /// url path of the route, e.g. `/users/:id`
+  pub path: String,
+  /// absolute path of the page file
+  pub file: String,
+}
+
+pub fn page_dirs(config: &RoutesConfig, root: &str) -> Vec<PathBuf> {
+  config
+    .dirs
+    .iter()
+    .map(|dir| RelativePath::new(dir).to_logical_path(root))
+    .collect()
+}
+
+pub fn is_page_file(file: &str, config: &RoutesConfig, root: &str) -> bool {
+  let path = Path::new(file);
+  let ext_matched = path
+    .extension()
+    .is_some_and(|ext| config.extensions.iter().any(|e| ext == e.as_str()));
+
+  ext_matched && page_dirs(config, root).iter().any(|dir| path.starts_with(dir))
+}
+
+/// Scan all page dirs, routes are sorted by path so static segments come before dynamic ones
+pub fn scan_routes(config: &RoutesConfig, root: &str) -> Vec<Route> {
+  let mut routes = vec![];
+
+  for dir in page_dirs(config, root) {
+    let mut files = vec![];
+    walk(&dir, &mut files);
+
+    for file in files {
+      let file_str = file.to_string_lossy().to_string();
+
+      if !is_page_file(&file_str, config, root) {
+        continue;
+      }
+
+      let relative = file.strip_prefix(&dir).unwrap().with_extension("");
+      routes.push(Route {
+        path: route_path(&relative.to_string_lossy()),
+        file: file_str,
+      });
+    }
+  }
+
+  routes.sort_by(|a, b| {
+    let is_dynamic = |r: &Route| r.path.contains(':') || r.path.contains('*');
+    is_dynamic(a)
+      .cmp(&is_dynamic(b))
+      .then_with(|| a.path.cmp(&b.path))
+  });
+  routes
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return;
+  };
+
+  for entry in entries.flatten() {
+    let path = entry.path();
+
+    if path.is_dir() {
+      walk(&path, files);
+    } else {
+      files.push(path);
+    }
+  }
+}
+
+/// `users/[id]` -> `/users/:id`, `docs/[...slug]` -> `/docs/*`, `index` -> `/`
+pub fn route_path(relative: &str) -> String {
+  let segments = relative
+    .split(['/', '\\'])
+    .filter(|s| !s.is_empty() && *s != "index")
+    .map(|s| {
+      if let Some(name) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        if name.starts_with("...") {
+          "*".to_string()
+        } else {
+          format!(":{name}")
+        }
+      } else {
+        s.to_string()
+      }
+    })
+    .collect::<Vec<_>>();
+
+  format!("/{}", segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::route_path;
+
+  #[test]
+  fn test_route_path() {
+    assert_eq!(route_path("index"), "/");
+    assert_eq!(route_path("about"), "/about");
+    assert_eq!(route_path("users/index"), "/users");
+    assert_eq!(route_path("users/[id]"), "/users/:id");
+    assert_eq!(route_path("docs/[...slug]"), "/docs/*");
+  }
+}
@@ -1,32 +1,98 @@
-use std::{
-  collections::{HashSet, VecDeque},
-  sync::Arc,
-};
+use std::collections::{HashSet, VecDeque};
 
 use farmfe_core::{
-  context::CompilationContext,
-  module::{ModuleId, ModuleMetaData},
+  module::{module_graph::ModuleGraph, ModuleId, ModuleMetaData},
+  plugin::PluginModuleGraphUpdatedHookParams,
 };
 use farmfe_toolkit::swc_ecma_utils::contains_top_level_await;
 
-pub fn find_async_modules(context: &Arc<CompilationContext>) -> HashSet<ModuleId> {
-  let module_graph = context.module_graph.read();
-  let mut init_async_modules = HashSet::new();
+/// Async modules of the module graph, stored in the context under [crate::ASYNC_MODULES].
+/// A module is async if it contains top level await or it imports an async module statically (transitively)
+#[derive(Debug, Default)]
+pub struct AsyncModules {
+  /// modules that contain top level await
+  pub top_level_await: HashSet<ModuleId>,
+  /// all the async modules, rendered with the async wrapper
+  pub modules: HashSet<ModuleId>,
+  /// modules whose asyncness is changed by the last update, they should be rendered and executed again
+  pub changed: HashSet<ModuleId>,
+}
+
+impl AsyncModules {
+  pub fn new(module_graph: &ModuleGraph) -> Self {
+    let top_level_await = find_top_level_await_modules(module_graph);
+    let modules = propagate_async_modules(module_graph, &top_level_await);
+
+    Self {
+      top_level_await,
+      modules,
+      changed: HashSet::new(),
+    }
+  }
+
+  /// Update the top level await modules by the updated module graph and propagate them again,
+  /// so a module that no longer contains top level await makes its importers sync again
+  pub fn update(&mut self, module_graph: &ModuleGraph, param: &PluginModuleGraphUpdatedHookParams) {
+    for removed in &param.removed_modules_ids {
+      self.top_level_await.remove(removed);
+    }
 
-  for module in module_graph.modules() {
-    if let ModuleMetaData::Script(script_meta) = module.meta.as_ref() {
-      if contains_top_level_await(&script_meta.ast) {
-        init_async_modules.insert(module.id.clone());
+    for module_id in param
+      .added_modules_ids
+      .iter()
+      .chain(param.updated_modules_ids.iter())
+    {
+      if module_graph
+        .module(module_id)
+        .is_some_and(|module| is_top_level_await_module(&module.meta))
+      {
+        self.top_level_await.insert(module_id.clone());
+      } else {
+        self.top_level_await.remove(module_id);
       }
     }
+
+    let modules = propagate_async_modules(module_graph, &self.top_level_await);
+
+    // only scripts are rendered with the async wrapper, e.g. a html entry importing an async module is not affected
+    self.changed = modules
+      .symmetric_difference(&self.modules)
+      .filter(|module_id| {
+        module_graph
+          .module(module_id)
+          .is_some_and(|module| module.module_type.is_script())
+      })
+      .cloned()
+      .collect();
+    self.modules = modules;
   }
+}
+
+fn is_top_level_await_module(meta: &ModuleMetaData) -> bool {
+  matches!(meta, ModuleMetaData::Script(script_meta) if contains_top_level_await(&script_meta.ast))
+}
+
+pub fn find_top_level_await_modules(module_graph: &ModuleGraph) -> HashSet<ModuleId> {
+  module_graph
+    .modules()
+    .into_iter()
+    .filter(|module| is_top_level_await_module(&module.meta))
+    .map(|module| module.id.clone())
+    .collect()
+}
 
-  let mut queue = VecDeque::from(init_async_modules.into_iter().collect::<Vec<_>>());
+/// The importers of async modules are async too, dynamic imports are not affected
+pub fn propagate_async_modules(
+  module_graph: &ModuleGraph,
+  top_level_await: &HashSet<ModuleId>,
+) -> HashSet<ModuleId> {
+  let mut queue = top_level_await.iter().cloned().collect::<VecDeque<_>>();
   let mut async_modules = HashSet::new();
 
-  while !queue.is_empty() {
-    let module_id = queue.pop_front().unwrap();
-    async_modules.insert(module_id.clone());
+  while let Some(module_id) = queue.pop_front() {
+    if !async_modules.insert(module_id.clone()) {
+      continue;
+    }
 
     for (dept, edge) in module_graph.dependents(&module_id) {
       if !async_modules.contains(&dept) && !edge.is_dynamic() {
@@ -40,12 +106,11 @@ pub fn find_async_modules(context: &Arc<CompilationContext>) -> HashSet<ModuleId
 
 #[cfg(test)]
 mod tests {
-  use std::{collections::HashSet, sync::Arc};
+  use std::collections::HashSet;
 
   use farmfe_core::{
-    context::CompilationContext,
     module::{ModuleMetaData, ModuleType, ScriptModuleMetaData},
-    parking_lot::RwLock,
+    plugin::PluginModuleGraphUpdatedHookParams,
     swc_common::DUMMY_SP,
     swc_ecma_ast::{AwaitExpr, Expr, ExprStmt, Lit, Module, ModuleItem, Stmt},
   };
@@ -76,13 +141,28 @@ mod tests {
       shebang: None,
     };
 
-    let mut context = CompilationContext::new(Default::default(), vec![]).unwrap();
-    context.module_graph = Box::new(RwLock::new(module_graph));
-
-    let async_modules = super::find_async_modules(&Arc::new(context));
+    let mut async_modules = super::AsyncModules::new(&module_graph);
     println!("{:#?}", async_modules);
     assert_eq!(
-      async_modules,
+      async_modules.modules,
+      HashSet::from_iter(vec!["C".into(), "F".into(), "A".into()])
+    );
+
+    // C no longer contains top level await, its importers are sync again
+    let module_c = module_graph.module_mut(&"C".into()).unwrap();
+    module_c.meta.as_script_mut().ast.body.clear();
+    async_modules.update(
+      &module_graph,
+      &PluginModuleGraphUpdatedHookParams {
+        added_modules_ids: vec![],
+        removed_modules_ids: vec![],
+        updated_modules_ids: vec!["C".into()],
+      },
+    );
+
+    assert!(async_modules.modules.is_empty());
+    assert_eq!(
+      async_modules.changed,
       HashSet::from_iter(vec!["C".into(), "F".into(), "A".into()])
     );
   }
@@ -15,10 +15,13 @@ use farmfe_core::{
   resource::{Resource, ResourceType},
   swc_ecma_ast::{self, Decl, ModuleDecl, ModuleItem, Pat},
 };
-use farmfe_toolkit::common::{append_source_map_comment, generate_source_map_resource};
-use farmfe_toolkit::fs::transform_output_entry_filename;
+use farmfe_toolkit::common::{
+  append_source_map_comment, generate_source_map_resource, set_source_map_file,
+};
+use farmfe_toolkit::fs::transform_output_entry_filename_with_hash;
 use farmfe_toolkit::get_dynamic_resources_map::{
-  get_dynamic_resources_code, get_dynamic_resources_map,
+  get_dynamic_resources_code, get_dynamic_resources_map, get_resource_load_retry_code,
+  get_trusted_types_code,
 };
 use farmfe_toolkit::html::get_farm_global_this;
 use farmfe_toolkit::sourcemap::SourceMap;
@@ -182,9 +185,9 @@ fn get_export_info_code(
           if let Some(import_as) = import_as {
             match context.config.output.format {
               ModuleFormat::CommonJs => format!("module.exports.{import_as} = entry.{name};"),
-              ModuleFormat::EsModule => format!(
-                "var {name}=entry.{name};export {{ {name} as {import_as} }};"
-              ),
+              ModuleFormat::EsModule => {
+                format!("var {name}=entry.{name};export {{ {name} as {import_as} }};")
+              }
             }
           } else {
             match context.config.output.format {
@@ -212,6 +215,7 @@ struct EntryResourceAndDepResources {
   pub entry_js_resource_source_map_name: String,
   pub entry_js_resource_code: Arc<String>,
   pub dep_resources: Vec<String>,
+  pub css_resources: Vec<String>,
   pub dynamic_resources: String,
   pub dynamic_module_resources_map: String,
 }
@@ -269,13 +273,26 @@ fn get_entry_resource_and_dep_resources_name(
         }
       }
     }
+
+    // the css of the entry is included by the host page with the entry manifest, so it's not loaded again
+    if context.config.entry_manifest.is_some() {
+      for resource_id in resource_pot.resources() {
+        let resource = resource_map
+          .get(resource_id)
+          .expect("resource is not found");
+
+        if matches!(resource.resource_type, ResourceType::Css) {
+          result.css_resources.push(resource.name.clone());
+        }
+      }
+    }
   }
 
   let dynamic_resources_map = get_dynamic_resources_map(
     module_group_graph,
     entry,
     &resource_pot_map,
-    resource_map,
+    |name| resource_map.get(name).map(|r| r.resource_type.clone()),
     module_graph,
   );
   let (dynamic_resources, dynamic_module_resources_map) =
@@ -313,6 +330,7 @@ pub fn handle_entry_resources(
         entry_js_resource_source_map_name,
         entry_js_resource_source_map,
         mut dep_resources,
+        mut css_resources,
         dynamic_resources,
         dynamic_module_resources_map,
       } = get_entry_resource_and_dep_resources_name(
@@ -324,6 +342,7 @@ pub fn handle_entry_resources(
         context,
       );
       dep_resources.sort();
+      css_resources.sort();
 
       if !should_inject_runtime {
         should_inject_runtime = !dep_resources.is_empty();
@@ -346,16 +365,20 @@ pub fn handle_entry_resources(
 
       // 4. setInitialLoadedResources and setDynamicModuleResourcesMap
       let set_initial_loaded_resources_code = format!(
-        r#"{farm_global_this}.{FARM_MODULE_SYSTEM}.setInitialLoadedResources([{initial_loaded_resources}]);"#,
+        r#"{trusted_types}{farm_global_this}.{FARM_MODULE_SYSTEM}.setInitialLoadedResources([{initial_loaded_resources}]);"#,
+        trusted_types =
+          get_trusted_types_code(&context.config.runtime.trusted_types, &farm_global_this),
         initial_loaded_resources = dep_resources
           .iter()
+          .chain(css_resources.iter())
           .map(|rn| format!("'{rn}'"))
           .collect::<Vec<_>>()
           .join(",")
       );
       let set_dynamic_resources_map_code = if !dynamic_resources.is_empty() {
         format!(
-          r#"{farm_global_this}.{FARM_MODULE_SYSTEM}.setDynamicModuleResourcesMap({dynamic_resources},{dynamic_module_resources_map});"#,
+          r#"{farm_global_this}.{FARM_MODULE_SYSTEM}.setDynamicModuleResourcesMap({dynamic_resources},{dynamic_module_resources_map});{}"#,
+          get_resource_load_retry_code(&context.config.runtime.retry, &farm_global_this)
         )
       } else {
         "".to_string()
@@ -448,6 +471,11 @@ pub fn handle_entry_resources(
 
         source_map.bytes = src_map;
         source_map.name = entry_js_resource_source_map_name.clone();
+
+        if context.config.sourcemap_base.is_some() {
+          set_source_map_file(&mut source_map, &entry_js_resource.name);
+        }
+
         append_source_map_comment(entry_js_resource, &source_map, &context.config.sourcemap);
         // update sourcemap resource
         resources_map.insert(entry_js_resource_source_map_name, source_map);
@@ -462,7 +490,7 @@ pub fn handle_entry_resources(
   }
 }
 
-fn create_runtime_code(
+pub(crate) fn create_runtime_code(
   resources_map: &HashMap<String, Resource>,
   context: &Arc<CompilationContext>,
 ) -> String {
@@ -511,11 +539,12 @@ fn create_runtime_code(
 
 fn create_farm_runtime_resource(runtime_code: &str, context: &Arc<CompilationContext>) -> Resource {
   let bytes = runtime_code.to_string().into_bytes();
-  let name = transform_output_entry_filename(
+  let hash_config = &context.config.hash;
+  let name = transform_output_entry_filename_with_hash(
     context.config.output.entry_filename.clone(),
     "__farm_runtime",
     "__farm_runtime",
-    &bytes,
+    || hash_config.hash(&bytes, hash_config.length()),
     "js", // todo: support configuring extension
           // match context.config.output.format {
           //   ModuleFormat::EsModule => "mjs",
@@ -529,6 +558,8 @@ fn create_farm_runtime_resource(runtime_code: &str, context: &Arc<CompilationCon
     // this resource should be Js instead of Runtime because it may cause duplicated runtime code when HMR if it's Runtime
     resource_type: ResourceType::Js,
     origin: ResourceOrigin::ResourcePot(name),
+    source_path: None,
+    scope: Default::default(),
     info: None,
   }
 }
@@ -0,0 +1,281 @@
+//! Bundle the module group of each web worker into a standalone worker resource, e.g. `new Worker(new URL('./worker.ts', import.meta.url))`.
+//! The url of the worker is rendered as a placeholder of the worker id, and replaced by the url of the worker resource here.
+//! In browser, a worker served from a cross origin public path (e.g. a CDN) is created from a same-origin blob url that
+//! loads the worker resource by `importScripts` for classic workers or `import` for module workers.
+
+use std::{
+  collections::{HashMap, HashSet},
+  path::Path,
+  sync::Arc,
+};
+
+use farmfe_core::{
+  config::{FARM_MODULE_SYSTEM, FARM_PUBLIC_PATH_GLOBAL},
+  context::CompilationContext,
+  module::ModuleId,
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde_json::{self, json, Map},
+};
+use farmfe_toolkit::{fs::transform_output_filename_with_hash, html::get_farm_global_this};
+
+use crate::handle_entry_resources::create_runtime_code;
+
+const WORKER_URL_PLACEHOLDER: &str = "__FARM_WORKER_URL__";
+const WORKERS_MANIFEST: &str = "workers-manifest.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerType {
+  Classic,
+  /// created with `{ type: "module" }`
+  Module,
+}
+
+impl WorkerType {
+  fn as_str(&self) -> &'static str {
+    match self {
+      WorkerType::Classic => "classic",
+      WorkerType::Module => "module",
+    }
+  }
+}
+
+pub fn worker_url_placeholder(worker_id: &str, worker_type: WorkerType) -> String {
+  format!(
+    "{WORKER_URL_PLACEHOLDER}({}:{worker_id})",
+    worker_type.as_str()
+  )
+}
+
+pub fn handle_worker_resources(
+  resources_map: &mut HashMap<String, Resource>,
+  context: &Arc<CompilationContext>,
+) {
+  let module_graph = context.module_graph.read();
+  let module_group_graph = context.module_group_graph.read();
+  let resource_pot_map = context.resource_pot_map.read();
+
+  let workers = module_group_graph
+    .module_groups()
+    .into_iter()
+    .filter(|module_group| module_graph.is_worker(&module_group.id))
+    .collect::<Vec<_>>();
+  let worker_ids = workers.iter().map(|w| w.id.clone()).collect::<HashSet<_>>();
+
+  // remove the worker resources generated by the previous compilation
+  resources_map.retain(|_, resource| {
+    !matches!(resource.resource_type, ResourceType::Js)
+      || !matches!(&resource.origin, ResourceOrigin::Module(m) if worker_ids.contains(m))
+  });
+
+  if workers.is_empty() {
+    return;
+  }
+
+  let farm_global_this = get_farm_global_this(
+    &context.config.runtime.namespace,
+    &context.config.output.target_env,
+  );
+  // the runtime refers to the global object as `window`, which is `self` in workers
+  let runtime_code = format!(
+    "var window = self;{}",
+    create_runtime_code(resources_map, context)
+  );
+  let mut worker_urls = vec![];
+
+  for worker in workers {
+    let mut dep_resources = vec![];
+    let mut code = runtime_code.clone();
+
+    for resource_pot_id in worker.sorted_resource_pots(&module_graph, &resource_pot_map) {
+      let resource_pot = resource_pot_map
+        .resource_pot(&resource_pot_id)
+        .expect("resource pot is not found");
+
+      for resource_name in resource_pot.resources() {
+        let Some(resource) = resources_map.get(resource_name) else {
+          continue;
+        };
+
+        if matches!(resource.resource_type, ResourceType::Js) {
+          code.push('\n');
+          code.push_str(&String::from_utf8_lossy(&resource.bytes));
+          dep_resources.push(format!("'{}'", resource.name));
+        }
+      }
+    }
+
+    code.push_str(&format!(
+      r#"
+var farmModuleSystem = {farm_global_this}.{FARM_MODULE_SYSTEM};farmModuleSystem.setInitialLoadedResources([{}]);farmModuleSystem.bootstrap();farmModuleSystem.require("{}");"#,
+      dep_resources.join(","),
+      worker.id.id(context.config.mode.clone()),
+    ));
+
+    let bytes = code.into_bytes();
+    let hash_config = &context.config.hash;
+    let name = transform_output_filename_with_hash(
+      context.config.output.filename.clone(),
+      &worker_resource_name(&worker.id),
+      || hash_config.hash(&bytes, hash_config.length()),
+      "js",
+    );
+
+    // resolved against the runtime public path by [worker_url_expr]
+    let url = if context.config.output.is_runtime_public_path() {
+      name.clone()
+    } else {
+      format!(
+        "{}/{}",
+        context.config.output.public_path.trim_end_matches('/'),
+        name
+      )
+    };
+    worker_urls.push((worker.id.clone(), url));
+    resources_map.insert(
+      name.clone(),
+      Resource {
+        name,
+        bytes,
+        emitted: false,
+        resource_type: ResourceType::Js,
+        origin: ResourceOrigin::Module(worker.id.clone()),
+        source_path: None,
+        scope: Default::default(),
+        info: None,
+      },
+    );
+  }
+
+  let is_browser = context.config.output.target_env.is_browser();
+  let runtime_public_path = context.config.output.is_runtime_public_path();
+  // the runtime public path may be cross origin, so the origin is checked at runtime
+  let cross_origin =
+    runtime_public_path || is_cross_origin_public_path(&context.config.output.public_path);
+  let mut worker_types = HashMap::new();
+
+  for resource in resources_map.values_mut() {
+    if !matches!(resource.resource_type, ResourceType::Js)
+      || !String::from_utf8_lossy(&resource.bytes).contains(WORKER_URL_PLACEHOLDER)
+    {
+      continue;
+    }
+
+    let mut code = String::from_utf8_lossy(&resource.bytes).to_string();
+
+    for (worker_id, url) in &worker_urls {
+      for worker_type in [WorkerType::Classic, WorkerType::Module] {
+        let placeholder =
+          worker_url_placeholder(&worker_id.id(context.config.mode.clone()), worker_type);
+
+        if !code.contains(&placeholder) {
+          continue;
+        }
+
+        // in browser the whole url argument is rendered as the placeholder, in node it's the source of `new URL`
+        if is_browser {
+          let expr = worker_url_expr(url, worker_type, cross_origin, runtime_public_path);
+
+          for quote in ['"', '\''] {
+            code = code.replace(&format!("{quote}{placeholder}{quote}"), &expr);
+          }
+        } else {
+          code = code.replace(&placeholder, url);
+        }
+
+        if worker_type == WorkerType::Module || !worker_types.contains_key(worker_id) {
+          worker_types.insert(worker_id.clone(), worker_type);
+        }
+      }
+    }
+
+    resource.bytes = code.into_bytes();
+  }
+
+  if context.config.output.cross_origin_isolated {
+    let workers = worker_urls
+      .iter()
+      .filter_map(|(worker_id, url)| {
+        let worker_type = worker_types.get(worker_id)?;
+        Some((
+          worker_id.relative_path().to_string(),
+          json!({ "url": url, "type": worker_type.as_str() }),
+        ))
+      })
+      .collect::<Map<_, _>>();
+    let manifest = json!({
+      "headers": {
+        "Cross-Origin-Opener-Policy": "same-origin",
+        "Cross-Origin-Embedder-Policy": "require-corp",
+      },
+      "workers": workers,
+    });
+
+    resources_map.insert(
+      WORKERS_MANIFEST.to_string(),
+      Resource {
+        name: WORKERS_MANIFEST.to_string(),
+        bytes: serde_json::to_vec_pretty(&manifest).unwrap(),
+        emitted: false,
+        resource_type: ResourceType::Asset("json".to_string()),
+        origin: ResourceOrigin::ResourcePot(WORKERS_MANIFEST.to_string()),
+        source_path: None,
+        scope: Default::default(),
+        info: None,
+      },
+    );
+  }
+}
+
+/// e.g. `https://cdn.example.com/` or `//cdn.example.com/`
+fn is_cross_origin_public_path(public_path: &str) -> bool {
+  public_path.starts_with("//") || public_path.contains("://")
+}
+
+/// The expression passed to `new Worker`. A cross origin worker url is used directly only if the page is served
+/// from the same origin, otherwise the worker is created from a same-origin blob url that loads the worker resource.
+/// With `publicPath: "runtime"` the url is prefixed with [FARM_PUBLIC_PATH_GLOBAL]
+fn worker_url_expr(
+  url: &str,
+  worker_type: WorkerType,
+  cross_origin: bool,
+  runtime_public_path: bool,
+) -> String {
+  let url = if runtime_public_path {
+    format!(
+      "{FARM_PUBLIC_PATH_GLOBAL} + {}",
+      serde_json::to_string(url).unwrap()
+    )
+  } else {
+    serde_json::to_string(url).unwrap()
+  };
+
+  if !cross_origin {
+    return url;
+  }
+
+  let loader = match worker_type {
+    WorkerType::Classic => r#""importScripts(" + JSON.stringify(u) + ");""#,
+    WorkerType::Module => r#""import " + JSON.stringify(u) + ";""#,
+  };
+
+  format!(
+    r#"(function(u){{try{{if(new URL(u,location.href).origin===location.origin)return u}}catch(e){{}}return URL.createObjectURL(new Blob([{loader}],{{type:"text/javascript"}}))}})({url})"#
+  )
+}
+
+/// e.g. `src_worker.worker` for `src/worker.ts`
+fn worker_resource_name(worker_id: &ModuleId) -> String {
+  Path::new(worker_id.relative_path())
+    .with_extension("")
+    .to_string_lossy()
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+        c
+      } else {
+        '_'
+      }
+    })
+    .chain(".worker".chars())
+    .collect()
+}
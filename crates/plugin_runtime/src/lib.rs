@@ -1,10 +1,6 @@
 #![feature(box_patterns)]
 
-use std::{
-  any::Any,
-  collections::{HashMap, HashSet, VecDeque},
-  sync::Arc,
-};
+use std::{any::Any, collections::HashMap, sync::Arc};
 
 use farmfe_core::{
   config::{
@@ -15,12 +11,13 @@ use farmfe_core::{
   context::CompilationContext,
   enhanced_magic_string::types::{MappingsOptionHires, SourceMapOptions},
   error::CompilationError,
-  module::{ModuleId, ModuleType},
+  module::ModuleType,
   plugin::{
     Plugin, PluginFinalizeResourcesHookParams, PluginGenerateResourcesHookResult,
     PluginHookContext, PluginLoadHookParam, PluginLoadHookResult, PluginResolveHookParam,
     PluginResolveHookResult, PluginTransformHookResult,
   },
+  regex,
   resource::{
     resource_pot::{ResourcePot, ResourcePotMetaData, ResourcePotType},
     Resource, ResourceOrigin, ResourceType,
@@ -28,19 +25,26 @@ use farmfe_core::{
   serde_json,
 };
 use farmfe_toolkit::{
+  common::SourcemapSources,
   fs::read_file_utf8,
   html::get_farm_global_this,
-  script::{module_type_from_id, set_module_system_for_module_meta},
+  script::{
+    import_attributes::{is_json_import_source, strip_json_import_query},
+    module_type_from_id, set_module_system_for_module_meta,
+  },
 };
+use farmfe_utils::relative;
 
 use insert_runtime_plugins::insert_runtime_plugins;
 use render_resource_pot::*;
 
 pub use farmfe_toolkit::script::constant::RUNTIME_SUFFIX;
+pub use find_async_modules::AsyncModules;
 pub const ASYNC_MODULES: &str = "async_modules";
 
 mod find_async_modules;
 mod handle_entry_resources;
+mod handle_worker_resources;
 mod insert_runtime_plugins;
 pub mod render_resource_pot;
 
@@ -61,7 +65,7 @@ impl Plugin for FarmPluginRuntime {
   }
 
   fn config(&self, config: &mut Config) -> farmfe_core::error::Result<Option<()>> {
-    if config.output.target_env.is_library() {
+    if config.output.is_native_esm() {
       return Ok(None);
     }
     // runtime package entry file
@@ -87,6 +91,26 @@ impl Plugin for FarmPluginRuntime {
       },
     );
 
+    if let Some(name) = &config.runtime.helpers_resource {
+      let mut test = vec![ConfigRegex::new("@swc/helpers/")];
+
+      if !config.runtime.swc_helpers_path.is_empty() {
+        let helpers_dir = relative(&config.root, &config.runtime.swc_helpers_path);
+        test.push(ConfigRegex::new(&format!(
+          "^{}/",
+          regex::escape(&helpers_dir)
+        )));
+      }
+
+      config.partial_bundling.enforce_resources.insert(
+        1,
+        PartialBundlingEnforceResourceConfig {
+          name: name.clone(),
+          test,
+        },
+      );
+    }
+
     config.define.insert(
       "'<@__farm_global_this__@>'".to_string(),
       serde_json::Value::String(format!(
@@ -117,17 +141,31 @@ impl Plugin for FarmPluginRuntime {
           .ends_with(RUNTIME_SUFFIX))
     {
       let ori_source = param.source.replace(RUNTIME_SUFFIX, "");
-      let resolve_result = context.plugin_driver.resolve(
-        &PluginResolveHookParam {
-          source: ori_source,
-          ..param.clone()
-        },
-        context,
-        &PluginHookContext {
-          caller: hook_context.add_caller(PLUGIN_NAME),
-          meta: HashMap::new(),
+      let alias = &context.config.runtime.alias;
+      let resolve = |source: String| {
+        context.plugin_driver.resolve(
+          &PluginResolveHookParam {
+            source,
+            ..param.clone()
+          },
+          context,
+          &PluginHookContext {
+            caller: hook_context.add_caller(PLUGIN_NAME),
+            meta: HashMap::new(),
+          },
+        )
+      };
+
+      let resolve_result = match alias.get(&ori_source) {
+        Some(replacement) => resolve(replacement.clone())?,
+        None => match resolve(ori_source)? {
+          // the runtime module is replaced by its absolute path
+          Some(res) if alias.contains_key(&res.resolved_path) => {
+            resolve(alias[&res.resolved_path].clone())?
+          }
+          res => res,
         },
-      )?;
+      };
 
       if let Some(mut res) = resolve_result {
         res.resolved_path = format!("{}{}", res.resolved_path, RUNTIME_SUFFIX);
@@ -207,7 +245,7 @@ impl Plugin for FarmPluginRuntime {
     // render start is only called once when the compilation start
     context.custom.insert(
       ASYNC_MODULES.to_string(),
-      Box::new(find_async_modules::find_async_modules(context)),
+      Box::new(AsyncModules::new(&context.module_graph.read())),
     );
 
     Ok(Some(()))
@@ -221,48 +259,9 @@ impl Plugin for FarmPluginRuntime {
     // detect async module like top level await when module graph updated
     // module graph updated is called when the module graph is updated
     let mut async_modules = context.custom.get_mut(ASYNC_MODULES).unwrap();
-    let async_modules = async_modules.downcast_mut::<HashSet<ModuleId>>().unwrap();
+    let async_modules = async_modules.downcast_mut::<AsyncModules>().unwrap();
 
-    for remove in &param.removed_modules_ids {
-      async_modules.remove(remove);
-    }
-
-    let module_graph = context.module_graph.read();
-    let mut added_async_modules = vec![];
-    // find added modules that contains top level await
-    let mut analyze_top_level_await = |module_id: &ModuleId| {
-      let module = module_graph.module(module_id).unwrap();
-
-      if module.module_type.is_script() {
-        let ast = &module.meta.as_script().ast;
-        let dependencies = module_graph.dependencies(module_id);
-        let is_deps_async = dependencies
-          .iter()
-          .any(|(dep, edge)| async_modules.contains(dep) && !edge.is_dynamic());
-        if is_deps_async || farmfe_toolkit::swc_ecma_utils::contains_top_level_await(ast) {
-          added_async_modules.push(module_id.clone());
-        }
-      }
-    };
-    for added in &param.added_modules_ids {
-      analyze_top_level_await(added);
-    }
-    for updated in &param.updated_modules_ids {
-      analyze_top_level_await(updated);
-    }
-
-    let mut queue = VecDeque::from(added_async_modules.into_iter().collect::<Vec<_>>());
-
-    while !queue.is_empty() {
-      let module_id = queue.pop_front().unwrap();
-      async_modules.insert(module_id.clone());
-
-      for (dept, edge) in module_graph.dependents(&module_id) {
-        if !async_modules.contains(&dept) && !edge.is_dynamic() {
-          queue.push_back(dept);
-        }
-      }
-    }
+    async_modules.update(&context.module_graph.read(), param);
 
     Ok(Some(()))
   }
@@ -273,18 +272,22 @@ impl Plugin for FarmPluginRuntime {
     context: &Arc<CompilationContext>,
     _hook_context: &PluginHookContext,
   ) -> farmfe_core::error::Result<Option<ResourcePotMetaData>> {
-    if !context.config.output.target_env.is_library()
+    if !context.config.output.is_native_esm()
       && matches!(resource_pot.resource_pot_type, ResourcePotType::Js)
     {
       let async_modules = self.get_async_modules(context);
-      let async_modules = async_modules.downcast_ref::<HashSet<ModuleId>>().unwrap();
+      let async_modules = &async_modules
+        .downcast_ref::<AsyncModules>()
+        .unwrap()
+        .modules;
       let module_graph = context.module_graph.read();
       let external_config = ExternalConfig::from(&*context.config);
       let RenderedJsResourcePot {
         mut bundle,
         rendered_modules,
         external_modules,
-      } = resource_pot_to_runtime_object(resource_pot, &module_graph, async_modules, context)?;
+      } =
+        resource_pot_to_runtime_object(resource_pot, &module_graph, async_modules, None, context)?;
 
       let mut external_modules_str = None;
 
@@ -309,7 +312,13 @@ impl Plugin for FarmPluginRuntime {
           name = format!("__farm_external_module_{name}");
 
           let import_str = if context.config.output.format == ModuleFormat::EsModule {
-            format!("import * as {name} from {external_module:?};")
+            // preserve `with { type: "json" }` of json externals
+            if is_json_import_source(&external_module) {
+              let source = strip_json_import_query(&external_module);
+              format!("import * as {name} from {source:?} with {{ type: \"json\" }};")
+            } else {
+              format!("import * as {name} from {external_module:?};")
+            }
           } else {
             format!("var {name} = require({external_module:?});")
           };
@@ -384,13 +393,11 @@ impl Plugin for FarmPluginRuntime {
         rendered_modules,
         rendered_content: Arc::new(bundle.to_string()),
         rendered_map_chain: if context.config.sourcemap.enabled(resource_pot.immutable) {
-          let root = context.config.root.clone();
+          let sources = SourcemapSources::new(&context.config);
           let map = bundle
             .generate_map(SourceMapOptions {
               include_content: Some(true),
-              remap_source: Some(Box::new(move |src| {
-                format!("/{}", farmfe_utils::relative(&root, src))
-              })),
+              remap_source: Some(Box::new(move |src| sources.remap(src))),
               hires: if context.config.minify.enabled() {
                 Some(MappingsOptionHires::Boundary)
               } else {
@@ -439,6 +446,8 @@ impl Plugin for FarmPluginRuntime {
           emitted: true, // do not emit runtime resource by default. The runtime will be injected into the html or script entry.
           resource_type: ResourceType::Runtime,
           origin: ResourceOrigin::ResourcePot(resource_pot.id.clone()),
+          source_path: None,
+          scope: Default::default(),
           info: None,
         },
         source_map: None,
@@ -453,13 +462,25 @@ impl Plugin for FarmPluginRuntime {
     param: &mut PluginFinalizeResourcesHookParams,
     context: &Arc<CompilationContext>,
   ) -> farmfe_core::error::Result<Option<()>> {
-    if context.config.output.target_env.is_library() {
+    if context.config.output.is_native_esm() {
       return Ok(None);
     }
 
     let async_modules = self.get_async_modules(context);
-    let async_modules = async_modules.downcast_ref::<HashSet<ModuleId>>().unwrap();
-    handle_entry_resources::handle_entry_resources(param.resources_map, context, async_modules);
+    let async_modules = &async_modules
+      .downcast_ref::<AsyncModules>()
+      .unwrap()
+      .modules;
+    // resources of a vendor reference build only register the modules, the runtime is provided by the builds linking against it
+    if !context
+      .config
+      .vendor_reference
+      .as_ref()
+      .is_some_and(|c| c.is_vendor_build())
+    {
+      handle_entry_resources::handle_entry_resources(param.resources_map, context, async_modules);
+    }
+    handle_worker_resources::handle_worker_resources(param.resources_map, context);
 
     Ok(Some(()))
   }
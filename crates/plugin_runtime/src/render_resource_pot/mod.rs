@@ -22,12 +22,13 @@ use farmfe_core::{
 };
 use farmfe_toolkit::common::MinifyBuilder;
 
-use farmfe_utils::hash::sha256;
 use render_module::RenderModuleOptions;
+use rendered_module_cache::RenderedModuleCache;
 
 use self::render_module::{render_module, RenderModuleResult};
 
 mod render_module;
+pub mod rendered_module_cache;
 // mod farm_module_system;
 mod source_replacer;
 mod transform_async_module;
@@ -53,10 +54,12 @@ mod transform_module_decls;
 ///    }
 /// }
 /// ```
+/// When `rendered_module_cache` is provided, unchanged modules reuse their rendered result instead of being rendered again.
 pub fn resource_pot_to_runtime_object(
   resource_pot: &ResourcePot,
   module_graph: &ModuleGraph,
   async_modules: &HashSet<ModuleId>,
+  rendered_module_cache: Option<&RenderedModuleCache>,
   context: &Arc<CompilationContext>,
 ) -> Result<RenderedJsResourcePot> {
   let modules = Mutex::new(vec![]);
@@ -64,8 +67,12 @@ pub fn resource_pot_to_runtime_object(
   let minify_builder =
     MinifyBuilder::create_builder(&context.config.minify, Some(MinifyMode::Module));
 
+  let minify_js = minify_builder
+    .minify_options
+    .as_ref()
+    .is_some_and(|options| options.js);
   let is_enabled_minify = |module_id: &ModuleId| {
-    minify_builder.is_enabled(&module_id.resolved_path(&context.config.root))
+    minify_js && minify_builder.is_enabled(&module_id.resolved_path(&context.config.root))
   };
 
   resource_pot
@@ -75,6 +82,17 @@ pub fn resource_pot_to_runtime_object(
       let module = module_graph
         .module(m_id)
         .unwrap_or_else(|| panic!("Module not found: {m_id:?}"));
+      let is_async_module = async_modules.contains(m_id);
+
+      let rendered_module_cache_key = rendered_module_cache
+        .map(|_| RenderedModuleCache::cache_key(module, module_graph, async_modules, context));
+
+      if let (Some(cache), Some(key)) = (rendered_module_cache, &rendered_module_cache_key) {
+        if let Some(rendered_script_module) = cache.get(m_id, key, context) {
+          modules.lock().push(rendered_script_module);
+          return Ok(());
+        }
+      }
 
       let mut cache_store_key = None;
 
@@ -83,12 +101,13 @@ pub fn resource_pot_to_runtime_object(
         let content_hash = module.content_hash.clone();
         let store_key = CacheStoreKey {
           name: m_id.to_string() + "-resource_pot_to_runtime_object",
-          key: sha256(
+          key: context.config.hash.hash(
             format!(
-              "resource_pot_to_runtime_object_{}_{}_{}",
+              "resource_pot_to_runtime_object_{}_{}_{}_{}",
               content_hash,
               m_id.to_string(),
-              module.used_exports.join(",")
+              module.used_exports.join(","),
+              is_async_module
             )
             .as_bytes(),
             32,
@@ -115,7 +134,23 @@ pub fn resource_pot_to_runtime_object(
         }
       }
 
-      let is_async_module = async_modules.contains(m_id);
+      // immutable modules rendered by other projects on this machine
+      let global_cache_key =
+        RenderedModuleCache::global_cache_key(module, module_graph, async_modules, context);
+
+      if let Some(cached) = global_cache_key
+        .as_ref()
+        .and_then(|key| RenderedModuleCache::read_global(key, context))
+      {
+        modules.lock().push(RenderedScriptModule {
+          module: cached.to_magic_string(context),
+          id: cached.id,
+          rendered_module: cached.rendered_module,
+          external_modules: cached.external_modules,
+        });
+        return Ok(());
+      }
+
       let RenderModuleResult {
         rendered_module,
         external_modules,
@@ -147,6 +182,33 @@ pub fn resource_pot_to_runtime_object(
           .expect("failed to write resource pot to runtime object cache");
       }
 
+      if let Some(key) = global_cache_key {
+        RenderedModuleCache::write_global(
+          &key,
+          &CacheRenderedScriptModule::new(
+            m_id.clone(),
+            code.clone(),
+            rendered_module.clone(),
+            external_modules.clone(),
+            source_map_chain.clone(),
+          ),
+          context,
+        );
+      }
+
+      if let (Some(cache), Some(key)) = (rendered_module_cache, rendered_module_cache_key) {
+        cache.insert(
+          key,
+          CacheRenderedScriptModule::new(
+            m_id.clone(),
+            code.clone(),
+            rendered_module.clone(),
+            external_modules.clone(),
+            source_map_chain.clone(),
+          ),
+        );
+      }
+
       let mut module = MagicString::new(
         &code,
         Some(MagicStringOptions {
@@ -4,12 +4,15 @@ use farmfe_core::{
   context::CompilationContext,
   error::CompilationError,
   module::{module_graph::ModuleGraph, Module, ModuleId, ModuleSystem},
+  plugin::{PluginModuleSystemInteropHookParam, PluginRenderModuleSystemHookParam},
   resource::resource_pot::RenderedModule,
   swc_common::{comments::SingleThreadedComments, util::take::Take, Mark},
   swc_ecma_ast::{ArrowExpr, BlockStmtOrExpr, Expr, ExprStmt},
 };
 use farmfe_toolkit::{
-  common::{build_source_map, create_swc_source_map, MinifyBuilder, Source},
+  common::{
+    build_source_map, create_swc_source_map, remove_license_comments, MinifyBuilder, Source,
+  },
   minify::minify_js_module,
   script::{
     codegen_module,
@@ -54,6 +57,49 @@ pub struct RenderModuleOptions<'a, F: Fn(&ModuleId) -> bool> {
   pub context: &'a Arc<CompilationContext>,
 }
 
+/// Let plugins render the module of a custom module system and decide the module system it interops as,
+/// returns the marks of the rendered ast
+fn render_module_system(
+  ast: &mut SwcModule,
+  module_system: &mut ModuleSystem,
+  unresolved_mark: Mark,
+  top_level_mark: Mark,
+  module: &Module,
+  context: &Arc<CompilationContext>,
+) -> farmfe_core::error::Result<(Mark, Mark)> {
+  let ModuleSystem::Custom(name) = module_system.clone() else {
+    return Ok((unresolved_mark, top_level_mark));
+  };
+
+  if name == "unknown" {
+    return Ok((unresolved_mark, top_level_mark));
+  }
+
+  let mut param = PluginRenderModuleSystemHookParam {
+    module_id: &module.id,
+    module_system: &name,
+    ast,
+    unresolved_mark,
+    top_level_mark,
+  };
+  context
+    .plugin_driver
+    .render_module_system(&mut param, context)?;
+  let marks = (param.unresolved_mark, param.top_level_mark);
+
+  if let Some(interop) = context.plugin_driver.module_system_interop(
+    &PluginModuleSystemInteropHookParam {
+      module_id: &module.id,
+      module_system: &name,
+    },
+    context,
+  )? {
+    *module_system = interop;
+  }
+
+  Ok(marks)
+}
+
 pub fn render_module<'a, F: Fn(&ModuleId) -> bool>(
   options: RenderModuleOptions<'a, F>,
 ) -> farmfe_core::error::Result<RenderModuleResult> {
@@ -73,6 +119,8 @@ pub fn render_module<'a, F: Fn(&ModuleId) -> bool>(
   let mut external_modules = vec![];
   let comments: SingleThreadedComments = module.meta.as_script().comments.clone().into();
   let minify_enabled = is_enabled_minify(&module.id);
+  let mut module_system = module.meta.as_script().module_system.clone();
+  let mut module_system_result = Ok(());
 
   try_with(cm.clone(), &context.meta.script.globals, || {
     let (unresolved_mark, top_level_mark) = if module.meta.as_script().unresolved_mark == 0
@@ -89,11 +137,24 @@ pub fn render_module<'a, F: Fn(&ModuleId) -> bool>(
       (unresolved_mark, top_level_mark)
     };
 
-    // replace commonjs require('./xxx') to require('./xxx', true)
-    if matches!(
-      module.meta.as_script().module_system,
-      ModuleSystem::CommonJs | ModuleSystem::Hybrid
+    // custom module systems that are not normalized when building are rendered by plugins
+    let (unresolved_mark, top_level_mark) = match render_module_system(
+      &mut cloned_module,
+      &mut module_system,
+      unresolved_mark,
+      top_level_mark,
+      module,
+      context,
     ) {
+      Ok(marks) => marks,
+      Err(e) => {
+        module_system_result = Err(e);
+        return;
+      }
+    };
+
+    // replace commonjs require('./xxx') to require('./xxx', true)
+    if matches!(module_system, ModuleSystem::CommonJs | ModuleSystem::Hybrid) {
       cloned_module.visit_mut_with(&mut ExistingCommonJsRequireVisitor::new(
         unresolved_mark,
         top_level_mark,
@@ -103,10 +164,7 @@ pub fn render_module<'a, F: Fn(&ModuleId) -> bool>(
     cloned_module.visit_mut_with(&mut paren_remover(Some(&comments)));
 
     // ESM to commonjs, then commonjs to farm's runtime module systems
-    if matches!(
-      module.meta.as_script().module_system,
-      ModuleSystem::EsModule | ModuleSystem::Hybrid
-    ) {
+    if matches!(module_system, ModuleSystem::EsModule | ModuleSystem::Hybrid) {
       transform_module_decls(
         &mut cloned_module,
         unresolved_mark,
@@ -131,11 +189,7 @@ pub fn render_module<'a, F: Fn(&ModuleId) -> bool>(
       ..Default::default()
     }));
 
-    if matches!(
-      module.meta.as_script().module_system,
-      ModuleSystem::EsModule
-    ) && is_async_module
-    {
+    if matches!(module_system, ModuleSystem::EsModule) && is_async_module {
       // transform async module to meet the requirements of farm runtime
       transform_async_module::transform_async_module(&mut cloned_module);
     }
@@ -158,10 +212,16 @@ pub fn render_module<'a, F: Fn(&ModuleId) -> bool>(
 
     external_modules = source_replacer.external_modules;
   })?;
+  module_system_result?;
 
   // remove shebang
   cloned_module.shebang = None;
 
+  // the license comments are emitted to a separate resource instead
+  if minify_enabled && context.config.output.extract_license_comments {
+    remove_license_comments(&comments);
+  }
+
   let sourcemap_enabled = context.config.sourcemap.enabled(module.immutable);
   // wrap module function
   // let wrapped_module = wrap_module_ast(cloned_module);
@@ -0,0 +1,194 @@
+use std::{collections::HashSet, path::Path, sync::Arc};
+
+use farmfe_core::{
+  context::CompilationContext,
+  dashmap::DashMap,
+  module::{module_graph::ModuleGraph, Module, ModuleId, ModuleMetaData},
+  rkyv::Deserialize,
+  serialize, try_deserialize,
+};
+
+use super::{CacheRenderedScriptModule, RenderedScriptModule};
+
+pub const RENDERED_MODULE_CACHE: &str = "rendered_module_cache";
+
+/// In memory cache of the rendered modules of hmr update resource pots.
+/// Modules that are updated only because of graph churn, e.g. parents that are re-executed, are not rendered again when they are not changed.
+/// Rendered immutable modules are also shared with other projects through the machine global cache, see [farmfe_core::cache::global_cache].
+#[derive(Default)]
+pub struct RenderedModuleCache {
+  modules: DashMap<ModuleId, (String, CacheRenderedScriptModule)>,
+}
+
+impl RenderedModuleCache {
+  /// The rendered code depends on the content of the module, the used exports, whether it's async and how its dependencies are resolved.
+  /// The module system and the asyncness of the dependencies decide how they are imported, e.g. the interop of a cjs dependency
+  /// or awaiting an async dependency, so they are part of the key too
+  pub fn cache_key(
+    module: &Module,
+    module_graph: &ModuleGraph,
+    async_modules: &HashSet<ModuleId>,
+    context: &Arc<CompilationContext>,
+  ) -> String {
+    let mut deps = module_graph
+      .dependencies_ids(&module.id)
+      .into_iter()
+      .map(|id| {
+        let module_system = module_graph
+          .module(&id)
+          .and_then(|dep| match &*dep.meta {
+            ModuleMetaData::Script(script) => Some(format!("{:?}", script.module_system)),
+            _ => None,
+          })
+          .unwrap_or_default();
+
+        format!(
+          "{}:{module_system}:{}",
+          id.to_string(),
+          async_modules.contains(&id)
+        )
+      })
+      .collect::<Vec<_>>();
+    deps.sort();
+
+    context.config.hash.hash(
+      format!(
+        "{}_{}_{}_{}",
+        module.content_hash,
+        module.used_exports.join(","),
+        async_modules.contains(&module.id),
+        deps.join(",")
+      )
+      .as_bytes(),
+      32,
+    )
+  }
+
+  pub fn get(
+    &self,
+    module_id: &ModuleId,
+    key: &str,
+    context: &Arc<CompilationContext>,
+  ) -> Option<RenderedScriptModule> {
+    let cached = self.modules.get(module_id)?;
+    let (cached_key, cached_module) = cached.value();
+
+    if cached_key != key {
+      return None;
+    }
+
+    Some(RenderedScriptModule {
+      id: cached_module.id.clone(),
+      module: cached_module.to_magic_string(context),
+      rendered_module: cached_module.rendered_module.clone(),
+      external_modules: cached_module.external_modules.clone(),
+    })
+  }
+
+  pub fn insert(&self, key: String, module: CacheRenderedScriptModule) {
+    self.modules.insert(module.id.clone(), (key, module));
+  }
+
+  pub fn remove(&self, module_id: &ModuleId) {
+    self.modules.remove(module_id);
+  }
+
+  /// Key of the rendered immutable module in the machine global cache, [None] if the global cache is not enabled
+  /// or the module does not belong to a package
+  pub fn global_cache_key(
+    module: &Module,
+    module_graph: &ModuleGraph,
+    async_modules: &HashSet<ModuleId>,
+    context: &Arc<CompilationContext>,
+  ) -> Option<String> {
+    if !module.immutable {
+      return None;
+    }
+
+    let global_cache = context.cache_manager.global_cache.as_ref()?;
+    let root = &context.config.root;
+    let package = global_cache.find_package(Path::new(&module.id.resolved_path(root)))?;
+    // options that change the rendered code but are not part of the options of the global cache
+    let render_hash = format!(
+      "{}_{}_{}_{:?}",
+      Self::cache_key(module, module_graph, async_modules, context),
+      context.config.sourcemap.enabled(true),
+      context.config.output.extract_license_comments,
+      context.config.comments
+    );
+
+    Some(global_cache.gen_rendered_module_key(&package, root, &module.id.to_string(), &render_hash))
+  }
+
+  /// [None] if the module is not cached or the cached bytes are invalid, the global cache is shared by other versions of farm
+  pub fn read_global(
+    key: &str,
+    context: &Arc<CompilationContext>,
+  ) -> Option<CacheRenderedScriptModule> {
+    let bytes = context.cache_manager.global_cache.as_ref()?.read(key)?;
+
+    try_deserialize!(&bytes, CacheRenderedScriptModule)
+  }
+
+  pub fn write_global(
+    key: &str,
+    module: &CacheRenderedScriptModule,
+    context: &Arc<CompilationContext>,
+  ) {
+    if let Some(global_cache) = &context.cache_manager.global_cache {
+      global_cache.write(key, &serialize!(module));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{collections::HashSet, sync::Arc};
+
+  use farmfe_core::{
+    config::Config,
+    context::CompilationContext,
+    module::{ModuleMetaData, ModuleSystem, ScriptModuleMetaData},
+  };
+  use farmfe_testing_helpers::construct_test_module_graph;
+
+  use super::RenderedModuleCache;
+
+  #[test]
+  fn cache_key_of_dependencies() {
+    let context = Arc::new(CompilationContext::new(Config::default(), vec![]).unwrap());
+    let mut module_graph = construct_test_module_graph();
+    module_graph.modules_mut().into_iter().for_each(|module| {
+      module.meta = Box::new(ModuleMetaData::Script(ScriptModuleMetaData {
+        module_system: ModuleSystem::EsModule,
+        ..Default::default()
+      }));
+    });
+    let cache_key = |module_graph: &farmfe_core::module::module_graph::ModuleGraph,
+                     async_modules: &HashSet<_>| {
+      let module_a = module_graph.module(&"A".into()).unwrap();
+      RenderedModuleCache::cache_key(module_a, module_graph, async_modules, &context)
+    };
+    let key = cache_key(&module_graph, &HashSet::new());
+
+    // A imports C, the interop of C changes when it becomes commonjs
+    module_graph
+      .module_mut(&"C".into())
+      .unwrap()
+      .meta
+      .as_script_mut()
+      .module_system = ModuleSystem::CommonJs;
+    let cjs_key = cache_key(&module_graph, &HashSet::new());
+    assert_ne!(key, cjs_key);
+
+    // C is awaited by A when it becomes async
+    let async_key = cache_key(&module_graph, &HashSet::from(["C".into()]));
+    assert_ne!(cjs_key, async_key);
+
+    // modules that are not dependencies of A do not change its key
+    assert_eq!(
+      cjs_key,
+      cache_key(&module_graph, &HashSet::from(["B".into()]))
+    );
+  }
+}
@@ -10,17 +10,23 @@
 use farmfe_core::{
   config::{Mode, TargetEnv, FARM_DYNAMIC_REQUIRE, FARM_REQUIRE},
   module::{module_graph::ModuleGraph, ModuleId},
-  plugin::ResolveKind,
+  plugin::{constants::WORKER_RESOLVE_KIND, ResolveKind},
   swc_common::{Mark, DUMMY_SP},
   swc_ecma_ast::{
-    Bool, CallExpr, Callee, Expr, ExprOrSpread, Ident, Lit, MemberExpr, MemberProp, Str,
+    Bool, CallExpr, Callee, Expr, ExprOrSpread, Ident, Lit, MemberExpr, MemberProp, NewExpr, Str,
   },
 };
 use farmfe_toolkit::{
-  script::{is_commonjs_require, is_dynamic_import},
+  script::{
+    get_worker_url_source,
+    import_attributes::{is_json_import_source, strip_json_import_query},
+    is_commonjs_require, is_dynamic_import, is_module_worker,
+  },
   swc_ecma_visit::{VisitMut, VisitMutWith},
 };
 
+use crate::handle_worker_resources::{worker_url_placeholder, WorkerType};
+
 /// replace all `require('./xxx')` to the actual id and transform require('./xxx'). for example:
 /// ```js
 /// // a.js is originally a commonjs module
@@ -80,6 +86,9 @@ impl<'a> VisitMut for SourceReplacer<'a> {
           raw: None,
         }));
       }
+    } else if let Expr::New(new_expr) = expr {
+      self.replace_worker_url(new_expr);
+      new_expr.visit_mut_children_with(self);
     } else {
       expr.visit_mut_children_with(self);
     }
@@ -94,6 +103,41 @@ enum SourceReplaceResult {
 }
 
 impl SourceReplacer<'_> {
+  /// replace the url of `new Worker(new URL('./worker.ts', import.meta.url))` with a placeholder of the worker id,
+  /// the placeholder is replaced by the url of the worker resource when the resources are finalized.
+  /// In browser the whole `new URL(...)` is replaced, so the url can be replaced by a same-origin fallback expression
+  fn replace_worker_url(&self, new_expr: &mut NewExpr) {
+    let Some(source) = get_worker_url_source(new_expr) else {
+      return;
+    };
+
+    let id = self.module_graph.get_dep_by_source(
+      &self.module_id,
+      &source,
+      Some(ResolveKind::Custom(WORKER_RESOLVE_KIND.to_string())),
+    );
+    let worker_type = if is_module_worker(new_expr) {
+      WorkerType::Module
+    } else {
+      WorkerType::Classic
+    };
+    let placeholder = Box::new(Expr::Lit(Lit::Str(
+      worker_url_placeholder(&id.id(self.mode.clone()), worker_type).into(),
+    )));
+
+    if self.target_env.is_browser() {
+      if let Some(args) = new_expr.args.as_mut() {
+        args[0].expr = placeholder;
+      }
+    } else if let Some(box Expr::New(NewExpr {
+      args: Some(url_args),
+      ..
+    })) = new_expr.args.as_mut().map(|args| &mut args[0].expr)
+    {
+      url_args[0].expr = placeholder;
+    }
+  }
+
   fn find_real_module_meta_by_source(&self, source: &str) -> Option<(ModuleId, ResolveKind)> {
     let mut id = None;
     // treat non dynamic import as the same
@@ -193,6 +237,12 @@ impl SourceReplacer<'_> {
         if dep_module.external {
           self.external_modules.push(id.to_string());
 
+          // `import("./data.json", { with: { type: "json" } })` is kept, the query marking the attribute is removed
+          if is_json_import_source(&source) {
+            str.value = strip_json_import_query(&source).into();
+            str.raw = None;
+          }
+
           return SourceReplaceResult::NotReplaced;
         }
 
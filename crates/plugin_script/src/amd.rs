@@ -0,0 +1,283 @@
+//! Convert AMD modules (e.g. RequireJS modules) to esm, so legacy codebases can be bundled without rewriting.
+//! for example:
+//! ```js
+//! define(['./a', 'exports'], function (a, exports) { exports.b = a + 1; });
+//! ```
+//! will be transformed to:
+//! ```js
+//! import __farm_amd_dep_0 from "./a";
+//! var __farm_amd_module = { exports: {} };
+//! var __farm_amd_result = (function (a, exports) { exports.b = a + 1; })(__farm_amd_dep_0, __farm_amd_module.exports);
+//! export default __farm_amd_result !== void 0 ? __farm_amd_result : __farm_amd_module.exports;
+//! ```
+//! The dependencies required by the simplified CommonJS wrapper `define(function (require) { require('./a') })` are imported too.
+use farmfe_core::{
+  error::{CompilationError, Result},
+  serde_json,
+  swc_common::{Mark, Span, SyntaxContext, DUMMY_SP},
+  swc_ecma_ast::{
+    CallExpr, Callee, EsVersion, Expr, ExprOrSpread, ExprStmt, Ident, Lit, Module, ModuleItem,
+    ParenExpr, Stmt,
+  },
+};
+use farmfe_toolkit::{
+  script::parse_module,
+  swc_ecma_parser::{EsSyntax, Syntax},
+  swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith},
+};
+
+pub const AMD_MODULE_SYSTEM: &str = "amd";
+
+const FACTORY: &str = "__farm_amd_factory";
+/// dependencies that are provided by the AMD loader instead of modules
+const SPECIAL_DEPS: [&str; 3] = ["require", "exports", "module"];
+
+/// Return the index of the `define()` statement if the module is an AMD module,
+/// which means the module has no import/export and only one top level `define()` call.
+/// Must be called inside the globals of the module
+pub fn find_amd_define(ast: &Module, unresolved_mark: Mark) -> Option<usize> {
+  let mut index = None;
+
+  for (i, item) in ast.body.iter().enumerate() {
+    match item {
+      ModuleItem::ModuleDecl(_) => return None,
+      ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+        expr: box Expr::Call(call),
+        ..
+      }))
+        if is_define_call(call, unresolved_mark) =>
+      {
+        if index.is_some() {
+          return None;
+        }
+
+        index = Some(i);
+      }
+      _ => {}
+    }
+  }
+
+  index
+}
+
+fn is_define_call(call: &CallExpr, unresolved_mark: Mark) -> bool {
+  matches!(
+    &call.callee,
+    Callee::Expr(box Expr::Ident(Ident { sym, span, .. }))
+      if sym == "define" && span.ctxt.outer() == unresolved_mark
+  )
+}
+
+/// Must be called inside the globals of the module
+pub fn transform_amd_to_esm(
+  ast: &mut Module,
+  module_id: &str,
+  unresolved_mark: Mark,
+  top_level_mark: Mark,
+) -> Result<()> {
+  let Some(index) = find_amd_define(ast, unresolved_mark) else {
+    return Ok(());
+  };
+
+  let ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+    expr: box Expr::Call(call),
+    ..
+  })) = &mut ast.body[index]
+  else {
+    unreachable!("find_amd_define returns the index of a define() call");
+  };
+
+  let error = |msg: &str| CompilationError::TransformError {
+    resolved_path: module_id.to_string(),
+    msg: format!("Unsupported AMD module: {msg}"),
+  };
+
+  let mut args = std::mem::take(&mut call.args);
+
+  if args.iter().any(|arg| arg.spread.is_some()) {
+    return Err(error("spread arguments of define() are not supported"));
+  }
+
+  // named module, `define('name', deps, factory)`, the name is ignored
+  if args.len() > 1 && matches!(&*args[0].expr, Expr::Lit(Lit::Str(_))) {
+    args.remove(0);
+  }
+
+  let (deps, factory) = match args.len() {
+    1 => (None, args.remove(0).expr),
+    2 => {
+      let deps = args.remove(0).expr;
+      (
+        Some(deps_from_expr(&deps).ok_or_else(|| error("deps must be an array of strings"))?),
+        args.remove(0).expr,
+      )
+    }
+    _ => return Err(error("define() must be called with a factory")),
+  };
+
+  let params_len = match &*factory {
+    Expr::Fn(f) => Some(f.function.params.len()),
+    Expr::Arrow(arrow) => Some(arrow.params.len()),
+    _ => None,
+  };
+
+  let code = if let Some(params_len) = params_len {
+    // the default deps of a factory without deps is `['require', 'exports', 'module']`
+    let deps = deps.unwrap_or_else(|| {
+      SPECIAL_DEPS[..params_len.min(SPECIAL_DEPS.len())]
+        .iter()
+        .map(|d| d.to_string())
+        .collect()
+    });
+    let mut required = RequireCollector::default();
+    factory.visit_with(&mut required);
+
+    generate_factory_call(&deps, &required.sources)
+  } else {
+    // the factory is the value of the module, e.g. `define({ a: 1 })`
+    format!("export default {FACTORY};")
+  };
+
+  let mut generated = parse_module(
+    module_id,
+    &code,
+    Syntax::Es(EsSyntax::default()),
+    EsVersion::EsNext,
+  )
+  .map_err(|e| error(&e.to_string()))?
+  .ast;
+
+  generated.visit_mut_with(&mut GeneratedCodeMarker {
+    ctxt: SyntaxContext::empty().apply_mark(top_level_mark),
+    factory: Some(factory),
+  });
+
+  ast.body.splice(index..index + 1, generated.body);
+
+  Ok(())
+}
+
+fn deps_from_expr(expr: &Expr) -> Option<Vec<String>> {
+  let Expr::Array(array) = expr else {
+    return None;
+  };
+
+  array
+    .elems
+    .iter()
+    .map(|elem| match elem {
+      Some(ExprOrSpread {
+        spread: None,
+        expr: box Expr::Lit(Lit::Str(str)),
+      }) => Some(str.value.to_string()),
+      _ => None,
+    })
+    .collect()
+}
+
+fn generate_factory_call(deps: &[String], required: &[String]) -> String {
+  let mut sources: Vec<&String> = vec![];
+
+  for source in deps.iter().chain(required) {
+    if !SPECIAL_DEPS.contains(&source.as_str()) && !sources.contains(&source) {
+      sources.push(source);
+    }
+  }
+
+  let local = |source: &String| {
+    let i = sources.iter().position(|s| *s == source).unwrap();
+    format!("__farm_amd_dep_{i}")
+  };
+
+  let mut code = String::new();
+
+  for source in &sources {
+    code.push_str(&format!(
+      "import {} from {};\n",
+      local(source),
+      serde_json::to_string(source).unwrap()
+    ));
+  }
+
+  code.push_str("var __farm_amd_module = { exports: {} };\n");
+
+  if deps.iter().any(|d| d == "require") {
+    let modules = sources
+      .iter()
+      .map(|s| format!("{}: {}", serde_json::to_string(s).unwrap(), local(s)))
+      .collect::<Vec<_>>()
+      .join(", ");
+    code.push_str(&format!(
+      "var __farm_amd_modules = {{ {modules} }};\nfunction __farm_amd_require(__farm_amd_id) {{ return __farm_amd_modules[__farm_amd_id]; }}\n"
+    ));
+  }
+
+  let args = deps
+    .iter()
+    .map(|d| match d.as_str() {
+      "require" => "__farm_amd_require".to_string(),
+      "exports" => "__farm_amd_module.exports".to_string(),
+      "module" => "__farm_amd_module".to_string(),
+      _ => local(d),
+    })
+    .collect::<Vec<_>>()
+    .join(", ");
+
+  code.push_str(&format!(
+    "var __farm_amd_result = {FACTORY}({args});\nexport default __farm_amd_result !== void 0 ? __farm_amd_result : __farm_amd_module.exports;\n"
+  ));
+
+  code
+}
+
+/// string literal sources of `require('./a')` in the factory
+#[derive(Default)]
+struct RequireCollector {
+  sources: Vec<String>,
+}
+
+impl Visit for RequireCollector {
+  fn visit_call_expr(&mut self, call: &CallExpr) {
+    if let (Callee::Expr(box Expr::Ident(ident)), [arg]) = (&call.callee, &call.args[..]) {
+      if let (true, None, Expr::Lit(Lit::Str(str))) =
+        (ident.sym == "require", arg.spread, &*arg.expr)
+      {
+        self.sources.push(str.value.to_string());
+      }
+    }
+
+    call.visit_children_with(self);
+  }
+}
+
+/// Generated code is parsed from another source file, reset its spans so they do not point into the current module.
+/// All identifiers of the generated code are top level bindings, and the factory placeholder is replaced by the original factory
+struct GeneratedCodeMarker {
+  ctxt: SyntaxContext,
+  factory: Option<Box<Expr>>,
+}
+
+impl VisitMut for GeneratedCodeMarker {
+  fn visit_mut_span(&mut self, span: &mut Span) {
+    *span = DUMMY_SP;
+  }
+
+  fn visit_mut_ident(&mut self, ident: &mut Ident) {
+    ident.span = DUMMY_SP;
+    ident.span.ctxt = self.ctxt;
+  }
+
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    if matches!(expr, Expr::Ident(ident) if ident.sym == FACTORY) {
+      if let Some(factory) = self.factory.take() {
+        *expr = Expr::Paren(ParenExpr {
+          span: DUMMY_SP,
+          expr: factory,
+        });
+        return;
+      }
+    }
+
+    expr.visit_mut_children_with(self);
+  }
+}
@@ -1,15 +1,15 @@
 use farmfe_core::{
   module::ModuleId,
-  plugin::{PluginAnalyzeDepsHookResultEntry, ResolveKind},
+  plugin::{constants::WORKER_RESOLVE_KIND, PluginAnalyzeDepsHookResultEntry, ResolveKind},
   swc_common::Mark,
   swc_ecma_ast::{
-    CallExpr, ExportAll, Expr, Lit, Module, ModuleDecl, ModuleItem, NamedExport,
+    CallExpr, ExportAll, Expr, Lit, Module, ModuleDecl, ModuleItem, NamedExport, NewExpr,
     TsExternalModuleRef, TsImportEqualsDecl,
   },
 };
 
 use farmfe_toolkit::{
-  script::{is_commonjs_require, is_dynamic_import},
+  script::{get_worker_url_source, is_commonjs_require, is_dynamic_import},
   swc_ecma_visit::{Visit, VisitWith},
 };
 
@@ -99,7 +99,10 @@ impl<'a> Visit for DepsAnalyzer<'a> {
   }
 
   fn visit_call_expr(&mut self, call_expr: &CallExpr) {
-    if call_expr.args.len() != 1 {
+    // the dynamic import may have the options argument, e.g. `import("./a", { with: { type: "json" } })`
+    let max_args = if is_dynamic_import(call_expr) { 2 } else { 1 };
+
+    if call_expr.args.is_empty() || call_expr.args.len() > max_args {
       call_expr.visit_children_with(self);
       return;
     }
@@ -122,4 +125,15 @@ impl<'a> Visit for DepsAnalyzer<'a> {
 
     call_expr.visit_children_with(self);
   }
+
+  fn visit_new_expr(&mut self, new_expr: &NewExpr) {
+    if let Some(source) = get_worker_url_source(new_expr) {
+      self.insert_dep(PluginAnalyzeDepsHookResultEntry {
+        source,
+        kind: ResolveKind::Custom(WORKER_RESOLVE_KIND.to_string()),
+      });
+    }
+
+    new_expr.visit_children_with(self);
+  }
 }
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use farmfe_core::{
+  module::DynamicImportHints,
+  swc_common::{
+    comments::{Comments, SingleThreadedComments},
+    Spanned,
+  },
+  swc_ecma_ast::{CallExpr, Expr, Lit, Module},
+};
+use farmfe_toolkit::{
+  script::is_dynamic_import,
+  swc_ecma_visit::{Visit, VisitWith},
+};
+
+/// Keys of the magic comments, the webpack ones are supported for compatibility
+const CHUNK_NAME_KEYS: [&str; 2] = ["farmChunkName", "webpackChunkName"];
+const PREFETCH_KEYS: [&str; 2] = ["farmPrefetch", "webpackPrefetch"];
+const PRELOAD_KEYS: [&str; 2] = ["farmPreload", "webpackPreload"];
+
+/// Collect the hints of the dynamic imports from magic comments, e.g. `import(/* farmChunkName: "settings", farmPrefetch: true */ './settings')`.
+/// Returns source of the dynamic import -> hints, dynamic imports without hints are not included
+pub fn collect_dynamic_import_hints(
+  ast: &Module,
+  comments: &SingleThreadedComments,
+) -> HashMap<String, DynamicImportHints> {
+  let mut collector = DynamicImportHintsCollector {
+    comments,
+    hints: HashMap::new(),
+  };
+  ast.visit_with(&mut collector);
+
+  collector.hints
+}
+
+struct DynamicImportHintsCollector<'a> {
+  comments: &'a SingleThreadedComments,
+  hints: HashMap<String, DynamicImportHints>,
+}
+
+impl<'a> Visit for DynamicImportHintsCollector<'a> {
+  fn visit_call_expr(&mut self, call_expr: &CallExpr) {
+    if is_dynamic_import(call_expr) && !call_expr.args.is_empty() {
+      if let box Expr::Lit(Lit::Str(str)) = &call_expr.args[0].expr {
+        let mut hints = DynamicImportHints::default();
+
+        for comment in self
+          .comments
+          .get_leading(call_expr.args[0].span_lo())
+          .unwrap_or_default()
+        {
+          if hints.chunk_name.is_none() {
+            hints.chunk_name = parse_string_value(&comment.text, &CHUNK_NAME_KEYS);
+          }
+          hints.prefetch |= parse_true_value(&comment.text, &PREFETCH_KEYS);
+          hints.preload |= parse_true_value(&comment.text, &PRELOAD_KEYS);
+        }
+
+        if hints != DynamicImportHints::default() {
+          self.hints.insert(str.value.to_string(), hints);
+        }
+      }
+    }
+
+    call_expr.visit_children_with(self);
+  }
+}
+
+/// The value after `key:` in the comment, e.g. ` "settings", farmPrefetch: true `
+fn find_value<'a>(comment: &'a str, keys: &[&str]) -> Option<&'a str> {
+  keys.iter().find_map(|key| {
+    let rest = comment[comment.find(key)? + key.len()..].trim_start();
+    Some(rest.strip_prefix(':')?.trim_start())
+  })
+}
+
+/// `farmChunkName: "settings", webpackPrefetch: true` -> `settings`
+fn parse_string_value(comment: &str, keys: &[&str]) -> Option<String> {
+  let value = find_value(comment, keys)?;
+  let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+  let value = &value[1..];
+  let value = &value[..value.find(quote)?];
+
+  (!value.is_empty()).then(|| value.to_string())
+}
+
+/// `farmPrefetch: true` -> true
+fn parse_true_value(comment: &str, keys: &[&str]) -> bool {
+  find_value(comment, keys).is_some_and(|value| value.starts_with("true"))
+}
@@ -0,0 +1,109 @@
+use farmfe_core::{
+  module::ModuleId,
+  swc_ecma_ast::{
+    CallExpr, ExportAll, Expr, ExprOrSpread, ImportDecl, KeyValueProp, Lit, NamedExport, ObjectLit,
+    Prop, PropName, PropOrSpread, Str,
+  },
+};
+use farmfe_toolkit::{
+  script::import_attributes::{
+    add_json_import_query, get_import_attributes, IMPORT_ATTRIBUTE_TYPE, IMPORT_ATTRIBUTE_TYPE_JSON,
+  },
+  script::is_dynamic_import,
+  swc_ecma_visit::{VisitMut, VisitMutWith},
+};
+
+/// Apply the semantics of import attributes to static imports, re-exports and dynamic imports like `import("./a", { with: { type: "json" } })`:
+/// * `with { type: "json" }` appends `?type=json` to the source, so the module is loaded by the json pipeline whatever its extension is
+/// * unsupported attributes are collected as warnings, the `with` clause itself is kept untouched
+pub struct ImportAttributesVisitor<'a> {
+  module_id: &'a ModuleId,
+  pub warnings: Vec<String>,
+}
+
+impl<'a> ImportAttributesVisitor<'a> {
+  pub fn new(module_id: &'a ModuleId) -> Self {
+    Self {
+      module_id,
+      warnings: vec![],
+    }
+  }
+
+  fn apply(&mut self, src: &mut Str, with: Option<&ObjectLit>) {
+    let Some(with) = with else {
+      return;
+    };
+
+    for (key, value) in get_import_attributes(with) {
+      match (key.as_str(), value.as_deref()) {
+        (IMPORT_ATTRIBUTE_TYPE, Some(IMPORT_ATTRIBUTE_TYPE_JSON)) => {
+          src.value = add_json_import_query(&src.value).into();
+          src.raw = None;
+        }
+        (IMPORT_ATTRIBUTE_TYPE, value) => self.warnings.push(format!(
+          "Unsupported import attribute `type: {}` of `{}` in {}, only `type: \"json\"` is supported",
+          value.unwrap_or("<non-string>"),
+          src.value,
+          self.module_id.to_string()
+        )),
+        (key, _) => self.warnings.push(format!(
+          "Unknown import attribute `{key}` of `{}` in {}, it is ignored",
+          src.value,
+          self.module_id.to_string()
+        )),
+      }
+    }
+  }
+}
+
+/// `{ with: { type: "json" } }` of `import("./a", { with: { type: "json" } })`
+fn dynamic_import_attributes(options: &Expr) -> Option<&ObjectLit> {
+  let Expr::Object(options) = options else {
+    return None;
+  };
+
+  options.props.iter().find_map(|prop| match prop {
+    PropOrSpread::Prop(box Prop::KeyValue(KeyValueProp {
+      key: PropName::Ident(key),
+      value: box Expr::Object(with),
+    }))
+      if &*key.sym == "with" =>
+    {
+      Some(with)
+    }
+    _ => None,
+  })
+}
+
+impl<'a> VisitMut for ImportAttributesVisitor<'a> {
+  fn visit_mut_import_decl(&mut self, n: &mut ImportDecl) {
+    self.apply(&mut n.src, n.with.as_deref());
+  }
+
+  fn visit_mut_export_all(&mut self, n: &mut ExportAll) {
+    self.apply(&mut n.src, n.with.as_deref());
+  }
+
+  fn visit_mut_named_export(&mut self, n: &mut NamedExport) {
+    if let Some(src) = &mut n.src {
+      self.apply(src, n.with.as_deref());
+    }
+  }
+
+  fn visit_mut_call_expr(&mut self, n: &mut CallExpr) {
+    if is_dynamic_import(n) {
+      if let [ExprOrSpread {
+        spread: None,
+        expr: box Expr::Lit(Lit::Str(src)),
+      }, ExprOrSpread {
+        spread: None,
+        expr: options,
+      }] = &mut n.args[..]
+      {
+        self.apply(src, dynamic_import_attributes(options));
+      }
+    }
+
+    n.visit_mut_children_with(self);
+  }
+}
@@ -8,7 +8,7 @@ use std::{
 
 use deps_analyzer::DepsAnalyzer;
 use farmfe_core::{
-  config::{Config, ModuleFormat, TargetEnv},
+  config::{module_types::CustomModuleTypeTreatAs, Config, ModuleFormat, TargetEnv},
   context::CompilationContext,
   error::Result,
   module::{
@@ -16,9 +16,10 @@ use farmfe_core::{
     VIRTUAL_MODULE_PREFIX,
   },
   plugin::{
-    Plugin, PluginAnalyzeDepsHookParam, PluginFinalizeModuleHookParam,
-    PluginGenerateResourcesHookResult, PluginHookContext, PluginLoadHookParam,
-    PluginLoadHookResult, PluginParseHookParam, PluginProcessModuleHookParam,
+    Plugin, PluginAnalyzeDepsHookParam, PluginDetectModuleSystemHookParam,
+    PluginFinalizeModuleHookParam, PluginGenerateResourcesHookResult, PluginHookContext,
+    PluginLoadHookParam, PluginLoadHookResult, PluginNormalizeModuleSystemHookParam,
+    PluginParseHookParam, PluginProcessModuleHookParam,
   },
   resource::{
     resource_pot::{ResourcePot, ResourcePotType},
@@ -41,18 +42,26 @@ use farmfe_toolkit::{
   swc_ecma_visit::VisitMutWith,
 };
 
+use amd::{find_amd_define, transform_amd_to_esm, AMD_MODULE_SYSTEM};
+use dynamic_import_hints::collect_dynamic_import_hints;
+use import_attributes::ImportAttributesVisitor;
 use import_meta_visitor::{replace_import_meta_url, ImportMetaVisitor};
 #[cfg(feature = "swc_plugin")]
 use swc_plugins::{init_plugin_module_cache_once, transform_by_swc_plugins};
 
+mod amd;
 mod deps_analyzer;
+mod dynamic_import_hints;
+mod import_attributes;
 mod import_meta_visitor;
 #[cfg(feature = "swc_plugin")]
 mod swc_plugins;
 mod swc_script_transforms;
 mod transform_import_meta_url;
+mod transform_require_context;
 
 use transform_import_meta_url::transform_url_with_import_meta_url;
+use transform_require_context::transform_require_context;
 
 /// ScriptPlugin is used to support compiling js/ts/jsx/tsx/... files, support loading, parse, analyze dependencies and code generation.
 /// Note that we do not do transforms here, the transforms (e.g. strip types, jsx...) are handled in a separate plugin (farmfe_plugin_swc_transforms).
@@ -70,13 +79,23 @@ impl Plugin for FarmPluginScript {
   fn load(
     &self,
     param: &PluginLoadHookParam,
-    _context: &Arc<CompilationContext>,
+    context: &Arc<CompilationContext>,
     _hook_context: &PluginHookContext,
   ) -> Result<Option<PluginLoadHookResult>> {
     let module_type = module_type_from_id(param.resolved_path);
 
     if let Some(module_type) = module_type {
-      if module_type.is_script() {
+      // the plugins transform the declared `js-via-plugin` extensions to js, see `transform` of the build stage
+      let is_js_via_plugin = matches!(&module_type, ModuleType::Custom(ext)
+        if context.config.custom_module_type(ext) == Some(CustomModuleTypeTreatAs::JsViaPlugin));
+
+      if is_js_via_plugin {
+        Ok(Some(PluginLoadHookResult {
+          content: read_file_utf8(param.resolved_path)?,
+          module_type,
+          source_map: None,
+        }))
+      } else if module_type.is_script() {
         let content = read_file_utf8(param.resolved_path)?;
 
         let map =
@@ -124,6 +143,7 @@ impl Plugin for FarmPluginScript {
           param.module_type.is_typescript(),
         ));
 
+        let dynamic_import_hints = collect_dynamic_import_hints(&swc_module, &comments);
         let meta = ScriptModuleMetaData {
           ast: swc_module,
           top_level_mark: top_level_mark.as_u32(),
@@ -133,6 +153,10 @@ impl Plugin for FarmPluginScript {
           hmr_self_accepted: false,
           hmr_accepted_deps: Default::default(),
           comments: CommentsMetaData::from(comments),
+          original_module_system: None,
+          dynamic_import_hints,
+          used_defines: Default::default(),
+          pruned_imports: Default::default(),
           custom: Default::default(),
         };
 
@@ -196,6 +220,28 @@ impl Plugin for FarmPluginScript {
 
       transform_url_with_import_meta_url(ast, &comments);
 
+      // apply import attributes, e.g. `with { type: "json" }`
+      let mut import_attributes_visitor = ImportAttributesVisitor::new(param.module_id);
+      ast.visit_mut_with(&mut import_attributes_visitor);
+
+      if !import_attributes_visitor.warnings.is_empty() {
+        let mut log_store = context.log_store.lock();
+
+        for warning in import_attributes_visitor.warnings {
+          log_store.add_warning(warning);
+        }
+      }
+
+      // transform webpack-style `require.context`, marks are only accessible inside the globals
+      GLOBALS.set(&context.meta.script.globals, || {
+        transform_require_context(
+          ast,
+          &cur_dir,
+          &param.module_id.to_string(),
+          Mark::from_u32(script.unresolved_mark),
+        )
+      })?;
+
       transform_import_meta_glob(
         ast,
         context.config.root.clone(),
@@ -208,6 +254,48 @@ impl Plugin for FarmPluginScript {
     Ok(Some(()))
   }
 
+  fn detect_module_system(
+    &self,
+    param: &PluginDetectModuleSystemHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<String>> {
+    if !param.module_type.is_script() {
+      return Ok(None);
+    }
+
+    let script = param.meta.as_script();
+    let is_amd = GLOBALS.set(&context.meta.script.globals, || {
+      find_amd_define(&script.ast, Mark::from_u32(script.unresolved_mark)).is_some()
+    });
+
+    Ok(is_amd.then(|| AMD_MODULE_SYSTEM.to_string()))
+  }
+
+  fn normalize_module_system(
+    &self,
+    param: &mut PluginNormalizeModuleSystemHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if param.module_system != AMD_MODULE_SYSTEM {
+      return Ok(None);
+    }
+
+    let script = param.meta.as_script_mut();
+    let unresolved_mark = Mark::from_u32(script.unresolved_mark);
+    let top_level_mark = Mark::from_u32(script.top_level_mark);
+
+    GLOBALS.set(&context.meta.script.globals, || {
+      transform_amd_to_esm(
+        &mut script.ast,
+        &param.module_id.to_string(),
+        unresolved_mark,
+        top_level_mark,
+      )
+    })?;
+
+    Ok(Some(()))
+  }
+
   fn analyze_deps(
     &self,
     param: &mut PluginAnalyzeDepsHookParam,
@@ -254,7 +342,7 @@ impl Plugin for FarmPluginScript {
     let target_env = context.config.output.target_env.clone();
     let format = context.config.output.format;
 
-    let is_library = target_env.is_library();
+    let is_library = context.config.output.is_native_esm();
 
     if is_library && matches!(format, ModuleFormat::CommonJs) {
       replace_import_meta_url(&mut param.module.meta.as_script_mut().ast)
@@ -296,6 +384,8 @@ impl Plugin for FarmPluginScript {
         emitted: false,
         resource_type: ResourceType::Js,
         origin: ResourceOrigin::ResourcePot(resource_pot.id.clone()),
+        source_path: None,
+        scope: Default::default(),
         info: None,
       };
       let mut source_map = None;
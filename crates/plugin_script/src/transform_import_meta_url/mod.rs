@@ -9,7 +9,10 @@ use farmfe_core::{
     MemberProp, MetaPropExpr, MetaPropKind, Module, ObjectLit, Prop, PropName, PropOrSpread,
   },
 };
-use farmfe_toolkit::swc_ecma_visit::{VisitMut, VisitMutWith};
+use farmfe_toolkit::{
+  script::get_worker_url_source,
+  swc_ecma_visit::{VisitMut, VisitMutWith},
+};
 use farmfe_utils::is_skip_action_by_comment;
 
 fn normalized_glob_pattern(pattern: String) -> String {
@@ -229,6 +232,11 @@ impl<'a> ImportMetaURLVisitor<'a> {
 
 impl<'a> VisitMut for ImportMetaURLVisitor<'a> {
   fn visit_mut_expr(&mut self, node: &mut Expr) {
+    // the url of a web worker is kept, the worker is bundled as a dependency of the module
+    if matches!(node, Expr::New(new_expr) if get_worker_url_source(new_expr).is_some()) {
+      return;
+    }
+
     if self.transform_url(node).is_none() {
       node.visit_mut_children_with(self);
     };
@@ -0,0 +1,294 @@
+//! Transform webpack's `require.context(directory, useSubdirectories = true, regExp = /^\.\/.*$/)` at build time.
+//! for example:
+//! ```js
+//! const ctx = require.context('./locales', false, /\.json$/);
+//! ```
+//! will be transformed to:
+//! ```js
+//! const ctx = (function () {
+//!   var map = { "./en.json": function () { return require("./locales/en.json"); } };
+//!   function context(key) { ... return map[key](); }
+//!   context.keys = function () { return Object.keys(map); };
+//!   context.resolve = function (key) { ... };
+//!   context.id = "./locales";
+//!   return context;
+//! })();
+//! ```
+//! Matched modules are required lazily, so they are only executed when `ctx(key)` is called like webpack does.
+use std::path::{Path, PathBuf};
+
+use farmfe_core::{
+  error::{CompilationError, Result},
+  regex::Regex,
+  serde_json,
+  swc_common::{Mark, Span, SyntaxContext, DUMMY_SP},
+  swc_ecma_ast::{
+    CallExpr, Callee, EsVersion, Expr, Ident, Lit, MemberExpr, MemberProp, Module, ModuleItem, Stmt,
+  },
+};
+use farmfe_toolkit::{
+  script::parse_module,
+  swc_ecma_parser::{EsSyntax, Syntax},
+  swc_ecma_visit::{VisitMut, VisitMutWith},
+};
+
+pub fn transform_require_context(
+  ast: &mut Module,
+  cur_dir: &str,
+  module_id: &str,
+  unresolved_mark: Mark,
+) -> Result<()> {
+  let mut visitor = RequireContextVisitor {
+    cur_dir,
+    module_id,
+    unresolved_mark,
+    errors: vec![],
+  };
+  ast.visit_mut_with(&mut visitor);
+
+  if !visitor.errors.is_empty() {
+    return Err(CompilationError::TransformError {
+      resolved_path: module_id.to_string(),
+      msg: visitor.errors.join("\n"),
+    });
+  }
+
+  Ok(())
+}
+
+struct RequireContextVisitor<'a> {
+  cur_dir: &'a str,
+  module_id: &'a str,
+  unresolved_mark: Mark,
+  errors: Vec<String>,
+}
+
+struct RequireContextArgs {
+  directory: String,
+  recursive: bool,
+  regex: Regex,
+}
+
+impl<'a> RequireContextVisitor<'a> {
+  /// `require.context` where `require` is not declared in the module
+  fn is_require_context(&self, call_expr: &CallExpr) -> bool {
+    matches!(&call_expr.callee, Callee::Expr(box Expr::Member(MemberExpr {
+      obj: box Expr::Ident(Ident { sym: obj, span: obj_span, .. }),
+      prop: MemberProp::Ident(Ident { sym: prop, .. }),
+      ..
+    })) if obj == "require" && prop == "context" && obj_span.ctxt.outer() == self.unresolved_mark)
+  }
+
+  fn parse_args(call_expr: &CallExpr) -> std::result::Result<RequireContextArgs, String> {
+    let directory = match call_expr.args.first() {
+      Some(arg) if arg.spread.is_none() => match &*arg.expr {
+        Expr::Lit(Lit::Str(str)) => str.value.to_string(),
+        _ => return Err("the directory of require.context must be a string literal".to_string()),
+      },
+      _ => return Err("require.context requires a directory".to_string()),
+    };
+
+    let recursive = match call_expr.args.get(1).map(|arg| &*arg.expr) {
+      None => true,
+      Some(Expr::Lit(Lit::Bool(b))) => b.value,
+      Some(_) => {
+        return Err(
+          "the useSubdirectories argument of require.context must be a boolean literal".to_string(),
+        )
+      }
+    };
+
+    let regex = match call_expr.args.get(2).map(|arg| &*arg.expr) {
+      None => r"^\./.*$".to_string(),
+      Some(Expr::Lit(Lit::Regex(regex))) => {
+        if regex.flags.chars().any(|f| f != 'i') {
+          return Err(format!(
+            "unsupported regex flags `{}` of require.context, only `i` is supported",
+            regex.flags
+          ));
+        }
+
+        if regex.flags.contains('i') {
+          format!("(?i){}", regex.exp)
+        } else {
+          regex.exp.to_string()
+        }
+      }
+      Some(_) => {
+        return Err("the regExp argument of require.context must be a regex literal".to_string())
+      }
+    };
+
+    if call_expr.args.len() > 3 {
+      return Err("the mode argument of require.context is not supported".to_string());
+    }
+
+    let regex = Regex::new(&regex).map_err(|e| format!("invalid regex of require.context: {e}"))?;
+
+    Ok(RequireContextArgs {
+      directory,
+      recursive,
+      regex,
+    })
+  }
+
+  /// return the sorted `(key, source)` pairs, e.g. `("./en.json", "./locales/en.json")`
+  fn collect_modules(
+    &self,
+    args: &RequireContextArgs,
+  ) -> std::result::Result<Vec<(String, String)>, String> {
+    let dir = Path::new(self.cur_dir).join(&args.directory);
+
+    if !dir.is_dir() {
+      return Err(format!(
+        "the directory `{}` of require.context does not exist",
+        args.directory
+      ));
+    }
+
+    let mut files = vec![];
+    walk(&dir, args.recursive, &mut files);
+
+    let source_prefix = args.directory.trim_end_matches('/');
+    let mut modules = files
+      .into_iter()
+      .filter_map(|file| {
+        let relative = file
+          .strip_prefix(&dir)
+          .ok()?
+          .components()
+          .map(|c| c.as_os_str().to_string_lossy().to_string())
+          .collect::<Vec<_>>()
+          .join("/");
+        let key = format!("./{relative}");
+
+        args
+          .regex
+          .is_match(&key)
+          .then(|| (key, format!("{source_prefix}/{relative}")))
+      })
+      .collect::<Vec<_>>();
+    modules.sort();
+
+    Ok(modules)
+  }
+
+  fn create_context_expr(
+    &self,
+    directory: &str,
+    modules: &[(String, String)],
+  ) -> std::result::Result<Expr, String> {
+    let map = modules
+      .iter()
+      .map(|(key, source)| {
+        format!(
+          "{}: function () {{ return require({}); }}",
+          serde_json::to_string(key).unwrap(),
+          serde_json::to_string(source).unwrap()
+        )
+      })
+      .collect::<Vec<_>>()
+      .join(", ");
+    let code = format!(
+      r#"(function () {{
+  var map = {{ {map} }};
+  function context(key) {{
+    if (!Object.prototype.hasOwnProperty.call(map, key)) {{
+      var e = new Error("Cannot find module '" + key + "'");
+      e.code = "MODULE_NOT_FOUND";
+      throw e;
+    }}
+    return map[key]();
+  }}
+  context.keys = function () {{ return Object.keys(map); }};
+  context.resolve = function (key) {{
+    if (!Object.prototype.hasOwnProperty.call(map, key)) {{
+      throw new Error("Cannot find module '" + key + "'");
+    }}
+    return key;
+  }};
+  context.id = {};
+  return context;
+}})();"#,
+      serde_json::to_string(directory).unwrap()
+    );
+
+    let mut ast = parse_module(
+      self.module_id,
+      &code,
+      Syntax::Es(EsSyntax::default()),
+      EsVersion::EsNext,
+    )
+    .map_err(|e| e.to_string())?
+    .ast;
+
+    ast.visit_mut_with(&mut GeneratedSpanResetter {
+      require_ctxt: SyntaxContext::empty().apply_mark(self.unresolved_mark),
+    });
+
+    match ast.body.pop() {
+      Some(ModuleItem::Stmt(Stmt::Expr(expr_stmt))) => Ok(*expr_stmt.expr),
+      _ => unreachable!("generated require.context code must be an expression statement"),
+    }
+  }
+}
+
+impl<'a> VisitMut for RequireContextVisitor<'a> {
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    if let Expr::Call(call_expr) = expr {
+      if self.is_require_context(call_expr) {
+        let result = Self::parse_args(call_expr).and_then(|args| {
+          let modules = self.collect_modules(&args)?;
+          self.create_context_expr(&args.directory, &modules)
+        });
+
+        match result {
+          Ok(context_expr) => *expr = context_expr,
+          Err(e) => self.errors.push(e),
+        }
+
+        return;
+      }
+    }
+
+    expr.visit_mut_children_with(self);
+  }
+}
+
+/// Generated code is parsed from another source file, reset its spans so they do not point into the current module.
+/// `require` is marked as unresolved so the generated requires are analyzed as dependencies.
+struct GeneratedSpanResetter {
+  require_ctxt: SyntaxContext,
+}
+
+impl VisitMut for GeneratedSpanResetter {
+  fn visit_mut_span(&mut self, span: &mut Span) {
+    *span = DUMMY_SP;
+  }
+
+  fn visit_mut_ident(&mut self, ident: &mut Ident) {
+    ident.span = DUMMY_SP;
+
+    if ident.sym == "require" {
+      ident.span.ctxt = self.require_ctxt;
+    }
+  }
+}
+
+fn walk(dir: &Path, recursive: bool, files: &mut Vec<PathBuf>) {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return;
+  };
+
+  for entry in entries.flatten() {
+    let path = entry.path();
+
+    if path.is_dir() {
+      if recursive && !path.ends_with("node_modules") {
+        walk(&path, recursive, files);
+      }
+    } else {
+      files.push(path);
+    }
+  }
+}
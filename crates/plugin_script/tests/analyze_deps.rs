@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+
+use farmfe_core::module::DynamicImportHints;
 use farmfe_testing_helpers::fixture;
 
 use crate::common::build_module_deps;
@@ -16,3 +19,67 @@ pub fn import_equal() {
     }
   );
 }
+
+#[test]
+pub fn require_context() {
+  fixture!(
+    "tests/fixtures/analyze_deps/require_context/index.js",
+    |path, base| {
+      let (_, deps) = build_module_deps(path, base);
+      let sources = deps.iter().map(|d| d.source.as_str()).collect::<Vec<_>>();
+
+      assert_eq!(
+        sources,
+        vec![
+          "./locales/en.json",
+          "./locales/nested/fr.json",
+          "./locales/zh.json",
+          "./locales/en.json",
+          "./locales/zh.json",
+        ]
+      );
+      assert!(deps
+        .iter()
+        .all(|d| d.kind == farmfe_core::plugin::ResolveKind::Require));
+    }
+  );
+}
+
+#[test]
+pub fn dynamic_import_hints() {
+  fixture!(
+    "tests/fixtures/analyze_deps/dynamic_import_hints.ts",
+    |path, base| {
+      let (module, deps) = build_module_deps(path, base);
+
+      assert_eq!(deps.len(), 5);
+      assert_eq!(
+        module.meta.as_script().dynamic_import_hints,
+        HashMap::from([
+          (
+            "./settings".to_string(),
+            DynamicImportHints {
+              chunk_name: Some("settings".to_string()),
+              ..Default::default()
+            }
+          ),
+          (
+            "./admin".to_string(),
+            DynamicImportHints {
+              chunk_name: Some("admin".to_string()),
+              prefetch: true,
+              ..Default::default()
+            }
+          ),
+          (
+            "./editor".to_string(),
+            DynamicImportHints {
+              preload: true,
+              ..Default::default()
+            }
+          ),
+        ])
+      );
+    }
+  );
+}
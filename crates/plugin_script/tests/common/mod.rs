@@ -5,7 +5,8 @@ use farmfe_core::{
   module::{Module, ModuleId},
   plugin::{
     Plugin, PluginAnalyzeDepsHookParam, PluginAnalyzeDepsHookResultEntry,
-    PluginFinalizeModuleHookParam, PluginHookContext, PluginLoadHookParam, PluginParseHookParam,
+    PluginDetectModuleSystemHookParam, PluginFinalizeModuleHookParam, PluginHookContext,
+    PluginLoadHookParam, PluginNormalizeModuleSystemHookParam, PluginParseHookParam,
     PluginProcessModuleHookParam,
   },
 };
@@ -60,16 +61,44 @@ pub fn build_module_deps(
 
   module.module_type = load_result.module_type;
 
+  let content = Arc::new(load_result.content);
   let mut process_module_param = PluginProcessModuleHookParam {
     module_id: &module.id,
     module_type: &module.module_type,
     meta: &mut parse_result,
-    content: Arc::new(load_result.content),
+    content: content.clone(),
   };
   script_plugin
     .process_module(&mut process_module_param, &context)
     .unwrap();
 
+  let module_system = script_plugin
+    .detect_module_system(
+      &PluginDetectModuleSystemHookParam {
+        module_id: &module.id,
+        module_type: &module.module_type,
+        content: &content,
+        meta: &parse_result,
+      },
+      &context,
+    )
+    .unwrap();
+
+  if let Some(module_system) = module_system {
+    script_plugin
+      .normalize_module_system(
+        &mut PluginNormalizeModuleSystemHookParam {
+          module_id: &module.id,
+          module_type: &module.module_type,
+          module_system: &module_system,
+          meta: &mut parse_result,
+        },
+        &context,
+      )
+      .unwrap();
+    parse_result.as_script_mut().original_module_system = Some(module_system);
+  }
+
   module.meta = Box::new(parse_result);
 
   let mut analyze_deps_param = PluginAnalyzeDepsHookParam {
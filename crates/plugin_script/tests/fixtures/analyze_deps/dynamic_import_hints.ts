@@ -0,0 +1,5 @@
+import(/* farmChunkName: "settings" */ './settings');
+import(/* webpackChunkName: 'admin', webpackPrefetch: true */ './admin');
+import(/* farmPreload: true */ './editor');
+import(/* farmPrefetch: false */ './help');
+import('./profile');
@@ -0,0 +1,4 @@
+const locales = require.context('./locales', true, /\.json$/);
+const topLevel = require.context('./locales', false, /\.json$/);
+
+export default locales.keys().concat(topLevel.keys());
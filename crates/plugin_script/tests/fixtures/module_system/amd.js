@@ -0,0 +1,4 @@
+define('amd', ['./a', './b', 'require', 'exports'], function (a, b, require, exports) {
+  var c = require('./c');
+  exports.value = a + b + c;
+});
@@ -1,4 +1,4 @@
-use common::build_module;
+use common::{build_module, build_module_deps};
 use farmfe_core::module::ModuleSystem;
 use farmfe_testing_helpers::fixture;
 
@@ -21,12 +21,32 @@ pub fn module_system() {
       );
     } else if path.ends_with("hybrid.js") {
       assert_eq!(module.meta.as_script().module_system, ModuleSystem::Hybrid);
+    } else if path.ends_with("amd.js") {
+      assert_eq!(
+        module.meta.as_script().module_system,
+        ModuleSystem::EsModule
+      );
+      assert_eq!(
+        module.meta.as_script().original_module_system.as_deref(),
+        Some("amd")
+      );
     } else {
       unreachable!("Unexpected file: {}", path.display());
     }
   })
 }
 
+#[test]
+pub fn amd_module_deps() {
+  fixture!("tests/fixtures/module_system/amd.js", |path, base| {
+    let (_, deps) = build_module_deps(path, base);
+    let mut sources = deps.into_iter().map(|d| d.source).collect::<Vec<_>>();
+    sources.sort();
+
+    assert_eq!(sources, vec!["./a", "./b", "./c"]);
+  });
+}
+
 #[test]
 pub fn module_system_with_ts() {
   fixture!(
@@ -0,0 +1,49 @@
+//! Passthrough of binary files like native addons (`.node`) and models (`.onnx`) for node target.
+//! The files are emitted beside the bundle and loaded relative to the bundle at runtime, for example:
+//! ```js
+//! // esm
+//! import { createRequire } from "node:module";
+//! export default createRequire(import.meta.url)("./addon-a1b2c3d4.node");
+//! // cjs
+//! module.exports = require("node:module").createRequire(__filename)("./addon-a1b2c3d4.node");
+//! ```
+use std::sync::Arc;
+
+use farmfe_core::{config::ModuleFormat, context::CompilationContext};
+use farmfe_utils::FARM_IGNORE_ACTION_COMMENT;
+
+const NATIVE_ADDON_EXT: &str = "node";
+
+pub fn is_binary_asset(ext: &str, context: &Arc<CompilationContext>) -> bool {
+  context.config.output.target_env.is_node()
+    && context
+      .config
+      .assets
+      .binary
+      .include
+      .iter()
+      .any(|e| e.eq_ignore_ascii_case(ext))
+}
+
+/// Native addons are required, other binary files are resolved to their absolute path
+pub fn binary_asset_code(ext: &str, resource_name: &str, context: &Arc<CompilationContext>) -> String {
+  let relative_path = format!("./{resource_name}");
+  let is_native_addon = ext.eq_ignore_ascii_case(NATIVE_ADDON_EXT);
+
+  match (&context.config.output.format, is_native_addon) {
+    (ModuleFormat::EsModule, true) => format!(
+      r#"import {{ createRequire }} from "node:module";
+export default createRequire(import.meta.url)({relative_path:?});"#
+    ),
+    (ModuleFormat::EsModule, false) => format!(
+      r#"import {{ fileURLToPath }} from "node:url";
+export default fileURLToPath(new URL(/* {FARM_IGNORE_ACTION_COMMENT} */{relative_path:?}, import.meta.url));"#
+    ),
+    (ModuleFormat::CommonJs, true) => format!(
+      r#"module.exports = require("node:module").createRequire(__filename)({relative_path:?});"#
+    ),
+    (ModuleFormat::CommonJs, false) => {
+      format!(r#"module.exports = require("node:path").join(__dirname, {relative_path:?});"#)
+    }
+  }
+}
@@ -0,0 +1,240 @@
+//! Read the dimensions of images from their headers, the image is never fully decoded.
+//! Supports png, gif, jpeg, webp, bmp, ico and svg.
+use farmfe_core::regex::Regex;
+use farmfe_toolkit::lazy_static::lazy_static;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ImageMeta {
+  pub width: u32,
+  pub height: u32,
+  pub format: &'static str,
+}
+
+lazy_static! {
+  static ref SVG_TAG: Regex = Regex::new(r"(?s)<svg\b[^>]*>").unwrap();
+  static ref SVG_WIDTH: Regex = Regex::new(r#"\swidth\s*=\s*["']\s*([\d.]+)(px)?\s*["']"#).unwrap();
+  static ref SVG_HEIGHT: Regex =
+    Regex::new(r#"\sheight\s*=\s*["']\s*([\d.]+)(px)?\s*["']"#).unwrap();
+  static ref SVG_VIEW_BOX: Regex =
+    Regex::new(r#"\sviewBox\s*=\s*["']\s*[-\d.]+[\s,]+[-\d.]+[\s,]+([\d.]+)[\s,]+([\d.]+)\s*["']"#)
+      .unwrap();
+}
+
+fn be_u16(bytes: &[u8], offset: usize) -> Option<u32> {
+  let b = bytes.get(offset..offset + 2)?;
+  Some(u16::from_be_bytes([b[0], b[1]]) as u32)
+}
+
+fn le_u16(bytes: &[u8], offset: usize) -> Option<u32> {
+  let b = bytes.get(offset..offset + 2)?;
+  Some(u16::from_le_bytes([b[0], b[1]]) as u32)
+}
+
+fn be_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+  let b = bytes.get(offset..offset + 4)?;
+  Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn le_u24(bytes: &[u8], offset: usize) -> Option<u32> {
+  let b = bytes.get(offset..offset + 3)?;
+  Some(u32::from_le_bytes([b[0], b[1], b[2], 0]))
+}
+
+fn le_i32(bytes: &[u8], offset: usize) -> Option<i32> {
+  let b = bytes.get(offset..offset + 4)?;
+  Some(i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn png(bytes: &[u8]) -> Option<ImageMeta> {
+  if !bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+    return None;
+  }
+
+  Some(ImageMeta {
+    width: be_u32(bytes, 16)?,
+    height: be_u32(bytes, 20)?,
+    format: "png",
+  })
+}
+
+fn gif(bytes: &[u8]) -> Option<ImageMeta> {
+  if !bytes.starts_with(b"GIF87a") && !bytes.starts_with(b"GIF89a") {
+    return None;
+  }
+
+  Some(ImageMeta {
+    width: le_u16(bytes, 6)?,
+    height: le_u16(bytes, 8)?,
+    format: "gif",
+  })
+}
+
+fn bmp(bytes: &[u8]) -> Option<ImageMeta> {
+  if !bytes.starts_with(b"BM") {
+    return None;
+  }
+
+  Some(ImageMeta {
+    width: le_i32(bytes, 18)?.unsigned_abs(),
+    // height is negative for top-down bitmaps
+    height: le_i32(bytes, 22)?.unsigned_abs(),
+    format: "bmp",
+  })
+}
+
+fn ico(bytes: &[u8]) -> Option<ImageMeta> {
+  if !bytes.starts_with(&[0, 0, 1, 0]) {
+    return None;
+  }
+
+  // use the largest image in the directory, 0 means 256
+  let count = le_u16(bytes, 4)? as usize;
+  let size = |v: u8| if v == 0 { 256 } else { v as u32 };
+  let (width, height) = (0..count)
+    .filter_map(|i| {
+      let entry = bytes.get(6 + i * 16..6 + i * 16 + 2)?;
+      Some((size(entry[0]), size(entry[1])))
+    })
+    .max_by_key(|(w, h)| w * h)?;
+
+  Some(ImageMeta {
+    width,
+    height,
+    format: "ico",
+  })
+}
+
+fn jpeg(bytes: &[u8]) -> Option<ImageMeta> {
+  if !bytes.starts_with(&[0xFF, 0xD8]) {
+    return None;
+  }
+
+  let mut offset = 2;
+
+  // walk the segments until a SOFn segment which contains the dimensions
+  while offset + 4 <= bytes.len() {
+    if bytes[offset] != 0xFF {
+      return None;
+    }
+
+    let marker = bytes[offset + 1];
+
+    // padding bytes
+    if marker == 0xFF {
+      offset += 1;
+      continue;
+    }
+
+    let len = be_u16(bytes, offset + 2)? as usize;
+    let is_sof = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+
+    if is_sof {
+      return Some(ImageMeta {
+        height: be_u16(bytes, offset + 5)?,
+        width: be_u16(bytes, offset + 7)?,
+        format: "jpeg",
+      });
+    }
+
+    offset += 2 + len;
+  }
+
+  None
+}
+
+fn webp(bytes: &[u8]) -> Option<ImageMeta> {
+  if !bytes.starts_with(b"RIFF") || bytes.get(8..12)? != b"WEBP" {
+    return None;
+  }
+
+  let (width, height) = match bytes.get(12..16)? {
+    // lossy
+    b"VP8 " => (le_u16(bytes, 26)? & 0x3fff, le_u16(bytes, 28)? & 0x3fff),
+    // lossless, 14 bits width - 1 and 14 bits height - 1
+    b"VP8L" => {
+      let b = bytes.get(21..25)?;
+      let bits = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+      ((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1)
+    }
+    // extended, 24 bits canvas width - 1 and height - 1
+    b"VP8X" => (le_u24(bytes, 24)? + 1, le_u24(bytes, 27)? + 1),
+    _ => return None,
+  };
+
+  Some(ImageMeta {
+    width,
+    height,
+    format: "webp",
+  })
+}
+
+fn svg(bytes: &[u8]) -> Option<ImageMeta> {
+  let content = std::str::from_utf8(bytes).ok()?;
+  let tag = SVG_TAG.find(content)?.as_str();
+  let attr = |regex: &Regex| {
+    regex
+      .captures(tag)
+      .and_then(|c| c[1].parse::<f64>().ok())
+      .map(|v| v.round() as u32)
+  };
+  let view_box = SVG_VIEW_BOX.captures(tag).and_then(|c| {
+    Some((
+      c[1].parse::<f64>().ok()?.round() as u32,
+      c[2].parse::<f64>().ok()?.round() as u32,
+    ))
+  });
+
+  let (width, height) = match (attr(&SVG_WIDTH), attr(&SVG_HEIGHT), view_box) {
+    (Some(w), Some(h), _) => (w, h),
+    // keep the aspect ratio of the view box if only one dimension is specified
+    (Some(w), None, Some((vw, vh))) if vw > 0 => (w, w * vh / vw),
+    (None, Some(h), Some((vw, vh))) if vh > 0 => (h * vw / vh, h),
+    (_, _, Some((vw, vh))) => (vw, vh),
+    _ => return None,
+  };
+
+  Some(ImageMeta {
+    width,
+    height,
+    format: "svg",
+  })
+}
+
+/// Return [None] if the format is not supported or the header is malformed.
+pub fn read_image_meta(bytes: &[u8]) -> Option<ImageMeta> {
+  png(bytes)
+    .or_else(|| jpeg(bytes))
+    .or_else(|| gif(bytes))
+    .or_else(|| webp(bytes))
+    .or_else(|| bmp(bytes))
+    .or_else(|| ico(bytes))
+    .or_else(|| svg(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{read_image_meta, ImageMeta};
+
+  #[test]
+  fn read_meta_from_headers() {
+    let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    png.extend_from_slice(&640u32.to_be_bytes());
+    png.extend_from_slice(&480u32.to_be_bytes());
+    assert_eq!(
+      read_image_meta(&png),
+      Some(ImageMeta {
+        width: 640,
+        height: 480,
+        format: "png"
+      })
+    );
+
+    let gif = b"GIF89a\x20\x00\x10\x00";
+    assert_eq!(read_image_meta(gif).map(|m| (m.width, m.height)), Some((32, 16)));
+
+    let svg = br#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg" width="100" viewBox="0 0 50 25"></svg>"#;
+    assert_eq!(read_image_meta(svg).map(|m| (m.width, m.height)), Some((100, 50)));
+
+    assert_eq!(read_image_meta(b"not an image"), None);
+  }
+}
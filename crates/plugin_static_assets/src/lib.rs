@@ -1,7 +1,9 @@
 #![feature(path_file_prefix)]
 
 use std::{
-  collections::HashMap,
+  collections::{BTreeMap, HashMap},
+  fs::File,
+  io::BufReader,
   path::{Path, PathBuf},
   sync::Arc,
 };
@@ -9,19 +11,29 @@ use std::{
 use base64::engine::{general_purpose, Engine};
 use farmfe_core::{
   cache_item,
-  config::{asset::AssetFormatMode, custom::get_config_assets_mode, Config},
+  config::{
+    asset::{asset_target_key, asset_target_placeholder, AssetFormatMode, ASSET_TARGETS_MANIFEST},
+    custom::get_config_assets_mode,
+    module_types::CustomModuleTypeTreatAs,
+    Config, Mode, FARM_PUBLIC_PATH_GLOBAL,
+  },
   context::{CompilationContext, EmitFileParams},
+  dashmap::{DashMap, DashSet},
   deserialize,
-  module::ModuleType,
-  plugin::{Plugin, PluginResolveHookResult},
+  error::CompilationError,
+  module::{ModuleId, ModuleType},
+  plugin::{
+    AssetVariant, Plugin, PluginFinalizeResourcesHookParams, PluginResolveHookResult,
+    PluginTransformAssetHookParam, PluginTransformHookParam,
+  },
   relative_path::RelativePath,
   resource::{Resource, ResourceOrigin, ResourceType},
   rkyv::Deserialize,
-  serialize,
+  serde_json, serialize,
   swc_common::sync::OnceCell,
 };
 use farmfe_toolkit::{
-  fs::{read_file_raw, read_file_utf8, transform_output_filename},
+  fs::{read_file_raw, read_file_utf8, transform_output_filename_with_hash, EXT, RESOURCE_NAME},
   lazy_static::lazy_static,
 };
 use farmfe_utils::{hash::sha256, stringify_query, FARM_IGNORE_ACTION_COMMENT};
@@ -36,48 +48,483 @@ lazy_static! {
 
 const PLUGIN_NAME: &str = "FarmPluginStaticAssets";
 const PUBLIC_ASSET_PREFIX: &str = "virtual:__FARM_PUBLIC_ASSET__:";
+/// `import meta from './a.png?meta'` returns `{ src, width, height, format }`
+const META_QUERY: &str = "meta";
+/// upper bound of the bytes of the transformed assets written to the persistent cache
+const MAX_PERSISTED_TRANSFORMED_ASSETS_BYTES: usize = 256 * 1024 * 1024;
+
+mod binary;
+mod image_meta;
+mod sprite;
+mod wasm;
+
+use binary::{binary_asset_code, is_binary_asset};
+use image_meta::read_image_meta;
+use sprite::{build_sprite, sprite_symbol_id, svg_to_symbol, SpriteSymbol, SPRITE_QUERY};
+use wasm::{is_wasm, read_wasm_interface, wasm_module_code};
 
 fn is_asset_query(query: &Vec<(String, String)>) -> bool {
   let query_map = query.iter().cloned().collect::<HashMap<_, _>>();
 
-  query_map.contains_key("raw") || query_map.contains_key("inline") || query_map.contains_key("url")
+  query_map.contains_key("raw")
+    || query_map.contains_key("inline")
+    || query_map.contains_key("url")
+    || query_map.contains_key(META_QUERY)
+    || query_map.contains_key(SPRITE_QUERY)
+}
+
+enum AssetContent {
+  Bytes(Vec<u8>),
+  /// the asset is too large to be loaded into memory
+  Path(String),
 }
 
 pub struct FarmPluginStaticAssets {
   asset_format_mode: OnceCell<AssetFormatMode>,
+  /// `{variant}[.{target}]:{ext}:{plugins cache key}:{content hash}` -> asset changed by the `transform_asset` hook,
+  /// so an asset is only transformed once per variant and target, across builds when the persistent cache is enabled
+  transformed_assets: DashMap<String, TransformedAsset>,
+  /// keys of the transformed assets used by this process, only they are persisted
+  used_transformed_assets: DashSet<String>,
+  plugins_cache_key: OnceCell<String>,
+  /// symbols of the icons imported with `?sprite`, the sprite is built from the symbols of the icons in the module graph
+  sprite_symbols: DashMap<ModuleId, SpriteSymbol>,
+  /// names of the binary files emitted without the content hash, two modules must not be emitted with the same name
+  unhashed_binary_names: DashMap<ModuleId, String>,
 }
 
 impl FarmPluginStaticAssets {
   pub fn new(_: &Config) -> Self {
     Self {
       asset_format_mode: OnceCell::new(),
+      transformed_assets: DashMap::new(),
+      used_transformed_assets: DashSet::new(),
+      plugins_cache_key: OnceCell::new(),
+      sprite_symbols: DashMap::new(),
+      unhashed_binary_names: DashMap::new(),
     }
   }
 
   fn is_asset(&self, ext: &str, context: &Arc<CompilationContext>) -> bool {
-    DEFAULT_STATIC_ASSETS
-      .iter()
-      .any(|a| a.eq_ignore_ascii_case(ext))
+    is_binary_asset(ext, context)
+      || is_wasm(ext)
+      || DEFAULT_STATIC_ASSETS
+        .iter()
+        .any(|a| a.eq_ignore_ascii_case(ext))
       || context
         .config
         .assets
         .include
         .iter()
         .any(|a| a.eq_ignore_ascii_case(ext))
+      || context.config.custom_module_type(ext) == Some(CustomModuleTypeTreatAs::Asset)
   }
 
-  fn get_resource_name(name: &str, module_id: &str) -> String {
+  fn get_resource_name(name: &str, module_id: &str, context: &Arc<CompilationContext>) -> String {
+    let hash = context.config.hash.hash(module_id.as_bytes(), 6);
     let last_dot = name.rfind('.').unwrap_or(0);
     if last_dot == 0 {
-      format!("{}-{}", name, sha256(module_id.as_bytes(), 6))
+      format!("{name}-{hash}")
+    } else {
+      format!("{}-{hash}{}", &name[..last_dot], &name[last_dot..])
+    }
+  }
+
+  fn read_asset_content(
+    resolved_path: &str,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<AssetContent> {
+    let threshold = context.config.assets.stream_threshold;
+    let is_large_file =
+      threshold > 0 && std::fs::metadata(resolved_path).is_ok_and(|m| m.len() as usize > threshold);
+
+    // large media files like videos are copied to the output directory directly instead of being loaded into memory
+    if is_large_file {
+      Ok(AssetContent::Path(resolved_path.to_string()))
     } else {
+      Ok(AssetContent::Bytes(read_file_raw(resolved_path)?))
+    }
+  }
+
+  fn asset_format_mode(&self, context: &Arc<CompilationContext>) -> &AssetFormatMode {
+    self.asset_format_mode.get_or_init(|| {
+      get_config_assets_mode(&context.config)
+        .unwrap_or_else(|| (context.config.output.target_env.clone().into()))
+    })
+  }
+
+  /// Emit the asset and return `(imports, src expression)` of the emitted file
+  fn emit_asset(
+    &self,
+    param: &farmfe_core::plugin::PluginTransformHookParam,
+    query: &Vec<(String, String)>,
+    content: AssetContent,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<(String, String)> {
+    let resource_name = match content {
+      // emitted once per target and referenced by the placeholder that is replaced when the output variant of the target is written
+      AssetContent::Bytes(bytes) if self.is_target_asset(param, context) => {
+        for target in &context.config.assets.targets {
+          let content = AssetContent::Bytes(bytes.clone());
+          self.emit_asset_file(param, query, content, true, Some(target), context)?;
+        }
+
+        asset_target_placeholder(&param.module_id.as_str().into())
+      }
+      content => self.emit_asset_file(param, query, content, true, None, context)?,
+    };
+
+    Ok(self.asset_src(&resource_name, context))
+  }
+
+  /// `(imports, src expression)` of the resource, the public path is prepended in browser
+  fn asset_src(&self, resource_name: &str, context: &Arc<CompilationContext>) -> (String, String) {
+    let assets_path = if !context.config.output.public_path.is_empty() {
+      let normalized_public_path = context.config.output.public_path.trim_end_matches('/');
+
+      format!("{normalized_public_path}/{resource_name}")
+    } else {
+      format!("/{resource_name}")
+    };
+
+    match self.asset_format_mode(context) {
+      AssetFormatMode::Node => (
+        r#"import { fileURLToPath } from "node:url";"#.to_string(),
+        format!(
+          "fileURLToPath(new URL(/* {FARM_IGNORE_ACTION_COMMENT} */{assets_path:?}, import.meta.url))"
+        ),
+      ),
+      AssetFormatMode::Browser if context.config.output.is_runtime_public_path() => (
+        String::new(),
+        format!("{FARM_PUBLIC_PATH_GLOBAL} + {resource_name:?}"),
+      ),
+      AssetFormatMode::Browser => (String::new(), format!("{assets_path:?}")),
+    }
+  }
+
+  /// Add the icon to the sprite and return the code of `{ id, url }`, see [farmfe_core::config::asset::SvgSpriteConfig]
+  fn transform_sprite_icon(
+    &self,
+    param: &PluginTransformHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<String> {
+    let Some(config) = &context.config.assets.sprite else {
+      return Err(CompilationError::TransformError {
+        resolved_path: param.resolved_path.to_string(),
+        msg: format!("`?{SPRITE_QUERY}` requires `assets.sprite` to be configured"),
+      });
+    };
+    let svg = read_file_utf8(param.resolved_path)?;
+    let id = sprite_symbol_id(&config.symbol_id_prefix, param.resolved_path);
+    let Some(symbol) = svg_to_symbol(&svg, &id) else {
+      return Err(CompilationError::TransformError {
+        resolved_path: param.resolved_path.to_string(),
+        msg: format!("`?{SPRITE_QUERY}` is only supported for svg icons"),
+      });
+    };
+
+    // the url changes with the icon in development, so the updated icon is fetched again after hmr
+    let resource_name = if matches!(context.config.mode, Mode::Development) {
       format!(
-        "{}-{}{}",
-        &name[..last_dot],
-        sha256(module_id.to_string().as_bytes(), 6),
-        &name[last_dot..]
+        "{}?t={}",
+        config.filename,
+        context.config.hash.hash(symbol.as_bytes(), 8)
       )
+    } else {
+      config.filename.clone()
+    };
+    let (imports, src) = self.asset_src(&resource_name, context);
+    self.sprite_symbols.insert(
+      param.module_id.as_str().into(),
+      SpriteSymbol {
+        id: id.clone(),
+        symbol,
+      },
+    );
+
+    Ok(format!(
+      "{imports}\nexport default {{ id: {id:?}, url: {src} + {:?} }};",
+      format!("#{id}")
+    ))
+  }
+
+  /// Build the sprite from the symbols of the icons that are still in the module graph, it's removed and built again
+  /// when any of the icons is updated or removed
+  fn emit_sprite(
+    &self,
+    param: &mut PluginFinalizeResourcesHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<()> {
+    let Some(config) = &context.config.assets.sprite else {
+      return Ok(());
+    };
+
+    let module_graph = context.module_graph.read();
+    self
+      .sprite_symbols
+      .retain(|module_id, _| module_graph.has_module(module_id));
+    drop(module_graph);
+
+    let mut icons = self
+      .sprite_symbols
+      .iter()
+      .map(|entry| (entry.key().clone(), entry.value().clone()))
+      .collect::<Vec<_>>();
+
+    if icons.is_empty() {
+      return Ok(());
+    }
+
+    icons.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut ids = HashMap::new();
+
+    for (module_id, symbol) in &icons {
+      if let Some(other) = ids.insert(&symbol.id, module_id) {
+        return Err(CompilationError::GenericError(format!(
+          "the sprite icons {} and {} have the same symbol id {}, rename one of them",
+          other.to_string(),
+          module_id.to_string(),
+          symbol.id
+        )));
+      }
+    }
+
+    let sprite = build_sprite(icons.iter().map(|(_, symbol)| symbol).collect());
+    param.resources_map.insert(
+      config.filename.clone(),
+      Resource {
+        name: config.filename.clone(),
+        bytes: sprite.into_bytes(),
+        emitted: false,
+        resource_type: ResourceType::Asset("svg".to_string()),
+        origin: ResourceOrigin::Modules(
+          icons.into_iter().map(|(module_id, _)| module_id).collect(),
+        ),
+        source_path: None,
+        scope: Default::default(),
+        info: None,
+      },
+    );
+
+    Ok(())
+  }
+
+  /// Whether the asset is emitted per target, see [farmfe_core::config::asset::AssetsConfig::targets]
+  /// `a/addon.node` and `b/addon.node` are both emitted as `addon.node` when `assets.binary.hash` is false,
+  /// one of them would be overwritten silently
+  fn check_unhashed_binary_names(
+    &self,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<()> {
+    let module_graph = context.module_graph.read();
+    self
+      .unhashed_binary_names
+      .retain(|module_id, _| module_graph.has_module(module_id));
+    drop(module_graph);
+
+    let mut modules_of_names = BTreeMap::<String, Vec<String>>::new();
+
+    for entry in self.unhashed_binary_names.iter() {
+      modules_of_names
+        .entry(entry.value().clone())
+        .or_default()
+        .push(entry.key().relative_path().to_string());
+    }
+
+    for (name, mut modules) in modules_of_names {
+      if modules.len() > 1 {
+        modules.sort();
+
+        return Err(CompilationError::GenericError(format!(
+          "Binary files {} are all emitted as {name}. Rename them or set `assets.binary.hash` to true",
+          modules.join(", ")
+        )));
+      }
+    }
+
+    Ok(())
+  }
+
+  fn is_target_asset(
+    &self,
+    param: &PluginTransformHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> bool {
+    // the facade module of wasm with ESM integration loads the binary by its own url
+    context.config.assets.has_targets(&context.config.mode)
+      && !(context.config.assets.wasm_esm_integration
+        && Path::new(param.resolved_path)
+          .extension()
+          .and_then(|s| s.to_str())
+          .is_some_and(is_wasm))
+  }
+
+  /// Call the `transform_asset` hook of the plugins and return the transformed `(content, ext)`.
+  /// Assets streamed from the disk are passed with empty content and kept streamed unless a plugin fills the content
+  fn transform_asset(
+    &self,
+    param: &PluginTransformHookParam,
+    content: AssetContent,
+    ext: &str,
+    target: Option<&str>,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<(AssetContent, String)> {
+    let variant = AssetVariant::from_mode(&context.config.mode);
+    let content_hash = match &content {
+      AssetContent::Bytes(bytes) => sha256(bytes, 32),
+      AssetContent::Path(source_path) => File::open(source_path)
+        .and_then(|file| context.config.hash.hash_reader(BufReader::new(file), 32))
+        .map_err(|e| CompilationError::TransformError {
+          resolved_path: param.resolved_path.to_string(),
+          msg: format!("Failed to read {}: {e}", param.resolved_path),
+        })?,
+    };
+    // the output of the hook changes when the plugins or their options change
+    let plugins_cache_key = self
+      .plugins_cache_key
+      .get_or_init(|| context.plugin_driver.plugins_cache_key());
+    let key = match target {
+      Some(target) => format!(
+        "{}.{target}:{ext}:{plugins_cache_key}:{content_hash}",
+        variant.as_str()
+      ),
+      None => format!(
+        "{}:{ext}:{plugins_cache_key}:{content_hash}",
+        variant.as_str()
+      ),
+    };
+
+    if let Some(asset) = self.transformed_assets.get(&key) {
+      self.used_transformed_assets.insert(key);
+      return Ok((
+        AssetContent::Bytes(asset.content.clone()),
+        asset.ext.clone(),
+      ));
+    }
+
+    let (content, source_path) = match content {
+      AssetContent::Bytes(bytes) => (bytes, None),
+      AssetContent::Path(source_path) => (vec![], Some(source_path)),
+    };
+    let mut hook_param = PluginTransformAssetHookParam {
+      module_id: param.module_id.clone().into(),
+      resolved_path: param.resolved_path.to_string(),
+      variant,
+      target: target.map(|t| t.to_string()),
+      ext: ext.to_string(),
+      content,
+      source_path,
+    };
+    context
+      .plugin_driver
+      .transform_asset(&mut hook_param, context)?;
+
+    // the streamed asset is copied as is if no plugin replaced its content
+    if let Some(source_path) = hook_param
+      .source_path
+      .filter(|_| hook_param.content.is_empty())
+    {
+      return Ok((AssetContent::Path(source_path), ext.to_string()));
+    }
+
+    // unchanged assets are not cached, passing them through is cheap
+    if hook_param.ext != ext || sha256(&hook_param.content, 32) != content_hash {
+      self.transformed_assets.insert(
+        key.clone(),
+        TransformedAsset {
+          ext: hook_param.ext.clone(),
+          content: hook_param.content.clone(),
+        },
+      );
+      self.used_transformed_assets.insert(key);
+    }
+
+    Ok((AssetContent::Bytes(hook_param.content), hook_param.ext))
+  }
+
+  /// Emit the asset and return the resource name. The original filename is kept if `hash` is false,
+  /// the asset of a target is emitted to `<target>/`
+  fn emit_asset_file(
+    &self,
+    param: &PluginTransformHookParam,
+    query: &Vec<(String, String)>,
+    content: AssetContent,
+    hash: bool,
+    target: Option<&str>,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<String> {
+    let ext = Path::new(param.resolved_path)
+      .extension()
+      .and_then(|s| s.to_str())
+      .unwrap();
+    let (content, ext) = self.transform_asset(param, content, ext, target, context)?;
+    let ext = ext.as_str();
+
+    let filename = Path::new(param.resolved_path)
+      .file_prefix()
+      .and_then(|s| s.to_str())
+      .unwrap();
+    let filename_config = if hash {
+      context.config.output.assets_filename.clone()
+    } else {
+      format!("{RESOURCE_NAME}.{EXT}")
+    };
+    // the hash is only computed when the filename contains a content hash placeholder
+    let mut read_error = None;
+    let resource_name = transform_output_filename_with_hash(
+      filename_config,
+      filename,
+      || match &content {
+        AssetContent::Bytes(bytes) => context.config.hash.hash(bytes, 8),
+        AssetContent::Path(source_path) => File::open(source_path)
+          .and_then(|file| context.config.hash.hash_reader(BufReader::new(file), 8))
+          .unwrap_or_else(|e| {
+            read_error = Some(e);
+            String::new()
+          }),
+      },
+      ext,
+    ) + stringify_query(query).as_str();
+
+    if let Some(e) = read_error {
+      return Err(CompilationError::TransformError {
+        resolved_path: param.resolved_path.to_string(),
+        msg: format!("Failed to read {}: {e}", param.resolved_path),
+      });
+    }
+
+    let resource_name = if hash {
+      Self::get_resource_name(&resource_name, &param.module_id, context)
+    } else {
+      resource_name
+    };
+    let resource_name = match target {
+      Some(target) => format!("{target}/{resource_name}"),
+      None => resource_name,
+    };
+
+    let (content, source_path) = match content {
+      AssetContent::Bytes(bytes) => (bytes, None),
+      AssetContent::Path(source_path) => (vec![], Some(source_path)),
+    };
+    let params = EmitFileParams {
+      resolved_path: param.module_id.clone(),
+      name: resource_name.clone(),
+      content,
+      resource_type: if is_wasm(ext) {
+        ResourceType::Wasm
+      } else {
+        ResourceType::Asset(ext.to_string())
+      },
+      scope: Default::default(),
+    };
+
+    match source_path {
+      Some(source_path) => context.emit_file_from_path(params, source_path),
+      None => context.emit_file(params),
     }
+
+    Ok(resource_name)
   }
 }
 
@@ -134,6 +581,17 @@ impl Plugin for FarmPluginStaticAssets {
     let public_path = context.config.output.public_path.clone();
 
     if let Some(source) = param.resolved_path.strip_prefix(PUBLIC_ASSET_PREFIX) {
+      if context.config.output.is_runtime_public_path() {
+        return Ok(Some(farmfe_core::plugin::PluginLoadHookResult {
+          content: format!(
+            "export default {FARM_PUBLIC_PATH_GLOBAL} + {:?};",
+            source.trim_start_matches('/')
+          ),
+          module_type: ModuleType::Js,
+          source_map: None,
+        }));
+      }
+
       // fix https://github.com/farm-fe/farm/issues/1165
       let mut base_path = PathBuf::from(public_path);
       base_path.push(source.trim_start_matches("/"));
@@ -190,6 +648,44 @@ impl Plugin for FarmPluginStaticAssets {
         };
         let content = format!("export default {:?}", file_utf8.replace("\r\n", "\n"));
 
+        return Ok(Some(farmfe_core::plugin::PluginTransformHookResult {
+          content,
+          module_type: Some(ModuleType::Js),
+          source_map: None,
+          ignore_previous_source_map: false,
+        }));
+      } else if param.query.iter().any(|(k, _)| k == SPRITE_QUERY) {
+        let content = self.transform_sprite_icon(param, context)?;
+
+        return Ok(Some(farmfe_core::plugin::PluginTransformHookResult {
+          content,
+          module_type: Some(ModuleType::Js),
+          source_map: None,
+          ignore_previous_source_map: false,
+        }));
+      } else if param.query.iter().any(|(k, _)| k == META_QUERY) {
+        let bytes = read_file_raw(param.resolved_path)?;
+        let Some(meta) = read_image_meta(&bytes) else {
+          return Err(CompilationError::TransformError {
+            resolved_path: param.resolved_path.to_string(),
+            msg: format!(
+              "`?{META_QUERY}` is only supported for png, jpeg, gif, webp, bmp, ico and svg images"
+            ),
+          });
+        };
+        // the emitted asset is the same as importing it without `?meta`
+        let query = param
+          .query
+          .iter()
+          .filter(|(k, _)| k != META_QUERY)
+          .cloned()
+          .collect();
+        let (imports, src) = self.emit_asset(param, &query, AssetContent::Bytes(bytes), context)?;
+        let content = format!(
+          "{imports}\nexport default {{ src: {src}, width: {}, height: {}, format: {:?} }};",
+          meta.width, meta.height, meta.format
+        );
+
         return Ok(Some(farmfe_core::plugin::PluginTransformHookResult {
           content,
           module_type: Some(ModuleType::Js),
@@ -197,65 +693,48 @@ impl Plugin for FarmPluginStaticAssets {
           ignore_previous_source_map: false,
         }));
       } else {
-        let bytes = if param.content.is_empty() {
-          read_file_raw(param.resolved_path)?
-        } else {
+        if !param.content.is_empty() {
           // if content is not empty, it means the content is already read by the load hook in other plugins
           return Ok(None);
-        };
+        }
 
+        let content = Self::read_asset_content(param.resolved_path, context)?;
         let ext = Path::new(param.resolved_path)
           .extension()
           .and_then(|s| s.to_str())
           .unwrap();
 
-        let filename = Path::new(param.resolved_path)
-          .file_prefix()
-          .and_then(|s| s.to_str())
-          .unwrap();
-        let resource_name = transform_output_filename(
-          context.config.output.assets_filename.clone(),
-          filename,
-          &bytes,
-          ext,
-        ) + stringify_query(&param.query).as_str();
-
-        let resource_name = Self::get_resource_name(&resource_name, &param.module_id);
+        let content = if is_binary_asset(ext, context) {
+          let hash = context.config.assets.binary.hash;
+          let resource_name =
+            self.emit_asset_file(param, &param.query, content, hash, None, context)?;
 
-        let assets_path = if !context.config.output.public_path.is_empty() {
-          let normalized_public_path = context.config.output.public_path.trim_end_matches("/");
+          if !hash {
+            self
+              .unhashed_binary_names
+              .insert(param.module_id.as_str().into(), resource_name.clone());
+          }
 
-          format!("{normalized_public_path}/{resource_name}")
+          binary_asset_code(ext, &resource_name, context)
+        } else if is_wasm(ext)
+          && context.config.assets.wasm_esm_integration
+          && !param.query.iter().any(|(k, _)| k == "url")
+        {
+          let bytes = read_file_raw(param.resolved_path)?;
+          let Some(interface) = read_wasm_interface(&bytes) else {
+            return Err(CompilationError::TransformError {
+              resolved_path: param.resolved_path.to_string(),
+              msg: format!("{} is not a valid WebAssembly binary", param.resolved_path),
+            });
+          };
+          let (imports, src) =
+            self.emit_asset(param, &param.query, AssetContent::Bytes(bytes), context)?;
+          wasm_module_code(&interface, &imports, &src, self.asset_format_mode(context))
         } else {
-          format!("/{resource_name}")
+          let (imports, src) = self.emit_asset(param, &param.query, content, context)?;
+          format!("{imports}\nexport default {src};")
         };
 
-        let mode = self.asset_format_mode.get_or_init(|| {
-          get_config_assets_mode(&context.config)
-            .unwrap_or_else(|| (context.config.output.target_env.clone().into()))
-        });
-
-        let content = match mode {
-          AssetFormatMode::Node => {
-            format!(
-              r#"
-    import {{ fileURLToPath }} from "node:url";
-    export default fileURLToPath(new URL(/* {FARM_IGNORE_ACTION_COMMENT} */{assets_path:?}, import.meta.url))
-                "#
-            )
-          }
-          AssetFormatMode::Browser => {
-            format!("export default {assets_path:?};")
-          }
-        };
-
-        context.emit_file(EmitFileParams {
-          resolved_path: param.module_id.clone(),
-          name: resource_name,
-          content: bytes,
-          resource_type: ResourceType::Asset(ext.to_string()),
-        });
-
         return Ok(Some(farmfe_core::plugin::PluginTransformHookResult {
           content,
           module_type: Some(ModuleType::Js),
@@ -268,6 +747,57 @@ impl Plugin for FarmPluginStaticAssets {
     Ok(None)
   }
 
+  /// Emit the sprite of the icons, and the manifest of the assets of the targets, the placeholders are replaced by the assets
+  /// of the target of each output variant when the resources are written
+  fn finalize_resources(
+    &self,
+    param: &mut PluginFinalizeResourcesHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<Option<()>> {
+    self.emit_sprite(param, context)?;
+    self.check_unhashed_binary_names(context)?;
+
+    if !context.config.assets.has_targets(&context.config.mode) {
+      return Ok(None);
+    }
+
+    let targets = &context.config.assets.targets;
+    let mut manifest = BTreeMap::<String, BTreeMap<String, String>>::new();
+
+    for resource in param.resources_map.values() {
+      let (ResourceOrigin::Module(module_id), ResourceType::Asset(_)) =
+        (&resource.origin, &resource.resource_type)
+      else {
+        continue;
+      };
+
+      if let Some((target, _)) = resource.name.split_once('/') {
+        if targets.iter().any(|t| t == target) {
+          manifest
+            .entry(asset_target_key(module_id))
+            .or_default()
+            .insert(target.to_string(), resource.name.clone());
+        }
+      }
+    }
+
+    param.resources_map.insert(
+      ASSET_TARGETS_MANIFEST.to_string(),
+      Resource {
+        name: ASSET_TARGETS_MANIFEST.to_string(),
+        bytes: serde_json::to_vec_pretty(&manifest).unwrap(),
+        emitted: false,
+        resource_type: ResourceType::Asset("json".to_string()),
+        origin: ResourceOrigin::ResourcePot(ASSET_TARGETS_MANIFEST.to_string()),
+        source_path: None,
+        scope: Default::default(),
+        info: None,
+      },
+    );
+
+    Ok(Some(()))
+  }
+
   fn plugin_cache_loaded(
     &self,
     cache: &Vec<u8>,
@@ -275,14 +805,30 @@ impl Plugin for FarmPluginStaticAssets {
   ) -> farmfe_core::error::Result<Option<()>> {
     let cached_static_assets = deserialize!(cache, CachedStaticAssets);
 
+    for (key, asset) in cached_static_assets.transformed {
+      self.transformed_assets.insert(key, asset);
+    }
+
+    for (module_id, symbol) in cached_static_assets.sprite_symbols {
+      self
+        .sprite_symbols
+        .insert(module_id.as_str().into(), symbol);
+    }
+
     for asset in cached_static_assets.list {
       if let ResourceOrigin::Module(m) = asset.origin {
-        context.emit_file(EmitFileParams {
+        let params = EmitFileParams {
           resolved_path: m.to_string(),
           name: asset.name,
           content: asset.bytes,
           resource_type: asset.resource_type,
-        });
+          scope: asset.scope,
+        };
+
+        match asset.source_path {
+          Some(source_path) => context.emit_file_from_path(params, source_path),
+          None => context.emit_file(params),
+        }
       }
     }
 
@@ -294,18 +840,43 @@ impl Plugin for FarmPluginStaticAssets {
     context: &Arc<CompilationContext>,
   ) -> farmfe_core::error::Result<Option<Vec<u8>>> {
     let mut list = vec![];
-    let resources_map = context.resources_map.lock();
 
-    for (_, resource) in resources_map.iter() {
+    for resource in context.resources_map.iter() {
       if let ResourceOrigin::Module(m) = &resource.origin {
         if context.cache_manager.module_cache.has_cache(m) {
-          list.push(resource.clone());
+          list.push(resource.value().clone());
         }
       }
     }
 
-    if !list.is_empty() {
-      let cached_static_assets = CachedStaticAssets { list };
+    // the transformed assets not used by this process are dropped, e.g. the ones of removed assets or previous
+    // plugin options, and the rest are not persisted once the total size exceeds the limit
+    let mut transformed_bytes = 0;
+    let transformed = self
+      .transformed_assets
+      .iter()
+      .filter(|entry| self.used_transformed_assets.contains(entry.key()))
+      .filter(|entry| {
+        transformed_bytes += entry.value().content.len();
+        transformed_bytes <= MAX_PERSISTED_TRANSFORMED_ASSETS_BYTES
+      })
+      .map(|entry| (entry.key().clone(), entry.value().clone()))
+      .collect::<HashMap<_, _>>();
+
+    // the icons of the cached modules are not transformed again
+    let sprite_symbols = self
+      .sprite_symbols
+      .iter()
+      .filter(|entry| context.cache_manager.module_cache.has_cache(entry.key()))
+      .map(|entry| (entry.key().to_string(), entry.value().clone()))
+      .collect::<HashMap<_, _>>();
+
+    if !list.is_empty() || !transformed.is_empty() || !sprite_symbols.is_empty() {
+      let cached_static_assets = CachedStaticAssets {
+        list,
+        transformed,
+        sprite_symbols,
+      };
 
       Ok(Some(serialize!(&cached_static_assets)))
     } else {
@@ -317,6 +888,15 @@ impl Plugin for FarmPluginStaticAssets {
 #[cache_item]
 struct CachedStaticAssets {
   list: Vec<Resource>,
+  transformed: HashMap<String, TransformedAsset>,
+  sprite_symbols: HashMap<String, SpriteSymbol>,
+}
+
+#[cache_item]
+#[derive(Clone)]
+struct TransformedAsset {
+  ext: String,
+  content: Vec<u8>,
 }
 
 pub struct FarmPluginRaw {}
@@ -0,0 +1,114 @@
+//! Svg sprite of the icons imported with `?sprite`. Each icon is converted to a `<symbol>` when it's transformed,
+//! the sprite is built from the symbols of the icons in the module graph when the resources are finalized:
+//! ```html
+//! <svg xmlns="http://www.w3.org/2000/svg"><symbol id="icon-home" viewBox="0 0 24 24">...</symbol></svg>
+//! ```
+use farmfe_core::{cache_item, regex::Regex};
+use farmfe_toolkit::lazy_static::lazy_static;
+
+pub const SPRITE_QUERY: &str = "sprite";
+
+lazy_static! {
+  static ref SVG_TAG: Regex = Regex::new(r"(?s)<svg\b([^>]*)>").unwrap();
+  /// attributes of the root svg that are kept on the symbol
+  static ref SYMBOL_ATTRIBUTES: Regex =
+    Regex::new(r#"\s(viewBox|preserveAspectRatio)\s*=\s*("[^"]*"|'[^']*')"#).unwrap();
+}
+
+#[cache_item]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpriteSymbol {
+  pub id: String,
+  pub symbol: String,
+}
+
+/// `icon-` and `src/icons/arrow left.svg` -> `icon-arrow-left`
+pub fn sprite_symbol_id(prefix: &str, resolved_path: &str) -> String {
+  let name = std::path::Path::new(resolved_path)
+    .file_stem()
+    .and_then(|s| s.to_str())
+    .unwrap_or_default()
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+        c
+      } else {
+        '-'
+      }
+    })
+    .collect::<String>();
+
+  format!("{prefix}{name}")
+}
+
+/// Convert the root `<svg>` of the icon to a `<symbol>`, [None] if the content is not a svg
+pub fn svg_to_symbol(svg: &str, id: &str) -> Option<String> {
+  let svg_tag = SVG_TAG.captures(svg)?;
+  let content_start = svg_tag.get(0)?.end();
+  let content_end = svg.rfind("</svg>")?;
+
+  if content_end < content_start {
+    return None;
+  }
+
+  let attributes = SYMBOL_ATTRIBUTES
+    .find_iter(&svg_tag[1])
+    .map(|m| m.as_str())
+    .collect::<String>();
+
+  Some(format!(
+    "<symbol id=\"{id}\"{attributes}>{}</symbol>",
+    svg[content_start..content_end].trim()
+  ))
+}
+
+/// The symbols are sorted by id, so the sprite is stable whatever the order of the icons is transformed
+pub fn build_sprite(mut symbols: Vec<&SpriteSymbol>) -> String {
+  symbols.sort_by(|a, b| a.id.cmp(&b.id));
+
+  let mut sprite = String::from(r#"<svg xmlns="http://www.w3.org/2000/svg">"#);
+
+  for symbol in symbols {
+    sprite.push_str(&symbol.symbol);
+  }
+
+  sprite.push_str("</svg>");
+  sprite
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn build_sprite_from_icons() {
+    let id = sprite_symbol_id("icon-", "/root/src/icons/arrow left.svg");
+    assert_eq!(id, "icon-arrow-left");
+
+    let svg = r#"<?xml version="1.0"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24">
+  <path d="M0 0h24v24H0z"/>
+</svg>"#;
+    let arrow = SpriteSymbol {
+      symbol: svg_to_symbol(svg, &id).unwrap(),
+      id,
+    };
+    assert_eq!(
+      arrow.symbol,
+      r#"<symbol id="icon-arrow-left" viewBox="0 0 24 24"><path d="M0 0h24v24H0z"/></symbol>"#
+    );
+    assert_eq!(svg_to_symbol("not a svg", "icon-a"), None);
+
+    let home = SpriteSymbol {
+      id: "icon-home".to_string(),
+      symbol: r#"<symbol id="icon-home"></symbol>"#.to_string(),
+    };
+    assert_eq!(
+      build_sprite(vec![&home, &arrow]),
+      format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg">{}{}</svg>"#,
+        arrow.symbol, home.symbol
+      )
+    );
+  }
+}
@@ -0,0 +1,321 @@
+//! With `assets.wasmEsmIntegration`, WebAssembly modules are imported with ESM integration, the imports of the wasm binary are imported as js modules
+//! and its exports are re-exported by a facade module, for example:
+//! ```js
+//! import * as __farm_wasm_import_0 from "./env.js";
+//! const __farm_wasm_imports = { "./env.js": __farm_wasm_import_0 };
+//! const { instance: __farm_wasm_instance } = await WebAssembly.instantiateStreaming(fetch("/add-a1b2c3d4.wasm"), __farm_wasm_imports);
+//! const __farm_wasm_exports = __farm_wasm_instance.exports;
+//! export const add = __farm_wasm_exports.add;
+//! export default __farm_wasm_exports;
+//! ```
+use farmfe_core::config::asset::AssetFormatMode;
+
+const WASM_EXT: &str = "wasm";
+const WASM_MAGIC: &[u8] = b"\0asm";
+const IMPORT_SECTION: u8 = 2;
+const EXPORT_SECTION: u8 = 7;
+
+pub fn is_wasm(ext: &str) -> bool {
+  ext.eq_ignore_ascii_case(WASM_EXT)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct WasmInterface {
+  /// module names of the imports, deduplicated and in the order they first appear
+  pub imports: Vec<String>,
+  pub exports: Vec<String>,
+}
+
+/// Read the imported module names and the export names from the binary, `None` if the binary is malformed
+pub fn read_wasm_interface(bytes: &[u8]) -> Option<WasmInterface> {
+  if bytes.len() < 8 || &bytes[..4] != WASM_MAGIC {
+    return None;
+  }
+
+  let mut reader = Reader { bytes, pos: 8 };
+  let mut interface = WasmInterface::default();
+
+  while !reader.is_end() {
+    let id = reader.byte()?;
+    let size = reader.u32()? as usize;
+    let end = reader.pos.checked_add(size)?;
+
+    if end > bytes.len() {
+      return None;
+    }
+
+    match id {
+      IMPORT_SECTION => {
+        for _ in 0..reader.u32()? {
+          let module = reader.name()?;
+          reader.name()?;
+          reader.skip_import_desc()?;
+
+          if !interface.imports.contains(&module) {
+            interface.imports.push(module);
+          }
+        }
+      }
+      EXPORT_SECTION => {
+        for _ in 0..reader.u32()? {
+          interface.exports.push(reader.name()?);
+          reader.byte()?;
+          reader.u32()?;
+        }
+      }
+      _ => {}
+    }
+
+    reader.pos = end;
+  }
+
+  Some(interface)
+}
+
+/// Facade module of the wasm binary, `src` is the url of the emitted binary in browser, or its path in node
+pub fn wasm_module_code(
+  interface: &WasmInterface,
+  imports: &str,
+  src: &str,
+  mode: &AssetFormatMode,
+) -> String {
+  let mut code = vec![imports.to_string()];
+  let mut import_object = vec![];
+
+  for (i, module) in interface.imports.iter().enumerate() {
+    code.push(format!(
+      "import * as __farm_wasm_import_{i} from {module:?};"
+    ));
+    import_object.push(format!("{module:?}: __farm_wasm_import_{i}"));
+  }
+
+  code.push(format!(
+    "const __farm_wasm_imports = {{ {} }};",
+    import_object.join(", ")
+  ));
+  let instantiate = match mode {
+    // instantiateStreaming requires the `application/wasm` mime type, fallback to instantiate if the server does not send it
+    AssetFormatMode::Browser => format!(
+      r#"const __farm_wasm_instantiate = () => fetch({src}).then((r) => r.arrayBuffer()).then((b) => WebAssembly.instantiate(b, __farm_wasm_imports));
+const {{ instance: __farm_wasm_instance }} = await (typeof WebAssembly.instantiateStreaming === "function"
+  ? WebAssembly.instantiateStreaming(fetch({src}), __farm_wasm_imports).catch(__farm_wasm_instantiate)
+  : __farm_wasm_instantiate());"#
+    ),
+    AssetFormatMode::Node => format!(
+      r#"import {{ readFile as __farm_wasm_read_file }} from "node:fs/promises";
+const {{ instance: __farm_wasm_instance }} = await WebAssembly.instantiate(await __farm_wasm_read_file({src}), __farm_wasm_imports);"#
+    ),
+  };
+  code.push(instantiate);
+  code.push("const __farm_wasm_exports = __farm_wasm_instance.exports;".to_string());
+
+  for name in &interface.exports {
+    if is_valid_identifier(name) {
+      code.push(format!("export const {name} = __farm_wasm_exports.{name};"));
+    }
+  }
+
+  code.push("export default __farm_wasm_exports;".to_string());
+  code.join("\n")
+}
+
+/// Reserved words of strict mode code can not be the name of a `const` binding
+const RESERVED_WORDS: [&str; 48] = [
+  "arguments",
+  "await",
+  "break",
+  "case",
+  "catch",
+  "class",
+  "const",
+  "continue",
+  "debugger",
+  "default",
+  "delete",
+  "do",
+  "else",
+  "enum",
+  "eval",
+  "export",
+  "extends",
+  "false",
+  "finally",
+  "for",
+  "function",
+  "if",
+  "implements",
+  "import",
+  "in",
+  "instanceof",
+  "interface",
+  "let",
+  "new",
+  "null",
+  "package",
+  "private",
+  "protected",
+  "public",
+  "return",
+  "static",
+  "super",
+  "switch",
+  "this",
+  "throw",
+  "true",
+  "try",
+  "typeof",
+  "var",
+  "void",
+  "while",
+  "with",
+  "yield",
+];
+
+/// Exports that are not valid identifiers are only accessible from the default export
+fn is_valid_identifier(name: &str) -> bool {
+  let mut chars = name.chars();
+
+  chars
+    .next()
+    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    && !RESERVED_WORDS.contains(&name)
+    // the wasm exports are bound to the generated names of the facade module
+    && !name.starts_with("__farm_wasm_")
+}
+
+struct Reader<'a> {
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+impl Reader<'_> {
+  fn is_end(&self) -> bool {
+    self.pos >= self.bytes.len()
+  }
+
+  fn byte(&mut self) -> Option<u8> {
+    let byte = *self.bytes.get(self.pos)?;
+    self.pos += 1;
+    Some(byte)
+  }
+
+  /// unsigned LEB128
+  fn u32(&mut self) -> Option<u32> {
+    let mut result = 0u32;
+
+    for shift in (0..35).step_by(7) {
+      let byte = self.byte()?;
+      result |= ((byte & 0x7f) as u32) << shift;
+
+      if byte & 0x80 == 0 {
+        return Some(result);
+      }
+    }
+
+    None
+  }
+
+  fn name(&mut self) -> Option<String> {
+    let len = self.u32()? as usize;
+    let end = self.pos.checked_add(len)?;
+    let name = String::from_utf8(self.bytes.get(self.pos..end)?.to_vec()).ok()?;
+    self.pos = end;
+    Some(name)
+  }
+
+  fn limits(&mut self) -> Option<()> {
+    let flags = self.byte()?;
+    self.u32()?;
+
+    if flags & 0x01 != 0 {
+      self.u32()?;
+    }
+
+    Some(())
+  }
+
+  fn skip_import_desc(&mut self) -> Option<()> {
+    match self.byte()? {
+      // func: type index
+      0x00 => {
+        self.u32()?;
+      }
+      // table: ref type and limits
+      0x01 => {
+        self.byte()?;
+        self.limits()?;
+      }
+      // memory
+      0x02 => self.limits()?,
+      // global: value type and mutability
+      0x03 => {
+        self.byte()?;
+        self.byte()?;
+      }
+      // tag: attribute and type index
+      0x04 => {
+        self.byte()?;
+        self.u32()?;
+      }
+      _ => return None,
+    }
+
+    Some(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn read_interface() {
+    // (module
+    //   (import "./env.js" "log" (func (param i32)))
+    //   (memory (export "memory") 1)
+    //   (func (export "add") (param i32 i32) (result i32) local.get 0 local.get 1 i32.add))
+    let bytes = [
+      0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+      0x01, 0x0b, 0x02, 0x60, 0x01, 0x7f, 0x00, 0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7f, // types
+      0x02, 0x10, 0x01, 0x08, b'.', b'/', b'e', b'n', b'v', b'.', b'j', b's', 0x03, b'l', b'o',
+      b'g', 0x00, 0x00, // imports
+      0x03, 0x02, 0x01, 0x01, // functions
+      0x05, 0x03, 0x01, 0x00, 0x01, // memory
+      0x07, 0x10, 0x02, 0x06, b'm', b'e', b'm', b'o', b'r', b'y', 0x02, 0x00, 0x03, b'a', b'd',
+      b'd', 0x00, 0x01, // exports
+      0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6a, 0x0b, // code
+    ];
+
+    assert_eq!(
+      read_wasm_interface(&bytes),
+      Some(WasmInterface {
+        imports: vec!["./env.js".to_string()],
+        exports: vec!["memory".to_string(), "add".to_string()],
+      })
+    );
+    assert_eq!(read_wasm_interface(b"not wasm"), None);
+  }
+
+  #[test]
+  fn facade_code() {
+    let interface = WasmInterface {
+      imports: vec![],
+      exports: vec![
+        "add".to_string(),
+        "my-export".to_string(),
+        "delete".to_string(),
+        "default".to_string(),
+        "yield".to_string(),
+      ],
+    };
+    let code = wasm_module_code(&interface, "", "\"/add.wasm\"", &AssetFormatMode::Browser);
+
+    assert!(code.contains("export const add = __farm_wasm_exports.add;"));
+    assert!(!code.contains("my-export ="));
+    assert!(!code.contains("const delete"));
+    assert!(!code.contains("const default"));
+    assert!(!code.contains("const yield"));
+    assert!(code.contains("WebAssembly.instantiateStreaming"));
+  }
+}
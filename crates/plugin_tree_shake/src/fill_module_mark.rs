@@ -7,11 +7,13 @@ use farmfe_core::{
 };
 use farmfe_toolkit::script::swc_try_with::resolve_module_mark;
 
+use crate::module::is_tree_shakable;
+
 pub fn fill_module_mark(module_graph: &mut ModuleGraph, context: &Arc<CompilationContext>) {
   module_graph
     .modules_mut()
     .into_par_iter()
-    .filter(|m| m.module_type.is_script() && !m.external)
+    .filter(|m| is_tree_shakable(m))
     .for_each(|module| {
       let meta = module.meta.as_script_mut();
 
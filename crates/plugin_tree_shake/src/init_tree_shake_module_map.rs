@@ -7,7 +7,7 @@ use farmfe_core::{
   swc_common::GLOBALS,
 };
 
-use crate::module::TreeShakeModule;
+use crate::module::{is_tree_shakable, TreeShakeModule};
 
 pub fn init_tree_shake_module_map(
   module_graph: &mut ModuleGraph,
@@ -19,7 +19,7 @@ pub fn init_tree_shake_module_map(
     .modules_mut()
     .into_par_iter()
     .for_each(|module| {
-      if !module.module_type.is_script() || module.external {
+      if !is_tree_shakable(module) {
         return;
       }
 
@@ -11,7 +11,9 @@ use crate::remove_hot_update::remove_useless_hot_update_stmts;
 pub mod fill_module_mark;
 pub mod init_tree_shake_module_map;
 pub mod mark_initial_side_effects;
+pub mod mark_tree_shaken_edges;
 pub mod module;
+pub mod module_statements;
 pub mod remove_hot_update;
 pub mod statement_graph;
 pub mod tree_shake_modules;
@@ -77,7 +79,10 @@ impl Plugin for FarmPluginTreeShake {
       module_graph.remove_module(&module_id);
     }
 
-    // 7. remove useless hot update statements if production
+    // 7. mark the import edges whose statements are removed
+    mark_tree_shaken_edges::mark_tree_shaken_edges(module_graph);
+
+    // 8. remove useless hot update statements if production
     if matches!(context.config.mode, Mode::Production) {
       remove_useless_hot_update_stmts(module_graph);
     }
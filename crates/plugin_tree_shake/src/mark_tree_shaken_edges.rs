@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+
+use farmfe_core::{
+  module::module_graph::ModuleGraph,
+  plugin::ResolveKind,
+  swc_ecma_ast::{ModuleDecl, ModuleItem},
+};
+
+use crate::module::is_tree_shakable;
+
+/// Mark the static import edges whose import statements are all removed by tree shaking,
+/// so that the edges can be reported by [ModuleGraph::import_chains]
+pub fn mark_tree_shaken_edges(module_graph: &mut ModuleGraph) {
+  let mut shaken_edges = vec![];
+
+  for module in module_graph.modules() {
+    if !is_tree_shakable(module) {
+      continue;
+    }
+
+    let remaining_sources = module
+      .meta
+      .as_script()
+      .ast
+      .body
+      .iter()
+      .filter_map(|item| match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => Some(import.src.value.to_string()),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export_all)) => {
+          Some(export_all.src.value.to_string())
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export_named)) => {
+          export_named.src.as_ref().map(|src| src.value.to_string())
+        }
+        _ => None,
+      })
+      .collect::<HashSet<_>>();
+
+    for (dep, edge) in module_graph.dependencies(&module.id) {
+      let shaken = edge.items().iter().all(|item| {
+        matches!(item.kind, ResolveKind::Import | ResolveKind::ExportFrom)
+          && !remaining_sources.contains(&item.source)
+      });
+
+      if shaken {
+        shaken_edges.push((module.id.clone(), dep));
+      }
+    }
+  }
+
+  for (from, to) in shaken_edges {
+    module_graph.mark_edge_tree_shaken(&from, &to);
+  }
+}
@@ -4,7 +4,7 @@ use std::{
 };
 
 use farmfe_core::{
-  module::{Module, ModuleId, ModuleSystem},
+  module::{Module, ModuleId, ModuleSystem, ModuleType},
   swc_common::{comments::SingleThreadedComments, Mark},
 };
 
@@ -130,6 +130,13 @@ impl UsedExports {
   }
 }
 
+/// Whether the module takes part in tree shaking. Runtime modules are tree shaken too, so helpers
+/// that the runtime entry never uses are removed from the runtime resource pot.
+pub fn is_tree_shakable(module: &Module) -> bool {
+  (module.module_type.is_script() || matches!(module.module_type, ModuleType::Runtime))
+    && !module.external
+}
+
 pub struct TreeShakeModule {
   pub module_id: ModuleId,
   pub side_effects: bool,
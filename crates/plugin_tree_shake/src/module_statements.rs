@@ -0,0 +1,220 @@
+use std::collections::HashSet;
+
+use farmfe_core::{
+  context::CompilationContext,
+  module::{
+    module_statement::{ModuleExport, ModuleStatement, StatementSideEffectsKind},
+    ModuleId, ModuleMetaData, ScriptModuleMetaData,
+  },
+  swc_common::{comments::SingleThreadedComments, Mark, GLOBALS},
+  swc_ecma_ast::{Id, Module as SwcModule, ModuleDecl, ModuleItem},
+};
+
+use crate::statement_graph::{
+  ExportSpecifierInfo, ImportSpecifierInfo, Statement, StatementGraph, StatementSideEffects,
+};
+
+/// Analyze the top level statements of the script module in the module graph, [None] if it's not a script module
+pub fn module_statements(
+  context: &CompilationContext,
+  module_id: &ModuleId,
+) -> Option<Vec<ModuleStatement>> {
+  let module_graph = context.module_graph.read();
+  let module = module_graph.module(module_id)?;
+
+  let ModuleMetaData::Script(script) = module.meta.as_ref() else {
+    return None;
+  };
+
+  Some(analyze_script(context, script, analyze_module_statements))
+}
+
+/// Analyze the exported names of the script module in the module graph, [None] if it's not a script module
+pub fn module_exports(
+  context: &CompilationContext,
+  module_id: &ModuleId,
+) -> Option<Vec<ModuleExport>> {
+  let module_graph = context.module_graph.read();
+  let module = module_graph.module(module_id)?;
+
+  let ModuleMetaData::Script(script) = module.meta.as_ref() else {
+    return None;
+  };
+
+  Some(script_module_exports(context, script))
+}
+
+/// Exported names of the script module, for callers that already hold the module graph
+pub fn script_module_exports(
+  context: &CompilationContext,
+  script: &ScriptModuleMetaData,
+) -> Vec<ModuleExport> {
+  analyze_script(context, script, analyze_module_exports)
+}
+
+fn analyze_script<T>(
+  context: &CompilationContext,
+  script: &ScriptModuleMetaData,
+  analyze: impl FnOnce(&SwcModule, Mark, Mark, &SingleThreadedComments) -> T,
+) -> T {
+  GLOBALS.set(&context.meta.script.globals, || {
+    analyze(
+      &script.ast,
+      Mark::from_u32(script.unresolved_mark),
+      Mark::from_u32(script.top_level_mark),
+      &SingleThreadedComments::from(script.comments.clone()),
+    )
+  })
+}
+
+/// Analyze the imports, exports, defined names, dependencies and side effects of the top level statements, see [ModuleStatement]
+pub fn analyze_module_statements(
+  ast: &SwcModule,
+  unresolved_mark: Mark,
+  top_level_mark: Mark,
+  comments: &SingleThreadedComments,
+) -> Vec<ModuleStatement> {
+  let stmt_graph = StatementGraph::new(ast, unresolved_mark, top_level_mark, comments);
+
+  let mut statements = stmt_graph
+    .stmts()
+    .into_iter()
+    .map(|stmt| {
+      let mut dependencies = stmt_graph
+        .dependencies(&stmt.id)
+        .into_iter()
+        .map(|(dep, _)| dep.id)
+        .collect::<Vec<_>>();
+      dependencies.sort();
+
+      // `export { a, b as c }` re-exports the names defined by other statements
+      let defined = if matches!(
+        ast.body[stmt.id],
+        ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(_))
+      ) {
+        vec![]
+      } else {
+        sorted_names(&stmt.defined_idents)
+      };
+
+      ModuleStatement {
+        index: stmt.id,
+        source: statement_source(stmt),
+        imported: imported_names(stmt),
+        exported: exported_names(stmt),
+        defined,
+        dependencies,
+        side_effects: side_effects_kind(&stmt.side_effects),
+      }
+    })
+    .collect::<Vec<_>>();
+  statements.sort_by_key(|stmt| stmt.index);
+
+  statements
+}
+
+/// Analyze the exported names and re-exports of the module in the order of the statements, see [ModuleExport]
+pub fn analyze_module_exports(
+  ast: &SwcModule,
+  unresolved_mark: Mark,
+  top_level_mark: Mark,
+  comments: &SingleThreadedComments,
+) -> Vec<ModuleExport> {
+  let stmt_graph = StatementGraph::new(ast, unresolved_mark, top_level_mark, comments);
+
+  let mut stmts = stmt_graph.stmts();
+  stmts.sort_by_key(|stmt| stmt.id);
+
+  stmts
+    .into_iter()
+    .filter_map(|stmt| stmt.export_info.as_ref())
+    .flat_map(|export_info| {
+      export_info.specifiers.iter().map(move |specifier| {
+        let (name, local) = match specifier {
+          ExportSpecifierInfo::All => ("*".to_string(), None),
+          ExportSpecifierInfo::Named { local, exported } => (
+            exported.as_ref().unwrap_or(local).0.to_string(),
+            Some(local.0.to_string()),
+          ),
+          ExportSpecifierInfo::Default => ("default".to_string(), None),
+          ExportSpecifierInfo::Namespace(name) => (name.0.to_string(), None),
+        };
+
+        ModuleExport {
+          name,
+          local,
+          source: export_info.source.clone(),
+          statement: export_info.stmt_id,
+        }
+      })
+    })
+    .collect()
+}
+
+fn statement_source(stmt: &Statement) -> Option<String> {
+  if let Some(import_info) = &stmt.import_info {
+    return Some(import_info.source.clone());
+  }
+
+  stmt
+    .export_info
+    .as_ref()
+    .and_then(|export_info| export_info.source.clone())
+}
+
+fn imported_names(stmt: &Statement) -> Vec<String> {
+  let Some(import_info) = &stmt.import_info else {
+    return vec![];
+  };
+
+  import_info
+    .specifiers
+    .iter()
+    .map(|specifier| match specifier {
+      ImportSpecifierInfo::Namespace(local)
+      | ImportSpecifierInfo::Named { local, .. }
+      | ImportSpecifierInfo::Default(local) => local.0.to_string(),
+    })
+    .collect()
+}
+
+fn exported_names(stmt: &Statement) -> Vec<String> {
+  let Some(export_info) = &stmt.export_info else {
+    return vec![];
+  };
+
+  export_info
+    .specifiers
+    .iter()
+    .map(|specifier| match specifier {
+      ExportSpecifierInfo::All => "*".to_string(),
+      ExportSpecifierInfo::Named { local, exported } => {
+        exported.as_ref().unwrap_or(local).0.to_string()
+      }
+      ExportSpecifierInfo::Default => "default".to_string(),
+      ExportSpecifierInfo::Namespace(name) => name.0.to_string(),
+    })
+    .collect()
+}
+
+fn sorted_names(idents: &HashSet<Id>) -> Vec<String> {
+  let mut names = idents
+    .iter()
+    .map(|ident| ident.0.to_string())
+    .collect::<Vec<_>>();
+  names.sort();
+
+  names
+}
+
+fn side_effects_kind(side_effects: &StatementSideEffects) -> StatementSideEffectsKind {
+  match side_effects {
+    StatementSideEffects::NoSideEffects => StatementSideEffectsKind::NoSideEffects,
+    StatementSideEffects::WriteTopLevelVar(_) => StatementSideEffectsKind::WriteTopLevelVar,
+    StatementSideEffects::ReadTopLevelVar(_) => StatementSideEffectsKind::ReadTopLevelVar,
+    StatementSideEffects::WriteOrCallGlobalVar => StatementSideEffectsKind::WriteOrCallGlobalVar,
+    StatementSideEffects::UnclassifiedSelfExecuted => {
+      StatementSideEffectsKind::UnclassifiedSelfExecuted
+    }
+  }
+}
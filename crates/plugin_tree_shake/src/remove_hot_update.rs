@@ -5,11 +5,13 @@ use farmfe_core::{
 };
 use farmfe_toolkit::swc_ecma_visit::{VisitMut, VisitMutWith};
 
+use crate::module::is_tree_shakable;
+
 pub fn remove_useless_hot_update_stmts(module_graph: &mut ModuleGraph) {
   let mut remover = UselessHotUpdateStmtRemover;
 
   module_graph.modules_mut().iter_mut().for_each(|module| {
-    if !module.module_type.is_script() || module.external {
+    if !is_tree_shakable(module) {
       return;
     }
     let script_meta_data = module.meta.as_script_mut();
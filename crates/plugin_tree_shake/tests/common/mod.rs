@@ -63,11 +63,8 @@ pub fn create_module_with_comments(code: &str) -> Module {
     ast,
     top_level_mark: top_level_mark.as_u32(),
     unresolved_mark: unresolved_mark.as_u32(),
-    module_system: farmfe_core::module::ModuleSystem::EsModule,
-    hmr_self_accepted: false,
-    hmr_accepted_deps: Default::default(),
     comments: comments.into(),
-    custom: Default::default(),
+    ..Default::default()
   }));
   module
 }
@@ -78,13 +75,7 @@ pub fn create_module(code: &str) -> (Module, Arc<SourceMap>) {
   let (ast, cm) = parse_module(code);
   module.meta = Box::new(ModuleMetaData::Script(ScriptModuleMetaData {
     ast,
-    top_level_mark: 0,
-    unresolved_mark: 0,
-    module_system: farmfe_core::module::ModuleSystem::EsModule,
-    hmr_self_accepted: false,
-    hmr_accepted_deps: Default::default(),
-    comments: Default::default(),
-    custom: Default::default(),
+    ..Default::default()
   }));
   (module, cm)
 }
@@ -96,13 +87,7 @@ pub fn create_module_with_globals(code: &str) -> Module {
     let (ast, _) = parse_module(code);
     module.meta = Box::new(ModuleMetaData::Script(ScriptModuleMetaData {
       ast,
-      top_level_mark: 0,
-      unresolved_mark: 0,
-      module_system: farmfe_core::module::ModuleSystem::EsModule,
-      hmr_self_accepted: false,
-      hmr_accepted_deps: Default::default(),
-      comments: Default::default(),
-      custom: Default::default(),
+      ..Default::default()
     }));
     module
   })
@@ -0,0 +1,102 @@
+use farmfe_core::{
+  module::module_statement::StatementSideEffectsKind,
+  swc_common::{Globals, GLOBALS},
+};
+use farmfe_plugin_tree_shake::module_statements::{
+  analyze_module_exports, analyze_module_statements,
+};
+
+mod common;
+
+use crate::common::parse_module_with_comments;
+
+#[test]
+fn analyze_module_statements_basic() {
+  let code = r#"
+import React, { useState } from 'react';
+const count = 1;
+export function Counter() {
+  const [value] = useState(count);
+  return React.createElement('div', null, value);
+}
+console.log('loaded');
+export { count as initialCount };
+export * from './utils';
+"#;
+
+  GLOBALS.set(&Globals::new(), || {
+    let (ast, comments, unresolved_mark, top_level_mark) = parse_module_with_comments(code);
+    let statements = analyze_module_statements(&ast, unresolved_mark, top_level_mark, &comments);
+
+    assert_eq!(statements.len(), 6);
+
+    assert_eq!(statements[0].source, Some("react".to_string()));
+    assert_eq!(statements[0].imported, vec!["React", "useState"]);
+    assert_eq!(statements[0].defined, vec!["React", "useState"]);
+
+    assert_eq!(statements[1].defined, vec!["count"]);
+    assert_eq!(
+      statements[1].side_effects,
+      StatementSideEffectsKind::NoSideEffects
+    );
+
+    assert_eq!(statements[2].exported, vec!["Counter"]);
+    assert_eq!(statements[2].defined, vec!["Counter"]);
+    assert_eq!(statements[2].dependencies, vec![0, 1]);
+
+    assert_eq!(
+      statements[3].side_effects,
+      StatementSideEffectsKind::WriteOrCallGlobalVar
+    );
+
+    assert_eq!(statements[4].exported, vec!["initialCount"]);
+    assert!(statements[4].defined.is_empty());
+    assert_eq!(statements[4].dependencies, vec![1]);
+
+    assert_eq!(statements[5].source, Some("./utils".to_string()));
+    assert_eq!(statements[5].exported, vec!["*"]);
+  });
+}
+
+#[test]
+fn analyze_module_exports_basic() {
+  let code = r#"
+import { helper } from './helper';
+const count = 1;
+export function Counter() {}
+export { count as initialCount, helper };
+export { format as formatDate } from './date';
+export * as utils from './utils';
+export * from './shared';
+export default Counter;
+"#;
+
+  GLOBALS.set(&Globals::new(), || {
+    let (ast, comments, unresolved_mark, top_level_mark) = parse_module_with_comments(code);
+    let exports = analyze_module_exports(&ast, unresolved_mark, top_level_mark, &comments);
+    let exports = exports
+      .iter()
+      .map(|e| {
+        (
+          e.name.as_str(),
+          e.local.as_deref(),
+          e.source.as_deref(),
+          e.statement,
+        )
+      })
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      exports,
+      vec![
+        ("Counter", Some("Counter"), None, 2),
+        ("initialCount", Some("count"), None, 3),
+        ("helper", Some("helper"), None, 3),
+        ("formatDate", Some("format"), Some("./date"), 4),
+        ("utils", None, Some("./utils"), 5),
+        ("*", None, Some("./shared"), 6),
+        ("default", None, None, 7),
+      ]
+    );
+  });
+}
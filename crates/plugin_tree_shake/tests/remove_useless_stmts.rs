@@ -77,6 +77,7 @@ export default 'default';
           source: "./src/bar".to_string(),
           kind: ResolveKind::ExportFrom,
           order: 0,
+          resolve_trace: None,
         }]),
       )
       .unwrap();
@@ -195,6 +196,7 @@ export * from './src/bar';
           source: "./foo".to_string(),
           kind: ResolveKind::Import,
           order: 0,
+          resolve_trace: None,
         }]),
       )
       .unwrap();
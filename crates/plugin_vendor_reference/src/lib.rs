@@ -0,0 +1,270 @@
+use std::{
+  collections::{BTreeMap, HashMap, HashSet},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  config::{
+    vendor_reference::{VendorReferenceConfig, VendorReferenceManifest},
+    Config, FARM_MODULE_SYSTEM,
+  },
+  context::CompilationContext,
+  error::{CompilationError, Result},
+  module::ModuleType,
+  parking_lot::RwLock,
+  plugin::{
+    Plugin, PluginFinalizeResourcesHookParams, PluginHookContext, PluginLoadHookParam,
+    PluginLoadHookResult, PluginResolveHookParam, PluginResolveHookResult,
+  },
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde_json,
+};
+use farmfe_toolkit::html::get_farm_global_this;
+
+const PLUGIN_NAME: &str = "FarmPluginVendorReference";
+/// suffix of the virtual module that loads a module of an externals provider
+pub const VENDOR_PROVIDED_SUFFIX: &str = ".farm_vendor_provided";
+
+/// Vendor reference builds, see [VendorReferenceConfig].
+/// A vendor reference build emits a manifest of the import sources it provides and registers each entry under its import source.
+/// A build that links against the manifests resolves the provided import sources as externals, so they are required from the
+/// module system at runtime instead of being bundled again. The resources of the vendor reference builds are loaded by the html.
+/// The import sources of the providers are resolved to virtual modules that load the resources of the provider build on demand.
+pub struct FarmPluginVendorReference {
+  config: VendorReferenceConfig,
+  /// import sources provided by the referenced manifests
+  provided_sources: RwLock<HashSet<String>>,
+  /// import source -> the module of a provider
+  provider_modules: RwLock<HashMap<String, ProvidedModule>>,
+}
+
+struct ProvidedModule {
+  /// id of the module registered by the provider build
+  module_id: String,
+  /// urls of the resources of the provider build
+  resources: Vec<String>,
+}
+
+impl FarmPluginVendorReference {
+  pub fn new(config: &Config) -> Self {
+    Self {
+      config: config
+        .vendor_reference
+        .as_ref()
+        .map(|c| *c.clone())
+        .unwrap_or_default(),
+      provided_sources: RwLock::new(HashSet::new()),
+      provider_modules: RwLock::new(HashMap::new()),
+    }
+  }
+
+  fn load_providers(&self, root: &str) -> Result<HashMap<String, ProvidedModule>> {
+    let mut provider_modules = HashMap::new();
+
+    for (provider, manifest) in self.config.load_providers(root)? {
+      let resources = manifest.resource_urls();
+      let mut sources = manifest
+        .modules
+        .keys()
+        .map(|source| (source, source))
+        .collect::<Vec<_>>();
+
+      for (alias, source) in &provider.alias {
+        if !manifest.modules.contains_key(source) {
+          return Err(CompilationError::GenericError(format!(
+            "`{source}` aliased by `{alias}` is not provided by vendor reference manifest `{}`",
+            provider.manifest
+          )));
+        }
+
+        sources.push((alias, source));
+      }
+
+      for (source, provided) in sources {
+        provider_modules
+          .entry(source.clone())
+          .or_insert_with(|| ProvidedModule {
+            module_id: manifest.modules[provided].clone(),
+            resources: resources.clone(),
+          });
+      }
+    }
+
+    Ok(provider_modules)
+  }
+
+  fn provided_code(&self, source: &str, context: &Arc<CompilationContext>) -> Option<String> {
+    let provider_modules = self.provider_modules.read();
+    let provided = provider_modules.get(source)?;
+    let farm_global_this = get_farm_global_this(
+      &context.config.runtime.namespace,
+      &context.config.output.target_env,
+    );
+
+    Some(
+      include_str!("provided_module.js")
+        .replace(
+          "'FARM_MODULE_SYSTEM'",
+          &format!("{farm_global_this}.{FARM_MODULE_SYSTEM}"),
+        )
+        .replace(
+          "'RESOURCES'",
+          &serde_json::to_string(&provided.resources).unwrap(),
+        )
+        .replace(
+          "'MODULE_ID'",
+          &serde_json::to_string(&provided.module_id).unwrap(),
+        ),
+    )
+  }
+
+  fn emit_manifest(
+    &self,
+    filename: &str,
+    param: &mut PluginFinalizeResourcesHookParams,
+    context: &Arc<CompilationContext>,
+  ) {
+    let module_graph = context.module_graph.read();
+    let resource_pot_map = context.resource_pot_map.read();
+    let farm_global_this = get_farm_global_this(
+      &context.config.runtime.namespace,
+      &context.config.output.target_env,
+    );
+    let mut modules = BTreeMap::new();
+
+    for (entry, name) in &module_graph.entries {
+      let Some(source) = context.config.input.get(name) else {
+        continue;
+      };
+      let Some(resource_pot) = module_graph
+        .module(entry)
+        .and_then(|m| m.resource_pot.as_ref())
+        .and_then(|id| resource_pot_map.resource_pot(id))
+      else {
+        continue;
+      };
+      let id = entry.id(context.config.mode.clone());
+      // the linked builds require the entry by its import source
+      let alias = format!(
+        "{farm_global_this}.{FARM_MODULE_SYSTEM}.register({source:?},function(m,e,r){{m.exports=r({id:?})}});"
+      );
+
+      for resource_name in resource_pot.resources() {
+        if let Some(resource) = param.resources_map.get_mut(resource_name) {
+          if matches!(resource.resource_type, ResourceType::Js) {
+            resource.bytes.extend(alias.as_bytes());
+          }
+        }
+      }
+
+      modules.insert(source.clone(), id);
+    }
+
+    // the runtime is provided by the linked builds
+    param
+      .resources_map
+      .retain(|_, resource| !matches!(resource.resource_type, ResourceType::Runtime));
+
+    let mut resources = param
+      .resources_map
+      .values()
+      .filter(|r| !r.emitted && matches!(r.resource_type, ResourceType::Js))
+      .map(|r| r.name.clone())
+      .collect::<Vec<_>>();
+    resources.sort();
+
+    let manifest = VendorReferenceManifest {
+      public_path: context.config.output.public_path.clone(),
+      resources,
+      modules,
+    };
+
+    param.resources_map.insert(
+      filename.to_string(),
+      Resource {
+        name: filename.to_string(),
+        bytes: serde_json::to_vec_pretty(&manifest).unwrap(),
+        resource_type: ResourceType::Custom("json".to_string()),
+        origin: ResourceOrigin::ResourcePot(filename.to_string()),
+        ..Default::default()
+      },
+    );
+  }
+}
+
+impl Plugin for FarmPluginVendorReference {
+  fn name(&self) -> &str {
+    PLUGIN_NAME
+  }
+
+  fn build_start(&self, context: &Arc<CompilationContext>) -> Result<Option<()>> {
+    let manifests = self.config.load_references(&context.config.root)?;
+
+    *self.provided_sources.write() = manifests
+      .into_iter()
+      .flat_map(|manifest| manifest.modules.into_keys())
+      .collect();
+    *self.provider_modules.write() = self.load_providers(&context.config.root)?;
+
+    Ok(Some(()))
+  }
+
+  fn resolve(
+    &self,
+    param: &PluginResolveHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginResolveHookResult>> {
+    if self.provided_sources.read().contains(&param.source) {
+      return Ok(Some(PluginResolveHookResult {
+        resolved_path: param.source.clone(),
+        external: true,
+        ..Default::default()
+      }));
+    }
+
+    if self.provider_modules.read().contains_key(&param.source) {
+      return Ok(Some(PluginResolveHookResult {
+        resolved_path: format!("{}{VENDOR_PROVIDED_SUFFIX}", param.source),
+        ..Default::default()
+      }));
+    }
+
+    Ok(None)
+  }
+
+  fn load(
+    &self,
+    param: &PluginLoadHookParam,
+    context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginLoadHookResult>> {
+    let Some(source) = param.resolved_path.strip_suffix(VENDOR_PROVIDED_SUFFIX) else {
+      return Ok(None);
+    };
+
+    Ok(
+      self
+        .provided_code(source, context)
+        .map(|content| PluginLoadHookResult {
+          content,
+          module_type: ModuleType::Js,
+          source_map: None,
+        }),
+    )
+  }
+
+  fn finalize_resources(
+    &self,
+    param: &mut PluginFinalizeResourcesHookParams,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    let Some(filename) = &self.config.manifest else {
+      return Ok(None);
+    };
+
+    self.emit_manifest(filename, param, context);
+
+    Ok(Some(()))
+  }
+}
@@ -0,0 +1,19 @@
+// Inject during compile time
+const FarmModuleSystem = 'FARM_MODULE_SYSTEM';
+const resources = 'RESOURCES';
+const moduleId = 'MODULE_ID';
+
+const baseUrl =
+  typeof location !== 'undefined' ? location.href : 'http://localhost/';
+
+// the resources of the provider build register the provided modules when they are executed
+await Promise.all(
+  resources.map((path) =>
+    FarmModuleSystem.resourceLoader.load({
+      path: new URL(path, baseUrl).href,
+      type: 0
+    })
+  )
+);
+
+module.exports = FarmModuleSystem.require(moduleId);
@@ -51,6 +51,7 @@ pub fn construct_test_module_graph() -> ModuleGraph {
           source: format!("./{to}"),
           kind: ResolveKind::Import,
           order,
+          resolve_trace: None,
         },
       )
       .unwrap();
@@ -65,6 +66,7 @@ pub fn construct_test_module_graph() -> ModuleGraph {
           source: format!("./{to}"),
           kind: ResolveKind::DynamicImport,
           order,
+          resolve_trace: None,
         },
       )
       .unwrap();
@@ -78,6 +80,7 @@ pub fn construct_test_module_graph() -> ModuleGraph {
         source: "./A".to_string(),
         kind: ResolveKind::Import,
         order: 0,
+        resolve_trace: None,
       },
     )
     .unwrap();
@@ -173,6 +176,7 @@ pub fn construct_test_module_graph_complex() -> ModuleGraph {
           source: format!("./{to}"),
           kind: ResolveKind::Import,
           order,
+          resolve_trace: None,
         },
       )
       .unwrap();
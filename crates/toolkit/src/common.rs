@@ -6,22 +6,23 @@ use std::{
 use farmfe_core::{
   config::{
     bool_or_obj::BoolOrObj,
-    comments::CommentsConfig,
+    comments::{is_license_comment, CommentsConfig},
     config_regex::ConfigRegex,
     minify::{MinifyMode, MinifyOptions},
-    SourcemapConfig,
+    path_mapping::{to_host_path, PathMapping},
+    Config, SourcemapConfig,
   },
   enhanced_magic_string::collapse_sourcemap::collapse_sourcemap_chain,
   relative_path::RelativePath,
   resource::{resource_pot::ResourcePot, Resource, ResourceOrigin, ResourceType},
   serde_json::Value,
   swc_common::{
-    comments::{Comment, CommentKind, SingleThreadedComments},
+    comments::{Comment, SingleThreadedComments},
     source_map::SourceMapGenConfig,
     BytePos, FileName, LineCol, SourceFile, SourceMap,
   },
 };
-use farmfe_utils::hash::base64_decode;
+use farmfe_utils::{hash::base64_decode, relative};
 
 use crate::hash::base64_encode;
 
@@ -77,6 +78,62 @@ pub fn append_source_map_comment(
   resource.bytes.append(&mut source_map_comment.into_bytes());
 }
 
+/// Remap the sources of the generated source maps. Used when generating maps for both dev and build,
+/// so the served maps and the written maps are the same.
+#[derive(Debug, Clone)]
+pub struct SourcemapSources {
+  root: String,
+  /// absolute base directory, see [farmfe_core::config::SourcemapBase]
+  base: Option<String>,
+  path_mappings: Vec<PathMapping>,
+}
+
+impl SourcemapSources {
+  pub fn new(config: &Config) -> Self {
+    Self {
+      root: config.root.clone(),
+      base: config
+        .sourcemap_base
+        .as_ref()
+        .map(|base| base.resolve_dir(&config.root)),
+      path_mappings: config.path_mappings.clone(),
+    }
+  }
+
+  pub fn remap(&self, src: &str) -> String {
+    let Some(base) = &self.base else {
+      // the absolute host path is used so the devtools of the host can find the file
+      let path = Path::new(&self.root).join(src);
+
+      return to_host_path(&self.path_mappings, &path.to_string_lossy())
+        .unwrap_or_else(|| format!("/{}", relative(&self.root, src)));
+    };
+
+    // module ids are relative to root
+    let path = Path::new(&self.root).join(src);
+    relative(base, &path.to_string_lossy())
+  }
+}
+
+/// Set the `file` field of the source map to the file name of the resource, it's relative to the source map file
+pub fn set_source_map_file(map: &mut Resource, resource_name: &str) {
+  let Ok(mut source_map) = sourcemap::SourceMap::from_slice(&map.bytes) else {
+    return;
+  };
+
+  let file = Path::new(resource_name)
+    .file_name()
+    .map(|name| name.to_string_lossy().to_string())
+    .unwrap_or_else(|| resource_name.to_string());
+  source_map.set_file(Some(file));
+
+  let mut bytes = vec![];
+
+  if source_map.to_writer(&mut bytes).is_ok() {
+    map.bytes = bytes;
+  }
+}
+
 pub fn generate_source_map_resource(resource_pot: &ResourcePot) -> Resource {
   // collapse source map chain
   let source_map_chain = resource_pot
@@ -96,6 +153,8 @@ pub fn generate_source_map_resource(resource_pot: &ResourcePot) -> Resource {
     emitted: false,
     resource_type: ResourceType::SourceMap(resource_pot.id.to_string()),
     origin: ResourceOrigin::ResourcePot(resource_pot.id.clone()),
+    source_path: None,
+    scope: Default::default(),
     info: None,
   }
 }
@@ -141,36 +200,35 @@ impl SourceMapGenConfig for FarmSwcSourceMapConfig {
   }
 }
 
-/// minify comments, the rule is same as swc, see https://github.com/swc-project/swc/blob/main/crates/swc_compiler_base/src/lib.rs
+/// remove the comments that are not preserved by the policy, the rule is same as swc, see https://github.com/swc-project/swc/blob/main/crates/swc_compiler_base/src/lib.rs
 pub fn minify_comments(comments: &SingleThreadedComments, config: &CommentsConfig) {
-  match config {
-    // preserve all comments
-    CommentsConfig::Bool(true) => {}
-    CommentsConfig::Bool(false) => {
-      let (mut l, mut t) = comments.borrow_all_mut();
-      l.clear();
-      t.clear();
-    }
-    CommentsConfig::License => {
-      let preserve_excl = |_: &BytePos, vc: &mut Vec<Comment>| -> bool {
-        // Preserve license comments.
-        //
-        // See https://github.com/terser/terser/blob/798135e04baddd94fea403cfaab4ba8b22b1b524/lib/output.js#L175-L181
-        vc.retain(|c: &Comment| {
-          c.text.contains("@lic")
-            || c.text.contains("@preserve")
-            || c.text.contains("@copyright")
-            || c.text.contains("@cc_on")
-            || (c.kind == CommentKind::Block && c.text.starts_with('!'))
-        });
-        !vc.is_empty()
-      };
-      let (mut l, mut t) = comments.borrow_all_mut();
+  let (mut l, mut t) = comments.borrow_all_mut();
 
-      l.retain(preserve_excl);
-      t.retain(preserve_excl);
-    }
+  if !config.enabled() {
+    l.clear();
+    t.clear();
+    return;
   }
+
+  let preserve = |_: &BytePos, vc: &mut Vec<Comment>| -> bool {
+    vc.retain(|c| config.preserves(c));
+    !vc.is_empty()
+  };
+
+  l.retain(preserve);
+  t.retain(preserve);
+}
+
+/// remove license comments, used when the license comments are extracted to a separate resource
+pub fn remove_license_comments(comments: &SingleThreadedComments) {
+  let remove_license = |_: &BytePos, vc: &mut Vec<Comment>| -> bool {
+    vc.retain(|c| !is_license_comment(c));
+    !vc.is_empty()
+  };
+  let (mut l, mut t) = comments.borrow_all_mut();
+
+  l.retain(remove_license);
+  t.retain(remove_license);
 }
 
 pub fn load_source_original_source_map(
@@ -247,7 +305,7 @@ impl MinifyBuilder {
     false
   }
 
-  fn is_match_mode(&self) -> bool {
+  pub fn is_match_mode(&self) -> bool {
     if let Some(expect_mode) = &self.expect_mode {
       return if let Some(ref minify_options) = self.minify_options {
         expect_mode == &minify_options.mode
@@ -335,4 +393,37 @@ mod tests {
       assert!(!builder.is_enabled("index.js"));
     }
   }
+
+  mod sourcemap_sources {
+    use super::super::SourcemapSources;
+    use farmfe_core::config::{path_mapping::PathMapping, Config, SourcemapBase};
+
+    #[test]
+    fn remap_sources() {
+      let root = std::env::current_dir().unwrap().join("packages/app");
+      let mut config = Config {
+        root: root.to_string_lossy().to_string(),
+        ..Default::default()
+      };
+
+      let sources = SourcemapSources::new(&config);
+      assert_eq!(sources.remap("src/index.ts"), "/src/index.ts");
+
+      config.path_mappings = vec![PathMapping {
+        compiler: root.to_string_lossy().to_string(),
+        host: "/Users/me/app".to_string(),
+      }];
+      let sources = SourcemapSources::new(&config);
+      assert_eq!(sources.remap("src/index.ts"), "/Users/me/app/src/index.ts");
+      config.path_mappings = vec![];
+
+      config.sourcemap_base = Some(SourcemapBase::Custom("../..".to_string()));
+      let sources = SourcemapSources::new(&config);
+      assert_eq!(sources.remap("src/index.ts"), "packages/app/src/index.ts");
+      assert_eq!(
+        sources.remap(&root.join("src/a.ts").to_string_lossy()),
+        "packages/app/src/a.ts"
+      );
+    }
+  }
 }
@@ -1,9 +1,13 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{fmt::Write, path::PathBuf, sync::Arc};
 
 use farmfe_core::{
+  config::comments::{is_license_comment, CommentsConfig},
   error::CompilationError,
   regex::Regex,
-  swc_common::{comments::SingleThreadedComments, input::SourceFileInput},
+  swc_common::{
+    comments::{Comment, SingleThreadedComments},
+    input::SourceFileInput,
+  },
   swc_css_ast::Stylesheet,
 };
 use swc_css_codegen::{
@@ -125,3 +129,44 @@ pub fn codegen_css_stylesheet(
     (css_code, None)
   }
 }
+
+/// swc css codegen does not emit comments, so the license comments preserved by the policy are generated separately and
+/// placed before the stylesheet, e.g. `/*! normalize.css v8.0.1 | MIT License */`
+pub fn codegen_css_license_comments(
+  comments: &SingleThreadedComments,
+  config: &CommentsConfig,
+) -> String {
+  let (leading, trailing) = comments.borrow_all();
+  let mut license_comments = leading
+    .iter()
+    .chain(trailing.iter())
+    .flat_map(|(pos, comments)| {
+      comments
+        .iter()
+        .map(move |c| (*pos, strip_comment_delimiters(c)))
+    })
+    .filter(|(_, c)| is_license_comment(c) && config.preserves(c))
+    .collect::<Vec<_>>();
+  license_comments.sort_by_key(|(pos, _)| *pos);
+
+  license_comments
+    .into_iter()
+    .fold(String::new(), |mut code, (_, c)| {
+      let _ = writeln!(code, "/*{}*/", c.text);
+      code
+    })
+}
+
+/// the css lexer keeps the `/*` and `*/` delimiters in the comment text unlike the script lexer
+fn strip_comment_delimiters(comment: &Comment) -> Comment {
+  let text = comment
+    .text
+    .strip_prefix("/*")
+    .and_then(|text| text.strip_suffix("*/"))
+    .unwrap_or(&comment.text);
+
+  Comment {
+    text: text.into(),
+    ..comment.clone()
+  }
+}
@@ -25,6 +25,16 @@ pub fn transform_output_filename(
   name: &str,
   bytes: &[u8],
   ext: &str,
+) -> String {
+  transform_output_filename_with_hash(filename_config, name, || sha256(bytes, 8), ext)
+}
+
+/// Same as [transform_output_filename], but the content hash is computed by `content_hash` only when the filename contains a content hash placeholder
+pub fn transform_output_filename_with_hash<F: FnOnce() -> String>(
+  filename_config: String,
+  name: &str,
+  content_hash: F,
+  ext: &str,
 ) -> String {
   let mut res = filename_config;
 
@@ -35,11 +45,9 @@ pub fn transform_output_filename(
   }
 
   if res.contains(CONTENT_HASH) {
-    let content_hash = sha256(bytes, 8);
-    res = res.replace(CONTENT_HASH, &content_hash);
+    res = res.replace(CONTENT_HASH, &content_hash());
   } else if res.contains(CONTENT_HASH_NEW) {
-    let content_hash = sha256(bytes, 8);
-    res = res.replace(CONTENT_HASH_NEW, &content_hash);
+    res = res.replace(CONTENT_HASH_NEW, &content_hash());
   }
 
   if res.contains(EXT) {
@@ -55,6 +63,23 @@ pub fn transform_output_entry_filename(
   entry_filename: &str,
   bytes: &[u8],
   ext: &str,
+) -> String {
+  transform_output_entry_filename_with_hash(
+    entry_filename_config,
+    name,
+    entry_filename,
+    || sha256(bytes, 8),
+    ext,
+  )
+}
+
+/// Same as [transform_output_entry_filename], but the content hash is computed by `content_hash` lazily
+pub fn transform_output_entry_filename_with_hash<F: FnOnce() -> String>(
+  entry_filename_config: String,
+  name: &str,
+  entry_filename: &str,
+  content_hash: F,
+  ext: &str,
 ) -> String {
   let mut res = entry_filename_config;
 
@@ -62,5 +87,5 @@ pub fn transform_output_entry_filename(
     res = res.replace(ENTRY_NAME, entry_filename);
   }
 
-  transform_output_filename(res, name, bytes, ext)
+  transform_output_filename_with_hash(res, name, content_hash, ext)
 }
@@ -1,20 +1,25 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use farmfe_core::{
-  config::Mode,
+  config::{
+    config_regex::ConfigRegex, Mode, RuntimeRetryConfig, RuntimeTrustedTypesConfig,
+    FARM_MODULE_SYSTEM,
+  },
   module::{
     module_graph::ModuleGraph,
     module_group::{ModuleGroupGraph, ModuleGroupId},
-    ModuleId,
+    DynamicImportHints, ModuleId, ModuleMetaData,
   },
+  plugin::ResolveKind,
   resource::{resource_pot_map::ResourcePotMap, Resource, ResourceType},
+  serde_json,
 };
 
 pub fn get_dynamic_resources_map(
   module_group_graph: &ModuleGroupGraph,
   module_group_id: &ModuleGroupId,
   resource_pot_map: &ResourcePotMap,
-  resources_map: &HashMap<String, Resource>,
+  resource_type_of: impl Fn(&str) -> Option<ResourceType>,
   module_graph: &ModuleGraph,
 ) -> HashMap<ModuleId, Vec<(String, ResourceType)>> {
   let mut dep_module_groups = vec![];
@@ -42,29 +47,27 @@ pub fn get_dynamic_resources_map(
         let resources = dynamic_resources_map.get_mut(&mg_id).unwrap();
 
         for r in rp.resources() {
-          let resource = resources_map.get(r).unwrap();
+          let resource_type = resource_type_of(r).unwrap();
 
           // Currently only support js and css
-          if !matches!(resource.resource_type, ResourceType::Js | ResourceType::Css) {
+          if !matches!(resource_type, ResourceType::Js | ResourceType::Css) {
             continue;
           }
 
-          resources.push((resource.name.clone(), resource.resource_type.clone()));
+          resources.push((r.clone(), resource_type));
         }
       } else {
         let mut resources = vec![];
 
         for r in rp.resources() {
-          let resource = resources_map
-            .get(r)
-            .unwrap_or_else(|| panic!("{r} not found"));
+          let resource_type = resource_type_of(r).unwrap_or_else(|| panic!("{r} not found"));
 
           // Currently only support js and css
-          if !matches!(resource.resource_type, ResourceType::Js | ResourceType::Css) {
+          if !matches!(resource_type, ResourceType::Js | ResourceType::Css) {
             continue;
           }
 
-          resources.push((resource.name.clone(), resource.resource_type.clone()));
+          resources.push((r.clone(), resource_type));
         }
 
         dynamic_resources_map.insert(mg_id.clone(), resources);
@@ -105,9 +108,7 @@ pub fn get_dynamic_resources_code(
           dynamic_resources.push(format!(r#"{{ path: '{resource_name}', type: 1 }}"#));
         }
         _ => {
-          panic!(
-            "unsupported type ({resource_type:?}) when injecting dynamic resources"
-          )
+          panic!("unsupported type ({resource_type:?}) when injecting dynamic resources")
         }
       }
 
@@ -132,3 +133,113 @@ pub fn get_dynamic_resources_code(
     dynamic_resources_code,
   )
 }
+
+/// Hints of the dynamic imports of the module from the magic comments of all its importers, see [DynamicImportHints].
+/// The importers are sorted to make sure the chunk name is stable
+pub fn get_dynamic_import_hints(
+  module_graph: &ModuleGraph,
+  module_id: &ModuleId,
+) -> DynamicImportHints {
+  // e.g. the module group of a module that is removed by an update
+  if !module_graph.has_module(module_id) {
+    return DynamicImportHints::default();
+  }
+
+  let mut dependents = module_graph.dependents(module_id);
+  dependents.sort_by(|a, b| a.0.cmp(&b.0));
+
+  let mut hints = DynamicImportHints::default();
+
+  for (importer, edge) in dependents {
+    let Some(ModuleMetaData::Script(meta)) = module_graph.module(&importer).map(|m| &*m.meta)
+    else {
+      continue;
+    };
+
+    for item in edge.iter() {
+      if !matches!(item.kind, ResolveKind::DynamicImport) {
+        continue;
+      }
+
+      if let Some(item_hints) = meta.dynamic_import_hints.get(&item.source) {
+        if hints.chunk_name.is_none() {
+          hints.chunk_name.clone_from(&item_hints.chunk_name);
+        }
+
+        hints.prefetch |= item_hints.prefetch;
+        hints.preload |= item_hints.preload;
+      }
+    }
+  }
+
+  hints
+}
+
+/// Names of the high priority resources, see [Resource::is_high_priority]
+pub fn get_high_priority_resources(
+  resources_map: &HashMap<String, Resource>,
+  high_priority: &[ConfigRegex],
+) -> HashSet<String> {
+  resources_map
+    .values()
+    .filter(|r| r.is_high_priority(high_priority))
+    .map(|r| r.name.clone())
+    .collect()
+}
+
+/// Code that tells the runtime to load the dynamic modules that have high priority resources when the browser is idle,
+/// empty if there is no such module
+pub fn get_high_priority_modules_code(
+  dynamic_resources_map: &HashMap<ModuleId, Vec<(String, ResourceType)>>,
+  high_priority_resources: &HashSet<String>,
+  farm_global_this: &str,
+  mode: Mode,
+) -> String {
+  let mut modules = dynamic_resources_map
+    .iter()
+    .filter(|(_, resources)| {
+      resources
+        .iter()
+        .any(|(name, _)| high_priority_resources.contains(name))
+    })
+    .map(|(module_id, _)| format!("'{}'", module_id.id(mode.clone()).replace('\\', r"\\")))
+    .collect::<Vec<_>>();
+
+  if modules.is_empty() {
+    return String::new();
+  }
+
+  modules.sort();
+
+  format!(
+    r#"{farm_global_this}.{FARM_MODULE_SYSTEM}.setHighPriorityModules([{}]);"#,
+    modules.join(",")
+  )
+}
+
+/// Code that configures the retry of the dynamic resources that are failed to load, empty if retry is disabled
+pub fn get_resource_load_retry_code(retry: &RuntimeRetryConfig, farm_global_this: &str) -> String {
+  if retry.count == 0 {
+    return String::new();
+  }
+
+  format!(
+    r#"{farm_global_this}.{FARM_MODULE_SYSTEM}.setResourceLoadRetry({{count:{},delay:{},cacheBusting:{}}});"#,
+    retry.count, retry.delay, retry.cache_busting
+  )
+}
+
+/// Create the Trusted Types policy of the runtime before the runtime plugins are bootstrapped and the resources are loaded
+pub fn get_trusted_types_code(
+  trusted_types: &Option<RuntimeTrustedTypesConfig>,
+  farm_global_this: &str,
+) -> String {
+  let Some(trusted_types) = trusted_types else {
+    return String::new();
+  };
+
+  format!(
+    r#"{farm_global_this}.{FARM_MODULE_SYSTEM}.setTrustedTypesPolicy({});"#,
+    serde_json::to_string(&trusted_types.policy_name).unwrap()
+  )
+}
@@ -0,0 +1,243 @@
+//! Check whether the emitted code is ES5, used by `output.es5` to make sure every syntax is downgraded.
+use std::{path::PathBuf, sync::Arc};
+
+use farmfe_core::{
+  error::{CompilationError, Result},
+  swc_common::{Span, Spanned},
+  swc_ecma_ast::{
+    ArrayPat, ArrowExpr, AssignExpr, AssignOp, AssignPat, AwaitExpr, BinExpr, BinaryOp, CallExpr,
+    Callee, Class, EsVersion, ExprOrSpread, ForOfStmt, Function, Lit, MetaPropExpr, MetaPropKind,
+    Module as SwcModule, ModuleDecl, ObjectPat, OptChainExpr, Prop, PropName, RestPat,
+    SpreadElement, TaggedTpl, Tpl, VarDecl, VarDeclKind,
+  },
+};
+use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax};
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::common::{create_swc_source_map, Source};
+
+/// Return an error that points to the first non ES5 syntax of the code, `name` is the name of the resource
+pub fn check_es5_syntax(name: &str, code: &str) -> Result<()> {
+  let (cm, source_file) = create_swc_source_map(Source {
+    path: PathBuf::from(name),
+    content: Arc::new(code.to_string()),
+  });
+  let lexer = Lexer::new(
+    Syntax::Es(Default::default()),
+    EsVersion::EsNext,
+    StringInput::from(&*source_file),
+    None,
+  );
+  let ast = Parser::new_from(lexer)
+    .parse_module()
+    .map_err(|e| CompilationError::ParseError {
+      resolved_path: name.to_string(),
+      msg: format!("{e:?}"),
+    })?;
+
+  if let Some((syntax, span)) = find_non_es5_syntax(&ast) {
+    let loc = cm.lookup_char_pos(span.lo);
+
+    return Err(CompilationError::GenericError(format!(
+      "`output.es5` is enabled but {syntax} is found at {name}:{}:{}. Make sure the module is transpiled by `presetEnv`",
+      loc.line,
+      loc.col.0 + 1
+    )));
+  }
+
+  Ok(())
+}
+
+/// Find the first syntax that is introduced after ES5, e.g. arrow functions, `let` or `import.meta`
+pub fn find_non_es5_syntax(ast: &SwcModule) -> Option<(&'static str, Span)> {
+  let mut finder = NonEs5SyntaxFinder { found: None };
+  ast.visit_with(&mut finder);
+
+  finder.found
+}
+
+struct NonEs5SyntaxFinder {
+  found: Option<(&'static str, Span)>,
+}
+
+impl NonEs5SyntaxFinder {
+  fn found(&mut self, syntax: &'static str, span: Span) {
+    if self.found.is_none() {
+      self.found = Some((syntax, span));
+    }
+  }
+}
+
+impl Visit for NonEs5SyntaxFinder {
+  fn visit_module_decl(&mut self, n: &ModuleDecl) {
+    self.found("module declaration", n.span());
+  }
+
+  fn visit_var_decl(&mut self, n: &VarDecl) {
+    match n.kind {
+      VarDeclKind::Var => n.visit_children_with(self),
+      VarDeclKind::Let => self.found("`let`", n.span),
+      VarDeclKind::Const => self.found("`const`", n.span),
+    }
+  }
+
+  fn visit_arrow_expr(&mut self, n: &ArrowExpr) {
+    self.found("arrow function", n.span);
+  }
+
+  fn visit_function(&mut self, n: &Function) {
+    if n.is_async {
+      self.found("async function", n.span);
+    } else if n.is_generator {
+      self.found("generator function", n.span);
+    } else {
+      n.visit_children_with(self);
+    }
+  }
+
+  fn visit_class(&mut self, n: &Class) {
+    self.found("class", n.span);
+  }
+
+  fn visit_tpl(&mut self, n: &Tpl) {
+    self.found("template literal", n.span);
+  }
+
+  fn visit_tagged_tpl(&mut self, n: &TaggedTpl) {
+    self.found("tagged template", n.span);
+  }
+
+  fn visit_meta_prop_expr(&mut self, n: &MetaPropExpr) {
+    match n.kind {
+      MetaPropKind::ImportMeta => self.found("`import.meta`", n.span),
+      MetaPropKind::NewTarget => self.found("`new.target`", n.span),
+    }
+  }
+
+  fn visit_call_expr(&mut self, n: &CallExpr) {
+    if matches!(n.callee, Callee::Import(_)) {
+      self.found("dynamic import", n.span);
+    } else {
+      n.visit_children_with(self);
+    }
+  }
+
+  fn visit_await_expr(&mut self, n: &AwaitExpr) {
+    self.found("`await`", n.span);
+  }
+
+  fn visit_for_of_stmt(&mut self, n: &ForOfStmt) {
+    self.found("`for...of`", n.span);
+  }
+
+  fn visit_expr_or_spread(&mut self, n: &ExprOrSpread) {
+    if let Some(spread) = n.spread {
+      self.found("spread element", spread);
+    } else {
+      n.visit_children_with(self);
+    }
+  }
+
+  fn visit_spread_element(&mut self, n: &SpreadElement) {
+    self.found("object spread", n.dot3_token);
+  }
+
+  fn visit_object_pat(&mut self, n: &ObjectPat) {
+    self.found("destructuring", n.span);
+  }
+
+  fn visit_array_pat(&mut self, n: &ArrayPat) {
+    self.found("destructuring", n.span);
+  }
+
+  fn visit_rest_pat(&mut self, n: &RestPat) {
+    self.found("rest parameter", n.span);
+  }
+
+  fn visit_assign_pat(&mut self, n: &AssignPat) {
+    self.found("default parameter", n.span);
+  }
+
+  fn visit_opt_chain_expr(&mut self, n: &OptChainExpr) {
+    self.found("optional chaining", n.span);
+  }
+
+  fn visit_bin_expr(&mut self, n: &BinExpr) {
+    match n.op {
+      BinaryOp::NullishCoalescing => self.found("nullish coalescing", n.span),
+      BinaryOp::Exp => self.found("exponentiation operator", n.span),
+      _ => n.visit_children_with(self),
+    }
+  }
+
+  fn visit_assign_expr(&mut self, n: &AssignExpr) {
+    match n.op {
+      AssignOp::ExpAssign | AssignOp::AndAssign | AssignOp::OrAssign | AssignOp::NullishAssign => {
+        self.found("logical or exponentiation assignment", n.span)
+      }
+      _ => n.visit_children_with(self),
+    }
+  }
+
+  fn visit_prop(&mut self, n: &Prop) {
+    match n {
+      Prop::Shorthand(ident) => self.found("shorthand property", ident.span),
+      Prop::Method(method) => self.found("method property", method.function.span),
+      _ => n.visit_children_with(self),
+    }
+  }
+
+  // `a[b]` is a computed member and valid ES5, only the computed keys of object literals are not
+  fn visit_prop_name(&mut self, n: &PropName) {
+    match n {
+      PropName::Computed(computed) => self.found("computed property", computed.span),
+      _ => n.visit_children_with(self),
+    }
+  }
+
+  fn visit_lit(&mut self, n: &Lit) {
+    if let Lit::BigInt(big_int) = n {
+      self.found("bigint literal", big_int.span);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn es5_code() {
+    let code = r#"(function(){var a = { b: 1, get c() { return 2; } };for(var k in a){console.log(a[k]);}})();"#;
+
+    assert!(check_es5_syntax("index.js", code).is_ok());
+  }
+
+  #[test]
+  fn non_es5_code() {
+    let cases = [
+      (
+        "var a = 1;\nvar f = () => a;",
+        "arrow function",
+        "index.js:2:9",
+      ),
+      ("let a = 1;", "`let`", "index.js:1:1"),
+      (
+        "var url = import.meta.url;",
+        "`import.meta`",
+        "index.js:1:11",
+      ),
+      ("var a = { b };", "shorthand property", "index.js:1:11"),
+      ("var a = `b`;", "template literal", "index.js:1:9"),
+      ("import './a';", "module declaration", "index.js:1:1"),
+      ("var a = { [b]: 1 };", "computed property", "index.js:1:11"),
+    ];
+
+    for (code, syntax, position) in cases {
+      let err = check_es5_syntax("index.js", code).unwrap_err().to_string();
+
+      assert!(err.contains(syntax), "{err}");
+      assert!(err.contains(position), "{err}");
+    }
+  }
+}
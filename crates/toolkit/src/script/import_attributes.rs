@@ -0,0 +1,117 @@
+//! Helpers for import attributes, e.g. `import data from './data' with { type: "json" }`.
+use farmfe_core::{
+  swc_common::DUMMY_SP,
+  swc_ecma_ast::{KeyValueProp, Lit, ObjectLit, Prop, PropName, PropOrSpread, Str},
+};
+use farmfe_utils::{parse_query, stringify_query};
+
+pub const IMPORT_ATTRIBUTE_TYPE: &str = "type";
+pub const IMPORT_ATTRIBUTE_TYPE_JSON: &str = "json";
+
+/// Collect `key: "value"` pairs of the `with` clause. Entries whose value is not a string literal are collected with [None] value.
+pub fn get_import_attributes(with: &ObjectLit) -> Vec<(String, Option<String>)> {
+  with
+    .props
+    .iter()
+    .filter_map(|prop| match prop {
+      PropOrSpread::Prop(box Prop::KeyValue(KeyValueProp { key, value })) => {
+        let key = match key {
+          PropName::Ident(ident) => ident.sym.to_string(),
+          PropName::Str(str) => str.value.to_string(),
+          _ => return None,
+        };
+        let value = match &**value {
+          farmfe_core::swc_ecma_ast::Expr::Lit(Lit::Str(str)) => Some(str.value.to_string()),
+          _ => None,
+        };
+
+        Some((key, value))
+      }
+      _ => None,
+    })
+    .collect()
+}
+
+/// `{ type: "json" }`
+pub fn create_json_import_attributes() -> Box<ObjectLit> {
+  Box::new(ObjectLit {
+    span: DUMMY_SP,
+    props: vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+      key: PropName::Ident(IMPORT_ATTRIBUTE_TYPE.into()),
+      value: Box::new(farmfe_core::swc_ecma_ast::Expr::Lit(Lit::Str(Str {
+        span: DUMMY_SP,
+        value: IMPORT_ATTRIBUTE_TYPE_JSON.into(),
+        raw: None,
+      }))),
+    })))],
+  })
+}
+
+fn is_json_type_query(query: &(String, String)) -> bool {
+  query.0 == IMPORT_ATTRIBUTE_TYPE && query.1 == IMPORT_ATTRIBUTE_TYPE_JSON
+}
+
+/// Whether the source is imported with `type: "json"`, which is marked by `?type=json`, see [add_json_import_query].
+/// Sources with `.json` extension but without the attribute are not json imports, their `with` clause is not generated for externals.
+pub fn is_json_import_source(source: &str) -> bool {
+  parse_query(source).iter().any(is_json_type_query)
+}
+
+/// `./data.txt` -> `./data.txt?type=json`, so the json pipeline loads the module whatever its extension is and the
+/// attribute is generated again for externals.
+pub fn add_json_import_query(source: &str) -> String {
+  if is_json_import_source(source) {
+    return source.to_string();
+  }
+
+  let separator = if source.contains('?') { '&' } else { '?' };
+
+  format!("{source}{separator}{IMPORT_ATTRIBUTE_TYPE}={IMPORT_ATTRIBUTE_TYPE_JSON}")
+}
+
+/// `./data.txt?type=json` -> `./data.txt`, reverse of [add_json_import_query]
+pub fn strip_json_import_query(source: &str) -> String {
+  let Some((path, _)) = source.split_once('?') else {
+    return source.to_string();
+  };
+
+  let query = parse_query(source)
+    .into_iter()
+    .filter(|q| !is_json_type_query(q))
+    .collect::<Vec<_>>();
+
+  format!("{path}{}", stringify_query(&query))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{add_json_import_query, is_json_import_source, strip_json_import_query};
+
+  #[test]
+  fn json_import_query() {
+    assert_eq!(add_json_import_query("./data.txt"), "./data.txt?type=json");
+    assert_eq!(
+      add_json_import_query("./data.txt?a=b"),
+      "./data.txt?a=b&type=json"
+    );
+    assert_eq!(
+      add_json_import_query("./data.json"),
+      "./data.json?type=json"
+    );
+    assert_eq!(
+      add_json_import_query("./data.json?type=json"),
+      "./data.json?type=json"
+    );
+    assert!(is_json_import_source("./data.txt?type=json"));
+    assert!(!is_json_import_source("./data.txt"));
+    assert!(!is_json_import_source("./data.json"));
+    assert_eq!(
+      strip_json_import_query("./data.txt?type=json"),
+      "./data.txt"
+    );
+    assert_eq!(
+      strip_json_import_query("./data.txt?a=b&type=json"),
+      "./data.txt?a=b"
+    );
+  }
+}
@@ -7,7 +7,7 @@ use swc_ecma_codegen::{
 use swc_ecma_parser::{lexer::Lexer, EsConfig, Parser, StringInput, Syntax, TsConfig};
 
 use farmfe_core::{
-  config::{comments::CommentsConfig, ScriptParserConfig},
+  config::{comments::CommentsConfig, ScriptParserConfig, FARM_MODULE},
   context::CompilationContext,
   error::{CompilationError, Result},
   module::{ModuleSystem, ModuleType},
@@ -17,8 +17,9 @@ use farmfe_core::{
     BytePos, FileName, LineCol, Mark, SourceMap,
   },
   swc_ecma_ast::{
-    CallExpr, Callee, EsVersion, Expr, Ident, Import, MemberProp, Module as SwcModule, ModuleItem,
-    Stmt,
+    CallExpr, Callee, EsVersion, Expr, Ident, Import, Lit, MemberExpr, MemberProp, MetaPropExpr,
+    MetaPropKind, Module as SwcModule, ModuleItem, NewExpr, ObjectLit, Prop, PropName,
+    PropOrSpread, Stmt,
   },
 };
 use swc_ecma_visit::{Visit, VisitWith};
@@ -30,9 +31,11 @@ pub use farmfe_toolkit_plugin_types::swc_ast::ParseScriptModuleResult;
 
 use self::swc_try_with::try_with;
 
+pub mod constant;
 pub mod defined_idents_collector;
+pub mod es5_syntax;
+pub mod import_attributes;
 pub mod swc_try_with;
-pub mod constant;
 
 /// parse the content of a module to [SwcModule] ast.
 pub fn parse_module(
@@ -209,6 +212,80 @@ pub fn is_dynamic_import(call_expr: &CallExpr) -> bool {
   matches!(&call_expr.callee, Callee::Import(Import { .. }))
 }
 
+/// Whether the expr is `import.meta.url`, or `module.meta.url` which it's transformed to before the modules are rendered.
+pub fn is_import_meta_url(expr: &Expr) -> bool {
+  let Expr::Member(MemberExpr {
+    obj,
+    prop: MemberProp::Ident(Ident { sym, .. }),
+    ..
+  }) = expr
+  else {
+    return false;
+  };
+
+  sym == "url"
+    && match &**obj {
+      Expr::MetaProp(MetaPropExpr {
+        kind: MetaPropKind::ImportMeta,
+        ..
+      }) => true,
+      Expr::Member(MemberExpr {
+        obj: box Expr::Ident(module),
+        prop: MemberProp::Ident(meta),
+        ..
+      }) => module.sym == FARM_MODULE && meta.sym == "meta",
+      _ => false,
+    }
+}
+
+/// Get the source of a web worker, e.g. `./worker.ts` of `new Worker(new URL('./worker.ts', import.meta.url))`.
+/// `SharedWorker` is also supported.
+pub fn get_worker_url_source(new_expr: &NewExpr) -> Option<String> {
+  let Expr::Ident(Ident { sym, .. }) = &*new_expr.callee else {
+    return None;
+  };
+
+  if sym != "Worker" && sym != "SharedWorker" {
+    return None;
+  }
+
+  let Expr::New(NewExpr {
+    callee: box Expr::Ident(url),
+    args: Some(url_args),
+    ..
+  }) = &*new_expr.args.as_ref()?.first()?.expr
+  else {
+    return None;
+  };
+
+  if url.sym != "URL" || url_args.len() != 2 || !is_import_meta_url(&url_args[1].expr) {
+    return None;
+  }
+
+  match &*url_args[0].expr {
+    Expr::Lit(Lit::Str(str)) => Some(str.value.to_string()),
+    _ => None,
+  }
+}
+
+/// Whether the worker is created with `{ type: "module" }`, e.g. `new Worker(new URL('./worker.ts', import.meta.url), { type: 'module' })`
+pub fn is_module_worker(new_expr: &NewExpr) -> bool {
+  let Some(box Expr::Object(ObjectLit { props, .. })) = new_expr
+    .args
+    .as_ref()
+    .and_then(|args| args.get(1))
+    .map(|arg| &arg.expr)
+  else {
+    return false;
+  };
+
+  props.iter().any(|prop| {
+    matches!(prop, PropOrSpread::Prop(box Prop::KeyValue(kv))
+      if matches!(&kv.key, PropName::Ident(i) if i.sym == "type")
+        && matches!(&*kv.value, Expr::Lit(Lit::Str(s)) if s.value == "module"))
+  })
+}
+
 pub fn module_system_from_deps(deps: Vec<ResolveKind>) -> ModuleSystem {
   let mut module_system = ModuleSystem::Custom(String::from("unknown"));
 
@@ -313,6 +390,12 @@ pub fn set_module_system_for_module_meta(
   param: &mut PluginFinalizeModuleHookParam,
   context: &Arc<CompilationContext>,
 ) {
+  // custom module systems detected by plugins are kept, see `Plugin::detect_module_system`
+  if matches!(&param.module.meta.as_script().module_system, ModuleSystem::Custom(name) if name != "unknown")
+  {
+    return;
+  }
+
   // default to commonjs
   let module_system_from_deps_option = if !param.deps.is_empty() {
     module_system_from_deps(param.deps.iter().map(|d| d.kind.clone()).collect())
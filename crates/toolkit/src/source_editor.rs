@@ -0,0 +1,284 @@
+//! Targeted edits of code with the source map of the edits generated, like [MagicString](https://github.com/rich-harris/magic-string).
+//! Plugins that only insert or replace a few pieces of the code use it instead of returning an unmapped whole-file string.
+//! The source map of the edits is pushed to the source map chain of the module, e.g. as the `source_map` of the transform result.
+
+use farmfe_core::error::{CompilationError, Result};
+use sourcemap::{SourceMap, SourceMapBuilder};
+
+#[derive(Debug, Clone)]
+struct Edit {
+  start: usize,
+  end: usize,
+  content: String,
+}
+
+/// Edits of `code` applied by [SourceEditor::generate], positions are byte offsets of the original code.
+/// Contents inserted at the same position are kept in the inserting order, and the replacements must not overlap
+#[derive(Debug, Clone)]
+pub struct SourceEditor {
+  original: String,
+  filename: String,
+  intro: String,
+  outro: String,
+  /// sorted by start, insertions are empty ranges
+  edits: Vec<Edit>,
+}
+
+impl SourceEditor {
+  /// `filename` is the source of the generated source map, e.g. the resolved path of the module
+  pub fn new(code: impl Into<String>, filename: impl Into<String>) -> Self {
+    Self {
+      original: code.into(),
+      filename: filename.into(),
+      intro: String::new(),
+      outro: String::new(),
+      edits: vec![],
+    }
+  }
+
+  pub fn original(&self) -> &str {
+    &self.original
+  }
+
+  pub fn has_changed(&self) -> bool {
+    !self.intro.is_empty() || !self.outro.is_empty() || !self.edits.is_empty()
+  }
+
+  /// Insert `content` at the start of the code, before the contents prepended earlier
+  pub fn prepend(&mut self, content: &str) -> &mut Self {
+    self.intro.insert_str(0, content);
+    self
+  }
+
+  /// Insert `content` at the end of the code, after the contents appended earlier
+  pub fn append(&mut self, content: &str) -> &mut Self {
+    self.outro.push_str(content);
+    self
+  }
+
+  /// Insert `content` at `index`, after the contents inserted at the same index earlier
+  pub fn insert(&mut self, index: usize, content: &str) -> Result<&mut Self> {
+    self.add_edit(index, index, content)?;
+    Ok(self)
+  }
+
+  /// Replace `start..end` of the original code with `content`, the replacement is mapped to `start`
+  pub fn overwrite(&mut self, start: usize, end: usize, content: &str) -> Result<&mut Self> {
+    if start == end {
+      return Err(CompilationError::GenericError(format!(
+        "can not overwrite the empty range {start}..{end} of {}, insert the content instead",
+        self.filename
+      )));
+    }
+
+    self.add_edit(start, end, content)?;
+    Ok(self)
+  }
+
+  /// Remove `start..end` of the original code
+  pub fn remove(&mut self, start: usize, end: usize) -> Result<&mut Self> {
+    if start == end {
+      return Ok(self);
+    }
+
+    self.overwrite(start, end, "")
+  }
+
+  fn add_edit(&mut self, start: usize, end: usize, content: &str) -> Result<()> {
+    if start > end
+      || end > self.original.len()
+      || !self.original.is_char_boundary(start)
+      || !self.original.is_char_boundary(end)
+    {
+      return Err(CompilationError::GenericError(format!(
+        "invalid range {start}..{end} of {}, whose length is {}",
+        self.filename,
+        self.original.len()
+      )));
+    }
+
+    if let Some(edit) = self
+      .edits
+      .iter()
+      .find(|edit| edit.start < end && start < edit.end)
+    {
+      return Err(CompilationError::GenericError(format!(
+        "range {start}..{end} of {} overlaps the edited range {}..{}",
+        self.filename, edit.start, edit.end
+      )));
+    }
+
+    // insertions at the start of a replacement are placed before it, and the ones at the end after it
+    let index = self
+      .edits
+      .partition_point(|edit| edit.start < start || (edit.start == start && edit.end <= end));
+    self.edits.insert(
+      index,
+      Edit {
+        start,
+        end,
+        content: content.to_string(),
+      },
+    );
+
+    Ok(())
+  }
+
+  /// Generate the edited code and the source map from it to the original code
+  pub fn generate(&self) -> (String, SourceMap) {
+    let mut code = String::with_capacity(self.original.len() + self.intro.len() + self.outro.len());
+    let mut builder = SourceMapBuilder::new(None);
+    let source = builder.add_source(&self.filename);
+    builder.set_source_contents(source, Some(&self.original));
+
+    let mut generated = Position::default();
+    let mut original = Position::default();
+    let mut cursor = 0;
+
+    generated.advance(&self.intro);
+    code.push_str(&self.intro);
+
+    for edit in &self.edits {
+      let unchanged = &self.original[cursor..edit.start];
+      add_unchanged_mappings(&mut builder, &self.filename, unchanged, generated, original);
+      generated.advance(unchanged);
+      original.advance(unchanged);
+      code.push_str(unchanged);
+
+      if edit.start != edit.end && !edit.content.is_empty() {
+        builder.add(
+          generated.line,
+          generated.column,
+          original.line,
+          original.column,
+          Some(&self.filename),
+          None,
+          false,
+        );
+      }
+
+      generated.advance(&edit.content);
+      original.advance(&self.original[edit.start..edit.end]);
+      code.push_str(&edit.content);
+      cursor = edit.end;
+    }
+
+    let unchanged = &self.original[cursor..];
+    add_unchanged_mappings(&mut builder, &self.filename, unchanged, generated, original);
+    code.push_str(unchanged);
+    code.push_str(&self.outro);
+
+    (code, builder.into_sourcemap())
+  }
+
+  /// The source map of [SourceEditor::generate] serialized to json
+  pub fn generate_map(&self) -> String {
+    let (_, map) = self.generate();
+    let mut buf = vec![];
+    map.to_writer(&mut buf).expect("failed to write sourcemap");
+
+    String::from_utf8(buf).unwrap()
+  }
+}
+
+impl std::fmt::Display for SourceEditor {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.intro)?;
+    let mut cursor = 0;
+
+    for edit in &self.edits {
+      f.write_str(&self.original[cursor..edit.start])?;
+      f.write_str(&edit.content)?;
+      cursor = edit.end;
+    }
+
+    f.write_str(&self.original[cursor..])?;
+    f.write_str(&self.outro)
+  }
+}
+
+/// 0-based line and utf-16 column, the same as the positions of source maps
+#[derive(Debug, Clone, Copy, Default)]
+struct Position {
+  line: u32,
+  column: u32,
+}
+
+impl Position {
+  fn advance(&mut self, text: &str) {
+    for c in text.chars() {
+      if c == '\n' {
+        self.line += 1;
+        self.column = 0;
+      } else {
+        self.column += c.len_utf16() as u32;
+      }
+    }
+  }
+}
+
+/// Map the start of the unchanged code and the start of each of its lines
+fn add_unchanged_mappings(
+  builder: &mut SourceMapBuilder,
+  filename: &str,
+  unchanged: &str,
+  mut generated: Position,
+  mut original: Position,
+) {
+  if unchanged.is_empty() {
+    return;
+  }
+
+  builder.add(
+    generated.line,
+    generated.column,
+    original.line,
+    original.column,
+    Some(filename),
+    None,
+    false,
+  );
+
+  let mut lines = unchanged.split('\n');
+  let first = lines.next().unwrap_or_default();
+  generated.advance(first);
+  original.advance(first);
+
+  for line in lines {
+    generated.advance("\n");
+    original.advance("\n");
+
+    if !line.is_empty() {
+      builder.add(
+        generated.line,
+        generated.column,
+        original.line,
+        original.column,
+        Some(filename),
+        None,
+        false,
+      );
+    }
+
+    generated.advance(line);
+    original.advance(line);
+  }
+}
+
+/// Byte offset of the utf-16 `index` of `code`, e.g. the indexes of js strings. [None] if it's out of range or inside a character
+pub fn utf16_to_byte_offset(code: &str, index: usize) -> Option<usize> {
+  let mut utf16_index = 0;
+
+  for (offset, c) in code.char_indices() {
+    if utf16_index == index {
+      return Some(offset);
+    }
+    if utf16_index > index {
+      return None;
+    }
+
+    utf16_index += c.len_utf16();
+  }
+
+  (utf16_index == index).then_some(code.len())
+}
@@ -0,0 +1,44 @@
+use farmfe_toolkit::source_editor::{utf16_to_byte_offset, SourceEditor};
+
+#[test]
+fn edit_and_generate_map() {
+  let code = "import a from './a';\nconsole.log(a);\n";
+  let mut editor = SourceEditor::new(code, "index.ts");
+  assert!(!editor.has_changed());
+
+  editor.prepend("// header\n");
+  editor.overwrite(14, 19, "'./b'").unwrap();
+  editor.insert(21, "debugger;\n").unwrap();
+  editor.remove(33, 34).unwrap();
+  editor.append("export {};\n");
+
+  // overlapping replacements are rejected
+  assert!(editor.overwrite(15, 25, "x").is_err());
+  assert!(editor.insert(100, "x").is_err());
+
+  let (generated, map) = editor.generate();
+  let expected = "// header\nimport a from './b';\ndebugger;\nconsole.log();\nexport {};\n";
+  assert_eq!(generated, expected);
+  assert_eq!(editor.to_string(), expected);
+
+  // `console` is mapped back to the second line of the original code
+  let token = map.lookup_token(3, 0).unwrap();
+  assert_eq!(token.get_src(), (1, 0));
+  assert_eq!(token.get_source(), Some("index.ts"));
+  // the replaced source is mapped to the start of the replaced range
+  let token = map.lookup_token(1, 14).unwrap();
+  assert_eq!(token.get_src(), (0, 14));
+  assert_eq!(map.get_source_contents(0), Some(code));
+}
+
+#[test]
+fn convert_utf16_offsets() {
+  let code = "const s = '😀';";
+  assert_eq!(utf16_to_byte_offset(code, 11), Some(11));
+  // after the surrogate pair
+  assert_eq!(utf16_to_byte_offset(code, 13), Some(15));
+  // inside the surrogate pair
+  assert_eq!(utf16_to_byte_offset(code, 12), None);
+  assert_eq!(utf16_to_byte_offset(code, 15), Some(code.len()));
+  assert_eq!(utf16_to_byte_offset(code, 16), None);
+}
@@ -1,5 +1,8 @@
+use std::io::Read;
+
 use base64::{engine::general_purpose, Engine};
 use sha2::{Digest, Sha256};
+use xxhash_rust::xxh3::{xxh3_128, Xxh3};
 
 pub fn sha256(bytes: &[u8], len: usize) -> String {
   let mut hasher = Sha256::new();
@@ -11,6 +14,54 @@ pub fn sha256(bytes: &[u8], len: usize) -> String {
   format!("{hash:x}")[..len].to_string()
 }
 
+/// Same as [sha256] but the content is read in chunks, used for large files that should not be loaded into memory
+pub fn sha256_reader<R: Read>(reader: R, len: usize) -> std::io::Result<String> {
+  let mut hasher = Sha256::new();
+  read_chunks(reader, |chunk| hasher.update(chunk))?;
+  let hash = hasher.finalize();
+
+  Ok(format!("{hash:x}")[..len].to_string())
+}
+
+/// xxh3 is much faster than sha256 but not cryptographic, the max length is 32
+pub fn xxh3(bytes: &[u8], len: usize) -> String {
+  format!("{:032x}", xxh3_128(bytes))[..len].to_string()
+}
+
+pub fn xxh3_reader<R: Read>(reader: R, len: usize) -> std::io::Result<String> {
+  let mut hasher = Xxh3::new();
+  read_chunks(reader, |chunk| hasher.update(chunk))?;
+
+  Ok(format!("{:032x}", hasher.digest128())[..len].to_string())
+}
+
+pub fn blake3(bytes: &[u8], len: usize) -> String {
+  blake3::hash(bytes).to_hex()[..len].to_string()
+}
+
+pub fn blake3_reader<R: Read>(reader: R, len: usize) -> std::io::Result<String> {
+  let mut hasher = blake3::Hasher::new();
+  read_chunks(reader, |chunk| {
+    hasher.update(chunk);
+  })?;
+
+  Ok(hasher.finalize().to_hex()[..len].to_string())
+}
+
+fn read_chunks<R: Read, F: FnMut(&[u8])>(mut reader: R, mut f: F) -> std::io::Result<()> {
+  let mut buf = [0u8; 64 * 1024];
+
+  loop {
+    let n = reader.read(&mut buf)?;
+
+    if n == 0 {
+      return Ok(());
+    }
+
+    f(&buf[..n]);
+  }
+}
+
 pub fn base64_encode(bytes: &[u8]) -> String {
   general_purpose::STANDARD.encode(bytes)
 }
@@ -26,6 +77,28 @@ mod tests {
     assert_eq!(super::sha256(b"hello world", 8), "b94d27b9".to_string());
   }
 
+  #[test]
+  fn test_sha256_reader() {
+    assert_eq!(
+      super::sha256_reader(&b"hello world"[..], 8).unwrap(),
+      super::sha256(b"hello world", 8)
+    );
+  }
+
+  #[test]
+  fn test_xxh3_and_blake3() {
+    assert_eq!(super::xxh3(b"hello world", 32).len(), 32);
+    assert_eq!(
+      super::xxh3_reader(&b"hello world"[..], 16).unwrap(),
+      super::xxh3(b"hello world", 16)
+    );
+    assert_eq!(super::blake3(b"hello world", 8), "d74981ef".to_string());
+    assert_eq!(
+      super::blake3_reader(&b"hello world"[..], 8).unwrap(),
+      super::blake3(b"hello world", 8)
+    );
+  }
+
   #[test]
   fn test_base64_encode() {
     assert_eq!(super::base64_encode(b"hello world"), "aGVsbG8gd29ybGQ=");
@@ -13,6 +13,10 @@ export function getOptionFromBuildOption(
     minify,
     sourcemap,
     treeShaking,
+    graph,
+    graphFilter,
+    graphDepth,
+    json,
     mode
   } = options;
 
@@ -33,7 +37,15 @@ export function getOptionFromBuildOption(
 
   const defaultOptions: FarmCLIOptions & UserConfig = {
     compilation,
-    ...(mode && { mode })
+    ...(mode && { mode }),
+    ...(graph && {
+      graph: {
+        output: graph,
+        pattern: graphFilter && String(graphFilter),
+        depth: graphDepth === undefined ? undefined : Number(graphDepth)
+      }
+    }),
+    ...(json && { diagnosticsJson: json })
   };
 
   return defaultOptions;
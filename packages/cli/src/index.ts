@@ -15,6 +15,7 @@ import type {
   FarmCLIPreviewOptions,
   FarmCLIServerOptions,
   GlobalFarmCLIOptions,
+  ICacheOptions,
   ICleanOptions
 } from './types.js';
 
@@ -86,6 +87,19 @@ cli
   .option('--sourcemap', 'output source maps for build')
   .option('--treeShaking', 'Eliminate useless code without side effects')
   .option('--minify', 'code compression at build time')
+  .option(
+    '--graph <file>',
+    'write the dependency diagram of the modules, .dot files in Graphviz DOT and others in Mermaid'
+  )
+  .option(
+    '--graph-filter <pattern>',
+    'regex of the module ids the dependency diagram starts from'
+  )
+  .option(
+    '--graph-depth <depth>',
+    'levels of the dependencies of the matched modules in the dependency diagram'
+  )
+  .option('--json <file>', 'write all the diagnostics of the build as JSON')
   .action(
     async (
       rootPath: string,
@@ -168,11 +182,25 @@ cli
     '--recursive',
     'Recursively search for node_modules directories and clean them'
   )
+  .option(
+    '--global',
+    'Remove the least recently used packages of the global cache shared by projects'
+  )
+  .option('--global-cache-dir <dir>', 'Directory of the global cache')
+  .option(
+    '--max-size <bytes>',
+    'Max size of the global cache after cleaning, 0 to remove all packages'
+  )
   .action(async (rootPath: string, options: ICleanOptions) => {
     const { root } = resolveCliConfig(rootPath, options);
-    const { clean } = await resolveCore();
+    const { clean, cleanGlobalCache } = await resolveCore();
 
     try {
+      if (options?.global) {
+        cleanGlobalCache(options.globalCacheDir, options.maxSize);
+        return;
+      }
+
       await clean(root, options?.recursive);
     } catch (e) {
       const { Logger } = await import('@farmfe/core');
@@ -182,6 +210,56 @@ cli
     }
   });
 
+cli
+  .command(
+    'cache <action> [root]',
+    'Manage the persistent cache for CI: warm, export or import'
+  )
+  .option(
+    '--archive <file>',
+    'archive file the cache is exported to or imported from',
+    { default: 'farm-cache.archive' }
+  )
+  .action(
+    async (
+      action: string,
+      rootPath: string,
+      options: ICacheOptions & GlobalFarmCLIOptions
+    ) => {
+      const { root, configPath } = resolveCliConfig(rootPath, options);
+      const defaultOptions = { root, configPath };
+      const { warmCache, exportCache, importCache } = await resolveCore();
+
+      switch (action) {
+        case 'warm':
+          handleAsyncOperationErrors(
+            warmCache(defaultOptions),
+            'Failed to warm the cache'
+          );
+          break;
+        case 'export':
+          handleAsyncOperationErrors(
+            exportCache(options.archive, defaultOptions),
+            'Failed to export the cache'
+          );
+          break;
+        case 'import':
+          handleAsyncOperationErrors(
+            importCache(options.archive, defaultOptions),
+            'Failed to import the cache'
+          );
+          break;
+        default: {
+          const { Logger } = await import('@farmfe/core');
+          new Logger().error(
+            `Unknown cache action ${action}, expected warm, export or import`
+          );
+          process.exit(1);
+        }
+      }
+    }
+  );
+
 // Listening for unknown command
 cli.on('command:*', async () => {
   const { Logger } = await import('@farmfe/core');
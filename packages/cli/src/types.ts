@@ -18,6 +18,13 @@ export interface GlobalFarmCLIOptions {
 export interface ICleanOptions {
   path?: string;
   recursive?: boolean;
+  global?: boolean;
+  globalCacheDir?: string;
+  maxSize?: number;
+}
+
+export interface ICacheOptions {
+  archive?: string;
 }
 
 export interface FarmCLIServerOptions {
@@ -34,6 +41,10 @@ export interface FarmCLIBuildOptions {
   sourcemap?: boolean;
   minify?: boolean;
   treeShaking?: boolean;
+  graph?: string;
+  graphFilter?: string;
+  graphDepth?: number;
+  json?: string;
   format?: 'cjs' | 'esm';
   target?:
     | 'browser'
@@ -2,7 +2,16 @@ import { existsSync, mkdirSync, readFileSync, writeFileSync } from 'node:fs';
 import path from 'node:path';
 import readline from 'node:readline';
 import { fileURLToPath } from 'node:url';
-import type { build, clean, preview, start, watch } from '@farmfe/core';
+import type {
+  build,
+  clean,
+  exportCache,
+  importCache,
+  preview,
+  start,
+  warmCache,
+  watch
+} from '@farmfe/core';
 import { Logger } from '@farmfe/core';
 import spawn from 'cross-spawn';
 import walkdir from 'walkdir';
@@ -27,6 +36,9 @@ export async function resolveCore(): Promise<{
   watch: typeof watch;
   preview: typeof preview;
   clean: typeof clean;
+  warmCache: typeof warmCache;
+  exportCache: typeof exportCache;
+  importCache: typeof importCache;
 }> {
   try {
     return import('@farmfe/core');
@@ -310,7 +310,10 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { JsPluginTransformHtmlHookOrder, Compiler } = nativeBinding
+const { JsPluginTransformHtmlHookOrder, Compiler, gcGlobalCache, exportCache, importCache } = nativeBinding
 
 module.exports.JsPluginTransformHtmlHookOrder = JsPluginTransformHtmlHookOrder
 module.exports.Compiler = Compiler
+module.exports.gcGlobalCache = gcGlobalCache
+module.exports.exportCache = exportCache
+module.exports.importCache = importCache
@@ -32,6 +32,10 @@ export interface JsPluginProcessModuleHookFilters {
   moduleTypes: Array<string>
   resolvedPaths: Array<string>
 }
+/** The hooks are only called for the modules of these custom module systems */
+export interface JsPluginModuleSystemHookFilters {
+  moduleSystems: Array<string>
+}
 export interface WatchDiffResult {
   add: Array<string>
   remove: Array<string>
@@ -54,10 +58,38 @@ export interface JsUpdateResult {
   removed: Array<string>
   immutableModules: string
   mutableModules: string
+  cssUpdates: Record<string, string>
   boundaries: Record<string, Array<Array<string>>>
+  acceptedDeps: Record<string, Array<string>>
+  hmrFullReload?: JsHmrFullReload
   dynamicResourcesMap?: Record<string, Array<Array<string>>>
   extraWatchResult: WatchDiffResult
+  removedResources: Array<string>
+  updatedHtmlResources: Array<string>
+  updatedWorkers: Array<string>
+  fullRebuildReason?: string
+}
+/** see [farmfe_core::plugin::HmrFullReload] */
+export interface JsHmrFullReload {
+  module: string
+  path: Array<string>
+  /** `entry` or `nonScriptImporter` */
+  reason: string
+}
+export interface JsGlobalCacheGcResult {
+  removed: number
+  freedBytes: number
 }
+/** Remove the least recently used packages of the global cache until it's not larger than `max_size` */
+export function gcGlobalCache(dir?: string | undefined | null, maxSize?: number | undefined | null): JsGlobalCacheGcResult
+export interface JsCacheArchiveResult {
+  files: number
+  bytes: number
+}
+/** Pack the persistent cache under `cache_dir` into a portable archive file */
+export function exportCache(cacheDir: string, archive: string): JsCacheArchiveResult
+/** Merge the persistent cache packed by `export_cache` into `cache_dir`, the existing cache entries are kept */
+export function importCache(archive: string, cacheDir: string): JsCacheArchiveResult
 export type JsCompiler = Compiler
 export declare class Compiler {
   constructor(config: object)
@@ -65,18 +97,77 @@ export declare class Compiler {
   traceModuleGraph(): object
   /** async compile, return promise */
   compile(): object
+  /** async compile of the subgraph reachable from `input` (entry name -> source), return promise */
+  compileSubset(input: Record<string, string>): object
   /** sync compile */
   compileSync(): void
+  /**
+   * Call `callback` with the progress of the builds, see [farmfe_core::context::progress::BuildProgress].
+   * The callback doesn't keep the process alive
+   */
+  onProgress(callback: (...args: any[]) => any): void
+  /**
+   * Call `callback` with the custom events sent by the plugins to the hmr clients, see [farmfe_core::context::hmr_channel::HmrChannel].
+   * The callback doesn't keep the process alive
+   */
+  onHmrEvent(callback: (...args: any[]) => any): void
+  /** Pass a custom event sent by a hmr client to the `hmr_event` hook of the plugins */
+  hmrEvent(event: string, data: unknown): void
   /** TODO: usage example */
-  update(paths: Array<string>, callback: (...args: any[]) => any, sync: boolean, generateUpdateResource: boolean): object
+  update(paths: Array<string>, callback: (...args: any[]) => any, sync: boolean, generateUpdateResource: boolean, priority?: string | undefined | null): object
   addWatchFiles(root: string, paths: Array<string>): void
   hasModule(resolvedPath: string): boolean
   getParentFiles(resolvedPath: string): Array<string>
-  resources(): Record<string, Buffer>
+  /**
+   * Resources served by the dev server, or written to disk when `for_disk` is true.
+   * Dev server only resources are excluded when writing to disk
+   */
+  resources(forDisk?: boolean | undefined | null): Record<string, Buffer>
   resourcesMap(): Record<string, unknown>
+  /** Large resources that are not loaded into memory, returns resource name -> source path */
+  streamedResources(): Record<string, string>
   watchModules(): Array<string>
   relativeModulePaths(): Array<string>
   resource(name: string): Buffer | null
+  resourceSourcePath(name: string): string | null
   stats(): string
-  invalidateModule(moduleId: string): void
+  /** Json report of the generated resources, null if `bundleStats` is not configured */
+  bundleStats(): string | null
+  /** Json array of the diagnostics reported by the analysis passes, e.g. circular dependencies */
+  diagnostics(): string
+  /** Readable report of the diagnostics grouped by module and plugin, at most `max_diagnostics` of them are rendered */
+  diagnosticsReport(maxDiagnostics: number): string
+  /** Counters and histograms of the builds and hmr updates in the Prometheus text format */
+  metrics(): string
+  /** Json array of the shortest import chains from the entries to the module */
+  importChains(moduleId: string): string
+  /** Graphviz DOT diagram of the modules selected by the filter, all the modules reachable from the entries by default */
+  moduleGraphToDot(filter?: JsModuleGraphFilter | undefined | null): string
+  /** Mermaid flowchart of the modules selected by the filter, all the modules reachable from the entries by default */
+  moduleGraphToMermaid(filter?: JsModuleGraphFilter | undefined | null): string
+  /** Json of the changes the update of the paths would apply, the module graph and the resources are not changed */
+  planUpdate(paths: Array<string>): Promise<string>
+  /** Json array of the top level statements of the script module, `null` if it's not a script module */
+  moduleStatements(moduleId: string): string
+  /** Json array of the exported names of the script module, `null` if it's not a script module */
+  moduleExports(moduleId: string): string
+  /** Schedule the module to be recompiled by the next update, like the file is changed */
+  invalidateModule(moduleId: string, options?: JsInvalidateModuleOptions | undefined | null): void
+  /** Resolved paths of the invalidated modules that are not recompiled yet */
+  invalidatedModules(): Array<string>
+  /** The policy that decides when the resources of the updates are regenerated, see [farmfe_core::context::update_schedule::UpdateSchedulePolicy] */
+  updateSchedulePolicy(): unknown
+  setUpdateSchedulePolicy(policy: unknown): void
+  /**
+   * Regenerate the resources of the deferred updates now, returns the number of the regenerated updates.
+   * The resources are flushed before they are read by `resources` and `resource` as well
+   */
+  flushDeferredUpdates(): number
+}
+export interface JsInvalidateModuleOptions {
+  recompileDependents?: boolean
+}
+export interface JsModuleGraphFilter {
+  pattern?: string
+  depth?: number
 }
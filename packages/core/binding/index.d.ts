@@ -1,4 +1,9 @@
 export const bindingPath: string;
 
-import { Compiler } from './binding.js';
-export { Compiler };
\ No newline at end of file
+import {
+  Compiler,
+  gcGlobalCache,
+  exportCache,
+  importCache
+} from './binding.js';
+export { Compiler, gcGlobalCache, exportCache, importCache };
\ No newline at end of file
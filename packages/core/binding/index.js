@@ -8,4 +8,14 @@ process.env.FARM_LIB_CORE_PATH = bindingPath;
 
 const Compiler = binding.Compiler;
 const JsFileWatcher = binding.JsFileWatcher;
-export { Compiler, bindingPath, JsFileWatcher };
+const gcGlobalCache = binding.gcGlobalCache;
+const exportCache = binding.exportCache;
+const importCache = binding.importCache;
+export {
+  Compiler,
+  bindingPath,
+  JsFileWatcher,
+  gcGlobalCache,
+  exportCache,
+  importCache
+};
@@ -239,3 +239,23 @@ declare module '*?inline' {
   const src: string;
   export default src;
 }
+
+declare module '*?meta' {
+  const meta: {
+    src: string;
+    width: number;
+    height: number;
+    format: 'png' | 'jpeg' | 'gif' | 'webp' | 'bmp' | 'ico' | 'svg';
+  };
+  export default meta;
+}
+
+declare module '*.svg?sprite' {
+  const icon: {
+    /** symbol id of the icon in the sprite */
+    id: string;
+    /** sprite url with the symbol id as the fragment, e.g. `/sprite.svg#icon-home` */
+    url: string;
+  };
+  export default icon;
+}
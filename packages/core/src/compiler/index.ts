@@ -1,14 +1,42 @@
-import { existsSync, mkdirSync, rmSync, writeFileSync } from 'node:fs';
+import { createHash } from 'node:crypto';
+import {
+  chmodSync,
+  closeSync,
+  constants,
+  copyFileSync,
+  existsSync,
+  mkdirSync,
+  openSync,
+  readFileSync,
+  readSync,
+  readdirSync,
+  rmSync,
+  writeFileSync
+} from 'node:fs';
 import path from 'node:path';
 import { Compiler as BindingCompiler } from '../../binding/index.js';
 
 import type { Resource } from '../index.js';
+import type {
+  InvalidateModuleOptions,
+  WrittenResource
+} from '../plugin/type.js';
 import type { Config, JsUpdateResult } from '../types/binding.js';
 import { type ILogger, Logger } from '../utils/logger.js';
 
 export const VIRTUAL_FARM_DYNAMIC_IMPORT_SUFFIX =
   '.farm_dynamic_import_virtual_module';
 
+// records the files emitted by the retained builds, relative to output.path
+const RETENTION_MANIFEST = '.farm-retention.json';
+
+// the diagnostics beyond it are only counted in the summary of the report
+const MAX_LOGGED_DIAGNOSTICS = 20;
+
+const VARIANT_VARIABLE_PREFIX = '__FARM_VARIANT_';
+const VARIANT_VARIABLE_REGEX =
+  /__FARM_VARIANT_([A-Za-z0-9]+(?:_[A-Za-z0-9]+)*)__/g;
+
 /**
  * Cause the update process is async, we need to keep the update queue to make sure the update process is executed in order.
  * So the latter update process will not override the previous one if they are updating at the same time.
@@ -16,8 +44,16 @@ export const VIRTUAL_FARM_DYNAMIC_IMPORT_SUFFIX =
 export interface UpdateQueueItem {
   paths: string[];
   resolve: (res: JsUpdateResult) => void;
+  generateUpdateResource: boolean;
+  priority: UpdatePriority;
 }
 
+/**
+ * Interactive updates (hmr updates triggered by editing) are scheduled before background updates (e.g. lazy compilation),
+ * and preempt the running background update, which yields to them at module boundaries.
+ */
+export type UpdatePriority = 'interactive' | 'background';
+
 export interface TracedModuleGraph {
   root: string;
   modules: Array<{
@@ -30,10 +66,186 @@ export interface TracedModuleGraph {
   reverseEdges: Record<string, string[]>;
 }
 
+/**
+ * How a source is resolved, `resolvedBy` is `alias`, `imports`, `exports`, `browser`,
+ * the main field like `module` or `main`, `index` or `path`
+ */
+export interface ResolveTrace {
+  resolvedBy: string;
+  // conditions the `exports` or `imports` field is resolved with
+  conditions: string[];
+}
+
+/**
+ * Report of the generated resources, emitted when `bundleStats` is configured
+ */
+export interface BundleStats {
+  resources: Array<{
+    name: string;
+    resourceType: string;
+    size: number;
+    gzipSize: number;
+    origin: Resource['origin'];
+    modules: Array<{
+      id: string;
+      renderedSize: number;
+      originalSize: number;
+      // byte ranges [start, end) of the module in the resource, empty if the source map is not generated
+      ranges: Array<[number, number]>;
+    }>;
+  }>;
+  edges: Array<{
+    importer: string;
+    dependency: string;
+    source: string;
+    kind: unknown;
+    resolveTrace: ResolveTrace | null;
+    // `name@version` of the package the dependency is resolved from, null if it's not in node_modules
+    package: string | null;
+  }>;
+  // exports of the es modules, empty unless `bundleStats.exports` is enabled
+  exports: Array<{
+    id: string;
+    exports: ModuleExport[];
+  }>;
+}
+
+/**
+ * A problem found by the analysis passes over the module graph, e.g. circular dependencies
+ */
+export interface Diagnostic {
+  code: string;
+  severity: 'warning' | 'error';
+  message: string;
+  // related module ids, e.g. the modules of the import cycle
+  modules: string[];
+  // name of the plugin that reported the diagnostic
+  plugin: string | null;
+}
+
+/**
+ * A step of an import chain returned by `importChains`, `from` imports `to`
+ */
+export interface ImportChainStep {
+  from: string;
+  to: string;
+  kinds: unknown[];
+  treeShaken: boolean;
+  resolveTraces: Array<ResolveTrace | null>;
+  // `name@version` of the package `to` is resolved from, null if it's not in node_modules
+  package: string | null;
+}
+
+/**
+ * Modules of the diagrams returned by `moduleGraphToDot` and `moduleGraphToMermaid`
+ */
+export interface ModuleGraphFilter {
+  // regex of the module ids the diagram starts from, the entries by default
+  pattern?: string;
+  // levels of the dependencies of the matched modules, all the reachable modules by default
+  depth?: number;
+}
+
+/**
+ * The changes `update` would apply for the same paths, returned by `planUpdate`
+ */
+export interface UpdatePlan {
+  updatedModuleIds: string[];
+  diffResult: {
+    depsChanges: [string, { added: unknown[]; removed: unknown[] }][];
+    addedModules: string[];
+    removedModules: string[];
+  };
+  affectedModuleGroups: string[];
+  // the resource pots that would be regenerated
+  affectedResourcePots: string[];
+  // why the update would be escalated to a full compilation, see `adaptiveRebuild`
+  fullRebuildReason: string | null;
+}
+
+/**
+ * A top level statement of a script module returned by `moduleStatements`
+ */
+export interface ModuleStatement {
+  // index of the statement in the body of the module
+  index: number;
+  // source of `import ... from 'x'` or `export ... from 'x'`
+  source: string | null;
+  imported: string[];
+  // `default` for the default export and `*` for `export * from 'x'`
+  exported: string[];
+  defined: string[];
+  // indexes of the statements that define the top level names used by this statement
+  dependencies: number[];
+  sideEffects:
+    | 'noSideEffects'
+    | 'writeTopLevelVar'
+    | 'readTopLevelVar'
+    | 'writeOrCallGlobalVar'
+    | 'unclassifiedSelfExecuted';
+}
+
+/**
+ * A name exported by a script module returned by `moduleExports`
+ */
+export interface ModuleExport {
+  // `default` for the default export and `*` for `export * from 'x'`
+  name: string;
+  // the exported local name, or the imported name of `export { a as b } from 'x'`
+  local: string | null;
+  // source of the re-export
+  source: string | null;
+  // index of the exporting statement
+  statement: number;
+}
+
+/**
+ * Progress of a build reported to the `onProgress` callbacks
+ */
+export interface BuildProgress {
+  phase: 'build' | 'generate' | 'write' | 'finished';
+  modulesResolved: number;
+  modulesLoaded: number;
+  modulesTransformed: number;
+  // modules restored from the persistent cache
+  modulesCached: number;
+  // number of the resource pots to render, known after partial bundling
+  resourcePots: number;
+  resourcePotsRendered: number;
+  resourcesWritten: number;
+}
+
+/**
+ * When the resources of an update are regenerated:
+ * - `deferred`: when they are read, before the next update or when `flushDeferredUpdates` is called
+ * - `async`: in a background thread, the update returns without waiting for them
+ * - `sync`: before the update returns
+ */
+export type UpdateSchedule = 'deferred' | 'async' | 'sync';
+
+/**
+ * The schedule of each kind of update trigger, the most urgent schedule of the triggers of an update is used
+ */
+export interface UpdateSchedulePolicy {
+  // the changed modules of interactive updates
+  moduleChange: UpdateSchedule;
+  // the changed modules of background updates, e.g. lazy compilation
+  background: UpdateSchedule;
+  // the update creates new module groups, e.g. a new dynamic import
+  newModuleGroup: UpdateSchedule;
+  // the injected resources of html entries may change
+  htmlEntry: UpdateSchedule;
+  // the update affects workers
+  worker: UpdateSchedule;
+}
+
 export class Compiler {
   private _bindingCompiler: BindingCompiler;
   private _updateQueue: UpdateQueueItem[] = [];
   private _onUpdateFinishQueue: (() => void | Promise<void>)[] = [];
+  // priority of the running queued update
+  private _runningUpdatePriority: UpdatePriority | undefined;
+  private _preempting = false;
 
   public compiling = false;
 
@@ -66,6 +278,24 @@ export class Compiler {
       await this._bindingCompiler.compile();
     }
     this.compiling = false;
+    this.logDiagnostics();
+  }
+
+  /**
+   * Compile only the modules reachable from `input` (entry name -> source) instead of `config.input`,
+   * e.g. to preview a single component of a huge project
+   */
+  async compileSubset(input: Record<string, string>) {
+    if (this.compiling) {
+      this.logger.error('Already compiling', {
+        exit: true
+      });
+    }
+
+    this.compiling = true;
+    await this._bindingCompiler.compileSubset(input);
+    this.compiling = false;
+    this.logDiagnostics();
   }
 
   compileSync() {
@@ -77,14 +307,61 @@ export class Compiler {
     this.compiling = true;
     this._bindingCompiler.compileSync();
     this.compiling = false;
+    this.logDiagnostics();
+  }
+
+  /**
+   * Call `callback` with the progress of the builds, the calls are throttled except the phase changes
+   */
+  onProgress(callback: (progress: BuildProgress) => void) {
+    this._bindingCompiler.onProgress(callback);
+  }
+
+  /**
+   * Call `callback` with the custom events sent by the plugins to the hmr clients
+   */
+  onHmrEvent(callback: (event: { event: string; data: unknown }) => void) {
+    this._bindingCompiler.onHmrEvent(callback);
+  }
+
+  /**
+   * Pass a custom event sent by a hmr client with `import.meta.hot.send` to the rust plugins
+   */
+  hmrEvent(event: string, data: unknown) {
+    this._bindingCompiler.hmrEvent(event, data ?? null);
+  }
+
+  // errors fail the compilation, only the warnings are logged
+  private logDiagnostics() {
+    const diagnostics = this.diagnostics();
+
+    if (diagnostics.some((diagnostic) => diagnostic.severity === 'warning')) {
+      this.logger.warn(this.diagnosticsReport());
+    }
+  }
+
+  /**
+   * Whether an interactive update can start now by preempting the running background update
+   */
+  get canPreemptUpdate() {
+    return this._runningUpdatePriority === 'background' && !this._preempting;
   }
 
   async update(
     paths: string[],
     sync = false,
     ignoreCompilingCheck = false,
-    generateUpdateResource = true
+    generateUpdateResource = true,
+    priority: UpdatePriority = 'interactive'
   ): Promise<JsUpdateResult> {
+    if (
+      priority === 'interactive' &&
+      !ignoreCompilingCheck &&
+      this.canPreemptUpdate
+    ) {
+      return this.preemptUpdate(paths, sync, generateUpdateResource);
+    }
+
     let resolve: (res: JsUpdateResult) => void;
 
     const promise = new Promise<JsUpdateResult>((r) => {
@@ -93,10 +370,11 @@ export class Compiler {
 
     // if there is already a update process, we need to wait for it to finish
     if (this.compiling && !ignoreCompilingCheck) {
-      this._updateQueue.push({ paths, resolve });
+      this.enqueueUpdate({ paths, resolve, generateUpdateResource, priority });
       return promise;
     }
     this.compiling = true;
+    this._runningUpdatePriority = priority;
     try {
       const res = await this._bindingCompiler.update(
         paths,
@@ -108,10 +386,12 @@ export class Compiler {
               next.paths,
               true,
               true,
-              generateUpdateResource
+              next.generateUpdateResource,
+              next.priority
             ).then(next.resolve);
           } else {
             this.compiling = false;
+            this._runningUpdatePriority = undefined;
             while (this._onUpdateFinishQueue.length) {
               if (this.compiling) {
                 break;
@@ -122,16 +402,57 @@ export class Compiler {
           }
         },
         sync,
-        generateUpdateResource
+        generateUpdateResource,
+        priority
       );
 
       return res as JsUpdateResult;
     } catch (e) {
       this.compiling = false;
+      this._runningUpdatePriority = undefined;
       throw e;
     }
   }
 
+  /**
+   * Send the interactive update to the compiler while a background update is running instead of queueing it.
+   * The compiler runs one update at a time: the background update yields to it until its modules are built, which is the safe point
+   * where the background update waits for the interactive one. The queued updates are handled when the background update finishes
+   */
+  private async preemptUpdate(
+    paths: string[],
+    sync: boolean,
+    generateUpdateResource: boolean
+  ): Promise<JsUpdateResult> {
+    this._preempting = true;
+
+    try {
+      const res = await this._bindingCompiler.update(
+        paths,
+        () => {},
+        sync,
+        generateUpdateResource,
+        'interactive'
+      );
+
+      return res as JsUpdateResult;
+    } finally {
+      this._preempting = false;
+    }
+  }
+
+  private enqueueUpdate(item: UpdateQueueItem) {
+    const firstBackground = this._updateQueue.findIndex(
+      ({ priority }) => priority === 'background'
+    );
+
+    if (item.priority === 'interactive' && firstBackground !== -1) {
+      this._updateQueue.splice(firstBackground, 0, item);
+    } else {
+      this._updateQueue.push(item);
+    }
+  }
+
   hasModule(resolvedPath: string): boolean {
     return this._bindingCompiler.hasModule(resolvedPath);
   }
@@ -140,39 +461,125 @@ export class Compiler {
     return this._bindingCompiler.getParentFiles(idOrResolvedPath);
   }
 
-  resources(): Record<string, Buffer> {
-    return this._bindingCompiler.resources();
+  resources(forDisk = false): Record<string, Buffer> {
+    return this._bindingCompiler.resources(forDisk);
   }
 
   resource(path: string): Buffer {
     return this._bindingCompiler.resource(path);
   }
 
+  streamedResources(): Record<string, string> {
+    return this._bindingCompiler.streamedResources();
+  }
+
+  resourceSourcePath(path: string): string | null {
+    return this._bindingCompiler.resourceSourcePath(path);
+  }
+
   resourcesMap(): Record<string, Resource> {
     return this._bindingCompiler.resourcesMap() as Record<string, Resource>;
   }
 
   writeResourcesToDisk(): void {
-    const resources = this.resources();
-    const configOutputPath = this.config.config.output.path;
-    const outputPath = path.isAbsolute(configOutputPath)
-      ? configOutputPath
-      : path.join(this.config.config.root, configOutputPath);
+    // dev server only resources are never written to disk
+    const { resources, assetTargets } = takeAssetTargetsManifest(
+      this.resources(true)
+    );
+    const outputPath = this.outputPath();
+    const variants = this.config.config.output.variants ?? [];
+    const defaultAssetTarget = this.config.config.assets?.targets?.[0];
+
+    if (!variants.length) {
+      const written = assetTargets
+        ? this.writeResourcesToDir(
+            outputPath,
+            resourcesOfAssetTarget(resources, assetTargets, defaultAssetTarget),
+            (bytes) =>
+              substituteVariantVariables(
+                bytes,
+                assetTargetVariables(assetTargets, defaultAssetTarget)
+              )
+          )
+        : this.writeResourcesToDir(outputPath, resources);
+
+      this.recordRetainedBuild(outputPath, written);
+      this.callWriteResourcesHook();
+      this.callResourcesWrittenHook(outputPath, written);
+      return;
+    }
 
-    for (const [name, resource] of Object.entries(resources)) {
+    // all variants share the same compiled resources, only the placeholders are substituted when writing
+    const writtenVariants = variants.map((variant) => {
+      const variantOutputPath = variant.path
+        ? path.resolve(this.config.config.root, variant.path)
+        : path.join(outputPath, variant.name);
+      const assetTarget = variant.assetTarget ?? defaultAssetTarget;
+      const variables = {
+        ...assetTargetVariables(assetTargets, assetTarget),
+        ...variant.variables
+      };
+      const written = this.writeResourcesToDir(
+        variantOutputPath,
+        resourcesOfAssetTarget(resources, assetTargets, assetTarget),
+        (bytes) => substituteVariantVariables(bytes, variables)
+      );
+
+      return [variantOutputPath, written] as const;
+    });
+
+    this.recordRetainedBuild(
+      outputPath,
+      writtenVariants.flatMap(([, written]) => written)
+    );
+    this.callWriteResourcesHook();
+
+    for (const [variantOutputPath, written] of writtenVariants) {
+      this.callResourcesWrittenHook(variantOutputPath, written);
+    }
+  }
+
+  private writeResourcesToDir(
+    outputPath: string,
+    resources: Record<string, Buffer>,
+    transform: (bytes: Buffer) => Buffer = (bytes) => bytes
+  ): Array<[name: string, filePath: string, bytes?: Buffer]> {
+    const getFilePath = (name: string) => {
       // remove query params and hash of name
       const nameWithoutQuery = name.split('?')[0];
       const nameWithoutHash = nameWithoutQuery.split('#')[0];
 
-      let filePath = path.join(outputPath, nameWithoutHash);
+      const filePath = path.join(outputPath, nameWithoutHash);
       if (!existsSync(path.dirname(filePath))) {
         mkdirSync(path.dirname(filePath), { recursive: true });
       }
 
-      writeFileSync(filePath, resource);
+      return filePath;
+    };
+
+    const written: Array<[name: string, filePath: string, bytes?: Buffer]> =
+      [];
+
+    for (const [name, resource] of Object.entries(resources)) {
+      const filePath = getFilePath(name);
+      const bytes = transform(resource);
+      writeFileSync(filePath, bytes);
+
+      // executables of cli tools, see `output.shebang`
+      if (bytes[0] === 0x23 && bytes[1] === 0x21) {
+        chmodSync(filePath, 0o755);
+      }
+      written.push([name, filePath, bytes]);
+    }
+
+    // large assets are copied from the source file, use copy-on-write when the file system supports it
+    for (const [name, sourcePath] of Object.entries(this.streamedResources())) {
+      const filePath = getFilePath(name);
+      copyFileSync(sourcePath, filePath, constants.COPYFILE_FICLONE);
+      written.push([name, filePath]);
     }
 
-    this.callWriteResourcesHook();
+    return written;
   }
 
   callWriteResourcesHook() {
@@ -187,11 +594,91 @@ export class Compiler {
     }
   }
 
+  callResourcesWrittenHook(
+    outputPath: string,
+    written: Array<[name: string, filePath: string, bytes?: Buffer]>
+  ) {
+    const plugins = (this.config.jsPlugins ?? []).filter(
+      (plugin) => plugin.resourcesWritten?.executor
+    );
+
+    // digests are only computed when they are used
+    if (!plugins.length) {
+      return;
+    }
+
+    const resources: WrittenResource[] = written
+      .sort(([a], [b]) => (a < b ? -1 : a > b ? 1 : 0))
+      .map(([name, filePath, bytes]) => ({
+        name,
+        path: filePath,
+        ...(bytes ? digestBytes(bytes) : digestFile(filePath))
+      }));
+
+    for (const plugin of plugins) {
+      plugin.resourcesWritten.executor({ outputPath, resources });
+    }
+  }
+
   removeOutputPathDir() {
     const outputPath = this.outputPath();
-    if (existsSync(outputPath)) {
+    if (!existsSync(outputPath)) {
+      return;
+    }
+
+    const retained = this.retainedBuilds(outputPath).flat();
+
+    if (!retained.length) {
       rmSync(outputPath, { recursive: true });
+      return;
     }
+
+    // files of the retained builds are kept, so pages of the previous deploys can still load their lazy chunks
+    removeStaleFiles(
+      outputPath,
+      new Set([...retained, RETENTION_MANIFEST].map((p) => path.normalize(p)))
+    );
+  }
+
+  /**
+   * Files emitted by the previous builds that are retained, the latest build comes last
+   */
+  private retainedBuilds(outputPath: string): string[][] {
+    const manifestPath = path.join(outputPath, RETENTION_MANIFEST);
+
+    if (!this.config.config.output.retainBuilds || !existsSync(manifestPath)) {
+      return [];
+    }
+
+    try {
+      const { builds } = JSON.parse(readFileSync(manifestPath, 'utf-8'));
+      return Array.isArray(builds) ? builds : [];
+    } catch {
+      return [];
+    }
+  }
+
+  private recordRetainedBuild(
+    outputPath: string,
+    written: Array<[name: string, filePath: string, bytes?: Buffer]>
+  ) {
+    const retainBuilds = this.config.config.output.retainBuilds;
+
+    if (!retainBuilds) {
+      return;
+    }
+
+    const files = written
+      .map(([, filePath]) => path.relative(outputPath, filePath))
+      .filter((file) => !file.startsWith('..') && !path.isAbsolute(file));
+    const builds = [...this.retainedBuilds(outputPath), files].slice(
+      -retainBuilds
+    );
+
+    writeFileSync(
+      path.join(outputPath, RETENTION_MANIFEST),
+      JSON.stringify({ builds }, null, 2)
+    );
   }
 
   resolvedWatchPaths(): string[] {
@@ -241,7 +728,221 @@ export class Compiler {
     return this._bindingCompiler.stats();
   }
 
-  invalidateModule(moduleId: string) {
-    this._bindingCompiler.invalidateModule(moduleId);
+  bundleStats(): BundleStats | null {
+    const bundleStats = this._bindingCompiler.bundleStats();
+    return bundleStats ? JSON.parse(bundleStats) : null;
+  }
+
+  diagnostics(): Diagnostic[] {
+    return JSON.parse(this._bindingCompiler.diagnostics());
+  }
+
+  /**
+   * Readable report of the diagnostics grouped by module and plugin, the modules with errors come first
+   */
+  diagnosticsReport(maxDiagnostics = MAX_LOGGED_DIAGNOSTICS): string {
+    return this._bindingCompiler.diagnosticsReport(maxDiagnostics);
+  }
+
+  /**
+   * Counters and histograms of the builds and hmr updates in the Prometheus text format
+   */
+  metrics(): string {
+    return this._bindingCompiler.metrics();
   }
+
+  importChains(moduleId: string): ImportChainStep[][] {
+    return JSON.parse(this._bindingCompiler.importChains(moduleId));
+  }
+
+  moduleGraphToDot(filter?: ModuleGraphFilter): string {
+    return this._bindingCompiler.moduleGraphToDot(filter);
+  }
+
+  moduleGraphToMermaid(filter?: ModuleGraphFilter): string {
+    return this._bindingCompiler.moduleGraphToMermaid(filter);
+  }
+
+  async planUpdate(paths: string[]): Promise<UpdatePlan> {
+    return JSON.parse(await this._bindingCompiler.planUpdate(paths));
+  }
+
+  moduleStatements(moduleId: string): ModuleStatement[] | null {
+    return JSON.parse(this._bindingCompiler.moduleStatements(moduleId));
+  }
+
+  moduleExports(moduleId: string): ModuleExport[] | null {
+    return JSON.parse(this._bindingCompiler.moduleExports(moduleId));
+  }
+
+  invalidateModule(moduleId: string, options?: InvalidateModuleOptions) {
+    this._bindingCompiler.invalidateModule(moduleId, options);
+  }
+
+  invalidatedModules() {
+    return this._bindingCompiler.invalidatedModules();
+  }
+
+  updateSchedulePolicy(): UpdateSchedulePolicy {
+    return this._bindingCompiler.updateSchedulePolicy() as UpdateSchedulePolicy;
+  }
+
+  setUpdateSchedulePolicy(policy: Partial<UpdateSchedulePolicy>) {
+    this._bindingCompiler.setUpdateSchedulePolicy({
+      ...this.updateSchedulePolicy(),
+      ...policy
+    });
+  }
+
+  /**
+   * Regenerate the resources of the deferred updates now, returns the number of the regenerated updates
+   */
+  flushDeferredUpdates(): number {
+    return this._bindingCompiler.flushDeferredUpdates();
+  }
+}
+
+// placeholder key -> target -> name of the asset emitted for the target, see `assets.targets`
+const ASSET_TARGETS_MANIFEST = 'asset-targets.json';
+
+type AssetTargetsManifest = Record<string, Record<string, string>>;
+
+/**
+ * Remove the manifest of the asset targets from the resources, it's only used when writing
+ */
+function takeAssetTargetsManifest(resources: Record<string, Buffer>): {
+  resources: Record<string, Buffer>;
+  assetTargets?: AssetTargetsManifest;
+} {
+  const manifest = resources[ASSET_TARGETS_MANIFEST];
+
+  if (!manifest) {
+    return { resources };
+  }
+
+  return {
+    resources: Object.fromEntries(
+      Object.entries(resources).filter(
+        ([name]) => name !== ASSET_TARGETS_MANIFEST
+      )
+    ),
+    assetTargets: JSON.parse(manifest.toString())
+  };
+}
+
+/**
+ * The placeholders of the assets are replaced by the names of the assets of the target
+ */
+function assetTargetVariables(
+  assetTargets: AssetTargetsManifest | undefined,
+  target: string | undefined
+): Record<string, string> {
+  const variables: Record<string, string> = {};
+
+  for (const [key, assets] of Object.entries(assetTargets ?? {})) {
+    if (target && assets[target]) {
+      variables[key] = assets[target];
+    }
+  }
+
+  return variables;
+}
+
+/**
+ * The assets of the other targets are not written
+ */
+function resourcesOfAssetTarget(
+  resources: Record<string, Buffer>,
+  assetTargets: AssetTargetsManifest | undefined,
+  target: string | undefined
+): Record<string, Buffer> {
+  if (!assetTargets) {
+    return resources;
+  }
+
+  const otherTargetAssets = new Set(
+    Object.values(assetTargets).flatMap((assets) =>
+      Object.entries(assets)
+        .filter(([assetTarget]) => assetTarget !== target)
+        .map(([, name]) => name)
+    )
+  );
+
+  return Object.fromEntries(
+    Object.entries(resources).filter(([name]) => !otherTargetAssets.has(name))
+  );
+}
+
+/**
+ * Replace the `__FARM_VARIANT_<KEY>__` placeholders with the values of the variant variables,
+ * placeholders of unknown variables are kept as is.
+ */
+function substituteVariantVariables(
+  bytes: Buffer,
+  variables: Record<string, string>
+): Buffer {
+  if (!bytes.includes(VARIANT_VARIABLE_PREFIX)) {
+    return bytes;
+  }
+
+  const content = bytes
+    .toString()
+    .replace(VARIANT_VARIABLE_REGEX, (placeholder, key) =>
+      Object.prototype.hasOwnProperty.call(variables, key)
+        ? variables[key]
+        : placeholder
+    );
+
+  return Buffer.from(content);
+}
+
+/**
+ * Remove the files under `dir` that are not in `retained`, empty directories are removed too
+ */
+function removeStaleFiles(
+  dir: string,
+  retained: Set<string>,
+  outputPath = dir
+) {
+  for (const entry of readdirSync(dir, { withFileTypes: true })) {
+    const fullPath = path.join(dir, entry.name);
+
+    if (entry.isDirectory()) {
+      removeStaleFiles(fullPath, retained, outputPath);
+
+      if (!readdirSync(fullPath).length) {
+        rmSync(fullPath, { recursive: true });
+      }
+    } else if (!retained.has(path.relative(outputPath, fullPath))) {
+      rmSync(fullPath);
+    }
+  }
+}
+
+function digestBytes(bytes: Buffer): Pick<WrittenResource, 'size' | 'digest'> {
+  return {
+    size: bytes.length,
+    digest: { sha256: createHash('sha256').update(bytes).digest('hex') }
+  };
+}
+
+// large assets are hashed in chunks instead of being loaded into memory
+function digestFile(filePath: string): Pick<WrittenResource, 'size' | 'digest'> {
+  const hash = createHash('sha256');
+  const buffer = Buffer.alloc(64 * 1024);
+  const fd = openSync(filePath, 'r');
+  let size = 0;
+
+  try {
+    let bytesRead: number;
+
+    while ((bytesRead = readSync(fd, buffer, 0, buffer.length, null)) > 0) {
+      hash.update(buffer.subarray(0, bytesRead));
+      size += bytesRead;
+    }
+  } finally {
+    closeSync(fd);
+  }
+
+  return { size, digest: { sha256: hash.digest('hex') } };
 }
@@ -0,0 +1,91 @@
+import { statSync } from 'node:fs';
+import { pathToFileURL } from 'node:url';
+import { Worker } from 'node:worker_threads';
+
+import type { MacroCall, MacroResult } from '../types/binding.js';
+
+/**
+ * Macros are executed in a worker thread, which has its own globals, an empty `process.env` and limited memory,
+ * so a macro can not mutate the state of the compiler process.
+ */
+const WORKER_CODE = `
+const { parentPort } = require('node:worker_threads');
+
+parentPort.on('message', async ({ id, url, exportName, args }) => {
+  try {
+    const mod = await import(url);
+    const fn = mod[exportName];
+
+    if (typeof fn !== 'function') {
+      throw new Error('export "' + exportName + '" of the macro module is not a function');
+    }
+
+    const result = await fn(...args);
+    parentPort.postMessage({
+      id,
+      result: {
+        value: result === undefined ? null : JSON.parse(JSON.stringify(result)),
+        undefined: result === undefined
+      }
+    });
+  } catch (e) {
+    parentPort.postMessage({ id, error: e instanceof Error ? e.stack || e.message : String(e) });
+  }
+});
+`;
+
+interface PendingCall {
+  resolve: (result: MacroResult) => void;
+  reject: (error: Error) => void;
+}
+
+export function createMacroHost() {
+  let worker: Worker | undefined;
+  let nextId = 0;
+  const pending = new Map<number, PendingCall>();
+
+  const getWorker = () => {
+    if (worker) return worker;
+
+    worker = new Worker(WORKER_CODE, {
+      eval: true,
+      env: {},
+      resourceLimits: { maxOldGenerationSizeMb: 256 }
+    });
+    // do not keep the process alive when the compilation is done
+    worker.unref();
+    worker.on('message', ({ id, result, error }) => {
+      const call = pending.get(id);
+      pending.delete(id);
+
+      if (error) {
+        call?.reject(new Error(error));
+      } else {
+        call?.resolve(result);
+      }
+    });
+    worker.on('error', (e) => {
+      for (const call of pending.values()) call.reject(e);
+      pending.clear();
+      worker = undefined;
+    });
+
+    return worker;
+  };
+
+  return (call: MacroCall): Promise<MacroResult> => {
+    // bust the module cache of the worker when the macro module is modified
+    const url = `${pathToFileURL(call.module).href}?t=${statSync(call.module).mtimeMs}`;
+    const id = nextId++;
+
+    return new Promise((resolve, reject) => {
+      pending.set(id, { resolve, reject });
+      getWorker().postMessage({
+        id,
+        url,
+        exportName: call.exportName,
+        args: call.args
+      });
+    });
+  };
+}
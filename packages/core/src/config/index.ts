@@ -48,6 +48,7 @@ import {
 import { normalizePersistentCache } from './normalize-config/normalize-persistent-cache.js';
 import { parseUserConfig } from './schema.js';
 
+import { aliasAdapter } from '../plugin/js/alias-adapter.js';
 import { externalAdapter } from '../plugin/js/external-adapter.js';
 import { convertErrorMessage } from '../utils/error.js';
 import merge from '../utils/merge.js';
@@ -225,7 +226,13 @@ export async function resolveConfig(
   resolvedUserConfig.root = normalizeBasePath(
     resolvedUserConfig.compilation.root
   );
-  resolvedUserConfig.jsPlugins = sortFarmJsPlugins;
+  // function aliases are resolved by a js plugin, they can not be passed to the compiler
+  const aliasAdapterPlugin = aliasAdapter(
+    mergedUserConfig.compilation?.resolve?.alias
+  );
+  resolvedUserConfig.jsPlugins = aliasAdapterPlugin
+    ? [aliasAdapterPlugin, ...sortFarmJsPlugins]
+    : sortFarmJsPlugins;
   resolvedUserConfig.rustPlugins = rustPlugins;
 
   // Temporarily dealing with alias objects and arrays in js will be unified in rust in the future.]
@@ -342,11 +349,15 @@ export async function normalizeUserCompilationConfig(
     // for node target, we should not define process.env.NODE_ENV
     resolvedCompilation.output?.targetEnv === 'node'
       ? {}
-      : Object.keys(resolvedUserConfig.env || {}).reduce((env: any, key) => {
-          env[`$__farm_regex:(global(This)?\\.)?process\\.env\\.${key}`] =
-            JSON.stringify(resolvedUserConfig.env[key]);
-          return env;
-        }, {})
+      : Object.keys(resolvedUserConfig.env || {}).reduce(
+          (env: any, key) => {
+            env[
+              `$__farm_regex:((global(This)?\\.)?process|import\\.meta)\\.env\\.${key}`
+            ] = JSON.stringify(resolvedUserConfig.env[key]);
+            return env;
+          },
+          { 'import.meta.env': JSON.stringify(resolvedUserConfig.env ?? {}) }
+        )
   );
 
   const require = module.createRequire(import.meta.url);
@@ -449,6 +460,18 @@ export async function normalizeUserCompilationConfig(
       resolvedUserConfig.server.hmr.protocol
     );
     resolvedCompilation.define.FARM_HMR_PATH = JSON.stringify(defineHmrPath);
+    resolvedCompilation.define.FARM_HMR_EXECUTION_TRACE = String(
+      !!resolvedUserConfig.server.hmr.executionTrace
+    );
+    resolvedCompilation.define.FARM_HMR_UPDATE_PANEL = String(
+      !!resolvedUserConfig.server.hmr.updatePanel
+    );
+    resolvedCompilation.define.FARM_HMR_RETRY_FAILED_MODULES = String(
+      !!resolvedUserConfig.server.hmr.retryFailedModules
+    );
+    resolvedCompilation.define.FARM_HMR_LOG_BOUNDARIES = String(
+      !!resolvedUserConfig.server.hmr.logBoundaries
+    );
   }
 
   if (
@@ -505,6 +528,28 @@ export async function normalizeUserCompilationConfig(
     resolvedCompilation.lazyCompilation = false;
   }
 
+  // native esm builds do not include the runtime that hmr and lazyCompilation rely on
+  if (
+    resolvedCompilation.output?.nativeEsm &&
+    (resolvedCompilation.mode === 'development' ||
+      resolvedCompilation.lazyCompilation ||
+      resolvedUserConfig.server?.hmr)
+  ) {
+    throw new Error(
+      'output.nativeEsm can not be used with development mode, hmr or lazyCompilation, they require the Farm runtime that native esm builds do not include.'
+    );
+  }
+
+  if (
+    resolvedCompilation.output?.nativeEsm &&
+    resolvedCompilation.output.format &&
+    resolvedCompilation.output.format !== 'esm'
+  ) {
+    throw new Error(
+      'output.nativeEsm requires output.format to be esm, the resource pots are emitted as ES modules.'
+    );
+  }
+
   if (resolvedCompilation.minify === undefined) {
     if (isProduction) {
       resolvedCompilation.minify = true;
@@ -521,6 +566,11 @@ export async function normalizeUserCompilationConfig(
     }
   }
 
+  // keep only the license comments in production, all comments are kept in development
+  if (resolvedCompilation.comments === undefined) {
+    resolvedCompilation.comments = isProduction ? 'license' : 'all';
+  }
+
   // setting the custom configuration
   resolvedCompilation.custom = {
     ...(resolvedCompilation.custom || {}),
@@ -576,7 +626,11 @@ export const DEFAULT_HMR_OPTIONS: Required<UserHmrConfig> = {
   path: '/__hmr',
   overlay: true,
   protocol: '',
-  watchOptions: {}
+  watchOptions: {},
+  executionTrace: false,
+  updatePanel: false,
+  retryFailedModules: false,
+  logBoundaries: false
 };
 
 export const DEFAULT_DEV_SERVER_OPTIONS: NormalizedServerConfig = {
@@ -596,7 +650,8 @@ export const DEFAULT_DEV_SERVER_OPTIONS: NormalizedServerConfig = {
   cors: false,
   spa: true,
   middlewares: [],
-  writeToDisk: false
+  writeToDisk: false,
+  metrics: false
 };
 
 export const DEFAULT_COMPILATION_OPTIONS: Partial<ResolvedCompilation> = {
@@ -825,6 +880,21 @@ export function normalizePublicDir(root: string, userPublicDir?: string) {
   return absPublicDirPath;
 }
 
+/**
+ * Directory of the persistent cache, `node_modules/.farm/cache` by default
+ */
+export function resolveCacheDir(config: ResolvedUserConfig) {
+  const { persistentCache } = config.compilation ?? {};
+
+  if (typeof persistentCache === 'object' && persistentCache.cacheDir) {
+    return path.isAbsolute(persistentCache.cacheDir)
+      ? persistentCache.cacheDir
+      : path.resolve(config.root, persistentCache.cacheDir);
+  }
+
+  return path.resolve(config.root, 'node_modules', '.farm', 'cache');
+}
+
 export function checkClearScreen(
   inlineConfig: FarmCLIOptions | ResolvedUserConfig
 ) {
@@ -10,9 +10,16 @@ import type { ResolvedCompilation, UserConfig } from '../types.js';
 
 type PartialExternal = [string[], Record<string, string>];
 
+type ExternalConfig = Config['config']['external'];
+
 export function partialExternal(
-  externalConfig: (string | Record<string, string>)[] = []
+  externalConfig: ExternalConfig = [],
+  root = process.cwd()
 ): PartialExternal {
+  if (typeof externalConfig === 'string') {
+    return [dependencyExternals(externalConfig, readPackageJson(root)), {}];
+  }
+
   const stringExternal: string[] = [];
   const recordExternal: Record<string, string> = {};
 
@@ -34,6 +41,43 @@ export function partialExternal(
   return [stringExternal, recordExternal];
 }
 
+/**
+ * `dependencies` externalizes the `dependencies`, `peerDependencies` and `optionalDependencies` of the package.json,
+ * `peerDependencies` only externalizes the `peerDependencies`. The `devDependencies` are still bundled
+ */
+function dependencyExternals(
+  kind: Extract<ExternalConfig, string>,
+  packageJson: any
+): string[] {
+  const fields =
+    kind === 'dependencies'
+      ? ['dependencies', 'peerDependencies', 'optionalDependencies']
+      : ['peerDependencies'];
+  const names = new Set(
+    fields.flatMap((field) => Object.keys(packageJson?.[field] ?? {}))
+  );
+
+  // the package and its subpaths, e.g. `lodash` and `lodash/merge`
+  return [...names].map((name) => {
+    const escaped = name.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+    return `^${escaped}($|/)`;
+  });
+}
+
+function readPackageJson(root: string): any {
+  const pkgPath = path.join(root, 'package.json');
+
+  if (existsSync(pkgPath)) {
+    try {
+      return JSON.parse(readFileSync(pkgPath, 'utf8'));
+    } catch {
+      /**/
+    }
+  }
+
+  return {};
+}
+
 export function normalizeExternal(
   config: UserConfig,
   resolvedCompilation: ResolvedCompilation
@@ -45,24 +89,21 @@ export function normalizeExternal(
     if (Array.isArray(externalNodeBuiltins)) {
       defaultExternals.push(...externalNodeBuiltins);
     } else if (externalNodeBuiltins === true) {
-      let packageJson: any = {};
-      const pkgPath = path.join(
-        resolvedCompilation.root || process.cwd(),
-        'package.json'
-      );
       // the project installed polyfill
-      if (existsSync(pkgPath)) {
-        try {
-          packageJson = JSON.parse(readFileSync(pkgPath, 'utf8'));
-        } catch {
-          /**/
-        }
-      }
+      const packageJson = readPackageJson(
+        resolvedCompilation.root || process.cwd()
+      );
+
+      const alias = resolvedCompilation.resolve?.alias;
+      const isAliased = (m: string) =>
+        Array.isArray(alias)
+          ? alias.some(({ find }) => find === m)
+          : Boolean(alias?.[m]);
 
       defaultExternals.push(
         ...[...module.builtinModules].filter(
           (m) =>
-            !resolvedCompilation.resolve?.alias?.[m] &&
+            !isAliased(m) &&
             !packageJson?.devDependencies?.[m] &&
             !packageJson?.dependencies?.[m]
         )
@@ -82,7 +123,10 @@ export function normalizeExternal(
     config.compilation,
     mergeCustomExternal(
       resolvedCompilation,
-      partialExternal(config.compilation.external)
+      partialExternal(
+        config.compilation.external,
+        resolvedCompilation.root || process.cwd()
+      )
     )
   );
 
@@ -11,6 +11,11 @@ import {
 } from '../../utils/share.js';
 import { ResolvedCompilation } from '../types.js';
 
+/**
+ * the public path is resolved at runtime by `globalThis.__farm_public_path__`
+ */
+export const RUNTIME_PUBLIC_PATH = 'runtime';
+
 export function normalizeOutput(
   config: ResolvedCompilation,
   isProduction: boolean,
@@ -40,17 +45,28 @@ export function normalizeOutput(
   // only set default polyfill in production
   if (isProduction) {
     normalizeTargetEnv(config);
+
+    if (config.output.es5) {
+      normalizeEs5(config, logger);
+    }
   }
 
   // the rust compiler only receives 'node' or 'browser'.
   mapTargetEnvValue(config);
 
   // resolve public path
-  config.output.publicPath = normalizePublicPath(
-    config.output.targetEnv,
-    config.output?.publicPath,
-    logger
-  );
+  if (config.output.publicPath === RUNTIME_PUBLIC_PATH) {
+    // the dev server always serves the resources under '/'
+    if (!isProduction) {
+      config.output.publicPath = '/';
+    }
+  } else {
+    config.output.publicPath = normalizePublicPath(
+      config.output.targetEnv,
+      config.output?.publicPath,
+      logger
+    );
+  }
 }
 
 type TargetEnvKeys = Config['config']['output']['targetEnv'];
@@ -174,6 +190,33 @@ function normalizeTargetEnv(config: Config['config']) {
   }
 }
 
+/**
+ * Downgrade all modules including the runtime to es5 and never emit esm syntax, so the output can be loaded as classic scripts.
+ */
+function normalizeEs5(config: Config['config'], logger: Logger) {
+  if (config.output.format === 'esm') {
+    logger.warn(
+      '`output.format: esm` is not supported when `output.es5` is enabled, fallback to `cjs`.'
+    );
+  }
+
+  config.output.format = 'cjs';
+  config.script ??= { plugins: [] };
+  config.script.target = 'es5';
+  config.script.nativeTopLevelAwait = false;
+
+  if (typeof config.presetEnv === 'object') {
+    config.presetEnv.options ??= {};
+    config.presetEnv.options.targets ??= LEGACY_BROWSERS;
+  } else {
+    config.presetEnv = {
+      options: {
+        targets: LEGACY_BROWSERS
+      }
+    };
+  }
+}
+
 function tryGetDefaultPublicPath(
   targetEnv: string,
   publicPath: string | undefined,
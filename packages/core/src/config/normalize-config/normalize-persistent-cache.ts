@@ -9,12 +9,13 @@ import { traceDependencies } from '../../utils/trace-dependencies.js';
 import { isDisableCache } from '../env.js';
 import { ResolvedUserConfig } from '../index.js';
 
+// defines and envs are tracked per module by the define plugin, only the modules using the changed ones are rebuilt
 const defaultGlobalBuiltinCacheKeyStrategy = {
-  define: true,
+  define: false,
   buildDependencies: true,
   lockfile: true,
   packageJson: true,
-  env: true
+  env: false
 };
 
 export async function normalizePersistentCache(
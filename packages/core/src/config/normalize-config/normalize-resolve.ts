@@ -1,6 +1,9 @@
+import { AliasEntry } from '../../types/binding.js';
 import { CUSTOM_KEYS } from '../constants.js';
 import { ResolvedCompilation, UserConfig } from '../types.js';
 
+const FARM_REGEX_PREFIX = '$__farm_regex:';
+
 export function normalizeResolve(
   config: UserConfig,
   resolvedCompilation: ResolvedCompilation
@@ -16,4 +19,38 @@ export function normalizeResolve(
 
   resolvedCompilation.custom[CUSTOM_KEYS.resolve_dedupe] =
     JSON.stringify(dedupe);
+
+  normalizeAlias(resolvedCompilation);
+}
+
+/**
+ * The array form of `alias` is matched in the declaration order, it's passed to the compiler as `aliasEntries`.
+ * Function replacements can not be passed to the compiler, they are resolved by the alias adapter plugin
+ */
+function normalizeAlias(resolvedCompilation: ResolvedCompilation) {
+  const alias = resolvedCompilation.resolve?.alias;
+
+  if (!Array.isArray(alias)) {
+    return;
+  }
+
+  resolvedCompilation.resolve.alias = {};
+  resolvedCompilation.resolve.aliasEntries = [
+    ...(resolvedCompilation.resolve.aliasEntries ?? []),
+    ...alias
+      .filter(({ replacement }) => typeof replacement === 'string')
+      .map(({ find, replacement }) => ({
+        find: normalizeAliasFind(find),
+        replacement: replacement as string
+      }))
+  ];
+}
+
+export function normalizeAliasFind(find: AliasEntry['find']): string {
+  if (typeof find === 'string') {
+    return find;
+  }
+
+  const flags = find.flags.includes('i') ? '(?i)' : '';
+  return `${FARM_REGEX_PREFIX}${flags}${find.source}`;
 }
@@ -23,9 +23,24 @@ const pathFilterSchema = z.union([
 
 const pathRewriteSchema = z.union([stringRewriteSchema, functionRewriteSchema]);
 
+const logLevelSchema = z.enum([
+  'silent',
+  'error',
+  'warn',
+  'info',
+  'debug',
+  'trace'
+]);
+
+const nodePrefixActionSchema = z.union([
+  z.enum(['external', 'error', 'empty']),
+  z.object({ polyfill: z.string() }).strict()
+]);
+
 const compilationConfigSchema = z
   .object({
     root: z.string().optional(),
+    sourceRoots: z.array(z.string()).optional(),
     input: z.record(z.string()).optional(),
     output: z
       .object({
@@ -51,26 +66,88 @@ const compilationConfigSchema = z
           ])
           .optional(),
         format: z.enum(['cjs', 'esm']).optional(),
-        clean: z.boolean().optional()
+        clean: z.boolean().optional(),
+        retainBuilds: z.number().int().nonnegative().optional(),
+        variants: z
+          .array(
+            z
+              .object({
+                name: z.string(),
+                path: z.string().optional(),
+                variables: z.record(z.string()),
+                assetTarget: z.string().optional()
+              })
+              .strict()
+          )
+          .optional(),
+        injections: z
+          .array(
+            z
+              .object({
+                resourcePotTypes: z.array(z.string()).optional(),
+                name: z.string().optional(),
+                banner: z.string().optional(),
+                intro: z.string().optional(),
+                footer: z.string().optional()
+              })
+              .strict()
+          )
+          .optional(),
+        highPriority: z.array(z.string()).optional(),
+        es5: z.boolean().optional(),
+        crossOriginIsolated: z.boolean().optional(),
+        extractLicenseComments: z.boolean().optional(),
+        nativeEsm: z.boolean().optional(),
+        shebang: z.string().optional(),
+        bin: z.boolean().optional()
       })
       .strict()
       .optional(),
     resolve: z
       .object({
         extensions: z.array(z.string()).optional(),
-        alias: z.record(z.string()).optional(),
+        alias: z
+          .union([
+            z.record(z.string()),
+            z.array(
+              z
+                .object({
+                  find: z.union([z.string(), z.instanceof(RegExp)]),
+                  replacement: z.union([z.string(), z.function()])
+                })
+                .strict()
+            )
+          ])
+          .optional(),
+        aliasEntries: z
+          .array(
+            z.object({ find: z.string(), replacement: z.string() }).strict()
+          )
+          .optional(),
         mainFields: z.array(z.string()).optional(),
         conditions: z.array(z.string()).optional(),
         symlinks: z.boolean().optional(),
         strictExports: z.boolean().optional(),
         autoExternalFailedResolve: z.boolean().optional(),
-        dedupe: z.array(z.string()).optional()
+        dedupe: z.array(z.string()).optional(),
+        nodePrefix: z
+          .object({
+            default: nodePrefixActionSchema.optional(),
+            builtins: z.record(nodePrefixActionSchema).optional()
+          })
+          .strict()
+          .optional()
       })
       .strict()
       .optional(),
     define: z.record(z.any()).optional(),
+    entryDefine: z.record(z.record(z.any())).optional(),
     external: z
-      .array(z.string().or(z.record(z.string(), z.string())))
+      .union([
+        z.literal('dependencies'),
+        z.literal('peerDependencies'),
+        z.array(z.string().or(z.record(z.string(), z.string())))
+      ])
       .optional(),
     externalNodeBuiltins: z
       .union([z.boolean(), z.array(z.string())])
@@ -96,7 +173,23 @@ const compilationConfigSchema = z
         path: z.string().optional(),
         plugins: z.array(z.string()).optional(),
         swcHelpersPath: z.string().optional(),
-        isolate: z.boolean().optional()
+        helpersResource: z.string().optional(),
+        alias: z.record(z.string()).optional(),
+        isolate: z.boolean().optional(),
+        retry: z
+          .object({
+            count: z.number().int().nonnegative().optional(),
+            delay: z.number().int().nonnegative().optional(),
+            cacheBusting: z.boolean().optional()
+          })
+          .strict()
+          .optional(),
+        trustedTypes: z
+          .object({
+            policyName: z.string().optional()
+          })
+          .strict()
+          .optional()
       })
       .strict()
       .optional(),
@@ -104,7 +197,24 @@ const compilationConfigSchema = z
       .object({
         include: z.array(z.string()).optional(),
         publicDir: z.string().optional(),
-        mode: z.enum(['browser', 'node']).optional()
+        mode: z.enum(['browser', 'node']).optional(),
+        streamThreshold: z.number().int().nonnegative().optional(),
+        wasmEsmIntegration: z.boolean().optional(),
+        binary: z
+          .object({
+            include: z.array(z.string()).optional(),
+            hash: z.boolean().optional()
+          })
+          .strict()
+          .optional(),
+        targets: z.array(z.string()).optional(),
+        sprite: z
+          .object({
+            filename: z.string().optional(),
+            symbolIdPrefix: z.string().optional()
+          })
+          .strict()
+          .optional()
       })
       .strict()
       .optional(),
@@ -185,6 +295,31 @@ const compilationConfigSchema = z
         z.literal('all-inline')
       ])
       .optional(),
+    sourcemapBase: z.string().optional(),
+    pathMappings: z
+      .array(z.object({ compiler: z.string(), host: z.string() }).strict())
+      .optional(),
+    patches: z
+      .array(
+        z
+          .object({
+            test: z.array(z.string()).optional(),
+            diff: z.string().optional(),
+            replace: z
+              .array(
+                z.object({ search: z.string(), replace: z.string() }).strict()
+              )
+              .optional()
+          })
+          .strict()
+      )
+      .optional(),
+    moduleTypes: z
+      .record(
+        z.string(),
+        z.object({ treatAs: z.enum(['asset', 'js-via-plugin']) }).strict()
+      )
+      .optional(),
     partialBundling: z
       .object({
         targetConcurrentRequests: z.number().positive().int().optional(),
@@ -213,7 +348,10 @@ const compilationConfigSchema = z
         enforceTargetConcurrentRequests: z.boolean().optional(),
         enforceTargetMinSize: z.boolean().optional(),
         immutableModules: z.array(z.string()).optional(),
-        immutableModulesWeight: z.number().optional()
+        immutableModulesWeight: z.number().optional(),
+        licenseGroups: z.boolean().optional(),
+        splitPackages: z.array(z.string()).optional(),
+        maxAsyncDepth: z.number().nonnegative().int().optional()
       })
       .strict()
       .optional(),
@@ -233,7 +371,9 @@ const compilationConfigSchema = z
               z.literal('minify-resource-pot')
             ])
             .optional(),
-          moduleDecls: z.boolean().optional()
+          moduleDecls: z.boolean().optional(),
+          js: z.boolean().optional(),
+          css: z.boolean().optional()
         })
       ])
       .optional(),
@@ -257,6 +397,7 @@ const compilationConfigSchema = z
             z.null(),
             z.object({
               indentName: z.string().optional(),
+              hashStrategy: z.enum(['path', 'content']).optional(),
               localsConversion: z.string().optional(),
               paths: z.array(z.string()).optional()
             })
@@ -277,7 +418,238 @@ const compilationConfigSchema = z
           .optional()
       })
       .optional(),
-    html: z.object({ base: z.string().optional() }).optional(),
+    routes: z
+      .object({
+        dirs: z.array(z.string()).optional(),
+        extensions: z.array(z.string()).optional(),
+        manifestFilename: z.string().optional()
+      })
+      .optional(),
+    macros: z
+      .object({
+        modules: z.array(z.string()).optional()
+      })
+      .optional(),
+    hash: z
+      .object({
+        algorithm: z.enum(['xxh3', 'blake3', 'sha256']).optional(),
+        seed: z.string().optional(),
+        length: z.number().int().min(6).max(32).optional()
+      })
+      .optional(),
+    polyfillEntries: z.array(z.string()).optional(),
+    executionOrderTieBreak: z.enum(['importOrder', 'path']).optional(),
+    bundleStats: z
+      .object({
+        filename: z.string().optional(),
+        gzipSize: z.boolean().optional(),
+        exports: z.boolean().optional()
+      })
+      .strict()
+      .optional(),
+    unusedExports: z
+      .object({
+        filename: z.string().optional()
+      })
+      .strict()
+      .optional(),
+    licenses: z
+      .object({
+        filename: z.string().optional(),
+        notices: z.string().optional(),
+        deny: z.array(z.string()).optional(),
+        allow: z.array(z.string()).optional()
+      })
+      .strict()
+      .optional(),
+    moduleBoundaries: z
+      .object({
+        serverOnly: z.array(z.string()).optional(),
+        clientOnly: z.array(z.string()).optional()
+      })
+      .strict()
+      .optional(),
+    vendorReference: z
+      .object({
+        manifest: z.string().optional(),
+        references: z.array(z.string()).optional(),
+        providers: z
+          .array(
+            z
+              .object({
+                manifest: z.string(),
+                publicPath: z.string().optional(),
+                alias: z.record(z.string()).optional()
+              })
+              .strict()
+          )
+          .optional()
+      })
+      .strict()
+      .optional(),
+    circularDependency: z
+      .object({
+        severity: z.enum(['warn', 'error', 'ignore']).optional(),
+        include: z.array(z.string()).optional(),
+        exclude: z.array(z.string()).optional(),
+        ignoreDynamicImports: z.boolean().optional(),
+        ignoreNodeModules: z.boolean().optional()
+      })
+      .strict()
+      .optional(),
+    dependencyPolicy: z
+      .object({
+        rules: z
+          .array(
+            z
+              .object({
+                from: z.array(z.string()).optional(),
+                deny: z.array(z.string()).optional(),
+                allow: z.array(z.string()).optional(),
+                severity: z.enum(['warn', 'error']).optional(),
+                message: z.string().optional()
+              })
+              .strict()
+          )
+          .optional()
+      })
+      .strict()
+      .optional(),
+    federation: z
+      .object({
+        name: z.string().optional(),
+        manifest: z.string().optional(),
+        exposes: z.record(z.string()).optional(),
+        remotes: z.record(z.string()).optional(),
+        shared: z
+          .record(
+            z
+              .object({
+                singleton: z.boolean().optional(),
+                requiredVersion: z.string().optional()
+              })
+              .strict()
+          )
+          .optional()
+      })
+      .strict()
+      .optional(),
+    flags: z
+      .object({
+        values: z.record(z.boolean()).optional(),
+        report: z.string().optional()
+      })
+      .strict()
+      .optional(),
+    buildMeta: z
+      .object({
+        package: z.boolean().optional(),
+        gitCommit: z.boolean().optional(),
+        buildTime: z.boolean().optional(),
+        mode: z.boolean().optional()
+      })
+      .strict()
+      .optional(),
+    checkpoint: z
+      .object({
+        dir: z.string().optional()
+      })
+      .strict()
+      .optional(),
+    adaptiveRebuild: z
+      .object({
+        threshold: z.number().min(0).max(1).optional(),
+        minChangedModules: z.number().int().nonnegative().optional()
+      })
+      .strict()
+      .optional(),
+    hosting: z
+      .object({
+        providers: z.array(z.enum(['netlify', 'vercel', 'nginx'])).optional(),
+        spaFallback: z.string().nullable().optional(),
+        redirects: z
+          .array(
+            z
+              .object({
+                from: z.string(),
+                to: z.string(),
+                status: z
+                  .union([
+                    z.literal(301),
+                    z.literal(302),
+                    z.literal(307),
+                    z.literal(308)
+                  ])
+                  .optional()
+              })
+              .strict()
+          )
+          .optional(),
+        rewrites: z
+          .array(z.object({ from: z.string(), to: z.string() }).strict())
+          .optional(),
+        immutable: z.array(z.string()).optional(),
+        immutableCacheControl: z.string().optional(),
+        precompressed: z.boolean().optional()
+      })
+      .strict()
+      .optional(),
+    entryManifest: z
+      .object({
+        filename: z.string().optional(),
+        loader: z.string().optional()
+      })
+      .strict()
+      .optional(),
+    logging: z
+      .object({
+        level: logLevelSchema.optional(),
+        format: z.enum(['text', 'json']).optional(),
+        filters: z.record(z.string(), logLevelSchema).optional()
+      })
+      .strict()
+      .optional(),
+    html: z
+      .object({
+        base: z.string().optional(),
+        template: z.boolean().optional(),
+        icons: z.object({ source: z.string() }).optional(),
+        noscriptCss: z
+          .object({
+            include: z.array(z.string()).optional(),
+            inject: z.enum(['noscript', 'link']).optional()
+          })
+          .strict()
+          .optional(),
+        manifest: z
+          .object({
+            filename: z.string().optional(),
+            name: z.string().optional(),
+            shortName: z.string().optional(),
+            description: z.string().optional(),
+            startUrl: z.string().optional(),
+            scope: z.string().optional(),
+            display: z
+              .enum(['fullscreen', 'standalone', 'minimal-ui', 'browser'])
+              .optional(),
+            themeColor: z.string().optional(),
+            backgroundColor: z.string().optional(),
+            icons: z
+              .array(
+                z
+                  .object({
+                    src: z.string(),
+                    sizes: z.string().optional(),
+                    purpose: z.string().optional()
+                  })
+                  .strict()
+              )
+              .optional()
+          })
+          .strict()
+          .optional()
+      })
+      .optional(),
     persistentCache: z.union([
       z.boolean(),
       z
@@ -288,7 +660,8 @@ const compilationConfigSchema = z
           moduleCacheKeyStrategy: z
             .object({
               timestamp: z.boolean().optional(),
-              hash: z.boolean().optional()
+              hash: z.boolean().optional(),
+              exportSignature: z.boolean().optional()
             })
             .optional(),
           envs: z.record(z.string(), z.string()).optional(),
@@ -300,11 +673,19 @@ const compilationConfigSchema = z
               lockfile: z.boolean().optional(),
               packageJson: z.boolean().optional()
             })
+            .optional(),
+          globalCache: z
+            .object({
+              dir: z.string().optional(),
+              maxSize: z.number().nonnegative().optional()
+            })
             .optional()
         })
         .optional()
     ]),
-    comments: z.union([z.boolean(), z.literal('license')]).optional(),
+    comments: z
+      .union([z.boolean(), z.enum(['all', 'license', 'none'])])
+      .optional(),
     custom: z.record(z.string(), z.string()).optional()
   })
   .strict();
@@ -398,13 +779,18 @@ const FarmConfigSchema = z
                     awaitWriteFinish: z.number().positive().int().optional()
                   })
                   .optional(),
-                overlay: z.boolean().optional()
+                overlay: z.boolean().optional(),
+                executionTrace: z.boolean().optional(),
+                updatePanel: z.boolean().optional(),
+                retryFailedModules: z.boolean().optional(),
+                logBoundaries: z.boolean().optional()
               })
               .strict()
           ])
           .optional(),
         middlewares: z.array(z.any()).optional(),
-        writeToDisk: z.boolean().optional()
+        writeToDisk: z.boolean().optional(),
+        metrics: z.boolean().optional()
       })
       .strict()
       .optional()
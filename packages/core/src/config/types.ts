@@ -6,9 +6,10 @@ import type cors from '@koa/cors';
 import { WatchOptions } from 'chokidar';
 import type { Options } from 'http-proxy-middleware';
 import { Middleware } from 'koa';
+import type { ModuleGraphFilter } from '../compiler/index.js';
 import type { RustPlugin } from '../plugin/rust/index.js';
 import type { JsPlugin } from '../plugin/type.js';
-import type { Config, CssConfig } from '../types/binding.js';
+import type { AliasEntry, Config, CssConfig } from '../types/binding.js';
 import type { Logger } from '../utils/index.js';
 
 export interface ConfigEnv {
@@ -45,6 +46,8 @@ export interface UserServerConfig {
   spa?: boolean;
   middlewares?: DevServerMiddleware[];
   writeToDisk?: boolean;
+  // serve the compiler metrics in the Prometheus text format at `/__farm_metrics`, default to false
+  metrics?: boolean;
 }
 
 export interface UserPreviewServerConfig {
@@ -74,6 +77,30 @@ export interface UserHmrConfig {
   overlay?: boolean;
   protocol?: string;
   watchOptions?: WatchOptions;
+  /**
+   * Record the execution order and duration of every module in the browser and report the slowest modules,
+   * useful to find expensive top level side effects that slow down page startup
+   * @default false
+   */
+  executionTrace?: boolean;
+  /**
+   * Show a panel in the browser listing every hot update with its size, affected modules, compile time and apply time,
+   * and the resources loaded by the page, useful to find pathologically expensive updates
+   * @default false
+   */
+  updatePanel?: boolean;
+  /**
+   * Execute the modules that threw during execution again after the next hot update is applied,
+   * so fixing a dependency of a failed module recovers the page without a full reload
+   * @default false
+   */
+  retryFailedModules?: boolean;
+  /**
+   * Log the accepted boundary of every updated module and the importers the update propagates through,
+   * in the terminal and the browser console
+   * @default false
+   */
+  logBoundaries?: boolean;
 }
 
 type InternalConfig = Config['config'] extends undefined
@@ -160,6 +187,15 @@ export interface FarmCLIBuildOptions {
   outDir?: string;
   sourcemap?: boolean;
   minify?: boolean;
+  // write the dependency diagram of the module graph after the build
+  graph?: FarmCLIGraphOptions;
+  // write all the diagnostics of the build to this json file
+  diagnosticsJson?: string;
+}
+
+export interface FarmCLIGraphOptions extends ModuleGraphFilter {
+  // `.dot` and `.gv` files are written as Graphviz DOT diagrams, others as Mermaid flowcharts
+  output: string;
 }
 
 export interface FarmCLIPreviewOptions {
@@ -184,8 +220,4 @@ export interface FarmCLIOptions
 
 export type DevServerMiddleware = (context: Server) => Middleware | undefined;
 
-export interface Alias {
-  // TODO support RegExp
-  find: string;
-  replacement: string;
-}
+export type Alias = AliasEntry;
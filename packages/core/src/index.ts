@@ -28,16 +28,24 @@ import fs from 'node:fs/promises';
 import path from 'node:path';
 import fse from 'fs-extra';
 
+import {
+  exportCache as exportCacheArchive,
+  gcGlobalCache,
+  importCache as importCacheArchive
+} from '../binding/index.js';
 import { Compiler } from './compiler/index.js';
+import { createMacroHost } from './compiler/macro-host.js';
 import { loadEnv, setProcessEnv } from './config/env.js';
 import {
   UserConfig,
   checkClearScreen,
   getConfigFilePath,
   normalizePublicDir,
+  resolveCacheDir,
   resolveConfig
 } from './config/index.js';
 import { Server } from './server/index.js';
+import type { Server as HttpServer } from './server/type.js';
 import { compilerHandler } from './utils/build.js';
 import { colors } from './utils/color.js';
 import { Logger } from './utils/logger.js';
@@ -45,6 +53,7 @@ import { FileWatcher } from './watcher/index.js';
 
 import { __FARM_GLOBAL__ } from './config/_global.js';
 import type {
+  FarmCLIGraphOptions,
   FarmCLIOptions,
   ResolvedUserConfig,
   UserPreviewServerConfig
@@ -106,7 +115,13 @@ export async function build(
   );
 
   try {
-    await createBundleHandler(resolvedUserConfig, logger);
+    await createBundleHandler(
+      resolvedUserConfig,
+      logger,
+      false,
+      inlineConfig.graph,
+      inlineConfig.diagnosticsJson
+    );
     // copy resources under publicDir to output.path
     await copyPublicDirectory(resolvedUserConfig, logger);
   } catch (err) {
@@ -268,6 +283,106 @@ export async function clean(
   );
 }
 
+/**
+ * Remove the least recently used packages of the global cache until it's not larger than `maxSize`, pass 0 to remove all of them
+ */
+export function cleanGlobalCache(dir?: string, maxSize?: number): void {
+  const logger = new Logger();
+  const { removed, freedBytes } = gcGlobalCache(dir, maxSize);
+
+  logger.info(
+    `Removed ${colors.bold(
+      colors.green(String(removed))
+    )} packages from the global cache, freed ${colors.bold(
+      colors.green(`${(freedBytes / 1024 / 1024).toFixed(2)} MB`)
+    )}`
+  );
+}
+
+/**
+ * Compile the project in production mode to fill the persistent cache, the resources are not written to disk
+ */
+export async function warmCache(
+  inlineConfig?: FarmCLIOptions & UserConfig
+): Promise<void> {
+  inlineConfig = inlineConfig ?? {};
+  const logger = inlineConfig.logger ?? new Logger();
+  setProcessEnv('production');
+
+  const resolvedUserConfig = await resolveConfig(
+    inlineConfig,
+    'production',
+    logger,
+    false
+  );
+  const compiler = await createCompiler(resolvedUserConfig, logger);
+  await compiler.compile();
+
+  logger.info(
+    `Persistent cache warmed at ${colors.bold(
+      colors.green(resolveCacheDir(resolvedUserConfig))
+    )}`
+  );
+}
+
+/**
+ * Pack the persistent cache of the project into a portable archive, e.g. to save it as a CI artifact
+ */
+export async function exportCache(
+  archive: string,
+  inlineConfig?: FarmCLIOptions & UserConfig
+): Promise<void> {
+  inlineConfig = inlineConfig ?? {};
+  const logger = inlineConfig.logger ?? new Logger();
+  const resolvedUserConfig = await resolveConfig(
+    inlineConfig,
+    'production',
+    logger,
+    false
+  );
+  const archivePath = path.resolve(resolvedUserConfig.root, archive);
+  const { files, bytes } = exportCacheArchive(
+    resolveCacheDir(resolvedUserConfig),
+    archivePath
+  );
+
+  logger.info(
+    `Exported ${colors.bold(colors.green(String(files)))} cache files (${(
+      bytes /
+      1024 /
+      1024
+    ).toFixed(2)} MB) to ${colors.bold(colors.green(archivePath))}`
+  );
+}
+
+/**
+ * Merge the persistent cache packed by `exportCache` into the cache of the project, the existing cache entries are kept
+ */
+export async function importCache(
+  archive: string,
+  inlineConfig?: FarmCLIOptions & UserConfig
+): Promise<void> {
+  inlineConfig = inlineConfig ?? {};
+  const logger = inlineConfig.logger ?? new Logger();
+  const resolvedUserConfig = await resolveConfig(
+    inlineConfig,
+    'production',
+    logger,
+    false
+  );
+  const cacheDir = resolveCacheDir(resolvedUserConfig);
+  const { files } = importCacheArchive(
+    path.resolve(resolvedUserConfig.root, archive),
+    cacheDir
+  );
+
+  logger.info(
+    `Imported ${colors.bold(
+      colors.green(String(files))
+    )} cache files into ${colors.bold(colors.green(cacheDir))}`
+  );
+}
+
 async function findNodeModulesRecursively(rootPath: string): Promise<string[]> {
   const result: string[] = [];
 
@@ -294,7 +409,9 @@ async function findNodeModulesRecursively(rootPath: string): Promise<string[]> {
 export async function createBundleHandler(
   resolvedUserConfig: ResolvedUserConfig,
   logger: Logger,
-  watchMode = false
+  watchMode = false,
+  graph?: FarmCLIGraphOptions,
+  diagnosticsJson?: string
 ) {
   const compiler = await createCompiler(resolvedUserConfig, logger);
 
@@ -306,9 +423,30 @@ export async function createBundleHandler(
       try {
         await compiler.compile();
       } catch (err) {
+        const errors = compiler
+          .diagnostics()
+          .filter((diagnostic) => diagnostic.severity === 'error');
+
+        // the errors are reported by module instead of an unbounded wall of messages
+        if (errors.length > 0) {
+          logger.error(compiler.diagnosticsReport());
+          throw new Error(`Build failed with ${errors.length} error(s)`);
+        }
+
         throw new Error(logError(err) as unknown as string);
+      } finally {
+        if (diagnosticsJson) {
+          await fs.writeFile(
+            path.resolve(resolvedUserConfig.root, diagnosticsJson),
+            JSON.stringify(compiler.diagnostics(), null, 2)
+          );
+        }
       }
       compiler.writeResourcesToDisk();
+
+      if (graph) {
+        await writeModuleGraphDiagram(compiler, graph, resolvedUserConfig);
+      }
     },
     resolvedUserConfig,
     logger
@@ -321,6 +459,18 @@ export async function createBundleHandler(
   }
 }
 
+async function writeModuleGraphDiagram(
+  compiler: Compiler,
+  { output, ...filter }: FarmCLIGraphOptions,
+  resolvedUserConfig: ResolvedUserConfig
+) {
+  const diagram = /\.(dot|gv)$/.test(output)
+    ? compiler.moduleGraphToDot(filter)
+    : compiler.moduleGraphToMermaid(filter);
+
+  await fs.writeFile(path.resolve(resolvedUserConfig.root, output), diagram);
+}
+
 export async function createCompiler(
   resolvedUserConfig: ResolvedUserConfig,
   logger: Logger
@@ -335,7 +485,8 @@ export async function createCompiler(
     {
       config: compilationConfig,
       jsPlugins,
-      rustPlugins
+      rustPlugins,
+      macroHost: compilationConfig.macros ? createMacroHost() : undefined
     },
     logger
   );
@@ -397,6 +548,38 @@ export async function createDevServer(
   return server;
 }
 
+/**
+ * Create the dev middlewares of farm for an existing Node server instead of starting the dev server, e.g.
+ * `app.use(server.middlewares)` of Express or Connect. The hmr websocket is attached to `httpServer`, or
+ * the upgrade requests can be forwarded by `server.handleUpgrade`
+ */
+export async function createDevMiddleware(
+  inlineConfig?: FarmCLIOptions & UserConfig,
+  httpServer?: HttpServer
+): Promise<Server> {
+  inlineConfig = inlineConfig ?? {};
+  const logger = inlineConfig.logger ?? new Logger();
+  setProcessEnv('development');
+
+  const resolvedUserConfig = await resolveConfig(
+    inlineConfig,
+    'development',
+    logger
+  );
+  const compiler = await createCompiler(resolvedUserConfig, logger);
+
+  const server = new Server({ compiler, logger });
+  await server.createMiddlewareServer(resolvedUserConfig.server, httpServer);
+  await createFileWatcher(server, resolvedUserConfig, logger);
+  resolvedUserConfig.jsPlugins.forEach((plugin: JsPlugin) =>
+    plugin.configureDevServer?.(server)
+  );
+
+  await server.compile();
+
+  return server;
+}
+
 export async function createFileWatcher(
   devServer: Server,
   resolvedUserConfig: ResolvedUserConfig,
@@ -428,6 +611,17 @@ export async function createFileWatcher(
     configFilePath
   });
   farmWatcher.watch(async (files: string[]) => {
+    // the host server owns the middlewares and can't be restarted here
+    if (devServer.middlewareMode) {
+      const changedFiles = files
+        .map((file) => path.relative(resolvedUserConfig.root, file))
+        .join(', ');
+      logger.warn(
+        `${changedFiles} changed, restart the server to apply the changes.`
+      );
+      return;
+    }
+
     checkClearScreen(resolvedUserConfig);
 
     devServer.restart(async () => {
@@ -0,0 +1,81 @@
+import {
+  AliasEntry,
+  PluginResolveHookParam,
+  PluginResolveHookResult
+} from '../../types/binding.js';
+import { CompilationContext, JsPlugin } from '../type.js';
+
+const PLUGIN_NAME = 'farm:alias-adapter';
+
+type AliasFunction = (source: string, ...groups: string[]) => string;
+
+/**
+ * Aliases whose replacements are functions can not be passed to the compiler,
+ * they are matched in the declaration order before the other aliases and the replaced sources are resolved by the compiler
+ */
+export function aliasAdapter(
+  alias: Record<string, string> | AliasEntry[] | undefined
+): JsPlugin | undefined {
+  const entries = (Array.isArray(alias) ? alias : []).filter(
+    ({ replacement }) => typeof replacement === 'function'
+  );
+
+  if (entries.length === 0) {
+    return;
+  }
+
+  return {
+    name: PLUGIN_NAME,
+    // before the resolve plugin of the compiler
+    priority: 101,
+    resolve: {
+      filters: { sources: ['.*'], importers: ['.*'] },
+      async executor(
+        param: PluginResolveHookParam,
+        context: CompilationContext,
+        hookContext?: { caller?: string; meta: Record<string, unknown> }
+      ): Promise<PluginResolveHookResult> {
+        if (hookContext?.caller?.includes(PLUGIN_NAME)) {
+          return null;
+        }
+
+        for (const { find, replacement } of entries) {
+          const groups = matchAlias(find, param.source);
+
+          if (!groups) {
+            continue;
+          }
+
+          const source = (replacement as AliasFunction)(
+            param.source,
+            ...groups
+          );
+          const resolved = await context.resolve(
+            { ...param, source },
+            { meta: hookContext?.meta ?? {}, caller: PLUGIN_NAME }
+          );
+
+          if (resolved) {
+            return resolved;
+          }
+        }
+
+        return null;
+      }
+    }
+  };
+}
+
+/** capture groups of a regex `find`, the rest of the source for a prefix `find` */
+function matchAlias(find: AliasEntry['find'], source: string) {
+  if (find instanceof RegExp) {
+    const match = source.match(find);
+    return match ? match.slice(1) : undefined;
+  }
+
+  if (find.endsWith('$')) {
+    return source === find.slice(0, -1) ? [] : undefined;
+  }
+
+  return source.startsWith(find) ? [source.slice(find.length)] : undefined;
+}
@@ -353,14 +353,18 @@ export function viteConfigToFarmConfig(
           farmConfig.compilation.resolve.alias = {};
         }
 
+        const alias = farmConfig.compilation.resolve.alias as Record<
+          string,
+          string
+        >;
         const farmRegexPrefix = '$__farm_regex:';
 
         for (const { find, replacement } of config.resolve.alias) {
           if (find instanceof RegExp) {
             const key = farmRegexPrefix + find.source;
-            farmConfig.compilation.resolve.alias[key] = replacement;
+            alias[key] = replacement;
           } else {
-            farmConfig.compilation.resolve.alias[find] = replacement;
+            alias[find] = replacement;
           }
         }
       }
@@ -172,11 +172,11 @@ export function farmContextToViteContext(
     },
     warn: (message) => {
       if (typeof message === 'object') {
-        farmContext.warn(JSON.stringify(message));
+        farmContext.warn(JSON.stringify(message), pluginName);
       } else if (typeof message === 'function') {
-        farmContext.warn(JSON.stringify(message()));
+        farmContext.warn(JSON.stringify(message()), pluginName);
       } else {
-        farmContext.warn(message);
+        farmContext.warn(message, pluginName);
       }
     },
     cache: {
@@ -113,12 +113,35 @@ export function convertPlugin(plugin: JsPlugin): void {
     }
   }
 
+  if (plugin.transformAsset) {
+    plugin.transformAsset.filters ??= {};
+    plugin.transformAsset.filters.resolvedPaths ??= [];
+  }
+
   if (plugin.processModule) {
     plugin.processModule.filters ??= {};
     plugin.processModule.filters.moduleTypes ??= [];
     plugin.processModule.filters.resolvedPaths ??= [];
   }
 
+  if (plugin.detectModuleSystem) {
+    plugin.detectModuleSystem.filters ??= {};
+    plugin.detectModuleSystem.filters.moduleTypes ??= [];
+    plugin.detectModuleSystem.filters.resolvedPaths ??= [];
+  }
+
+  for (const hookName of [
+    'normalizeModuleSystem',
+    'renderModuleSystem',
+    'moduleSystemInterop'
+  ] as const) {
+    if (plugin[hookName] && !plugin[hookName].filters?.moduleSystems?.length) {
+      throw new Error(
+        `${hookName} hook of plugin ${plugin.name} must have at least one filter(moduleSystems)`
+      );
+    }
+  }
+
   if (plugin.renderResourcePot) {
     plugin.renderResourcePot.filters ??= {};
 
@@ -171,6 +194,11 @@ export function convertPlugin(plugin: JsPlugin): void {
     plugin.transform.filters.resolvedPaths =
       plugin.transform.filters.resolvedPaths.map(normalizeFilterPath);
   }
+
+  if (plugin.transformAsset?.filters?.resolvedPaths?.length) {
+    plugin.transformAsset.filters.resolvedPaths =
+      plugin.transformAsset.filters.resolvedPaths.map(normalizeFilterPath);
+  }
   if (plugin.augmentResourceHash?.filters?.moduleIds) {
     plugin.augmentResourceHash.filters.moduleIds =
       plugin.augmentResourceHash.filters.moduleIds.map(normalizeFilterPath);
@@ -306,7 +306,10 @@ export function transformResourceInfo2RollupResource(
       name: resource.name,
       map: undefined,
       sourcemapFileName: null,
-      preliminaryFileName: resource.origin.value
+      preliminaryFileName:
+        resource.origin.type === 'Modules'
+          ? resource.name
+          : resource.origin.value
     } satisfies OutputChunk;
   } else {
     return {
@@ -336,7 +339,8 @@ export function transformRollupResource2FarmResource(
       emitted: originResource.emitted,
       name: chunk.name,
       origin: originResource.origin,
-      resourceType: originResource.resourceType
+      resourceType: originResource.resourceType,
+      scope: originResource.scope
     };
   }
 }
@@ -109,6 +109,7 @@ export class VitePluginAdapter implements JsPlugin {
   transform: JsPlugin['transform'];
   buildEnd: JsPlugin['buildEnd'];
   finish: JsPlugin['finish'];
+  watchChange: JsPlugin['watchChange'];
   updateModules: JsPlugin['updateModules'];
   renderResourcePot: JsPlugin['renderResourcePot'];
   renderStart: JsPlugin['renderStart'];
@@ -150,6 +151,8 @@ export class VitePluginAdapter implements JsPlugin {
       transform: () => (this.transform = this.viteTransformToFarmTransform()),
       buildEnd: () => (this.buildEnd = this.viteBuildEndToFarmBuildEnd()),
       // closeBundle: () => (this.finish = this.viteCloseBundleToFarmFinish()),
+      watchChange: () =>
+        (this.watchChange = this.viteWatchChangeToFarmWatchChange()),
       handleHotUpdate: () =>
         (this.updateModules = this.viteHandleHotUpdateToFarmUpdateModules()),
       renderChunk: () =>
@@ -610,6 +613,20 @@ export class VitePluginAdapter implements JsPlugin {
     };
   }
 
+  private viteWatchChangeToFarmWatchChange(): JsPlugin['watchChange'] {
+    return {
+      executor: this.wrapExecutor(async ({ id, event }, context) => {
+        const hook = this.wrapRawPluginHook(
+          'watchChange',
+          this._rawPlugin.watchChange,
+          context
+        );
+        // vite plugins can not remap the change
+        await hook?.(normalizePath(id), { event });
+      })
+    };
+  }
+
   private viteHandleHotUpdateToFarmUpdateModules(): JsPlugin['updateModules'] {
     return {
       executor: this.wrapExecutor(
@@ -1,4 +1,11 @@
-import { Compiler, ResolvedUserConfig, Server, UserConfig } from '../index.js';
+import {
+  Compiler,
+  ModuleExport,
+  ModuleStatement,
+  ResolvedUserConfig,
+  Server,
+  UserConfig
+} from '../index.js';
 import {
   Config,
   ModuleType,
@@ -23,6 +30,11 @@ export interface CompilationContextEmitFileParams {
   name: string;
   content: number[];
   resourceType: 'runtime' | 'js' | 'css' | 'html' | string;
+  /**
+   * `devServer` resources are only served in development,
+   * `production` resources are only present in production builds
+   */
+  scope?: ResourceScope;
 }
 
 export interface ViteModule {
@@ -32,6 +44,34 @@ export interface ViteModule {
   type: 'js' | 'css';
 }
 
+export interface InvalidateModuleOptions {
+  /** recompile the modules that import the invalidated module as well */
+  recompileDependents?: boolean;
+}
+
+/**
+ * Replace `start..end` of the original code with `content`, or insert `content` at `start` if `end` equals to `start`.
+ * Indexes are the indexes of the original js string
+ */
+export interface SourceEdit {
+  start: number;
+  end: number;
+  content: string;
+}
+
+export interface EditSourceParams {
+  code: string;
+  /** source of the generated source map, e.g. the resolved path of the module */
+  filename: string;
+  /** contents inserted at the same index are kept in order, replaced ranges must not overlap */
+  edits: SourceEdit[];
+}
+
+export interface EditSourceResult {
+  content: string;
+  sourceMap: string;
+}
+
 export interface CompilationContext {
   resolve(
     param: PluginResolveHookParam,
@@ -40,10 +80,57 @@ export interface CompilationContext {
 
   addWatchFile(currentFile: string, targetFile: string): void;
   emitFile(params: CompilationContextEmitFileParams): void;
+  /**
+   * Emit css extracted from a script module by zero-runtime css-in-js plugins.
+   * Returns the source of the virtual css module, import it from the transformed code, e.g. `import "${source}";`
+   */
+  emitExtractedCss(moduleId: string, css: string): string;
   getWatchFiles(): string[];
-  warn(message: string): void;
-  error(message: string): void;
+  /**
+   * Schedule the module to be recompiled by the next update like the file is changed,
+   * useful for plugins whose outputs depend on external state
+   */
+  invalidateModule(moduleId: string, options?: InvalidateModuleOptions): void;
+  /**
+   * Warnings with `pluginName` are logged by the compiler logger and attributed to the plugin,
+   * others are reported at the end of the build
+   */
+  warn(message: string, pluginName?: string): void;
+  error(message: string, pluginName?: string): void;
   sourceMapEnabled(id: string): boolean;
+  /**
+   * Generate a deterministic id for `key`, e.g. a css scope hash. The id only depends on `compilation.hash.seed`,
+   * `namespace` and `key`, and different keys of the same namespace never share an id
+   */
+  generateId(namespace: string, key: string): string;
+  /**
+   * Publish a json serializable payload to the topic of the event bus shared with other plugins, including rust plugins,
+   * e.g. `publishEvent('router:routes', routes)`
+   */
+  publishEvent(topic: string, payload: unknown): void;
+  /**
+   * The payloads published to the topic so far, in publishing order
+   */
+  getEvents<T = unknown>(topic: string): T[];
+  /**
+   * Send a custom event to the hmr clients, which receive it by `import.meta.hot.on(event, cb)`.
+   * Dropped if no dev server is running. The events sent by the clients are received by `server.ws.on(event, cb)` of `configureDevServer`
+   */
+  sendHmrEvent(event: string, data?: unknown): void;
+  /**
+   * The imports, exports, dependencies and side effects of the top level statements of the script module,
+   * null if the module is not a script module
+   */
+  getModuleStatements(moduleId: string): ModuleStatement[] | null;
+  /**
+   * The exported names and re-exports of the script module, null if the module is not a script module
+   */
+  getModuleExports(moduleId: string): ModuleExport[] | null;
+  /**
+   * Apply targeted edits to the code and generate the source map of the edits, return it as the `sourceMap`
+   * of the transform result instead of an unmapped whole-file string. See {@link SourceEditor}
+   */
+  editSource(params: EditSourceParams): EditSourceResult;
 
   viteGetModulesByFile(file: string): ViteModule[];
   viteGetModuleById(id: string): ViteModule;
@@ -108,12 +195,20 @@ export interface PluginRenderResourcePotResult {
   sourceMap?: string;
 }
 
+export type ResourceScope = 'all' | 'devServer' | 'production';
+
 export interface Resource {
   name: string;
   bytes: number[];
   emitted: boolean;
   resourceType: string;
-  origin: { type: 'ResourcePot' | 'Module'; value: string };
+  origin:
+    | { type: 'ResourcePot' | 'Module'; value: string }
+    /** derived from several modules, e.g. a sprite sheet */
+    | { type: 'Modules'; value: string[] };
+  /** large assets are copied from this path when written to disk, their content is still loaded into `bytes` for js plugins */
+  sourcePath?: string;
+  scope?: ResourceScope;
   info?: ResourcePotInfo;
 }
 
@@ -122,6 +217,22 @@ export type PluginFinalizeResourcesHookParams = {
   config: Config['config'];
 };
 
+export interface WrittenResource {
+  /** name of the resource, relative to the output path */
+  name: string;
+  /** absolute path of the written file */
+  path: string;
+  size: number;
+  /** hex encoded digests of the written file, the same shape as the subject of an in-toto statement */
+  digest: { sha256: string };
+}
+
+export type PluginResourcesWrittenHookParams = {
+  outputPath: string;
+  /** sorted by name */
+  resources: WrittenResource[];
+};
+
 type Callback<P, R> = (
   param: P,
   context?: CompilationContext,
@@ -139,6 +250,55 @@ export interface PluginProcessModuleResult {
   content: string;
 }
 
+export interface PluginDetectModuleSystemParams {
+  moduleId: string;
+  moduleType: ModuleType;
+  content: string;
+}
+
+export interface PluginModuleSystemParams {
+  moduleId: string;
+  /**
+   * name of the custom module system returned by `detectModuleSystem`
+   */
+  moduleSystem: string;
+  /**
+   * code of the module, not passed to `moduleSystemInterop`
+   */
+  content?: string;
+}
+
+export interface PluginModuleSystemResult {
+  content: string;
+}
+
+export interface PluginTransformAssetParams {
+  moduleId: string;
+  resolvedPath: string;
+  /**
+   * `original` in development and `optimized` in production
+   */
+  variant: 'original' | 'optimized';
+  /**
+   * the target the asset is emitted for in production, see `compilation.assets.targets`
+   */
+  target?: string;
+  ext: string;
+  /**
+   * empty for assets larger than `compilation.assets.streamThreshold`, read them from `sourcePath` instead
+   */
+  content: number[];
+  sourcePath?: string;
+}
+
+export interface PluginTransformAssetResult {
+  content: number[];
+  /**
+   * extension of the emitted file, e.g. `avif` when a png is converted
+   */
+  ext?: string;
+}
+
 type NormalizeFilterParams = {
   moduleTypes?: ModuleType[];
   resolvedPaths?: string[];
@@ -185,7 +345,18 @@ export interface JsPlugin {
     { resolvedPaths?: string[]; moduleTypes?: string[] },
     PluginTransformHookParam,
     PluginTransformHookResult
-  >;
+  > & {
+    /**
+     * Whether the transform result only depends on the module id, the content and `cacheKey`.
+     * The result of a pure transform hook will be stored in the persistent cache and the executor is skipped when the input is not changed.
+     */
+    pure?: boolean;
+    /**
+     * Extra cache key of a pure transform hook, for example the serialized options of the plugin.
+     * It also identifies the options of the plugin in the global cache, projects share the cached node_modules only when it's the same.
+     */
+    cacheKey?: string;
+  };
 
   processModule?: JsPluginHook<
     NormalizeFilterParams,
@@ -193,6 +364,53 @@ export interface JsPlugin {
     PluginProcessModuleResult
   >;
 
+  /**
+   * Detect the module systems that Farm does not handle natively, e.g. SystemJS, return the name of the module system
+   */
+  detectModuleSystem?: JsPluginHook<
+    NormalizeFilterParams,
+    PluginDetectModuleSystemParams,
+    string
+  >;
+
+  /**
+   * Normalize a module of the detected custom module system to esm or commonjs, so its dependencies can be analyzed
+   */
+  normalizeModuleSystem?: JsPluginHook<
+    { moduleSystems: string[] },
+    PluginModuleSystemParams,
+    PluginModuleSystemResult
+  >;
+
+  /**
+   * Render a module of a custom module system that is not normalized, e.g. replace `System.register` with `module.exports`.
+   * The module is rendered as is if no plugin renders it
+   */
+  renderModuleSystem?: JsPluginHook<
+    { moduleSystems: string[] },
+    PluginModuleSystemParams,
+    PluginModuleSystemResult
+  >;
+
+  /**
+   * The module system that the rendered module of a custom module system interops with other modules as
+   */
+  moduleSystemInterop?: JsPluginHook<
+    { moduleSystems: string[] },
+    PluginModuleSystemParams,
+    'esm' | 'commonjs' | 'hybrid'
+  >;
+
+  /**
+   * Transform the content of an asset before it's emitted. Return nothing for the `original` variant
+   * so that hmr is not slowed down, the results are cached per variant
+   */
+  transformAsset?: JsPluginHook<
+    { resolvedPaths?: string[] },
+    PluginTransformAssetParams,
+    PluginTransformAssetResult
+  >;
+
   buildEnd?: { executor: Callback<Record<string, never>, void> };
 
   renderStart?: {
@@ -217,6 +435,11 @@ export interface JsPlugin {
     string
   >;
 
+  /**
+   * Add, remove or rename resources after all resources are generated, e.g. generate a manifest.json.
+   * A resource is renamed by changing its `name`, `[contenthash]` in the name of an added resource
+   * is replaced by its content hash, which changes when the resources it references change
+   */
   finalizeResources?: {
     executor: Callback<
       PluginFinalizeResourcesHookParams,
@@ -236,6 +459,16 @@ export interface JsPlugin {
     ) => void | Promise<void>;
   };
 
+  /**
+   * Called after the resources are written to disk with the final digest of every written file,
+   * e.g. to sign a manifest or generate an attestation of the build output
+   */
+  resourcesWritten?: {
+    executor: (
+      param: PluginResourcesWrittenHookParams
+    ) => void | Promise<void>;
+  };
+
   pluginCacheLoaded?: {
     executor: Callback<number[], undefined | null | void>;
   };
@@ -247,6 +480,21 @@ export interface JsPlugin {
   finish?: { executor: Callback<Record<string, never>, void> };
   updateFinished?: { executor: Callback<Record<string, never>, void> };
 
+  /**
+   * Called for each changed file before the update pipeline runs.
+   * Return module paths to remap the change to them, return nothing to keep the change
+   */
+  watchChange?: {
+    executor: Callback<
+      {
+        id: string;
+        event: 'create' | 'update' | 'delete';
+        paths: [string, string][];
+      },
+      string[] | undefined | null | void
+    >;
+  };
+
   updateModules?: {
     executor: Callback<
       { paths: [string, string][] },
@@ -6,13 +6,15 @@ import { isAbsolute, relative } from 'node:path';
 
 import { Compiler } from '../compiler/index.js';
 import { checkClearScreen } from '../config/index.js';
-import type { JsUpdateResult } from '../types/binding.js';
+import type { InvalidateModuleOptions } from '../plugin/type.js';
+import type { JsHmrFullReload, JsUpdateResult } from '../types/binding.js';
 import {
   Logger,
   bold,
   cyan,
   getDynamicResources,
-  green
+  green,
+  toHostPaths
 } from '../utils/index.js';
 import { logError } from './error.js';
 import { Server } from './index.js';
@@ -27,6 +29,8 @@ export class HmrEngine {
   private _onUpdates: ((result: JsUpdateResult) => void)[];
 
   private _lastModifiedTimestamp: Map<string, string>;
+  // updates are stamped with increasing timestamps, so the runtime can reject stale updates that arrive out of order
+  private _lastUpdateTimestamp = 0;
 
   constructor(
     compiler: Compiler,
@@ -75,9 +79,15 @@ export class HmrEngine {
         updatedFilesStr.slice(0, 100) + `...(${queue.length} files)`;
     }
 
+    // stamp the update before compiling, so the timestamp follows the order of the edits
+    const timestamp = Math.max(Date.now(), this._lastUpdateTimestamp + 1);
+    this._lastUpdateTimestamp = timestamp;
+
     try {
       // we must add callback before update
       this._compiler.onUpdateFinish(async () => {
+        // modules invalidated by plugins during the update are recompiled by the next update
+        this.queueInvalidatedModules();
         // if there are more updates, recompile again
         if (this._updateQueue.length > 0) {
           await this.recompileAndSendResult();
@@ -90,12 +100,33 @@ export class HmrEngine {
       checkClearScreen(this._compiler.config.config);
       const start = Date.now();
       const result = await this._compiler.update(queue);
+      const duration = Date.now() - start;
       this._logger.info(
         `${bold(cyan(updatedFilesStr))} updated in ${bold(
-          green(`${Date.now() - start}ms`)
+          green(`${duration}ms`)
         )}`
       );
 
+      if (result.fullRebuildReason) {
+        this._logger.info(
+          `escalated to a full rebuild: ${result.fullRebuildReason}`
+        );
+      }
+
+      if (result.hmrFullReload) {
+        this._logger.info(
+          `page reloaded: ${formatHmrFullReload(result.hmrFullReload)}`
+        );
+      }
+
+      if (this._devServer?.config?.hmr?.logBoundaries) {
+        for (const [id, chains] of Object.entries(result.boundaries)) {
+          for (const chain of chains) {
+            this._logger.info(`hmr boundary of ${id}: ${chain.join(' -> ')}`);
+          }
+        }
+      }
+
       // clear update queue after update finished
       this._updateQueue = this._updateQueue.filter(
         (item) => !queue.includes(item)
@@ -110,35 +141,106 @@ export class HmrEngine {
         removed,
         immutableModules,
         mutableModules,
-        boundaries
+        cssUpdates,
+        boundaries,
+        acceptedDeps,
+        hmrFullReload
       } = result;
-      const resultStr = `{
-        added: [${formatHmrResult(added)}],
-        changed: [${formatHmrResult(changed)}],
-        removed: [${formatHmrResult(removed)}],
-        immutableModules: ${JSON.stringify(immutableModules.trim())},
-        mutableModules: ${JSON.stringify(mutableModules.trim())},
-        boundaries: ${JSON.stringify(boundaries)},
-        dynamicResources: ${JSON.stringify(dynamicResources)},
-        dynamicModuleResourcesMap: ${JSON.stringify(dynamicModuleResourcesMap)}
-      }`;
+      // bytes of the code sent to the client, shown in the update panel of the client
+      const size = [
+        immutableModules,
+        mutableModules,
+        ...Object.values(cssUpdates ?? {})
+      ].reduce((total, code) => total + Buffer.byteLength(code), 0);
+      // the payload is JSON, only the module code is evaluated by the hmr client
+      const updatePayload = JSON.stringify({
+        type: 'farm-update',
+        result: {
+          added,
+          changed,
+          removed,
+          immutableModules: immutableModules.trim(),
+          mutableModules: mutableModules.trim(),
+          cssUpdates: cssUpdates ?? {},
+          boundaries,
+          acceptedDeps: acceptedDeps ?? {},
+          fullReload: hmrFullReload ?? null,
+          dynamicResources,
+          dynamicModuleResourcesMap,
+          timestamp,
+          stats: { size, duration }
+        }
+      });
 
       this.callUpdates(result);
 
       this._devServer.ws.clients.forEach((client: WebSocketClient) => {
-        client.rawSend(`
-        {
-          type: 'farm-update',
-          result: ${resultStr}
-        }
-      `);
+        client.rawSend(updatePayload);
       });
+
+      // resources of the removed modules are not served any more
+      if (result.removedResources?.length > 0) {
+        const prunePayload = JSON.stringify({
+          type: 'farm-prune-resources',
+          resources: result.removedResources
+        });
+        this._devServer.ws.clients.forEach((client: WebSocketClient) => {
+          client.rawSend(prunePayload);
+        });
+      }
+
+      // html entries are regenerated when their injected resources change, reload the page to apply them
+      if (result.updatedHtmlResources?.length > 0) {
+        const reloadPayload = JSON.stringify({ type: 'full-reload' });
+        this._devServer.ws.clients.forEach((client: WebSocketClient) => {
+          client.rawSend(reloadPayload);
+        });
+      }
+
+      // workers are not hot updated, the client decides how to restart them
+      if (result.updatedWorkers?.length > 0) {
+        const workerPayload = JSON.stringify({
+          type: 'worker-update',
+          workers: result.updatedWorkers
+        });
+        this._devServer.ws.clients.forEach((client: WebSocketClient) => {
+          client.rawSend(workerPayload);
+        });
+      }
     } catch (err) {
       checkClearScreen(this._compiler.config.config);
       throw new Error(logError(err) as unknown as string);
     }
   };
 
+  /**
+   * Recompile the module like the file is changed, useful when the output of the module depends on external state
+   */
+  async invalidateModule(
+    moduleId: string,
+    options?: InvalidateModuleOptions
+  ) {
+    this._compiler.invalidateModule(moduleId, options);
+    this.queueInvalidatedModules();
+
+    if (this.canUpdate() && this._updateQueue.length > 0) {
+      await this.recompileAndSendResult();
+    }
+  }
+
+  // interactive updates do not wait for the running background update, e.g. lazy compilation
+  private canUpdate() {
+    return !this._compiler.compiling || this._compiler.canPreemptUpdate;
+  }
+
+  private queueInvalidatedModules() {
+    for (const path of this._compiler.invalidatedModules()) {
+      if (!this._updateQueue.includes(path)) {
+        this._updateQueue.push(path);
+      }
+    }
+  }
+
   async hmrUpdate(absPath: string | string[], force = false) {
     const paths = Array.isArray(absPath) ? absPath : [absPath];
 
@@ -158,23 +260,25 @@ export class HmrEngine {
       }
     }
 
-    if (!this._compiler.compiling && this._updateQueue.length > 0) {
+    if (this.canUpdate() && this._updateQueue.length > 0) {
       try {
         await this.recompileAndSendResult();
       } catch (e) {
         // eslint-disable-next-line no-control-regex
         const serialization = e.message.replace(/\x1b\[[0-9;]*m/g, '');
-        const errorStr = `${JSON.stringify({
-          message: serialization
-        })}`;
+        // the paths of the overlay are opened by the editor of the host
+        const errorPayload = JSON.stringify({
+          type: 'error',
+          err: {
+            message: toHostPaths(
+              this._compiler.config.config.pathMappings,
+              serialization
+            )
+          },
+          overlay: this._devServer.config.hmr.overlay
+        });
         this._devServer.ws.clients.forEach((client: WebSocketClient) => {
-          client.rawSend(`
-            {
-              type: 'error',
-              err: ${errorStr},
-              overlay: ${this._devServer.config.hmr.overlay}
-            }
-          `);
+          client.rawSend(errorPayload);
         });
         this._logger.error(e);
       }
@@ -182,6 +286,17 @@ export class HmrEngine {
   }
 }
 
-function formatHmrResult(array: string[]) {
-  return array.map((item) => `'${item.replaceAll('\\', '\\\\')}'`).join(', ');
+/**
+ * `src/a.ts is not accepted: src/a.ts -> src/main.ts reaches the entry src/main.ts`
+ */
+export function formatHmrFullReload(fullReload: JsHmrFullReload): string {
+  const last = fullReload.path[fullReload.path.length - 1];
+  const reason =
+    fullReload.reason === 'entry'
+      ? `reaches the entry ${last}`
+      : `is imported by ${last}, which is not a script module`;
+
+  return `${fullReload.module} is not accepted: ${fullReload.path.join(
+    ' -> '
+  )} ${reason}`;
 }
@@ -5,6 +5,7 @@ import Koa from 'koa';
 import compression from 'koa-compress';
 
 import path from 'node:path';
+import type { Duplex } from 'node:stream';
 import { promisify } from 'node:util';
 import { Compiler } from '../compiler/index.js';
 import { __FARM_GLOBAL__ } from '../config/_global.js';
@@ -23,10 +24,13 @@ import {
 import { resolveHostname, resolveServerUrls } from '../utils/http.js';
 import {
   Logger,
+  RawSourceMap,
   bootstrap,
   clearScreen,
+  mapStackTrace,
   normalizeBasePath,
-  printServerUrls
+  printServerUrls,
+  toHostPaths
 } from '../utils/index.js';
 import { FileWatcher } from '../watcher/index.js';
 import { logError } from './error.js';
@@ -36,13 +40,30 @@ import {
   cors,
   headers,
   lazyCompilation,
+  metrics,
+  openInEditor,
   proxy,
   resources,
   staticMiddleware
 } from './middlewares/index.js';
 import { openBrowser } from './open.js';
 import { Server as httpServer } from './type.js';
-import WsServer from './ws.js';
+import WsServer, { WebSocketClient } from './ws.js';
+
+interface ExecutionTraceItem {
+  id: string;
+  order: number;
+  duration: number;
+  selfDuration: number;
+}
+
+const EXECUTION_TRACE_REPORT_SIZE = 10;
+
+interface ModuleErrorReport {
+  id: string;
+  message: string;
+  stack?: string;
+}
 
 /**
  * Farm Dev Server, responsible for:
@@ -70,11 +91,25 @@ interface ImplDevServer {
   getCompiler(): Compiler;
 }
 
+/**
+ * Connect style middleware, e.g. `app.use(server.middlewares)` of Express or Connect
+ */
+export type ConnectMiddleware = (
+  req: http.IncomingMessage,
+  res: http.ServerResponse,
+  next?: (err?: unknown) => void
+) => void;
+
 export class Server implements ImplDevServer {
   private _app: Koa;
   private restart_promise: Promise<void> | null = null;
   private compiler: Compiler | null;
+  private _middlewares?: ConnectMiddleware;
+  // the responses that no middleware handles in middleware mode, passed to the host server
+  private unhandledResponses = new WeakSet<http.ServerResponse>();
   public logger: Logger;
+  // the middlewares are mounted into an existing server, see `createMiddlewareServer`
+  public middlewareMode = false;
 
   ws: WsServer;
   config: NormalizedServerConfig & UserPreviewServerConfig;
@@ -147,7 +182,7 @@ export class Server implements ImplDevServer {
     }
   }
 
-  private async compile(): Promise<void> {
+  async compile(): Promise<void> {
     try {
       await this.compiler.compile();
     } catch (err) {
@@ -188,6 +223,12 @@ export class Server implements ImplDevServer {
   }
 
   async close() {
+    // the host server is closed by its owner
+    if (this.middlewareMode) {
+      await this.ws?.close();
+      return;
+    }
+
     if (!this.server) {
       this.logger.error('HTTP server is not created yet');
     }
@@ -218,7 +259,7 @@ export class Server implements ImplDevServer {
     this._app = new Koa();
   }
 
-  public async createServer(
+  private async resolveServerConfig(
     options: NormalizedServerConfig & UserPreviewServerConfig
   ) {
     const { https, host } = options;
@@ -243,6 +284,13 @@ export class Server implements ImplDevServer {
       protocol,
       hostname
     };
+  }
+
+  public async createServer(
+    options: NormalizedServerConfig & UserPreviewServerConfig
+  ) {
+    const { https } = options;
+    await this.resolveServerConfig(options);
 
     const isProxy = Object.keys(options.proxy).length;
     if (https) {
@@ -264,12 +312,49 @@ export class Server implements ImplDevServer {
   }
 
   public createWebSocket() {
-    if (!this.server) {
+    if (!this.server && !this.middlewareMode) {
       throw new Error('Websocket requires a server.');
     }
     this.ws = new WsServer(this.server, this.config, this.hmrEngine);
   }
 
+  /**
+   * Upgrade the hmr websocket requests of the host server in middleware mode, needed if no http server is
+   * passed to `createMiddlewareServer`, e.g. `httpServer.on('upgrade', server.handleUpgrade)`
+   */
+  handleUpgrade = (
+    req: http.IncomingMessage,
+    socket: Duplex,
+    head: Buffer
+  ) => {
+    this.ws?.upgradeWsServer(req, socket, head);
+  };
+
+  /**
+   * The dev middlewares for the host server in middleware mode. The requests that no middleware
+   * responds to are passed to `next`
+   */
+  get middlewares(): ConnectMiddleware {
+    if (!this._middlewares) {
+      const callback = this._app.callback();
+
+      this._middlewares = (req, res, next) => {
+        callback(req, res).then(() => {
+          if (!this.unhandledResponses.has(res)) return;
+
+          if (next) {
+            next();
+          } else {
+            res.statusCode = 404;
+            res.end();
+          }
+        }, next);
+      };
+    }
+
+    return this._middlewares;
+  }
+
   private invalidateVite() {
     // Note: path should be Farm's id, which is a relative path in dev mode,
     // but in vite, it's a url path like /xxx/xxx.js
@@ -281,6 +366,93 @@ export class Server implements ImplDevServer {
     });
   }
 
+  private reportExecutionTrace() {
+    // sent by the hmr runtime plugin, see `server.hmr.executionTrace`
+    this.ws.on(
+      'farm:execution-trace',
+      ({ modules }: { modules: ExecutionTraceItem[] }) => {
+        const slowest = [...modules]
+          .sort((a, b) => b.selfDuration - a.selfDuration)
+          .slice(0, EXECUTION_TRACE_REPORT_SIZE)
+          .map((m) => {
+            const self = m.selfDuration.toFixed(1);
+            const total = m.duration.toFixed(1);
+            return `  ${self}ms (${total}ms total) #${m.order} ${m.id}`;
+          });
+
+        this.logger.info(
+          `Executed ${modules.length} modules, the slowest modules:\n` +
+            slowest.join('\n')
+        );
+      }
+    );
+  }
+
+  // custom events between the plugins and `import.meta.hot` of the clients
+  private bridgeHmrEvents() {
+    this.compiler.onHmrEvent(({ event, data }) => this.ws.send(event, data));
+    this.ws.onAnyCustomEvent((event, data) => {
+      try {
+        this.compiler.hmrEvent(event, data);
+      } catch (e) {
+        this.logger.error(`Failed to handle hmr event ${event}: ${e}`);
+      }
+    });
+  }
+
+  private reportModuleErrors() {
+    // sent by the hmr runtime plugin when a module throws during execution
+    this.ws.on(
+      'farm:module-error',
+      (
+        { id, message, stack }: ModuleErrorReport,
+        client: WebSocketClient
+      ) => {
+        const originalStack = mapStackTrace(stack ?? message, (file) =>
+          this.resourceSourceMap(file)
+        );
+        const mappedStack = toHostPaths(
+          this.compiler.config.config.pathMappings,
+          originalStack
+        );
+
+        this.logger.error(`Failed to execute module ${id}:\n${mappedStack}`);
+        client.send({
+          type: 'error',
+          err: { id, message, stack: mappedStack },
+          overlay: this.config.hmr?.overlay
+        });
+      }
+    );
+  }
+
+  // the source map of the resource that the url of a stack frame points to
+  private resourceSourceMap(url: string): RawSourceMap | undefined {
+    let pathname: string;
+
+    try {
+      pathname = decodeURIComponent(new URL(url).pathname);
+    } catch {
+      return;
+    }
+
+    const publicPath = getValidPublicPath(this.publicPath) || '/';
+    const resourceName = pathname.startsWith(publicPath)
+      ? pathname.slice(publicPath.length)
+      : pathname.slice(1);
+    const sourceMap = this.compiler.resource(`${resourceName}.map`);
+
+    if (!sourceMap) {
+      return;
+    }
+
+    try {
+      return JSON.parse(sourceMap.toString());
+    } catch {
+      return;
+    }
+  }
+
   public async createPreviewServer(options: UserPreviewServerConfig) {
     await this.createServer(options as NormalizedServerConfig);
 
@@ -304,6 +476,57 @@ export class Server implements ImplDevServer {
 
     this.invalidateVite();
 
+    if (this.config.hmr?.executionTrace) {
+      this.reportExecutionTrace();
+    }
+
+    this.reportModuleErrors();
+
+    this.bridgeHmrEvents();
+
+    this.applyServerMiddlewares(options.middlewares);
+  }
+
+  /**
+   * Create the dev middlewares and the hmr websocket without starting an http server, so the dev pipeline
+   * can be mounted into an existing server. The hmr websocket handles the upgrade requests of `httpServer`,
+   * `server.port` or `server.hmr.port` should be the port of the host server so the hmr client can connect to it
+   */
+  public async createMiddlewareServer(
+    options: NormalizedServerConfig,
+    httpServer?: httpServer
+  ) {
+    if (!this.compiler) {
+      throw new Error('DevServer requires a compiler for development mode.');
+    }
+
+    this.middlewareMode = true;
+    this.server = httpServer;
+    await this.resolveServerConfig(options);
+
+    this.hmrEngine = new HmrEngine(this.compiler, this, this.logger);
+
+    this.createWebSocket();
+
+    this.invalidateVite();
+
+    if (this.config.hmr?.executionTrace) {
+      this.reportExecutionTrace();
+    }
+
+    this.reportModuleErrors();
+
+    this.bridgeHmrEvents();
+
+    this._app.use(async (ctx, next) => {
+      await next();
+
+      if (ctx.status === 404 && ctx.body == null) {
+        ctx.respond = false;
+        this.unhandledResponses.add(ctx.res);
+      }
+    });
+
     this.applyServerMiddlewares(options.middlewares);
   }
 
@@ -404,6 +627,8 @@ export class Server implements ImplDevServer {
     const internalMiddlewares = [
       ...(middlewares || []),
       hmrPing,
+      metrics,
+      openInEditor,
       headers,
       lazyCompilation,
       cors,
@@ -1,6 +1,8 @@
 export * from './cors.js';
 export * from './headers.js';
 export * from './lazy-compilation.js';
+export * from './metrics.js';
+export * from './open-in-editor.js';
 export * from './proxy.js';
 export * from './resources.js';
 export * from './static.js';
@@ -50,8 +50,8 @@ export function lazyCompilation(devSeverContext: Server): Middleware {
       // sync update when node is true
       let result;
       try {
-        // sync regenerate resources
-        result = await compiler.update(paths, true, false, false);
+        // sync regenerate resources, hmr updates preempt lazy compilation
+        result = await compiler.update(paths, true, false, false, 'background');
       } catch (e) {
         logError(e);
       }
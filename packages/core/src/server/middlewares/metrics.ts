@@ -0,0 +1,22 @@
+/**
+ * Serve the counters and histograms of the compiler in the Prometheus text format at `/__farm_metrics`,
+ * so the dev servers can be monitored centrally. Enabled by `server.metrics`
+ */
+
+import { Context, Middleware, Next } from 'koa';
+
+import { Server } from '../index.js';
+
+export function metrics(devSeverContext: Server): Middleware {
+  if (!devSeverContext.config.metrics) return;
+
+  return async (ctx: Context, next: Next) => {
+    if (ctx.method !== 'GET' || !ctx.path.endsWith('/__farm_metrics')) {
+      await next();
+      return;
+    }
+
+    ctx.type = 'text/plain; version=0.0.4; charset=utf-8';
+    ctx.body = devSeverContext.getCompiler().metrics();
+  };
+}
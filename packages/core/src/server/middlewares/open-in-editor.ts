@@ -0,0 +1,51 @@
+/**
+ * Open the file linked by the error overlay in the editor, `/__open-in-editor?file=src/index.ts:1:2`.
+ * The overlay shows the host paths, they are mapped back to the paths seen by the editor launched by the server
+ */
+
+import path from 'node:path';
+import { execa } from 'execa';
+import { Context, Middleware, Next } from 'koa';
+
+import { toCompilerPath } from '../../utils/index.js';
+import { Server } from '../index.js';
+
+// editors that open `file:line:column` with `-g`
+const GOTO_EDITORS = ['code', 'code-insiders', 'codium', 'cursor'];
+
+export function openInEditor(devSeverContext: Server): Middleware {
+  return async (ctx: Context, next: Next) => {
+    if (!ctx.path.endsWith('/__open-in-editor')) {
+      await next();
+      return;
+    }
+
+    const file = ctx.query.file as string | undefined;
+
+    if (!file) {
+      ctx.status = 400;
+      ctx.body = 'missing file';
+      return;
+    }
+
+    const compiler = devSeverContext.getCompiler();
+    const compilerPath = toCompilerPath(
+      compiler.config.config.pathMappings,
+      path.isAbsolute(file)
+        ? file
+        : path.join(compiler.config.config.root, file)
+    );
+    const editor = process.env.LAUNCH_EDITOR || process.env.EDITOR || 'code';
+    const args = GOTO_EDITORS.includes(path.basename(editor))
+      ? ['-g', compilerPath]
+      : [compilerPath.replace(/(:\d+)+$/, '')];
+
+    // the editor may keep running, e.g. a terminal editor
+    execa(editor, args, { detached: true, stdio: 'ignore' }).catch((e) => {
+      devSeverContext.logger.error(
+        `Failed to open ${compilerPath} in ${editor}: ${e.message}`
+      );
+    });
+    ctx.status = 204;
+  };
+}
@@ -2,7 +2,13 @@
  * Serve resources that stored in memory. This middleware will be enabled when server.writeToDisk is false.
  */
 
-import { ReadStream, existsSync, readFileSync, statSync } from 'node:fs';
+import {
+  ReadStream,
+  createReadStream,
+  existsSync,
+  readFileSync,
+  statSync
+} from 'node:fs';
 import path, { extname } from 'node:path';
 import { Context, Middleware, Next } from 'koa';
 import koaStatic from 'koa-static';
@@ -17,7 +23,7 @@ import { Server } from '../index.js';
 interface RealResourcePath {
   resourcePath: string;
   rawPath: string;
-  resource: Buffer;
+  resource: Buffer | ReadStream;
 }
 
 function normalizePathByPublicPath(publicPath: string, resourcePath: string) {
@@ -56,7 +62,11 @@ function findResource(
       resourcePath
     );
 
-    const resource = compiler.resource(resourceWithoutPublicPath);
+    const sourcePath = compiler.resourceSourcePath(resourceWithoutPublicPath);
+    // large assets are not loaded into memory, serve them from the source file
+    const resource = sourcePath
+      ? createReadStream(sourcePath)
+      : compiler.resource(resourceWithoutPublicPath);
 
     if (resource) {
       return {
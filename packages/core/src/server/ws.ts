@@ -42,11 +42,16 @@ export interface WebSocketClient {
 export default class WsServer implements IWebSocketServer {
   public wss: WebSocketServerRaw;
   public customListeners = new Map<string, Set<WebSocketCustomListener<any>>>();
+  // listeners of all the custom events, e.g. the bridge to the rust plugins
+  public anyCustomListeners = new Set<
+    (event: string, data: any, client: WebSocketClient) => void
+  >();
   public clientsMap = new WeakMap<WebSocketRawType, WebSocketClient>();
   public bufferedError: any = null;
   public logger: ILogger;
   constructor(
-    private httpServer: Server,
+    // undefined in middleware mode if the host server forwards the upgrade requests by `handleUpgrade`
+    private httpServer: Server | undefined,
     private config: NormalizedServerConfig,
     private hmrEngine: HmrEngine,
     logger?: ILogger
@@ -63,22 +68,21 @@ export default class WsServer implements IWebSocketServer {
         : WebSocketServerRaw;
       this.wss = new WebSocketServer({ noServer: true });
       this.connection();
-      // TODO IF not have httpServer
-      this.httpServer.on('upgrade', this.upgradeWsServer.bind(this));
+      this.httpServer?.on('upgrade', this.upgradeWsServer);
     } catch (err) {
       this.handleSocketError(err);
     }
   }
 
-  private upgradeWsServer(
+  public upgradeWsServer = (
     request: IncomingMessage,
     socket: Duplex,
     head: Buffer
-  ) {
+  ) => {
     if (this.isHMRRequest(request)) {
       this.handleHMRUpgrade(request, socket, head);
     }
-  }
+  };
 
   listen() {
     // TODO alone with use httpServer we need start this function
@@ -150,6 +154,13 @@ export default class WsServer implements IWebSocketServer {
     }
   }
 
+  // listen to all the custom events sent by the clients
+  public onAnyCustomEvent(
+    listener: (event: string, data: any, client: WebSocketClient) => void
+  ) {
+    this.anyCustomListeners.add(listener);
+  }
+
   public off(event: string, listener: () => void) {
     if (wsServerEvents.includes(event)) {
       this.wss.off(event, listener);
@@ -161,7 +172,9 @@ export default class WsServer implements IWebSocketServer {
   connection() {
     this.wss.on('connection', (socket: WebSocketRawType) => {
       socket.on('message', (raw) => {
-        if (!this.customListeners.size) return;
+        if (!this.customListeners.size && !this.anyCustomListeners.size) {
+          return;
+        }
         let parsed: any;
         try {
           parsed = JSON.parse(String(raw));
@@ -175,9 +188,12 @@ export default class WsServer implements IWebSocketServer {
         }
 
         if (!parsed || parsed.type !== 'custom' || !parsed.event) return;
+        const client = this.getSocketClient(socket);
+        this.anyCustomListeners.forEach((listener) =>
+          listener(parsed.event, parsed.data, client)
+        );
         const listeners = this.customListeners.get(parsed.event);
         if (!listeners?.size) return;
-        const client = this.getSocketClient(socket);
         listeners.forEach((listener) => listener(parsed.data, client));
       });
 
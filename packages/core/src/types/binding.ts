@@ -94,6 +94,14 @@ export interface PluginTransformHookResult {
   ignorePreviousSourceMap?: boolean;
 }
 
+export type LogLevel =
+  | 'silent'
+  | 'error'
+  | 'warn'
+  | 'info'
+  | 'debug'
+  | 'trace';
+
 type BrowserTargetsRecord = Partial<
   Record<
     | 'chrome'
@@ -128,6 +136,10 @@ export interface OutputConfig {
    * then the url output files in html will be `https://xxx.cdn.com/index_ecad.xxxx.js`
    *
    * default by `output.targetEnv`, if node, publicPath is `./`, if browser, publicPath is `/`
+   *
+   * set to `'runtime'` to resolve it when the resources are loaded: asset urls in scripts are prefixed with
+   * `globalThis.__farm_public_path__`, which defaults to the directory of the html document and can be set
+   * before the entry is loaded, e.g. `window.__farm_public_path__ = '/v2/'`. Urls in css and html are relative.
    */
   publicPath?: string;
   /**
@@ -160,6 +172,118 @@ export interface OutputConfig {
    * clean output.path automatically or not
    */
   clean?: boolean;
+  /**
+   * Keep the files emitted by the last N builds when cleaning output.path, recorded in `output.path/.farm-retention.json`.
+   * Pages served by a previous deploy can still load their lazy chunks while the new deploy rolls out.
+   * @default 0
+   */
+  retainBuilds?: number;
+  /**
+   * Emit a variant of the resources for each deployment target, e.g. per tenant, sharing all compilation work.
+   * `__FARM_VARIANT_<KEY>__` placeholders in the resources (including `publicPath`) are replaced by the variables of the variant when writing.
+   */
+  variants?: OutputVariantConfig[];
+  /**
+   * Code injected into the rendered resource pots, e.g. copyright banners
+   */
+  injections?: ResourcePotInjectionConfig[];
+  /**
+   * Regexes that match the names of the high priority resources, e.g. the chunk of the landing page.
+   * They are injected with `fetchpriority="high"` and preloaded first, and dynamic imported modules that have high priority resources are loaded when the browser is idle.
+   * Plugins can also mark a resource pot as high priority by setting `fetchpriority` to `high` in the custom info of the resource pot.
+   */
+  highPriority?: string[];
+  /**
+   * Emit classic scripts that only contain ES5 syntax for old embedded webviews, only works in production.
+   * All modules including the runtime are downgraded to es5, the format falls back to `cjs`, and the build fails if any emitted js is not ES5.
+   * @default false
+   */
+  es5?: boolean;
+  /**
+   * The app is served cross origin isolated. A `workers-manifest.json` that lists the worker resources and
+   * the `Cross-Origin-Opener-Policy`/`Cross-Origin-Embedder-Policy` headers required by hosting is emitted.
+   * @default false
+   */
+  crossOriginIsolated?: boolean;
+  /**
+   * Extract the license comments (`@license`, `@preserve`, `/*!`) of the modules in each js resource to a sibling `<resource>.LICENSE.txt`.
+   * The license comments are stripped from the minified output.
+   * @default false
+   */
+  extractLicenseComments?: boolean;
+  /**
+   * Emit the js resources as native ES modules that import and export each other instead of registering their modules to the runtime.
+   * The runtime is not emitted and html entries load the resources by `<script type="module">`, hmr is not supported.
+   * Requires `format: 'esm'`, other formats are rejected. Always enabled when `targetEnv` is `library`.
+   * @default false
+   */
+  nativeEsm?: boolean;
+  /**
+   * The `#!` line of the entries of node targets. `preserve` keeps the shebang of the entry module, `remove` never emits one,
+   * other strings are inserted into the entries whose entry module has no shebang, e.g. `#!/usr/bin/env node`.
+   * Files starting with a shebang are written with executable permissions.
+   * @default 'preserve'
+   */
+  shebang?: string;
+  /**
+   * Emit the entries of node targets as extension-less executables for cli tools, e.g. `dist/cli` instead of `dist/cli.js`.
+   * `#!/usr/bin/env node` is inserted into the entries without a shebang
+   * @default false
+   */
+  bin?: boolean;
+}
+
+export interface OutputVariantConfig {
+  /**
+   * Name of the variant, resources of the variant are written to `output.path/<name>` by default
+   */
+  name: string;
+  /**
+   * Output dir of the variant, relative to root
+   */
+  path?: string;
+  /**
+   * Values of the placeholders, `__FARM_VARIANT_API_BASE__` is replaced by the value of `API_BASE`
+   */
+  variables: Record<string, string>;
+  /**
+   * The assets of this target are referenced and written, see `assets.targets`
+   * @default the first asset target
+   */
+  assetTarget?: string;
+}
+
+export interface ResourcePotInjectionConfig {
+  /**
+   * Types of the matched resource pots, e.g. `js`, all types if empty
+   */
+  resourcePotTypes?: ('js' | 'css' | 'html' | 'runtime' | string)[];
+  /**
+   * Regex that matches the name of the resource pot, all resource pots if not set
+   */
+  name?: string;
+  /**
+   * Prepended to the resource pot after it is optimized, comments in the banner are preserved
+   */
+  banner?: string;
+  /**
+   * Prepended to the resource pot before it is optimized
+   */
+  intro?: string;
+  /**
+   * Appended to the resource pot after it is optimized
+   */
+  footer?: string;
+}
+
+export interface AliasEntry {
+  /** prefix of the source, the source itself if it ends with `$`, or a regex */
+  find: string | RegExp;
+  /**
+   * `$1` in the replacement is replaced by the first capture group of a regex `find`.
+   * Function replacements receive the source and the capture groups, they are matched before the other aliases
+   */
+  replacement: string | ((source: string, ...groups: string[]) => string);
 }
 
 export interface ResolveConfig {
@@ -170,8 +294,15 @@ export interface ResolveConfig {
   extensions?: string[];
   /**
    * Configure parsing alias. Alias is prefix replacement, for example /@/pages/index will be replaced by /root/src/pages/index. If you want an exact match, you can add $, for example stream$ will only replace stream, but not stream/xxx.
+   * The object form is matched from the longest alias to the shortest, the array form is matched in the declaration order and supports regex `find` and function `replacement`.
+   * The first alias whose replaced source is resolved wins, run with `FARM_LOG=debug` to see which alias matched
    */
-  alias?: Record<string, string>;
+  alias?: Record<string, string> | AliasEntry[];
+  /**
+   * Aliases matched in the declaration order before `alias`, normalized from the array form of `alias`.
+   * `find` prefixed with `$__farm_regex:` is a regex whose capture groups are referenced by `$1` in `replacement`
+   */
+  aliasEntries?: { find: string; replacement: string }[];
   /**
    * When parsing dependencies under node_modules, the fields and order configured in mainFields will be parsed from package.json. For package.json
    * @default ["exports", "browser", "module", "main"]
@@ -201,6 +332,35 @@ export interface ResolveConfig {
    * @default []
    */
   dedupe?: string[];
+  /**
+   * How `node:` prefixed imports like `import path from 'node:path'` are handled when the target env is browser
+   */
+  nodePrefix?: NodePrefixConfig;
+}
+
+/**
+ * - `external`: keep the import as external
+ * - `error`: fail the build and report the import chains of the import
+ * - `empty`: replace the builtin with an empty module
+ * - `{ polyfill: 'path-browserify' }`: resolve the builtin to the polyfill package
+ */
+export type NodePrefixAction =
+  | 'external'
+  | 'error'
+  | 'empty'
+  | { polyfill: string };
+
+export interface NodePrefixConfig {
+  /**
+   * Action of the builtins that are not configured in `builtins`
+   * @default 'external'
+   */
+  default?: NodePrefixAction;
+  /**
+   * Builtin name without the prefix -> action, e.g. `{ path: { polyfill: 'path-browserify' } }`.
+   * A subpath like `fs/promises` falls back to the action of `fs`
+   */
+  builtins?: Record<string, NodePrefixAction>;
 }
 
 export interface RuntimeConfig {
@@ -218,6 +378,11 @@ export interface RuntimeConfig {
    * Note: It's not recommended to set this options
    */
   swcHelpersPath?: string;
+  /**
+   * Bundle the swc helpers injected by the syntax lowering and module system transforms into one resource with this name,
+   * so they are shared by all resources instead of bundled with the modules using them. The unused helpers are still tree shaken
+   */
+  helpersResource?: string;
   /**
    * Configure the namespace of Farm Runtime to ensure that the execution of different products under the same window or global can be isolated from each other.
    * By default, the name field of the project package.json is used as the namespace.
@@ -228,6 +393,44 @@ export interface RuntimeConfig {
    * If set to true, the farm entry script will be emitted as a separate file.
    */
   isolate?: boolean;
+  /**
+   * Replace individual runtime modules. The key is the import source used in the runtime modules or the absolute path of the runtime module,
+   * the value is the absolute path of the replacement module.
+   */
+  alias?: Record<string, string>;
+  /**
+   * Retry the dynamic resources that are failed to load, e.g. chunks served by an unstable CDN.
+   * A failed resource is retried with backoff before falling back to the next public path.
+   * Runtime plugins can handle the final failure in the `loadResourceFailed` hook.
+   */
+  retry?: {
+    /**
+     * How many times a failed resource is retried, 0 means no retry
+     * @default 0
+     */
+    count?: number;
+    /**
+     * Delay in milliseconds before the first retry, doubled after each retry
+     * @default 500
+     */
+    delay?: number;
+    /**
+     * Append a timestamp query to the url of the retried resource, so a cached failed response is not reused
+     * @default true
+     */
+    cacheBusting?: boolean;
+  };
+  /**
+   * Create the script urls loaded by the runtime, the module code evaluated by the hmr client and the html of the hmr overlays
+   * through a Trusted Types policy, so apps enforcing `require-trusted-types-for 'script'` run without violations.
+   * The policy name should be allowed by the `trusted-types` directive of the content security policy.
+   */
+  trustedTypes?: {
+    /**
+     * @default 'farm'
+     */
+    policyName?: string;
+  };
 }
 
 export interface ScriptConfig {
@@ -300,7 +503,14 @@ export interface CssConfig {
     // defaults to `.module.css` or `.module.scss` or `.module.less`
     paths?: string[];
     // configure the generated css class name, the default is `[name]-[hash]`
+    // supported placeholders: `[name]`/`[local]` (class name), `[file]`, `[package]`, `[hash]`/`[hash:N]`
     indentName?: string;
+    /**
+     * What `[hash]` is computed from, the path relative to root or the content of the file.
+     * Both are stable across machines
+     * @default 'path'
+     */
+    hashStrategy?: 'path' | 'content';
     /**
      *
      * - `asIs` - Do not convert the local variable name
@@ -331,6 +541,12 @@ export interface PersistentCacheConfig {
   moduleCacheKeyStrategy?: {
     timestamp?: boolean;
     hash?: boolean;
+    /**
+     * Key the cached modules by the export signature of their script watch dependencies instead of the content,
+     * so edits that don't change the exports of a dependency don't invalidate the modules watching it
+     * @default true
+     */
+    exportSignature?: boolean;
   };
   envs?: Record<string, string>;
   /**
@@ -343,7 +559,10 @@ export interface PersistentCacheConfig {
    * }
    */
   globalBuiltinCacheKeyStrategy?: {
-    /** @default true */
+    /**
+     * Invalidate the whole cache when a define changes. By default only the modules using the changed defines are rebuilt
+     * @default false
+     */
     define?: boolean;
     /** @default true */
     buildDependencies?: boolean;
@@ -351,9 +570,22 @@ export interface PersistentCacheConfig {
     lockfile?: boolean;
     /** @default true */
     packageJson?: boolean;
-    /** @default true */
+    /**
+     * Invalidate the whole cache when an env changes. By default only the modules using the changed envs are rebuilt
+     * @default false
+     */
     env?: boolean;
   };
+  /**
+   * Share the transformed node_modules with other projects on this machine.
+   * Packages are keyed by name, version, location and the transform options, run `farm clean --global` to remove the least recently used packages
+   */
+  globalCache?: {
+    /** @default '~/.farm/global-cache' */
+    dir?: string;
+    /** Max size of the global cache in bytes, @default 1073741824 */
+    maxSize?: number;
+  };
 }
 
 export interface PartialBundlingConfig {
@@ -401,6 +633,21 @@ export interface PartialBundlingConfig {
    * @default ["node_modules"]
    */
   immutableModules?: string[];
+  /**
+   * Route immutable modules into `vendor-permissive`, `vendor-copyleft` and `vendor-unknown` resources by the license of their packages, and emit a `{name}.licenses.json` report for each of them.
+   * @default false
+   */
+  licenseGroups?: boolean;
+  /**
+   * Regex array to match the names of giant packages, e.g. `echarts` or `@ant-design/icons`. The modules of the matched packages are split by the top-level subpath they are imported through, e.g. `@ant-design/icons/UserOutlined`, and each subpath is placed in its own resource, so importing one subpath doesn't pull the whole package.
+   * @default []
+   */
+  splitPackages?: string[];
+  /**
+   * Max depth of the dynamic imports that get their own resources, the entries are at depth 0 and their dynamic imports at depth 1. The modules of deeper dynamic imports are placed in the resources of their ancestor at this depth, e.g. `1` keeps the route-level chunks and folds the lazy components of the routes into them. The css of the folded dynamic imports is not folded, it's still emitted as its own resource and loaded before the dynamic import is executed.
+   * @default undefined, every dynamic import gets its own resources
+   */
+  maxAsyncDepth?: number;
 }
 
 export interface PresetEnvConfig {
@@ -432,24 +679,414 @@ export interface Config {
      * Global variable injection, the configured variable name and value will be injected into the product at compile time. Farm injects process.env.NODE_ENV and some variables used by Farm itself such as FARM_HMR_PORT by default
      */
     define?: Record<string, any>;
+    /**
+     * Defines overriding `define` for the modules of an entry, e.g. `{ admin: { ADMIN_BUILD: 'true' } }`.
+     * The modules shared by the entries are compiled once for each entry with overrides
+     */
+    entryDefine?: Record<string, Record<string, any>>;
     /**
      * Configure the imports that are external, and the imports that are external will not appear in the compiled product.
+     * `dependencies` externalizes the imports of the `dependencies`, `peerDependencies` and `optionalDependencies` of the package.json
+     * and `peerDependencies` externalizes the imports of the `peerDependencies`, the `devDependencies` are still bundled
      */
-    external?: (string | Record<string, string>)[];
+    external?:
+      | 'dependencies'
+      | 'peerDependencies'
+      | (string | Record<string, string>)[];
     externalNodeBuiltins?: boolean | string[];
     mode?: 'development' | 'production';
     root?: string;
+    /**
+     * Directories of first party sources outside the root, e.g. a design system shared in the monorepo, relative to the root.
+     * Their modules are mutable, transformed and watched like the modules under the root even if they are linked into node_modules
+     */
+    sourceRoots?: string[];
     runtime?: RuntimeConfig;
     watch?: boolean | WatchOptions;
     assets?: {
       include?: string[];
       publicDir?: string;
       mode?: 'node' | 'browser';
+      /**
+       * Assets larger than this size in bytes are copied from the source file to the output directory instead of being loaded into memory. `0` means always loading assets into memory.
+       * @default 10485760
+       */
+      streamThreshold?: number;
+      /**
+       * Import `.wasm` files with ESM integration, the default export is the exports object of the instance and the exports are named exports.
+       * When disabled, the default export is the url like other assets.
+       * @default false
+       */
+      wasmEsmIntegration?: boolean;
+      /**
+       * Binary files that are emitted beside the bundle for node target. `.node` files are loaded as native addons, others are resolved to their absolute path at runtime.
+       */
+      binary?: {
+        /**
+         * @default ['node', 'onnx']
+         */
+        include?: string[];
+        /**
+         * Whether to add the content hash to the emitted filename. The build fails if binary files of different directories have the same filename when it is false
+         * @default true
+         */
+        hash?: boolean;
+      };
+      /**
+       * Targets of the asset variants in production, e.g. `['web', 'webview']`. Each asset is transformed by the `transformAsset` hook
+       * once per target and emitted to `<target>/`, the output variant of a target references the assets of its target, see `output.variants[].assetTarget`.
+       */
+      targets?: string[];
+      /**
+       * Collect the svg icons imported with `?sprite` into a single svg sprite. The import returns `{ id, url }`, `url` is the sprite url with the symbol id as the fragment, e.g. `<use href={icon.url} />`.
+       */
+      sprite?: {
+        /**
+         * Name of the sprite resource, it's not content hashed.
+         * @default 'sprite.svg'
+         */
+        filename?: string;
+        /**
+         * The symbol id of an icon is the prefix followed by its filename, e.g. `icon-home`.
+         * @default 'icon-'
+         */
+        symbolIdPrefix?: string;
+      };
     };
     script?: ScriptConfig;
     css?: CssConfig;
+    /**
+     * File based routing. Routes can be imported from `virtual:farm-routes` and a route manifest is emitted
+     */
+    routes?: {
+      dirs?: string[];
+      extensions?: string[];
+      manifestFilename?: string;
+    };
+    /**
+     * Build time macros. Functions imported from macro modules (listed in `modules` or imported with `with { type: "macro" }`)
+     * are executed at build time in a sandboxed worker and the calls are replaced by their results
+     */
+    macros?: {
+      modules?: string[];
+    };
+    /**
+     * Hash function used for resource names, cache keys and resource pot ids, default is `sha256`.
+     * Changing `seed` changes all hashes, e.g. to bust the cache of all resources after a deployment issue.
+     * The `[hash]` of a resource name also changes when a resource referenced by it changes.
+     * Before `finalizeResources`, the hash of resource pot names is a placeholder like `~1a2b3c~`
+     */
+    hash?: {
+      algorithm?: 'xxh3' | 'blake3' | 'sha256';
+      seed?: string;
+      /**
+       * Length of `[hash]` in resource names, between 6 and 32
+       * @default 8
+       */
+      length?: number;
+    };
+    /**
+     * Modules imported before the code of every entry, e.g. `core-js/stable` or `./src/polyfills.ts`,
+     * so they are executed before all entries
+     */
+    polyfillEntries?: string[];
+    /**
+     * How the dependencies of a module are ordered when the execution order of the modules is computed, e.g. the order of the css.
+     * `importOrder` follows the import statements and sorts the dependencies of the same order by module id,
+     * `path` sorts the dependencies by module id so the order doesn't change when the transforms reorder the imports
+     * @default 'importOrder'
+     */
+    executionOrderTieBreak?: 'importOrder' | 'path';
+    /**
+     * Emit a json report of the generated resources for bundle analysis: the size and gzip size of every resource,
+     * the rendered size of its modules and the module graph edges
+     */
+    bundleStats?: {
+      /** @default 'bundle-stats.json' */
+      filename?: string;
+      /** @default true */
+      gzipSize?: boolean;
+      /**
+       * Include the exported names of each es module, e.g. to generate auto import indexes
+       * @default false
+       */
+      exports?: boolean;
+    };
+    /**
+     * Emit a json report of the exports of each module that are never imported by other modules.
+     * Exports only exposed by the entries are reported as `public`. The report is also included in `bundleStats`
+     */
+    unusedExports?: {
+      /** @default 'unused-exports.json' */
+      filename?: string;
+    };
+    /**
+     * Emit a json report and the third party notices of the licenses of the bundled packages.
+     * The build fails if a package has a license that is denied or not allowed
+     */
+    licenses?: {
+      /** @default 'licenses.json' */
+      filename?: string;
+      /** file name of the notices containing the license files, not emitted if empty */
+      notices?: string;
+      /** spdx license ids that fail the build, e.g. `GPL-3.0` */
+      deny?: string[];
+      /** the only spdx license ids that are allowed if not empty */
+      allow?: string[];
+    };
+    /**
+     * Fail the build and report the import chain when a server only module is imported by browser entries,
+     * or a client only module is imported by node entries.
+     * Modules can also mark themselves by importing `server-only` or `client-only`
+     */
+    moduleBoundaries?: {
+      /** regex of module ids, e.g. `src/server/` */
+      serverOnly?: string[];
+      /** regex of module ids */
+      clientOnly?: string[];
+    };
+    /**
+     * Build the dependencies once as a vendor reference, then link other builds against it instead of bundling them again.
+     * The inputs of a vendor reference build are the import sources it provides, e.g. `{ react: 'react' }`
+     */
+    vendorReference?: {
+      /** file name of the manifest emitted by a vendor reference build, e.g. `vendor-manifest.json` */
+      manifest?: string;
+      /** paths of the manifests of the vendor reference builds to link against, relative to the root */
+      references?: string[];
+      /**
+       * vendor reference builds consumed as externals providers, an import of a provided source loads the resources
+       * of the provider build at runtime instead of the html loading them
+       */
+      providers?: {
+        /** path of the manifest of the provider build, relative to the root */
+        manifest: string;
+        /** url the resources of the provider build are served from, defaults to the public path of the manifest */
+        publicPath?: string;
+        /** import source -> the import source provided by the manifest, e.g. `{ 'react-dom/client': 'react-dom' }` */
+        alias?: Record<string, string>;
+      }[];
+    };
+    /**
+     * Report the import cycles of the module graph, cycles usually show up as `undefined` exports at runtime
+     */
+    circularDependency?: {
+      /** `error` fails the build when a cycle is found, default `warn` */
+      severity?: 'warn' | 'error' | 'ignore';
+      /** only report the cycles that contain a module matching these patterns */
+      include?: string[];
+      /** do not report the cycles that contain a module matching these patterns */
+      exclude?: string[];
+      /** default `true` */
+      ignoreDynamicImports?: boolean;
+      /** ignore the cycles between modules of node_modules, default `true` */
+      ignoreNodeModules?: boolean;
+    };
+    /**
+     * Rules of the imports allowed between modules, e.g. modules under `src/ui/` must not import `src/server/`.
+     * An import is matched by the patterns if its source or the id of the imported module matches one of them
+     */
+    dependencyPolicy?: {
+      rules?: {
+        /** importers the rule applies to, all modules if empty */
+        from?: string[];
+        /** the imports that are not allowed */
+        deny?: string[];
+        /** the only imports that are allowed if not empty */
+        allow?: string[];
+        /** `error` fails the build when the rule is violated, default `error` */
+        severity?: 'warn' | 'error';
+        /** explanation appended to the diagnostic, e.g. `use lodash-es instead` */
+        message?: string;
+      }[];
+    };
+    /**
+     * Expose modules to other builds and load the modules exposed by remote builds at runtime.
+     * Remote modules should be imported dynamically, e.g. `import('app2/Button')`
+     */
+    federation?: {
+      /** name of the build, required to expose modules */
+      name?: string;
+      /** file name of the emitted manifest, default `federation-manifest.json` */
+      manifest?: string;
+      /** expose name -> path of the exposed module relative to the root, e.g. `{ './Button': './src/Button.tsx' }` */
+      exposes?: Record<string, string>;
+      /** remote name -> url of the manifest of the remote build */
+      remotes?: Record<string, string>;
+      /** import sources shared with the remote builds, e.g. `{ react: { singleton: true } }` */
+      shared?: Record<
+        string,
+        {
+          /** always use the module of the consumer, whatever the versions are */
+          singleton?: boolean;
+          /** semver range the version of the consumer should satisfy, defaults to the caret range of the own version */
+          requiredVersion?: string;
+        }
+      >;
+    };
+    /**
+     * Named boolean flags of the build, every flag is defined as `FARM_FLAGS.<name>`, e.g. `if (FARM_FLAGS.enterprise) { ... }`.
+     * The dead branches are removed and the modules only imported by them are reported per flag
+     */
+    flags?: {
+      /** flag name -> value */
+      values?: Record<string, boolean>;
+      /** file name of the emitted report, default `flags-report.json` */
+      report?: string;
+    };
+    /**
+     * Expose the build metadata as `import.meta.env.<KEY>` and `process.env.<KEY>`,
+     * the defines configured by `define` are not overridden
+     */
+    buildMeta?: {
+      /** `PACKAGE_NAME` and `PACKAGE_VERSION` of the package.json of the root, default `true` */
+      package?: boolean;
+      /** `GIT_COMMIT`, the sha of `HEAD`, default `true` */
+      gitCommit?: boolean;
+      /** `BUILD_TIME`, milliseconds since the unix epoch, default `true` */
+      buildTime?: boolean;
+      /** `MODE`, default `true` */
+      mode?: boolean;
+    };
+    /**
+     * Persist the module graph after the build stage, so a crashed or interrupted production build resumes
+     * at the generate stage instead of rebuilding the whole module graph.
+     * The checkpoint is discarded when any of the built files changed, and removed once the build finished
+     */
+    checkpoint?: {
+      /** directory of the checkpoints relative to the root, default `node_modules/.farm/checkpoint` */
+      dir?: string;
+    };
+    /**
+     * Escalate an update to a full compilation when it changes a large part of the module graph, e.g. after switching branches
+     * or installing dependencies. The unchanged modules are restored from the persistent cache and the page is reloaded
+     */
+    adaptiveRebuild?: {
+      /** ratio of the changed modules to all modules above which the update is escalated, @default 0.3 */
+      threshold?: number;
+      /** updates changing fewer modules are always incremental, @default 50 */
+      minChangedModules?: number;
+    };
+    /**
+     * Emit the config of hosting providers in production: `_redirects` and `_headers` of Netlify, `vercel.json` and a `nginx.conf` snippet.
+     * A trailing `*` of a path matches the rest of the path, which is referenced by `:splat` in the targets, e.g. `{ from: '/docs/*', to: '/guide/:splat' }`
+     */
+    hosting?: {
+      /** @default ['netlify', 'vercel', 'nginx'] */
+      providers?: ('netlify' | 'vercel' | 'nginx')[];
+      /** html served for the paths that match no file, `null` disables the fallback, @default '/index.html' */
+      spaFallback?: string | null;
+      redirects?: {
+        from: string;
+        to: string;
+        /** @default 301 */
+        status?: 301 | 302 | 307 | 308;
+      }[];
+      /** serve `to` for the paths matching `from` without redirecting */
+      rewrites?: {
+        from: string;
+        to: string;
+      }[];
+      /** paths of the resources with the content hash in the names, e.g. `/assets/*` */
+      immutable?: string[];
+      /** @default 'public, max-age=31536000, immutable' */
+      immutableCacheControl?: string;
+      /** serve the precompressed `.gz` files next to the requested files, only used by nginx, @default false */
+      precompressed?: boolean;
+    };
+    /**
+     * Emit a manifest of the js and css resources of the script entries, so a host page without a html entry, e.g. the template of a CMS,
+     * can include the tags of the entries. The css of the entries is extracted to css resources like the css of html entries
+     */
+    entryManifest?: {
+      /** file name of the manifest, which maps an entry name to `{ file, imports, css }`, @default 'entry-manifest.json' */
+      filename?: string;
+      /** emit a html snippet of the tags of each entry, `[entryName]` is replaced by the entry name, e.g. `[entryName].tags.html` */
+      loader?: string;
+    };
+    /**
+     * Logs of the compiler and Rust plugins, written to stderr
+     */
+    logging?: {
+      /** @default 'info' */
+      level?: LogLevel;
+      /**
+       * `json` writes one json object per line, e.g. for CI log ingestion
+       * @default 'text'
+       */
+      format?: 'text' | 'json';
+      /**
+       * Level of the logs whose target starts with the key, e.g. `{ "farmfe_plugin_css": "debug" }`.
+       * Targets of plugin logs are the plugin names
+       */
+      filters?: Record<string, LogLevel>;
+    };
     html?: {
       base?: string;
+      /**
+       * Evaluate EJS-like template tags in html entries, e.g. `<%= env.FARM_TITLE %>` (html escaped), `<%- env.FARM_SNIPPET %>` (raw) and `<% if (mode === 'production') { %> ... <% } %>`
+       */
+      template?: boolean;
+      /**
+       * Generate favicon, apple touch icon and manifest icons from a single source image, and inject the `<link>` tags into html entries
+       */
+      icons?: {
+        source: string;
+      };
+      /**
+       * Reference the css of dynamically imported modules in html entries, so progressive-enhancement pages are styled without js
+       */
+      noscriptCss?: {
+        /**
+         * Regex array to match the css module ids, the css resources containing the matched modules are referenced
+         */
+        include?: string[];
+        /**
+         * - noscript: `<link>` inside a `<noscript>` block, only applied when js is disabled
+         * - link: `<link>` that is always applied, the dynamic import doesn't load the css again
+         * @default 'noscript'
+         */
+        inject?: 'noscript' | 'link';
+      };
+      /**
+       * Emit a web app manifest, and inject `<link rel="manifest">` and `<meta name="theme-color">` into html entries.
+       * The manifest icons generated by `html.icons` are added to the manifest too
+       */
+      manifest?: {
+        /**
+         * Name of the manifest resource
+         * @default 'manifest.webmanifest'
+         */
+        filename?: string;
+        name?: string;
+        shortName?: string;
+        description?: string;
+        /**
+         * @default '/'
+         */
+        startUrl?: string;
+        scope?: string;
+        /**
+         * @default 'standalone'
+         */
+        display?: 'fullscreen' | 'standalone' | 'minimal-ui' | 'browser';
+        themeColor?: string;
+        backgroundColor?: string;
+        /**
+         * Icons relative to root, they are transformed and emitted like other assets
+         */
+        icons?: {
+          src: string;
+          /**
+           * e.g. `192x192`, read from the image by default
+           */
+          sizes?: string;
+          /**
+           * e.g. `any maskable`
+           */
+          purpose?: string;
+        }[];
+      };
     };
     /**
      * Configure whether to enable sourcemap, optional configuration items and descriptions are as follows:
@@ -460,6 +1097,37 @@ export interface Config {
       - all-inline: Generate sourcemaps for all files, and inline sourcemaps into the product, do not generate separate files
      */
     sourcemap?: boolean | 'inline' | 'all' | 'all-inline';
+    /**
+     * Base directory of the `sources` in generated sourcemaps. By default the sources are `/{path relative to root}`, which are served by the dev server.
+      - package: relative to the closest directory containing `package.json`
+      - workspace: relative to the repository root, the closest directory containing `.git` or `pnpm-workspace.yaml`
+      - other string: relative to the directory, which is resolved from root
+     */
+    sourcemapBase?: 'package' | 'workspace' | string;
+    /**
+     * Directories mapped between the compiler and the host when the compiler runs in a container or on a remote machine,
+     * e.g. `{ compiler: '/app', host: '/Users/me/project' }`. The `sources` of the source maps, the paths of the error overlay
+     * and the files opened in the editor are the host paths
+     */
+    pathMappings?: { compiler: string; host: string }[];
+    /**
+     * Source patches of dependency files applied when they are loaded, so broken packages can be fixed without mutating node_modules.
+     * The patched modules are rebuilt when their patches change
+     */
+    patches?: {
+      /** module ids of the files the replacements are applied to, e.g. `node_modules/lodash/merge\\.js$` */
+      test?: string[];
+      /** unified diff file relative to the root, e.g. created by `git diff` or `patch-package` */
+      diff?: string;
+      /** string replacements applied in order after the diff, the build fails if a search string is not found */
+      replace?: { search: string; replace: string }[];
+    }[];
+    /**
+     * How the files of the extensions without a core plugin are built, so they don't fail with unknown module type errors.
+     * The keys are extensions without the dot, e.g. `{ glsl: { treatAs: 'js-via-plugin' } }`.
+     * `asset` emits them as static assets, `js-via-plugin` loads them as text and parses them as js after the transform hooks of the plugins
+     */
+    moduleTypes?: Record<string, { treatAs: 'asset' | 'js-via-plugin' }>;
     /**
      * Configure the behavior of Farm's partial bundling. For details, please refer to https://farmfe.org/docs/features/partial-bundling
      */
@@ -477,10 +1145,25 @@ export interface Config {
     progress?: boolean;
     presetEnv?: boolean | PresetEnvConfig;
     persistentCache?: boolean | PersistentCacheConfig;
-    comments?: boolean | 'license';
+    comments?: boolean | 'all' | 'license' | 'none';
     custom?: Record<string, any>;
   };
   jsPlugins?: JsPlugin[];
   // [rustPluginFilePath, jsonStringifiedOptions]
   rustPlugins?: [string, string][];
+  // executes build time macros, see `compilation.macros`
+  macroHost?: (call: MacroCall) => Promise<MacroResult>;
+}
+
+export interface MacroCall {
+  /** resolved path of the macro module */
+  module: string;
+  exportName: string;
+  args: unknown[];
+  importer: string;
+}
+
+export interface MacroResult {
+  value: unknown;
+  undefined: boolean;
 }
@@ -252,4 +252,16 @@ export interface JsMinifyOptions {
    * @default true
    */
   moduleDecls?: boolean;
+
+  /**
+   * Minify js resource pots and modules, set to `false` to keep them readable
+   * @default true
+   */
+  js?: boolean;
+
+  /**
+   * Minify css resource pots and modules
+   * @default true
+   */
+  css?: boolean;
 }
@@ -9,3 +9,6 @@ export * from './publicDir.js';
 export * from './rebase-url.js';
 export * from './plugin-utils.js';
 export * from './dynamic-resources.js';
+export * from './path-mapping.js';
+export * from './source-map.js';
+export * from './source-editor.js';
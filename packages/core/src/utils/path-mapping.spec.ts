@@ -0,0 +1,25 @@
+import { expect, test } from 'vitest';
+import { toCompilerPath, toHostPath, toHostPaths } from './path-mapping.js';
+
+const mappings = [{ compiler: '/app', host: '/Users/me/project/' }];
+
+test('path mapping - host and compiler paths', () => {
+  expect(toHostPath(mappings, '/app/src/index.ts')).toBe(
+    '/Users/me/project/src/index.ts'
+  );
+  expect(toHostPath(mappings, '/application/index.ts')).toBe(
+    '/application/index.ts'
+  );
+  expect(toCompilerPath(mappings, '/Users/me/project/src/index.ts:1:2')).toBe(
+    '/app/src/index.ts:1:2'
+  );
+  expect(toCompilerPath(undefined, '/Users/me/project/a.ts')).toBe(
+    '/Users/me/project/a.ts'
+  );
+});
+
+test('path mapping - paths in text', () => {
+  expect(toHostPaths(mappings, 'Failed to parse /app/src/a.ts:1:2')).toBe(
+    'Failed to parse /Users/me/project/src/a.ts:1:2'
+  );
+});
@@ -0,0 +1,71 @@
+import { slash, withTrailingSlash } from './path.js';
+
+/**
+ * The same directory seen by the compiler and by the host, e.g. `/app` in a container mounted from `/Users/me/project`
+ */
+export interface PathMapping {
+  compiler: string;
+  host: string;
+}
+
+function mapPrefix(path: string, from: string, to: string): string | null {
+  const normalizedPath = slash(path);
+  const normalizedFrom = slash(from).replace(/\/+$/, '');
+
+  if (normalizedPath === normalizedFrom) {
+    return slash(to).replace(/\/+$/, '');
+  }
+
+  if (normalizedPath.startsWith(withTrailingSlash(normalizedFrom))) {
+    return (
+      withTrailingSlash(slash(to)) +
+      normalizedPath.slice(normalizedFrom.length + 1)
+    );
+  }
+
+  return null;
+}
+
+/**
+ * Map the path seen by the compiler to the host path, it's returned unchanged if no mapping contains it
+ */
+export function toHostPath(mappings: PathMapping[] = [], path: string) {
+  for (const { compiler, host } of mappings) {
+    const mapped = mapPrefix(path, compiler, host);
+
+    if (mapped !== null) {
+      return mapped;
+    }
+  }
+
+  return path;
+}
+
+/**
+ * Map the host path to the path seen by the compiler, it's returned unchanged if no mapping contains it
+ */
+export function toCompilerPath(mappings: PathMapping[] = [], path: string) {
+  for (const { compiler, host } of mappings) {
+    const mapped = mapPrefix(path, host, compiler);
+
+    if (mapped !== null) {
+      return mapped;
+    }
+  }
+
+  return path;
+}
+
+/**
+ * Replace the compiler paths in the text, e.g. an error message, with the host paths
+ */
+export function toHostPaths(mappings: PathMapping[] = [], text: string) {
+  return mappings.reduce(
+    (result, { compiler, host }) =>
+      result.replaceAll(
+        withTrailingSlash(slash(compiler)),
+        withTrailingSlash(slash(host))
+      ),
+    text
+  );
+}
@@ -1,5 +1,6 @@
 import fs from 'node:fs';
 import { isAbsolute, join } from 'node:path';
+import { normalizeAliasFind } from '../config/normalize-config/normalize-resolve.js';
 import { Alias } from '../config/types.js';
 import { CompilationContext } from '../plugin/type.js';
 
@@ -79,7 +80,9 @@ export function transformAliasWithVite(
   alias: Array<Alias>
 ): Record<string, string> {
   return alias.reduce<Record<string, string>>((acc, item) => {
-    acc[item.find] = item.replacement;
+    if (typeof item.replacement === 'string') {
+      acc[normalizeAliasFind(item.find)] = item.replacement;
+    }
     return acc;
   }, {});
 }
@@ -0,0 +1,71 @@
+import type {
+  CompilationContext,
+  EditSourceResult,
+  SourceEdit
+} from '../plugin/type.js';
+
+/**
+ * MagicString like editor of the code of a js plugin hook, the edits are applied by the compiler which
+ * generates the source map of them, e.g.
+ * ```ts
+ * const editor = new SourceEditor(context, param.content, param.resolvedPath);
+ * editor.overwrite(start, end, 'replaced');
+ * return { ...editor.generate(), moduleType: param.moduleType };
+ * ```
+ */
+export class SourceEditor {
+  private edits: SourceEdit[] = [];
+  // prepended contents are inserted before the contents prepended earlier
+  private prepended: SourceEdit[] = [];
+
+  constructor(
+    private context: CompilationContext,
+    public readonly original: string,
+    private filename: string
+  ) {}
+
+  hasChanged() {
+    return this.prepended.length > 0 || this.edits.length > 0;
+  }
+
+  prepend(content: string) {
+    this.prepended.unshift({ start: 0, end: 0, content });
+    return this;
+  }
+
+  append(content: string) {
+    return this.insert(this.original.length, content);
+  }
+
+  insert(index: number, content: string) {
+    this.edits.push({ start: index, end: index, content });
+    return this;
+  }
+
+  overwrite(start: number, end: number, content: string) {
+    if (start >= end) {
+      throw new Error(
+        `can not overwrite the empty range ${start}..${end} of ${this.filename}`
+      );
+    }
+
+    this.edits.push({ start, end, content });
+    return this;
+  }
+
+  remove(start: number, end: number) {
+    return start === end ? this : this.overwrite(start, end, '');
+  }
+
+  generate(): EditSourceResult {
+    return this.context.editSource({
+      code: this.original,
+      filename: this.filename,
+      edits: [...this.prepended, ...this.edits]
+    });
+  }
+
+  toString() {
+    return this.generate().content;
+  }
+}
@@ -0,0 +1,60 @@
+import { expect, test } from 'vitest';
+import {
+  decodeMappings,
+  mapStackTrace,
+  originalPositionFor
+} from './source-map.js';
+
+// line 1: col 0 -> a.ts 1:1, col 4 -> a.ts 1:5; line 2: col 2 -> b.ts 3:1
+const map = {
+  sources: ['a.ts', 'b.ts'],
+  mappings: 'AAAA,IAAI;ECEJ'
+};
+
+test('source map - decode mappings', () => {
+  expect(decodeMappings(map.mappings)).toEqual([
+    [
+      [0, 0, 0, 0],
+      [4, 0, 0, 4]
+    ],
+    [[2, 1, 2, 0]]
+  ]);
+});
+
+test('source map - original position', () => {
+  const decoded = decodeMappings(map.mappings);
+
+  expect(originalPositionFor(map, decoded, 1, 7)).toEqual({
+    source: 'a.ts',
+    line: 1,
+    column: 5
+  });
+  expect(originalPositionFor(map, decoded, 2, 3)).toEqual({
+    source: 'b.ts',
+    line: 3,
+    column: 1
+  });
+  expect(originalPositionFor(map, decoded, 3, 1)).toBeUndefined();
+});
+
+test('source map - map stack trace', () => {
+  const stack = [
+    'Error: boom',
+    '    at run (http://localhost:9000/index.js:2:3)',
+    'run@http://localhost:9000/index.js:1:5',
+    '    at http://localhost:9000/vendor.js:1:1'
+  ].join('\n');
+
+  expect(
+    mapStackTrace(stack, (file) =>
+      file === 'http://localhost:9000/index.js' ? map : undefined
+    )
+  ).toBe(
+    [
+      'Error: boom',
+      '    at run (b.ts:3:1)',
+      'run@a.ts:1:5',
+      '    at http://localhost:9000/vendor.js:1:1'
+    ].join('\n')
+  );
+});
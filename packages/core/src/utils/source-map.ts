@@ -0,0 +1,183 @@
+const BASE64_CHARS =
+  'ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/';
+const BASE64_VALUES = new Map(
+  [...BASE64_CHARS].map((char, index) => [char, index])
+);
+
+export interface RawSourceMap {
+  sources: string[];
+  sourceRoot?: string;
+  mappings: string;
+}
+
+export interface OriginalPosition {
+  source: string;
+  // 1-based line and column, the same as the positions of a stack trace
+  line: number;
+  column: number;
+}
+
+// [generated column, source index, original line, original column], 0-based
+type Segment = [number, number, number, number];
+
+/**
+ * Decode the mappings of the source map into segments of each generated line, sorted by the generated column.
+ * Segments without an original position are dropped.
+ */
+export function decodeMappings(mappings: string): Segment[][] {
+  const lines: Segment[][] = [];
+  let sourceIndex = 0;
+  let originalLine = 0;
+  let originalColumn = 0;
+
+  for (const line of mappings.split(';')) {
+    const segments: Segment[] = [];
+    let generatedColumn = 0;
+
+    for (const segment of line.split(',')) {
+      if (!segment) {
+        continue;
+      }
+
+      const fields = decodeVlq(segment);
+      generatedColumn += fields[0];
+
+      if (fields.length >= 4) {
+        sourceIndex += fields[1];
+        originalLine += fields[2];
+        originalColumn += fields[3];
+        segments.push([
+          generatedColumn,
+          sourceIndex,
+          originalLine,
+          originalColumn
+        ]);
+      }
+    }
+
+    lines.push(segments);
+  }
+
+  return lines;
+}
+
+function decodeVlq(segment: string): number[] {
+  const values: number[] = [];
+  let value = 0;
+  let shift = 0;
+
+  for (const char of segment) {
+    const digit = BASE64_VALUES.get(char) ?? 0;
+    value += (digit & 31) << shift;
+
+    if (digit & 32) {
+      shift += 5;
+    } else {
+      const negative = value & 1;
+      value >>>= 1;
+      values.push(negative ? -value : value);
+      value = shift = 0;
+    }
+  }
+
+  return values;
+}
+
+/**
+ * Find the original position of the 1-based generated line and column, the closest segment before the column is used
+ */
+export function originalPositionFor(
+  map: RawSourceMap,
+  decoded: Segment[][],
+  line: number,
+  column: number
+): OriginalPosition | undefined {
+  const segments = decoded[line - 1];
+
+  if (!segments?.length) {
+    return;
+  }
+
+  let low = 0;
+  let high = segments.length - 1;
+  let found: Segment | undefined;
+
+  while (low <= high) {
+    const mid = (low + high) >> 1;
+
+    if (segments[mid][0] <= column - 1) {
+      found = segments[mid];
+      low = mid + 1;
+    } else {
+      high = mid - 1;
+    }
+  }
+
+  found ??= segments[0];
+  const source = map.sources[found[1]];
+
+  if (source === undefined) {
+    return;
+  }
+
+  return {
+    source: map.sourceRoot ? `${map.sourceRoot}${source}` : source,
+    line: found[2] + 1,
+    column: found[3] + 1
+  };
+}
+
+// `at fn (http://localhost:9000/index.js:1:2)` or `fn@http://localhost:9000/index.js:1:2`
+const STACK_FRAME_LOCATION = /(?<=^|[\s(@])([^\s(@]+):(\d+):(\d+)(?=\)?\s*$)/;
+
+/**
+ * Replace the generated locations of the stack trace with the original ones, `loadSourceMap` returns the source map of the
+ * location of a frame, e.g. a resource url. Frames without a source map are kept as is.
+ */
+export function mapStackTrace(
+  stack: string,
+  loadSourceMap: (file: string) => RawSourceMap | undefined
+): string {
+  const decodedMaps = new Map<
+    string,
+    { map: RawSourceMap; decoded: Segment[][] } | undefined
+  >();
+
+  return stack
+    .split('\n')
+    .map((frame) => {
+      const match = frame.match(STACK_FRAME_LOCATION);
+
+      if (!match) {
+        return frame;
+      }
+
+      const [location, file, line, column] = match;
+
+      if (!decodedMaps.has(file)) {
+        const map = loadSourceMap(file);
+        decodedMaps.set(
+          file,
+          map && { map, decoded: decodeMappings(map.mappings) }
+        );
+      }
+
+      const sourceMap = decodedMaps.get(file);
+      const position =
+        sourceMap &&
+        originalPositionFor(
+          sourceMap.map,
+          sourceMap.decoded,
+          Number(line),
+          Number(column)
+        );
+
+      return position
+        ? frame.replace(
+            location,
+            `${position.source}:${position.line}:${position.column}`
+          )
+        : frame;
+    })
+    .join('\n');
+}
@@ -1,10 +1,11 @@
 import { createRequire } from 'node:module';
+import path from 'node:path';
 
 import { FSWatcher } from 'chokidar';
 
 import { Compiler } from '../compiler/index.js';
 import { Server } from '../server/index.js';
-import { Logger, compilerHandler } from '../utils/index.js';
+import { Logger, compilerHandler, toCompilerPath } from '../utils/index.js';
 
 import { existsSync } from 'node:fs';
 import type { ResolvedUserConfig } from '../config/index.js';
@@ -17,6 +18,7 @@ interface ImplFileWatcher {
 
 export class FileWatcher implements ImplFileWatcher {
   private _root: string;
+  private _sourceRoots: string[];
   private _watcher: FSWatcher;
   private _close = false;
   private _watchedFiles = new Set<string>();
@@ -27,6 +29,9 @@ export class FileWatcher implements ImplFileWatcher {
     private _logger: Logger
   ) {
     this._root = options.root;
+    this._sourceRoots = (options.compilation?.sourceRoots ?? []).map((dir) =>
+      path.resolve(this._root, dir)
+    );
   }
 
   getInternalWatcher() {
@@ -38,6 +43,8 @@ export class FileWatcher implements ImplFileWatcher {
 
     return (
       !file.startsWith(`${root}${suffix}`) &&
+      // the source roots are watched as a whole
+      !this._sourceRoots.some((dir) => file.startsWith(`${dir}${suffix}`)) &&
       !file.includes(`node_modules${suffix}`) &&
       !file.includes('\0') &&
       existsSync(file)
@@ -49,10 +56,13 @@ export class FileWatcher implements ImplFileWatcher {
       this.serverOrCompiler
     );
 
+    // watch paths added by plugins may be host paths
     return [
       ...compiler.resolvedModulePaths(this._root),
       ...compiler.resolvedWatchPaths()
-    ].filter((file) => this.filterWatchFile(file, this._root));
+    ]
+      .map((file) => toCompilerPath(compiler.config.config.pathMappings, file))
+      .filter((file) => this.filterWatchFile(file, this._root));
   }
 
   watchExtraFiles() {
@@ -104,7 +114,7 @@ export class FileWatcher implements ImplFileWatcher {
 
     const watchedFiles = this.getExtraWatchedFiles();
 
-    const files = [this.options.root, ...watchedFiles];
+    const files = [this.options.root, ...this._sourceRoots, ...watchedFiles];
     this._watchedFiles = new Set(files);
     this._watcher = createWatcher(this.options, files);
 
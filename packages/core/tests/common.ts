@@ -14,7 +14,7 @@ export async function getCompiler(
   p: string,
   plugins: JsPlugin[],
   input?: Record<string, string>,
-  output?: Record<string, string>
+  output?: UserConfig['compilation']['output']
 ): Promise<Compiler> {
   const originalExit = process.exit;
   process.exit = (code) => {
@@ -0,0 +1,34 @@
+import { existsSync, readFileSync, writeFileSync } from 'node:fs';
+import path from 'path';
+import { expect, test } from 'vitest';
+import { getCompiler, getFixturesDir } from '../common.js';
+
+test('Retain the files of the previous builds when cleaning', async () => {
+  const root = path.join(getFixturesDir(), 'retain-builds');
+  const compiler = await getCompiler(
+    root,
+    'retain-builds',
+    [],
+    { index: './index.ts' },
+    { retainBuilds: 1 }
+  );
+  const outputPath = path.join(root, 'dist', 'retain-builds');
+
+  compiler.removeOutputPathDir();
+  await compiler.compile();
+  compiler.writeResourcesToDisk();
+
+  const manifest = JSON.parse(
+    readFileSync(path.join(outputPath, '.farm-retention.json'), 'utf-8')
+  );
+  expect(manifest.builds).toHaveLength(1);
+  expect(manifest.builds[0]).toContain('index.mjs');
+
+  // files that are not emitted by the retained builds are removed
+  const staleFilePath = path.join(outputPath, 'stale.mjs');
+  writeFileSync(staleFilePath, 'export default "stale";');
+  compiler.removeOutputPathDir();
+
+  expect(existsSync(staleFilePath)).toBe(false);
+  expect(existsSync(path.join(outputPath, 'index.mjs'))).toBe(true);
+});
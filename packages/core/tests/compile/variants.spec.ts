@@ -0,0 +1,33 @@
+import path from 'path';
+import { expect, test } from 'vitest';
+import { getCompiler, getFixturesDir, getOutputResult } from '../common.js';
+
+test('Output variants', async () => {
+  const root = path.join(getFixturesDir(), 'variants');
+  const compiler = await getCompiler(
+    root,
+    'variants',
+    [],
+    { index: './index.ts' },
+    {
+      variants: [
+        { name: 'a', variables: { API_BASE: 'https://a.example.com' } },
+        { name: 'b', variables: { API_BASE: 'https://b.example.com' } }
+      ]
+    }
+  );
+  await compiler.compile();
+  compiler.writeResourcesToDisk();
+
+  for (const name of ['a', 'b']) {
+    const outputFilePath = path.join(
+      root,
+      'dist',
+      'variants',
+      name,
+      'index.mjs'
+    );
+    const result = await getOutputResult(outputFilePath);
+    expect(result.default.apiBase).toBe(`https://${name}.example.com`);
+  }
+});
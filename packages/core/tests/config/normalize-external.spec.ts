@@ -0,0 +1,43 @@
+import path from 'path';
+import { fileURLToPath } from 'url';
+import { describe, expect, test } from 'vitest';
+
+import { partialExternal } from '../../src/config/normalize-config/normalize-external.js';
+
+const root = path.join(
+  path.dirname(fileURLToPath(import.meta.url)),
+  '..',
+  'fixtures',
+  'config',
+  'external-dependencies'
+);
+
+const isExternal = (externals: string[], source: string) =>
+  externals.some((external) => new RegExp(external).test(source));
+
+describe('partialExternal', () => {
+  test('dependencies', () => {
+    const [externals, record] = partialExternal('dependencies', root);
+
+    expect(record).toEqual({});
+    expect(isExternal(externals, 'lodash.merge')).toBe(true);
+    expect(isExternal(externals, 'react')).toBe(true);
+    expect(isExternal(externals, 'react/jsx-runtime')).toBe(true);
+    expect(isExternal(externals, 'lodash-merge')).toBe(false);
+    expect(isExternal(externals, 'react-dom')).toBe(false);
+    expect(isExternal(externals, 'typescript')).toBe(false);
+  });
+
+  test('peerDependencies', () => {
+    const [externals] = partialExternal('peerDependencies', root);
+
+    expect(externals).toEqual(['^react($|/)']);
+  });
+
+  test('patterns', () => {
+    expect(partialExternal(['^node:', { jquery: '$' }], root)).toEqual([
+      ['^node:'],
+      { jquery: '$' }
+    ]);
+  });
+});
@@ -0,0 +1,51 @@
+import { describe, expect, test } from 'vitest';
+
+import {
+  normalizeAliasFind,
+  normalizeResolve
+} from '../../src/config/normalize-config/normalize-resolve.js';
+import { ResolvedCompilation } from '../../src/config/types.js';
+
+describe('normalizeResolve', () => {
+  test('array alias', () => {
+    const toComponent = (_: string, name: string) => `./components/${name}`;
+    const resolvedCompilation = {
+      custom: {},
+      resolve: {
+        alias: [
+          { find: /^~(\w+)\/(.*)$/, replacement: '/root/$1/$2' },
+          { find: '@', replacement: '/root/src' },
+          { find: /^#(.*)$/, replacement: toComponent }
+        ]
+      }
+    } as ResolvedCompilation;
+
+    normalizeResolve({ compilation: { custom: {} } }, resolvedCompilation);
+
+    expect(resolvedCompilation.resolve.alias).toEqual({});
+    // function replacements are resolved by the alias adapter plugin
+    expect(resolvedCompilation.resolve.aliasEntries).toEqual([
+      {
+        find: '$__farm_regex:^~(\\w+)\\/(.*)$',
+        replacement: '/root/$1/$2'
+      },
+      { find: '@', replacement: '/root/src' }
+    ]);
+  });
+
+  test('object alias', () => {
+    const resolvedCompilation = {
+      custom: {},
+      resolve: { alias: { '@': '/root/src' } }
+    } as ResolvedCompilation;
+
+    normalizeResolve({ compilation: { custom: {} } }, resolvedCompilation);
+
+    expect(resolvedCompilation.resolve.alias).toEqual({ '@': '/root/src' });
+    expect(resolvedCompilation.resolve.aliasEntries).toBeUndefined();
+  });
+
+  test('regex flags', () => {
+    expect(normalizeAliasFind(/^react$/i)).toBe('$__farm_regex:(?i)^react$');
+  });
+});
@@ -0,0 +1 @@
+export default { apiBase: '__FARM_VARIANT_API_BASE__' };
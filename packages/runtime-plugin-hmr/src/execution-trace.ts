@@ -0,0 +1,100 @@
+import type { HmrClient } from './hmr-client.js';
+import type { ExecutionTraceItem } from './types.js';
+
+export const EXECUTION_TRACE_EVENT = 'farm:execution-trace';
+// the trace is sent after no module is executed for a while
+const FLUSH_DELAY = 1000;
+
+// the module instance of the runtime, only the id is used
+interface TracedModule {
+  id: string;
+}
+
+interface PendingModule {
+  id: string;
+  order: number;
+  start: number;
+  childrenDuration: number;
+}
+
+/**
+ * Record the execution order and duration of module factories and send them to the dev server,
+ * so expensive top level side effects that slow down page startup can be found.
+ */
+export class ExecutionTracer {
+  private order = 0;
+  private pending = new Map<string, PendingModule>();
+  // modules that are being executed synchronously, the top one is the innermost
+  private stack: PendingModule[] = [];
+  private items: ExecutionTraceItem[] = [];
+  private timer: ReturnType<typeof setTimeout> | undefined;
+
+  constructor(private hmrClient: HmrClient) {}
+
+  moduleCreated(module: TracedModule) {
+    const pendingModule = {
+      id: module.id,
+      order: this.order++,
+      start: performance.now(),
+      childrenDuration: 0
+    };
+    this.pending.set(module.id, pendingModule);
+    this.stack.push(pendingModule);
+  }
+
+  moduleInitialized(module: TracedModule) {
+    const pendingModule = this.pending.get(module.id);
+
+    if (!pendingModule) {
+      return;
+    }
+
+    this.pending.delete(module.id);
+    const duration = performance.now() - pendingModule.start;
+
+    // async modules are initialized after the stack is unwound
+    if (this.stack[this.stack.length - 1] === pendingModule) {
+      this.stack.pop();
+      const parent = this.stack[this.stack.length - 1];
+
+      if (parent) {
+        parent.childrenDuration += duration;
+      }
+    } else {
+      this.stack = this.stack.filter((m) => m !== pendingModule);
+    }
+
+    this.items.push({
+      id: pendingModule.id,
+      order: pendingModule.order,
+      duration,
+      selfDuration: Math.max(duration - pendingModule.childrenDuration, 0)
+    });
+    this.scheduleFlush();
+  }
+
+  private scheduleFlush() {
+    clearTimeout(this.timer);
+    this.timer = setTimeout(() => this.flush(), FLUSH_DELAY);
+  }
+
+  private flush() {
+    const socket = this.hmrClient.socket;
+
+    if (socket.readyState !== WebSocket.OPEN) {
+      socket.addEventListener('open', () => this.flush(), { once: true });
+      return;
+    }
+
+    const modules = this.items.sort((a, b) => a.order - b.order);
+    this.items = [];
+
+    socket.send(
+      JSON.stringify({
+        type: 'custom',
+        event: EXECUTION_TRACE_EVENT,
+        data: { modules }
+      })
+    );
+  }
+}
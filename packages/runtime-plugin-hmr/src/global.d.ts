@@ -1,4 +1,8 @@
 declare const FARM_HMR_PORT: string | undefined;
 declare const FARM_HMR_HOST: string | undefined;
 declare const FARM_HMR_PATH: string | undefined;
-declare const FARM_HMR_PROTOCOL: string | undefined;
\ No newline at end of file
+declare const FARM_HMR_PROTOCOL: string | undefined;
+declare const FARM_HMR_EXECUTION_TRACE: boolean | undefined;
+declare const FARM_HMR_UPDATE_PANEL: boolean | undefined;
+declare const FARM_HMR_RETRY_FAILED_MODULES: boolean | undefined;
+declare const FARM_HMR_LOG_BOUNDARIES: boolean | undefined;
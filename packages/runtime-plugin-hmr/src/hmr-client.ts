@@ -1,8 +1,16 @@
 import type { ModuleSystem } from '@farmfe/runtime';
 import { HotModuleState } from './hot-module-state.js';
 import { logger } from './logger.js';
+import type { ModuleErrorReporter } from './module-errors.js';
 import { ErrorOverlay, overlayId } from './overlay.js';
-import { HMRPayload, HmrUpdateResult, RawHmrUpdateResult } from './types.js';
+import { evaluateScript } from './trusted-types.js';
+import {
+  HMRPayload,
+  HmrFullReload,
+  HmrUpdateResult,
+  RawHmrUpdateResult
+} from './types.js';
+import type { UpdatePanel } from './update-panel.js';
 
 // Inject during compile time
 const usingClientHost = typeof FARM_HMR_HOST === 'boolean'; // using client host/port by default
@@ -22,6 +30,13 @@ export class HmrClient {
     string,
     ((data: any) => void | Promise<void>)[]
   >();
+  // timestamp of the last applied update of each module
+  moduleTimestamps = new Map<string, number>();
+  // updates are applied one by one in the order they arrive
+  private updateQueue: Promise<void> = Promise.resolve();
+  // set when `server.hmr.updatePanel` is enabled
+  updatePanel: UpdatePanel | undefined;
+  moduleErrors: ModuleErrorReporter | undefined;
 
   constructor(private moduleSystem: ModuleSystem) {}
 
@@ -39,7 +54,7 @@ export class HmrClient {
     // after the file is recompiled, the server will generated a update resource and send its id to the client
     // the client will apply the update
     socket.addEventListener('message', (event) => {
-      const result: HMRPayload = new Function(`return (${event.data})`)();
+      const result: HMRPayload = JSON.parse(event.data);
       if (result?.type === 'closing') {
         this.closeConnectionGracefully();
         return;
@@ -81,11 +96,84 @@ export class HmrClient {
     this.socket.close(1000, 'Client closing connection');
   }
 
+  /**
+   * Drop the modules of the update that are older than the applied ones, so the old code never flashes back after rapid successive edits.
+   * Returns null if the whole update is stale.
+   */
+  rejectStaleModules(result: HmrUpdateResult): HmrUpdateResult | null {
+    const { timestamp } = result;
+
+    if (timestamp === undefined) {
+      return result;
+    }
+
+    const isStale = (id: string) =>
+      (this.moduleTimestamps.get(id) ?? 0) > timestamp;
+    const staleIds = [
+      ...result.added,
+      ...result.changed,
+      ...result.removed
+    ].filter(isStale);
+
+    if (staleIds.length > 0) {
+      logger.debug(
+        `stale update of ${staleIds.join(', ')} is skipped, a newer version is already applied.`
+      );
+    }
+
+    const fresh = (ids: string[]) => ids.filter((id) => !isStale(id));
+    const updated = {
+      ...result,
+      added: fresh(result.added),
+      changed: fresh(result.changed),
+      removed: fresh(result.removed),
+      boundaries: Object.fromEntries(
+        Object.entries(result.boundaries).filter(([id]) => !isStale(id))
+      ),
+      cssUpdates: Object.fromEntries(
+        Object.entries(result.cssUpdates ?? {}).filter(([id]) => !isStale(id))
+      )
+    };
+
+    for (const id of [
+      ...updated.added,
+      ...updated.changed,
+      ...updated.removed
+    ]) {
+      this.moduleTimestamps.set(id, timestamp);
+    }
+
+    if (
+      staleIds.length > 0 &&
+      updated.added.length === 0 &&
+      updated.changed.length === 0 &&
+      updated.removed.length === 0
+    ) {
+      return null;
+    }
+
+    return updated;
+  }
+
   async applyHotUpdates(result: HmrUpdateResult, moduleSystem: ModuleSystem) {
     result.changed.forEach((id) => {
       logger.debug(`${id} updated`);
     });
 
+    if (FARM_HMR_LOG_BOUNDARIES) {
+      for (const [id, chains] of Object.entries(result.boundaries)) {
+        for (const chain of chains) {
+          logger.log(`hmr boundary of ${id}: ${chain.join(' -> ')}`);
+        }
+      }
+    }
+
+    const cssUpdates = result.cssUpdates ?? {};
+
+    for (const [id, css] of Object.entries(cssUpdates)) {
+      this.patchStyle(id, css);
+    }
+
     for (const id of result.removed) {
       const prune = this.pruneMap.get(id);
       if (prune) {
@@ -104,8 +192,11 @@ export class HmrClient {
     for (const id of result.changed) {
       moduleSystem.update(id, result.modules[id]);
 
-      if (!result.boundaries[id]) {
+      if (!result.boundaries[id] && !cssUpdates[id]) {
         // do not found boundary module, reload the window
+        if (result.fullReload) {
+          logger.log(formatFullReload(result.fullReload));
+        }
         location.reload();
       }
     }
@@ -117,19 +208,35 @@ export class HmrClient {
       );
     }
 
-    for (const chains of Object.values(result.boundaries)) {
+    const acceptedDeps = result.acceptedDeps ?? {};
+    // boundaries that accept their updated dependencies, their callbacks are called after all the chains are handled
+    const depsAcceptedBoundaries = new Set<string>();
+
+    for (const [id, chains] of Object.entries(result.boundaries)) {
+      // the styles of css modules are already patched, executing them again would recreate the style elements
+      if (cssUpdates[id]) {
+        continue;
+      }
+
       for (const chain of chains) {
-        // clear the cache of the boundary module and its dependencies
-        for (const id of chain) {
+        const boundary = chain[chain.length - 1];
+        const acceptedDep =
+          chain.length > 1 ? chain[chain.length - 2] : undefined;
+        const isDepAccepted = acceptedDeps[boundary]?.includes(acceptedDep);
+
+        // clear the cache of the boundary module and its dependencies, a boundary that accepts the dependency is not executed again
+        for (const id of isDepAccepted ? chain.slice(0, -1) : chain) {
           moduleSystem.clearCache(id);
         }
 
+        if (isDepAccepted) {
+          depsAcceptedBoundaries.add(boundary);
+          continue;
+        }
+
         try {
           // require the boundary module
-          const boundary = chain[chain.length - 1];
           const hotContext = this.registeredHotModulesMap.get(boundary);
-          const acceptedDep =
-            chain.length > 1 ? chain[chain.length - 2] : undefined;
 
           if (!hotContext) {
             logger.debug(
@@ -159,7 +266,8 @@ export class HmrClient {
               const disposer = this.disposeMap.get(acceptedId);
               if (disposer) await disposer(acceptHotContext.data);
 
-              const acceptedExports = moduleSystem.require(acceptedId);
+              // async modules (top level await) resolve their exports after they are executed again
+              const acceptedExports = await moduleSystem.require(acceptedId);
 
               for (const { deps, fn } of acceptedCallbacks) {
                 fn(
@@ -173,6 +281,11 @@ export class HmrClient {
             }
           }
         } catch (err) {
+          // the error of the module is shown in the overlay, keep the page so the module can be fixed by the next update
+          if (this.moduleErrors?.isReported(err)) {
+            continue;
+          }
+
           // The boundary module's dependencies may not present in current module system for a multi-page application. We should reload the window in this case.
           // See https://github.com/farm-fe/farm/issues/383
           logger.error(err);
@@ -180,6 +293,80 @@ export class HmrClient {
         }
       }
     }
+
+    for (const boundary of depsAcceptedBoundaries) {
+      await this.acceptDeps(boundary, acceptedDeps[boundary], moduleSystem);
+    }
+  }
+
+  /**
+   * Call the accept callbacks of the boundary with the new exports of its updated dependencies. A callback is called once
+   * even if several of its dependencies are updated, e.g. `hot.accept(['./a', './b'], ([a, b]) => {})`
+   */
+  async acceptDeps(
+    boundary: string,
+    deps: string[],
+    moduleSystem: ModuleSystem
+  ) {
+    const hotContext = this.registeredHotModulesMap.get(boundary);
+
+    if (!hotContext) {
+      logger.debug(
+        `hot context is empty for boundary ${boundary}. Hot update of ${boundary} is skipped.`
+      );
+      return;
+    }
+
+    try {
+      const depsExports = new Map<string, unknown>();
+
+      for (const dep of deps) {
+        const disposer = this.disposeMap.get(dep);
+        if (disposer) {
+          await disposer(this.registeredHotModulesMap.get(dep)?.data);
+        }
+
+        depsExports.set(dep, await moduleSystem.require(dep));
+      }
+
+      for (const { deps: acceptedDeps, fn } of hotContext.acceptCallbacks) {
+        if (acceptedDeps.some((dep) => depsExports.has(dep))) {
+          fn(acceptedDeps.map((dep) => depsExports.get(dep)));
+        }
+      }
+    } catch (err) {
+      if (this.moduleErrors?.isReported(err)) {
+        return;
+      }
+
+      logger.error(err);
+      location.reload();
+    }
+  }
+
+  /**
+   * Replace the content of the style of the css module in place, so running css animations and transitions are preserved
+   */
+  patchStyle(id: string, css: string) {
+    const style = document.querySelector<HTMLStyleElement>(
+      `style[data-farm-id="${CSS.escape(id)}"]`
+    );
+
+    if (style) {
+      style.textContent = css;
+    } else {
+      const newStyle = document.createElement('style');
+      newStyle.setAttribute('data-farm-id', id);
+      newStyle.textContent = css;
+
+      const anchor = findStylesAnchor();
+
+      if (anchor) {
+        anchor.parentNode.insertBefore(newStyle, anchor);
+      } else {
+        document.head.appendChild(newStyle);
+      }
+    }
   }
 
   async notifyListeners(event: string, data: any) {
@@ -235,31 +422,82 @@ export class HmrClient {
         this.notifyListeners('vite:beforePrune', payload);
         this.notifyListeners('farm:beforePrune', payload);
         break;
+      case 'farm-prune-resources':
+        this.pruneResources(payload.resources);
+        break;
+      case 'worker-update':
+        // workers can not be hot updated, reload the page unless the app restarts them itself
+        if (this.customListenersMap.has('farm:workerUpdate')) {
+          this.notifyListeners('farm:workerUpdate', payload);
+        } else {
+          location.reload();
+        }
+        break;
 
       default:
         logger.warn(`unknown message payload: ${payload}`);
     }
   }
 
+  /**
+   * Remove the stale resources of the removed modules, so they are loaded again if the modules are added back
+   */
+  pruneResources(resources: string[]) {
+    for (const resource of resources) {
+      this.moduleSystem.resourceLoader.setLoadedResource(resource, false);
+
+      // stylesheets of the removed modules should not be applied any more
+      document
+        .querySelectorAll<HTMLLinkElement>('link[href]')
+        .forEach((link) => {
+          if (new URL(link.href).pathname.endsWith(`/${resource}`)) {
+            link.remove();
+          }
+        });
+    }
+  }
+
   handleFarmUpdate(result: RawHmrUpdateResult) {
     hasErrorOverlay() && clearOverlay();
-    const immutableModules = new Function(
-      `return ${result.immutableModules}`
-    )();
-    const mutableModules = new Function(`return ${result.mutableModules}`)();
-    const modules = { ...immutableModules, ...mutableModules };
-    this.applyHotUpdates(
-      {
-        added: result.added,
-        changed: result.changed,
-        removed: result.removed,
-        boundaries: result.boundaries,
-        modules,
-        dynamicResources: result.dynamicResources,
-        dynamicModuleResourcesMap: result.dynamicModuleResourcesMap
-      },
-      this.moduleSystem
+    const immutableModules = evaluateScript<HmrUpdateResult['modules']>(
+      result.immutableModules
     );
+    const mutableModules = evaluateScript<HmrUpdateResult['modules']>(
+      result.mutableModules
+    );
+    const modules = { ...immutableModules, ...mutableModules };
+
+    this.updateQueue = this.updateQueue
+      .then(() => {
+        const update = this.rejectStaleModules({
+          added: result.added,
+          changed: result.changed,
+          removed: result.removed,
+          boundaries: result.boundaries,
+          acceptedDeps: result.acceptedDeps,
+          modules,
+          cssUpdates: result.cssUpdates,
+          fullReload: result.fullReload,
+          dynamicResources: result.dynamicResources,
+          dynamicModuleResourcesMap: result.dynamicModuleResourcesMap,
+          timestamp: result.timestamp
+        });
+
+        if (update) {
+          const start = performance.now();
+          return this.applyHotUpdates(update, this.moduleSystem).then(
+            async () => {
+              this.updatePanel?.recordUpdate(
+                update,
+                result.stats,
+                performance.now() - start
+              );
+              await this.moduleErrors?.retry(update, this.moduleSystem);
+            }
+          );
+        }
+      })
+      .catch((err) => logger.error(err));
   }
 }
 
@@ -272,6 +510,29 @@ function clearOverlay() {
   document.querySelectorAll<ErrorOverlay>(overlayId).forEach((n) => n.close());
 }
 
+// `<!-- farm-styles -->` or an element with the `data-farm-styles` attribute, the styles of the modules are inserted before it
+function findStylesAnchor(): Node | undefined {
+  return (
+    document.querySelector('[data-farm-styles]') ??
+    Array.prototype.find.call(
+      document.head.childNodes,
+      (node: Node) =>
+        node.nodeType === Node.COMMENT_NODE &&
+        node.textContent.trim() === 'farm-styles'
+    )
+  );
+}
+
+function formatFullReload({ module, path, reason }: HmrFullReload) {
+  const last = path[path.length - 1];
+  const cause =
+    reason === 'entry'
+      ? `reaches the entry ${last}`
+      : `is imported by ${last}, which is not a script module`;
+
+  return `${module} is not accepted: ${path.join(' -> ')} ${cause}`;
+}
+
 function hasErrorOverlay() {
   return document.querySelectorAll(overlayId).length;
 }
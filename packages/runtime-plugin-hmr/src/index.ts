@@ -2,19 +2,42 @@
  * HMR client as a Farm Runtime Plugin
  */
 import type { Plugin } from '@farmfe/runtime';
+import { ExecutionTracer } from './execution-trace.js';
 import { HmrClient } from './hmr-client.js';
 import { createHotContext } from './hot-module-state.js';
+import { ModuleErrorReporter } from './module-errors.js';
+import { setTrustedTypesPolicy } from './trusted-types.js';
+import { UpdatePanel } from './update-panel.js';
 
 let hmrClient: HmrClient;
+let executionTracer: ExecutionTracer | undefined;
 
 export default (<Plugin>{
   name: 'farm-runtime-hmr-client-plugin',
   bootstrap(moduleSystem) {
+    setTrustedTypesPolicy(moduleSystem.trustedTypes);
     hmrClient = new HmrClient(moduleSystem);
     hmrClient.connect();
+    hmrClient.moduleErrors = new ModuleErrorReporter(hmrClient);
+
+    if (FARM_HMR_EXECUTION_TRACE) {
+      executionTracer = new ExecutionTracer(hmrClient);
+    }
+
+    if (FARM_HMR_UPDATE_PANEL) {
+      hmrClient.updatePanel = new UpdatePanel();
+      hmrClient.updatePanel.observeResourceLoads();
+    }
   },
   moduleCreated(module) {
     // create a hot context for each module
     module.meta.hot = createHotContext(module.id, hmrClient);
+    executionTracer?.moduleCreated(module);
+  },
+  moduleInitialized(module) {
+    executionTracer?.moduleInitialized(module);
+  },
+  moduleExecutionFailed(module, err) {
+    hmrClient.moduleErrors.moduleExecutionFailed(module, err);
   }
 });
@@ -0,0 +1,84 @@
+import type { ModuleSystem } from '@farmfe/runtime';
+import type { HmrClient } from './hmr-client.js';
+import { logger } from './logger.js';
+import type { HmrUpdateResult } from './types.js';
+
+export const MODULE_ERROR_EVENT = 'farm:module-error';
+
+// the module instance of the runtime, only the id is used
+interface FailedModule {
+  id: string;
+}
+
+/**
+ * Report the modules that throw during execution to the dev server, which maps the stack with the source maps
+ * of the resources and shows it in the overlay. The failed modules are executed again after the next hot update
+ * when `server.hmr.retryFailedModules` is enabled.
+ */
+export class ModuleErrorReporter {
+  // an error is rethrown by every module that requires the failed one, only the innermost module is reported
+  private reportedErrors = new WeakSet<object>();
+  // ordered from the innermost module to the outermost one
+  private failedModules = new Set<string>();
+
+  constructor(private hmrClient: HmrClient) {}
+
+  moduleExecutionFailed(module: FailedModule, err: unknown) {
+    if (FARM_HMR_RETRY_FAILED_MODULES) {
+      this.failedModules.add(module.id);
+    }
+
+    if (typeof err === 'object' && err !== null) {
+      if (this.reportedErrors.has(err)) {
+        return;
+      }
+
+      this.reportedErrors.add(err);
+    }
+
+    const error = err instanceof Error ? err : new Error(String(err));
+    this.send({ id: module.id, message: error.message, stack: error.stack });
+  }
+
+  isReported(err: unknown) {
+    return (
+      typeof err === 'object' && err !== null && this.reportedErrors.has(err)
+    );
+  }
+
+  /**
+   * Execute the failed modules again, a module that still throws is reported and kept for the next update
+   */
+  async retry(update: HmrUpdateResult, moduleSystem: ModuleSystem) {
+    const failedModules = [...this.failedModules].filter(
+      (id) => !update.removed.includes(id)
+    );
+    this.failedModules.clear();
+
+    for (const id of failedModules) {
+      // executed again by a module that requires it
+      if (moduleSystem.getCache(id)) {
+        continue;
+      }
+
+      try {
+        await moduleSystem.require(id);
+      } catch {
+        logger.debug(`${id} failed again after the update`);
+      }
+    }
+  }
+
+  private send(data: { id: string; message: string; stack?: string }) {
+    const socket = this.hmrClient.socket;
+
+    if (socket.readyState !== WebSocket.OPEN) {
+      socket.addEventListener('open', () => this.send(data), { once: true });
+      return;
+    }
+
+    socket.send(
+      JSON.stringify({ type: 'custom', event: MODULE_ERROR_EVENT, data })
+    );
+  }
+}
@@ -0,0 +1,25 @@
+import type { TrustedTypesPolicy } from '@farmfe/runtime';
+
+// the policy of the module system, set when the plugin is bootstrapped
+let policy: Pick<TrustedTypesPolicy, 'createHTML' | 'createScript'> = {
+  createHTML: (html) => html,
+  createScript: (script) => script
+};
+
+export function setTrustedTypesPolicy(trustedTypes: TrustedTypesPolicy) {
+  policy = trustedTypes;
+}
+
+/**
+ * Html of the shadow roots of the overlays, they are static templates
+ */
+export function trustedHTML(html: string): string {
+  return policy.createHTML(html);
+}
+
+/**
+ * Evaluate the code of the updated modules sent by the dev server
+ */
+export function evaluateScript<T>(script: string): T {
+  return new Function(policy.createScript(`return ${script}`))();
+}
@@ -19,10 +19,18 @@ export interface HmrUpdateResult {
 
   // closest boundary modules which are related to added or changed
   boundaries: Record<string, string[][]>;
+  // boundary modules -> the updated dependencies they accept by `hot.accept(deps, cb)`
+  acceptedDeps?: Record<string, string[]>;
   // modules which are added or changed
   modules: ModuleMap;
+  // css text of the changed css modules, their styles are patched in place instead of re-executing the modules
+  cssUpdates?: Record<string, string>;
+  // why the update is not accepted and the page is reloaded
+  fullReload?: HmrFullReload | null;
   dynamicResources: Resource[] | null;
   dynamicModuleResourcesMap: Record<string, number[]> | null;
+  // stamped by the server when the update starts, later updates have larger timestamps
+  timestamp?: number;
 }
 
 export interface RawHmrUpdateResult {
@@ -30,10 +38,30 @@ export interface RawHmrUpdateResult {
   changed: string[];
   removed: string[];
   boundaries: Record<string, string[][]>;
+  acceptedDeps?: Record<string, string[]>;
+  // why the update is not accepted and the page is reloaded
+  fullReload?: HmrFullReload | null;
   immutableModules: string;
   mutableModules: string;
+  cssUpdates?: Record<string, string>;
   dynamicResources: Resource[] | null;
   dynamicModuleResourcesMap: Record<string, number[]> | null;
+  timestamp?: number;
+  stats?: HmrUpdateStats;
+}
+
+export interface HmrFullReload {
+  module: string;
+  // the importers from the updated module to the module that stops the propagation
+  path: string[];
+  reason: 'entry' | 'nonScriptImporter';
+}
+
+export interface HmrUpdateStats {
+  // bytes of the module code and css of the update
+  size: number;
+  // milliseconds spent by the server to compile the update
+  duration: number;
 }
 
 // the same as Vite, see LICENSE. modified by @farmfe
@@ -45,6 +73,8 @@ export type HMRPayload =
   | CustomPayload
   | ErrorPayload
   | PrunePayload
+  | PruneResourcesPayload
+  | WorkerUpdatePayload
   | ClosingPayload;
 
 export interface FarmHmrPayload {
@@ -77,11 +107,33 @@ export interface PrunePayload {
   paths: string[];
 }
 
+export interface PruneResourcesPayload {
+  type: 'farm-prune-resources';
+  // names of the resources of the removed modules
+  resources: string[];
+}
+
+export interface WorkerUpdatePayload {
+  type: 'worker-update';
+  // ids of the web workers that should be restarted
+  workers: string[];
+}
+
 export interface FullReloadPayload {
   type: 'full-reload';
   path?: string;
 }
 
+export interface ExecutionTraceItem {
+  id: string;
+  // the order in which the module starts executing
+  order: number;
+  // milliseconds spent in the module factory, including its dependencies
+  duration: number;
+  // milliseconds spent in the module factory itself
+  selfDuration: number;
+}
+
 export interface CustomPayload {
   type: 'custom';
   event: string;
@@ -0,0 +1,225 @@
+import { trustedHTML } from './trusted-types.js';
+import type { HmrUpdateResult, HmrUpdateStats } from './types.js';
+
+// only the latest entries are shown
+const MAX_ENTRIES = 50;
+
+const template = /*html*/ `
+<style>
+:host {
+  position: fixed;
+  right: 12px;
+  bottom: 12px;
+  z-index: 99998;
+  --monospace: 'SFMono-Regular', Consolas,
+  'Liberation Mono', Menlo, Courier, monospace;
+  --brand-color: #9f1a8f;
+  font-family: var(--monospace);
+  font-size: 12px;
+}
+
+.toggle {
+  display: block;
+  margin-left: auto;
+  padding: 4px 10px;
+  border: none;
+  border-radius: 4px;
+  background: var(--brand-color);
+  color: #fff;
+  font-family: var(--monospace);
+  cursor: pointer;
+}
+
+.panel {
+  width: 560px;
+  max-height: 320px;
+  margin-bottom: 6px;
+  overflow-y: auto;
+  background: #1e1e1e;
+  color: #d8d8d8;
+  border-radius: 6px;
+  box-shadow: 0 10px 20px rgba(0, 0, 0, 0.3);
+}
+
+.panel[hidden] {
+  display: none;
+}
+
+table {
+  width: 100%;
+  border-collapse: collapse;
+}
+
+th, td {
+  padding: 3px 8px;
+  text-align: left;
+  white-space: nowrap;
+}
+
+th {
+  position: sticky;
+  top: 0;
+  background: #2a2a2a;
+}
+
+td.modules {
+  max-width: 220px;
+  overflow: hidden;
+  text-overflow: ellipsis;
+}
+
+tr.hmr td:first-child {
+  color: #ffb86c;
+}
+</style>
+<div class="panel" part="panel" hidden>
+  <table>
+    <thead>
+      <tr>
+        <th>type</th>
+        <th>size</th>
+        <th>modules</th>
+        <th>server</th>
+        <th>apply</th>
+      </tr>
+    </thead>
+    <tbody></tbody>
+  </table>
+</div>
+<button class="toggle" part="toggle">farm</button>
+`;
+
+interface PanelEntry {
+  type: 'hmr' | 'script' | 'css';
+  // bytes of the update or the transferred resource
+  size?: number;
+  // affected modules of the update, or the url of the resource
+  modules: string[];
+  // milliseconds spent by the server to regenerate the update
+  serverDuration?: number;
+  // milliseconds spent by the client to apply the update or load the resource
+  duration: number;
+}
+
+// Allow `UpdatePanel` to extend `HTMLElement` even in environments where
+// `HTMLElement` was not originally defined.
+const { HTMLElement = class {} as typeof globalThis.HTMLElement } = globalThis;
+
+/**
+ * A collapsible panel listing the hot updates and the script and css resources loaded by the page,
+ * enabled by `server.hmr.updatePanel`
+ */
+export class UpdatePanel extends HTMLElement {
+  root: ShadowRoot;
+  private entries: PanelEntry[] = [];
+
+  constructor() {
+    super();
+    this.root = this.attachShadow({ mode: 'open' });
+    this.root.innerHTML = trustedHTML(template);
+
+    const panel = this.root.querySelector<HTMLElement>('.panel')!;
+    this.root.querySelector('.toggle')!.addEventListener('click', () => {
+      panel.hidden = !panel.hidden;
+    });
+  }
+
+  recordUpdate(
+    update: HmrUpdateResult,
+    stats: HmrUpdateStats | undefined,
+    duration: number
+  ) {
+    this.push({
+      type: 'hmr',
+      size: stats?.size,
+      modules: [...update.added, ...update.changed, ...update.removed],
+      serverDuration: stats?.duration,
+      duration
+    });
+  }
+
+  /**
+   * Record the scripts and stylesheets loaded after the panel is created, e.g. dynamic imported chunks
+   */
+  observeResourceLoads() {
+    if (typeof PerformanceObserver === 'undefined') {
+      return;
+    }
+
+    const observer = new PerformanceObserver((list) => {
+      for (const entry of list.getEntries() as PerformanceResourceTiming[]) {
+        if (
+          entry.initiatorType !== 'script' &&
+          entry.initiatorType !== 'link'
+        ) {
+          continue;
+        }
+
+        this.push({
+          type: entry.initiatorType === 'script' ? 'script' : 'css',
+          size: entry.transferSize || entry.encodedBodySize,
+          modules: [new URL(entry.name).pathname],
+          duration: entry.duration
+        });
+      }
+    });
+    observer.observe({ type: 'resource' });
+  }
+
+  private push(entry: PanelEntry) {
+    this.entries.unshift(entry);
+    this.entries.length = Math.min(this.entries.length, MAX_ENTRIES);
+
+    if (!this.isConnected && document.body) {
+      document.body.appendChild(this);
+    }
+
+    this.render();
+  }
+
+  private render() {
+    const tbody = this.root.querySelector('tbody')!;
+    tbody.textContent = '';
+
+    for (const entry of this.entries) {
+      const row = document.createElement('tr');
+      row.className = entry.type;
+
+      const cells = [
+        entry.type,
+        entry.size === undefined ? '-' : formatSize(entry.size),
+        entry.type === 'hmr'
+          ? `${entry.modules.length} ${entry.modules.join(', ')}`
+          : entry.modules.join(', '),
+        entry.serverDuration === undefined
+          ? '-'
+          : `${entry.serverDuration}ms`,
+        `${Math.round(entry.duration)}ms`
+      ];
+
+      cells.forEach((text, index) => {
+        const cell = document.createElement('td');
+        cell.textContent = text;
+
+        if (index === 2) {
+          cell.className = 'modules';
+          cell.title = entry.modules.join('\n');
+        }
+
+        row.appendChild(cell);
+      });
+
+      tbody.appendChild(row);
+    }
+  }
+}
+
+function formatSize(bytes: number) {
+  return bytes < 1024 ? `${bytes}B` : `${(bytes / 1024).toFixed(1)}KB`;
+}
+
+export const updatePanelId = 'farm-update-panel';
+const { customElements } = globalThis; // Ensure `customElements` is defined before the next line.
+if (customElements && !customElements.get(updatePanelId)) {
+  customElements.define(updatePanelId, UpdatePanel);
+}
@@ -11,5 +11,6 @@ __farm_global_this__.__farm_module_system__ = (function () {
 })()();
 
 export * from './resource-loader.js'
+export type { TrustedTypesPolicy } from './trusted-types.js';
 
 export { ModuleSystem, FarmRuntimePlugin as Plugin };
@@ -2,11 +2,17 @@ import { Module } from "./module.js";
 import { type FarmRuntimePlugin, FarmRuntimePluginContainer } from "./plugin.js";
 import {
   type Resource,
+  type ResourceLoadRetryOptions,
   ResourceLoader,
   __global_this__,
   isBrowser,
   targetEnv,
 } from "./resource-loader.js";
+import {
+  type TrustedTypesPolicy,
+  createTrustedTypesPolicy,
+  passthroughPolicy,
+} from "./trusted-types.js";
 
 declare const nodeRequire: (id: string) => any;
 
@@ -36,11 +42,15 @@ export class ModuleSystem {
   dynamicResources: Resource[] = [];
   // dynamic module entry and resources map
   dynamicModuleResourcesMap: Record<string, number[]>;
+  // dynamic modules that have high priority resources, they are loaded when the browser is idle after bootstrap
+  highPriorityModules: string[] = [];
   // resources loader
   resourceLoader: ResourceLoader;
   // runtime plugin container
   pluginContainer: FarmRuntimePluginContainer;
   targetEnv: "browser" | "node";
+  // creates the values assigned to the script sinks, a Trusted Types policy if `runtime.trustedTypes` is enabled
+  trustedTypes: TrustedTypesPolicy = passthroughPolicy;
 
   constructor() {
     this.modules = {};
@@ -108,22 +118,35 @@ export class ModuleSystem {
         this.require.bind(this);
     }
     // initialize the new module
-    const result = initializer(
-      module,
-      module.exports,
-      this.require.bind(this),
-      this.farmDynamicRequire.bind(this),
-    );
+    let result: any;
+
+    try {
+      result = initializer(
+        module,
+        module.exports,
+        this.require.bind(this),
+        this.farmDynamicRequire.bind(this),
+      );
+    } catch (err) {
+      this.moduleExecutionFailed(module, err);
+      throw err;
+    }
 
     // it's a async module, return the promise
     if (result && result instanceof Promise) {
-      module.initializer = result.then(() => {
-        // call the module initialized hook
-        this.pluginContainer.hookSerial("moduleInitialized", module);
-        module.initializer = undefined;
-        // return the exports of the module
-        return module.exports;
-      });
+      module.initializer = result.then(
+        () => {
+          // call the module initialized hook
+          this.pluginContainer.hookSerial("moduleInitialized", module);
+          module.initializer = undefined;
+          // return the exports of the module
+          return module.exports;
+        },
+        (err) => {
+          this.moduleExecutionFailed(module, err);
+          throw err;
+        },
+      );
       return module.initializer;
     } else {
       // call the module initialized hook
@@ -133,6 +156,15 @@ export class ModuleSystem {
     }
   }
 
+  // a partially initialized module must not be returned from the cache, remove it so the module is executed again next time
+  private moduleExecutionFailed(module: Module, err: unknown): void {
+    if (this.cache[module.id] === module) {
+      delete this.cache[module.id];
+    }
+
+    this.pluginContainer.hookSerial("moduleExecutionFailed", module, err);
+  }
+
   farmDynamicRequire(moduleId: string): Promise<any> {
     if (this.modules[moduleId]) {
       const exports = this.require(moduleId);
@@ -280,12 +312,27 @@ export class ModuleSystem {
     this.dynamicModuleResourcesMap = dynamicModuleResourcesMap;
   }
 
+  // The high priority dynamic modules are injected during compile time
+  setHighPriorityModules(moduleIds: string[]): void {
+    this.highPriorityModules = moduleIds;
+  }
+
   // The public paths are injected during compile time
   setPublicPaths(publicPaths: string[]): void {
     this.publicPaths = publicPaths;
     this.resourceLoader.publicPaths = this.publicPaths;
   }
 
+  // The retry options of the failed resources are injected during compile time
+  setResourceLoadRetry(retry: Partial<ResourceLoadRetryOptions>): void {
+    Object.assign(this.resourceLoader.retry, retry);
+  }
+
+  // The Trusted Types policy is injected during compile time, before the plugins are bootstrapped
+  setTrustedTypesPolicy(policyName: string): void {
+    this.trustedTypes = createTrustedTypesPolicy(policyName);
+  }
+
   // The plugins are injected during compile time.
   setPlugins(plugins: FarmRuntimePlugin[]): void {
     this.pluginContainer.plugins = plugins;
@@ -312,5 +359,29 @@ export class ModuleSystem {
   // This method should only be called once
   bootstrap(): void {
     this.pluginContainer.hookSerial("bootstrap", this);
+    this.preloadHighPriorityModules();
+  }
+
+  // load the resources of the high priority dynamic modules eagerly, so they are ready when the modules are imported
+  private preloadHighPriorityModules(): void {
+    if (!isBrowser || this.highPriorityModules.length === 0) {
+      return;
+    }
+
+    const preload = () => {
+      for (const moduleId of this.highPriorityModules) {
+        if (this.dynamicModuleResourcesMap[moduleId]) {
+          this.loadDynamicResourcesOnly(moduleId).catch((err) => {
+            console.warn(`[Farm] Failed to preload module "${moduleId}"`, err);
+          });
+        }
+      }
+    };
+
+    if (typeof __global_this__.requestIdleCallback === "function") {
+      __global_this__.requestIdleCallback(preload);
+    } else {
+      setTimeout(preload, 0);
+    }
   }
 }
@@ -17,6 +17,12 @@ export interface FarmRuntimePlugin {
   moduleCreated?: (module: Module) => void | Promise<void>;
   // invoked after module initialization functions are called
   moduleInitialized?: (module: Module) => void | Promise<void>;
+  // invoked when the initialization function of a module throws or its async initialization rejects.
+  // the failed module is removed from the cache, so it is executed again when it's required next time
+  moduleExecutionFailed?: (
+    module: Module,
+    err: unknown
+  ) => void | Promise<void>;
   // invoked after module caches are read, return true to skip cache reading
   readModuleCache?: (module: Module) => boolean | Promise<boolean>;
   // called when module is not found
@@ -28,6 +34,11 @@ export interface FarmRuntimePlugin {
     resource: Resource,
     targetEnv: 'browser' | 'node'
   ) => Promise<ResourceLoadResult>;
+  // called when a resource is failed to load after all retries and public paths are exhausted, e.g. network or integrity errors.
+  loadResourceFailed?: (
+    resource: Resource,
+    err: unknown
+  ) => void | Promise<void>;
 }
 
 /* eslint-disable @typescript-eslint/no-explicit-any */
@@ -8,6 +8,15 @@ export interface Resource {
   type: 0 | 1; // 0: script, 1: link
 }
 
+export interface ResourceLoadRetryOptions {
+  // how many times a failed resource is retried before trying the next public path
+  count: number;
+  // delay in milliseconds before the first retry, doubled after each retry
+  delay: number;
+  // append a timestamp query to the url of the retried resource to skip the cached failed response
+  cacheBusting: boolean;
+}
+
 // Injected during build
 export const __farm_global_this__: any = '<@__farm_global_this__@>';
 export const __global_this__: any = typeof window !== 'undefined' ? window : typeof global !== 'undefined' ? global : {};
@@ -24,6 +33,11 @@ export class ResourceLoader {
   private _loadingResources: Record<string, Promise<void>> = {};
 
   publicPaths: string[];
+  retry: ResourceLoadRetryOptions = {
+    count: 0,
+    delay: 500,
+    cacheBusting: true
+  };
 
   constructor(private moduleSystem: ModuleSystem, publicPaths: string[]) {
     this.publicPaths = publicPaths;
@@ -60,10 +74,7 @@ export class ResourceLoader {
       }
     }
 
-    const publicPath = this.publicPaths[index];
-    const url = `${
-      publicPath.endsWith('/') ? publicPath.slice(0, -1) : publicPath
-    }/${resource.path}`;
+    const url = this._resourceUrl(resource, index);
 
     if (this._loadedResources[resource.path]) {
       return Promise.resolve();
@@ -103,40 +114,92 @@ export class ResourceLoader {
     return this._loadedResources[path];
   }
 
+  private _resourceUrl(resource: Resource, index: number): string {
+    // resources of remote builds are loaded by their absolute urls
+    if (/^(https?:)?\/\//.test(resource.path)) {
+      return resource.path;
+    }
+
+    const publicPath = this.publicPaths[index];
+    return `${
+      publicPath.endsWith('/') ? publicPath.slice(0, -1) : publicPath
+    }/${resource.path}`;
+  }
+
   private _load(url: string, resource: Resource, index: number): Promise<void> {
+    const promise = this._loadWithRetry(url, resource, index, 0).then(
+      () => {
+        this._loadedResources[resource.path] = true;
+        // @ts-ignore
+        this._loadingResources[resource.path] = null;
+      },
+      (e) => {
+        // @ts-ignore
+        this._loadingResources[resource.path] = null;
+        this.moduleSystem.pluginContainer.hookSerial(
+          'loadResourceFailed',
+          resource,
+          e
+        );
+        throw new Error(
+          `[Farm] Failed to load resource: "${resource.path}, type: ${resource.type}". ${e}`
+        );
+      }
+    );
+
+    this._loadingResources[resource.path] = promise;
+    return promise;
+  }
+
+  // retry the same public path with backoff first, then fallback to the next public path
+  private _loadWithRetry(
+    url: string,
+    resource: Resource,
+    index: number,
+    attempt: number
+  ): Promise<void> {
+    const src =
+      attempt > 0 && this.retry.cacheBusting
+        ? `${url}${url.includes('?') ? '&' : '?'}t=${Date.now()}`
+        : url;
     let promise = Promise.resolve();
 
     if (resource.type === 0) {
-      promise = this._loadScript(url);
+      promise = this._loadScript(src);
     } else if (resource.type === 1) {
-      promise = this._loadLink(url);
+      promise = this._loadLink(src);
     }
 
-    this._loadingResources[resource.path] = promise;
-
-    promise
-      .then(() => {
-        this._loadedResources[resource.path] = true;
-        // @ts-ignore
-        this._loadingResources[resource.path] = null;
-      })
-      .catch((e) => {
+    return promise.catch((e) => {
+      if (attempt < this.retry.count) {
         console.warn(
-          `[Farm] Failed to load resource "${url}" using publicPath: ${this.publicPaths[index]}`
+          `[Farm] Failed to load resource "${src}", retrying (${attempt + 1}/${
+            this.retry.count
+          })`
         );
-        index++;
+        const delay = this.retry.delay * Math.pow(2, attempt);
 
-        if (index < this.publicPaths.length) {
-          return this._load(url, resource, index);
-        } else {
-          // @ts-ignore
-          this._loadingResources[resource.path] = null;
-          throw new Error(
-            `[Farm] Failed to load resource: "${resource.path}, type: ${resource.type}". ${e}`
-          );
-        }
-      });
-    return promise;
+        return new Promise<void>((resolve) => setTimeout(resolve, delay)).then(
+          () => this._loadWithRetry(url, resource, index, attempt + 1)
+        );
+      }
+
+      console.warn(
+        `[Farm] Failed to load resource "${url}" using publicPath: ${this.publicPaths[index]}`
+      );
+      index++;
+
+      if (index < this.publicPaths.length) {
+        return this._loadWithRetry(
+          this._resourceUrl(resource, index),
+          resource,
+          index,
+          0
+        );
+      }
+
+      throw e;
+    });
   }
 
   private _loadScript(path: string): Promise<void> {
@@ -146,13 +209,14 @@ export class ResourceLoader {
     } else {
       return new Promise((resolve, reject) => {
         const script = document.createElement('script');
-        script.src = path;
+        script.src = this.moduleSystem.trustedTypes.createScriptURL(path);
         document.body.appendChild(script);
 
         script.onload = () => {
           resolve();
         };
         script.onerror = (e) => {
+          document.body.removeChild(script);
           reject(e);
         };
       });
@@ -176,6 +240,7 @@ export class ResourceLoader {
           resolve();
         };
         link.onerror = (e) => {
+          document.head.removeChild(link);
           reject(e);
         };
       });
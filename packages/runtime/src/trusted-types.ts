@@ -0,0 +1,33 @@
+import { __global_this__ } from './resource-loader.js';
+
+/**
+ * Creates the script urls loaded by the runtime, the code evaluated by the hmr client and the html of the hmr overlays.
+ * The values are Trusted Types when a policy is set by `runtime.trustedTypes`, they are assigned to the sinks as strings.
+ */
+export interface TrustedTypesPolicy {
+  createHTML(html: string): string;
+  createScript(script: string): string;
+  createScriptURL(url: string): string;
+}
+
+const passthrough = (value: string) => value;
+
+export const passthroughPolicy: TrustedTypesPolicy = {
+  createHTML: passthrough,
+  createScript: passthrough,
+  createScriptURL: passthrough
+};
+
+/**
+ * The policy should be allowed by the `trusted-types` directive of the content security policy,
+ * the values are passed through when Trusted Types are not supported
+ */
+export function createTrustedTypesPolicy(name: string): TrustedTypesPolicy {
+  const trustedTypes = __global_this__.trustedTypes;
+
+  if (!trustedTypes?.createPolicy) {
+    return passthroughPolicy;
+  }
+
+  return trustedTypes.createPolicy(name, passthroughPolicy);
+}
@@ -0,0 +1,124 @@
+import fs from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { fileURLToPath } from 'node:url';
+import { test, expect } from 'vitest';
+import { ModuleSystem } from '../src/module-system.js';
+
+const REMOTE_MODULE_TEMPLATE = fileURLToPath(
+  new URL(
+    '../../../crates/plugin_federation/src/remote_module.ts',
+    import.meta.url
+  )
+);
+const MANIFEST_URL = 'http://localhost/remote/federation-manifest.json';
+
+// the virtual module the federation plugin generates for `import('remote/Button')` in the `host` build
+function createRemoteModule() {
+  const code = fs
+    .readFileSync(REMOTE_MODULE_TEMPLATE, 'utf-8')
+    .replace("'FARM_MODULE_SYSTEM'", "window['host'].__farm_module_system__")
+    .replace("'FARM_GLOBAL_THIS'", 'window')
+    .replace(
+      "'FARM_MODULE_SYSTEM_KEY'",
+      JSON.stringify('__farm_module_system__')
+    )
+    .replace("'REMOTE_URL'", JSON.stringify(MANIFEST_URL))
+    .replace("'EXPOSE'", JSON.stringify('./Button'));
+  const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'farm-federation-'));
+  const file = path.join(dir, 'remote_module.ts');
+  fs.writeFileSync(file, code);
+
+  return file;
+}
+
+test('require exposed modules from the module system of the remote build', async () => {
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  const global = globalThis as any;
+  const host = new ModuleSystem();
+  const remote = new ModuleSystem();
+  const fetch = global.fetch;
+  global.window = global;
+  global.host = { __farm_module_system__: host };
+
+  // both builds have a module with the same id, they must not collide
+  host.register('shared.js', (module) => {
+    module.exports = { from: 'host' };
+  });
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  (host as any).federationShared = {
+    'shared-dep': {
+      moduleId: 'shared.js',
+      version: '1.2.3',
+      singleton: true,
+      requiredVersion: '^1.2.3'
+    }
+  };
+
+  // the resources of the remote build set up its module system and register its modules when they are loaded
+  const scripts: Record<string, () => void> = {
+    'http://localhost/remote/remote.js': () => {
+      global.remote = { __farm_module_system__: remote };
+    },
+    'http://localhost/remote/remote_Button.js': () => {
+      remote.register('Button.ts', (module, _exports, require) => {
+        module.exports = { shared: require('shared.js') };
+      });
+      remote.register('shared.js', (module) => {
+        module.exports = { from: 'remote' };
+      });
+    }
+  };
+  const loaded: string[] = [];
+  host.resourceLoader.load = (resource) => {
+    loaded.push(resource.path);
+    scripts[resource.path]();
+    return Promise.resolve();
+  };
+
+  global.fetch = (url: string) => {
+    expect(url).toBe(MANIFEST_URL);
+
+    return Promise.resolve({
+      ok: true,
+      json: () =>
+        Promise.resolve({
+          name: 'remote',
+          publicPath: '/remote/',
+          namespace: 'remote',
+          entry: [{ path: 'remote.js', type: 0 }],
+          exposes: {
+            './Button': {
+              moduleId: 'Button.ts',
+              resources: [{ path: 'remote_Button.js', type: 0 }]
+            }
+          },
+          shared: {
+            'shared-dep': {
+              moduleId: 'shared.js',
+              version: '1.2.0',
+              singleton: false,
+              requiredVersion: '^1.2.0'
+            }
+          }
+        })
+    });
+  };
+
+  try {
+    const { default: button } = await import(createRemoteModule());
+
+    expect(await button).toEqual({ shared: { from: 'host' } });
+    expect(loaded).toEqual([
+      'http://localhost/remote/remote.js',
+      'http://localhost/remote/remote_Button.js'
+    ]);
+    expect(host.getCache('Button.ts')).toBeUndefined();
+    expect(remote.getCache('Button.ts')).toBeDefined();
+  } finally {
+    delete global.window;
+    delete global.host;
+    delete global.remote;
+    global.fetch = fetch;
+  }
+});
@@ -0,0 +1,74 @@
+import { test, expect } from 'vitest';
+import { ModuleSystem } from '../src/module-system.js';
+
+function createModuleSystem(failures: number) {
+  const moduleSystem = new ModuleSystem();
+  const urls: string[] = [];
+  moduleSystem.setPublicPaths(['/', 'https://cdn.example.com/']);
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  (moduleSystem.resourceLoader as any)._loadScript = (url: string) => {
+    urls.push(url);
+    return urls.length > failures
+      ? Promise.resolve()
+      : Promise.reject(new Error('network error'));
+  };
+
+  return { moduleSystem, urls };
+}
+
+function load(moduleSystem: ModuleSystem) {
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  return (moduleSystem.resourceLoader as any)._load(
+    '/index.js',
+    { path: 'index.js', type: 0 },
+    0
+  );
+}
+
+test('retry failed resources with cache busting query', async () => {
+  const { moduleSystem, urls } = createModuleSystem(2);
+  moduleSystem.setResourceLoadRetry({ count: 2, delay: 0 });
+
+  await load(moduleSystem);
+
+  expect(urls.length).toBe(3);
+  expect(urls[0]).toBe('/index.js');
+  expect(urls[1]).toMatch(/^\/index\.js\?t=\d+$/);
+  expect(urls[2]).toMatch(/^\/index\.js\?t=\d+$/);
+  expect(moduleSystem.resourceLoader.isResourceLoaded('index.js')).toBe(true);
+});
+
+test('fallback to the next public path after retries', async () => {
+  const { moduleSystem, urls } = createModuleSystem(2);
+  moduleSystem.setResourceLoadRetry({
+    count: 1,
+    delay: 0,
+    cacheBusting: false
+  });
+
+  await load(moduleSystem);
+
+  expect(urls).toEqual([
+    '/index.js',
+    '/index.js',
+    'https://cdn.example.com/index.js'
+  ]);
+});
+
+test('call loadResourceFailed when all retries fail', async () => {
+  const { moduleSystem, urls } = createModuleSystem(Infinity);
+  const failed: string[] = [];
+  moduleSystem.setResourceLoadRetry({ count: 1, delay: 0 });
+  moduleSystem.addPlugin({
+    name: 'test',
+    loadResourceFailed: (resource) => {
+      failed.push(resource.path);
+    }
+  });
+
+  await expect(load(moduleSystem)).rejects.toThrow('network error');
+
+  expect(urls.length).toBe(4);
+  expect(failed).toEqual(['index.js']);
+  expect(moduleSystem.resourceLoader.isResourceLoaded('index.js')).toBeFalsy();
+});
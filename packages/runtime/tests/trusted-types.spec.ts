@@ -0,0 +1,41 @@
+import { test, expect } from 'vitest';
+import { ModuleSystem } from '../src/module-system.js';
+
+test('pass through the values without trusted types', () => {
+  const moduleSystem = new ModuleSystem();
+  moduleSystem.setTrustedTypesPolicy('farm');
+
+  expect(moduleSystem.trustedTypes.createScriptURL('/index.js')).toBe(
+    '/index.js'
+  );
+});
+
+test('create the values through the trusted types policy', () => {
+  const policies: string[] = [];
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  (globalThis as any).trustedTypes = {
+    createPolicy(name: string, rules: Record<string, (s: string) => string>) {
+      policies.push(name);
+      return {
+        createHTML: (html: string) => `html:${rules.createHTML(html)}`,
+        createScript: (script: string) =>
+          `script:${rules.createScript(script)}`,
+        createScriptURL: (url: string) => `url:${rules.createScriptURL(url)}`
+      };
+    }
+  };
+
+  try {
+    const moduleSystem = new ModuleSystem();
+    moduleSystem.setTrustedTypesPolicy('my-app#farm');
+
+    expect(policies).toEqual(['my-app#farm']);
+    expect(moduleSystem.trustedTypes.createScriptURL('/index.js')).toBe(
+      'url:/index.js'
+    );
+    expect(moduleSystem.trustedTypes.createScript('1')).toBe('script:1');
+  } finally {
+    // eslint-disable-next-line @typescript-eslint/no-explicit-any
+    delete (globalThis as any).trustedTypes;
+  }
+});
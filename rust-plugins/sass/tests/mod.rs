@@ -114,7 +114,7 @@ fn test_with_compiler() {
     let compiler = Compiler::new(config, vec![Arc::new(plugin_sass) as _]).unwrap();
     compiler.compile().unwrap();
 
-    let resources_map = compiler.context().resources_map.lock();
+    let resources_map = &compiler.context().resources_map;
     let css = resources_map.get("index.css").unwrap();
     let css_code = normalize_css(&String::from_utf8(css.bytes.clone()).unwrap());
 